@@ -0,0 +1,266 @@
+// 🎨 Display - What the Terminal Shows! 🎨
+// Progress bars, timeline lines, feedback tables and coloured diffs. Everything
+// here builds strings, so main.rs decides where they're printed
+// Created with love by Aye & Hue ✨
+
+use feedbacker_client::{FeedbackDetails, FeedbackEvent, FeedbackStatus};
+
+/// 🚦 The stages feedback goes through on its way to a pull request
+const STAGES: [FeedbackStatus; 5] = [
+    FeedbackStatus::Pending,
+    FeedbackStatus::Processing,
+    FeedbackStatus::GeneratingChanges,
+    FeedbackStatus::CreatingPullRequest,
+    FeedbackStatus::Completed,
+];
+
+/// 🏷️ A status as people write it ("generating changes")
+pub fn status_label(status: FeedbackStatus) -> String {
+    status.as_str().replace('_', " ")
+}
+
+/// 🔍 A status from its name, as in `--status generating_changes`
+pub fn parse_status(name: &str) -> Result<FeedbackStatus, String> {
+    FeedbackStatus::ALL
+        .into_iter()
+        .find(|status| status.as_str() == name)
+        .ok_or_else(|| {
+            let names: Vec<_> = FeedbackStatus::ALL.iter().map(|s| s.as_str()).collect();
+            format!("expected one of: {}", names.join(", "))
+        })
+}
+
+/// 📊 How far along the pipeline a status is, as a bar
+pub fn progress(status: FeedbackStatus) -> String {
+    match STAGES.iter().position(|stage| *stage == status) {
+        Some(index) => format!(
+            "[{}{}] {}/{} {}",
+            "█".repeat(index + 1),
+            "░".repeat(STAGES.len() - index - 1),
+            index + 1,
+            STAGES.len(),
+            status_label(status)
+        ),
+        None if status == FeedbackStatus::Paused => "⏸️ paused".to_string(),
+        None if status == FeedbackStatus::BudgetExceeded => {
+            "💸 over budget, waiting for approval".to_string()
+        }
+        None => format!("❌ {}", status_label(status)),
+    }
+}
+
+/// 🕰️ One line for a timeline event (None for status changes and snapshots, which the
+/// progress bar already shows)
+pub fn describe_event(event: &FeedbackEvent) -> Option<String> {
+    let payload = &event.payload;
+    let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).unwrap_or("?");
+    let number = |key: &str| payload.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let step = payload.get("step").and_then(|v| v.as_u64()).unwrap_or(0);
+    Some(match event.event_type.as_str() {
+        "status_changed" | "created" | "lifecycle_snapshot" => return None,
+        "plan_created" => {
+            let steps = payload
+                .get("steps")
+                .and_then(|v| v.as_array())
+                .map_or(0, |steps| steps.len());
+            format!("🗺️ Planned changes to {} file(s)", steps)
+        }
+        "file_generated" => format!("📄 {}. {}", step, text("file_path")),
+        "file_failed" => format!("❌ {}. {}: {}", step, text("file_path"), text("error")),
+        "budget_exceeded" => format!(
+            "💸 Paused at the {} budget ({} of {} tokens)",
+            text("scope"),
+            number("used"),
+            number("limit")
+        ),
+        "budget_approved" => format!("👍 Approved {} more tokens", number("additional_tokens")),
+        "pull_request_closed" => format!("🚫 Rejected, closed PR #{}", number("number")),
+        other => format!("🕰️ {}", other.replace('_', " ")),
+    })
+}
+
+/// 📋 A table of feedback, one line each
+pub fn feedback_table(items: &[FeedbackDetails]) -> String {
+    let mut table = format!(
+        "{:<36}  {:<21}  {:<16}  {:<30}  {}\n",
+        "ID", "STATUS", "SUBMITTED", "REPOSITORY", "FEEDBACK"
+    );
+    for item in items {
+        table.push_str(&format!(
+            "{:<36}  {:<21}  {:<16}  {:<30}  {}\n",
+            item.id,
+            item.status.as_str(),
+            item.created_at.format("%Y-%m-%d %H:%M"),
+            item.repository,
+            first_line(&item.content_preview, 50)
+        ));
+    }
+    table
+}
+
+/// 🔍 Everything known about one feedback
+pub fn feedback_details(details: &FeedbackDetails) -> String {
+    let mut lines = vec![
+        format!("🆔 {}", details.id),
+        format!("🎯 {}", details.repository),
+        format!("📋 {}", progress(details.status)),
+        format!(
+            "⏰ Submitted {}",
+            details.created_at.format("%Y-%m-%d %H:%M UTC")
+        ),
+    ];
+    if let Some(completed_at) = details.completed_at {
+        lines.push(format!(
+            "✅ Finished {}",
+            completed_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+    if let Some(provider) = &details.llm_provider {
+        lines.push(format!("🤖 {}", provider));
+    }
+    if let Some(branch) = &details.branch_name {
+        lines.push(format!("🌿 {}", branch));
+    }
+    if let Some(url) = &details.pull_request_url {
+        lines.push(format!("🔗 {}", url));
+    }
+    if let Some(error) = &details.error_message {
+        lines.push(format!("❌ {}", error));
+    }
+    lines.push(String::new());
+    lines.push(details.content_preview.clone());
+    lines.join("\n")
+}
+
+/// 🖍️ A unified diff, with additions green and removals red when `color` is on
+pub fn colorize_diff(diff: &str, color: bool) -> String {
+    if !color {
+        return diff.to_string();
+    }
+    diff.lines()
+        .map(|line| {
+            let code = if line.starts_with("+++") || line.starts_with("---") {
+                "1"
+            } else if line.starts_with('+') {
+                "32"
+            } else if line.starts_with('-') {
+                "31"
+            } else if line.starts_with("@@") {
+                "36"
+            } else {
+                return format!("{}\n", line);
+            };
+            format!("\x1b[{}m{}\x1b[0m\n", code, line)
+        })
+        .collect()
+}
+
+/// ✂️ The first line of some text, cut to `max` characters
+fn first_line(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > max {
+        format!("{}...", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+// 🧪 Tests - Pretty on the outside, tested on the inside!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(event_type: &str, payload: serde_json::Value) -> FeedbackEvent {
+        FeedbackEvent {
+            id: uuid::Uuid::nil(),
+            feedback_id: uuid::Uuid::nil(),
+            event_type: event_type.to_string(),
+            payload,
+            created_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(FeedbackStatus::Pending), "[█░░░░] 1/5 pending");
+        assert_eq!(
+            progress(FeedbackStatus::GeneratingChanges),
+            "[███░░] 3/5 generating changes"
+        );
+        assert_eq!(progress(FeedbackStatus::Completed), "[█████] 5/5 completed");
+        assert_eq!(progress(FeedbackStatus::Failed), "❌ failed");
+        assert_eq!(progress(FeedbackStatus::Paused), "⏸️ paused");
+        assert_eq!(
+            progress(FeedbackStatus::BudgetExceeded),
+            "💸 over budget, waiting for approval"
+        );
+        println!("✅ Progress bar test passed!");
+    }
+
+    #[test]
+    fn test_parse_status() {
+        for status in FeedbackStatus::ALL {
+            assert_eq!(parse_status(status.as_str()), Ok(status));
+        }
+        assert!(parse_status("Pending")
+            .unwrap_err()
+            .contains("generating_changes"));
+        println!("✅ Status parsing test passed!");
+    }
+
+    #[test]
+    fn test_describe_event() {
+        assert_eq!(
+            describe_event(&event("status_changed", json!({ "status": "processing" }))),
+            None
+        );
+        assert_eq!(
+            describe_event(&event("plan_created", json!({ "steps": [{}, {}] }))),
+            Some("🗺️ Planned changes to 2 file(s)".to_string())
+        );
+        assert_eq!(
+            describe_event(&event(
+                "file_failed",
+                json!({ "step": 2, "file_path": "src/lib.rs", "error": "did not compile" })
+            )),
+            Some("❌ 2. src/lib.rs: did not compile".to_string())
+        );
+        assert_eq!(
+            describe_event(&event(
+                "budget_exceeded",
+                json!({ "scope": "project", "used": 120000, "limit": 100000 })
+            )),
+            Some("💸 Paused at the project budget (120000 of 100000 tokens)".to_string())
+        );
+        assert_eq!(
+            describe_event(&event("pull_request_closed", json!({ "number": 7 }))),
+            Some("🚫 Rejected, closed PR #7".to_string())
+        );
+        assert_eq!(
+            describe_event(&event("sandbox_run", json!({}))),
+            Some("🕰️ sandbox run".to_string())
+        );
+        println!("✅ Event description test passed!");
+    }
+
+    #[test]
+    fn test_colorize_diff() {
+        let diff = "--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-old\n+new\n context\n";
+        assert_eq!(colorize_diff(diff, false), diff);
+        let colored = colorize_diff(diff, true);
+        assert!(colored.contains("\x1b[31m-old\x1b[0m\n"));
+        assert!(colored.contains("\x1b[32m+new\x1b[0m\n"));
+        assert!(colored.contains("\x1b[1m+++ b/README.md\x1b[0m\n"));
+        assert!(colored.ends_with(" context\n"));
+        println!("✅ Diff colouring test passed!");
+    }
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(first_line("Add dark mode\nPlease!", 50), "Add dark mode");
+        assert_eq!(first_line("ñññññ", 3), "ñññ...");
+        assert_eq!(first_line("", 3), "");
+        println!("✅ First line test passed!");
+    }
+}
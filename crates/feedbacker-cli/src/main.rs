@@ -0,0 +1,336 @@
+// 🖥️ Feedbacker CLI - Feedback From the Terminal! 🖥️
+// Talks to a Feedbacker server through feedbacker-client, signed in with an API
+// key (--api-key or $FEEDBACKER_API_KEY; the server is --url or $FEEDBACKER_URL):
+//   feedbacker-cli submit owner/repo "What to improve" --watch   📝 submit, then follow it
+//   feedbacker-cli submit owner/repo --file idea.md --path crates/foo
+//   feedbacker-cli watch <id>        📡 progress until the pull request is up (or it fails)
+//   feedbacker-cli list              📋 your feedback (--status, --repository, --page)
+//   feedbacker-cli show <id>         🔍 one feedback in full
+//   feedbacker-cli diff <id>         🔀 the changes proposed for it
+//   feedbacker-cli approve <id>      👍 they do what was asked
+//   feedbacker-cli reject <id>       👎 they don't (maintainers also close its open PRs)
+// Feedback content is read from stdin when neither an argument nor --file gives it
+// Created with love by Aye & Hue - No browser required! ✨
+
+mod display;
+
+use std::{
+    io::{IsTerminal, Read},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use feedbacker_client::{
+    ClientError, FeedbackDetails, FeedbackQuery, FeedbackStatus, FeedbackerClient,
+    SubmitFeedbackRequest,
+};
+use uuid::Uuid;
+
+/// 🚢 Submit feedback to Feedbacker and follow it to a pull request
+#[derive(Debug, Parser)]
+#[command(name = "feedbacker-cli", version, about)]
+struct Cli {
+    /// 🌐 Feedbacker server
+    #[arg(long, env = "FEEDBACKER_URL", default_value = "https://f.8b.is")]
+    url: String,
+
+    /// 🔑 API key to sign requests with
+    #[arg(long, env = "FEEDBACKER_API_KEY", hide_env_values = true)]
+    api_key: String,
+
+    /// 🔏 Request signing key, for service accounts that must sign their requests
+    #[arg(long, env = "FEEDBACKER_SIGNING_KEY", hide_env_values = true)]
+    signing_key: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// 🎯 What to do
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 📝 Submit feedback for a repository
+    Submit {
+        /// 🎯 Repository, as owner/repo
+        repository: String,
+        /// 📝 What to improve [default: read from stdin]
+        content: Option<String>,
+        /// 📄 Read the feedback from this file instead
+        #[arg(long, conflicts_with = "content")]
+        file: Option<PathBuf>,
+        /// 📁 Monorepo subdirectory to scope the feedback to
+        #[arg(long)]
+        path: Option<String>,
+        /// 🤖 LLM provider to use instead of the project's default
+        #[arg(long)]
+        llm_provider: Option<String>,
+        /// 📡 Follow its processing until it finishes
+        #[arg(long)]
+        watch: bool,
+    },
+    /// 📡 Follow a feedback's processing until it finishes
+    Watch {
+        /// 🆔 Feedback ID
+        id: Uuid,
+        /// ⏱️ Seconds between checks
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+    /// 📋 List your feedback, newest first
+    List {
+        /// 📋 Only feedback in this status (pending, processing, completed, failed ...)
+        #[arg(long, value_parser = display::parse_status)]
+        status: Option<FeedbackStatus>,
+        /// 🎯 Only feedback for this repository
+        #[arg(long)]
+        repository: Option<String>,
+        /// 📄 Page to show
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+        /// 📏 Feedback per page (at most 100)
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+    },
+    /// 🔍 Show one feedback
+    Show {
+        /// 🆔 Feedback ID
+        id: Uuid,
+    },
+    /// 🔀 Show the diff proposed for a feedback
+    Diff {
+        /// 🆔 Feedback ID
+        id: Uuid,
+    },
+    /// 👍 Approve the changes made for a feedback
+    Approve {
+        /// 🆔 Feedback ID
+        id: Uuid,
+    },
+    /// 👎 Reject the changes made for a feedback (as a project maintainer, also
+    /// close its open pull requests)
+    Reject {
+        /// 🆔 Feedback ID
+        id: Uuid,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut client = FeedbackerClient::new(cli.url)?.with_token(cli.api_key);
+    if let Some(signing_key) = cli.signing_key {
+        client = client.with_signing_key(signing_key);
+    }
+
+    match cli.command {
+        Command::Submit {
+            repository,
+            content,
+            file,
+            path,
+            llm_provider,
+            watch: follow,
+        } => {
+            let content = match (content, file) {
+                (Some(content), _) => content,
+                (None, Some(file)) => std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?,
+                (None, None) => {
+                    let mut content = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut content)
+                        .context("Failed to read feedback from stdin")?;
+                    content
+                }
+            };
+            let submitted = client
+                .submit_feedback(&SubmitFeedbackRequest {
+                    repository,
+                    content: content.trim().to_string(),
+                    path,
+                    llm_provider,
+                    ..Default::default()
+                })
+                .await?;
+            println!("📝 Submitted {}", submitted.feedback_id);
+            if follow {
+                watch(&client, submitted.feedback_id, Duration::from_secs(5)).await?;
+            } else {
+                println!(
+                    "📡 Follow it with: feedbacker-cli watch {}",
+                    submitted.feedback_id
+                );
+            }
+        }
+        Command::Watch { id, interval } => {
+            watch(&client, id, Duration::from_secs(interval.max(1))).await?;
+        }
+        Command::List {
+            status,
+            repository,
+            page,
+            limit,
+        } => {
+            let query = FeedbackQuery {
+                status,
+                repository,
+                ..Default::default()
+            };
+            let listed = client.list_feedback(&query, page, limit).await?;
+            if listed.items.is_empty() {
+                println!("📭 No feedback found");
+            } else {
+                print!("{}", display::feedback_table(&listed.items));
+                println!(
+                    "📄 Page {} of {} ({} total)",
+                    listed.pagination.page, listed.pagination.total_pages, listed.pagination.total
+                );
+            }
+        }
+        Command::Show { id } => {
+            println!("{}", display::feedback_details(&client.feedback(id).await?));
+        }
+        Command::Diff { id } => {
+            let proposed = client.proposed_diff(id).await?;
+            match proposed.diff {
+                Some(diff) => {
+                    let color = std::io::stdout().is_terminal();
+                    print!("{}", display::colorize_diff(&diff, color));
+                }
+                None => println!(
+                    "⏳ No changes proposed yet ({})",
+                    display::status_label(proposed.status)
+                ),
+            }
+        }
+        Command::Approve { id } => {
+            client.approve_feedback(id, true).await?;
+            println!("👍 Approved {}", id);
+        }
+        Command::Reject { id } => {
+            let message = client.approve_feedback(id, false).await?;
+            println!("👎 Rejected {}: {}", id, message);
+        }
+    }
+    Ok(())
+}
+
+/// 📡 Print a feedback's progress and timeline until processing is over
+/// Fails when processing did, so scripts can tell from the exit code
+async fn watch(client: &FeedbackerClient, id: Uuid, interval: Duration) -> Result<()> {
+    let mut seen_events = 0;
+    let mut last_status = None;
+    loop {
+        let details = match poll(client, id, &mut seen_events).await {
+            Ok(details) => details,
+            // 🚦 Slow down instead of giving up
+            Err(ClientError::RateLimited { retry_after, .. }) => {
+                tokio::time::sleep(retry_after.unwrap_or(interval)).await;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if last_status != Some(details.status) {
+            println!("{}", display::progress(details.status));
+            last_status = Some(details.status);
+        }
+        if details.status.is_finished() {
+            return finished(&details);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// 🔄 Print the timeline events not seen yet, then fetch the feedback itself
+async fn poll(
+    client: &FeedbackerClient,
+    id: Uuid,
+    seen_events: &mut usize,
+) -> Result<FeedbackDetails, ClientError> {
+    let events = client.feedback_events(id).await?;
+    for event in events.iter().skip(*seen_events) {
+        if let Some(line) = display::describe_event(event) {
+            println!("   {}", line);
+        }
+    }
+    *seen_events = events.len().max(*seen_events);
+    client.feedback(id).await
+}
+
+/// 🏁 Say how processing ended
+fn finished(details: &FeedbackDetails) -> Result<()> {
+    if details.status == FeedbackStatus::Failed {
+        bail!(
+            "Processing failed: {}",
+            details
+                .error_message
+                .as_deref()
+                .unwrap_or("no reason given")
+        );
+    }
+    if let Some(url) = &details.pull_request_url {
+        println!("🎉 Pull request: {}", url);
+    }
+    println!(
+        "🔀 Review it with `feedbacker-cli diff {0}`, then `approve {0}` or `reject {0}`",
+        details.id
+    );
+    Ok(())
+}
+
+// 🧪 Tests - Every flag where it belongs!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+        println!("✅ CLI definition test passed!");
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let cli = Cli::try_parse_from([
+            "feedbacker-cli",
+            "--url",
+            "http://localhost:3000",
+            "--api-key",
+            "key-123",
+            "list",
+            "--status",
+            "failed",
+            "--page",
+            "2",
+        ])
+        .unwrap();
+        assert_eq!(cli.url, "http://localhost:3000");
+        assert!(matches!(
+            cli.command,
+            Command::List {
+                status: Some(FeedbackStatus::Failed),
+                page: 2,
+                limit: 20,
+                ..
+            }
+        ));
+
+        let submit = Cli::try_parse_from([
+            "feedbacker-cli",
+            "--api-key",
+            "key-123",
+            "submit",
+            "aye-is/feedbacker",
+            "Add a dark mode",
+            "--file",
+            "idea.md",
+        ]);
+        assert!(submit.is_err(), "content and --file are exclusive");
+        assert!(Cli::try_parse_from(["feedbacker-cli", "--api-key", "k", "show", "nope"]).is_err());
+        println!("✅ Argument parsing test passed!");
+    }
+}
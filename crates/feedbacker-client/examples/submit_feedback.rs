@@ -0,0 +1,43 @@
+// 📝 Submit Feedback - The Client Library in Twenty Lines! 📝
+// Submits feedback and prints its timeline so far:
+//   FEEDBACKER_URL=https://f.8b.is FEEDBACKER_API_KEY=... \
+//     cargo run -p feedbacker-client --example submit_feedback -- owner/repo "What to improve"
+// Created with love by Aye & Hue ✨
+
+use feedbacker_client::{FeedbackerClient, SubmitFeedbackRequest};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let (Some(repository), Some(content)) = (args.next(), args.next()) else {
+        eprintln!("usage: submit_feedback <owner/repo> <feedback>");
+        std::process::exit(2);
+    };
+
+    let url = std::env::var("FEEDBACKER_URL").unwrap_or_else(|_| "https://f.8b.is".to_string());
+    let mut client = FeedbackerClient::new(url)?;
+    if let Ok(api_key) = std::env::var("FEEDBACKER_API_KEY") {
+        client = client.with_token(api_key);
+    }
+
+    let submitted = client
+        .submit_feedback(&SubmitFeedbackRequest {
+            repository,
+            content,
+            ..Default::default()
+        })
+        .await?;
+    println!(
+        "🆔 {} is {}",
+        submitted.feedback_id,
+        submitted.status.as_str()
+    );
+
+    for event in client.feedback_events(submitted.feedback_id).await? {
+        println!(
+            "🕰️ {} {} {}",
+            event.created_at, event.event_type, event.payload
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,581 @@
+// 🚢 Feedbacker Client - The API, Typed! 🚢
+// A small async client for the public Feedbacker API: submit feedback, follow
+// its timeline, review and approve the result, and look up projects and their
+// status.
+// Requests and responses are the feedbacker-types models the server itself
+// uses, re-exported here, so the two can't drift apart
+//
+//     let client = FeedbackerClient::new("https://f.8b.is")?.with_token(api_key);
+//     let submitted = client.submit_feedback(&SubmitFeedbackRequest {
+//         repository: "aye-is/feedbacker".to_string(),
+//         content: "Add a dark mode to the dashboard".to_string(),
+//         ..Default::default()
+//     }).await?;
+//
+// Created with love by Aye & Hue - Promoted from examples/ at last! ✨
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+pub use feedbacker_types::*;
+
+const USER_AGENT: &str = concat!("feedbacker-client/", env!("CARGO_PKG_VERSION"));
+
+/// ❌ Why a call didn't return what was asked for
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// 🔌 The request never got an answer (connection, TLS, timeout, bad URL)
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// 🚦 Rate limited; try again after `retry_after` when the server said
+    #[error("Rate limit exceeded")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        error: Option<ApiError>,
+    },
+    /// 🙅 The server refused, with its reason
+    #[error("{status}: {} ({})", .error.message, .error.code)]
+    Api { status: StatusCode, error: ApiError },
+    /// 🤷 An answer that isn't the API's envelope (a proxy's error page, say)
+    #[error("Unexpected response ({status}): {body}")]
+    UnexpectedResponse { status: StatusCode, body: String },
+}
+
+/// 🚢 Client for one Feedbacker server
+#[derive(Debug, Clone)]
+pub struct FeedbackerClient {
+    http: Client,
+    base_url: String,
+    token: Option<String>,
+    signing_key: Option<String>,
+}
+
+impl FeedbackerClient {
+    /// ➕ A client for the server at `base_url` (e.g. "https://f.8b.is"), signed out
+    pub fn new(base_url: impl Into<String>) -> Result<Self, ClientError> {
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            signing_key: None,
+        })
+    }
+
+    /// 🔑 Sign requests with an API key or login token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// 🔏 Sign every request with the key issued alongside a service account's API
+    /// key (accounts with one must sign; see feedbacker_types::signing)
+    pub fn with_signing_key(mut self, key: impl Into<String>) -> Self {
+        self.signing_key = Some(key.into());
+        self
+    }
+
+    /// 📝 Submit feedback for processing (POST /api/feedback)
+    pub async fn submit_feedback(
+        &self,
+        request: &SubmitFeedbackRequest,
+    ) -> Result<SubmitFeedbackResponse, ClientError> {
+        self.send(self.request(Method::POST, "/api/feedback").json(request))
+            .await
+    }
+
+    /// 🔍 One feedback item (GET /api/feedback/:id)
+    pub async fn feedback(&self, feedback_id: Uuid) -> Result<FeedbackDetails, ClientError> {
+        let path = format!("/api/feedback/{}", feedback_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 📋 A page of this account's feedback, newest first (GET /api/feedback)
+    pub async fn list_feedback(
+        &self,
+        query: &FeedbackQuery,
+        page: u32,
+        limit: u32,
+    ) -> Result<PaginatedResponse<FeedbackDetails>, ClientError> {
+        let request = self
+            .request(Method::GET, "/api/feedback")
+            .query(query)
+            .query(&[("page", page), ("limit", limit)]);
+        self.send(request).await
+    }
+
+    /// 🔀 The changes proposed for a feedback (GET /api/feedback/:id/diff)
+    pub async fn proposed_diff(&self, feedback_id: Uuid) -> Result<ProposedDiff, ClientError> {
+        let path = format!("/api/feedback/{}/diff", feedback_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 🕰️ A feedback's processing timeline so far (GET /api/feedback/:id/events)
+    pub async fn feedback_events(
+        &self,
+        feedback_id: Uuid,
+    ) -> Result<Vec<FeedbackEvent>, ClientError> {
+        let path = format!("/api/feedback/{}/events", feedback_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 👍 Say whether the changes made for a feedback do what was asked; a
+    /// rejection from a project maintainer also closes its pull requests that
+    /// are still open. Returns the server's account of what happened
+    /// (POST /api/feedback/:id/approval)
+    pub async fn approve_feedback(
+        &self,
+        feedback_id: Uuid,
+        approved: bool,
+    ) -> Result<String, ClientError> {
+        let path = format!("/api/feedback/{}/approval", feedback_id);
+        let request = self
+            .request(Method::POST, &path)
+            .json(&FeedbackApprovalRequest { approved });
+        self.send_for_message(request).await
+    }
+
+    /// 💸 Let feedback paused at its token budget use `additional_tokens` more
+    /// (None = another per-feedback cap's worth) and continue
+    /// (POST /api/feedback/:id/budget-approval)
+    pub async fn approve_budget(
+        &self,
+        feedback_id: Uuid,
+        additional_tokens: Option<u64>,
+    ) -> Result<FeedbackDetails, ClientError> {
+        let path = format!("/api/feedback/{}/budget-approval", feedback_id);
+        let request = self
+            .request(Method::POST, &path)
+            .json(&BudgetApprovalRequest { additional_tokens });
+        self.send(request).await
+    }
+
+    /// 🧹 Retry, cancel or label many feedback items in one go
+    /// (POST /api/feedback/bulk)
+    pub async fn bulk_feedback(
+        &self,
+        request: &BulkFeedbackRequest,
+    ) -> Result<BulkFeedbackResponse, ClientError> {
+        self.send(
+            self.request(Method::POST, "/api/feedback/bulk")
+                .json(request),
+        )
+        .await
+    }
+
+    /// 👥 Assign a feedback item to a maintainer, or unassign it with None
+    /// (PUT /api/feedback/:id/assignee)
+    pub async fn assign_feedback(
+        &self,
+        feedback_id: Uuid,
+        assignee_id: Option<Uuid>,
+    ) -> Result<FeedbackDetails, ClientError> {
+        let path = format!("/api/feedback/{}/assignee", feedback_id);
+        let request = self
+            .request(Method::PUT, &path)
+            .json(&AssignFeedbackRequest { assignee_id });
+        self.send(request).await
+    }
+
+    /// 💬 Maintainers' internal notes on a feedback item
+    /// (GET /api/feedback/:id/comments)
+    pub async fn feedback_comments(
+        &self,
+        feedback_id: Uuid,
+    ) -> Result<Vec<FeedbackComment>, ClientError> {
+        let path = format!("/api/feedback/{}/comments", feedback_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 💬 Leave an internal note on a feedback item
+    /// (POST /api/feedback/:id/comments)
+    pub async fn add_feedback_comment(
+        &self,
+        feedback_id: Uuid,
+        body: impl Into<String>,
+    ) -> Result<FeedbackComment, ClientError> {
+        let path = format!("/api/feedback/{}/comments", feedback_id);
+        let request = self
+            .request(Method::POST, &path)
+            .json(&FeedbackCommentRequest { body: body.into() });
+        self.send(request).await
+    }
+
+    /// 🗑️ Delete an internal note (DELETE /api/feedback/:id/comments/:comment_id)
+    pub async fn delete_feedback_comment(
+        &self,
+        feedback_id: Uuid,
+        comment_id: Uuid,
+    ) -> Result<(), ClientError> {
+        let path = format!("/api/feedback/{}/comments/{}", feedback_id, comment_id);
+        self.send_no_data(self.request(Method::DELETE, &path)).await
+    }
+
+    /// 📋 Projects this account can see (GET /api/projects)
+    pub async fn list_projects(&self) -> Result<Vec<ProjectInfo>, ClientError> {
+        self.send(self.request(Method::GET, "/api/projects")).await
+    }
+
+    /// 🏠 One project (GET /api/projects/:id)
+    pub async fn project(&self, project_id: Uuid) -> Result<ProjectInfo, ClientError> {
+        let path = format!("/api/projects/{}", project_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 📊 How a project is doing (GET /api/status/:project_id)
+    pub async fn project_status(&self, project_id: Uuid) -> Result<ProjectStatus, ClientError> {
+        let path = format!("/api/status/{}", project_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    /// 🌳 Latest Smart Tree release (GET /api/smart-tree/latest)
+    pub async fn latest_smart_tree_version(&self) -> Result<VersionInfo, ClientError> {
+        self.send(self.request(Method::GET, "/api/smart-tree/latest"))
+            .await
+    }
+
+    /// 🔧 A request to the server, signed when there is a token
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// 📦 Send a request and unwrap the data of its envelope
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let (status, body) = self.exchange(request).await?;
+        match serde_json::from_str::<ApiResponse<T>>(&body) {
+            Ok(ApiResponse {
+                data: Some(data), ..
+            }) => Ok(data),
+            _ => Err(unexpected(status, body)),
+        }
+    }
+
+    /// 📦 Send a request whose answer carries no data
+    async fn send_no_data(&self, request: RequestBuilder) -> Result<(), ClientError> {
+        self.send_for_message(request).await.map(|_| ())
+    }
+
+    /// 📤 Send a request answered without data, keeping the server's message
+    async fn send_for_message(&self, request: RequestBuilder) -> Result<String, ClientError> {
+        let (status, body) = self.exchange(request).await?;
+        match serde_json::from_str::<ApiResponse<serde_json::Value>>(&body) {
+            Ok(response) if response.success => Ok(response.message),
+            _ => Err(unexpected(status, body)),
+        }
+    }
+
+    /// 📡 Send a request; refusals become errors, successes come back as their body
+    async fn exchange(&self, request: RequestBuilder) -> Result<(StatusCode, String), ClientError> {
+        let mut request = request.build()?;
+        if let Some(key) = &self.signing_key {
+            sign_request(&mut request, key, unix_now());
+        }
+        let response = self.http.execute(request).await?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await?;
+        if status.is_success() {
+            return Ok((status, body));
+        }
+
+        let error = serde_json::from_str::<ApiResponse<serde_json::Value>>(&body)
+            .ok()
+            .and_then(|response| response.error);
+        Err(match (status, error) {
+            (StatusCode::TOO_MANY_REQUESTS, error) => {
+                ClientError::RateLimited { retry_after, error }
+            }
+            (status, Some(error)) => ClientError::Api { status, error },
+            (status, None) => ClientError::UnexpectedResponse { status, body },
+        })
+    }
+}
+
+/// ⏰ Seconds since the Unix epoch
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() as i64)
+}
+
+/// 🔏 Add the timestamp and signature headers to a built request
+fn sign_request(request: &mut reqwest::Request, key: &str, timestamp: i64) {
+    let url = request.url();
+    let path_and_query = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default();
+    let signature = signing::sign(
+        key,
+        timestamp,
+        request.method().as_str(),
+        &path_and_query,
+        body,
+    );
+    let headers = request.headers_mut();
+    headers.insert(
+        signing::TIMESTAMP_HEADER,
+        header::HeaderValue::from(timestamp),
+    );
+    headers.insert(
+        signing::SIGNATURE_HEADER,
+        header::HeaderValue::from_str(&signature).expect("a hex signature is a valid header"),
+    );
+}
+
+/// 🤷 A successful status with a body that isn't the expected envelope
+fn unexpected(status: StatusCode, body: String) -> ClientError {
+    ClientError::UnexpectedResponse { status, body }
+}
+
+// 🧪 Tests - Against a pretend server!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_json, header, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_submit_feedback() {
+        let server = MockServer::start().await;
+        let feedback_id = Uuid::new_v4();
+        Mock::given(method("POST"))
+            .and(path("/api/feedback"))
+            .and(header("authorization", "Bearer key-123"))
+            .and(body_json(json!({
+                "repository": "aye-is/feedbacker",
+                "content": "Add a dark mode to the dashboard",
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "success": true,
+                "message": "Feedback submitted successfully! Processing will begin shortly.",
+                "data": {
+                    "feedback_id": feedback_id,
+                    "status": "Pending",
+                    "tracking_url": format!("/api/feedback/{}", feedback_id),
+                    "estimated_processing_time": 5
+                },
+                "timestamp": "2024-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = FeedbackerClient::new(format!("{}/", server.uri()))
+            .unwrap()
+            .with_token("key-123");
+        let submitted = client
+            .submit_feedback(&SubmitFeedbackRequest {
+                repository: "aye-is/feedbacker".to_string(),
+                content: "Add a dark mode to the dashboard".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(submitted.feedback_id, feedback_id);
+        assert_eq!(submitted.status, FeedbackStatus::Pending);
+        println!("✅ Client submit test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_list_feedback() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/feedback"))
+            .and(query_param("status", "Failed"))
+            .and(query_param("repository", "aye-is/feedbacker"))
+            .and(query_param("page", "2"))
+            .and(query_param("limit", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "message": "Feedback list retrieved",
+                "data": {
+                    "items": [],
+                    "pagination": {
+                        "page": 2,
+                        "limit": 10,
+                        "total": 11,
+                        "total_pages": 2,
+                        "has_prev": true,
+                        "has_next": false
+                    }
+                },
+                "timestamp": "2024-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = FeedbackerClient::new(server.uri()).unwrap();
+        let query = FeedbackQuery {
+            status: Some(FeedbackStatus::Failed),
+            repository: Some("aye-is/feedbacker".to_string()),
+            ..Default::default()
+        };
+        let page = client.list_feedback(&query, 2, 10).await.unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(page.pagination.total, 11);
+        assert!(page.pagination.has_prev && !page.pagination.has_next);
+        println!("✅ Client feedback listing test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_feedback_comments() {
+        let server = MockServer::start().await;
+        let feedback_id = Uuid::new_v4();
+        let comment_path = format!("/api/feedback/{}/comments", feedback_id);
+        Mock::given(method("POST"))
+            .and(path(comment_path.as_str()))
+            .and(body_json(
+                json!({ "body": "Duplicate of the dark mode request" }),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "success": true,
+                "message": "Comment added",
+                "data": {
+                    "id": Uuid::nil(),
+                    "feedback_id": feedback_id,
+                    "author_id": Uuid::nil(),
+                    "author_name": "Hue",
+                    "body": "Duplicate of the dark mode request",
+                    "created_at": "2024-01-01T00:00:00Z"
+                },
+                "timestamp": "2024-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = FeedbackerClient::new(server.uri()).unwrap();
+        let comment = client
+            .add_feedback_comment(feedback_id, "Duplicate of the dark mode request")
+            .await
+            .unwrap();
+        assert_eq!(comment.feedback_id, feedback_id);
+        assert_eq!(comment.author_name.as_deref(), Some("Hue"));
+        println!("✅ Client feedback comment test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_refusals() {
+        let server = MockServer::start().await;
+        let project_id = Uuid::new_v4();
+        Mock::given(path(format!("/api/status/{}", project_id)))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "success": false,
+                "message": "Operation failed",
+                "error": { "code": "forbidden", "message": "Insufficient permissions" },
+                "timestamp": "2024-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/api/projects"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "42"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/api/smart-tree/latest"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+            .mount(&server)
+            .await;
+
+        let client = FeedbackerClient::new(server.uri()).unwrap();
+        match client.project_status(project_id).await {
+            Err(ClientError::Api { status, error }) => {
+                assert_eq!(status, StatusCode::FORBIDDEN);
+                assert_eq!(error.code, "forbidden");
+            }
+            other => panic!("expected an API error, got {:?}", other),
+        }
+        match client.list_projects().await {
+            Err(ClientError::RateLimited { retry_after, .. }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(42)));
+            }
+            other => panic!("expected a rate limit, got {:?}", other),
+        }
+        assert!(matches!(
+            client.latest_smart_tree_version().await,
+            Err(ClientError::UnexpectedResponse { status, .. }) if status == StatusCode::BAD_GATEWAY
+        ));
+        println!("✅ Client refusal test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_signed_requests() {
+        let server = MockServer::start().await;
+        let feedback_id = Uuid::new_v4();
+        Mock::given(method("POST"))
+            .and(path(format!("/api/feedback/{}/comments", feedback_id)))
+            .and(|request: &wiremock::Request| {
+                let header = |name: &str| request.headers.get(name)?.to_str().ok();
+                let (Some(timestamp), Some(signature)) = (
+                    header(signing::TIMESTAMP_HEADER).and_then(|value| value.parse::<i64>().ok()),
+                    header(signing::SIGNATURE_HEADER),
+                ) else {
+                    return false;
+                };
+                (timestamp - unix_now()).abs() < 60
+                    && signing::verify(
+                        "signing-key",
+                        timestamp,
+                        request.method.as_str(),
+                        request.url.path(),
+                        &request.body,
+                        signature,
+                    )
+            })
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "success": true,
+                "message": "Comment added",
+                "data": {
+                    "id": Uuid::new_v4(),
+                    "feedback_id": feedback_id,
+                    "author_id": null,
+                    "author_name": "Smart Tree",
+                    "body": "Signed and sealed",
+                    "created_at": "2025-01-01T00:00:00Z"
+                },
+                "timestamp": "2025-01-01T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = FeedbackerClient::new(server.uri())
+            .unwrap()
+            .with_token("key-123")
+            .with_signing_key("signing-key");
+        let comment = client
+            .add_feedback_comment(feedback_id, "Signed and sealed")
+            .await
+            .unwrap();
+        assert_eq!(comment.body, "Signed and sealed");
+
+        // 🙅 Without the key the same call doesn't match the signed mock
+        let unsigned = FeedbackerClient::new(server.uri()).unwrap();
+        assert!(unsigned
+            .add_feedback_comment(feedback_id, "Unsigned")
+            .await
+            .is_err());
+        println!("✅ Signed request test passed!");
+    }
+}
@@ -0,0 +1,372 @@
+// 📝 Feedback - From Suggestion to Pull Request! 📝
+// Created with love by Aye & Hue ✨
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 📋 Feedback Status Enum - Track where we are in the process!
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "sqlx",
+    derive(sqlx::Type),
+    sqlx(type_name = "feedback_status", rename_all = "snake_case")
+)]
+pub enum FeedbackStatus {
+    /// 📥 Just received, waiting for processing
+    Pending,
+    /// 🔄 Currently being processed by AI
+    Processing,
+    /// 🤖 AI analysis complete, creating GitHub changes
+    GeneratingChanges,
+    /// 🐙 Creating branch and pull request
+    CreatingPullRequest,
+    /// ✅ Successfully completed with PR created
+    Completed,
+    /// ❌ Failed during processing
+    Failed,
+    /// ⏸️ Paused (waiting for user input or manual intervention)
+    Paused,
+    /// 💸 Paused after going over its token budget, until someone approves more
+    BudgetExceeded,
+}
+
+impl FeedbackStatus {
+    /// 📋 Every status, in pipeline order
+    pub const ALL: [FeedbackStatus; 8] = [
+        FeedbackStatus::Pending,
+        FeedbackStatus::Processing,
+        FeedbackStatus::GeneratingChanges,
+        FeedbackStatus::CreatingPullRequest,
+        FeedbackStatus::Completed,
+        FeedbackStatus::Failed,
+        FeedbackStatus::Paused,
+        FeedbackStatus::BudgetExceeded,
+    ];
+
+    /// 🏷️ Name as stored in the database (and sent in status_changed events)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedbackStatus::Pending => "pending",
+            FeedbackStatus::Processing => "processing",
+            FeedbackStatus::GeneratingChanges => "generating_changes",
+            FeedbackStatus::CreatingPullRequest => "creating_pull_request",
+            FeedbackStatus::Completed => "completed",
+            FeedbackStatus::Failed => "failed",
+            FeedbackStatus::Paused => "paused",
+            FeedbackStatus::BudgetExceeded => "budget_exceeded",
+        }
+    }
+
+    /// 🏁 Whether processing is over (nothing changes until a retry)
+    pub fn is_finished(&self) -> bool {
+        matches!(self, FeedbackStatus::Completed | FeedbackStatus::Failed)
+    }
+}
+
+/// 📝 Feedback submission request structure
+/// This is what users send us when they want to improve a repository!
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubmitFeedbackRequest {
+    /// 🎯 Target repository in "owner/repo" format
+    pub repository: String,
+    /// 📝 The actual feedback content - what the user wants to improve
+    pub content: String,
+    /// 📁 Monorepo subdirectory to scope the feedback to (optional, e.g. "crates/foo")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 🤖 Preferred LLM provider (optional - will use project default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_provider: Option<String>,
+    /// 🔧 Additional metadata for processing (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// 👤 User information (for anonymous submissions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_info: Option<AnonymousUserInfo>,
+}
+
+/// 👤 Anonymous user information for feedback without accounts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnonymousUserInfo {
+    /// 📧 Email for notifications (optional)
+    pub email: Option<String>,
+    /// 👤 Display name (optional)
+    pub name: Option<String>,
+}
+
+/// 📊 Feedback submission response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitFeedbackResponse {
+    /// 🆔 Unique feedback ID for tracking
+    pub feedback_id: Uuid,
+    /// 📋 Current status of the feedback
+    pub status: FeedbackStatus,
+    /// 🔗 URL to track the feedback progress
+    pub tracking_url: String,
+    /// ⏰ Estimated processing time in minutes
+    pub estimated_processing_time: u32,
+}
+
+/// 📊 Detailed feedback information for responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackDetails {
+    /// 🆔 Feedback ID
+    pub id: Uuid,
+    /// 🎯 Target repository
+    pub repository: String,
+    /// 📝 Feedback content (truncated for privacy)
+    pub content_preview: String,
+    /// 📋 Current status
+    pub status: FeedbackStatus,
+    /// 🌿 GitHub branch name (if created)
+    pub branch_name: Option<String>,
+    /// 🔗 Pull request URL (if created)
+    pub pull_request_url: Option<String>,
+    /// 🤖 LLM provider used
+    pub llm_provider: Option<String>,
+    /// ❌ Error message (if failed)
+    pub error_message: Option<String>,
+    /// ⏰ When submitted
+    pub created_at: DateTime<Utc>,
+    /// 🔄 Last updated
+    pub updated_at: DateTime<Utc>,
+    /// ✅ When completed (if applicable)
+    pub completed_at: Option<DateTime<Utc>>,
+    /// 👥 Maintainer it's assigned to (if any)
+    #[serde(default)]
+    pub assigned_to: Option<Uuid>,
+}
+
+impl FeedbackDetails {
+    /// 🪶 Field names, for `?fields=` on feedback lists
+    pub const FIELDS: &'static [&'static str] = &[
+        "id",
+        "repository",
+        "content_preview",
+        "status",
+        "branch_name",
+        "pull_request_url",
+        "llm_provider",
+        "error_message",
+        "created_at",
+        "updated_at",
+        "completed_at",
+        "assigned_to",
+    ];
+}
+
+/// 👍 Submitter's verdict on the changes made for their feedback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackApprovalRequest {
+    /// ✅ The result does what was asked
+    pub approved: bool,
+}
+
+/// 💸 Approval to spend more tokens on feedback paused at its budget
+/// (POST /api/feedback/:id/budget-approval)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetApprovalRequest {
+    /// 🔢 Tokens the item may use on top of what it already used
+    /// (None = another per-feedback cap's worth)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_tokens: Option<u64>,
+}
+
+/// 👥 Who a feedback item is assigned to (PUT /api/feedback/:id/assignee)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignFeedbackRequest {
+    /// 👤 The maintainer taking it on (None = unassign)
+    pub assignee_id: Option<Uuid>,
+}
+
+/// 💬 An internal note to leave on a feedback item (POST /api/feedback/:id/comments)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackCommentRequest {
+    pub body: String,
+}
+
+/// 💬 An internal note maintainers left on a feedback item
+/// (GET /api/feedback/:id/comments; never shown to submitters)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackComment {
+    pub id: Uuid,
+    pub feedback_id: Uuid,
+    /// 👤 Who wrote it (None once their account is deleted)
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 🧹 What a bulk operation does to each item (POST /api/feedback/bulk)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BulkAction {
+    /// 🔄 Process failed or paused items again
+    Retry,
+    /// 🛑 Stop unfinished items (they end up failed, so they can be retried)
+    Cancel,
+    /// 🏷️ Add a label to the items' metadata
+    SetLabel,
+}
+
+/// 🧹 One action over many feedback items, applied in a single transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFeedbackRequest {
+    pub action: BulkAction,
+    /// 🆔 Items to act on (the caller's own, or any for admins)
+    pub ids: Vec<Uuid>,
+    /// 🏷️ The label, for set-label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// 📋 What a bulk operation did to one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkItemResult {
+    pub id: Uuid,
+    pub success: bool,
+    /// 📋 Status afterwards (None when the item wasn't found)
+    pub status: Option<FeedbackStatus>,
+    /// ❌ Why the item was left alone: not_found, not_retryable or already_finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 📊 Outcome of a bulk operation, item by item in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFeedbackResponse {
+    pub action: BulkAction,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
+
+/// 🔍 Filters for listing feedback (GET /api/feedback)
+/// Everyone lists their own feedback; `user_id` picks someone else's for
+/// those allowed to view all feedback, and `assigned_to_me` lists what's
+/// assigned to the caller, whoever submitted it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedbackQuery {
+    /// 📋 Filter by status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<FeedbackStatus>,
+    /// 🎯 Filter by repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    /// 👤 Filter by user (admin only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    /// 🤖 Filter by LLM provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_provider: Option<String>,
+    /// ⏰ Filter by date range (from)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<DateTime<Utc>>,
+    /// ⏰ Filter by date range (to)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<DateTime<Utc>>,
+    /// 👥 Filter by assignee
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<Uuid>,
+    /// 🙋 Only feedback assigned to the caller
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub assigned_to_me: bool,
+}
+
+/// 🔍 The changes proposed for a feedback (GET /api/feedback/:id/diff)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedDiff {
+    /// 🆔 Feedback ID
+    pub feedback_id: Uuid,
+    /// 📋 Current status of the feedback
+    pub status: FeedbackStatus,
+    /// 🔍 Unified diff of every changed file (None until changes are generated)
+    pub diff: Option<String>,
+    /// 🔗 Pull request URL (if created)
+    pub pull_request_url: Option<String>,
+}
+
+/// 🕰️ One entry in a feedback's processing timeline (GET /api/feedback/:id/events)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackEvent {
+    /// 🆔 Unique identifier for this event
+    pub id: Uuid,
+    /// 📝 Feedback this event belongs to
+    pub feedback_id: Uuid,
+    /// 🏷️ What happened (status_changed, plan_created, file_generated, ...)
+    pub event_type: String,
+    /// 📦 Event details (JSON)
+    pub payload: serde_json::Value,
+    /// ⏰ When it happened
+    pub created_at: DateTime<Utc>,
+}
+
+// 🧪 Tests - Same bytes in, same bytes out!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_request_leaves_out_unset_fields() {
+        let request = SubmitFeedbackRequest {
+            repository: "aye-is/feedbacker".to_string(),
+            content: "Add a dark mode to the dashboard".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "repository": "aye-is/feedbacker",
+                "content": "Add a dark mode to the dashboard",
+            })
+        );
+
+        let parsed: SubmitFeedbackRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.repository, "aye-is/feedbacker");
+        assert!(parsed.path.is_none());
+        println!("✅ Submit request serialization test passed!");
+    }
+
+    #[test]
+    fn test_feedback_details_fields() {
+        let details = FeedbackDetails {
+            id: Uuid::nil(),
+            repository: "aye-is/feedbacker".to_string(),
+            content_preview: "Add a dark mode".to_string(),
+            status: FeedbackStatus::Pending,
+            branch_name: None,
+            pull_request_url: None,
+            llm_provider: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+            assigned_to: None,
+        };
+        let json = serde_json::to_value(&details).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut fields = FeedbackDetails::FIELDS.to_vec();
+        keys.sort_unstable();
+        fields.sort_unstable();
+        assert_eq!(keys, fields);
+        println!("✅ Feedback details fields test passed!");
+    }
+
+    #[test]
+    fn test_finished_statuses() {
+        assert!(FeedbackStatus::Completed.is_finished());
+        assert!(FeedbackStatus::Failed.is_finished());
+        assert!(!FeedbackStatus::Paused.is_finished());
+        assert!(!FeedbackStatus::BudgetExceeded.is_finished());
+        assert!(!FeedbackStatus::CreatingPullRequest.is_finished());
+        println!("✅ Finished status test passed!");
+    }
+}
@@ -0,0 +1,23 @@
+// 📦 Feedbacker Types - One Schema, Both Sides of the Wire! 📦
+// The request and response models of the public Feedbacker API. The server
+// (with the `sqlx` feature, for the enums stored in Postgres) and the
+// feedbacker-client crate both use these, so a field renamed on one side is a
+// compile error on the other instead of a surprise in production. The request
+// signing scheme lives here for the same reason
+// Created with love by Aye & Hue - Schema drift walks the plank! ✨
+
+mod feedback;
+mod projects;
+mod response;
+pub mod signing;
+mod smart_tree;
+
+pub use feedback::{
+    AnonymousUserInfo, AssignFeedbackRequest, BudgetApprovalRequest, BulkAction,
+    BulkFeedbackRequest, BulkFeedbackResponse, BulkItemResult, FeedbackApprovalRequest,
+    FeedbackComment, FeedbackCommentRequest, FeedbackDetails, FeedbackEvent, FeedbackQuery,
+    FeedbackStatus, ProposedDiff, SubmitFeedbackRequest, SubmitFeedbackResponse,
+};
+pub use projects::{ProjectInfo, ProjectStatus};
+pub use response::{ApiError, ApiResponse, PaginatedResponse, PaginationMeta};
+pub use smart_tree::VersionInfo;
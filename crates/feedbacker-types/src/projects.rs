@@ -0,0 +1,69 @@
+// 🏠 Projects - Repositories Feedbacker Looks After! 🏠
+// Created with love by Aye & Hue ✨
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 🏠 A registered repository (GET /api/projects)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub id: Uuid,
+    pub repository: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    /// 🏢 Organization sharing the project
+    pub organization_id: Option<Uuid>,
+    /// 🧑‍🤝‍🧑 Team the project is limited to
+    pub team_id: Option<Uuid>,
+}
+
+impl ProjectInfo {
+    /// 🪶 Field names, for `?fields=` on project lists
+    pub const FIELDS: &'static [&'static str] = &[
+        "id",
+        "repository",
+        "description",
+        "is_active",
+        "organization_id",
+        "team_id",
+    ];
+}
+
+/// 📊 How a project is doing (GET /api/status/:project_id)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStatus {
+    pub project_id: Uuid,
+    pub repository: String,
+    pub status: String,
+    pub last_activity: chrono::DateTime<chrono::Utc>,
+}
+
+// 🧪 Tests - Every field accounted for!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_info_fields() {
+        let project = ProjectInfo {
+            id: Uuid::nil(),
+            repository: "aye-is/feedbacker".to_string(),
+            description: None,
+            is_active: true,
+            organization_id: None,
+            team_id: None,
+        };
+        let json = serde_json::to_value(&project).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut fields = ProjectInfo::FIELDS.to_vec();
+        keys.sort_unstable();
+        fields.sort_unstable();
+        assert_eq!(keys, fields);
+        println!("✅ Project info fields test passed!");
+    }
+}
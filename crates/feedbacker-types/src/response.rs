@@ -0,0 +1,124 @@
+// 📝 Response Envelope - Every Answer Comes in the Same Box! 📝
+// Created with love by Aye & Hue ✨
+
+use serde::{Deserialize, Serialize};
+
+/// 📝 Standard API response structure
+/// Provides consistent response format across all endpoints
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    /// ✅ Whether the operation was successful
+    pub success: bool,
+    /// 📝 Human-readable message
+    pub message: String,
+    /// 📊 Response data (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    /// ❌ Error details (only present if success = false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+    /// ⏰ Response timestamp
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// ❌ API error structure
+/// Provides structured error information for debugging and user feedback
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    /// 🎯 Error code for programmatic handling
+    pub code: String,
+    /// 📝 Human-readable error message
+    pub message: String,
+    /// 🔍 Additional error details (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl<T> ApiResponse<T> {
+    /// ✅ Create a successful response
+    pub fn success(message: String, data: T) -> Self {
+        Self {
+            success: true,
+            message,
+            data: Some(data),
+            error: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// ✅ Create a successful response without data
+    pub fn success_no_data(message: String) -> ApiResponse<()> {
+        ApiResponse {
+            success: true,
+            message,
+            data: None,
+            error: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// ❌ Create a failed response: `message` sums it up, `error` says what went wrong
+    pub fn failure(message: String, error: ApiError) -> ApiResponse<()> {
+        ApiResponse {
+            success: false,
+            message,
+            data: None,
+            error: Some(error),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+/// 📊 Paginated response structure
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedResponse<T> {
+    /// 📋 The actual data items
+    pub items: Vec<T>,
+    /// 📊 Pagination metadata
+    pub pagination: PaginationMeta,
+}
+
+/// 📊 Pagination metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationMeta {
+    /// 📄 Current page number
+    pub page: u32,
+    /// 📏 Items per page
+    pub limit: u32,
+    /// 📈 Total number of items
+    pub total: u64,
+    /// 📑 Total number of pages
+    pub total_pages: u32,
+    /// ⬅️ Has previous page
+    pub has_prev: bool,
+    /// ➡️ Has next page
+    pub has_next: bool,
+}
+
+impl PaginationMeta {
+    /// ➕ Create pagination metadata
+    pub fn new(page: u32, limit: u32, total: u64) -> Self {
+        let total_pages = ((total as f64) / (limit as f64)).ceil() as u32;
+        let has_prev = page > 1;
+        let has_next = page < total_pages;
+
+        Self {
+            page,
+            limit,
+            total,
+            total_pages,
+            has_prev,
+            has_next,
+        }
+    }
+}
+
+impl<T> PaginatedResponse<T> {
+    /// ➕ Create a paginated response
+    pub fn new(items: Vec<T>, page: u32, limit: u32, total: u64) -> Self {
+        Self {
+            items,
+            pagination: PaginationMeta::new(page, limit, total),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// 🌳 Smart Tree - Release Info for the MCP Crowd! 🌳
+// Created with love by Aye & Hue ✨
+
+use serde::{Deserialize, Serialize};
+
+/// 🆕 Latest Smart Tree release (GET /api/smart-tree/latest)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub download_url: String,
+    pub release_notes: String,
+}
@@ -0,0 +1,453 @@
+# 🌍 Feedbacker messages - English (the fallback for every other locale)
+# Message ids are shared by every locale; attributes (.title, .body) hold the
+# parts of a page that belong together
+
+## 📡 API responses
+
+operation-failed = Operation failed
+resource-not-found = { $resource } not found
+rate-limit-try-later = Rate limit exceeded. Please try again later.
+rate-limit-exceeded = Rate limit exceeded for { $limit_type }. Try again in { $seconds ->
+        [one] { $seconds } second
+       *[other] { $seconds } seconds
+    }.
+no-endpoint = No endpoint at { $method } { $path }
+method-only-accepts = This endpoint only accepts { $methods }
+preferences-updated = Preferences updated
+profile-retrieved = Profile retrieved
+profile-updated = Profile updated
+password-changed = Password changed
+notifications-retrieved = Notifications retrieved
+notification-marked-read = Notification marked read
+unsupported-locale = Unsupported locale '{ $locale }'. Supported: { $supported }
+unsupported-digest-frequency = Unsupported digest frequency '{ $frequency }'. Supported: { $supported }
+health-check-completed = Health check completed
+health-check-detailed-completed = Detailed health check completed
+version-info-retrieved = Version info retrieved
+rate-limits-retrieved = Rate limits retrieved
+issue-automation-completed = Issue automation completed
+webhook-pong = pong
+webhook-pong-missing-events = pong, but the webhook doesn't send { $events }
+webhook-push-processed = Push processed
+webhook-installation-processed = Installation processed
+
+## 🧯 Error kinds (the `title` of problem documents)
+
+error-validation = Request validation failed
+error-unauthorized = Authentication required
+error-forbidden = Access denied
+error-not-found = Not found
+error-method-not-allowed = Method not allowed
+error-conflict = Conflicts with the current state
+error-quota-exceeded = Quota exceeded
+error-rate-limited = Rate limit exceeded
+error-maintenance = Down for maintenance
+error-login-method-disabled = Login method disabled
+error-sso-failed = Single sign-on failed
+error-upstream = An upstream service failed
+error-invalid-configuration = Invalid configuration
+error-repository-not-accessible = Repository not accessible
+error-unavailable = Temporarily unavailable
+error-internal = An internal error occurred
+
+## 🔐 Authentication
+
+auth-token-required = Authentication token required
+auth-insufficient-permissions = Insufficient permissions
+ip-not-allowed = Requests to this endpoint aren't allowed from your network
+request-signature-required = This account must sign its requests (X-Feedbacker-Timestamp and X-Feedbacker-Signature headers)
+request-signature-expired = The request was signed too long ago; check the client's clock
+request-signature-invalid = The request signature doesn't match
+request-signature-replayed = This signed request was already received
+request-signature-body-too-large = The request body is too large to verify its signature
+auth-missing-scope = This API key lacks the { $scope } scope
+auth-no-project-access = You don't have access to this project
+auth-project-access-unverified = Project access could not be verified
+auth-invalid-user = Invalid user or account disabled
+auth-invalid-token = Invalid or expired token
+auth-login-successful = Login successful
+auth-registration-successful = Registration successful
+auth-logout-successful = Logout successful
+auth-password-login-disabled = Password login is disabled; sign in through your organization's single sign-on
+auth-sso-required = Your organization requires single sign-on; sign in through your identity provider
+auth-password-accounts-disabled = Password accounts are disabled; sign in through your organization's single sign-on
+
+## ✅ Validation
+
+validation-email-required = Valid email is required
+validation-password-required = Password is required
+validation-name-required = Name is required
+validation-password-too-short = Password must be at least { $min } characters
+validation-name-too-long = Name cannot exceed { $max } characters
+validation-github-username = GitHub usernames have up to 39 letters, digits or single hyphens, not at the start or end
+validation-current-password-incorrect = Current password is incorrect
+validation-repository-empty = Repository cannot be empty
+validation-repository-format = Repository must be in 'owner/repo' format
+validation-content-empty = Feedback content cannot be empty
+validation-content-too-long = Feedback content cannot exceed { $max } characters
+validation-content-too-short = Feedback content must be at least { $min } characters
+validation-llm-provider = Invalid LLM provider. Supported: { $supported }
+validation-email-invalid = Invalid email address
+validation-bulk-ids-empty = At least one feedback ID is required
+validation-bulk-ids-too-many = At most { $max } feedback items can be changed at once
+validation-bulk-ids-duplicate = Feedback IDs must not repeat
+validation-bulk-label = set-label needs a label of 1 to { $max } characters
+validation-bulk-label-unexpected = Only set-label takes a label
+validation-comment-empty = Comment cannot be empty
+validation-comment-too-long = Comments cannot exceed { $max } characters
+validation-assignee-not-maintainer = Feedback can only be assigned to active members of a project for its repository
+validation-budget-not-exceeded = Only feedback paused at its token budget can be approved to continue
+validation-budget-tokens = Approve at least 1 more token (no per-feedback cap applies, so the amount is required)
+
+## 📝 Feedback
+
+feedback-submitted = Feedback submitted successfully! Processing will begin shortly.
+feedback-found = Feedback found
+feedback-events-retrieved = Feedback events retrieved
+feedback-trace-retrieved = Feedback trace retrieved
+feedback-list-retrieved = Feedback list retrieved successfully
+feedback-stats-retrieved = Statistics retrieved successfully
+feedback-retry-queued = Feedback processing retry queued successfully
+feedback-approval-recorded = Feedback approval recorded
+feedback-rejected = { $closed ->
+        [0] Rejection recorded; no pull request was open
+        [one] Rejection recorded and its pull request closed
+       *[other] Rejection recorded and { $closed } pull requests closed
+    }
+feedback-rejected-partly = Rejection recorded, but { $failed ->
+        [one] one pull request
+       *[other] { $failed } pull requests
+    } couldn't be closed; try again or close them on GitHub
+feedback-rejection-recorded = Rejection recorded; the project's maintainers decide whether to close its pull requests
+feedback-budget-approved = Approved { $tokens } more tokens; processing will continue shortly
+feedback-diff-retrieved = Proposed diff retrieved
+feedback-bulk-applied = Applied to { $succeeded } of { $total } feedback items
+feedback-assigned = Feedback assigned
+feedback-unassigned = Feedback unassigned
+feedback-comments-retrieved = Comments retrieved
+feedback-comment-added = Comment added
+feedback-comment-deleted = Comment deleted
+issues-imported = { $count ->
+        [one] Imported 1 issue
+       *[other] Imported { $count } issues
+    }
+export-queued = Export queued. Check its status for the download link.
+export-retrieved = Export retrieved
+export-link-invalid = This download link is invalid or has expired
+artifacts-retrieved = Artifacts retrieved
+artifact-link-invalid = This artifact link is invalid or has expired
+
+## 📦 Projects
+
+projects-retrieved = Projects retrieved
+project-retrieved = Project retrieved
+project-status-retrieved = Project status retrieved
+project-collaborators = { $count ->
+        [one] 1 collaborator
+       *[other] { $count } collaborators
+    }
+project-webhook-deliveries = { $count ->
+        [one] 1 webhook delivery
+       *[other] { $count } webhook deliveries
+    }
+project-style-profile-retrieved = Style profile retrieved
+project-style-analysis-queued = Style analysis queued
+project-style-analysis-already-queued = Style analysis is already queued
+project-config-updated = Project configuration updated
+project-run-queued = Project run queued
+
+## 🏢 Organizations
+
+organization-created = Organization created
+organizations-retrieved = Organizations retrieved
+organization-retrieved = Organization retrieved
+organization-settings-updated = Organization settings updated
+team-created = Team created
+organization-project-assigned = Project assigned to organization
+organization-project-removed = Project removed from organization
+organization-api-key-issued = API key issued; it is shown only once
+organization-sso-retrieved = Single sign-on provider retrieved
+organization-sso-saved = Single sign-on provider saved
+
+## 👑 Administration
+
+admin-overview-retrieved = Overview retrieved
+admin-users-retrieved = Users retrieved
+admin-user-updated = User updated
+admin-llm-exchanges-retrieved = LLM exchanges retrieved
+admin-llm-exchange-retrieved = LLM exchange retrieved
+admin-prompt-versions-retrieved = Prompt versions retrieved
+admin-prompt-version-created = Prompt version created
+admin-prompt-stats-retrieved = Prompt stats retrieved
+admin-dead-jobs-retrieved = Dead-lettered jobs retrieved
+admin-job-retrieved = Job retrieved
+admin-job-requeued = Job requeued
+admin-dead-jobs-requeued = Dead-lettered jobs requeued
+admin-dead-jobs-purged = Dead-lettered jobs purged
+admin-schedules-retrieved = Schedules retrieved
+admin-schedule-paused = Schedule paused
+admin-schedule-resumed = Schedule resumed
+admin-schedule-triggered = Schedule triggered
+admin-config-reloaded = Configuration reloaded: { $count ->
+        [one] 1 setting changed
+       *[other] { $count } settings changed
+    }
+admin-flags-retrieved = Feature flags retrieved
+admin-flag-created = Feature flag created
+admin-flag-retrieved = Feature flag retrieved
+admin-flag-updated = Feature flag updated
+admin-flag-evaluated = Feature flag evaluated
+admin-flag-override-set = Feature flag override set
+admin-maintenance-retrieved = Maintenance status retrieved
+admin-maintenance-updated = Maintenance mode updated
+admin-roles-retrieved = Roles retrieved
+admin-role-created = Role created
+admin-role-updated = Role updated
+admin-quotas-updated = Organization quotas updated
+
+## ⏱️ Stuck feedback
+
+feedback-stuck-title = Feedback got stuck
+feedback-stuck-failed = Feedback on { $repository } spent over { $minutes } minutes in { $status } with nothing running it, and was marked failed
+feedback-stuck-requeued = Feedback on { $repository } spent over { $minutes } minutes in { $status } with nothing running it, and was put back in the queue
+
+## 💸 Token budgets
+
+budget-exceeded-title = Feedback paused at its token budget
+budget-exceeded-feedback = Feedback on { $repository } used { $used } of its { $limit } tokens and is waiting for your approval to continue
+budget-exceeded-project = { $repository } used { $used } of its { $limit } tokens this month; feedback is waiting for your approval to continue
+
+## 🔌 GitHub App access
+
+project-deactivated-title = Project deactivated
+project-deactivated-access-revoked = The GitHub App can no longer reach { $repository }, so its project was deactivated. It becomes active again once the app is given access to the repository
+
+## 📬 Activity digests
+
+digest-subject = { $frequency ->
+        [daily] 📬 Your daily Feedbacker digest
+       *[weekly] 📬 Your weekly Feedbacker digest
+    }
+digest-greeting = Hi { $name },
+digest-intro = { $frequency ->
+        [daily] Here's what happened in your projects since yesterday.
+       *[weekly] Here's what happened in your projects this week.
+    }
+digest-new-feedback = { $count ->
+        [one] 1 new feedback item
+       *[other] { $count } new feedback items
+    }
+digest-pull-requests-opened = { $count ->
+        [one] 1 pull request opened
+       *[other] { $count } pull requests opened
+    }
+digest-pull-requests-merged = { $count ->
+        [one] 1 pull request merged
+       *[other] { $count } pull requests merged
+    }
+digest-failed = { $count ->
+        [one] 1 failure needs attention
+       *[other] { $count } failures need attention
+    }
+digest-needs-attention = ❌ Needs attention
+digest-view-project = View project
+digest-footer = You get this digest { $frequency ->
+        [daily] every day
+       *[weekly] every week
+    }. Change how often with PUT /api/users/me/preferences.
+digest-unsubscribe = Unsubscribe
+
+## 🙋 Claiming anonymous feedback
+
+feedback-claim-subject = 🙋 Track your feedback on { $repository }
+feedback-claim-greeting = Hi there,
+feedback-claim-intro = Thanks for your feedback on { $repository }! Sign in to Feedbacker, or create an account, with this email address and open the link below to follow your feedback as it turns into a pull request.
+feedback-claim-action = Claim your feedback
+feedback-claim-footer = The link works for { $days } days. Didn't leave any feedback? Just ignore this email.
+
+## 🔐 Security alerts
+
+security-alert-subject = 🔐 Feedbacker security alert: { $title }
+security-alert-greeting = Hi { $name },
+security-alert-new-device-login = New sign-in from another device
+    .body = Your account was signed in to from { $device } at { $ip }, a device it was never used on before.
+security-alert-password-changed = Your password was changed
+    .body = The password of your Feedbacker account was changed.
+security-alert-api-key-created = An API key was issued
+    .body = { $issuer } issued an API key for { $account } in { $organization }.
+security-alert-unknown-device = an unknown device
+security-alert-occurred-at = When: { $time }
+security-alert-advice = If this wasn't you, change your password and revoke your sessions right away.
+security-alert-footer = You get these emails because security alerts are on. Turn them off with PUT /api/users/me/preferences.
+
+## 🎨 Web UI
+
+nav-projects = 📊 Projects
+nav-submit = 📝 Submit
+nav-docs = 📚 Docs
+nav-about = ℹ️ About
+nav-admin = 👑 Admin
+nav-login = 🔐 Login
+footer = Built with ❤️ by Aye & Hue
+
+status-inactive = inactive
+counts-feedback = Feedback
+counts-suggestions = Suggestions
+counts-open = Open
+counts-completed = Completed
+counts-failed = Failed
+counts-merged = Merged
+column-repository = Repository
+column-submitted = Submitted
+column-status = Status
+column-feedback = Feedback
+column-suggestion = Suggestion
+column-pull-request = Pull request
+column-merged = Merged
+column-last-activity = Last activity
+link-github = 🐙 View on GitHub
+link-submit-feedback = 📝 Submit feedback
+link-open-pr = 🔗 Open PR
+
+projects = 🏠 Projects Dashboard
+    .title = Projects
+    .empty = No projects yet. Register one through the API and it will show up here:
+
+project-recent-feedback = 🕒 Recent Feedback
+project-no-feedback = No feedback for this project yet.
+
+status-page = { $repository } status
+status-recently-merged = 🎉 Recently Merged
+status-no-merged = No Feedbacker pull requests have been merged yet.
+status-open-suggestions = 💡 Open Suggestions
+status-nothing-open = Nothing in progress right now.
+
+submit = 📝 Submit Feedback
+    .title = Submit Feedback
+    .intro = Tell us what to improve, then watch it turn into a pull request.
+submit-sign-in = 🔐 Sign in first
+submit-email = Email
+submit-password = Password
+submit-sign-in-button = Sign in
+submit-repository = Repository
+submit-path = Path
+submit-path-hint = (optional, for monorepos)
+submit-feedback = Feedback
+submit-button = 🚀 Submit
+submit-sign-out = Not you? Sign out
+submit-processing = 📡 Processing
+submit-status = Status:
+submit-open-pr = Open the pull request
+submit-connection-lost = Live updates stopped; reload to see the latest status.
+submit-more = 📝 Submit more feedback
+
+diff = 🔍 Proposed Changes
+    .title = Proposed changes - { $repository }
+    .empty = No changes have been proposed for this feedback yet.
+diff-approve = 👍 Approve
+diff-reject = 👎 Reject
+diff-reject-close = 👎 Reject and close the PR
+diff-reject-confirm = Reject these changes and close the pull requests still open for them?
+diff-approved = ✅ Approved, thanks!
+diff-rejected = 🛑 Rejected, thanks!
+diff-session-expired = Your session has expired; sign in on the submit page and try again.
+diff-not-recorded = The decision couldn't be recorded.
+
+admin-console = 👑 Admin Console
+    .title = Admin
+    .note = Errors and rate-limit hits are the latest ones seen by this instance.
+admin-manage-users = 👥 Manage users
+admin-job-queues = 📮 Job Queues
+admin-no-jobs = No unfinished jobs.
+admin-column-job-type = Job type
+admin-column-pending = Pending
+admin-column-running = Running
+admin-column-dead = Dead
+admin-requeue-dead = 🔁 Requeue dead
+admin-requeue-dead-confirm = Requeue every dead { $job_type } job?
+admin-dead-letters = ☠️ Dead Letters ({ $count })
+admin-no-dead-letters = The dead-letter queue is empty.
+admin-column-dead-since = Dead since
+admin-column-job = Job
+admin-column-attempts = Attempts
+admin-column-last-error = Last error
+admin-requeue = 🔁 Requeue
+admin-recent-errors = 💥 Recent Errors
+admin-no-errors = No unexpected errors since this instance started.
+admin-column-when = When
+admin-column-request = Request
+admin-column-code = Code
+admin-column-what-failed = What failed
+admin-rate-limit-hits = 🚫 Rate-Limit Hits
+admin-no-rate-limit-hits = Nobody has hit a rate limit since this instance started.
+admin-column-client = Client
+admin-column-path = Path
+admin-column-limit = Limit
+admin-tier = ({ $tier } tier)
+admin-action-failed = That didn't work
+
+admin-users = 👥 Users
+    .title = Users - Admin
+admin-back = 👑 Back to the admin console
+admin-column-account = Account
+admin-column-role = Role
+admin-column-last-login = Last login
+admin-user-active = active
+admin-you = (you)
+admin-make-admin = 👑 Make admin
+admin-make-admin-confirm = Make { $email } an admin?
+admin-make-user = 👤 Make user
+admin-deactivate = 🚫 Deactivate
+admin-deactivate-confirm = Deactivate { $email } and sign them out everywhere?
+admin-activate = ✅ Activate
+admin-newer = ⬅️ Newer
+admin-older = Older ➡️
+admin-users-page = Page { $page } of { $pages } ({ $total ->
+        [one] 1 account
+       *[other] { $total } accounts
+    })
+
+## 📄 Plain pages (heading, .title and .body)
+
+page-error = 💥 Something went wrong
+    .title = Something went wrong
+    .body = This page couldn't be loaded. If it keeps happening, mention request { $request_id }.
+page-project-not-found = 🔍 Project not found
+    .title = Project not found
+    .body = There's no project here, or you don't have access to it.
+page-status-not-found = 🔍 Status page not found
+    .title = Status page not found
+    .body = There's no public status page here.
+page-feedback-not-found = 🔍 Feedback not found
+    .title = Feedback not found
+    .body = There's no feedback here, or you don't have access to it.
+page-not-found = 🔍 Page not found
+    .title = Page not found
+    .body = There's nothing at { $path }.
+page-login = 🔐 Login
+    .title = Login
+    .body = Coming soon...
+page-register = 📝 Register
+    .title = Register
+    .body = Coming soon...
+page-docs = 📚 Documentation
+    .title = Documentation
+    .body = Coming soon...
+page-about = ℹ️ About Feedbacker
+    .title = About
+    .body = AI-powered repository management by Aye & Hue!
+page-digest-unsubscribed = 📭 Unsubscribed
+    .title = Unsubscribed
+    .body = You won't get activity digests anymore. Turn them back on in your preferences whenever you like.
+page-digest-link-invalid = 🔗 Invalid link
+    .title = Invalid link
+    .body = This unsubscribe link isn't valid. Make sure you opened the whole link from the email.
+page-feedback-claimed = 🙋 Feedback claimed
+    .title = Feedback claimed
+    .body = The feedback you left with { $email } is now on your account.
+page-feedback-claim-confirmed = ✅ Email confirmed
+    .title = Email confirmed
+    .body = Sign in or register as { $email } and the feedback you left with it will be on your account.
+page-feedback-claim-link-invalid = 🔗 Invalid link
+    .title = Invalid link
+    .body = This claim link isn't valid or has expired. Make sure you opened the whole link from the email.
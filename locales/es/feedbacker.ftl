@@ -0,0 +1,453 @@
+# 🌍 Mensajes de Feedbacker - Español
+# Los ids son los mismos que en locales/en/feedbacker.ftl; lo que falte aquí
+# se muestra en inglés
+
+## 📡 Respuestas de la API
+
+operation-failed = La operación falló
+resource-not-found = No se encontró: { $resource }
+rate-limit-try-later = Límite de solicitudes superado. Inténtalo de nuevo más tarde.
+rate-limit-exceeded = Límite de solicitudes superado para { $limit_type }. Inténtalo de nuevo en { $seconds ->
+        [one] { $seconds } segundo
+       *[other] { $seconds } segundos
+    }.
+no-endpoint = No hay ningún endpoint en { $method } { $path }
+method-only-accepts = Este endpoint solo acepta { $methods }
+preferences-updated = Preferencias actualizadas
+profile-retrieved = Perfil obtenido
+profile-updated = Perfil actualizado
+password-changed = Contraseña cambiada
+notifications-retrieved = Notificaciones obtenidas
+notification-marked-read = Notificación marcada como leída
+unsupported-locale = Idioma '{ $locale }' no disponible. Disponibles: { $supported }
+unsupported-digest-frequency = Frecuencia de resumen '{ $frequency }' no disponible. Disponibles: { $supported }
+health-check-completed = Comprobación de estado completada
+health-check-detailed-completed = Comprobación de estado detallada completada
+version-info-retrieved = Información de versión obtenida
+rate-limits-retrieved = Límites de solicitudes obtenidos
+issue-automation-completed = Automatización de la incidencia completada
+webhook-pong = pong
+webhook-pong-missing-events = pong, pero el webhook no envía { $events }
+webhook-push-processed = Push procesado
+webhook-installation-processed = Instalación procesada
+
+## 🧯 Tipos de error (el `title` de los documentos de problema)
+
+error-validation = La validación de la solicitud falló
+error-unauthorized = Se requiere autenticación
+error-forbidden = Acceso denegado
+error-not-found = No encontrado
+error-method-not-allowed = Método no permitido
+error-conflict = Entra en conflicto con el estado actual
+error-quota-exceeded = Cuota superada
+error-rate-limited = Límite de solicitudes superado
+error-maintenance = En mantenimiento
+error-login-method-disabled = Método de inicio de sesión desactivado
+error-sso-failed = El inicio de sesión único falló
+error-upstream = Falló un servicio externo
+error-invalid-configuration = Configuración no válida
+error-repository-not-accessible = Repositorio no accesible
+error-unavailable = No disponible temporalmente
+error-internal = Se produjo un error interno
+
+## 🔐 Autenticación
+
+auth-token-required = Se requiere un token de autenticación
+auth-insufficient-permissions = Permisos insuficientes
+ip-not-allowed = No se permiten solicitudes a este endpoint desde tu red
+request-signature-required = Esta cuenta debe firmar sus solicitudes (cabeceras X-Feedbacker-Timestamp y X-Feedbacker-Signature)
+request-signature-expired = La solicitud se firmó hace demasiado tiempo; revisa el reloj del cliente
+request-signature-invalid = La firma de la solicitud no coincide
+request-signature-replayed = Esta solicitud firmada ya se recibió
+request-signature-body-too-large = El cuerpo de la solicitud es demasiado grande para verificar su firma
+auth-missing-scope = A esta clave de API le falta el ámbito { $scope }
+auth-no-project-access = No tienes acceso a este proyecto
+auth-project-access-unverified = No se pudo verificar el acceso al proyecto
+auth-invalid-user = Usuario no válido o cuenta desactivada
+auth-invalid-token = Token no válido o caducado
+auth-login-successful = Sesión iniciada
+auth-registration-successful = Registro completado
+auth-logout-successful = Sesión cerrada
+auth-password-login-disabled = El inicio de sesión con contraseña está desactivado; entra con el inicio de sesión único de tu organización
+auth-sso-required = Tu organización exige inicio de sesión único; entra a través de tu proveedor de identidad
+auth-password-accounts-disabled = Las cuentas con contraseña están desactivadas; entra con el inicio de sesión único de tu organización
+
+## ✅ Validación
+
+validation-email-required = Se requiere un correo electrónico válido
+validation-password-required = Se requiere la contraseña
+validation-name-required = Se requiere el nombre
+validation-password-too-short = La contraseña debe tener al menos { $min } caracteres
+validation-name-too-long = El nombre no puede superar los { $max } caracteres
+validation-github-username = Los usuarios de GitHub tienen hasta 39 letras, dígitos o guiones sueltos, sin guion al principio ni al final
+validation-current-password-incorrect = La contraseña actual no es correcta
+validation-repository-empty = El repositorio no puede estar vacío
+validation-repository-format = El repositorio debe tener el formato 'propietario/repositorio'
+validation-content-empty = El contenido del comentario no puede estar vacío
+validation-content-too-long = El contenido del comentario no puede superar los { $max } caracteres
+validation-content-too-short = El contenido del comentario debe tener al menos { $min } caracteres
+validation-llm-provider = Proveedor de LLM no válido. Disponibles: { $supported }
+validation-email-invalid = Correo electrónico no válido
+validation-bulk-ids-empty = Se necesita al menos un ID de comentario
+validation-bulk-ids-too-many = Se pueden cambiar como máximo { $max } comentarios a la vez
+validation-bulk-ids-duplicate = Los IDs de comentario no se pueden repetir
+validation-bulk-label = set-label necesita una etiqueta de 1 a { $max } caracteres
+validation-bulk-label-unexpected = Solo set-label admite una etiqueta
+validation-comment-empty = La nota no puede estar vacía
+validation-comment-too-long = Las notas no pueden superar { $max } caracteres
+validation-assignee-not-maintainer = Solo se puede asignar a miembros activos de un proyecto de su repositorio
+validation-budget-not-exceeded = Solo se puede aprobar que continúe un comentario detenido por su presupuesto de tokens
+validation-budget-tokens = Aprueba al menos 1 token más (no hay límite por comentario, así que la cantidad es obligatoria)
+
+## 📝 Comentarios
+
+feedback-submitted = ¡Comentario enviado! El procesamiento empezará en breve.
+feedback-found = Comentario encontrado
+feedback-events-retrieved = Eventos del comentario obtenidos
+feedback-trace-retrieved = Traza del comentario obtenida
+feedback-list-retrieved = Lista de comentarios obtenida
+feedback-stats-retrieved = Estadísticas obtenidas
+feedback-retry-queued = Reintento del procesamiento en cola
+feedback-approval-recorded = Aprobación registrada
+feedback-rejected = { $closed ->
+        [0] Rechazo registrado; no había ninguna pull request abierta
+        [one] Rechazo registrado y su pull request cerrada
+       *[other] Rechazo registrado y { $closed } pull requests cerradas
+    }
+feedback-rejected-partly = Rechazo registrado, pero { $failed ->
+        [one] una pull request no se pudo cerrar
+       *[other] { $failed } pull requests no se pudieron cerrar
+    }; inténtalo de nuevo o ciérralas en GitHub
+feedback-rejection-recorded = Rechazo registrado; los responsables del proyecto deciden si cierran sus pull requests
+feedback-budget-approved = Aprobados { $tokens } tokens más; el procesamiento continuará en breve
+feedback-diff-retrieved = Diff propuesto obtenido
+feedback-bulk-applied = Aplicado a { $succeeded } de { $total } comentarios
+feedback-assigned = Comentario asignado
+feedback-unassigned = Asignación retirada
+feedback-comments-retrieved = Notas obtenidas
+feedback-comment-added = Nota añadida
+feedback-comment-deleted = Nota eliminada
+issues-imported = { $count ->
+        [one] Se importó 1 incidencia
+       *[other] Se importaron { $count } incidencias
+    }
+export-queued = Exportación en cola. Consulta su estado para obtener el enlace de descarga.
+export-retrieved = Exportación obtenida
+export-link-invalid = Este enlace de descarga no es válido o ha caducado
+artifacts-retrieved = Artefactos obtenidos
+artifact-link-invalid = Este enlace al artefacto no es válido o ha caducado
+
+## 📦 Proyectos
+
+projects-retrieved = Proyectos obtenidos
+project-retrieved = Proyecto obtenido
+project-status-retrieved = Estado del proyecto obtenido
+project-collaborators = { $count ->
+        [one] 1 colaborador
+       *[other] { $count } colaboradores
+    }
+project-webhook-deliveries = { $count ->
+        [one] 1 entrega de webhook
+       *[other] { $count } entregas de webhook
+    }
+project-style-profile-retrieved = Perfil de estilo obtenido
+project-style-analysis-queued = Análisis de estilo en cola
+project-style-analysis-already-queued = El análisis de estilo ya está en cola
+project-config-updated = Configuración del proyecto actualizada
+project-run-queued = Ejecución del proyecto en cola
+
+## 🏢 Organizaciones
+
+organization-created = Organización creada
+organizations-retrieved = Organizaciones obtenidas
+organization-retrieved = Organización obtenida
+organization-settings-updated = Ajustes de la organización actualizados
+team-created = Equipo creado
+organization-project-assigned = Proyecto asignado a la organización
+organization-project-removed = Proyecto quitado de la organización
+organization-api-key-issued = Clave de API emitida; solo se muestra una vez
+organization-sso-retrieved = Proveedor de inicio de sesión único obtenido
+organization-sso-saved = Proveedor de inicio de sesión único guardado
+
+## 👑 Administración
+
+admin-overview-retrieved = Resumen obtenido
+admin-users-retrieved = Usuarios obtenidos
+admin-user-updated = Usuario actualizado
+admin-llm-exchanges-retrieved = Intercambios con el LLM obtenidos
+admin-llm-exchange-retrieved = Intercambio con el LLM obtenido
+admin-prompt-versions-retrieved = Versiones del prompt obtenidas
+admin-prompt-version-created = Versión del prompt creada
+admin-prompt-stats-retrieved = Estadísticas del prompt obtenidas
+admin-dead-jobs-retrieved = Trabajos descartados obtenidos
+admin-job-retrieved = Trabajo obtenido
+admin-job-requeued = Trabajo devuelto a la cola
+admin-dead-jobs-requeued = Trabajos descartados devueltos a la cola
+admin-dead-jobs-purged = Trabajos descartados eliminados
+admin-schedules-retrieved = Programaciones obtenidas
+admin-schedule-paused = Programación pausada
+admin-schedule-resumed = Programación reanudada
+admin-schedule-triggered = Programación lanzada
+admin-config-reloaded = Configuración recargada: { $count ->
+        [one] 1 ajuste cambiado
+       *[other] { $count } ajustes cambiados
+    }
+admin-flags-retrieved = Indicadores de funcionalidad obtenidos
+admin-flag-created = Indicador de funcionalidad creado
+admin-flag-retrieved = Indicador de funcionalidad obtenido
+admin-flag-updated = Indicador de funcionalidad actualizado
+admin-flag-evaluated = Indicador de funcionalidad evaluado
+admin-flag-override-set = Excepción del indicador de funcionalidad guardada
+admin-maintenance-retrieved = Estado de mantenimiento obtenido
+admin-maintenance-updated = Modo de mantenimiento actualizado
+admin-roles-retrieved = Roles obtenidos
+admin-role-created = Rol creado
+admin-role-updated = Rol actualizado
+admin-quotas-updated = Cuotas de la organización actualizadas
+
+## ⏱️ Comentarios atascados
+
+feedback-stuck-title = Un comentario se quedó atascado
+feedback-stuck-failed = Un comentario sobre { $repository } pasó más de { $minutes } minutos en { $status } sin nada que lo procesara y se marcó como fallido
+feedback-stuck-requeued = Un comentario sobre { $repository } pasó más de { $minutes } minutos en { $status } sin nada que lo procesara y volvió a la cola
+
+## 💸 Presupuestos de tokens
+
+budget-exceeded-title = Comentario detenido por su presupuesto de tokens
+budget-exceeded-feedback = Un comentario sobre { $repository } usó { $used } de sus { $limit } tokens y espera tu aprobación para continuar
+budget-exceeded-project = { $repository } usó { $used } de sus { $limit } tokens este mes; un comentario espera tu aprobación para continuar
+
+## 🔌 Acceso de la GitHub App
+
+project-deactivated-title = Proyecto desactivado
+project-deactivated-access-revoked = La GitHub App ya no tiene acceso a { $repository }, así que su proyecto se desactivó. Volverá a estar activo cuando la app recupere el acceso al repositorio
+
+## 📬 Resúmenes de actividad
+
+digest-subject = { $frequency ->
+        [daily] 📬 Tu resumen diario de Feedbacker
+       *[weekly] 📬 Tu resumen semanal de Feedbacker
+    }
+digest-greeting = Hola { $name },
+digest-intro = { $frequency ->
+        [daily] Esto es lo que pasó en tus proyectos desde ayer.
+       *[weekly] Esto es lo que pasó en tus proyectos esta semana.
+    }
+digest-new-feedback = { $count ->
+        [one] 1 comentario nuevo
+       *[other] { $count } comentarios nuevos
+    }
+digest-pull-requests-opened = { $count ->
+        [one] 1 pull request abierto
+       *[other] { $count } pull requests abiertos
+    }
+digest-pull-requests-merged = { $count ->
+        [one] 1 pull request fusionado
+       *[other] { $count } pull requests fusionados
+    }
+digest-failed = { $count ->
+        [one] 1 fallo requiere atención
+       *[other] { $count } fallos requieren atención
+    }
+digest-needs-attention = ❌ Requiere atención
+digest-view-project = Ver proyecto
+digest-footer = Recibes este resumen { $frequency ->
+        [daily] cada día
+       *[weekly] cada semana
+    }. Cambia la frecuencia con PUT /api/users/me/preferences.
+digest-unsubscribe = Darse de baja
+
+## 🙋 Reclamar comentarios anónimos
+
+feedback-claim-subject = 🙋 Sigue tus comentarios sobre { $repository }
+feedback-claim-greeting = Hola,
+feedback-claim-intro = ¡Gracias por tus comentarios sobre { $repository }! Inicia sesión en Feedbacker, o crea una cuenta, con esta dirección de correo y abre el enlace de abajo para seguir tus comentarios mientras se convierten en un pull request.
+feedback-claim-action = Reclamar tus comentarios
+feedback-claim-footer = El enlace funciona durante { $days } días. ¿No dejaste ningún comentario? Ignora este correo.
+
+## 🔐 Alertas de seguridad
+
+security-alert-subject = 🔐 Alerta de seguridad de Feedbacker: { $title }
+security-alert-greeting = Hola { $name },
+security-alert-new-device-login = Nuevo inicio de sesión desde otro dispositivo
+    .body = Se inició sesión en tu cuenta desde { $device } en { $ip }, un dispositivo que nunca se había usado con ella.
+security-alert-password-changed = Se cambió tu contraseña
+    .body = Se cambió la contraseña de tu cuenta de Feedbacker.
+security-alert-api-key-created = Se emitió una clave de API
+    .body = { $issuer } emitió una clave de API para { $account } en { $organization }.
+security-alert-unknown-device = un dispositivo desconocido
+security-alert-occurred-at = Cuándo: { $time }
+security-alert-advice = Si no fuiste tú, cambia tu contraseña y cierra tus sesiones cuanto antes.
+security-alert-footer = Recibes estos correos porque las alertas de seguridad están activadas. Desactívalas con PUT /api/users/me/preferences.
+
+## 🎨 Interfaz web
+
+nav-projects = 📊 Proyectos
+nav-submit = 📝 Enviar
+nav-docs = 📚 Documentación
+nav-about = ℹ️ Acerca de
+nav-admin = 👑 Administración
+nav-login = 🔐 Entrar
+footer = Hecho con ❤️ por Aye & Hue
+
+status-inactive = inactivo
+counts-feedback = Comentarios
+counts-suggestions = Sugerencias
+counts-open = Abiertos
+counts-completed = Completados
+counts-failed = Fallidos
+counts-merged = Fusionados
+column-repository = Repositorio
+column-submitted = Enviado
+column-status = Estado
+column-feedback = Comentario
+column-suggestion = Sugerencia
+column-pull-request = Pull request
+column-merged = Fusionado
+column-last-activity = Última actividad
+link-github = 🐙 Ver en GitHub
+link-submit-feedback = 📝 Enviar un comentario
+link-open-pr = 🔗 Abrir PR
+
+projects = 🏠 Panel de proyectos
+    .title = Proyectos
+    .empty = Todavía no hay proyectos. Registra uno a través de la API y aparecerá aquí:
+
+project-recent-feedback = 🕒 Comentarios recientes
+project-no-feedback = Este proyecto todavía no tiene comentarios.
+
+status-page = Estado de { $repository }
+status-recently-merged = 🎉 Fusionados recientemente
+status-no-merged = Todavía no se ha fusionado ninguna pull request de Feedbacker.
+status-open-suggestions = 💡 Sugerencias abiertas
+status-nothing-open = No hay nada en curso ahora mismo.
+
+submit = 📝 Enviar un comentario
+    .title = Enviar un comentario
+    .intro = Cuéntanos qué mejorar y mira cómo se convierte en una pull request.
+submit-sign-in = 🔐 Primero inicia sesión
+submit-email = Correo electrónico
+submit-password = Contraseña
+submit-sign-in-button = Entrar
+submit-repository = Repositorio
+submit-path = Ruta
+submit-path-hint = (opcional, para monorepos)
+submit-feedback = Comentario
+submit-button = 🚀 Enviar
+submit-sign-out = ¿No eres tú? Cierra la sesión
+submit-processing = 📡 Procesando
+submit-status = Estado:
+submit-open-pr = Abrir la pull request
+submit-connection-lost = Las actualizaciones en directo se detuvieron; recarga para ver el estado más reciente.
+submit-more = 📝 Enviar otro comentario
+
+diff = 🔍 Cambios propuestos
+    .title = Cambios propuestos - { $repository }
+    .empty = Todavía no se ha propuesto ningún cambio para este comentario.
+diff-approve = 👍 Aprobar
+diff-reject = 👎 Rechazar
+diff-reject-close = 👎 Rechazar y cerrar la PR
+diff-reject-confirm = ¿Rechazar estos cambios y cerrar las pull requests que sigan abiertas?
+diff-approved = ✅ Aprobado, ¡gracias!
+diff-rejected = 🛑 Rechazado, ¡gracias!
+diff-session-expired = Tu sesión ha caducado; inicia sesión en la página de envío y vuelve a intentarlo.
+diff-not-recorded = No se pudo registrar la decisión.
+
+admin-console = 👑 Consola de administración
+    .title = Administración
+    .note = Los errores y los límites alcanzados son los últimos que ha visto esta instancia.
+admin-manage-users = 👥 Gestionar usuarios
+admin-job-queues = 📮 Colas de trabajos
+admin-no-jobs = No hay trabajos pendientes.
+admin-column-job-type = Tipo de trabajo
+admin-column-pending = Pendientes
+admin-column-running = En curso
+admin-column-dead = Descartados
+admin-requeue-dead = 🔁 Reencolar descartados
+admin-requeue-dead-confirm = ¿Devolver a la cola todos los trabajos { $job_type } descartados?
+admin-dead-letters = ☠️ Trabajos descartados ({ $count })
+admin-no-dead-letters = La cola de descartados está vacía.
+admin-column-dead-since = Descartado desde
+admin-column-job = Trabajo
+admin-column-attempts = Intentos
+admin-column-last-error = Último error
+admin-requeue = 🔁 Reencolar
+admin-recent-errors = 💥 Errores recientes
+admin-no-errors = No ha habido errores inesperados desde que arrancó esta instancia.
+admin-column-when = Cuándo
+admin-column-request = Solicitud
+admin-column-code = Código
+admin-column-what-failed = Qué falló
+admin-rate-limit-hits = 🚫 Límites de solicitudes alcanzados
+admin-no-rate-limit-hits = Nadie ha alcanzado un límite de solicitudes desde que arrancó esta instancia.
+admin-column-client = Cliente
+admin-column-path = Ruta
+admin-column-limit = Límite
+admin-tier = (nivel { $tier })
+admin-action-failed = No funcionó
+
+admin-users = 👥 Usuarios
+    .title = Usuarios - Administración
+admin-back = 👑 Volver a la consola de administración
+admin-column-account = Cuenta
+admin-column-role = Rol
+admin-column-last-login = Último acceso
+admin-user-active = activo
+admin-you = (tú)
+admin-make-admin = 👑 Hacer administrador
+admin-make-admin-confirm = ¿Hacer administrador a { $email }?
+admin-make-user = 👤 Hacer usuario
+admin-deactivate = 🚫 Desactivar
+admin-deactivate-confirm = ¿Desactivar a { $email } y cerrar todas sus sesiones?
+admin-activate = ✅ Activar
+admin-newer = ⬅️ Más recientes
+admin-older = Más antiguas ➡️
+admin-users-page = Página { $page } de { $pages } ({ $total ->
+        [one] 1 cuenta
+       *[other] { $total } cuentas
+    })
+
+## 📄 Páginas sencillas (título, .title y .body)
+
+page-error = 💥 Algo salió mal
+    .title = Algo salió mal
+    .body = No se pudo cargar esta página. Si sigue ocurriendo, menciona la solicitud { $request_id }.
+page-project-not-found = 🔍 Proyecto no encontrado
+    .title = Proyecto no encontrado
+    .body = Aquí no hay ningún proyecto, o no tienes acceso a él.
+page-status-not-found = 🔍 Página de estado no encontrada
+    .title = Página de estado no encontrada
+    .body = Aquí no hay ninguna página de estado pública.
+page-feedback-not-found = 🔍 Comentario no encontrado
+    .title = Comentario no encontrado
+    .body = Aquí no hay ningún comentario, o no tienes acceso a él.
+page-not-found = 🔍 Página no encontrada
+    .title = Página no encontrada
+    .body = No hay nada en { $path }.
+page-login = 🔐 Entrar
+    .title = Entrar
+    .body = Próximamente...
+page-register = 📝 Registrarse
+    .title = Registrarse
+    .body = Próximamente...
+page-docs = 📚 Documentación
+    .title = Documentación
+    .body = Próximamente...
+page-about = ℹ️ Acerca de Feedbacker
+    .title = Acerca de
+    .body = ¡Gestión de repositorios con IA, por Aye & Hue!
+page-digest-unsubscribed = 📭 Baja confirmada
+    .title = Baja confirmada
+    .body = Ya no recibirás resúmenes de actividad. Puedes volver a activarlos en tus preferencias cuando quieras.
+page-digest-link-invalid = 🔗 Enlace no válido
+    .title = Enlace no válido
+    .body = Este enlace para darse de baja no es válido. Asegúrate de abrir el enlace completo del correo.
+page-feedback-claimed = 🙋 Comentarios reclamados
+    .title = Comentarios reclamados
+    .body = Los comentarios que dejaste con { $email } ya están en tu cuenta.
+page-feedback-claim-confirmed = ✅ Correo confirmado
+    .title = Correo confirmado
+    .body = Inicia sesión o regístrate como { $email } y los comentarios que dejaste con él estarán en tu cuenta.
+page-feedback-claim-link-invalid = 🔗 Enlace no válido
+    .title = Enlace no válido
+    .body = Este enlace para reclamar no es válido o ha caducado. Asegúrate de abrir el enlace completo del correo.
@@ -0,0 +1,1470 @@
+// 👑 Admin API - Looking Under the Hood! 👑
+// Admin-only endpoints for debugging the pipeline. Everything under /api/admin/
+// requires the SystemAdmin permission (see middleware/auth.rs)
+// Created with love by Aye & Hue - Evidence beats guesswork! ✨
+
+use crate::{
+    api::{
+        utils::{not_found_error, validation_error},
+        ApiResponse, AppState, ErrorResponse, PaginatedResponse, PaginationParams,
+    },
+    database::models::{
+        BackgroundJob, DeadJobFilter, FeatureFlag, FeatureFlagOverride, JobQueueDepth, LlmExchange,
+        LlmExchangeFilter, Organization, Project, PromptVersion, PromptVersionStats, Role,
+        ScheduledJob, User, UserRole,
+    },
+    config::InvalidConfig,
+    errors::{self, ErrorKind, RecentError},
+    feature_flags::{self, FlagEvaluation},
+    i18n,
+    jobs::schedules::{self, Schedules},
+    llm::experiments,
+    middleware::{
+        auth::{AuthenticatedUser, Permission},
+        rate_limiting::RateLimitHit,
+    },
+    reload, roles,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// 🔍 Filter for listing prompt versions
+#[derive(Debug, Deserialize)]
+pub struct PromptVersionQuery {
+    /// 🏷️ Only this stage's versions
+    pub name: Option<String>,
+}
+
+/// ➕ A new candidate prompt version
+#[derive(Debug, Deserialize)]
+pub struct CreatePromptVersionRequest {
+    /// 📄 Template body (may only use the built-in template's placeholders)
+    pub body: String,
+    /// 📝 What this version changes
+    pub description: Option<String>,
+    /// 🎲 Share of feedback items to serve it to
+    #[serde(default)]
+    pub traffic_percent: i32,
+}
+
+/// 🎲 New traffic share for a candidate
+#[derive(Debug, Deserialize)]
+pub struct PromptTrafficRequest {
+    pub traffic_percent: i32,
+}
+
+/// 🔁 Bulk requeue of one job type's dead letters
+#[derive(Debug, Deserialize)]
+pub struct RequeueDeadJobsRequest {
+    pub job_type: String,
+}
+
+/// 🚩 A new feature flag
+#[derive(Debug, Deserialize)]
+pub struct CreateFeatureFlagRequest {
+    pub name: String,
+    /// 📝 What the flag switches
+    pub description: Option<String>,
+    /// 🌍 Global switch (off by default)
+    #[serde(default)]
+    pub enabled: bool,
+    /// 🎲 Percent of projects it is on for while enabled (default 100)
+    pub rollout_percent: Option<i32>,
+}
+
+/// ✏️ Changes to a feature flag (omitted fields keep their value)
+#[derive(Debug, Deserialize)]
+pub struct UpdateFeatureFlagRequest {
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub rollout_percent: Option<i32>,
+}
+
+/// 🎯 A project's own setting of a flag
+#[derive(Debug, Deserialize)]
+pub struct FeatureFlagOverrideRequest {
+    pub enabled: bool,
+}
+
+/// 🔍 Project to evaluate a flag for
+#[derive(Debug, Deserialize)]
+pub struct EvaluateFeatureFlagQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// 🚩 A feature flag with its per-project overrides
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagDetails {
+    #[serde(flatten)]
+    pub flag: FeatureFlag,
+    pub overrides: Vec<FeatureFlagOverride>,
+}
+
+/// 🚧 Switch maintenance mode on or off
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
+    /// 💬 What users are told (keeps the previous message when omitted)
+    pub message: Option<String>,
+}
+
+/// 🎭 New role
+#[derive(Debug, Deserialize)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// 🎯 Permission names (see GET /api/admin/roles)
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// ✏️ New description and permissions of a role
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+/// 🎭 Roles and the permissions they can grant
+#[derive(Debug, Serialize)]
+pub struct RolesOverview {
+    pub roles: Vec<Role>,
+    pub permissions: Vec<&'static str>,
+}
+
+/// 📏 New organization quotas (None = unlimited)
+#[derive(Debug, Deserialize)]
+pub struct OrganizationQuotasRequest {
+    pub max_projects: Option<i32>,
+    pub max_monthly_feedback: Option<i32>,
+}
+
+/// 🧹 Which dead letters to purge
+#[derive(Debug, Deserialize)]
+pub struct PurgeDeadJobsQuery {
+    /// 📅 Only jobs dead-lettered more than this many days ago (default 30)
+    pub older_than_days: Option<i64>,
+}
+
+/// ☠️ Dead letters shown on the admin overview
+const OVERVIEW_DEAD_LETTERS: u32 = 10;
+
+/// 📊 Operational state at a glance, for the admin console
+#[derive(Debug, Serialize)]
+pub struct AdminOverview {
+    /// 📮 Unfinished jobs per type
+    pub queues: Vec<JobQueueDepth>,
+    /// ☠️ The latest dead-lettered jobs
+    pub dead_letters: Vec<BackgroundJob>,
+    /// ☠️ Every dead-lettered job, counted
+    pub dead_letter_total: u64,
+    /// 💥 The latest unexpected failures on this instance
+    pub recent_errors: Vec<RecentError>,
+    /// 🚫 The latest rate-limited requests on this instance
+    pub rate_limit_hits: Vec<RateLimitHit>,
+}
+
+/// 👤 An account as admins see it
+#[derive(Debug, Serialize)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    /// 👑 user, admin or service
+    pub role: &'static str,
+    pub is_active: bool,
+    /// 🏢 Organization owning this service account
+    pub organization_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<User> for UserSummary {
+    fn from(user: User) -> Self {
+        Self {
+            role: roles::builtin_role(&user.role),
+            id: user.id,
+            email: user.email,
+            name: user.name,
+            is_active: user.is_active,
+            organization_id: user.organization_id,
+            created_at: user.created_at,
+            last_login_at: user.last_login_at,
+        }
+    }
+}
+
+/// ✏️ Changes to an account (omitted fields keep their value)
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    /// 👑 "user" or "admin"
+    pub role: Option<String>,
+    /// 🚫 false signs the account out everywhere and stops it signing in
+    pub is_active: Option<bool>,
+}
+
+/// 📊 Queue depth, dead letters, recent errors and rate-limit hits
+pub async fn load_overview(app_state: &AppState) -> anyhow::Result<AdminOverview> {
+    let queues = BackgroundJob::queue_depth(&app_state.db_pool).await?;
+    let (dead_letters, dead_letter_total) = BackgroundJob::list_dead(
+        &app_state.db_pool,
+        &DeadJobFilter::default(),
+        "dead_lettered_at DESC",
+        OVERVIEW_DEAD_LETTERS,
+        0,
+    )
+    .await?;
+
+    Ok(AdminOverview {
+        queues,
+        dead_letters,
+        dead_letter_total,
+        recent_errors: errors::recent_errors(),
+        rate_limit_hits: app_state.rate_limits.recent_hits(),
+    })
+}
+
+/// 📊 Operational state at a glance (errors and rate-limit hits are this instance's)
+pub async fn get_overview(State(app_state): State<AppState>) -> Response {
+    match load_overview(&app_state).await {
+        Ok(overview) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-overview-retrieved"),
+                overview,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 👥 Every account, newest first; paginate with `page` and `limit`, sort
+/// with `sort_by` (any of User::SORTABLE)
+pub async fn list_users(
+    State(app_state): State<AppState>,
+    Query(pagination): Query<PaginationParams>,
+) -> Response {
+    let pagination = pagination.validate();
+    let order_by = match pagination.order_by(User::SORTABLE, "created_at") {
+        Ok(order_by) => order_by,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+
+    match User::list(
+        &app_state.db_pool,
+        &order_by,
+        pagination.limit,
+        pagination.offset(),
+    )
+    .await
+    {
+        Ok((users, total)) => {
+            let items = users.into_iter().map(UserSummary::from).collect();
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-users-retrieved"),
+                    PaginatedResponse::new(items, pagination.page, pagination.limit, total),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ✏️ Change an account's role or switch it on or off
+/// Admins can't demote or deactivate themselves, so there's always one left
+pub async fn update_user(
+    State(app_state): State<AppState>,
+    Extension(admin): Extension<AuthenticatedUser>,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Response {
+    let role = match request.role.as_deref() {
+        None => None,
+        Some("user") => Some(UserRole::User),
+        Some("admin") => Some(UserRole::Admin),
+        Some(_) => {
+            return validation_error(vec!["role must be user or admin".to_string()]).into_response()
+        }
+    };
+    if user_id == admin.id && (role.is_some() || request.is_active == Some(false)) {
+        return validation_error(vec![
+            "You can't change your own role or deactivate yourself".to_string(),
+        ])
+        .into_response();
+    }
+
+    let result = async {
+        let Some(mut user) = User::find_by_id(&app_state.db_pool, user_id).await? else {
+            return Ok(Err(not_found_error("User").into_response()));
+        };
+        if let Some(role) = role {
+            if matches!(user.role, UserRole::Service) {
+                return Ok(Err(validation_error(vec![
+                    "Service accounts keep the service role".to_string(),
+                ])
+                .into_response()));
+            }
+            user.set_role(&app_state.db_pool, role).await?;
+        }
+        if let Some(is_active) = request.is_active {
+            user.set_active(&app_state.db_pool, is_active).await?;
+            if !is_active {
+                User::revoke_tokens(&app_state.db_pool, Some(user.id)).await?;
+            }
+        }
+        Ok::<_, anyhow::Error>(Ok(user))
+    };
+
+    match result.await {
+        Ok(Ok(user)) => {
+            app_state.roles.invalidate();
+            info!(
+                "👤 User {} is now {} ({})",
+                user.id,
+                roles::builtin_role(&user.role),
+                if user.is_active { "active" } else { "inactive" }
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-user-updated"),
+                    UserSummary::from(user),
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 📜 Browse stored LLM exchanges, newest first
+/// Filter with `feedback_id`, `project_id`, and `stage`; paginate with `page` and `limit`;
+/// sort with `sort_by` (any of LlmExchange::SORTABLE)
+pub async fn list_llm_exchanges(
+    State(app_state): State<AppState>,
+    Query(filter): Query<LlmExchangeFilter>,
+    Query(pagination): Query<PaginationParams>,
+) -> Response {
+    let pagination = pagination.validate();
+    info!("📜 Listing LLM exchanges: {:?}", filter);
+    let order_by = match pagination.order_by(LlmExchange::SORTABLE, "created_at") {
+        Ok(order_by) => order_by,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+
+    match LlmExchange::list(
+        &app_state.db_pool,
+        &filter,
+        &order_by,
+        pagination.limit,
+        pagination.offset(),
+    )
+    .await
+    {
+        Ok((items, total)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-llm-exchanges-retrieved"),
+                PaginatedResponse::new(items, pagination.page, pagination.limit, total),
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 📜 One stored exchange with its full (redacted) prompt and response
+pub async fn get_llm_exchange(State(app_state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match LlmExchange::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(exchange)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-llm-exchange-retrieved"),
+                exchange,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "LLM exchange not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🧪 Stored prompt versions, optionally for one stage
+pub async fn list_prompt_versions(
+    State(app_state): State<AppState>,
+    Query(query): Query<PromptVersionQuery>,
+) -> Response {
+    match PromptVersion::list(&app_state.db_pool, query.name.as_deref()).await {
+        Ok(versions) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-prompt-versions-retrieved"),
+                versions,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ➕ Add a candidate version for a stage
+pub async fn create_prompt_version(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<CreatePromptVersionRequest>,
+) -> Response {
+    if let Err(errors) = experiments::validate_candidate(&name, &request.body) {
+        return validation_error(errors).into_response();
+    }
+
+    let result = async {
+        let versions = PromptVersion::list(&app_state.db_pool, Some(&name)).await?;
+        if let Err(message) = experiments::validate_traffic(&versions, 0, request.traffic_percent) {
+            return Ok(Err(message));
+        }
+        PromptVersion::create(
+            &app_state.db_pool,
+            &name,
+            &request.body,
+            request.description.as_deref(),
+            request.traffic_percent,
+        )
+        .await
+        .map(Ok)
+    }
+    .await;
+
+    match result {
+        Ok(Ok(version)) => {
+            info!(
+                "🧪 Created {} v{} with {}% of traffic",
+                version.name, version.version, version.traffic_percent
+            );
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(
+                    i18n::t("admin-prompt-version-created"),
+                    version,
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(message)) => validation_error(vec![message]).into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🎲 Change the share of traffic a candidate gets (0 pauses it)
+pub async fn set_prompt_traffic(
+    State(app_state): State<AppState>,
+    Path((name, version)): Path<(String, i32)>,
+    Json(request): Json<PromptTrafficRequest>,
+) -> Response {
+    let result = async {
+        let versions = PromptVersion::list(&app_state.db_pool, Some(&name)).await?;
+        let Some(target) = versions.iter().find(|v| v.version == version) else {
+            return Ok(None);
+        };
+        if target.is_active {
+            return Ok(Some(Err(
+                "The active version serves all remaining traffic".to_string()
+            )));
+        }
+        if let Err(message) =
+            experiments::validate_traffic(&versions, version, request.traffic_percent)
+        {
+            return Ok(Some(Err(message)));
+        }
+        PromptVersion::set_traffic(&app_state.db_pool, &name, version, request.traffic_percent)
+            .await
+            .map(|_| Some(Ok(())))
+    }
+    .await;
+
+    match result {
+        Ok(Some(Ok(()))) => {
+            info!(
+                "🎲 {} v{} now gets {}% of traffic",
+                name, version, request.traffic_percent
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Prompt traffic updated".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(Some(Err(message))) => validation_error(vec![message]).into_response(),
+        Ok(None) => not_found_error("Prompt version").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ✅ Promote a version to the stage's default (version 0 restores the built-in template)
+pub async fn activate_prompt_version(
+    State(app_state): State<AppState>,
+    Path((name, version)): Path<(String, i32)>,
+) -> Response {
+    if !experiments::EXPERIMENT_STAGES.contains(&name.as_str()) {
+        return not_found_error("Prompt").into_response();
+    }
+
+    let result = async {
+        if version != 0
+            && PromptVersion::find(&app_state.db_pool, &name, version)
+                .await?
+                .is_none()
+        {
+            return Ok(false);
+        }
+        PromptVersion::activate(&app_state.db_pool, &name, version)
+            .await
+            .map(|_| true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {
+            info!("✅ {} v{} is now active", name, version);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Prompt version activated".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Prompt version").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 📈 Outcome rates per version of a stage's prompt
+pub async fn get_prompt_stats(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    match PromptVersionStats::for_stage(&app_state.db_pool, &name).await {
+        Ok(stats) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-prompt-stats-retrieved"),
+                stats,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ☠️ Dead-lettered jobs with their payload and error history, most recent first
+/// Filter with `job_type`; paginate with `page` and `limit`; sort with `sort_by`
+/// (any of BackgroundJob::DEAD_SORTABLE)
+pub async fn list_dead_jobs(
+    State(app_state): State<AppState>,
+    Query(filter): Query<DeadJobFilter>,
+    Query(pagination): Query<PaginationParams>,
+) -> Response {
+    let pagination = pagination.validate();
+    let order_by = match pagination.order_by(BackgroundJob::DEAD_SORTABLE, "dead_lettered_at") {
+        Ok(order_by) => order_by,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+
+    match BackgroundJob::list_dead(
+        &app_state.db_pool,
+        &filter,
+        &order_by,
+        pagination.limit,
+        pagination.offset(),
+    )
+    .await
+    {
+        Ok((items, total)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-dead-jobs-retrieved"),
+                PaginatedResponse::new(items, pagination.page, pagination.limit, total),
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔍 One job, in any state
+pub async fn get_job(State(app_state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match BackgroundJob::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(job)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(i18n::t("admin-job-retrieved"), job)),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("Job").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔁 Put a dead-lettered job back in the queue with a fresh set of retries
+pub async fn requeue_job(State(app_state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    match BackgroundJob::requeue(&app_state.db_pool, id).await {
+        Ok(Some(job)) => {
+            info!("🔁 Requeued {} job {}", job.job_type, job.id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(i18n::t("admin-job-requeued"), job)),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Dead-lettered job").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔁 Requeue every dead-lettered job of one type (e.g. after an outage is over)
+pub async fn requeue_dead_jobs(
+    State(app_state): State<AppState>,
+    Json(request): Json<RequeueDeadJobsRequest>,
+) -> Response {
+    if request.job_type.trim().is_empty() {
+        return validation_error(vec!["job_type is required".to_string()]).into_response();
+    }
+
+    match BackgroundJob::requeue_dead(&app_state.db_pool, &request.job_type).await {
+        Ok(requeued) => {
+            info!("🔁 Requeued {} dead {} jobs", requeued, request.job_type);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-dead-jobs-requeued"),
+                    serde_json::json!({ "requeued": requeued }),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🧹 Delete old dead letters
+pub async fn purge_dead_jobs(
+    State(app_state): State<AppState>,
+    Query(query): Query<PurgeDeadJobsQuery>,
+) -> Response {
+    let days = query.older_than_days.unwrap_or(30);
+    if days < 0 {
+        return validation_error(vec!["older_than_days can't be negative".to_string()])
+            .into_response();
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(days);
+    match BackgroundJob::purge_dead(&app_state.db_pool, cutoff).await {
+        Ok(purged) => {
+            info!(
+                "🧹 Purged {} dead-lettered jobs older than {} days",
+                purged, days
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-dead-jobs-purged"),
+                    serde_json::json!({ "purged": purged }),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ⏰ Every recurring schedule with its next and last run
+pub async fn list_schedules(State(app_state): State<AppState>) -> Response {
+    match ScheduledJob::list(&app_state.db_pool).await {
+        Ok(schedules) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-schedules-retrieved"),
+                schedules,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ⏸️ Stop a schedule from running until it is resumed
+pub async fn pause_schedule(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let result = async {
+        let Some(mut schedule) = ScheduledJob::find_by_name(&app_state.db_pool, &name).await?
+        else {
+            return Ok(None);
+        };
+        let next_run_at = schedule.next_run_at;
+        schedule
+            .set_paused(&app_state.db_pool, true, next_run_at)
+            .await?;
+        Ok::<_, anyhow::Error>(Some(schedule))
+    };
+
+    match result.await {
+        Ok(Some(schedule)) => {
+            info!("⏸️ Paused schedule {}", schedule.name);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-schedule-paused"),
+                    schedule,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Schedule").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ▶️ Resume a paused schedule from its next occurrence (runs missed while paused are dropped)
+pub async fn resume_schedule(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let result = async {
+        let Some(mut schedule) = ScheduledJob::find_by_name(&app_state.db_pool, &name).await?
+        else {
+            return Ok(None);
+        };
+        let cron = schedules::parse_cron(&schedule.cron_expression)?;
+        let next_run_at = schedules::next_occurrence(&cron, chrono::Utc::now())?;
+        schedule
+            .set_paused(&app_state.db_pool, false, next_run_at)
+            .await?;
+        Ok::<_, anyhow::Error>(Some(schedule))
+    };
+
+    match result.await {
+        Ok(Some(schedule)) => {
+            info!("▶️ Resumed schedule {}", schedule.name);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-schedule-resumed"),
+                    schedule,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Schedule").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚀 Enqueue a schedule's job right now (paused schedules can be triggered too)
+/// Refused while its previous run is still going, unless the schedule allows overlap
+pub async fn trigger_schedule(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let mut schedule = match ScheduledJob::find_by_name(&app_state.db_pool, &name).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return not_found_error("Schedule").into_response(),
+        Err(e) => return internal_error(e),
+    };
+
+    match Schedules::new(&app_state).trigger(&mut schedule).await {
+        Ok(Some(job)) => {
+            info!("🚀 Triggered schedule {} (job {})", schedule.name, job.id);
+            (
+                StatusCode::ACCEPTED,
+                Json(ApiResponse::success(
+                    i18n::t("admin-schedule-triggered"),
+                    job,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "conflict".to_string(),
+                "The previous run of this schedule is still queued or running".to_string(),
+                Some(serde_json::json!({ "last_job_id": schedule.last_job_id })),
+            );
+            (StatusCode::CONFLICT, Json(api_response)).into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔄 Reload the configuration and apply its non-structural settings
+/// Responds with what changed; an invalid configuration changes nothing
+pub async fn reload_config(State(app_state): State<AppState>) -> Response {
+    match reload::reload(&app_state) {
+        Ok(changes) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with("admin-config-reloaded", [("count", changes.len().into())]),
+                changes,
+            )),
+        )
+            .into_response(),
+        Err(e) => match InvalidConfig::find(&e) {
+            // ⚙️ The operator's mistake: every problem, one per entry
+            Some(invalid) => {
+                warn!("⚠️ {}", invalid);
+                let kind = ErrorKind::InvalidConfiguration;
+                let api_response = ApiResponse::<()>::error(
+                    kind.code().to_string(),
+                    "Configuration reload failed; the current settings stay".to_string(),
+                    Some(serde_json::json!({ "problems": invalid.problems })),
+                );
+                (kind.status(), Json(api_response)).into_response()
+            }
+            None => errors::error_response("Configuration reload failed", e),
+        },
+    }
+}
+
+/// 🚩 Every feature flag with its overrides
+pub async fn list_feature_flags(State(app_state): State<AppState>) -> Response {
+    let result = async {
+        let flags = FeatureFlag::list(&app_state.db_pool).await?;
+        let overrides = FeatureFlagOverride::list(&app_state.db_pool, None).await?;
+        let details: Vec<FeatureFlagDetails> = flags
+            .into_iter()
+            .map(|flag| FeatureFlagDetails {
+                overrides: overrides
+                    .iter()
+                    .filter(|o| o.flag_name == flag.name)
+                    .cloned()
+                    .collect(),
+                flag,
+            })
+            .collect();
+        Ok::<_, anyhow::Error>(details)
+    };
+
+    match result.await {
+        Ok(flags) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-flags-retrieved"),
+                flags,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ➕ Create a feature flag (off unless `enabled` is set)
+pub async fn create_feature_flag(
+    State(app_state): State<AppState>,
+    Json(request): Json<CreateFeatureFlagRequest>,
+) -> Response {
+    let rollout_percent = request.rollout_percent.unwrap_or(100);
+    let errors: Vec<String> = [
+        feature_flags::validate_name(&request.name),
+        feature_flags::validate_rollout(rollout_percent),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .collect();
+    if !errors.is_empty() {
+        return validation_error(errors).into_response();
+    }
+
+    let result = async {
+        if FeatureFlag::find_by_name(&app_state.db_pool, &request.name)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        FeatureFlag::create(
+            &app_state.db_pool,
+            &request.name,
+            request.description.as_deref(),
+            request.enabled,
+            rollout_percent,
+        )
+        .await
+        .map(Some)
+    };
+
+    match result.await {
+        Ok(Some(flag)) => {
+            app_state.flags.invalidate();
+            info!(
+                "🚩 Created flag {} (enabled: {}, rollout {}%)",
+                flag.name, flag.enabled, flag.rollout_percent
+            );
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(i18n::t("admin-flag-created"), flag)),
+            )
+                .into_response()
+        }
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "conflict".to_string(),
+                format!("Feature flag '{}' already exists", request.name),
+                None,
+            );
+            (StatusCode::CONFLICT, Json(api_response)).into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚩 One feature flag with its overrides
+pub async fn get_feature_flag(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let result = async {
+        let Some(flag) = FeatureFlag::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(None);
+        };
+        let overrides = FeatureFlagOverride::list(&app_state.db_pool, Some(&name)).await?;
+        Ok::<_, anyhow::Error>(Some(FeatureFlagDetails { flag, overrides }))
+    };
+
+    match result.await {
+        Ok(Some(details)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-flag-retrieved"),
+                details,
+            )),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("Feature flag").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ✏️ Change a flag's description, global switch or rollout
+pub async fn update_feature_flag(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<UpdateFeatureFlagRequest>,
+) -> Response {
+    if let Some(Err(message)) = request.rollout_percent.map(feature_flags::validate_rollout) {
+        return validation_error(vec![message]).into_response();
+    }
+
+    let result = async {
+        let Some(mut flag) = FeatureFlag::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(None);
+        };
+        let description = request.description.clone().or(flag.description.clone());
+        let enabled = request.enabled.unwrap_or(flag.enabled);
+        let rollout_percent = request.rollout_percent.unwrap_or(flag.rollout_percent);
+        flag.update(
+            &app_state.db_pool,
+            description.as_deref(),
+            enabled,
+            rollout_percent,
+        )
+        .await?;
+        Ok::<_, anyhow::Error>(Some(flag))
+    };
+
+    match result.await {
+        Ok(Some(flag)) => {
+            app_state.flags.invalidate();
+            info!(
+                "🚩 Updated flag {} (enabled: {}, rollout {}%)",
+                flag.name, flag.enabled, flag.rollout_percent
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(i18n::t("admin-flag-updated"), flag)),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Feature flag").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🗑️ Delete a flag and its overrides (code checking it falls back to its default)
+pub async fn delete_feature_flag(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    match FeatureFlag::delete(&app_state.db_pool, &name).await {
+        Ok(true) => {
+            app_state.flags.invalidate();
+            info!("🗑️ Deleted flag {}", name);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Feature flag deleted".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Feature flag").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔍 Whether a flag is on, globally or for `project_id`, as the code sees it
+pub async fn evaluate_feature_flag(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<EvaluateFeatureFlagQuery>,
+) -> Response {
+    match app_state.flags.evaluate(&name, query.project_id).await {
+        Some(enabled) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-flag-evaluated"),
+                FlagEvaluation {
+                    name,
+                    project_id: query.project_id,
+                    enabled,
+                },
+            )),
+        )
+            .into_response(),
+        None => not_found_error("Feature flag").into_response(),
+    }
+}
+
+/// 🎯 Turn a flag on or off for one project, whatever its global setting
+pub async fn set_feature_flag_override(
+    State(app_state): State<AppState>,
+    Path((name, project_id)): Path<(String, Uuid)>,
+    Json(request): Json<FeatureFlagOverrideRequest>,
+) -> Response {
+    let result = async {
+        let Some(flag) = FeatureFlag::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(None);
+        };
+        if Project::find_by_id(&app_state.db_pool, project_id)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        FeatureFlagOverride::set(&app_state.db_pool, &flag, project_id, request.enabled)
+            .await
+            .map(Some)
+    };
+
+    match result.await {
+        Ok(Some(row)) => {
+            app_state.flags.invalidate();
+            info!(
+                "🎯 Flag {} is {} for project {}",
+                row.flag_name,
+                if row.enabled { "on" } else { "off" },
+                row.project_id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-flag-override-set"),
+                    row,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Feature flag or project").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🧹 Let a project follow the flag's global setting again
+pub async fn clear_feature_flag_override(
+    State(app_state): State<AppState>,
+    Path((name, project_id)): Path<(String, Uuid)>,
+) -> Response {
+    let result = async {
+        let Some(flag) = FeatureFlag::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(false);
+        };
+        FeatureFlagOverride::clear(&app_state.db_pool, &flag, project_id).await
+    };
+
+    match result.await {
+        Ok(true) => {
+            app_state.flags.invalidate();
+            info!(
+                "🧹 Cleared flag {} override for project {}",
+                name, project_id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Feature flag override cleared".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Feature flag override").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚧 Whether maintenance mode is on, why, and what users are told
+pub async fn get_maintenance(State(app_state): State<AppState>) -> Response {
+    let status = app_state.maintenance.status().await;
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            i18n::t("admin-maintenance-retrieved"),
+            status,
+        )),
+    )
+        .into_response()
+}
+
+/// 🚧 Switch maintenance mode on or off for every instance
+/// MAINTENANCE_MODE=true in the configuration keeps it on regardless
+pub async fn set_maintenance(
+    State(app_state): State<AppState>,
+    Json(request): Json<MaintenanceRequest>,
+) -> Response {
+    if request
+        .message
+        .as_deref()
+        .is_some_and(|message| message.len() > 500)
+    {
+        return validation_error(vec!["message must be at most 500 characters".to_string()])
+            .into_response();
+    }
+
+    match app_state
+        .maintenance
+        .set(
+            &app_state.db_pool,
+            request.enabled,
+            request.message.as_deref(),
+        )
+        .await
+    {
+        Ok(status) => {
+            info!(
+                "🚧 Maintenance mode switched {} by an admin",
+                if request.enabled { "on" } else { "off" }
+            );
+            if !request.enabled && status.enabled {
+                warn!("⚠️ Maintenance mode stays on: MAINTENANCE_MODE is set in the configuration");
+            }
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-maintenance-updated"),
+                    status,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🎭 Every role with its permissions, and the permissions there are
+pub async fn list_roles(State(app_state): State<AppState>) -> Response {
+    match Role::list(&app_state.db_pool).await {
+        Ok(roles) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("admin-roles-retrieved"),
+                RolesOverview {
+                    roles,
+                    permissions: Permission::ALL.iter().map(|p| p.as_str()).collect(),
+                },
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ➕ Create a role
+pub async fn create_role(
+    State(app_state): State<AppState>,
+    Json(request): Json<CreateRoleRequest>,
+) -> Response {
+    let mut errors = Vec::new();
+    if let Err(message) = roles::validate_name(&request.name) {
+        errors.push(message);
+    }
+    let permissions = match roles::validate_permissions(&request.permissions) {
+        Ok(permissions) => permissions,
+        Err(unknown) => {
+            errors.extend(unknown);
+            Vec::new()
+        }
+    };
+    if !errors.is_empty() {
+        return validation_error(errors).into_response();
+    }
+
+    let result = async {
+        if Role::find_by_name(&app_state.db_pool, &request.name)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        Role::create(
+            &app_state.db_pool,
+            &request.name,
+            request.description.as_deref(),
+            &permissions,
+        )
+        .await
+        .map(Some)
+    };
+
+    match result.await {
+        Ok(Some(role)) => {
+            app_state.roles.invalidate();
+            info!(
+                "🎭 Created role {} ({})",
+                role.name,
+                role.permissions.join(", ")
+            );
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(i18n::t("admin-role-created"), role)),
+            )
+                .into_response()
+        }
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "conflict".to_string(),
+                "A role with this name already exists".to_string(),
+                None,
+            );
+            (StatusCode::CONFLICT, Json(api_response)).into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ✏️ Change a role's description and permissions
+/// Built-in roles can be edited too, except admin (which always holds everything)
+pub async fn update_role(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<UpdateRoleRequest>,
+) -> Response {
+    if name == roles::builtin_role(&UserRole::Admin) {
+        return validation_error(vec![
+            "The admin role always holds every permission".to_string()
+        ])
+        .into_response();
+    }
+    let permissions = match roles::validate_permissions(&request.permissions) {
+        Ok(permissions) => permissions,
+        Err(unknown) => return validation_error(unknown).into_response(),
+    };
+
+    let result = async {
+        let Some(mut role) = Role::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(None);
+        };
+        role.update(
+            &app_state.db_pool,
+            request.description.as_deref(),
+            &permissions,
+        )
+        .await?;
+        Ok(Some(role))
+    };
+
+    match result.await {
+        Ok(Some(role)) => {
+            app_state.roles.invalidate();
+            info!(
+                "🎭 Role {} now grants: {}",
+                role.name,
+                role.permissions.join(", ")
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(i18n::t("admin-role-updated"), role)),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Role").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🗑️ Delete a role (built-in roles can't be deleted)
+pub async fn delete_role(State(app_state): State<AppState>, Path(name): Path<String>) -> Response {
+    let role = match Role::find_by_name(&app_state.db_pool, &name).await {
+        Ok(Some(role)) => role,
+        Ok(None) => return not_found_error("Role").into_response(),
+        Err(e) => return internal_error(e),
+    };
+    if role.is_builtin {
+        return validation_error(vec!["Built-in roles can't be deleted".to_string()])
+            .into_response();
+    }
+
+    match role.delete(&app_state.db_pool).await {
+        Ok(()) => {
+            app_state.roles.invalidate();
+            info!("🗑️ Deleted role {}", role.name);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Role deleted".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 👤 Give a user a role on top of their account role
+pub async fn assign_role(
+    State(app_state): State<AppState>,
+    Path((user_id, name)): Path<(Uuid, String)>,
+) -> Response {
+    let result = async {
+        let Some(role) = Role::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(Err(not_found_error("Role").into_response()));
+        };
+        if role.is_builtin {
+            return Ok(Err(validation_error(vec![
+                "Built-in roles follow the account role; assign a custom role instead".to_string(),
+            ])
+            .into_response()));
+        }
+        if User::find_by_id(&app_state.db_pool, user_id)
+            .await?
+            .is_none()
+        {
+            return Ok(Err(not_found_error("User").into_response()));
+        }
+        role.assign(&app_state.db_pool, user_id).await?;
+        Ok::<_, anyhow::Error>(Ok(role))
+    };
+
+    match result.await {
+        Ok(Ok(role)) => {
+            app_state.roles.invalidate();
+            info!("👤 Assigned role {} to user {}", role.name, user_id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Role assigned".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚪 Take a role away from a user
+pub async fn unassign_role(
+    State(app_state): State<AppState>,
+    Path((user_id, name)): Path<(Uuid, String)>,
+) -> Response {
+    let result = async {
+        let Some(role) = Role::find_by_name(&app_state.db_pool, &name).await? else {
+            return Ok(false);
+        };
+        role.unassign(&app_state.db_pool, user_id).await
+    };
+
+    match result.await {
+        Ok(true) => {
+            app_state.roles.invalidate();
+            info!("🚪 Took role {} away from user {}", name, user_id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Role unassigned".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Role assignment").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 📏 Set an organization's project and monthly feedback quotas
+pub async fn set_organization_quotas(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OrganizationQuotasRequest>,
+) -> Response {
+    let negative = [request.max_projects, request.max_monthly_feedback]
+        .iter()
+        .flatten()
+        .any(|limit| *limit < 0);
+    if negative {
+        return validation_error(vec!["Quotas can't be negative".to_string()]).into_response();
+    }
+
+    let mut organization = match Organization::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(organization)) => organization,
+        Ok(None) => return not_found_error("Organization").into_response(),
+        Err(e) => return internal_error(e),
+    };
+    match organization
+        .set_quotas(
+            &app_state.db_pool,
+            request.max_projects,
+            request.max_monthly_feedback,
+        )
+        .await
+    {
+        Ok(()) => {
+            info!(
+                "📏 Quotas of {}: {:?} projects, {:?} feedback per month",
+                organization.slug, organization.max_projects, organization.max_monthly_feedback
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("admin-quotas-updated"),
+                    organization,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 💥 Log and wrap an unexpected failure
+fn internal_error(e: anyhow::Error) -> Response {
+    crate::errors::error_response("Admin request failed", e)
+}
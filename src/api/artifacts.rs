@@ -0,0 +1,150 @@
+// 🗄️ Artifacts API - What a Run Left Behind! 🗄️
+// GET /api/feedback/:id/artifacts lists the diff, change plan and sandbox log
+// stored for a feedback item, each with a signed link good for
+// ARTIFACT_LINK_MINUTES. GET /api/artifacts/:feedback_id/:kind serves the file
+// (or redirects to a presigned bucket URL); the signature is the credential
+// Created with love by Aye & Hue ✨
+
+use crate::{
+    api::{
+        feedback::find_visible_feedback, utils::not_found_error, ApiResponse, AppState,
+        ErrorResponse,
+    },
+    artifacts::{self, ArtifactKind, ArtifactStore, Retrieval},
+    database::models::Feedback,
+    errors, i18n,
+    middleware::auth::AuthenticatedUser,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Redirect, Response},
+    Extension,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 🔏 Query parameters of a signed artifact link
+#[derive(Debug, Deserialize)]
+pub struct ArtifactLinkQuery {
+    /// ⏳ Unix time the link stops working
+    pub expires: i64,
+    /// 🔏 Hex HMAC over the feedback id, artifact kind and `expires`
+    pub signature: String,
+}
+
+/// 📄 A stored artifact, with a link to download it
+#[derive(Debug, Serialize)]
+pub struct ArtifactInfo {
+    pub kind: ArtifactKind,
+    pub file_name: &'static str,
+    pub size_bytes: u64,
+    pub stored_at: DateTime<Utc>,
+    /// 🔗 Signed download link
+    pub download_url: String,
+    /// ⏳ When the link stops working
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 📋 The artifacts stored for a feedback item the user may see
+pub async fn list_artifacts(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    let feedback = match find_visible_feedback(&app_state, &user, feedback_id).await {
+        Ok(Some(feedback)) => feedback,
+        Ok(None) => return not_found_error("Feedback").into_response(),
+        Err(e) => return errors::error_response("Failed to load feedback", e),
+    };
+
+    let config = app_state.config.load();
+    let expires_at = Utc::now() + Duration::minutes(config.artifacts.link_minutes.into());
+    let artifacts: Vec<ArtifactInfo> = artifacts::stored_artifacts(&feedback)
+        .into_iter()
+        .map(|(kind, stored)| ArtifactInfo {
+            kind,
+            file_name: kind.file_name(),
+            size_bytes: stored.size_bytes,
+            stored_at: stored.stored_at,
+            download_url: artifacts::download_url(
+                &config.auth.jwt_secret,
+                feedback.id,
+                kind,
+                expires_at,
+            ),
+            expires_at,
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            i18n::t("artifacts-retrieved"),
+            artifacts,
+        )),
+    )
+        .into_response()
+}
+
+/// ⬇️ Download an artifact through its signed link
+pub async fn download_artifact(
+    State(app_state): State<AppState>,
+    Path((feedback_id, kind)): Path<(Uuid, String)>,
+    Query(link): Query<ArtifactLinkQuery>,
+) -> Response {
+    let config = app_state.config.load_full();
+    let genuine = kind.parse::<ArtifactKind>().ok().filter(|kind| {
+        artifacts::verify_link(
+            &config.auth.jwt_secret,
+            feedback_id,
+            *kind,
+            link.expires,
+            &link.signature,
+            Utc::now(),
+        )
+    });
+    let Some(kind) = genuine else {
+        let api_response = ApiResponse::<()>::error(
+            "forbidden".to_string(),
+            i18n::t("artifact-link-invalid"),
+            None,
+        );
+        return (StatusCode::FORBIDDEN, Json(api_response)).into_response();
+    };
+
+    let stored = match Feedback::find_by_id(&app_state.db_pool, feedback_id).await {
+        Ok(feedback) => {
+            feedback.and_then(|feedback| artifacts::stored_artifacts(&feedback).remove(&kind))
+        }
+        Err(e) => return errors::error_response("Failed to load feedback", e),
+    };
+    let store = match ArtifactStore::from_config(&config.artifacts) {
+        Ok(store) => store,
+        Err(e) => return errors::error_response("Artifact storage is misconfigured", e),
+    };
+    let (Some(stored), Some(store)) = (stored, store) else {
+        return not_found_error("Artifact").into_response();
+    };
+
+    // ⏳ A presigned URL lasts no longer than the link that led to it
+    let expires_in = link.expires.saturating_sub(Utc::now().timestamp()).max(1) as u64;
+    match store.retrieve(&stored.key, expires_in).await {
+        Ok(Some(Retrieval::Bytes(bytes))) => (
+            [
+                (header::CONTENT_TYPE, kind.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", kind.file_name()),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(Some(Retrieval::Redirect(url))) => Redirect::temporary(&url).into_response(),
+        // 🧹 Purged since it was listed
+        Ok(None) => not_found_error("Artifact").into_response(),
+        Err(e) => errors::error_response("Failed to retrieve artifact", e),
+    }
+}
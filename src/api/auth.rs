@@ -5,19 +5,26 @@
 
 use anyhow::{Context, Result};
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response},
+    Extension,
 };
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use tracing::{error, info, warn};
 
 use crate::{
     api::{
         utils::{handle_error, validation_error},
-        ApiResponse, AppState, ValidateRequest,
+        ApiResponse, AppState, ErrorResponse, ValidateRequest,
     },
-    database::models::{User, UserRole},
+    database::models::{SsoProvider, User, UserRole},
+    digest::DigestFrequency,
+    errors, feedback_claims,
+    i18n::{self, Locale},
+    middleware::auth::AuthenticatedUser,
+    security_alerts,
 };
 
 /// 🔐 User login request
@@ -36,6 +43,19 @@ pub struct RegisterRequest {
     pub github_username: Option<String>,
 }
 
+/// 👤 The signed-in user's preferences
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreferencesRequest {
+    /// 🌍 Language for messages and pages (None = follow Accept-Language)
+    pub locale: Option<String>,
+    /// 📬 Activity digests: never, daily or weekly (None = leave as is)
+    #[serde(default)]
+    pub digest_frequency: Option<String>,
+    /// 🔐 Whether security alerts are emailed too (None = leave as is)
+    #[serde(default)]
+    pub security_alert_emails: Option<bool>,
+}
+
 /// 🎫 Authentication response with token
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
@@ -60,11 +80,11 @@ impl ValidateRequest for LoginRequest {
         let mut errors = Vec::new();
 
         if self.email.is_empty() || !self.email.contains('@') {
-            errors.push("Valid email is required".to_string());
+            errors.push(i18n::t("validation-email-required"));
         }
 
         if self.password.is_empty() {
-            errors.push("Password is required".to_string());
+            errors.push(i18n::t("validation-password-required"));
         }
 
         if errors.is_empty() {
@@ -80,15 +100,18 @@ impl ValidateRequest for RegisterRequest {
         let mut errors = Vec::new();
 
         if self.email.is_empty() || !self.email.contains('@') {
-            errors.push("Valid email is required".to_string());
+            errors.push(i18n::t("validation-email-required"));
         }
 
         if self.name.trim().is_empty() {
-            errors.push("Name is required".to_string());
+            errors.push(i18n::t("validation-name-required"));
         }
 
         if self.password.len() < 8 {
-            errors.push("Password must be at least 8 characters".to_string());
+            errors.push(i18n::t_with(
+                "validation-password-too-short",
+                [("min", 8.into())],
+            ));
         }
 
         if errors.is_empty() {
@@ -102,6 +125,8 @@ impl ValidateRequest for RegisterRequest {
 /// 🔐 User login endpoint
 pub async fn login(
     State(app_state): State<AppState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(request): Json<LoginRequest>,
 ) -> Response {
     info!("🔐 Login attempt for email: {}", request.email);
@@ -109,33 +134,52 @@ pub async fn login(
     if let Err(errors) = request.validate() {
         let api_response = ApiResponse::<()>::error(
             "validation_error".to_string(),
-            "Request validation failed".to_string(),
+            errors::ErrorKind::Validation.title(),
             Some(serde_json::json!({ "errors": errors })),
         );
         return (StatusCode::BAD_REQUEST, Json(api_response)).into_response();
     }
 
+    if !app_state.config.load().auth.password_login {
+        return password_login_disabled(&i18n::t("auth-password-login-disabled"));
+    }
+    // 🔐 Members of organizations that enforce single sign-on must use it
+    match SsoProvider::enforced_for_email(&app_state.db_pool, &request.email).await {
+        Ok(Some(_)) => {
+            return password_login_disabled(&i18n::t("auth-sso-required"));
+        }
+        Ok(None) => {}
+        Err(e) => return handle_error(e).into_response(),
+    }
+
     match authenticate_user(&app_state, request).await {
         Ok(response) => {
             info!("✅ Login successful for user: {}", response.user.email);
+            security_alerts::record_sign_in(
+                &app_state,
+                response.user.id,
+                &response.user.email,
+                &headers,
+                connect_info.map(|ConnectInfo(addr)| addr.ip()),
+            )
+            .await;
+            feedback_claims::claim_on_sign_in(
+                &app_state,
+                response.user.id,
+                &response.user.email,
+                response.user.email_verified,
+            )
+            .await;
             (
                 StatusCode::OK,
                 Json(ApiResponse::<AuthResponse>::success(
-                    "Login successful".to_string(),
+                    i18n::t("auth-login-successful"),
                     response,
                 )),
-            ).into_response()
-        }
-        Err(e) => {
-            warn!("❌ Login failed: {:#}", e);
-            let error_msg = format!("{:#}", e);
-            let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
-            );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            )
+                .into_response()
         }
+        Err(e) => errors::error_response("Login failed", e),
     }
 }
 
@@ -149,36 +193,39 @@ pub async fn register(
     if let Err(errors) = request.validate() {
         let api_response = ApiResponse::<()>::error(
             "validation_error".to_string(),
-            "Request validation failed".to_string(),
+            errors::ErrorKind::Validation.title(),
             Some(serde_json::json!({ "errors": errors })),
         );
         return (StatusCode::BAD_REQUEST, Json(api_response)).into_response();
     }
 
+    if !app_state.config.load().auth.password_login {
+        return password_login_disabled(&i18n::t("auth-password-accounts-disabled"));
+    }
+
     match create_user_account(&app_state, request).await {
         Ok(response) => {
             info!(
                 "✅ Registration successful for user: {}",
                 response.user.email
             );
+            feedback_claims::claim_on_sign_in(
+                &app_state,
+                response.user.id,
+                &response.user.email,
+                response.user.email_verified,
+            )
+            .await;
             (
                 StatusCode::CREATED,
                 Json(ApiResponse::<AuthResponse>::success(
-                    "Registration successful".to_string(),
+                    i18n::t("auth-registration-successful"),
                     response,
                 )),
-            ).into_response()
-        }
-        Err(e) => {
-            warn!("❌ Registration failed: {:#}", e);
-            let error_msg = format!("{:#}", e);
-            let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
-            );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            )
+                .into_response()
         }
+        Err(e) => errors::error_response("Registration failed", e),
     }
 }
 
@@ -189,14 +236,123 @@ pub async fn logout(State(_app_state): State<AppState>) -> impl IntoResponse {
     // TODO: Implement token invalidation when session management is ready
     (
         StatusCode::OK,
-        Json(ApiResponse::<()>::success_no_data(
-            "Logout successful".to_string(),
-        )),
+        Json(ApiResponse::<()>::success_no_data(i18n::t(
+            "auth-logout-successful",
+        ))),
+    )
+}
+
+/// 👤 Save the signed-in user's preferences
+pub async fn update_preferences(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<PreferencesRequest>,
+) -> Response {
+    let locale = match request
+        .locale
+        .as_deref()
+        .map(str::parse::<Locale>)
+        .transpose()
+    {
+        Ok(locale) => locale,
+        Err(_) => {
+            let supported: Vec<&str> = Locale::ALL.iter().map(|locale| locale.code()).collect();
+            return validation_error(vec![i18n::t_with(
+                "unsupported-locale",
+                [
+                    ("locale", request.locale.unwrap_or_default().into()),
+                    ("supported", supported.join(", ").into()),
+                ],
+            )])
+            .into_response();
+        }
+    };
+
+    let digest_frequency = match request
+        .digest_frequency
+        .as_deref()
+        .map(str::parse::<DigestFrequency>)
+        .transpose()
+    {
+        Ok(frequency) => frequency,
+        Err(_) => {
+            let supported: Vec<&str> = DigestFrequency::ALL
+                .iter()
+                .map(|frequency| frequency.as_str())
+                .collect();
+            return validation_error(vec![i18n::t_with(
+                "unsupported-digest-frequency",
+                [
+                    (
+                        "frequency",
+                        request.digest_frequency.unwrap_or_default().into(),
+                    ),
+                    ("supported", supported.join(", ").into()),
+                ],
+            )])
+            .into_response();
+        }
+    };
+
+    let pool = &app_state.db_pool;
+    let saved = async {
+        User::set_locale(pool, user.id, locale.map(Locale::code)).await?;
+        if let Some(frequency) = digest_frequency {
+            User::set_digest_frequency(pool, user.id, frequency.as_str()).await?;
+        }
+        if let Some(enabled) = request.security_alert_emails {
+            User::set_security_alert_emails(pool, user.id, enabled).await?;
+        }
+        User::find_by_id(pool, user.id)
+            .await?
+            .context("User no longer exists")
+    };
+    match saved.await {
+        Ok(saved) => {
+            info!(
+                "🌍 User {} now prefers locale {:?} and {} digests",
+                user.email, locale, saved.digest_frequency
+            );
+            let preferences = PreferencesRequest {
+                locale: saved.locale,
+                digest_frequency: Some(saved.digest_frequency),
+                security_alert_emails: Some(saved.security_alert_emails),
+            };
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("preferences-updated"),
+                    preferences,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => errors::error_response("Failed to save preferences", e),
+    }
+}
+
+/// 🔏 Public keys that verify Feedbacker tokens (JWKS), for other services
+pub async fn jwks(State(app_state): State<AppState>) -> impl IntoResponse {
+    let keys = app_state.token_keys.snapshot().await;
+    (
+        StatusCode::OK,
+        [(axum::http::header::CACHE_CONTROL, "public, max-age=300")],
+        Json(keys.jwks()),
     )
 }
 
 // Helper functions
 
+/// 🚫 Refuse a password login or registration in favor of single sign-on
+fn password_login_disabled(message: &str) -> Response {
+    let api_response = ApiResponse::<()>::error(
+        "password_login_disabled".to_string(),
+        message.to_string(),
+        None,
+    );
+    (StatusCode::FORBIDDEN, Json(api_response)).into_response()
+}
+
 async fn authenticate_user(app_state: &AppState, request: LoginRequest) -> Result<AuthResponse> {
     // TODO: Implement actual authentication logic
     anyhow::bail!("Authentication not implemented yet")
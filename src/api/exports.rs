@@ -0,0 +1,221 @@
+// 📦 Exports API - Take Your Feedback With You! 📦
+// GET /api/projects/:id/export?format=csv|jsonl streams a project's feedback, or
+// queues a background export (202) when there's more than EXPORT_INLINE_LIMIT
+// of it. GET /api/projects/:id/exports/:export_id reports how that's going and,
+// once the file is written, a signed download link for
+// GET /api/exports/:export_id/download (the signature is the credential)
+// Created with love by Aye & Hue ✨
+
+use crate::{
+    api::{
+        utils::{not_found_error, validation_error},
+        ApiResponse, AppState, ErrorResponse,
+    },
+    database::models::{Feedback, Project, ProjectExport},
+    errors,
+    export::{self, ExportFormat},
+    i18n,
+    middleware::auth::AuthenticatedUser,
+};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio_util::io::ReaderStream;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// 📄 Query parameters of an export
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// 📄 csv (default) or jsonl
+    pub format: Option<String>,
+}
+
+/// 🔏 Query parameters of a signed download link
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    /// ⏳ Unix time the link stops working
+    pub expires: i64,
+    /// 🔏 Hex HMAC over the export id and `expires`
+    pub signature: String,
+}
+
+/// 📦 A background export, with where to find it
+#[derive(Debug, Serialize)]
+pub struct ExportInfo {
+    #[serde(flatten)]
+    pub export: ProjectExport,
+    /// 📡 Poll this until the export completes
+    pub status_url: String,
+    /// 🔗 Signed link to the file, once it is written
+    pub download_url: Option<String>,
+}
+
+impl ExportInfo {
+    fn new(app_state: &AppState, export: ProjectExport) -> Self {
+        let download_url = export::download_url(&app_state.config.load().auth.jwt_secret, &export);
+        Self {
+            status_url: format!("/api/projects/{}/exports/{}", export.project_id, export.id),
+            download_url,
+            export,
+        }
+    }
+}
+
+/// 📦 Export a project's feedback: streamed when small, queued when large
+pub async fn export_project(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    let format = match query
+        .format
+        .as_deref()
+        .map(str::parse::<ExportFormat>)
+        .transpose()
+    {
+        Ok(format) => format.unwrap_or_default(),
+        Err(e) => return validation_error(vec![e.to_string()]).into_response(),
+    };
+
+    let project = match Project::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return not_found_error("Project").into_response(),
+        Err(e) => return errors::error_response("Failed to load project", e),
+    };
+
+    let count = match Feedback::count_for_repository(&app_state.db_pool, &project.repository).await
+    {
+        Ok(count) => count,
+        Err(e) => return errors::error_response("Failed to count feedback", e),
+    };
+
+    if count as u64 <= app_state.config.load().exports.inline_limit {
+        info!(
+            "📦 Streaming {} export of {} ({} items)",
+            format.as_str(),
+            project.repository,
+            count
+        );
+        return stream_export(&app_state, &project, format);
+    }
+
+    match export::queue_export(&app_state, &project, user.id, format).await {
+        Ok(export) => (
+            StatusCode::ACCEPTED,
+            Json(ApiResponse::success(
+                i18n::t("export-queued"),
+                ExportInfo::new(&app_state, export),
+            )),
+        )
+            .into_response(),
+        Err(e) => errors::error_response("Failed to queue export", e),
+    }
+}
+
+/// 📊 Status of a background export (and its download link once written)
+pub async fn get_export(
+    State(app_state): State<AppState>,
+    Path((project_id, export_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    match ProjectExport::find_by_id(&app_state.db_pool, export_id).await {
+        Ok(Some(export)) if export.project_id == project_id => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("export-retrieved"),
+                ExportInfo::new(&app_state, export),
+            )),
+        )
+            .into_response(),
+        Ok(_) => not_found_error("Export").into_response(),
+        Err(e) => errors::error_response("Failed to load export", e),
+    }
+}
+
+/// ⬇️ Download a written export through its signed link
+pub async fn download_export(
+    State(app_state): State<AppState>,
+    Path(export_id): Path<Uuid>,
+    Query(link): Query<DownloadQuery>,
+) -> Response {
+    let secret = app_state.config.load().auth.jwt_secret.clone();
+    if !export::verify_link(
+        &secret,
+        export_id,
+        link.expires,
+        &link.signature,
+        Utc::now(),
+    ) {
+        let api_response = ApiResponse::<()>::error(
+            "forbidden".to_string(),
+            i18n::t("export-link-invalid"),
+            None,
+        );
+        return (StatusCode::FORBIDDEN, Json(api_response)).into_response();
+    }
+
+    let export = match ProjectExport::find_by_id(&app_state.db_pool, export_id).await {
+        Ok(Some(export)) if export.status == ProjectExport::COMPLETED => export,
+        Ok(_) => return not_found_error("Export").into_response(),
+        Err(e) => return errors::error_response("Failed to load export", e),
+    };
+    let (Some(path), Ok(format)) = (&export.file_path, export.format.parse::<ExportFormat>())
+    else {
+        return not_found_error("Export").into_response();
+    };
+    let project = match Project::find_by_id(&app_state.db_pool, export.project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return not_found_error("Project").into_response(),
+        Err(e) => return errors::error_response("Failed to load project", e),
+    };
+
+    match tokio::fs::File::open(path).await {
+        Ok(file) => download(
+            format,
+            &project.repository,
+            Body::from_stream(ReaderStream::new(file)),
+        ),
+        // 🧹 Purged between the status check and the download
+        Err(_) => not_found_error("Export").into_response(),
+    }
+}
+
+/// 🚰 Stream an export straight into the response as it's read from the database
+fn stream_export(app_state: &AppState, project: &Project, format: ExportFormat) -> Response {
+    let (writer, reader) = tokio::io::duplex(64 * 1024);
+    let pool = app_state.db_pool.clone();
+    let repository = project.repository.clone();
+    tokio::spawn(async move {
+        // 💥 The response has started by now, so a cut-off body is all the client gets
+        if let Err(e) = export::write_export(&pool, &repository, format, writer).await {
+            error!("❌ Export of {} failed midway: {:#}", repository, e);
+        }
+    });
+    download(
+        format,
+        &project.repository,
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+}
+
+/// 📨 An export as a file download
+fn download(format: ExportFormat, repository: &str, body: Body) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name(repository)),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,150 @@
+// 🧭 Fallback API - Lost? Here's a Map! 🧭
+// What answers when no route does: unknown paths under /api get a JSON 404
+// naming the method and path (the web UI has its own HTML 404 page), and
+// known paths asked with the wrong method get a JSON 405
+// listing the methods that would work (axum's own 405 has the Allow header but
+// an empty body, so the listing is filled in from that header on the way out)
+// Created with love by Aye & Hue - Every wrong turn gets directions! ✨
+
+use crate::{
+    api::{ApiResponse, ErrorResponse},
+    errors::ErrorKind,
+    i18n,
+};
+use axum::{
+    body::HttpBody,
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    response::{IntoResponse, Json, Response},
+};
+
+/// 🔍 No API route matches the path
+pub async fn not_found(method: Method, uri: Uri) -> Response {
+    let api_response = ApiResponse::<()>::error(
+        ErrorKind::NotFound.code().to_string(),
+        i18n::t_with(
+            "no-endpoint",
+            [
+                ("method", method.as_str().into()),
+                ("path", uri.path().into()),
+            ],
+        ),
+        None,
+    );
+    (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+}
+
+/// 🚷 Give axum's empty 405s a body listing the allowed methods
+pub async fn describe_method_not_allowed(response: Response) -> Response {
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED
+        || response.body().size_hint().exact() != Some(0)
+    {
+        return response;
+    }
+
+    let allowed = allowed_methods(response.headers());
+    let api_response = ApiResponse::<()>::error(
+        ErrorKind::MethodNotAllowed.code().to_string(),
+        i18n::t_with(
+            "method-only-accepts",
+            [("methods", allowed.join(", ").into())],
+        ),
+        Some(serde_json::json!({ "allowed_methods": allowed })),
+    );
+
+    // 📦 Same status and headers (Allow included), now with a body
+    let (mut parts, _) = response.into_parts();
+    let (json_parts, body) = Json(api_response).into_response().into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.extend(json_parts.headers);
+    Response::from_parts(parts, body)
+}
+
+/// 📋 The methods named in the Allow header
+fn allowed_methods(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::ALLOW)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|method| !method.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// 🧪 Tests - Wrong turns always come with directions!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+
+    /// 📦 A response body as JSON
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    /// 🚷 What axum answers a wrong method with: an Allow header and no body
+    fn empty_405() -> Response {
+        Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .header(header::ALLOW, "GET,HEAD")
+            .header(header::ALLOW, "POST")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let response = not_found(Method::DELETE, Uri::from_static("/api/nowhere?page=2")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = json_body(response).await;
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"]["code"], ErrorKind::NotFound.code());
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.contains("DELETE"));
+        assert!(message.contains("/api/nowhere"));
+        assert!(!message.contains("page=2"));
+        println!("✅ JSON 404 test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_describe_method_not_allowed() {
+        let response = describe_method_not_allowed(empty_405()).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(allowed_methods(response.headers()), ["GET", "HEAD", "POST"]);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+        let body = json_body(response).await;
+        assert_eq!(body["error"]["code"], ErrorKind::MethodNotAllowed.code());
+        assert_eq!(
+            body["error"]["details"]["allowed_methods"],
+            serde_json::json!(["GET", "HEAD", "POST"])
+        );
+        println!("✅ JSON 405 test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_describe_method_not_allowed_keeps_bodies() {
+        // 📝 A handler's own 405 already says what it means
+        let own = Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .header(header::ALLOW, "GET")
+            .body(Body::from("{\"reason\":\"read only\"}"))
+            .unwrap();
+        let response = describe_method_not_allowed(own).await;
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"{\"reason\":\"read only\"}");
+
+        // 🙈 Other statuses pass through untouched
+        let ok = Response::builder()
+            .status(StatusCode::OK)
+            .body(Body::empty())
+            .unwrap();
+        let response = describe_method_not_allowed(ok).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+        println!("✅ 405 passthrough test passed!");
+    }
+}
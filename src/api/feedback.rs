@@ -9,101 +9,47 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Json, Response},
+    Extension,
 };
-use serde::{Deserialize, Serialize};
-use sqlx::Row;  // 🔧 Added Row trait import for database row access
+use sqlx::PgPool;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::{
     api::{
-        utils::{handle_error, not_found_error, validation_error},
-        ApiResponse, AppState, PaginatedResponse, PaginationParams, ValidateRequest,
+        organizations::quota_exceeded,
+        utils::{forbidden_error, handle_error, not_found_error, validation_error},
+        ApiResponse, AppState, ErrorResponse, FieldSelection, FieldsParams, PaginatedResponse,
+        PaginationParams, Sparse, ValidateRequest,
     },
-    database::models::{Feedback, FeedbackStats, FeedbackStatus},
+    cache::CacheNamespace,
+    database::models::{
+        Feedback, FeedbackEvent, FeedbackStats, FeedbackStatus, Project, PromptMetric,
+        PromptOutcome,
+    },
+    budgets, errors, feedback_bulk, feedback_claims,
+    feedback_trace::{self, Stage},
+    feedback_triage::{self, TriageRejection},
+    github::{parse_repository, GitHubClient},
+    i18n,
+    jobs::runs,
+    middleware::auth::{AuthenticatedUser, Permission},
+    models::path_scope::normalize_scope_path,
+    organizations::{self, ProjectAccess},
+    pipeline::PipelineMode,
+    scm,
+    utils::text,
 };
 
-/// 📝 Feedback submission request structure
-/// This is what users send us when they want to improve a repository!
-#[derive(Debug, Deserialize)]
-pub struct SubmitFeedbackRequest {
-    /// 🎯 Target repository in "owner/repo" format
-    pub repository: String,
-    /// 📝 The actual feedback content - what the user wants to improve
-    pub content: String,
-    /// 🤖 Preferred LLM provider (optional - will use project default)
-    pub llm_provider: Option<String>,
-    /// 🔧 Additional metadata for processing (optional)
-    pub metadata: Option<serde_json::Value>,
-    /// 👤 User information (for anonymous submissions)
-    pub user_info: Option<AnonymousUserInfo>,
-}
-
-/// 👤 Anonymous user information for feedback without accounts
-#[derive(Debug, Deserialize)]
-pub struct AnonymousUserInfo {
-    /// 📧 Email for notifications (optional)
-    pub email: Option<String>,
-    /// 👤 Display name (optional)
-    pub name: Option<String>,
-}
-
-/// 📊 Feedback submission response
-#[derive(Debug, Serialize)]
-pub struct SubmitFeedbackResponse {
-    /// 🆔 Unique feedback ID for tracking
-    pub feedback_id: Uuid,
-    /// 📋 Current status of the feedback
-    pub status: FeedbackStatus,
-    /// 🔗 URL to track the feedback progress
-    pub tracking_url: String,
-    /// ⏰ Estimated processing time in minutes
-    pub estimated_processing_time: u32,
-}
-
-/// 📊 Detailed feedback information for responses
-#[derive(Debug, Serialize)]
-pub struct FeedbackDetails {
-    /// 🆔 Feedback ID
-    pub id: Uuid,
-    /// 🎯 Target repository
-    pub repository: String,
-    /// 📝 Feedback content (truncated for privacy)
-    pub content_preview: String,
-    /// 📋 Current status
-    pub status: FeedbackStatus,
-    /// 🌿 GitHub branch name (if created)
-    pub branch_name: Option<String>,
-    /// 🔗 Pull request URL (if created)
-    pub pull_request_url: Option<String>,
-    /// 🤖 LLM provider used
-    pub llm_provider: Option<String>,
-    /// ❌ Error message (if failed)
-    pub error_message: Option<String>,
-    /// ⏰ When submitted
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    /// 🔄 Last updated
-    pub updated_at: chrono::DateTime<chrono::Utc>,
-    /// ✅ When completed (if applicable)
-    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
-}
-
-/// 🔍 Feedback query parameters for listing
-#[derive(Debug, Deserialize)]
-pub struct FeedbackQuery {
-    /// 📋 Filter by status
-    pub status: Option<FeedbackStatus>,
-    /// 🎯 Filter by repository
-    pub repository: Option<String>,
-    /// 👤 Filter by user (admin only)
-    pub user_id: Option<Uuid>,
-    /// 🤖 Filter by LLM provider
-    pub llm_provider: Option<String>,
-    /// ⏰ Filter by date range (from)
-    pub from_date: Option<chrono::DateTime<chrono::Utc>>,
-    /// ⏰ Filter by date range (to)
-    pub to_date: Option<chrono::DateTime<chrono::Utc>>,
-}
+/// ✂️ Characters of content in a feedback preview (privacy-friendly)
+const CONTENT_PREVIEW_LENGTH: usize = 200;
+
+// 📦 Request and response models, shared with API clients through feedbacker-types
+pub use feedbacker_types::{
+    AnonymousUserInfo, AssignFeedbackRequest, BudgetApprovalRequest, BulkFeedbackRequest,
+    BulkFeedbackResponse, FeedbackApprovalRequest, FeedbackCommentRequest, FeedbackDetails,
+    FeedbackQuery, ProposedDiff, SubmitFeedbackRequest, SubmitFeedbackResponse,
+};
 
 impl ValidateRequest for SubmitFeedbackRequest {
     /// ✅ Validate feedback submission request
@@ -112,24 +58,34 @@ impl ValidateRequest for SubmitFeedbackRequest {
 
         // 🎯 Validate repository format
         if self.repository.is_empty() {
-            errors.push("Repository cannot be empty".to_string());
+            errors.push(i18n::t("validation-repository-empty"));
         } else if !self.repository.contains('/') || self.repository.split('/').count() != 2 {
-            errors.push("Repository must be in 'owner/repo' format".to_string());
+            errors.push(i18n::t("validation-repository-format"));
         }
 
         // 📝 Validate content
         if self.content.trim().is_empty() {
-            errors.push("Feedback content cannot be empty".to_string());
+            errors.push(i18n::t("validation-content-empty"));
         } else if self.content.len() > 10000 {
-            errors.push("Feedback content cannot exceed 10,000 characters".to_string());
+            errors.push(i18n::t_with("validation-content-too-long", [("max", 10000.into())]));
         } else if self.content.len() < 10 {
-            errors.push("Feedback content must be at least 10 characters".to_string());
+            errors.push(i18n::t_with("validation-content-too-short", [("min", 10.into())]));
+        }
+
+        // 📁 Validate the subdirectory scope if specified
+        if let Some(path) = &self.path {
+            if let Err(e) = normalize_scope_path(path) {
+                errors.push(e.to_string());
+            }
         }
 
         // 🤖 Validate LLM provider if specified
         if let Some(provider) = &self.llm_provider {
-            if !["openai", "anthropic"].contains(&provider.as_str()) {
-                errors.push("Invalid LLM provider. Supported: openai, anthropic".to_string());
+            if !["openai", "anthropic", "custom"].contains(&provider.as_str()) {
+                errors.push(i18n::t_with(
+                    "validation-llm-provider",
+                    [("supported", "openai, anthropic, custom".into())],
+                ));
             }
         }
 
@@ -137,7 +93,7 @@ impl ValidateRequest for SubmitFeedbackRequest {
         if let Some(user_info) = &self.user_info {
             if let Some(email) = &user_info.email {
                 if !email.contains('@') || email.len() > 255 {
-                    errors.push("Invalid email address".to_string());
+                    errors.push(i18n::t("validation-email-invalid"));
                 }
             }
         }
@@ -154,6 +110,7 @@ impl ValidateRequest for SubmitFeedbackRequest {
 /// This is the main endpoint where users submit their improvement ideas!
 pub async fn submit_feedback(
     State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
     Json(request): Json<SubmitFeedbackRequest>,
 ) -> Response {
     info!(
@@ -161,69 +118,182 @@ pub async fn submit_feedback(
         request.repository
     );
 
-    // ✅ Validate the request
-    if let Err(errors) = request.validate() {
-        warn!("❌ Validation failed for feedback submission: {:?}", errors);
-        let api_response = ApiResponse::<()>::error(
-            "validation_error".to_string(),
-            "Request validation failed".to_string(),
-            Some(serde_json::json!({ "errors": errors })),
-        );
-        return (StatusCode::BAD_REQUEST, Json(api_response)).into_response();
-    }
-
-    // 🔍 Check if the repository is accessible and aye-is is a collaborator
-    // TODO: Add repository validation when GitHub module is ready
-    // if !github_client.is_collaborator(&request.repository, "aye-is").await? {
-    //     return forbidden_error();
-    // }
-
-    match create_feedback_record(&app_state, request).await {
+    match submit(&app_state, Some(user.id), request).await {
         Ok(response) => {
             info!(
                 "✅ Feedback submitted successfully: {}",
                 response.feedback_id
             );
-
-            // 🚀 Queue the feedback for processing
-            // TODO: Add job queuing when background jobs module is ready
-            // app_state.job_queue.queue_feedback_processing(response.feedback_id).await?;
-
             (
                 StatusCode::CREATED,
                 Json(ApiResponse::<SubmitFeedbackResponse>::success(
-                    "Feedback submitted successfully! Processing will begin shortly.".to_string(),
+                    i18n::t("feedback-submitted"),
                     response,
                 )),
             ).into_response()
         }
-        Err(e) => {
-            error!("❌ Failed to submit feedback: {:#}", e);
-            let error_msg = format!("{:#}", e);
+        Err(SubmitRejection::Invalid(errors)) => {
             let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
+                "validation_error".to_string(),
+                errors::ErrorKind::Validation.title(),
+                Some(serde_json::json!({ "errors": errors })),
             );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            (StatusCode::BAD_REQUEST, Json(api_response)).into_response()
+        }
+        Err(SubmitRejection::QuotaExceeded(message)) => quota_exceeded(message),
+        Err(SubmitRejection::RepositoryNotAllowed(message)) => {
+            let api_response = ApiResponse::<()>::error("forbidden".to_string(), message, None);
+            (StatusCode::FORBIDDEN, Json(api_response)).into_response()
+        }
+        Err(SubmitRejection::RepositoryNotAccessible(message)) => {
+            let kind = errors::ErrorKind::RepositoryNotAccessible;
+            let api_response = ApiResponse::<()>::error(kind.code().to_string(), message, None);
+            (kind.status(), Json(api_response)).into_response()
+        }
+        Err(SubmitRejection::Failed(e)) => {
+            errors::error_response("Failed to submit feedback", e)
         }
     }
 }
 
+/// 🚧 Why a feedback submission was turned away
+#[derive(Debug)]
+pub enum SubmitRejection {
+    /// ❌ The request didn't validate (one message per problem)
+    Invalid(Vec<String>),
+    /// 📏 The organization's monthly feedback quota is used up
+    QuotaExceeded(String),
+    /// 🚧 The submitter's organization doesn't allow feedback for this repository
+    RepositoryNotAllowed(String),
+    /// 🐙 The repository is missing, archived, a fork, or aye-is can't push to it
+    RepositoryNotAccessible(String),
+    /// 💥 Storing it failed
+    Failed(anyhow::Error),
+}
+
+/// 📝 Validate, check the quota and store a feedback submission
+/// Shared by the HTTP API and the MCP server's submit_feedback tool (see crate::mcp)
+pub async fn submit(
+    app_state: &AppState,
+    user_id: Option<Uuid>,
+    request: SubmitFeedbackRequest,
+) -> Result<SubmitFeedbackResponse, SubmitRejection> {
+    // ✅ Validate the request
+    if let Err(errors) = request.validate() {
+        warn!("❌ Validation failed for feedback submission: {:?}", errors);
+        return Err(SubmitRejection::Invalid(errors));
+    }
+
+    // 📏 Organizations may have a monthly feedback quota
+    match organizations::feedback_quota_exceeded(&app_state.db_pool, &request.repository).await {
+        Ok(Some(message)) => {
+            warn!("📏 Feedback for {} refused: {}", request.repository, message);
+            return Err(SubmitRejection::QuotaExceeded(message));
+        }
+        Ok(None) => {}
+        Err(e) => error!("❌ Feedback quota check failed: {:#}", e),
+    }
+
+    // 🚧 Organizations may limit which repositories their members send feedback to
+    if let Some(user_id) = user_id {
+        match organizations::feedback_repository_not_allowed(
+            &app_state.db_pool,
+            user_id,
+            &request.repository,
+        )
+        .await
+        {
+            Ok(Some(message)) => {
+                warn!("🚧 Feedback for {} refused: {}", request.repository, message);
+                return Err(SubmitRejection::RepositoryNotAllowed(message));
+            }
+            Ok(None) => {}
+            Err(e) => return Err(SubmitRejection::Failed(e)),
+        }
+    }
+
+    // 🔍 Check the repository exists and aye-is can open pull requests on it
+    // (GitHub being unreachable doesn't turn feedback away)
+    match repository_access_problem(app_state, &request.repository).await {
+        Ok(Some(problem)) => {
+            warn!("🐙 Feedback for {} refused: {}", request.repository, problem);
+            return Err(SubmitRejection::RepositoryNotAccessible(problem));
+        }
+        Ok(None) => {}
+        Err(e) => warn!(
+            "⚠️ Repository access check for {} failed, accepting the feedback: {:#}",
+            request.repository, e
+        ),
+    }
+
+    // 🧵 The submission stage learns its feedback id once the record exists
+    let span = Stage::Submission.span(None);
+    let response = feedback_trace::traced(
+        span.clone(),
+        create_feedback_record(app_state, user_id, request),
+    )
+    .await
+    .map_err(SubmitRejection::Failed)?;
+    feedback_trace::attach_feedback(&span, response.feedback_id);
+
+    // 🚀 Queue the feedback for processing (it's stored either way)
+    if let Err(e) = queue_processing(app_state, response.feedback_id).await {
+        error!(
+            "❌ Could not queue feedback {} for processing: {:#}",
+            response.feedback_id, e
+        );
+    }
+
+    Ok(response)
+}
+
+/// 🐙 Why aye-is couldn't open a pull request on a repository (None when it can),
+/// asked of the GitHub endpoint of the repository's project, if it has one
+async fn repository_access_problem(
+    app_state: &AppState,
+    repository: &str,
+) -> Result<Option<String>> {
+    let (owner, repo) = parse_repository(repository)?;
+    let github = app_state.config.load().github.clone();
+    let client = repository_github_client(app_state, repository).await?;
+    Ok(match client.find_repository_info(&owner, &repo).await? {
+        Some(info) => info.access_problem(&github.username),
+        None => Some(format!(
+            "Repository {} does not exist or {} cannot see it",
+            repository, github.username
+        )),
+    })
+}
+
+/// 🐙 Client for the GitHub endpoint of a repository's project (the configured
+/// one when no project has the repository)
+async fn repository_github_client(app_state: &AppState, repository: &str) -> Result<GitHubClient> {
+    let github = app_state.config.load().github.clone();
+    let github_config = match Project::list_by_repository(&app_state.db_pool, repository)
+        .await?
+        .first()
+    {
+        Some(project) => scm::github_config(&app_state.db_pool, &github, project).await?,
+        None => github,
+    };
+    GitHubClient::new(github_config)
+}
+
 /// 🔍 Get feedback by ID
 /// Allows users to check the status of their submitted feedback
 pub async fn get_feedback(
     State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
     Path(feedback_id): Path<Uuid>,
 ) -> Response {
     info!("🔍 Fetching feedback details for ID: {}", feedback_id);
 
-    match fetch_feedback_details(&app_state, feedback_id).await {
+    match fetch_feedback_details(&app_state, &user, feedback_id).await {
         Ok(Some(feedback)) => {
             info!("✅ Found feedback: {}", feedback_id);
             (
                 StatusCode::OK,
-                Json(ApiResponse::success("Feedback found".to_string(), feedback)),
+                Json(ApiResponse::success(i18n::t("feedback-found"), feedback)),
             ).into_response()
         }
         Ok(None) => {
@@ -236,49 +306,193 @@ pub async fn get_feedback(
             (StatusCode::NOT_FOUND, Json(api_response)).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to fetch feedback {}: {:#}", feedback_id, e);
-            let error_msg = format!("{:#}", e);
+            errors::error_response(
+                &format!("Failed to fetch feedback {}", feedback_id),
+                e,
+            )
+        }
+    }
+}
+
+/// 🔀 Get the diff proposed for a feedback item
+/// Lets the submitter review it before approving or rejecting the result
+pub async fn get_feedback_diff(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    info!("🔀 Fetching proposed diff for feedback: {}", feedback_id);
+
+    match find_visible_feedback(&app_state, &user, feedback_id).await {
+        Ok(Some(feedback)) => {
+            let proposed = ProposedDiff {
+                feedback_id: feedback.id,
+                status: feedback.status,
+                diff: feedback.proposed_diff().map(str::to_string),
+                pull_request_url: feedback.pull_request_url,
+            };
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(i18n::t("feedback-diff-retrieved"), proposed)),
+            )
+                .into_response()
+        }
+        Ok(None) => {
+            warn!("🔍 Feedback not found: {}", feedback_id);
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Feedback not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => {
+            errors::error_response(
+                &format!("Failed to fetch diff for feedback {}", feedback_id),
+                e,
+            )
+        }
+    }
+}
+
+/// 🕰️ Get the processing timeline for a feedback item
+/// Shows the change plan and every per-file generation step as it happens
+pub async fn get_feedback_events(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    info!("🕰️ Fetching event timeline for feedback: {}", feedback_id);
+
+    let result = async {
+        if find_visible_feedback(&app_state, &user, feedback_id).await?.is_none() {
+            return Ok(None);
+        }
+        let events = FeedbackEvent::list_for_feedback(&app_state.db_pool, feedback_id).await?;
+        Ok::<_, anyhow::Error>(Some(
+            events
+                .into_iter()
+                .map(feedbacker_types::FeedbackEvent::from)
+                .collect::<Vec<_>>(),
+        ))
+    }
+    .await;
+
+    match result {
+        Ok(Some(events)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("feedback-events-retrieved"),
+                events,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            warn!("🔍 Feedback not found: {}", feedback_id);
             let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
+                "not_found".to_string(),
+                "Feedback not found".to_string(),
+                None,
             );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => {
+            errors::error_response(
+                &format!("Failed to fetch events for feedback {}", feedback_id),
+                e,
+            )
         }
     }
 }
 
+/// 🧵 Where a feedback item's time went: recorded stage timings, per stage
+/// Admins only (see the auth middleware)
+pub async fn get_feedback_trace(
+    State(app_state): State<AppState>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    info!("🧵 Fetching stage trace for feedback: {}", feedback_id);
+
+    let result = async {
+        let Some(feedback) = Feedback::find_by_id(&app_state.db_pool, feedback_id).await? else {
+            return Ok(None);
+        };
+        feedback_trace::load(&app_state.db_pool, &feedback)
+            .await
+            .map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(trace)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("feedback-trace-retrieved"),
+                trace,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            warn!("🔍 Feedback not found: {}", feedback_id);
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Feedback not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => errors::error_response(
+            &format!("Failed to load the trace of feedback {}", feedback_id),
+            e,
+        ),
+    }
+}
+
 /// 📋 List feedback with filtering and pagination
-/// Allows users to see all their submitted feedback
+/// Allows users to see all their submitted feedback; only those who may view
+/// all feedback can list someone else's
 pub async fn list_feedback(
     State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(pagination): Query<PaginationParams>,
-    Query(query): Query<FeedbackQuery>,
+    Query(fields): Query<FieldsParams>,
+    Query(mut query): Query<FeedbackQuery>,
 ) -> Response {
+    if query.assigned_to_me {
+        query.assigned_to = Some(user.id);
+    }
+    // 👥 What's assigned to the caller is theirs to list, whoever submitted it
+    let own_assignments = query.assigned_to == Some(user.id);
+    if !own_assignments
+        && (query.user_id.is_none() || !user.has_permission(Permission::ViewAllFeedback))
+    {
+        query.user_id = Some(user.id);
+    }
     info!("📋 Listing feedback with filters: {:?}", query);
 
     let pagination = pagination.validate();
+    let order_by = match pagination.order_by(Feedback::SORTABLE, "created_at") {
+        Ok(order_by) => order_by,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+    let fields = match fields.selection(FeedbackDetails::FIELDS) {
+        Ok(fields) => fields,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
 
-    match fetch_feedback_list(&app_state, &pagination, &query).await {
+    match fetch_feedback_list(&app_state, &pagination, &order_by, &fields, &query).await {
         Ok(response) => {
             info!("✅ Retrieved {} feedback items", response.items.len());
             (
                 StatusCode::OK,
                 Json(ApiResponse::success(
-                    "Feedback list retrieved successfully".to_string(),
+                    i18n::t("feedback-list-retrieved"),
                     response,
                 )),
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to list feedback: {:#}", e);
-            let error_msg = format!("{:#}", e);
-            let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
-            );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            errors::error_response("Failed to list feedback", e)
         }
     }
 }
@@ -297,20 +511,13 @@ pub async fn get_feedback_stats(
             (
                 StatusCode::OK,
                 Json(ApiResponse::success(
-                    "Statistics retrieved successfully".to_string(),
+                    i18n::t("feedback-stats-retrieved"),
                     stats,
                 )),
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to get feedback statistics: {:#}", e);
-            let error_msg = format!("{:#}", e);
-            let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
-            );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            errors::error_response("Failed to get feedback statistics", e)
         }
     }
 }
@@ -329,41 +536,393 @@ pub async fn retry_feedback(
             (
                 StatusCode::OK,
                 Json(ApiResponse::<()>::success_no_data(
-                    "Feedback processing retry queued successfully".to_string(),
+                    i18n::t("feedback-retry-queued"),
                 )),
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to retry feedback {}: {:#}", feedback_id, e);
-            let error_msg = format!("{:#}", e);
+            errors::error_response(
+                &format!("Failed to retry feedback {}", feedback_id),
+                e,
+            )
+        }
+    }
+}
+
+/// 👍 Record whether the submitter approves of the result
+/// Counts towards the user approval rate of the prompt versions that produced it.
+/// A rejection from someone who may run the project's pipelines also closes the
+/// pull requests still open for it; anyone else's only counts
+pub async fn approve_feedback(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+    Json(request): Json<FeedbackApprovalRequest>,
+) -> Response {
+    info!("👍 Recording approval={} for feedback: {}", request.approved, feedback_id);
+
+    let result = async {
+        let Some(feedback) = find_visible_feedback(&app_state, &user, feedback_id).await? else {
+            return Ok(None);
+        };
+        PromptOutcome::record(
+            &app_state.db_pool,
+            feedback_id,
+            PromptMetric::UserApproved,
+            request.approved,
+        )
+        .await?;
+        if request.approved {
+            return Ok(Some(i18n::t("feedback-approval-recorded")));
+        }
+
+        // 🚫 A rejected result doesn't stay up for review, if they may take it down
+        if !may_close_pull_requests(&app_state.db_pool, &user, &feedback).await? {
+            return Ok(Some(i18n::t("feedback-rejection-recorded")));
+        }
+        let outcome = close_pull_requests(&app_state, &feedback).await?;
+        Ok::<_, anyhow::Error>(Some(if outcome.failed.is_empty() {
+            i18n::t_with(
+                "feedback-rejected",
+                [("closed", outcome.closed.len().into())],
+            )
+        } else {
+            i18n::t_with(
+                "feedback-rejected-partly",
+                [("failed", outcome.failed.len().into())],
+            )
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(Some(message)) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_no_data(message)),
+        )
+            .into_response(),
+        Ok(None) => {
+            warn!("🔍 Feedback not found: {}", feedback_id);
             let api_response = ApiResponse::<()>::error(
-                "internal_error".to_string(),
-                "An internal error occurred".to_string(),
-                Some(serde_json::json!({ "details": error_msg })),
+                "not_found".to_string(),
+                "Feedback not found".to_string(),
+                None,
             );
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response)).into_response()
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => {
+            errors::error_response(
+                &format!("Failed to record approval for feedback {}", feedback_id),
+                e,
+            )
+        }
+    }
+}
+
+/// 🐙 Whether a user may close a feedback item's pull requests: they need to be
+/// able to run pipelines on a project for its repository, not just see it
+pub async fn may_close_pull_requests(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback: &Feedback,
+) -> Result<bool> {
+    let role = organizations::repository_role(pool, user, &feedback.repository).await?;
+    Ok(role.is_some_and(|role| role >= ProjectAccess::Run.required_role()))
+}
+
+/// 🚫 What rejecting feedback did to its pull requests
+#[derive(Debug, Default)]
+struct ClosedPullRequests {
+    /// ✅ Closed just now
+    closed: Vec<u64>,
+    /// ❌ Still open: GitHub refused or couldn't be reached
+    failed: Vec<u64>,
+}
+
+/// 🚫 Close the pull requests of rejected feedback that are still open, noting
+/// each one on the timeline as it's closed. A PR that can't be closed doesn't
+/// stop the others
+async fn close_pull_requests(
+    app_state: &AppState,
+    feedback: &Feedback,
+) -> Result<ClosedPullRequests> {
+    let mut outcome = ClosedPullRequests::default();
+    let numbers = feedback.pull_request_numbers(&app_state.db_pool).await?;
+    if numbers.is_empty() {
+        return Ok(outcome);
+    }
+
+    let (owner, repo) = parse_repository(&feedback.repository)?;
+    let client = repository_github_client(app_state, &feedback.repository).await?;
+    for number in numbers {
+        let closed = async {
+            let pr = client.get_pull_request(&owner, &repo, number).await?;
+            if pr.state != "open" {
+                return Ok(false);
+            }
+            client.close_pull_request(&owner, &repo, number).await?;
+            Ok::<_, anyhow::Error>(true)
+        };
+        match closed.await {
+            Ok(true) => {
+                info!(
+                    "🚫 Closed PR #{} of rejected feedback {}",
+                    number, feedback.id
+                );
+                FeedbackEvent::record(
+                    &app_state.db_pool,
+                    feedback.id,
+                    FeedbackEvent::PULL_REQUEST_CLOSED,
+                    serde_json::json!({ "number": number }),
+                )
+                .await?;
+                outcome.closed.push(number);
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(
+                    "⚠️ Couldn't close PR #{} of rejected feedback {}: {:#}",
+                    number, feedback.id, e
+                );
+                outcome.failed.push(number);
+            }
         }
     }
+    Ok(outcome)
+}
+
+/// 💸 Approve spending more tokens on feedback paused at its budget, and resume it
+/// Needs an admin role on the project of the paused run (see crate::budgets)
+pub async fn approve_budget(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+    Json(request): Json<BudgetApprovalRequest>,
+) -> Response {
+    info!(
+        "💸 Budget approval of {:?} more tokens for feedback {} by {}",
+        request.additional_tokens, feedback_id, user.email
+    );
+
+    match budgets::approve(&app_state, &user, feedback_id, request.additional_tokens).await {
+        Ok((feedback, additional)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with("feedback-budget-approved", [("tokens", additional.into())]),
+                feedback_details(feedback),
+            )),
+        )
+            .into_response(),
+        Err(rejection) => triage_rejection(
+            rejection,
+            &format!("Failed to approve budget for feedback {}", feedback_id),
+        ),
+    }
+}
+
+/// 🧹 Retry, cancel or label many feedback items at once
+/// Items that aren't the caller's (unless they're an admin) are reported as not found
+pub async fn bulk_feedback(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<BulkFeedbackRequest>,
+) -> Response {
+    info!(
+        "🧹 Bulk {:?} over {} feedback items requested by {}",
+        request.action,
+        request.ids.len(),
+        user.email
+    );
+
+    if let Err(errors) = feedback_bulk::validate(&request) {
+        warn!("❌ Bulk feedback request rejected: {:?}", errors);
+        return validation_error(errors).into_response();
+    }
+
+    match feedback_bulk::apply(&app_state.db_pool, app_state.jobs.as_ref(), &user, &request).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with(
+                    "feedback-bulk-applied",
+                    [
+                        ("succeeded", response.succeeded.into()),
+                        ("total", response.results.len().into()),
+                    ],
+                ),
+                response,
+            )),
+        )
+            .into_response(),
+        Err(e) => errors::error_response("Failed to apply bulk feedback operation", e),
+    }
+}
+
+/// 👥 Assign a feedback item to a maintainer, or unassign it
+/// Needs a member role on a project for its repository (see crate::feedback_triage)
+pub async fn assign_feedback(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+    Json(request): Json<AssignFeedbackRequest>,
+) -> Response {
+    info!(
+        "👥 Assigning feedback {} to {:?}",
+        feedback_id, request.assignee_id
+    );
+
+    match feedback_triage::assign(&app_state.db_pool, &user, feedback_id, request.assignee_id)
+        .await
+    {
+        Ok(feedback) => {
+            let message = if feedback.assigned_to.is_some() {
+                i18n::t("feedback-assigned")
+            } else {
+                i18n::t("feedback-unassigned")
+            };
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(message, feedback_details(feedback))),
+            )
+                .into_response()
+        }
+        Err(rejection) => triage_rejection(
+            rejection,
+            &format!("Failed to assign feedback {}", feedback_id),
+        ),
+    }
+}
+
+/// 💬 List the internal notes maintainers left on a feedback item
+pub async fn list_feedback_comments(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    info!("💬 Fetching comments on feedback: {}", feedback_id);
+
+    match feedback_triage::comments(&app_state.db_pool, &user, feedback_id).await {
+        Ok(comments) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("feedback-comments-retrieved"),
+                comments
+                    .into_iter()
+                    .map(feedbacker_types::FeedbackComment::from)
+                    .collect::<Vec<_>>(),
+            )),
+        )
+            .into_response(),
+        Err(rejection) => triage_rejection(
+            rejection,
+            &format!("Failed to fetch comments on feedback {}", feedback_id),
+        ),
+    }
+}
+
+/// 💬 Leave an internal note on a feedback item
+pub async fn add_feedback_comment(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+    Json(request): Json<FeedbackCommentRequest>,
+) -> Response {
+    info!("💬 Adding a comment to feedback: {}", feedback_id);
+
+    match feedback_triage::add_comment(&app_state.db_pool, &user, feedback_id, &request.body)
+        .await
+    {
+        Ok(comment) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                i18n::t("feedback-comment-added"),
+                feedbacker_types::FeedbackComment::from(comment),
+            )),
+        )
+            .into_response(),
+        Err(rejection) => triage_rejection(
+            rejection,
+            &format!("Failed to comment on feedback {}", feedback_id),
+        ),
+    }
+}
+
+/// 🗑️ Delete an internal note (the author's own, or any for project admins)
+pub async fn delete_feedback_comment(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((feedback_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    info!(
+        "🗑️ Deleting comment {} on feedback: {}",
+        comment_id, feedback_id
+    );
+
+    match feedback_triage::delete_comment(&app_state.db_pool, &user, feedback_id, comment_id)
+        .await
+    {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_no_data(i18n::t(
+                "feedback-comment-deleted",
+            ))),
+        )
+            .into_response(),
+        Err(rejection) => triage_rejection(
+            rejection,
+            &format!("Failed to delete comment {}", comment_id),
+        ),
+    }
 }
 
 // 🔧 Helper functions for the API endpoints
 
+/// 🚧 The response for a turned-away triage request
+fn triage_rejection(rejection: TriageRejection, context: &str) -> Response {
+    match rejection {
+        TriageRejection::NotFound(resource) => not_found_error(resource).into_response(),
+        TriageRejection::Forbidden => forbidden_error().into_response(),
+        TriageRejection::Invalid(errors) => validation_error(errors).into_response(),
+        TriageRejection::Failed(e) => errors::error_response(context, e),
+    }
+}
+
 /// ➕ Create a new feedback record in the database
 async fn create_feedback_record(
     app_state: &AppState,
+    user_id: Option<Uuid>,
     request: SubmitFeedbackRequest,
 ) -> Result<SubmitFeedbackResponse> {
-    // TODO: Get user_id from authentication when auth module is ready
-    let user_id = None; // For now, support anonymous feedback
+    // 📁 Store the normalized scope (validation already rejected bad paths)
+    let path = match &request.path {
+        Some(path) => normalize_scope_path(path)?,
+        None => None,
+    };
 
-    let feedback = Feedback::create(
+    let submitter_email = request
+        .user_info
+        .as_ref()
+        .and_then(|user_info| user_info.email.clone());
+    let mut feedback = Feedback::create(
         &app_state.db_pool,
         user_id,
         request.repository.clone(),
+        path,
         request.content,
     )
     .await
     .context("Failed to create feedback record")?;
+    // 🙋 Anonymous submitters can claim their feedback with the email they left
+    if let Some(email) = submitter_email {
+        feedback_claims::offer_claim(app_state, &mut feedback, &email)
+            .await
+            .context("Failed to offer the feedback claim")?;
+    }
+    // 📊 New open feedback changes its project's status
+    app_state
+        .cache
+        .invalidate_all(CacheNamespace::ProjectStatus)
+        .await;
 
     let response = SubmitFeedbackResponse {
         feedback_id: feedback.id,
@@ -375,124 +934,95 @@ async fn create_feedback_record(
     Ok(response)
 }
 
+/// 🔍 Find a feedback item the user may see (None when it doesn't exist or
+/// belongs to someone else, so the two look the same from outside)
+pub async fn find_visible_feedback(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+) -> Result<Option<Feedback>> {
+    visible_feedback(&app_state.db_pool, user, feedback_id).await
+}
+
+/// 👀 `find_visible_feedback` for code that holds only the pool
+pub async fn visible_feedback(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+) -> Result<Option<Feedback>> {
+    let Some(feedback) = Feedback::find_by_id(pool, feedback_id)
+        .await
+        .context("Failed to fetch feedback from database")?
+    else {
+        return Ok(None);
+    };
+    if !organizations::can_view_feedback(pool, user, &feedback).await? {
+        return Ok(None);
+    }
+    Ok(Some(feedback))
+}
+
 /// 🔍 Fetch detailed feedback information
-async fn fetch_feedback_details(
+/// Also answers the MCP server's get_feedback_status tool
+pub async fn fetch_feedback_details(
     app_state: &AppState,
+    user: &AuthenticatedUser,
     feedback_id: Uuid,
 ) -> Result<Option<FeedbackDetails>> {
-    let feedback = Feedback::find_by_id(&app_state.db_pool, feedback_id)
-        .await
-        .context("Failed to fetch feedback from database")?;
-
-    Ok(feedback.map(|f| FeedbackDetails {
-        id: f.id,
-        repository: f.repository,
-        content_preview: truncate_content(&f.content, 200),
-        status: f.status,
-        branch_name: f.branch_name,
-        pull_request_url: f.pull_request_url,
-        llm_provider: f.llm_provider,
-        error_message: f.error_message,
-        created_at: f.created_at,
-        updated_at: f.updated_at,
-        completed_at: f.completed_at,
-    }))
+    let feedback = find_visible_feedback(app_state, user, feedback_id).await?;
+    Ok(feedback.map(feedback_details))
 }
 
 /// 📋 Fetch a paginated list of feedback
 async fn fetch_feedback_list(
     app_state: &AppState,
     pagination: &PaginationParams,
+    order_by: &str,
+    fields: &FieldSelection,
     query: &FeedbackQuery,
-) -> Result<PaginatedResponse<FeedbackDetails>> {
-    // 🏗️ Build the SQL query with filters
-    let mut sql_where = Vec::new();
-    let mut params = Vec::new();
-    let mut param_index = 1;
-
-    if let Some(status) = &query.status {
-        sql_where.push(format!("status = ${}", param_index));
-        params.push(serde_json::to_value(status)?);
-        param_index += 1;
-    }
-
-    if let Some(repository) = &query.repository {
-        sql_where.push(format!("repository = ${}", param_index));
-        params.push(serde_json::to_value(repository)?);
-        param_index += 1;
-    }
-
-    if let Some(user_id) = &query.user_id {
-        sql_where.push(format!("user_id = ${}", param_index));
-        params.push(serde_json::to_value(user_id)?);
-        param_index += 1;
-    }
-
-    let where_clause = if sql_where.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", sql_where.join(" AND "))
-    };
-
-    // 📊 Get total count
-    let count_query = format!("SELECT COUNT(*) FROM feedback {}", where_clause);
-    let total = sqlx::query_scalar::<_, i64>(&count_query)
-        .fetch_one(&app_state.db_pool)
+) -> Result<PaginatedResponse<Sparse<FeedbackDetails>>> {
+    let total = Feedback::count_matching(&app_state.db_pool, query)
         .await
         .context("Failed to get feedback count")?;
-
-    // 📋 Get the actual feedback records
-    let order_clause = format!(
-        "ORDER BY created_at {} LIMIT {} OFFSET {}",
-        match pagination.sort_order {
-            crate::api::SortOrder::Asc => "ASC",
-            crate::api::SortOrder::Desc => "DESC",
-        },
-        pagination.limit,
-        pagination.offset()
-    );
-
-    let query_sql = format!(
-        r#"
-        SELECT id, repository, content, status, branch_name, pull_request_url,
-               llm_provider, error_message, created_at, updated_at, completed_at
-        FROM feedback
-        {}
-        {}
-        "#,
-        where_clause, order_clause
-    );
-
-    let feedback_records = sqlx::query(&query_sql)
-        .fetch_all(&app_state.db_pool)
-        .await
-        .context("Failed to fetch feedback list")?;
-
-    let feedback_details: Vec<FeedbackDetails> = feedback_records
-        .into_iter()
-        .map(|row| FeedbackDetails {
-            id: row.get("id"),
-            repository: row.get("repository"),
-            content_preview: truncate_content(&row.get::<String, _>("content"), 200),
-            status: serde_json::from_value(row.get("status")).unwrap_or(FeedbackStatus::Pending),
-            branch_name: row.get("branch_name"),
-            pull_request_url: row.get("pull_request_url"),
-            llm_provider: row.get("llm_provider"),
-            error_message: row.get("error_message"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            completed_at: row.get("completed_at"),
-        })
-        .collect();
+    let feedback = Feedback::list_matching(
+        &app_state.db_pool,
+        query,
+        order_by,
+        pagination.limit as i64,
+        pagination.offset() as i64,
+    )
+    .await
+    .context("Failed to fetch feedback list")?;
 
     Ok(PaginatedResponse::new(
-        feedback_details,
+        feedback
+            .into_iter()
+            .map(|feedback| fields.apply(feedback_details(feedback)))
+            .collect(),
         pagination.page,
         pagination.limit,
         total as u64,
     ))
 }
 
+/// 📦 The API's view of a feedback item
+fn feedback_details(feedback: Feedback) -> FeedbackDetails {
+    FeedbackDetails {
+        id: feedback.id,
+        repository: feedback.repository,
+        content_preview: text::preview(&feedback.content, CONTENT_PREVIEW_LENGTH),
+        status: feedback.status,
+        branch_name: feedback.branch_name,
+        pull_request_url: feedback.pull_request_url,
+        llm_provider: feedback.llm_provider,
+        error_message: feedback.error_message,
+        created_at: feedback.created_at,
+        updated_at: feedback.updated_at,
+        completed_at: feedback.completed_at,
+        assigned_to: feedback.assigned_to,
+    }
+}
+
 /// 🔄 Retry failed feedback processing
 async fn retry_feedback_processing(app_state: &AppState, feedback_id: Uuid) -> Result<()> {
     // 🔍 First, verify the feedback exists and can be retried
@@ -520,21 +1050,37 @@ async fn retry_feedback_processing(app_state: &AppState, feedback_id: Uuid) -> R
         .context("Failed to reset feedback status")?;
 
     // 🚀 Queue the feedback for processing again
-    // TODO: Add job queuing when background jobs module is ready
-    // app_state.job_queue.queue_feedback_processing(feedback_id).await?;
-
-    info!("🔄 Feedback {} queued for retry processing", feedback_id);
+    if queue_processing(app_state, feedback_id).await? {
+        info!("🔄 Feedback {} queued for retry processing", feedback_id);
+    }
 
     Ok(())
 }
 
-/// ✂️ Truncate content for preview (privacy-friendly)
-fn truncate_content(content: &str, max_length: usize) -> String {
-    if content.len() <= max_length {
-        content.to_string()
-    } else {
-        format!("{}...", &content[..max_length])
-    }
+/// 🚀 Queue a feedback item as a run on its repository's project
+/// Returns false when the repository has no active project: the feedback stays
+/// pending until there is one to run it
+async fn queue_processing(app_state: &AppState, feedback_id: Uuid) -> Result<bool> {
+    let mut feedback = Feedback::find_by_id(&app_state.db_pool, feedback_id)
+        .await?
+        .context("Feedback not found")?;
+    let project = Project::list_by_repository(&app_state.db_pool, &feedback.repository)
+        .await?
+        .into_iter()
+        .find(|project| project.is_active);
+    let Some(project) = project else {
+        info!(
+            "📭 No active project for {}, feedback {} stays pending",
+            feedback.repository, feedback.id
+        );
+        return Ok(false);
+    };
+
+    feedback
+        .update_status(&app_state.db_pool, FeedbackStatus::Processing, None)
+        .await?;
+    runs::queue_project_run(app_state, &project, &feedback, PipelineMode::Feedback).await?;
+    Ok(true)
 }
 
 // 🧪 Tests - Because we thoroughly test our feedback API!
@@ -547,6 +1093,7 @@ mod tests {
         let valid_request = SubmitFeedbackRequest {
             repository: "owner/repo".to_string(),
             content: "This is a valid feedback content that is long enough".to_string(),
+            path: Some("crates/foo".to_string()),
             llm_provider: Some("openai".to_string()),
             metadata: None,
             user_info: None,
@@ -558,6 +1105,7 @@ mod tests {
         let invalid_request = SubmitFeedbackRequest {
             repository: "invalid".to_string(),
             content: "short".to_string(),
+            path: Some("../escape".to_string()),
             llm_provider: Some("invalid_provider".to_string()),
             metadata: None,
             user_info: None,
@@ -571,12 +1119,17 @@ mod tests {
     #[test]
     fn test_content_truncation() {
         let short_content = "Short content";
-        let truncated = truncate_content(short_content, 100);
+        let truncated = text::preview(short_content, 100);
         assert_eq!(truncated, short_content);
 
         let long_content = "This is a very long content that should be truncated when it exceeds the maximum length limit";
-        let truncated = truncate_content(long_content, 20);
-        assert_eq!(truncated, "This is a very long ...".to_string());
+        let truncated = text::preview(long_content, 20);
+        assert_eq!(truncated, "This is a very long…".to_string());
+
+        // 🌏 Cut between characters, never inside one
+        let emoji_content = "🚀".repeat(CONTENT_PREVIEW_LENGTH + 1);
+        let truncated = text::preview(&emoji_content, CONTENT_PREVIEW_LENGTH);
+        assert_eq!(truncated.chars().count(), CONTENT_PREVIEW_LENGTH);
         println!("✅ Content truncation test passed!");
     }
 
@@ -15,7 +15,16 @@ use tracing::{error, info, warn};
 
 use crate::{
     api::{ApiResponse, AppState},
-    database::get_pool_stats,
+    config::LlmProvider,
+    database::{
+        get_pool_stats,
+        query_metrics::{self, SlowQuery},
+    },
+    github::rate_limit,
+    i18n,
+    jobs::worker::WorkerUtilization,
+    llm::circuit_breaker::CircuitState,
+    metrics,
 };
 
 /// 💚 Basic health check response
@@ -99,6 +108,8 @@ pub struct LlmProvidersHealth {
     pub openai: Option<ComponentStatus>,
     /// 🎭 Anthropic API status
     pub anthropic: Option<ComponentStatus>,
+    /// 🔌 OpenAI-compatible endpoint status
+    pub custom: Option<ComponentStatus>,
 }
 
 /// 📈 Performance metrics
@@ -106,10 +117,14 @@ pub struct LlmProvidersHealth {
 pub struct PerformanceMetrics {
     /// 🗄️ Database connection pool statistics
     pub database_pool: DatabasePoolMetrics,
+    /// 🐢 Database statement counts and the latest slow ones
+    pub database_queries: DatabaseQueryMetrics,
     /// 💾 Memory usage information
     pub memory: MemoryMetrics,
     /// 📊 Request statistics (if available)
     pub requests: Option<RequestMetrics>,
+    /// 👷 Job worker utilization (when background jobs are enabled)
+    pub job_workers: Option<WorkerUtilization>,
 }
 
 /// 🗄️ Database pool metrics
@@ -121,10 +136,25 @@ pub struct DatabasePoolMetrics {
     pub idle_connections: u32,
     /// 🏃‍♂️ Active connections
     pub active_connections: u32,
+    /// 🎯 Most connections the pool will open
+    pub max_connections: u32,
     /// ✅ Pool health status
     pub is_healthy: bool,
 }
 
+/// 🐢 Database query metrics
+#[derive(Debug, Serialize)]
+pub struct DatabaseQueryMetrics {
+    /// 🔢 Statements run since startup
+    pub total_queries: u64,
+    /// 🐢 Statements over the slow query threshold
+    pub slow_queries: u64,
+    /// ⏱️ The slow query threshold
+    pub slow_query_threshold_ms: u64,
+    /// 📋 The latest slow queries, newest first
+    pub recent_slow_queries: Vec<SlowQuery>,
+}
+
 /// 💾 Memory usage metrics
 #[derive(Debug, Serialize)]
 pub struct MemoryMetrics {
@@ -171,7 +201,7 @@ pub async fn health_check(State(app_state): State<AppState>) -> impl IntoRespons
         timestamp: chrono::Utc::now(),
         uptime_seconds: uptime.as_secs(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        environment: app_state.config.server.environment.to_string(),
+        environment: app_state.config.load().server.environment.to_string(),
     };
 
     let status_code = match response.status {
@@ -185,7 +215,7 @@ pub async fn health_check(State(app_state): State<AppState>) -> impl IntoRespons
     (
         status_code,
         Json(ApiResponse::success(
-            "Health check completed".to_string(),
+            i18n::t("health-check-completed"),
             response,
         )),
     )
@@ -208,7 +238,7 @@ pub async fn detailed_health_check(State(app_state): State<AppState>) -> impl In
         timestamp: chrono::Utc::now(),
         uptime_seconds: uptime.as_secs(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        environment: app_state.config.server.environment.to_string(),
+        environment: app_state.config.load().server.environment.to_string(),
         components,
         metrics,
     };
@@ -227,7 +257,7 @@ pub async fn detailed_health_check(State(app_state): State<AppState>) -> impl In
     (
         status_code,
         Json(ApiResponse::success(
-            "Detailed health check completed".to_string(),
+            i18n::t("health-check-detailed-completed"),
             response,
         )),
     )
@@ -319,13 +349,14 @@ async fn check_all_components(app_state: &AppState) -> ComponentHealth {
     let llm_providers = LlmProvidersHealth {
         openai: check_openai_health(app_state).await,
         anthropic: check_anthropic_health(app_state).await,
+        custom: check_custom_llm_health(app_state).await,
     };
 
     // 🐙 GitHub API health check
     let github_api = check_github_health(app_state).await;
 
     // 📧 Email service health check (if enabled)
-    let email_service = if app_state.config.email.is_some() {
+    let email_service = if app_state.config.load().email.is_some() {
         Some(check_email_health(app_state).await)
     } else {
         None
@@ -345,48 +376,92 @@ async fn check_all_components(app_state: &AppState) -> ComponentHealth {
 
 /// 🧠 Check OpenAI API health
 async fn check_openai_health(app_state: &AppState) -> Option<ComponentStatus> {
-    if app_state.config.llm.openai.is_none() {
-        return None;
-    }
-
-    let now = chrono::Utc::now();
-
-    // TODO: Implement actual OpenAI health check when LLM module is ready
-    // For now, return a placeholder
-    Some(ComponentStatus {
-        status: HealthStatus::Healthy,
-        response_time_ms: Some(150),
-        message: "OpenAI API connection not implemented yet".to_string(),
-        last_checked: now,
-    })
+    llm_provider_status(app_state, LlmProvider::OpenAi).await
 }
 
 /// 🎭 Check Anthropic API health
 async fn check_anthropic_health(app_state: &AppState) -> Option<ComponentStatus> {
-    if app_state.config.llm.anthropic.is_none() {
-        return None;
-    }
+    llm_provider_status(app_state, LlmProvider::Anthropic).await
+}
 
-    let now = chrono::Utc::now();
+/// 🔌 Check the OpenAI-compatible endpoint health
+async fn check_custom_llm_health(app_state: &AppState) -> Option<ComponentStatus> {
+    llm_provider_status(app_state, LlmProvider::Custom).await
+}
+
+/// 🏓 Provider health from a (cached) ping and its circuit breaker
+async fn llm_provider_status(
+    app_state: &AppState,
+    provider: LlmProvider,
+) -> Option<ComponentStatus> {
+    let ping = app_state.llm_manager.check_health(&provider).await?;
+
+    let (status, message) = match (ping.reachable, ping.circuit) {
+        (true, CircuitState::Closed) => (
+            HealthStatus::Healthy,
+            "Reachable, calls flowing".to_string(),
+        ),
+        (true, _) => (
+            HealthStatus::Degraded,
+            "Reachable, circuit recovering from recent failures".to_string(),
+        ),
+        (false, _) => (
+            HealthStatus::Unhealthy,
+            format!(
+                "Unreachable ({}), using fallback providers",
+                ping.error.as_deref().unwrap_or("unknown error")
+            ),
+        ),
+    };
 
-    // TODO: Implement actual Anthropic health check when LLM module is ready
     Some(ComponentStatus {
-        status: HealthStatus::Healthy,
-        response_time_ms: Some(200),
-        message: "Anthropic API connection not implemented yet".to_string(),
-        last_checked: now,
+        status,
+        response_time_ms: ping.latency_ms,
+        message,
+        last_checked: ping.checked_at,
     })
 }
 
 /// 🐙 Check GitHub API health
+/// From the rate limit the last response reported: background work is held
+/// back below GITHUB_RATE_LIMIT_RESERVE, so that is already degraded
 async fn check_github_health(app_state: &AppState) -> ComponentStatus {
     let now = chrono::Utc::now();
-
-    // TODO: Implement actual GitHub health check when GitHub module is ready
+    let reserve = app_state.config.load().github.rate_limit_reserve;
+
+    let Some(limit) = rate_limit::current("core").filter(|limit| limit.reset_at > now) else {
+        return ComponentStatus {
+            status: HealthStatus::Healthy,
+            response_time_ms: None,
+            message: "No GitHub rate limit in effect".to_string(),
+            last_checked: now,
+        };
+    };
+    let (status, message) = if limit.remaining == 0 {
+        (
+            HealthStatus::Degraded,
+            format!("Rate limit exhausted until {}", limit.reset_at.to_rfc3339()),
+        )
+    } else if limit.remaining <= reserve {
+        (
+            HealthStatus::Degraded,
+            format!(
+                "{} of {} requests left, background work held back until {}",
+                limit.remaining,
+                limit.limit,
+                limit.reset_at.to_rfc3339()
+            ),
+        )
+    } else {
+        (
+            HealthStatus::Healthy,
+            format!("{} of {} requests left", limit.remaining, limit.limit),
+        )
+    };
     ComponentStatus {
-        status: HealthStatus::Healthy,
-        response_time_ms: Some(100),
-        message: "GitHub API connection not implemented yet".to_string(),
+        status,
+        response_time_ms: None,
+        message,
         last_checked: now,
     }
 }
@@ -405,14 +480,31 @@ async fn check_email_health(app_state: &AppState) -> ComponentStatus {
 }
 
 /// 🔄 Check background jobs health
+/// Every worker busy means new jobs wait, so the service is degraded until one frees up
 async fn check_background_jobs_health(app_state: &AppState) -> ComponentStatus {
     let now = chrono::Utc::now();
 
-    // TODO: Implement actual background jobs health check when jobs module is ready
+    if !app_state.config.load().features.enable_background_jobs {
+        return ComponentStatus {
+            status: HealthStatus::Healthy,
+            response_time_ms: None,
+            message: "Background jobs disabled".to_string(),
+            last_checked: now,
+        };
+    }
+
+    let utilization = app_state.workers.utilization();
     ComponentStatus {
-        status: HealthStatus::Healthy,
-        response_time_ms: Some(25),
-        message: "Background jobs processor not implemented yet".to_string(),
+        status: if utilization.busy < utilization.workers {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded
+        },
+        response_time_ms: None,
+        message: format!(
+            "{} of {} workers busy ({} queue)",
+            utilization.busy, utilization.workers, utilization.backend
+        ),
         last_checked: now,
     }
 }
@@ -425,9 +517,18 @@ async fn collect_performance_metrics(app_state: &AppState) -> PerformanceMetrics
         total_connections: pool_stats.size,
         idle_connections: pool_stats.idle,
         active_connections: pool_stats.active(),
+        max_connections: pool_stats.max,
         is_healthy: pool_stats.is_healthy(),
     };
 
+    let (total_queries, slow_queries) = metrics::db_query_totals();
+    let database_queries = DatabaseQueryMetrics {
+        total_queries,
+        slow_queries,
+        slow_query_threshold_ms: app_state.config.load().database.slow_query_ms,
+        recent_slow_queries: query_metrics::recent_slow_queries(),
+    };
+
     let memory = MemoryMetrics {
         used_bytes: get_memory_usage(),
         peak_bytes: get_peak_memory_usage(),
@@ -436,8 +537,15 @@ async fn collect_performance_metrics(app_state: &AppState) -> PerformanceMetrics
 
     PerformanceMetrics {
         database_pool,
+        database_queries,
         memory,
         requests: None, // TODO: Implement request metrics
+        job_workers: app_state
+            .config
+            .load()
+            .features
+            .enable_background_jobs
+            .then(|| app_state.workers.utilization()),
     }
 }
 
@@ -472,6 +580,12 @@ fn determine_overall_status(components: &ComponentHealth) -> HealthStatus {
             .anthropic
             .as_ref()
             .map(|s| s.status == HealthStatus::Unhealthy)
+            .unwrap_or(true)
+        && components
+            .llm_providers
+            .custom
+            .as_ref()
+            .map(|s| s.status == HealthStatus::Unhealthy)
             .unwrap_or(true);
 
     if llm_all_unhealthy {
@@ -551,6 +665,7 @@ mod tests {
                     last_checked: chrono::Utc::now(),
                 }),
                 anthropic: None,
+                custom: None,
             },
             github_api: ComponentStatus {
                 status: HealthStatus::Healthy,
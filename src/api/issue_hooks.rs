@@ -3,59 +3,22 @@
 // Created with love by Aye & Hue - Making issue management magical! ✨
 
 use crate::{
-    api::{ApiResponse, AppState},
-    github::client::GitHubClient,
+    api::{utils::validation_error, webhooks::Pong, ApiResponse, AppState, ErrorResponse},
+    errors,
+    github::{
+        client::GitHubClient,
+        webhooks::{Issue, IssuesEvent, WebhookEvent},
+    },
+    i18n,
 };
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response},
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tracing::{error, info, warn};
 
-/// 🎫 GitHub Issue webhook payload structure
-#[derive(Debug, Deserialize)]
-pub struct IssueWebhookPayload {
-    pub action: String,
-    pub issue: IssueData,
-    pub repository: RepositoryData,
-    pub sender: UserData,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct IssueData {
-    pub id: u64,
-    pub number: u32,
-    pub title: String,
-    pub body: Option<String>,
-    pub state: String,
-    pub html_url: String,
-    pub user: UserData,
-    pub labels: Vec<LabelData>,
-    pub assignees: Vec<UserData>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct RepositoryData {
-    pub id: u64,
-    pub name: String,
-    pub full_name: String,
-    pub owner: UserData,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UserData {
-    pub id: u64,
-    pub login: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct LabelData {
-    pub name: String,
-    pub color: String,
-}
-
 /// 🎯 Issue automation response structure
 #[derive(Debug, Serialize)]
 pub struct IssueAutomationResponse {
@@ -69,8 +32,34 @@ pub struct IssueAutomationResponse {
 /// 🪝 Main GitHub issue webhook handler
 pub async fn github_issue_webhook(
     State(app_state): State<AppState>,
-    Json(payload): Json<IssueWebhookPayload>,
+    headers: HeaderMap,
+    Json(payload): Json<serde_json::Value>,
 ) -> Response {
+    let payload = match WebhookEvent::parse(WebhookEvent::name(&headers), payload) {
+        Ok(WebhookEvent::Issues(payload)) => payload,
+        // 🤷 Only issue events are automated; the rest go to /api/webhook/github
+        Ok(
+            WebhookEvent::IssueComment(_)
+            | WebhookEvent::PullRequest(_)
+            | WebhookEvent::CheckSuite(_)
+            | WebhookEvent::Push(_)
+            | WebhookEvent::Installation(_)
+            | WebhookEvent::InstallationRepositories(_)
+            | WebhookEvent::Unsupported,
+        ) => {
+            return (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "No issue automation for this event".to_string(),
+                )),
+            ).into_response();
+        }
+        Ok(WebhookEvent::Ping(ping)) => return Pong::new(&ping, &["issues"]).into_response(),
+        Err(e) => {
+            warn!("⚠️ Rejected issue webhook delivery: {:#}", e);
+            return validation_error(vec![format!("{:#}", e)]).into_response();
+        }
+    };
     info!(
         "🎫 Received GitHub issue webhook: {} for issue #{} in {}",
         payload.action,
@@ -84,19 +73,18 @@ pub async fn github_issue_webhook(
             (
                 StatusCode::OK,
                 Json(ApiResponse::success(
-                    "Issue automation completed".to_string(),
+                    i18n::t("issue-automation-completed"),
                     response,
                 )),
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to process issue automation: {:#}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error(
                     "automation_failed".to_string(),
                     "Failed to process issue automation".to_string(),
-                    Some(serde_json::json!({ "error": e.to_string() })),
+                    Some(errors::error_reference("Failed to process issue automation", &e)),
                 )),
             ).into_response()
         }
@@ -106,9 +94,9 @@ pub async fn github_issue_webhook(
 /// 🤖 Process different types of issue events
 async fn process_issue_event(
     app_state: &AppState,
-    payload: &IssueWebhookPayload,
+    payload: &IssuesEvent,
 ) -> anyhow::Result<IssueAutomationResponse> {
-    let github_client = GitHubClient::new(&app_state.config.github.token)?;
+    let github_client = GitHubClient::new(&app_state.config.load().github.token)?;
 
     match payload.action.as_str() {
         "opened" => handle_issue_opened(&github_client, payload).await,
@@ -131,7 +119,7 @@ async fn process_issue_event(
 /// 🆕 Handle new issue creation
 async fn handle_issue_opened(
     github_client: &GitHubClient,
-    payload: &IssueWebhookPayload,
+    payload: &IssuesEvent,
 ) -> anyhow::Result<IssueAutomationResponse> {
     info!("🆕 Processing newly opened issue #{}", payload.issue.number);
 
@@ -147,7 +135,7 @@ async fn handle_issue_opened(
     let labels_to_add = analyze_issue_for_labels(&payload.issue).await;
     if !labels_to_add.is_empty() {
         github_client.add_labels_to_issue(
-            &payload.repository.owner.login,
+            payload.repository.owner_login(),
             &payload.repository.name,
             payload.issue.number,
             &labels_to_add,
@@ -158,7 +146,7 @@ async fn handle_issue_opened(
     // 💬 Add welcome comment with helpful information
     let welcome_comment = create_welcome_comment(&payload.issue).await;
     github_client.add_comment_to_issue(
-        &payload.repository.owner.login,
+        payload.repository.owner_login(),
         &payload.repository.name,
         payload.issue.number,
         &welcome_comment,
@@ -168,7 +156,7 @@ async fn handle_issue_opened(
     // 🎯 Auto-assign if it's a specific type of issue
     if let Some(assignee) = determine_auto_assignee(&payload.issue).await {
         github_client.assign_issue(
-            &payload.repository.owner.login,
+            payload.repository.owner_login(),
             &payload.repository.name,
             payload.issue.number,
             &assignee,
@@ -182,7 +170,7 @@ async fn handle_issue_opened(
 /// ✅ Handle issue closure
 async fn handle_issue_closed(
     github_client: &GitHubClient,
-    payload: &IssueWebhookPayload,
+    payload: &IssuesEvent,
 ) -> anyhow::Result<IssueAutomationResponse> {
     info!("✅ Processing closed issue #{}", payload.issue.number);
 
@@ -198,7 +186,7 @@ async fn handle_issue_closed(
     let thank_you_comment = "🎉 Thank you for reporting this issue! If you have any other feedback or feature requests, feel free to submit them through our Feedbacker service at f.8b.is. \n\nHappy coding! 🚢\n\n*- Aye & Hue*";
 
     github_client.add_comment_to_issue(
-        &payload.repository.owner.login,
+        payload.repository.owner_login(),
         &payload.repository.name,
         payload.issue.number,
         thank_you_comment,
@@ -211,7 +199,7 @@ async fn handle_issue_closed(
 /// 🏷️ Handle issue labeling events
 async fn handle_issue_labeled(
     _github_client: &GitHubClient,
-    payload: &IssueWebhookPayload,
+    payload: &IssuesEvent,
 ) -> anyhow::Result<IssueAutomationResponse> {
     info!("🏷️ Processing labeled issue #{}", payload.issue.number);
 
@@ -235,7 +223,7 @@ async fn handle_issue_labeled(
 /// 👤 Handle issue assignment
 async fn handle_issue_assigned(
     _github_client: &GitHubClient,
-    payload: &IssueWebhookPayload,
+    payload: &IssuesEvent,
 ) -> anyhow::Result<IssueAutomationResponse> {
     info!("👤 Processing assigned issue #{}", payload.issue.number);
 
@@ -249,7 +237,7 @@ async fn handle_issue_assigned(
 }
 
 /// 🔍 Analyze issue content to suggest appropriate labels
-async fn analyze_issue_for_labels(issue: &IssueData) -> Vec<String> {
+async fn analyze_issue_for_labels(issue: &Issue) -> Vec<String> {
     let mut labels = Vec::new();
     let content = format!("{} {}", issue.title, issue.body.as_deref().unwrap_or(""));
     let content_lower = content.to_lowercase();
@@ -296,7 +284,7 @@ async fn analyze_issue_for_labels(issue: &IssueData) -> Vec<String> {
 }
 
 /// 💬 Create a welcoming comment for new issues
-async fn create_welcome_comment(issue: &IssueData) -> String {
+async fn create_welcome_comment(issue: &Issue) -> String {
     let issue_type = if issue.title.to_lowercase().contains("bug") {
         "🐛 **Bug Report**"
     } else if issue.title.to_lowercase().contains("feature") {
@@ -336,7 +324,7 @@ Thanks for helping make Feedbacker better!
 }
 
 /// 🎯 Determine if an issue should be auto-assigned
-async fn determine_auto_assignee(issue: &IssueData) -> Option<String> {
+async fn determine_auto_assignee(issue: &Issue) -> Option<String> {
     let content = format!("{} {}", issue.title, issue.body.as_deref().unwrap_or(""));
     let content_lower = content.to_lowercase();
 
@@ -358,7 +346,7 @@ pub async fn add_issue_comment(
     Path((owner, repo, issue_number)): Path<(String, String, u32)>,
     Json(comment): Json<serde_json::Value>,
 ) -> Response {
-    let github_client = match GitHubClient::new(&app_state.config.github.token) {
+    let github_client = match GitHubClient::new(&app_state.config.load().github.token) {
         Ok(client) => client,
         Err(e) => {
             error!("❌ Failed to create GitHub client: {:#}", e);
@@ -388,13 +376,12 @@ pub async fn add_issue_comment(
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to add comment: {:#}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error(
                     "comment_failed".to_string(),
                     "Failed to add comment".to_string(),
-                    Some(serde_json::json!({ "error": e.to_string() })),
+                    Some(errors::error_reference("Failed to add comment", &e)),
                 )),
             ).into_response()
         }
@@ -407,7 +394,7 @@ pub async fn add_issue_labels(
     Path((owner, repo, issue_number)): Path<(String, String, u32)>,
     Json(labels): Json<Vec<String>>,
 ) -> Response {
-    let github_client = match GitHubClient::new(&app_state.config.github.token) {
+    let github_client = match GitHubClient::new(&app_state.config.load().github.token) {
         Ok(client) => client,
         Err(e) => {
             return (
@@ -432,13 +419,12 @@ pub async fn add_issue_labels(
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to add labels: {:#}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error(
                     "labels_failed".to_string(),
                     "Failed to add labels".to_string(),
-                    Some(serde_json::json!({ "error": e.to_string() })),
+                    Some(errors::error_reference("Failed to add labels", &e)),
                 )),
             ).into_response()
         }
@@ -451,7 +437,7 @@ pub async fn close_issue_with_comment(
     Path((owner, repo, issue_number)): Path<(String, String, u32)>,
     Json(payload): Json<serde_json::Value>,
 ) -> Response {
-    let github_client = match GitHubClient::new(&app_state.config.github.token) {
+    let github_client = match GitHubClient::new(&app_state.config.load().github.token) {
         Ok(client) => client,
         Err(e) => {
             return (
@@ -484,13 +470,12 @@ pub async fn close_issue_with_comment(
             ).into_response()
         }
         Err(e) => {
-            error!("❌ Failed to close issue: {:#}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error(
                     "close_failed".to_string(),
                     "Failed to close issue".to_string(),
-                    Some(serde_json::json!({ "error": e.to_string() })),
+                    Some(errors::error_reference("Failed to close issue", &e)),
                 )),
             ).into_response()
         }
@@ -0,0 +1,361 @@
+// 📡 Live API - Watch Your Feedback Move, No Refresh Needed! 📡
+// GET /api/feedback/:id/ws follows one feedback item; GET /api/ws follows any
+// number of them over a single socket, chosen by the client with
+//   {"action": "subscribe", "feedback_id": "..."} and
+//   {"action": "unsubscribe", "feedback_id": "..."}
+// Both start each subscription with a snapshot (current status and the timeline
+// so far), then push every new event (status_changed included) as it's recorded.
+// Falling behind earns a fresh snapshot. Browsers, which can't set headers on a
+// handshake, may pass their token as ?access_token=. Only feedback the caller
+// may see (see api::feedback::find_visible_feedback) can be followed; anything
+// else is "not found", exactly like feedback that doesn't exist. Events come
+// from crate::live_updates
+// Created with love by Aye & Hue - The tracking page that tracks itself! ✨
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    api::{
+        feedback::{find_visible_feedback, visible_feedback},
+        utils::not_found_error,
+        AppState,
+    },
+    database::models::{Feedback, FeedbackEvent},
+    errors,
+    middleware::auth::AuthenticatedUser,
+};
+use anyhow::Result;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::{IntoResponse, Response},
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// 📏 Most feedback items one socket may follow
+const MAX_SUBSCRIPTIONS: usize = 50;
+
+/// 📤 What the server pushes
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    /// 📸 Where a feedback item stands right now
+    Snapshot {
+        feedback_id: Uuid,
+        status: &'static str,
+        events: &'a [FeedbackEvent],
+    },
+    /// 🆕 Something just happened
+    Event { event: &'a FeedbackEvent },
+    /// 👋 No longer following this item
+    Unsubscribed { feedback_id: Uuid },
+    /// ❌ The last client message couldn't be honoured
+    Error { message: String },
+}
+
+/// 📥 What clients of /api/ws send
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe { feedback_id: Uuid },
+    Unsubscribe { feedback_id: Uuid },
+}
+
+/// 🔌 Follow one feedback item
+pub async fn feedback_socket(
+    ws: WebSocketUpgrade,
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(feedback_id): Path<Uuid>,
+) -> Response {
+    match find_visible_feedback(&app_state, &user, feedback_id).await {
+        Ok(Some(_)) => ws.on_upgrade(move |socket| async move {
+            let mut session = Session::new(socket, &app_state, user, false);
+            if session.subscribe(feedback_id).await.is_ok() {
+                session.run().await;
+            }
+        }),
+        Ok(None) => not_found_error("Feedback").into_response(),
+        Err(e) => errors::error_response(
+            &format!("Failed to open live updates for feedback {}", feedback_id),
+            e,
+        ),
+    }
+}
+
+/// 🔌 Follow whichever feedback items the client subscribes to
+pub async fn socket(
+    ws: WebSocketUpgrade,
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Response {
+    info!("📡 {} opened a live updates socket", user.email);
+    ws.on_upgrade(move |socket| async move {
+        Session::new(socket, &app_state, user, true).run().await;
+    })
+}
+
+/// 🧵 One connected socket and what it follows
+struct Session<'a> {
+    socket: WebSocket,
+    app_state: &'a AppState,
+    /// 👤 Who is watching (every subscription is checked against them)
+    user: AuthenticatedUser,
+    /// 📡 Every new event, from before the first snapshot on
+    updates: broadcast::Receiver<Arc<FeedbackEvent>>,
+    /// 🎛️ Whether the client picks its own subscriptions
+    multiplexed: bool,
+    /// 📋 Followed feedback, with the ids of events already sent in its snapshot
+    subscriptions: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl<'a> Session<'a> {
+    fn new(
+        socket: WebSocket,
+        app_state: &'a AppState,
+        user: AuthenticatedUser,
+        multiplexed: bool,
+    ) -> Self {
+        Self {
+            socket,
+            updates: app_state.live_updates.subscribe(),
+            app_state,
+            user,
+            multiplexed,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    /// 🔁 Push events and take requests until either side hangs up
+    async fn run(mut self) {
+        loop {
+            let delivered = tokio::select! {
+                update = self.updates.recv() => match update {
+                    Ok(event) => self.forward(&event).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        debug!("🐢 Live updates socket missed {} events, resyncing", missed);
+                        self.resync().await
+                    }
+                    Err(RecvError::Closed) => return,
+                },
+                incoming = self.socket.recv() => match incoming {
+                    Some(Ok(Message::Text(text))) => self.handle(&text).await,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    // 🏓 Pings are answered for us
+                    Some(Ok(_)) => Ok(()),
+                },
+            };
+            if delivered.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// 📤 Send one message (an error means the client is gone)
+    async fn send(&mut self, message: &ServerMessage<'_>) -> Result<()> {
+        let text = serde_json::to_string(message)?;
+        self.socket.send(Message::Text(text)).await?;
+        Ok(())
+    }
+
+    /// 🆕 Pass an event on if this socket follows its feedback
+    async fn forward(&mut self, event: &Arc<FeedbackEvent>) -> Result<()> {
+        let Some(snapshot_ids) = self.subscriptions.get_mut(&event.feedback_id) else {
+            return Ok(());
+        };
+        // 📸 Already sent as part of the snapshot
+        if snapshot_ids.remove(&event.id) {
+            return Ok(());
+        }
+        self.send(&ServerMessage::Event { event }).await
+    }
+
+    /// 📥 Act on a client message (only /api/ws takes any)
+    async fn handle(&mut self, text: &str) -> Result<()> {
+        if !self.multiplexed {
+            return self
+                .error("This socket follows a single feedback item; use /api/ws to pick your own")
+                .await;
+        }
+        match serde_json::from_str::<ClientMessage>(text) {
+            Ok(ClientMessage::Subscribe { feedback_id }) => {
+                if !self.subscriptions.contains_key(&feedback_id)
+                    && self.subscriptions.len() >= MAX_SUBSCRIPTIONS
+                {
+                    return self
+                        .error(&format!(
+                            "A socket can follow at most {} feedback items",
+                            MAX_SUBSCRIPTIONS
+                        ))
+                        .await;
+                }
+                self.subscribe(feedback_id).await
+            }
+            Ok(ClientMessage::Unsubscribe { feedback_id }) => {
+                self.subscriptions.remove(&feedback_id);
+                self.send(&ServerMessage::Unsubscribed { feedback_id })
+                    .await
+            }
+            Err(e) => self.error(&format!("Unrecognised message: {}", e)).await,
+        }
+    }
+
+    /// 📸 Start (or restart) following a feedback item with a snapshot
+    /// Access is checked every time, so a resync drops what the user lost
+    async fn subscribe(&mut self, feedback_id: Uuid) -> Result<()> {
+        match snapshot(&self.app_state.db_pool, &self.user, feedback_id).await {
+            Ok(Some((feedback, events))) => {
+                self.subscriptions
+                    .insert(feedback_id, events.iter().map(|event| event.id).collect());
+                self.send(&ServerMessage::Snapshot {
+                    feedback_id,
+                    status: feedback.status.as_str(),
+                    events: &events,
+                })
+                .await
+            }
+            Ok(None) => {
+                self.subscriptions.remove(&feedback_id);
+                self.error(&format!("Feedback {} not found", feedback_id))
+                    .await
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️ Live updates snapshot of feedback {} failed: {:#}",
+                    feedback_id, e
+                );
+                self.error("The feedback couldn't be loaded; try subscribing again")
+                    .await
+            }
+        }
+    }
+
+    /// 🔄 Fresh snapshots of everything followed, after missing events
+    async fn resync(&mut self) -> Result<()> {
+        for feedback_id in self.subscriptions.keys().copied().collect::<Vec<_>>() {
+            self.subscribe(feedback_id).await?;
+        }
+        Ok(())
+    }
+
+    /// ❌ Tell the client its last message didn't work out
+    async fn error(&mut self, message: &str) -> Result<()> {
+        self.send(&ServerMessage::Error {
+            message: message.to_string(),
+        })
+        .await
+    }
+}
+
+/// 📸 A feedback item and its timeline so far (None when it doesn't exist or
+/// `user` may not see it)
+async fn snapshot(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+) -> Result<Option<(Feedback, Vec<FeedbackEvent>)>> {
+    let Some(feedback) = visible_feedback(pool, user, feedback_id).await? else {
+        return Ok(None);
+    };
+    let events = FeedbackEvent::list_for_feedback(pool, feedback_id).await?;
+    Ok(Some((feedback, events)))
+}
+
+// 🧪 Tests - Nobody follows feedback they can't see!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::{User, UserRole};
+    use crate::middleware::auth::{Claims, Permission};
+
+    fn authenticated(user: &User) -> AuthenticatedUser {
+        AuthenticatedUser {
+            id: user.id,
+            email: user.email.clone(),
+            name: user.name.clone(),
+            role: UserRole::User,
+            organization_id: None,
+            permissions: Permission::defaults_for(&UserRole::User),
+            scopes: None,
+            rate_limit_tier: Default::default(),
+            locale: None,
+            request_signing_key: None,
+            claims: Claims {
+                sub: user.id.to_string(),
+                email: user.email.clone(),
+                name: user.name.clone(),
+                role: UserRole::User,
+                exp: 0,
+                iat: 0,
+                iss: "feedbacker".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_refuses_other_users() {
+        // This test only runs if we have a (migrated) test database available
+        let Ok(database_url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&database_url).await.unwrap();
+
+        let mut users = Vec::new();
+        for name in ["Aye", "Hue"] {
+            let user = User::create(
+                &pool,
+                format!("{}@8b.is", Uuid::new_v4()),
+                name.to_string(),
+                "hash".to_string(),
+                UserRole::User,
+            )
+            .await
+            .unwrap();
+            users.push(user);
+        }
+        let feedback = Feedback::create(
+            &pool,
+            Some(users[0].id),
+            format!("aye-is/live-{}", Uuid::new_v4()),
+            None,
+            "Show progress while the PR is being made".to_string(),
+        )
+        .await
+        .unwrap();
+
+        let submitter = authenticated(&users[0]);
+        let (seen, _) = snapshot(&pool, &submitter, feedback.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(seen.id, feedback.id);
+
+        // 🙈 Someone else's feedback looks exactly like missing feedback
+        let stranger = authenticated(&users[1]);
+        assert!(snapshot(&pool, &stranger, feedback.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(snapshot(&pool, &stranger, Uuid::new_v4())
+            .await
+            .unwrap()
+            .is_none());
+
+        sqlx::query("DELETE FROM users WHERE id = ANY($1)")
+            .bind(users.iter().map(|user| user.id).collect::<Vec<_>>())
+            .execute(&pool)
+            .await
+            .unwrap();
+        println!("✅ Live updates visibility test passed!");
+    }
+}
@@ -0,0 +1,27 @@
+// 🤖 MCP API - The Model Context Protocol over HTTP! 🤖
+// POST /api/mcp takes one JSON-RPC message and answers it as JSON (the
+// streamable HTTP transport, without server-initiated streams). Callers sign in
+// like any other API client; the tools themselves live in crate::mcp
+// Created with love by Aye & Hue - Agents welcome! ✨
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use serde_json::Value;
+
+use crate::{api::AppState, mcp::McpServer, middleware::auth::AuthenticatedUser};
+
+/// 📨 Answer an MCP message (202 with no body for notifications)
+pub async fn handle_message(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(message): Json<Value>,
+) -> Response {
+    match McpServer::new(app_state, user).handle(message).await {
+        Some(reply) => (StatusCode::OK, Json(reply)).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
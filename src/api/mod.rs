@@ -4,22 +4,34 @@
 // Created with love by Aye & Hue - Making APIs beautiful and functional! ✨
 // Trisha from Accounting loves well-organized API endpoints! 📊
 
+use arc_swap::ArcSwap;
 use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::config::Config;
+use crate::{config::Config, errors::ErrorKind, i18n};
 
 // 📦 Re-export all our API modules
+pub mod admin; // 👑 Admin-only debugging endpoints
+pub mod artifacts; // 🗄️ Pipeline artifacts and their signed downloads
 pub mod auth; // 🔐 Authentication endpoints
+pub mod exports; // 📦 Feedback exports and their signed downloads
+pub mod fallback; // 🧭 JSON 404s for unknown API paths, and 405s for wrong methods
 pub mod feedback; // 📝 Feedback submission and management
 pub mod health; // 💚 Health check endpoints
 pub mod issue_hooks; // 🎯 GitHub issue automation
+pub mod live; // 📡 WebSocket feed of feedback status and pipeline events
+pub mod mcp; // 🤖 Model Context Protocol over HTTP, for AI agents
+pub mod organizations; // 🏢 Organizations, teams and memberships
 pub mod projects; // 🏠 Project management endpoints
+pub mod rate_limit; // 🚦 The caller's rate limits
 pub mod smart_tree; // 🌳 Smart Tree integration
+pub mod sso; // 🔐 Single sign-on through organization identity providers
 pub mod status; // 📊 Status checking endpoints
+pub mod users; // 👤 The signed-in user's profile and password
 pub mod web; // 🎨 Web UI endpoints
 pub mod webhooks; // 🪝 GitHub webhook handlers
 
@@ -27,100 +39,123 @@ pub mod webhooks; // 🪝 GitHub webhook handlers
 /// This contains everything our API endpoints need to function!
 #[derive(Debug, Clone)]
 pub struct AppState {
-    /// ⚙️ Application configuration
-    pub config: Arc<Config>,
+    /// ⚙️ Application configuration, as loaded at startup (rotated secrets are swapped in)
+    pub config: Arc<ArcSwap<Config>>,
+    /// 🔄 Settings that change on a config reload (rate limits, flags, log level, models)
+    pub live: Arc<crate::reload::LiveConfig>,
     /// 🗄️ Database connection pool
     pub db_pool: PgPool,
-    // 🤖 LLM client manager (will be added when we create LLM module)
-    // pub llm_manager: Arc<crate::llm::LlmManager>,
+    /// 🤖 LLM client manager
+    pub llm_manager: Arc<crate::llm::LlmManager>,
+    /// 📮 Job queue (enqueue here; workers claim from it)
+    pub jobs: Arc<dyn crate::jobs::queue::JobQueue>,
+    /// 👷 Background job worker limits (idle unless background jobs are enabled)
+    pub workers: Arc<crate::jobs::worker::WorkerPool>,
+    /// 🚩 Runtime feature flags (cached evaluator over the feature_flags table)
+    pub flags: Arc<crate::feature_flags::FeatureFlags>,
+    /// 🚧 Maintenance mode (from configuration or the admin switch)
+    pub maintenance: Arc<crate::maintenance::Maintenance>,
+    /// 🎭 Role grants (cached evaluator over the roles tables)
+    pub roles: Arc<crate::roles::Roles>,
+    /// 🔏 Token signing keys (cached over the signing_keys table)
+    pub token_keys: Arc<crate::auth::keys::TokenKeys>,
+    /// 🚦 Request counts per client, for rate limiting
+    pub rate_limits: Arc<crate::middleware::rate_limiting::RateLimitManager>,
+    /// 📡 New feedback events, for WebSocket subscribers
+    pub live_updates: Arc<crate::live_updates::LiveUpdates>,
+    /// 🗃️ Cached responses of the hot read endpoints
+    pub cache: Arc<crate::cache::ResponseCache>,
+    /// 🐙 GitHub's webhook delivery ranges, for the webhook IP allowlist
+    pub github_hook_ranges: Arc<crate::middleware::ip_allowlist::GitHubHookRanges>,
     // 🐙 GitHub client (will be added when we create GitHub module)
     // pub github_client: Arc<crate::github::GitHubClient>,
 }
 
 impl AppState {
     /// ➕ Create a new application state instance
-    pub fn new(config: Config, db_pool: PgPool) -> Self {
+    /// `config_path` is the --config file, read again on every reload
+    pub fn new(
+        config: Config,
+        config_path: Option<&Path>,
+        db_pool: PgPool,
+        jobs: Arc<dyn crate::jobs::queue::JobQueue>,
+    ) -> Self {
+        let live = Arc::new(crate::reload::LiveConfig::new(
+            &config,
+            config_path.map(Path::to_path_buf),
+        ));
+        let flags = Arc::new(crate::feature_flags::FeatureFlags::new(db_pool.clone()));
+        let maintenance = Arc::new(crate::maintenance::Maintenance::new(
+            live.clone(),
+            flags.clone(),
+        ));
         Self {
-            config: Arc::new(config),
+            live,
+            llm_manager: Arc::new(
+                crate::llm::LlmManager::new(&config.llm).with_exchange_log(
+                    crate::llm::ExchangeLog::new(
+                        db_pool.clone(),
+                        crate::utils::redaction::Redactor::from_config(&config),
+                        config.features.enable_dev_features,
+                    ),
+                ),
+            ),
+            workers: Arc::new(
+                crate::jobs::worker::WorkerPool::new(db_pool.clone(), jobs.clone(), &config.jobs)
+                    .with_maintenance(maintenance.clone()),
+            ),
+            flags,
+            maintenance,
+            roles: Arc::new(crate::roles::Roles::new(db_pool.clone())),
+            token_keys: Arc::new(crate::auth::keys::TokenKeys::new(db_pool.clone())),
+            rate_limits: Arc::new({
+                let manager = crate::middleware::rate_limiting::RateLimitManager::new();
+                if config.rate_limiting.sync_seconds > 0 {
+                    manager.with_database(db_pool.clone())
+                } else {
+                    manager
+                }
+            }),
+            live_updates: Arc::new(crate::live_updates::LiveUpdates::new()),
+            cache: Arc::new(crate::cache::ResponseCache::new(&config.cache)),
+            github_hook_ranges: Arc::new(crate::middleware::ip_allowlist::GitHubHookRanges::new()),
+            jobs,
+            config: Arc::new(ArcSwap::from_pointee(config)),
             db_pool,
-            // These will be uncommented when we create the respective modules
-            // llm_manager: Arc::new(crate::llm::LlmManager::new(&config.llm)),
+            // This will be uncommented when we create the respective module
             // github_client: Arc::new(crate::github::GitHubClient::new(&config.github)),
         }
     }
 }
 
-/// 📝 Standard API response structure
-/// Provides consistent response format across all endpoints
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    /// ✅ Whether the operation was successful
-    pub success: bool,
-    /// 📝 Human-readable message
-    pub message: String,
-    /// 📊 Response data (optional)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub data: Option<T>,
-    /// ❌ Error details (only present if success = false)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<ApiError>,
-    /// ⏰ Response timestamp
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-}
+/// 📝 The response envelopes, shared with API clients
+pub use feedbacker_types::{ApiError, ApiResponse, PaginatedResponse, PaginationMeta};
 
-/// ❌ API error structure
-/// Provides structured error information for debugging and user feedback
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiError {
-    /// 🎯 Error code for programmatic handling
-    pub code: String,
-    /// 📝 Human-readable error message
-    pub message: String,
-    /// 🔍 Additional error details (optional)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+/// ❌ Error envelopes, summed up in the request's language
+/// (ApiResponse lives in feedbacker-types, which knows nothing of locales)
+pub trait ErrorResponse {
+    /// ❌ Create an error response
+    fn error(
+        code: String,
+        message: String,
+        details: Option<serde_json::Value>,
+    ) -> ApiResponse<()>;
 }
 
-impl<T> ApiResponse<T> {
-    /// ✅ Create a successful response
-    pub fn success(message: String, data: T) -> Self {
-        Self {
-            success: true,
-            message,
-            data: Some(data),
-            error: None,
-            timestamp: chrono::Utc::now(),
-        }
-    }
-
-    /// ✅ Create a successful response without data
-    pub fn success_no_data(message: String) -> ApiResponse<()> {
-        ApiResponse {
-            success: true,
-            message,
-            data: None,
-            error: None,
-            timestamp: chrono::Utc::now(),
-        }
-    }
-
-    /// ❌ Create an error response
-    pub fn error(
+impl<T> ErrorResponse for ApiResponse<T> {
+    fn error(
         code: String,
         message: String,
         details: Option<serde_json::Value>,
     ) -> ApiResponse<()> {
-        ApiResponse {
-            success: false,
-            message: "Operation failed".to_string(),
-            data: None,
-            error: Some(ApiError {
+        ApiResponse::<()>::failure(
+            i18n::t("operation-failed"),
+            ApiError {
                 code,
                 message,
                 details,
-            }),
-            timestamp: chrono::Utc::now(),
-        }
+            },
+        )
     }
 }
 
@@ -133,7 +168,8 @@ pub struct PaginationParams {
     /// 📏 Items per page (max 100)
     #[serde(default = "default_limit")]
     pub limit: u32,
-    /// 🔍 Sort field
+    /// 🔍 Sort fields, comma-separated (`status,created_at`), each optionally
+    /// with its own direction (`status:asc`); see `order_by`
     pub sort_by: Option<String>,
     /// ⬆️⬇️ Sort order (asc/desc)
     #[serde(default = "default_sort_order")]
@@ -141,63 +177,19 @@ pub struct PaginationParams {
 }
 
 /// ⬆️⬇️ Sort order enumeration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Asc,
     Desc,
 }
 
-/// 📊 Paginated response structure
-#[derive(Debug, Serialize)]
-pub struct PaginatedResponse<T> {
-    /// 📋 The actual data items
-    pub items: Vec<T>,
-    /// 📊 Pagination metadata
-    pub pagination: PaginationMeta,
-}
-
-/// 📊 Pagination metadata
-#[derive(Debug, Serialize)]
-pub struct PaginationMeta {
-    /// 📄 Current page number
-    pub page: u32,
-    /// 📏 Items per page
-    pub limit: u32,
-    /// 📈 Total number of items
-    pub total: u64,
-    /// 📑 Total number of pages
-    pub total_pages: u32,
-    /// ⬅️ Has previous page
-    pub has_prev: bool,
-    /// ➡️ Has next page
-    pub has_next: bool,
-}
-
-impl PaginationMeta {
-    /// ➕ Create pagination metadata
-    pub fn new(page: u32, limit: u32, total: u64) -> Self {
-        let total_pages = ((total as f64) / (limit as f64)).ceil() as u32;
-        let has_prev = page > 1;
-        let has_next = page < total_pages;
-
-        Self {
-            page,
-            limit,
-            total,
-            total_pages,
-            has_prev,
-            has_next,
-        }
-    }
-}
-
-impl<T> PaginatedResponse<T> {
-    /// ➕ Create a paginated response
-    pub fn new(items: Vec<T>, page: u32, limit: u32, total: u64) -> Self {
-        Self {
-            items,
-            pagination: PaginationMeta::new(page, limit, total),
+impl SortOrder {
+    /// 🗄️ The SQL keyword
+    pub fn sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
         }
     }
 }
@@ -236,6 +228,144 @@ impl PaginationParams {
     pub fn offset(&self) -> u32 {
         (self.page - 1) * self.limit
     }
+
+    /// 🔀 ORDER BY terms for `sort_by` (`default` when it's not given)
+    /// Only the endpoint's `sortable` columns get through, so the result is safe
+    /// to put in SQL; any other field is a validation error. Fields without
+    /// their own direction are sorted in `sort_order`
+    pub fn order_by(
+        &self,
+        sortable: &[&'static str],
+        default: &'static str,
+    ) -> Result<String, Vec<String>> {
+        let fields = match self.sort_by.as_deref().map(str::trim) {
+            Some(fields) if !fields.is_empty() => fields,
+            _ => return Ok(format!("{} {}", default, self.sort_order.sql())),
+        };
+
+        let mut errors = Vec::new();
+        let mut columns: Vec<&str> = Vec::new();
+        let mut terms = Vec::new();
+        for field in fields.split(',').map(str::trim) {
+            let (name, order) = match field.split_once(':') {
+                Some((name, "asc")) => (name.trim(), SortOrder::Asc),
+                Some((name, "desc")) => (name.trim(), SortOrder::Desc),
+                Some(_) => {
+                    errors.push(format!(
+                        "Invalid sort direction in '{}' (use asc or desc)",
+                        field
+                    ));
+                    continue;
+                }
+                None => (field, self.sort_order),
+            };
+            match sortable.iter().find(|column| **column == name) {
+                Some(column) if columns.contains(column) => {
+                    errors.push(format!("Cannot sort by '{}' twice", name));
+                }
+                Some(column) => {
+                    columns.push(column);
+                    terms.push(format!("{} {}", column, order.sql()));
+                }
+                None => errors.push(format!(
+                    "Cannot sort by '{}' (sortable fields: {})",
+                    name,
+                    sortable.join(", ")
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(terms.join(", "))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// 🪶 Sparse fieldsets for list endpoints: `?fields=id,status,updated_at`
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsParams {
+    /// 🪶 Comma-separated fields to return for each item (every field when absent)
+    pub fields: Option<String>,
+}
+
+impl FieldsParams {
+    /// ✅ The requested fields, checked against the ones items have
+    /// `id` is always kept, so rows can still be told apart
+    pub fn selection(&self, available: &[&'static str]) -> Result<FieldSelection, Vec<String>> {
+        let Some(fields) = self.fields.as_deref().filter(|f| !f.trim().is_empty()) else {
+            return Ok(FieldSelection::default());
+        };
+
+        let mut errors = Vec::new();
+        let mut selected: Vec<&'static str> = available
+            .iter()
+            .copied()
+            .filter(|field| *field == "id")
+            .collect();
+        for name in fields.split(',').map(str::trim) {
+            match available.iter().find(|field| **field == name) {
+                Some(field) if !selected.contains(field) => selected.push(field),
+                Some(_) => {}
+                None => errors.push(format!(
+                    "Unknown field '{}' (available fields: {})",
+                    name,
+                    available.join(", ")
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(FieldSelection(Some(selected.into())))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// 🪶 Which fields of each listed item to serialize (all of them by default)
+#[derive(Debug, Clone, Default)]
+pub struct FieldSelection(Option<Arc<[&'static str]>>);
+
+impl FieldSelection {
+    /// 🪶 An item that serializes as just the selected fields
+    pub fn apply<T>(&self, item: T) -> Sparse<T> {
+        Sparse {
+            item,
+            fields: self.clone(),
+        }
+    }
+}
+
+/// 🪶 An item projected onto a field selection when it's serialized
+#[derive(Debug)]
+pub struct Sparse<T> {
+    item: T,
+    fields: FieldSelection,
+}
+
+impl<T: Serialize> Serialize for Sparse<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeMap};
+
+        let Some(fields) = &self.fields.0 else {
+            return self.item.serialize(serializer);
+        };
+        match serde_json::to_value(&self.item).map_err(S::Error::custom)? {
+            serde_json::Value::Object(object) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, value) in object
+                    .iter()
+                    .filter(|(key, _)| fields.contains(&key.as_str()))
+                {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
 }
 
 /// 📝 Common request validation trait
@@ -253,24 +383,16 @@ pub mod utils {
     };
 
     /// 🎯 Convert an anyhow error to an API error response
+    /// (classified by crate::errors; the details stay in the log)
     pub fn handle_error(error: anyhow::Error) -> impl IntoResponse {
-        let error_msg = format!("{:#}", error);
-        tracing::error!("API error: {}", error_msg);
-
-        let api_response = ApiResponse::<()>::error(
-            "internal_error".to_string(),
-            "An internal error occurred".to_string(),
-            Some(serde_json::json!({ "details": error_msg })),
-        );
-
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(api_response))
+        crate::errors::error_response("API request failed", error)
     }
 
     /// ✅ Create a validation error response
     pub fn validation_error(errors: Vec<String>) -> impl IntoResponse {
         let api_response = ApiResponse::<()>::error(
             "validation_error".to_string(),
-            "Request validation failed".to_string(),
+            ErrorKind::Validation.title(),
             Some(serde_json::json!({ "errors": errors })),
         );
 
@@ -281,7 +403,7 @@ pub mod utils {
     pub fn not_found_error(resource: &str) -> impl IntoResponse {
         let api_response = ApiResponse::<()>::error(
             "not_found".to_string(),
-            format!("{} not found", resource),
+            i18n::t_with("resource-not-found", [("resource", resource.into())]),
             None,
         );
 
@@ -292,7 +414,7 @@ pub mod utils {
     pub fn unauthorized_error() -> impl IntoResponse {
         let api_response = ApiResponse::<()>::error(
             "unauthorized".to_string(),
-            "Authentication required".to_string(),
+            ErrorKind::Unauthorized.title(),
             None,
         );
 
@@ -302,7 +424,7 @@ pub mod utils {
     /// 🛡️ Create a forbidden error response
     pub fn forbidden_error() -> impl IntoResponse {
         let api_response =
-            ApiResponse::<()>::error("forbidden".to_string(), "Access denied".to_string(), None);
+            ApiResponse::<()>::error("forbidden".to_string(), ErrorKind::Forbidden.title(), None);
 
         (StatusCode::FORBIDDEN, Json(api_response))
     }
@@ -311,7 +433,7 @@ pub mod utils {
     pub fn rate_limit_error() -> impl IntoResponse {
         let api_response = ApiResponse::<()>::error(
             "rate_limit_exceeded".to_string(),
-            "Rate limit exceeded. Please try again later.".to_string(),
+            i18n::t("rate-limit-try-later"),
             None,
         );
 
@@ -348,6 +470,7 @@ mod tests {
         );
 
         assert!(!response.success);
+        assert_eq!(response.message, "Operation failed");
         assert!(response.data.is_none());
         assert!(response.error.is_some());
 
@@ -385,6 +508,72 @@ mod tests {
         println!("✅ Pagination offset calculation test passed!");
     }
 
+    #[test]
+    fn test_pagination_order_by() {
+        const SORTABLE: &[&str] = &["created_at", "status"];
+        let params = |sort_by: Option<&str>, sort_order| PaginationParams {
+            page: 1,
+            limit: 20,
+            sort_by: sort_by.map(str::to_string),
+            sort_order,
+        };
+
+        assert_eq!(
+            params(None, SortOrder::Desc).order_by(SORTABLE, "created_at"),
+            Ok("created_at DESC".to_string())
+        );
+        assert_eq!(
+            params(Some("status, created_at"), SortOrder::Asc).order_by(SORTABLE, "created_at"),
+            Ok("status ASC, created_at ASC".to_string())
+        );
+        assert_eq!(
+            params(Some("status:asc,created_at"), SortOrder::Desc)
+                .order_by(SORTABLE, "created_at"),
+            Ok("status ASC, created_at DESC".to_string())
+        );
+
+        // 🛡️ Anything off the whitelist never reaches the SQL
+        let rejected = params(
+            Some("status,content;DROP TABLE feedback,created_at:up,status"),
+            SortOrder::Desc,
+        )
+        .order_by(SORTABLE, "created_at")
+        .unwrap_err();
+        assert_eq!(rejected.len(), 3);
+        assert!(rejected[0].contains("sortable fields: created_at, status"));
+        println!("✅ Pagination sort whitelist test passed!");
+    }
+
+    #[test]
+    fn test_sparse_fieldsets() {
+        const FIELDS: &[&str] = &["id", "status", "content_preview"];
+        let item = serde_json::json!({
+            "id": "0b9e",
+            "status": "pending",
+            "content_preview": "Add a dark mode",
+        });
+        let select = |fields: Option<&str>| {
+            FieldsParams {
+                fields: fields.map(str::to_string),
+            }
+            .selection(FIELDS)
+        };
+
+        let all = select(None).unwrap();
+        assert_eq!(serde_json::to_value(all.apply(&item)).unwrap(), item);
+
+        let status = select(Some("status, status")).unwrap();
+        assert_eq!(
+            serde_json::to_value(status.apply(&item)).unwrap(),
+            serde_json::json!({ "id": "0b9e", "status": "pending" })
+        );
+
+        let errors = select(Some("status,metadata")).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("'metadata'"));
+        println!("✅ Sparse fieldset test passed!");
+    }
+
     #[test]
     fn test_pagination_meta() {
         let meta = PaginationMeta::new(2, 10, 45);
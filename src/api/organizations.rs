@@ -0,0 +1,1009 @@
+// 🏢 Organizations API - Teams Sharing Projects! 🏢
+// Any signed-in user can create an organization and becomes its owner. Admins
+// of the organization manage members, teams, settings, which projects belong
+// to it, and its service-account API keys; quotas are set by system admins
+// (see PUT /api/admin/orgs/:id/quotas). Who may use a shared project is
+// decided in crate::organizations
+// Created with love by Aye & Hue - Better together! ✨
+
+use crate::{
+    api::{
+        utils::{not_found_error, validation_error},
+        ApiResponse, AppState, ErrorResponse,
+    },
+    auth,
+    cache::CacheNamespace,
+    database::models::{
+        OrgRole, Organization, OrganizationMember, Project, SsoProvider, SsoProviderSettings, Team,
+        User,
+    },
+    i18n,
+    middleware::{auth::AuthenticatedUser, rate_limiting::RateLimitTier},
+    models::ScmSettings,
+    organizations::{self, project_quota_exceeded, RepositoryPolicy},
+    scm, security_alerts, sso,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// 📏 Largest settings object an organization may store (serialized bytes)
+const MAX_SETTINGS_BYTES: usize = 16 * 1024;
+
+/// ⏳ Longest an organization API key may stay valid
+const MAX_API_KEY_DAYS: u64 = 3650;
+
+/// ➕ New organization
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    pub slug: String,
+}
+
+/// ⚙️ New organization settings
+#[derive(Debug, Deserialize)]
+pub struct OrganizationSettingsRequest {
+    /// 📦 JSON object replacing the current settings
+    pub settings: serde_json::Value,
+}
+
+/// 👥 Add a member or change their role
+#[derive(Debug, Deserialize)]
+pub struct SetMemberRequest {
+    pub email: String,
+    pub role: OrgRole,
+}
+
+/// 🧑‍🤝‍🧑 New team
+#[derive(Debug, Deserialize)]
+pub struct CreateTeamRequest {
+    pub name: String,
+}
+
+/// 🏠 Put a project in the organization
+#[derive(Debug, Deserialize)]
+pub struct AssignProjectRequest {
+    /// 🧑‍🤝‍🧑 Limit the project to this team (whole organization when None)
+    pub team_id: Option<Uuid>,
+}
+
+/// 🔑 Issue an API key for one of the organization's service accounts
+#[derive(Debug, Deserialize)]
+pub struct IssueApiKeyRequest {
+    /// 📧 Service account email (created on first use)
+    pub email: String,
+    /// 👤 Display name for a new service account
+    pub name: Option<String>,
+    /// ⏳ Days the key stays valid (default 365)
+    pub days: Option<u64>,
+    /// 🔑 Scopes to limit the key to (omitted = unchanged, empty = not limited)
+    pub scopes: Option<Vec<String>>,
+    /// 🎚️ Rate limit tier (omitted = unchanged; only system admins go above standard)
+    pub rate_limit_tier: Option<RateLimitTier>,
+    /// 🔏 Require signed requests with a new signing key (omitted = unchanged, false = off)
+    pub signed_requests: Option<bool>,
+}
+
+/// 🏢 An organization with the caller's role in it
+#[derive(Debug, Serialize)]
+pub struct OrganizationSummary {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub role: Option<OrgRole>,
+}
+
+/// 🧑‍🤝‍🧑 A team and who is on it
+#[derive(Debug, Serialize)]
+pub struct TeamDetails {
+    #[serde(flatten)]
+    pub team: Team,
+    pub member_ids: Vec<Uuid>,
+}
+
+/// 📊 How much of its quotas an organization uses
+#[derive(Debug, Serialize)]
+pub struct OrganizationUsage {
+    pub projects: i64,
+    pub monthly_feedback: i64,
+}
+
+/// 🏢 Everything about an organization
+#[derive(Debug, Serialize)]
+pub struct OrganizationDetails {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub role: OrgRole,
+    pub members: Vec<OrganizationMember>,
+    pub teams: Vec<TeamDetails>,
+    pub usage: OrganizationUsage,
+}
+
+/// 🔑 A freshly issued API key (shown only once)
+#[derive(Debug, Serialize)]
+pub struct IssuedApiKey {
+    pub user_id: Uuid,
+    pub email: String,
+    pub api_key: String,
+    pub valid_days: u64,
+    /// 🔑 Scopes the key is limited to (None = not limited)
+    pub scopes: Option<Vec<String>>,
+    pub rate_limit_tier: RateLimitTier,
+    /// 🔏 Key to sign every request with, when signed requests were just turned on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    /// 🔏 Whether the account must sign its requests
+    pub signed_requests: bool,
+}
+
+/// ➕ Create an organization; the caller becomes its owner
+pub async fn create_organization(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Response {
+    let mut errors = Vec::new();
+    if request.name.trim().is_empty() || request.name.len() > 255 {
+        errors.push("name must be 1-255 characters".to_string());
+    }
+    if let Err(message) = organizations::validate_slug(&request.slug) {
+        errors.push(message);
+    }
+    if user.organization_id.is_some() {
+        errors.push("Service accounts can't create organizations".to_string());
+    }
+    if !errors.is_empty() {
+        return validation_error(errors).into_response();
+    }
+
+    let result = async {
+        if Organization::find_by_slug(&app_state.db_pool, &request.slug)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        Organization::create(
+            &app_state.db_pool,
+            request.name.trim(),
+            &request.slug,
+            user.id,
+        )
+        .await
+        .map(Some)
+    };
+
+    match result.await {
+        Ok(Some(organization)) => {
+            info!(
+                "🏢 {} created organization {}",
+                user.email, organization.slug
+            );
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(
+                    i18n::t("organization-created"),
+                    organization,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => conflict("An organization with this slug already exists"),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 📋 Organizations the caller belongs to (all of them for system admins)
+pub async fn list_organizations(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Response {
+    let result = async {
+        let organizations = match user.organization_id {
+            Some(id) => Organization::find_by_id(&app_state.db_pool, id)
+                .await?
+                .into_iter()
+                .collect(),
+            None if user.is_admin() => Organization::list(&app_state.db_pool, None).await?,
+            None => Organization::list(&app_state.db_pool, Some(user.id)).await?,
+        };
+        let mut summaries = Vec::with_capacity(organizations.len());
+        for organization in organizations {
+            let role = organizations::organization_role(&app_state.db_pool, &user, organization.id)
+                .await?;
+            summaries.push(OrganizationSummary { organization, role });
+        }
+        Ok::<_, anyhow::Error>(summaries)
+    };
+
+    match result.await {
+        Ok(summaries) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("organizations-retrieved"),
+                summaries,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔍 An organization with its members, teams and quota usage
+pub async fn get_organization(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let (organization, role) = match require_role(&app_state, &user, id, OrgRole::Viewer).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let members = OrganizationMember::list(&app_state.db_pool, id).await?;
+        let mut teams = Vec::new();
+        for team in Team::list(&app_state.db_pool, id).await? {
+            let member_ids = team.member_ids(&app_state.db_pool).await?;
+            teams.push(TeamDetails { team, member_ids });
+        }
+        let usage = OrganizationUsage {
+            projects: organization.project_count(&app_state.db_pool).await?,
+            monthly_feedback: organization
+                .monthly_feedback_count(&app_state.db_pool)
+                .await?,
+        };
+        Ok::<_, anyhow::Error>(OrganizationDetails {
+            organization,
+            role,
+            members,
+            teams,
+            usage,
+        })
+    };
+
+    match result.await {
+        Ok(details) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("organization-retrieved"),
+                details,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ⚙️ Replace the organization's settings
+pub async fn update_organization_settings(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OrganizationSettingsRequest>,
+) -> Response {
+    if !request.settings.is_object() {
+        return validation_error(vec!["settings must be a JSON object".to_string()])
+            .into_response();
+    }
+    if request.settings.to_string().len() > MAX_SETTINGS_BYTES {
+        return validation_error(vec![format!(
+            "settings must be at most {} bytes",
+            MAX_SETTINGS_BYTES
+        )])
+        .into_response();
+    }
+    // 🏢 Projects inherit `scm.endpoint`, so it must name a configured GitHub
+    let mut problems = match ScmSettings::from_organization_settings(&request.settings) {
+        Ok(scm_settings) => scm::validate(&app_state.config.load().github.endpoints, &scm_settings),
+        Err(e) => vec![format!("{:#}", e)],
+    };
+    // 🚧 Members' feedback is held to `repositories`, so its entries must be well-formed
+    match RepositoryPolicy::from_organization_settings(&request.settings) {
+        Ok(policy) => policy.validate_into(&mut problems),
+        Err(e) => problems.push(format!("{:#}", e)),
+    }
+    if !problems.is_empty() {
+        return validation_error(problems).into_response();
+    }
+    let (mut organization, _) = match require_role(&app_state, &user, id, OrgRole::Admin).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match organization
+        .update_settings(&app_state.db_pool, &request.settings)
+        .await
+    {
+        Ok(()) => {
+            info!(
+                "⚙️ {} updated settings of {}",
+                user.email, organization.slug
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("organization-settings-updated"),
+                    organization,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 👥 Add a member, or change an existing member's role
+/// Only owners can make someone an owner, and the last owner can't be demoted
+pub async fn set_member(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<SetMemberRequest>,
+) -> Response {
+    let (organization, caller_role) =
+        match require_role(&app_state, &user, id, OrgRole::Admin).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+
+    let result = async {
+        let Some(member) = User::find_by_email(&app_state.db_pool, &request.email).await? else {
+            return Ok(Err(not_found_error("User").into_response()));
+        };
+        let current = OrganizationMember::role_of(&app_state.db_pool, id, member.id).await?;
+        if caller_role < OrgRole::Owner
+            && (request.role == OrgRole::Owner || current == Some(OrgRole::Owner))
+        {
+            return Ok(Err(forbidden("Only owners can grant or change ownership")));
+        }
+        if current == Some(OrgRole::Owner)
+            && request.role != OrgRole::Owner
+            && OrganizationMember::owner_count(&app_state.db_pool, id).await? <= 1
+        {
+            return Ok(Err(conflict("An organization needs at least one owner")));
+        }
+        if member.organization_id.is_some_and(|owner| owner != id) {
+            return Ok(Err(conflict(
+                "This service account belongs to another organization",
+            )));
+        }
+
+        OrganizationMember::set(&app_state.db_pool, id, member.id, request.role).await?;
+        Ok::<_, anyhow::Error>(Ok(member))
+    };
+
+    match result.await {
+        Ok(Ok(member)) => {
+            app_state
+                .cache
+                .invalidate_all(CacheNamespace::Projects)
+                .await;
+            info!(
+                "👥 {} set {} as {} of {}",
+                user.email,
+                member.email,
+                request.role.as_str(),
+                organization.slug
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(format!(
+                    "{} is now {} of {}",
+                    member.email,
+                    request.role.as_str(),
+                    organization.slug
+                ))),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚪 Remove a member (admins remove others; anyone can leave)
+pub async fn remove_member(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let needed = if member_id == user.id {
+        OrgRole::Viewer
+    } else {
+        OrgRole::Admin
+    };
+    let (organization, caller_role) = match require_role(&app_state, &user, id, needed).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let current = OrganizationMember::role_of(&app_state.db_pool, id, member_id).await?;
+        if current == Some(OrgRole::Owner) {
+            if caller_role < OrgRole::Owner {
+                return Ok(Err(forbidden("Only owners can remove an owner")));
+            }
+            if OrganizationMember::owner_count(&app_state.db_pool, id).await? <= 1 {
+                return Ok(Err(conflict("An organization needs at least one owner")));
+            }
+        }
+        OrganizationMember::remove(&app_state.db_pool, id, member_id)
+            .await
+            .map(Ok)
+    };
+
+    match result.await {
+        Ok(Ok(true)) => {
+            app_state
+                .cache
+                .invalidate_all(CacheNamespace::Projects)
+                .await;
+            info!(
+                "🚪 {} removed {} from {}",
+                user.email, member_id, organization.slug
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Member removed".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(Ok(false)) => not_found_error("Member").into_response(),
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🧑‍🤝‍🧑 Create a team
+pub async fn create_team(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateTeamRequest>,
+) -> Response {
+    let name = request.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return validation_error(vec!["name must be 1-100 characters".to_string()]).into_response();
+    }
+    if let Err(response) = require_role(&app_state, &user, id, OrgRole::Admin).await {
+        return response;
+    }
+
+    let result = async {
+        if Team::list(&app_state.db_pool, id)
+            .await?
+            .iter()
+            .any(|team| team.name == name)
+        {
+            return Ok(None);
+        }
+        Team::create(&app_state.db_pool, id, name).await.map(Some)
+    };
+
+    match result.await {
+        Ok(Some(team)) => {
+            info!("🧑‍🤝‍🧑 {} created team {}", user.email, team.name);
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(i18n::t("team-created"), team)),
+            )
+                .into_response()
+        }
+        Ok(None) => conflict("A team with this name already exists"),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🗑️ Delete a team; its projects become visible to the whole organization
+pub async fn delete_team(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, team_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    let team = match require_team(&app_state, &user, id, team_id).await {
+        Ok(team) => team,
+        Err(response) => return response,
+    };
+
+    match team.delete(&app_state.db_pool).await {
+        Ok(()) => {
+            app_state
+                .cache
+                .invalidate_all(CacheNamespace::Projects)
+                .await;
+            info!("🗑️ {} deleted team {}", user.email, team.name);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Team deleted".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ➕ Put a member of the organization on a team
+pub async fn add_team_member(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, team_id, member_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Response {
+    let team = match require_team(&app_state, &user, id, team_id).await {
+        Ok(team) => team,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        if OrganizationMember::role_of(&app_state.db_pool, id, member_id)
+            .await?
+            .is_none()
+        {
+            return Ok(false);
+        }
+        team.add_member(&app_state.db_pool, member_id).await?;
+        Ok::<_, anyhow::Error>(true)
+    };
+
+    match result.await {
+        Ok(true) => {
+            app_state
+                .cache
+                .invalidate_all(CacheNamespace::Projects)
+                .await;
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Team member added".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => validation_error(vec![
+            "Only members of the organization can join its teams".to_string()
+        ])
+        .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🚪 Take a member off a team
+pub async fn remove_team_member(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, team_id, member_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Response {
+    let team = match require_team(&app_state, &user, id, team_id).await {
+        Ok(team) => team,
+        Err(response) => return response,
+    };
+
+    match team.remove_member(&app_state.db_pool, member_id).await {
+        Ok(true) => {
+            app_state
+                .cache
+                .invalidate_all(CacheNamespace::Projects)
+                .await;
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Team member removed".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Team member").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🏠 Move a project into the organization (optionally limited to a team)
+/// Needs admin in the organization and ownership of the project
+pub async fn assign_project(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, project_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<AssignProjectRequest>,
+) -> Response {
+    let (organization, _) = match require_role(&app_state, &user, id, OrgRole::Admin).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let Some(mut project) = Project::find_by_id(&app_state.db_pool, project_id).await? else {
+            return Ok(Err(not_found_error("Project").into_response()));
+        };
+        let project_role =
+            organizations::project_role_for(&app_state.db_pool, &user, &project).await?;
+        if project_role != Some(OrgRole::Owner) {
+            return Ok(Err(forbidden(
+                "Only the project's owner can move it into an organization",
+            )));
+        }
+        if let Some(team_id) = request.team_id {
+            if Team::find(&app_state.db_pool, id, team_id).await?.is_none() {
+                return Ok(Err(not_found_error("Team").into_response()));
+            }
+        }
+        if project.organization_id != Some(id) {
+            if let Some(message) = project_quota_exceeded(&app_state.db_pool, &organization).await?
+            {
+                return Ok(Err(quota_exceeded(message)));
+            }
+            if let Some(message) =
+                organizations::repository_not_allowed(&organization, &project.repository)?
+            {
+                return Ok(Err(forbidden(&message)));
+            }
+        }
+
+        project
+            .set_organization(&app_state.db_pool, Some(id), request.team_id)
+            .await?;
+        Ok::<_, anyhow::Error>(Ok(project))
+    };
+
+    match result.await {
+        Ok(Ok(project)) => {
+            app_state.cache.project_changed(project.id).await;
+            info!(
+                "🏠 {} moved {} into {}",
+                user.email, project.repository, organization.slug
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("organization-project-assigned"),
+                    project,
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🏠 Take a project out of the organization (back to its owner alone)
+pub async fn remove_project(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, project_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    if let Err(response) = require_role(&app_state, &user, id, OrgRole::Admin).await {
+        return response;
+    }
+
+    let result = async {
+        let project = Project::find_by_id(&app_state.db_pool, project_id)
+            .await?
+            .filter(|project| project.organization_id == Some(id));
+        let Some(mut project) = project else {
+            return Ok(None);
+        };
+        project
+            .set_organization(&app_state.db_pool, None, None)
+            .await?;
+        Ok::<_, anyhow::Error>(Some(project))
+    };
+
+    match result.await {
+        Ok(Some(project)) => {
+            app_state.cache.project_changed(project.id).await;
+            info!(
+                "🏠 {} took {} out of organization {}",
+                user.email, project.repository, id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("organization-project-removed"),
+                    project,
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => not_found_error("Project in this organization").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔑 Issue an API key for one of the organization's service accounts
+/// The account is created on first use; earlier keys of an existing one are revoked.
+/// Its keys only reach this organization's projects
+pub async fn issue_api_key(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<IssueApiKeyRequest>,
+) -> Response {
+    let days = request.days.unwrap_or(365);
+    if days == 0 || days > MAX_API_KEY_DAYS {
+        return validation_error(vec![format!(
+            "days must be between 1 and {}",
+            MAX_API_KEY_DAYS
+        )])
+        .into_response();
+    }
+    if !request.email.contains('@') {
+        return validation_error(vec!["email must be an email address".to_string()])
+            .into_response();
+    }
+    let scopes = match request.scopes.as_deref().map(auth::validate_scopes) {
+        Some(Ok(scopes)) => Some(scopes),
+        Some(Err(errors)) => return validation_error(errors).into_response(),
+        None => None,
+    };
+    let (organization, _) = match require_role(&app_state, &user, id, OrgRole::Admin).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    if request
+        .rate_limit_tier
+        .is_some_and(|tier| tier != RateLimitTier::Standard)
+        && !user.is_admin()
+    {
+        return forbidden("Only system admins can raise an API key's rate limit tier");
+    }
+
+    let result = async {
+        let mut account = match User::find_by_email(&app_state.db_pool, &request.email).await? {
+            Some(account) if organizations::is_organization_service_account(&account, id) => {
+                User::revoke_tokens(&app_state.db_pool, Some(account.id)).await?;
+                account
+            }
+            Some(_) => {
+                return Ok(Err(conflict(
+                    "This email belongs to an account that isn't one of the organization's service accounts",
+                )))
+            }
+            None => {
+                let password = auth::generate_secret(auth::GENERATED_PASSWORD_LENGTH);
+                let account = User::create_service_account(
+                    &app_state.db_pool,
+                    &request.email,
+                    request.name.as_deref().unwrap_or(&request.email),
+                    &auth::hash_password(&password)?,
+                    id,
+                )
+                .await?;
+                OrganizationMember::set(&app_state.db_pool, id, account.id, OrgRole::Member)
+                    .await?;
+                account
+            }
+        };
+        auth::update_api_key_limits(
+            &app_state.db_pool,
+            &mut account,
+            scopes,
+            request.rate_limit_tier,
+        )
+        .await?;
+        let signing_key = match request.signed_requests {
+            Some(true) => {
+                let key = auth::generate_secret(auth::REQUEST_SIGNING_KEY_LENGTH);
+                account
+                    .set_request_signing_key(&app_state.db_pool, Some(&key))
+                    .await?;
+                Some(key)
+            }
+            Some(false) => {
+                account
+                    .set_request_signing_key(&app_state.db_pool, None)
+                    .await?;
+                None
+            }
+            None => None,
+        };
+        let signer = app_state
+            .token_keys
+            .signer(&app_state.config.load().auth)
+            .await;
+        let api_key = auth::issue_api_key(&account, &signer, days)?;
+        Ok::<_, anyhow::Error>(Ok(IssuedApiKey {
+            user_id: account.id,
+            email: account.email,
+            api_key,
+            valid_days: days,
+            rate_limit_tier: account
+                .rate_limit_tier
+                .as_deref()
+                .and_then(|tier| tier.parse().ok())
+                .unwrap_or_default(),
+            scopes: account.api_scopes,
+            signing_key,
+            signed_requests: account.request_signing_key.is_some(),
+        }))
+    };
+
+    match result.await {
+        Ok(Ok(issued)) => {
+            info!(
+                "🔑 {} issued an API key for {} in {}",
+                user.email, issued.email, organization.slug
+            );
+            security_alerts::api_key_issued(&app_state, &organization, &issued.email, &user.email)
+                .await;
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::success(
+                    i18n::t("organization-api-key-issued"),
+                    issued,
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔐 The organization's single sign-on provider (owners only; the secret is never shown)
+pub async fn get_sso_provider(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    if let Err(response) = require_role(&app_state, &user, id, OrgRole::Owner).await {
+        return response;
+    }
+
+    match SsoProvider::find_by_organization(&app_state.db_pool, id).await {
+        Ok(Some(provider)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("organization-sso-retrieved"),
+                provider,
+            )),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("Single sign-on provider").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🔐 Configure the organization's single sign-on provider (owners only)
+pub async fn set_sso_provider(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(settings): Json<SsoProviderSettings>,
+) -> Response {
+    let (organization, _) = match require_role(&app_state, &user, id, OrgRole::Owner).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let existing = SsoProvider::find_by_organization(&app_state.db_pool, id).await?;
+        if let Err(errors) = sso::validate_settings(&settings, existing.is_some()) {
+            return Ok(Err(validation_error(errors).into_response()));
+        }
+        let provider = SsoProvider::upsert(&app_state.db_pool, id, &settings).await?;
+        Ok::<_, anyhow::Error>(Ok(provider))
+    };
+
+    match result.await {
+        Ok(Ok(provider)) => {
+            info!(
+                "🔐 {} configured single sign-on for {} ({})",
+                user.email, organization.slug, provider.issuer_url
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("organization-sso-saved"),
+                    provider,
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(response)) => response,
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🗑️ Remove the organization's single sign-on provider and its account links (owners only)
+pub async fn delete_sso_provider(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let (organization, _) = match require_role(&app_state, &user, id, OrgRole::Owner).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match SsoProvider::delete(&app_state.db_pool, id).await {
+        Ok(true) => {
+            info!(
+                "🗑️ {} removed single sign-on from {}",
+                user.email, organization.slug
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(
+                    "Single sign-on provider removed".to_string(),
+                )),
+            )
+                .into_response()
+        }
+        Ok(false) => not_found_error("Single sign-on provider").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 👑 The organization, if the caller has at least `needed` in it
+/// Non-members get a 404 so organizations can't be probed
+async fn require_role(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    id: Uuid,
+    needed: OrgRole,
+) -> Result<(Organization, OrgRole), Response> {
+    let lookup = async {
+        let Some(organization) = Organization::find_by_id(&app_state.db_pool, id).await? else {
+            return Ok(None);
+        };
+        let role = organizations::organization_role(&app_state.db_pool, user, id).await?;
+        Ok::<_, anyhow::Error>(role.map(|role| (organization, role)))
+    };
+
+    match lookup.await {
+        Ok(Some((organization, role))) if role >= needed => Ok((organization, role)),
+        Ok(Some(_)) => Err(forbidden(&format!(
+            "This needs the {} role in the organization",
+            needed.as_str()
+        ))),
+        Ok(None) => Err(not_found_error("Organization").into_response()),
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+/// 🧑‍🤝‍🧑 One of the organization's teams, if the caller administers the organization
+async fn require_team(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    id: Uuid,
+    team_id: Uuid,
+) -> Result<Team, Response> {
+    require_role(app_state, user, id, OrgRole::Admin).await?;
+    match Team::find(&app_state.db_pool, id, team_id).await {
+        Ok(Some(team)) => Ok(team),
+        Ok(None) => Err(not_found_error("Team").into_response()),
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+/// 🛡️ Wrap a forbidden response
+fn forbidden(message: &str) -> Response {
+    let api_response = ApiResponse::<()>::error("forbidden".to_string(), message.to_string(), None);
+    (StatusCode::FORBIDDEN, Json(api_response)).into_response()
+}
+
+/// ⚔️ Wrap a conflict response
+fn conflict(message: &str) -> Response {
+    let api_response = ApiResponse::<()>::error("conflict".to_string(), message.to_string(), None);
+    (StatusCode::CONFLICT, Json(api_response)).into_response()
+}
+
+/// 📏 Wrap a quota response
+pub fn quota_exceeded(message: String) -> Response {
+    let api_response = ApiResponse::<()>::error("quota_exceeded".to_string(), message, None);
+    (StatusCode::TOO_MANY_REQUESTS, Json(api_response)).into_response()
+}
+
+/// 💥 Log and wrap an unexpected failure
+fn internal_error(e: anyhow::Error) -> Response {
+    crate::errors::error_response("Organization request failed", e)
+}
@@ -2,33 +2,155 @@
 // This module handles project management endpoints
 // Created with love by Aye & Hue! ✨
 
-use crate::api::{ApiResponse, AppState};
+use crate::{
+    api::{utils::validation_error, ApiResponse, AppState, ErrorResponse, FieldsParams},
+    cache::CacheNamespace,
+    database::models::{
+        Feedback, FeedbackStatus, Project, ProjectCollaborator, ProjectStyleProfile,
+        WebhookDelivery,
+    },
+    errors,
+    github::{parse_repository, GitHubClient},
+    i18n,
+    issue_import::{self, ImportIssuesRequest},
+    jobs::runs,
+    middleware::auth::AuthenticatedUser,
+    models::ProjectConfig,
+    organizations,
+    pipeline::{
+        pr_description::tracking_url,
+        testgen::{CoverageReport, COVERAGE_METADATA_KEY, MAX_COVERAGE_REPORT_BYTES},
+        PipelineMode,
+    },
+    scm,
+    style_guide::{self, StyleProfileView},
+};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::{IntoResponse, Json, Response},
+    Extension,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
-pub struct ProjectInfo {
-    pub id: Uuid,
-    pub repository: String,
-    pub description: Option<String>,
-    pub is_active: bool,
+/// 🧪 Test generation request
+#[derive(Debug, Deserialize)]
+pub struct TestGenerationRequest {
+    /// 💬 What to test, e.g. "add tests for the parser" (whole project when None)
+    pub request: Option<String>,
+    /// 📊 LCOV coverage report pinpointing uncovered functions (optional)
+    pub coverage_report: Option<String>,
 }
 
-pub async fn list_projects(State(_app_state): State<AppState>) -> impl IntoResponse {
-    // TODO: Implement project listing
-    let projects: Vec<ProjectInfo> = vec![];  // 🔧 Added explicit type annotation
-    (
-        StatusCode::OK,
-        Json(ApiResponse::success(
-            "Projects retrieved".to_string(),
-            projects,
-        )),
-    )
+impl TestGenerationRequest {
+    /// ✅ Validate the request before starting a run
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if let Some(request) = &self.request {
+            if request.trim().is_empty() {
+                errors.push("Request cannot be empty".to_string());
+            } else if request.len() > 10000 {
+                errors.push("Request is too long (max 10,000 characters)".to_string());
+            }
+        }
+
+        if let Some(report) = &self.coverage_report {
+            if report.len() > MAX_COVERAGE_REPORT_BYTES {
+                errors.push(format!(
+                    "Coverage report is too large (max {} bytes)",
+                    MAX_COVERAGE_REPORT_BYTES
+                ));
+            } else if let Err(e) = CoverageReport::parse_lcov(report) {
+                errors.push(format!("Invalid coverage report: {:#}", e));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// 📦 What a project-level run starts from
+#[derive(Debug, Default)]
+struct RunInput {
+    /// 💬 Feedback content (the mode's run description when None)
+    content: Option<String>,
+    /// 🏷️ Extra feedback metadata for the mode
+    metadata: Option<serde_json::Value>,
+}
+
+// 📦 What the API says about a project, shared with API clients through feedbacker-types
+pub use feedbacker_types::ProjectInfo;
+
+/// 📋 Projects the caller can see: their own and their organizations'
+/// (everything for system admins; only its organization's for an organization service account)
+/// `?fields=` trims each project to the listed fields
+pub async fn list_projects(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(fields): Query<FieldsParams>,
+) -> Response {
+    let fields = match fields.selection(ProjectInfo::FIELDS) {
+        Ok(fields) => fields,
+        Err(errors) => return validation_error(errors).into_response(),
+    };
+    let (member_id, organization_id) = organizations::visible_projects_filter(&user);
+    let cache_key = format!(
+        "member={}:organization={}",
+        member_id.map_or_else(|| "*".to_string(), |id| id.to_string()),
+        organization_id.map_or_else(|| "*".to_string(), |id| id.to_string())
+    );
+    let projects = app_state
+        .cache
+        .get_or_load(CacheNamespace::Projects, &cache_key, || {
+            visible_projects(&app_state.db_pool, &user)
+        })
+        .await;
+
+    match projects {
+        Ok(projects) => {
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("projects-retrieved"),
+                    projects
+                        .into_iter()
+                        .map(|project| fields.apply(project))
+                        .collect::<Vec<_>>(),
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 👀 The projects a user can see, as the API lists them
+/// Shared with the MCP server's list_projects tool (see crate::mcp)
+pub async fn visible_projects(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+) -> anyhow::Result<Vec<ProjectInfo>> {
+    let (member_id, organization_id) = organizations::visible_projects_filter(user);
+    let projects = Project::list_visible(pool, member_id, organization_id).await?;
+    Ok(projects
+        .into_iter()
+        .map(|project| ProjectInfo {
+            id: project.id,
+            repository: project.repository,
+            description: project.description,
+            is_active: project.is_active,
+            organization_id: project.organization_id,
+            team_id: project.team_id,
+        })
+        .collect())
 }
 
 pub async fn get_project(
@@ -41,13 +163,470 @@ pub async fn get_project(
         repository: "example/repo".to_string(),
         description: Some("Example project".to_string()),
         is_active: true,
+        organization_id: None,
+        team_id: None,
     };
 
     (
         StatusCode::OK,
+        Json(ApiResponse::success(i18n::t("project-retrieved"), project)),
+    )
+}
+
+/// 👥 GitHub logins mirrored into a project by the collaborator sync
+/// (empty unless the project sets `collaborators.sync`)
+pub async fn list_collaborators(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let result = async {
+        if Project::find_by_id(&app_state.db_pool, id).await?.is_none() {
+            return Ok(None);
+        }
+        ProjectCollaborator::list(&app_state.db_pool, id).await.map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(collaborators)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with(
+                    "project-collaborators",
+                    [("count", collaborators.len().into())],
+                ),
+                collaborators,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => errors::error_response("Failed to list project collaborators", e),
+    }
+}
+
+/// 📬 Webhook deliveries listed for a project
+const RECENT_WEBHOOK_DELIVERIES: i64 = 50;
+
+/// 📬 Recent webhook deliveries about a project's repository, newest first,
+/// with the answer each got and how long it took
+pub async fn list_webhook_deliveries(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let result = async {
+        let Some(project) = Project::find_by_id(&app_state.db_pool, id).await? else {
+            return Ok(None);
+        };
+        WebhookDelivery::list_for_repository(
+            &app_state.db_pool,
+            &project.repository,
+            RECENT_WEBHOOK_DELIVERIES,
+        )
+        .await
+        .map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(deliveries)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with(
+                    "project-webhook-deliveries",
+                    [("count", deliveries.len().into())],
+                ),
+                deliveries,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => errors::error_response("Failed to list webhook deliveries", e),
+    }
+}
+
+/// 🎨 The style profile learned from a project's repository, with the section
+/// generation prompts get from it
+pub async fn get_style_profile(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let result = async {
+        ProjectStyleProfile::find(&app_state.db_pool, id)
+            .await?
+            .map(StyleProfileView::new)
+            .transpose()
+    }
+    .await;
+
+    match result {
+        Ok(Some(view)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("project-style-profile-retrieved"),
+                view,
+            )),
+        )
+            .into_response(),
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "The project's style hasn't been analyzed yet".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => errors::error_response("Failed to fetch style profile", e),
+    }
+}
+
+/// 🔄 Queue a fresh analysis of a project's style (after a formatter change, say)
+pub async fn analyze_style(State(app_state): State<AppState>, Path(id): Path<Uuid>) -> Response {
+    let result = async {
+        if Project::find_by_id(&app_state.db_pool, id).await?.is_none() {
+            return Ok(None);
+        }
+        style_guide::queue_analysis(&app_state, id).await.map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(queued)) => {
+            let message = if queued {
+                i18n::t("project-style-analysis-queued")
+            } else {
+                i18n::t("project-style-analysis-already-queued")
+            };
+            (
+                StatusCode::ACCEPTED,
+                Json(ApiResponse::success(
+                    message,
+                    serde_json::json!({ "queued": queued }),
+                )),
+            )
+                .into_response()
+        }
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            (StatusCode::NOT_FOUND, Json(api_response)).into_response()
+        }
+        Err(e) => errors::error_response("Failed to queue style analysis", e),
+    }
+}
+
+/// ⚙️ Replace a project's configuration
+/// PR settings are validated against the repository before saving
+pub async fn update_project_config(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(config): Json<ProjectConfig>,
+) -> Response {
+    info!("⚙️ Updating configuration for project: {}", id);
+
+    if let Err(errors) = config.validate() {
+        return validation_failed(errors);
+    }
+
+    let mut project = match Project::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            return (StatusCode::NOT_FOUND, Json(api_response)).into_response();
+        }
+        Err(e) => return internal_error(e),
+    };
+
+    // 🐙 Make sure the repository is reachable on its (new) GitHub endpoint and
+    // labels, milestone, and assignees actually exist in it
+    let problems = match validate_against_repository(&app_state, &project, &config).await {
+        Ok(problems) => problems,
+        Err(e) => return internal_error(e),
+    };
+    if !problems.is_empty() {
+        warn!("❌ Project {} config rejected by GitHub validation", id);
+        return validation_failed(problems);
+    }
+
+    // 🌍 Public status page slugs are shared by every project
+    if let Some(slug) = config.public_status.public_slug() {
+        match Project::find_by_public_slug(&app_state.db_pool, slug).await {
+            Ok(Some(other)) if other.id != project.id => {
+                return validation_failed(vec![format!(
+                    "Public status slug '{}' is already used by another project",
+                    slug
+                )]);
+            }
+            Ok(_) => {}
+            Err(e) => return internal_error(e),
+        }
+    }
+
+    match project.update_config(&app_state.db_pool, &config).await {
+        Ok(()) => {
+            app_state.cache.project_changed(id).await;
+            info!("✅ Project {} configuration saved", id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("project-config-updated"),
+                    config,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// ⬆️ Start a dependency update run for a project
+/// The run is tracked as a feedback item; progress shows up in its events timeline
+pub async fn start_dependency_updates(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    start_project_run(app_state, id, PipelineMode::DependencyUpdate, RunInput::default()).await
+}
+
+/// 📚 Start a documentation-only pass for a project
+/// Opens a PR labelled `documentation` that never touches code
+pub async fn start_docs_pass(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    start_project_run(app_state, id, PipelineMode::Documentation, RunInput::default()).await
+}
+
+/// 🧪 Start a test generation run for a project
+/// Only test files are written, and the PR opens only if they pass in the sandbox
+pub async fn start_test_generation(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<TestGenerationRequest>,
+) -> Response {
+    if let Err(errors) = request.validate() {
+        return validation_failed(errors);
+    }
+
+    let input = RunInput {
+        content: request.request,
+        metadata: request
+            .coverage_report
+            .map(|report| serde_json::json!({ COVERAGE_METADATA_KEY: report })),
+    };
+    start_project_run(app_state, id, PipelineMode::TestGeneration, input).await
+}
+
+/// 🎫 Import a project's open GitHub issues as feedback
+/// Issues imported before are skipped, so running it again only picks up new ones
+pub async fn import_issues(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ImportIssuesRequest>,
+) -> Response {
+    if let Err(errors) = request.validate() {
+        return validation_failed(errors);
+    }
+
+    let project = match Project::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            return (StatusCode::NOT_FOUND, Json(api_response)).into_response();
+        }
+        Err(e) => return internal_error(e),
+    };
+
+    // 📏 Imported issues count towards the organization's monthly quota
+    match organizations::feedback_quota_exceeded(&app_state.db_pool, &project.repository).await {
+        Ok(Some(message)) => return crate::api::organizations::quota_exceeded(message),
+        Ok(None) => {}
+        Err(e) => error!("❌ Feedback quota check failed: {:#}", e),
+    }
+
+    let result = async {
+        let github_config =
+            scm::github_config(&app_state.db_pool, &app_state.config.load().github, &project)
+                .await?;
+        let github = GitHubClient::new(github_config)?;
+        issue_import::import_issues(
+            &app_state.db_pool,
+            app_state.jobs.as_ref(),
+            &github,
+            &project,
+            user.id,
+            &request,
+        )
+        .await
+    }
+    .await;
+
+    match result {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t_with(
+                    "issues-imported",
+                    [("count", response.imported.len().into())],
+                ),
+                response,
+            )),
+        )
+            .into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// 🏭 Queue a project-level pipeline run (see jobs::runs) and return its tracking info
+async fn start_project_run(
+    app_state: AppState,
+    id: Uuid,
+    mode: PipelineMode,
+    input: RunInput,
+) -> Response {
+    let project = match Project::find_by_id(&app_state.db_pool, id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            let api_response = ApiResponse::<()>::error(
+                "not_found".to_string(),
+                "Project not found".to_string(),
+                None,
+            );
+            return (StatusCode::NOT_FOUND, Json(api_response)).into_response();
+        }
+        Err(e) => return internal_error(e),
+    };
+
+    let feedback = match create_run_feedback(&app_state, &project, mode, input).await {
+        Ok(feedback) => feedback,
+        Err(e) => return internal_error(e),
+    };
+    info!(
+        "🏭 {:?} run {} queued for {}",
+        mode, feedback.id, project.repository
+    );
+
+    if let Err(e) = runs::queue_project_run(&app_state, &project, &feedback, mode).await {
+        return internal_error(e);
+    }
+
+    let tracking = tracking_url(&app_state.config.load().server.public_url, feedback.id);
+    (
+        StatusCode::ACCEPTED,
         Json(ApiResponse::success(
-            "Project retrieved".to_string(),
-            project,
+            i18n::t("project-run-queued"),
+            serde_json::json!({ "feedback_id": feedback.id, "tracking_url": tracking }),
         )),
     )
+        .into_response()
+}
+
+/// 📝 Feedback item that tracks a project-level run
+async fn create_run_feedback(
+    app_state: &AppState,
+    project: &Project,
+    mode: PipelineMode,
+    input: RunInput,
+) -> anyhow::Result<Feedback> {
+    let scope = project.settings()?.scope()?;
+    let mut feedback = Feedback::create(
+        &app_state.db_pool,
+        None,
+        project.repository.clone(),
+        scope.root,
+        input
+            .content
+            .unwrap_or_else(|| mode.run_description(&project.repository)),
+    )
+    .await?;
+    feedback
+        .merge_metadata(&app_state.db_pool, mode.to_metadata())
+        .await?;
+    if let Some(metadata) = input.metadata {
+        feedback
+            .merge_metadata(&app_state.db_pool, metadata)
+            .await?;
+    }
+    feedback
+        .update_status(&app_state.db_pool, FeedbackStatus::Processing, None)
+        .await?;
+    Ok(feedback)
+}
+
+/// 🐙 Check the SCM endpoint and PR settings against the live repository
+async fn validate_against_repository(
+    app_state: &AppState,
+    project: &Project,
+    config: &ProjectConfig,
+) -> anyhow::Result<Vec<String>> {
+    let github = app_state.config.load().github.clone();
+    let problems = scm::validate(&github.endpoints, &config.scm);
+    if !problems.is_empty() {
+        return Ok(problems);
+    }
+
+    // 🔌 A project moving to another GitHub must be reachable there
+    let github_config = scm::resolve(&app_state.db_pool, &github, project, &config.scm).await?;
+    let current = scm::github_config(&app_state.db_pool, &github, project)
+        .await
+        .ok()
+        .map(|current| current.api_base_url);
+    if current.as_deref() != Some(github_config.api_base_url.as_str()) {
+        if let Some(problem) =
+            scm::check_connectivity(github_config.clone(), &project.repository).await
+        {
+            return Ok(vec![problem]);
+        }
+    }
+
+    let settings = &config.pull_requests;
+    if !settings.has_issue_fields() {
+        return Ok(Vec::new());
+    }
+
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let github_client = GitHubClient::new(github_config)?;
+    github_client
+        .validate_pull_request_settings(&owner, &repo, settings)
+        .await
+}
+
+/// ✅ Validation error response
+fn validation_failed(errors: Vec<String>) -> Response {
+    let api_response = ApiResponse::<()>::error(
+        "validation_error".to_string(),
+        errors::ErrorKind::Validation.title(),
+        Some(serde_json::json!({ "errors": errors })),
+    );
+    (StatusCode::BAD_REQUEST, Json(api_response)).into_response()
+}
+
+/// ❌ Internal error response
+fn internal_error(e: anyhow::Error) -> Response {
+    crate::errors::error_response("Project request failed", e)
 }
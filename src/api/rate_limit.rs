@@ -0,0 +1,85 @@
+// 🚦 Rate Limit API - Know Your Limits Before You Hit Them! 🚦
+// GET /api/rate-limit tells the caller which tier they are on and, for each
+// limit type, how much of the current window is left and when it resets, so
+// clients like the smart-tree CLI can pace themselves instead of waiting for a 429.
+// Other replicas' requests show up once they are synced (RATE_LIMIT_SYNC_SECONDS)
+// Created with love by Aye & Hue - Slow and steady gets the feedback in! ✨
+
+use crate::{
+    api::{ApiResponse, AppState},
+    i18n,
+    middleware::{
+        auth::AuthenticatedUser,
+        rate_limiting::{client_id_for, RateLimitTier, RateLimitType},
+    },
+};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// 🚦 The caller's limits
+#[derive(Debug, Serialize)]
+pub struct RateLimitStatus {
+    pub tier: RateLimitTier,
+    pub limits: Vec<LimitStatus>,
+}
+
+/// 📊 One limit type, as it stands right now
+#[derive(Debug, Serialize)]
+pub struct LimitStatus {
+    /// 📋 api or feedback
+    pub limit_type: &'static str,
+    /// 📏 Requests allowed per window (None = not limited)
+    pub limit: Option<u32>,
+    /// 📉 Requests left in the current window (None = not limited)
+    pub remaining: Option<u32>,
+    /// ⏱️ Length of a window
+    pub window_seconds: u64,
+    /// ⏰ When the current window ends (None = no window open yet)
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+/// 👀 The caller's tier, remaining quota and reset times
+pub async fn get_rate_limit(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Response {
+    let config = app_state.live.current().rate_limiting.clone();
+    let client_id = client_id_for(&user);
+    let tier = user.rate_limit_tier;
+
+    let limits = [RateLimitType::Api, RateLimitType::Feedback]
+        .into_iter()
+        .map(|limit_type| {
+            let limit = tier.limit(&config, limit_type);
+            let usage =
+                limit.map(|limit| app_state.rate_limits.usage(&client_id, limit_type, limit));
+            let resets_in = usage.as_ref().and_then(|usage| usage.resets_in);
+            LimitStatus {
+                limit_type: limit_type.as_str(),
+                limit,
+                remaining: usage.map(|usage| usage.remaining),
+                window_seconds: limit_type.window().as_secs(),
+                resets_at: resets_in.and_then(|resets_in| {
+                    chrono::Duration::from_std(resets_in)
+                        .ok()
+                        .map(|resets_in| Utc::now() + resets_in)
+                }),
+            }
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            i18n::t("rate-limits-retrieved"),
+            RateLimitStatus { tier, limits },
+        )),
+    )
+        .into_response()
+}
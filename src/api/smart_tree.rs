@@ -2,33 +2,40 @@
 // This module provides Smart Tree MCP integration endpoints
 // Created with love by Aye & Hue! ✨
 
-use crate::api::{ApiResponse, AppState};
+use crate::{
+    api::{ApiResponse, AppState},
+    cache::CacheNamespace,
+    errors, i18n,
+};
 use axum::{
     extract::State,
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::{IntoResponse, Json, Response},
 };
-use serde::Serialize;
-
-#[derive(Debug, Serialize)]
-pub struct VersionInfo {
-    pub version: String,
-    pub download_url: String,
-    pub release_notes: String,
-}
+use feedbacker_types::VersionInfo;
 
-pub async fn get_latest_version(State(_app_state): State<AppState>) -> impl IntoResponse {
-    let version_info = VersionInfo {
-        version: "1.0.0".to_string(),
-        download_url: "https://github.com/aye-is/smart-tree/releases/latest".to_string(),
-        release_notes: "Latest Smart Tree MCP release".to_string(),
-    };
+/// 🌳 The latest Smart Tree release (cached, see crate::cache)
+pub async fn get_latest_version(State(app_state): State<AppState>) -> Response {
+    let version_info = app_state
+        .cache
+        .get_or_load(CacheNamespace::SmartTree, "latest", || async {
+            Ok(VersionInfo {
+                version: "1.0.0".to_string(),
+                download_url: "https://github.com/aye-is/smart-tree/releases/latest".to_string(),
+                release_notes: "Latest Smart Tree MCP release".to_string(),
+            })
+        })
+        .await;
 
-    (
-        StatusCode::OK,
-        Json(ApiResponse::success(
-            "Version info retrieved".to_string(),
-            version_info,
-        )),
-    )
+    match version_info {
+        Ok(version_info) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("version-info-retrieved"),
+                version_info,
+            )),
+        )
+            .into_response(),
+        Err(e) => errors::error_response("Failed to load Smart Tree version", e),
+    }
 }
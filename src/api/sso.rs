@@ -0,0 +1,178 @@
+// 🔐 Single Sign-On API - Through the Front Door of Your Identity Provider! 🔐
+// GET /api/auth/sso/:slug/login redirects to the organization's provider, and
+// GET /api/auth/sso/:slug/callback is where it sends the user back; a successful
+// callback answers like /api/auth/login, with a token. Both are public. The
+// provider itself is configured by organization owners at /api/orgs/:id/sso,
+// and how accounts are linked is decided in crate::sso
+// Created with love by Aye & Hue - Welcome back, you're already signed in! ✨
+
+use crate::{
+    api::{
+        auth::{AuthResponse, UserInfo},
+        utils::not_found_error,
+        ApiResponse, AppState, ErrorResponse,
+    },
+    database::models::{Organization, SsoLoginState, SsoProvider},
+    errors, feedback_claims, i18n,
+    middleware::auth::jwt_utils,
+    security_alerts, sso,
+};
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Redirect, Response},
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use tracing::{error, info, warn};
+
+/// 🔙 What the provider sends back
+#[derive(Debug, Deserialize)]
+pub struct CallbackParams {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// ❌ Set instead of `code` when the provider refused the sign-in
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// 🚪 Send the user to their organization's identity provider
+pub async fn login(State(app_state): State<AppState>, Path(slug): Path<String>) -> Response {
+    let provider = match enabled_provider(&app_state, &slug).await {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+    let redirect_uri = sso::redirect_uri(&app_state.config.load().server.public_url, &slug);
+
+    match sso::begin_login(&app_state.db_pool, &provider, &redirect_uri).await {
+        Ok(url) => Redirect::to(&url).into_response(),
+        Err(e) => {
+            let context = format!("SSO login for {} failed to start", slug);
+            let api_response = ApiResponse::<()>::error(
+                "sso_unavailable".to_string(),
+                "The identity provider couldn't be reached".to_string(),
+                Some(errors::error_reference(&context, &e)),
+            );
+            (StatusCode::BAD_GATEWAY, Json(api_response)).into_response()
+        }
+    }
+}
+
+/// 🔙 Finish signing in and issue a token
+pub async fn callback(
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<CallbackParams>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(error) = params.error {
+        let message = params.error_description.unwrap_or(error);
+        return sign_in_refused(&format!("The identity provider refused: {}", message));
+    }
+    let (Some(code), Some(state)) = (params.code, params.state) else {
+        return sign_in_refused("The callback is missing its code or state");
+    };
+    let provider = match enabled_provider(&app_state, &slug).await {
+        Ok(provider) => provider,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let login_state =
+            SsoLoginState::take(&app_state.db_pool, &state, sso::LOGIN_STATE_MAX_AGE_SECONDS)
+                .await?;
+        let Some(login_state) = login_state.filter(|s| s.provider_id == provider.id) else {
+            return Ok(Err(
+                "This sign-in expired or was already used; please start again".to_string(),
+            ));
+        };
+        let config = app_state.config.load();
+        let redirect_uri = sso::redirect_uri(&config.server.public_url, &slug);
+        let identity = match sso::finish_login(&provider, &login_state, &code, &redirect_uri).await
+        {
+            Ok(identity) => identity,
+            Err(e) => {
+                warn!("❌ SSO callback for {} rejected: {:#}", slug, e);
+                return Ok(Err(format!("{:#}", e)));
+            }
+        };
+        let user = match sso::sign_in(&app_state.db_pool, &provider, &identity).await? {
+            Ok(user) => user,
+            Err(refusal) => return Ok(Err(refusal)),
+        };
+
+        let hours = config.auth.token_expiration_hours;
+        let signer = app_state.token_keys.signer(&config.auth).await;
+        let token = jwt_utils::create_jwt_token(&user, &signer, hours)?;
+        Ok::<_, anyhow::Error>(Ok(AuthResponse {
+            user: UserInfo {
+                id: user.id,
+                email: user.email,
+                name: user.name,
+                github_username: user.github_username,
+                role: user.role,
+                email_verified: user.email_verified,
+            },
+            token,
+            expires_at: chrono::Utc::now() + chrono::Duration::hours(hours as i64),
+        }))
+    };
+
+    match result.await {
+        Ok(Ok(response)) => {
+            info!("🔐 {} signed in through {}", response.user.email, slug);
+            security_alerts::record_sign_in(
+                &app_state,
+                response.user.id,
+                &response.user.email,
+                &headers,
+                connect_info.map(|ConnectInfo(addr)| addr.ip()),
+            )
+            .await;
+            feedback_claims::claim_on_sign_in(
+                &app_state,
+                response.user.id,
+                &response.user.email,
+                response.user.email_verified,
+            )
+            .await;
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("auth-login-successful"),
+                    response,
+                )),
+            )
+                .into_response()
+        }
+        Ok(Err(refusal)) => sign_in_refused(&refusal),
+        Err(e) => errors::error_response(&format!("SSO callback for {} failed", slug), e),
+    }
+}
+
+/// 🔍 The organization's provider, if it has one accepting sign-ins
+async fn enabled_provider(app_state: &AppState, slug: &str) -> Result<SsoProvider, Response> {
+    let lookup = async {
+        let Some(organization) = Organization::find_by_slug(&app_state.db_pool, slug).await? else {
+            return Ok(None);
+        };
+        SsoProvider::find_by_organization(&app_state.db_pool, organization.id).await
+    };
+
+    match lookup.await {
+        Ok(Some(provider)) if provider.enabled => Ok(provider),
+        Ok(_) => Err(not_found_error("Single sign-on").into_response()),
+        Err(e) => {
+            error!("❌ SSO provider lookup for {} failed: {:#}", slug, e);
+            Err(crate::api::utils::handle_error(e).into_response())
+        }
+    }
+}
+
+/// 🚫 Wrap a refused sign-in
+fn sign_in_refused(message: &str) -> Response {
+    let api_response =
+        ApiResponse::<()>::error("sso_failed".to_string(), message.to_string(), None);
+    (StatusCode::UNAUTHORIZED, Json(api_response)).into_response()
+}
@@ -1,41 +1,81 @@
 // 📊 Status API - Project Status Tracking! 📊
-// This module provides endpoints for checking project and feedback status
+// This module provides endpoints for checking project and feedback status.
+// Statuses are polled a lot, so they're cached (see crate::cache) until the
+// project's feedback or settings change
 // Created with love by Aye & Hue! ✨
 
-use crate::api::{ApiResponse, AppState};
+use crate::{
+    api::{utils::not_found_error, ApiResponse, AppState},
+    cache::CacheNamespace,
+    database::models::{Feedback, Project},
+    errors, i18n,
+};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::{IntoResponse, Json, Response},
 };
-use serde::Serialize;
+use feedbacker_types::ProjectStatus;
+use sqlx::PgPool;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
-pub struct ProjectStatus {
-    pub project_id: Uuid,
-    pub repository: String,
-    pub status: String,
-    pub last_activity: chrono::DateTime<chrono::Utc>,
-}
-
+/// 📊 A project's status: inactive, processing (feedback still open) or active
 pub async fn get_project_status(
-    State(_app_state): State<AppState>,
+    State(app_state): State<AppState>,
     Path(project_id): Path<Uuid>,
-) -> impl IntoResponse {
-    // TODO: Implement project status retrieval
-    let status = ProjectStatus {
-        project_id,
-        repository: "example/repo".to_string(),
-        status: "active".to_string(),
-        last_activity: chrono::Utc::now(),
+) -> Response {
+    let status = app_state
+        .cache
+        .get_or_load(
+            CacheNamespace::ProjectStatus,
+            &project_id.to_string(),
+            || load_project_status(&app_state.db_pool, project_id),
+        )
+        .await;
+
+    match status {
+        Ok(Some(status)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("project-status-retrieved"),
+                status,
+            )),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("Project").into_response(),
+        Err(e) => errors::error_response("Failed to load project status", e),
+    }
+}
+
+/// 🔍 Work out a project's status (None when there's no such project)
+async fn load_project_status(
+    pool: &PgPool,
+    project_id: Uuid,
+) -> anyhow::Result<Option<ProjectStatus>> {
+    let Some(project) = Project::find_by_id(pool, project_id).await? else {
+        return Ok(None);
+    };
+
+    let counts =
+        Feedback::counts_by_repository(pool, std::slice::from_ref(&project.repository)).await?;
+    let open = counts.first().map_or(0, |counts| counts.open);
+    let status = if !project.is_active {
+        "inactive"
+    } else if open > 0 {
+        "processing"
+    } else {
+        "active"
     };
 
-    (
-        StatusCode::OK,
-        Json(ApiResponse::success(
-            "Project status retrieved".to_string(),
-            status,
-        )),
-    )
+    let last_activity = Feedback::last_activity_for_repository(pool, &project.repository)
+        .await?
+        .or(project.last_activity_at)
+        .unwrap_or(project.updated_at);
+
+    Ok(Some(ProjectStatus {
+        project_id,
+        repository: project.repository,
+        status: status.to_string(),
+        last_activity,
+    }))
 }
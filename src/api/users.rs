@@ -0,0 +1,410 @@
+// 👤 Users API - Your Own Account! 👤
+// Everyone can read and edit their own profile under /api/users/me: display
+// name, GitHub username, the LLM provider they prefer and how often they get
+// activity digests (see crate::digest). Fields left out of a PATCH stay as they
+// are; an empty GitHub username or provider clears it. Changing the password
+// takes the current one. Language lives in PUT /api/users/me/preferences.
+// Notifications (security alerts among them, see crate::security_alerts) are
+// listed newest first and marked read one at a time
+// Created with love by Aye & Hue - Make yourself at home! ✨
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        utils::{handle_error, not_found_error, validation_error},
+        ApiResponse, AppState, ValidateRequest,
+    },
+    auth::{self, MIN_PASSWORD_LENGTH},
+    config::LlmProvider,
+    database::models::{Notification, User, UserRole},
+    digest::DigestFrequency,
+    i18n,
+    middleware::auth::AuthenticatedUser,
+    security_alerts::{self, SecurityEvent},
+};
+
+/// 📏 Longest display name
+const MAX_NAME_LENGTH: usize = 255;
+
+/// 📏 Longest GitHub username GitHub allows
+const MAX_GITHUB_USERNAME_LENGTH: usize = 39;
+
+/// 📏 Most notifications listed at once
+const MAX_NOTIFICATIONS_LISTED: i64 = 50;
+
+/// 👤 The signed-in user's profile
+#[derive(Debug, Serialize)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub github_username: Option<String>,
+    pub role: UserRole,
+    pub email_verified: bool,
+    /// 🌍 Language for messages and pages (None = follow Accept-Language)
+    pub locale: Option<String>,
+    /// 📬 Activity digests: never, daily or weekly
+    pub digest_frequency: String,
+    /// 🤖 Preferred LLM provider (None = the project's default)
+    pub default_llm_provider: Option<String>,
+    /// 🔐 Whether security alerts are emailed too
+    pub security_alert_emails: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+impl From<User> for UserProfile {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            email: user.email,
+            name: user.name,
+            github_username: user.github_username,
+            role: user.role,
+            email_verified: user.email_verified,
+            locale: user.locale,
+            digest_frequency: user.digest_frequency,
+            default_llm_provider: user.default_llm_provider,
+            security_alert_emails: user.security_alert_emails,
+            created_at: user.created_at,
+            last_login_at: user.last_login_at,
+        }
+    }
+}
+
+/// ✏️ Profile changes (omitted fields stay as they are)
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub name: Option<String>,
+    /// 🐙 GitHub username (empty clears it)
+    pub github_username: Option<String>,
+    /// 🤖 openai, anthropic or custom (empty clears it)
+    pub default_llm_provider: Option<String>,
+    /// 📬 never, daily or weekly
+    pub digest_frequency: Option<String>,
+}
+
+/// 🔔 Which notifications to list
+#[derive(Debug, Default, Deserialize)]
+pub struct NotificationsQuery {
+    /// 👀 Only the unread ones
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// 🔒 A new password, with the current one to prove it's you
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// 🐙 Whether a name follows GitHub's username rules: letters, digits and
+/// single hyphens, not at either end
+fn is_github_username(username: &str) -> bool {
+    !username.is_empty()
+        && username.len() <= MAX_GITHUB_USERNAME_LENGTH
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !username.starts_with('-')
+        && !username.ends_with('-')
+        && !username.contains("--")
+}
+
+/// 🧹 None for an empty value (which clears the field)
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl ValidateRequest for UpdateProfileRequest {
+    fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if let Some(name) = &self.name {
+            if name.trim().is_empty() {
+                errors.push(i18n::t("validation-name-required"));
+            } else if name.trim().chars().count() > MAX_NAME_LENGTH {
+                errors.push(i18n::t_with(
+                    "validation-name-too-long",
+                    [("max", MAX_NAME_LENGTH.into())],
+                ));
+            }
+        }
+
+        if let Some(username) = self.github_username.as_deref().and_then(non_empty) {
+            if !is_github_username(&username) {
+                errors.push(i18n::t("validation-github-username"));
+            }
+        }
+
+        if let Some(provider) = self.default_llm_provider.as_deref().and_then(non_empty) {
+            if provider.parse::<LlmProvider>().is_err() {
+                errors.push(i18n::t_with(
+                    "validation-llm-provider",
+                    [("supported", "openai, anthropic, custom".into())],
+                ));
+            }
+        }
+
+        if let Some(frequency) = &self.digest_frequency {
+            if frequency.parse::<DigestFrequency>().is_err() {
+                let supported: Vec<&str> = DigestFrequency::ALL
+                    .iter()
+                    .map(|frequency| frequency.as_str())
+                    .collect();
+                errors.push(i18n::t_with(
+                    "unsupported-digest-frequency",
+                    [
+                        ("frequency", frequency.as_str().into()),
+                        ("supported", supported.join(", ").into()),
+                    ],
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl UpdateProfileRequest {
+    /// ✏️ Apply the (validated) changes to a user
+    fn apply(self, user: &mut User) {
+        if let Some(name) = self.name {
+            user.name = name.trim().to_string();
+        }
+        if let Some(username) = self.github_username {
+            user.github_username = non_empty(&username);
+        }
+        if let Some(provider) = self.default_llm_provider {
+            user.default_llm_provider = non_empty(&provider).and_then(|provider| {
+                provider
+                    .parse::<LlmProvider>()
+                    .ok()
+                    .map(|provider| provider.as_str().to_string())
+            });
+        }
+        if let Some(frequency) = self.digest_frequency {
+            user.digest_frequency = frequency;
+        }
+    }
+}
+
+impl ValidateRequest for ChangePasswordRequest {
+    fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.current_password.is_empty() {
+            errors.push(i18n::t("validation-password-required"));
+        }
+
+        if self.new_password.chars().count() < MIN_PASSWORD_LENGTH {
+            errors.push(i18n::t_with(
+                "validation-password-too-short",
+                [("min", MIN_PASSWORD_LENGTH.into())],
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// 👤 The signed-in user's profile
+pub async fn get_profile(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    match User::find_by_id(&app_state.db_pool, user.id).await {
+        Ok(Some(user)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("profile-retrieved"),
+                UserProfile::from(user),
+            )),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("User").into_response(),
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+/// ✏️ Change the signed-in user's profile
+pub async fn update_profile(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<UpdateProfileRequest>,
+) -> impl IntoResponse {
+    if let Err(errors) = request.validate() {
+        return validation_error(errors).into_response();
+    }
+
+    let pool = &app_state.db_pool;
+    let mut profile = match User::find_by_id(pool, user.id).await {
+        Ok(Some(profile)) => profile,
+        Ok(None) => return not_found_error("User").into_response(),
+        Err(e) => return handle_error(e).into_response(),
+    };
+    request.apply(&mut profile);
+    if let Err(e) = profile.save_profile(pool).await {
+        return handle_error(e).into_response();
+    }
+
+    info!("👤 User {} updated their profile", user.email);
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            i18n::t("profile-updated"),
+            UserProfile::from(profile),
+        )),
+    )
+        .into_response()
+}
+
+/// 🔒 Change the signed-in user's password
+pub async fn change_password(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<ChangePasswordRequest>,
+) -> impl IntoResponse {
+    if let Err(errors) = request.validate() {
+        return validation_error(errors).into_response();
+    }
+
+    let pool = &app_state.db_pool;
+    let account = match User::find_by_id(pool, user.id).await {
+        Ok(Some(account)) => account,
+        Ok(None) => return not_found_error("User").into_response(),
+        Err(e) => return handle_error(e).into_response(),
+    };
+    if !auth::verify_password(&request.current_password, &account.password_hash) {
+        info!(
+            "🔒 Wrong current password in a password change for {}",
+            user.email
+        );
+        return validation_error(vec![i18n::t("validation-current-password-incorrect")])
+            .into_response();
+    }
+
+    let saved = async {
+        let password_hash = auth::hash_password(&request.new_password)?;
+        User::set_password_hash(pool, account.id, &password_hash).await
+    };
+    match saved.await {
+        Ok(()) => {
+            info!("🔒 User {} changed their password", user.email);
+            security_alerts::raise(&app_state, account.id, SecurityEvent::PasswordChanged).await;
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success_no_data(i18n::t(
+                    "password-changed",
+                ))),
+            )
+                .into_response()
+        }
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+/// 🔔 The signed-in user's notifications, newest first
+pub async fn list_notifications(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(query): Query<NotificationsQuery>,
+) -> impl IntoResponse {
+    match Notification::list_for_user(
+        &app_state.db_pool,
+        user.id,
+        query.unread,
+        MAX_NOTIFICATIONS_LISTED,
+    )
+    .await
+    {
+        Ok(notifications) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("notifications-retrieved"),
+                notifications,
+            )),
+        )
+            .into_response(),
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+/// 👀 Mark one of the signed-in user's notifications read
+pub async fn mark_notification_read(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match Notification::mark_read(&app_state.db_pool, user.id, id).await {
+        Ok(Some(notification)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                i18n::t("notification-marked-read"),
+                notification,
+            )),
+        )
+            .into_response(),
+        Ok(None) => not_found_error("Notification").into_response(),
+        Err(e) => handle_error(e).into_response(),
+    }
+}
+
+// 🧪 Tests - A profile that checks out!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_validation() {
+        assert!(is_github_username("aye-is"));
+        assert!(!is_github_username("-aye"));
+        assert!(!is_github_username("aye--is"));
+        assert!(!is_github_username("aye_is"));
+        assert!(!is_github_username(&"a".repeat(40)));
+
+        let update = UpdateProfileRequest {
+            name: Some("Hue".to_string()),
+            github_username: Some(String::new()),
+            default_llm_provider: Some("claude".to_string()),
+            digest_frequency: Some("daily".to_string()),
+        };
+        assert!(update.validate().is_ok());
+
+        let invalid = UpdateProfileRequest {
+            name: Some("  ".to_string()),
+            github_username: Some("not valid".to_string()),
+            default_llm_provider: Some("gemini".to_string()),
+            digest_frequency: Some("hourly".to_string()),
+        };
+        assert_eq!(invalid.validate().unwrap_err().len(), 4);
+        assert!(UpdateProfileRequest::default().validate().is_ok());
+
+        let password = ChangePasswordRequest {
+            current_password: String::new(),
+            new_password: "short".to_string(),
+        };
+        assert_eq!(password.validate().unwrap_err().len(), 2);
+        println!("✅ Profile validation test passed!");
+    }
+}
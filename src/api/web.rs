@@ -1,33 +1,741 @@
 // 🎨 Web UI API - Beautiful Web Interface! 🎨
-// This module provides web UI endpoints for the Feedbacker interface
+// Server-rendered pages from the askama templates in templates/: every page
+// extends layout.html (navigation plus the maintenance banner). The projects
+// dashboard and project pages show the projects the signed-in user can see,
+// with their feedback counts, recent feedback and pull requests. The submit
+// page posts feedback through the API and follows it over the WebSocket, and
+// the diff page shows the proposed changes with approve/reject buttons. The
+// admin console (SystemAdmin only) renders the admin API's overview and users,
+// and its buttons call the admin API. The signed link at the bottom of every
+// activity digest lands on a page that turns digests off
 // Created with love by Aye & Hue! ✨
 
-use crate::api::AppState;
+use std::collections::HashMap;
+
+use crate::{
+    api::{
+        admin::{self, AdminOverview, UserSummary},
+        feedback::may_close_pull_requests,
+        AppState, PaginationMeta, PaginationParams,
+    },
+    database::models::{Feedback, FeedbackCounts, Project, User},
+    digest::{self, DigestFrequency},
+    errors,
+    feedback_claims::{self, ClaimOutcome},
+    highlighting,
+    i18n::{self, FluentArgs, FluentValue, Locale},
+    middleware::auth::{AuthenticatedUser, Permission},
+    organizations,
+    pipeline::{parse_unified_diff, DiffLineKind},
+    utils::text,
+};
+use askama::Template;
 use axum::{
-    extract::State,
-    response::{Html, IntoResponse},
+    extract::{Path, Query, State},
+    http::{StatusCode, Uri},
+    response::{Html, IntoResponse, Response},
+    Extension,
 };
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// 🕒 Feedback items listed on a project page
+const RECENT_FEEDBACK_LIMIT: i64 = 25;
+
+/// ✂️ Characters of feedback shown in listings
+const PREVIEW_LENGTH: usize = 120;
+
+/// 🌍 Merged pull requests and open suggestions listed on a public status page
+const PUBLIC_STATUS_LIMIT: i64 = 10;
+
+/// 🧱 What every page's layout needs
+struct Layout {
+    /// 🚧 Maintenance banner message, while maintenance mode is on
+    maintenance: Option<String>,
+    /// 👤 Who is signed in, if anyone
+    user_email: Option<String>,
+    /// 👑 Whether to link the admin console
+    is_admin: bool,
+    /// 🌍 Language the page is rendered in
+    locale: Locale,
+}
+
+impl Layout {
+    async fn new(app_state: &AppState, user: Option<&AuthenticatedUser>) -> Self {
+        let status = app_state.maintenance.status().await;
+        Self {
+            maintenance: status.enabled.then_some(status.message),
+            user_email: user.map(|user| user.email.clone()),
+            is_admin: user.is_some_and(|user| user.has_permission(Permission::SystemAdmin)),
+            locale: Locale::current(),
+        }
+    }
+
+    /// 📝 A message in the page's language, for templates
+    fn t(&self, id: &str) -> String {
+        self.locale.message(id)
+    }
+
+    /// 📝 A message in the page's language with its arguments filled in, for
+    /// templates (numeric values count for plurals)
+    fn t_with(&self, id: &str, args: &[(&str, String)]) -> String {
+        let args = FluentArgs::from_iter(
+            args.iter()
+                .map(|(name, value)| (*name, FluentValue::try_number(value))),
+        );
+        self.locale.message_with(id, Some(&args))
+    }
+}
+
+/// 🏠 The projects dashboard
+#[derive(Template)]
+#[template(path = "projects.html")]
+struct ProjectsPage {
+    layout: Layout,
+    projects: Vec<ProjectRow>,
+}
+
+/// 📦 One dashboard row
+struct ProjectRow {
+    id: Uuid,
+    repository: String,
+    description: Option<String>,
+    is_active: bool,
+    counts: FeedbackCounts,
+    last_activity: String,
+}
+
+/// 📊 One project and its recent feedback
+#[derive(Template)]
+#[template(path = "project_detail.html")]
+struct ProjectDetailPage {
+    layout: Layout,
+    project: Project,
+    counts: FeedbackCounts,
+    feedback: Vec<FeedbackRow>,
+}
+
+/// 📝 One recent feedback row
+struct FeedbackRow {
+    id: Uuid,
+    submitted: String,
+    status: &'static str,
+    preview: String,
+    pull_request_url: Option<String>,
+}
+
+/// 🌍 A project's opt-in public status page
+#[derive(Template)]
+#[template(path = "project_status.html")]
+struct PublicStatusPage {
+    layout: Layout,
+    title: String,
+    repository: String,
+    description: Option<String>,
+    counts: FeedbackCounts,
+    merged_total: i64,
+    merged: Vec<MergedRow>,
+    open: Vec<OpenSuggestionRow>,
+}
+
+/// 🎉 One merged pull request
+struct MergedRow {
+    number: i64,
+    title: String,
+    url: String,
+    merged: String,
+}
+
+/// 💡 One suggestion still being worked on
+struct OpenSuggestionRow {
+    submitted: String,
+    status: &'static str,
+    preview: String,
+}
+
+/// 🔍 The changes proposed for a feedback item
+#[derive(Template)]
+#[template(path = "feedback_diff.html")]
+struct DiffPage {
+    layout: Layout,
+    feedback_id: Uuid,
+    repository: String,
+    status: &'static str,
+    preview: String,
+    pull_request_url: Option<String>,
+    files: Vec<DiffFileView>,
+    /// 👍 Whether to show the approve/reject buttons
+    can_approve: bool,
+    /// 🚫 Whether rejecting also closes the pull requests
+    can_close: bool,
+}
+
+/// 📄 One file of the proposed diff
+struct DiffFileView {
+    path: String,
+    additions: usize,
+    deletions: usize,
+    lines: Vec<DiffLineView>,
+}
+
+/// 🖍️ One highlighted diff line
+struct DiffLineView {
+    class: &'static str,
+    marker: &'static str,
+    old_line: String,
+    new_line: String,
+    html: String,
+}
+
+/// 👑 The admin console's overview
+#[derive(Template)]
+#[template(path = "admin.html")]
+struct AdminPage {
+    layout: Layout,
+    overview: AdminOverview,
+}
+
+/// 👥 One page of accounts, with role and status actions
+#[derive(Template)]
+#[template(path = "admin_users.html")]
+struct AdminUsersPage {
+    layout: Layout,
+    users: Vec<UserSummary>,
+    /// 🙅 Admins can't demote or deactivate themselves
+    current_user_id: Uuid,
+    page: u32,
+    total_pages: u32,
+    total: u64,
+}
+
+/// 📝 The feedback form, with live progress once submitted
+#[derive(Template)]
+#[template(path = "submit.html")]
+struct SubmitPage {
+    layout: Layout,
+    repository: String,
+}
+
+/// 🔗 Lets project pages prefill the form's repository
+#[derive(Debug, Deserialize)]
+pub struct SubmitQuery {
+    repository: Option<String>,
+}
+
+/// 📭 A digest's unsubscribe link
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    user: Option<String>,
+    signature: Option<String>,
+}
+
+/// 🙋 A signed link claiming the feedback left with an email
+#[derive(Debug, Deserialize)]
+pub struct ClaimQuery {
+    email: Option<String>,
+    expires: Option<i64>,
+    signature: Option<String>,
+}
+
+/// 📄 A page of plain paragraphs
+#[derive(Template)]
+#[template(path = "content.html")]
+struct ContentPage {
+    layout: Layout,
+    title: String,
+    heading: String,
+    paragraphs: Vec<String>,
+}
+
+/// 🚧 Banner shown at the top of every page while maintenance mode is on
+pub async fn maintenance_banner(app_state: &AppState) -> String {
+    let status = app_state.maintenance.status().await;
+    if !status.enabled {
+        return String::new();
+    }
+    format!(
+        r#"<div role="status" style="background:#f6c343;color:#222;padding:12px;text-align:center;font-family:sans-serif">🚧 {}</div>"#,
+        escape_html(&status.message)
+    )
+}
+
+/// 🛡️ Escape text for use inside HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 🖨️ Render a template into a response
+fn render(status: StatusCode, template: &impl Template) -> Response {
+    match template.render() {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(e) => errors::error_response("Failed to render page", e.into()),
+    }
+}
+
+/// 📄 A page of plain paragraphs, from a message: its value is the heading,
+/// `.title` the title and `.body` the text (with `args` filled in)
+async fn content_page(
+    app_state: &AppState,
+    status: StatusCode,
+    message: &str,
+    args: &[(&str, &str)],
+) -> Response {
+    let layout = Layout::new(app_state, None).await;
+    let locale = layout.locale;
+    let args = FluentArgs::from_iter(args.iter().copied());
+    let page = ContentPage {
+        title: locale.message(&format!("{}.title", message)),
+        heading: locale.message(message),
+        paragraphs: vec![locale.message_with(&format!("{}.body", message), Some(&args))],
+        layout,
+    };
+    render(status, &page)
+}
+
+/// 💥 A failed page load: logged in full, the visitor gets the request id
+async fn error_page(app_state: &AppState, context: &str, error: anyhow::Error) -> Response {
+    let reference = errors::error_reference(context, &error);
+    let request_id = reference["request_id"].as_str().unwrap_or_default();
+    content_page(
+        app_state,
+        errors::ErrorKind::classify(&error).status(),
+        "page-error",
+        &[("request_id", request_id)],
+    )
+    .await
+}
+
+/// ✂️ The start of a feedback item, on one line
+fn preview(content: &str) -> String {
+    text::preview(content, PREVIEW_LENGTH)
+}
+
+/// 🕒 A timestamp as shown in tables
+fn format_time(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// 🏠 Every project the user can see, with feedback counts
+pub async fn projects_page(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Response {
+    let (member_id, organization_id) = organizations::visible_projects_filter(&user);
+    let rows = async {
+        let projects =
+            Project::list_visible(&app_state.db_pool, member_id, organization_id).await?;
+        let repositories: Vec<String> = projects.iter().map(|p| p.repository.clone()).collect();
+        let mut counts: HashMap<String, FeedbackCounts> =
+            Feedback::counts_by_repository(&app_state.db_pool, &repositories)
+                .await?
+                .into_iter()
+                .map(|counts| (counts.repository.clone(), counts))
+                .collect();
+
+        Ok::<_, anyhow::Error>(
+            projects
+                .into_iter()
+                .map(|project| ProjectRow {
+                    counts: counts.remove(&project.repository).unwrap_or_default(),
+                    last_activity: project
+                        .last_activity_at
+                        .map(format_time)
+                        .unwrap_or_else(|| "-".to_string()),
+                    id: project.id,
+                    repository: project.repository,
+                    description: project.description,
+                    is_active: project.is_active,
+                })
+                .collect(),
+        )
+    };
+
+    match rows.await {
+        Ok(projects) => {
+            let page = ProjectsPage {
+                layout: Layout::new(&app_state, Some(&user)).await,
+                projects,
+            };
+            render(StatusCode::OK, &page)
+        }
+        Err(e) => error_page(&app_state, "Failed to load the projects dashboard", e).await,
+    }
+}
+
+/// 📊 A project's feedback counts, recent feedback and pull requests
+pub async fn project_detail_page(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let pool = &app_state.db_pool;
+    let loaded = async {
+        // 🙈 Projects the user can't see look the same as missing ones
+        let Some(project) = Project::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        if organizations::project_role_for(pool, &user, &project)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let counts =
+            Feedback::counts_by_repository(pool, std::slice::from_ref(&project.repository))
+                .await?
+                .pop()
+                .unwrap_or_default();
+        let feedback =
+            Feedback::list_recent_for_repository(pool, &project.repository, RECENT_FEEDBACK_LIMIT)
+                .await?
+                .into_iter()
+                .map(|feedback| FeedbackRow {
+                    id: feedback.id,
+                    submitted: format_time(feedback.created_at),
+                    status: feedback.status.as_str(),
+                    preview: preview(&feedback.content),
+                    pull_request_url: feedback.pull_request_url,
+                })
+                .collect();
+        Ok::<_, anyhow::Error>(Some((project, counts, feedback)))
+    };
+
+    match loaded.await {
+        Ok(Some((project, counts, feedback))) => {
+            let page = ProjectDetailPage {
+                layout: Layout::new(&app_state, Some(&user)).await,
+                project,
+                counts,
+                feedback,
+            };
+            render(StatusCode::OK, &page)
+        }
+        Ok(None) => {
+            content_page(
+                &app_state,
+                StatusCode::NOT_FOUND,
+                "page-project-not-found",
+                &[],
+            )
+            .await
+        }
+        Err(e) => error_page(&app_state, &format!("Failed to load project {}", id), e).await,
+    }
+}
+
+/// 🌍 A project's public status page, for projects that turned it on
+pub async fn public_status_page(
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> Response {
+    let pool = &app_state.db_pool;
+    let loaded = async {
+        let Some(project) = Project::find_by_public_slug(pool, &slug).await? else {
+            return Ok(None);
+        };
+        let repository = &project.repository;
+
+        let counts = Feedback::counts_by_repository(pool, std::slice::from_ref(repository))
+            .await?
+            .pop()
+            .unwrap_or_default();
+        let merged_total = Feedback::count_merged(pool, repository).await?;
+        let merged = Feedback::list_merged_pull_requests(pool, repository, PUBLIC_STATUS_LIMIT)
+            .await?
+            .into_iter()
+            .map(|pull_request| MergedRow {
+                number: pull_request.number,
+                title: pull_request.title,
+                url: pull_request.url,
+                merged: format_time(pull_request.merged_at),
+            })
+            .collect();
+        let open = Feedback::list_open_for_repository(pool, repository, PUBLIC_STATUS_LIMIT)
+            .await?
+            .into_iter()
+            .map(|feedback| OpenSuggestionRow {
+                submitted: format_time(feedback.created_at),
+                status: feedback.status.as_str(),
+                preview: preview(&feedback.content),
+            })
+            .collect();
+
+        Ok::<_, anyhow::Error>(Some(PublicStatusPage {
+            layout: Layout::new(&app_state, None).await,
+            title: i18n::t_with("status-page", [("repository", repository.as_str().into())]),
+            repository: project.repository,
+            description: project.description,
+            counts,
+            merged_total,
+            merged,
+            open,
+        }))
+    };
+
+    match loaded.await {
+        Ok(Some(page)) => render(StatusCode::OK, &page),
+        // 🙈 Disabled pages look the same as missing ones
+        Ok(None) => {
+            content_page(
+                &app_state,
+                StatusCode::NOT_FOUND,
+                "page-status-not-found",
+                &[],
+            )
+            .await
+        }
+        Err(e) => {
+            error_page(
+                &app_state,
+                &format!("Failed to load public status page {}", slug),
+                e,
+            )
+            .await
+        }
+    }
+}
+
+/// 🖍️ A stored unified diff, split into highlighted files
+fn diff_files(diff: &str) -> Vec<DiffFileView> {
+    parse_unified_diff(diff)
+        .into_iter()
+        .map(|file| {
+            let highlighted = highlighting::highlight_diff(&file);
+            let number = |line: Option<usize>| line.map(|n| n.to_string()).unwrap_or_default();
+            DiffFileView {
+                additions: file.additions(),
+                deletions: file.deletions(),
+                lines: file
+                    .lines
+                    .iter()
+                    .zip(highlighted)
+                    .map(|(line, html)| {
+                        let (class, marker) = match line.kind {
+                            DiffLineKind::Hunk => ("diff-hunk", ""),
+                            DiffLineKind::Context => ("diff-context", " "),
+                            DiffLineKind::Added => ("diff-added", "+"),
+                            DiffLineKind::Removed => ("diff-removed", "-"),
+                        };
+                        DiffLineView {
+                            class,
+                            marker,
+                            old_line: number(line.old_line),
+                            new_line: number(line.new_line),
+                            html,
+                        }
+                    })
+                    .collect(),
+                path: file.path,
+            }
+        })
+        .collect()
+}
+
+/// 🔍 The diff proposed for a feedback item, highlighted, with approve/reject
+/// buttons for users who may approve pull requests
+pub async fn feedback_diff_page(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let loaded = async {
+        // 🙈 Feedback the user can't see looks the same as missing feedback
+        let Some(feedback) = Feedback::find_by_id(&app_state.db_pool, id).await? else {
+            return Ok(None);
+        };
+        if !organizations::can_view_feedback(&app_state.db_pool, &user, &feedback).await? {
+            return Ok(None);
+        }
+        let can_close = may_close_pull_requests(&app_state.db_pool, &user, &feedback).await?;
+        Ok::<_, anyhow::Error>(Some((feedback, can_close)))
+    };
+
+    match loaded.await {
+        Ok(Some((feedback, can_close))) => {
+            let page = DiffPage {
+                layout: Layout::new(&app_state, Some(&user)).await,
+                feedback_id: feedback.id,
+                status: feedback.status.as_str(),
+                preview: preview(&feedback.content),
+                files: feedback.proposed_diff().map(diff_files).unwrap_or_default(),
+                can_approve: user.has_permission(Permission::ApprovePullRequests),
+                can_close,
+                repository: feedback.repository,
+                pull_request_url: feedback.pull_request_url,
+            };
+            render(StatusCode::OK, &page)
+        }
+        Ok(None) => {
+            content_page(
+                &app_state,
+                StatusCode::NOT_FOUND,
+                "page-feedback-not-found",
+                &[],
+            )
+            .await
+        }
+        Err(e) => {
+            error_page(
+                &app_state,
+                &format!("Failed to load the diff for feedback {}", id),
+                e,
+            )
+            .await
+        }
+    }
+}
+
+/// 👑 Job queues, dead letters, recent errors and rate-limit hits
+pub async fn admin_page(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Response {
+    match admin::load_overview(&app_state).await {
+        Ok(overview) => {
+            let page = AdminPage {
+                layout: Layout::new(&app_state, Some(&user)).await,
+                overview,
+            };
+            render(StatusCode::OK, &page)
+        }
+        Err(e) => error_page(&app_state, "Failed to load the admin console", e).await,
+    }
+}
+
+/// 👥 Accounts, newest first, with role and activation buttons
+pub async fn admin_users_page(
+    State(app_state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(pagination): Query<PaginationParams>,
+) -> Response {
+    let pagination = pagination.validate();
+
+    match User::list(
+        &app_state.db_pool,
+        "created_at DESC",
+        pagination.limit,
+        pagination.offset(),
+    )
+    .await
+    {
+        Ok((users, total)) => {
+            let meta = PaginationMeta::new(pagination.page, pagination.limit, total);
+            let page = AdminUsersPage {
+                layout: Layout::new(&app_state, Some(&user)).await,
+                users: users.into_iter().map(UserSummary::from).collect(),
+                current_user_id: user.id,
+                page: meta.page,
+                total_pages: meta.total_pages.max(1),
+                total,
+            };
+            render(StatusCode::OK, &page)
+        }
+        Err(e) => error_page(&app_state, "Failed to load users", e).await,
+    }
+}
+
+/// 📝 The feedback form; the page signs in and submits through the JSON API
+pub async fn submit_page(
+    State(app_state): State<AppState>,
+    Query(query): Query<SubmitQuery>,
+) -> Response {
+    let page = SubmitPage {
+        layout: Layout::new(&app_state, None).await,
+        repository: query.repository.unwrap_or_default(),
+    };
+    render(StatusCode::OK, &page)
+}
+
+/// 📭 Turn off a user's activity digests from the signed link in one
+pub async fn digest_unsubscribe_page(
+    State(app_state): State<AppState>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Response {
+    let secret = app_state.config.load().auth.jwt_secret.clone();
+    let user_id = query
+        .user
+        .and_then(|user| user.parse::<Uuid>().ok())
+        .filter(|user_id| {
+            digest::verify_unsubscribe(&secret, *user_id, &query.signature.unwrap_or_default())
+        });
+    let Some(user_id) = user_id else {
+        return content_page(
+            &app_state,
+            StatusCode::BAD_REQUEST,
+            "page-digest-link-invalid",
+            &[],
+        )
+        .await;
+    };
+
+    let frequency = DigestFrequency::Never.as_str();
+    match User::set_digest_frequency(&app_state.db_pool, user_id, frequency).await {
+        Ok(()) => content_page(&app_state, StatusCode::OK, "page-digest-unsubscribed", &[]).await,
+        Err(e) => error_page(&app_state, "Failed to unsubscribe from digests", e).await,
+    }
+}
+
+/// 🙋 Claim the feedback left with an email from the signed link sent to it
+pub async fn feedback_claim_page(
+    State(app_state): State<AppState>,
+    Query(query): Query<ClaimQuery>,
+) -> Response {
+    let secret = app_state.config.load().auth.jwt_secret.clone();
+    let email = query.email.filter(|email| {
+        feedback_claims::verify_claim(
+            &secret,
+            email,
+            query.expires.unwrap_or_default(),
+            &query.signature.unwrap_or_default(),
+            chrono::Utc::now(),
+        )
+    });
+    let Some(email) = email else {
+        return content_page(
+            &app_state,
+            StatusCode::BAD_REQUEST,
+            "page-feedback-claim-link-invalid",
+            &[],
+        )
+        .await;
+    };
 
-pub async fn projects_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>🏠 Projects Dashboard</h1><p>Coming soon...</p>")
+    let message = match feedback_claims::confirm(&app_state.db_pool, &email).await {
+        Ok(ClaimOutcome::Attached(_)) => "page-feedback-claimed",
+        Ok(ClaimOutcome::Confirmed) => "page-feedback-claim-confirmed",
+        Err(e) => return error_page(&app_state, "Failed to claim feedback", e).await,
+    };
+    content_page(&app_state, StatusCode::OK, message, &[("email", &email)]).await
 }
 
-pub async fn project_detail_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>📊 Project Details</h1><p>Coming soon...</p>")
+pub async fn login_page(State(app_state): State<AppState>) -> Response {
+    content_page(&app_state, StatusCode::OK, "page-login", &[]).await
 }
 
-pub async fn login_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>🔐 Login</h1><p>Coming soon...</p>")
+pub async fn register_page(State(app_state): State<AppState>) -> Response {
+    content_page(&app_state, StatusCode::OK, "page-register", &[]).await
 }
 
-pub async fn register_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>📝 Register</h1><p>Coming soon...</p>")
+pub async fn docs_page(State(app_state): State<AppState>) -> Response {
+    content_page(&app_state, StatusCode::OK, "page-docs", &[]).await
 }
 
-pub async fn docs_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>📚 Documentation</h1><p>Coming soon...</p>")
+pub async fn about_page(State(app_state): State<AppState>) -> Response {
+    content_page(&app_state, StatusCode::OK, "page-about", &[]).await
 }
 
-pub async fn about_page(State(_app_state): State<AppState>) -> impl IntoResponse {
-    Html("<h1>ℹ️ About Feedbacker</h1><p>AI-powered repository management by Aye & Hue!</p>")
+/// 🔍 The page for paths nothing is served at (unknown /api paths get JSON instead)
+pub async fn not_found_page(State(app_state): State<AppState>, uri: Uri) -> Response {
+    content_page(
+        &app_state,
+        StatusCode::NOT_FOUND,
+        "page-not-found",
+        &[("path", uri.path())],
+    )
+    .await
 }
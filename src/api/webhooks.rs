@@ -1,31 +1,658 @@
 // 🪝 Webhooks API - GitHub Integration Events! 🪝
-// This module handles GitHub webhook endpoints
+// This module handles GitHub webhook endpoints. Deliveries are read as typed
+// events (see github::webhooks) by their `X-GitHub-Event` header. Pull request and check suite
+// events for PRs we opened become prompt experiment outcomes (merged, CI passed).
+// Push events drop the cached clone of the commit pushed over, so the next run
+// reads the new files, and re-scan projects that opted in (`scans.on_push`)
+// when a push to the default branch touches their path. Repositories with a
+// project that opted into `pull_requests.auto_rebase` get their open Feedbacker
+// PRs brought up to date (see pipeline::rebase). GitHub App `installation` and
+// `installation_repositories` events keep track of which repositories the app
+// can reach, deactivating projects it lost (see crate::installations).
+// Pings are answered with a pong that lists the events the hook should send
+// but doesn't; every delivery lands in the delivery log (see
+// middleware::webhook_deliveries)
 // Created with love by Aye & Hue! ✨
 
-use crate::api::{ApiResponse, AppState};
+use crate::{
+    api::{utils::validation_error, ApiResponse, AppState},
+    database::models::{Feedback, Project, PromptMetric, PromptOutcome},
+    errors,
+    feedback_trace::{self, Stage},
+    github::{
+        git::CloneCache,
+        webhooks::{
+            InstallationEvent, InstallationRepositoriesEvent, PingEvent, PushEvent, RepositoryName,
+            WebhookEvent,
+        },
+    },
+    i18n,
+    installations::{self, InstallationAction, InstallationChange},
+    jobs::scheduler::ScanRunner,
+    models::PathScope,
+    pipeline::rebase,
+};
 use axum::{
     extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
-use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// 📏 Commits a push event lists at most; longer pushes may have touched anything
+const MAX_PUSH_COMMITS: usize = 2048;
+
+/// 📋 Events this endpoint acts on, which a repository webhook should send
+pub const GITHUB_EVENTS: [&str; 3] = ["pull_request", "check_suite", "push"];
+
+/// 🏓 What a ping is answered with
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Pong {
+    pub hook_id: Option<i64>,
+    pub zen: String,
+    /// 📋 Events the webhook sends
+    pub events: Vec<String>,
+    /// ⚠️ Events the endpoint needs that the webhook doesn't send
+    pub missing_events: Vec<String>,
+}
+
+impl Pong {
+    /// 🏓 The answer to a ping, checked against the events an endpoint needs
+    pub fn new(ping: &PingEvent, wanted: &[&str]) -> Self {
+        let events = ping
+            .hook
+            .as_ref()
+            .map(|hook| hook.events.clone())
+            .unwrap_or_default();
+        // 🤷 Without the hook we can't tell what it sends; `*` sends everything
+        let missing_events = if ping.hook.is_none() || events.iter().any(|event| event == "*") {
+            Vec::new()
+        } else {
+            wanted
+                .iter()
+                .filter(|wanted| !events.iter().any(|event| event == *wanted))
+                .map(|wanted| wanted.to_string())
+                .collect()
+        };
+        Pong {
+            hook_id: ping.hook_id.or(ping.hook.as_ref().map(|hook| hook.id)),
+            zen: ping.zen.clone(),
+            events,
+            missing_events,
+        }
+    }
+}
+
+impl IntoResponse for Pong {
+    fn into_response(self) -> Response {
+        // 📬 The message is what the delivery log shows, so it names what's missing
+        let message = if self.missing_events.is_empty() {
+            i18n::t("webhook-pong")
+        } else {
+            let missing = self.missing_events.join(", ");
+            warn!("🏓 Webhook {:?} doesn't send {}", self.hook_id, missing);
+            i18n::t_with("webhook-pong-missing-events", [("events", missing.into())])
+        };
+        (StatusCode::OK, Json(ApiResponse::success(message, self))).into_response()
+    }
+}
+
+/// 📤 A push to a branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchPush {
+    pub branch: String,
+    pub before: String,
+    pub after: String,
+    /// 🌳 Whether it went to the repository's default branch
+    pub default_branch: bool,
+    /// 🗑️ Whether the branch was deleted
+    pub deleted: bool,
+    /// 📄 Paths it changed (None when unknown: force pushes, huge pushes)
+    pub paths: Option<BTreeSet<String>>,
+}
+
+impl BranchPush {
+    /// 📤 The branch push a push event reports (None for tag pushes)
+    pub fn from_event(event: &PushEvent) -> Option<Self> {
+        let branch = event.git_ref.strip_prefix("refs/heads/")?;
+        let default_branch = event.repository.default_branch.as_deref() == Some(branch);
+        let paths = (!event.forced && event.commits.len() < MAX_PUSH_COMMITS).then(|| {
+            event
+                .commits
+                .iter()
+                .flat_map(|commit| [&commit.added, &commit.removed, &commit.modified])
+                .flatten()
+                .cloned()
+                .collect()
+        });
+        Some(BranchPush {
+            branch: branch.to_string(),
+            before: event.before.clone(),
+            after: event.after.clone(),
+            default_branch,
+            deleted: event.deleted,
+            paths,
+        })
+    }
+
+    /// 🔍 Whether the push may have changed files inside a scope
+    pub fn touches(&self, scope: &PathScope) -> bool {
+        match &self.paths {
+            None => true,
+            Some(paths) => paths.iter().any(|path| scope.contains(path)),
+        }
+    }
+}
 
-#[derive(Debug, Deserialize)]
-pub struct GitHubWebhookPayload {
-    pub action: String,
-    pub repository: serde_json::Value,
-    pub pull_request: Option<serde_json::Value>,
+/// 📤 What a push event led to
+#[derive(Debug, Default, Serialize)]
+pub struct PushOutcome {
+    /// 🧹 Whether a cached clone of the old commit was dropped
+    pub invalidated_clone: bool,
+    /// 🎯 Projects whose files the push touched
+    pub affected_projects: Vec<Uuid>,
+    /// 🩺 Projects a scan was queued for
+    pub queued_scans: Vec<Uuid>,
+    /// 🔀 Whether a rebase of the repository's open PRs was queued
+    pub queued_rebase: bool,
+}
+
+/// 📊 An outcome reported for one pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequestOutcome {
+    pub number: u64,
+    pub metric: PromptMetric,
+    pub value: bool,
+}
+
+/// 📊 Outcomes an event reports: a closed PR (merged or not), or a finished check suite
+/// Neutral, skipped, and cancelled suites don't say anything about the change
+pub fn outcomes(event: &WebhookEvent) -> Vec<PullRequestOutcome> {
+    match event {
+        WebhookEvent::PullRequest(event) if event.action == "closed" => {
+            vec![PullRequestOutcome {
+                number: event.pull_request.number,
+                metric: PromptMetric::PrMerged,
+                value: event.pull_request.merged,
+            }]
+        }
+        WebhookEvent::CheckSuite(event) if event.action == "completed" => {
+            let passed = match event.check_suite.conclusion.as_deref() {
+                Some("success") => true,
+                Some("failure" | "timed_out" | "action_required") => false,
+                _ => return Vec::new(),
+            };
+            event
+                .check_suite
+                .pull_requests
+                .iter()
+                .map(|pull_request| PullRequestOutcome {
+                    number: pull_request.number,
+                    metric: PromptMetric::CiPassed,
+                    value: passed,
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// 🏷️ `owner/repo` names of the repositories an installation event lists
+fn full_names(repositories: &[RepositoryName]) -> Vec<String> {
+    repositories
+        .iter()
+        .map(|repository| repository.full_name.clone())
+        .collect()
+}
+
+/// 🔌 The installation change an `installation` event reports
+pub fn installation_change(event: &InstallationEvent) -> InstallationChange {
+    let action = match event.action.as_str() {
+        "created" => InstallationAction::Created,
+        "deleted" => InstallationAction::Deleted,
+        "suspend" => InstallationAction::Suspended,
+        "unsuspend" => InstallationAction::Unsuspended,
+        _ => InstallationAction::Other,
+    };
+    let added = match action {
+        InstallationAction::Created => full_names(&event.repositories),
+        _ => Vec::new(),
+    };
+    InstallationChange {
+        installation_id: event.installation.id,
+        account: event.installation.account.login.clone(),
+        repository_selection: event
+            .installation
+            .repository_selection
+            .clone()
+            .unwrap_or_else(|| "selected".to_string()),
+        action,
+        added,
+        removed: Vec::new(),
+    }
+}
+
+/// 📋 The installation change an `installation_repositories` event reports
+pub fn repositories_change(event: &InstallationRepositoriesEvent) -> InstallationChange {
+    InstallationChange {
+        installation_id: event.installation.id,
+        account: event.installation.account.login.clone(),
+        repository_selection: event
+            .repository_selection
+            .clone()
+            .or_else(|| event.installation.repository_selection.clone())
+            .unwrap_or_else(|| "selected".to_string()),
+        action: InstallationAction::RepositoriesChanged,
+        added: full_names(&event.repositories_added),
+        removed: full_names(&event.repositories_removed),
+    }
 }
 
 pub async fn github_webhook(
-    State(_app_state): State<AppState>,
-    Json(_payload): Json<GitHubWebhookPayload>,
-) -> impl IntoResponse {
-    // TODO: Implement GitHub webhook processing
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<serde_json::Value>,
+) -> Response {
+    let event = match WebhookEvent::parse(WebhookEvent::name(&headers), payload) {
+        Ok(event) => event,
+        Err(e) => {
+            warn!("⚠️ Rejected webhook delivery: {:#}", e);
+            return validation_error(vec![format!("{:#}", e)]).into_response();
+        }
+    };
+
+    match &event {
+        WebhookEvent::PullRequest(_) | WebhookEvent::CheckSuite(_) => {
+            let repository = event
+                .repository()
+                .map(|repository| repository.full_name.as_str())
+                .unwrap_or_default();
+            if let Err(e) = record_outcomes(&app_state, repository, outcomes(&event)).await {
+                return errors::error_response("Failed to record webhook outcome", e);
+            }
+        }
+        WebhookEvent::Push(push) => {
+            if let Some(branch_push) = BranchPush::from_event(push) {
+                let repository = &push.repository.full_name;
+                return match handle_push(&app_state, repository, &branch_push).await {
+                    Ok(outcome) => (
+                        StatusCode::OK,
+                        Json(ApiResponse::success(
+                            i18n::t("webhook-push-processed"),
+                            outcome,
+                        )),
+                    )
+                        .into_response(),
+                    Err(e) => errors::error_response("Failed to process push", e),
+                };
+            }
+        }
+        WebhookEvent::Installation(installation) => {
+            return sync_installation(&app_state, installation_change(installation)).await;
+        }
+        WebhookEvent::InstallationRepositories(repositories) => {
+            return sync_installation(&app_state, repositories_change(repositories)).await;
+        }
+        WebhookEvent::Ping(ping) => return Pong::new(ping, &GITHUB_EVENTS).into_response(),
+        // 🤷 Issue events go to /api/webhook/issues
+        WebhookEvent::Issues(_) | WebhookEvent::IssueComment(_) | WebhookEvent::Unsupported => {}
+    }
+
     (
         StatusCode::OK,
-        Json(ApiResponse::<()>::success_no_data(  // 🔧 Added explicit type annotation
+        Json(ApiResponse::<()>::success_no_data(
+            // 🔧 Added explicit type annotation
             "Webhook processed".to_string(),
         )),
     )
+        .into_response()
+}
+
+/// 📊 Record the outcomes of the pull requests we opened (others are ignored)
+async fn record_outcomes(
+    app_state: &AppState,
+    repository: &str,
+    outcomes: Vec<PullRequestOutcome>,
+) -> anyhow::Result<()> {
+    for outcome in outcomes {
+        let span = Stage::Webhook.span(None);
+        feedback_trace::traced(span.clone(), async {
+            match Feedback::find_by_pull_request(&app_state.db_pool, repository, outcome.number)
+                .await?
+            {
+                Some(feedback) => {
+                    feedback_trace::attach_feedback(&span, feedback.id);
+                    info!(
+                        "📊 {}#{} {} = {} (feedback {})",
+                        repository,
+                        outcome.number,
+                        outcome.metric.as_str(),
+                        outcome.value,
+                        feedback.id
+                    );
+                    PromptOutcome::record(
+                        &app_state.db_pool,
+                        feedback.id,
+                        outcome.metric,
+                        outcome.value,
+                    )
+                    .await
+                }
+                // 🤷 Not one of our pull requests
+                None => Ok(()),
+            }
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// 🔌 Apply an installation change and refresh the projects it switched
+async fn sync_installation(app_state: &AppState, change: InstallationChange) -> Response {
+    match installations::sync(&app_state.db_pool, &change).await {
+        Ok(outcome) => {
+            for project_id in outcome.deactivated.iter().chain(&outcome.reactivated) {
+                app_state.cache.project_changed(*project_id).await;
+            }
+            info!(
+                "🔌 Installation {} ({}) {:?}: {} projects deactivated, {} reactivated",
+                change.installation_id,
+                change.account,
+                change.action,
+                outcome.deactivated.len(),
+                outcome.reactivated.len()
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    i18n::t("webhook-installation-processed"),
+                    outcome,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => errors::error_response("Failed to process installation event", e),
+    }
+}
+
+/// 📤 Drop the clone of the commit pushed over, re-scan opted-in projects
+/// whose files a push to the default branch touched, and queue a rebase of the
+/// open PRs when a project asks for it
+async fn handle_push(
+    app_state: &AppState,
+    repository: &str,
+    push: &BranchPush,
+) -> anyhow::Result<PushOutcome> {
+    let mut outcome = PushOutcome::default();
+
+    // 🧹 Clones are keyed by commit, so only the old one went stale
+    let github = app_state.config.load().github.clone();
+    let cache = CloneCache::new(&github.clone_cache_dir, github.clone_cache_size);
+    let (cached_repository, before) = (repository.to_string(), push.before.clone());
+    outcome.invalidated_clone =
+        tokio::task::spawn_blocking(move || cache.invalidate(&cached_repository, &before)).await?;
+
+    if push.deleted || !push.default_branch {
+        return Ok(outcome);
+    }
+
+    let runner = ScanRunner::new(app_state);
+    let mut auto_rebase = false;
+    for project in Project::list_by_repository(&app_state.db_pool, repository).await? {
+        let settings = match project.settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                warn!("⚠️ Skipping push for {}: {:#}", project.repository, e);
+                continue;
+            }
+        };
+        auto_rebase |= settings.pull_requests.auto_rebase;
+        let touched = settings
+            .scope()
+            .map(|scope| push.touches(&scope))
+            .unwrap_or(false);
+        if !touched {
+            continue;
+        }
+        outcome.affected_projects.push(project.id);
+
+        if settings.scans.enabled
+            && settings.scans.on_push
+            && runner.scans_allowed(&project).await
+            && runner.queue_scan(&project).await?
+        {
+            outcome.queued_scans.push(project.id);
+        }
+    }
+    // 🔀 Any push can leave open PRs behind, whichever files it touched
+    if auto_rebase {
+        outcome.queued_rebase = rebase::queue_rebase(app_state, repository).await?;
+    }
+
+    info!(
+        "📤 Push to {}@{} ({}..{}): {} projects touched, {} scans queued{}",
+        repository,
+        push.branch,
+        short(&push.before),
+        short(&push.after),
+        outcome.affected_projects.len(),
+        outcome.queued_scans.len(),
+        if outcome.queued_rebase {
+            ", open PRs queued for rebase"
+        } else {
+            ""
+        }
+    );
+    Ok(outcome)
+}
+
+/// ✂️ Short form of a SHA for logs
+fn short(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+// 🧪 Tests - Every push lands where it should!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn event(name: &str, payload: Value) -> WebhookEvent {
+        WebhookEvent::parse(name, payload).unwrap()
+    }
+
+    fn push(payload: Value) -> Option<BranchPush> {
+        match event("push", payload) {
+            WebhookEvent::Push(push) => BranchPush::from_event(&push),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_push_event() {
+        let push_to_main = push(json!({
+            "ref": "refs/heads/main",
+            "before": "1111111111111111111111111111111111111111",
+            "after": "2222222222222222222222222222222222222222",
+            "repository": { "full_name": "aye-is/feedbacker", "default_branch": "main" },
+            "commits": [
+                { "added": ["crates/foo/src/new.rs"], "removed": [], "modified": ["README.md"] },
+                { "added": [], "removed": ["docs/old.md"], "modified": [] }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(push_to_main.branch, "main");
+        assert!(push_to_main.default_branch);
+        assert_eq!(push_to_main.paths.as_ref().unwrap().len(), 3);
+        assert!(push_to_main.touches(&PathScope::new("crates/foo").unwrap()));
+        assert!(push_to_main.touches(&PathScope::whole_repository()));
+        assert!(!push_to_main.touches(&PathScope::new("crates/bar").unwrap()));
+
+        // 💥 A force push may have changed anything
+        let forced = push(json!({
+            "ref": "refs/heads/feature",
+            "before": "1111111",
+            "after": "3333333",
+            "forced": true,
+            "repository": { "full_name": "aye-is/feedbacker", "default_branch": "main" }
+        }))
+        .unwrap();
+        assert!(!forced.default_branch);
+        assert!(forced.touches(&PathScope::new("crates/bar").unwrap()));
+
+        // 🏷️ Tags aren't branch pushes
+        let tag = push(json!({
+            "ref": "refs/tags/v1.0.0",
+            "before": "1111111",
+            "after": "3333333",
+            "repository": { "full_name": "aye-is/feedbacker" }
+        }));
+        assert!(tag.is_none());
+        println!("✅ Push event test passed!");
+    }
+
+    #[test]
+    fn test_pull_request_outcomes() {
+        let repository = json!({ "full_name": "aye-is/feedbacker" });
+        let closed = event(
+            "pull_request",
+            json!({
+                "action": "closed",
+                "repository": repository,
+                "pull_request": { "number": 7, "merged": true }
+            }),
+        );
+        assert_eq!(
+            outcomes(&closed),
+            vec![PullRequestOutcome {
+                number: 7,
+                metric: PromptMetric::PrMerged,
+                value: true
+            }]
+        );
+        let opened = event(
+            "pull_request",
+            json!({
+                "action": "opened",
+                "repository": repository,
+                "pull_request": { "number": 8 }
+            }),
+        );
+        assert!(outcomes(&opened).is_empty());
+
+        let failed = event(
+            "check_suite",
+            json!({
+                "action": "completed",
+                "repository": repository,
+                "check_suite": { "conclusion": "timed_out", "pull_requests": [{ "number": 7 }, { "number": 9 }] }
+            }),
+        );
+        let reported = outcomes(&failed);
+        assert_eq!(reported.len(), 2);
+        assert!(reported
+            .iter()
+            .all(|outcome| outcome.metric == PromptMetric::CiPassed && !outcome.value));
+        let neutral = event(
+            "check_suite",
+            json!({
+                "action": "completed",
+                "repository": repository,
+                "check_suite": { "conclusion": "neutral", "pull_requests": [{ "number": 7 }] }
+            }),
+        );
+        assert!(outcomes(&neutral).is_empty());
+        println!("✅ Pull request outcome test passed!");
+    }
+
+    #[test]
+    fn test_installation_change() {
+        let created = event(
+            "installation",
+            json!({
+                "action": "created",
+                "installation": {
+                    "id": 42,
+                    "account": { "login": "aye-is" },
+                    "repository_selection": "selected"
+                },
+                "repositories": [{ "full_name": "aye-is/feedbacker" }, { "full_name": "aye-is/smart-tree" }]
+            }),
+        );
+        let WebhookEvent::Installation(created) = created else {
+            panic!("not an installation event");
+        };
+        let change = installation_change(&created);
+        assert_eq!(change.installation_id, 42);
+        assert_eq!(change.account, "aye-is");
+        assert_eq!(change.action, InstallationAction::Created);
+        assert_eq!(change.added, vec!["aye-is/feedbacker", "aye-is/smart-tree"]);
+        assert!(change.removed.is_empty());
+
+        let removed = event(
+            "installation_repositories",
+            json!({
+                "action": "removed",
+                "installation": { "id": 42, "account": { "login": "aye-is" } },
+                "repository_selection": "selected",
+                "repositories_added": [],
+                "repositories_removed": [{ "full_name": "aye-is/smart-tree" }]
+            }),
+        );
+        let WebhookEvent::InstallationRepositories(removed) = removed else {
+            panic!("not an installation_repositories event");
+        };
+        let change = repositories_change(&removed);
+        assert_eq!(change.action, InstallationAction::RepositoriesChanged);
+        assert_eq!(change.removed, vec!["aye-is/smart-tree"]);
+        assert_eq!(change.repository_selection, "selected");
+
+        let suspended = event(
+            "installation",
+            json!({
+                "action": "suspend",
+                "installation": { "id": 42, "account": { "login": "aye-is" }, "repository_selection": "all" }
+            }),
+        );
+        let WebhookEvent::Installation(suspended) = suspended else {
+            panic!("not an installation event");
+        };
+        let change = installation_change(&suspended);
+        assert_eq!(change.action, InstallationAction::Suspended);
+        assert_eq!(change.repository_selection, "all");
+        assert!(change.added.is_empty());
+        println!("✅ Installation change test passed!");
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        let ping = |hook: Value| {
+            let WebhookEvent::Ping(ping) = event(
+                "ping",
+                json!({ "zen": "Design for failure.", "hook_id": 7, "hook": hook }),
+            ) else {
+                panic!("not a ping event");
+            };
+            ping
+        };
+
+        let pong = Pong::new(
+            &ping(json!({ "id": 7, "events": ["push"] })),
+            &GITHUB_EVENTS,
+        );
+        assert_eq!(pong.hook_id, Some(7));
+        assert_eq!(pong.zen, "Design for failure.");
+        assert_eq!(pong.missing_events, vec!["pull_request", "check_suite"]);
+
+        let everything = Pong::new(&ping(json!({ "id": 7, "events": ["*"] })), &GITHUB_EVENTS);
+        assert!(everything.missing_events.is_empty());
+        let issues = Pong::new(&ping(json!({ "id": 7, "events": ["issues"] })), &["issues"]);
+        assert!(issues.missing_events.is_empty());
+        let unknown = Pong::new(&ping(Value::Null), &GITHUB_EVENTS);
+        assert!(unknown.missing_events.is_empty());
+        println!("✅ Ping pong test passed!");
+    }
 }
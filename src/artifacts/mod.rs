@@ -0,0 +1,460 @@
+// 🗄️ Pipeline Artifacts - The Evidence, Kept for Later! 🗄️
+// When a project run finishes, what it produced is written to ARTIFACT_STORAGE
+// (a local directory or an S3-compatible bucket): the final diff, the LLM's change
+// plan and the output of the sandbox's test runs. The feedback's metadata lists
+// them under "artifacts", and GET /api/feedback/:id/artifacts hands out links
+// signed with JWT_SECRET that work for ARTIFACT_LINK_MINUTES without logging in
+// (bucket downloads redirect once more, to a presigned URL). The nightly cleanup
+// deletes artifacts older than ARTIFACT_RETENTION_DAYS
+// Created with love by Aye & Hue - Receipts for every run! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::{ArtifactConfig, ArtifactStorage};
+use crate::database::models::{Feedback, FeedbackEvent};
+use crate::pipeline::planning::PLAN_METADATA_KEY;
+use crate::utils::signed_links;
+
+pub mod s3; // 🪣 S3-compatible buckets, signed with SigV4
+
+use s3::S3Bucket;
+
+/// 🏷️ Feedback metadata key listing a feedback's stored artifacts
+pub const ARTIFACTS_METADATA_KEY: &str = "artifacts";
+
+/// 📄 What a run leaves behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// 🔍 Unified diff of the proposed changes
+    Diff,
+    /// 🗺️ The LLM's multi-file change plan
+    Plan,
+    /// 📦 Commands run in the sandbox, with their output
+    SandboxLog,
+}
+
+impl ArtifactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArtifactKind::Diff => "diff",
+            ArtifactKind::Plan => "plan",
+            ArtifactKind::SandboxLog => "sandbox_log",
+        }
+    }
+
+    /// 📄 File name the artifact is stored and downloaded as
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ArtifactKind::Diff => "diff.patch",
+            ArtifactKind::Plan => "plan.json",
+            ArtifactKind::SandboxLog => "sandbox.log",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ArtifactKind::Diff => "text/x-diff; charset=utf-8",
+            ArtifactKind::Plan => "application/json",
+            ArtifactKind::SandboxLog => "text/plain; charset=utf-8",
+        }
+    }
+
+    /// 🔑 Storage key of a feedback's artifact of this kind
+    fn key(self, feedback_id: Uuid) -> String {
+        format!("{}/{}", feedback_id, self.file_name())
+    }
+}
+
+impl FromStr for ArtifactKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "diff" => Ok(ArtifactKind::Diff),
+            "plan" => Ok(ArtifactKind::Plan),
+            "sandbox_log" => Ok(ArtifactKind::SandboxLog),
+            _ => anyhow::bail!(
+                "Unknown artifact: {} (expected diff, plan or sandbox_log)",
+                s
+            ),
+        }
+    }
+}
+
+/// 📍 Where an artifact was stored (what feedback metadata keeps)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredArtifact {
+    /// 🔑 Key in the artifact store
+    pub key: String,
+    /// 📏 Size in bytes
+    pub size_bytes: u64,
+    /// ⏰ When it was written (retention counts from here)
+    pub stored_at: DateTime<Utc>,
+}
+
+/// 📥 How an artifact is handed to whoever asks for it
+pub enum Retrieval {
+    /// 📄 Its bytes, served by us
+    Bytes(Vec<u8>),
+    /// 🔗 A presigned URL to fetch it from the bucket
+    Redirect(String),
+}
+
+/// 🗄️ The configured artifact store
+pub enum ArtifactStore {
+    /// 📂 Files under a directory
+    Local(PathBuf),
+    /// 🪣 Objects in a bucket
+    S3(S3Bucket),
+}
+
+impl ArtifactStore {
+    /// 🗄️ The store ARTIFACT_STORAGE picks (None when artifacts aren't kept)
+    pub fn from_config(config: &ArtifactConfig) -> Result<Option<Self>> {
+        Ok(match config.storage {
+            ArtifactStorage::None => None,
+            ArtifactStorage::Local => Some(ArtifactStore::Local(PathBuf::from(&config.dir))),
+            ArtifactStorage::S3 => Some(ArtifactStore::S3(S3Bucket::new(config)?)),
+        })
+    }
+
+    /// ⬆️ Write an artifact, replacing any earlier one under the same key
+    async fn put(&self, key: &str, bytes: Vec<u8>, kind: ArtifactKind) -> Result<()> {
+        match self {
+            ArtifactStore::Local(dir) => {
+                let path = dir.join(key);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                tokio::fs::write(&path, bytes)
+                    .await
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            }
+            ArtifactStore::S3(bucket) => bucket.put(key, bytes, kind.content_type()).await,
+        }
+    }
+
+    /// 🗑️ Delete an artifact (one that's gone already is fine)
+    async fn delete(&self, key: &str) -> Result<()> {
+        match self {
+            ArtifactStore::Local(dir) => match tokio::fs::remove_file(dir.join(key)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(e).with_context(|| format!("Failed to delete artifact {}", key))
+                }
+                _ => Ok(()),
+            },
+            ArtifactStore::S3(bucket) => bucket.delete(key).await,
+        }
+    }
+
+    /// 📥 An artifact's bytes, or a presigned URL valid `expires_in` seconds
+    /// (None when a local file has disappeared)
+    pub async fn retrieve(&self, key: &str, expires_in: u64) -> Result<Option<Retrieval>> {
+        match self {
+            ArtifactStore::Local(dir) => match tokio::fs::read(dir.join(key)).await {
+                Ok(bytes) => Ok(Some(Retrieval::Bytes(bytes))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).with_context(|| format!("Failed to read artifact {}", key)),
+            },
+            ArtifactStore::S3(bucket) => Ok(Some(Retrieval::Redirect(bucket.presigned_get(
+                key,
+                expires_in,
+                Utc::now(),
+            )))),
+        }
+    }
+}
+
+/// 📋 The artifacts stored for a feedback item, from its metadata
+pub fn stored_artifacts(feedback: &Feedback) -> BTreeMap<ArtifactKind, StoredArtifact> {
+    feedback
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(ARTIFACTS_METADATA_KEY))
+        .and_then(|artifacts| serde_json::from_value(artifacts.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// 📦 What a run produced, ready to store
+fn collect(feedback: &Feedback, events: &[FeedbackEvent]) -> Vec<(ArtifactKind, Vec<u8>)> {
+    let mut artifacts = Vec::new();
+    if let Some(diff) = feedback.proposed_diff() {
+        artifacts.push((ArtifactKind::Diff, diff.as_bytes().to_vec()));
+    }
+    if let Some(plan) = feedback
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(PLAN_METADATA_KEY))
+    {
+        if let Ok(plan) = serde_json::to_vec_pretty(plan) {
+            artifacts.push((ArtifactKind::Plan, plan));
+        }
+    }
+    if let Some(log) = sandbox_log(events) {
+        artifacts.push((ArtifactKind::SandboxLog, log.into_bytes()));
+    }
+    artifacts
+}
+
+/// 📜 Every sandbox run of a timeline as one log (None when nothing ran)
+fn sandbox_log(events: &[FeedbackEvent]) -> Option<String> {
+    let runs: Vec<String> = events
+        .iter()
+        .filter(|event| event.event_type == FeedbackEvent::SANDBOX_RUN)
+        .map(|event| {
+            let run = &event.payload;
+            let result = if run["timed_out"].as_bool().unwrap_or(false) {
+                "timed out".to_string()
+            } else {
+                match run["exit_code"].as_i64() {
+                    Some(code) => format!("exited with {}", code),
+                    None => "was killed".to_string(),
+                }
+            };
+            format!(
+                "$ {}\n{}\n# {} {} after {} ms ({})\n",
+                run["command"].as_str().unwrap_or_default(),
+                run["output"].as_str().unwrap_or_default().trim_end(),
+                event.created_at.to_rfc3339(),
+                result,
+                run["duration_ms"].as_u64().unwrap_or_default(),
+                if run["passed"].as_bool().unwrap_or(false) {
+                    "passed"
+                } else {
+                    "failed"
+                }
+            )
+        })
+        .collect();
+    (!runs.is_empty()).then(|| runs.join("\n"))
+}
+
+/// 🗄️ Store what a finished run produced and list it in the feedback's metadata,
+/// returning how many artifacts were written
+pub async fn capture(pool: &PgPool, config: &ArtifactConfig, feedback_id: Uuid) -> Result<usize> {
+    let Some(store) = ArtifactStore::from_config(config)? else {
+        return Ok(0);
+    };
+    let Some(mut feedback) = Feedback::find_by_id(pool, feedback_id).await? else {
+        return Ok(0);
+    };
+    let events = FeedbackEvent::list_for_feedback(pool, feedback.id).await?;
+    let artifacts = collect(&feedback, &events);
+    if artifacts.is_empty() {
+        return Ok(0);
+    }
+
+    let mut stored = stored_artifacts(&feedback);
+    let count = artifacts.len();
+    for (kind, bytes) in artifacts {
+        let key = kind.key(feedback.id);
+        let size_bytes = bytes.len() as u64;
+        store
+            .put(&key, bytes, kind)
+            .await
+            .with_context(|| format!("Failed to store the {} artifact", kind.as_str()))?;
+        stored.insert(
+            kind,
+            StoredArtifact {
+                key,
+                size_bytes,
+                stored_at: Utc::now(),
+            },
+        );
+    }
+    feedback
+        .merge_metadata(pool, serde_json::json!({ ARTIFACTS_METADATA_KEY: stored }))
+        .await?;
+    info!("🗄️ Stored {} artifacts of run {}", count, feedback.id);
+    Ok(count)
+}
+
+/// 🧹 Delete artifacts older than ARTIFACT_RETENTION_DAYS, returning how many went
+pub async fn purge_expired(pool: &PgPool, config: &ArtifactConfig) -> Result<usize> {
+    if config.retention_days == 0 {
+        return Ok(0);
+    }
+    let Some(store) = ArtifactStore::from_config(config)? else {
+        return Ok(0);
+    };
+
+    let expired =
+        Feedback::expired_artifacts(pool, ARTIFACTS_METADATA_KEY, config.retention_days).await?;
+    let mut purged = 0;
+    for (feedback_id, kind, key) in expired {
+        // 🔁 Left listed when the delete fails, so the next cleanup tries again
+        if let Err(e) = store.delete(&key).await {
+            warn!("⚠️ Could not delete expired artifact {}: {:#}", key, e);
+            continue;
+        }
+        Feedback::forget_artifact(pool, feedback_id, ARTIFACTS_METADATA_KEY, &kind).await?;
+        purged += 1;
+    }
+    if purged > 0 {
+        info!("🧹 Deleted {} expired artifacts", purged);
+    }
+    Ok(purged)
+}
+
+/// 🏷️ Domain artifact download links are signed under
+const LINK_DOMAIN: &str = "artifact";
+
+/// 🔗 Signed download link of a feedback's artifact
+pub fn download_url(
+    secret: &str,
+    feedback_id: Uuid,
+    kind: ArtifactKind,
+    expires: DateTime<Utc>,
+) -> String {
+    let expires = expires.timestamp();
+    let signature = signed_links::sign(
+        secret,
+        LINK_DOMAIN,
+        &[&feedback_id, &kind.as_str(), &expires],
+    );
+    format!(
+        "/api/artifacts/{}/{}?expires={}&signature={}",
+        feedback_id,
+        kind.as_str(),
+        expires,
+        signature
+    )
+}
+
+/// ✅ Whether a download link is genuine and not yet expired
+pub fn verify_link(
+    secret: &str,
+    feedback_id: Uuid,
+    kind: ArtifactKind,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    expires >= now.timestamp()
+        && signed_links::verify(
+            secret,
+            LINK_DOMAIN,
+            &[&feedback_id, &kind.as_str(), &expires],
+            signature,
+        )
+}
+
+// 🧪 Tests - Kept, listed, and only for those with the link!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn event(event_type: &str, payload: serde_json::Value) -> FeedbackEvent {
+        FeedbackEvent {
+            id: Uuid::new_v4(),
+            feedback_id: Uuid::nil(),
+            event_type: event_type.to_string(),
+            payload,
+            created_at: Utc::now(),
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn test_sandbox_log() {
+        assert_eq!(sandbox_log(&[]), None);
+        let events = [
+            event(FeedbackEvent::PLAN_CREATED, json!({})),
+            event(
+                FeedbackEvent::SANDBOX_RUN,
+                json!({
+                    "command": "cargo test",
+                    "passed": false,
+                    "exit_code": 101,
+                    "timed_out": false,
+                    "duration_ms": 5120,
+                    "output": "test result: FAILED. 1 passed; 1 failed\n",
+                }),
+            ),
+            event(
+                FeedbackEvent::SANDBOX_RUN,
+                json!({
+                    "command": "npm test",
+                    "passed": false,
+                    "exit_code": null,
+                    "timed_out": true,
+                    "duration_ms": 600000,
+                    "output": "",
+                }),
+            ),
+        ];
+        let log = sandbox_log(&events).unwrap();
+        assert!(log.starts_with("$ cargo test\ntest result: FAILED. 1 passed; 1 failed\n# "));
+        assert!(log.contains(" exited with 101 after 5120 ms (failed)\n\n$ npm test\n"));
+        assert!(log.ends_with(" timed out after 600000 ms (failed)\n"));
+        println!("✅ Sandbox log test passed!");
+    }
+
+    #[test]
+    fn test_artifact_kinds() {
+        for kind in [
+            ArtifactKind::Diff,
+            ArtifactKind::Plan,
+            ArtifactKind::SandboxLog,
+        ] {
+            assert_eq!(kind.as_str().parse::<ArtifactKind>().unwrap(), kind);
+            assert_eq!(serde_json::to_value(kind).unwrap(), json!(kind.as_str()));
+        }
+        assert!("build_log".parse::<ArtifactKind>().is_err());
+        assert_eq!(
+            ArtifactKind::SandboxLog.key(Uuid::nil()),
+            "00000000-0000-0000-0000-000000000000/sandbox.log"
+        );
+        println!("✅ Artifact kind test passed!");
+    }
+
+    #[test]
+    fn test_signed_links() {
+        let secret = "test-secret";
+        let feedback_id = Uuid::new_v4();
+        let now = Utc::now();
+        let url = download_url(
+            secret,
+            feedback_id,
+            ArtifactKind::Diff,
+            now + Duration::minutes(60),
+        );
+        assert!(url.starts_with(&format!("/api/artifacts/{}/diff?expires=", feedback_id)));
+
+        let expires = (now + Duration::minutes(60)).timestamp();
+        let signature = url.rsplit("signature=").next().unwrap();
+        let check = |kind: ArtifactKind, expires: i64, now: DateTime<Utc>| {
+            verify_link(secret, feedback_id, kind, expires, signature, now)
+        };
+        assert!(check(ArtifactKind::Diff, expires, now));
+        assert!(!check(ArtifactKind::Plan, expires, now));
+        assert!(!check(ArtifactKind::Diff, expires + 60, now));
+        assert!(!check(
+            ArtifactKind::Diff,
+            expires,
+            now + Duration::minutes(61)
+        ));
+        assert!(!verify_link(
+            secret,
+            feedback_id,
+            ArtifactKind::Diff,
+            expires,
+            "not-hex",
+            now
+        ));
+        println!("✅ Artifact link test passed!");
+    }
+}
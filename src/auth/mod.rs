@@ -1,2 +1,158 @@
 // 🔐 Authentication Module - User Management! 🔐
-// TODO: Implement authentication logic
+// Credentials the service creates itself: Argon2 password hashes for accounts
+// (checked again when people change their password), random secrets for JWT
+// signing and generated passwords, and API keys, which
+// are long-lived JWTs for service accounts, optionally limited to scopes and
+// given a rate limit tier (both stored on the account, so they survive key
+// rotations). Used by the bootstrap commands
+// (`feedbacker create-admin`, `rotate-jwt-secret`, `rotate-api-key`,
+// `rotate-signing-key`); token signing keys live in `keys`
+// Created with love by Aye & Hue - Keys made fresh, never by hand! ✨
+
+use anyhow::Result;
+use argon2::password_hash::{
+    rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+};
+use argon2::Argon2;
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::database::models::User;
+use crate::middleware::auth::{jwt_utils, ApiScope};
+use crate::middleware::rate_limiting::RateLimitTier;
+use sqlx::PgPool;
+
+pub mod keys;
+
+use keys::TokenSigner;
+
+/// 📏 Length of a generated JWT secret (JWT_SECRET needs at least 32)
+pub const JWT_SECRET_LENGTH: usize = 64;
+
+/// 📏 Length of a generated password
+pub const GENERATED_PASSWORD_LENGTH: usize = 24;
+
+/// 📏 Length of a generated request signing key (fits users.request_signing_key)
+pub const REQUEST_SIGNING_KEY_LENGTH: usize = 48;
+
+/// 📏 Shortest password an account may have
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// 🔒 Argon2id hash of a password, in PHC string format
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))
+}
+
+/// ✅ Whether a password matches a stored hash (unparseable hashes match nothing)
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// 🎲 Random alphanumeric secret
+pub fn generate_secret(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// 🔑 A new API key for a service account, valid for `days`
+pub fn issue_api_key(user: &User, signer: &TokenSigner, days: u64) -> Result<String> {
+    jwt_utils::create_jwt_token(user, signer, days * 24)
+}
+
+/// 🎚️ Change a service account's API key scopes and rate limit tier
+/// What's omitted stays as it is; an empty scope list lifts the scope limits
+pub async fn update_api_key_limits(
+    pool: &PgPool,
+    user: &mut User,
+    scopes: Option<Vec<String>>,
+    rate_limit_tier: Option<RateLimitTier>,
+) -> Result<()> {
+    if scopes.is_none() && rate_limit_tier.is_none() {
+        return Ok(());
+    }
+    let scopes = match scopes {
+        Some(scopes) if scopes.is_empty() => None,
+        Some(scopes) => Some(scopes),
+        None => user.api_scopes.clone(),
+    };
+    let tier = match rate_limit_tier {
+        Some(tier) => tier.as_str().to_string(),
+        None => user
+            .rate_limit_tier
+            .clone()
+            .unwrap_or_else(|| RateLimitTier::Standard.as_str().to_string()),
+    };
+    user.set_api_key_limits(pool, scopes, &tier).await
+}
+
+/// ✅ Check API key scope names, returning them deduplicated and sorted
+pub fn validate_scopes(names: &[String]) -> Result<Vec<String>, Vec<String>> {
+    let unknown: Vec<String> = names
+        .iter()
+        .filter(|name| name.parse::<ApiScope>().is_err())
+        .map(|name| format!("Unknown API key scope: {}", name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(unknown);
+    }
+    let mut names = names.to_vec();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+// 🧪 Tests - Fresh secrets every time!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_credentials() {
+        let secret = generate_secret(JWT_SECRET_LENGTH);
+        assert_eq!(secret.len(), JWT_SECRET_LENGTH);
+        assert!(secret.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(secret, generate_secret(JWT_SECRET_LENGTH));
+
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        let parsed = PasswordHash::new(&hash).unwrap();
+        assert!(Argon2::default()
+            .verify_password(b"correct horse", &parsed)
+            .is_ok());
+        assert!(Argon2::default()
+            .verify_password(b"battery staple", &parsed)
+            .is_err());
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("battery staple", &hash));
+        assert!(!verify_password("correct horse", "not a hash"));
+        println!("✅ Generated credentials test passed!");
+    }
+
+    #[test]
+    fn test_scope_validation() {
+        let names = vec![
+            "projects:read".to_string(),
+            "feedback:write".to_string(),
+            "projects:read".to_string(),
+        ];
+        assert_eq!(
+            validate_scopes(&names).unwrap(),
+            vec!["feedback:write", "projects:read"]
+        );
+        assert_eq!(
+            validate_scopes(&["everything".to_string()]).unwrap_err(),
+            vec!["Unknown API key scope: everything"]
+        );
+        println!("✅ API key scope validation test passed!");
+    }
+}
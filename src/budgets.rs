@@ -0,0 +1,425 @@
+// 💸 Budgets - Spend Caps That Ask Before Spending More! 💸
+// Every LLM call made for a feedback item adds its tokens to the item and to its
+// project's total for the calendar month. Before the next call, the item is held
+// to MAX_TOKENS_PER_FEEDBACK and its project to MAX_TOKENS_PER_PROJECT_MONTH (a
+// project's `budget` settings override both). A run that hits either cap stops
+// in budget_exceeded and the project owner is notified. Nothing more is spent
+// until a project admin approves it (POST /api/feedback/:id/budget-approval),
+// which lets that item use a given number of tokens more, past both caps, and
+// queues its run again. The run resumes from its checkpoint, so the work done
+// before the pause isn't paid for twice
+// Created with love by Aye & Hue - Every token accounted for! ✨
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use std::fmt;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::BudgetConfig;
+use crate::database::models::{
+    BackgroundJob, Feedback, FeedbackEvent, FeedbackStatus, Notification, NotificationType,
+    OrgRole, Project, User,
+};
+use crate::feedback_triage::TriageRejection;
+use crate::i18n::{self, Locale};
+use crate::jobs::runs::{self, RUN_JOB};
+use crate::llm::ExchangeTrace;
+use crate::metrics;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::BudgetSettings;
+use crate::organizations;
+
+/// 🔑 Metadata key holding the tokens an item may reach, once an overrun was approved
+const APPROVED_KEY: &str = "budget_approved_tokens";
+
+/// 💸 Token caps a call has to stay under (None = no cap)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpendLimits {
+    /// 📝 Tokens one feedback item may use
+    pub feedback_tokens: Option<u64>,
+    /// 🗓️ Tokens its project may use this calendar month
+    pub project_monthly_tokens: Option<u64>,
+}
+
+impl SpendLimits {
+    /// 🧭 The caps of a project: its own settings, else the service-wide ones
+    pub fn resolve(config: &BudgetConfig, settings: &BudgetSettings) -> Self {
+        let cap = |tokens: u64| (tokens > 0).then_some(tokens);
+        Self {
+            feedback_tokens: cap(settings.feedback_tokens.unwrap_or(config.feedback_tokens)),
+            project_monthly_tokens: cap(settings
+                .monthly_tokens
+                .unwrap_or(config.project_monthly_tokens)),
+        }
+    }
+
+    /// ♾️ Whether there is nothing to check
+    pub fn is_unlimited(&self) -> bool {
+        self.feedback_tokens.is_none() && self.project_monthly_tokens.is_none()
+    }
+}
+
+/// 🏷️ Which cap was hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    /// 📝 The feedback item's own (or the overrun approved for it)
+    Feedback,
+    /// 🗓️ The project's for this month
+    Project,
+}
+
+impl BudgetScope {
+    /// 🏷️ Label used in metrics, events and messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BudgetScope::Feedback => "feedback",
+            BudgetScope::Project => "project",
+        }
+    }
+}
+
+/// 🛑 A call refused because a cap was reached (carried in the anyhow error)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    /// 📊 Tokens used so far
+    pub used: u64,
+    /// 💸 The cap
+    pub limit: u64,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.scope {
+            BudgetScope::Feedback => write!(
+                f,
+                "Used {} of the {} tokens this feedback may use",
+                self.used, self.limit
+            ),
+            BudgetScope::Project => write!(
+                f,
+                "The project used {} of its {} tokens this month",
+                self.used, self.limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// 🔍 The cap behind an error, when a budget stopped the call
+pub fn exceeded_by(error: &anyhow::Error) -> Option<&BudgetExceeded> {
+    error.downcast_ref::<BudgetExceeded>()
+}
+
+/// 🧮 The cap a call would go past, given what the item and its project used
+/// and the tokens an approval lets the item reach (which replaces both caps)
+pub fn exceeded(
+    limits: &SpendLimits,
+    feedback_used: u64,
+    project_used: u64,
+    approved: Option<u64>,
+) -> Option<BudgetExceeded> {
+    let over = |scope, used, limit: Option<u64>| {
+        limit
+            .filter(|limit| used >= *limit)
+            .map(|limit| BudgetExceeded { scope, used, limit })
+    };
+    if approved.is_some() {
+        return over(BudgetScope::Feedback, feedback_used, approved);
+    }
+    over(BudgetScope::Feedback, feedback_used, limits.feedback_tokens).or_else(|| {
+        over(
+            BudgetScope::Project,
+            project_used,
+            limits.project_monthly_tokens,
+        )
+    })
+}
+
+/// 🔢 Tokens an approval lets an item reach
+fn approved_tokens(feedback: &Feedback) -> Option<u64> {
+    feedback
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(APPROVED_KEY))
+        .and_then(|tokens| tokens.as_u64())
+}
+
+/// 🛑 Refuse a call (with a `BudgetExceeded` error) when its feedback or
+/// project has reached a cap
+pub async fn check(pool: &PgPool, trace: &ExchangeTrace) -> Result<()> {
+    let Some(feedback_id) = trace.feedback_id else {
+        return Ok(());
+    };
+    if trace.budget.is_unlimited() {
+        return Ok(());
+    }
+
+    let feedback = Feedback::find_by_id(pool, feedback_id)
+        .await?
+        .context("Feedback not found")?;
+    let feedback_used = Feedback::tokens_used(pool, feedback_id).await?;
+    let project_used = match (trace.project_id, trace.budget.project_monthly_tokens) {
+        (Some(project_id), Some(_)) => Project::monthly_token_usage(pool, project_id).await?,
+        _ => 0,
+    };
+    match exceeded(
+        &trace.budget,
+        feedback_used as u64,
+        project_used as u64,
+        approved_tokens(&feedback),
+    ) {
+        Some(exceeded) => Err(exceeded.into()),
+        None => Ok(()),
+    }
+}
+
+/// ⏸️ Park a run's feedback in budget_exceeded and tell the project owner
+pub async fn pause(
+    pool: &PgPool,
+    feedback: &mut Feedback,
+    project: &Project,
+    exceeded: &BudgetExceeded,
+) -> Result<()> {
+    FeedbackEvent::record(
+        pool,
+        feedback.id,
+        FeedbackEvent::BUDGET_EXCEEDED,
+        json!({
+            "scope": exceeded.scope.as_str(),
+            "used": exceeded.used,
+            "limit": exceeded.limit,
+        }),
+    )
+    .await?;
+    feedback
+        .update_status(
+            pool,
+            FeedbackStatus::BudgetExceeded,
+            Some(exceeded.to_string()),
+        )
+        .await?;
+    metrics::record_budget_exceeded(exceeded.scope.as_str());
+    notify_owner(pool, feedback, project, exceeded).await;
+    Ok(())
+}
+
+/// 📣 Notify the project owner, in their language (failures are logged)
+async fn notify_owner(
+    pool: &PgPool,
+    feedback: &Feedback,
+    project: &Project,
+    exceeded: &BudgetExceeded,
+) {
+    let owner = match User::find_by_id(pool, project.owner_id).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("⚠️ No budget alert for {}: {:#}", feedback.id, e);
+            return;
+        }
+    };
+    let locale: Locale = owner
+        .locale
+        .as_deref()
+        .and_then(|code| code.parse().ok())
+        .unwrap_or_default();
+    let created = locale
+        .scope(async {
+            let title = i18n::t("budget-exceeded-title");
+            let content = i18n::t_with(
+                &format!("budget-exceeded-{}", exceeded.scope.as_str()),
+                [
+                    ("repository", project.repository.clone().into()),
+                    ("used", exceeded.used.into()),
+                    ("limit", exceeded.limit.into()),
+                ],
+            );
+            Notification::create(
+                pool,
+                owner.id,
+                NotificationType::Warning,
+                &title,
+                &content,
+                Some(feedback.id),
+            )
+            .await
+        })
+        .await;
+    if let Err(e) = created {
+        warn!("⚠️ Budget alert for {} failed: {:#}", owner.email, e);
+    }
+}
+
+/// 👍 Let an item paused at its budget use `additional_tokens` more (None =
+/// another per-feedback cap's worth) and queue its run again. Needs an admin
+/// role on the run's project. Returns the item and the tokens approved
+pub async fn approve(
+    app_state: &AppState,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+    additional_tokens: Option<u64>,
+) -> Result<(Feedback, u64), TriageRejection> {
+    let pool = &app_state.db_pool;
+    let feedback = Feedback::find_by_id(pool, feedback_id)
+        .await?
+        .ok_or(TriageRejection::NotFound("Feedback"))?;
+    if !organizations::can_view_feedback(pool, user, &feedback).await? {
+        return Err(TriageRejection::NotFound("Feedback"));
+    }
+    if feedback.status != FeedbackStatus::BudgetExceeded {
+        return Err(TriageRejection::Invalid(vec![i18n::t(
+            "validation-budget-not-exceeded",
+        )]));
+    }
+
+    let run = BackgroundJob::latest_matching(pool, RUN_JOB, &json!({ "feedback_id": feedback_id }))
+        .await?
+        .ok_or(TriageRejection::NotFound("Project run"))?;
+    let project_id: Uuid = serde_json::from_value(run.payload["project_id"].clone())
+        .context("Project run job has no project_id")?;
+    let project = Project::find_by_id(pool, project_id)
+        .await?
+        .ok_or(TriageRejection::NotFound("Project"))?;
+    if organizations::project_role_for(pool, user, &project).await? < Some(OrgRole::Admin) {
+        return Err(TriageRejection::Forbidden);
+    }
+
+    let limits = SpendLimits::resolve(
+        &app_state.config.load().budgets,
+        &project.settings()?.budget,
+    );
+    let Some(additional) = additional_tokens
+        .or(limits.feedback_tokens)
+        .filter(|tokens| *tokens > 0)
+    else {
+        return Err(TriageRejection::Invalid(vec![i18n::t(
+            "validation-budget-tokens",
+        )]));
+    };
+    let allowed = Feedback::tokens_used(pool, feedback_id).await? as u64 + additional;
+
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start budget approval transaction")?;
+    // 🔒 Two approvals at once must not queue the run twice
+    let Some(mut feedback) = Feedback::find_for_update(&mut transaction, feedback_id).await? else {
+        return Err(TriageRejection::NotFound("Feedback"));
+    };
+    if feedback.status != FeedbackStatus::BudgetExceeded {
+        return Err(TriageRejection::Invalid(vec![i18n::t(
+            "validation-budget-not-exceeded",
+        )]));
+    }
+    FeedbackEvent::record_in(
+        &mut transaction,
+        feedback_id,
+        FeedbackEvent::BUDGET_APPROVED,
+        json!({
+            "approved_by": user.id,
+            "additional_tokens": additional,
+            "allowed_tokens": allowed,
+        }),
+    )
+    .await?;
+    feedback
+        .update_status_in(&mut transaction, FeedbackStatus::Pending, None)
+        .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit budget approval")?;
+
+    feedback
+        .merge_metadata(pool, json!({ APPROVED_KEY: allowed }))
+        .await?;
+    runs::requeue_project_run(app_state, &run, &feedback).await?;
+    info!(
+        "👍 {} approved {} more tokens for feedback {}",
+        user.email, additional, feedback_id
+    );
+    Ok((feedback, additional))
+}
+
+// 🧪 Tests - Not a token past the line!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_limits() {
+        let config = BudgetConfig {
+            feedback_tokens: 50_000,
+            project_monthly_tokens: 0,
+        };
+        let inherited = SpendLimits::resolve(&config, &BudgetSettings::default());
+        assert_eq!(inherited.feedback_tokens, Some(50_000));
+        assert_eq!(inherited.project_monthly_tokens, None);
+
+        // 🏠 The project's own caps win, and 0 lifts one
+        let own = SpendLimits::resolve(
+            &config,
+            &BudgetSettings {
+                feedback_tokens: Some(0),
+                monthly_tokens: Some(1_000_000),
+            },
+        );
+        assert_eq!(own.feedback_tokens, None);
+        assert_eq!(own.project_monthly_tokens, Some(1_000_000));
+        assert!(SpendLimits::default().is_unlimited());
+        println!("✅ Budget limit resolution test passed!");
+    }
+
+    #[test]
+    fn test_exceeded() {
+        let limits = SpendLimits {
+            feedback_tokens: Some(10_000),
+            project_monthly_tokens: Some(100_000),
+        };
+        assert_eq!(exceeded(&limits, 9_999, 50_000, None), None);
+        assert_eq!(
+            exceeded(&limits, 10_000, 50_000, None),
+            Some(BudgetExceeded {
+                scope: BudgetScope::Feedback,
+                used: 10_000,
+                limit: 10_000,
+            })
+        );
+        let project = exceeded(&limits, 500, 120_000, None).unwrap();
+        assert_eq!(project.scope, BudgetScope::Project);
+        assert_eq!(
+            project.to_string(),
+            "The project used 120000 of its 100000 tokens this month"
+        );
+
+        // 👍 An approved overrun replaces both caps until it runs out too
+        assert_eq!(exceeded(&limits, 15_000, 120_000, Some(20_000)), None);
+        assert_eq!(
+            exceeded(&limits, 20_000, 0, Some(20_000)).map(|e| e.limit),
+            Some(20_000)
+        );
+        assert_eq!(
+            exceeded(&SpendLimits::default(), 1 << 40, 1 << 40, None),
+            None
+        );
+        println!("✅ Budget exceeded test passed!");
+    }
+
+    #[test]
+    fn test_exceeded_survives_context() {
+        let error = anyhow::Error::from(BudgetExceeded {
+            scope: BudgetScope::Feedback,
+            used: 3,
+            limit: 2,
+        })
+        .context("Planned edit for 'src/lib.rs' failed");
+        assert_eq!(exceeded_by(&error).map(|e| e.used), Some(3));
+        assert!(exceeded_by(&anyhow::anyhow!("LLM timed out")).is_none());
+        println!("✅ Budget error downcast test passed!");
+    }
+}
@@ -0,0 +1,357 @@
+// 🗃️ Response Cache - Hot Reads Without the Database Round Trip! 🗃️
+// What the busiest read endpoints answer (GET /api/projects, GET
+// /api/status/:project_id, GET /api/smart-tree/latest) is kept for
+// RESPONSE_CACHE_TTL_SECONDS in process and, with ENABLE_REDIS_CACHE, in Redis
+// shared by every instance. Invalidation hooks drop entries early: feedback
+// events (which every instance hears, see crate::live_updates) clear project
+// statuses, and project updates clear the listings and that project's status.
+// With Redis shared, the in-process copy is kept for LOCAL_TTL_WITH_REDIS at
+// most, so an instance catches up quickly with invalidations made elsewhere.
+// Lookups are counted per namespace and layer at /metrics
+// Created with love by Aye & Hue - Asked twice, answered once! ✨
+
+#[cfg(feature = "redis-cache")]
+pub mod redis; // 🟥 Shared Redis layer
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+#[cfg(feature = "redis-cache")]
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::CacheConfig;
+use crate::metrics;
+
+/// ⏳ Longest an in-process copy is served when Redis is shared
+const LOCAL_TTL_WITH_REDIS: Duration = Duration::from_secs(5);
+
+/// 🏷️ What a cached response is of (invalidation works per namespace)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheNamespace {
+    /// 📋 GET /api/projects, per visibility filter
+    Projects,
+    /// 📊 GET /api/status/:project_id, per project
+    ProjectStatus,
+    /// 🌳 GET /api/smart-tree/latest
+    SmartTree,
+}
+
+impl CacheNamespace {
+    /// 🏷️ Name used in Redis keys and metric labels
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CacheNamespace::Projects => "projects",
+            CacheNamespace::ProjectStatus => "project_status",
+            CacheNamespace::SmartTree => "smart_tree",
+        }
+    }
+}
+
+/// 📦 One response kept in process
+#[derive(Debug)]
+struct LocalEntry {
+    json: String,
+    expires_at: Instant,
+}
+
+/// 🗃️ Two-layer cache of serialized responses
+pub struct ResponseCache {
+    /// 🔄 Off means every lookup loads
+    enabled: bool,
+    /// ⏳ How long an entry is served
+    ttl: Duration,
+    /// 📏 Entries kept in process at most
+    max_entries: usize,
+    /// 🧠 This instance's copies
+    local: Mutex<HashMap<(CacheNamespace, String), LocalEntry>>,
+    /// 🟥 Copies shared by every instance
+    #[cfg(feature = "redis-cache")]
+    shared: Option<redis::SharedCache>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("enabled", &self.enabled)
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseCache {
+    /// ➕ Cache as configured (Redis is connected to on first use)
+    pub fn new(config: &CacheConfig) -> Self {
+        #[cfg(feature = "redis-cache")]
+        let shared = config.redis_url.as_deref().and_then(|url| {
+            redis::SharedCache::new(url, &config.redis_prefix)
+                .map_err(|e| warn!("⚠️ Response cache stays in process: {:#}", e))
+                .ok()
+        });
+        #[cfg(not(feature = "redis-cache"))]
+        if config.redis_url.is_some() {
+            tracing::warn!(
+                "⚠️ ENABLE_REDIS_CACHE is set, but this build has no redis-cache feature"
+            );
+        }
+
+        Self {
+            enabled: config.enabled,
+            ttl: Duration::from_secs(config.ttl_seconds),
+            max_entries: config.max_entries,
+            local: Mutex::new(HashMap::new()),
+            #[cfg(feature = "redis-cache")]
+            shared,
+        }
+    }
+
+    /// 🔍 The cached response, or what `load` returns (kept for next time)
+    /// Load errors are returned as they are and never cached
+    pub async fn get_or_load<T, F, Fut>(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+        load: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if !self.enabled {
+            return load().await;
+        }
+
+        let local = self.get_local(namespace, key);
+        metrics::record_response_cache_lookup(namespace.as_str(), "local", local.is_some());
+        if let Some(value) = local {
+            return Ok(value);
+        }
+
+        #[cfg(feature = "redis-cache")]
+        let mut generation = None;
+        #[cfg(feature = "redis-cache")]
+        if let Some(shared) = &self.shared {
+            match shared.get(namespace, key).await {
+                Ok((current, json)) => {
+                    generation = Some(current);
+                    let value = json.as_deref().and_then(|json| decode(json));
+                    metrics::record_response_cache_lookup(
+                        namespace.as_str(),
+                        "redis",
+                        value.is_some(),
+                    );
+                    if let (Some(value), Some(json)) = (value, json) {
+                        self.put_local(namespace, key, json);
+                        return Ok(value);
+                    }
+                }
+                Err(e) => warn!("⚠️ Response cache Redis lookup failed: {:#}", e),
+            }
+        }
+
+        let value = load().await?;
+        let json = serde_json::to_string(&value)?;
+        #[cfg(feature = "redis-cache")]
+        if let (Some(shared), Some(generation)) = (&self.shared, generation) {
+            if let Err(e) = shared
+                .put(namespace, generation, key, &json, self.ttl)
+                .await
+            {
+                warn!("⚠️ Response cache Redis store failed: {:#}", e);
+            }
+        }
+        self.put_local(namespace, key, json);
+        Ok(value)
+    }
+
+    /// 🧹 Drop one cached response
+    pub async fn invalidate(&self, namespace: CacheNamespace, key: &str) {
+        metrics::record_response_cache_invalidation(namespace.as_str());
+        self.local_entries().remove(&(namespace, key.to_string()));
+
+        #[cfg(feature = "redis-cache")]
+        if let Some(shared) = &self.shared {
+            if let Err(e) = shared.delete(namespace, key).await {
+                warn!("⚠️ Response cache Redis invalidation failed: {:#}", e);
+            }
+        }
+    }
+
+    /// 🧹 Drop every cached response of a namespace
+    pub async fn invalidate_all(&self, namespace: CacheNamespace) {
+        metrics::record_response_cache_invalidation(namespace.as_str());
+        self.local_entries()
+            .retain(|(entry_namespace, _), _| *entry_namespace != namespace);
+
+        #[cfg(feature = "redis-cache")]
+        if let Some(shared) = &self.shared {
+            if let Err(e) = shared.retire(namespace).await {
+                warn!("⚠️ Response cache Redis invalidation failed: {:#}", e);
+            }
+        }
+    }
+
+    /// 🏠 A project was changed: listings and its status are stale
+    pub async fn project_changed(&self, project_id: Uuid) {
+        self.invalidate_all(CacheNamespace::Projects).await;
+        self.invalidate(CacheNamespace::ProjectStatus, &project_id.to_string())
+            .await;
+    }
+
+    /// ⏳ How long this instance keeps its own copy
+    fn local_ttl(&self) -> Duration {
+        #[cfg(feature = "redis-cache")]
+        if self.shared.is_some() {
+            return self.ttl.min(LOCAL_TTL_WITH_REDIS);
+        }
+        self.ttl
+    }
+
+    /// 🔒 The in-process entries (a poisoned lock only means a panic mid-update of a cache)
+    fn local_entries(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<(CacheNamespace, String), LocalEntry>> {
+        self.local
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 🧠 This instance's unexpired copy
+    fn get_local<T: DeserializeOwned>(&self, namespace: CacheNamespace, key: &str) -> Option<T> {
+        let entries = self.local_entries();
+        let entry = entries.get(&(namespace, key.to_string()))?;
+        if entry.expires_at <= Instant::now() {
+            return None;
+        }
+        decode(&entry.json)
+    }
+
+    /// 💾 Keep a copy in process, making room when full
+    fn put_local(&self, namespace: CacheNamespace, key: &str, json: String) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = self.local_entries();
+        if entries.len() >= self.max_entries {
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+        if entries.len() >= self.max_entries {
+            // 🪓 Still full: the entry closest to expiring goes
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            (namespace, key.to_string()),
+            LocalEntry {
+                json,
+                expires_at: now + self.local_ttl(),
+            },
+        );
+    }
+}
+
+/// 📦 A cached response read back (None when it no longer fits the type)
+fn decode<T: DeserializeOwned>(json: &str) -> Option<T> {
+    serde_json::from_str(json).ok()
+}
+
+// 🧪 Tests - Loaded once, served many times!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn new_cache(max_entries: usize) -> ResponseCache {
+        ResponseCache::new(&CacheConfig {
+            enabled: true,
+            ttl_seconds: 30,
+            max_entries,
+            redis_url: None,
+            redis_prefix: "test".to_string(),
+        })
+    }
+
+    async fn load(cache: &ResponseCache, key: &str, loads: &AtomicUsize) -> Vec<String> {
+        cache
+            .get_or_load(CacheNamespace::Projects, key, || async {
+                loads.fetch_add(1, Ordering::SeqCst);
+                Ok(vec![format!("aye-is/{}", key)])
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load_caches() {
+        let cache = new_cache(100);
+        let loads = AtomicUsize::new(0);
+
+        assert_eq!(load(&cache, "a", &loads).await, vec!["aye-is/a"]);
+        assert_eq!(load(&cache, "a", &loads).await, vec!["aye-is/a"]);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // 💥 Failed loads aren't cached
+        let failed: Result<Vec<String>> = cache
+            .get_or_load(CacheNamespace::Projects, "b", || async {
+                anyhow::bail!("database is down")
+            })
+            .await;
+        assert!(failed.is_err());
+        load(&cache, "b", &loads).await;
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        println!("✅ Response cache test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_invalidation() {
+        let cache = new_cache(100);
+        let loads = AtomicUsize::new(0);
+        load(&cache, "a", &loads).await;
+        load(&cache, "b", &loads).await;
+
+        cache.invalidate(CacheNamespace::Projects, "a").await;
+        load(&cache, "a", &loads).await;
+        load(&cache, "b", &loads).await;
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+
+        cache.invalidate_all(CacheNamespace::ProjectStatus).await;
+        load(&cache, "a", &loads).await;
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+
+        cache.invalidate_all(CacheNamespace::Projects).await;
+        load(&cache, "a", &loads).await;
+        load(&cache, "b", &loads).await;
+        assert_eq!(loads.load(Ordering::SeqCst), 5);
+        println!("✅ Response cache invalidation test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_capacity_and_disabled() {
+        let cache = new_cache(2);
+        let loads = AtomicUsize::new(0);
+        for key in ["a", "b", "c"] {
+            load(&cache, key, &loads).await;
+        }
+        assert_eq!(cache.local_entries().len(), 2);
+
+        let disabled = ResponseCache {
+            enabled: false,
+            ..new_cache(100)
+        };
+        load(&disabled, "a", &loads).await;
+        load(&disabled, "a", &loads).await;
+        assert_eq!(loads.load(Ordering::SeqCst), 5);
+        println!("✅ Response cache capacity test passed!");
+    }
+}
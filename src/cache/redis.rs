@@ -0,0 +1,165 @@
+// 🟥 Redis Cache Layer - One Cache for Every Instance! 🟥
+// Entries live at `{prefix}:{namespace}:{generation}:{key}` and expire with the
+// cache TTL. Each namespace has a generation counter at
+// `{prefix}:{namespace}:generation`: bumping it retires the whole namespace in
+// one command, and the retired entries just expire. The connection is made on
+// first use; while Redis can't be reached, attempts pause for RETRY_AFTER, so
+// an outage costs cache misses rather than slow requests
+// Created with love by Aye & Hue ✨
+
+use anyhow::{Context, Result};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+use super::CacheNamespace;
+
+/// ⏱️ Longest wait for the first connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 💤 Pause before trying an unreachable Redis again
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// 🟥 Cached responses shared through Redis
+pub struct SharedCache {
+    client: redis::Client,
+    connection: OnceCell<ConnectionManager>,
+    /// ⏳ No connection attempts before this
+    unavailable_until: Mutex<Option<Instant>>,
+    prefix: String,
+}
+
+impl SharedCache {
+    /// ➕ Shared cache on a Redis server (not connected yet)
+    pub fn new(url: &str, prefix: &str) -> Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(url).context("Invalid cache Redis URL")?,
+            connection: OnceCell::new(),
+            unavailable_until: Mutex::new(None),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    /// 🔍 The namespace's current generation and the entry stored under it
+    pub async fn get(&self, namespace: CacheNamespace, key: &str) -> Result<(u64, Option<String>)> {
+        let mut redis = self.connection().await?;
+        let generation: Option<u64> = redis.get(generation_key(&self.prefix, namespace)).await?;
+        let generation = generation.unwrap_or(0);
+        let json: Option<String> = redis
+            .get(entry_key(&self.prefix, namespace, generation, key))
+            .await?;
+        Ok((generation, json))
+    }
+
+    /// 💾 Store an entry under the generation it was looked up in, so a value
+    /// loaded across an invalidation lands in the retired generation
+    pub async fn put(
+        &self,
+        namespace: CacheNamespace,
+        generation: u64,
+        key: &str,
+        json: &str,
+        ttl: Duration,
+    ) -> Result<()> {
+        let mut redis = self.connection().await?;
+        let _: () = redis
+            .set_ex(
+                entry_key(&self.prefix, namespace, generation, key),
+                json,
+                ttl.as_secs().max(1),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 🧹 Delete one entry of the current generation
+    pub async fn delete(&self, namespace: CacheNamespace, key: &str) -> Result<()> {
+        let mut redis = self.connection().await?;
+        let generation: Option<u64> = redis.get(generation_key(&self.prefix, namespace)).await?;
+        let _: () = redis
+            .del(entry_key(
+                &self.prefix,
+                namespace,
+                generation.unwrap_or(0),
+                key,
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// 🧹 Retire every entry of a namespace
+    pub async fn retire(&self, namespace: CacheNamespace) -> Result<()> {
+        let mut redis = self.connection().await?;
+        let _: u64 = redis
+            .incr(generation_key(&self.prefix, namespace), 1)
+            .await?;
+        Ok(())
+    }
+
+    /// 🔌 The shared connection, made on first use
+    async fn connection(&self) -> Result<ConnectionManager> {
+        if let Some(connection) = self.connection.get() {
+            return Ok(connection.clone());
+        }
+        let paused = self
+            .unavailable_until
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_some_and(|until| Instant::now() < until);
+        if paused {
+            anyhow::bail!("Cache Redis was unreachable a moment ago");
+        }
+
+        let connected = self
+            .connection
+            .get_or_try_init(|| async {
+                tokio::time::timeout(CONNECT_TIMEOUT, self.client.get_connection_manager())
+                    .await
+                    .context("Timed out connecting to the cache Redis")?
+                    .context("Failed to connect to the cache Redis")
+            })
+            .await;
+        match connected {
+            Ok(connection) => Ok(connection.clone()),
+            Err(e) => {
+                *self
+                    .unavailable_until
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+                    Some(Instant::now() + RETRY_AFTER);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 🏷️ Key of one cached entry
+pub fn entry_key(prefix: &str, namespace: CacheNamespace, generation: u64, key: &str) -> String {
+    format!("{}:{}:{}:{}", prefix, namespace.as_str(), generation, key)
+}
+
+/// 🔢 Key of a namespace's generation counter
+pub fn generation_key(prefix: &str, namespace: CacheNamespace) -> String {
+    format!("{}:{}:generation", prefix, namespace.as_str())
+}
+
+// 🧪 Tests - Keys that never collide!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(
+            entry_key("feedbacker:cache", CacheNamespace::ProjectStatus, 3, "abc"),
+            "feedbacker:cache:project_status:3:abc"
+        );
+        assert_eq!(
+            generation_key("feedbacker:cache", CacheNamespace::Projects),
+            "feedbacker:cache:projects:generation"
+        );
+        assert!(SharedCache::new("not a url", "p").is_err());
+        println!("✅ Redis cache key test passed!");
+    }
+}
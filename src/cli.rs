@@ -0,0 +1,859 @@
+// 🖥️ Command Line - Maintenance Without Starting the Service! 🖥️
+// Running `feedbacker` with no arguments starts the web service. Subcommands do
+// one-off maintenance against the configured database and exit:
+//   feedbacker migrate status        📋 which migrations are applied
+//   feedbacker migrate up            ⬆️ apply the pending ones
+//   feedbacker migrate down <id>     ⬇️ roll one back
+//   feedbacker migrate redo          🔁 roll back the latest one and apply it again
+//   feedbacker --migrate-only        🗄️ the same as `migrate up`, for deploy pipelines
+//   feedbacker check-config          📋 validate the configuration
+//   feedbacker doctor                🩺 validate it and probe every dependency
+//   feedbacker create-admin --email  👑 seed an admin account on a fresh deployment
+//   feedbacker rotate-jwt-secret     🔐 new signing secret, service API keys re-signed
+//   feedbacker rotate-api-key --email 🔑 new API key for a service account
+//     (--scopes feedback:write,projects:read and --rate-limit-tier trusted limit it)
+//   feedbacker rotate-signing-key    🔏 new token signing key pair, old one kept for the overlap
+//   feedbacker rebuild-projections   🧾 replay feedback timelines onto status columns
+//     (--feedback <id> for one item)
+//   feedbacker mcp                   🤖 MCP server on stdin/stdout for AI agents, acting as
+//     the account whose API key is in $FEEDBACKER_API_KEY
+// Add --dry-run to print the SQL instead of running it, and --config <path>
+// (anywhere, also without a subcommand) to read settings from a TOML/YAML file
+// Created with love by Aye & Hue - Schema changes on your terms! ✨
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::auth::{
+    self,
+    keys::{self, KeyRing, TokenAlgorithm, TokenKeys, TokenSigner},
+};
+use crate::config::Config;
+use crate::database::{
+    self,
+    migrations::{self, Migration, MigrationState, MigrationStatus},
+    models::{SigningKey, User, UserRole},
+};
+use crate::doctor;
+use crate::feedback_lifecycle;
+use crate::jobs;
+use crate::mcp;
+use crate::middleware::{self, rate_limiting::RateLimitTier};
+use crate::secrets;
+
+/// 🚢 Feedbacker - AI-powered repository management
+#[derive(Debug, Parser)]
+#[command(name = "feedbacker", version, about)]
+pub struct Cli {
+    /// 📄 Config file (TOML or YAML); environment variables override its values
+    /// [default: $FEEDBACKER_CONFIG, else ./feedbacker.toml if present]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// 🗄️ Apply pending migrations and exit instead of starting the service
+    /// (`migrate up` for deploy pipelines that run the service's own command line)
+    #[arg(long)]
+    pub migrate_only: bool,
+
+    /// 🎯 What to do (start the service when omitted)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// 🎯 The maintenance command to run instead of the service, if any
+    pub fn maintenance_command(&mut self) -> Result<Option<Command>> {
+        match (self.migrate_only, self.command.take()) {
+            (true, Some(_)) => anyhow::bail!("--migrate-only can't be combined with a subcommand"),
+            (true, None) => Ok(Some(Command::Migrate {
+                dry_run: false,
+                action: MigrateAction::Up,
+            })),
+            (false, command) => Ok(command),
+        }
+    }
+}
+
+/// 🎯 Maintenance commands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 🗄️ Inspect and change the database schema
+    Migrate {
+        /// 👀 Print the SQL that would run instead of running it
+        #[arg(long, global = true)]
+        dry_run: bool,
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// 📋 Load and validate the configuration, without contacting anything
+    CheckConfig,
+    /// 🩺 Validate the configuration, then probe the database, GitHub, LLM providers and SMTP
+    Doctor,
+    /// 👑 Create an admin account (prints a generated password unless --password-stdin)
+    CreateAdmin {
+        /// 📧 Email address to log in with
+        #[arg(long)]
+        email: String,
+        /// 👤 Display name [default: the part of the email before @]
+        #[arg(long)]
+        name: Option<String>,
+        /// ⌨️ Read the password from the first line of stdin
+        #[arg(long)]
+        password_stdin: bool,
+        /// ⬆️ Make an existing account an admin instead of failing
+        #[arg(long)]
+        promote: bool,
+    },
+    /// 🔐 Generate a new JWT secret and re-sign every service account's API key with it
+    RotateJwtSecret {
+        /// 🚫 Revoke every token right away, before the new secret is deployed
+        /// (for a leaked secret; users sign in again and services need their new keys)
+        #[arg(long)]
+        revoke_now: bool,
+        /// ⏳ Days the re-signed API keys stay valid
+        #[arg(long, default_value_t = 365)]
+        api_key_days: u64,
+    },
+    /// 🔑 Issue a new API key for a service account and revoke its old ones
+    RotateApiKey {
+        /// 📧 Email of the service account
+        #[arg(long)]
+        email: String,
+        /// ⏳ Days the new key stays valid
+        #[arg(long, default_value_t = 365)]
+        days: u64,
+        /// 🔑 Limit the key to these scopes, comma-separated (omitted = unchanged)
+        #[arg(long, value_delimiter = ',', conflicts_with = "all_scopes")]
+        scopes: Option<Vec<String>>,
+        /// 🔓 Lift the key's scope limits
+        #[arg(long)]
+        all_scopes: bool,
+        /// 🎚️ Rate limit tier: standard, trusted or unlimited (omitted = unchanged)
+        #[arg(long)]
+        rate_limit_tier: Option<RateLimitTier>,
+    },
+    /// 🔏 Make a new token signing key current and re-sign every service account's API key with it
+    RotateSigningKey {
+        /// 📥 Use this PEM private key (Ed25519 or RSA) instead of generating an Ed25519 one
+        #[arg(long, value_name = "PATH")]
+        private_key: Option<PathBuf>,
+        /// ⏳ Hours tokens signed with the previous key keep being accepted
+        #[arg(long, default_value_t = 168)]
+        overlap_hours: u32,
+        /// ⏳ Days the re-signed API keys stay valid
+        #[arg(long, default_value_t = 365)]
+        api_key_days: u64,
+    },
+    /// 🧾 Replay feedback event timelines and repair status columns that don't match them
+    RebuildProjections {
+        /// 📝 Only this feedback item
+        #[arg(long)]
+        feedback: Option<Uuid>,
+        /// 👀 Report rows that are out of date without changing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 🤖 Serve the MCP tools (submit_feedback, get_feedback_status, list_projects) on stdin/stdout
+    Mcp,
+}
+
+/// 🗄️ Migration commands
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+pub enum MigrateAction {
+    /// 📋 List every migration and whether it is applied
+    Status,
+    /// ⬆️ Apply all pending migrations
+    Up,
+    /// ⬇️ Roll back one applied migration
+    Down {
+        /// 🆔 Id of the migration, as shown by `migrate status`
+        id: String,
+    },
+    /// 🔁 Roll back the most recently applied migration and apply it again
+    Redo,
+}
+
+/// 🚀 Run a maintenance command
+pub async fn run(command: Command, config_path: Option<&Path>) -> Result<()> {
+    match command {
+        Command::Migrate { dry_run, action } => {
+            let config = Config::load_from(config_path)
+                .context("Failed to load configuration - check your environment variables!")?;
+            let pool = database::create_pool(&config.database)
+                .await
+                .context("Failed to create database connection pool")?;
+            migrate(&pool, action, dry_run).await
+        }
+        Command::CheckConfig => doctor::run_check_config(config_path),
+        Command::Doctor => doctor::run_doctor(config_path).await,
+        Command::CreateAdmin {
+            email,
+            name,
+            password_stdin,
+            promote,
+        } => {
+            let (_, pool) = connect(config_path).await?;
+            create_admin(&pool, &email, name, password_stdin, promote).await
+        }
+        Command::RotateJwtSecret {
+            revoke_now,
+            api_key_days,
+        } => {
+            let (config, pool) = connect(config_path).await?;
+            rotate_jwt_secret(&config, &pool, revoke_now, api_key_days).await
+        }
+        Command::RotateApiKey {
+            email,
+            days,
+            scopes,
+            all_scopes,
+            rate_limit_tier,
+        } => {
+            let (mut config, pool) = connect(config_path).await?;
+            // 🔑 Keys are signed with the secret itself, not its reference
+            let resolver = secrets::SecretResolver::new(&config.secrets);
+            secrets::resolve_config(&resolver, &mut config)
+                .await
+                .context("Failed to read secrets from the secrets manager")?;
+            // 🔑 An empty list lifts the scope limits
+            let scopes = if all_scopes { Some(Vec::new()) } else { scopes };
+            rotate_api_key(&config, &pool, &email, days, scopes, rate_limit_tier).await
+        }
+        Command::RotateSigningKey {
+            private_key,
+            overlap_hours,
+            api_key_days,
+        } => {
+            let (config, pool) = connect(config_path).await?;
+            rotate_signing_key(
+                &config,
+                &pool,
+                private_key.as_deref(),
+                overlap_hours,
+                api_key_days,
+            )
+            .await
+        }
+        Command::RebuildProjections { feedback, dry_run } => {
+            let (_, pool) = connect(config_path).await?;
+            let summary = feedback_lifecycle::rebuild(&pool, feedback, dry_run).await?;
+            println!(
+                "🧾 {} feedback timelines replayed, {} rows {}",
+                summary.checked,
+                summary.repaired,
+                if dry_run { "out of date" } else { "repaired" }
+            );
+            Ok(())
+        }
+        Command::Mcp => serve_mcp(config_path).await,
+    }
+}
+
+/// 🔗 The configuration and a pool for its database, with the schema up to date enough
+async fn connect(config_path: Option<&Path>) -> Result<(Config, PgPool)> {
+    let config = Config::load_from(config_path)
+        .context("Failed to load configuration - check your environment variables!")?;
+    let pool = database::create_pool(&config.database)
+        .await
+        .context("Failed to create database connection pool")?;
+    let pending = migrations::migration_status(
+        &migrations::get_all_migrations(),
+        &migrations::list_applied_migrations(&pool).await?,
+    )
+    .into_iter()
+    .filter(|status| status.state == MigrationState::Pending)
+    .count();
+    if pending > 0 {
+        anyhow::bail!(
+            "{} migrations are pending; run `feedbacker migrate up` first",
+            pending
+        );
+    }
+    Ok((config, pool))
+}
+
+/// 🤖 Serve MCP on stdio as the account of $FEEDBACKER_API_KEY
+async fn serve_mcp(config_path: Option<&Path>) -> Result<()> {
+    let token = std::env::var("FEEDBACKER_API_KEY")
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .context("Set FEEDBACKER_API_KEY to the API key (or login token) to act as")?;
+
+    let (mut config, pool) = connect(config_path).await?;
+    // 🔑 Tokens signed with the secret are checked against the secret itself
+    let resolver = secrets::SecretResolver::new(&config.secrets);
+    secrets::resolve_config(&resolver, &mut config)
+        .await
+        .context("Failed to read secrets from the secrets manager")?;
+    let queue = jobs::queue::connect(&config.jobs, pool.clone())
+        .await
+        .context("Failed to connect the job queue")?;
+    let app_state = AppState::new(config, config_path, pool, queue);
+
+    let user = middleware::auth::authenticate_token(&app_state, token.trim())
+        .await
+        .context("FEEDBACKER_API_KEY was not accepted")?;
+    mcp::serve_stdio(mcp::McpServer::new(app_state, user)).await
+}
+
+/// 👑 Create an admin account, or promote an existing one
+async fn create_admin(
+    pool: &PgPool,
+    email: &str,
+    name: Option<String>,
+    password_stdin: bool,
+    promote: bool,
+) -> Result<()> {
+    let email = email.trim();
+    let Some((local_part, _)) = email.split_once('@') else {
+        anyhow::bail!("'{}' is not an email address", email);
+    };
+
+    if let Some(mut user) = User::find_by_email(pool, email).await? {
+        if !promote {
+            anyhow::bail!(
+                "An account for {} already exists; add --promote to make it an admin",
+                email
+            );
+        }
+        if matches!(user.role, UserRole::Admin) {
+            println!("✅ {} is already an admin", user.email);
+            return Ok(());
+        }
+        user.set_role(pool, UserRole::Admin).await?;
+        println!("👑 {} is now an admin", user.email);
+        return Ok(());
+    }
+
+    let (password, generated) = if password_stdin {
+        let mut line = String::new();
+        std::io::stdin()
+            .read_line(&mut line)
+            .context("Failed to read the password from stdin")?;
+        (line.trim_end_matches(['\r', '\n']).to_string(), false)
+    } else {
+        (auth::generate_secret(auth::GENERATED_PASSWORD_LENGTH), true)
+    };
+    if password.len() < auth::MIN_PASSWORD_LENGTH {
+        anyhow::bail!(
+            "Password must be at least {} characters",
+            auth::MIN_PASSWORD_LENGTH
+        );
+    }
+
+    let name = name.unwrap_or_else(|| local_part.to_string());
+    let user = User::create(
+        pool,
+        email.to_string(),
+        name,
+        auth::hash_password(&password)?,
+        UserRole::Admin,
+    )
+    .await?;
+    println!("👑 Created admin {} ({})", user.email, user.id);
+    if generated {
+        println!("🔑 Password: {}", password);
+        println!("   It is shown only once - store it in your password manager");
+    }
+    Ok(())
+}
+
+/// 🔐 Generate a new JWT secret and API keys signed with it
+async fn rotate_jwt_secret(
+    config: &Config,
+    pool: &PgPool,
+    revoke_now: bool,
+    api_key_days: u64,
+) -> Result<()> {
+    let secret = auth::generate_secret(auth::JWT_SECRET_LENGTH);
+
+    if revoke_now {
+        let revoked = User::revoke_tokens(pool, None).await?;
+        println!("🚫 Revoked the tokens and sessions of {} accounts", revoked);
+    }
+
+    // 🔏 With key pairs the secret only verifies older HS256 tokens
+    let signs_with_secret = config.auth.jwt_algorithm == TokenAlgorithm::Hs256;
+    let services = if signs_with_secret {
+        User::list_active_by_role(pool, UserRole::Service).await?
+    } else {
+        Vec::new()
+    };
+    let signer = TokenSigner::Secret(secret.clone());
+    let keys = services
+        .iter()
+        .map(|user| Ok((user, auth::issue_api_key(user, &signer, api_key_days)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    println!("🔐 New JWT secret:\n\n    {}\n", secret);
+    match secrets::SecretRef::parse(&config.auth.jwt_secret) {
+        Some(Ok(reference)) => println!(
+            "   Store it at {}; running instances pick it up within {}s",
+            reference, config.secrets.refresh_seconds
+        ),
+        _ => println!("   Set JWT_SECRET to it and restart the service"),
+    }
+    if revoke_now {
+        println!("   Tokens signed with the old secret are already rejected");
+    } else {
+        println!("   Tokens signed with the old secret stop working once it is deployed");
+    }
+
+    if !signs_with_secret {
+        println!(
+            "\n🔏 Tokens are signed with {} key pairs, so API keys were not re-signed;",
+            config.auth.jwt_algorithm.as_str()
+        );
+        println!("   rotate the signing key with `feedbacker rotate-signing-key`");
+    } else {
+        print_api_keys(&keys, "the new secret", api_key_days);
+    }
+    Ok(())
+}
+
+/// 🔏 Make a new signing key current and API keys signed with it
+async fn rotate_signing_key(
+    config: &Config,
+    pool: &PgPool,
+    private_key: Option<&Path>,
+    overlap_hours: u32,
+    api_key_days: u64,
+) -> Result<()> {
+    let new_key = match private_key {
+        Some(path) => {
+            let pem = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            keys::import_pem(&pem)?
+        }
+        None => keys::generate_ed25519()?,
+    };
+
+    let key = SigningKey::rotate(
+        pool,
+        &new_key.kid,
+        new_key.algorithm.as_str(),
+        &new_key.private_key,
+        &new_key.public_jwk,
+        overlap_hours as i64,
+    )
+    .await?;
+    let removed = SigningKey::delete_expired(pool).await?;
+
+    let signer = KeyRing::new(vec![key.clone()])
+        .current()
+        .cloned()
+        .context("The new signing key can't sign")?;
+    let services = User::list_active_by_role(pool, UserRole::Service).await?;
+    let api_keys = services
+        .iter()
+        .map(|user| Ok((user, auth::issue_api_key(user, &signer, api_key_days)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    println!(
+        "🔏 Signing key {} ({}) is now current",
+        key.kid, key.algorithm
+    );
+    println!(
+        "   Running instances sign with it within a minute; tokens signed with the previous key are accepted for {} more hours",
+        overlap_hours
+    );
+    if removed > 0 {
+        println!("🧹 Deleted {} expired signing keys", removed);
+    }
+    if config.auth.jwt_algorithm == TokenAlgorithm::Hs256 {
+        println!("⚠️ JWT_SIGNING_ALGORITHM is HS256, so tokens stay signed with JWT_SECRET until it is changed");
+    }
+    print_api_keys(&api_keys, "the new key", api_key_days);
+    Ok(())
+}
+
+/// 🔑 Print re-signed API keys, one service account per line
+fn print_api_keys(keys: &[(&User, String)], signed_with: &str, days: u64) {
+    if keys.is_empty() {
+        println!("\n🔑 No service accounts have API keys to re-sign");
+    } else {
+        println!(
+            "\n🔑 API keys signed with {} (valid {} days; hand them to each service):",
+            signed_with, days
+        );
+        for (user, key) in keys {
+            println!("   {}  {}", user.email, key);
+        }
+    }
+}
+
+/// 🔑 Revoke a service account's tokens and issue a new API key
+/// Scopes and tier are changed when given (an empty scope list lifts the limits)
+async fn rotate_api_key(
+    config: &Config,
+    pool: &PgPool,
+    email: &str,
+    days: u64,
+    scopes: Option<Vec<String>>,
+    rate_limit_tier: Option<RateLimitTier>,
+) -> Result<()> {
+    let scopes = scopes
+        .map(|names| auth::validate_scopes(&names))
+        .transpose()
+        .map_err(|errors| anyhow::anyhow!(errors.join("; ")))?;
+    let mut user = User::find_by_email(pool, email)
+        .await?
+        .with_context(|| format!("No account for {}", email))?;
+    if !user.is_active {
+        anyhow::bail!("{} is disabled", user.email);
+    }
+    if !matches!(user.role, UserRole::Service) {
+        anyhow::bail!(
+            "{} is not a service account; API keys are only issued to service accounts",
+            user.email
+        );
+    }
+
+    auth::update_api_key_limits(pool, &mut user, scopes, rate_limit_tier).await?;
+
+    User::revoke_tokens(pool, Some(user.id)).await?;
+    let signer = TokenKeys::new(pool.clone()).signer(&config.auth).await;
+    let key = auth::issue_api_key(&user, &signer, days)?;
+    println!("🚫 Revoked every earlier key and session of {}", user.email);
+    println!(
+        "🎚️ Scopes: {}; rate limit tier: {}",
+        user.api_scopes
+            .as_ref()
+            .map_or("all".to_string(), |scopes| scopes.join(", ")),
+        user.rate_limit_tier.as_deref().unwrap_or("standard")
+    );
+    println!(
+        "🔑 New API key (valid {} days, shown only once):\n\n    {}",
+        days, key
+    );
+    Ok(())
+}
+
+/// 🗄️ Run one migration command
+async fn migrate(pool: &PgPool, action: MigrateAction, dry_run: bool) -> Result<()> {
+    let statuses = migrations::migration_status(
+        &migrations::get_all_migrations(),
+        &migrations::list_applied_migrations(pool).await?,
+    );
+
+    match action {
+        MigrateAction::Status => print_status(&statuses),
+        MigrateAction::Up => migrate_up(pool, &statuses, dry_run).await?,
+        MigrateAction::Down { id } => migrate_down(pool, &statuses, &id, dry_run).await?,
+        MigrateAction::Redo => migrate_redo(pool, &statuses, dry_run).await?,
+    }
+    Ok(())
+}
+
+/// 📋 Print one line per migration, then a summary
+fn print_status(statuses: &[MigrationStatus]) {
+    for status in statuses {
+        let applied_at = status
+            .applied_at
+            .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<9} {:<45} {:<19}  {}",
+            status.state.as_str(),
+            status.id,
+            applied_at,
+            status.description
+        );
+    }
+
+    let count = |state| statuses.iter().filter(|s| s.state == state).count();
+    println!(
+        "\n📋 {} applied, {} pending",
+        statuses.iter().filter(|s| s.state.is_applied()).count(),
+        count(MigrationState::Pending)
+    );
+    if count(MigrationState::Modified) > 0 {
+        println!("⚠️ Some applied migrations have changed since they ran");
+    }
+    if count(MigrationState::Unknown) > 0 {
+        println!("⚠️ Some applied migrations are unknown to this build");
+    }
+}
+
+/// ⬆️ Apply (or print) every pending migration
+async fn migrate_up(pool: &PgPool, statuses: &[MigrationStatus], dry_run: bool) -> Result<()> {
+    let pending: Vec<&MigrationStatus> = statuses
+        .iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .collect();
+    if pending.is_empty() {
+        println!("✅ Database schema is up to date");
+        return Ok(());
+    }
+
+    if dry_run {
+        for status in pending {
+            print_sql("⬆️", &migrations::find_migration(&status.id)?, true);
+        }
+        return Ok(());
+    }
+
+    database::run_migrations(pool).await?;
+    println!("✅ Applied {} migrations", pending.len());
+    Ok(())
+}
+
+/// ⬇️ Roll back (or print the rollback of) one applied migration
+async fn migrate_down(
+    pool: &PgPool,
+    statuses: &[MigrationStatus],
+    id: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let migration = rollback_target(statuses, id)?;
+
+    // ⚠️ Later migrations may depend on this one
+    let later = statuses
+        .iter()
+        .filter(|status| status.state.is_applied() && status.id.as_str() > id)
+        .count();
+    if later > 0 {
+        println!("⚠️ {} later migrations are still applied", later);
+    }
+
+    if dry_run {
+        print_sql("⬇️", &migration, false);
+        return Ok(());
+    }
+
+    database::with_migration_lock(pool, || migrations::rollback_migration(pool, id)).await?;
+    println!("✅ Rolled back {}", id);
+    Ok(())
+}
+
+/// 🔁 Roll back the most recently applied migration and apply it again
+async fn migrate_redo(pool: &PgPool, statuses: &[MigrationStatus], dry_run: bool) -> Result<()> {
+    let latest = statuses
+        .iter()
+        .filter(|status| status.state.is_applied())
+        .max_by_key(|status| (status.applied_at, status.id.clone()))
+        .context("No migrations are applied")?;
+    let migration = rollback_target(statuses, &latest.id)?;
+
+    if dry_run {
+        print_sql("⬇️", &migration, false);
+        print_sql("⬆️", &migration, true);
+        return Ok(());
+    }
+
+    database::with_migration_lock(pool, || async {
+        migrations::rollback_migration(pool, &migration.id).await?;
+        migrations::apply_migration_by_id(pool, &migration.id).await
+    })
+    .await?;
+    println!("✅ Redid {}", migration.id);
+    Ok(())
+}
+
+/// 🎯 The migration `id` if it is applied and can be rolled back
+fn rollback_target(statuses: &[MigrationStatus], id: &str) -> Result<Migration> {
+    let status = statuses
+        .iter()
+        .find(|status| status.id == id)
+        .with_context(|| format!("Migration {} not found", id))?;
+    match status.state {
+        MigrationState::Pending => anyhow::bail!("Migration {} is not applied", id),
+        MigrationState::Unknown => {
+            anyhow::bail!(
+                "Migration {} is unknown to this build; it has no rollback SQL here",
+                id
+            )
+        }
+        MigrationState::Applied | MigrationState::Modified => {}
+    }
+
+    let migration = migrations::find_migration(id)?;
+    if migration.down_sql.is_none() {
+        anyhow::bail!("Migration {} does not have rollback SQL", id);
+    }
+    Ok(migration)
+}
+
+/// 👀 Print the SQL of one direction of a migration
+fn print_sql(arrow: &str, migration: &Migration, up: bool) {
+    let sql = if up {
+        Some(&migration.up_sql)
+    } else {
+        migration.down_sql.as_ref()
+    };
+    println!("-- {} {} - {}", arrow, migration.id, migration.description);
+    println!("{}\n", sql.map(|sql| sql.trim()).unwrap_or("-- (no SQL)"));
+}
+
+// 🧪 Tests - The command line says what you meant!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_migrate_commands_parse() {
+        let parse = |args: &[&str]| Cli::try_parse_from(args).map(|cli| cli.command);
+
+        assert!(parse(&["feedbacker"]).unwrap().is_none());
+        match parse(&[
+            "feedbacker",
+            "migrate",
+            "down",
+            "20240101000016_x",
+            "--dry-run",
+        ]) {
+            Ok(Some(Command::Migrate { dry_run, action })) => {
+                assert!(dry_run);
+                assert_eq!(
+                    action,
+                    MigrateAction::Down {
+                        id: "20240101000016_x".to_string()
+                    }
+                );
+            }
+            other => panic!("unexpected parse: {:?}", other),
+        }
+        assert!(matches!(
+            parse(&["feedbacker", "migrate", "--dry-run", "redo"]),
+            Ok(Some(Command::Migrate {
+                dry_run: true,
+                action: MigrateAction::Redo
+            }))
+        ));
+        assert!(parse(&["feedbacker", "migrate", "down"]).is_err());
+        assert!(parse(&["feedbacker", "migrate", "sideways"]).is_err());
+        assert!(matches!(
+            parse(&["feedbacker", "check-config"]),
+            Ok(Some(Command::CheckConfig))
+        ));
+        assert!(matches!(
+            parse(&["feedbacker", "doctor", "--config", "prod.toml"]),
+            Ok(Some(Command::Doctor))
+        ));
+        match parse(&["feedbacker", "create-admin", "--email", "ops@example.com"]) {
+            Ok(Some(Command::CreateAdmin {
+                email,
+                name: None,
+                password_stdin: false,
+                promote: false,
+            })) => assert_eq!(email, "ops@example.com"),
+            other => panic!("unexpected parse: {:?}", other),
+        }
+        assert!(parse(&["feedbacker", "create-admin"]).is_err());
+        assert!(matches!(
+            parse(&["feedbacker", "rotate-jwt-secret", "--revoke-now"]),
+            Ok(Some(Command::RotateJwtSecret {
+                revoke_now: true,
+                api_key_days: 365
+            }))
+        ));
+        assert!(matches!(
+            parse(&[
+                "feedbacker",
+                "rotate-api-key",
+                "--email",
+                "ci@example.com",
+                "--days",
+                "30"
+            ]),
+            Ok(Some(Command::RotateApiKey { days: 30, .. }))
+        ));
+        assert!(matches!(
+            parse(&[
+                "feedbacker",
+                "rotate-api-key",
+                "--email",
+                "smart-tree@example.com",
+                "--scopes",
+                "feedback:write,projects:read",
+                "--rate-limit-tier",
+                "trusted"
+            ]),
+            Ok(Some(Command::RotateApiKey {
+                scopes: Some(scopes),
+                all_scopes: false,
+                rate_limit_tier: Some(RateLimitTier::Trusted),
+                ..
+            })) if scopes == ["feedback:write", "projects:read"]
+        ));
+        assert!(parse(&[
+            "feedbacker",
+            "rotate-api-key",
+            "--email",
+            "ci@example.com",
+            "--scopes",
+            "admin",
+            "--all-scopes"
+        ])
+        .is_err());
+        assert!(matches!(
+            parse(&[
+                "feedbacker",
+                "rotate-signing-key",
+                "--private-key",
+                "rsa.pem",
+                "--overlap-hours",
+                "24"
+            ]),
+            Ok(Some(Command::RotateSigningKey {
+                private_key: Some(_),
+                overlap_hours: 24,
+                api_key_days: 365
+            }))
+        ));
+
+        assert!(matches!(
+            parse(&["feedbacker", "rebuild-projections", "--dry-run"]),
+            Ok(Some(Command::RebuildProjections {
+                feedback: None,
+                dry_run: true
+            }))
+        ));
+        assert!(parse(&["feedbacker", "rebuild-projections", "--feedback", "nope"]).is_err());
+
+        let cli = Cli::try_parse_from(["feedbacker", "--config", "prod.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("prod.toml")));
+        assert!(cli.command.is_none());
+
+        let maintenance = |args: &[&str]| Cli::try_parse_from(args).unwrap().maintenance_command();
+        assert!(maintenance(&["feedbacker"]).unwrap().is_none());
+        assert!(matches!(
+            maintenance(&["feedbacker", "--migrate-only"]),
+            Ok(Some(Command::Migrate {
+                dry_run: false,
+                action: MigrateAction::Up
+            }))
+        ));
+        assert!(maintenance(&["feedbacker", "--migrate-only", "doctor"]).is_err());
+        println!("✅ Migrate command parsing test passed!");
+    }
+
+    #[test]
+    fn test_rollback_target() {
+        let migrations = migrations::get_all_migrations();
+        let status = |migration: &Migration, state, applied: bool| MigrationStatus {
+            id: migration.id.clone(),
+            description: migration.description.clone(),
+            state,
+            applied_at: applied.then(Utc::now),
+        };
+        let statuses = vec![
+            status(&migrations[0], MigrationState::Applied, true),
+            status(&migrations[1], MigrationState::Pending, false),
+        ];
+
+        assert_eq!(
+            rollback_target(&statuses, &migrations[0].id).unwrap().id,
+            migrations[0].id
+        );
+        let pending = rollback_target(&statuses, &migrations[1].id).unwrap_err();
+        assert!(pending.to_string().contains("not applied"));
+        assert!(rollback_target(&statuses, "nope").is_err());
+        println!("✅ Rollback target test passed!");
+    }
+}
@@ -0,0 +1,83 @@
+// 👥 Collaborator Sync - Your Repo's Team Is Your Project's Team! 👥
+// Projects that set `"collaborators": { "sync": true }` mirror who can push to
+// their repository. Every hour the `collaborator_sync` schedule lists everyone
+// with at least the configured permission (push by default), directly, as
+// organization members or through teams, and stores their logins. A Feedbacker
+// user whose GitHub username is on the list gets a member role on the project,
+// so they see its feedback, approve its pull requests and start its runs
+// without being added to an organization by hand. Projects that stop syncing
+// lose their list on the next run. A repository with more collaborators than
+// GitHub lets us read fails its sync and keeps the list it had
+// Created with love by Aye & Hue - Push access, meet approval rights! ✨
+
+use anyhow::Result;
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::api::AppState;
+use crate::config::GitHubConfig;
+use crate::database::models::{Project, ProjectCollaborator};
+use crate::github::{parse_repository, GitHubClient};
+use crate::jobs::worker::{self, JobHandler};
+use crate::scm;
+
+/// 🔧 Job type of the hourly sync
+pub const COLLABORATOR_SYNC_JOB: &str = "collaborator_sync";
+
+/// 🔧 Worker pool handler that runs a sync
+pub fn collaborator_sync_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |_job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        async move { sync_all(&db_pool, &config.github).await }
+    })
+}
+
+/// 🔄 Sync every project that mirrors its collaborators, then drop the lists
+/// of projects that stopped. One project failing doesn't hold up the others
+pub async fn sync_all(pool: &PgPool, github: &GitHubConfig) -> Result<()> {
+    let projects = Project::list_collaborator_sync(pool).await?;
+    let mut synced = 0;
+    for project in &projects {
+        match sync_project(pool, github, project).await {
+            Ok(count) => {
+                synced += 1;
+                info!(
+                    "👥 {} has {} collaborators with access",
+                    project.repository, count
+                );
+            }
+            Err(e) => warn!(
+                "⚠️ Could not sync collaborators of {}: {:#}",
+                project.repository, e
+            ),
+        }
+    }
+    let cleared = ProjectCollaborator::clear_unsynced(pool).await?;
+    info!(
+        "👥 Collaborator sync: {}/{} projects synced, {} stale logins cleared",
+        synced,
+        projects.len(),
+        cleared
+    );
+    Ok(())
+}
+
+/// 👥 Replace one project's collaborators with the repository's current ones.
+/// A listing that couldn't be read in full errors before anything is replaced
+pub async fn sync_project(
+    pool: &PgPool,
+    github: &GitHubConfig,
+    project: &Project,
+) -> Result<usize> {
+    let settings = project.settings()?;
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let client = GitHubClient::new(scm::github_config(pool, github, project).await?)?;
+    let logins = client
+        .list_collaborators(&owner, &repo, settings.collaborators.permission.as_str())
+        .await?;
+    ProjectCollaborator::replace(pool, project.id, &logins).await?;
+    Ok(logins.len())
+}
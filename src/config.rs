@@ -4,9 +4,14 @@
 // Trisha from Accounting loves organized settings, so we made this EXTRA organized! 📊
 
 use anyhow::{Context, Result};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::env;
-use std::path::Path;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 // 🎯 Main configuration structure - The heart of our settings!
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +34,28 @@ pub struct Config {
     pub logging: LoggingConfig,
     /// 🔧 Feature flags and toggles
     pub features: FeaturesConfig,
+    /// 🧪 Sandbox for running generated tests
+    pub sandbox: SandboxConfig,
+    /// 🔄 Background job workers
+    pub jobs: JobsConfig,
+    /// 🧹 How long old records are kept
+    pub retention: RetentionConfig,
+    /// 📦 Feedback exports
+    pub exports: ExportConfig,
+    /// 🗄️ Pipeline artifacts (diffs, plans, sandbox logs)
+    pub artifacts: ArtifactConfig,
+    /// 📤 Feedback and pull request events published to Kafka or NATS
+    pub event_stream: EventStreamConfig,
+    /// ⏱️ Feedback stuck mid-pipeline, failed or requeued
+    pub watchdog: WatchdogConfig,
+    /// 💸 Token spend caps per feedback item and per project
+    pub budgets: BudgetConfig,
+    /// 🗃️ Response cache of the hot read endpoints
+    pub cache: CacheConfig,
+    /// 🔑 Secrets managers that credentials can be read from
+    pub secrets: SecretsConfig,
+    /// 🚧 Maintenance mode forced from configuration
+    pub maintenance: MaintenanceConfig,
 }
 
 // 🌐 Server configuration - Where we listen and how we behave
@@ -42,6 +69,55 @@ pub struct ServerConfig {
     pub max_body_size: usize,
     /// 🌍 Environment (development, staging, production)
     pub environment: Environment,
+    /// 🔗 Public base URL used in links we hand out (PR bodies, emails)
+    pub public_url: String,
+    /// 🌐 Proxies whose forwarding headers name the real client (empty = trust none)
+    pub trusted_proxies: Vec<IpNet>,
+    /// 🧱 Networks allowed to reach the admin and webhook routes
+    pub ip_allowlists: IpAllowlistConfig,
+    /// 🔒 TLS termination (off when a reverse proxy does it)
+    pub tls: TlsConfig,
+}
+
+// 🧱 IP allowlists - Who may reach the admin and webhook routes (see middleware::ip_allowlist)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpAllowlistConfig {
+    /// 👑 Networks allowed to reach the admin API and console (empty = anyone)
+    pub admin: Vec<IpNet>,
+    /// 🪝 Networks allowed to deliver webhooks (empty = anyone, unless `github_hooks`)
+    pub webhooks: Vec<IpNet>,
+    /// 🐙 Also allow webhooks from GitHub's published hook ranges (GET /meta)
+    pub github_hooks: bool,
+}
+
+// 🔒 TLS configuration - HTTPS without a reverse proxy (see crate::tls)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// 🔐 Where certificates come from
+    pub mode: TlsMode,
+    /// ↪️ Port of a plain HTTP listener that redirects to HTTPS (None = no listener)
+    pub redirect_port: Option<u16>,
+}
+
+/// 🔐 Where the server's certificates come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum TlsMode {
+    /// 🔓 Plain HTTP
+    Off,
+    /// 📜 PEM certificate chain and private key files
+    Files { cert_path: PathBuf, key_path: PathBuf },
+    /// 🔏 Issued and renewed through ACME (Let's Encrypt)
+    Acme {
+        /// 🌐 Domains the certificate covers
+        domains: Vec<String>,
+        /// 📧 Contact address for expiry notices
+        contact: Option<String>,
+        /// 💾 Where the account key and certificates are kept between restarts
+        cache_dir: PathBuf,
+        /// 🧪 Use Let's Encrypt's staging directory (untrusted, but generous rate limits)
+        staging: bool,
+    },
 }
 
 // 🗄️ Database configuration - Our data storage settings
@@ -51,10 +127,24 @@ pub struct DatabaseConfig {
     pub url: String,
     /// 🏊‍♂️ Maximum number of connections in the pool
     pub max_connections: u32,
-    /// ⏱️ Connection timeout in seconds
+    /// 🔄 Connections kept open even when idle
+    pub min_connections: u32,
+    /// ⏱️ Connection timeout in seconds (how long a query waits for a free connection)
     pub connection_timeout_seconds: u64,
+    /// 💤 Idle connections above the minimum are closed after this long
+    pub idle_timeout_seconds: u64,
+    /// 🔄 Connections are recreated after this long
+    pub max_lifetime_seconds: u64,
+    /// ⏱️ Server-side limit for one statement (0 = no limit)
+    pub statement_timeout_ms: u64,
+    /// 🐢 Queries slower than this are logged and counted as slow
+    pub slow_query_ms: u64,
+    /// 🏷️ Shown in pg_stat_activity, so our connections are easy to spot
+    pub application_name: String,
     /// 🔄 Enable automatic migrations
     pub auto_migrate: bool,
+    /// 🔏 What to do on startup when applied migrations no longer match their SQL
+    pub migration_drift: MigrationDrift,
 }
 
 // 🐙 GitHub configuration - Settings for the legendary aye-is user!
@@ -72,6 +162,29 @@ pub struct GitHubConfig {
     pub default_commit_message: String,
     /// 🌿 Default branch name for new branches
     pub default_branch_prefix: String,
+    /// 🗃️ Directory holding cached repository clones
+    pub clone_cache_dir: String,
+    /// 📦 Maximum number of cached clones kept on disk
+    pub clone_cache_size: usize,
+    /// 🚦 API requests kept back for urgent work (deferrable calls wait below this)
+    pub rate_limit_reserve: u64,
+    /// ⏳ Longest wait for the rate limit before a call gives up
+    pub rate_limit_max_wait_seconds: u64,
+    /// 🔁 Times a rate limited request is sent again
+    pub rate_limit_retries: u32,
+    /// 🏢 Other GitHub instances projects can be hosted on (GITHUB_ENDPOINTS)
+    pub endpoints: Vec<GitHubEndpoint>,
+}
+
+/// 🏢 A GitHub Enterprise Server instance, picked by projects and organizations by name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitHubEndpoint {
+    /// 🏷️ Name used in `scm.endpoint` settings
+    pub name: String,
+    /// 🏠 REST API base URL, e.g. https://ghe.example.com/api/v3
+    pub api_base_url: String,
+    /// 🔑 Token for it, from GITHUB_TOKEN_<NAME>
+    pub token: String,
 }
 
 // 🤖 LLM configuration - Settings for all our AI friends!
@@ -81,12 +194,35 @@ pub struct LlmConfig {
     pub openai: Option<OpenAiConfig>,
     /// 🎭 Anthropic configuration
     pub anthropic: Option<AnthropicConfig>,
+    /// 🔌 OpenAI-compatible endpoint (vLLM, LM Studio, Together, ...)
+    pub custom: Option<CustomLlmConfig>,
     /// 🔄 Default provider to use
     pub default_provider: LlmProvider,
     /// ⏱️ Request timeout in seconds
     pub timeout_seconds: u64,
     /// 🔄 Maximum retry attempts
     pub max_retries: u32,
+    /// 🪂 Providers tried, in order, when the default one fails
+    pub fallback_providers: Vec<LlmProvider>,
+    /// 🔌 Consecutive failures that open a provider's circuit breaker
+    pub circuit_breaker_threshold: u32,
+    /// ⏱️ Seconds an open breaker waits before a half-open probe
+    pub circuit_breaker_cooldown_seconds: u64,
+    /// 🏓 Seconds a provider health ping may take
+    pub health_check_timeout_seconds: u64,
+    /// 🗄️ Seconds a provider health result is reused
+    pub health_check_cache_seconds: u64,
+    /// 🧭 Which tasks go to the small models
+    pub routing: RoutingConfig,
+}
+
+// 🧭 Model routing - Small models for small jobs!
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// 🏷️ Tier per task type (pipeline stage), on top of the built-in rules
+    pub tiers: HashMap<String, ModelTier>,
+    /// 📏 `auto` tasks with prompts up to this many tokens use the small model
+    pub small_prompt_tokens: usize,
 }
 
 // 🧠 OpenAI specific configuration
@@ -100,6 +236,10 @@ pub struct OpenAiConfig {
     pub temperature: f32,
     /// 📏 Maximum tokens in response
     pub max_tokens: u32,
+    /// 🪟 Context window override for models the token counter doesn't know
+    pub context_window: Option<usize>,
+    /// 🐣 Cheaper model for small tasks (routing is off for this provider when None)
+    pub small_model: Option<String>,
 }
 
 // 🎭 Anthropic specific configuration
@@ -111,6 +251,31 @@ pub struct AnthropicConfig {
     pub default_model: String,
     /// 📏 Maximum tokens in response
     pub max_tokens: u32,
+    /// 🪟 Context window override for models the token counter doesn't know
+    pub context_window: Option<usize>,
+    /// 🐣 Cheaper model for small tasks (routing is off for this provider when None)
+    pub small_model: Option<String>,
+}
+
+// 🔌 OpenAI-compatible endpoint configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomLlmConfig {
+    /// 🌐 API base URL, up to and including the version (e.g., "http://localhost:8000/v1")
+    pub base_url: String,
+    /// 🔑 API key, for backends that require one
+    pub api_key: Option<String>,
+    /// 🤖 Model to request
+    pub default_model: String,
+    /// 🌡️ Temperature for responses
+    pub temperature: f32,
+    /// 📏 Maximum tokens in response
+    pub max_tokens: u32,
+    /// 🪟 Context window of the model (the token counter can't know it)
+    pub context_window: Option<usize>,
+    /// 🐣 Cheaper model for small tasks (routing is off for this provider when None)
+    pub small_model: Option<String>,
+    /// 🧱 Whether the backend supports tool calling (schemas go in the prompt otherwise)
+    pub supports_tools: bool,
 }
 
 // 🔐 Authentication configuration
@@ -124,6 +289,14 @@ pub struct AuthConfig {
     pub password_salt_rounds: u32,
     /// 🔄 Enable user registration
     pub enable_registration: bool,
+    /// 🔑 Accept email + password logins (off = single sign-on only)
+    pub password_login: bool,
+    /// 🔏 How new tokens are signed (see crate::auth::keys)
+    pub jwt_algorithm: crate::auth::keys::TokenAlgorithm,
+    /// 🔐 Keep accepting HS256 tokens signed with the JWT secret
+    pub accept_hs256: bool,
+    /// 🔏 How far a signed request's timestamp may be from now, in seconds
+    pub request_signature_max_age_seconds: u64,
 }
 
 // 🚦 Rate limiting configuration
@@ -137,6 +310,12 @@ pub struct RateLimitConfig {
     pub burst_size: u32,
     /// ⏱️ Rate limit window in seconds
     pub window_seconds: u64,
+    /// 🤝 Requests per minute for API keys on the trusted tier
+    pub trusted_requests_per_minute: u32,
+    /// 🤝 Feedback submissions per hour for API keys on the trusted tier
+    pub trusted_feedback_per_hour: u32,
+    /// 🔄 Seconds between syncs of the counts with the database (0 = memory only)
+    pub sync_seconds: u64,
 }
 
 // 📧 Email configuration (optional feature)
@@ -167,6 +346,10 @@ pub struct LoggingConfig {
     pub file_path: Option<String>,
     /// 🔄 Enable request logging
     pub log_requests: bool,
+    /// 🩺 Busy paths (health checks, scrapes) whose successful requests are sampled
+    pub quiet_paths: Vec<String>,
+    /// 🎲 One in this many successful requests to a quiet path is logged (0 = none)
+    pub quiet_sample_every: u64,
 }
 
 // 🔧 Feature flags configuration
@@ -186,6 +369,229 @@ pub struct FeaturesConfig {
     pub enable_dev_features: bool,
 }
 
+// 🧪 Sandbox configuration - Where generated tests get to prove themselves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// 📂 Directory holding throwaway copies of checkouts
+    pub work_dir: String,
+    /// ⏱️ Wall-clock limit for one test command
+    pub timeout_seconds: u64,
+    /// 📏 Captured output kept per command (the tail is what matters)
+    pub max_output_bytes: usize,
+}
+
+// 🔄 Background job workers - How much runs at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsConfig {
+    /// 👷 Jobs running at the same time, across all types
+    pub workers: usize,
+    /// ⏱️ Fallback poll while idle (new jobs wake the workers through NOTIFY)
+    pub poll_interval_ms: u64,
+    /// 🚦 Per-type limits on top of the worker count (types not listed only share the workers)
+    pub type_concurrency: HashMap<String, usize>,
+    /// 💓 How often a running job's claim is refreshed
+    pub heartbeat_interval_seconds: u64,
+    /// 🪦 Running jobs without a heartbeat for this long are treated as crashed
+    pub stale_after_seconds: u64,
+    /// 📮 Where claims are dispatched from (job state always lives in Postgres)
+    pub backend: QueueBackend,
+    /// 🔗 Redis server for the `redis` backend
+    pub redis_url: Option<String>,
+    /// 🏷️ Prefix of the Redis stream keys
+    pub redis_prefix: String,
+    /// 🔗 NATS servers for the `nats` backend (a URL or comma-separated list)
+    pub nats_url: Option<String>,
+    /// 🏷️ Prefix of the JetStream subjects (the stream is named after it)
+    pub nats_prefix: String,
+}
+
+// 🧹 Retention - How long old records are kept (0 keeps them forever)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// 🎫 Days an expired session is kept
+    pub expired_session_days: u32,
+    /// 🚦 Hours a rate limit window is kept after its last request
+    pub rate_limit_hours: u32,
+    /// 🪝 Days a processed webhook is kept
+    pub processed_webhook_days: u32,
+    /// 📬 Days a webhook delivery stays in the delivery log
+    pub webhook_delivery_days: u32,
+    /// 📝 Months a completed feedback item keeps its content (the record itself stays)
+    pub completed_feedback_months: u32,
+}
+
+// 📦 Exports - Feedback downloaded as CSV or JSON Lines (see crate::export)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    /// 📏 Projects with more feedback than this are exported in the background
+    pub inline_limit: u64,
+    /// 📂 Where background exports are written
+    pub dir: String,
+    /// ⏳ Hours a background export (and its download link) is kept
+    pub link_hours: u32,
+}
+
+// 🗄️ Pipeline artifacts - What each run produced, kept for debugging (see crate::artifacts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactConfig {
+    /// 🗄️ Where artifacts are stored
+    pub storage: ArtifactStorage,
+    /// 📂 Directory of locally stored artifacts
+    pub dir: String,
+    /// ⏳ Days artifacts are kept (0 = as long as their feedback)
+    pub retention_days: u32,
+    /// 🔗 Minutes a signed artifact link works
+    pub link_minutes: u32,
+    /// 🪣 Bucket of S3 storage
+    pub s3_bucket: Option<String>,
+    /// 🌐 S3-compatible endpoint (None = AWS in `s3_region`)
+    pub s3_endpoint: Option<String>,
+    /// 🌍 Region requests are signed for
+    pub s3_region: String,
+    /// 🔑 Credentials (default to AWS_ACCESS_KEY_ID and friends)
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+    pub s3_session_token: Option<String>,
+}
+
+// 🗄️ Where pipeline artifacts go
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactStorage {
+    /// 🚫 Not kept
+    None,
+    /// 📂 Files under ARTIFACT_DIR
+    Local,
+    /// 🪣 Objects in an S3-compatible bucket
+    S3,
+}
+
+// 📤 Event stream - Domain events for analytics (see crate::event_stream)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventStreamConfig {
+    /// 📤 Where events are published (None = not at all)
+    pub backend: EventStreamBackend,
+    /// 🔗 Kafka bootstrap servers (comma-separated) or NATS server URL(s)
+    pub servers: Option<String>,
+    /// 🏷️ Prefix of the topics (Kafka) or subjects (NATS)
+    pub topic_prefix: String,
+    /// ⏱️ How often unpublished events are looked for
+    pub poll_interval_ms: u64,
+}
+
+// 📤 Event stream backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStreamBackend {
+    /// 🚫 Events stay in the database
+    None,
+    /// 🪵 Apache Kafka (or anything speaking its protocol, like Redpanda)
+    Kafka,
+    /// 📨 NATS core subjects
+    Nats,
+}
+
+// ⏱️ Watchdog - Feedback left mid-pipeline by a crashed worker (see crate::feedback_watchdog)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// ⏳ Minutes in processing before an item counts as stuck (0 = never)
+    pub processing_minutes: u32,
+    /// ⏳ Minutes in generating_changes before an item counts as stuck (0 = never)
+    pub generating_changes_minutes: u32,
+    /// 🔧 What happens to a stuck item
+    pub action: StuckAction,
+    /// 🔁 Times an item is requeued before it's failed instead
+    pub max_requeues: u32,
+}
+
+// ⏱️ What the watchdog does with stuck feedback
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StuckAction {
+    /// ❌ Fail it with a timeout reason
+    Fail,
+    /// 🔁 Run it again (failed once it's been requeued max_requeues times)
+    Requeue,
+}
+
+// 💸 Spend caps - Tokens a run may use before it waits for approval (see crate::budgets)
+// Projects can override both in their `budget` settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// 📝 Tokens one feedback item may use (0 = no cap)
+    pub feedback_tokens: u64,
+    /// 🏠 Tokens one project may use per calendar month (0 = no cap)
+    pub project_monthly_tokens: u64,
+}
+
+// 🗃️ Response cache - Hot read endpoints served from memory (see crate::cache)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// 🔄 Cache responses at all
+    pub enabled: bool,
+    /// ⏳ Seconds a cached response is served before it's read again
+    pub ttl_seconds: u64,
+    /// 📏 Responses kept in process at most
+    pub max_entries: usize,
+    /// 🔗 Redis server shared by every instance (None = in process only)
+    pub redis_url: Option<String>,
+    /// 🏷️ Prefix of the Redis cache keys
+    pub redis_prefix: String,
+}
+
+// 🔑 Secrets managers - GITHUB_TOKEN, JWT_SECRET and the LLM API keys may be
+// vault://, aws-sm:// or gcp-sm:// references (see crate::secrets)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// ⏱️ How often referenced secrets are fetched again (0 = only at startup)
+    pub refresh_seconds: u64,
+    /// 🏛️ Vault server address
+    pub vault_addr: Option<String>,
+    /// 🎫 Vault token
+    pub vault_token: Option<String>,
+    /// 🏷️ Vault Enterprise namespace
+    pub vault_namespace: Option<String>,
+    /// 🌍 AWS region of Secrets Manager
+    pub aws_region: Option<String>,
+    /// 🔑 AWS credentials (static, from the environment)
+    pub aws_access_key_id: Option<String>,
+    pub aws_secret_access_key: Option<String>,
+    pub aws_session_token: Option<String>,
+    /// 🎫 GCP OAuth access token (asked from the metadata server when unset)
+    pub gcp_access_token: Option<String>,
+}
+
+// 🚧 Maintenance mode - Writes refused and job workers paused (see crate::maintenance)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// 🚧 Start (or, on reload, switch) into maintenance mode
+    pub enabled: bool,
+    /// 💬 Message shown to users while it is on
+    pub message: String,
+}
+
+// 📮 Job queue backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueBackend {
+    /// 🐘 SKIP LOCKED claims and LISTEN/NOTIFY wake-ups
+    Postgres,
+    /// 🟥 Redis Streams consumer groups, one stream per job type and lane
+    Redis,
+    /// 📨 NATS JetStream work queue, one pull consumer per job type and lane
+    Nats,
+}
+
+// 🔏 Reaction to applied migrations whose SQL has changed since
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationDrift {
+    /// 🛑 Refuse to start
+    Fail,
+    /// ⚠️ Log every drifted migration and start anyway
+    Warn,
+}
+
 // 🌍 Environment enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -195,68 +601,275 @@ pub enum Environment {
     Production,
 }
 
+// 🧭 Model size a task is routed to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelTier {
+    /// 🐣 The provider's small model
+    Small,
+    /// 🦣 The provider's default model
+    Large,
+    /// 📏 Small when the prompt is short, large otherwise
+    Auto,
+}
+
 // 🤖 LLM provider enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum LlmProvider {
     OpenAi,
     Anthropic,
+    Custom,
+}
+
+// 🚫 A configuration with missing or invalid values, every problem listed
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidConfig {
+    /// 📄 Config file read under the environment, if any
+    pub path: Option<PathBuf>,
+    /// 📋 What's wrong, one entry per value
+    pub problems: Vec<String>,
+}
+
+impl std::fmt::Display for InvalidConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid configuration{} ({} problems):\n  - {}",
+            self.path
+                .as_ref()
+                .map(|path| format!(" in {} and the environment", path.display()))
+                .unwrap_or_default(),
+            self.problems.len(),
+            self.problems.join("\n  - ")
+        )
+    }
+}
+
+impl std::error::Error for InvalidConfig {}
+
+impl InvalidConfig {
+    /// 🔍 The invalid configuration behind an error, when that's what failed
+    pub fn find(error: &anyhow::Error) -> Option<&InvalidConfig> {
+        error
+            .chain()
+            .find_map(|e| e.downcast_ref::<InvalidConfig>())
+    }
 }
 
 impl Config {
     /// 🚀 Load configuration from environment variables and files
     /// This is the main entry point for configuration loading!
     pub fn load() -> Result<Self> {
+        Self::load_from(None)
+    }
+
+    /// 🚀 Load configuration, layering environment variables over a config file
+    /// The file is `path` (from --config), else FEEDBACKER_CONFIG, else
+    /// ./feedbacker.toml when it exists. Every missing or invalid value is
+    /// reported in one error instead of stopping at the first
+    pub fn load_from(path: Option<&Path>) -> Result<Self> {
         // 🔍 Load environment variables from .env file if it exists
         if Path::new(".env").exists() {
             dotenv::dotenv().context("Failed to load .env file")?;
         }
 
-        // 🏗️ Build configuration from environment variables
+        // 📄 Values from the config file sit under the environment
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| env::var("FEEDBACKER_CONFIG").ok().map(PathBuf::from))
+            .or_else(|| {
+                let default = PathBuf::from(DEFAULT_CONFIG_FILE);
+                default.exists().then_some(default)
+            });
+        let settings = match &path {
+            Some(path) => Settings::new(read_config_file(path)?),
+            None => Settings::default(),
+        };
+
+        // 🏗️ Build configuration from environment variables (and the file)
         let config = Self {
-            server: ServerConfig::load()?,
-            database: DatabaseConfig::load()?,
-            github: GitHubConfig::load()?,
-            llm: LlmConfig::load()?,
-            auth: AuthConfig::load()?,
-            rate_limiting: RateLimitConfig::load()?,
-            email: EmailConfig::load_optional(),
-            logging: LoggingConfig::load()?,
-            features: FeaturesConfig::load()?,
+            server: ServerConfig::load(&settings),
+            database: DatabaseConfig::load(&settings),
+            github: GitHubConfig::load(&settings),
+            llm: LlmConfig::load(&settings),
+            auth: AuthConfig::load(&settings),
+            rate_limiting: RateLimitConfig::load(&settings),
+            email: EmailConfig::load_optional(&settings),
+            logging: LoggingConfig::load(&settings),
+            features: FeaturesConfig::load(&settings),
+            sandbox: SandboxConfig::load(&settings),
+            jobs: JobsConfig::load(&settings),
+            retention: RetentionConfig::load(&settings),
+            exports: ExportConfig::load(&settings),
+            artifacts: ArtifactConfig::load(&settings),
+            event_stream: EventStreamConfig::load(&settings),
+            watchdog: WatchdogConfig::load(&settings),
+            budgets: BudgetConfig::load(&settings),
+            cache: CacheConfig::load(&settings),
+            secrets: SecretsConfig::load(&settings),
+            maintenance: MaintenanceConfig::load(&settings),
         };
 
-        // ✅ Validate the configuration
-        config.validate()?;
+        // ✅ Validate the configuration, then report everything that's wrong at once
+        let mut problems = settings.into_problems();
+        problems.extend(config.problems());
+        if !problems.is_empty() {
+            return Err(InvalidConfig { path, problems }.into());
+        }
 
         Ok(config)
     }
 
-    /// ✅ Validate the configuration for any issues
-    fn validate(&self) -> Result<()> {
+    /// ✅ Check the loaded values for anything that can't work
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
         // 🔍 Check required fields and reasonable values
         if self.server.address.is_empty() {
-            anyhow::bail!("Server address cannot be empty");
+            problems.push("SERVER_ADDRESS cannot be empty".to_string());
         }
 
-        if self.database.url.is_empty() {
-            anyhow::bail!("Database URL cannot be empty");
+        // 🏊‍♂️ The pool needs room for at least its minimum
+        if self.database.max_connections == 0
+            || self.database.min_connections > self.database.max_connections
+        {
+            problems.push(
+                "DATABASE_MAX_CONNECTIONS must be at least 1 and at least DATABASE_MIN_CONNECTIONS"
+                    .to_string(),
+            );
         }
 
-        if self.github.token.is_empty() {
-            anyhow::bail!("GitHub token cannot be empty");
+        // 🔐 A missing secret is already reported as required, and a referenced one
+        // is checked once it has been fetched
+        if !self.auth.jwt_secret.is_empty()
+            && !crate::secrets::is_reference(&self.auth.jwt_secret)
+            && self.auth.jwt_secret.len() < 32
+        {
+            problems.push("JWT_SECRET must be at least 32 characters long".to_string());
         }
 
-        if self.auth.jwt_secret.len() < 32 {
-            anyhow::bail!("JWT secret must be at least 32 characters long");
+        // 🔏 Seen signatures are only kept a day, so a longer-lived one could be replayed
+        let max_signature_age = crate::middleware::request_signing::MAX_SIGNATURE_AGE_SECONDS;
+        if self.auth.request_signature_max_age_seconds == 0
+            || self.auth.request_signature_max_age_seconds > max_signature_age
+        {
+            problems.push(format!(
+                "REQUEST_SIGNATURE_MAX_AGE_SECONDS must be between 1 and {}",
+                max_signature_age
+            ));
         }
 
+        // ⏳ A download link that's expired on arrival is no link at all
+        if self.exports.link_hours == 0 {
+            problems.push("EXPORT_LINK_HOURS must be at least 1".to_string());
+        }
+
+        // 🔑 Secret references must be well-formed, and their manager configured
+        problems.extend(crate::secrets::config_problems(self));
+
         // 🎯 Validate rate limiting values
         if self.rate_limiting.requests_per_minute == 0 {
-            anyhow::bail!("Rate limiting requests per minute must be greater than 0");
+            problems.push("RATE_LIMIT_REQUESTS_PER_MINUTE must be greater than 0".to_string());
+        }
+        if self.rate_limiting.trusted_requests_per_minute == 0 {
+            problems.push(
+                "RATE_LIMIT_TRUSTED_REQUESTS_PER_MINUTE must be greater than 0".to_string(),
+            );
         }
 
-        // ✅ All validations passed!
-        Ok(())
+        // 🔌 The custom LLM endpoint must be a full http(s) URL
+        if let Some(custom) = &self.llm.custom {
+            if !custom.base_url.starts_with("http://") && !custom.base_url.starts_with("https://") {
+                problems
+                    .push("CUSTOM_LLM_BASE_URL must start with http:// or https://".to_string());
+            }
+        }
+
+        // 👷 A pool without workers never runs anything
+        if self.jobs.workers == 0 {
+            problems.push("JOB_WORKERS must be greater than 0".to_string());
+        }
+
+        // 💓 Live jobs must heartbeat well within the stale window, or they get reaped
+        if self.jobs.heartbeat_interval_seconds == 0
+            || self.jobs.stale_after_seconds < 2 * self.jobs.heartbeat_interval_seconds
+        {
+            problems.push(
+                "JOB_STALE_AFTER_SECONDS must be at least twice JOB_HEARTBEAT_INTERVAL_SECONDS"
+                    .to_string(),
+            );
+        }
+
+        // 🪣 S3 artifact storage needs a bucket and credentials to sign with
+        if self.artifacts.storage == ArtifactStorage::S3 {
+            if self.artifacts.s3_bucket.is_none() {
+                problems.push("ARTIFACT_S3_BUCKET is required for s3 artifact storage".to_string());
+            }
+            if self.artifacts.s3_access_key_id.is_none()
+                || self.artifacts.s3_secret_access_key.is_none()
+            {
+                problems.push(
+                    "ARTIFACT_S3_ACCESS_KEY_ID and ARTIFACT_S3_SECRET_ACCESS_KEY (or the AWS_ ones) are required for s3 artifact storage"
+                        .to_string(),
+                );
+            }
+        }
+        if self.artifacts.link_minutes == 0 {
+            problems.push("ARTIFACT_LINK_MINUTES must be at least 1".to_string());
+        }
+
+        // 📤 The event stream needs somewhere to publish, and a build that can
+        if self.event_stream.backend != EventStreamBackend::None {
+            if self.event_stream.servers.is_none() {
+                problems.push(format!(
+                    "EVENT_STREAM_SERVERS is required for the {} event stream",
+                    self.event_stream.backend.as_str()
+                ));
+            }
+            let built_in = match self.event_stream.backend {
+                EventStreamBackend::Kafka => cfg!(feature = "kafka-events"),
+                EventStreamBackend::Nats => cfg!(feature = "nats-events"),
+                EventStreamBackend::None => true,
+            };
+            if !built_in {
+                problems.push(format!(
+                    "EVENT_STREAM={} needs a build with the {}-events feature",
+                    self.event_stream.backend.as_str(),
+                    self.event_stream.backend.as_str()
+                ));
+            }
+        }
+
+        // 🟥 The Redis queue needs a server to talk to
+        if self.jobs.backend == QueueBackend::Redis && self.jobs.redis_url.is_none() {
+            problems.push(
+                "JOB_QUEUE_REDIS_URL (or REDIS_URL) is required for the redis queue backend"
+                    .to_string(),
+            );
+        }
+        // 📨 So does the JetStream queue, and it has to be built in
+        if self.jobs.backend == QueueBackend::Nats {
+            if self.jobs.nats_url.is_none() {
+                problems.push(
+                    "JOB_QUEUE_NATS_URL (or NATS_URL) is required for the nats queue backend"
+                        .to_string(),
+                );
+            }
+            if !cfg!(feature = "nats-queue") {
+                problems.push(
+                    "JOB_QUEUE_BACKEND=nats needs a build with the nats-queue feature".to_string(),
+                );
+            }
+            if !is_subject_prefix(&self.jobs.nats_prefix) {
+                problems.push(format!(
+                    "JOB_QUEUE_NATS_PREFIX must be dot-separated subject tokens without wildcards, got '{}'",
+                    self.jobs.nats_prefix
+                ));
+            }
+        }
+
+        problems
     }
 
     /// 🌍 Check if we're running in development mode
@@ -270,176 +883,579 @@ impl Config {
     }
 }
 
+/// 📄 Config file read when neither --config nor FEEDBACKER_CONFIG names one
+pub const DEFAULT_CONFIG_FILE: &str = "feedbacker.toml";
+
+/// 📚 Where configuration values come from: the environment first, then the
+/// config file. Problems are collected as values are read, so all of them can
+/// be reported together
+#[derive(Debug, Default)]
+pub struct Settings {
+    /// 📄 Config file values, keyed by environment variable name
+    file: HashMap<String, String>,
+    /// 📋 Missing and invalid values seen so far
+    problems: RefCell<Vec<String>>,
+}
+
+impl Settings {
+    /// ➕ Settings over these config file values
+    pub fn new(file: HashMap<String, String>) -> Self {
+        Self {
+            file,
+            problems: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// 🔍 Raw value: the environment variable, else the config file
+    fn var(&self, name: &str) -> std::result::Result<String, env::VarError> {
+        match env::var(name) {
+            Err(env::VarError::NotPresent) => self
+                .file
+                .get(name)
+                .cloned()
+                .ok_or(env::VarError::NotPresent),
+            value => value,
+        }
+    }
+
+    /// ❗ A value that must be set (empty counts as missing)
+    fn required(&self, name: &str) -> String {
+        match self.var(name) {
+            Ok(value) if !value.is_empty() => value,
+            _ => {
+                self.problem(format!("{} is required", name));
+                String::new()
+            }
+        }
+    }
+
+    /// 🔢 A parsed value, or `default` when it isn't set (or is invalid, which is recorded)
+    fn parse<T>(&self, name: &str, default: &str) -> T
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        if let Ok(value) = self.var(name) {
+            match value.parse() {
+                Ok(parsed) => return parsed,
+                Err(e) => self.problem(format!("{} = {:?}: {}", name, value, e)),
+            }
+        }
+        match default.parse() {
+            Ok(parsed) => parsed,
+            Err(_) => unreachable!("built-in default of {} is valid", name),
+        }
+    }
+
+    /// 🔢 A parsed value that may be left unset
+    fn parse_optional<T>(&self, name: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let value = self.var(name).ok()?;
+        match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                self.problem(format!("{} = {:?}: {}", name, value, e));
+                None
+            }
+        }
+    }
+
+    /// 📋 A value parsed by hand; an error is recorded and the default used
+    fn check<T: Default>(&self, name: &str, result: Result<T>) -> T {
+        result.unwrap_or_else(|e| {
+            self.problem(format!("{}: {:#}", name, e));
+            T::default()
+        })
+    }
+
+    /// 🌐 Comma separated IP addresses and CIDR networks (empty when unset)
+    fn networks(&self, name: &str) -> Vec<IpNet> {
+        self.check(
+            name,
+            self.var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|network| !network.is_empty())
+                .map(parse_network)
+                .collect(),
+        )
+    }
+
+    /// 📋 Record a problem
+    fn problem(&self, problem: String) {
+        self.problems.borrow_mut().push(problem);
+    }
+
+    /// 📋 Every problem recorded
+    pub fn into_problems(self) -> Vec<String> {
+        self.problems.into_inner()
+    }
+}
+
+/// 📄 Read a TOML or YAML config file (by extension) into environment variable names
+pub fn read_config_file(path: &Path) -> Result<HashMap<String, String>> {
+    let file = ::config::Config::builder()
+        .add_source(::config::File::from(path))
+        .build()
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    flatten_config(
+        file.try_deserialize()
+            .with_context(|| format!("Failed to read config file {}", path.display()))?,
+    )
+}
+
+/// 🗂️ Flatten config file tables into environment variable names:
+/// `[database] max_connections = 20` is DATABASE_MAX_CONNECTIONS, and so is a
+/// top-level `database_max_connections = 20`. Lists become comma-separated
+pub fn flatten_config(table: HashMap<String, ::config::Value>) -> Result<HashMap<String, String>> {
+    fn flatten(
+        prefix: &str,
+        table: HashMap<String, ::config::Value>,
+        values: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        for (key, value) in table {
+            let name = if prefix.is_empty() {
+                key.to_uppercase()
+            } else {
+                format!("{}_{}", prefix, key.to_uppercase())
+            };
+            match value.kind {
+                ::config::ValueKind::Nil => {}
+                ::config::ValueKind::Table(table) => {
+                    flatten(&name, table.into_iter().collect(), values)?
+                }
+                ::config::ValueKind::Array(items) => {
+                    let items = items
+                        .into_iter()
+                        .map(|item| item.into_string())
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                        .with_context(|| format!("{} must be a list of plain values", name))?;
+                    values.insert(name, items.join(","));
+                }
+                _ => {
+                    let value = value
+                        .into_string()
+                        .with_context(|| format!("Invalid value for {}", name))?;
+                    values.insert(name, value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut values = HashMap::new();
+    flatten("", table, &mut values)?;
+    Ok(values)
+}
+
 impl ServerConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            address: env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:3000".to_string()),
-            timeout_seconds: env::var("SERVER_TIMEOUT_SECONDS")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .context("Invalid SERVER_TIMEOUT_SECONDS")?,
-            max_body_size: env::var("SERVER_MAX_BODY_SIZE")
-                .unwrap_or_else(|_| "1048576".to_string()) // 1MB default
-                .parse()
-                .context("Invalid SERVER_MAX_BODY_SIZE")?,
-            environment: env::var("ENVIRONMENT")
+    fn load(settings: &Settings) -> Self {
+        let address = settings
+            .var("SERVER_ADDRESS")
+            .unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+        Self {
+            timeout_seconds: settings.parse("SERVER_TIMEOUT_SECONDS", "30"),
+            max_body_size: settings.parse("SERVER_MAX_BODY_SIZE", "1048576"), // 1MB default
+            environment: settings
+                .var("ENVIRONMENT")
                 .unwrap_or_else(|_| "development".to_string())
                 .parse()
                 .unwrap_or(Environment::Development),
-        })
+            public_url: settings
+                .var("SERVER_PUBLIC_URL")
+                .unwrap_or_else(|_| default_public_url(&address))
+                .trim_end_matches('/')
+                .to_string(),
+            trusted_proxies: settings.networks("TRUSTED_PROXIES"),
+            ip_allowlists: IpAllowlistConfig {
+                admin: settings.networks("ADMIN_IP_ALLOWLIST"),
+                webhooks: settings.networks("WEBHOOK_IP_ALLOWLIST"),
+                github_hooks: settings.parse("WEBHOOK_ALLOW_GITHUB_HOOKS", "false"),
+            },
+            tls: TlsConfig::load(settings),
+            address,
+        }
+    }
+}
+
+/// 🔗 Public URL when SERVER_PUBLIC_URL isn't set: the listen address, with a
+/// wildcard host (0.0.0.0 or ::) shown as localhost
+fn default_public_url(address: &str) -> String {
+    match address.parse::<std::net::SocketAddr>() {
+        Ok(socket) if socket.ip().is_unspecified() => {
+            format!("http://localhost:{}", socket.port())
+        }
+        _ => format!("http://{}", address),
     }
 }
 
+impl TlsConfig {
+    fn load(settings: &Settings) -> Self {
+        let cert_path = settings
+            .var("TLS_CERT_PATH")
+            .ok()
+            .filter(|path| !path.is_empty());
+        let key_path = settings
+            .var("TLS_KEY_PATH")
+            .ok()
+            .filter(|path| !path.is_empty());
+        let domains: Vec<String> = settings
+            .var("TLS_ACME_DOMAINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|domain| !domain.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let mode = match (cert_path, key_path) {
+            (Some(_), Some(_)) if !domains.is_empty() => {
+                settings.problem(
+                    "Set either TLS_CERT_PATH and TLS_KEY_PATH or TLS_ACME_DOMAINS, not both"
+                        .to_string(),
+                );
+                TlsMode::Off
+            }
+            (Some(cert_path), Some(key_path)) => TlsMode::Files {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            },
+            (Some(_), None) | (None, Some(_)) => {
+                settings.problem("TLS_CERT_PATH and TLS_KEY_PATH must be set together".to_string());
+                TlsMode::Off
+            }
+            (None, None) if !domains.is_empty() => {
+                if !cfg!(feature = "acme") {
+                    settings.problem(
+                        "TLS_ACME_DOMAINS needs a build with the acme feature".to_string(),
+                    );
+                }
+                TlsMode::Acme {
+                    domains,
+                    contact: settings
+                        .var("TLS_ACME_CONTACT")
+                        .ok()
+                        .filter(|email| !email.is_empty()),
+                    cache_dir: settings
+                        .var("TLS_ACME_CACHE_DIR")
+                        .unwrap_or_else(|_| "./acme-cache".to_string())
+                        .into(),
+                    staging: settings.parse("TLS_ACME_STAGING", "false"),
+                }
+            }
+            (None, None) => TlsMode::Off,
+        };
+
+        let redirect_port = settings.parse_optional("TLS_REDIRECT_PORT");
+        if redirect_port.is_some() && mode == TlsMode::Off {
+            settings.problem("TLS_REDIRECT_PORT only works with TLS turned on".to_string());
+        }
+        Self {
+            mode,
+            redirect_port,
+        }
+    }
+}
+
+/// 🌐 A network (10.0.0.0/8) or a single address (10.0.0.1)
+fn parse_network(network: &str) -> Result<IpNet> {
+    network
+        .parse()
+        .or_else(|_| network.parse::<IpAddr>().map(IpNet::from))
+        .with_context(|| format!("Expected an IP address or network, got '{}'", network))
+}
+
 impl DatabaseConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            url: env::var("DATABASE_URL")
-                .context("DATABASE_URL environment variable is required")?,
-            max_connections: env::var("DATABASE_MAX_CONNECTIONS")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .context("Invalid DATABASE_MAX_CONNECTIONS")?,
-            connection_timeout_seconds: env::var("DATABASE_CONNECTION_TIMEOUT_SECONDS")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .context("Invalid DATABASE_CONNECTION_TIMEOUT_SECONDS")?,
-            auto_migrate: env::var("DATABASE_AUTO_MIGRATE")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid DATABASE_AUTO_MIGRATE")?,
-        })
+    fn load(settings: &Settings) -> Self {
+        Self {
+            url: settings.required("DATABASE_URL"),
+            max_connections: settings.parse("DATABASE_MAX_CONNECTIONS", "20"),
+            min_connections: settings.parse("DATABASE_MIN_CONNECTIONS", "2"),
+            connection_timeout_seconds: settings.parse("DATABASE_CONNECTION_TIMEOUT_SECONDS", "30"),
+            idle_timeout_seconds: settings.parse("DATABASE_IDLE_TIMEOUT_SECONDS", "600"),
+            max_lifetime_seconds: settings.parse("DATABASE_MAX_LIFETIME_SECONDS", "1800"),
+            statement_timeout_ms: settings.parse("DATABASE_STATEMENT_TIMEOUT_MS", "0"),
+            slow_query_ms: settings.parse("DATABASE_SLOW_QUERY_MS", "500"),
+            application_name: settings
+                .var("DATABASE_APPLICATION_NAME")
+                .unwrap_or_else(|_| "feedbacker".to_string()),
+            auto_migrate: settings.parse("DATABASE_AUTO_MIGRATE", "true"),
+            migration_drift: settings.parse("DATABASE_MIGRATION_DRIFT", "fail"),
+        }
     }
 }
 
 impl GitHubConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            username: env::var("GITHUB_USERNAME")
+    fn load(settings: &Settings) -> Self {
+        Self {
+            username: settings
+                .var("GITHUB_USERNAME")
                 .unwrap_or_else(|_| "aye-is".to_string()),
-            token: env::var("GITHUB_TOKEN")
-                .context("GITHUB_TOKEN environment variable is required")?,
-            ssh_private_key_path: env::var("GITHUB_SSH_PRIVATE_KEY_PATH")
+            token: settings.required("GITHUB_TOKEN"),
+            ssh_private_key_path: settings
+                .var("GITHUB_SSH_PRIVATE_KEY_PATH")
                 .unwrap_or_else(|_| "~/.ssh/id_rsa".to_string()),
-            api_base_url: env::var("GITHUB_API_BASE_URL")
+            api_base_url: settings
+                .var("GITHUB_API_BASE_URL")
                 .unwrap_or_else(|_| "https://api.github.com".to_string()),
-            default_commit_message: env::var("GITHUB_DEFAULT_COMMIT_MESSAGE")
+            default_commit_message: settings.var("GITHUB_DEFAULT_COMMIT_MESSAGE")
                 .unwrap_or_else(|_| "🤖 AI-generated improvement based on user feedback\n\n✨ Generated by Feedbacker with love by Aye & Hue".to_string()),
-            default_branch_prefix: env::var("GITHUB_DEFAULT_BRANCH_PREFIX")
+            default_branch_prefix: settings
+                .var("GITHUB_DEFAULT_BRANCH_PREFIX")
                 .unwrap_or_else(|_| "feedbacker/".to_string()),
+            clone_cache_dir: settings
+                .var("GIT_CLONE_CACHE_DIR")
+                .unwrap_or_else(|_| "./data/clones".to_string()),
+            clone_cache_size: settings.parse("GIT_CLONE_CACHE_SIZE", "10"),
+            rate_limit_reserve: settings.parse("GITHUB_RATE_LIMIT_RESERVE", "200"),
+            rate_limit_max_wait_seconds: settings.parse("GITHUB_RATE_LIMIT_MAX_WAIT_SECONDS", "900"),
+            rate_limit_retries: settings.parse("GITHUB_RATE_LIMIT_RETRIES", "3"),
+            endpoints: GitHubEndpoint::load_all(settings),
+        }
+    }
+
+    /// 🏢 This configuration pointed at a named endpoint (itself for None)
+    pub fn for_endpoint(&self, name: Option<&str>) -> Result<GitHubConfig> {
+        let Some(name) = name else {
+            return Ok(self.clone());
+        };
+        let endpoint = self
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.name == name)
+            .with_context(|| format!("GitHub endpoint '{}' is not in GITHUB_ENDPOINTS", name))?;
+        Ok(GitHubConfig {
+            api_base_url: endpoint.api_base_url.clone(),
+            token: endpoint.token.clone(),
+            ..self.clone()
         })
     }
 }
 
+impl GitHubEndpoint {
+    /// 📥 GITHUB_ENDPOINTS ("ghe=https://ghe.example.com/api/v3,...") with their tokens
+    fn load_all(settings: &Settings) -> Vec<Self> {
+        let mut endpoints: Vec<Self> = Vec::new();
+        let list = settings.var("GITHUB_ENDPOINTS").unwrap_or_default();
+        for entry in list.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let Some((name, url)) = entry.split_once('=') else {
+                settings.problem(format!(
+                    "GITHUB_ENDPOINTS entry '{}' must look like name=https://host/api/v3",
+                    entry
+                ));
+                continue;
+            };
+            let (name, url) = (name.trim(), url.trim());
+            if !is_endpoint_name(name) {
+                settings.problem(format!(
+                    "GitHub endpoint name '{}' may only use lowercase letters, digits and dashes",
+                    name
+                ));
+                continue;
+            }
+            if endpoints.iter().any(|endpoint| endpoint.name == name) {
+                settings.problem(format!("GitHub endpoint '{}' is listed twice", name));
+                continue;
+            }
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                settings.problem(format!(
+                    "GitHub endpoint '{}' needs an http(s) URL, got '{}'",
+                    name, url
+                ));
+                continue;
+            }
+            let token_var = format!("GITHUB_TOKEN_{}", name.to_uppercase().replace('-', "_"));
+            endpoints.push(Self {
+                name: name.to_string(),
+                api_base_url: url.trim_end_matches('/').to_string(),
+                token: settings.required(&token_var),
+            });
+        }
+        endpoints
+    }
+}
+
+/// 🏷️ Whether a GitHub endpoint name is usable (lowercase letters, digits and dashes)
+pub fn is_endpoint_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
 impl LlmConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            openai: OpenAiConfig::load_optional(),
-            anthropic: AnthropicConfig::load_optional(),
-            default_provider: env::var("LLM_DEFAULT_PROVIDER")
+    fn load(settings: &Settings) -> Self {
+        Self {
+            openai: OpenAiConfig::load_optional(settings),
+            anthropic: AnthropicConfig::load_optional(settings),
+            custom: CustomLlmConfig::load_optional(settings),
+            default_provider: settings
+                .var("LLM_DEFAULT_PROVIDER")
                 .unwrap_or_else(|_| "openai".to_string())
                 .parse()
                 .unwrap_or(LlmProvider::OpenAi),
-            timeout_seconds: env::var("LLM_TIMEOUT_SECONDS")
-                .unwrap_or_else(|_| "60".to_string())
-                .parse()
-                .context("Invalid LLM_TIMEOUT_SECONDS")?,
-            max_retries: env::var("LLM_MAX_RETRIES")
-                .unwrap_or_else(|_| "3".to_string())
-                .parse()
-                .context("Invalid LLM_MAX_RETRIES")?,
-        })
+            timeout_seconds: settings.parse("LLM_TIMEOUT_SECONDS", "60"),
+            max_retries: settings.parse("LLM_MAX_RETRIES", "3"),
+            fallback_providers: settings.check(
+                "LLM_FALLBACK_PROVIDERS",
+                settings
+                    .var("LLM_FALLBACK_PROVIDERS")
+                    .unwrap_or_else(|_| "openai,anthropic".to_string())
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|provider| !provider.is_empty())
+                    .map(str::parse)
+                    .collect(),
+            ),
+            circuit_breaker_threshold: settings.parse("LLM_CIRCUIT_BREAKER_THRESHOLD", "5"),
+            circuit_breaker_cooldown_seconds: settings
+                .parse("LLM_CIRCUIT_BREAKER_COOLDOWN_SECONDS", "60"),
+            health_check_timeout_seconds: settings.parse("LLM_HEALTH_CHECK_TIMEOUT_SECONDS", "5"),
+            health_check_cache_seconds: settings.parse("LLM_HEALTH_CHECK_CACHE_SECONDS", "60"),
+            routing: RoutingConfig {
+                tiers: settings.check(
+                    "LLM_ROUTING_RULES",
+                    settings
+                        .var("LLM_ROUTING_RULES")
+                        .unwrap_or_default()
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|rule| !rule.is_empty())
+                        .map(|rule| {
+                            let (task, tier) = rule
+                                .split_once('=')
+                                .with_context(|| format!("Expected task=tier, got '{}'", rule))?;
+                            Ok((task.trim().to_string(), tier.trim().parse()?))
+                        })
+                        .collect(),
+                ),
+                small_prompt_tokens: settings.parse("LLM_SMALL_PROMPT_TOKENS", "3000"),
+            },
+        }
     }
 }
 
 impl OpenAiConfig {
-    fn load_optional() -> Option<Self> {
-        env::var("OPENAI_API_KEY").ok().map(|api_key| Self {
+    fn load_optional(settings: &Settings) -> Option<Self> {
+        settings.var("OPENAI_API_KEY").ok().map(|api_key| Self {
             api_key,
-            default_model: env::var("OPENAI_DEFAULT_MODEL").unwrap_or_else(|_| "gpt-4".to_string()),
-            temperature: env::var("OPENAI_TEMPERATURE")
+            default_model: settings
+                .var("OPENAI_DEFAULT_MODEL")
+                .unwrap_or_else(|_| "gpt-4".to_string()),
+            temperature: settings
+                .var("OPENAI_TEMPERATURE")
                 .unwrap_or_else(|_| "0.7".to_string())
                 .parse()
                 .unwrap_or(0.7),
-            max_tokens: env::var("OPENAI_MAX_TOKENS")
+            max_tokens: settings
+                .var("OPENAI_MAX_TOKENS")
                 .unwrap_or_else(|_| "2000".to_string())
                 .parse()
                 .unwrap_or(2000),
+            context_window: settings
+                .var("OPENAI_CONTEXT_WINDOW")
+                .ok()
+                .and_then(|value| value.parse().ok()),
+            small_model: settings.var("OPENAI_SMALL_MODEL").ok(),
         })
     }
 }
 
 impl AnthropicConfig {
-    fn load_optional() -> Option<Self> {
-        env::var("ANTHROPIC_API_KEY").ok().map(|api_key| Self {
+    fn load_optional(settings: &Settings) -> Option<Self> {
+        settings.var("ANTHROPIC_API_KEY").ok().map(|api_key| Self {
             api_key,
-            default_model: env::var("ANTHROPIC_DEFAULT_MODEL")
+            default_model: settings
+                .var("ANTHROPIC_DEFAULT_MODEL")
                 .unwrap_or_else(|_| "claude-3-sonnet-20240229".to_string()),
-            max_tokens: env::var("ANTHROPIC_MAX_TOKENS")
+            max_tokens: settings
+                .var("ANTHROPIC_MAX_TOKENS")
                 .unwrap_or_else(|_| "2000".to_string())
                 .parse()
                 .unwrap_or(2000),
+            context_window: settings
+                .var("ANTHROPIC_CONTEXT_WINDOW")
+                .ok()
+                .and_then(|value| value.parse().ok()),
+            small_model: settings.var("ANTHROPIC_SMALL_MODEL").ok(),
         })
     }
 }
 
-impl AuthConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            jwt_secret: env::var("JWT_SECRET")
-                .context("JWT_SECRET environment variable is required")?,
-            token_expiration_hours: env::var("JWT_TOKEN_EXPIRATION_HOURS")
-                .unwrap_or_else(|_| "24".to_string())
-                .parse()
-                .context("Invalid JWT_TOKEN_EXPIRATION_HOURS")?,
-            password_salt_rounds: env::var("PASSWORD_SALT_ROUNDS")
-                .unwrap_or_else(|_| "12".to_string())
-                .parse()
-                .context("Invalid PASSWORD_SALT_ROUNDS")?,
-            enable_registration: env::var("ENABLE_REGISTRATION")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid ENABLE_REGISTRATION")?,
+impl CustomLlmConfig {
+    fn load_optional(settings: &Settings) -> Option<Self> {
+        let (Ok(base_url), Ok(default_model)) = (
+            settings.var("CUSTOM_LLM_BASE_URL"),
+            settings.var("CUSTOM_LLM_MODEL"),
+        ) else {
+            return None;
+        };
+
+        Some(Self {
+            base_url,
+            api_key: settings.var("CUSTOM_LLM_API_KEY").ok(),
+            default_model,
+            temperature: settings.parse("CUSTOM_LLM_TEMPERATURE", "0.7"),
+            max_tokens: settings.parse("CUSTOM_LLM_MAX_TOKENS", "2000"),
+            context_window: settings.parse_optional("CUSTOM_LLM_CONTEXT_WINDOW"),
+            small_model: settings.var("CUSTOM_LLM_SMALL_MODEL").ok(),
+            supports_tools: settings.parse("CUSTOM_LLM_SUPPORTS_TOOLS", "true"),
         })
     }
 }
 
+impl AuthConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            jwt_secret: settings.required("JWT_SECRET"),
+            token_expiration_hours: settings.parse("JWT_TOKEN_EXPIRATION_HOURS", "24"),
+            password_salt_rounds: settings.parse("PASSWORD_SALT_ROUNDS", "12"),
+            enable_registration: settings.parse("ENABLE_REGISTRATION", "true"),
+            password_login: settings.parse("PASSWORD_LOGIN_ENABLED", "true"),
+            jwt_algorithm: settings.parse("JWT_SIGNING_ALGORITHM", "EdDSA"),
+            accept_hs256: settings.parse("JWT_ACCEPT_HS256", "true"),
+            request_signature_max_age_seconds: settings
+                .parse("REQUEST_SIGNATURE_MAX_AGE_SECONDS", "300"),
+        }
+    }
+}
+
 impl RateLimitConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            requests_per_minute: env::var("RATE_LIMIT_REQUESTS_PER_MINUTE")
-                .unwrap_or_else(|_| "60".to_string())
-                .parse()
-                .context("Invalid RATE_LIMIT_REQUESTS_PER_MINUTE")?,
-            feedback_per_hour: env::var("RATE_LIMIT_FEEDBACK_PER_HOUR")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .context("Invalid RATE_LIMIT_FEEDBACK_PER_HOUR")?,
-            burst_size: env::var("RATE_LIMIT_BURST_SIZE")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .context("Invalid RATE_LIMIT_BURST_SIZE")?,
-            window_seconds: env::var("RATE_LIMIT_WINDOW_SECONDS")
-                .unwrap_or_else(|_| "60".to_string())
-                .parse()
-                .context("Invalid RATE_LIMIT_WINDOW_SECONDS")?,
-        })
+    fn load(settings: &Settings) -> Self {
+        Self {
+            requests_per_minute: settings.parse("RATE_LIMIT_REQUESTS_PER_MINUTE", "60"),
+            feedback_per_hour: settings.parse("RATE_LIMIT_FEEDBACK_PER_HOUR", "10"),
+            burst_size: settings.parse("RATE_LIMIT_BURST_SIZE", "10"),
+            window_seconds: settings.parse("RATE_LIMIT_WINDOW_SECONDS", "60"),
+            trusted_requests_per_minute: settings
+                .parse("RATE_LIMIT_TRUSTED_REQUESTS_PER_MINUTE", "600"),
+            trusted_feedback_per_hour: settings.parse("RATE_LIMIT_TRUSTED_FEEDBACK_PER_HOUR", "200"),
+            sync_seconds: settings.parse("RATE_LIMIT_SYNC_SECONDS", "5"),
+        }
     }
 }
 
 impl EmailConfig {
-    fn load_optional() -> Option<Self> {
-        let smtp_host = env::var("SMTP_HOST").ok()?;
+    fn load_optional(settings: &Settings) -> Option<Self> {
+        let smtp_host = settings.var("SMTP_HOST").ok()?;
         Some(Self {
             smtp_host,
-            smtp_port: env::var("SMTP_PORT")
+            smtp_port: settings
+                .var("SMTP_PORT")
                 .unwrap_or_else(|_| "587".to_string())
                 .parse()
                 .unwrap_or(587),
-            smtp_username: env::var("SMTP_USERNAME").unwrap_or_default(),
-            smtp_password: env::var("SMTP_PASSWORD").unwrap_or_default(),
-            from_email: env::var("FROM_EMAIL")
+            smtp_username: settings.var("SMTP_USERNAME").unwrap_or_default(),
+            smtp_password: settings.var("SMTP_PASSWORD").unwrap_or_default(),
+            from_email: settings
+                .var("FROM_EMAIL")
                 .unwrap_or_else(|_| "noreply@feedbacker.com".to_string()),
-            use_tls: env::var("SMTP_USE_TLS")
+            use_tls: settings
+                .var("SMTP_USE_TLS")
                 .unwrap_or_else(|_| "true".to_string())
                 .parse()
                 .unwrap_or(true),
@@ -448,47 +1464,352 @@ impl EmailConfig {
 }
 
 impl LoggingConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
-            format: env::var("LOG_FORMAT").unwrap_or_else(|_| "pretty".to_string()),
-            file_path: env::var("LOG_FILE_PATH").ok(),
-            log_requests: env::var("LOG_REQUESTS")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid LOG_REQUESTS")?,
-        })
+    fn load(settings: &Settings) -> Self {
+        Self {
+            level: settings
+                .var("LOG_LEVEL")
+                .unwrap_or_else(|_| "info".to_string()),
+            format: settings
+                .var("LOG_FORMAT")
+                .unwrap_or_else(|_| "pretty".to_string()),
+            file_path: settings.var("LOG_FILE_PATH").ok(),
+            log_requests: settings.parse("LOG_REQUESTS", "true"),
+            quiet_paths: settings
+                .var("LOG_REQUESTS_QUIET_PATHS")
+                .unwrap_or_else(|_| "/api/health,/metrics".to_string())
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(str::to_string)
+                .collect(),
+            quiet_sample_every: settings.parse("LOG_REQUESTS_QUIET_SAMPLE_EVERY", "100"),
+        }
     }
 }
 
 impl FeaturesConfig {
-    fn load() -> Result<Self> {
-        Ok(Self {
-            enable_background_jobs: env::var("ENABLE_BACKGROUND_JOBS")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid ENABLE_BACKGROUND_JOBS")?,
-            enable_email_notifications: env::var("ENABLE_EMAIL_NOTIFICATIONS")
-                .unwrap_or_else(|_| "false".to_string())
-                .parse()
-                .context("Invalid ENABLE_EMAIL_NOTIFICATIONS")?,
-            enable_web_ui: env::var("ENABLE_WEB_UI")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid ENABLE_WEB_UI")?,
-            enable_github_webhooks: env::var("ENABLE_GITHUB_WEBHOOKS")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid ENABLE_GITHUB_WEBHOOKS")?,
-            enable_metrics: env::var("ENABLE_METRICS")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .context("Invalid ENABLE_METRICS")?,
-            enable_dev_features: env::var("ENABLE_DEV_FEATURES")
-                .unwrap_or_else(|_| "false".to_string())
-                .parse()
-                .context("Invalid ENABLE_DEV_FEATURES")?,
-        })
+    fn load(settings: &Settings) -> Self {
+        Self {
+            enable_background_jobs: settings.parse("ENABLE_BACKGROUND_JOBS", "true"),
+            enable_email_notifications: settings.parse("ENABLE_EMAIL_NOTIFICATIONS", "false"),
+            enable_web_ui: settings.parse("ENABLE_WEB_UI", "true"),
+            enable_github_webhooks: settings.parse("ENABLE_GITHUB_WEBHOOKS", "true"),
+            enable_metrics: settings.parse("ENABLE_METRICS", "true"),
+            enable_dev_features: settings.parse("ENABLE_DEV_FEATURES", "false"),
+        }
+    }
+}
+
+impl SandboxConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            work_dir: settings
+                .var("SANDBOX_WORK_DIR")
+                .unwrap_or_else(|_| "./data/sandbox".to_string()),
+            timeout_seconds: settings.parse("SANDBOX_TIMEOUT_SECONDS", "600"),
+            max_output_bytes: settings.parse("SANDBOX_MAX_OUTPUT_BYTES", "65536"),
+        }
+    }
+}
+
+impl JobsConfig {
+    fn load(settings: &Settings) -> Self {
+        let mut type_concurrency = HashMap::from([("repository_scan".to_string(), 2)]);
+        for rule in settings
+            .var("JOB_TYPE_CONCURRENCY")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+        {
+            let limit = rule
+                .split_once('=')
+                .and_then(|(job_type, limit)| {
+                    Some((job_type.trim().to_string(), limit.trim().parse().ok()?))
+                })
+                .with_context(|| format!("Expected job_type=limit, got '{}'", rule));
+            if let Some((job_type, limit)) = settings.check("JOB_TYPE_CONCURRENCY", limit.map(Some))
+            {
+                type_concurrency.insert(job_type, limit);
+            }
+        }
+
+        Self {
+            workers: settings.parse("JOB_WORKERS", "4"),
+            poll_interval_ms: settings.parse("JOB_POLL_INTERVAL_MS", "10000"),
+            type_concurrency,
+            heartbeat_interval_seconds: settings.parse("JOB_HEARTBEAT_INTERVAL_SECONDS", "15"),
+            stale_after_seconds: settings.parse("JOB_STALE_AFTER_SECONDS", "120"),
+            backend: settings.parse("JOB_QUEUE_BACKEND", "postgres"),
+            redis_url: settings
+                .var("JOB_QUEUE_REDIS_URL")
+                .or_else(|_| settings.var("REDIS_URL"))
+                .ok(),
+            redis_prefix: settings
+                .var("JOB_QUEUE_REDIS_PREFIX")
+                .unwrap_or_else(|_| "feedbacker:jobs".to_string()),
+            nats_url: settings
+                .var("JOB_QUEUE_NATS_URL")
+                .or_else(|_| settings.var("NATS_URL"))
+                .ok(),
+            nats_prefix: settings
+                .var("JOB_QUEUE_NATS_PREFIX")
+                .unwrap_or_else(|_| "feedbacker.jobs".to_string()),
+        }
+    }
+}
+
+/// 📨 Whether a JetStream subject prefix is usable (dot-separated tokens of
+/// letters, digits, dashes and underscores)
+pub fn is_subject_prefix(prefix: &str) -> bool {
+    prefix.split('.').all(|token| {
+        !token.is_empty()
+            && token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+impl RetentionConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            expired_session_days: settings.parse("RETENTION_EXPIRED_SESSION_DAYS", "7"),
+            rate_limit_hours: settings.parse("RETENTION_RATE_LIMIT_HOURS", "24"),
+            processed_webhook_days: settings.parse("RETENTION_PROCESSED_WEBHOOK_DAYS", "30"),
+            webhook_delivery_days: settings.parse("RETENTION_WEBHOOK_DELIVERY_DAYS", "14"),
+            completed_feedback_months: settings.parse("RETENTION_COMPLETED_FEEDBACK_MONTHS", "0"),
+        }
+    }
+}
+
+impl ExportConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            inline_limit: settings.parse("EXPORT_INLINE_LIMIT", "1000"),
+            dir: settings
+                .var("EXPORT_DIR")
+                .unwrap_or_else(|_| "./exports".to_string()),
+            link_hours: settings.parse("EXPORT_LINK_HOURS", "24"),
+        }
+    }
+}
+
+impl ArtifactConfig {
+    fn load(settings: &Settings) -> Self {
+        let aws = |name: &str| settings.var(name).ok();
+        Self {
+            storage: settings.parse("ARTIFACT_STORAGE", "local"),
+            dir: settings
+                .var("ARTIFACT_DIR")
+                .unwrap_or_else(|_| "./artifacts".to_string()),
+            retention_days: settings.parse("ARTIFACT_RETENTION_DAYS", "30"),
+            link_minutes: settings.parse("ARTIFACT_LINK_MINUTES", "60"),
+            s3_bucket: settings.var("ARTIFACT_S3_BUCKET").ok(),
+            s3_endpoint: settings.var("ARTIFACT_S3_ENDPOINT").ok(),
+            s3_region: aws("ARTIFACT_S3_REGION")
+                .or_else(|| aws("AWS_REGION"))
+                .or_else(|| aws("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|| "us-east-1".to_string()),
+            s3_access_key_id: aws("ARTIFACT_S3_ACCESS_KEY_ID").or_else(|| aws("AWS_ACCESS_KEY_ID")),
+            s3_secret_access_key: aws("ARTIFACT_S3_SECRET_ACCESS_KEY")
+                .or_else(|| aws("AWS_SECRET_ACCESS_KEY")),
+            s3_session_token: aws("ARTIFACT_S3_SESSION_TOKEN").or_else(|| aws("AWS_SESSION_TOKEN")),
+        }
+    }
+}
+
+impl EventStreamConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            backend: settings.parse("EVENT_STREAM", "none"),
+            servers: settings
+                .var("EVENT_STREAM_SERVERS")
+                .ok()
+                .filter(|servers| !servers.trim().is_empty()),
+            topic_prefix: settings
+                .var("EVENT_STREAM_TOPIC_PREFIX")
+                .unwrap_or_else(|_| "feedbacker".to_string()),
+            poll_interval_ms: settings.parse("EVENT_STREAM_POLL_INTERVAL_MS", "2000"),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            processing_minutes: settings.parse("STUCK_PROCESSING_MINUTES", "60"),
+            generating_changes_minutes: settings.parse("STUCK_GENERATING_CHANGES_MINUTES", "30"),
+            action: settings.parse("STUCK_FEEDBACK_ACTION", "fail"),
+            max_requeues: settings.parse("STUCK_MAX_REQUEUES", "2"),
+        }
+    }
+}
+
+impl BudgetConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            feedback_tokens: settings.parse("MAX_TOKENS_PER_FEEDBACK", "0"),
+            project_monthly_tokens: settings.parse("MAX_TOKENS_PER_PROJECT_MONTH", "0"),
+        }
+    }
+}
+
+impl CacheConfig {
+    fn load(settings: &Settings) -> Self {
+        let use_redis: bool = settings.parse("ENABLE_REDIS_CACHE", "false");
+        let redis_url = settings
+            .var("CACHE_REDIS_URL")
+            .or_else(|_| settings.var("REDIS_URL"))
+            .ok();
+        if use_redis && redis_url.is_none() {
+            settings.problem(
+                "ENABLE_REDIS_CACHE needs CACHE_REDIS_URL (or REDIS_URL)".to_string(),
+            );
+        }
+        Self {
+            enabled: settings.parse("RESPONSE_CACHE_ENABLED", "true"),
+            ttl_seconds: settings.parse("RESPONSE_CACHE_TTL_SECONDS", "30"),
+            max_entries: settings.parse("RESPONSE_CACHE_MAX_ENTRIES", "10000"),
+            redis_url: redis_url.filter(|_| use_redis),
+            redis_prefix: settings
+                .var("CACHE_REDIS_PREFIX")
+                .unwrap_or_else(|_| "feedbacker:cache".to_string()),
+        }
+    }
+}
+
+impl SecretsConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            refresh_seconds: settings.parse("SECRETS_REFRESH_SECONDS", "300"),
+            vault_addr: settings.var("VAULT_ADDR").ok(),
+            vault_token: settings.var("VAULT_TOKEN").ok(),
+            vault_namespace: settings.var("VAULT_NAMESPACE").ok(),
+            aws_region: settings
+                .var("AWS_REGION")
+                .or_else(|_| settings.var("AWS_DEFAULT_REGION"))
+                .ok(),
+            aws_access_key_id: settings.var("AWS_ACCESS_KEY_ID").ok(),
+            aws_secret_access_key: settings.var("AWS_SECRET_ACCESS_KEY").ok(),
+            aws_session_token: settings.var("AWS_SESSION_TOKEN").ok(),
+            gcp_access_token: settings.var("GCP_ACCESS_TOKEN").ok(),
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    fn load(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.parse("MAINTENANCE_MODE", "false"),
+            message: settings.var("MAINTENANCE_MESSAGE").unwrap_or_else(|_| {
+                "Feedbacker is down for maintenance. Please try again in a few minutes."
+                    .to_string()
+            }),
+        }
+    }
+}
+
+impl QueueBackend {
+    /// 🏷️ Name used in configuration and health output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueBackend::Postgres => "postgres",
+            QueueBackend::Redis => "redis",
+            QueueBackend::Nats => "nats",
+        }
+    }
+}
+
+impl std::str::FromStr for QueueBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "postgres" | "postgresql" | "pg" => Ok(QueueBackend::Postgres),
+            "redis" => Ok(QueueBackend::Redis),
+            "nats" | "jetstream" => Ok(QueueBackend::Nats),
+            _ => anyhow::bail!(
+                "Invalid JOB_QUEUE_BACKEND: {} (expected postgres, redis or nats)",
+                s
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for ArtifactStorage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" | "off" => Ok(ArtifactStorage::None),
+            "local" => Ok(ArtifactStorage::Local),
+            "s3" => Ok(ArtifactStorage::S3),
+            _ => anyhow::bail!(
+                "Invalid ARTIFACT_STORAGE: {} (expected none, local or s3)",
+                s
+            ),
+        }
+    }
+}
+
+impl EventStreamBackend {
+    /// 🏷️ Name used in configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventStreamBackend::None => "none",
+            EventStreamBackend::Kafka => "kafka",
+            EventStreamBackend::Nats => "nats",
+        }
+    }
+}
+
+impl StuckAction {
+    /// 🏷️ Name used in configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StuckAction::Fail => "fail",
+            StuckAction::Requeue => "requeue",
+        }
+    }
+}
+
+impl std::str::FromStr for StuckAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(StuckAction::Fail),
+            "requeue" => Ok(StuckAction::Requeue),
+            _ => anyhow::bail!("Invalid STUCK_FEEDBACK_ACTION: {} (expected fail or requeue)", s),
+        }
+    }
+}
+
+impl std::str::FromStr for EventStreamBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" | "off" => Ok(EventStreamBackend::None),
+            "kafka" => Ok(EventStreamBackend::Kafka),
+            "nats" => Ok(EventStreamBackend::Nats),
+            _ => anyhow::bail!("Invalid EVENT_STREAM: {} (expected none, kafka or nats)", s),
+        }
+    }
+}
+
+impl std::str::FromStr for MigrationDrift {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fail" | "error" => Ok(MigrationDrift::Fail),
+            "warn" => Ok(MigrationDrift::Warn),
+            _ => anyhow::bail!(
+                "Invalid DATABASE_MIGRATION_DRIFT: {} (expected fail or warn)",
+                s
+            ),
+        }
     }
 }
 
@@ -506,6 +1827,17 @@ impl std::str::FromStr for Environment {
     }
 }
 
+impl LlmProvider {
+    /// 🏷️ Name stored on feedback records and used in metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "openai",
+            LlmProvider::Anthropic => "anthropic",
+            LlmProvider::Custom => "custom",
+        }
+    }
+}
+
 impl std::str::FromStr for LlmProvider {
     type Err = anyhow::Error;
 
@@ -513,11 +1845,25 @@ impl std::str::FromStr for LlmProvider {
         match s.to_lowercase().as_str() {
             "openai" | "openai-gpt" => Ok(LlmProvider::OpenAi),
             "anthropic" | "claude" => Ok(LlmProvider::Anthropic),
+            "custom" | "openai-compatible" => Ok(LlmProvider::Custom),
             _ => anyhow::bail!("Invalid LLM provider: {}", s),
         }
     }
 }
 
+impl std::str::FromStr for ModelTier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "small" => Ok(ModelTier::Small),
+            "large" => Ok(ModelTier::Large),
+            "auto" => Ok(ModelTier::Auto),
+            _ => anyhow::bail!("Invalid model tier: {} (expected small, large, or auto)", s),
+        }
+    }
+}
+
 // 🧪 Tests - Because Trisha loves when we test our configuration!
 #[cfg(test)]
 mod tests {
@@ -551,9 +1897,266 @@ mod tests {
             "anthropic".parse::<LlmProvider>().unwrap(),
             LlmProvider::Anthropic
         );
+        assert_eq!(
+            "openai-compatible".parse::<LlmProvider>().unwrap(),
+            LlmProvider::Custom
+        );
+        assert_eq!("Small".parse::<ModelTier>().unwrap(), ModelTier::Small);
+        assert!("medium".parse::<ModelTier>().is_err());
+        assert_eq!(
+            "WARN".parse::<MigrationDrift>().unwrap(),
+            MigrationDrift::Warn
+        );
+        assert!("ignore".parse::<MigrationDrift>().is_err());
+        assert_eq!(
+            "Requeue".parse::<StuckAction>().unwrap(),
+            StuckAction::Requeue
+        );
+        assert!("retry".parse::<StuckAction>().is_err());
         println!("✅ LLM provider parsing test passed!");
     }
 
+    fn parse_file(contents: &str, format: ::config::FileFormat) -> HashMap<String, String> {
+        let file = ::config::Config::builder()
+            .add_source(::config::File::from_str(contents, format))
+            .build()
+            .unwrap();
+        flatten_config(file.try_deserialize().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_config_file_layering() {
+        let toml = parse_file(
+            r#"
+            environment = "staging"
+            jwt_secret = "from-the-file"
+
+            [database]
+            max_connections = 30
+            auto_migrate = false
+
+            [llm]
+            fallback_providers = ["anthropic", "custom"]
+            "#,
+            ::config::FileFormat::Toml,
+        );
+        assert_eq!(toml["ENVIRONMENT"], "staging");
+        assert_eq!(toml["DATABASE_MAX_CONNECTIONS"], "30");
+        assert_eq!(toml["DATABASE_AUTO_MIGRATE"], "false");
+        assert_eq!(toml["LLM_FALLBACK_PROVIDERS"], "anthropic,custom");
+
+        let yaml = parse_file(
+            "job:\n  workers: 8\n  queue:\n    backend: redis\n",
+            ::config::FileFormat::Yaml,
+        );
+        assert_eq!(yaml["JOB_WORKERS"], "8");
+        assert_eq!(yaml["JOB_QUEUE_BACKEND"], "redis");
+
+        // 🌍 The environment wins over the file
+        let settings = Settings::new(HashMap::from([
+            ("FEEDBACKER_TEST_LAYERED".to_string(), "file".to_string()),
+            ("FEEDBACKER_TEST_FILE_ONLY".to_string(), "file".to_string()),
+        ]));
+        env::set_var("FEEDBACKER_TEST_LAYERED", "env");
+        assert_eq!(settings.var("FEEDBACKER_TEST_LAYERED").unwrap(), "env");
+        assert_eq!(settings.var("FEEDBACKER_TEST_FILE_ONLY").unwrap(), "file");
+        assert!(settings.var("FEEDBACKER_TEST_NOWHERE").is_err());
+        println!("✅ Config file layering test passed!");
+    }
+
+    #[test]
+    fn test_config_problems_are_collected() {
+        let settings = Settings::new(HashMap::from([
+            ("FEEDBACKER_TEST_PORT".to_string(), "eighty".to_string()),
+            ("FEEDBACKER_TEST_FLAG".to_string(), "maybe".to_string()),
+            ("FEEDBACKER_TEST_EMPTY".to_string(), String::new()),
+        ]));
+
+        let port: u16 = settings.parse("FEEDBACKER_TEST_PORT", "8080");
+        let flag: bool = settings.parse("FEEDBACKER_TEST_FLAG", "true");
+        let unset: u64 = settings.parse("FEEDBACKER_TEST_UNSET", "7");
+        assert_eq!((port, flag, unset), (8080, true, 7));
+        assert_eq!(settings.required("FEEDBACKER_TEST_EMPTY"), "");
+        assert_eq!(
+            settings.parse_optional::<u32>("FEEDBACKER_TEST_UNSET"),
+            None
+        );
+
+        let problems = settings.into_problems();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].starts_with("FEEDBACKER_TEST_PORT = \"eighty\""));
+        assert!(problems[1].starts_with("FEEDBACKER_TEST_FLAG"));
+        assert_eq!(problems[2], "FEEDBACKER_TEST_EMPTY is required");
+        println!("✅ Config problem collection test passed!");
+    }
+
+    #[test]
+    fn test_invalid_config_error() {
+        let invalid = InvalidConfig {
+            path: Some(PathBuf::from("feedbacker.toml")),
+            problems: vec![
+                "JOB_WORKERS must be greater than 0".to_string(),
+                "JWT_SECRET is required".to_string(),
+            ],
+        };
+        assert_eq!(
+            invalid.to_string(),
+            "Invalid configuration in feedbacker.toml and the environment (2 problems):\n  - JOB_WORKERS must be greater than 0\n  - JWT_SECRET is required"
+        );
+
+        let error = anyhow::Error::new(invalid.clone()).context("Configuration reload failed");
+        assert_eq!(InvalidConfig::find(&error), Some(&invalid));
+        assert_eq!(InvalidConfig::find(&anyhow::anyhow!("disk on fire")), None);
+        println!("✅ Invalid config error test passed!");
+    }
+
+    #[test]
+    fn test_parse_network() {
+        assert_eq!(
+            parse_network("10.0.0.0/8").unwrap(),
+            "10.0.0.0/8".parse::<IpNet>().unwrap()
+        );
+        assert_eq!(
+            parse_network("10.0.0.1").unwrap(),
+            "10.0.0.1/32".parse::<IpNet>().unwrap()
+        );
+        assert_eq!(
+            parse_network("::1").unwrap(),
+            "::1/128".parse::<IpNet>().unwrap()
+        );
+        assert!(parse_network("proxy.internal").is_err());
+        println!("✅ Network parsing test passed!");
+    }
+
+    #[test]
+    fn test_tls_config() {
+        let settings = |values: &[(&str, &str)]| {
+            Settings::new(
+                values
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            )
+        };
+
+        let plain = settings(&[]);
+        assert_eq!(TlsConfig::load(&plain).mode, TlsMode::Off);
+        assert!(plain.into_problems().is_empty());
+
+        let files = settings(&[
+            ("TLS_CERT_PATH", "/etc/feedbacker/cert.pem"),
+            ("TLS_KEY_PATH", "/etc/feedbacker/key.pem"),
+            ("TLS_REDIRECT_PORT", "80"),
+        ]);
+        let tls = TlsConfig::load(&files);
+        assert_eq!(
+            tls.mode,
+            TlsMode::Files {
+                cert_path: "/etc/feedbacker/cert.pem".into(),
+                key_path: "/etc/feedbacker/key.pem".into(),
+            }
+        );
+        assert_eq!(tls.redirect_port, Some(80));
+        assert!(files.into_problems().is_empty());
+
+        let acme = settings(&[("TLS_ACME_DOMAINS", "f.8b.is, www.f.8b.is")]);
+        match TlsConfig::load(&acme).mode {
+            TlsMode::Acme { domains, staging, .. } => {
+                assert_eq!(domains, vec!["f.8b.is", "www.f.8b.is"]);
+                assert!(!staging);
+            }
+            mode => panic!("expected ACME, got {:?}", mode),
+        }
+        assert_eq!(acme.into_problems().len(), usize::from(!cfg!(feature = "acme")));
+
+        // 💥 Half a certificate, or a redirect to nowhere
+        let broken = settings(&[
+            ("TLS_CERT_PATH", "/etc/feedbacker/cert.pem"),
+            ("TLS_REDIRECT_PORT", "80"),
+        ]);
+        assert_eq!(TlsConfig::load(&broken).mode, TlsMode::Off);
+        assert_eq!(broken.into_problems().len(), 2);
+        println!("✅ TLS config test passed!");
+    }
+
+    #[test]
+    fn test_default_public_url() {
+        assert_eq!(
+            default_public_url("127.0.0.1:3000"),
+            "http://127.0.0.1:3000"
+        );
+        assert_eq!(default_public_url("0.0.0.0:8080"), "http://localhost:8080");
+        assert_eq!(default_public_url("[::]:8080"), "http://localhost:8080");
+        assert_eq!(default_public_url("[::1]:8080"), "http://[::1]:8080");
+        assert_eq!(
+            default_public_url("feedbacker.internal:80"),
+            "http://feedbacker.internal:80"
+        );
+
+        let settings = Settings::new(HashMap::from([(
+            "SERVER_ADDRESS".to_string(),
+            "0.0.0.0:8080".to_string(),
+        )]));
+        let server = ServerConfig::load(&settings);
+        assert_eq!(server.address, "0.0.0.0:8080");
+        assert_eq!(server.public_url, "http://localhost:8080");
+        println!("✅ Default public URL test passed!");
+    }
+
+    #[test]
+    fn test_nats_queue_subject_prefix() {
+        assert!(is_subject_prefix("feedbacker.jobs"));
+        assert!(is_subject_prefix("team-a_jobs"));
+        assert!(!is_subject_prefix("feedbacker.*"));
+        assert!(!is_subject_prefix("feedbacker..jobs"));
+        assert!(!is_subject_prefix("feedbacker.jobs."));
+        assert!(!is_subject_prefix("feedbacker jobs"));
+
+        let settings = Settings::new(HashMap::from([
+            ("JOB_QUEUE_BACKEND".to_string(), "nats".to_string()),
+            ("NATS_URL".to_string(), "nats://localhost:4222".to_string()),
+        ]));
+        let jobs = JobsConfig::load(&settings);
+        assert_eq!(jobs.backend, QueueBackend::Nats);
+        assert_eq!(jobs.nats_url.as_deref(), Some("nats://localhost:4222"));
+        assert_eq!(jobs.nats_prefix, "feedbacker.jobs");
+        println!("✅ NATS queue config test passed!");
+    }
+
+    #[test]
+    fn test_github_endpoints() {
+        let settings = Settings::new(HashMap::from([
+            (
+                "GITHUB_ENDPOINTS".to_string(),
+                "ghe-eu=https://ghe.example.eu/api/v3/, Bad=https://x, nope, ghe-eu=https://y"
+                    .to_string(),
+            ),
+            ("GITHUB_TOKEN_GHE_EU".to_string(), "ghp_eu".to_string()),
+        ]));
+        let endpoints = GitHubEndpoint::load_all(&settings);
+        assert_eq!(
+            endpoints,
+            vec![GitHubEndpoint {
+                name: "ghe-eu".to_string(),
+                api_base_url: "https://ghe.example.eu/api/v3".to_string(),
+                token: "ghp_eu".to_string(),
+            }]
+        );
+        // 🚫 Uppercase name, missing URL and duplicate
+        assert_eq!(settings.into_problems().len(), 3);
+
+        let missing_token = Settings::new(HashMap::from([(
+            "GITHUB_ENDPOINTS".to_string(),
+            "ghe=https://ghe.example.com/api/v3".to_string(),
+        )]));
+        GitHubEndpoint::load_all(&missing_token);
+        assert_eq!(
+            missing_token.into_problems(),
+            vec!["GITHUB_TOKEN_GHE is required".to_string()]
+        );
+        println!("✅ GitHub endpoints config test passed!");
+    }
+
     #[test]
     fn test_config_validation() {
         // Set up minimal required environment variables for testing
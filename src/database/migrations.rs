@@ -434,6 +434,1005 @@ pub fn get_all_migrations() -> Vec<Migration> {
                 .to_string(),
             ),
         },
+        // 🏗️ Migration 8: Create feedback events timeline
+        Migration {
+            id: "20240101000008_create_feedback_events".to_string(),
+            description: "Create feedback_events table for the processing timeline".to_string(),
+            up_sql: r#"
+                -- 🕰️ Feedback events - Every step a feedback item takes
+                CREATE TABLE feedback_events (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    feedback_id UUID NOT NULL REFERENCES feedback(id) ON DELETE CASCADE,
+                    event_type VARCHAR(100) NOT NULL,
+                    payload JSONB NOT NULL DEFAULT '{}',
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🔍 Timelines are always read per feedback, oldest first
+                CREATE INDEX idx_feedback_events_feedback_id ON feedback_events(feedback_id, created_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS feedback_events;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 9: Monorepo path scoping
+        Migration {
+            id: "20240101000009_add_feedback_path".to_string(),
+            description: "Add optional subdirectory scope to feedback".to_string(),
+            up_sql: r#"
+                -- 📁 Subdirectory the feedback is scoped to (NULL = whole repository)
+                ALTER TABLE feedback ADD COLUMN path TEXT;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE feedback DROP COLUMN IF EXISTS path;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 10: Scheduled repository health scans
+        Migration {
+            id: "20240101000010_create_repository_scans".to_string(),
+            description: "Create repository_scans table for scheduled health scans".to_string(),
+            up_sql: r#"
+                -- 🩺 Repository scans - One row per scheduled health analysis
+                CREATE TABLE repository_scans (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                    status VARCHAR(20) NOT NULL DEFAULT 'running',
+                    findings JSONB NOT NULL DEFAULT '[]',
+                    feedback_id UUID REFERENCES feedback(id) ON DELETE SET NULL,
+                    issue_url TEXT,
+                    error_message TEXT,
+                    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    completed_at TIMESTAMPTZ
+                );
+
+                -- 🔍 The scheduler asks for the latest scan of each project
+                CREATE INDEX idx_repository_scans_project_id ON repository_scans(project_id, started_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS repository_scans;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 11: Prompt/response logging for debugging
+        Migration {
+            id: "20240101000011_create_llm_exchanges".to_string(),
+            description: "Create llm_exchanges table for opt-in prompt/response logging".to_string(),
+            up_sql: r#"
+                -- 📜 LLM exchanges - Redacted prompts and responses, stored only when opted in
+                CREATE TABLE llm_exchanges (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    feedback_id UUID REFERENCES feedback(id) ON DELETE SET NULL,
+                    project_id UUID REFERENCES projects(id) ON DELETE SET NULL,
+                    stage VARCHAR(100),
+                    provider VARCHAR(50),
+                    model VARCHAR(255),
+                    request JSONB NOT NULL DEFAULT '{}',
+                    response TEXT,
+                    error_message TEXT,
+                    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                    completion_tokens INTEGER NOT NULL DEFAULT 0,
+                    duration_ms BIGINT NOT NULL DEFAULT 0,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🔍 Browsed newest first, usually narrowed to one feedback item
+                CREATE INDEX idx_llm_exchanges_created_at ON llm_exchanges(created_at);
+                CREATE INDEX idx_llm_exchanges_feedback_id ON llm_exchanges(feedback_id, created_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS llm_exchanges;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 12: Prompt versions and A/B experiments
+        Migration {
+            id: "20240101000012_create_prompt_experiments".to_string(),
+            description: "Create prompt_versions, prompt_assignments, and prompt_outcomes tables".to_string(),
+            up_sql: r#"
+                -- 📜 Prompt versions - Stored templates per pipeline stage (built-ins are version 0)
+                CREATE TABLE prompt_versions (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    name VARCHAR(100) NOT NULL,
+                    version INTEGER NOT NULL,
+                    body TEXT NOT NULL,
+                    description TEXT,
+                    traffic_percent INTEGER NOT NULL DEFAULT 0 CHECK (traffic_percent BETWEEN 0 AND 100),
+                    is_active BOOLEAN NOT NULL DEFAULT false,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    UNIQUE (name, version)
+                );
+
+                -- 🎲 Which version each feedback item was served, per stage
+                CREATE TABLE prompt_assignments (
+                    feedback_id UUID NOT NULL REFERENCES feedback(id) ON DELETE CASCADE,
+                    name VARCHAR(100) NOT NULL,
+                    version INTEGER NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (feedback_id, name)
+                );
+
+                -- 📊 Outcomes per feedback item (latest value wins)
+                CREATE TABLE prompt_outcomes (
+                    feedback_id UUID NOT NULL REFERENCES feedback(id) ON DELETE CASCADE,
+                    metric VARCHAR(50) NOT NULL,
+                    value BOOLEAN NOT NULL,
+                    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (feedback_id, metric)
+                );
+
+                -- 🔍 Stats are grouped by stage and version
+                CREATE INDEX idx_prompt_assignments_name ON prompt_assignments(name, version);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS prompt_outcomes;
+                DROP TABLE IF EXISTS prompt_assignments;
+                DROP TABLE IF EXISTS prompt_versions;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 13: Dead-letter queue for background jobs
+        Migration {
+            id: "20240101000013_add_job_dead_letters".to_string(),
+            description: "Add error history and dead-letter timestamp to background_jobs".to_string(),
+            up_sql: r#"
+                -- 📜 Every failed attempt, not just the last one
+                ALTER TABLE background_jobs ADD COLUMN error_history JSONB NOT NULL DEFAULT '[]';
+                -- ☠️ When the job ran out of retries
+                ALTER TABLE background_jobs ADD COLUMN dead_lettered_at TIMESTAMPTZ;
+
+                CREATE INDEX idx_background_jobs_dead_lettered_at ON background_jobs(dead_lettered_at)
+                    WHERE status = 'dead';
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_background_jobs_dead_lettered_at;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS dead_lettered_at;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS error_history;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 14: Job priority lanes and per-user fairness
+        Migration {
+            id: "20240101000014_add_job_priority".to_string(),
+            description: "Add priority and user_id to background_jobs".to_string(),
+            up_sql: r#"
+                -- 🚦 Lane: 100 interactive, 50 normal, 0 bulk (higher is claimed first)
+                ALTER TABLE background_jobs ADD COLUMN priority SMALLINT NOT NULL DEFAULT 50;
+                -- 👤 Whose work this is, so one account's batch can't take every worker
+                ALTER TABLE background_jobs ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+                CREATE INDEX idx_background_jobs_claim ON background_jobs(priority DESC, scheduled_at)
+                    WHERE status = 'pending';
+                CREATE INDEX idx_background_jobs_running_user ON background_jobs(user_id)
+                    WHERE status = 'running';
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_background_jobs_running_user;
+                DROP INDEX IF EXISTS idx_background_jobs_claim;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS user_id;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS priority;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 15: Job claims and heartbeats for multi-instance workers
+        Migration {
+            id: "20240101000015_add_job_heartbeats".to_string(),
+            description: "Add worker_id and heartbeat_at to background_jobs".to_string(),
+            up_sql: r#"
+                -- 👷 Instance that claimed the job
+                ALTER TABLE background_jobs ADD COLUMN worker_id VARCHAR(255);
+                -- 💓 Last sign of life from that instance
+                ALTER TABLE background_jobs ADD COLUMN heartbeat_at TIMESTAMPTZ;
+
+                CREATE INDEX idx_background_jobs_heartbeat_at ON background_jobs(heartbeat_at)
+                    WHERE status = 'running';
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_background_jobs_heartbeat_at;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS heartbeat_at;
+                ALTER TABLE background_jobs DROP COLUMN IF EXISTS worker_id;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 16: Recurring jobs on cron schedules
+        Migration {
+            id: "20240101000016_create_scheduled_jobs".to_string(),
+            description: "Create scheduled_jobs table".to_string(),
+            up_sql: r#"
+                -- ⏰ Scheduled jobs - Cron schedules that enqueue background jobs
+                CREATE TABLE scheduled_jobs (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    name VARCHAR(100) NOT NULL UNIQUE,
+                    job_type VARCHAR(100) NOT NULL,
+                    cron_expression VARCHAR(100) NOT NULL,
+                    payload JSONB NOT NULL DEFAULT '{}',
+                    catch_up VARCHAR(20) NOT NULL DEFAULT 'once' CHECK (catch_up IN ('skip', 'once', 'all')),
+                    allow_overlap BOOLEAN NOT NULL DEFAULT false,
+                    paused BOOLEAN NOT NULL DEFAULT false,
+                    next_run_at TIMESTAMPTZ NOT NULL,
+                    last_run_at TIMESTAMPTZ,
+                    last_job_id UUID REFERENCES background_jobs(id) ON DELETE SET NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                CREATE INDEX idx_scheduled_jobs_next_run_at ON scheduled_jobs(next_run_at)
+                    WHERE NOT paused;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS scheduled_jobs;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 17: Revoking a user's tokens
+        Migration {
+            id: "20240101000017_add_user_tokens_valid_after".to_string(),
+            description: "Add tokens_valid_after to users".to_string(),
+            up_sql: r#"
+                -- 🚫 Tokens issued before this moment are rejected (NULL = all accepted)
+                ALTER TABLE users ADD COLUMN tokens_valid_after TIMESTAMPTZ;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS tokens_valid_after;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 18: Runtime feature flags
+        Migration {
+            id: "20240101000018_create_feature_flags".to_string(),
+            description: "Create feature_flags and feature_flag_overrides tables".to_string(),
+            up_sql: r#"
+                -- 🚩 Feature flags - Toggled at runtime by admins
+                CREATE TABLE feature_flags (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    name VARCHAR(100) NOT NULL UNIQUE,
+                    description TEXT,
+                    enabled BOOLEAN NOT NULL DEFAULT false,
+                    rollout_percent INTEGER NOT NULL DEFAULT 100 CHECK (rollout_percent BETWEEN 0 AND 100),
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🎯 Per-project overrides, which win over the flag's own setting
+                CREATE TABLE feature_flag_overrides (
+                    flag_id UUID NOT NULL REFERENCES feature_flags(id) ON DELETE CASCADE,
+                    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                    enabled BOOLEAN NOT NULL,
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (flag_id, project_id)
+                );
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS feature_flag_overrides;
+                DROP TABLE IF EXISTS feature_flags;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 19: Organizations and teams
+        Migration {
+            id: "20240101000019_create_organizations".to_string(),
+            description: "Create organizations and teams; scope projects and service accounts to them".to_string(),
+            up_sql: r#"
+                -- 🏢 Organizations - Share projects without sharing an account
+                CREATE TABLE organizations (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    name VARCHAR(255) NOT NULL,
+                    slug VARCHAR(100) NOT NULL UNIQUE,
+                    settings JSONB NOT NULL DEFAULT '{}',
+                    max_projects INTEGER CHECK (max_projects >= 0),
+                    max_monthly_feedback INTEGER CHECK (max_monthly_feedback >= 0),
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 👥 Members and their role in the organization
+                CREATE TABLE organization_members (
+                    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'admin', 'member', 'viewer')),
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (organization_id, user_id)
+                );
+                CREATE INDEX idx_organization_members_user_id ON organization_members(user_id);
+
+                -- 🧑‍🤝‍🧑 Teams - Groups of members a project can be limited to
+                CREATE TABLE teams (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+                    name VARCHAR(100) NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    UNIQUE(organization_id, name)
+                );
+
+                CREATE TABLE team_members (
+                    team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (team_id, user_id)
+                );
+
+                -- 🏠 Projects belong to an organization (and optionally one of its teams)
+                ALTER TABLE projects
+                    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL,
+                    ADD COLUMN team_id UUID REFERENCES teams(id) ON DELETE SET NULL;
+                CREATE INDEX idx_projects_organization_id ON projects(organization_id);
+
+                -- 🔑 Service accounts owned by an organization only reach its projects
+                ALTER TABLE users
+                    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS organization_id;
+                ALTER TABLE projects DROP COLUMN IF EXISTS team_id;
+                ALTER TABLE projects DROP COLUMN IF EXISTS organization_id;
+                DROP TABLE IF EXISTS team_members;
+                DROP TABLE IF EXISTS teams;
+                DROP TABLE IF EXISTS organization_members;
+                DROP TABLE IF EXISTS organizations;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 20: Editable roles and permissions
+        Migration {
+            id: "20240101000020_create_roles".to_string(),
+            description: "Create roles, role_permissions and user_roles tables".to_string(),
+            up_sql: r#"
+                -- 🎭 Roles - Named sets of permissions, editable by admins
+                CREATE TABLE roles (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    name VARCHAR(100) NOT NULL UNIQUE,
+                    description TEXT,
+                    is_builtin BOOLEAN NOT NULL DEFAULT false,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                CREATE TABLE role_permissions (
+                    role_id UUID NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+                    permission VARCHAR(50) NOT NULL,
+                    PRIMARY KEY (role_id, permission)
+                );
+
+                -- 👤 Extra roles granted to users on top of their account role
+                CREATE TABLE user_roles (
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    role_id UUID NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (user_id, role_id)
+                );
+
+                -- 🌱 Built-in roles for the account roles, with the grants they always had
+                INSERT INTO roles (name, description, is_builtin) VALUES
+                    ('user', 'Every regular account', true),
+                    ('service', 'Service accounts used for automation', true),
+                    ('admin', 'Administrators (always hold every permission)', true);
+                INSERT INTO role_permissions (role_id, permission)
+                SELECT r.id, p.permission FROM roles r
+                JOIN (VALUES
+                    ('user', 'read_feedback'), ('user', 'submit_feedback'), ('user', 'approve_pull_requests'),
+                    ('service', 'read_feedback'), ('service', 'submit_feedback'), ('service', 'approve_pull_requests'),
+                    ('service', 'manage_projects')
+                ) AS p(role, permission) ON p.role = r.name;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS user_roles;
+                DROP TABLE IF EXISTS role_permissions;
+                DROP TABLE IF EXISTS roles;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 21: Single sign-on
+        Migration {
+            id: "20240101000021_create_sso_providers".to_string(),
+            description: "Create sso_providers, sso_login_states and user_identities tables".to_string(),
+            up_sql: r#"
+                -- 🔑 One identity provider per organization
+                CREATE TABLE sso_providers (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    organization_id UUID NOT NULL UNIQUE REFERENCES organizations(id) ON DELETE CASCADE,
+                    protocol VARCHAR(10) NOT NULL DEFAULT 'oidc' CHECK (protocol IN ('oidc')),
+                    issuer_url TEXT NOT NULL,
+                    client_id TEXT NOT NULL,
+                    client_secret TEXT NOT NULL,
+                    scopes TEXT NOT NULL DEFAULT 'openid email profile',
+                    email_domains TEXT[] NOT NULL DEFAULT '{}',
+                    groups_claim VARCHAR(100) NOT NULL DEFAULT 'groups',
+                    role_mappings JSONB NOT NULL DEFAULT '{}',
+                    default_role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (default_role IN ('viewer', 'member', 'admin')),
+                    jit_provisioning BOOLEAN NOT NULL DEFAULT true,
+                    enforce_sso BOOLEAN NOT NULL DEFAULT false,
+                    enabled BOOLEAN NOT NULL DEFAULT true,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🎟️ Sign-ins in progress (state, nonce and PKCE verifier)
+                CREATE TABLE sso_login_states (
+                    state VARCHAR(64) PRIMARY KEY,
+                    provider_id UUID NOT NULL REFERENCES sso_providers(id) ON DELETE CASCADE,
+                    nonce VARCHAR(64) NOT NULL,
+                    code_verifier VARCHAR(128) NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🪪 Which provider account belongs to which user
+                CREATE TABLE user_identities (
+                    provider_id UUID NOT NULL REFERENCES sso_providers(id) ON DELETE CASCADE,
+                    subject TEXT NOT NULL,
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    last_login_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (provider_id, subject)
+                );
+                CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS user_identities;
+                DROP TABLE IF EXISTS sso_login_states;
+                DROP TABLE IF EXISTS sso_providers;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 22: Token signing keys
+        Migration {
+            id: "20240101000022_create_signing_keys".to_string(),
+            description: "Create signing_keys table for asymmetric JWT signing".to_string(),
+            up_sql: r#"
+                -- 🔏 Key pairs tokens are signed with; the one not yet retired signs,
+                -- retired ones keep verifying until they expire
+                CREATE TABLE signing_keys (
+                    kid VARCHAR(64) PRIMARY KEY,
+                    algorithm VARCHAR(10) NOT NULL CHECK (algorithm IN ('EdDSA', 'RS256')),
+                    private_key TEXT NOT NULL,
+                    public_jwk JSONB NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    retired_at TIMESTAMPTZ,
+                    expires_at TIMESTAMPTZ
+                );
+                CREATE UNIQUE INDEX idx_signing_keys_current ON signing_keys ((true)) WHERE retired_at IS NULL;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS signing_keys;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 23: API key scopes and rate limit tiers
+        Migration {
+            id: "20240101000023_add_user_api_key_limits".to_string(),
+            description: "Add api_scopes and rate_limit_tier to users".to_string(),
+            up_sql: r#"
+                -- 🔑 Areas of the API a service account's key reaches (NULL = all of them)
+                -- and how much traffic it may send
+                ALTER TABLE users
+                    ADD COLUMN api_scopes TEXT[],
+                    ADD COLUMN rate_limit_tier VARCHAR(20)
+                        CHECK (rate_limit_tier IN ('standard', 'trusted', 'unlimited'));
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS rate_limit_tier;
+                ALTER TABLE users DROP COLUMN IF EXISTS api_scopes;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 24: Sliding rate limit windows
+        Migration {
+            id: "20240101000024_add_rate_limit_previous_count".to_string(),
+            description: "Add previous_count to rate_limits for sliding windows".to_string(),
+            up_sql: r#"
+                -- 📊 The window before the current one still counts while it slides out
+                ALTER TABLE rate_limits ADD COLUMN previous_count INTEGER NOT NULL DEFAULT 0;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE rate_limits DROP COLUMN IF EXISTS previous_count;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 25: Preferred language
+        Migration {
+            id: "20240101000025_add_user_locale".to_string(),
+            description: "Add locale to users".to_string(),
+            up_sql: r#"
+                -- 🌍 Language for API messages and pages (NULL = follow Accept-Language)
+                ALTER TABLE users ADD COLUMN locale VARCHAR(10);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS locale;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 26: Feedback exports and token usage
+        Migration {
+            id: "20240101000026_create_project_exports".to_string(),
+            description: "Add token usage to feedback; create project_exports table".to_string(),
+            up_sql: r#"
+                -- 🔢 LLM tokens spent on each feedback item, counted for every call
+                ALTER TABLE feedback
+                    ADD COLUMN prompt_tokens BIGINT NOT NULL DEFAULT 0,
+                    ADD COLUMN completion_tokens BIGINT NOT NULL DEFAULT 0;
+
+                -- 📦 Exports too large to stream, written by a background job
+                CREATE TABLE project_exports (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+                    format VARCHAR(10) NOT NULL CHECK (format IN ('csv', 'jsonl')),
+                    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+                        CHECK (status IN ('pending', 'completed', 'failed')),
+                    row_count BIGINT,
+                    file_path TEXT,
+                    error_message TEXT,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    completed_at TIMESTAMPTZ,
+                    expires_at TIMESTAMPTZ
+                );
+                CREATE INDEX idx_project_exports_expires_at ON project_exports(expires_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS project_exports;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS completion_tokens;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS prompt_tokens;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 27: Stage timings per feedback
+        Migration {
+            id: "20240101000027_create_feedback_spans".to_string(),
+            description: "Create feedback_spans table for per-stage pipeline timings".to_string(),
+            up_sql: r#"
+                -- 🧵 Feedback spans - How long each stage of a feedback item took
+                CREATE TABLE feedback_spans (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    feedback_id UUID NOT NULL REFERENCES feedback(id) ON DELETE CASCADE,
+                    stage VARCHAR(50) NOT NULL,
+                    started_at TIMESTAMPTZ NOT NULL,
+                    duration_ms BIGINT NOT NULL,
+                    failed BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 🔍 Traces are always read per feedback, in the order stages started
+                CREATE INDEX idx_feedback_spans_feedback_id ON feedback_spans(feedback_id, started_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS feedback_spans;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 28: Triage
+        Migration {
+            id: "20240101000028_create_feedback_comments".to_string(),
+            description: "Add assignee to feedback; create feedback_comments table".to_string(),
+            up_sql: r#"
+                -- 👥 Maintainer looking after each feedback item
+                ALTER TABLE feedback
+                    ADD COLUMN assigned_to UUID REFERENCES users(id) ON DELETE SET NULL;
+                CREATE INDEX idx_feedback_assigned_to ON feedback(assigned_to)
+                    WHERE assigned_to IS NOT NULL;
+
+                -- 💬 Internal notes maintainers leave on feedback (submitters don't see them)
+                CREATE TABLE feedback_comments (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    feedback_id UUID NOT NULL REFERENCES feedback(id) ON DELETE CASCADE,
+                    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+                    body TEXT NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+                CREATE INDEX idx_feedback_comments_feedback_id ON feedback_comments(feedback_id, created_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS feedback_comments;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS assigned_to;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 29: Activity digests
+        Migration {
+            id: "20240101000029_add_user_digest_preferences".to_string(),
+            description: "Add digest frequency and last digest time to users".to_string(),
+            up_sql: r#"
+                -- 📬 How often each user gets an activity digest by email, and when the last went out
+                ALTER TABLE users
+                    ADD COLUMN digest_frequency VARCHAR(10) NOT NULL DEFAULT 'weekly'
+                        CHECK (digest_frequency IN ('never', 'daily', 'weekly')),
+                    ADD COLUMN digest_sent_at TIMESTAMPTZ;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS digest_sent_at;
+                ALTER TABLE users DROP COLUMN IF EXISTS digest_frequency;
+            "#
+                .to_string(),
+            ),
+        },
+        Migration {
+            id: "20240101000030_add_user_default_llm_provider".to_string(),
+            description: "Add the preferred LLM provider to users".to_string(),
+            up_sql: r#"
+                -- 🤖 LLM provider a user prefers for their feedback (NULL = the project's default)
+                ALTER TABLE users ADD COLUMN default_llm_provider VARCHAR(20);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                ALTER TABLE users DROP COLUMN IF EXISTS default_llm_provider;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 31: Security alerts
+        Migration {
+            id: "20240101000031_create_security_alerts".to_string(),
+            description: "Create user devices and security alert preferences".to_string(),
+            up_sql: r#"
+                -- 🔐 Security alerts are notifications of their own kind
+                ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'security_alert';
+
+                -- 📧 Whether security alerts are also emailed (they're always in the notifications)
+                ALTER TABLE users ADD COLUMN security_alert_emails BOOLEAN NOT NULL DEFAULT TRUE;
+
+                -- 💻 Devices each user has signed in from, so a new one can be told apart
+                CREATE TABLE user_devices (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    fingerprint VARCHAR(64) NOT NULL,
+                    user_agent TEXT NOT NULL,
+                    ip_address VARCHAR(45) NOT NULL,
+                    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    UNIQUE (user_id, fingerprint)
+                );
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS user_devices;
+                ALTER TABLE users DROP COLUMN IF EXISTS security_alert_emails;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 32: Claiming anonymous feedback
+        Migration {
+            id: "20240101000032_add_feedback_claims".to_string(),
+            description: "Add the submitter email and claim confirmation to feedback".to_string(),
+            up_sql: r#"
+                -- 🙋 Anonymous submitters can leave an email and claim their feedback with it later
+                ALTER TABLE feedback
+                    ADD COLUMN submitter_email VARCHAR(255),
+                    ADD COLUMN claim_confirmed_at TIMESTAMPTZ;
+
+                CREATE INDEX idx_feedback_submitter_email ON feedback (LOWER(submitter_email))
+                    WHERE submitter_email IS NOT NULL;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_feedback_submitter_email;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS claim_confirmed_at;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS submitter_email;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 33: Signed requests from machine clients
+        Migration {
+            id: "20240101000033_add_request_signing".to_string(),
+            description: "Add request signing keys and the signatures already seen".to_string(),
+            up_sql: r#"
+                -- 🔏 A service account with a signing key must sign every request
+                ALTER TABLE users ADD COLUMN request_signing_key VARCHAR(64);
+
+                -- 🔁 Signatures already accepted, so a captured request can't be replayed
+                CREATE TABLE request_signatures (
+                    signature VARCHAR(64) PRIMARY KEY,
+                    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                CREATE INDEX idx_request_signatures_seen_at ON request_signatures (seen_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS request_signatures;
+                ALTER TABLE users DROP COLUMN IF EXISTS request_signing_key;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 34: Publishing feedback events to Kafka or NATS
+        Migration {
+            id: "20240101000034_add_event_streaming".to_string(),
+            description: "Track which feedback events were published to the event stream"
+                .to_string(),
+            up_sql: r#"
+                -- 📤 Events recorded before this migration count as published (no
+                -- history is replayed); the default then flips so new ones wait
+                ALTER TABLE feedback_events ADD COLUMN streamed BOOLEAN NOT NULL DEFAULT TRUE;
+                ALTER TABLE feedback_events ALTER COLUMN streamed SET DEFAULT FALSE;
+
+                CREATE INDEX idx_feedback_events_unstreamed ON feedback_events (created_at, id)
+                    WHERE NOT streamed;
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_feedback_events_unstreamed;
+                ALTER TABLE feedback_events DROP COLUMN IF EXISTS streamed;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 35: Feedback events as the source of truth of the lifecycle
+        Migration {
+            id: "20240101000035_add_feedback_event_versions".to_string(),
+            description: "Number each feedback's events and snapshot the lifecycle they start from"
+                .to_string(),
+            up_sql: r#"
+                -- 🔢 Each feedback's events are numbered 1, 2, 3... in the order they were appended
+                ALTER TABLE feedback ADD COLUMN event_version BIGINT NOT NULL DEFAULT 0;
+                ALTER TABLE feedback_events ADD COLUMN version BIGINT;
+
+                UPDATE feedback_events e SET version = numbered.version
+                FROM (
+                    SELECT id, ROW_NUMBER() OVER (PARTITION BY feedback_id ORDER BY created_at, id) AS version
+                    FROM feedback_events
+                ) numbered
+                WHERE e.id = numbered.id;
+
+                -- 📸 Older events don't say everything the row knows (error messages),
+                -- so every existing item's history ends in a snapshot of its lifecycle
+                INSERT INTO feedback_events (feedback_id, event_type, payload, version, streamed)
+                SELECT f.id, 'lifecycle_snapshot',
+                    jsonb_build_object('status', f.status, 'error_message', f.error_message, 'completed_at', f.completed_at),
+                    COALESCE((SELECT MAX(e.version) FROM feedback_events e WHERE e.feedback_id = f.id), 0) + 1,
+                    TRUE
+                FROM feedback f;
+
+                UPDATE feedback f SET event_version = (SELECT MAX(e.version) FROM feedback_events e WHERE e.feedback_id = f.id);
+
+                ALTER TABLE feedback_events ALTER COLUMN version SET NOT NULL;
+                CREATE UNIQUE INDEX idx_feedback_events_version ON feedback_events (feedback_id, version);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_feedback_events_version;
+                DELETE FROM feedback_events WHERE event_type = 'lifecycle_snapshot';
+                ALTER TABLE feedback_events DROP COLUMN IF EXISTS version;
+                ALTER TABLE feedback DROP COLUMN IF EXISTS event_version;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 36: Pipeline checkpoints, so a retried run picks up where it stopped
+        Migration {
+            id: "20240101000036_add_pipeline_checkpoints".to_string(),
+            description: "Keep the plan and generated files of an unfinished pipeline run"
+                .to_string(),
+            up_sql: r#"
+                -- 💾 One per feedback item; gone once its pull request is open
+                CREATE TABLE pipeline_checkpoints (
+                    feedback_id UUID PRIMARY KEY REFERENCES feedback(id) ON DELETE CASCADE,
+                    base_commit TEXT NOT NULL,
+                    plan JSONB,
+                    generated JSONB NOT NULL DEFAULT '[]',
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+                CREATE INDEX idx_pipeline_checkpoints_updated_at ON pipeline_checkpoints (updated_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS pipeline_checkpoints;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 37: Spend caps, per feedback item and per project per month
+        Migration {
+            id: "20240101000037_add_token_budgets".to_string(),
+            description: "Pause feedback that goes over its token budget and count project usage per month"
+                .to_string(),
+            up_sql: r#"
+                -- 💸 Paused until someone approves spending more
+                ALTER TYPE feedback_status ADD VALUE IF NOT EXISTS 'budget_exceeded';
+
+                -- 📊 Tokens each project used, one row per calendar month (UTC)
+                CREATE TABLE project_token_usage (
+                    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                    month DATE NOT NULL,
+                    prompt_tokens BIGINT NOT NULL DEFAULT 0,
+                    completion_tokens BIGINT NOT NULL DEFAULT 0,
+                    PRIMARY KEY (project_id, month)
+                );
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS project_token_usage;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 38: Repositories each GitHub App installation can reach
+        Migration {
+            id: "20240101000038_add_github_installations".to_string(),
+            description: "Track GitHub App installations and the repositories they grant access to"
+                .to_string(),
+            up_sql: r#"
+                -- 🔌 One row per installation of the GitHub App
+                CREATE TABLE github_installations (
+                    id BIGINT PRIMARY KEY,
+                    account VARCHAR(255) NOT NULL,
+                    repository_selection VARCHAR(20) NOT NULL DEFAULT 'selected',
+                    suspended BOOLEAN NOT NULL DEFAULT false,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                -- 📋 Repositories an installation was granted
+                CREATE TABLE github_installation_repositories (
+                    installation_id BIGINT NOT NULL REFERENCES github_installations(id) ON DELETE CASCADE,
+                    repository VARCHAR(255) NOT NULL,
+                    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (installation_id, repository)
+                );
+
+                CREATE INDEX idx_github_installation_repositories_repository
+                    ON github_installation_repositories(LOWER(repository));
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS github_installation_repositories;
+                DROP TABLE IF EXISTS github_installations;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 39: Repository collaborators mirrored into projects
+        Migration {
+            id: "20240101000039_add_project_collaborators".to_string(),
+            description: "Store the GitHub collaborators synced into each project".to_string(),
+            up_sql: r#"
+                -- 👥 GitHub logins with enough access to the project's repository
+                CREATE TABLE project_collaborators (
+                    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                    github_login VARCHAR(255) NOT NULL,
+                    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (project_id, github_login)
+                );
+
+                CREATE INDEX idx_users_github_username_lower ON users(LOWER(github_username));
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP INDEX IF EXISTS idx_users_github_username_lower;
+                DROP TABLE IF EXISTS project_collaborators;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 40: Webhook delivery log
+        Migration {
+            id: "20240101000040_add_webhook_deliveries".to_string(),
+            description: "Record every webhook delivery with its result and latency".to_string(),
+            up_sql: r#"
+                -- 📬 One row per delivery received on a webhook endpoint
+                CREATE TABLE webhook_deliveries (
+                    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                    delivery_id VARCHAR(255),
+                    endpoint VARCHAR(50) NOT NULL,
+                    event VARCHAR(100) NOT NULL,
+                    action VARCHAR(100),
+                    repository VARCHAR(255),
+                    status_code INTEGER NOT NULL,
+                    result TEXT NOT NULL,
+                    duration_ms BIGINT NOT NULL,
+                    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                CREATE INDEX idx_webhook_deliveries_repository ON webhook_deliveries(LOWER(repository), received_at DESC);
+                CREATE INDEX idx_webhook_deliveries_received_at ON webhook_deliveries(received_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS webhook_deliveries;
+            "#
+                .to_string(),
+            ),
+        },
+        // 🏗️ Migration 41: Learned repository style profiles
+        Migration {
+            id: "20240101000041_add_project_style_profiles".to_string(),
+            description: "Store the style profile learned from each project's repository".to_string(),
+            up_sql: r#"
+                -- 🎨 One profile per project, replaced on every analysis
+                CREATE TABLE project_style_profiles (
+                    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+                    profile JSONB NOT NULL,
+                    commit_sha VARCHAR(64),
+                    analyzed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );
+
+                CREATE INDEX idx_project_style_profiles_analyzed_at ON project_style_profiles(analyzed_at);
+            "#
+            .to_string(),
+            down_sql: Some(
+                r#"
+                DROP TABLE IF EXISTS project_style_profiles;
+            "#
+                .to_string(),
+            ),
+        },
     ]
 }
 
@@ -480,6 +1479,147 @@ pub async fn rollback_migration(pool: &PgPool, migration_id: &str) -> Result<()>
     Ok(())
 }
 
+/// 🔍 Whether the migrations tracking table exists yet
+pub async fn migrations_table_exists(pool: &PgPool) -> Result<bool> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM information_schema.tables WHERE table_name = 'migrations')",
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to check if migrations table exists")
+}
+
+/// 🚦 Where a migration stands in this database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    /// ⏳ Not applied yet
+    Pending,
+    /// ✅ Applied with the SQL this build has
+    Applied,
+    /// ⚠️ Applied, but its SQL has changed since
+    Modified,
+    /// ❓ Applied, but unknown to this build (e.g. applied by a newer version)
+    Unknown,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Pending => "pending",
+            MigrationState::Applied => "applied",
+            MigrationState::Modified => "modified",
+            MigrationState::Unknown => "unknown",
+        }
+    }
+
+    /// ✅ Whether the migration is recorded as applied
+    pub fn is_applied(&self) -> bool {
+        *self != MigrationState::Pending
+    }
+}
+
+/// 📋 One migration and where it stands
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub id: String,
+    pub description: String,
+    pub state: MigrationState,
+    /// ⏰ When it was applied (None while pending)
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// 📋 A row of the migrations tracking table
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub id: String,
+    pub description: String,
+    pub applied_at: DateTime<Utc>,
+    pub checksum: String,
+}
+
+/// 📋 Status of every migration: the ones this build knows, in order, then
+/// any recorded in the database that it doesn't
+pub fn migration_status(
+    migrations: &[Migration],
+    applied: &[AppliedMigration],
+) -> Vec<MigrationStatus> {
+    let mut statuses: Vec<MigrationStatus> = migrations
+        .iter()
+        .map(|migration| {
+            let record = applied.iter().find(|record| record.id == migration.id);
+            let state = match record {
+                None => MigrationState::Pending,
+                Some(record) if record.checksum != calculate_checksum(&migration.up_sql) => {
+                    MigrationState::Modified
+                }
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus {
+                id: migration.id.clone(),
+                description: migration.description.clone(),
+                state,
+                applied_at: record.map(|record| record.applied_at),
+            }
+        })
+        .collect();
+
+    statuses.extend(
+        applied
+            .iter()
+            .filter(|record| !migrations.iter().any(|m| m.id == record.id))
+            .map(|record| MigrationStatus {
+                id: record.id.clone(),
+                description: record.description.clone(),
+                state: MigrationState::Unknown,
+                applied_at: Some(record.applied_at),
+            }),
+    );
+    statuses
+}
+
+/// 📋 Rows of the migrations tracking table, oldest first (none before it exists)
+pub async fn list_applied_migrations(pool: &PgPool) -> Result<Vec<AppliedMigration>> {
+    if !migrations_table_exists(pool).await? {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, description, applied_at, checksum FROM migrations ORDER BY applied_at, id",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch applied migrations")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AppliedMigration {
+            id: row.get("id"),
+            description: row.get("description"),
+            applied_at: row.get("applied_at"),
+            checksum: row.get("checksum"),
+        })
+        .collect())
+}
+
+/// 🔍 A migration of this build by id
+pub fn find_migration(migration_id: &str) -> Result<Migration> {
+    get_all_migrations()
+        .into_iter()
+        .find(|m| m.id == migration_id)
+        .with_context(|| format!("Migration {} not found", migration_id))
+}
+
+/// 📝 Apply one migration by id (for the migrate CLI)
+pub async fn apply_migration_by_id(pool: &PgPool, migration_id: &str) -> Result<()> {
+    let migration = find_migration(migration_id)?;
+    if !migrations_table_exists(pool).await? {
+        create_migrations_table(pool).await?;
+    }
+    apply_migration(pool, &migration)
+        .await
+        .with_context(|| format!("Failed to apply migration {}", migration.id))
+}
+
 // 🧪 Tests - Because we test our migrations thoroughly!
 #[cfg(test)]
 mod tests {
@@ -525,4 +1665,38 @@ mod tests {
 
         println!("✅ Migration completeness test passed!");
     }
+
+    #[test]
+    fn test_migration_status() {
+        let migrations = get_all_migrations();
+        let record = |migration: &Migration, checksum: String| AppliedMigration {
+            id: migration.id.clone(),
+            description: migration.description.clone(),
+            applied_at: Utc::now(),
+            checksum,
+        };
+        let applied = vec![
+            record(&migrations[0], calculate_checksum(&migrations[0].up_sql)),
+            record(&migrations[1], "edited".to_string()),
+            AppliedMigration {
+                id: "29990101000000_from_the_future".to_string(),
+                description: "Applied by a newer build".to_string(),
+                applied_at: Utc::now(),
+                checksum: "x".to_string(),
+            },
+        ];
+
+        let statuses = migration_status(&migrations, &applied);
+        assert_eq!(statuses.len(), migrations.len() + 1);
+        assert_eq!(statuses[0].state, MigrationState::Applied);
+        assert_eq!(statuses[1].state, MigrationState::Modified);
+        assert_eq!(statuses[2].state, MigrationState::Pending);
+        assert!(statuses[2].applied_at.is_none());
+        assert_eq!(statuses.last().unwrap().state, MigrationState::Unknown);
+        assert!(statuses.last().unwrap().state.is_applied());
+
+        assert!(find_migration(&migrations[3].id).is_ok());
+        assert!(find_migration("nope").is_err());
+        println!("✅ Migration status test passed!");
+    }
 }
@@ -6,10 +6,17 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgPool};
+use std::collections::HashSet;
 use uuid::Uuid;
 
+use crate::config::LlmProvider;
+use crate::feedback_lifecycle::FeedbackLifecycle;
+use feedbacker_types::FeedbackQuery;
+use crate::models::{PathScope, ProjectConfig};
+
 // 📝 Feedback Model - The heart of our system!
 // This represents user feedback that gets processed into GitHub PRs
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -20,6 +27,8 @@ pub struct Feedback {
     pub user_id: Option<Uuid>,
     /// 🎯 Target repository (format: "owner/repo")
     pub repository: String,
+    /// 📁 Monorepo subdirectory the feedback is scoped to (None = whole repository)
+    pub path: Option<String>,
     /// 📝 The actual feedback content
     pub content: String,
     /// 📋 Current status of the feedback processing
@@ -40,28 +49,39 @@ pub struct Feedback {
     pub updated_at: DateTime<Utc>,
     /// ✅ When processing was completed (if applicable)
     pub completed_at: Option<DateTime<Utc>>,
+    /// 👥 Maintainer looking after this feedback (None = unassigned)
+    pub assigned_to: Option<Uuid>,
+    /// 📧 Email an anonymous submitter left, to claim the feedback with later
+    pub submitter_email: Option<String>,
+    /// 🔗 When the submitter opened their claim link (proving the email is theirs)
+    pub claim_confirmed_at: Option<DateTime<Utc>>,
 }
 
-// 📋 Feedback Status Enum - Track where we are in the process!
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "feedback_status", rename_all = "lowercase")]
-pub enum FeedbackStatus {
-    /// 📥 Just received, waiting for processing
-    Pending,
-    /// 🔄 Currently being processed by AI
-    Processing,
-    /// 🤖 AI analysis complete, creating GitHub changes
-    GeneratingChanges,
-    /// 🐙 Creating branch and pull request
-    CreatingPullRequest,
-    /// ✅ Successfully completed with PR created
-    Completed,
-    /// ❌ Failed during processing
-    Failed,
-    /// ⏸️ Paused (waiting for user input or manual intervention)
-    Paused,
+/// 🙋 WHERE clause of feedback left with email $1 and not linked to a person yet
+/// (anonymous, or submitted by a service account on someone's behalf)
+const UNCLAIMED_BY_EMAIL: &str = "LOWER(submitter_email) = LOWER($1) AND (user_id IS NULL OR user_id IN (SELECT id FROM users WHERE role = 'service'))";
+
+/// 🔍 WHERE clause of feedback listings; unset filters ($1 to $6) match everything
+const FEEDBACK_FILTER: &str = "($1::uuid IS NULL OR user_id = $1) AND ($2::feedback_status IS NULL OR status = $2) AND ($3::text IS NULL OR repository = $3) AND ($4::text IS NULL OR llm_provider = $4) AND ($5::timestamptz IS NULL OR created_at >= $5) AND ($6::timestamptz IS NULL OR created_at <= $6) AND ($7::uuid IS NULL OR assigned_to = $7)";
+
+/// 🔗 Bind a listing's filters to FEEDBACK_FILTER's parameters
+fn bind_feedback_filter<'q, O>(
+    query: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+    filter: &'q FeedbackQuery,
+) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+    query
+        .bind(filter.user_id)
+        .bind(filter.status)
+        .bind(filter.repository.as_deref())
+        .bind(filter.llm_provider.as_deref())
+        .bind(filter.from_date)
+        .bind(filter.to_date)
+        .bind(filter.assigned_to)
 }
 
+// 📋 Feedback Status Enum - shared with API clients through feedbacker-types
+pub use feedbacker_types::FeedbackStatus;
+
 // 👤 User Model - Our amazing users who provide feedback!
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -87,6 +107,26 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
     /// 🕒 When the user last logged in
     pub last_login_at: Option<DateTime<Utc>>,
+    /// 🚫 Tokens issued before this are rejected (set when sessions are revoked)
+    pub tokens_valid_after: Option<DateTime<Utc>>,
+    /// 🏢 Organization owning this service account (its API keys only reach that org)
+    pub organization_id: Option<Uuid>,
+    /// 🔑 Scopes this service account's API key is limited to (None = not limited)
+    pub api_scopes: Option<Vec<String>>,
+    /// 🎚️ Rate limit tier of this service account's API key (None = standard)
+    pub rate_limit_tier: Option<String>,
+    /// 🌍 Language the user picked for messages and pages (None = Accept-Language)
+    pub locale: Option<String>,
+    /// 📬 How often they get an activity digest: never, daily or weekly
+    pub digest_frequency: String,
+    /// 📬 When their last digest went out (quiet periods count, nothing to send)
+    pub digest_sent_at: Option<DateTime<Utc>>,
+    /// 🤖 LLM provider they prefer for their feedback (None = the project's default)
+    pub default_llm_provider: Option<String>,
+    /// 🔐 Whether security alerts are emailed too (they always become notifications)
+    pub security_alert_emails: bool,
+    /// 🔏 Key this service account signs its requests with (None = unsigned requests)
+    pub request_signing_key: Option<String>,
 }
 
 // 👑 User Role Enum - Different levels of access
@@ -126,6 +166,10 @@ pub struct Project {
     pub updated_at: DateTime<Utc>,
     /// 🕒 When we last interacted with this project
     pub last_activity_at: Option<DateTime<Utc>>,
+    /// 🏢 Organization sharing this project (None = only the owner)
+    pub organization_id: Option<Uuid>,
+    /// 🧑‍🤝‍🧑 Team within the organization the project is limited to
+    pub team_id: Option<Uuid>,
 }
 
 // 🎫 User Session Model - Track user sessions securely
@@ -162,6 +206,8 @@ pub struct RateLimit {
     pub window_start: DateTime<Utc>,
     /// 🕒 When the last request was made
     pub last_request: DateTime<Utc>,
+    /// 📊 Request count of the window before (weighs in as the window slides)
+    pub previous_count: i32,
 }
 
 // 🔔 Notification Model - Keep users informed
@@ -189,7 +235,7 @@ pub struct Notification {
 
 // 🔔 Notification Type Enum
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "notification_type", rename_all = "lowercase")]
+#[sqlx(type_name = "notification_type", rename_all = "snake_case")]
 pub enum NotificationType {
     /// ✅ Feedback processing completed
     FeedbackCompleted,
@@ -201,49 +247,265 @@ pub enum NotificationType {
     SystemUpdate,
     /// ⚠️ Warning or important notice
     Warning,
+    /// 🔐 Something happened to the account: new sign-in, password change...
+    SecurityAlert,
+}
+
+// 💻 User Device Model - Where someone has signed in from
+// A device is told apart by a fingerprint of its user agent (see
+// crate::security_alerts), so a sign-in from one never seen before stands out
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserDevice {
+    /// 🆔 Unique identifier for this device
+    pub id: Uuid,
+    /// 👤 Whose device it is
+    pub user_id: Uuid,
+    /// 🔏 Fingerprint of the user agent
+    pub fingerprint: String,
+    /// 🧭 User agent of the latest sign-in
+    pub user_agent: String,
+    /// 🌐 Address of the latest sign-in
+    pub ip_address: String,
+    /// 🆕 First sign-in from this device
+    pub first_seen_at: DateTime<Utc>,
+    /// 🕒 Latest sign-in from this device
+    pub last_seen_at: DateTime<Utc>,
 }
 
 // 🏭 Implementation blocks for our models
 impl Feedback {
+    /// 🔍 Metadata key holding the unified diff of the proposed changes
+    pub const PROPOSED_DIFF_KEY: &'static str = "proposed_diff";
+    /// 🎫 Metadata key linking imported feedback back to its GitHub issue
+    pub const SOURCE_ISSUE_KEY: &'static str = "source_issue";
+    /// 🏷️ Metadata key holding the labels set on the feedback
+    pub const LABELS_KEY: &'static str = "labels";
+    /// 🔀 Columns listings can be sorted by
+    pub const SORTABLE: &'static [&'static str] = &[
+        "created_at",
+        "updated_at",
+        "completed_at",
+        "status",
+        "repository",
+    ];
+
     /// ➕ Create a new feedback record
     pub async fn create(
         pool: &PgPool,
         user_id: Option<Uuid>,
         repository: String,
+        path: Option<String>,
         content: String,
     ) -> Result<Self> {
-        let id = Uuid::new_v4();
-        let now = Utc::now();
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start feedback transaction")?;
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "INSERT INTO feedback (user_id, repository, path, content) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(user_id)
+        .bind(repository)
+        .bind(path)
+        .bind(content)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create feedback")?;
 
-        // TODO: Implement proper query when database is set up
-        // For now, return a placeholder feedback object
-        let feedback = Feedback {
-            id,
-            user_id,
-            repository,
-            content,
-            status: FeedbackStatus::Pending,
-            branch_name: None,
-            pull_request_url: None,
-            llm_provider: None,
-            metadata: None,
-            error_message: None,
-            created_at: now,
-            updated_at: now,
-            completed_at: None,
-        };
+        // 🧾 Every lifecycle starts with the event that created it
+        FeedbackEvent::record_in(
+            &mut transaction,
+            feedback.id,
+            FeedbackEvent::CREATED,
+            serde_json::json!({ "status": feedback.status.as_str() }),
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit new feedback")?;
 
         Ok(feedback)
     }
 
+    /// 📧 Remember the email an anonymous submitter left
+    pub async fn set_submitter_email(&mut self, pool: &PgPool, email: &str) -> Result<()> {
+        sqlx::query("UPDATE feedback SET submitter_email = $2 WHERE id = $1")
+            .bind(self.id)
+            .bind(email)
+            .execute(pool)
+            .await
+            .context("Failed to save submitter email")?;
+
+        self.submitter_email = Some(email.to_string());
+        Ok(())
+    }
+
+    /// 🔗 Note that the owner of an email opened a claim link; returns how much
+    /// unclaimed feedback was left with that email
+    pub async fn confirm_claims(pool: &PgPool, email: &str) -> Result<u64> {
+        let confirmed = sqlx::query(&format!(
+            "UPDATE feedback SET claim_confirmed_at = COALESCE(claim_confirmed_at, NOW()) WHERE {}",
+            UNCLAIMED_BY_EMAIL
+        ))
+        .bind(email)
+        .execute(pool)
+        .await
+        .context("Failed to confirm feedback claims")?;
+
+        Ok(confirmed.rows_affected())
+    }
+
+    /// 🙋 Attach the unclaimed feedback left with an email to the account of
+    /// that email; only claims confirmed through a link count, unless the
+    /// account's email is verified itself
+    pub async fn attach_claimed(
+        pool: &PgPool,
+        user_id: Uuid,
+        email: &str,
+        email_verified: bool,
+    ) -> Result<u64> {
+        let attached = sqlx::query(&format!(
+            "UPDATE feedback SET user_id = $2, updated_at = NOW() WHERE {} AND ($3 OR claim_confirmed_at IS NOT NULL)",
+            UNCLAIMED_BY_EMAIL
+        ))
+        .bind(email)
+        .bind(user_id)
+        .bind(email_verified)
+        .execute(pool)
+        .await
+        .context("Failed to attach claimed feedback")?;
+
+        Ok(attached.rows_affected())
+    }
+
     /// 🔍 Find feedback by ID
     pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
-        // TODO: Implement proper query when database is set up
-        let feedback: Option<Feedback> = None;
+        let feedback = sqlx::query_as::<_, Feedback>("SELECT * FROM feedback WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch feedback")?;
 
         Ok(feedback)
     }
 
+    /// 🔒 The feedback items with these IDs, locked until the transaction ends
+    pub async fn lock_many(
+        connection: &mut PgConnection,
+        ids: &[Uuid],
+    ) -> Result<Vec<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback WHERE id = ANY($1) ORDER BY id FOR UPDATE",
+        )
+        .bind(ids)
+        .fetch_all(connection)
+        .await
+        .context("Failed to lock feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// 🏷️ Add a label to the ones in metadata (false when it's already there)
+    pub async fn add_label(
+        &mut self,
+        connection: &mut PgConnection,
+        label: &str,
+    ) -> Result<bool> {
+        let labelled: Option<Option<serde_json::Value>> = sqlx::query_scalar(
+            "UPDATE feedback SET metadata = jsonb_set(COALESCE(metadata, '{}'::jsonb), ARRAY[$3], COALESCE(metadata->$3, '[]'::jsonb) || to_jsonb($2::text)), updated_at = NOW() WHERE id = $1 AND NOT COALESCE(metadata->$3, '[]'::jsonb) ? $2 RETURNING metadata",
+        )
+        .bind(self.id)
+        .bind(label)
+        .bind(Self::LABELS_KEY)
+        .fetch_optional(connection)
+        .await
+        .context("Failed to label feedback")?;
+
+        match labelled {
+            Some(metadata) => {
+                self.metadata = metadata;
+                self.updated_at = Utc::now();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 👥 Assign the feedback to a maintainer (None = unassign)
+    pub async fn assign(&mut self, pool: &PgPool, assignee: Option<Uuid>) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query("UPDATE feedback SET assigned_to = $1, updated_at = $2 WHERE id = $3")
+            .bind(assignee)
+            .bind(now)
+            .bind(self.id)
+            .execute(pool)
+            .await
+            .context("Failed to assign feedback")?;
+
+        self.assigned_to = assignee;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// 🐙 Find the feedback whose pull request this is
+    pub async fn find_by_pull_request(
+        pool: &PgPool,
+        repository: &str,
+        number: u64,
+    ) -> Result<Option<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback WHERE repository = $1 AND id IN (SELECT feedback_id FROM feedback_events WHERE event_type = $2 AND payload->>'number' = $3) ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(repository)
+        .bind(FeedbackEvent::PULL_REQUEST_OPENED)
+        .bind(number.to_string())
+        .fetch_optional(pool)
+        .await
+        .context("Failed to find feedback by pull request")?;
+
+        Ok(feedback)
+    }
+
+    /// 📋 A page of the feedback matching a listing's filters, in `order_by`
+    /// (ORDER BY terms over SORTABLE columns, see PaginationParams::order_by)
+    pub async fn list_matching(
+        pool: &PgPool,
+        filter: &FeedbackQuery,
+        order_by: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>> {
+        let sql = format!(
+            "SELECT * FROM feedback WHERE {} ORDER BY {}, id LIMIT $8 OFFSET $9",
+            FEEDBACK_FILTER, order_by
+        );
+        let feedback = bind_feedback_filter(sqlx::query_as::<_, Feedback>(&sql), filter)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+            .context("Failed to list feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// 🔢 How much feedback matches a listing's filters
+    pub async fn count_matching(pool: &PgPool, filter: &FeedbackQuery) -> Result<i64> {
+        let sql = format!("SELECT COUNT(*) FROM feedback WHERE {}", FEEDBACK_FILTER);
+        let (count,) = bind_feedback_filter(sqlx::query_as::<_, (i64,)>(&sql), filter)
+            .fetch_one(pool)
+            .await
+            .context("Failed to count feedback")?;
+
+        Ok(count)
+    }
+
+    /// 📁 Resolve the scope for this feedback within its project's scope
+    pub fn scope(&self, project_config: &ProjectConfig) -> Result<PathScope> {
+        PathScope::resolve(project_config.path.as_deref(), self.path.as_deref())
+    }
+
     /// 🔄 Update feedback status
     pub async fn update_status(
         &mut self,
@@ -251,20 +513,239 @@ impl Feedback {
         status: FeedbackStatus,
         error_message: Option<String>,
     ) -> Result<()> {
-        let now = Utc::now();
-        let completed_at = if matches!(status, FeedbackStatus::Completed | FeedbackStatus::Failed) {
-            Some(now)
-        } else {
-            None
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start status transaction")?;
+        self.update_status_in(&mut transaction, status, error_message)
+            .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit status change")?;
+        Ok(())
+    }
+
+    /// 🔄 Update feedback status on a connection (e.g. inside a transaction).
+    /// The status_changed event is appended first and the row is then brought
+    /// up to date from it, so the timeline is the record and the row follows
+    pub async fn update_status_in(
+        &mut self,
+        connection: &mut PgConnection,
+        status: FeedbackStatus,
+        error_message: Option<String>,
+    ) -> Result<()> {
+        // 🧾 Appending locks the row, so concurrent writers queue up here
+        let event = FeedbackEvent::record_in(
+            &mut *connection,
+            self.id,
+            FeedbackEvent::STATUS_CHANGED,
+            serde_json::json!({ "status": status.as_str(), "error_message": error_message }),
+        )
+        .await?;
+
+        let lifecycle = FeedbackLifecycle::default().apply(&event);
+        Self::set_lifecycle(&mut *connection, self.id, &lifecycle, event.created_at).await?;
+
+        self.status = lifecycle.status;
+        self.error_message = lifecycle.error_message;
+        self.updated_at = event.created_at;
+        self.completed_at = lifecycle.completed_at;
+
+        Ok(())
+    }
+
+    /// 🧾 Write a lifecycle replayed from the timeline onto the feedback row
+    pub async fn set_lifecycle(
+        connection: &mut PgConnection,
+        id: Uuid,
+        lifecycle: &FeedbackLifecycle,
+        updated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE feedback SET status = $1, error_message = $2, updated_at = $3, completed_at = $4 WHERE id = $5",
+        )
+        .bind(lifecycle.status)
+        .bind(&lifecycle.error_message)
+        .bind(updated_at)
+        .bind(lifecycle.completed_at)
+        .bind(id)
+        .execute(&mut *connection)
+        .await
+        .context("Failed to update feedback status")?;
+        Ok(())
+    }
+
+    /// 🔒 Lock a feedback row for the rest of the transaction (None if it's gone)
+    pub async fn find_for_update(connection: &mut PgConnection, id: Uuid) -> Result<Option<Self>> {
+        let feedback =
+            sqlx::query_as::<_, Feedback>("SELECT * FROM feedback WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *connection)
+                .await
+                .context("Failed to lock feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// ⏱️ Items in `status` not updated since `since`, with no queued or
+    /// running job about them (longest waiting first)
+    pub async fn stuck(
+        pool: &PgPool,
+        status: FeedbackStatus,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback f WHERE status = $1 AND updated_at < $2 AND NOT EXISTS (SELECT 1 FROM background_jobs j WHERE j.payload @> jsonb_build_object('feedback_id', f.id) AND j.status IN ($3, $4)) ORDER BY updated_at LIMIT $5",
+        )
+        .bind(status)
+        .bind(since)
+        .bind(BackgroundJob::PENDING)
+        .bind(BackgroundJob::RUNNING)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to find stuck feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// ⏰ Last update of the longest waiting item in `status` (None if there are none)
+    pub async fn oldest_in_status(
+        pool: &PgPool,
+        status: FeedbackStatus,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let oldest = sqlx::query_scalar("SELECT MIN(updated_at) FROM feedback WHERE status = $1")
+            .bind(status)
+            .fetch_one(pool)
+            .await
+            .context("Failed to find the oldest feedback in a status")?;
+
+        Ok(oldest)
+    }
+
+    /// 🆔 IDs of every feedback item, oldest first
+    pub async fn all_ids(pool: &PgPool) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar("SELECT id FROM feedback ORDER BY created_at, id")
+            .fetch_all(pool)
+            .await
+            .context("Failed to list feedback")?;
+
+        Ok(ids)
+    }
+
+    /// 🧩 Merge keys into the metadata JSON (existing keys are overwritten)
+    pub async fn merge_metadata(&mut self, pool: &PgPool, patch: serde_json::Value) -> Result<()> {
+        let merged: Option<serde_json::Value> = sqlx::query_scalar(
+            "UPDATE feedback SET metadata = COALESCE(metadata, '{}'::jsonb) || $1 WHERE id = $2 RETURNING metadata",
+        )
+        .bind(&patch)
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update feedback metadata")?;
+
+        self.metadata = merged;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// 🤖 Record the LLM provider that actually answered (fallbacks included)
+    /// Skips the write when the stored provider is already the same
+    pub async fn record_llm_provider(&self, pool: &PgPool, provider: LlmProvider) -> Result<()> {
+        sqlx::query(
+            "UPDATE feedback SET llm_provider = $1, updated_at = NOW() WHERE id = $2 AND llm_provider IS DISTINCT FROM $1",
+        )
+        .bind(provider.as_str())
+        .bind(self.id)
+        .execute(pool)
+        .await
+        .context("Failed to record feedback LLM provider")?;
+        Ok(())
+    }
+
+    /// 🐙 Numbers of the pull requests opened for this feedback, oldest first
+    pub async fn pull_request_numbers(&self, pool: &PgPool) -> Result<Vec<u64>> {
+        let numbers: Vec<i64> = sqlx::query_scalar(
+            "SELECT DISTINCT (payload->>'number')::bigint FROM feedback_events WHERE feedback_id = $1 AND event_type = $2 AND payload ? 'number' ORDER BY 1",
+        )
+        .bind(self.id)
+        .bind(FeedbackEvent::PULL_REQUEST_OPENED)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list feedback pull requests")?;
+
+        Ok(numbers.into_iter().map(|number| number as u64).collect())
+    }
+
+    /// 🤖 The submitter's default LLM provider (None without a submitter or a preference)
+    pub async fn submitter_llm_provider(&self, pool: &PgPool) -> Result<Option<LlmProvider>> {
+        let Some(user_id) = self.user_id else {
+            return Ok(None);
         };
+        let provider: Option<String> =
+            sqlx::query_scalar("SELECT default_llm_provider FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch the submitter's LLM provider")?
+                .flatten();
+        Ok(provider.and_then(|provider| provider.parse().ok()))
+    }
 
-        // TODO: Implement proper query when database is set up
+    /// 🔍 Store the unified diff of the changes proposed for this feedback
+    pub async fn record_proposed_diff(&self, pool: &PgPool, diff: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE feedback SET metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object($1::text, $2::text), updated_at = NOW() WHERE id = $3",
+        )
+        .bind(Self::PROPOSED_DIFF_KEY)
+        .bind(diff)
+        .bind(self.id)
+        .execute(pool)
+        .await
+        .context("Failed to record proposed diff")?;
+        Ok(())
+    }
 
-        self.status = status;
-        self.error_message = error_message;
-        self.updated_at = now;
-        self.completed_at = completed_at;
+    /// 🔍 The stored diff of the proposed changes, if any were generated
+    pub fn proposed_diff(&self) -> Option<&str> {
+        self.metadata.as_ref()?.get(Self::PROPOSED_DIFF_KEY)?.as_str()
+    }
+
+    /// 🗄️ Artifacts listed under a metadata key that were stored more than `days`
+    /// ago, as (feedback id, kind, storage key)
+    pub async fn expired_artifacts(
+        pool: &PgPool,
+        metadata_key: &str,
+        days: u32,
+    ) -> Result<Vec<(Uuid, String, String)>> {
+        let expired = sqlx::query_as::<_, (Uuid, String, String)>(
+            "SELECT f.id, a.key, a.value->>'key' FROM feedback f, jsonb_each(f.metadata->$1) a WHERE jsonb_typeof(f.metadata->$1) = 'object' AND a.value->>'key' IS NOT NULL AND (a.value->>'stored_at')::timestamptz < NOW() - make_interval(days => $2)",
+        )
+        .bind(metadata_key)
+        .bind(days as i32)
+        .fetch_all(pool)
+        .await
+        .context("Failed to find expired artifacts")?;
 
+        Ok(expired)
+    }
+
+    /// 🗑️ Drop one artifact from the listing under a metadata key
+    pub async fn forget_artifact(
+        pool: &PgPool,
+        id: Uuid,
+        metadata_key: &str,
+        kind: &str,
+    ) -> Result<()> {
+        sqlx::query("UPDATE feedback SET metadata = metadata #- ARRAY[$1::text, $2::text] WHERE id = $3")
+            .bind(metadata_key)
+            .bind(kind)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to forget artifact")?;
         Ok(())
     }
 
@@ -279,6 +760,273 @@ impl Feedback {
             failed: 0,
         })
     }
+
+    /// 🔢 Feedback counts for each of these repositories (ones without feedback are left out)
+    pub async fn counts_by_repository(
+        pool: &PgPool,
+        repositories: &[String],
+    ) -> Result<Vec<FeedbackCounts>> {
+        let counts = sqlx::query_as::<_, FeedbackCounts>(
+            "SELECT repository, COUNT(*) AS total, COUNT(*) FILTER (WHERE status NOT IN ('completed', 'failed')) AS open, COUNT(*) FILTER (WHERE status = 'completed') AS completed, COUNT(*) FILTER (WHERE status = 'failed') AS failed FROM feedback WHERE repository = ANY($1) GROUP BY repository",
+        )
+        .bind(repositories)
+        .fetch_all(pool)
+        .await
+        .context("Failed to count feedback by repository")?;
+
+        Ok(counts)
+    }
+
+    /// 📬 What happened in each of these repositories since `since` (ones where
+    /// nothing did are left out)
+    pub async fn activity_by_repository(
+        pool: &PgPool,
+        repositories: &[String],
+        since: DateTime<Utc>,
+    ) -> Result<Vec<RepositoryActivity>> {
+        let activity = sqlx::query_as::<_, RepositoryActivity>(
+            "SELECT * FROM (SELECT f.repository, COUNT(*) FILTER (WHERE f.created_at >= $2) AS new_feedback, COALESCE(SUM((SELECT COUNT(*) FROM feedback_events e WHERE e.feedback_id = f.id AND e.event_type = $3 AND e.created_at >= $2)), 0)::bigint AS pull_requests_opened, COALESCE(SUM((SELECT COUNT(*) FROM prompt_outcomes o WHERE o.feedback_id = f.id AND o.metric = $4 AND o.value AND o.recorded_at >= $2)), 0)::bigint AS pull_requests_merged, COUNT(*) FILTER (WHERE f.status = 'failed' AND f.completed_at >= $2) AS failed FROM feedback f WHERE f.repository = ANY($1) GROUP BY f.repository) activity WHERE new_feedback + pull_requests_opened + pull_requests_merged + failed > 0",
+        )
+        .bind(repositories)
+        .bind(since)
+        .bind(FeedbackEvent::PULL_REQUEST_OPENED)
+        .bind(PromptMetric::PrMerged.as_str())
+        .fetch_all(pool)
+        .await
+        .context("Failed to summarize repository activity")?;
+
+        Ok(activity)
+    }
+
+    /// ❌ Feedback for these repositories that failed since `since` and hasn't
+    /// been retried, newest first
+    pub async fn list_failed_since(
+        pool: &PgPool,
+        repositories: &[String],
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback WHERE repository = ANY($1) AND status = 'failed' AND completed_at >= $2 ORDER BY completed_at DESC LIMIT $3",
+        )
+        .bind(repositories)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list failed feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// 🕒 Most recent feedback for a repository, newest first
+    pub async fn list_recent_for_repository(
+        pool: &PgPool,
+        repository: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback WHERE repository = $1 ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(repository)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list recent feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// 📬 Feedback for a repository that is still being worked on, newest first
+    pub async fn list_open_for_repository(
+        pool: &PgPool,
+        repository: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let feedback = sqlx::query_as::<_, Feedback>(
+            "SELECT * FROM feedback WHERE repository = $1 AND status NOT IN ('completed', 'failed') ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(repository)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list open feedback")?;
+
+        Ok(feedback)
+    }
+
+    /// 🎉 Pull requests opened for a repository's feedback that got merged, latest first
+    pub async fn list_merged_pull_requests(
+        pool: &PgPool,
+        repository: &str,
+        limit: i64,
+    ) -> Result<Vec<MergedPullRequest>> {
+        let merged = sqlx::query_as::<_, MergedPullRequest>(
+            "SELECT (e.payload->>'number')::bigint AS number, e.payload->>'url' AS url, e.payload->>'title' AS title, o.recorded_at AS merged_at FROM prompt_outcomes o JOIN feedback f ON f.id = o.feedback_id JOIN feedback_events e ON e.feedback_id = f.id AND e.event_type = $2 WHERE f.repository = $1 AND o.metric = $3 AND o.value ORDER BY o.recorded_at DESC, number DESC LIMIT $4",
+        )
+        .bind(repository)
+        .bind(FeedbackEvent::PULL_REQUEST_OPENED)
+        .bind(PromptMetric::PrMerged.as_str())
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list merged pull requests")?;
+
+        Ok(merged)
+    }
+
+    /// 🎉 How many of a repository's feedback items ended in a merged pull request
+    pub async fn count_merged(pool: &PgPool, repository: &str) -> Result<i64> {
+        let merged: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM prompt_outcomes o JOIN feedback f ON f.id = o.feedback_id WHERE f.repository = $1 AND o.metric = $2 AND o.value",
+        )
+        .bind(repository)
+        .bind(PromptMetric::PrMerged.as_str())
+        .fetch_one(pool)
+        .await
+        .context("Failed to count merged feedback")?;
+
+        Ok(merged)
+    }
+
+    /// 🔢 Add the tokens of one LLM call to a feedback item's usage
+    pub async fn add_token_usage(
+        pool: &PgPool,
+        id: Uuid,
+        prompt_tokens: i64,
+        completion_tokens: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE feedback SET prompt_tokens = prompt_tokens + $1, completion_tokens = completion_tokens + $2 WHERE id = $3",
+        )
+        .bind(prompt_tokens)
+        .bind(completion_tokens)
+        .bind(id)
+        .execute(pool)
+        .await
+        .context("Failed to record token usage")?;
+
+        Ok(())
+    }
+
+    /// 📊 Tokens spent on a feedback item so far
+    pub async fn tokens_used(pool: &PgPool, id: Uuid) -> Result<i64> {
+        let used: i64 = sqlx::query_scalar(
+            "SELECT prompt_tokens + completion_tokens FROM feedback WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to fetch feedback token usage")?;
+
+        Ok(used)
+    }
+
+    /// 🎫 Numbers of a repository's GitHub issues already imported as feedback
+    pub async fn imported_issue_numbers(pool: &PgPool, repository: &str) -> Result<HashSet<i64>> {
+        let numbers = sqlx::query_scalar::<_, i64>(
+            "SELECT (metadata->'source_issue'->>'number')::bigint FROM feedback WHERE repository = $1 AND metadata->'source_issue' IS NOT NULL",
+        )
+        .bind(repository)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch imported issues")?;
+
+        Ok(numbers.into_iter().collect())
+    }
+
+    /// 📊 How many feedback items a repository has
+    pub async fn count_for_repository(pool: &PgPool, repository: &str) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM feedback WHERE repository = $1")
+            .bind(repository)
+            .fetch_one(pool)
+            .await
+            .context("Failed to count feedback for repository")?;
+
+        Ok(count)
+    }
+
+    /// 🕐 When a repository's feedback last changed (None without feedback)
+    pub async fn last_activity_for_repository(
+        pool: &PgPool,
+        repository: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let last: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT MAX(updated_at) FROM feedback WHERE repository = $1")
+                .bind(repository)
+                .fetch_one(pool)
+                .await
+                .context("Failed to load last feedback activity")?;
+
+        Ok(last)
+    }
+
+    /// 📦 Every feedback item of a repository as export rows, oldest first,
+    /// streamed so large exports never sit in memory
+    pub fn export_rows<'a>(
+        pool: &'a PgPool,
+        repository: &'a str,
+    ) -> BoxStream<'a, Result<FeedbackExportRow, sqlx::Error>> {
+        sqlx::query_as::<_, FeedbackExportRow>(
+            "SELECT id, user_id, path, status, content, branch_name, pull_request_url, llm_provider, error_message, prompt_tokens, completion_tokens, created_at, updated_at, completed_at, EXTRACT(EPOCH FROM completed_at - created_at)::BIGINT AS processing_seconds FROM feedback WHERE repository = $1 ORDER BY created_at, id",
+        )
+        .bind(repository)
+        .fetch(pool)
+    }
+}
+
+// 📦 One feedback item as it appears in a project export
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FeedbackExportRow {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub path: Option<String>,
+    pub status: FeedbackStatus,
+    pub content: String,
+    pub branch_name: Option<String>,
+    pub pull_request_url: Option<String>,
+    pub llm_provider: Option<String>,
+    pub error_message: Option<String>,
+    /// 🔢 LLM tokens spent on it
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// ⏱️ Seconds from submission to completion
+    pub processing_seconds: Option<i64>,
+}
+
+// 🎉 A merged pull request Feedbacker opened
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MergedPullRequest {
+    pub number: i64,
+    pub url: String,
+    pub title: String,
+    /// ⏰ When the merge was reported
+    pub merged_at: DateTime<Utc>,
+}
+
+// 🔢 Feedback counts for one repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct FeedbackCounts {
+    pub repository: String,
+    pub total: i64,
+    /// 🔄 Not completed or failed yet
+    pub open: i64,
+    pub completed: i64,
+    pub failed: i64,
+}
+
+// 📬 What happened in one repository over a digest's period
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct RepositoryActivity {
+    pub repository: String,
+    pub new_feedback: i64,
+    pub pull_requests_opened: i64,
+    pub pull_requests_merged: i64,
+    /// ❌ Failed in the period, and not retried since
+    pub failed: i64,
 }
 
 // 📊 Feedback Statistics Structure
@@ -291,51 +1039,3789 @@ pub struct FeedbackStats {
     pub failed: u32,
 }
 
-impl User {
-    /// ➕ Create a new user
-    pub async fn create(
+// 🕰️ Feedback Event Model - One entry in a feedback's processing timeline
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeedbackEvent {
+    /// 🆔 Unique identifier for this event
+    pub id: Uuid,
+    /// 📝 Feedback this event belongs to
+    pub feedback_id: Uuid,
+    /// 🏷️ What happened (see `FeedbackEvent::*` constants)
+    pub event_type: String,
+    /// 📦 Event details (JSON)
+    pub payload: serde_json::Value,
+    /// ⏰ When it happened
+    pub created_at: DateTime<Utc>,
+    /// 🔢 Position in its feedback's timeline (1, 2, 3...)
+    pub version: i64,
+}
+
+// 📤 A feedback event waiting for the event stream, with its feedback's repository
+#[derive(Debug, Clone, FromRow)]
+pub struct UnstreamedEvent {
+    #[sqlx(flatten)]
+    pub event: FeedbackEvent,
+    pub repository: String,
+}
+
+impl FeedbackEvent {
+    /// 🔔 NOTIFY channel announcing new events (the payload is the event id)
+    pub const CHANNEL: &'static str = "feedback_events";
+
+    /// 🆕 The feedback was submitted
+    pub const CREATED: &'static str = "created";
+    /// 📸 The lifecycle as it stood when timelines became the record
+    pub const SNAPSHOT: &'static str = "lifecycle_snapshot";
+    /// 🔄 The feedback moved to another status
+    pub const STATUS_CHANGED: &'static str = "status_changed";
+    /// 🗺️ A multi-file change plan was produced
+    pub const PLAN_CREATED: &'static str = "plan_created";
+    /// 📄 A planned file edit was generated and validated
+    pub const FILE_GENERATED: &'static str = "file_generated";
+    /// ❌ A planned file edit failed generation or validation
+    pub const FILE_FAILED: &'static str = "file_failed";
+    /// 📦 Dependency update mode finished checking the registries
+    pub const DEPENDENCY_UPDATES_FOUND: &'static str = "dependency_updates_found";
+    /// 📚 Docs pass picked the files it will document
+    pub const DOCS_TARGETS_FOUND: &'static str = "docs_targets_found";
+    /// 🧪 Test generation picked the functions it will cover
+    pub const TEST_TARGETS_FOUND: &'static str = "test_targets_found";
+    /// 📦 Generated changes were run in the sandbox
+    pub const SANDBOX_RUN: &'static str = "sandbox_run";
+    /// 🎨 Formatters and linters ran over the generated files
+    pub const FILES_FORMATTED: &'static str = "files_formatted";
+    /// ⚖️ Generated files were checked against the project's licensing policy
+    pub const LICENSE_CHECKED: &'static str = "license_checked";
+    /// 🐙 A pull request was opened for this feedback
+    pub const PULL_REQUEST_OPENED: &'static str = "pull_request_opened";
+    /// 🔀 The pull request was rebased onto the latest base branch
+    pub const PULL_REQUEST_REBASED: &'static str = "pull_request_rebased";
+    /// 🟠 The pull request fell behind or conflicts and was left for a maintainer
+    pub const PULL_REQUEST_STALE: &'static str = "pull_request_stale";
+    /// 🚫 The result was rejected and one of its pull requests was closed
+    pub const PULL_REQUEST_CLOSED: &'static str = "pull_request_closed";
+    /// 💸 The pipeline stopped at a token budget
+    pub const BUDGET_EXCEEDED: &'static str = "budget_exceeded";
+    /// 👍 Someone approved spending more tokens on the feedback
+    pub const BUDGET_APPROVED: &'static str = "budget_approved";
+
+    /// ➕ Append an event to a feedback timeline
+    pub async fn record(
         pool: &PgPool,
-        email: String,
-        name: String,
-        password_hash: String,
+        feedback_id: Uuid,
+        event_type: &str,
+        payload: serde_json::Value,
     ) -> Result<Self> {
-        let id = Uuid::new_v4();
-        let now = Utc::now();
+        let mut connection = pool
+            .acquire()
+            .await
+            .context("Failed to get a database connection")?;
+        Self::record_in(&mut connection, feedback_id, event_type, payload).await
+    }
 
-        // TODO: Implement proper query when database is set up
-        let user = User {
-            id,
-            email,
-            name,
-            github_username: None,
-            password_hash,
-            email_verified: false,
-            role: UserRole::User,
-            is_active: true,
-            created_at: now,
-            updated_at: now,
-            last_login_at: None,
-        };
+    /// ➕ Append an event on a connection (inside a transaction, the
+    /// announcement goes out when it commits). Taking the next version locks
+    /// the feedback row, so appends to one timeline never interleave
+    pub async fn record_in(
+        connection: &mut PgConnection,
+        feedback_id: Uuid,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<Self> {
+        let event = sqlx::query_as::<_, FeedbackEvent>(
+            "WITH next AS (UPDATE feedback SET event_version = event_version + 1 WHERE id = $1 RETURNING event_version) INSERT INTO feedback_events (feedback_id, event_type, payload, version) SELECT $1, $2, $3, event_version FROM next RETURNING *",
+        )
+        .bind(feedback_id)
+        .bind(event_type)
+        .bind(payload)
+        .fetch_one(&mut *connection)
+        .await
+        .context("Failed to record feedback event")?;
 
-        Ok(user)
+        // 📡 Tell live subscribers on every instance
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(Self::CHANNEL)
+            .bind(event.id.to_string())
+            .execute(&mut *connection)
+            .await
+            .context("Failed to announce feedback event")?;
+
+        Ok(event)
     }
 
-    /// 🔍 Find user by email
-    pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<Self>> {
-        // TODO: Implement proper query when database is set up
-        let user: Option<User> = None;
+    /// ⏰ When a pull request was last opened in a repository (None if never)
+    pub async fn last_pull_request_at(
+        pool: &PgPool,
+        repository: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let opened_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT MAX(e.created_at) FROM feedback_events e JOIN feedback f ON f.id = e.feedback_id WHERE e.event_type = $1 AND LOWER(f.repository) = LOWER($2)",
+        )
+        .bind(Self::PULL_REQUEST_OPENED)
+        .bind(repository)
+        .fetch_one(pool)
+        .await
+        .context("Failed to find the latest pull request")?;
 
-        Ok(user)
+        Ok(opened_at)
+    }
+
+    /// 🔍 Find an event by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let event =
+            sqlx::query_as::<_, FeedbackEvent>("SELECT * FROM feedback_events WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch feedback event")?;
+
+        Ok(event)
+    }
+
+    /// 📋 Timeline for a feedback item, oldest first
+    pub async fn list_for_feedback(pool: &PgPool, feedback_id: Uuid) -> Result<Vec<Self>> {
+        let mut connection = pool
+            .acquire()
+            .await
+            .context("Failed to get a database connection")?;
+        Self::list_for_feedback_in(&mut connection, feedback_id).await
+    }
+
+    /// 📋 Timeline for a feedback item on a connection, oldest first
+    pub async fn list_for_feedback_in(
+        connection: &mut PgConnection,
+        feedback_id: Uuid,
+    ) -> Result<Vec<Self>> {
+        let events = sqlx::query_as::<_, FeedbackEvent>(
+            "SELECT * FROM feedback_events WHERE feedback_id = $1 ORDER BY version",
+        )
+        .bind(feedback_id)
+        .fetch_all(&mut *connection)
+        .await
+        .context("Failed to fetch feedback events")?;
+
+        Ok(events)
+    }
+
+    /// 📤 The oldest events not yet published to the event stream
+    pub async fn unstreamed(
+        connection: &mut PgConnection,
+        limit: i64,
+    ) -> Result<Vec<UnstreamedEvent>> {
+        let events = sqlx::query_as::<_, UnstreamedEvent>(
+            "SELECT e.id, e.feedback_id, e.event_type, e.payload, e.created_at, e.version, f.repository FROM feedback_events e JOIN feedback f ON f.id = e.feedback_id WHERE NOT e.streamed ORDER BY e.created_at, e.id LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&mut *connection)
+        .await
+        .context("Failed to fetch unpublished feedback events")?;
+
+        Ok(events)
+    }
+
+    /// ✅ Mark events as published to the event stream
+    pub async fn mark_streamed(connection: &mut PgConnection, ids: &[Uuid]) -> Result<()> {
+        sqlx::query("UPDATE feedback_events SET streamed = TRUE WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&mut *connection)
+            .await
+            .context("Failed to mark feedback events as published")?;
+        Ok(())
     }
 }
 
-impl Project {
-    /// ➕ Create a new project
+/// 📦 The API's view of an event (what GET /api/feedback/:id/events returns)
+impl From<FeedbackEvent> for feedbacker_types::FeedbackEvent {
+    fn from(event: FeedbackEvent) -> Self {
+        Self {
+            id: event.id,
+            feedback_id: event.feedback_id,
+            event_type: event.event_type,
+            payload: event.payload,
+            created_at: event.created_at,
+        }
+    }
+}
+
+// 💬 Feedback Comment Model - An internal note maintainers leave on feedback
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeedbackComment {
+    /// 🆔 Unique identifier for this comment
+    pub id: Uuid,
+    /// 📝 Feedback the comment is on
+    pub feedback_id: Uuid,
+    /// 👤 Who wrote it (None once their account is deleted)
+    pub author_id: Option<Uuid>,
+    /// 👤 Their display name at the time it's read
+    pub author_name: Option<String>,
+    /// 💬 The note itself
+    pub body: String,
+    /// ⏰ When it was written
+    pub created_at: DateTime<Utc>,
+}
+
+impl FeedbackComment {
+    /// ➕ Leave a comment on a feedback item
     pub async fn create(
         pool: &PgPool,
-        owner_id: Uuid,
-        repository: String,
-        description: Option<String>,
+        feedback_id: Uuid,
+        author_id: Uuid,
+        body: &str,
+    ) -> Result<Self> {
+        let comment = sqlx::query_as::<_, FeedbackComment>(
+            "WITH inserted AS (INSERT INTO feedback_comments (feedback_id, author_id, body) VALUES ($1, $2, $3) RETURNING *) SELECT c.*, u.name AS author_name FROM inserted c LEFT JOIN users u ON u.id = c.author_id",
+        )
+        .bind(feedback_id)
+        .bind(author_id)
+        .bind(body)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create feedback comment")?;
+
+        Ok(comment)
+    }
+
+    /// 📋 A feedback item's comments, oldest first
+    pub async fn list_for_feedback(pool: &PgPool, feedback_id: Uuid) -> Result<Vec<Self>> {
+        let comments = sqlx::query_as::<_, FeedbackComment>(
+            "SELECT c.*, u.name AS author_name FROM feedback_comments c LEFT JOIN users u ON u.id = c.author_id WHERE c.feedback_id = $1 ORDER BY c.created_at, c.id",
+        )
+        .bind(feedback_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list feedback comments")?;
+
+        Ok(comments)
+    }
+
+    /// 🔍 One comment on a feedback item
+    pub async fn find(pool: &PgPool, feedback_id: Uuid, id: Uuid) -> Result<Option<Self>> {
+        let comment = sqlx::query_as::<_, FeedbackComment>(
+            "SELECT c.*, u.name AS author_name FROM feedback_comments c LEFT JOIN users u ON u.id = c.author_id WHERE c.feedback_id = $1 AND c.id = $2",
+        )
+        .bind(feedback_id)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch feedback comment")?;
+
+        Ok(comment)
+    }
+
+    /// 🗑️ Delete a comment
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM feedback_comments WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("Failed to delete feedback comment")?;
+
+        Ok(())
+    }
+}
+
+/// 📦 The API's view of a comment (what GET /api/feedback/:id/comments returns)
+impl From<FeedbackComment> for feedbacker_types::FeedbackComment {
+    fn from(comment: FeedbackComment) -> Self {
+        Self {
+            id: comment.id,
+            feedback_id: comment.feedback_id,
+            author_id: comment.author_id,
+            author_name: comment.author_name,
+            body: comment.body,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+// 🩺 Repository Scan Model - One scheduled health analysis of a project
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RepositoryScan {
+    /// 🆔 Unique identifier for this scan
+    pub id: Uuid,
+    /// 🏠 Project that was scanned
+    pub project_id: Uuid,
+    /// 📋 running, completed, or failed (see `RepositoryScan::*` constants)
+    pub status: String,
+    /// 🔍 Findings reported by the health checks (JSON array)
+    pub findings: serde_json::Value,
+    /// 📝 Suggested feedback filed for the findings
+    pub feedback_id: Option<Uuid>,
+    /// 🐙 Issue filed for the findings
+    pub issue_url: Option<String>,
+    /// ❌ Why the scan failed
+    pub error_message: Option<String>,
+    /// ⏰ When the scan started
+    pub started_at: DateTime<Utc>,
+    /// ✅ When the scan finished
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl RepositoryScan {
+    /// 🏃 Scan in progress
+    pub const RUNNING: &'static str = "running";
+    /// ✅ Scan finished (with or without findings)
+    pub const COMPLETED: &'static str = "completed";
+    /// ❌ Scan could not run
+    pub const FAILED: &'static str = "failed";
+
+    /// ➕ Record the start of a scan
+    pub async fn start(pool: &PgPool, project_id: Uuid) -> Result<Self> {
+        let scan = sqlx::query_as::<_, RepositoryScan>(
+            "INSERT INTO repository_scans (project_id) VALUES ($1) RETURNING *",
+        )
+        .bind(project_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record repository scan")?;
+
+        Ok(scan)
+    }
+
+    /// ✅ Store the findings and wherever they were filed
+    pub async fn complete(
+        &mut self,
+        pool: &PgPool,
+        findings: serde_json::Value,
+        feedback_id: Option<Uuid>,
+        issue_url: Option<String>,
+    ) -> Result<()> {
+        *self = sqlx::query_as::<_, RepositoryScan>(
+            "UPDATE repository_scans SET status = $1, findings = $2, feedback_id = $3, issue_url = $4, completed_at = NOW() WHERE id = $5 RETURNING *",
+        )
+        .bind(Self::COMPLETED)
+        .bind(findings)
+        .bind(feedback_id)
+        .bind(issue_url)
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to complete repository scan")?;
+
+        Ok(())
+    }
+
+    /// ❌ Mark the scan as failed
+    pub async fn fail(&mut self, pool: &PgPool, error_message: &str) -> Result<()> {
+        *self = sqlx::query_as::<_, RepositoryScan>(
+            "UPDATE repository_scans SET status = $1, error_message = $2, completed_at = NOW() WHERE id = $3 RETURNING *",
+        )
+        .bind(Self::FAILED)
+        .bind(error_message)
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to mark repository scan as failed")?;
+
+        Ok(())
+    }
+
+    /// 🔍 Most recent scan of a project (any status)
+    pub async fn latest_for_project(pool: &PgPool, project_id: Uuid) -> Result<Option<Self>> {
+        let scan = sqlx::query_as::<_, RepositoryScan>(
+            "SELECT * FROM repository_scans WHERE project_id = $1 ORDER BY started_at DESC LIMIT 1",
+        )
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch latest repository scan")?;
+
+        Ok(scan)
+    }
+}
+
+// 📦 Project Export Model - A feedback export written in the background
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectExport {
+    /// 🆔 Unique identifier for this export
+    pub id: Uuid,
+    /// 🏠 Project whose feedback is exported
+    pub project_id: Uuid,
+    /// 👤 Who asked for it
+    pub requested_by: Option<Uuid>,
+    /// 📄 csv or jsonl
+    pub format: String,
+    /// 📋 pending, completed, or failed (see `ProjectExport::*` constants)
+    pub status: String,
+    /// 📊 Feedback items written
+    pub row_count: Option<i64>,
+    /// 📂 Where the file was written
+    #[serde(skip_serializing)]
+    pub file_path: Option<String>,
+    /// ❌ Why the export failed
+    pub error_message: Option<String>,
+    /// ⏰ When it was requested
+    pub created_at: DateTime<Utc>,
+    /// ✅ When the file was written
+    pub completed_at: Option<DateTime<Utc>>,
+    /// ⏳ When the file (and its download links) go away
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectExport {
+    /// ⏳ Queued, or being written
+    pub const PENDING: &'static str = "pending";
+    /// ✅ File written, ready to download
+    pub const COMPLETED: &'static str = "completed";
+    /// ❌ Could not be written
+    pub const FAILED: &'static str = "failed";
+
+    /// ➕ Record a requested export
+    pub async fn create(
+        pool: &PgPool,
+        project_id: Uuid,
+        requested_by: Uuid,
+        format: &str,
+    ) -> Result<Self> {
+        let export = sqlx::query_as::<_, ProjectExport>(
+            "INSERT INTO project_exports (project_id, requested_by, format) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(project_id)
+        .bind(requested_by)
+        .bind(format)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record project export")?;
+
+        Ok(export)
+    }
+
+    /// 🔍 Find an export by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let export =
+            sqlx::query_as::<_, ProjectExport>("SELECT * FROM project_exports WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch project export")?;
+
+        Ok(export)
+    }
+
+    /// ✅ Store where the file went and until when it is kept
+    pub async fn complete(
+        &mut self,
+        pool: &PgPool,
+        row_count: i64,
+        file_path: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        *self = sqlx::query_as::<_, ProjectExport>(
+            "UPDATE project_exports SET status = $1, row_count = $2, file_path = $3, expires_at = $4, completed_at = NOW() WHERE id = $5 RETURNING *",
+        )
+        .bind(Self::COMPLETED)
+        .bind(row_count)
+        .bind(file_path)
+        .bind(expires_at)
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to complete project export")?;
+
+        Ok(())
+    }
+
+    /// ❌ Mark the export as failed (the record is kept until `expires_at`)
+    pub async fn fail(
+        &mut self,
+        pool: &PgPool,
+        error_message: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        *self = sqlx::query_as::<_, ProjectExport>(
+            "UPDATE project_exports SET status = $1, error_message = $2, expires_at = $3, completed_at = NOW() WHERE id = $4 RETURNING *",
+        )
+        .bind(Self::FAILED)
+        .bind(error_message)
+        .bind(expires_at)
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to mark project export as failed")?;
+
+        Ok(())
+    }
+
+    /// 🧹 Delete expired exports, returning them so their files can go too
+    pub async fn delete_expired(pool: &PgPool) -> Result<Vec<Self>> {
+        let expired = sqlx::query_as::<_, ProjectExport>(
+            "DELETE FROM project_exports WHERE expires_at < NOW() RETURNING *",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to delete expired project exports")?;
+
+        Ok(expired)
+    }
+}
+
+// 🧵 Feedback Span Model - How long one stage of a feedback item took
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeedbackSpan {
+    /// 🆔 Unique identifier for this span
+    pub id: Uuid,
+    /// 📝 Feedback the stage worked on
+    pub feedback_id: Uuid,
+    /// 🏷️ Which stage (see `crate::feedback_trace::Stage`)
+    pub stage: String,
+    /// ⏰ When the stage started
+    pub started_at: DateTime<Utc>,
+    /// ⏱️ How long it ran
+    pub duration_ms: i64,
+    /// ❌ The stage ended in an error
+    pub failed: bool,
+    /// ⏰ When it was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// ➕ A finished stage waiting to be stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewFeedbackSpan {
+    pub feedback_id: Uuid,
+    pub stage: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub failed: bool,
+}
+
+impl FeedbackSpan {
+    /// ➕ Store finished stages in one statement (those of deleted feedback are skipped)
+    pub async fn record_many(pool: &PgPool, spans: &[NewFeedbackSpan]) -> Result<u64> {
+        let recorded = sqlx::query(
+            "INSERT INTO feedback_spans (feedback_id, stage, started_at, duration_ms, failed) \
+             SELECT s.feedback_id, s.stage, s.started_at, s.duration_ms, s.failed \
+             FROM UNNEST($1::uuid[], $2::text[], $3::timestamptz[], $4::bigint[], $5::bool[]) \
+                 AS s(feedback_id, stage, started_at, duration_ms, failed) \
+             WHERE EXISTS (SELECT 1 FROM feedback WHERE feedback.id = s.feedback_id)",
+        )
+        .bind(spans.iter().map(|span| span.feedback_id).collect::<Vec<_>>())
+        .bind(spans.iter().map(|span| span.stage.clone()).collect::<Vec<_>>())
+        .bind(spans.iter().map(|span| span.started_at).collect::<Vec<_>>())
+        .bind(spans.iter().map(|span| span.duration_ms).collect::<Vec<_>>())
+        .bind(spans.iter().map(|span| span.failed).collect::<Vec<_>>())
+        .execute(pool)
+        .await
+        .context("Failed to record feedback spans")?
+        .rows_affected();
+
+        Ok(recorded)
+    }
+
+    /// 📋 Stages of a feedback item, in the order they started
+    pub async fn list_for_feedback(pool: &PgPool, feedback_id: Uuid) -> Result<Vec<Self>> {
+        let spans = sqlx::query_as::<_, FeedbackSpan>(
+            "SELECT * FROM feedback_spans WHERE feedback_id = $1 ORDER BY started_at, id",
+        )
+        .bind(feedback_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch feedback spans")?;
+
+        Ok(spans)
+    }
+}
+
+// 💾 Pipeline Checkpoint Model - What an unfinished run already paid for
+// (see crate::pipeline::checkpoint for what goes in it)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PipelineCheckpoint {
+    /// 📝 Feedback item the run is for
+    pub feedback_id: Uuid,
+    /// 🧭 Commit the run's context was gathered from
+    pub base_commit: String,
+    /// 🗺️ Change plan, once there is one
+    pub plan: Option<serde_json::Value>,
+    /// 📄 Files generated so far
+    pub generated: serde_json::Value,
+    /// ⏰ When it was last saved
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PipelineCheckpoint {
+    /// 🔍 The checkpoint of a feedback item, if a run left one
+    pub async fn find(pool: &PgPool, feedback_id: Uuid) -> Result<Option<Self>> {
+        let checkpoint = sqlx::query_as::<_, PipelineCheckpoint>(
+            "SELECT * FROM pipeline_checkpoints WHERE feedback_id = $1",
+        )
+        .bind(feedback_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch pipeline checkpoint")?;
+
+        Ok(checkpoint)
+    }
+
+    /// 💾 Save (or replace) a feedback item's checkpoint
+    pub async fn save(
+        pool: &PgPool,
+        feedback_id: Uuid,
+        base_commit: &str,
+        plan: Option<&serde_json::Value>,
+        generated: &serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO pipeline_checkpoints (feedback_id, base_commit, plan, generated) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (feedback_id) DO UPDATE SET base_commit = $2, plan = $3, generated = $4, updated_at = NOW()",
+        )
+        .bind(feedback_id)
+        .bind(base_commit)
+        .bind(plan)
+        .bind(generated)
+        .execute(pool)
+        .await
+        .context("Failed to save pipeline checkpoint")?;
+        Ok(())
+    }
+
+    /// 🗑️ Forget a feedback item's checkpoint
+    pub async fn delete(pool: &PgPool, feedback_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM pipeline_checkpoints WHERE feedback_id = $1")
+            .bind(feedback_id)
+            .execute(pool)
+            .await
+            .context("Failed to delete pipeline checkpoint")?;
+        Ok(())
+    }
+
+    /// 🧹 Delete checkpoints not saved since `before`, returning how many went
+    pub async fn delete_stale(pool: &PgPool, before: DateTime<Utc>) -> Result<u64> {
+        let deleted = sqlx::query("DELETE FROM pipeline_checkpoints WHERE updated_at < $1")
+            .bind(before)
+            .execute(pool)
+            .await
+            .context("Failed to delete stale pipeline checkpoints")?
+            .rows_affected();
+        Ok(deleted)
+    }
+}
+
+// 📜 LLM Exchange Model - A stored (redacted) prompt and response, for debugging
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LlmExchange {
+    /// 🆔 Unique identifier for this exchange
+    pub id: Uuid,
+    /// 📝 Feedback the call was made for
+    pub feedback_id: Option<Uuid>,
+    /// 🏠 Project the call was made for
+    pub project_id: Option<Uuid>,
+    /// 🏷️ Pipeline stage (prompt template name)
+    pub stage: Option<String>,
+    /// 🤖 Provider that answered (None when every provider failed)
+    pub provider: Option<String>,
+    /// 🤖 Model that answered
+    pub model: Option<String>,
+    /// 📤 Redacted request: system message, conversation, and settings
+    pub request: serde_json::Value,
+    /// 📥 Redacted response text
+    pub response: Option<String>,
+    /// ❌ Why the call failed
+    pub error_message: Option<String>,
+    /// 📊 Prompt tokens reported by the provider
+    pub prompt_tokens: i32,
+    /// 📊 Completion tokens reported by the provider
+    pub completion_tokens: i32,
+    /// ⏱️ Wall-clock duration of the call, fallbacks included
+    pub duration_ms: i64,
+    /// ⏰ When the call was made
+    pub created_at: DateTime<Utc>,
+}
+
+/// 📋 Exchange listing row: `LlmExchange` without the bulky request and response
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LlmExchangeSummary {
+    pub id: Uuid,
+    pub feedback_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub stage: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub error_message: Option<String>,
+    pub prompt_tokens: i32,
+    pub completion_tokens: i32,
+    pub duration_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// ➕ A new exchange to store (already redacted); fields mirror `LlmExchange`
+#[derive(Debug, Clone, Default)]
+pub struct NewLlmExchange {
+    pub feedback_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub stage: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub request: serde_json::Value,
+    pub response: Option<String>,
+    pub error_message: Option<String>,
+    pub prompt_tokens: i32,
+    pub completion_tokens: i32,
+    pub duration_ms: i64,
+}
+
+/// 🔍 Filters for browsing exchanges (all optional, combined with AND)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LlmExchangeFilter {
+    pub feedback_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub stage: Option<String>,
+}
+
+impl LlmExchange {
+    /// 🔀 Columns listings can be sorted by
+    pub const SORTABLE: &'static [&'static str] = &[
+        "created_at",
+        "stage",
+        "provider",
+        "model",
+        "prompt_tokens",
+        "completion_tokens",
+        "duration_ms",
+    ];
+
+    /// ➕ Store an exchange
+    pub async fn record(pool: &PgPool, exchange: &NewLlmExchange) -> Result<Self> {
+        let stored = sqlx::query_as::<_, LlmExchange>(
+            "INSERT INTO llm_exchanges (feedback_id, project_id, stage, provider, model, request, response, error_message, prompt_tokens, completion_tokens, duration_ms) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING *",
+        )
+        .bind(exchange.feedback_id)
+        .bind(exchange.project_id)
+        .bind(&exchange.stage)
+        .bind(&exchange.provider)
+        .bind(&exchange.model)
+        .bind(&exchange.request)
+        .bind(&exchange.response)
+        .bind(&exchange.error_message)
+        .bind(exchange.prompt_tokens)
+        .bind(exchange.completion_tokens)
+        .bind(exchange.duration_ms)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record LLM exchange")?;
+
+        Ok(stored)
+    }
+
+    /// 🔍 Find an exchange by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let exchange =
+            sqlx::query_as::<_, LlmExchange>("SELECT * FROM llm_exchanges WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch LLM exchange")?;
+
+        Ok(exchange)
+    }
+
+    /// 📋 Exchanges matching the filter in `order_by` (ORDER BY terms over
+    /// SORTABLE columns), plus the total match count
+    pub async fn list(
+        pool: &PgPool,
+        filter: &LlmExchangeFilter,
+        order_by: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<LlmExchangeSummary>, u64)> {
+        const MATCHES: &str = "($1::uuid IS NULL OR feedback_id = $1) AND ($2::uuid IS NULL OR project_id = $2) AND ($3::text IS NULL OR stage = $3)";
+
+        let items = sqlx::query_as::<_, LlmExchangeSummary>(&format!(
+            "SELECT id, feedback_id, project_id, stage, provider, model, error_message, prompt_tokens, completion_tokens, duration_ms, created_at FROM llm_exchanges WHERE {} ORDER BY {}, id LIMIT $4 OFFSET $5",
+            MATCHES, order_by
+        ))
+        .bind(filter.feedback_id)
+        .bind(filter.project_id)
+        .bind(&filter.stage)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list LLM exchanges")?;
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM llm_exchanges WHERE {}",
+            MATCHES
+        ))
+        .bind(filter.feedback_id)
+        .bind(filter.project_id)
+        .bind(&filter.stage)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count LLM exchanges")?;
+
+        Ok((items, total as u64))
+    }
+}
+
+// 📜 Prompt Version Model - A stored template for one pipeline stage
+// The active version replaces the built-in template; candidates get a share of traffic
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromptVersion {
+    /// 🆔 Unique identifier for this version
+    pub id: Uuid,
+    /// 🏷️ Pipeline stage (prompt template name)
+    pub name: String,
+    /// 🔢 Version number within the stage (the built-in template is version 0)
+    pub version: i32,
+    /// 📄 Template body with `{{variable}}` placeholders
+    pub body: String,
+    /// 📝 What changed compared to earlier versions
+    pub description: Option<String>,
+    /// 🎲 Percent of feedback items served this version while it is a candidate
+    pub traffic_percent: i32,
+    /// ✅ Serves all remaining traffic for the stage
+    pub is_active: bool,
+    /// ⏰ When the version was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptVersion {
+    /// ➕ Store a new candidate version, numbered after the stage's latest one
+    pub async fn create(
+        pool: &PgPool,
+        name: &str,
+        body: &str,
+        description: Option<&str>,
+        traffic_percent: i32,
+    ) -> Result<Self> {
+        let version = sqlx::query_as::<_, PromptVersion>(
+            "INSERT INTO prompt_versions (name, version, body, description, traffic_percent) VALUES ($1, (SELECT COALESCE(MAX(version), 0) + 1 FROM prompt_versions WHERE name = $1), $2, $3, $4) RETURNING *",
+        )
+        .bind(name)
+        .bind(body)
+        .bind(description)
+        .bind(traffic_percent)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create prompt version")?;
+
+        Ok(version)
+    }
+
+    /// 🔍 Find one version of a stage's prompt
+    pub async fn find(pool: &PgPool, name: &str, version: i32) -> Result<Option<Self>> {
+        let found = sqlx::query_as::<_, PromptVersion>(
+            "SELECT * FROM prompt_versions WHERE name = $1 AND version = $2",
+        )
+        .bind(name)
+        .bind(version)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch prompt version")?;
+
+        Ok(found)
+    }
+
+    /// 📋 Versions of one stage (or every stage), oldest first
+    pub async fn list(pool: &PgPool, name: Option<&str>) -> Result<Vec<Self>> {
+        let versions = sqlx::query_as::<_, PromptVersion>(
+            "SELECT * FROM prompt_versions WHERE ($1::text IS NULL OR name = $1) ORDER BY name, version",
+        )
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list prompt versions")?;
+
+        Ok(versions)
+    }
+
+    /// 🎲 Change the share of traffic a candidate gets
+    pub async fn set_traffic(pool: &PgPool, name: &str, version: i32, percent: i32) -> Result<()> {
+        sqlx::query("UPDATE prompt_versions SET traffic_percent = $3 WHERE name = $1 AND version = $2")
+            .bind(name)
+            .bind(version)
+            .bind(percent)
+            .execute(pool)
+            .await
+            .context("Failed to update prompt traffic")?;
+        Ok(())
+    }
+
+    /// ✅ Make a version the stage's default; version 0 goes back to the built-in template
+    /// The promoted version stops being a candidate, so its traffic share is cleared
+    pub async fn activate(pool: &PgPool, name: &str, version: i32) -> Result<()> {
+        sqlx::query(
+            "UPDATE prompt_versions SET is_active = (version = $2), traffic_percent = CASE WHEN version = $2 THEN 0 ELSE traffic_percent END WHERE name = $1",
+        )
+        .bind(name)
+        .bind(version)
+        .execute(pool)
+        .await
+        .context("Failed to activate prompt version")?;
+        Ok(())
+    }
+}
+
+/// 🎲 Which prompt version a feedback item was served for one stage
+pub struct PromptAssignment;
+
+impl PromptAssignment {
+    /// 💾 Remember the version served (a re-run replaces the earlier assignment)
+    pub async fn record(pool: &PgPool, feedback_id: Uuid, name: &str, version: i32) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO prompt_assignments (feedback_id, name, version) VALUES ($1, $2, $3) ON CONFLICT (feedback_id, name) DO UPDATE SET version = EXCLUDED.version, created_at = NOW()",
+        )
+        .bind(feedback_id)
+        .bind(name)
+        .bind(version)
+        .execute(pool)
+        .await
+        .context("Failed to record prompt assignment")?;
+        Ok(())
+    }
+}
+
+/// 📊 Outcomes prompt versions are judged by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptMetric {
+    /// 🐙 The pull request was merged (false: closed without merging)
+    PrMerged,
+    /// ✅ CI passed on the pull request
+    CiPassed,
+    /// 👍 The submitter approved the result
+    UserApproved,
+}
+
+impl PromptMetric {
+    /// 🏷️ Stored metric name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptMetric::PrMerged => "pr_merged",
+            PromptMetric::CiPassed => "ci_passed",
+            PromptMetric::UserApproved => "user_approved",
+        }
+    }
+}
+
+/// 📊 One outcome of a feedback item, credited to every prompt version it was served
+pub struct PromptOutcome;
+
+impl PromptOutcome {
+    /// 💾 Record an outcome (the latest value wins)
+    pub async fn record(
+        pool: &PgPool,
+        feedback_id: Uuid,
+        metric: PromptMetric,
+        value: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO prompt_outcomes (feedback_id, metric, value) VALUES ($1, $2, $3) ON CONFLICT (feedback_id, metric) DO UPDATE SET value = EXCLUDED.value, recorded_at = NOW()",
+        )
+        .bind(feedback_id)
+        .bind(metric.as_str())
+        .bind(value)
+        .execute(pool)
+        .await
+        .context("Failed to record prompt outcome")?;
+        Ok(())
+    }
+}
+
+/// 📈 Outcome rates of one prompt version (rates are None until a sample exists)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromptVersionStats {
+    pub name: String,
+    pub version: i32,
+    pub runs: i64,
+    pub merge_samples: i64,
+    pub merge_rate: Option<f64>,
+    pub ci_samples: i64,
+    pub ci_pass_rate: Option<f64>,
+    pub approval_samples: i64,
+    pub approval_rate: Option<f64>,
+}
+
+impl PromptVersionStats {
+    /// 📈 Per-version outcome rates for one stage
+    pub async fn for_stage(pool: &PgPool, name: &str) -> Result<Vec<Self>> {
+        let stats = sqlx::query_as::<_, PromptVersionStats>(
+            r#"
+            SELECT a.name, a.version,
+                COUNT(DISTINCT a.feedback_id) AS runs,
+                COUNT(*) FILTER (WHERE o.metric = 'pr_merged') AS merge_samples,
+                (AVG(o.value::int) FILTER (WHERE o.metric = 'pr_merged'))::float8 AS merge_rate,
+                COUNT(*) FILTER (WHERE o.metric = 'ci_passed') AS ci_samples,
+                (AVG(o.value::int) FILTER (WHERE o.metric = 'ci_passed'))::float8 AS ci_pass_rate,
+                COUNT(*) FILTER (WHERE o.metric = 'user_approved') AS approval_samples,
+                (AVG(o.value::int) FILTER (WHERE o.metric = 'user_approved'))::float8 AS approval_rate
+            FROM prompt_assignments a
+            LEFT JOIN prompt_outcomes o ON o.feedback_id = a.feedback_id
+            WHERE a.name = $1
+            GROUP BY a.name, a.version
+            ORDER BY a.version
+            "#,
+        )
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .context("Failed to compute prompt version stats")?;
+
+        Ok(stats)
+    }
+}
+
+// 🔄 Background Job Model - One unit of queued work
+// Jobs are claimed by lane (interactive before normal before bulk), then from the
+// user with the fewest jobs running, then oldest first. Enqueueing a job sends a
+// NOTIFY on `BackgroundJob::CHANNEL` so idle workers wake up right away. The claiming instance
+// heartbeats the job while it runs; a job whose heartbeat goes stale (its worker
+// died) is reaped and counts as a failed attempt. Failed attempts are retried
+// with exponential backoff; a job that runs out of retries is dead-lettered and
+// waits for an operator to requeue or purge it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackgroundJob {
+    /// 🆔 Unique identifier for this job
+    pub id: Uuid,
+    /// 🏷️ What kind of work this is (decides which handler runs it)
+    pub job_type: String,
+    /// 📦 Handler input
+    pub payload: serde_json::Value,
+    /// 📋 pending, running, completed, or dead (see `BackgroundJob::*` constants)
+    pub status: String,
+    /// 🚦 Lane value (see `JobPriority`); higher is claimed first
+    pub priority: i16,
+    /// 👤 User the work is for (None for system work like scheduled scans)
+    pub user_id: Option<Uuid>,
+    /// 🔢 Failed attempts so far
+    pub retries: i32,
+    /// 🔢 Failed attempts before the job is dead-lettered
+    pub max_retries: i32,
+    /// ❌ Error of the latest failed attempt
+    pub error_message: Option<String>,
+    /// 📜 Every failed attempt: `[{attempt, error, failed_at}]`
+    pub error_history: serde_json::Value,
+    /// ⏰ Earliest time the job may run
+    pub scheduled_at: DateTime<Utc>,
+    /// 🏃 When the latest attempt started
+    pub started_at: Option<DateTime<Utc>>,
+    /// ✅ When the job finished
+    pub completed_at: Option<DateTime<Utc>>,
+    /// 👷 Instance running the job
+    pub worker_id: Option<String>,
+    /// 💓 Last heartbeat from that instance
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    /// ☠️ When the job ran out of retries
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+    /// 📅 When the job was enqueued
+    pub created_at: DateTime<Utc>,
+}
+
+/// 🚦 Scheduling lane of a job
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    /// 📦 Batch and scheduled work (repository scans, bulk runs)
+    Bulk,
+    /// 🔄 Everything else
+    Normal,
+    /// 👀 Someone is watching the tracking page
+    Interactive,
+}
+
+impl JobPriority {
+    /// 🔢 Value stored in the priority column
+    pub fn value(self) -> i16 {
+        match self {
+            JobPriority::Bulk => 0,
+            JobPriority::Normal => 50,
+            JobPriority::Interactive => 100,
+        }
+    }
+
+    /// 🏷️ Lane name used in API output and queue keys
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobPriority::Bulk => "bulk",
+            JobPriority::Normal => "normal",
+            JobPriority::Interactive => "interactive",
+        }
+    }
+
+    /// 🔢 Lane of a stored priority value
+    pub fn from_value(value: i16) -> Self {
+        match value {
+            i16::MIN..=49 => JobPriority::Bulk,
+            50..=99 => JobPriority::Normal,
+            _ => JobPriority::Interactive,
+        }
+    }
+}
+
+/// ➕ A job to enqueue
+#[derive(Debug, Clone)]
+pub struct NewBackgroundJob {
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub max_retries: i32,
+    pub priority: JobPriority,
+    pub user_id: Option<Uuid>,
+}
+
+impl NewBackgroundJob {
+    /// ➕ Normal-priority system job with the default 3 retries
+    pub fn new(job_type: &str, payload: serde_json::Value) -> Self {
+        Self {
+            job_type: job_type.to_string(),
+            payload,
+            max_retries: 3,
+            priority: JobPriority::Normal,
+            user_id: None,
+        }
+    }
+
+    /// 🚦 Run in this lane
+    pub fn with_priority(self, priority: JobPriority) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// 👤 Count towards this user's fair share
+    pub fn with_user(self, user_id: Option<Uuid>) -> Self {
+        Self { user_id, ..self }
+    }
+
+    /// 🔢 Dead-letter after this many failed attempts
+    pub fn with_max_retries(self, max_retries: i32) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+}
+
+/// 📮 Unfinished jobs of one type, by state
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct JobQueueDepth {
+    pub job_type: String,
+    /// ⏳ Waiting to run (including retries waiting out their backoff)
+    pub pending: i64,
+    /// 🏃 Claimed by a worker
+    pub running: i64,
+    /// ☠️ Parked in the dead-letter queue
+    pub dead: i64,
+}
+
+/// 🔍 Filter for listing dead-lettered jobs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeadJobFilter {
+    /// 🏷️ Only jobs of this type
+    pub job_type: Option<String>,
+}
+
+impl BackgroundJob {
+    /// ⏳ Waiting for its scheduled time
+    pub const PENDING: &'static str = "pending";
+    /// 🏃 Claimed by a worker
+    pub const RUNNING: &'static str = "running";
+    /// ✅ Finished successfully
+    pub const COMPLETED: &'static str = "completed";
+    /// ☠️ Out of retries, parked in the dead-letter queue
+    pub const DEAD: &'static str = "dead";
+    /// 🔔 NOTIFY channel announcing new work (the payload is the job type)
+    pub const CHANNEL: &'static str = "background_jobs";
+    /// 🔀 Columns dead-letter listings can be sorted by
+    pub const DEAD_SORTABLE: &'static [&'static str] = &[
+        "dead_lettered_at",
+        "created_at",
+        "job_type",
+        "priority",
+        "retries",
+    ];
+
+    /// ⏳ Delay before retrying after `retries` failed attempts: 30s doubling, capped at an hour
+    pub fn retry_delay(retries: i32) -> chrono::Duration {
+        let exponent = retries.clamp(1, 8) as u32 - 1;
+        chrono::Duration::seconds((30_i64 << exponent).min(3600))
+    }
+
+    /// 🚦 Lane this job runs in
+    pub fn lane(&self) -> JobPriority {
+        JobPriority::from_value(self.priority)
+    }
+
+    /// ➕ Queue a job to run as soon as a worker is free
+    pub async fn enqueue(pool: &PgPool, job: &NewBackgroundJob) -> Result<Self> {
+        let job = Self::create(pool, job).await?;
+        Self::notify(pool, &job.job_type).await?;
+        Ok(job)
+    }
+
+    /// ➕ Store a pending job without announcing it (queue backends that dispatch
+    /// through something other than NOTIFY announce it themselves)
+    pub async fn create(pool: &PgPool, job: &NewBackgroundJob) -> Result<Self> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            "INSERT INTO background_jobs (job_type, payload, max_retries, priority, user_id) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+        )
+        .bind(&job.job_type)
+        .bind(&job.payload)
+        .bind(job.max_retries)
+        .bind(job.priority.value())
+        .bind(job.user_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to enqueue background job")?;
+
+        Ok(job)
+    }
+
+    /// 🔔 Wake idle workers on every instance
+    async fn notify(pool: &PgPool, job_type: &str) -> Result<()> {
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(Self::CHANNEL)
+            .bind(job_type)
+            .execute(pool)
+            .await
+            .context("Failed to notify job workers")?;
+
+        Ok(())
+    }
+
+    /// 🎟️ Claim the next due job of one of `job_types` (concurrent workers skip each other's rows)
+    /// Highest lane first; within a lane, the user with the fewest running jobs
+    /// goes next (system jobs count as one user), then the oldest job
+    pub async fn claim_next(
+        pool: &PgPool,
+        job_types: &[String],
+        worker_id: &str,
+    ) -> Result<Option<Self>> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            r#"
+            UPDATE background_jobs SET status = $1, started_at = NOW(), worker_id = $4, heartbeat_at = NOW()
+            WHERE id = (
+                SELECT j.id FROM background_jobs j
+                LEFT JOIN (
+                    SELECT user_id, COUNT(*) AS running FROM background_jobs
+                    WHERE status = $1
+                    GROUP BY user_id
+                ) r ON r.user_id IS NOT DISTINCT FROM j.user_id
+                WHERE j.status = $2 AND j.scheduled_at <= NOW() AND j.job_type = ANY($3)
+                ORDER BY j.priority DESC, COALESCE(r.running, 0), j.scheduled_at, j.created_at
+                LIMIT 1
+                FOR UPDATE OF j SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(Self::RUNNING)
+        .bind(Self::PENDING)
+        .bind(job_types)
+        .bind(worker_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to claim background job")?;
+
+        Ok(job)
+    }
+
+    /// 🎟️ Claim one specific job, if it is still pending and due
+    /// None when another worker got to it first (or it was requeued for later)
+    pub async fn claim_by_id(pool: &PgPool, id: Uuid, worker_id: &str) -> Result<Option<Self>> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            "UPDATE background_jobs SET status = $1, started_at = NOW(), worker_id = $2, heartbeat_at = NOW() WHERE id = $3 AND status = $4 AND scheduled_at <= NOW() RETURNING *",
+        )
+        .bind(Self::RUNNING)
+        .bind(worker_id)
+        .bind(id)
+        .bind(Self::PENDING)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to claim background job")?;
+
+        Ok(job)
+    }
+
+    /// 💓 Keep the claim alive; false when the job was reaped or taken over meanwhile
+    pub async fn heartbeat(&self, pool: &PgPool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE background_jobs SET heartbeat_at = NOW() WHERE id = $1 AND worker_id = $2 AND status = $3",
+        )
+        .bind(self.id)
+        .bind(&self.worker_id)
+        .bind(Self::RUNNING)
+        .execute(pool)
+        .await
+        .context("Failed to record job heartbeat")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// 🪦 Fail running jobs whose last heartbeat is older than `stale_before`
+    /// Each counts as a failed attempt, so it is retried or dead-lettered as usual
+    pub async fn reap_stale(pool: &PgPool, stale_before: DateTime<Utc>) -> Result<Vec<Self>> {
+        let jobs = sqlx::query_as::<_, BackgroundJob>(
+            r#"
+            UPDATE background_jobs SET
+                status = CASE WHEN retries + 1 >= max_retries THEN $1 ELSE $2 END,
+                retries = retries + 1,
+                error_message = 'Worker ' || COALESCE(worker_id, 'unknown') || ' stopped responding',
+                error_history = error_history || jsonb_build_array(jsonb_build_object(
+                    'attempt', retries + 1,
+                    'error', 'Worker ' || COALESCE(worker_id, 'unknown') || ' stopped responding',
+                    'failed_at', NOW()
+                )),
+                scheduled_at = NOW(),
+                dead_lettered_at = CASE WHEN retries + 1 >= max_retries THEN NOW() END,
+                worker_id = NULL
+            WHERE status = $3 AND heartbeat_at < $4
+            RETURNING *
+            "#,
+        )
+        .bind(Self::DEAD)
+        .bind(Self::PENDING)
+        .bind(Self::RUNNING)
+        .bind(stale_before)
+        .fetch_all(pool)
+        .await
+        .context("Failed to reap stale jobs")?;
+
+        Ok(jobs)
+    }
+
+    /// 🔍 Whether a job of this type whose payload contains `payload` is waiting or running
+    pub async fn is_queued(
+        pool: &PgPool,
+        job_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<bool> {
+        let queued: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM background_jobs WHERE job_type = $1 AND payload @> $2 AND status IN ($3, $4))",
+        )
+        .bind(job_type)
+        .bind(payload)
+        .bind(Self::PENDING)
+        .bind(Self::RUNNING)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check the job queue")?;
+
+        Ok(queued)
+    }
+
+    /// 🔍 Find a job by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let job = sqlx::query_as::<_, BackgroundJob>("SELECT * FROM background_jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch background job")?;
+
+        Ok(job)
+    }
+
+    /// ✅ Mark the job as done
+    /// False when this worker's claim was lost (the job was reaped), so nothing changed
+    pub async fn complete(&mut self, pool: &PgPool) -> Result<bool> {
+        let completed = sqlx::query_as::<_, BackgroundJob>(
+            "UPDATE background_jobs SET status = $1, completed_at = NOW() WHERE id = $2 AND worker_id = $3 AND status = $4 RETURNING *",
+        )
+        .bind(Self::COMPLETED)
+        .bind(self.id)
+        .bind(&self.worker_id)
+        .bind(Self::RUNNING)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to complete background job")?;
+
+        Ok(self.replace_with(completed))
+    }
+
+    /// 🔄 Take the updated row, if there is one
+    fn replace_with(&mut self, updated: Option<Self>) -> bool {
+        match updated {
+            Some(updated) => {
+                *self = updated;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// ❌ Record a failed attempt: retry later, or dead-letter once retries run out
+    /// False when this worker's claim was lost (the job was reaped), so nothing changed
+    pub async fn fail(&mut self, pool: &PgPool, error_message: &str) -> Result<bool> {
+        let retries = self.retries + 1;
+        let dead = retries >= self.max_retries;
+        let attempt = serde_json::json!([{
+            "attempt": retries,
+            "error": error_message,
+            "failed_at": Utc::now(),
+        }]);
+
+        let failed = sqlx::query_as::<_, BackgroundJob>(
+            r#"
+            UPDATE background_jobs SET
+                status = $1,
+                retries = $2,
+                error_message = $3,
+                error_history = error_history || $4,
+                scheduled_at = $5,
+                dead_lettered_at = CASE WHEN $6 THEN NOW() END,
+                worker_id = NULL
+            WHERE id = $7 AND worker_id = $8 AND status = $9
+            RETURNING *
+            "#,
+        )
+        .bind(if dead { Self::DEAD } else { Self::PENDING })
+        .bind(retries)
+        .bind(error_message)
+        .bind(attempt)
+        .bind(Utc::now() + Self::retry_delay(retries))
+        .bind(dead)
+        .bind(self.id)
+        .bind(&self.worker_id)
+        .bind(Self::RUNNING)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to record background job failure")?;
+
+        Ok(self.replace_with(failed))
+    }
+
+    /// ⏸️ Put a job back to run at `until`, without counting a failed attempt
+    /// False when this worker's claim was lost (the job was reaped), so nothing changed
+    pub async fn defer(&mut self, pool: &PgPool, until: DateTime<Utc>) -> Result<bool> {
+        let deferred = sqlx::query_as::<_, BackgroundJob>(
+            "UPDATE background_jobs SET status = $1, scheduled_at = $2, worker_id = NULL WHERE id = $3 AND worker_id = $4 AND status = $5 RETURNING *",
+        )
+        .bind(Self::PENDING)
+        .bind(until)
+        .bind(self.id)
+        .bind(&self.worker_id)
+        .bind(Self::RUNNING)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to defer background job")?;
+
+        Ok(self.replace_with(deferred))
+    }
+
+    /// 🏃 Running jobs of a type whose payload contains `payload`, enqueued
+    /// before `job` (so of two jobs claimed at once, the older one goes first)
+    pub async fn running_ahead_of(
+        pool: &PgPool,
+        job: &BackgroundJob,
+        payload: &serde_json::Value,
+    ) -> Result<i64> {
+        let running: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM background_jobs WHERE job_type = $1 AND payload @> $2 AND status = $3 AND (created_at, id) < ($5, $4)",
+        )
+        .bind(&job.job_type)
+        .bind(payload)
+        .bind(Self::RUNNING)
+        .bind(job.id)
+        .bind(job.created_at)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count running jobs")?;
+
+        Ok(running)
+    }
+
+    /// 🔍 Most recently enqueued job of a type whose payload contains `payload`
+    pub async fn latest_matching(
+        pool: &PgPool,
+        job_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Option<Self>> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            "SELECT * FROM background_jobs WHERE job_type = $1 AND payload @> $2 ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(job_type)
+        .bind(payload)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to find job")?;
+
+        Ok(job)
+    }
+
+    /// ☠️ Dead-lettered jobs in `order_by` (ORDER BY terms over DEAD_SORTABLE
+    /// columns), plus the total match count
+    pub async fn list_dead(
+        pool: &PgPool,
+        filter: &DeadJobFilter,
+        order_by: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<Self>, u64)> {
+        let items = sqlx::query_as::<_, BackgroundJob>(&format!(
+            "SELECT * FROM background_jobs WHERE status = $1 AND ($2::text IS NULL OR job_type = $2) ORDER BY {}, id LIMIT $3 OFFSET $4",
+            order_by
+        ))
+        .bind(Self::DEAD)
+        .bind(&filter.job_type)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list dead-lettered jobs")?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM background_jobs WHERE status = $1 AND ($2::text IS NULL OR job_type = $2)",
+        )
+        .bind(Self::DEAD)
+        .bind(&filter.job_type)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count dead-lettered jobs")?;
+
+        Ok((items, total as u64))
+    }
+
+    /// 📮 Unfinished jobs per type, for the admin console
+    pub async fn queue_depth(pool: &PgPool) -> Result<Vec<JobQueueDepth>> {
+        let depth = sqlx::query_as::<_, JobQueueDepth>(
+            "SELECT job_type, COUNT(*) FILTER (WHERE status = $1) AS pending, COUNT(*) FILTER (WHERE status = $2) AS running, COUNT(*) FILTER (WHERE status = $3) AS dead FROM background_jobs WHERE status <> $4 GROUP BY job_type ORDER BY job_type",
+        )
+        .bind(Self::PENDING)
+        .bind(Self::RUNNING)
+        .bind(Self::DEAD)
+        .bind(Self::COMPLETED)
+        .fetch_all(pool)
+        .await
+        .context("Failed to count queued jobs")?;
+
+        Ok(depth)
+    }
+
+    /// 🔁 Give a dead-lettered job a fresh set of retries (its error history is kept)
+    /// None when the job doesn't exist or isn't dead-lettered
+    pub async fn requeue(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            "UPDATE background_jobs SET status = $1, retries = 0, scheduled_at = NOW(), dead_lettered_at = NULL WHERE id = $2 AND status = $3 RETURNING *",
+        )
+        .bind(Self::PENDING)
+        .bind(id)
+        .bind(Self::DEAD)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to requeue background job")?;
+
+        if let Some(job) = &job {
+            Self::notify(pool, &job.job_type).await?;
+        }
+        Ok(job)
+    }
+
+    /// 🔁 Requeue every dead-lettered job of a type; returns how many were requeued
+    pub async fn requeue_dead(pool: &PgPool, job_type: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE background_jobs SET status = $1, retries = 0, scheduled_at = NOW(), dead_lettered_at = NULL WHERE status = $2 AND job_type = $3",
+        )
+        .bind(Self::PENDING)
+        .bind(Self::DEAD)
+        .bind(job_type)
+        .execute(pool)
+        .await
+        .context("Failed to requeue dead-lettered jobs")?;
+
+        if result.rows_affected() > 0 {
+            Self::notify(pool, job_type).await?;
+        }
+        Ok(result.rows_affected())
+    }
+
+    /// 🧹 Delete jobs dead-lettered before a cutoff; returns how many were deleted
+    pub async fn purge_dead(pool: &PgPool, before: DateTime<Utc>) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM background_jobs WHERE status = $1 AND dead_lettered_at < $2")
+                .bind(Self::DEAD)
+                .bind(before)
+                .execute(pool)
+                .await
+                .context("Failed to purge dead-lettered jobs")?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+// ⏰ Scheduled Job Model - Recurring work on a cron schedule
+// Each tick, schedules whose `next_run_at` has passed enqueue a background job.
+// The catch-up policy decides what happens to runs missed while the service was
+// down, and unless overlap is allowed a run is skipped while the previous one is
+// still queued or running
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledJob {
+    /// 🆔 Unique identifier for this schedule
+    pub id: Uuid,
+    /// 🏷️ Unique name (used in the admin API)
+    pub name: String,
+    /// 🏷️ Type of the background job it enqueues
+    pub job_type: String,
+    /// 🗓️ Cron expression (UTC, 5 or 6 fields)
+    pub cron_expression: String,
+    /// 📦 Payload of the enqueued jobs
+    pub payload: serde_json::Value,
+    /// ⏳ skip, once, or all (see `CatchUpPolicy`)
+    pub catch_up: String,
+    /// 🔀 Whether a run may start while the previous one is queued or running
+    pub allow_overlap: bool,
+    /// ⏸️ Paused schedules don't run (missed runs are not caught up on resume)
+    pub paused: bool,
+    /// ⏰ Next time the schedule is due
+    pub next_run_at: DateTime<Utc>,
+    /// 🏃 When it last enqueued a job
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// 🔗 The job it last enqueued
+    pub last_job_id: Option<Uuid>,
+    /// 📅 When the schedule was created
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When the schedule was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// ⏳ What to do with runs missed while the service was down
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUpPolicy {
+    /// 🙈 Drop late runs; wait for the next occurrence
+    Skip,
+    /// 1️⃣ Run once, however many occurrences were missed
+    Once,
+    /// 🔁 Run once per missed occurrence (capped)
+    All,
+}
+
+impl CatchUpPolicy {
+    /// 🏷️ Value stored in the catch_up column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CatchUpPolicy::Skip => "skip",
+            CatchUpPolicy::Once => "once",
+            CatchUpPolicy::All => "all",
+        }
+    }
+}
+
+impl std::str::FromStr for CatchUpPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(CatchUpPolicy::Skip),
+            "once" => Ok(CatchUpPolicy::Once),
+            "all" => Ok(CatchUpPolicy::All),
+            _ => anyhow::bail!("Invalid catch-up policy: {} (expected skip, once, or all)", s),
+        }
+    }
+}
+
+impl ScheduledJob {
+    /// ⏳ Parsed catch-up policy (unknown values behave like `once`)
+    pub fn catch_up_policy(&self) -> CatchUpPolicy {
+        self.catch_up.parse().unwrap_or(CatchUpPolicy::Once)
+    }
+
+    /// ➕ Create a schedule unless one with this name exists (operator changes are kept)
+    pub async fn ensure(
+        pool: &PgPool,
+        name: &str,
+        job_type: &str,
+        cron_expression: &str,
+        catch_up: CatchUpPolicy,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO scheduled_jobs (name, job_type, cron_expression, catch_up, next_run_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (name) DO NOTHING",
+        )
+        .bind(name)
+        .bind(job_type)
+        .bind(cron_expression)
+        .bind(catch_up.as_str())
+        .bind(next_run_at)
+        .execute(pool)
+        .await
+        .context("Failed to create scheduled job")?;
+
+        Ok(())
+    }
+
+    /// 📋 All schedules, by name
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        let schedules =
+            sqlx::query_as::<_, ScheduledJob>("SELECT * FROM scheduled_jobs ORDER BY name")
+                .fetch_all(pool)
+                .await
+                .context("Failed to list scheduled jobs")?;
+
+        Ok(schedules)
+    }
+
+    /// ⏰ Unpaused schedules due at `now`
+    pub async fn list_due(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<Self>> {
+        let schedules = sqlx::query_as::<_, ScheduledJob>(
+            "SELECT * FROM scheduled_jobs WHERE NOT paused AND next_run_at <= $1 ORDER BY next_run_at",
+        )
+        .bind(now)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list due scheduled jobs")?;
+
+        Ok(schedules)
+    }
+
+    /// 🔍 Find a schedule by name
+    pub async fn find_by_name(pool: &PgPool, name: &str) -> Result<Option<Self>> {
+        let schedule =
+            sqlx::query_as::<_, ScheduledJob>("SELECT * FROM scheduled_jobs WHERE name = $1")
+                .bind(name)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch scheduled job")?;
+
+        Ok(schedule)
+    }
+
+    /// 📝 Move on to the next occurrence, recording the job enqueued for this one (if any)
+    pub async fn advance(
+        &mut self,
+        pool: &PgPool,
+        next_run_at: DateTime<Utc>,
+        job_id: Option<Uuid>,
+    ) -> Result<()> {
+        let updated = sqlx::query_as::<_, ScheduledJob>(
+            r#"
+            UPDATE scheduled_jobs SET
+                next_run_at = $2,
+                last_run_at = CASE WHEN $3::uuid IS NULL THEN last_run_at ELSE NOW() END,
+                last_job_id = COALESCE($3, last_job_id),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(self.id)
+        .bind(next_run_at)
+        .bind(job_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update scheduled job")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// ⏸️ Pause or resume; resuming starts again from `next_run_at`
+    pub async fn set_paused(
+        &mut self,
+        pool: &PgPool,
+        paused: bool,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let updated = sqlx::query_as::<_, ScheduledJob>(
+            "UPDATE scheduled_jobs SET paused = $2, next_run_at = $3, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(paused)
+        .bind(next_run_at)
+        .fetch_one(pool)
+        .await
+        .context("Failed to pause scheduled job")?;
+
+        *self = updated;
+        Ok(())
+    }
+}
+
+// 🚩 Feature Flag Model - Behavior toggled at runtime
+// A flag is on for a project when the project has an override saying so, or
+// when the flag is enabled and the project falls inside its rollout percentage
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeatureFlag {
+    /// 🆔 Unique identifier for this flag
+    pub id: Uuid,
+    /// 🏷️ Unique name, as checked by the code
+    pub name: String,
+    /// 📝 What the flag switches
+    pub description: Option<String>,
+    /// 🌍 Global switch
+    pub enabled: bool,
+    /// 🎲 Percent of projects the flag is on for while enabled
+    pub rollout_percent: i32,
+    /// 📅 When the flag was created
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When the flag was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 🎯 A project's own setting of a flag
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeatureFlagOverride {
+    /// 🏷️ Name of the flag
+    pub flag_name: String,
+    /// 🏠 Project the override applies to
+    pub project_id: Uuid,
+    /// ✅ Whether the flag is on for the project
+    pub enabled: bool,
+    /// 🔄 When the override was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeatureFlag {
+    /// ➕ Create a flag
+    pub async fn create(
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        enabled: bool,
+        rollout_percent: i32,
+    ) -> Result<Self> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(
+            "INSERT INTO feature_flags (name, description, enabled, rollout_percent) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(name)
+        .bind(description)
+        .bind(enabled)
+        .bind(rollout_percent)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create feature flag")?;
+
+        Ok(flag)
+    }
+
+    /// 🔍 Find a flag by name
+    pub async fn find_by_name(pool: &PgPool, name: &str) -> Result<Option<Self>> {
+        let flag = sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags WHERE name = $1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch feature flag")?;
+
+        Ok(flag)
+    }
+
+    /// 📋 Every flag, by name
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        let flags = sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags ORDER BY name")
+            .fetch_all(pool)
+            .await
+            .context("Failed to list feature flags")?;
+
+        Ok(flags)
+    }
+
+    /// ✏️ Save a new description, switch and rollout
+    pub async fn update(
+        &mut self,
+        pool: &PgPool,
+        description: Option<&str>,
+        enabled: bool,
+        rollout_percent: i32,
+    ) -> Result<()> {
+        let updated = sqlx::query_as::<_, FeatureFlag>(
+            "UPDATE feature_flags SET description = $2, enabled = $3, rollout_percent = $4, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(description)
+        .bind(enabled)
+        .bind(rollout_percent)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update feature flag")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🗑️ Delete a flag and its overrides; false when there was no such flag
+    pub async fn delete(pool: &PgPool, name: &str) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM feature_flags WHERE name = $1")
+            .bind(name)
+            .execute(pool)
+            .await
+            .context("Failed to delete feature flag")?
+            .rows_affected();
+
+        Ok(deleted > 0)
+    }
+}
+
+impl FeatureFlagOverride {
+    /// 📋 Overrides of one flag (or every flag)
+    pub async fn list(pool: &PgPool, flag_name: Option<&str>) -> Result<Vec<Self>> {
+        let overrides = sqlx::query_as::<_, FeatureFlagOverride>(
+            "SELECT f.name AS flag_name, o.project_id, o.enabled, o.updated_at FROM feature_flag_overrides o JOIN feature_flags f ON f.id = o.flag_id WHERE ($1::text IS NULL OR f.name = $1) ORDER BY f.name, o.project_id",
+        )
+        .bind(flag_name)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list feature flag overrides")?;
+
+        Ok(overrides)
+    }
+
+    /// 🎯 Turn a flag on or off for one project
+    pub async fn set(
+        pool: &PgPool,
+        flag: &FeatureFlag,
+        project_id: Uuid,
+        enabled: bool,
+    ) -> Result<Self> {
+        let row = sqlx::query_as::<_, FeatureFlagOverride>(
+            "INSERT INTO feature_flag_overrides (flag_id, project_id, enabled) VALUES ($1, $2, $3) ON CONFLICT (flag_id, project_id) DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW() RETURNING $4::text AS flag_name, project_id, enabled, updated_at",
+        )
+        .bind(flag.id)
+        .bind(project_id)
+        .bind(enabled)
+        .bind(&flag.name)
+        .fetch_one(pool)
+        .await
+        .context("Failed to set feature flag override")?;
+
+        Ok(row)
+    }
+
+    /// 🧹 Remove a project's override; false when it had none
+    pub async fn clear(pool: &PgPool, flag: &FeatureFlag, project_id: Uuid) -> Result<bool> {
+        let deleted = sqlx::query(
+            "DELETE FROM feature_flag_overrides WHERE flag_id = $1 AND project_id = $2",
+        )
+        .bind(flag.id)
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .context("Failed to clear feature flag override")?
+        .rows_affected();
+
+        Ok(deleted > 0)
+    }
+}
+
+// 🏢 Organization Model - Projects shared by a group of users
+// Members have a role (owner > admin > member > viewer); projects can be
+// limited further to one of the organization's teams. Quotas are set by
+// system admins, settings by the organization's admins
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    /// 🆔 Unique identifier for this organization
+    pub id: Uuid,
+    /// 🏷️ Display name
+    pub name: String,
+    /// 🔗 Unique short name (lowercase letters, digits and dashes)
+    pub slug: String,
+    /// ⚙️ Organization-wide settings (JSON object)
+    pub settings: serde_json::Value,
+    /// 🏠 Most projects the organization may have (None = unlimited)
+    pub max_projects: Option<i32>,
+    /// 📝 Most feedback submissions per calendar month (None = unlimited)
+    pub max_monthly_feedback: Option<i32>,
+    /// 📅 When the organization was created
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When the organization was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 👑 A member's role in an organization, from least to most access
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    /// 👀 Read projects and their feedback
+    Viewer,
+    /// 🏃 Also submit feedback and start pipeline runs
+    Member,
+    /// 🛠️ Also configure projects and manage members and teams
+    Admin,
+    /// 👑 Everything, including granting ownership
+    Owner,
+}
+
+impl OrgRole {
+    /// 🏷️ Value stored in the role column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrgRole::Viewer => "viewer",
+            OrgRole::Member => "member",
+            OrgRole::Admin => "admin",
+            OrgRole::Owner => "owner",
+        }
+    }
+}
+
+impl std::str::FromStr for OrgRole {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(OrgRole::Viewer),
+            "member" => Ok(OrgRole::Member),
+            "admin" => Ok(OrgRole::Admin),
+            "owner" => Ok(OrgRole::Owner),
+            _ => anyhow::bail!(
+                "Invalid organization role: {} (expected viewer, member, admin, or owner)",
+                s
+            ),
+        }
+    }
+}
+
+/// 👥 A user's membership in an organization
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrganizationMember {
+    /// 🏢 The organization
+    pub organization_id: Uuid,
+    /// 👤 The member
+    pub user_id: Uuid,
+    /// 📧 Member's email
+    pub email: String,
+    /// 👤 Member's display name
+    pub name: String,
+    /// 👑 viewer, member, admin, or owner (see `OrgRole`)
+    pub role: String,
+    /// 📅 When the user joined
+    pub created_at: DateTime<Utc>,
+}
+
+impl OrganizationMember {
+    /// 👑 Parsed role (unknown values get the least access)
+    pub fn org_role(&self) -> OrgRole {
+        self.role.parse().unwrap_or(OrgRole::Viewer)
+    }
+}
+
+/// 🧑‍🤝‍🧑 A team within an organization
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Team {
+    /// 🆔 Unique identifier for this team
+    pub id: Uuid,
+    /// 🏢 Organization the team belongs to
+    pub organization_id: Uuid,
+    /// 🏷️ Name, unique within the organization
+    pub name: String,
+    /// 📅 When the team was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Organization {
+    /// ➕ Create an organization with `owner_id` as its first owner
+    pub async fn create(pool: &PgPool, name: &str, slug: &str, owner_id: Uuid) -> Result<Self> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start organization transaction")?;
+
+        let organization = sqlx::query_as::<_, Organization>(
+            "INSERT INTO organizations (name, slug) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(name)
+        .bind(slug)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create organization")?;
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, 'owner')",
+        )
+        .bind(organization.id)
+        .bind(owner_id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to add organization owner")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit organization")?;
+        Ok(organization)
+    }
+
+    /// 🔍 Find an organization by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let organization =
+            sqlx::query_as::<_, Organization>("SELECT * FROM organizations WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch organization")?;
+
+        Ok(organization)
+    }
+
+    /// 🔍 Find an organization by slug
+    pub async fn find_by_slug(pool: &PgPool, slug: &str) -> Result<Option<Self>> {
+        let organization =
+            sqlx::query_as::<_, Organization>("SELECT * FROM organizations WHERE slug = $1")
+                .bind(slug)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to fetch organization")?;
+
+        Ok(organization)
+    }
+
+    /// 📋 Organizations a user belongs to (every organization when None)
+    pub async fn list(pool: &PgPool, member_id: Option<Uuid>) -> Result<Vec<Self>> {
+        let organizations = sqlx::query_as::<_, Organization>(
+            "SELECT * FROM organizations o WHERE $1::uuid IS NULL OR EXISTS (SELECT 1 FROM organization_members m WHERE m.organization_id = o.id AND m.user_id = $1) ORDER BY o.name",
+        )
+        .bind(member_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list organizations")?;
+
+        Ok(organizations)
+    }
+
+    /// 🏢 Organization of the project registered for a repository, if any
+    pub async fn find_by_repository(pool: &PgPool, repository: &str) -> Result<Option<Self>> {
+        let organization = sqlx::query_as::<_, Organization>(
+            "SELECT o.* FROM organizations o JOIN projects p ON p.organization_id = o.id WHERE LOWER(p.repository) = LOWER($1) LIMIT 1",
+        )
+        .bind(repository)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch repository organization")?;
+
+        Ok(organization)
+    }
+
+    /// ⚙️ Replace the settings
+    pub async fn update_settings(&mut self, pool: &PgPool, settings: &serde_json::Value) -> Result<()> {
+        let updated = sqlx::query_as::<_, Organization>(
+            "UPDATE organizations SET settings = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(settings)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update organization settings")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 📏 Replace the quotas (None = unlimited)
+    pub async fn set_quotas(
+        &mut self,
+        pool: &PgPool,
+        max_projects: Option<i32>,
+        max_monthly_feedback: Option<i32>,
+    ) -> Result<()> {
+        let updated = sqlx::query_as::<_, Organization>(
+            "UPDATE organizations SET max_projects = $2, max_monthly_feedback = $3, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(max_projects)
+        .bind(max_monthly_feedback)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update organization quotas")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🔢 Projects in the organization
+    pub async fn project_count(&self, pool: &PgPool) -> Result<i64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM projects WHERE organization_id = $1")
+                .bind(self.id)
+                .fetch_one(pool)
+                .await
+                .context("Failed to count organization projects")?;
+
+        Ok(count)
+    }
+
+    /// 🔢 Feedback submitted this calendar month for the organization's repositories
+    pub async fn monthly_feedback_count(&self, pool: &PgPool) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM feedback f WHERE f.created_at >= date_trunc('month', NOW()) AND LOWER(f.repository) IN (SELECT LOWER(p.repository) FROM projects p WHERE p.organization_id = $1)",
+        )
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count organization feedback")?;
+
+        Ok(count)
+    }
+}
+
+impl OrganizationMember {
+    /// 👑 A user's role in an organization, if they are a member
+    pub async fn role_of(pool: &PgPool, organization_id: Uuid, user_id: Uuid) -> Result<Option<OrgRole>> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch organization membership")?;
+
+        Ok(role.map(|role| role.parse().unwrap_or(OrgRole::Viewer)))
+    }
+
+    /// 📋 Members of an organization, owners first
+    pub async fn list(pool: &PgPool, organization_id: Uuid) -> Result<Vec<Self>> {
+        let members = sqlx::query_as::<_, OrganizationMember>(
+            "SELECT m.organization_id, m.user_id, u.email, u.name, m.role, m.created_at FROM organization_members m JOIN users u ON u.id = m.user_id WHERE m.organization_id = $1 ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'admin' THEN 1 WHEN 'member' THEN 2 ELSE 3 END, u.email",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list organization members")?;
+
+        Ok(members)
+    }
+
+    /// ➕ Add a member, or change the role of an existing one
+    pub async fn set(pool: &PgPool, organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3) ON CONFLICT (organization_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .execute(pool)
+        .await
+        .context("Failed to set organization membership")?;
+
+        Ok(())
+    }
+
+    /// 🚪 Remove a member and their team memberships; false when they weren't one
+    pub async fn remove(pool: &PgPool, organization_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start membership transaction")?;
+
+        sqlx::query(
+            "DELETE FROM team_members WHERE user_id = $2 AND team_id IN (SELECT id FROM teams WHERE organization_id = $1)",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to remove team memberships")?;
+        let removed = sqlx::query(
+            "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to remove organization member")?
+        .rows_affected();
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit membership removal")?;
+        Ok(removed > 0)
+    }
+
+    /// 🔢 Owners of an organization (the last one can't leave or be demoted)
+    pub async fn owner_count(pool: &PgPool, organization_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organization_members WHERE organization_id = $1 AND role = 'owner'",
+        )
+        .bind(organization_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count organization owners")?;
+
+        Ok(count)
+    }
+}
+
+impl Team {
+    /// ➕ Create a team
+    pub async fn create(pool: &PgPool, organization_id: Uuid, name: &str) -> Result<Self> {
+        let team = sqlx::query_as::<_, Team>(
+            "INSERT INTO teams (organization_id, name) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(organization_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create team")?;
+
+        Ok(team)
+    }
+
+    /// 🔍 Find one of an organization's teams
+    pub async fn find(pool: &PgPool, organization_id: Uuid, id: Uuid) -> Result<Option<Self>> {
+        let team = sqlx::query_as::<_, Team>(
+            "SELECT * FROM teams WHERE id = $1 AND organization_id = $2",
+        )
+        .bind(id)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch team")?;
+
+        Ok(team)
+    }
+
+    /// 📋 An organization's teams, by name
+    pub async fn list(pool: &PgPool, organization_id: Uuid) -> Result<Vec<Self>> {
+        let teams = sqlx::query_as::<_, Team>(
+            "SELECT * FROM teams WHERE organization_id = $1 ORDER BY name",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list teams")?;
+
+        Ok(teams)
+    }
+
+    /// 🗑️ Delete a team (its projects become visible to the whole organization)
+    pub async fn delete(&self, pool: &PgPool) -> Result<()> {
+        sqlx::query("DELETE FROM teams WHERE id = $1")
+            .bind(self.id)
+            .execute(pool)
+            .await
+            .context("Failed to delete team")?;
+
+        Ok(())
+    }
+
+    /// 👥 IDs of the team's members
+    pub async fn member_ids(&self, pool: &PgPool) -> Result<Vec<Uuid>> {
+        let members: Vec<Uuid> =
+            sqlx::query_scalar("SELECT user_id FROM team_members WHERE team_id = $1 ORDER BY created_at")
+                .bind(self.id)
+                .fetch_all(pool)
+                .await
+                .context("Failed to list team members")?;
+
+        Ok(members)
+    }
+
+    /// ✅ Whether a user is on a team
+    pub async fn has_member(pool: &PgPool, team_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM team_members WHERE team_id = $1 AND user_id = $2)",
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check team membership")?;
+
+        Ok(exists)
+    }
+
+    /// ➕ Add a member (no-op when already on the team)
+    pub async fn add_member(&self, pool: &PgPool, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO team_members (team_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(self.id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .context("Failed to add team member")?;
+
+        Ok(())
+    }
+
+    /// 🚪 Remove a member; false when they weren't on the team
+    pub async fn remove_member(&self, pool: &PgPool, user_id: Uuid) -> Result<bool> {
+        let removed = sqlx::query("DELETE FROM team_members WHERE team_id = $1 AND user_id = $2")
+            .bind(self.id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .context("Failed to remove team member")?
+            .rows_affected();
+
+        Ok(removed > 0)
+    }
+}
+
+// 🔌 GitHub Installation Model - Where the GitHub App is installed
+// Kept in sync from `installation` and `installation_repositories` webhooks:
+// an installation reaches either every repository of its account
+// (`repository_selection = all`) or the ones listed for it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GitHubInstallation {
+    /// 🆔 GitHub's id for the installation
+    pub id: i64,
+    /// 🏠 User or organization account the app is installed on
+    pub account: String,
+    /// 📋 `all` or `selected`
+    pub repository_selection: String,
+    /// ⏸️ Suspended installations reach nothing
+    pub suspended: bool,
+    /// 📅 When the installation was first seen
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When it last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GitHubInstallation {
+    /// 🌐 Every repository of the account
+    pub const ALL: &'static str = "all";
+
+    /// 💾 Record an installation, or update its account and selection
+    pub async fn upsert(
+        pool: &PgPool,
+        id: i64,
+        account: &str,
+        repository_selection: &str,
+    ) -> Result<Self> {
+        let installation = sqlx::query_as::<_, GitHubInstallation>(
+            "INSERT INTO github_installations (id, account, repository_selection) VALUES ($1, $2, $3) ON CONFLICT (id) DO UPDATE SET account = EXCLUDED.account, repository_selection = EXCLUDED.repository_selection, updated_at = NOW() RETURNING *",
+        )
+        .bind(id)
+        .bind(account)
+        .bind(repository_selection)
+        .fetch_one(pool)
+        .await
+        .context("Failed to save GitHub installation")?;
+
+        Ok(installation)
+    }
+
+    /// ⏸️ Suspend or unsuspend the installation
+    pub async fn set_suspended(&mut self, pool: &PgPool, suspended: bool) -> Result<()> {
+        let updated = sqlx::query_as::<_, GitHubInstallation>(
+            "UPDATE github_installations SET suspended = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(suspended)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update GitHub installation")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🗑️ Forget the installation and its repositories
+    pub async fn delete(&self, pool: &PgPool) -> Result<()> {
+        sqlx::query("DELETE FROM github_installations WHERE id = $1")
+            .bind(self.id)
+            .execute(pool)
+            .await
+            .context("Failed to delete GitHub installation")?;
+
+        Ok(())
+    }
+
+    /// 📋 Repositories listed for the installation
+    pub async fn repositories(&self, pool: &PgPool) -> Result<Vec<String>> {
+        let repositories = sqlx::query_scalar::<_, String>(
+            "SELECT repository FROM github_installation_repositories WHERE installation_id = $1 ORDER BY repository",
+        )
+        .bind(self.id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list GitHub installation repositories")?;
+
+        Ok(repositories)
+    }
+
+    /// ➕ List repositories for the installation (already listed ones are kept)
+    pub async fn add_repositories(&self, pool: &PgPool, repositories: &[String]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO github_installation_repositories (installation_id, repository) SELECT $1, UNNEST($2::text[]) ON CONFLICT DO NOTHING",
+        )
+        .bind(self.id)
+        .bind(repositories)
+        .execute(pool)
+        .await
+        .context("Failed to add GitHub installation repositories")?;
+
+        Ok(())
+    }
+
+    /// ➖ Take repositories off the installation's list
+    pub async fn remove_repositories(&self, pool: &PgPool, repositories: &[String]) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM github_installation_repositories WHERE installation_id = $1 AND LOWER(repository) IN (SELECT LOWER(r) FROM UNNEST($2::text[]) r)",
+        )
+        .bind(self.id)
+        .bind(repositories)
+        .execute(pool)
+        .await
+        .context("Failed to remove GitHub installation repositories")?;
+
+        Ok(())
+    }
+
+    /// 🔍 Whether any installation that isn't suspended still reaches a repository
+    pub async fn grants_access(pool: &PgPool, repository: &str) -> Result<bool> {
+        let granted = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM github_installations i WHERE NOT i.suspended AND ((i.repository_selection = 'all' AND LOWER(i.account) = LOWER(split_part($1, '/', 1))) OR EXISTS (SELECT 1 FROM github_installation_repositories r WHERE r.installation_id = i.id AND LOWER(r.repository) = LOWER($1))))",
+        )
+        .bind(repository)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check GitHub installation access")?;
+
+        Ok(granted)
+    }
+}
+
+// 👥 Project Collaborator Model - GitHub logins mirrored into a project
+// Projects with `collaborators.sync` keep the logins of everyone with enough
+// access to their repository; Feedbacker users whose GitHub username is one of
+// them get a member role on the project (see crate::collaborators)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectCollaborator {
+    /// 🏠 The project
+    pub project_id: Uuid,
+    /// 🐙 GitHub login (lowercase)
+    pub github_login: String,
+    /// 🔄 When the login was last seen on the repository
+    pub synced_at: DateTime<Utc>,
+}
+
+impl ProjectCollaborator {
+    /// 📋 A project's collaborators, by login
+    pub async fn list(pool: &PgPool, project_id: Uuid) -> Result<Vec<Self>> {
+        let collaborators = sqlx::query_as::<_, ProjectCollaborator>(
+            "SELECT * FROM project_collaborators WHERE project_id = $1 ORDER BY github_login",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list project collaborators")?;
+
+        Ok(collaborators)
+    }
+
+    /// 🔄 Make `logins` the project's collaborators, dropping everyone else
+    pub async fn replace(pool: &PgPool, project_id: Uuid, logins: &[String]) -> Result<()> {
+        let logins: Vec<String> = logins.iter().map(|login| login.to_lowercase()).collect();
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start collaborator sync")?;
+        sqlx::query(
+            "DELETE FROM project_collaborators WHERE project_id = $1 AND NOT (github_login = ANY($2))",
+        )
+        .bind(project_id)
+        .bind(&logins)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to remove project collaborators")?;
+        sqlx::query(
+            "INSERT INTO project_collaborators (project_id, github_login) SELECT $1, UNNEST($2::text[]) ON CONFLICT (project_id, github_login) DO UPDATE SET synced_at = NOW()",
+        )
+        .bind(project_id)
+        .bind(&logins)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to add project collaborators")?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit collaborator sync")?;
+
+        Ok(())
+    }
+
+    /// 🧹 Forget the collaborators of projects that stopped syncing them
+    pub async fn clear_unsynced(pool: &PgPool) -> Result<u64> {
+        let cleared = sqlx::query(
+            "DELETE FROM project_collaborators c USING projects p WHERE p.id = c.project_id AND NOT (p.is_active AND COALESCE((p.config->'collaborators'->>'sync')::boolean, false))",
+        )
+        .execute(pool)
+        .await
+        .context("Failed to clear project collaborators")?
+        .rows_affected();
+
+        Ok(cleared)
+    }
+
+    /// 🔍 Whether a user's GitHub username is one of the project's collaborators
+    pub async fn includes_user(pool: &PgPool, project_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let included = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM project_collaborators c JOIN users u ON LOWER(u.github_username) = c.github_login WHERE c.project_id = $1 AND u.id = $2)",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to check project collaborator")?;
+
+        Ok(included)
+    }
+}
+
+// 📬 Webhook Delivery Model - What GitHub sent and what we made of it
+// Every delivery to a webhook endpoint is logged with the answer it got and
+// how long that took, so a project owner can see whether their hook works
+// without digging through GitHub's delivery page
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    /// 🆔 Unique identifier for this record
+    pub id: Uuid,
+    /// 🏷️ GitHub's `X-GitHub-Delivery` id (None when the header was missing)
+    pub delivery_id: Option<String>,
+    /// 🪝 Endpoint that received it (`github` or `issues`)
+    pub endpoint: String,
+    /// 🎬 `X-GitHub-Event` name
+    pub event: String,
+    /// 🎯 Payload `action`, for events that have one
+    pub action: Option<String>,
+    /// 📦 `owner/repo` the event is about (None for installation events)
+    pub repository: Option<String>,
+    /// 🔢 HTTP status we answered with
+    pub status_code: i32,
+    /// 📝 Message we answered with
+    pub result: String,
+    /// ⏱️ Milliseconds spent processing it
+    pub duration_ms: i64,
+    /// 📅 When it arrived
+    pub received_at: DateTime<Utc>,
+}
+
+/// 📬 A delivery about to be logged
+#[derive(Debug, Clone)]
+pub struct NewWebhookDelivery {
+    pub delivery_id: Option<String>,
+    pub endpoint: String,
+    pub event: String,
+    pub action: Option<String>,
+    pub repository: Option<String>,
+    pub status_code: i32,
+    pub result: String,
+    pub duration_ms: i64,
+}
+
+impl WebhookDelivery {
+    /// ➕ Log a delivery
+    pub async fn record(pool: &PgPool, delivery: &NewWebhookDelivery) -> Result<Self> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            "INSERT INTO webhook_deliveries (delivery_id, endpoint, event, action, repository, status_code, result, duration_ms) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
+        )
+        .bind(&delivery.delivery_id)
+        .bind(&delivery.endpoint)
+        .bind(&delivery.event)
+        .bind(&delivery.action)
+        .bind(&delivery.repository)
+        .bind(delivery.status_code)
+        .bind(&delivery.result)
+        .bind(delivery.duration_ms)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record webhook delivery")?;
+
+        Ok(delivery)
+    }
+
+    /// 📋 Most recent deliveries about a repository, newest first
+    pub async fn list_for_repository(
+        pool: &PgPool,
+        repository: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            "SELECT * FROM webhook_deliveries WHERE LOWER(repository) = LOWER($1) ORDER BY received_at DESC LIMIT $2",
+        )
+        .bind(repository)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list webhook deliveries")?;
+
+        Ok(deliveries)
+    }
+}
+
+// 🎨 Project Style Profile Model - conventions learned from a repository
+// Formatter settings, naming and commit style found by analyzing the project's
+// checkout and recent history (see crate::style_guide), injected into
+// generation prompts. Replaced whole on every analysis
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProjectStyleProfile {
+    /// 🏠 The project
+    pub project_id: Uuid,
+    /// 🎨 The learned profile (a `style_guide::StyleProfile`)
+    pub profile: serde_json::Value,
+    /// 🔖 Commit the checkout was analyzed at
+    pub commit_sha: Option<String>,
+    /// 📅 When the analysis ran
+    pub analyzed_at: DateTime<Utc>,
+}
+
+impl ProjectStyleProfile {
+    /// 🔍 A project's profile, if it was analyzed
+    pub async fn find(pool: &PgPool, project_id: Uuid) -> Result<Option<Self>> {
+        let profile = sqlx::query_as::<_, ProjectStyleProfile>(
+            "SELECT * FROM project_style_profiles WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch project style profile")?;
+
+        Ok(profile)
+    }
+
+    /// 💾 Store a fresh analysis, replacing the previous one
+    pub async fn upsert(
+        pool: &PgPool,
+        project_id: Uuid,
+        profile: &serde_json::Value,
+        commit_sha: Option<&str>,
+    ) -> Result<Self> {
+        let profile = sqlx::query_as::<_, ProjectStyleProfile>(
+            "INSERT INTO project_style_profiles (project_id, profile, commit_sha) VALUES ($1, $2, $3) ON CONFLICT (project_id) DO UPDATE SET profile = EXCLUDED.profile, commit_sha = EXCLUDED.commit_sha, analyzed_at = NOW() RETURNING *",
+        )
+        .bind(project_id)
+        .bind(profile)
+        .bind(commit_sha)
+        .fetch_one(pool)
+        .await
+        .context("Failed to store project style profile")?;
+
+        Ok(profile)
+    }
+}
+
+// 🎭 Role Model - A named set of permissions
+// Every account gets the built-in role named after its account role (user,
+// service, admin) plus any roles assigned to it. Built-in roles can't be
+// deleted, and admin always holds every permission
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Role {
+    /// 🆔 Unique identifier for this role
+    pub id: Uuid,
+    /// 🏷️ Unique name
+    pub name: String,
+    /// 📝 What the role is for
+    pub description: Option<String>,
+    /// 🔒 Whether this is one of the account roles (can't be deleted)
+    pub is_builtin: bool,
+    /// 🎯 Permissions granted (see `Permission`)
+    pub permissions: Vec<String>,
+    /// 📅 When the role was created
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When the role was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 👤 A role assigned to a user
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoleAssignment {
+    /// 👤 The user
+    pub user_id: Uuid,
+    /// 🎭 Name of the role
+    pub role_name: String,
+}
+
+/// 🔍 Roles with their permissions aggregated
+const ROLE_SELECT: &str = "SELECT r.id, r.name, r.description, r.is_builtin, COALESCE(array_agg(p.permission::text ORDER BY p.permission) FILTER (WHERE p.permission IS NOT NULL), '{}') AS permissions, r.created_at, r.updated_at FROM roles r LEFT JOIN role_permissions p ON p.role_id = r.id";
+
+impl Role {
+    /// 📋 Every role, built-in roles first
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        let roles = sqlx::query_as::<_, Role>(&format!(
+            "{} GROUP BY r.id ORDER BY r.is_builtin DESC, r.name",
+            ROLE_SELECT
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to list roles")?;
+
+        Ok(roles)
+    }
+
+    /// 🔍 Find a role by name
+    pub async fn find_by_name(pool: &PgPool, name: &str) -> Result<Option<Self>> {
+        let role = sqlx::query_as::<_, Role>(&format!(
+            "{} WHERE r.name = $1 GROUP BY r.id",
+            ROLE_SELECT
+        ))
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch role")?;
+
+        Ok(role)
+    }
+
+    /// ➕ Create a role with its permissions
+    pub async fn create(
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        permissions: &[String],
+    ) -> Result<Self> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start role transaction")?;
+
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO roles (name, description) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(name)
+        .bind(description)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create role")?;
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission) SELECT $1, UNNEST($2::text[])",
+        )
+        .bind(id)
+        .bind(permissions)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to grant role permissions")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit role")?;
+        Self::find_by_name(pool, name)
+            .await?
+            .context("Role vanished after it was created")
+    }
+
+    /// ✏️ Save a new description and replace the permissions
+    pub async fn update(
+        &mut self,
+        pool: &PgPool,
+        description: Option<&str>,
+        permissions: &[String],
+    ) -> Result<()> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start role transaction")?;
+
+        sqlx::query("UPDATE roles SET description = $2, updated_at = NOW() WHERE id = $1")
+            .bind(self.id)
+            .bind(description)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to update role")?;
+        sqlx::query("DELETE FROM role_permissions WHERE role_id = $1")
+            .bind(self.id)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to revoke role permissions")?;
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission) SELECT $1, UNNEST($2::text[])",
+        )
+        .bind(self.id)
+        .bind(permissions)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to grant role permissions")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit role")?;
+        *self = Self::find_by_name(pool, &self.name)
+            .await?
+            .context("Role vanished while it was updated")?;
+        Ok(())
+    }
+
+    /// 🗑️ Delete a role (and its assignments)
+    pub async fn delete(&self, pool: &PgPool) -> Result<()> {
+        sqlx::query("DELETE FROM roles WHERE id = $1")
+            .bind(self.id)
+            .execute(pool)
+            .await
+            .context("Failed to delete role")?;
+
+        Ok(())
+    }
+
+    /// ➕ Assign the role to a user (no-op when already assigned)
+    pub async fn assign(&self, pool: &PgPool, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(self.id)
+        .execute(pool)
+        .await
+        .context("Failed to assign role")?;
+
+        Ok(())
+    }
+
+    /// 🚪 Take the role away from a user; false when they didn't have it
+    pub async fn unassign(&self, pool: &PgPool, user_id: Uuid) -> Result<bool> {
+        let removed = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role_id = $2")
+            .bind(user_id)
+            .bind(self.id)
+            .execute(pool)
+            .await
+            .context("Failed to unassign role")?
+            .rows_affected();
+
+        Ok(removed > 0)
+    }
+}
+
+impl RoleAssignment {
+    /// 📋 Every role assignment
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        let assignments = sqlx::query_as::<_, RoleAssignment>(
+            "SELECT ur.user_id, r.name AS role_name FROM user_roles ur JOIN roles r ON r.id = ur.role_id ORDER BY ur.user_id, r.name",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to list role assignments")?;
+
+        Ok(assignments)
+    }
+}
+
+// 🔐 SSO Provider Model - An organization's identity provider
+// Members sign in through the provider (OIDC); accounts are linked by the
+// provider's subject, created on first sign-in when JIT provisioning is on, and
+// their organization role follows their groups. With `enforce_sso`, password
+// login is refused for the provider's email domains
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SsoProvider {
+    /// 🆔 Unique identifier for this provider
+    pub id: Uuid,
+    /// 🏢 Organization signing in through it
+    pub organization_id: Uuid,
+    /// 📜 Protocol spoken (only "oidc" for now)
+    pub protocol: String,
+    /// 🌐 OIDC issuer (discovery document at /.well-known/openid-configuration)
+    pub issuer_url: String,
+    /// 🆔 Client ID registered with the provider
+    pub client_id: String,
+    /// 🔒 Client secret (never sent back by the API)
+    #[serde(skip_serializing)]
+    pub client_secret: String,
+    /// 🎯 Scopes requested, space separated
+    pub scopes: String,
+    /// 📧 Email domains belonging to the organization
+    pub email_domains: Vec<String>,
+    /// 👥 ID token claim listing the user's groups
+    pub groups_claim: String,
+    /// 🗺️ Group name → organization role (JSON object)
+    pub role_mappings: serde_json::Value,
+    /// 👑 Role for users in no mapped group
+    pub default_role: String,
+    /// 🌱 Create accounts for unknown users on first sign-in
+    pub jit_provisioning: bool,
+    /// 🚫 Refuse password login for the email domains
+    pub enforce_sso: bool,
+    /// ✅ Whether sign-in is open
+    pub enabled: bool,
+    /// 📅 When the provider was configured
+    pub created_at: DateTime<Utc>,
+    /// 🔄 When the provider was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// ⚙️ Settings for an organization's identity provider
+#[derive(Debug, Clone, Deserialize)]
+pub struct SsoProviderSettings {
+    pub issuer_url: String,
+    pub client_id: String,
+    /// 🔒 Required the first time; left unchanged when omitted afterwards
+    pub client_secret: Option<String>,
+    #[serde(default = "SsoProviderSettings::default_scopes")]
+    pub scopes: String,
+    #[serde(default)]
+    pub email_domains: Vec<String>,
+    #[serde(default = "SsoProviderSettings::default_groups_claim")]
+    pub groups_claim: String,
+    #[serde(default)]
+    pub role_mappings: std::collections::HashMap<String, OrgRole>,
+    #[serde(default = "SsoProviderSettings::default_role")]
+    pub default_role: OrgRole,
+    #[serde(default = "SsoProviderSettings::default_true")]
+    pub jit_provisioning: bool,
+    #[serde(default)]
+    pub enforce_sso: bool,
+    #[serde(default = "SsoProviderSettings::default_true")]
+    pub enabled: bool,
+}
+
+impl SsoProviderSettings {
+    fn default_scopes() -> String {
+        "openid email profile".to_string()
+    }
+
+    fn default_groups_claim() -> String {
+        "groups".to_string()
+    }
+
+    fn default_role() -> OrgRole {
+        OrgRole::Member
+    }
+
+    fn default_true() -> bool {
+        true
+    }
+}
+
+/// 🎟️ A sign-in waiting for the provider to send the user back
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SsoLoginState {
+    /// 🎲 Random value round-tripped through the provider
+    pub state: String,
+    /// 🔐 Provider the user was sent to
+    pub provider_id: Uuid,
+    /// 🎲 Value the ID token must echo
+    pub nonce: String,
+    /// 🔑 PKCE verifier for the code exchange
+    pub code_verifier: String,
+    /// 📅 When the sign-in started
+    pub created_at: DateTime<Utc>,
+}
+
+impl SsoProvider {
+    /// 🔍 Find an organization's provider
+    pub async fn find_by_organization(pool: &PgPool, organization_id: Uuid) -> Result<Option<Self>> {
+        let provider = sqlx::query_as::<_, SsoProvider>(
+            "SELECT * FROM sso_providers WHERE organization_id = $1",
+        )
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch SSO provider")?;
+
+        Ok(provider)
+    }
+
+    /// 🔍 Enabled provider that requires single sign-on for this account, if any:
+    /// the account belongs to the provider's organization and its email to one
+    /// of the provider's domains. System admins are never held to it
+    pub async fn enforced_for_email(pool: &PgPool, email: &str) -> Result<Option<Self>> {
+        let provider = sqlx::query_as::<_, SsoProvider>(
+            "SELECT p.* FROM sso_providers p JOIN organization_members m ON m.organization_id = p.organization_id JOIN users u ON u.id = m.user_id \
+             WHERE p.enabled AND p.enforce_sso AND LOWER(u.email) = LOWER($1) AND u.role <> 'admin' AND LOWER(split_part($1, '@', 2)) = ANY(p.email_domains) LIMIT 1",
+        )
+        .bind(email)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch SSO provider")?;
+
+        Ok(provider)
+    }
+
+    /// 💾 Configure an organization's provider, replacing the previous settings
+    pub async fn upsert(
+        pool: &PgPool,
+        organization_id: Uuid,
+        settings: &SsoProviderSettings,
+    ) -> Result<Self> {
+        let email_domains: Vec<String> = settings
+            .email_domains
+            .iter()
+            .map(|domain| domain.trim().to_lowercase())
+            .collect();
+        let role_mappings = serde_json::to_value(&settings.role_mappings)
+            .context("Failed to serialize role mappings")?;
+        let provider = sqlx::query_as::<_, SsoProvider>(
+            "INSERT INTO sso_providers (organization_id, issuer_url, client_id, client_secret, scopes, email_domains, groups_claim, role_mappings, default_role, jit_provisioning, enforce_sso, enabled) \
+             VALUES ($1, $2, $3, COALESCE($4, ''), $5, $6, $7, $8, $9, $10, $11, $12) \
+             ON CONFLICT (organization_id) DO UPDATE SET issuer_url = EXCLUDED.issuer_url, client_id = EXCLUDED.client_id, \
+             client_secret = COALESCE($4, sso_providers.client_secret), scopes = EXCLUDED.scopes, email_domains = EXCLUDED.email_domains, \
+             groups_claim = EXCLUDED.groups_claim, role_mappings = EXCLUDED.role_mappings, default_role = EXCLUDED.default_role, \
+             jit_provisioning = EXCLUDED.jit_provisioning, enforce_sso = EXCLUDED.enforce_sso, enabled = EXCLUDED.enabled, updated_at = NOW() \
+             RETURNING *",
+        )
+        .bind(organization_id)
+        .bind(settings.issuer_url.trim_end_matches('/'))
+        .bind(&settings.client_id)
+        .bind(settings.client_secret.as_deref())
+        .bind(&settings.scopes)
+        .bind(&email_domains)
+        .bind(&settings.groups_claim)
+        .bind(role_mappings)
+        .bind(settings.default_role.as_str())
+        .bind(settings.jit_provisioning)
+        .bind(settings.enforce_sso)
+        .bind(settings.enabled)
+        .fetch_one(pool)
+        .await
+        .context("Failed to save SSO provider")?;
+
+        Ok(provider)
+    }
+
+    /// 🗑️ Remove an organization's provider; false when it had none
+    pub async fn delete(pool: &PgPool, organization_id: Uuid) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM sso_providers WHERE organization_id = $1")
+            .bind(organization_id)
+            .execute(pool)
+            .await
+            .context("Failed to delete SSO provider")?
+            .rows_affected();
+
+        Ok(deleted > 0)
+    }
+
+    /// 🪪 User linked to a provider account, if any
+    pub async fn linked_user(&self, pool: &PgPool, subject: &str) -> Result<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT u.* FROM users u JOIN user_identities i ON i.user_id = u.id WHERE i.provider_id = $1 AND i.subject = $2",
+        )
+        .bind(self.id)
+        .bind(subject)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch linked user")?;
+
+        Ok(user)
+    }
+
+    /// 🔗 Link a provider account to a user (again, when already linked)
+    pub async fn link_user(&self, pool: &PgPool, subject: &str, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO user_identities (provider_id, subject, user_id) VALUES ($1, $2, $3) ON CONFLICT (provider_id, subject) DO UPDATE SET last_login_at = NOW()",
+        )
+        .bind(self.id)
+        .bind(subject)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .context("Failed to link SSO identity")?;
+
+        Ok(())
+    }
+}
+
+impl SsoLoginState {
+    /// ➕ Remember a sign-in in progress
+    pub async fn create(
+        pool: &PgPool,
+        state: &str,
+        provider_id: Uuid,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO sso_login_states (state, provider_id, nonce, code_verifier) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(state)
+        .bind(provider_id)
+        .bind(nonce)
+        .bind(code_verifier)
+        .execute(pool)
+        .await
+        .context("Failed to save SSO login state")?;
+
+        Ok(())
+    }
+
+    /// 🎟️ Take a sign-in in progress (each state works once and only while
+    /// younger than `max_age_seconds`); expired ones are cleared on the way
+    pub async fn take(pool: &PgPool, state: &str, max_age_seconds: i64) -> Result<Option<Self>> {
+        sqlx::query("DELETE FROM sso_login_states WHERE created_at < NOW() - make_interval(secs => $1)")
+            .bind(max_age_seconds as f64)
+            .execute(pool)
+            .await
+            .context("Failed to clear expired SSO login states")?;
+        let login_state = sqlx::query_as::<_, SsoLoginState>(
+            "DELETE FROM sso_login_states WHERE state = $1 RETURNING *",
+        )
+        .bind(state)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch SSO login state")?;
+
+        Ok(login_state)
+    }
+}
+
+// 🔏 Signing Key Model - Key pairs for signing tokens
+// Exactly one key is current and signs new tokens; a rotation retires it, and
+// retired keys keep verifying (and stay in the JWKS) until they expire
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SigningKey {
+    /// 🏷️ Key ID, sent as the `kid` token header
+    pub kid: String,
+    /// 🔏 EdDSA or RS256
+    pub algorithm: String,
+    /// 🔒 Private key (PEM, never sent out)
+    #[serde(skip_serializing)]
+    pub private_key: String,
+    /// 🌐 Public half as a JWK, as published at /.well-known/jwks.json
+    pub public_jwk: serde_json::Value,
+    /// 📅 When the key was created
+    pub created_at: DateTime<Utc>,
+    /// 🛑 When it stopped signing (None = current key)
+    pub retired_at: Option<DateTime<Utc>>,
+    /// ⏳ When tokens signed with it stop being accepted (None = never)
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SigningKey {
+    /// 📋 Keys still accepted, current key first
+    pub async fn list_usable(pool: &PgPool) -> Result<Vec<Self>> {
+        let keys = sqlx::query_as::<_, SigningKey>(
+            "SELECT * FROM signing_keys WHERE expires_at IS NULL OR expires_at > NOW() ORDER BY retired_at DESC NULLS FIRST, created_at DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to list signing keys")?;
+
+        Ok(keys)
+    }
+
+    /// ➕ Add the first key, unless another instance already did; false when one existed
+    pub async fn create_initial(
+        pool: &PgPool,
+        kid: &str,
+        algorithm: &str,
+        private_key: &str,
+        public_jwk: &serde_json::Value,
+    ) -> Result<bool> {
+        let inserted = sqlx::query(
+            "INSERT INTO signing_keys (kid, algorithm, private_key, public_jwk) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        )
+        .bind(kid)
+        .bind(algorithm)
+        .bind(private_key)
+        .bind(public_jwk)
+        .execute(pool)
+        .await
+        .context("Failed to create signing key")?
+        .rows_affected();
+
+        Ok(inserted > 0)
+    }
+
+    /// 🔄 Make a new key current; the previous one keeps verifying for `overlap_hours`
+    pub async fn rotate(
+        pool: &PgPool,
+        kid: &str,
+        algorithm: &str,
+        private_key: &str,
+        public_jwk: &serde_json::Value,
+        overlap_hours: i64,
+    ) -> Result<Self> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start key rotation transaction")?;
+
+        sqlx::query(
+            "UPDATE signing_keys SET retired_at = NOW(), expires_at = NOW() + make_interval(hours => $1) WHERE retired_at IS NULL",
+        )
+        .bind(overlap_hours as i32)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to retire the current signing key")?;
+        let key = sqlx::query_as::<_, SigningKey>(
+            "INSERT INTO signing_keys (kid, algorithm, private_key, public_jwk) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(kid)
+        .bind(algorithm)
+        .bind(private_key)
+        .bind(public_jwk)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create signing key")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit key rotation")?;
+        Ok(key)
+    }
+
+    /// 🧹 Delete keys that no longer verify anything; returns how many
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64> {
+        let deleted = sqlx::query("DELETE FROM signing_keys WHERE expires_at <= NOW()")
+            .execute(pool)
+            .await
+            .context("Failed to delete expired signing keys")?
+            .rows_affected();
+
+        Ok(deleted)
+    }
+}
+
+impl User {
+    /// ➕ Create a new user
+    pub async fn create(
+        pool: &PgPool,
+        email: String,
+        name: String,
+        password_hash: String,
+        role: UserRole,
+    ) -> Result<Self> {
+        let user = sqlx::query_as::<_, User>(
+            "INSERT INTO users (email, name, password_hash, role) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(email)
+        .bind(name)
+        .bind(password_hash)
+        .bind(role)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create user")?;
+
+        Ok(user)
+    }
+
+    /// 🔑 Create a service account owned by an organization
+    pub async fn create_service_account(
+        pool: &PgPool,
+        email: &str,
+        name: &str,
+        password_hash: &str,
+        organization_id: Uuid,
+    ) -> Result<Self> {
+        let user = sqlx::query_as::<_, User>(
+            "INSERT INTO users (email, name, password_hash, role, organization_id) VALUES ($1, $2, $3, 'service', $4) RETURNING *",
+        )
+        .bind(email)
+        .bind(name)
+        .bind(password_hash)
+        .bind(organization_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create service account")?;
+
+        Ok(user)
+    }
+
+    /// 🔍 Find user by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch user")?;
+
+        Ok(user)
+    }
+
+    /// 🔍 Find user by email (case-insensitive)
+    pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<Self>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = LOWER($1)")
+            .bind(email)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch user")?;
+
+        Ok(user)
+    }
+
+    /// 📋 Active users with a role, oldest first
+    pub async fn list_active_by_role(pool: &PgPool, role: UserRole) -> Result<Vec<Self>> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE role = $1 AND is_active ORDER BY created_at",
+        )
+        .bind(role)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list users")?;
+
+        Ok(users)
+    }
+
+    /// 🔀 Columns account listings can be sorted by
+    pub const SORTABLE: &'static [&'static str] = &[
+        "created_at",
+        "updated_at",
+        "last_login_at",
+        "email",
+        "name",
+        "role",
+        "is_active",
+    ];
+
+    /// 📋 Every account in `order_by` (ORDER BY terms over SORTABLE columns),
+    /// with the total count
+    pub async fn list(
+        pool: &PgPool,
+        order_by: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<Self>, u64)> {
+        let users = sqlx::query_as::<_, User>(&format!(
+            "SELECT * FROM users ORDER BY {}, id LIMIT $1 OFFSET $2",
+            order_by
+        ))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list users")?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await
+            .context("Failed to count users")?;
+
+        Ok((users, total as u64))
+    }
+
+    /// 🚫 Activate or deactivate the account (inactive accounts can't sign in)
+    pub async fn set_active(&mut self, pool: &PgPool, is_active: bool) -> Result<()> {
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET is_active = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(is_active)
+        .fetch_one(pool)
+        .await
+        .context("Failed to change user status")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 👤 Save the fields people edit on their own profile
+    pub async fn save_profile(&mut self, pool: &PgPool) -> Result<()> {
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET name = $2, github_username = $3, default_llm_provider = $4, digest_frequency = $5, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(&self.name)
+        .bind(&self.github_username)
+        .bind(&self.default_llm_provider)
+        .bind(&self.digest_frequency)
+        .fetch_one(pool)
+        .await
+        .context("Failed to save profile")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🔒 Replace the user's password hash
+    pub async fn set_password_hash(pool: &PgPool, id: Uuid, password_hash: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET password_hash = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(password_hash)
+            .execute(pool)
+            .await
+            .context("Failed to save password")?;
+
+        Ok(())
+    }
+
+    /// 🌍 Save the user's language (None = follow their client's Accept-Language)
+    pub async fn set_locale(pool: &PgPool, id: Uuid, locale: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET locale = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(locale)
+            .execute(pool)
+            .await
+            .context("Failed to save user locale")?;
+
+        Ok(())
+    }
+
+    /// 📬 Save how often the user gets an activity digest
+    pub async fn set_digest_frequency(pool: &PgPool, id: Uuid, frequency: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET digest_frequency = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(frequency)
+            .execute(pool)
+            .await
+            .context("Failed to save digest frequency")?;
+
+        Ok(())
+    }
+
+    /// 📬 Active people who get activity digests (service accounts never do)
+    pub async fn list_digest_recipients(pool: &PgPool) -> Result<Vec<Self>> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE is_active AND role <> 'service' AND digest_frequency <> 'never' ORDER BY id",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to list digest recipients")?;
+
+        Ok(users)
+    }
+
+    /// 📬 Remember when the user's last digest went out
+    pub async fn mark_digest_sent(pool: &PgPool, id: Uuid, sent_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE users SET digest_sent_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(sent_at)
+            .execute(pool)
+            .await
+            .context("Failed to record digest")?;
+
+        Ok(())
+    }
+
+    /// 👑 Change the user's role
+    pub async fn set_role(&mut self, pool: &PgPool, role: UserRole) -> Result<()> {
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(role)
+        .fetch_one(pool)
+        .await
+        .context("Failed to change user role")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🚫 Reject every token issued so far, for one user or (None) everyone,
+    /// and drop their sessions. Returns how many users were affected
+    pub async fn revoke_tokens(pool: &PgPool, user_id: Option<Uuid>) -> Result<u64> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start revocation transaction")?;
+
+        let revoked = sqlx::query(
+            "UPDATE users SET tokens_valid_after = NOW(), updated_at = NOW() WHERE $1::uuid IS NULL OR id = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to revoke tokens")?
+        .rows_affected();
+        sqlx::query("DELETE FROM user_sessions WHERE $1::uuid IS NULL OR user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to delete sessions")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit revocation")?;
+        Ok(revoked)
+    }
+
+    /// 🔐 Note a single sign-on: the provider vouched for the email
+    pub async fn record_sso_login(&mut self, pool: &PgPool) -> Result<()> {
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET email_verified = TRUE, last_login_at = NOW(), updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record login")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🔑 Limit this service account's API key to scopes and a rate limit tier
+    /// (scopes None = every scope; applies to keys already issued, too)
+    pub async fn set_api_key_limits(
+        &mut self,
+        pool: &PgPool,
+        scopes: Option<Vec<String>>,
+        rate_limit_tier: &str,
+    ) -> Result<()> {
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET api_scopes = $2, rate_limit_tier = $3, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(self.id)
+        .bind(scopes)
+        .bind(rate_limit_tier)
+        .fetch_one(pool)
+        .await
+        .context("Failed to set API key limits")?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 🔏 Require signed requests from this service account with a key, or stop requiring them
+    pub async fn set_request_signing_key(&mut self, pool: &PgPool, key: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET request_signing_key = $2, updated_at = NOW() WHERE id = $1")
+            .bind(self.id)
+            .bind(key)
+            .execute(pool)
+            .await
+            .context("Failed to set request signing key")?;
+
+        self.request_signing_key = key.map(str::to_string);
+        Ok(())
+    }
+
+    /// ✅ Whether a token issued at `issued_at` (Unix seconds) is still accepted
+    pub fn accepts_token_issued_at(&self, issued_at: i64) -> bool {
+        !matches!(self.tokens_valid_after, Some(valid_after) if issued_at < valid_after.timestamp())
+    }
+
+    /// 🔐 Save whether the user gets security alerts by email
+    pub async fn set_security_alert_emails(pool: &PgPool, id: Uuid, enabled: bool) -> Result<()> {
+        sqlx::query("UPDATE users SET security_alert_emails = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(enabled)
+            .execute(pool)
+            .await
+            .context("Failed to save security alert preference")?;
+
+        Ok(())
+    }
+}
+
+impl Notification {
+    /// ➕ Notify a user
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        notification_type: NotificationType,
+        title: &str,
+        content: &str,
+        related_id: Option<Uuid>,
+    ) -> Result<Self> {
+        let notification = sqlx::query_as::<_, Notification>(
+            "INSERT INTO notifications (user_id, notification_type, title, content, related_id) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+        )
+        .bind(user_id)
+        .bind(notification_type)
+        .bind(title)
+        .bind(content)
+        .bind(related_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create notification")?;
+
+        Ok(notification)
+    }
+
+    /// 📋 A user's notifications, newest first
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT * FROM notifications WHERE user_id = $1 AND (NOT $2 OR NOT is_read) ORDER BY created_at DESC LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list notifications")?;
+
+        Ok(notifications)
+    }
+
+    /// 👀 Mark one of a user's notifications read (None when they have no such notification)
+    pub async fn mark_read(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<Option<Self>> {
+        let notification = sqlx::query_as::<_, Notification>(
+            "UPDATE notifications SET is_read = TRUE, read_at = COALESCE(read_at, NOW()) WHERE id = $1 AND user_id = $2 RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to mark notification read")?;
+
+        Ok(notification)
+    }
+}
+
+impl UserDevice {
+    /// 💻 Note a sign-in from a device; true when the user has signed in before,
+    /// but never from this one
+    pub async fn record_sign_in(
+        pool: &PgPool,
+        user_id: Uuid,
+        fingerprint: &str,
+        user_agent: &str,
+        ip_address: &str,
+    ) -> Result<bool> {
+        let (known_devices,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM user_devices WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(pool)
+                .await
+                .context("Failed to count user devices")?;
+        let (inserted,): (bool,) = sqlx::query_as(
+            "INSERT INTO user_devices (user_id, fingerprint, user_agent, ip_address) VALUES ($1, $2, $3, $4) ON CONFLICT (user_id, fingerprint) DO UPDATE SET user_agent = EXCLUDED.user_agent, ip_address = EXCLUDED.ip_address, last_seen_at = NOW() RETURNING (xmax = 0)",
+        )
+        .bind(user_id)
+        .bind(fingerprint)
+        .bind(user_agent)
+        .bind(ip_address)
+        .fetch_one(pool)
+        .await
+        .context("Failed to record user device")?;
+
+        Ok(inserted && known_devices > 0)
+    }
+}
+
+impl RateLimit {
+    /// 🔍 A client's stored window
+    pub async fn find(pool: &PgPool, id: &str) -> Result<Option<Self>> {
+        let rate_limit = sqlx::query_as::<_, RateLimit>("SELECT * FROM rate_limits WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to find rate limit")?;
+
+        Ok(rate_limit)
+    }
+
+    /// ➕ Add requests to a client's window, moving it forward first when it has ended
+    /// (windows stay aligned to the first one, so every instance counts the same ones)
+    pub async fn record(
+        pool: &PgPool,
+        id: &str,
+        limit_type: &str,
+        added: u32,
+        window: std::time::Duration,
+    ) -> Result<Self> {
+        let rate_limit = sqlx::query_as::<_, RateLimit>(
+            r#"
+            INSERT INTO rate_limits (id, limit_type, request_count, previous_count, window_start, last_request)
+            VALUES ($1, $2, $3, 0, NOW(), NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                previous_count = CASE
+                    WHEN NOW() < rate_limits.window_start + make_interval(secs => $4) THEN rate_limits.previous_count
+                    WHEN NOW() < rate_limits.window_start + make_interval(secs => $4 * 2) THEN rate_limits.request_count
+                    ELSE 0
+                END,
+                request_count = CASE
+                    WHEN NOW() < rate_limits.window_start + make_interval(secs => $4) THEN rate_limits.request_count + $3
+                    ELSE $3
+                END,
+                window_start = rate_limits.window_start + make_interval(
+                    secs => $4 * floor(EXTRACT(EPOCH FROM NOW() - rate_limits.window_start) / $4)
+                ),
+                last_request = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(limit_type)
+        .bind(added as i32)
+        .bind(window.as_secs_f64())
+        .fetch_one(pool)
+        .await
+        .context("Failed to record rate limit")?;
+
+        Ok(rate_limit)
+    }
+
+    /// 🧹 Delete the windows of clients not seen for `older_than`
+    pub async fn delete_stale(pool: &PgPool, older_than: std::time::Duration) -> Result<u64> {
+        let deleted = sqlx::query(
+            "DELETE FROM rate_limits WHERE last_request < NOW() - make_interval(secs => $1)",
+        )
+        .bind(older_than.as_secs_f64())
+        .execute(pool)
+        .await
+        .context("Failed to delete stale rate limits")?
+        .rows_affected();
+
+        Ok(deleted)
+    }
+}
+
+impl Project {
+    /// ➕ Create a new project
+    pub async fn create(
+        pool: &PgPool,
+        owner_id: Uuid,
+        repository: String,
+        description: Option<String>,
     ) -> Result<Self> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -353,10 +4839,228 @@ impl Project {
             created_at: now,
             updated_at: now,
             last_activity_at: None,
+            organization_id: None,
+            team_id: None,
         };
 
         Ok(project)
     }
+
+    /// 🔍 Find a project by ID
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch project")?;
+
+        Ok(project)
+    }
+
+    /// 🌍 The active project whose public status page has this slug
+    pub async fn find_by_public_slug(pool: &PgPool, slug: &str) -> Result<Option<Self>> {
+        let project = sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE is_active AND (config->'public_status'->>'enabled')::boolean IS TRUE AND config->'public_status'->>'slug' = $1 ORDER BY created_at LIMIT 1",
+        )
+        .bind(slug)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch project by status page slug")?;
+
+        Ok(project)
+    }
+
+    /// 🎯 Projects registered for a repository (one per owner)
+    pub async fn list_by_repository(pool: &PgPool, repository: &str) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE LOWER(repository) = LOWER($1) ORDER BY created_at",
+        )
+        .bind(repository)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch projects for repository")?;
+
+        Ok(projects)
+    }
+
+    /// 🏠 Repositories of projects owned by a GitHub account
+    pub async fn repositories_of_account(pool: &PgPool, account: &str) -> Result<Vec<String>> {
+        let repositories = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT repository FROM projects WHERE LOWER(split_part(repository, '/', 1)) = LOWER($1) ORDER BY repository",
+        )
+        .bind(account)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch repositories of account")?;
+
+        Ok(repositories)
+    }
+
+    /// 🔌 Activate or deactivate every project of a repository; returns the ones that changed
+    pub async fn set_active_for_repository(
+        pool: &PgPool,
+        repository: &str,
+        is_active: bool,
+    ) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "UPDATE projects SET is_active = $2, updated_at = NOW() WHERE LOWER(repository) = LOWER($1) AND is_active <> $2 RETURNING *",
+        )
+        .bind(repository)
+        .bind(is_active)
+        .fetch_all(pool)
+        .await
+        .context("Failed to update project activity")?;
+
+        Ok(projects)
+    }
+
+    /// 👥 Active projects that mirror their repository's collaborators
+    pub async fn list_collaborator_sync(pool: &PgPool) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE is_active AND (config->'collaborators'->>'sync')::boolean IS TRUE ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch collaborator-synced projects")?;
+
+        Ok(projects)
+    }
+
+    /// 🎨 Active projects whose style was never analyzed or was analyzed more
+    /// than `max_age_days` ago, never-analyzed and oldest first
+    pub async fn list_style_analysis_due(
+        pool: &PgPool,
+        max_age_days: i32,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT p.* FROM projects p LEFT JOIN project_style_profiles s ON s.project_id = p.id WHERE p.is_active AND (s.analyzed_at IS NULL OR s.analyzed_at < NOW() - make_interval(days => $1)) ORDER BY s.analyzed_at NULLS FIRST, p.created_at LIMIT $2",
+        )
+        .bind(max_age_days)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch projects due a style analysis")?;
+
+        Ok(projects)
+    }
+
+    /// 🩺 Active projects that opted in to scheduled health scans
+    pub async fn list_scan_enabled(pool: &PgPool) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE is_active AND (config->'scans'->>'enabled')::boolean IS TRUE",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch scan-enabled projects")?;
+
+        Ok(projects)
+    }
+
+    /// 📋 Projects a user can see: their own, and those of their organizations
+    /// (team-limited projects only for the team and organization admins).
+    /// Everything when `user_id` is None; only one organization's when `organization_id` is set
+    pub async fn list_visible(
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+    ) -> Result<Vec<Self>> {
+        let projects = sqlx::query_as::<_, Project>(
+            "SELECT p.* FROM projects p WHERE ($2::uuid IS NULL OR p.organization_id = $2) AND ($1::uuid IS NULL OR p.owner_id = $1 OR EXISTS (SELECT 1 FROM organization_members m WHERE m.organization_id = p.organization_id AND m.user_id = $1 AND (p.team_id IS NULL OR m.role IN ('admin', 'owner') OR EXISTS (SELECT 1 FROM team_members t WHERE t.team_id = p.team_id AND t.user_id = $1)))) ORDER BY p.repository",
+        )
+        .bind(user_id)
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list projects")?;
+
+        Ok(projects)
+    }
+
+    /// 🏢 Move the project into an organization (and team), or back out with None
+    pub async fn set_organization(
+        &mut self,
+        pool: &PgPool,
+        organization_id: Option<Uuid>,
+        team_id: Option<Uuid>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE projects SET organization_id = $2, team_id = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(self.id)
+        .bind(organization_id)
+        .bind(team_id)
+        .execute(pool)
+        .await
+        .context("Failed to update project organization")?;
+
+        self.organization_id = organization_id;
+        self.team_id = team_id;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// ⚙️ Typed view of the JSONB config column
+    pub fn settings(&self) -> Result<ProjectConfig> {
+        ProjectConfig::from_json(self.config.as_ref())
+    }
+
+    /// 💾 Save the project configuration
+    /// Merged over the stored JSONB, so keys this version doesn't know about
+    /// (written by a newer release or by hand) survive the update
+    pub async fn update_config(&mut self, pool: &PgPool, config: &ProjectConfig) -> Result<()> {
+        let value: serde_json::Value = sqlx::query_scalar(
+            "UPDATE projects SET config = COALESCE(config, '{}'::jsonb) || $1 \
+             WHERE id = $2 RETURNING config",
+        )
+        .bind(config.to_json())
+        .bind(self.id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to update project config")?;
+
+        self.config = Some(value);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// 🔢 Add the tokens of one LLM call to a project's usage this month
+    pub async fn add_token_usage(
+        pool: &PgPool,
+        id: Uuid,
+        prompt_tokens: i64,
+        completion_tokens: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO project_token_usage (project_id, month, prompt_tokens, completion_tokens) \
+             VALUES ($1, date_trunc('month', NOW() AT TIME ZONE 'UTC')::date, $2, $3) \
+             ON CONFLICT (project_id, month) DO UPDATE SET \
+             prompt_tokens = project_token_usage.prompt_tokens + $2, \
+             completion_tokens = project_token_usage.completion_tokens + $3",
+        )
+        .bind(id)
+        .bind(prompt_tokens)
+        .bind(completion_tokens)
+        .execute(pool)
+        .await
+        .context("Failed to record project token usage")?;
+
+        Ok(())
+    }
+
+    /// 📊 Tokens a project used this calendar month (UTC)
+    pub async fn monthly_token_usage(pool: &PgPool, id: Uuid) -> Result<i64> {
+        let used: Option<i64> = sqlx::query_scalar(
+            "SELECT prompt_tokens + completion_tokens FROM project_token_usage \
+             WHERE project_id = $1 AND month = date_trunc('month', NOW() AT TIME ZONE 'UTC')::date",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch project token usage")?;
+
+        Ok(used.unwrap_or(0))
+    }
 }
 
 // 🧪 Tests - Making sure our models work perfectly!
@@ -397,4 +5101,113 @@ mod tests {
         );
         println!("✅ Feedback stats test passed!");
     }
+
+    #[test]
+    fn test_job_retry_delay() {
+        assert_eq!(BackgroundJob::retry_delay(1).num_seconds(), 30);
+        assert_eq!(BackgroundJob::retry_delay(2).num_seconds(), 60);
+        assert_eq!(BackgroundJob::retry_delay(4).num_seconds(), 240);
+        assert_eq!(BackgroundJob::retry_delay(20).num_seconds(), 3600);
+        println!("✅ Job retry delay test passed!");
+    }
+
+    #[test]
+    fn test_job_priority_lanes() {
+        assert!(JobPriority::Interactive > JobPriority::Normal);
+        assert!(JobPriority::Normal > JobPriority::Bulk);
+        for lane in [
+            JobPriority::Bulk,
+            JobPriority::Normal,
+            JobPriority::Interactive,
+        ] {
+            assert_eq!(JobPriority::from_value(lane.value()), lane);
+        }
+        assert_eq!(JobPriority::from_value(75), JobPriority::Normal);
+
+        let job = NewBackgroundJob::new("repository_scan", serde_json::json!({}))
+            .with_priority(JobPriority::Bulk);
+        assert_eq!(job.priority, JobPriority::Bulk);
+        assert_eq!(job.max_retries, 3);
+        println!("✅ Job priority lanes test passed!");
+    }
+
+    #[test]
+    fn test_token_revocation_cutoff() {
+        let revoked_at = Utc::now();
+        let mut user = User {
+            id: Uuid::new_v4(),
+            email: "ops@example.com".to_string(),
+            name: "Ops".to_string(),
+            github_username: None,
+            password_hash: String::new(),
+            email_verified: false,
+            role: UserRole::Service,
+            is_active: true,
+            created_at: revoked_at,
+            updated_at: revoked_at,
+            last_login_at: None,
+            tokens_valid_after: None,
+            organization_id: None,
+            api_scopes: None,
+            rate_limit_tier: None,
+            locale: None,
+            digest_frequency: "weekly".to_string(),
+            digest_sent_at: None,
+            default_llm_provider: None,
+            security_alert_emails: true,
+            request_signing_key: None,
+        };
+        assert!(user.accepts_token_issued_at(0));
+
+        user.tokens_valid_after = Some(revoked_at);
+        assert!(!user.accepts_token_issued_at(revoked_at.timestamp() - 60));
+        // 🎫 Tokens minted right after the revocation, in the same second, still work
+        assert!(user.accepts_token_issued_at(revoked_at.timestamp()));
+        println!("✅ Token revocation cutoff test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_update_config_keeps_unknown_keys() {
+        // This test only runs if we have a (migrated) test database available
+        let Ok(database_url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&database_url).await.unwrap();
+
+        let owner = User::create(
+            &pool,
+            format!("{}@8b.is", Uuid::new_v4()),
+            "Aye".to_string(),
+            "hash".to_string(),
+            UserRole::User,
+        )
+        .await
+        .unwrap();
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO projects (owner_id, repository, config) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(owner.id)
+        .bind("aye-is/config-merge")
+        .bind(serde_json::json!({ "path": "old", "from_the_future": { "enabled": true } }))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut project = Project::find_by_id(&pool, id).await.unwrap().unwrap();
+        let mut settings = project.settings().unwrap();
+        settings.path = Some("crates/foo".to_string());
+        project.update_config(&pool, &settings).await.unwrap();
+
+        let stored = Project::find_by_id(&pool, id).await.unwrap().unwrap().config.unwrap();
+        assert_eq!(stored["path"], "crates/foo");
+        assert_eq!(stored["from_the_future"]["enabled"], true);
+        assert_eq!(project.config.as_ref(), Some(&stored));
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(owner.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        println!("✅ Config merge test passed!");
+    }
 }
@@ -0,0 +1,269 @@
+// 🐢 Query Metrics - Every Statement Timed, Slow Ones Called Out! 🐢
+// sqlx reports each statement it runs as a `sqlx::query` tracing event carrying
+// its SQL and elapsed time, and flags the ones over DATABASE_SLOW_QUERY_MS. The
+// `QueryMetricsLayer` listens for those events: every statement lands in a
+// latency histogram labelled by operation and table, and slow ones are logged
+// with their shape (the SQL with its `$n` placeholders, never the bound values)
+// and kept in a short list for the detailed health check
+// Created with love by Aye & Hue - No query hides from us! ✨
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::{warn, Event, Subscriber};
+use tracing_subscriber::filter::{Filtered, Targets};
+use tracing_subscriber::layer::{Context, Layer};
+
+use crate::metrics;
+
+/// 🎯 Target sqlx reports statements under
+pub const QUERY_TARGET: &str = "sqlx::query";
+
+/// 📋 Slow queries kept for the health check
+const RECENT_SLOW_QUERIES: usize = 20;
+
+/// 📏 Longest statement shape logged
+const MAX_SHAPE_LEN: usize = 500;
+
+/// 🐢 The latest slow queries, oldest first
+static RECENT_SLOW: Mutex<VecDeque<SlowQuery>> = Mutex::new(VecDeque::new());
+
+/// 🐢 A statement that took longer than the slow query threshold
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowQuery {
+    /// ⏰ When it finished
+    pub at: DateTime<Utc>,
+    /// 🏷️ select, insert, update, delete, ...
+    pub operation: &'static str,
+    /// 🗃️ Table it reads or writes ("-" when it can't be told)
+    pub table: String,
+    /// ⏱️ How long it took
+    pub elapsed_ms: u64,
+    /// 🔢 Number of bound parameters
+    pub params: usize,
+    /// 📝 The SQL, whitespace collapsed and truncated
+    pub statement: String,
+}
+
+/// 🐢 Layer turning sqlx statement events into metrics and slow query logs
+pub struct QueryMetricsLayer;
+
+impl QueryMetricsLayer {
+    /// 🎯 The layer, seeing sqlx statements only (whatever the log filter lets through)
+    pub fn filtered<S: Subscriber>() -> Filtered<Self, Targets, S> {
+        QueryMetricsLayer
+            .with_filter(Targets::new().with_target(QUERY_TARGET, tracing::Level::TRACE))
+    }
+}
+
+impl<S: Subscriber> Layer<S> for QueryMetricsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut query = QueryEvent::default();
+        event.record(&mut query);
+        if let Some(elapsed_secs) = query.elapsed_secs {
+            record_query(
+                query.sql(),
+                Duration::from_secs_f64(elapsed_secs),
+                query.slow,
+            );
+        }
+    }
+}
+
+/// 📋 Fields of one sqlx statement event
+#[derive(Default)]
+struct QueryEvent {
+    summary: String,
+    statement: String,
+    elapsed_secs: Option<f64>,
+    slow: bool,
+}
+
+impl QueryEvent {
+    /// 📝 The full SQL (sqlx only sends it separately when the summary is cut short)
+    fn sql(&self) -> &str {
+        if self.statement.trim().is_empty() {
+            &self.summary
+        } else {
+            &self.statement
+        }
+    }
+}
+
+impl Visit for QueryEvent {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, _value: &dyn std::fmt::Debug) {
+        // ⏰ Only slow statements carry their threshold
+        if field.name() == "slow_threshold" {
+            self.slow = true;
+        }
+    }
+}
+
+/// 📈 Record one statement, and log it if it was slow
+pub fn record_query(sql: &str, elapsed: Duration, slow: bool) {
+    let (operation, table) = query_label(sql);
+    metrics::observe_db_query(operation, &table, elapsed, slow);
+    if !slow {
+        return;
+    }
+
+    let query = SlowQuery {
+        at: Utc::now(),
+        operation,
+        table,
+        elapsed_ms: elapsed.as_millis() as u64,
+        params: param_count(sql),
+        statement: statement_shape(sql),
+    };
+    warn!(
+        "🐢 Slow {} on {} took {} ms ({} params): {}",
+        query.operation, query.table, query.elapsed_ms, query.params, query.statement
+    );
+
+    let mut recent = RECENT_SLOW.lock().unwrap_or_else(|e| e.into_inner());
+    if recent.len() == RECENT_SLOW_QUERIES {
+        recent.pop_front();
+    }
+    recent.push_back(query);
+}
+
+/// 📋 The latest slow queries, newest first
+pub fn recent_slow_queries() -> Vec<SlowQuery> {
+    let recent = RECENT_SLOW.lock().unwrap_or_else(|e| e.into_inner());
+    recent.iter().rev().cloned().collect()
+}
+
+/// 🏷️ Operation and table of a statement, for metric labels
+pub fn query_label(sql: &str) -> (&'static str, String) {
+    let words: Vec<&str> = sql.split_whitespace().collect();
+    let first = words.first().map(|word| word.to_ascii_lowercase());
+    let operation = match first.as_deref() {
+        Some("select") => "select",
+        Some("insert") => "insert",
+        Some("update") => "update",
+        Some("delete") => "delete",
+        Some("with") => "with",
+        Some("begin" | "commit" | "rollback") => "transaction",
+        _ => "other",
+    };
+
+    // 🗃️ The table follows INTO, UPDATE, or (for everything else) the first FROM
+    let keyword = match operation {
+        "insert" => "into",
+        "update" => "update",
+        _ => "from",
+    };
+    let table = words
+        .windows(2)
+        .find(|pair| pair[0].eq_ignore_ascii_case(keyword))
+        .map(|pair| {
+            pair[1]
+                .trim_matches(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                .to_ascii_lowercase()
+        })
+        .filter(|table| {
+            !table.is_empty()
+                && table
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        })
+        .unwrap_or_else(|| "-".to_string());
+
+    (operation, table)
+}
+
+/// 🔢 Number of bound parameters (the highest `$n` placeholder)
+pub fn param_count(sql: &str) -> usize {
+    sql.split('$')
+        .skip(1)
+        .filter_map(|rest| {
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// 📝 A statement on one line, cut to a loggable length
+pub fn statement_shape(sql: &str) -> String {
+    let shape = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    match shape.char_indices().nth(MAX_SHAPE_LEN) {
+        Some((cut, _)) => format!("{}…", &shape[..cut]),
+        None => shape,
+    }
+}
+
+// 🧪 Tests - Every query gets the right name tag!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_labels_and_shapes() {
+        assert_eq!(
+            query_label("SELECT id, name FROM users WHERE id = $1"),
+            ("select", "users".to_string())
+        );
+        assert_eq!(
+            query_label("INSERT INTO feedback (id, content) VALUES ($1, $2)"),
+            ("insert", "feedback".to_string())
+        );
+        assert_eq!(
+            query_label("update background_jobs SET status = 'done'"),
+            ("update", "background_jobs".to_string())
+        );
+        assert_eq!(
+            query_label("SELECT EXISTS (SELECT 1 FROM information_schema.tables)"),
+            ("select", "information_schema.tables".to_string())
+        );
+        assert_eq!(query_label("SELECT 1"), ("select", "-".to_string()));
+        assert_eq!(query_label("VACUUM"), ("other", "-".to_string()));
+
+        let sql = "UPDATE jobs\n    SET a = $1,\n        b = $12\n  WHERE id = $2";
+        assert_eq!(param_count(sql), 12);
+        assert_eq!(param_count("SELECT 1"), 0);
+        assert_eq!(
+            statement_shape(sql),
+            "UPDATE jobs SET a = $1, b = $12 WHERE id = $2"
+        );
+        assert!(statement_shape(&"x ".repeat(1000)).ends_with('…'));
+        println!("✅ Query label test passed!");
+    }
+
+    #[test]
+    fn test_slow_queries_are_kept() {
+        record_query(
+            "SELECT * FROM slow_test_table WHERE id = $1",
+            Duration::from_millis(1200),
+            true,
+        );
+        record_query("SELECT 1", Duration::from_millis(1), false);
+
+        let recent = recent_slow_queries();
+        let slow = recent
+            .iter()
+            .find(|query| query.table == "slow_test_table")
+            .expect("slow query is kept");
+        assert_eq!(slow.elapsed_ms, 1200);
+        assert_eq!(slow.params, 1);
+        assert!(recent.iter().all(|query| query.statement != "SELECT 1"));
+        println!("✅ Slow query tracking test passed!");
+    }
+}
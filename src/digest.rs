@@ -0,0 +1,423 @@
+// 📬 Activity Digests - What Happened in Your Projects! 📬
+// Every morning the `email_digests` job goes through the users whose digest has
+// come due (daily or weekly, their choice; see PUT /api/users/me/preferences)
+// and emails each one a summary of the projects they can see: new feedback,
+// pull requests opened and merged, and the failures that need attention. The
+// email is rendered from templates/email/digest.{html,txt} in the user's
+// language, and ends with a signed link that turns digests off without signing
+// in. Quiet periods send nothing. Needs SMTP_HOST and ENABLE_EMAIL_NOTIFICATIONS
+// Created with love by Aye & Hue - Your week, in one email! ✨
+
+use anyhow::{Context, Result};
+use askama::Template;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::Config;
+use crate::database::models::{Feedback, Project, RepositoryActivity, User};
+use crate::email::{Mailer, OutgoingEmail};
+use crate::i18n::{self, Locale};
+use crate::jobs::worker::{self, JobHandler};
+use crate::pipeline::pr_description::tracking_url;
+use crate::utils::{signed_links, text};
+
+/// 🏷️ Job type of the digest run
+pub const DIGEST_JOB: &str = "email_digests";
+
+/// ❌ Failures listed by name in one digest
+const MAX_FAILURES_LISTED: i64 = 5;
+
+/// ✂️ Characters of a failed feedback item shown in a digest
+const PREVIEW_LENGTH: usize = 100;
+
+/// ⏳ A digest counts as due this long before its period is fully up, so the
+/// daily run doesn't skip a day when yesterday's went out a few minutes late
+const DUE_SLACK_HOURS: i64 = 1;
+
+/// 🗓️ How often a user gets a digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    /// 📋 Every frequency, as accepted in preferences
+    pub const ALL: [DigestFrequency; 3] = [
+        DigestFrequency::Never,
+        DigestFrequency::Daily,
+        DigestFrequency::Weekly,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DigestFrequency::Never => "never",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    /// ⏱️ Time covered by one digest (None when digests are off)
+    pub fn period(self) -> Option<Duration> {
+        match self {
+            DigestFrequency::Never => None,
+            DigestFrequency::Daily => Some(Duration::days(1)),
+            DigestFrequency::Weekly => Some(Duration::weeks(1)),
+        }
+    }
+}
+
+impl FromStr for DigestFrequency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        DigestFrequency::ALL
+            .into_iter()
+            .find(|frequency| frequency.as_str() == s)
+            .with_context(|| format!("Unknown digest frequency '{}'", s))
+    }
+}
+
+/// ⏰ Whether a digest is due, given when the last one went out
+pub fn is_due(
+    frequency: DigestFrequency,
+    last_sent: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(period) = frequency.period() else {
+        return false;
+    };
+    last_sent.is_none_or(|sent| now - sent >= period - Duration::hours(DUE_SLACK_HOURS))
+}
+
+/// 🕰️ Where a digest's period starts: the last digest, but never further back
+/// than one period
+pub fn period_start(
+    period: Duration,
+    last_sent: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> DateTime<Utc> {
+    let earliest = now - period;
+    last_sent.map_or(earliest, |sent| sent.max(earliest))
+}
+
+/// 🏷️ Domain unsubscribe links are signed under
+const UNSUBSCRIBE_DOMAIN: &str = "digest-unsubscribe";
+
+/// 🔗 Signed link that turns a user's digests off
+pub fn unsubscribe_url(public_url: &str, secret: &str, user_id: Uuid) -> String {
+    let signature = signed_links::sign(secret, UNSUBSCRIBE_DOMAIN, &[&user_id]);
+    format!(
+        "{}/digest/unsubscribe?user={}&signature={}",
+        public_url.trim_end_matches('/'),
+        user_id,
+        signature
+    )
+}
+
+/// ✅ Whether an unsubscribe link is genuine
+pub fn verify_unsubscribe(secret: &str, user_id: Uuid, signature: &str) -> bool {
+    signed_links::verify(secret, UNSUBSCRIBE_DOMAIN, &[&user_id], signature)
+}
+
+/// 📦 One project's part of a digest
+struct ProjectDigest {
+    repository: String,
+    url: String,
+    /// 📝 One line per kind of activity there was
+    lines: Vec<String>,
+}
+
+/// ❌ A failed feedback item listed in a digest
+struct FailureDigest {
+    repository: String,
+    preview: String,
+    url: String,
+}
+
+/// 📬 A digest, with every message already in the recipient's language
+struct Digest {
+    locale: Locale,
+    subject: String,
+    greeting: String,
+    intro: String,
+    projects: Vec<ProjectDigest>,
+    needs_attention: String,
+    failures: Vec<FailureDigest>,
+    view_project: String,
+    footer: String,
+    unsubscribe: String,
+    unsubscribe_url: String,
+}
+
+/// 🎨 The HTML part
+#[derive(Template)]
+#[template(path = "email/digest.html")]
+struct DigestHtml<'a> {
+    digest: &'a Digest,
+}
+
+/// 📝 The plain text part
+#[derive(Template)]
+#[template(path = "email/digest.txt")]
+struct DigestText<'a> {
+    digest: &'a Digest,
+}
+
+impl Digest {
+    /// ✉️ The digest as an email to `to`
+    fn email(&self, to: &str) -> Result<OutgoingEmail> {
+        Ok(OutgoingEmail {
+            to: to.to_string(),
+            subject: self.subject.clone(),
+            html: DigestHtml { digest: self }
+                .render()
+                .context("Failed to render digest")?,
+            text: DigestText { digest: self }
+                .render()
+                .context("Failed to render digest")?,
+        })
+    }
+}
+
+/// 📝 The lines summarizing one repository's activity (quiet kinds left out)
+fn activity_lines(activity: &RepositoryActivity) -> Vec<String> {
+    [
+        ("digest-new-feedback", activity.new_feedback),
+        ("digest-pull-requests-opened", activity.pull_requests_opened),
+        ("digest-pull-requests-merged", activity.pull_requests_merged),
+        ("digest-failed", activity.failed),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(id, count)| i18n::t_with(id, [("count", count.into())]))
+    .collect()
+}
+
+/// 📬 A user's digest for the period since `since` (None when nothing happened)
+async fn build_digest(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    frequency: DigestFrequency,
+    since: DateTime<Utc>,
+) -> Result<Option<Digest>> {
+    let projects = Project::list_visible(pool, Some(user.id), None).await?;
+    let repositories: Vec<String> = projects
+        .iter()
+        .map(|project| project.repository.clone())
+        .collect();
+    if repositories.is_empty() {
+        return Ok(None);
+    }
+    let activity = Feedback::activity_by_repository(pool, &repositories, since).await?;
+    if activity.is_empty() {
+        return Ok(None);
+    }
+    let failed =
+        Feedback::list_failed_since(pool, &repositories, since, MAX_FAILURES_LISTED).await?;
+
+    let public_url = config.server.public_url.trim_end_matches('/');
+    let frequency = || [("frequency", frequency.as_str().into())];
+    let project_digests = activity
+        .iter()
+        .filter_map(|activity| {
+            let project = projects
+                .iter()
+                .find(|project| project.repository == activity.repository)?;
+            Some(ProjectDigest {
+                repository: activity.repository.clone(),
+                url: format!("{}/projects/{}", public_url, project.id),
+                lines: activity_lines(activity),
+            })
+        })
+        .collect();
+    let failures = failed
+        .into_iter()
+        .map(|feedback| FailureDigest {
+            preview: text::preview(&feedback.content, PREVIEW_LENGTH),
+            url: tracking_url(public_url, feedback.id),
+            repository: feedback.repository,
+        })
+        .collect();
+
+    Ok(Some(Digest {
+        locale: Locale::current(),
+        subject: i18n::t_with("digest-subject", frequency()),
+        greeting: i18n::t_with("digest-greeting", [("name", user.name.as_str().into())]),
+        intro: i18n::t_with("digest-intro", frequency()),
+        projects: project_digests,
+        needs_attention: i18n::t("digest-needs-attention"),
+        failures,
+        view_project: i18n::t("digest-view-project"),
+        footer: i18n::t_with("digest-footer", frequency()),
+        unsubscribe: i18n::t("digest-unsubscribe"),
+        unsubscribe_url: unsubscribe_url(public_url, &config.auth.jwt_secret, user.id),
+    }))
+}
+
+/// 🔧 Worker pool handler that sends the digests that have come due
+pub fn digest_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |_job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        async move { send_due_digests(&db_pool, &config).await }
+    })
+}
+
+/// 📮 Send every digest that has come due; one user's failure doesn't stop the rest
+async fn send_due_digests(pool: &PgPool, config: &Config) -> Result<()> {
+    let email = match &config.email {
+        Some(email) if config.features.enable_email_notifications => email,
+        _ => {
+            info!("📬 Email notifications are off, no digests to send");
+            return Ok(());
+        }
+    };
+    let mailer = Mailer::new(email)?;
+
+    let now = Utc::now();
+    let (mut sent, mut failed) = (0, 0);
+    for user in User::list_digest_recipients(pool).await? {
+        let frequency = match user.digest_frequency.parse::<DigestFrequency>() {
+            Ok(frequency) => frequency,
+            Err(e) => {
+                warn!("⚠️ Skipping digest for {}: {:#}", user.email, e);
+                continue;
+            }
+        };
+        let Some(period) = frequency.period() else {
+            continue;
+        };
+        if !is_due(frequency, user.digest_sent_at, now) {
+            continue;
+        }
+
+        let since = period_start(period, user.digest_sent_at, now);
+        let locale: Locale = user
+            .locale
+            .as_deref()
+            .and_then(|code| code.parse().ok())
+            .unwrap_or_default();
+        let outcome = locale
+            .scope(async {
+                if let Some(digest) = build_digest(pool, config, &user, frequency, since).await? {
+                    mailer.send(&digest.email(&user.email)?).await?;
+                    sent += 1;
+                }
+                // 🤫 Quiet periods count as sent, so the next digest starts here
+                User::mark_digest_sent(pool, user.id, now).await
+            })
+            .await;
+        if let Err(e) = outcome {
+            failed += 1;
+            warn!("⚠️ Digest for {} failed: {:#}", user.email, e);
+        }
+    }
+
+    info!("📬 Sent {} digests ({} failed)", sent, failed);
+    Ok(())
+}
+
+// 🧪 Tests - Delivered on schedule!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_digest_schedule() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 8, 0, 0).unwrap();
+        assert_eq!(
+            "weekly".parse::<DigestFrequency>().unwrap(),
+            DigestFrequency::Weekly
+        );
+        assert!("hourly".parse::<DigestFrequency>().is_err());
+
+        assert!(!is_due(DigestFrequency::Never, None, now));
+        assert!(is_due(DigestFrequency::Weekly, None, now));
+        assert!(!is_due(
+            DigestFrequency::Weekly,
+            Some(now - Duration::days(3)),
+            now
+        ));
+        // ⏳ Yesterday's run went out a few minutes late
+        let late = now - Duration::days(1) + Duration::minutes(5);
+        assert!(is_due(DigestFrequency::Daily, Some(late), now));
+
+        let week = Duration::weeks(1);
+        assert_eq!(period_start(week, None, now), now - week);
+        assert_eq!(period_start(week, Some(late), now), late);
+        assert_eq!(
+            period_start(week, Some(now - Duration::days(30)), now),
+            now - week
+        );
+        println!("✅ Digest schedule test passed!");
+    }
+
+    #[test]
+    fn test_unsubscribe_links() {
+        let user_id = Uuid::new_v4();
+        let url = unsubscribe_url("https://feedbacker.example.com/", "secret", user_id);
+        assert!(url.starts_with(&format!(
+            "https://feedbacker.example.com/digest/unsubscribe?user={}&signature=",
+            user_id
+        )));
+        let signature = url.rsplit('=').next().unwrap();
+        assert!(verify_unsubscribe("secret", user_id, signature));
+        assert!(!verify_unsubscribe("other secret", user_id, signature));
+        assert!(!verify_unsubscribe("secret", Uuid::new_v4(), signature));
+        assert!(!verify_unsubscribe("secret", user_id, "not hex"));
+        println!("✅ Unsubscribe link test passed!");
+    }
+
+    #[test]
+    fn test_digest_email() {
+        let digest = Digest {
+            locale: Locale::English,
+            subject: "📬 Your weekly Feedbacker digest".to_string(),
+            greeting: "Hi Hue,".to_string(),
+            intro: "Here's what happened in your projects this week.".to_string(),
+            projects: vec![ProjectDigest {
+                repository: "aye-is/feedbacker".to_string(),
+                url: "https://feedbacker.example.com/projects/1".to_string(),
+                lines: activity_lines(&RepositoryActivity {
+                    repository: "aye-is/feedbacker".to_string(),
+                    new_feedback: 3,
+                    pull_requests_merged: 1,
+                    ..Default::default()
+                }),
+            }],
+            needs_attention: "❌ Needs attention".to_string(),
+            failures: vec![FailureDigest {
+                repository: "aye-is/feedbacker".to_string(),
+                preview: "Support <dark> mode".to_string(),
+                url: "https://feedbacker.example.com/feedback/1/diff".to_string(),
+            }],
+            view_project: "View project".to_string(),
+            footer: "You get this digest every week.".to_string(),
+            unsubscribe: "Unsubscribe".to_string(),
+            unsubscribe_url: "https://feedbacker.example.com/digest/unsubscribe".to_string(),
+        };
+        assert_eq!(digest.projects[0].lines.len(), 2);
+
+        let email = digest.email("hue@example.com").unwrap();
+        assert!(email.html.contains("3 new feedback items"));
+        assert!(email.html.contains("Support &lt;dark&gt; mode"));
+        assert!(email.text.contains("- 1 pull request merged"));
+        assert!(email.text.contains("Support <dark> mode"));
+        assert!(email
+            .text
+            .contains("Unsubscribe: https://feedbacker.example.com/digest/unsubscribe"));
+        println!("✅ Digest email test passed!");
+    }
+}
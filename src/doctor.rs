@@ -0,0 +1,540 @@
+// 🩺 Doctor - Is Everything Plugged In? 🩺
+// Two commands for checking a deployment before (or instead of) starting it:
+//   feedbacker check-config    📋 load and validate the configuration, offline
+//   feedbacker doctor          🩺 the same, then probe every dependency for real:
+//                                 database connection and migrations, GitHub
+//                                 token and its scopes, LLM API keys, SMTP login
+// Both print one line per check and exit non-zero when any check failed.
+// Warnings (a missing token scope, pending migrations) don't fail the run
+// Created with love by Aye & Hue - An ounce of prevention! ✨
+
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, EmailConfig, LlmProvider, MigrationDrift, TlsMode};
+use crate::database::{self, migrations::MigrationState};
+use crate::email;
+use crate::llm::LlmManager;
+use crate::secrets;
+
+/// ⏱️ How long one probe may take before it counts as failed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 🔑 Token scopes the pipeline needs (pushing branches, opening PRs, touching workflows)
+const GITHUB_SCOPES: &[&str] = &["repo", "workflow"];
+
+/// 🚦 Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// ⏭️ Not configured, so nothing to check
+    Skip,
+}
+
+impl CheckStatus {
+    fn icon(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️",
+            CheckStatus::Fail => "❌",
+            CheckStatus::Skip => "⏭️",
+        }
+    }
+}
+
+/// 🔍 One line of the report
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// 📋 Checks in the order they ran
+#[derive(Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, name: &str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    fn pass(&mut self, name: &str, detail: impl Into<String>) {
+        self.add(name, CheckStatus::Pass, detail);
+    }
+
+    fn warn(&mut self, name: &str, detail: impl Into<String>) {
+        self.add(name, CheckStatus::Warn, detail);
+    }
+
+    fn fail(&mut self, name: &str, detail: impl Into<String>) {
+        self.add(name, CheckStatus::Fail, detail);
+    }
+
+    fn skip(&mut self, name: &str, detail: impl Into<String>) {
+        self.add(name, CheckStatus::Skip, detail);
+    }
+
+    /// 🔢 Checks with this outcome
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// 🖨️ Print one line per check, then the totals
+    pub fn print(&self) {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        for check in &self.checks {
+            println!(
+                "{} {:<width$}  {}",
+                check.status.icon(),
+                check.name,
+                check.detail,
+                width = width
+            );
+        }
+        println!(
+            "\n📋 {} passed, {} warnings, {} failed, {} skipped",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+            self.count(CheckStatus::Skip)
+        );
+    }
+
+    /// 🏁 An error when any check failed, for the exit code
+    fn finish(&self) -> Result<()> {
+        match self.count(CheckStatus::Fail) {
+            0 => Ok(()),
+            failed => anyhow::bail!("{} checks failed", failed),
+        }
+    }
+}
+
+/// 📋 `feedbacker check-config`
+pub fn run_check_config(config_path: Option<&Path>) -> Result<()> {
+    let (report, _) = check_config(config_path);
+    report.print();
+    report.finish()
+}
+
+/// 🩺 `feedbacker doctor`
+pub async fn run_doctor(config_path: Option<&Path>) -> Result<()> {
+    let report = doctor(config_path).await;
+    report.print();
+    report.finish()
+}
+
+/// 📋 Load and validate the configuration without contacting anything
+pub fn check_config(config_path: Option<&Path>) -> (Report, Option<Config>) {
+    let mut report = Report::default();
+    let config = match Config::load_from(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            report.fail("configuration", format!("{:#}", e));
+            return (report, None);
+        }
+    };
+    report.pass(
+        "configuration",
+        match config_path {
+            Some(path) => format!("loaded (environment over {})", path.display()),
+            None => "loaded".to_string(),
+        },
+    );
+    summarize_config(&config, &mut report);
+    (report, Some(config))
+}
+
+/// 📋 What the configuration will do, with warnings for risky choices
+fn summarize_config(config: &Config, report: &mut Report) {
+    report.pass(
+        "server",
+        format!(
+            "{:?} on {}",
+            config.server.environment, config.server.address
+        ),
+    );
+    report.pass(
+        "database url",
+        crate::mask_database_url(&config.database.url),
+    );
+
+    match &config.server.tls.mode {
+        TlsMode::Off => report.skip(
+            "tls",
+            "plain HTTP (TLS_CERT_PATH or TLS_ACME_DOMAINS not set)",
+        ),
+        TlsMode::Files {
+            cert_path,
+            key_path,
+        } => match [cert_path, key_path]
+            .into_iter()
+            .find(|path| std::fs::File::open(path).is_err())
+        {
+            Some(path) => report.fail("tls", format!("{} can't be read", path.display())),
+            None => report.pass("tls", format!("certificate {}", cert_path.display())),
+        },
+        TlsMode::Acme {
+            domains, staging, ..
+        } => report.pass(
+            "tls",
+            format!(
+                "ACME for {}{}",
+                domains.join(", "),
+                if *staging { " (staging)" } else { "" }
+            ),
+        ),
+    }
+
+    let providers: Vec<&str> = [
+        LlmProvider::OpenAi,
+        LlmProvider::Anthropic,
+        LlmProvider::Custom,
+    ]
+    .iter()
+    .filter(|provider| match provider {
+        LlmProvider::OpenAi => config.llm.openai.is_some(),
+        LlmProvider::Anthropic => config.llm.anthropic.is_some(),
+        LlmProvider::Custom => config.llm.custom.is_some(),
+    })
+    .map(LlmProvider::as_str)
+    .collect();
+    if providers.is_empty() {
+        report.warn(
+            "llm providers",
+            "none configured; feedback can't be processed",
+        );
+    } else {
+        report.pass("llm providers", providers.join(", "));
+    }
+
+    let references = secrets::references(config);
+    if references.is_empty() {
+        report.skip("secret references", "all credentials are set directly");
+    } else {
+        let settings: Vec<String> = references
+            .iter()
+            .map(|binding| format!("{} ← {}", binding.setting, binding.reference))
+            .collect();
+        report.pass("secret references", settings.join(", "));
+    }
+
+    match &config.email {
+        Some(email) => report.pass(
+            "email",
+            format!(
+                "{}:{} as {}",
+                email.smtp_host, email.smtp_port, email.from_email
+            ),
+        ),
+        None => report.skip("email", "SMTP_HOST is not set"),
+    }
+
+    if config.is_production() && config.features.enable_dev_features {
+        report.warn(
+            "dev features",
+            "ENABLE_DEV_FEATURES is on in production (full LLM exchanges are logged)",
+        );
+    }
+}
+
+/// 🩺 Validate the configuration, then probe every dependency it names
+pub async fn doctor(config_path: Option<&Path>) -> Report {
+    let (mut report, config) = check_config(config_path);
+    let Some(mut config) = config else {
+        return report;
+    };
+
+    // 🔑 Later probes need the real credentials
+    if !secrets::references(&config).is_empty() {
+        let resolver = secrets::SecretResolver::new(&config.secrets);
+        match probe(secrets::resolve_config(&resolver, &mut config)).await {
+            Ok(bindings) => report.pass("secrets", format!("{} fetched", bindings.len())),
+            Err(e) => {
+                report.fail("secrets", format!("{:#}", e));
+                return report;
+            }
+        }
+    }
+
+    check_database(&config, &mut report).await;
+    check_github(&config, &mut report).await;
+    check_github_endpoints(&config, &mut report).await;
+    check_llm(&config, &mut report).await;
+    check_smtp(config.email.as_ref(), &mut report).await;
+    report
+}
+
+/// ⏱️ Run a probe under PROBE_TIMEOUT
+async fn probe<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(PROBE_TIMEOUT, future)
+        .await
+        .with_context(|| format!("no answer within {}s", PROBE_TIMEOUT.as_secs()))?
+}
+
+/// 🗄️ Connect, and compare the applied migrations with this build's
+async fn check_database(config: &Config, report: &mut Report) {
+    let started = Instant::now();
+    let connected = probe(async {
+        let pool = database::create_pool(&config.database).await?;
+        let version: String = sqlx::query_scalar("SELECT version()")
+            .fetch_one(&pool)
+            .await
+            .context("Query failed")?;
+        let applied = database::migrations::list_applied_migrations(&pool).await?;
+        Ok((version, applied))
+    })
+    .await;
+    let (version, applied) = match connected {
+        Ok(connected) => connected,
+        Err(e) => {
+            report.fail("database", format!("{:#}", e));
+            return;
+        }
+    };
+    report.pass(
+        "database",
+        format!(
+            "connected in {}ms ({})",
+            started.elapsed().as_millis(),
+            version.split(',').next().unwrap_or(&version)
+        ),
+    );
+
+    let statuses = database::migrations::migration_status(
+        &database::migrations::get_all_migrations(),
+        &applied,
+    );
+    let count = |state| statuses.iter().filter(|s| s.state == state).count();
+    let modified = count(MigrationState::Modified);
+    if modified > 0 {
+        let detail = format!(
+            "{} applied migrations have changed since they ran",
+            modified
+        );
+        match config.database.migration_drift {
+            MigrationDrift::Fail => report.fail("migrations", detail),
+            MigrationDrift::Warn => report.warn("migrations", detail),
+        }
+        return;
+    }
+
+    let pending = count(MigrationState::Pending);
+    if pending == 0 {
+        report.pass("migrations", "schema is up to date");
+    } else if config.database.auto_migrate {
+        report.pass(
+            "migrations",
+            format!("{} pending, applied on startup", pending),
+        );
+    } else {
+        report.warn(
+            "migrations",
+            format!(
+                "{} pending and auto-migrate is off; run `feedbacker migrate up`",
+                pending
+            ),
+        );
+    }
+}
+
+/// 🏢 Check every GitHub Enterprise endpoint answers with its token
+async fn check_github_endpoints(config: &Config, report: &mut Report) {
+    for endpoint in &config.github.endpoints {
+        let check = format!("github endpoint {}", endpoint.name);
+        let url = format!("{}/user", endpoint.api_base_url);
+        let response = probe(async {
+            reqwest::Client::new()
+                .get(&url)
+                .bearer_auth(&endpoint.token)
+                .header("User-Agent", "feedbacker")
+                .header("Accept", "application/vnd.github+json")
+                .send()
+                .await
+                .context("GitHub request failed")
+        })
+        .await;
+        match response {
+            Ok(response) if response.status().is_success() => {
+                report.pass(&check, endpoint.api_base_url.clone())
+            }
+            Ok(response) => report.fail(
+                &check,
+                format!("GitHub answered {} for {}", response.status(), url),
+            ),
+            Err(e) => report.fail(&check, format!("{:#}", e)),
+        }
+    }
+}
+
+/// 🐙 Check the token works, belongs to the configured user, and has the scopes we need
+async fn check_github(config: &Config, report: &mut Report) {
+    let github = &config.github;
+    let url = format!("{}/user", github.api_base_url.trim_end_matches('/'));
+    let response = probe(async {
+        reqwest::Client::new()
+            .get(&url)
+            .bearer_auth(&github.token)
+            .header("User-Agent", "feedbacker")
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("GitHub request failed")
+    })
+    .await;
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            report.fail("github token", format!("{:#}", e));
+            return;
+        }
+    };
+
+    let status = response.status();
+    if !status.is_success() {
+        report.fail(
+            "github token",
+            format!("GitHub answered {} for {}", status, url),
+        );
+        return;
+    }
+    // 🔑 Classic tokens list their scopes; fine-grained tokens send no header
+    let scopes: Option<Vec<String>> = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .split(',')
+                .map(|scope| scope.trim().to_string())
+                .filter(|scope| !scope.is_empty())
+                .collect()
+        });
+    let login = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|user| user["login"].as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    if login.eq_ignore_ascii_case(&github.username) {
+        report.pass("github token", format!("authenticated as {}", login));
+    } else {
+        report.warn(
+            "github token",
+            format!(
+                "authenticated as {}, but GITHUB_USERNAME is {}",
+                login, github.username
+            ),
+        );
+    }
+
+    match scopes {
+        None => report.skip(
+            "github scopes",
+            "fine-grained token; permissions can't be listed",
+        ),
+        Some(scopes) => {
+            let missing: Vec<&str> = GITHUB_SCOPES
+                .iter()
+                .copied()
+                .filter(|needed| !scopes.iter().any(|scope| scope == needed))
+                .collect();
+            if missing.is_empty() {
+                report.pass("github scopes", scopes.join(", "));
+            } else {
+                report.warn(
+                    "github scopes",
+                    format!("missing {} (has {})", missing.join(", "), scopes.join(", ")),
+                );
+            }
+        }
+    }
+}
+
+/// 🤖 Ping every configured provider with its API key
+async fn check_llm(config: &Config, report: &mut Report) {
+    let manager = LlmManager::new(&config.llm);
+    for provider in [
+        LlmProvider::OpenAi,
+        LlmProvider::Anthropic,
+        LlmProvider::Custom,
+    ] {
+        let name = format!("llm {}", provider.as_str());
+        match manager.check_health(&provider).await {
+            None => report.skip(&name, "not configured"),
+            Some(ping) if ping.reachable => report.pass(
+                &name,
+                format!("key accepted in {}ms", ping.latency_ms.unwrap_or_default()),
+            ),
+            Some(ping) => report.fail(
+                &name,
+                ping.error.unwrap_or_else(|| "unreachable".to_string()),
+            ),
+        }
+    }
+}
+
+/// 📧 Connect to the SMTP server and log in
+async fn check_smtp(email: Option<&EmailConfig>, report: &mut Report) {
+    let Some(email) = email else {
+        report.skip("smtp", "email notifications are not configured");
+        return;
+    };
+
+    let transport = match email::transport(email, PROBE_TIMEOUT) {
+        Ok(transport) => transport,
+        Err(e) => {
+            report.fail("smtp", format!("{:#}", e));
+            return;
+        }
+    };
+
+    let server = format!("{}:{}", email.smtp_host, email.smtp_port);
+    match transport.test_connection().await {
+        Ok(true) if email.smtp_username.is_empty() => report.pass(
+            "smtp",
+            format!("connected to {} (no login configured)", server),
+        ),
+        Ok(true) => report.pass(
+            "smtp",
+            format!("logged in to {} as {}", server, email.smtp_username),
+        ),
+        Ok(false) => report.fail("smtp", format!("{} refused the connection", server)),
+        Err(e) => report.fail("smtp", format!("{}: {:#}", server, e)),
+    }
+}
+
+// 🧪 Tests - The report adds up!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_outcome() {
+        let mut report = Report::default();
+        report.pass("database", "connected");
+        report.skip("smtp", "not configured");
+        report.warn("github scopes", "missing workflow");
+        assert!(report.finish().is_ok());
+
+        report.fail("llm openai", "401 Unauthorized");
+        assert_eq!(report.count(CheckStatus::Fail), 1);
+        assert_eq!(report.finish().unwrap_err().to_string(), "1 checks failed");
+
+        let (report, config) = check_config(Some(Path::new("/nonexistent/feedbacker.toml")));
+        assert!(config.is_none());
+        assert_eq!(report.count(CheckStatus::Fail), 1);
+        println!("✅ Doctor report test passed!");
+    }
+}
@@ -0,0 +1,142 @@
+// 📧 Email - Messages Out Through SMTP! 📧
+// One place that knows how to reach the SMTP server configured by SMTP_HOST and
+// friends: `feedbacker doctor` logs in with the same transport the digests
+// (crate::digest) are sent through. Without SMTP_USE_TLS the connection is
+// plain; port 465 speaks TLS from the start, other ports upgrade with STARTTLS
+// Created with love by Aye & Hue - You've got mail! ✨
+
+use anyhow::{Context, Result};
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::time::Duration;
+
+use crate::config::EmailConfig;
+
+/// ⏱️ How long one SMTP exchange may take
+pub const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 🚚 A transport to the configured SMTP server (logged in when a username is set)
+pub fn transport(
+    email: &EmailConfig,
+    timeout: Duration,
+) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let builder = if !email.use_tls {
+        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&email.smtp_host)
+    } else if email.smtp_port == 465 {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(&email.smtp_host)?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&email.smtp_host)?
+    };
+    let mut builder = builder.port(email.smtp_port).timeout(Some(timeout));
+    if !email.smtp_username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            email.smtp_username.clone(),
+            email.smtp_password.clone(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+/// ✉️ An email with an HTML body and its plain text alternative
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+impl OutgoingEmail {
+    /// 📝 The message, sent from FROM_EMAIL
+    pub fn message(&self, email: &EmailConfig) -> Result<Message> {
+        let from: Mailbox = email
+            .from_email
+            .parse()
+            .with_context(|| format!("FROM_EMAIL '{}' is not an address", email.from_email))?;
+        let to: Mailbox = self
+            .to
+            .parse()
+            .with_context(|| format!("'{}' is not an email address", self.to))?;
+        Message::builder()
+            .from(from)
+            .to(to)
+            .subject(&self.subject)
+            .multipart(
+                MultiPart::alternative()
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_PLAIN)
+                            .body(self.text.clone()),
+                    )
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_HTML)
+                            .body(self.html.clone()),
+                    ),
+            )
+            .context("Failed to build email")
+    }
+}
+
+/// 📮 Sends emails through the configured SMTP server
+pub struct Mailer {
+    config: EmailConfig,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl Mailer {
+    /// ➕ A mailer for the SMTP settings (connecting happens on the first send)
+    pub fn new(config: &EmailConfig) -> Result<Self> {
+        Ok(Self {
+            config: config.clone(),
+            transport: transport(config, SMTP_TIMEOUT)?,
+        })
+    }
+
+    /// ✉️ Send one email
+    pub async fn send(&self, outgoing: &OutgoingEmail) -> Result<()> {
+        self.transport
+            .send(outgoing.message(&self.config)?)
+            .await
+            .with_context(|| format!("Failed to send email to {}", outgoing.to))?;
+        Ok(())
+    }
+}
+
+// 🧪 Tests - Addressed and stamped!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EmailConfig {
+        EmailConfig {
+            smtp_host: "smtp.example.com".to_string(),
+            smtp_port: 587,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            from_email: "feedbacker@example.com".to_string(),
+            use_tls: true,
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        let outgoing = OutgoingEmail {
+            to: "hue@example.com".to_string(),
+            subject: "📬 Your weekly Feedbacker digest".to_string(),
+            html: "<p>Hi</p>".to_string(),
+            text: "Hi".to_string(),
+        };
+        let formatted =
+            String::from_utf8(outgoing.message(&config()).unwrap().formatted()).unwrap();
+        assert!(formatted.contains("To: hue@example.com"));
+        assert!(formatted.contains("multipart/alternative"));
+
+        let nowhere = OutgoingEmail {
+            to: "not an address".to_string(),
+            ..outgoing
+        };
+        assert!(nowhere.message(&config()).is_err());
+        println!("✅ Email message test passed!");
+    }
+}
@@ -0,0 +1,470 @@
+// 🧯 Errors - Every Failure Gets a Name, Never a Stack Trace! 🧯
+// The taxonomy behind the `code` of error responses: each kind has a stable
+// status and a stable problem `type` URI (RFC 7807), which clients asking for
+// application/problem+json get instead of the usual envelope (see
+// middleware::problem_json). Unexpected failures are classified here too: the
+// full error chain goes to the log under the request's id, and in production the
+// client only gets that id to quote, never the internals (development servers
+// still hand out the chain, see middleware::error_handling). The latest ones are
+// also kept in memory for the admin console
+// Created with love by Aye & Hue - Something went wrong, and we know exactly what! ✨
+
+use std::{any::Any, future::Future};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    api::{ApiResponse, ErrorResponse},
+    github::rate_limit::RateLimited,
+    i18n,
+    utils::recent::RecentLog,
+};
+
+/// 🔗 Problem types are this plus the kind's slug
+pub const PROBLEM_TYPE_BASE: &str = "https://f.8b.is/problems/";
+
+/// 🏷️ What went wrong, as far as a client needs to know
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// ✅ The request didn't pass validation
+    Validation,
+    /// 🔐 No valid credentials
+    Unauthorized,
+    /// 🛡️ Valid credentials, not allowed
+    Forbidden,
+    /// 🔍 Nothing there
+    NotFound,
+    /// 🚷 The path exists, but not for this method
+    MethodNotAllowed,
+    /// ⚔️ Clashes with what already exists
+    Conflict,
+    /// 📏 An organization quota is used up
+    QuotaExceeded,
+    /// 🚦 Too many requests
+    RateLimited,
+    /// 🚧 Writes are paused for maintenance
+    Maintenance,
+    /// 🔑 This way of signing in is switched off
+    LoginMethodDisabled,
+    /// 🔐 The identity provider's sign-in was refused
+    SsoFailed,
+    /// 🌐 A service we depend on (GitHub, an identity provider) failed
+    Upstream,
+    /// ⚙️ The new configuration was rejected
+    InvalidConfiguration,
+    /// 🐙 The repository doesn't exist or can't take pull requests from us
+    RepositoryNotAccessible,
+    /// ⏳ Temporarily unable to answer (database busy or unreachable)
+    Unavailable,
+    /// 💥 Anything else
+    Internal,
+}
+
+impl ErrorKind {
+    /// 📋 Every kind, for lookups
+    pub const ALL: [ErrorKind; 16] = [
+        ErrorKind::Validation,
+        ErrorKind::Unauthorized,
+        ErrorKind::Forbidden,
+        ErrorKind::NotFound,
+        ErrorKind::MethodNotAllowed,
+        ErrorKind::Conflict,
+        ErrorKind::QuotaExceeded,
+        ErrorKind::RateLimited,
+        ErrorKind::Maintenance,
+        ErrorKind::LoginMethodDisabled,
+        ErrorKind::SsoFailed,
+        ErrorKind::Upstream,
+        ErrorKind::InvalidConfiguration,
+        ErrorKind::RepositoryNotAccessible,
+        ErrorKind::Unavailable,
+        ErrorKind::Internal,
+    ];
+
+    /// 🎯 The `code` of error responses
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::Validation => "validation_error",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::Forbidden => "forbidden",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::MethodNotAllowed => "method_not_allowed",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::QuotaExceeded => "quota_exceeded",
+            ErrorKind::RateLimited => "rate_limit_exceeded",
+            ErrorKind::Maintenance => "maintenance",
+            ErrorKind::LoginMethodDisabled => "password_login_disabled",
+            ErrorKind::SsoFailed => "sso_failed",
+            ErrorKind::Upstream => "upstream_error",
+            ErrorKind::InvalidConfiguration => "invalid_configuration",
+            ErrorKind::RepositoryNotAccessible => "repository_not_accessible",
+            ErrorKind::Unavailable => "service_unavailable",
+            ErrorKind::Internal => "internal_error",
+        }
+    }
+
+    /// 🏷️ Kind for a response `code`; the codes of failed GitHub and sign-in
+    /// calls are upstream failures, unknown ones None
+    pub fn from_code(code: &str) -> Option<ErrorKind> {
+        match code {
+            "github_client_error"
+            | "sso_unavailable"
+            | "automation_failed"
+            | "comment_failed"
+            | "labels_failed"
+            | "close_failed" => Some(ErrorKind::Upstream),
+            _ => ErrorKind::ALL.into_iter().find(|kind| kind.code() == code),
+        }
+    }
+
+    /// 🔗 Last segment of the problem type URI
+    pub fn slug(self) -> &'static str {
+        match self {
+            ErrorKind::Validation => "validation",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::Forbidden => "forbidden",
+            ErrorKind::NotFound => "not-found",
+            ErrorKind::MethodNotAllowed => "method-not-allowed",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::QuotaExceeded => "quota-exceeded",
+            ErrorKind::RateLimited => "rate-limited",
+            ErrorKind::Maintenance => "maintenance",
+            ErrorKind::LoginMethodDisabled => "login-method-disabled",
+            ErrorKind::SsoFailed => "sso-failed",
+            ErrorKind::Upstream => "upstream",
+            ErrorKind::InvalidConfiguration => "invalid-configuration",
+            ErrorKind::RepositoryNotAccessible => "repository-not-accessible",
+            ErrorKind::Unavailable => "unavailable",
+            ErrorKind::Internal => "internal",
+        }
+    }
+
+    /// 🔗 The problem `type`
+    pub fn type_uri(self) -> String {
+        format!("{}{}", PROBLEM_TYPE_BASE, self.slug())
+    }
+
+    /// 📝 Short summary, the same for every occurrence (the problem `title`),
+    /// in the request's language
+    pub fn title(self) -> String {
+        i18n::t(&format!("error-{}", self.slug()))
+    }
+
+    /// 🔢 Status this kind is answered with
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorKind::Validation => StatusCode::BAD_REQUEST,
+            ErrorKind::Unauthorized | ErrorKind::SsoFailed => StatusCode::UNAUTHORIZED,
+            ErrorKind::Forbidden | ErrorKind::LoginMethodDisabled => StatusCode::FORBIDDEN,
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorKind::Conflict => StatusCode::CONFLICT,
+            ErrorKind::QuotaExceeded | ErrorKind::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorKind::Maintenance | ErrorKind::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Upstream => StatusCode::BAD_GATEWAY,
+            ErrorKind::InvalidConfiguration | ErrorKind::RepositoryNotAccessible => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorKind::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// 🔍 Kind of an unexpected failure, from the database error inside it if any
+    /// (or a GitHub rate limit, which is worth retrying later)
+    pub fn classify(error: &anyhow::Error) -> ErrorKind {
+        // 🚦 GitHub calls put off by its rate limit
+        if error.chain().any(|e| e.is::<RateLimited>()) {
+            return ErrorKind::RateLimited;
+        }
+        let Some(sqlx_error) = error.chain().find_map(|e| e.downcast_ref::<sqlx::Error>()) else {
+            return ErrorKind::Internal;
+        };
+        match sqlx_error {
+            sqlx::Error::RowNotFound => ErrorKind::NotFound,
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                ErrorKind::Unavailable
+            }
+            sqlx::Error::Database(database_error) => {
+                match database_error.code().as_deref() {
+                    // 🔑 unique_violation
+                    Some("23505") => ErrorKind::Conflict,
+                    // 🔗 foreign_key_violation, check_violation
+                    Some("23503") | Some("23514") => ErrorKind::Validation,
+                    _ => ErrorKind::Internal,
+                }
+            }
+            _ => ErrorKind::Internal,
+        }
+    }
+}
+
+/// 📄 An RFC 7807 problem document
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    /// 📝 What went wrong this time
+    pub detail: String,
+    /// 📍 The request path
+    pub instance: String,
+    /// 🎯 The response `code`, for clients matching on it
+    pub code: String,
+    /// ➕ The error's details (errors, retry_after_seconds, request_id...)
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl Problem {
+    /// 🔄 The problem for an error response body of ours
+    /// (None when the body isn't an error envelope)
+    pub fn from_response(status: StatusCode, body: &Value, instance: &str) -> Option<Problem> {
+        let error = body.get("error")?;
+        let code = error.get("code")?.as_str()?.to_string();
+        let detail = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let (problem_type, title) = match ErrorKind::from_code(&code) {
+            Some(kind) => (kind.type_uri(), kind.title()),
+            None => (
+                "about:blank".to_string(),
+                status.canonical_reason().unwrap_or("Error").to_string(),
+            ),
+        };
+        let extensions = match error.get("details") {
+            Some(Value::Object(details)) => details.clone(),
+            Some(Value::Null) | None => Map::new(),
+            Some(details) => Map::from_iter([("details".to_string(), details.clone())]),
+        };
+        Some(Problem {
+            problem_type,
+            title,
+            status: status.as_u16(),
+            detail,
+            instance: instance.to_string(),
+            code,
+            extensions,
+        })
+    }
+}
+
+/// 🧭 The request errors are being answered for, and whether its client may
+/// see their internals
+#[derive(Debug, Clone)]
+pub struct ErrorScope {
+    pub request_id: String,
+    pub expose_details: bool,
+}
+
+tokio::task_local! {
+    static ERROR_SCOPE: ErrorScope;
+}
+
+impl ErrorScope {
+    /// 🎯 Handle a request inside this scope
+    pub async fn run<F: Future>(self, future: F) -> F::Output {
+        ERROR_SCOPE.scope(self, future).await
+    }
+
+    /// 🔍 The scope of the request being handled, if there is one
+    fn current() -> Option<ErrorScope> {
+        ERROR_SCOPE.try_with(ErrorScope::clone).ok()
+    }
+}
+
+/// 💥 An unexpected failure, as listed in the admin console
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentError {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub request_id: String,
+    /// 🏷️ Error code the client got
+    pub code: &'static str,
+    /// 📝 What was being done
+    pub context: String,
+    /// 🔗 The full error chain
+    pub message: String,
+}
+
+lazy_static::lazy_static! {
+    /// 🕒 The latest unexpected failures on this instance
+    static ref RECENT_ERRORS: RecentLog<RecentError> = RecentLog::default();
+}
+
+/// 🕒 The latest unexpected failures on this instance, newest first
+pub fn recent_errors() -> Vec<RecentError> {
+    RECENT_ERRORS.snapshot()
+}
+
+/// 🔖 Log an unexpected failure in full under the request's id, returning the
+/// details the client gets instead: that id to quote when reporting it, plus
+/// the error chain when the scope allows it (development only)
+pub fn error_reference(context: &str, error: &anyhow::Error) -> Value {
+    let scope = ErrorScope::current();
+    let request_id = match &scope {
+        Some(scope) => scope.request_id.clone(),
+        None => Uuid::new_v4().to_string(),
+    };
+    error!("❌ {} [request {}]: {:#}", context, request_id, error);
+    RECENT_ERRORS.push(RecentError {
+        at: chrono::Utc::now(),
+        request_id: request_id.clone(),
+        code: ErrorKind::classify(error).code(),
+        context: context.to_string(),
+        message: format!("{:#}", error),
+    });
+
+    match scope {
+        Some(scope) if scope.expose_details => serde_json::json!({
+            "request_id": request_id,
+            "details": format!("{:#}", error),
+        }),
+        _ => serde_json::json!({ "request_id": request_id }),
+    }
+}
+
+/// 💥 Answer an unexpected failure by its kind, without its internals
+pub fn error_response(context: &str, error: anyhow::Error) -> Response {
+    let kind = ErrorKind::classify(&error);
+    let api_response = ApiResponse::<()>::error(
+        kind.code().to_string(),
+        kind.title(),
+        Some(error_reference(context, &error)),
+    );
+    (kind.status(), Json(api_response)).into_response()
+}
+
+/// 💥 Answer a panicked handler like any other internal failure (used by the
+/// CatchPanic layer, which runs inside the request's error scope)
+pub fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = if let Some(message) = panic.downcast_ref::<String>() {
+        message.as_str()
+    } else if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else {
+        "non-string panic payload"
+    };
+    error_response(
+        "Request handler panicked",
+        anyhow::anyhow!("panicked: {}", message),
+    )
+}
+
+// 🧪 Tests - Stable names for every failure!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_taxonomy() {
+        for kind in ErrorKind::ALL {
+            assert_eq!(ErrorKind::from_code(kind.code()), Some(kind));
+            assert!(kind.type_uri().starts_with(PROBLEM_TYPE_BASE));
+            assert!(kind.status().is_client_error() || kind.status().is_server_error());
+            assert!(!kind.title().starts_with("error-"));
+        }
+        assert_eq!(
+            ErrorKind::from_code("github_client_error"),
+            Some(ErrorKind::Upstream)
+        );
+        assert_eq!(ErrorKind::from_code("teapot"), None);
+
+        // 🔍 Database failures keep their meaning, everything else is internal
+        let missing = anyhow::Error::new(sqlx::Error::RowNotFound).context("Failed to find it");
+        assert_eq!(ErrorKind::classify(&missing), ErrorKind::NotFound);
+        let busy = anyhow::Error::new(sqlx::Error::PoolTimedOut);
+        assert_eq!(ErrorKind::classify(&busy), ErrorKind::Unavailable);
+        let held_back = anyhow::Error::new(RateLimited {
+            operation: "GET /repos/aye-is/feedbacker/issues".to_string(),
+            wait: std::time::Duration::from_secs(60),
+        })
+        .context("Failed to list issues");
+        assert_eq!(ErrorKind::classify(&held_back), ErrorKind::RateLimited);
+        assert_eq!(
+            ErrorKind::classify(&anyhow::anyhow!("disk on fire")),
+            ErrorKind::Internal
+        );
+        println!("✅ Error taxonomy test passed!");
+    }
+
+    #[test]
+    fn test_problem_from_response() {
+        let body = serde_json::to_value(ApiResponse::<()>::error(
+            "validation_error".to_string(),
+            "Request validation failed".to_string(),
+            Some(serde_json::json!({ "errors": ["days must be positive"] })),
+        ))
+        .unwrap();
+        let problem =
+            Problem::from_response(StatusCode::BAD_REQUEST, &body, "/api/orgs/1/api-keys").unwrap();
+        let document = serde_json::to_value(&problem).unwrap();
+        assert_eq!(document["type"], "https://f.8b.is/problems/validation");
+        assert_eq!(document["status"], 400);
+        assert_eq!(document["code"], "validation_error");
+        assert_eq!(document["errors"][0], "days must be positive");
+        assert_eq!(document["instance"], "/api/orgs/1/api-keys");
+
+        // 🤷 Unknown codes are plain HTTP problems; successes aren't problems at all
+        let teapot = serde_json::to_value(ApiResponse::<()>::error(
+            "teapot".to_string(),
+            "I'm a teapot".to_string(),
+            None,
+        ))
+        .unwrap();
+        let problem = Problem::from_response(StatusCode::IM_A_TEAPOT, &teapot, "/").unwrap();
+        assert_eq!(problem.problem_type, "about:blank");
+        assert!(problem.extensions.is_empty());
+        let success = serde_json::to_value(ApiResponse::success("Done".to_string(), 1)).unwrap();
+        assert!(Problem::from_response(StatusCode::OK, &success, "/").is_none());
+
+        // 🔖 Unexpected failures only tell the client their id
+        let reference = error_reference("Test", &anyhow::anyhow!("password=hunter2"));
+        assert!(reference["request_id"].is_string());
+        assert!(!reference.to_string().contains("hunter2"));
+        println!("✅ Problem document test passed!");
+    }
+
+    #[test]
+    fn test_error_scope() {
+        let error = anyhow::anyhow!("connection refused").context("Failed to load feedback");
+        let scope = |expose_details| ErrorScope {
+            request_id: "req-42".to_string(),
+            expose_details,
+        };
+
+        // 🏭 Production: the request id, nothing else
+        let reference = ERROR_SCOPE.sync_scope(scope(false), || error_reference("Test", &error));
+        assert_eq!(reference, serde_json::json!({ "request_id": "req-42" }));
+
+        // 🛠️ Development: the whole chain too
+        let reference = ERROR_SCOPE.sync_scope(scope(true), || error_reference("Test", &error));
+        assert_eq!(reference["request_id"], "req-42");
+        assert_eq!(
+            reference["details"],
+            "Failed to load feedback: connection refused"
+        );
+
+        // 🕒 Both are kept for the admin console
+        let recorded = recent_errors()
+            .into_iter()
+            .filter(|recent| recent.request_id == "req-42")
+            .count();
+        assert!(recorded >= 2);
+
+        // 💥 Panics are answered as internal errors
+        let response = ERROR_SCOPE.sync_scope(scope(false), || {
+            panic_response(Box::new("index out of bounds".to_string()))
+        });
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        println!("✅ Error scope test passed!");
+    }
+}
@@ -0,0 +1,55 @@
+// 🪵 Kafka Publisher - Events Onto the Log! 🪵
+// An idempotent librdkafka producer for EVENT_STREAM=kafka. EVENT_STREAM_SERVERS
+// is the bootstrap server list; each event waits for the brokers' acknowledgement
+// before the next goes out, so a feedback item's events land in order
+// Created with love by Aye & Hue - Append only, forget never! ✨
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+
+use super::{EventPublisher, StreamMessage};
+
+/// ⏱️ How long librdkafka keeps retrying one message
+const MESSAGE_TIMEOUT_MS: &str = "30000";
+
+/// 🪵 Producer for the configured cluster
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    /// 🔌 Producer for these bootstrap servers (connections open on first use)
+    pub fn connect(servers: &str) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", servers)
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", MESSAGE_TIMEOUT_MS)
+            .create()
+            .context("Failed to create the Kafka producer")?;
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, message: &StreamMessage<'_>) -> Result<()> {
+        let id = message.id.to_string();
+        let record = FutureRecord::to(&message.topic)
+            .key(&message.key)
+            .payload(message.payload)
+            .headers(OwnedHeaders::new().insert(Header {
+                key: "event-id",
+                value: Some(&id),
+            }));
+        self.producer
+            .send(record, Timeout::Never)
+            .await
+            .map_err(|(e, _)| e)
+            .with_context(|| format!("Kafka did not take the event for {}", message.topic))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+// 📤 Event Stream - Feedbacker Activity, Straight to the Data Team! 📤
+// With EVENT_STREAM=kafka or nats, every event in a feedback timeline (status
+// changes, plans, generated files, sandbox runs) is published as JSON with a
+// schema version: pull request events to `<EVENT_STREAM_TOPIC_PREFIX>.pull_requests`,
+// everything else to `<prefix>.feedback`, keyed by feedback id so one item's events
+// stay in order. Events are read from feedback_events in the order they were
+// recorded and only marked published once the broker has them, so delivery is at
+// least once (consumers dedupe on `id`); while the broker is down they wait in
+// the database. One instance publishes at a time, behind an advisory lock.
+// Kafka and NATS need the kafka-events and nats-events features
+// Created with love by Aye & Hue - Analytics without the polling! ✨
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::{EventStreamBackend, EventStreamConfig};
+use crate::database::models::{FeedbackEvent, UnstreamedEvent};
+
+#[cfg(feature = "kafka-events")]
+pub mod kafka; // 🪵 Kafka producer (librdkafka)
+#[cfg(feature = "nats-events")]
+pub mod nats; // 📨 NATS publisher
+
+/// 🏷️ Version of the published JSON; bumped when a field changes meaning or goes away
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// 📦 Events published per transaction
+const BATCH_SIZE: i64 = 100;
+
+/// 🔒 Advisory lock held by the instance publishing a batch
+const EVENT_STREAM_LOCK: i64 = 0x0046_4245_5645_4e54; // "FBEVENT"
+
+/// ⏳ Pause before connecting again after the broker failed
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// 📤 Where events are published
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// ✉️ Publish one message, returning once the broker has it
+    async fn publish(&self, message: &StreamMessage<'_>) -> Result<()>;
+}
+
+/// ✉️ One event on its way out
+#[derive(Debug)]
+pub struct StreamMessage<'a> {
+    /// 🏷️ Kafka topic or NATS subject
+    pub topic: String,
+    /// 🔑 Partition key (the feedback id)
+    pub key: String,
+    /// 🆔 Event id, for brokers that deduplicate
+    pub id: Uuid,
+    /// 📦 The JSON document
+    pub payload: &'a [u8],
+}
+
+/// 📄 The JSON published for each event
+#[derive(Debug, Serialize)]
+pub struct DomainEvent<'a> {
+    pub schema_version: u32,
+    /// 🆔 Unique per event; the same on a redelivery
+    pub id: Uuid,
+    /// 🏷️ Dotted type, e.g. `feedback.status_changed` or `pull_request.opened`
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    pub feedback_id: Uuid,
+    pub repository: &'a str,
+    /// 📦 Details, as in the feedback timeline
+    pub data: &'a serde_json::Value,
+}
+
+impl<'a> DomainEvent<'a> {
+    /// 📄 The published form of a timeline event, and the stream it goes to
+    fn new(event: &'a UnstreamedEvent) -> (&'static str, Self) {
+        let (stream, event_type) = match event.event.event_type.strip_prefix("pull_request_") {
+            Some(action) => ("pull_requests", format!("pull_request.{}", action)),
+            None => ("feedback", format!("feedback.{}", event.event.event_type)),
+        };
+        let domain_event = Self {
+            schema_version: SCHEMA_VERSION,
+            id: event.event.id,
+            event_type,
+            occurred_at: event.event.created_at,
+            feedback_id: event.event.feedback_id,
+            repository: &event.repository,
+            data: &event.event.payload,
+        };
+        (stream, domain_event)
+    }
+}
+
+/// 🔌 Publisher for the configured backend (None when events aren't published)
+pub async fn connect(config: &EventStreamConfig) -> Result<Option<Box<dyn EventPublisher>>> {
+    if config.backend == EventStreamBackend::None {
+        return Ok(None);
+    }
+    let servers = config
+        .servers
+        .as_deref()
+        .context("EVENT_STREAM_SERVERS is not set")?;
+    match config.backend {
+        #[cfg(feature = "kafka-events")]
+        EventStreamBackend::Kafka => Ok(Some(Box::new(kafka::KafkaPublisher::connect(servers)?))),
+        #[cfg(feature = "nats-events")]
+        EventStreamBackend::Nats => {
+            Ok(Some(Box::new(nats::NatsPublisher::connect(servers).await?)))
+        }
+        backend => anyhow::bail!(
+            "The {} event stream at {} needs the {}-events feature",
+            backend.as_str(),
+            servers,
+            backend.as_str()
+        ),
+    }
+}
+
+/// 🚀 Publish new feedback events in the background until shutdown
+pub fn start(app_state: &AppState) {
+    let config = app_state.config.load().event_stream.clone();
+    if config.backend == EventStreamBackend::None {
+        return;
+    }
+    let db_pool = app_state.db_pool.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = publish_until_failure(&db_pool, &config).await {
+                warn!("⚠️ Event stream publishing failed: {:#}", e);
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    });
+}
+
+/// 🔁 Connect, then keep publishing batches as events come in
+async fn publish_until_failure(pool: &PgPool, config: &EventStreamConfig) -> Result<()> {
+    let Some(publisher) = connect(config).await? else {
+        return Ok(());
+    };
+    info!(
+        "📤 Publishing feedback events to {} topics {}.*",
+        config.backend.as_str(),
+        config.topic_prefix
+    );
+
+    loop {
+        let published = publish_batch(pool, &config.topic_prefix, publisher.as_ref()).await?;
+        // 🏃 A full batch means more are probably waiting
+        if published < BATCH_SIZE as usize {
+            tokio::time::sleep(Duration::from_millis(config.poll_interval_ms)).await;
+        }
+    }
+}
+
+/// 📦 Publish the oldest unpublished events, returning how many went out (none
+/// while another instance is publishing)
+async fn publish_batch(
+    pool: &PgPool,
+    topic_prefix: &str,
+    publisher: &dyn EventPublisher,
+) -> Result<usize> {
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start event stream transaction")?;
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+        .bind(EVENT_STREAM_LOCK)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to take the event stream lock")?;
+    if !locked {
+        return Ok(0);
+    }
+
+    let events = FeedbackEvent::unstreamed(&mut transaction, BATCH_SIZE).await?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+    for event in &events {
+        let (stream, domain_event) = DomainEvent::new(event);
+        let payload = serde_json::to_vec(&domain_event)?;
+        let message = StreamMessage {
+            topic: format!("{}.{}", topic_prefix, stream),
+            key: event.event.feedback_id.to_string(),
+            id: event.event.id,
+            payload: &payload,
+        };
+        // 🔁 A failure rolls the batch back; what already went out goes again
+        publisher
+            .publish(&message)
+            .await
+            .with_context(|| format!("Failed to publish event {}", event.event.id))?;
+    }
+
+    let ids: Vec<Uuid> = events.iter().map(|event| event.event.id).collect();
+    FeedbackEvent::mark_streamed(&mut transaction, &ids).await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit published events")?;
+    Ok(events.len())
+}
+
+// 🧪 Tests - Every event in its proper place!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(event_type: &str, payload: serde_json::Value) -> UnstreamedEvent {
+        UnstreamedEvent {
+            event: FeedbackEvent {
+                id: Uuid::new_v4(),
+                feedback_id: Uuid::new_v4(),
+                event_type: event_type.to_string(),
+                payload,
+                created_at: Utc::now(),
+                version: 1,
+            },
+            repository: "aye-is/feedbacker".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_domain_events() {
+        let status = event(
+            FeedbackEvent::STATUS_CHANGED,
+            json!({ "status": "completed" }),
+        );
+        let (stream, domain_event) = DomainEvent::new(&status);
+        assert_eq!(stream, "feedback");
+        let published = serde_json::to_value(&domain_event).unwrap();
+        assert_eq!(published["schema_version"], 1);
+        assert_eq!(published["type"], "feedback.status_changed");
+        assert_eq!(published["id"], json!(status.event.id));
+        assert_eq!(published["feedback_id"], json!(status.event.feedback_id));
+        assert_eq!(published["repository"], "aye-is/feedbacker");
+        assert_eq!(published["data"]["status"], "completed");
+
+        let opened = event(
+            FeedbackEvent::PULL_REQUEST_OPENED,
+            json!({ "number": 42, "url": "https://github.com/aye-is/feedbacker/pull/42" }),
+        );
+        let (stream, domain_event) = DomainEvent::new(&opened);
+        assert_eq!(stream, "pull_requests");
+        assert_eq!(domain_event.event_type, "pull_request.opened");
+
+        let stale = event(FeedbackEvent::PULL_REQUEST_STALE, json!({}));
+        assert_eq!(DomainEvent::new(&stale).1.event_type, "pull_request.stale");
+        println!("✅ Domain event test passed!");
+    }
+}
@@ -0,0 +1,48 @@
+// 📨 NATS Publisher - Events Onto the Subjects! 📨
+// Core NATS publishing for EVENT_STREAM=nats; EVENT_STREAM_SERVERS is the server
+// URL (or a comma-separated list). Each message carries its event id as
+// Nats-Msg-Id, so a JetStream stream capturing `<prefix>.>` drops redeliveries.
+// A flush after each publish makes sure the server has it before it counts as sent
+// Created with love by Aye & Hue - Fire, and confirm! ✨
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::{EventPublisher, StreamMessage};
+
+/// 📨 Connection to the NATS servers
+pub struct NatsPublisher {
+    client: async_nats::Client,
+}
+
+impl NatsPublisher {
+    /// 🔌 Connect to these servers
+    pub async fn connect(servers: &str) -> Result<Self> {
+        let client = async_nats::connect(servers)
+            .await
+            .context("Failed to connect to NATS")?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&self, message: &StreamMessage<'_>) -> Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", message.id.to_string().as_str());
+        headers.insert("Feedbacker-Feedback-Id", message.key.as_str());
+        self.client
+            .publish_with_headers(
+                message.topic.clone(),
+                headers,
+                message.payload.to_vec().into(),
+            )
+            .await
+            .with_context(|| format!("Failed to publish to {}", message.topic))?;
+        self.client
+            .flush()
+            .await
+            .context("NATS did not take the event")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+// 📦 Export - Your Feedback, in Your Spreadsheet! 📦
+// A project's feedback (status, pull request links, timing and token usage) as
+// CSV or JSON Lines, through GET /api/projects/:id/export. Projects with up to
+// EXPORT_INLINE_LIMIT items are streamed straight into the response; larger
+// ones are written to EXPORT_DIR by a `project_export` job and downloaded from
+// a link signed with JWT_SECRET, which works without logging in until the
+// export expires. The nightly cleanup deletes expired exports and their files
+// Created with love by Aye & Hue - Data to go, hold the lock-in! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::path::Path;
+use std::str::FromStr;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::ExportConfig;
+use crate::database::models::{
+    Feedback, FeedbackExportRow, JobPriority, NewBackgroundJob, Project, ProjectExport,
+};
+use crate::jobs::worker::{self, JobHandler};
+use crate::utils::signed_links;
+
+/// 🏷️ Job type of a background export (payload: `{"export_id": ...}`)
+pub const EXPORT_JOB: &str = "project_export";
+
+/// 📄 Columns of a CSV export, in order
+const CSV_COLUMNS: [&str; 15] = [
+    "id",
+    "user_id",
+    "path",
+    "status",
+    "content",
+    "branch_name",
+    "pull_request_url",
+    "llm_provider",
+    "error_message",
+    "prompt_tokens",
+    "completion_tokens",
+    "created_at",
+    "updated_at",
+    "completed_at",
+    "processing_seconds",
+];
+
+/// 📄 What an export is written as
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// 📊 Comma-separated values with a header row
+    #[default]
+    Csv,
+    /// 📜 One JSON object per line
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// 🏷️ Name used in the `format` parameter and as the file extension
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+
+    /// 📨 Content-Type of a download
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    /// 📄 File name a repository's export is downloaded as
+    pub fn file_name(self, repository: &str) -> String {
+        format!(
+            "{}-feedback.{}",
+            repository.replace('/', "-"),
+            self.as_str()
+        )
+    }
+
+    /// 🏷️ Header line written before the rows (CSV only)
+    fn header(self) -> Option<String> {
+        match self {
+            ExportFormat::Csv => Some(format!("{}\r\n", CSV_COLUMNS.join(","))),
+            ExportFormat::Jsonl => None,
+        }
+    }
+
+    /// ✍️ One feedback item as a line of this format
+    fn encode(self, row: &FeedbackExportRow) -> Result<String> {
+        Ok(match self {
+            ExportFormat::Csv => csv_record(row),
+            ExportFormat::Jsonl => format!("{}\n", serde_json::to_string(row)?),
+        })
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            _ => anyhow::bail!("Unsupported export format '{}' (use csv or jsonl)", s),
+        }
+    }
+}
+
+/// 📊 One feedback item as a CSV record
+fn csv_record(row: &FeedbackExportRow) -> String {
+    let optional = |value: &Option<String>| value.as_deref().map(csv_field).unwrap_or_default();
+    let time = |value: Option<DateTime<Utc>>| value.map(|t| t.to_rfc3339()).unwrap_or_default();
+    let fields = [
+        row.id.to_string(),
+        row.user_id.map(|id| id.to_string()).unwrap_or_default(),
+        optional(&row.path),
+        row.status.as_str().to_string(),
+        csv_field(&row.content),
+        optional(&row.branch_name),
+        optional(&row.pull_request_url),
+        optional(&row.llm_provider),
+        optional(&row.error_message),
+        row.prompt_tokens.to_string(),
+        row.completion_tokens.to_string(),
+        row.created_at.to_rfc3339(),
+        row.updated_at.to_rfc3339(),
+        time(row.completed_at),
+        row.processing_seconds
+            .map(|seconds| seconds.to_string())
+            .unwrap_or_default(),
+    ];
+    format!("{}\r\n", fields.join(","))
+}
+
+/// 🛡️ A CSV field: quoted when it has to be, and never read as a formula by
+/// spreadsheets (user-written text starting with = + - @ gets a leading ')
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// ✍️ Write every feedback item of a repository to `writer`, returning how many
+/// were written (the writer is shut down at the end, so a pipe sees EOF)
+pub async fn write_export<W: AsyncWrite + Unpin>(
+    pool: &PgPool,
+    repository: &str,
+    format: ExportFormat,
+    writer: W,
+) -> Result<i64> {
+    let mut writer = BufWriter::new(writer);
+    if let Some(header) = format.header() {
+        writer.write_all(header.as_bytes()).await?;
+    }
+
+    let mut rows = Feedback::export_rows(pool, repository);
+    let mut count = 0;
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .context("Failed to read feedback for export")?
+    {
+        writer.write_all(format.encode(&row)?.as_bytes()).await?;
+        count += 1;
+    }
+    writer.shutdown().await?;
+    Ok(count)
+}
+
+/// ➕ Record an export and queue the job that writes it
+pub async fn queue_export(
+    app_state: &AppState,
+    project: &Project,
+    requested_by: Uuid,
+    format: ExportFormat,
+) -> Result<ProjectExport> {
+    let export = ProjectExport::create(
+        &app_state.db_pool,
+        project.id,
+        requested_by,
+        format.as_str(),
+    )
+    .await?;
+    // 🔂 A failed export is reported as failed rather than retried behind the user's back
+    let job = NewBackgroundJob::new(EXPORT_JOB, serde_json::json!({ "export_id": export.id }))
+        .with_priority(JobPriority::Normal)
+        .with_user(Some(requested_by))
+        .with_max_retries(1);
+    app_state.jobs.enqueue(&job).await?;
+    info!(
+        "📦 Queued {} export {} of {}",
+        format.as_str(),
+        export.id,
+        project.repository
+    );
+    Ok(export)
+}
+
+/// 🔧 Worker pool handler that writes queued exports
+pub fn export_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |job| {
+        let db_pool = db_pool.clone();
+        let config = config.load().exports.clone();
+        async move {
+            let export_id: Uuid = serde_json::from_value(job.payload["export_id"].clone())
+                .context("Export job has no export_id")?;
+            run_export(&db_pool, &config, export_id).await
+        }
+    })
+}
+
+/// 🏃 Write one queued export to a file and record where it went
+async fn run_export(pool: &PgPool, config: &ExportConfig, export_id: Uuid) -> Result<()> {
+    let mut export = ProjectExport::find_by_id(pool, export_id)
+        .await?
+        .with_context(|| format!("Export {} no longer exists", export_id))?;
+    let expires_at = Utc::now() + Duration::hours(config.link_hours as i64);
+
+    let written = async {
+        let project = Project::find_by_id(pool, export.project_id)
+            .await?
+            .with_context(|| format!("Project {} no longer exists", export.project_id))?;
+        let format: ExportFormat = export.format.parse()?;
+        write_file(pool, config, export.id, &project.repository, format).await
+    }
+    .await;
+
+    match written {
+        Ok((rows, path)) => {
+            export.complete(pool, rows, &path, expires_at).await?;
+            info!("✅ Export {} written: {} rows", export.id, rows);
+            Ok(())
+        }
+        Err(e) => {
+            export.fail(pool, &format!("{:#}", e), expires_at).await?;
+            Err(e)
+        }
+    }
+}
+
+/// 📂 Write an export under EXPORT_DIR; the file only appears once complete
+async fn write_file(
+    pool: &PgPool,
+    config: &ExportConfig,
+    export_id: Uuid,
+    repository: &str,
+    format: ExportFormat,
+) -> Result<(i64, String)> {
+    tokio::fs::create_dir_all(&config.dir)
+        .await
+        .with_context(|| format!("Failed to create export directory {}", config.dir))?;
+    let path = Path::new(&config.dir).join(format!("{}.{}", export_id, format.as_str()));
+    let partial = path.with_extension("partial");
+
+    let written = async {
+        let file = tokio::fs::File::create(&partial)
+            .await
+            .with_context(|| format!("Failed to create {}", partial.display()))?;
+        let rows = write_export(pool, repository, format, file).await?;
+        tokio::fs::rename(&partial, &path).await?;
+        anyhow::Ok(rows)
+    }
+    .await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&partial).await;
+    }
+    Ok((written?, path.to_string_lossy().into_owned()))
+}
+
+/// 🧹 Delete expired exports and their files
+pub async fn purge_expired(pool: &PgPool) -> Result<usize> {
+    let expired = ProjectExport::delete_expired(pool).await?;
+    for path in expired
+        .iter()
+        .filter_map(|export| export.file_path.as_ref())
+    {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("⚠️ Could not delete expired export {}: {}", path, e);
+            }
+        }
+    }
+    Ok(expired.len())
+}
+
+/// 🏷️ Domain export download links are signed under
+const LINK_DOMAIN: &str = "export";
+
+/// 🔗 Signed download link of a completed export (None until it is written)
+pub fn download_url(secret: &str, export: &ProjectExport) -> Option<String> {
+    if export.status != ProjectExport::COMPLETED {
+        return None;
+    }
+    let expires = export.expires_at?.timestamp();
+    let signature = signed_links::sign(secret, LINK_DOMAIN, &[&export.id, &expires]);
+    Some(format!(
+        "/api/exports/{}/download?expires={}&signature={}",
+        export.id, expires, signature
+    ))
+}
+
+/// ✅ Whether a download link is genuine and not yet expired
+pub fn verify_link(
+    secret: &str,
+    export_id: Uuid,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    expires >= now.timestamp()
+        && signed_links::verify(secret, LINK_DOMAIN, &[&export_id, &expires], signature)
+}
+
+// 🧪 Tests - Exported, escaped, and signed!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::FeedbackStatus;
+
+    fn row() -> FeedbackExportRow {
+        let created_at = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        FeedbackExportRow {
+            id: Uuid::nil(),
+            user_id: None,
+            path: Some("crates/api".to_string()),
+            status: FeedbackStatus::Completed,
+            content: "Fix the \"login\" page,\nplease".to_string(),
+            branch_name: Some("feedbacker/fix-login".to_string()),
+            pull_request_url: Some("https://github.com/aye-is/feedbacker/pull/7".to_string()),
+            llm_provider: Some("anthropic".to_string()),
+            error_message: None,
+            prompt_tokens: 1200,
+            completion_tokens: 300,
+            created_at,
+            updated_at: created_at,
+            completed_at: Some(created_at + Duration::seconds(90)),
+            processing_seconds: Some(90),
+        }
+    }
+
+    #[test]
+    fn test_csv_export() {
+        assert_eq!(
+            ExportFormat::Csv.header().unwrap().matches(',').count(),
+            CSV_COLUMNS.len() - 1
+        );
+        let line = ExportFormat::Csv.encode(&row()).unwrap();
+        assert_eq!(
+            line,
+            "00000000-0000-0000-0000-000000000000,,crates/api,completed,\"Fix the \"\"login\"\" page,\nplease\",feedbacker/fix-login,https://github.com/aye-is/feedbacker/pull/7,anthropic,,1200,300,2024-05-01T12:00:00+00:00,2024-05-01T12:00:00+00:00,2024-05-01T12:01:30+00:00,90\r\n"
+        );
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("plain"), "plain");
+        println!("✅ CSV export test passed!");
+    }
+
+    #[test]
+    fn test_jsonl_export() {
+        let line = ExportFormat::Jsonl.encode(&row()).unwrap();
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["prompt_tokens"], 1200);
+        assert_eq!(value["processing_seconds"], 90);
+        assert_eq!(
+            value["pull_request_url"],
+            "https://github.com/aye-is/feedbacker/pull/7"
+        );
+        assert_eq!(ExportFormat::Jsonl.header(), None);
+
+        assert_eq!(
+            "jsonl".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Jsonl
+        );
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+        assert_eq!(
+            ExportFormat::Csv.file_name("aye-is/feedbacker"),
+            "aye-is-feedbacker-feedback.csv"
+        );
+        println!("✅ JSON Lines export test passed!");
+    }
+
+    #[test]
+    fn test_signed_links() {
+        let secret = "a-very-secret-jwt-signing-secret!";
+        let now = Utc::now();
+        let mut export = ProjectExport {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            requested_by: None,
+            format: "csv".to_string(),
+            status: ProjectExport::PENDING.to_string(),
+            row_count: None,
+            file_path: None,
+            error_message: None,
+            created_at: now,
+            completed_at: None,
+            expires_at: Some(now + Duration::hours(1)),
+        };
+        assert_eq!(download_url(secret, &export), None);
+
+        export.status = ProjectExport::COMPLETED.to_string();
+        let url = download_url(secret, &export).unwrap();
+        let query = url.split_once('?').unwrap().1;
+        let (expires, signature) = query.split_once('&').unwrap();
+        let expires: i64 = expires.trim_start_matches("expires=").parse().unwrap();
+        let signature = signature.trim_start_matches("signature=");
+
+        assert!(verify_link(secret, export.id, expires, signature, now));
+        assert!(!verify_link(secret, export.id, expires + 1, signature, now));
+        assert!(!verify_link(
+            secret,
+            Uuid::new_v4(),
+            expires,
+            signature,
+            now
+        ));
+        assert!(!verify_link(
+            "another-secret",
+            export.id,
+            expires,
+            signature,
+            now
+        ));
+        assert!(!verify_link(secret, export.id, expires, "not-hex", now));
+        assert!(!verify_link(
+            secret,
+            export.id,
+            expires,
+            signature,
+            now + Duration::hours(2)
+        ));
+        println!("✅ Signed export link test passed!");
+    }
+}
@@ -0,0 +1,264 @@
+// 🚩 Feature Flags - Flip Behavior Without a Redeploy! 🚩
+// `FeaturesConfig` covers what is fixed per deployment; these flags live in the
+// database and are managed through /api/admin/feature-flags. A flag is on for a
+// project when:
+//   1. 🎯 the project has an override (on or off), or else
+//   2. 🌍 the flag is enabled and the project's stable bucket falls inside its
+//      rollout percentage
+// Checks without a project only see flags that are enabled for everyone (100%).
+// Flags are read from a snapshot refreshed every CACHE_TTL; admin changes
+// refresh this instance right away, other instances within the TTL
+// Created with love by Aye & Hue - Ship dark, light up gradually! ✨
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::database::models::{FeatureFlag, FeatureFlagOverride};
+use crate::llm::experiments::traffic_bucket;
+
+/// ⏱️ How long a snapshot is used before the flags are read again
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// 🏷️ Flags the code checks
+pub mod names {
+    /// 🩺 Scheduled repository health scans (on unless the flag says otherwise)
+    pub const PROACTIVE_SCANS: &str = "proactive_scans";
+    /// 🚧 Maintenance mode switched on by an admin (its description is the message)
+    pub const MAINTENANCE_MODE: &str = "maintenance_mode";
+}
+
+/// 🚩 How one flag evaluates
+#[derive(Debug, Clone, Default)]
+struct FlagRule {
+    enabled: bool,
+    rollout_percent: u32,
+    overrides: HashMap<Uuid, bool>,
+    description: Option<String>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// 📸 Every flag at one point in time
+#[derive(Debug, Clone, Default)]
+pub struct FlagSet {
+    rules: HashMap<String, FlagRule>,
+}
+
+impl FlagSet {
+    /// 🏗️ Build a snapshot from stored flags and overrides
+    pub fn new(flags: Vec<FeatureFlag>, overrides: Vec<FeatureFlagOverride>) -> Self {
+        let mut rules: HashMap<String, FlagRule> = flags
+            .into_iter()
+            .map(|flag| {
+                let rule = FlagRule {
+                    enabled: flag.enabled,
+                    rollout_percent: flag.rollout_percent.clamp(0, 100) as u32,
+                    overrides: HashMap::new(),
+                    description: flag.description,
+                    updated_at: Some(flag.updated_at),
+                };
+                (flag.name, rule)
+            })
+            .collect();
+        for row in overrides {
+            if let Some(rule) = rules.get_mut(&row.flag_name) {
+                rule.overrides.insert(row.project_id, row.enabled);
+            }
+        }
+        Self { rules }
+    }
+
+    /// 🔍 Whether a flag is on (for a project), or None when there is no such flag
+    pub fn evaluate(&self, name: &str, project_id: Option<Uuid>) -> Option<bool> {
+        let rule = self.rules.get(name)?;
+        let Some(project_id) = project_id else {
+            return Some(rule.enabled && rule.rollout_percent >= 100);
+        };
+        if let Some(enabled) = rule.overrides.get(&project_id) {
+            return Some(*enabled);
+        }
+        Some(rule.enabled && traffic_bucket(project_id, name) < rule.rollout_percent)
+    }
+
+    /// 📝 A flag's description and when it last changed
+    pub fn details(&self, name: &str) -> Option<(Option<&str>, Option<DateTime<Utc>>)> {
+        let rule = self.rules.get(name)?;
+        Some((rule.description.as_deref(), rule.updated_at))
+    }
+}
+
+/// 🔍 Result of evaluating a flag, for the admin API
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagEvaluation {
+    pub name: String,
+    pub project_id: Option<Uuid>,
+    pub enabled: bool,
+}
+
+/// 🚩 Cached flag evaluator, shared through the app state
+#[derive(Debug)]
+pub struct FeatureFlags {
+    db_pool: PgPool,
+    /// 📸 Latest snapshot and when it was read
+    cache: RwLock<Option<(Instant, Arc<FlagSet>)>>,
+}
+
+impl FeatureFlags {
+    /// ➕ An evaluator that reads flags on first use
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// ✅ Whether a flag is on (for a project); flags that don't exist are off
+    pub async fn is_enabled(&self, name: &str, project_id: Option<Uuid>) -> bool {
+        self.evaluate(name, project_id).await.unwrap_or(false)
+    }
+
+    /// 🔍 Whether a flag is on, or None when no such flag exists
+    /// (lets callers pick the default, e.g. a kill switch for something that's on)
+    pub async fn evaluate(&self, name: &str, project_id: Option<Uuid>) -> Option<bool> {
+        self.snapshot().await.evaluate(name, project_id)
+    }
+
+    /// 📸 Current flags, read again when the snapshot is older than CACHE_TTL
+    /// If the database can't be read, the previous snapshot keeps being used
+    pub async fn snapshot(&self) -> Arc<FlagSet> {
+        let cached = self.cache.read().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some((read_at, flags)) = &cached {
+            if read_at.elapsed() < CACHE_TTL {
+                return flags.clone();
+            }
+        }
+
+        match self.load().await {
+            Ok(flags) => {
+                let flags = Arc::new(flags);
+                *self.cache.write().unwrap_or_else(|e| e.into_inner()) =
+                    Some((Instant::now(), flags.clone()));
+                flags
+            }
+            Err(e) => {
+                warn!("⚠️ Feature flags could not be read: {:#}", e);
+                cached.map(|(_, flags)| flags).unwrap_or_default()
+            }
+        }
+    }
+
+    /// 🔄 Forget the snapshot so the next check sees the latest changes
+    pub fn invalidate(&self) {
+        *self.cache.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    async fn load(&self) -> Result<FlagSet> {
+        let flags = FeatureFlag::list(&self.db_pool).await?;
+        let overrides = FeatureFlagOverride::list(&self.db_pool, None).await?;
+        Ok(FlagSet::new(flags, overrides))
+    }
+}
+
+/// ✅ Check a flag name: lowercase letters, digits, `_`, `-` and `.`, up to 100 characters
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 100
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid flag name '{}': use lowercase letters, digits, '_', '-' and '.' (at most 100)",
+            name
+        ))
+    }
+}
+
+/// ✅ Check a rollout percentage
+pub fn validate_rollout(rollout_percent: i32) -> Result<(), String> {
+    if (0..=100).contains(&rollout_percent) {
+        Ok(())
+    } else {
+        Err("rollout_percent must be between 0 and 100".to_string())
+    }
+}
+
+// 🧪 Tests - Overrides first, then the rollout!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str, enabled: bool, rollout_percent: i32) -> FeatureFlag {
+        FeatureFlag {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            enabled,
+            rollout_percent,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_flag_evaluation() {
+        let pinned = Uuid::new_v4();
+        let flags = FlagSet::new(
+            vec![
+                flag("everywhere", true, 100),
+                flag("off", false, 100),
+                flag("half", true, 50),
+            ],
+            vec![FeatureFlagOverride {
+                flag_name: "off".to_string(),
+                project_id: pinned,
+                enabled: true,
+                updated_at: Utc::now(),
+            }],
+        );
+
+        assert_eq!(flags.evaluate("missing", None), None);
+        assert_eq!(flags.evaluate("everywhere", None), Some(true));
+        assert_eq!(flags.evaluate("everywhere", Some(pinned)), Some(true));
+        assert_eq!(flags.evaluate("off", Some(Uuid::new_v4())), Some(false));
+        assert_eq!(flags.evaluate("off", Some(pinned)), Some(true));
+        // 🎲 A partial rollout is off globally and stable per project
+        assert_eq!(flags.evaluate("half", None), Some(false));
+        let projects: Vec<Uuid> = (0..200).map(|_| Uuid::new_v4()).collect();
+        let on = projects
+            .iter()
+            .filter(|id| flags.evaluate("half", Some(**id)) == Some(true))
+            .count();
+        assert!(
+            on > 50 && on < 150,
+            "{} of 200 projects in a 50% rollout",
+            on
+        );
+        for id in &projects {
+            assert_eq!(
+                flags.evaluate("half", Some(*id)),
+                flags.evaluate("half", Some(*id))
+            );
+        }
+        println!("✅ Flag evaluation test passed!");
+    }
+
+    #[test]
+    fn test_flag_validation() {
+        assert!(validate_name("proactive_scans").is_ok());
+        assert!(validate_name("pipeline.require-approval").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Require Approval").is_err());
+        assert!(validate_rollout(0).is_ok());
+        assert!(validate_rollout(101).is_err());
+        println!("✅ Flag validation test passed!");
+    }
+}
@@ -0,0 +1,362 @@
+// 🧹 Bulk Feedback Operations - Cleanup in One Call! 🧹
+// POST /api/feedback/bulk retries, cancels or labels up to MAX_BULK_ITEMS
+// feedback items at once, e.g. everything that failed during an LLM provider
+// outage. All items are locked and changed in one transaction: an item that
+// can't take the action (missing, someone else's, wrong status) is reported and
+// left alone, while a database error rolls the whole batch back. Retried items
+// get their runs queued once the batch is committed, just like a single retry
+// Created with love by Aye & Hue - Hundreds of clicks, one request! ✨
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::database::models::{Feedback, FeedbackStatus, Project};
+use crate::i18n;
+use crate::jobs::{queue::JobQueue, runs};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::pipeline::PipelineMode;
+use feedbacker_types::{BulkAction, BulkFeedbackRequest, BulkFeedbackResponse, BulkItemResult};
+
+/// 📏 Most items one bulk request may name
+pub const MAX_BULK_ITEMS: usize = 500;
+
+/// 📏 Longest label, in characters
+pub const MAX_LABEL_CHARS: usize = 50;
+
+/// 🛑 Error message left on cancelled feedback
+const CANCELLED_MESSAGE: &str = "Cancelled";
+
+/// ✅ Validate a bulk request before touching any item
+pub fn validate(request: &BulkFeedbackRequest) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if request.ids.is_empty() {
+        errors.push(i18n::t("validation-bulk-ids-empty"));
+    } else if request.ids.len() > MAX_BULK_ITEMS {
+        errors.push(i18n::t_with(
+            "validation-bulk-ids-too-many",
+            [("max", MAX_BULK_ITEMS.into())],
+        ));
+    }
+    let mut seen = std::collections::HashSet::new();
+    if !request.ids.iter().all(|id| seen.insert(id)) {
+        errors.push(i18n::t("validation-bulk-ids-duplicate"));
+    }
+
+    let label = request.label.as_deref().map(str::trim);
+    match request.action {
+        BulkAction::SetLabel => {
+            if !label.is_some_and(|l| !l.is_empty() && l.chars().count() <= MAX_LABEL_CHARS) {
+                errors.push(i18n::t_with(
+                    "validation-bulk-label",
+                    [("max", MAX_LABEL_CHARS.into())],
+                ));
+            }
+        }
+        BulkAction::Retry | BulkAction::Cancel => {
+            if label.is_some() {
+                errors.push(i18n::t("validation-bulk-label-unexpected"));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 🚦 The status `action` moves an item in `status` to (None: stays put),
+/// or why the item can't take it
+fn transition(
+    action: BulkAction,
+    status: FeedbackStatus,
+) -> Result<Option<FeedbackStatus>, &'static str> {
+    match action {
+        BulkAction::Retry if matches!(status, FeedbackStatus::Failed | FeedbackStatus::Paused) => {
+            Ok(Some(FeedbackStatus::Pending))
+        }
+        BulkAction::Retry => Err("not_retryable"),
+        BulkAction::Cancel if status.is_finished() => Err("already_finished"),
+        BulkAction::Cancel => Ok(Some(FeedbackStatus::Failed)),
+        BulkAction::SetLabel => Ok(None),
+    }
+}
+
+/// 🧹 Apply a validated bulk request in one transaction
+/// Retried items whose repository has an active project are queued on it
+/// afterwards; the others stay pending until there is one to run them
+pub async fn apply(
+    pool: &PgPool,
+    jobs: &dyn JobQueue,
+    user: &AuthenticatedUser,
+    request: &BulkFeedbackRequest,
+) -> Result<BulkFeedbackResponse> {
+    let label = request.label.as_deref().map(str::trim);
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start bulk feedback transaction")?;
+
+    let mut items: HashMap<_, _> = Feedback::lock_many(&mut transaction, &request.ids)
+        .await?
+        .into_iter()
+        // 🔒 Someone else's feedback looks the same as missing feedback
+        .filter(|feedback| feedback.user_id == Some(user.id) || user.is_admin())
+        .map(|feedback| (feedback.id, feedback))
+        .collect();
+
+    let mut projects: HashMap<String, Option<Project>> = HashMap::new();
+    let mut retries = Vec::new();
+    let mut results = Vec::with_capacity(request.ids.len());
+    for id in &request.ids {
+        let Some(feedback) = items.get_mut(id) else {
+            results.push(skipped(*id, None, "not_found"));
+            continue;
+        };
+        match transition(request.action, feedback.status) {
+            Ok(Some(status)) => {
+                let project = match request.action {
+                    BulkAction::Retry => active_project(pool, &mut projects, &feedback.repository)
+                        .await?
+                        .cloned(),
+                    _ => None,
+                };
+                // 🚀 Items with a project to run on are processing from here on
+                let status = match project {
+                    Some(_) => FeedbackStatus::Processing,
+                    None => status,
+                };
+                let error_message =
+                    (request.action == BulkAction::Cancel).then(|| CANCELLED_MESSAGE.to_string());
+                feedback
+                    .update_status_in(&mut transaction, status, error_message)
+                    .await?;
+                if let Some(project) = project {
+                    retries.push((project, feedback.clone()));
+                }
+            }
+            Ok(None) => {
+                if let Some(label) = label {
+                    feedback.add_label(&mut transaction, label).await?;
+                }
+            }
+            Err(reason) => {
+                results.push(skipped(*id, Some(feedback.status), reason));
+                continue;
+            }
+        }
+        results.push(BulkItemResult {
+            id: *id,
+            success: true,
+            status: Some(feedback.status),
+            error: None,
+        });
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit bulk feedback changes")?;
+    let queued = queue_retries(pool, jobs, retries).await;
+
+    let succeeded = results.iter().filter(|result| result.success).count();
+    info!(
+        "🧹 {} applied {:?} to {} of {} feedback items ({} runs queued)",
+        user.email,
+        request.action,
+        succeeded,
+        results.len(),
+        queued
+    );
+    Ok(BulkFeedbackResponse {
+        action: request.action,
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    })
+}
+
+/// 🔍 The active project of a repository, looked up once per bulk request
+async fn active_project<'a>(
+    pool: &PgPool,
+    projects: &'a mut HashMap<String, Option<Project>>,
+    repository: &str,
+) -> Result<Option<&'a Project>> {
+    if !projects.contains_key(repository) {
+        let project = Project::list_by_repository(pool, repository)
+            .await?
+            .into_iter()
+            .find(|project| project.is_active);
+        projects.insert(repository.to_string(), project);
+    }
+    Ok(projects[repository].as_ref())
+}
+
+/// 🚀 Queue a run for each retried item and return how many were queued
+/// An item whose run can't be queued is failed rather than left processing
+async fn queue_retries(
+    pool: &PgPool,
+    jobs: &dyn JobQueue,
+    retries: Vec<(Project, Feedback)>,
+) -> usize {
+    let mut queued = 0;
+    for (project, mut feedback) in retries {
+        let job = runs::run_job(&project, &feedback, PipelineMode::Feedback);
+        match jobs.enqueue(&job).await {
+            Ok(_) => queued += 1,
+            Err(e) => {
+                warn!("⚠️ Could not queue retry of {}: {:#}", feedback.id, e);
+                let message = format!("Could not queue the retry: {:#}", e);
+                if let Err(e) = feedback
+                    .update_status(pool, FeedbackStatus::Failed, Some(message))
+                    .await
+                {
+                    warn!("⚠️ Could not fail feedback {}: {:#}", feedback.id, e);
+                }
+            }
+        }
+    }
+    queued
+}
+
+/// ❌ An item the action was not applied to
+fn skipped(id: uuid::Uuid, status: Option<FeedbackStatus>, reason: &str) -> BulkItemResult {
+    BulkItemResult {
+        id,
+        success: false,
+        status,
+        error: Some(reason.to_string()),
+    }
+}
+
+// 🧪 Tests - Only the right items move!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::queue::RecordingQueue;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn request(action: BulkAction, ids: Vec<Uuid>, label: Option<&str>) -> BulkFeedbackRequest {
+        BulkFeedbackRequest {
+            action,
+            ids,
+            label: label.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        assert_eq!(
+            transition(BulkAction::Retry, FeedbackStatus::Failed),
+            Ok(Some(FeedbackStatus::Pending))
+        );
+        assert_eq!(
+            transition(BulkAction::Retry, FeedbackStatus::Paused),
+            Ok(Some(FeedbackStatus::Pending))
+        );
+        assert_eq!(
+            transition(BulkAction::Retry, FeedbackStatus::Processing),
+            Err("not_retryable")
+        );
+        assert_eq!(
+            transition(BulkAction::Cancel, FeedbackStatus::Pending),
+            Ok(Some(FeedbackStatus::Failed))
+        );
+        assert_eq!(
+            transition(BulkAction::Cancel, FeedbackStatus::Completed),
+            Err("already_finished")
+        );
+        assert_eq!(
+            transition(BulkAction::SetLabel, FeedbackStatus::Completed),
+            Ok(None)
+        );
+        println!("✅ Bulk transition test passed!");
+    }
+
+    #[test]
+    fn test_validate_bulk_request() {
+        let id = Uuid::new_v4();
+        assert!(validate(&request(BulkAction::Retry, vec![id], None)).is_ok());
+        assert!(validate(&request(BulkAction::SetLabel, vec![id], Some("outage"))).is_ok());
+
+        assert_eq!(
+            validate(&request(BulkAction::Retry, vec![], None))
+                .unwrap_err()
+                .len(),
+            1
+        );
+        assert_eq!(
+            validate(&request(BulkAction::Cancel, vec![id, id], Some("x")))
+                .unwrap_err()
+                .len(),
+            2
+        );
+        assert!(validate(&request(BulkAction::SetLabel, vec![id], Some("  "))).is_err());
+        let too_many = (0..=MAX_BULK_ITEMS).map(|_| Uuid::new_v4()).collect();
+        assert!(validate(&request(BulkAction::Retry, too_many, None)).is_err());
+        println!("✅ Bulk request validation test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_retry_queues_runs() {
+        let now = Utc::now();
+        let project = Project {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            repository: "aye-is/feedbacker".to_string(),
+            description: None,
+            default_llm_provider: None,
+            system_message: None,
+            config: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+            last_activity_at: None,
+            organization_id: None,
+            team_id: None,
+        };
+        let feedback = Feedback {
+            id: Uuid::new_v4(),
+            user_id: Some(Uuid::new_v4()),
+            repository: project.repository.clone(),
+            path: None,
+            content: "Add dark mode".to_string(),
+            status: FeedbackStatus::Processing,
+            branch_name: None,
+            pull_request_url: None,
+            llm_provider: None,
+            metadata: None,
+            error_message: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            assigned_to: None,
+            submitter_email: None,
+            claim_confirmed_at: None,
+        };
+
+        let pool = PgPool::connect_lazy("postgres://localhost/feedbacker").unwrap();
+        let queue = RecordingQueue::default();
+        let retries = vec![(project.clone(), feedback.clone())];
+        assert_eq!(queue_retries(&pool, &queue, retries).await, 1);
+
+        let jobs = queue.jobs.lock().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_type, runs::RUN_JOB);
+        assert_eq!(jobs[0].user_id, feedback.user_id);
+        assert_eq!(
+            jobs[0].payload,
+            serde_json::json!({
+                "feedback_id": feedback.id,
+                "project_id": project.id,
+                "mode": PipelineMode::Feedback,
+            })
+        );
+        println!("✅ Bulk retry queueing test passed!");
+    }
+}
@@ -0,0 +1,323 @@
+// 🙋 Feedback Claims - Anonymous Feedback Finds Its Owner! 🙋
+// Feedback submitted without a person's account behind it (by a service account
+// such as the Smart Tree integration, on someone's behalf) can carry the
+// submitter's email in `user_info`. That address gets an email with a signed
+// claim link, good for CLAIM_LINK_DAYS days. Opening it proves the address is
+// theirs: the feedback left with it moves to the account with that email right
+// away, or as soon as one is registered or signs in. Accounts whose email is
+// verified (single sign-on vouches for it) claim on sign-in without a link.
+// Emails need SMTP_HOST and ENABLE_EMAIL_NOTIFICATIONS
+// Created with love by Aye & Hue - Finders keepers! ✨
+
+use anyhow::{Context, Result};
+use askama::Template;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::Config;
+use crate::database::models::{Feedback, NewBackgroundJob, User, UserRole};
+use crate::email::{Mailer, OutgoingEmail};
+use crate::i18n::{self, Locale};
+use crate::jobs::worker::{self, JobHandler};
+use crate::utils::signed_links;
+
+/// 🔧 Job type emailing a claim link for one feedback item
+pub const CLAIM_EMAIL_JOB: &str = "feedback_claim_email";
+
+/// ⏳ How long a claim link works
+pub const CLAIM_LINK_DAYS: i64 = 30;
+
+/// 🏷️ Domain claim links are signed under
+const CLAIM_DOMAIN: &str = "feedback-claim";
+
+/// 🔗 Signed link that claims the feedback left with an email
+pub fn claim_url(public_url: &str, secret: &str, email: &str, expires: DateTime<Utc>) -> String {
+    let expires = expires.timestamp();
+    let signature = signed_links::sign(secret, CLAIM_DOMAIN, &[&email.to_lowercase(), &expires]);
+    let query = serde_urlencoded::to_string([
+        ("email", email),
+        ("expires", &expires.to_string()),
+        ("signature", &signature),
+    ])
+    .expect("a claim link's query is plain strings");
+    format!(
+        "{}/feedback/claim?{}",
+        public_url.trim_end_matches('/'),
+        query
+    )
+}
+
+/// ✅ Whether a claim link is genuine and hasn't expired
+pub fn verify_claim(
+    secret: &str,
+    email: &str,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    expires > now.timestamp()
+        && signed_links::verify(
+            secret,
+            CLAIM_DOMAIN,
+            &[&email.to_lowercase(), &expires],
+            signature,
+        )
+}
+
+/// 📧 Keep the email an anonymous submitter left, and send them a claim link
+/// (feedback a person submitted under their own account has nothing to claim)
+pub async fn offer_claim(app_state: &AppState, feedback: &mut Feedback, email: &str) -> Result<()> {
+    let pool = &app_state.db_pool;
+    if let Some(user_id) = feedback.user_id {
+        let submitter = User::find_by_id(pool, user_id).await?;
+        if !submitter.is_some_and(|submitter| matches!(submitter.role, UserRole::Service)) {
+            return Ok(());
+        }
+    }
+    feedback.set_submitter_email(pool, email).await?;
+
+    let config = app_state.config.load();
+    if config.email.is_none() || !config.features.enable_email_notifications {
+        return Ok(());
+    }
+    let payload = serde_json::json!({
+        "feedback_id": feedback.id,
+        "locale": Locale::current().code(),
+    });
+    let job = NewBackgroundJob::new(CLAIM_EMAIL_JOB, payload).with_max_retries(2);
+    if let Err(e) = app_state.jobs.enqueue(&job).await {
+        warn!(
+            "⚠️ Failed to queue the claim email for feedback {}: {:#}",
+            feedback.id, e
+        );
+    }
+    Ok(())
+}
+
+/// 🙋 What opening a claim link did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimOutcome {
+    /// 🎉 This many items moved to the account with the email
+    Attached(u64),
+    /// ⏳ No account has the email yet; registering or signing in claims the items
+    Confirmed,
+}
+
+/// 🔗 Follow a genuine claim link for an email
+pub async fn confirm(pool: &PgPool, email: &str) -> Result<ClaimOutcome> {
+    Feedback::confirm_claims(pool, email).await?;
+    let account = User::find_by_email(pool, email)
+        .await?
+        .filter(|user| user.is_active && !matches!(user.role, UserRole::Service));
+    match account {
+        Some(user) => {
+            let attached = Feedback::attach_claimed(pool, user.id, email, true).await?;
+            info!("🙋 {} claimed {} feedback items", user.email, attached);
+            Ok(ClaimOutcome::Attached(attached))
+        }
+        None => Ok(ClaimOutcome::Confirmed),
+    }
+}
+
+/// 🔐 Attach the feedback someone claimed to their account as they sign in or
+/// register (a failure is logged, never passed on to the sign-in)
+pub async fn claim_on_sign_in(
+    app_state: &AppState,
+    user_id: Uuid,
+    email: &str,
+    email_verified: bool,
+) {
+    match Feedback::attach_claimed(&app_state.db_pool, user_id, email, email_verified).await {
+        Ok(0) => {}
+        Ok(attached) => info!("🙋 {} claimed {} feedback items", email, attached),
+        Err(e) => warn!("⚠️ Failed to attach claimed feedback of {}: {:#}", email, e),
+    }
+}
+
+/// 🙋 A claim email, with every message already in the submitter's language
+struct ClaimEmail {
+    locale: Locale,
+    subject: String,
+    greeting: String,
+    intro: String,
+    action: String,
+    claim_url: String,
+    footer: String,
+}
+
+/// 🎨 The HTML part
+#[derive(Template)]
+#[template(path = "email/feedback_claim.html")]
+struct ClaimEmailHtml<'a> {
+    claim: &'a ClaimEmail,
+}
+
+/// 📝 The plain text part
+#[derive(Template)]
+#[template(path = "email/feedback_claim.txt")]
+struct ClaimEmailText<'a> {
+    claim: &'a ClaimEmail,
+}
+
+impl ClaimEmail {
+    /// 🙋 The claim email for feedback on `repository`, in the current locale
+    fn new(repository: &str, claim_url: String) -> Self {
+        let repository = || [("repository", repository.into())];
+        Self {
+            locale: Locale::current(),
+            subject: i18n::t_with("feedback-claim-subject", repository()),
+            greeting: i18n::t("feedback-claim-greeting"),
+            intro: i18n::t_with("feedback-claim-intro", repository()),
+            action: i18n::t("feedback-claim-action"),
+            claim_url,
+            footer: i18n::t_with("feedback-claim-footer", [("days", CLAIM_LINK_DAYS.into())]),
+        }
+    }
+
+    /// ✉️ The claim email to `to`
+    fn email(&self, to: &str) -> Result<OutgoingEmail> {
+        Ok(OutgoingEmail {
+            to: to.to_string(),
+            subject: self.subject.clone(),
+            html: ClaimEmailHtml { claim: self }
+                .render()
+                .context("Failed to render claim email")?,
+            text: ClaimEmailText { claim: self }
+                .render()
+                .context("Failed to render claim email")?,
+        })
+    }
+}
+
+/// 🔧 Worker pool handler that emails a claim link
+pub fn claim_email_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        async move {
+            let feedback_id: Uuid = serde_json::from_value(job.payload["feedback_id"].clone())
+                .context("Claim email job has no feedback_id")?;
+            let locale: Locale = job.payload["locale"]
+                .as_str()
+                .and_then(|code| code.parse().ok())
+                .unwrap_or_default();
+            locale
+                .scope(send_claim_email(&db_pool, &config, feedback_id))
+                .await
+        }
+    })
+}
+
+/// ✉️ Email the claim link for a feedback item to the address left with it
+async fn send_claim_email(pool: &PgPool, config: &Config, feedback_id: Uuid) -> Result<()> {
+    let email = match &config.email {
+        Some(email) if config.features.enable_email_notifications => email,
+        _ => {
+            info!("🙋 Email notifications are off, no claim email to send");
+            return Ok(());
+        }
+    };
+    let Some(feedback) = Feedback::find_by_id(pool, feedback_id).await? else {
+        return Ok(());
+    };
+    let Some(to) = feedback.submitter_email.as_deref() else {
+        return Ok(());
+    };
+
+    let expires = Utc::now() + Duration::days(CLAIM_LINK_DAYS);
+    let url = claim_url(
+        &config.server.public_url,
+        &config.auth.jwt_secret,
+        to,
+        expires,
+    );
+    let claim = ClaimEmail::new(&feedback.repository, url);
+    Mailer::new(email)?.send(&claim.email(to)?).await?;
+    info!("🙋 Sent a claim link for feedback {}", feedback.id);
+    Ok(())
+}
+
+// 🧪 Tests - Only the rightful owner gets it back!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_links() {
+        let secret = "test-secret";
+        let now = Utc::now();
+        let expires = now + Duration::days(CLAIM_LINK_DAYS);
+        let url = claim_url(
+            "https://feedbacker.example.com/",
+            secret,
+            "Hue+smart-tree@example.com",
+            expires,
+        );
+        assert!(url.starts_with("https://feedbacker.example.com/feedback/claim?email=Hue%2Bsmart-tree%40example.com&expires="));
+
+        let signature = url.rsplit("signature=").next().unwrap();
+        let expires = expires.timestamp();
+        assert!(verify_claim(
+            secret,
+            "hue+smart-tree@example.com",
+            expires,
+            signature,
+            now
+        ));
+        assert!(!verify_claim(
+            secret,
+            "aye@example.com",
+            expires,
+            signature,
+            now
+        ));
+        assert!(!verify_claim(
+            secret,
+            "hue+smart-tree@example.com",
+            expires + 1,
+            signature,
+            now
+        ));
+        assert!(!verify_claim(
+            secret,
+            "hue+smart-tree@example.com",
+            expires,
+            signature,
+            now + Duration::days(CLAIM_LINK_DAYS + 1)
+        ));
+        assert!(!verify_claim(
+            secret,
+            "hue+smart-tree@example.com",
+            expires,
+            "not-hex",
+            now
+        ));
+        println!("✅ Claim link test passed!");
+    }
+
+    #[test]
+    fn test_claim_email() {
+        let claim = ClaimEmail {
+            locale: Locale::English,
+            subject: "🙋 Track your feedback on aye-is/feedbacker".to_string(),
+            greeting: "Hi there,".to_string(),
+            intro: "Thanks for your feedback on aye-is/feedbacker!".to_string(),
+            action: "Claim your feedback".to_string(),
+            claim_url:
+                "https://feedbacker.example.com/feedback/claim?email=a%40b.c&expires=1&signature=ab"
+                    .to_string(),
+            footer: "The link works for 30 days.".to_string(),
+        };
+        let email = claim.email("hue@example.com").unwrap();
+        assert!(email.html.contains("email=a%40b.c&amp;expires=1"));
+        assert!(email
+            .text
+            .contains("Claim your feedback: https://feedbacker.example.com/feedback/claim?email=a%40b.c&expires=1"));
+        println!("✅ Claim email test passed!");
+    }
+}
@@ -0,0 +1,260 @@
+// 🧾 Feedback Lifecycle - The Timeline Is the Record! 🧾
+// A feedback item's status, error message and completion time are decided by
+// the events in its timeline (created, status_changed, lifecycle_snapshot); the
+// columns on the feedback row are a projection of them. Appending an event locks
+// the row and takes the next version, so a webhook and a job changing the same
+// item queue up instead of overwriting each other, and every change is on record.
+// `feedbacker rebuild-projections` replays timelines and repairs rows that drifted
+// Created with love by Aye & Hue - Nothing happens off the books! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::database::models::{Feedback, FeedbackEvent, FeedbackStatus};
+
+/// 📋 The part of a feedback row its timeline decides
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedbackLifecycle {
+    pub status: FeedbackStatus,
+    pub error_message: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Default for FeedbackLifecycle {
+    fn default() -> Self {
+        Self {
+            status: FeedbackStatus::Pending,
+            error_message: None,
+            completed_at: None,
+        }
+    }
+}
+
+impl FeedbackLifecycle {
+    /// ➕ The lifecycle after one more event (events that don't touch it are skipped)
+    pub fn apply(self, event: &FeedbackEvent) -> Self {
+        let payload = &event.payload;
+        let Some(status) = payload
+            .get("status")
+            .and_then(|status| status.as_str())
+            .and_then(parse_status)
+        else {
+            return self;
+        };
+        let error_message = payload
+            .get("error_message")
+            .and_then(|message| message.as_str())
+            .map(str::to_string);
+
+        match event.event_type.as_str() {
+            FeedbackEvent::CREATED => Self {
+                status,
+                error_message: None,
+                completed_at: None,
+            },
+            FeedbackEvent::STATUS_CHANGED => Self {
+                status,
+                error_message,
+                completed_at: status.is_finished().then_some(event.created_at),
+            },
+            FeedbackEvent::SNAPSHOT => Self {
+                status,
+                error_message,
+                completed_at: payload
+                    .get("completed_at")
+                    .and_then(|at| serde_json::from_value(at.clone()).ok()),
+            },
+            _ => self,
+        }
+    }
+
+    /// 🔁 Replay a timeline, oldest event first
+    pub fn replay<'a>(events: impl IntoIterator<Item = &'a FeedbackEvent>) -> Self {
+        events
+            .into_iter()
+            .fold(Self::default(), |lifecycle, event| lifecycle.apply(event))
+    }
+
+    /// 🔍 Whether a feedback row already shows this lifecycle
+    fn matches(&self, feedback: &Feedback) -> bool {
+        self.status == feedback.status
+            && self.error_message == feedback.error_message
+            && self.completed_at == feedback.completed_at
+    }
+}
+
+/// 🏷️ A status from its database name
+fn parse_status(name: &str) -> Option<FeedbackStatus> {
+    FeedbackStatus::ALL
+        .into_iter()
+        .find(|status| status.as_str() == name)
+}
+
+/// 📊 What a rebuild found
+#[derive(Debug, Default)]
+pub struct RebuildSummary {
+    /// 🔍 Feedback items replayed
+    pub checked: usize,
+    /// 🔧 Rows that didn't match their timeline (and were fixed, unless a dry run)
+    pub repaired: usize,
+}
+
+/// 🔁 Replay timelines onto feedback rows (one item, or all of them), fixing
+/// rows that don't match. Each item is locked while it's replayed
+pub async fn rebuild(pool: &PgPool, only: Option<Uuid>, dry_run: bool) -> Result<RebuildSummary> {
+    let ids = match only {
+        Some(id) => vec![id],
+        None => Feedback::all_ids(pool).await?,
+    };
+
+    let mut summary = RebuildSummary::default();
+    for id in ids {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to start rebuild transaction")?;
+        let Some(feedback) = Feedback::find_for_update(&mut transaction, id).await? else {
+            anyhow::bail!("Feedback {} not found", id);
+        };
+        let events = FeedbackEvent::list_for_feedback_in(&mut transaction, id).await?;
+        summary.checked += 1;
+
+        let lifecycle = FeedbackLifecycle::replay(&events);
+        if lifecycle.matches(&feedback) {
+            continue;
+        }
+        summary.repaired += 1;
+        warn!(
+            "🧾 Feedback {} shows {} but its timeline says {}",
+            id,
+            feedback.status.as_str(),
+            lifecycle.status.as_str()
+        );
+        if !dry_run {
+            let updated_at = events.last().map_or(feedback.updated_at, |e| e.created_at);
+            Feedback::set_lifecycle(&mut transaction, id, &lifecycle, updated_at).await?;
+            transaction
+                .commit()
+                .await
+                .context("Failed to commit rebuilt feedback")?;
+        }
+    }
+
+    Ok(summary)
+}
+
+// 🧪 Tests - Every status, straight from the record!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn event(
+        version: i64,
+        event_type: &str,
+        payload: serde_json::Value,
+        created_at: DateTime<Utc>,
+    ) -> FeedbackEvent {
+        FeedbackEvent {
+            id: Uuid::new_v4(),
+            feedback_id: Uuid::nil(),
+            event_type: event_type.to_string(),
+            payload,
+            created_at,
+            version,
+        }
+    }
+
+    #[test]
+    fn test_replay() {
+        let start = Utc::now();
+        let failed_at = start + Duration::minutes(3);
+        let events = vec![
+            event(
+                1,
+                FeedbackEvent::CREATED,
+                json!({ "status": "pending" }),
+                start,
+            ),
+            event(
+                2,
+                FeedbackEvent::STATUS_CHANGED,
+                json!({ "status": "processing", "error_message": null }),
+                start + Duration::minutes(1),
+            ),
+            event(
+                3,
+                FeedbackEvent::PLAN_CREATED,
+                json!({ "steps": [] }),
+                start + Duration::minutes(2),
+            ),
+            event(
+                4,
+                FeedbackEvent::STATUS_CHANGED,
+                json!({ "status": "failed", "error_message": "LLM timed out" }),
+                failed_at,
+            ),
+        ];
+        let lifecycle = FeedbackLifecycle::replay(&events);
+        assert_eq!(lifecycle.status, FeedbackStatus::Failed);
+        assert_eq!(lifecycle.error_message.as_deref(), Some("LLM timed out"));
+        assert_eq!(lifecycle.completed_at, Some(failed_at));
+
+        // 🔁 A retry clears the error and the completion time
+        let retried = lifecycle.apply(&event(
+            5,
+            FeedbackEvent::STATUS_CHANGED,
+            json!({ "status": "pending" }),
+            failed_at + Duration::minutes(1),
+        ));
+        assert_eq!(retried, FeedbackLifecycle::default());
+        println!("✅ Lifecycle replay test passed!");
+    }
+
+    #[test]
+    fn test_snapshot_and_unknown_events() {
+        let completed_at = "2024-03-01T12:30:00.5+00:00"
+            .parse::<DateTime<Utc>>()
+            .unwrap();
+        let events = vec![
+            event(
+                1,
+                FeedbackEvent::STATUS_CHANGED,
+                json!({ "status": "processing" }),
+                Utc::now(),
+            ),
+            event(
+                2,
+                FeedbackEvent::SNAPSHOT,
+                json!({
+                    "status": "completed",
+                    "error_message": null,
+                    "completed_at": "2024-03-01T12:30:00.5+00:00"
+                }),
+                Utc::now(),
+            ),
+            event(3, FeedbackEvent::PULL_REQUEST_STALE, json!({}), Utc::now()),
+            event(
+                4,
+                FeedbackEvent::STATUS_CHANGED,
+                json!({ "status": "not_a_status" }),
+                Utc::now(),
+            ),
+        ];
+        let lifecycle = FeedbackLifecycle::replay(&events);
+        assert_eq!(lifecycle.status, FeedbackStatus::Completed);
+        assert_eq!(lifecycle.completed_at, Some(completed_at));
+        assert_eq!(lifecycle.error_message, None);
+
+        assert_eq!(FeedbackLifecycle::replay(&[]), FeedbackLifecycle::default());
+        assert_eq!(
+            parse_status("generating_changes"),
+            Some(FeedbackStatus::GeneratingChanges)
+        );
+        println!("✅ Lifecycle snapshot test passed!");
+    }
+}
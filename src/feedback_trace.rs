@@ -0,0 +1,490 @@
+// 🧵 Feedback Tracing - One Thread From Submission to Pull Request! 🧵
+// Each stage of a feedback item's life (submission, background job, pipeline
+// run, LLM call, git checkout, sandbox run, pull request, webhook) runs in a
+// `stage` span carrying the item's `feedback_id`, so its log lines can be picked
+// out of everything else the service is doing. A stage span that isn't told the
+// id inherits it from the stage it was started in. The `StageTimingLayer` times
+// every stage span with a feedback id when it closes and hands it to the
+// recorder, which stores finished stages in feedback_spans in batches;
+// GET /api/feedback/:id/trace sums them up per stage next to the item's events.
+// Recording never slows the pipeline down: when the recorder falls behind,
+// timings are dropped rather than waited for
+// Created with love by Aye & Hue - Follow the feedback, not the noise! ✨
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::field::{self, Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{info_span, warn, Instrument, Span, Subscriber};
+use tracing_subscriber::filter::{Filtered, Targets};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use uuid::Uuid;
+
+use crate::database::models::{
+    Feedback, FeedbackEvent, FeedbackSpan, FeedbackStatus, NewFeedbackSpan,
+};
+
+/// 🎯 Target stage spans are created under
+pub const STAGE_TARGET: &str = "feedbacker::stage";
+
+/// 📮 Finished stages waiting for the recorder, at most
+const RECORDER_BUFFER: usize = 10_000;
+
+/// 📦 Finished stages stored per statement, at most
+const RECORDER_BATCH: usize = 200;
+
+/// 📮 Where the layer sends finished stages (set once the recorder runs)
+static RECORDER: OnceLock<mpsc::Sender<NewFeedbackSpan>> = OnceLock::new();
+
+/// 🏷️ A stage of a feedback item's life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// 📝 Validating and storing a submission
+    Submission,
+    /// 🔄 A background job working on the item
+    Job,
+    /// 🏭 A whole pipeline run
+    Pipeline,
+    /// 🤖 One LLM completion (fallbacks and retries included)
+    Llm,
+    /// 🌿 Cloning or updating a checkout
+    Git,
+    /// 📦 Running commands in the sandbox
+    Sandbox,
+    /// 🐙 Opening a pull request
+    PullRequest,
+    /// 🪝 Handling a GitHub webhook about the item
+    Webhook,
+}
+
+impl Stage {
+    /// 🏷️ Name stored in feedback_spans and shown in logs
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Stage::Submission => "submission",
+            Stage::Job => "job",
+            Stage::Pipeline => "pipeline",
+            Stage::Llm => "llm",
+            Stage::Git => "git",
+            Stage::Sandbox => "sandbox",
+            Stage::PullRequest => "pull_request",
+            Stage::Webhook => "webhook",
+        }
+    }
+
+    /// 🧵 A span for this stage (the feedback id is inherited when None)
+    pub fn span(self, feedback_id: Option<Uuid>) -> Span {
+        let span = info_span!(
+            target: STAGE_TARGET,
+            "stage",
+            stage = self.as_str(),
+            feedback_id = field::Empty,
+            failed = field::Empty,
+        );
+        if let Some(feedback_id) = feedback_id {
+            attach_feedback(&span, feedback_id);
+        }
+        span
+    }
+}
+
+/// 📝 Name the feedback a stage works on, once it is known
+pub fn attach_feedback(span: &Span, feedback_id: Uuid) {
+    span.record("feedback_id", field::display(feedback_id));
+}
+
+/// ❌ Mark a stage as failed
+pub fn mark_failed(span: &Span) {
+    span.record("failed", true);
+}
+
+/// 🧵 Run async work as a stage, marking it failed when it errs
+pub async fn traced<T>(span: Span, work: impl Future<Output = Result<T>>) -> Result<T> {
+    let result = work.instrument(span.clone()).await;
+    if result.is_err() {
+        mark_failed(&span);
+    }
+    result
+}
+
+/// 🧵 Run blocking work as a stage (create the span before `spawn_blocking`,
+/// so it knows which stage it was started in)
+pub fn traced_blocking<T>(span: Span, work: impl FnOnce() -> Result<T>) -> Result<T> {
+    let result = span.in_scope(work);
+    if result.is_err() {
+        mark_failed(&span);
+    }
+    result
+}
+
+/// ⏱️ Layer timing stage spans that belong to a feedback item
+pub struct StageTimingLayer {
+    /// 📮 Where finished stages go (the global recorder when None)
+    sink: Option<mpsc::Sender<NewFeedbackSpan>>,
+}
+
+impl StageTimingLayer {
+    /// 🎯 The layer, seeing stage spans only (whatever the log filter lets through)
+    pub fn filtered<S>() -> Filtered<Self, Targets, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        StageTimingLayer { sink: None }
+            .with_filter(Targets::new().with_target(STAGE_TARGET, tracing::Level::INFO))
+    }
+
+    /// 📮 Hand a finished stage over (dropped when nobody records, or the buffer is full)
+    fn send(&self, span: NewFeedbackSpan) {
+        if let Some(sink) = self.sink.as_ref().or_else(|| RECORDER.get()) {
+            let _ = sink.try_send(span);
+        }
+    }
+}
+
+/// ⏱️ What the layer keeps on an open stage span
+#[derive(Debug)]
+struct OpenStage {
+    stage: String,
+    feedback_id: Option<Uuid>,
+    started: Instant,
+    started_at: DateTime<Utc>,
+    failed: bool,
+}
+
+/// 📋 Stage span fields, as recorded
+#[derive(Default)]
+struct StageFields {
+    stage: Option<String>,
+    feedback_id: Option<Uuid>,
+    failed: bool,
+}
+
+impl Visit for StageFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "stage" => self.stage = Some(value.to_string()),
+            "feedback_id" => self.feedback_id = Uuid::parse_str(value).ok(),
+            _ => {}
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "failed" {
+            self.failed = value;
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "feedback_id" {
+            self.feedback_id = Uuid::parse_str(&format!("{:?}", value)).ok();
+        }
+    }
+}
+
+impl<S> Layer<S> for StageTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = StageFields::default();
+        attrs.record(&mut fields);
+        let Some(stage) = fields.stage else {
+            return;
+        };
+
+        // 🧬 Started inside another stage: same feedback
+        let inherited = span.scope().skip(1).find_map(|ancestor| {
+            ancestor
+                .extensions()
+                .get::<OpenStage>()
+                .and_then(|open| open.feedback_id)
+        });
+        span.extensions_mut().insert(OpenStage {
+            stage,
+            feedback_id: fields.feedback_id.or(inherited),
+            started: Instant::now(),
+            started_at: Utc::now(),
+            failed: fields.failed,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = StageFields::default();
+        values.record(&mut fields);
+        let mut extensions = span.extensions_mut();
+        if let Some(open) = extensions.get_mut::<OpenStage>() {
+            if fields.feedback_id.is_some() {
+                open.feedback_id = fields.feedback_id;
+            }
+            open.failed |= fields.failed;
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(open) = extensions.get::<OpenStage>() else {
+            return;
+        };
+        if let Some(feedback_id) = open.feedback_id {
+            self.send(NewFeedbackSpan {
+                feedback_id,
+                stage: open.stage.clone(),
+                started_at: open.started_at,
+                duration_ms: open.started.elapsed().as_millis() as i64,
+                failed: open.failed,
+            });
+        }
+    }
+}
+
+/// 🚀 Start storing the stages the layer times
+pub fn start_recorder(pool: PgPool) {
+    let (sender, mut receiver) = mpsc::channel(RECORDER_BUFFER);
+    if RECORDER.set(sender).is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(RECORDER_BATCH);
+        while receiver.recv_many(&mut batch, RECORDER_BATCH).await > 0 {
+            if let Err(e) = FeedbackSpan::record_many(&pool, &batch).await {
+                warn!(
+                    "⚠️ {} feedback stage timings were lost: {:#}",
+                    batch.len(),
+                    e
+                );
+            }
+            batch.clear();
+        }
+    });
+}
+
+/// 📊 Timings of one stage, over every time it ran
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageSummary {
+    pub stage: String,
+    /// 🔢 Times it ran
+    pub runs: usize,
+    /// ❌ Runs that failed
+    pub failures: usize,
+    /// ⏱️ Time spent in it, all runs together
+    pub total_ms: i64,
+    /// 🐢 Longest run
+    pub max_ms: i64,
+    /// ⏰ When the first run started
+    pub first_started_at: DateTime<Utc>,
+    /// ⏰ When the last run finished
+    pub last_finished_at: DateTime<Utc>,
+}
+
+/// 🕰️ An event on the item's timeline, for lining stages up against
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    pub event_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 🧵 Where a feedback item's time went
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackTrace {
+    pub feedback_id: Uuid,
+    pub status: FeedbackStatus,
+    pub created_at: DateTime<Utc>,
+    /// ⏱️ From submission to the end of the last recorded stage
+    pub elapsed_ms: Option<i64>,
+    /// 📊 Per stage, in the order they first started
+    pub stages: Vec<StageSummary>,
+    /// 🧵 Every recorded stage run, in the order they started
+    pub spans: Vec<FeedbackSpan>,
+    /// 🕰️ The item's events
+    pub events: Vec<TraceEvent>,
+}
+
+/// 🧵 Load and sum up the recorded stages of a feedback item
+pub async fn load(pool: &PgPool, feedback: &Feedback) -> Result<FeedbackTrace> {
+    let spans = FeedbackSpan::list_for_feedback(pool, feedback.id).await?;
+    let events = FeedbackEvent::list_for_feedback(pool, feedback.id).await?;
+    Ok(summarize(feedback, spans, events))
+}
+
+/// 📊 Sum recorded stages up per stage
+pub fn summarize(
+    feedback: &Feedback,
+    mut spans: Vec<FeedbackSpan>,
+    events: Vec<FeedbackEvent>,
+) -> FeedbackTrace {
+    spans.sort_by_key(|span| span.started_at);
+    let finished_at = |span: &FeedbackSpan| {
+        span.started_at + Duration::from_millis(span.duration_ms.max(0) as u64)
+    };
+
+    let mut stages: Vec<StageSummary> = Vec::new();
+    for span in &spans {
+        let finished = finished_at(span);
+        match stages
+            .iter_mut()
+            .find(|summary| summary.stage == span.stage)
+        {
+            Some(summary) => {
+                summary.runs += 1;
+                summary.failures += usize::from(span.failed);
+                summary.total_ms += span.duration_ms;
+                summary.max_ms = summary.max_ms.max(span.duration_ms);
+                summary.last_finished_at = summary.last_finished_at.max(finished);
+            }
+            None => stages.push(StageSummary {
+                stage: span.stage.clone(),
+                runs: 1,
+                failures: usize::from(span.failed),
+                total_ms: span.duration_ms,
+                max_ms: span.duration_ms,
+                first_started_at: span.started_at,
+                last_finished_at: finished,
+            }),
+        }
+    }
+
+    let elapsed_ms = spans
+        .iter()
+        .map(finished_at)
+        .max()
+        .map(|last| (last - feedback.created_at).num_milliseconds().max(0));
+
+    FeedbackTrace {
+        feedback_id: feedback.id,
+        status: feedback.status,
+        created_at: feedback.created_at,
+        elapsed_ms,
+        stages,
+        spans,
+        events: events
+            .into_iter()
+            .map(|event| TraceEvent {
+                event_type: event.event_type,
+                created_at: event.created_at,
+            })
+            .collect(),
+    }
+}
+
+// 🧪 Tests - Every stage accounted for!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_stage_timing_layer() {
+        let (sink, mut finished) = mpsc::channel(16);
+        let subscriber = tracing_subscriber::registry().with(StageTimingLayer { sink: Some(sink) });
+        let feedback_id = Uuid::new_v4();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let pipeline = Stage::Pipeline.span(Some(feedback_id));
+            pipeline.in_scope(|| {
+                // 🧬 Inherited from the pipeline run
+                let llm: Result<()> =
+                    traced_blocking(Stage::Llm.span(None), || anyhow::bail!("rate limited"));
+                assert!(llm.is_err());
+            });
+            drop(pipeline);
+
+            // 🤷 Stages of no feedback aren't recorded
+            drop(Stage::Job.span(None));
+            // 📝 ... unless they learn it later
+            let submission = Stage::Submission.span(None);
+            attach_feedback(&submission, feedback_id);
+            drop(submission);
+        });
+
+        let llm = finished.try_recv().unwrap();
+        assert_eq!(
+            (llm.feedback_id, llm.stage.as_str(), llm.failed),
+            (feedback_id, "llm", true)
+        );
+        let pipeline = finished.try_recv().unwrap();
+        assert_eq!(
+            (pipeline.stage.as_str(), pipeline.failed),
+            ("pipeline", false)
+        );
+        assert!(pipeline.started_at <= llm.started_at);
+        let submission = finished.try_recv().unwrap();
+        assert_eq!(submission.stage, "submission");
+        assert!(finished.try_recv().is_err());
+        println!("✅ Stage timing layer test passed!");
+    }
+
+    #[test]
+    fn test_summarize() {
+        let created_at = Utc::now();
+        let feedback = Feedback {
+            id: Uuid::new_v4(),
+            user_id: None,
+            repository: "aye-is/feedbacker".to_string(),
+            path: None,
+            content: "Add dark mode".to_string(),
+            status: FeedbackStatus::Completed,
+            branch_name: None,
+            pull_request_url: None,
+            llm_provider: None,
+            metadata: None,
+            error_message: None,
+            created_at,
+            updated_at: created_at,
+            completed_at: None,
+            assigned_to: None,
+            submitter_email: None,
+            claim_confirmed_at: None,
+        };
+        let span = |stage: &str, start_ms: i64, duration_ms: i64, failed: bool| FeedbackSpan {
+            id: Uuid::new_v4(),
+            feedback_id: feedback.id,
+            stage: stage.to_string(),
+            started_at: created_at + chrono::Duration::milliseconds(start_ms),
+            duration_ms,
+            failed,
+            created_at,
+        };
+
+        let trace = summarize(
+            &feedback,
+            vec![
+                span("llm", 300, 200, false),
+                span("pipeline", 100, 1_000, false),
+                span("llm", 600, 400, true),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(trace.elapsed_ms, Some(1_100));
+        let stages: Vec<_> = trace.stages.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(stages, ["pipeline", "llm"]);
+        let llm = &trace.stages[1];
+        assert_eq!(
+            (llm.runs, llm.failures, llm.total_ms, llm.max_ms),
+            (2, 1, 600, 400)
+        );
+        assert_eq!(
+            llm.last_finished_at,
+            created_at + chrono::Duration::milliseconds(1_000)
+        );
+
+        let empty = summarize(&feedback, Vec::new(), Vec::new());
+        assert!(empty.elapsed_ms.is_none() && empty.stages.is_empty());
+        println!("✅ Trace summary test passed!");
+    }
+}
@@ -0,0 +1,182 @@
+// 🗂️ Feedback Triage - A Board for the Maintainers! 🗂️
+// Feedback can be assigned to a maintainer and discussed in internal notes
+// (feedback_comments) that submitters never see. Maintainers are whoever has a
+// role on a project for the feedback's repository (see crate::organizations):
+// viewers read the notes, members and up assign feedback and write notes, and
+// only members and up can be assigned. Notes are deleted by their author or a
+// project admin. GET /api/feedback?assigned_to_me=true lists what's on your plate
+// Created with love by Aye & Hue - Every item gets an owner! ✨
+
+use anyhow::Context;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::models::{Feedback, FeedbackComment, OrgRole, User};
+use crate::i18n;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::organizations;
+
+/// 📏 Longest comment, in characters
+pub const MAX_COMMENT_CHARS: usize = 5000;
+
+/// 🚧 Why a triage request was turned away
+#[derive(Debug)]
+pub enum TriageRejection {
+    /// 🔍 The feedback or comment (named) doesn't exist, or the caller can't see it
+    NotFound(&'static str),
+    /// 🛡️ The caller's role doesn't allow it
+    Forbidden,
+    /// ❌ The request didn't validate (one message per problem)
+    Invalid(Vec<String>),
+    /// 💥 Something else went wrong
+    Failed(anyhow::Error),
+}
+
+impl From<anyhow::Error> for TriageRejection {
+    fn from(error: anyhow::Error) -> Self {
+        TriageRejection::Failed(error)
+    }
+}
+
+/// ✅ Problems with a comment body
+pub fn validate_comment(body: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if body.trim().is_empty() {
+        errors.push(i18n::t("validation-comment-empty"));
+    } else if body.chars().count() > MAX_COMMENT_CHARS {
+        errors.push(i18n::t_with(
+            "validation-comment-too-long",
+            [("max", MAX_COMMENT_CHARS.into())],
+        ));
+    }
+    errors
+}
+
+/// 👑 The caller's triage role on a feedback item, when it is at least `least`
+/// (feedback they can't see is not found; too little access is forbidden)
+async fn require_role(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback: &Feedback,
+    least: OrgRole,
+) -> Result<OrgRole, TriageRejection> {
+    if !organizations::can_view_feedback(pool, user, feedback).await? {
+        return Err(TriageRejection::NotFound("Feedback"));
+    }
+    match organizations::repository_role(pool, user, &feedback.repository).await? {
+        Some(role) if role >= least => Ok(role),
+        _ => Err(TriageRejection::Forbidden),
+    }
+}
+
+/// 🔍 A feedback item, for triage
+async fn find_feedback(pool: &PgPool, feedback_id: Uuid) -> Result<Feedback, TriageRejection> {
+    Feedback::find_by_id(pool, feedback_id)
+        .await?
+        .ok_or(TriageRejection::NotFound("Feedback"))
+}
+
+/// 👥 Assign a feedback item to a maintainer, or unassign it (None)
+pub async fn assign(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+    assignee_id: Option<Uuid>,
+) -> Result<Feedback, TriageRejection> {
+    let mut feedback = find_feedback(pool, feedback_id).await?;
+    require_role(pool, user, &feedback, OrgRole::Member).await?;
+
+    if let Some(assignee_id) = assignee_id {
+        let assignee = User::find_by_id(pool, assignee_id)
+            .await?
+            .filter(|assignee| assignee.is_active);
+        let eligible = match &assignee {
+            Some(assignee) => {
+                let role =
+                    organizations::repository_role(pool, assignee, &feedback.repository).await?;
+                role >= Some(OrgRole::Member)
+            }
+            None => false,
+        };
+        if !eligible {
+            return Err(TriageRejection::Invalid(vec![i18n::t(
+                "validation-assignee-not-maintainer",
+            )]));
+        }
+    }
+
+    feedback
+        .assign(pool, assignee_id)
+        .await
+        .context("Failed to assign feedback")?;
+    info!(
+        "👥 Feedback {} assigned to {:?} by {}",
+        feedback.id, assignee_id, user.email
+    );
+    Ok(feedback)
+}
+
+/// 📋 The internal notes on a feedback item, oldest first
+pub async fn comments(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+) -> Result<Vec<FeedbackComment>, TriageRejection> {
+    let feedback = find_feedback(pool, feedback_id).await?;
+    require_role(pool, user, &feedback, OrgRole::Viewer).await?;
+    Ok(FeedbackComment::list_for_feedback(pool, feedback.id).await?)
+}
+
+/// 💬 Leave an internal note on a feedback item
+pub async fn add_comment(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+    body: &str,
+) -> Result<FeedbackComment, TriageRejection> {
+    let errors = validate_comment(body);
+    if !errors.is_empty() {
+        return Err(TriageRejection::Invalid(errors));
+    }
+    let feedback = find_feedback(pool, feedback_id).await?;
+    require_role(pool, user, &feedback, OrgRole::Member).await?;
+    Ok(FeedbackComment::create(pool, feedback.id, user.id, body.trim()).await?)
+}
+
+/// 🗑️ Delete an internal note (its author's, or any for project admins)
+pub async fn delete_comment(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback_id: Uuid,
+    comment_id: Uuid,
+) -> Result<(), TriageRejection> {
+    let feedback = find_feedback(pool, feedback_id).await?;
+    let role = require_role(pool, user, &feedback, OrgRole::Member).await?;
+    let comment = FeedbackComment::find(pool, feedback.id, comment_id)
+        .await?
+        .ok_or(TriageRejection::NotFound("Comment"))?;
+    if comment.author_id != Some(user.id) && role < OrgRole::Admin {
+        return Err(TriageRejection::Forbidden);
+    }
+    FeedbackComment::delete(pool, comment.id).await?;
+    Ok(())
+}
+
+// 🧪 Tests - Notes worth keeping!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_comment() {
+        assert!(validate_comment("Looks like a dup of the dark mode request").is_empty());
+        assert_eq!(validate_comment("  \n").len(), 1);
+        assert_eq!(
+            validate_comment(&"x".repeat(MAX_COMMENT_CHARS + 1)).len(),
+            1
+        );
+        assert!(validate_comment(&"🚀".repeat(MAX_COMMENT_CHARS)).is_empty());
+        println!("✅ Comment validation test passed!");
+    }
+}
@@ -0,0 +1,392 @@
+// ⏱️ Feedback Watchdog - Nothing Stays in Limbo! ⏱️
+// A worker that crashes mid-pipeline leaves its feedback in processing or
+// generating_changes with nothing left to move it on. Every five minutes the
+// `feedback_watchdog` schedule looks for items that have sat in one of those
+// statuses longer than STUCK_PROCESSING_MINUTES / STUCK_GENERATING_CHANGES_MINUTES
+// with no job queued or running for them. With STUCK_FEEDBACK_ACTION=fail they're
+// failed with a timeout reason; with requeue they go back to pending with their
+// last project run queued again (or a new run on the repository's active project
+// when they never had one), up to STUCK_MAX_REQUEUES times. Items that already
+// opened a PR, or have nothing to run them, are always failed, since running
+// them again could open the same PRs twice. Admins get a notification either
+// way, and /metrics shows how long the longest waiting item in each status has waited
+// Created with love by Aye & Hue - Every item gets where it's going! ✨
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::api::AppState;
+use crate::config::{StuckAction, WatchdogConfig};
+use crate::database::models::{
+    BackgroundJob, Feedback, FeedbackEvent, FeedbackStatus, Notification, NotificationType,
+    Project, User, UserRole,
+};
+use crate::i18n::{self, Locale};
+use crate::jobs::queue::JobQueue;
+use crate::jobs::runs::{self, RUN_JOB};
+use crate::jobs::worker::{self, JobHandler};
+use crate::metrics;
+use crate::pipeline::PipelineMode;
+
+/// 🔧 Job type of the watchdog's sweep
+pub const WATCHDOG_JOB: &str = "feedback_watchdog";
+
+/// 📏 Stuck items handled per status in one sweep
+const SWEEP_LIMIT: i64 = 100;
+
+/// 🔑 Metadata key counting the times the watchdog requeued an item
+const REQUEUES_KEY: &str = "watchdog_requeues";
+
+/// 📋 What happened to a stuck item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// ❌ Failed with a timeout reason
+    Failed,
+    /// 🔁 Back to pending, with its run queued again
+    Requeued,
+}
+
+impl Outcome {
+    /// 🏷️ Label used in metrics and messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Failed => "failed",
+            Outcome::Requeued => "requeued",
+        }
+    }
+}
+
+/// ⏳ Minutes an item may spend in `status` before it's stuck (None = no limit)
+fn threshold(config: &WatchdogConfig, status: FeedbackStatus) -> Option<u32> {
+    let minutes = match status {
+        FeedbackStatus::Processing => config.processing_minutes,
+        FeedbackStatus::GeneratingChanges => config.generating_changes_minutes,
+        _ => 0,
+    };
+    (minutes > 0).then_some(minutes)
+}
+
+/// 🤔 What to do with a stuck item, given how often it was requeued already
+/// and whether it can be run again (it has a run to queue and opened no PR yet)
+pub fn decide(config: &WatchdogConfig, requeues: u32, rerunnable: bool) -> Outcome {
+    match config.action {
+        StuckAction::Requeue if rerunnable && requeues < config.max_requeues => Outcome::Requeued,
+        _ => Outcome::Failed,
+    }
+}
+
+/// 🏭 How a requeued item gets run again
+#[derive(Debug)]
+pub enum Rerun {
+    /// 🔁 Its last project run, with the same payload
+    Run(BackgroundJob),
+    /// 🆕 A first run on its repository's active project
+    Project(Project),
+}
+
+/// 📥 Queue the run that picks a requeued item up again
+pub async fn queue_rerun(jobs: &dyn JobQueue, rerun: &Rerun, feedback: &Feedback) -> Result<()> {
+    let job = match rerun {
+        Rerun::Run(run) => runs::rerun_job(run, feedback),
+        Rerun::Project(project) => runs::run_job(project, feedback, PipelineMode::Feedback),
+    };
+    jobs.enqueue(&job).await?;
+    Ok(())
+}
+
+/// 🔢 Times the watchdog already requeued an item
+fn requeues(feedback: &Feedback) -> u32 {
+    feedback
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(REQUEUES_KEY))
+        .and_then(|count| count.as_u64())
+        .unwrap_or(0) as u32
+}
+
+/// 🔧 Worker pool handler that runs a sweep
+pub fn watchdog_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let jobs = app_state.jobs.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |_job| {
+        let db_pool = db_pool.clone();
+        let jobs = jobs.clone();
+        let config = config.load_full();
+        async move { sweep(&db_pool, jobs.as_ref(), &config.watchdog).await }
+    })
+}
+
+/// 🧹 Fail or requeue every stuck item, and tell the admins
+pub async fn sweep(pool: &PgPool, jobs: &dyn JobQueue, config: &WatchdogConfig) -> Result<()> {
+    let now = Utc::now();
+    for status in [
+        FeedbackStatus::Processing,
+        FeedbackStatus::GeneratingChanges,
+    ] {
+        let age = Feedback::oldest_in_status(pool, status)
+            .await?
+            .and_then(|oldest| (now - oldest).to_std().ok())
+            .unwrap_or_default();
+        metrics::observe_oldest_in_status(status.as_str(), age);
+
+        let Some(minutes) = threshold(config, status) else {
+            continue;
+        };
+        let since = now - Duration::minutes(minutes as i64);
+        for feedback in Feedback::stuck(pool, status, since, SWEEP_LIMIT).await? {
+            match handle(pool, jobs, config, &feedback, minutes).await {
+                Ok(Some(outcome)) => {
+                    metrics::record_stuck_feedback(status.as_str(), outcome.as_str());
+                    alert_admins(pool, &feedback, minutes, outcome).await;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("⚠️ Could not unstick feedback {}: {:#}", feedback.id, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// ⏱️ Fail or requeue one stuck item (None when it moved on in the meantime)
+async fn handle(
+    pool: &PgPool,
+    jobs: &dyn JobQueue,
+    config: &WatchdogConfig,
+    stuck: &Feedback,
+    minutes: u32,
+) -> Result<Option<Outcome>> {
+    let run = BackgroundJob::latest_matching(
+        pool,
+        RUN_JOB,
+        &serde_json::json!({ "feedback_id": stuck.id }),
+    )
+    .await?;
+    let rerun = match run {
+        Some(run) => Some(Rerun::Run(run)),
+        None => Project::list_by_repository(pool, &stuck.repository)
+            .await?
+            .into_iter()
+            .find(|project| project.is_active)
+            .map(Rerun::Project),
+    };
+    // 🐙 Running it again could open the same PRs twice
+    let opened_pr = FeedbackEvent::list_for_feedback(pool, stuck.id)
+        .await?
+        .iter()
+        .any(|event| event.event_type == FeedbackEvent::PULL_REQUEST_OPENED);
+
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to start watchdog transaction")?;
+    // 🔒 A worker that was only slow may have just moved it on
+    let Some(mut feedback) = Feedback::find_for_update(&mut transaction, stuck.id).await? else {
+        return Ok(None);
+    };
+    if feedback.status != stuck.status || feedback.updated_at != stuck.updated_at {
+        return Ok(None);
+    }
+
+    let requeues = requeues(&feedback);
+    let outcome = decide(config, requeues, rerun.is_some() && !opened_pr);
+    match outcome {
+        Outcome::Failed => {
+            let reason = format!(
+                "Timed out after {} minutes in {}",
+                minutes,
+                feedback.status.as_str()
+            );
+            feedback
+                .update_status_in(&mut transaction, FeedbackStatus::Failed, Some(reason))
+                .await?;
+        }
+        Outcome::Requeued => {
+            feedback
+                .update_status_in(&mut transaction, FeedbackStatus::Pending, None)
+                .await?;
+        }
+    }
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit watchdog change")?;
+    if let (Outcome::Requeued, Some(rerun)) = (outcome, &rerun) {
+        feedback
+            .merge_metadata(pool, serde_json::json!({ REQUEUES_KEY: requeues + 1 }))
+            .await?;
+        if let Err(e) = queue_rerun(jobs, rerun, &feedback).await {
+            // ❌ Pending with nothing queued would only be stuck under another name
+            let reason = format!("Could not queue it again after it got stuck: {:#}", e);
+            feedback
+                .update_status(pool, FeedbackStatus::Failed, Some(reason))
+                .await?;
+            return Ok(Some(Outcome::Failed));
+        }
+    }
+
+    warn!(
+        "⏱️ Feedback {} was stuck in {} for over {} minutes: {}",
+        stuck.id,
+        stuck.status.as_str(),
+        minutes,
+        outcome.as_str()
+    );
+    Ok(Some(outcome))
+}
+
+/// 📣 Notify every active admin, in their language (failures are logged)
+async fn alert_admins(pool: &PgPool, feedback: &Feedback, minutes: u32, outcome: Outcome) {
+    let admins = match User::list_active_by_role(pool, UserRole::Admin).await {
+        Ok(admins) => admins,
+        Err(e) => {
+            warn!("⚠️ No stuck feedback alerts for {}: {:#}", feedback.id, e);
+            return;
+        }
+    };
+    for admin in admins {
+        let locale: Locale = admin
+            .locale
+            .as_deref()
+            .and_then(|code| code.parse().ok())
+            .unwrap_or_default();
+        let created = locale
+            .scope(async {
+                let title = i18n::t("feedback-stuck-title");
+                let content = i18n::t_with(
+                    &format!("feedback-stuck-{}", outcome.as_str()),
+                    [
+                        ("repository", feedback.repository.clone().into()),
+                        ("status", feedback.status.as_str().into()),
+                        ("minutes", minutes.into()),
+                    ],
+                );
+                Notification::create(
+                    pool,
+                    admin.id,
+                    NotificationType::Warning,
+                    &title,
+                    &content,
+                    Some(feedback.id),
+                )
+                .await
+            })
+            .await;
+        if let Err(e) = created {
+            warn!(
+                "⚠️ Stuck feedback alert for {} failed: {:#}",
+                admin.email, e
+            );
+        }
+    }
+    info!("📣 Told the admins feedback {} was stuck", feedback.id);
+}
+
+// 🧪 Tests - No item left behind!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::queue::RecordingQueue;
+    use uuid::Uuid;
+
+    fn config(action: StuckAction) -> WatchdogConfig {
+        WatchdogConfig {
+            processing_minutes: 60,
+            generating_changes_minutes: 0,
+            action,
+            max_requeues: 2,
+        }
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let config = config(StuckAction::Fail);
+        assert_eq!(threshold(&config, FeedbackStatus::Processing), Some(60));
+        assert_eq!(threshold(&config, FeedbackStatus::GeneratingChanges), None);
+        assert_eq!(threshold(&config, FeedbackStatus::Pending), None);
+        println!("✅ Watchdog threshold test passed!");
+    }
+
+    #[test]
+    fn test_decide() {
+        let fail = config(StuckAction::Fail);
+        assert_eq!(decide(&fail, 0, false), Outcome::Failed);
+
+        let requeue = config(StuckAction::Requeue);
+        assert_eq!(decide(&requeue, 0, true), Outcome::Requeued);
+        assert_eq!(decide(&requeue, 1, true), Outcome::Requeued);
+        // 🔁 Out of requeues, or nothing that can safely run it again
+        assert_eq!(decide(&requeue, 2, true), Outcome::Failed);
+        assert_eq!(decide(&requeue, 0, false), Outcome::Failed);
+        println!("✅ Watchdog decision test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_requeue_queues_a_run() {
+        let now = Utc::now();
+        let project = Project {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            repository: "aye-is/feedbacker".to_string(),
+            description: None,
+            default_llm_provider: None,
+            system_message: None,
+            config: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+            last_activity_at: None,
+            organization_id: None,
+            team_id: None,
+        };
+        let feedback = Feedback {
+            id: Uuid::new_v4(),
+            user_id: Some(Uuid::new_v4()),
+            repository: project.repository.clone(),
+            path: None,
+            content: "Add dark mode".to_string(),
+            status: FeedbackStatus::Pending,
+            branch_name: None,
+            pull_request_url: None,
+            llm_provider: None,
+            metadata: None,
+            error_message: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            assigned_to: None,
+            submitter_email: None,
+            claim_confirmed_at: None,
+        };
+
+        let queue = RecordingQueue::default();
+        let first = Rerun::Project(project.clone());
+        queue_rerun(&queue, &first, &feedback).await.unwrap();
+        let run = queue
+            .enqueue(&runs::run_job(
+                &project,
+                &feedback,
+                PipelineMode::Documentation,
+            ))
+            .await
+            .unwrap();
+        queue_rerun(&queue, &Rerun::Run(run), &feedback)
+            .await
+            .unwrap();
+
+        let jobs = queue.jobs.lock().unwrap();
+        assert_eq!(jobs.len(), 3);
+        assert!(jobs.iter().all(|job| job.job_type == RUN_JOB));
+        assert_eq!(
+            jobs[0].payload["feedback_id"],
+            serde_json::json!(feedback.id)
+        );
+        assert_eq!(jobs[0].payload["project_id"], serde_json::json!(project.id));
+        // 🔁 The last run goes again as it was, mode and all
+        assert_eq!(jobs[2].payload, jobs[1].payload);
+        assert_eq!(jobs[2].user_id, feedback.user_id);
+        println!("✅ Watchdog requeue test passed!");
+    }
+}
@@ -0,0 +1,820 @@
+// 🌿 Git Engine - Local Clones for Context and Commits! 🌿
+// Clones target repositories with git2 so the pipeline can read real files.
+// Clones are shallow (depth=1) by default, and only the paths the context
+// retrieval step asked for (or the feedback scope) are materialized.
+// Recent clones are kept in a bounded on-disk cache keyed by repo + SHA.
+// A workspace handed out by the cache holds its repository's lock file until
+// it's dropped, so concurrent jobs (and worker processes) never clone, move,
+// check out or evict a clone someone else is using
+// All git2 calls are blocking - run them inside `tokio::task::spawn_blocking`
+// Created with love by Aye & Hue - Only check out what you need! ✨
+
+use anyhow::{Context, Result};
+use git2::{
+    build::CheckoutBuilder, build::RepoBuilder, Cred, Direction, FetchOptions, RemoteCallbacks,
+};
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::objects::{is_lfs_pointer, RepositoryObjects, GITLINK_MODE};
+use crate::{metrics, models::PathScope};
+
+/// 📏 History depth for shallow clones
+pub const SHALLOW_DEPTH: i32 = 1;
+
+/// 🏷️ Marker file whose mtime records when a cache entry was last used
+const CACHE_MARKER: &str = ".feedbacker-cache";
+
+/// 🔒 Cache subdirectory holding one lock file per repository
+const LOCK_DIR: &str = ".locks";
+
+/// ⚙️ How to clone a repository
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// 🌿 Branch to check out (remote default when None)
+    pub branch: Option<String>,
+    /// 📁 Subtree to materialize (whole repository by default)
+    pub scope: PathScope,
+    /// 📄 Paths chosen by context retrieval (limits the checkout further, within the scope)
+    pub context_paths: Vec<String>,
+    /// 📜 Fetch the full history instead of a shallow clone
+    pub full_history: bool,
+    /// 🔑 Token for HTTPS authentication
+    pub token: Option<String>,
+}
+
+impl CloneOptions {
+    /// 🌿 Paths to materialize (empty = everything)
+    /// Context paths outside the scope are ignored
+    pub fn sparse_paths(&self) -> Vec<String> {
+        let context: Vec<String> = self
+            .context_paths
+            .iter()
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty() && self.scope.contains(path))
+            .collect();
+
+        if context.is_empty() {
+            self.scope.sparse_paths()
+        } else {
+            context
+        }
+    }
+}
+
+/// 🌿 A local clone of a target repository
+pub struct GitWorkspace {
+    /// 📂 Working directory
+    root: PathBuf,
+    /// 📁 Scope the checkout was limited to
+    scope: PathScope,
+    /// 🗃️ Underlying repository handle
+    repository: git2::Repository,
+    /// 🔒 Cache lock held while the workspace is in use (None outside the cache)
+    lock: Option<CacheLock>,
+}
+
+/// 🔒 Exclusive use of one repository's cache entries, released on drop
+/// An OS file lock, so it also holds across worker processes sharing the cache
+#[derive(Debug)]
+struct CacheLock {
+    _file: File,
+}
+
+impl std::fmt::Debug for GitWorkspace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitWorkspace")
+            .field("root", &self.root)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl GitWorkspace {
+    /// 📥 Clone `url` into `destination`
+    pub fn clone(url: &str, destination: &Path, options: &CloneOptions) -> Result<Self> {
+        info!(
+            "📥 Cloning {} into {} (scope: {})",
+            url,
+            destination.display(),
+            options.scope.display()
+        );
+
+        let mut fetch = fetch_options(options.token.as_deref());
+        // 📏 libgit2's local transport can't do shallow fetches (local clones are cheap anyway)
+        if !options.full_history && !is_local_url(url) {
+            fetch.depth(SHALLOW_DEPTH);
+        }
+
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch);
+        if let Some(branch) = &options.branch {
+            builder.branch(branch);
+        }
+
+        let sparse_paths = options.sparse_paths();
+        if !sparse_paths.is_empty() {
+            // 🌿 Only write the requested paths to the working directory
+            builder.with_checkout(sparse_checkout(&sparse_paths));
+        }
+
+        let repository = builder
+            .clone(url, destination)
+            .with_context(|| format!("Failed to clone {}", url))?;
+
+        if !sparse_paths.is_empty() {
+            enable_sparse_checkout(&repository, &sparse_paths)?;
+        }
+
+        Ok(Self {
+            root: destination.to_path_buf(),
+            scope: options.scope.clone(),
+            repository,
+            lock: None,
+        })
+    }
+
+    /// 📂 Open an existing clone and materialize the requested paths
+    pub fn open(root: &Path, options: &CloneOptions) -> Result<Self> {
+        let repository = git2::Repository::open(root)
+            .with_context(|| format!("Failed to open clone at {}", root.display()))?;
+
+        let sparse_paths = options.sparse_paths();
+        if sparse_paths.is_empty() {
+            repository
+                .checkout_head(Some(CheckoutBuilder::new().force()))
+                .context("Failed to check out HEAD")?;
+        } else {
+            repository
+                .checkout_head(Some(sparse_checkout(&sparse_paths).force()))
+                .context("Failed to check out sparse paths")?;
+            enable_sparse_checkout(&repository, &sparse_paths)?;
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            scope: options.scope.clone(),
+            repository,
+            lock: None,
+        })
+    }
+
+    /// 📂 Working directory of the clone
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 📁 Scope the checkout was limited to
+    pub fn scope(&self) -> &PathScope {
+        &self.scope
+    }
+
+    /// 🔖 SHA of the checked-out commit
+    pub fn head_sha(&self) -> Result<String> {
+        let head = self
+            .repository
+            .head()
+            .context("Repository has no HEAD")?
+            .peel_to_commit()
+            .context("HEAD does not point at a commit")?;
+        Ok(head.id().to_string())
+    }
+
+    /// 🧱 LFS and submodule paths in this checkout
+    pub fn objects(&self) -> Result<RepositoryObjects> {
+        RepositoryObjects::scan(&self.repository)
+    }
+
+    /// 📋 Tracked files inside the scope (from the index, so it works with sparse checkouts)
+    /// Submodules are left out - they aren't files we can read or edit
+    pub fn list_files(&self) -> Result<Vec<String>> {
+        let index = self
+            .repository
+            .index()
+            .context("Failed to read git index")?;
+        let files = index
+            .iter()
+            .filter(|entry| entry.mode != GITLINK_MODE)
+            .filter_map(|entry| String::from_utf8(entry.path).ok())
+            .filter(|path| self.scope.contains(path))
+            .collect();
+        Ok(files)
+    }
+
+    /// 📄 Read a file from the working directory (None if missing or out of scope)
+    pub fn read_file(&self, relative_path: &str) -> Option<String> {
+        if !self.scope.contains(relative_path) {
+            debug!("🚫 Refusing to read out-of-scope file: {}", relative_path);
+            return None;
+        }
+        let content = std::fs::read_to_string(self.root.join(relative_path)).ok()?;
+
+        // 📦 An LFS pointer isn't the real file - never hand it out as content
+        if is_lfs_pointer(content.as_bytes()) {
+            debug!("📦 Skipping LFS pointer: {}", relative_path);
+            return None;
+        }
+        Some(content)
+    }
+}
+
+/// 🗃️ Bounded on-disk cache of recent clones, keyed by repository + commit SHA
+#[derive(Debug, Clone)]
+pub struct CloneCache {
+    /// 📂 Directory holding one subdirectory per cached clone
+    root: PathBuf,
+    /// 📦 Maximum number of clones kept
+    max_entries: usize,
+}
+
+impl CloneCache {
+    /// ➕ Create a cache rooted at `root`
+    pub fn new(root: impl Into<PathBuf>, max_entries: usize) -> Self {
+        Self {
+            root: root.into(),
+            max_entries: max_entries.max(1),
+        }
+    }
+
+    /// 🌿 Get a checkout of `repository` at the tip of the requested branch
+    /// Reuses a cached clone when the remote SHA hasn't moved
+    pub fn checkout(
+        &self,
+        repository: &str,
+        url: &str,
+        options: &CloneOptions,
+    ) -> Result<GitWorkspace> {
+        let started = Instant::now();
+        std::fs::create_dir_all(&self.root)
+            .with_context(|| format!("Failed to create clone cache at {}", self.root.display()))?;
+
+        let sha = resolve_remote_sha(url, options.branch.as_deref(), options.token.as_deref())?;
+        let entry = self.root.join(cache_key(repository, &sha));
+
+        // 🔒 Wait for other jobs on this repository; the workspace keeps the lock
+        let lock = self.lock(&repository_key(repository))?;
+
+        if entry.join(".git").exists() {
+            match GitWorkspace::open(&entry, options) {
+                Ok(mut workspace) => {
+                    workspace.lock = Some(lock);
+                    touch(&entry);
+                    metrics::record_clone_cache_lookup(true);
+                    metrics::observe_git_checkout("cache", started.elapsed());
+                    info!("🗃️ Clone cache hit for {}@{}", repository, short_sha(&sha));
+                    return Ok(workspace);
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️ Discarding broken cache entry {}: {:#}",
+                        entry.display(),
+                        e
+                    );
+                    std::fs::remove_dir_all(&entry).ok();
+                }
+            }
+        }
+        metrics::record_clone_cache_lookup(false);
+
+        // 📥 Clone next to the final location, then move into place
+        let staging = self.root.join(format!(".staging-{}", Uuid::new_v4()));
+        let cloned = GitWorkspace::clone(url, &staging, options)?;
+        let actual_sha = cloned.head_sha()?;
+        drop(cloned);
+
+        // 🔀 The branch may have moved since we asked; key by what we actually got
+        let entry = self.root.join(cache_key(repository, &actual_sha));
+        if entry.exists() {
+            std::fs::remove_dir_all(&staging).ok();
+        } else {
+            std::fs::rename(&staging, &entry).context("Failed to move clone into the cache")?;
+        }
+        touch(&entry);
+
+        let mut workspace = GitWorkspace::open(&entry, options)?;
+        workspace.lock = Some(lock);
+        metrics::observe_git_checkout("clone", started.elapsed());
+        info!(
+            "📥 Cloned {}@{} in {:?}",
+            repository,
+            short_sha(&actual_sha),
+            started.elapsed()
+        );
+
+        self.evict(&entry);
+        Ok(workspace)
+    }
+
+    /// 🧹 Drop the clone of `repository` at `sha`, once a push has moved past it
+    /// Returns whether it was dropped (a clone in use is left for eviction)
+    pub fn invalidate(&self, repository: &str, sha: &str) -> bool {
+        let entry = self.root.join(cache_key(repository, sha));
+        if !entry.exists() {
+            return false;
+        }
+        let _lock = match self.try_lock(&repository_key(repository)) {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                debug!("🔒 Cached clone {} is in use, keeping it", entry.display());
+                return false;
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to lock {}: {:#}", entry.display(), e);
+                return false;
+            }
+        };
+        match std::fs::remove_dir_all(&entry) {
+            Ok(()) => {
+                debug!("🧹 Dropped cached clone {}", entry.display());
+                true
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to drop {}: {}", entry.display(), e);
+                false
+            }
+        }
+    }
+
+    /// 🔒 Open (creating if needed) the lock file of a repository's entries
+    fn lock_file(&self, repository_key: &str) -> Result<File> {
+        let dir = self.root.join(LOCK_DIR);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}.lock", repository_key));
+        File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))
+    }
+
+    /// 🔒 Wait for exclusive use of a repository's entries
+    fn lock(&self, repository_key: &str) -> Result<CacheLock> {
+        let file = self.lock_file(repository_key)?;
+        file.lock()
+            .with_context(|| format!("Failed to lock cached clones of {}", repository_key))?;
+        Ok(CacheLock { _file: file })
+    }
+
+    /// 🔒 Exclusive use of a repository's entries, unless someone is using them
+    fn try_lock(&self, repository_key: &str) -> Result<Option<CacheLock>> {
+        let file = self.lock_file(repository_key)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(CacheLock { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e)
+                .with_context(|| format!("Failed to lock cached clones of {}", repository_key)),
+        }
+    }
+
+    /// 🧹 Remove the least recently used clones beyond the limit
+    /// `keep` is the entry just checked out; its repository's lock is already
+    /// ours, and entries of other repositories that are in use are skipped
+    fn evict(&self, keep: &Path) {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return;
+        };
+
+        let mut clones: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && path.as_path() != keep)
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| !name.starts_with('.'))
+            })
+            .map(|path| (last_used(&path), path))
+            .collect();
+
+        // 👈 The entry we just used always counts towards the limit
+        let allowed = self.max_entries.saturating_sub(1);
+        if clones.len() <= allowed {
+            return;
+        }
+
+        clones.sort_by_key(|(used, _)| *used);
+        let mut excess = clones.len() - allowed;
+        let held = keep
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(entry_repository_key);
+        for (_, path) in clones {
+            if excess == 0 {
+                break;
+            }
+            let Some(key) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(entry_repository_key)
+            else {
+                continue;
+            };
+            let _lock = if Some(key) == held {
+                None
+            } else {
+                match self.try_lock(key) {
+                    Ok(Some(lock)) => Some(lock),
+                    Ok(None) => {
+                        debug!("🔒 Not evicting {}, it's in use", path.display());
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Failed to lock {}: {:#}", path.display(), e);
+                        continue;
+                    }
+                }
+            };
+            excess -= 1;
+            debug!("🧹 Evicting cached clone {}", path.display());
+            if let Err(e) = std::fs::remove_dir_all(&path) {
+                warn!("⚠️ Failed to evict {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// 🔗 HTTPS clone URL for an "owner/repo" repository on the GitHub whose API is
+/// at `api_base_url` (github.com for api.github.com, the host itself for GitHub
+/// Enterprise Server's /api/v3)
+pub fn github_clone_url(api_base_url: &str, repository: &str) -> String {
+    let base = api_base_url.trim_end_matches('/');
+    let host = match base.strip_suffix("/api/v3") {
+        Some(host) => host.to_string(),
+        None => base.replacen("://api.", "://", 1),
+    };
+    format!("{}/{}.git", host, repository)
+}
+
+/// 🔍 Ask the remote which commit a branch (or HEAD) points at
+pub fn resolve_remote_sha(url: &str, branch: Option<&str>, token: Option<&str>) -> Result<String> {
+    let mut remote = git2::Remote::create_detached(url).context("Invalid remote URL")?;
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(token)), None)
+        .with_context(|| format!("Failed to connect to {}", url))?;
+
+    let wanted = match branch {
+        Some(branch) => format!("refs/heads/{}", branch),
+        None => "HEAD".to_string(),
+    };
+
+    connection
+        .list()
+        .context("Failed to list remote refs")?
+        .iter()
+        .find(|head| head.name() == wanted)
+        .map(|head| head.oid().to_string())
+        .with_context(|| format!("Remote has no ref named {}", wanted))
+}
+
+/// 🔑 Fetch options with token authentication
+fn fetch_options(token: Option<&str>) -> FetchOptions<'static> {
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(remote_callbacks(token));
+    fetch
+}
+
+/// 🔑 Remote callbacks that answer credential prompts with the token
+fn remote_callbacks(token: Option<&str>) -> RemoteCallbacks<'static> {
+    let mut callbacks = RemoteCallbacks::new();
+    if let Some(token) = token {
+        let token = token.to_string();
+        callbacks.credentials(move |_url, _username, _allowed| {
+            Cred::userpass_plaintext("x-access-token", &token)
+        });
+    }
+    callbacks
+}
+
+/// 🏠 Whether a URL goes through libgit2's local transport
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || !(url.contains("://") || url.contains('@'))
+}
+
+/// 🌿 Checkout limited to the given paths
+fn sparse_checkout(paths: &[String]) -> CheckoutBuilder<'static> {
+    let mut checkout = CheckoutBuilder::new();
+    for path in paths {
+        checkout.path(path.as_str());
+    }
+    checkout
+}
+
+/// 🏷️ Cache directory name for a repository at a commit
+fn cache_key(repository: &str, sha: &str) -> String {
+    format!("{}-{}", repository_key(repository), sha)
+}
+
+/// 🔑 Filesystem-safe name of a repository (prefix of its cache keys)
+fn repository_key(repository: &str) -> String {
+    let safe: String = repository
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    safe.trim_start_matches('.').to_string()
+}
+
+/// 🔑 Repository part of a cache entry's name (SHAs never contain '-')
+fn entry_repository_key(name: &str) -> Option<&str> {
+    name.rsplit_once('-').map(|(repository, _)| repository)
+}
+
+/// 🔖 Abbreviated SHA for logs
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// 👆 Mark a cache entry as just used
+fn touch(entry: &Path) {
+    let _ = std::fs::write(entry.join(CACHE_MARKER), chrono::Utc::now().to_rfc3339());
+}
+
+/// ⏰ When a cache entry was last used
+fn last_used(entry: &Path) -> SystemTime {
+    std::fs::metadata(entry.join(CACHE_MARKER))
+        .or_else(|_| std::fs::metadata(entry))
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// 🌿 Record the sparse paths so later checkouts (and the git CLI) respect them
+fn enable_sparse_checkout(repository: &git2::Repository, paths: &[String]) -> Result<()> {
+    let info_dir = repository.path().join("info");
+    std::fs::create_dir_all(&info_dir).context("Failed to create .git/info")?;
+
+    let patterns: String = paths.iter().map(|path| format!("/{}\n", path)).collect();
+    std::fs::write(info_dir.join("sparse-checkout"), patterns)
+        .context("Failed to write sparse-checkout file")?;
+
+    repository
+        .config()
+        .and_then(|mut config| config.set_bool("core.sparseCheckout", true))
+        .context("Failed to enable core.sparseCheckout")?;
+
+    Ok(())
+}
+
+// 🧪 Tests - Cloning a tiny local repository end to end!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::objects::UnsupportedObject;
+
+    /// 🏗️ Create a repository with files in two subtrees
+    fn fixture_repository(root: &Path) -> String {
+        let repository = git2::Repository::init(root).unwrap();
+        for (path, content) in [
+            ("crates/foo/src/lib.rs", "pub fn foo() {}\n"),
+            ("crates/bar/src/lib.rs", "pub fn bar() {}\n"),
+            ("README.md", "# Fixture\n"),
+            (
+                ".gitattributes",
+                "*.psd filter=lfs diff=lfs merge=lfs -text\n",
+            ),
+            (
+                "assets/logo.psd",
+                "version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 42\n",
+            ),
+        ] {
+            let full = root.join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(full, content).unwrap();
+        }
+
+        let mut index = repository.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Aye", "aye@8b.is").unwrap();
+        repository
+            .commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+
+        format!("file://{}", root.display())
+    }
+
+    #[test]
+    fn test_sparse_clone_only_materializes_scope() {
+        let base = std::env::temp_dir().join(format!("feedbacker-git-{}", Uuid::new_v4()));
+        let url = fixture_repository(&base.join("origin"));
+
+        let options = CloneOptions {
+            scope: PathScope::new("crates/foo").unwrap(),
+            ..Default::default()
+        };
+        let workspace = GitWorkspace::clone(&url, &base.join("clone"), &options).unwrap();
+
+        assert!(workspace.root().join("crates/foo/src/lib.rs").exists());
+        assert!(!workspace.root().join("crates/bar/src/lib.rs").exists());
+        assert_eq!(
+            workspace.list_files().unwrap(),
+            vec!["crates/foo/src/lib.rs"]
+        );
+        assert!(workspace.read_file("crates/foo/src/lib.rs").is_some());
+        assert!(workspace.read_file("README.md").is_none());
+        assert_eq!(workspace.head_sha().unwrap().len(), 40);
+
+        std::fs::remove_dir_all(&base).ok();
+        println!("✅ Sparse clone test passed!");
+    }
+
+    #[test]
+    fn test_clone_cache_reuses_and_evicts() {
+        let base = std::env::temp_dir().join(format!("feedbacker-cache-{}", Uuid::new_v4()));
+        let origin = base.join("origin");
+        let url = fixture_repository(&origin);
+        let cache = CloneCache::new(base.join("cache"), 1);
+
+        let options = CloneOptions {
+            context_paths: vec!["README.md".to_string()],
+            ..Default::default()
+        };
+        let first = cache.checkout("aye-is/fixture", &url, &options).unwrap();
+        assert!(first.root().join("README.md").exists());
+        assert!(!first.root().join("crates/foo/src/lib.rs").exists());
+        let first_root = first.root().to_path_buf();
+        drop(first);
+
+        // 🗃️ Same SHA: the cached clone is reused and widened to the new paths
+        let wider = CloneOptions::default();
+        let second = cache.checkout("aye-is/fixture", &url, &wider).unwrap();
+        assert_eq!(first_root, second.root());
+        assert!(second.root().join("crates/foo/src/lib.rs").exists());
+        drop(second);
+
+        // 🔀 New commit upstream: a fresh entry replaces the old one (limit is 1)
+        let repository = git2::Repository::open(&origin).unwrap();
+        let signature = git2::Signature::now("Hue", "hue@8b.is").unwrap();
+        let tree = repository.head().unwrap().peel_to_tree().unwrap();
+        let parent = repository.head().unwrap().peel_to_commit().unwrap();
+        repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Again",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+
+        let third = cache.checkout("aye-is/fixture", &url, &wider).unwrap();
+        assert_ne!(third.root(), first_root);
+        assert!(!first_root.exists());
+
+        // 📤 A push past the cached commit drops its clone, once nobody uses it
+        let sha = third.head_sha().unwrap();
+        assert!(!cache.invalidate("aye-is/fixture", &sha));
+        let third_root = third.root().to_path_buf();
+        drop(third);
+        assert!(cache.invalidate("aye-is/fixture", &sha));
+        assert!(!third_root.exists());
+        assert!(!cache.invalidate("aye-is/fixture", &sha));
+
+        std::fs::remove_dir_all(&base).ok();
+        println!("✅ Clone cache test passed!");
+    }
+
+    #[test]
+    fn test_clone_cache_locks_entries_in_use() {
+        use std::sync::{mpsc, Arc};
+        use std::time::Duration;
+
+        let base = std::env::temp_dir().join(format!("feedbacker-locks-{}", Uuid::new_v4()));
+        let url = fixture_repository(&base.join("origin"));
+        let cache = Arc::new(CloneCache::new(base.join("cache"), 1));
+
+        // 🏁 Racing first checkouts clone once and all land on the same entry
+        let roots: Vec<PathBuf> = (0..4)
+            .map(|_| {
+                let (cache, url) = (cache.clone(), url.clone());
+                std::thread::spawn(move || {
+                    let workspace = cache
+                        .checkout("aye-is/fixture", &url, &CloneOptions::default())
+                        .unwrap();
+                    assert!(workspace.read_file("README.md").is_some());
+                    workspace.root().to_path_buf()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert!(roots.windows(2).all(|pair| pair[0] == pair[1]));
+        let names: Vec<String> = std::fs::read_dir(base.join("cache"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        assert_eq!(names.len(), 1);
+
+        // 🔒 A second checkout waits until the first workspace is dropped
+        let held = cache
+            .checkout("aye-is/fixture", &url, &CloneOptions::default())
+            .unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let waiter = {
+            let (cache, url) = (cache.clone(), url.clone());
+            std::thread::spawn(move || {
+                let workspace = cache
+                    .checkout("aye-is/fixture", &url, &CloneOptions::default())
+                    .unwrap();
+                sender.send(workspace.root().to_path_buf()).unwrap();
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+        drop(held);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(30)).unwrap(),
+            roots[0]
+        );
+        waiter.join().unwrap();
+
+        // 🧹 Eviction skips a clone that's in use, even past the limit
+        let held = cache
+            .checkout("aye-is/fixture", &url, &CloneOptions::default())
+            .unwrap();
+        let other = std::thread::spawn({
+            let (cache, url) = (cache.clone(), url.clone());
+            move || {
+                let workspace = cache
+                    .checkout("aye-is/other", &url, &CloneOptions::default())
+                    .unwrap();
+                workspace.root().to_path_buf()
+            }
+        })
+        .join()
+        .unwrap();
+        assert!(held.root().exists());
+        drop(held);
+        cache.evict(&other);
+        assert!(!roots[0].exists());
+        assert!(other.exists());
+
+        std::fs::remove_dir_all(&base).ok();
+        println!("✅ Clone cache locking test passed!");
+    }
+
+    #[test]
+    fn test_lfs_pointers_are_never_context() {
+        let base = std::env::temp_dir().join(format!("feedbacker-lfs-{}", Uuid::new_v4()));
+        let url = fixture_repository(&base.join("origin"));
+        let workspace =
+            GitWorkspace::clone(&url, &base.join("clone"), &CloneOptions::default()).unwrap();
+
+        let objects = workspace.objects().unwrap();
+        assert_eq!(
+            objects.classify("assets/logo.psd"),
+            Some(UnsupportedObject::LfsTracked)
+        );
+        assert_eq!(objects.classify("README.md"), None);
+        assert!(workspace.read_file("assets/logo.psd").is_none());
+        assert!(objects.ensure_supported(["assets/new.psd"]).is_err());
+
+        std::fs::remove_dir_all(&base).ok();
+        println!("✅ LFS awareness test passed!");
+    }
+
+    #[test]
+    fn test_github_clone_url() {
+        assert_eq!(
+            github_clone_url("https://api.github.com", "aye-is/feedbacker"),
+            "https://github.com/aye-is/feedbacker.git"
+        );
+        assert_eq!(
+            github_clone_url("https://ghe.example.com/api/v3/", "platform/billing"),
+            "https://ghe.example.com/platform/billing.git"
+        );
+        println!("✅ Clone URL test passed!");
+    }
+
+    #[test]
+    fn test_context_paths_stay_within_scope() {
+        let options = CloneOptions {
+            scope: PathScope::new("crates/foo").unwrap(),
+            context_paths: vec![
+                "crates/foo/src/lib.rs".to_string(),
+                "crates/bar/src/lib.rs".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(options.sparse_paths(), vec!["crates/foo/src/lib.rs"]);
+        assert_eq!(
+            cache_key("aye-is/feedbacker", "abc123"),
+            "aye-is_feedbacker-abc123"
+        );
+        println!("✅ Context path scoping test passed!");
+    }
+}
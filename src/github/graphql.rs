@@ -0,0 +1,603 @@
+// 🕸️ GitHub GraphQL - Many Things, One Request! 🕸️
+// Some reads cost a REST call per item. GraphQL fetches them in one request:
+// issues with their labels and comments a hundred at a time (issue import), a
+// PR's review threads with their first comment (the rebase leaves PRs under
+// review alone), and the three versions of a file a rebase has to merge. Each
+// query is a type implementing `GraphQlQuery` (its text, variables and response
+// shape); those reading a connection also implement `PaginatedQuery`, and
+// `GitHubClient::query_all` follows the cursors until enough nodes are found.
+// Whole file trees stay on REST: `git/trees?recursive=1` returns every file of a
+// commit in one response, where GraphQL nests one directory level per query.
+// The issue triage webhook only writes (labels, a comment, an assignee) from
+// what the delivery carries, so it has nothing to read here.
+// Requests go through the same rate limit handling as REST (the `graphql`
+// resource) and land on /api/graphql for GitHub Enterprise Server
+// Created with love by Aye & Hue - Ask for exactly what you need! ✨
+
+use anyhow::{Context, Result};
+use axum::http::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+
+use super::pulls::{BlobContent, ReviewThread};
+use super::rate_limit::{RateLimited, Urgency};
+use super::{GitHubClient, IssueComment, OpenIssue};
+
+/// 🔗 Route of GraphQL requests (on the GraphQL base URL, see `graphql_base_url`)
+pub const GRAPHQL_ROUTE: &str = "/graphql";
+
+/// 📏 Nodes per page (GitHub's maximum)
+pub const PAGE_SIZE: u32 = 100;
+
+/// ⏳ Wait before retrying a query GitHub refused for its rate limit
+const RATE_LIMITED_WAIT: Duration = Duration::from_secs(60);
+
+/// 📜 A typed GraphQL query
+pub trait GraphQlQuery {
+    /// 📝 The query document
+    const QUERY: &'static str;
+    /// 🎛️ Its variables
+    type Variables: Serialize;
+    /// 📦 The shape of its `data`
+    type Data: DeserializeOwned;
+}
+
+/// 📚 A query reading one connection, page by page
+pub trait PaginatedQuery: GraphQlQuery {
+    /// 🧩 One item of the connection
+    type Node;
+
+    /// 📄 The page in a response (None when its parent doesn't exist)
+    fn page(data: Self::Data) -> Option<Connection<Self::Node>>;
+
+    /// ➡️ Ask for the page after `cursor` next
+    fn set_cursor(variables: &mut Self::Variables, cursor: String);
+}
+
+/// 📄 One page of a connection
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection<T> {
+    pub nodes: Vec<T>,
+    pub page_info: PageInfo,
+}
+
+/// ➡️ Where a connection goes on
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// 📋 A nested list read without paging (first or last N)
+#[derive(Debug, Deserialize)]
+pub struct Nodes<T> {
+    pub nodes: Vec<T>,
+}
+
+/// 📦 A GraphQL response
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<QueryError>,
+}
+
+/// 💥 One error of a GraphQL response
+#[derive(Debug, Deserialize)]
+struct QueryError {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    message: String,
+}
+
+impl GitHubClient {
+    /// 🕸️ Run a query and return its `data`
+    pub async fn query<Q: GraphQlQuery>(
+        &self,
+        variables: &Q::Variables,
+        urgency: Urgency,
+    ) -> Result<Q::Data> {
+        let body = serde_json::json!({ "query": Q::QUERY, "variables": variables });
+        let response: Response<Q::Data> = self
+            .send(
+                Method::POST,
+                GRAPHQL_ROUTE,
+                None::<&()>,
+                Some(&body),
+                urgency,
+            )
+            .await?;
+
+        if !response.errors.is_empty() {
+            // 🚦 GraphQL reports its own rate limit as an error on a 200
+            if response
+                .errors
+                .iter()
+                .any(|error| error.kind.as_deref() == Some("RATE_LIMITED"))
+            {
+                return Err(RateLimited {
+                    operation: "GraphQL query".to_string(),
+                    wait: RATE_LIMITED_WAIT,
+                }
+                .into());
+            }
+            let messages: Vec<&str> = response
+                .errors
+                .iter()
+                .map(|error| error.message.as_str())
+                .collect();
+            anyhow::bail!("GitHub GraphQL query failed: {}", messages.join("; "));
+        }
+        response.data.context("GitHub GraphQL response had no data")
+    }
+
+    /// 📚 Follow a connection page by page, keeping the nodes `keep` accepts,
+    /// until `limit` are kept or the connection ends
+    pub async fn query_all<Q, F>(
+        &self,
+        mut variables: Q::Variables,
+        limit: usize,
+        urgency: Urgency,
+        keep: F,
+    ) -> Result<Vec<Q::Node>>
+    where
+        Q: PaginatedQuery,
+        F: Fn(&Q::Node) -> bool,
+    {
+        let mut nodes = Vec::new();
+        while nodes.len() < limit {
+            let data = self.query::<Q>(&variables, urgency).await?;
+            let page = Q::page(data).context("GitHub GraphQL query found nothing to page")?;
+            nodes.extend(page.nodes.into_iter().filter(|node| keep(node)));
+            match page.page_info.end_cursor {
+                Some(cursor) if page.page_info.has_next_page => {
+                    Q::set_cursor(&mut variables, cursor)
+                }
+                _ => break,
+            }
+        }
+        nodes.truncate(limit);
+        Ok(nodes)
+    }
+}
+
+/// 🧭 Base URL GraphQL requests go to: GitHub Enterprise Server serves GraphQL
+/// at /api/graphql next to REST's /api/v3, github.com at api.github.com/graphql
+pub fn graphql_base_url(api_base_url: &str) -> String {
+    let base = api_base_url.trim_end_matches('/');
+    base.strip_suffix("/v3").unwrap_or(base).to_string()
+}
+
+/// 🎫 Open issues of a repository, oldest first, with their labels and latest comments
+#[derive(Debug)]
+pub struct OpenIssuesQuery;
+
+/// 🎛️ Variables of `OpenIssuesQuery`
+#[derive(Debug, Serialize)]
+pub struct OpenIssuesVariables {
+    pub owner: String,
+    pub name: String,
+    /// 🏷️ Issues with any of these labels (all issues when None)
+    pub labels: Option<Vec<String>>,
+    pub first: u32,
+    pub after: Option<String>,
+}
+
+/// 📦 `repository { issues }` of an `OpenIssuesQuery` response
+#[derive(Debug, Deserialize)]
+pub struct OpenIssuesData {
+    repository: Option<IssuesOfRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesOfRepository {
+    issues: Connection<IssueNode>,
+}
+
+/// 🎫 An issue as `OpenIssuesQuery` reads it
+#[derive(Debug, Deserialize)]
+pub struct IssueNode {
+    number: u64,
+    title: String,
+    body: String,
+    url: String,
+    labels: Nodes<LabelNode>,
+    comments: Nodes<CommentNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelNode {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentNode {
+    /// 👻 None for deleted accounts
+    author: Option<AuthorNode>,
+    body: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorNode {
+    login: String,
+}
+
+impl IssueNode {
+    /// 🏷️ Whether the issue carries a label (case-insensitively, like GitHub)
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels
+            .nodes
+            .iter()
+            .any(|node| node.name.eq_ignore_ascii_case(label))
+    }
+}
+
+impl From<IssueNode> for OpenIssue {
+    fn from(node: IssueNode) -> Self {
+        OpenIssue {
+            number: node.number,
+            title: node.title,
+            body: Some(node.body).filter(|body| !body.trim().is_empty()),
+            html_url: node.url,
+            labels: node
+                .labels
+                .nodes
+                .into_iter()
+                .map(|label| label.name)
+                .collect(),
+            comments: node
+                .comments
+                .nodes
+                .into_iter()
+                .map(|comment| IssueComment {
+                    author: comment
+                        .author
+                        .map(|author| author.login)
+                        .unwrap_or_else(|| "ghost".to_string()),
+                    body: comment.body,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl GraphQlQuery for OpenIssuesQuery {
+    const QUERY: &'static str = r#"
+query OpenIssues($owner: String!, $name: String!, $labels: [String!], $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    issues(states: OPEN, labels: $labels, first: $first, after: $after, orderBy: {field: CREATED_AT, direction: ASC}) {
+      nodes {
+        number
+        title
+        body
+        url
+        labels(first: 20) { nodes { name } }
+        comments(last: 10) { nodes { author { login } body } }
+      }
+      pageInfo { hasNextPage endCursor }
+    }
+  }
+}"#;
+    type Variables = OpenIssuesVariables;
+    type Data = OpenIssuesData;
+}
+
+impl PaginatedQuery for OpenIssuesQuery {
+    type Node = IssueNode;
+
+    fn page(data: OpenIssuesData) -> Option<Connection<IssueNode>> {
+        data.repository.map(|repository| repository.issues)
+    }
+
+    fn set_cursor(variables: &mut OpenIssuesVariables, cursor: String) {
+        variables.after = Some(cursor);
+    }
+}
+
+/// 💬 Review threads of a pull request, with their first comment
+#[derive(Debug)]
+pub struct ReviewThreadsQuery;
+
+/// 🎛️ Variables of `ReviewThreadsQuery`
+#[derive(Debug, Serialize)]
+pub struct ReviewThreadsVariables {
+    pub owner: String,
+    pub name: String,
+    pub number: u64,
+    pub first: u32,
+    pub after: Option<String>,
+}
+
+/// 📦 `repository { pullRequest { reviewThreads } }` of a `ReviewThreadsQuery` response
+#[derive(Debug, Deserialize)]
+pub struct ReviewThreadsData {
+    repository: Option<PullRequestOfRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestOfRepository {
+    pull_request: Option<ThreadsOfPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadsOfPullRequest {
+    review_threads: Connection<ReviewThreadNode>,
+}
+
+/// 💬 A review thread as `ReviewThreadsQuery` reads it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThreadNode {
+    path: String,
+    line: Option<u64>,
+    is_resolved: bool,
+    is_outdated: bool,
+    comments: Nodes<CommentNode>,
+}
+
+impl ReviewThreadNode {
+    /// ✅ Whether someone marked the thread resolved
+    pub fn is_resolved(&self) -> bool {
+        self.is_resolved
+    }
+}
+
+impl From<ReviewThreadNode> for ReviewThread {
+    fn from(node: ReviewThreadNode) -> Self {
+        let first = node.comments.nodes.into_iter().next();
+        ReviewThread {
+            path: node.path,
+            line: node.line,
+            resolved: node.is_resolved,
+            outdated: node.is_outdated,
+            author: first
+                .as_ref()
+                .and_then(|comment| comment.author.as_ref())
+                .map(|author| author.login.clone())
+                .unwrap_or_else(|| "ghost".to_string()),
+            body: first.map(|comment| comment.body).unwrap_or_default(),
+        }
+    }
+}
+
+impl GraphQlQuery for ReviewThreadsQuery {
+    const QUERY: &'static str = r#"
+query ReviewThreads($owner: String!, $name: String!, $number: Int!, $first: Int!, $after: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: $first, after: $after) {
+        nodes {
+          path
+          line
+          isResolved
+          isOutdated
+          comments(first: 1) { nodes { author { login } body } }
+        }
+        pageInfo { hasNextPage endCursor }
+      }
+    }
+  }
+}"#;
+    type Variables = ReviewThreadsVariables;
+    type Data = ReviewThreadsData;
+}
+
+impl PaginatedQuery for ReviewThreadsQuery {
+    type Node = ReviewThreadNode;
+
+    fn page(data: ReviewThreadsData) -> Option<Connection<ReviewThreadNode>> {
+        data.repository
+            .and_then(|repository| repository.pull_request)
+            .map(|pull_request| pull_request.review_threads)
+    }
+
+    fn set_cursor(variables: &mut ReviewThreadsVariables, cursor: String) {
+        variables.after = Some(cursor);
+    }
+}
+
+/// 📄 Three versions of a file by blob SHA: where two branches started from
+/// (optional) and where each of them is now
+#[derive(Debug)]
+pub struct FileVersionsQuery;
+
+/// 🎛️ Variables of `FileVersionsQuery`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersionsVariables {
+    pub owner: String,
+    pub name: String,
+    pub original: Option<String>,
+    /// 🔀 Whether `original` is asked for (GraphQL can't look up a null SHA)
+    pub with_original: bool,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// 📦 `repository { original ours theirs }` of a `FileVersionsQuery` response
+#[derive(Debug, Deserialize)]
+pub struct FileVersionsData {
+    pub repository: Option<FileVersions>,
+}
+
+/// 📄 The versions a `FileVersionsQuery` found (None for a blob that doesn't exist)
+#[derive(Debug, Deserialize)]
+pub struct FileVersions {
+    #[serde(default)]
+    pub original: Option<BlobNode>,
+    pub ours: Option<BlobNode>,
+    pub theirs: Option<BlobNode>,
+}
+
+/// 📄 A blob as `FileVersionsQuery` reads it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobNode {
+    /// 📝 None for binary blobs
+    text: Option<String>,
+    is_truncated: bool,
+    byte_size: u64,
+}
+
+impl From<BlobNode> for BlobContent {
+    fn from(node: BlobNode) -> Self {
+        BlobContent {
+            // ✂️ A truncated text isn't the file, so it's no better than none
+            text: node.text.filter(|_| !node.is_truncated),
+            size: node.byte_size,
+        }
+    }
+}
+
+impl GraphQlQuery for FileVersionsQuery {
+    const QUERY: &'static str = r#"
+query FileVersions($owner: String!, $name: String!, $original: GitObjectID, $withOriginal: Boolean!, $ours: GitObjectID!, $theirs: GitObjectID!) {
+  repository(owner: $owner, name: $name) {
+    original: object(oid: $original) @include(if: $withOriginal) { ...BlobText }
+    ours: object(oid: $ours) { ...BlobText }
+    theirs: object(oid: $theirs) { ...BlobText }
+  }
+}
+
+fragment BlobText on Blob {
+  text
+  isTruncated
+  byteSize
+}"#;
+    type Variables = FileVersionsVariables;
+    type Data = FileVersionsData;
+}
+
+// 🧪 Tests - One query to fetch them all!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_base_url() {
+        assert_eq!(
+            graphql_base_url("https://api.github.com"),
+            "https://api.github.com"
+        );
+        assert_eq!(
+            graphql_base_url("https://ghe.example.com/api/v3/"),
+            "https://ghe.example.com/api"
+        );
+        println!("✅ GraphQL base URL test passed!");
+    }
+
+    #[test]
+    fn test_open_issues_page() {
+        let data: OpenIssuesData = serde_json::from_value(serde_json::json!({
+            "repository": { "issues": {
+                "nodes": [{
+                    "number": 42,
+                    "title": "Add dark mode",
+                    "body": "",
+                    "url": "https://github.com/aye-is/feedbacker/issues/42",
+                    "labels": { "nodes": [{ "name": "Feedbacker" }] },
+                    "comments": { "nodes": [
+                        { "author": { "login": "hue" }, "body": "Yes please" },
+                        { "author": null, "body": "+1" }
+                    ] }
+                }],
+                "pageInfo": { "hasNextPage": true, "endCursor": "Y3Vyc29y" }
+            } }
+        }))
+        .unwrap();
+
+        let page = OpenIssuesQuery::page(data).unwrap();
+        assert_eq!(page.page_info.end_cursor.as_deref(), Some("Y3Vyc29y"));
+        let node = page.nodes.into_iter().next().unwrap();
+        assert!(node.has_label("feedbacker"));
+        assert!(!node.has_label("bug"));
+
+        let issue = OpenIssue::from(node);
+        assert_eq!(issue.body, None);
+        assert_eq!(issue.labels, vec!["Feedbacker"]);
+        assert_eq!(issue.comments[0].author, "hue");
+        assert_eq!(issue.comments[1].author, "ghost");
+
+        let missing: OpenIssuesData =
+            serde_json::from_value(serde_json::json!({ "repository": null })).unwrap();
+        assert!(OpenIssuesQuery::page(missing).is_none());
+        println!("✅ Open issues page test passed!");
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let response: Response<OpenIssuesData> = serde_json::from_value(serde_json::json!({
+            "data": null,
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        }))
+        .unwrap();
+        assert!(response.data.is_none());
+        assert_eq!(response.errors[0].kind.as_deref(), Some("RATE_LIMITED"));
+        println!("✅ GraphQL error response test passed!");
+    }
+
+    #[test]
+    fn test_review_threads_page() {
+        let data: ReviewThreadsData = serde_json::from_value(serde_json::json!({
+            "repository": { "pullRequest": { "reviewThreads": {
+                "nodes": [
+                    {
+                        "path": "src/lib.rs",
+                        "line": 12,
+                        "isResolved": false,
+                        "isOutdated": false,
+                        "comments": { "nodes": [{ "author": { "login": "hue" }, "body": "Why?" }] }
+                    },
+                    {
+                        "path": "README.md",
+                        "line": null,
+                        "isResolved": true,
+                        "isOutdated": true,
+                        "comments": { "nodes": [] }
+                    }
+                ],
+                "pageInfo": { "hasNextPage": false, "endCursor": null }
+            } } }
+        }))
+        .unwrap();
+
+        let page = ReviewThreadsQuery::page(data).unwrap();
+        assert!(!page.page_info.has_next_page);
+        let threads: Vec<ReviewThread> = page.nodes.into_iter().map(ReviewThread::from).collect();
+        assert_eq!(threads[0].author, "hue");
+        assert_eq!(threads[0].line, Some(12));
+        assert!(!threads[0].resolved);
+        assert!(threads[1].resolved && threads[1].outdated);
+        assert_eq!(threads[1].author, "ghost");
+
+        let no_pull_request: ReviewThreadsData =
+            serde_json::from_value(serde_json::json!({ "repository": { "pullRequest": null } }))
+                .unwrap();
+        assert!(ReviewThreadsQuery::page(no_pull_request).is_none());
+        println!("✅ Review threads page test passed!");
+    }
+
+    #[test]
+    fn test_file_versions() {
+        let data: FileVersionsData = serde_json::from_value(serde_json::json!({
+            "repository": {
+                "ours": { "text": "fn a() {}\n", "isTruncated": false, "byteSize": 10 },
+                "theirs": { "text": "fn a(", "isTruncated": true, "byteSize": 900000 }
+            }
+        }))
+        .unwrap();
+
+        let versions = data.repository.unwrap();
+        assert!(versions.original.is_none());
+        let ours = BlobContent::from(versions.ours.unwrap());
+        assert_eq!(ours.text.as_deref(), Some("fn a() {}\n"));
+        let theirs = BlobContent::from(versions.theirs.unwrap());
+        assert_eq!(theirs.text, None);
+        assert_eq!(theirs.size, 900000);
+        println!("✅ File versions test passed!");
+    }
+}
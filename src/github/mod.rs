@@ -5,28 +5,46 @@
 // Trisha from Accounting loves when PRs are created automatically! 📊
 
 use anyhow::{Context, Result};
-use octocrab::{
-    models::{pulls::PullRequest, Repository},
-    Octocrab,
-};
-use serde::{Deserialize, Serialize};
+use axum::http::{request::Builder, Method, StatusCode};
+use chrono::Utc;
+use ipnet::IpNet;
+use octocrab::Octocrab;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
 use std::path::Path;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::config::GitHubConfig;
+use crate::metrics;
+use crate::models::PullRequestSettings;
+use crate::utils::encoding::path_segment;
+use graphql::{OpenIssuesQuery, OpenIssuesVariables};
+use pulls::TreeChange;
+use rate_limit::{RateLimited, Urgency};
 
 pub mod client; // 🤖 GitHub API client wrapper
+pub mod git; // 🌿 Local clones with sparse checkout
+pub mod graphql; // 🕸️ Typed GraphQL queries with pagination
+pub mod objects; // 🧱 LFS and submodule detection
 pub mod operations; // 🔧 High-level GitHub operations
+pub mod pulls; // 🔀 Open pull requests and branch rewrites
+pub mod rate_limit; // 🚦 Rate limit tracking and backoff
 pub mod ssh; // 🔐 SSH key management for git operations
 pub mod webhooks; // 🪝 Webhook payload handling
 
+/// 📏 Pages of a hundred collaborators read at most per repository
+const MAX_COLLABORATOR_PAGES: u32 = 10;
+
 /// 🤖 GitHub client for API operations
-/// Handles authentication, rate limiting, and error handling
+/// Handles authentication, rate limiting (see `rate_limit`), and error handling
 #[derive(Debug, Clone)]
 pub struct GitHubClient {
     /// 🐙 Octocrab client instance
     octocrab: Octocrab,
+    /// 🕸️ Octocrab client on the GraphQL base URL
+    graphql: Octocrab,
     /// ⚙️ GitHub configuration
     config: GitHubConfig,
 }
@@ -46,6 +64,10 @@ pub struct FeedbackProcessingRequest {
     pub commit_message: String,
     /// 🌿 Branch name for the PR
     pub branch_name: String,
+    /// 🏷️ Project PR settings (labels, milestone, draft, assignees)
+    pub pull_request_settings: PullRequestSettings,
+    /// 🧪 Verification steps for the PR test plan
+    pub test_plan: Vec<String>,
 }
 
 /// 🔧 Code improvement generated by AI
@@ -66,7 +88,7 @@ pub struct CodeImprovement {
 }
 
 /// 🔄 Type of code change
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ChangeType {
     /// ➕ Create a new file
@@ -98,6 +120,19 @@ pub struct PullRequestResult {
     pub error_message: Option<String>,
 }
 
+/// 📝 Pull request content to open
+#[derive(Debug, Clone)]
+pub struct NewPullRequest {
+    /// 📝 PR title
+    pub title: String,
+    /// 📄 PR body (markdown)
+    pub body: String,
+    /// 🌿 Branch with the changes
+    pub head: String,
+    /// 🎯 Branch to merge into
+    pub base: String,
+}
+
 /// 📊 Repository information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryInfo {
@@ -113,8 +148,78 @@ pub struct RepositoryInfo {
     pub default_branch: String,
     /// 🔒 Whether repository is private
     pub is_private: bool,
-    /// 👥 Whether aye-is has collaborator access
+    /// 👥 Whether aye-is can push to it (also true when GitHub reports no
+    /// permissions, as for GitHub App installation tokens)
     pub has_collaborator_access: bool,
+    /// 🗄️ Whether it is archived (read-only)
+    #[serde(default)]
+    pub is_archived: bool,
+    /// 🍴 Whether it is a fork
+    #[serde(default)]
+    pub is_fork: bool,
+    /// 🌳 The repository it was forked from ("owner/repo")
+    #[serde(default)]
+    pub parent: Option<String>,
+}
+
+impl RepositoryInfo {
+    /// 🚧 Why feedback for this repository could never become a pull request
+    /// (None when it can)
+    pub fn access_problem(&self, username: &str) -> Option<String> {
+        if self.is_archived {
+            return Some(format!(
+                "Repository {} is archived and accepts no pull requests",
+                self.full_name
+            ));
+        }
+        if self.is_fork {
+            return Some(match &self.parent {
+                Some(parent) => format!(
+                    "Repository {} is a fork; send feedback for {} instead",
+                    self.full_name, parent
+                ),
+                None => format!("Repository {} is a fork", self.full_name),
+            });
+        }
+        if !self.has_collaborator_access {
+            return Some(format!(
+                "{} has no write access to {}; add it as a collaborator",
+                username, self.full_name
+            ));
+        }
+        None
+    }
+}
+
+/// 💥 GitHub answered a REST call with an error status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubStatusError {
+    /// 🔧 The call ("GET /repos/...")
+    pub operation: String,
+    /// 🔢 What GitHub answered
+    pub status: StatusCode,
+    /// 📝 GitHub's message
+    pub message: String,
+}
+
+impl fmt::Display for GitHubStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GitHub answered {} with {}: {}",
+            self.operation, self.status, self.message
+        )
+    }
+}
+
+impl std::error::Error for GitHubStatusError {}
+
+/// 🔢 The status GitHub answered a failed call with (None when it failed otherwise)
+pub fn error_status(error: &anyhow::Error) -> Option<StatusCode> {
+    error
+        .chain()
+        .find_map(|e| e.downcast_ref::<GitHubStatusError>())
+        .map(|e| e.status)
 }
 
 impl GitHubClient {
@@ -124,46 +229,163 @@ impl GitHubClient {
             .personal_token(config.token.clone())
             .base_uri(&config.api_base_url)?
             .build()?;
+        let graphql = Octocrab::builder()
+            .personal_token(config.token.clone())
+            .base_uri(graphql::graphql_base_url(&config.api_base_url))?
+            .build()?;
+
+        Ok(Self {
+            octocrab,
+            graphql,
+            config,
+        })
+    }
+
+    /// 📡 Send a REST request, minding the rate limit
+    /// Deferrable calls fail with `RateLimited` near exhaustion instead of waiting;
+    /// rate limited responses are waited out and retried GITHUB_RATE_LIMIT_RETRIES times
+    async fn send<R, Q>(
+        &self,
+        method: Method,
+        route: &str,
+        query: Option<&Q>,
+        body: Option<&serde_json::Value>,
+        urgency: Urgency,
+    ) -> Result<R>
+    where
+        R: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        let uri = match query {
+            Some(query) => format!("{}?{}", route, serde_urlencoded::to_string(query)?),
+            None => route.to_string(),
+        };
+        let operation = format!("{} {}", method, route);
+        let max_wait = Duration::from_secs(self.config.rate_limit_max_wait_seconds);
+        let (octocrab, resource) = if route == graphql::GRAPHQL_ROUTE {
+            (&self.graphql, "graphql")
+        } else {
+            (&self.octocrab, "core")
+        };
+
+        if let Some(wait) = rate_limit::wait_before(
+            urgency,
+            rate_limit::current(resource).as_ref(),
+            self.config.rate_limit_reserve,
+            Utc::now(),
+        ) {
+            if urgency == Urgency::Deferrable || wait > max_wait {
+                metrics::record_github_rate_limited_call("deferred");
+                return Err(RateLimited { operation, wait }.into());
+            }
+            warn!(
+                "⏸️ GitHub rate limit exhausted, waiting {}s before {}",
+                wait.as_secs(),
+                operation
+            );
+            metrics::record_github_rate_limited_call("waited");
+            tokio::time::sleep(wait).await;
+        }
+
+        let mut attempt = 0;
+        loop {
+            let request =
+                octocrab.build_request(Builder::new().method(method.clone()).uri(&uri), body)?;
+            let response = octocrab
+                .execute(request)
+                .await
+                .with_context(|| format!("GitHub request {} failed", operation))?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            rate_limit::record(&headers);
+            let text = octocrab.body_to_string(response).await?;
+
+            if status.is_success() {
+                // 📭 204 No Content and friends
+                let text = if text.trim().is_empty() { "null" } else { &text };
+                return serde_json::from_str(text)
+                    .with_context(|| format!("Unexpected GitHub response to {}", operation));
+            }
+
+            if let Some(wait) = rate_limit::retry_after(status, &headers, &text, Utc::now()) {
+                if attempt < self.config.rate_limit_retries && wait <= max_wait {
+                    attempt += 1;
+                    warn!(
+                        "🔁 GitHub rate limited {}, retrying in {}s ({}/{})",
+                        operation,
+                        wait.as_secs(),
+                        attempt,
+                        self.config.rate_limit_retries
+                    );
+                    metrics::record_github_rate_limited_call("retried");
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                metrics::record_github_rate_limited_call("deferred");
+                return Err(RateLimited { operation, wait }.into());
+            }
 
-        Ok(Self { octocrab, config })
+            let message = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|json| json["message"].as_str().map(str::to_string))
+                .unwrap_or(text);
+            return Err(GitHubStatusError {
+                operation,
+                status,
+                message,
+            }
+            .into());
+        }
     }
 
     /// 🔍 Get repository information
     pub async fn get_repository_info(&self, owner: &str, repo: &str) -> Result<RepositoryInfo> {
         info!("🔍 Fetching repository information for {}/{}", owner, repo);
 
-        let repository = self
-            .octocrab
-            .repos(owner, repo)
-            .get()
+        let repository: RepoRef = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}", owner, repo),
+                None::<&()>,
+                None,
+                Urgency::Urgent,
+            )
             .await
             .context("Failed to fetch repository information")?;
 
-        // 👥 Check if aye-is is a collaborator
-        let has_collaborator_access = self
-            .check_collaborator_access(owner, repo)
-            .await
-            .unwrap_or(false);
-
         let repo_info = RepositoryInfo {
             owner: repository
                 .owner
-                .map(|o| o.login)  // 🔧 Fixed: map returns Option<String>, which is what we want
+                .map(|o| o.login)
                 .unwrap_or_else(|| "unknown".to_string()),
-            name: repository.name.clone(),
-            full_name: repository.full_name.unwrap_or_default(),
-            description: repository.description.clone(),
+            name: repository.name,
+            full_name: repository.full_name,
+            description: repository.description,
             default_branch: repository
                 .default_branch
                 .unwrap_or_else(|| "main".to_string()),
-            is_private: repository.private.unwrap_or(false),
-            has_collaborator_access,
+            is_private: repository.private,
+            has_collaborator_access: repository
+                .permissions
+                .is_none_or(|permissions| permissions.push),
+            is_archived: repository.archived,
+            is_fork: repository.fork,
+            parent: repository.parent.map(|parent| parent.full_name),
         };
 
         debug!("✅ Repository info retrieved: {:?}", repo_info);
         Ok(repo_info)
     }
 
+    /// 🪝 Networks GitHub delivers webhooks from (the `hooks` list of GET /meta)
+    pub async fn hook_ranges(&self) -> Result<Vec<IpNet>> {
+        let meta: MetaRef = self
+            .send(Method::GET, "/meta", None::<&()>, None, Urgency::Urgent)
+            .await
+            .context("Failed to fetch GitHub's webhook IP ranges")?;
+        Ok(meta.hooks)
+    }
+
     /// 👥 Check if aye-is has collaborator access to the repository
     pub async fn check_collaborator_access(&self, owner: &str, repo: &str) -> Result<bool> {
         debug!(
@@ -171,23 +393,38 @@ impl GitHubClient {
             owner, repo
         );
 
-        // TODO: Implement proper collaborator check when GitHub API is ready
-        match Ok::<(), anyhow::Error>(()) {
-            Ok(_) => {
-                debug!("✅ aye-is has collaborator access to {}/{}", owner, repo);
-                Ok(true)
-            }
-            Err(e) => {
-                warn!(
-                    "❌ aye-is does not have collaborator access to {}/{}: {:#}",
-                    owner, repo, e
-                );
-                Ok(false)
-            }
+        let has_access = self
+            .get_repository_info(owner, repo)
+            .await?
+            .has_collaborator_access;
+        if has_access {
+            debug!("✅ aye-is has collaborator access to {}/{}", owner, repo);
+        } else {
+            warn!(
+                "❌ aye-is does not have collaborator access to {}/{}",
+                owner, repo
+            );
         }
+        Ok(has_access)
     }
 
-    /// 🌿 Create a new branch for feedback processing
+    /// 🔍 Get repository information, None when GitHub doesn't show us the
+    /// repository (it doesn't exist, or the token can't see it)
+    pub async fn find_repository_info(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Option<RepositoryInfo>> {
+        match self.get_repository_info(owner, repo).await {
+            Ok(info) => Ok(Some(info)),
+            Err(e) if error_status(&e) == Some(StatusCode::NOT_FOUND) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 🌿 Create a branch for feedback processing at the tip of `base_branch`
+    /// (the repository's default branch when None), resetting it if it exists
+    /// Returns the commit the branch starts at
     pub async fn create_feedback_branch(
         &self,
         owner: &str,
@@ -195,39 +432,520 @@ impl GitHubClient {
         branch_name: &str,
         base_branch: Option<&str>,
     ) -> Result<String> {
-        info!("🌿 Creating branch '{}' in {}/{}", branch_name, owner, repo);
-        // TODO: Implement proper branch creation when GitHub API is properly integrated
-        Ok("mock_sha".to_string())
+        let base_branch = match base_branch {
+            Some(branch) => branch.to_string(),
+            None => self.get_repository_info(owner, repo).await?.default_branch,
+        };
+        info!(
+            "🌿 Creating branch '{}' from '{}' in {}/{}",
+            branch_name, base_branch, owner, repo
+        );
+
+        let sha = self.branch_head(owner, repo, &base_branch).await?;
+        self.start_branch(owner, repo, branch_name, &sha).await?;
+        Ok(sha)
+    }
+
+    /// 📝 Commit a request's improvements onto its branch (one commit, through
+    /// the Git Data API) and return the new commit's SHA
+    pub async fn apply_improvements(&self, request: &FeedbackProcessingRequest) -> Result<String> {
+        info!(
+            "📝 Committing {} improvements for feedback {} to {}",
+            request.improvements.len(),
+            request.feedback_id,
+            request.branch_name
+        );
+        if request.improvements.is_empty() {
+            anyhow::bail!("Nothing to commit to {}", request.branch_name);
+        }
+        let (owner, repo) = parse_repository(&request.repository)?;
+        let parent = self
+            .branch_head(&owner, &repo, &request.branch_name)
+            .await?;
+
+        // 🔐 Edited files keep their mode, so executable scripts stay executable
+        let edits_existing = request.improvements.iter().any(|improvement| {
+            matches!(
+                improvement.change_type,
+                ChangeType::Modify | ChangeType::Append
+            )
+        });
+        let existing = if edits_existing {
+            self.blob_tree(&owner, &repo, &parent)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!("🌳 File modes unavailable, using 100644: {:#}", e);
+                    pulls::BlobTree::new()
+                })
+        } else {
+            pulls::BlobTree::new()
+        };
+
+        let changes: Vec<TreeChange> = request
+            .improvements
+            .iter()
+            .map(|improvement| match improvement.change_type {
+                ChangeType::Delete => TreeChange::Removed {
+                    path: improvement.file_path.clone(),
+                },
+                _ => TreeChange::Text {
+                    path: improvement.file_path.clone(),
+                    mode: existing
+                        .get(&improvement.file_path)
+                        .map(|entry| entry.mode.clone())
+                        .unwrap_or_else(|| "100644".to_string()),
+                    content: improvement.new_content.clone(),
+                },
+            })
+            .collect();
+
+        let commit = self
+            .commit_tree(&owner, &repo, &parent, &changes, &request.commit_message)
+            .await?;
+        self.force_update_branch(&owner, &repo, &request.branch_name, &commit)
+            .await?;
+        debug!("✅ Committed {} to {}", commit, request.branch_name);
+        Ok(commit)
     }
 
-    /// 📝 Apply code improvements to a repository
-    pub async fn apply_improvements(
+    /// 🔗 Open a pull request and apply the project's PR settings
+    /// Labels, milestone, and assignees go through the issues API because
+    /// the pulls API doesn't accept them at creation time
+    pub async fn create_pull_request(
         &self,
-        request: &FeedbackProcessingRequest,
+        owner: &str,
+        repo: &str,
+        new_pr: &NewPullRequest,
+        settings: &PullRequestSettings,
     ) -> Result<PullRequestResult> {
         info!(
-            "📝 Applying improvements for feedback: {}",
-            request.feedback_id
+            "🔗 Opening {} PR from {} to {} in {}/{}",
+            if settings.draft { "draft" } else { "ready" },
+            new_pr.head,
+            new_pr.base,
+            owner,
+            repo
         );
-        // TODO: Implement proper improvement application when GitHub API is ready
+
+        let pr: PullRef = self
+            .send(
+                Method::POST,
+                &format!("/repos/{}/{}/pulls", owner, repo),
+                None::<&()>,
+                Some(&serde_json::json!({
+                    "title": new_pr.title,
+                    "head": new_pr.head,
+                    "base": new_pr.base,
+                    "body": new_pr.body,
+                    "draft": settings.draft,
+                })),
+                Urgency::Urgent,
+            )
+            .await
+            .with_context(|| format!("Failed to create pull request in {}/{}", owner, repo))?;
+
+        if settings.has_issue_fields() {
+            if let Err(e) = self
+                .apply_pull_request_settings(owner, repo, pr.number, settings)
+                .await
+            {
+                // 🟡 The PR exists, so don't fail the whole run over metadata
+                warn!(
+                    "⚠️ PR #{} created but settings could not be applied: {:#}",
+                    pr.number, e
+                );
+            }
+        }
+
         Ok(PullRequestResult {
-            url: "https://github.com/mock/repo/pull/1".to_string(),
-            number: 1,
-            title: "Mock PR".to_string(),
-            branch_name: request.branch_name.clone(),
-            base_branch: "main".to_string(),
+            url: pr.html_url,
+            number: pr.number,
+            title: new_pr.title.clone(),
+            branch_name: new_pr.head.clone(),
+            base_branch: new_pr.base.clone(),
             success: true,
             error_message: None,
         })
     }
 
-    // TODO: Implement apply_single_improvement when GitHub API is ready
+    /// 📝 Replace the body of an existing pull request
+    pub async fn update_pull_request_body(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        body: &str,
+    ) -> Result<()> {
+        let route = format!("/repos/{}/{}/pulls/{}", owner, repo, number);
+        let _: serde_json::Value = self
+            .send(
+                Method::PATCH,
+                &route,
+                None::<&()>,
+                Some(&serde_json::json!({ "body": body })),
+                Urgency::Urgent,
+            )
+            .await
+            .with_context(|| format!("Failed to update PR #{} in {}/{}", number, owner, repo))?;
+
+        Ok(())
+    }
 
-    // TODO: Implement create_pull_request when GitHub API is ready
+    /// 🎫 Open an issue and return its URL
+    /// Deferrable: near the rate limit this fails with `RateLimited` for a later retry
+    pub async fn create_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<String> {
+        info!("🎫 Opening issue '{}' in {}/{}", title, owner, repo);
+
+        let issue: IssueRef = self
+            .send(
+                Method::POST,
+                &format!("/repos/{}/{}/issues", owner, repo),
+                None::<&()>,
+                Some(&serde_json::json!({ "title": title, "body": body })),
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to create issue in {}/{}", owner, repo))?;
+
+        debug!("✅ Issue #{} created", issue.number);
+        Ok(issue.html_url)
+    }
+
+    /// 📋 Open issues of a repository, oldest first, having every one of `labels`
+    /// Read through GraphQL a hundred at a time with their labels and latest
+    /// comments, until `limit` issues are found. Deferrable like `create_issue`
+    pub async fn list_open_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        labels: &[String],
+        limit: usize,
+    ) -> Result<Vec<OpenIssue>> {
+        let variables = OpenIssuesVariables {
+            owner: owner.to_string(),
+            name: repo.to_string(),
+            // 🏷️ GitHub matches any of the labels, so the rest is checked here
+            labels: (!labels.is_empty()).then(|| labels.to_vec()),
+            first: graphql::PAGE_SIZE,
+            after: None,
+        };
+        let issues: Vec<OpenIssue> = self
+            .query_all::<OpenIssuesQuery, _>(variables, limit, Urgency::Deferrable, |issue| {
+                labels.iter().all(|label| issue.has_label(label))
+            })
+            .await
+            .with_context(|| format!("Failed to list issues of {}/{}", owner, repo))?
+            .into_iter()
+            .map(OpenIssue::from)
+            .collect();
+
+        debug!("✅ {} open issues in {}/{}", issues.len(), owner, repo);
+        Ok(issues)
+    }
+
+    /// 👥 Logins of everyone with at least `permission` (push, maintain or admin)
+    /// on a repository: direct collaborators, organization members and team members.
+    /// Read a hundred at a time, up to MAX_COLLABORATOR_PAGES. A list that's still full
+    /// on the last page is an error rather than a partial answer, so callers never
+    /// mistake it for everyone. Deferrable like `create_issue`
+    pub async fn list_collaborators(
+        &self,
+        owner: &str,
+        repo: &str,
+        permission: &str,
+    ) -> Result<Vec<String>> {
+        let mut logins = Vec::new();
+        for page in 1..=MAX_COLLABORATOR_PAGES {
+            let collaborators: Vec<UserLogin> = self
+                .send(
+                    Method::GET,
+                    &format!("/repos/{}/{}/collaborators", owner, repo),
+                    Some(&[
+                        ("affiliation", "all"),
+                        ("permission", permission),
+                        ("per_page", "100"),
+                        ("page", &page.to_string()),
+                    ]),
+                    None,
+                    Urgency::Deferrable,
+                )
+                .await
+                .with_context(|| format!("Failed to list collaborators of {}/{}", owner, repo))?;
+            let last_page = collaborators.len() < 100;
+            logins.extend(collaborators.into_iter().map(|user| user.login));
+            if last_page {
+                debug!("✅ {} collaborators on {}/{}", logins.len(), owner, repo);
+                return Ok(logins);
+            }
+        }
+
+        anyhow::bail!(
+            "{}/{} has more than {} collaborators; the list would be incomplete",
+            owner,
+            repo,
+            logins.len()
+        )
+    }
+
+    /// 📜 Release notes for a version, matched by tag (`1.2.3`, `v1.2.3`, `name-v1.2.3`, `name@1.2.3`)
+    pub async fn find_release_notes(
+        &self,
+        owner: &str,
+        repo: &str,
+        version: &str,
+    ) -> Result<Option<String>> {
+        let releases: Vec<ReleaseRef> = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/releases", owner, repo),
+                Some(&[("per_page", 30)]),
+                None,
+                Urgency::Urgent,
+            )
+            .await
+            .with_context(|| format!("Failed to list releases of {}/{}", owner, repo))?;
+
+        Ok(releases
+            .into_iter()
+            .find(|release| {
+                let tag = release.tag_name.rsplit(['@', '-']).next().unwrap_or_default();
+                tag.trim_start_matches('v') == version
+            })
+            .and_then(|release| release.body)
+            .filter(|body| !body.trim().is_empty()))
+    }
+
+    /// 🏷️ Apply labels, milestone, and assignees to an existing PR
+    async fn apply_pull_request_settings(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        settings: &PullRequestSettings,
+    ) -> Result<()> {
+        let mut patch = serde_json::Map::new();
+
+        if !settings.labels.is_empty() {
+            patch.insert("labels".to_string(), serde_json::json!(settings.labels));
+        }
+        if !settings.assignees.is_empty() {
+            patch.insert("assignees".to_string(), serde_json::json!(settings.assignees));
+        }
+        if let Some(title) = &settings.milestone {
+            let milestone = self
+                .find_open_milestone(owner, repo, title)
+                .await?
+                .with_context(|| format!("Milestone '{}' not found", title))?;
+            patch.insert("milestone".to_string(), serde_json::json!(milestone.number));
+        }
+
+        let _: serde_json::Value = self
+            .send(
+                Method::PATCH,
+                &format!("/repos/{}/{}/issues/{}", owner, repo, number),
+                None::<&()>,
+                Some(&serde_json::Value::Object(patch)),
+                Urgency::Urgent,
+            )
+            .await
+            .context("Failed to update pull request labels/milestone/assignees")?;
+
+        debug!("✅ PR settings applied to #{}", number);
+        Ok(())
+    }
+
+    /// ✅ Validate PR settings against the repository itself
+    /// Returns a list of human-readable problems (empty means all good)
+    /// Labels and assignees are looked up one by one, so repositories with
+    /// more of them than fit on a page are checked just as well
+    pub async fn validate_pull_request_settings(
+        &self,
+        owner: &str,
+        repo: &str,
+        settings: &PullRequestSettings,
+    ) -> Result<Vec<String>> {
+        let mut problems = Vec::new();
+
+        for label in &settings.labels {
+            let route = format!("/repos/{}/{}/labels/{}", owner, repo, path_segment(label));
+            if !self
+                .exists(&route)
+                .await
+                .context("Failed to look up repository label")?
+            {
+                problems.push(format!("Label '{}' does not exist in {}/{}", label, owner, repo));
+            }
+        }
+
+        if let Some(title) = &settings.milestone {
+            if self.find_open_milestone(owner, repo, title).await?.is_none() {
+                problems.push(format!(
+                    "Milestone '{}' is not an open milestone in {}/{}",
+                    title, owner, repo
+                ));
+            }
+        }
+
+        for user in &settings.assignees {
+            let route = format!("/repos/{}/{}/assignees/{}", owner, repo, path_segment(user));
+            if !self
+                .exists(&route)
+                .await
+                .context("Failed to check assignable user")?
+            {
+                problems.push(format!("User '{}' cannot be assigned in {}/{}", user, owner, repo));
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// 🔍 Whether GET `route` finds something (false when GitHub answers 404)
+    async fn exists(&self, route: &str) -> Result<bool> {
+        match self
+            .send::<serde_json::Value, ()>(Method::GET, route, None, None, Urgency::Urgent)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if error_status(&e) == Some(StatusCode::NOT_FOUND) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 🎯 Look up an open milestone by title
+    async fn find_open_milestone(
+        &self,
+        owner: &str,
+        repo: &str,
+        title: &str,
+    ) -> Result<Option<MilestoneRef>> {
+        let milestones: Vec<MilestoneRef> = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/milestones", owner, repo),
+                Some(&[("state", "open"), ("per_page", "100")]),
+                None,
+                Urgency::Urgent,
+            )
+            .await
+            .context("Failed to list repository milestones")?;
+
+        Ok(milestones
+            .into_iter()
+            .find(|m| m.title.eq_ignore_ascii_case(title)))
+    }
+}
+
+/// 👤 Minimal user shape from the REST API
+#[derive(Debug, Deserialize)]
+struct UserLogin {
+    login: String,
+}
+
+/// 🌐 The part of GitHub's meta endpoint we read
+#[derive(Debug, Deserialize)]
+struct MetaRef {
+    #[serde(default)]
+    hooks: Vec<IpNet>,
+}
+
+/// 📦 Minimal repository shape from the REST API
+#[derive(Debug, Deserialize)]
+struct RepoRef {
+    owner: Option<UserLogin>,
+    name: String,
+    #[serde(default)]
+    full_name: String,
+    description: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    fork: bool,
+    parent: Option<RepoName>,
+    /// 🔑 What the token may do (absent for GitHub App installation tokens)
+    permissions: Option<RepoPermissions>,
+}
+
+/// 🌳 Just the name of a repository
+#[derive(Debug, Deserialize)]
+struct RepoName {
+    full_name: String,
+}
+
+/// 🔑 The token's permissions on a repository
+#[derive(Debug, Deserialize)]
+struct RepoPermissions {
+    #[serde(default)]
+    push: bool,
+}
+
+/// 🔗 Minimal pull request shape from the REST API
+#[derive(Debug, Deserialize)]
+struct PullRef {
+    number: u64,
+    html_url: String,
+}
+
+/// 🎯 Minimal milestone shape from the REST API
+#[derive(Debug, Deserialize)]
+struct MilestoneRef {
+    number: u64,
+    title: String,
+}
+
+/// 📜 Minimal release shape from the REST API
+#[derive(Debug, Deserialize)]
+struct ReleaseRef {
+    tag_name: String,
+    body: Option<String>,
+}
+
+/// 🎫 An open issue, as listed by `list_open_issues`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenIssue {
+    /// 🔢 Issue number
+    pub number: u64,
+    /// 🏷️ Title
+    pub title: String,
+    /// 📝 Description (None when left empty)
+    pub body: Option<String>,
+    /// 🔗 Where it lives on GitHub
+    pub html_url: String,
+    /// 🏷️ Label names
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// 💬 Latest comments, oldest first
+    #[serde(default)]
+    pub comments: Vec<IssueComment>,
+}
+
+/// 💬 A comment on an issue
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueComment {
+    /// 👤 Who wrote it ("ghost" for deleted accounts)
+    pub author: String,
+    /// 📝 What they wrote
+    pub body: String,
+}
+
+/// 🎫 Minimal issue shape from the REST API
+#[derive(Debug, Deserialize)]
+struct IssueRef {
+    number: u64,
+    html_url: String,
 }
 
 /// 🔧 Parse repository string (owner/repo) into components
-fn parse_repository(repository: &str) -> Result<(String, String)> {
+pub fn parse_repository(repository: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = repository.split('/').collect();
     if parts.len() != 2 {
         anyhow::bail!(
@@ -238,36 +956,6 @@ fn parse_repository(repository: &str) -> Result<(String, String)> {
     Ok((parts[0].to_string(), parts[1].to_string()))
 }
 
-/// 📝 Generate pull request description from feedback and improvements
-fn generate_pr_description(
-    feedback_content: &str,
-    applied_improvements: &[(CodeImprovement, String)],
-) -> String {
-    let mut description = String::new();
-
-    description.push_str("## 🤖 AI-Generated Improvements\n\n");
-    description.push_str("This pull request contains improvements generated by Feedbacker based on user feedback.\n\n");
-
-    description.push_str("### 📝 Original Feedback\n");
-    description.push_str(&format!("> {}\n\n", feedback_content));
-
-    description.push_str("### 🔧 Applied Changes\n");
-    for (improvement, _) in applied_improvements {
-        description.push_str(&format!(
-            "- **{}**: {} ({})\n",
-            improvement.file_path,
-            improvement.description,
-            format!("{:?}", improvement.change_type).to_lowercase()
-        ));
-    }
-
-    description.push_str("\n---\n");
-    description.push_str("🚢 Generated with love by [Feedbacker](https://github.com/aye-is/feedbacker) - Aye & Hue\n");
-    description.push_str("🤖 Powered by AI for intelligent code improvements\n");
-
-    description
-}
-
 // 🧪 Tests - Because GitHub integration needs thorough testing!
 #[cfg(test)]
 mod tests {
@@ -285,26 +973,55 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_pr_description() {
-        let feedback = "Please add error handling to the main function";
-        let improvements = vec![(
-            CodeImprovement {
-                file_path: "src/main.rs".to_string(),
-                description: "Add error handling".to_string(),
-                change_type: ChangeType::Modify,
-                original_content: None,
-                new_content: "// Updated with error handling".to_string(),
-                line_number: Some(10),
-            },
-            "abc123".to_string(),
-        )];
-
-        let description = generate_pr_description(feedback, &improvements);
-        assert!(description.contains("AI-Generated Improvements"));
-        assert!(description.contains("Please add error handling"));
-        assert!(description.contains("src/main.rs"));
-        assert!(description.contains("Feedbacker"));
-        println!("✅ PR description generation test passed!");
+    fn test_repository_access_problem() {
+        let repository: RepoRef = serde_json::from_value(serde_json::json!({
+            "owner": { "login": "aye-is" },
+            "name": "feedbacker",
+            "full_name": "aye-is/feedbacker",
+            "permissions": { "admin": false, "push": true, "pull": true }
+        }))
+        .unwrap();
+        let mut info = RepositoryInfo {
+            owner: "aye-is".to_string(),
+            name: repository.name,
+            full_name: repository.full_name,
+            description: None,
+            default_branch: "main".to_string(),
+            is_private: false,
+            has_collaborator_access: repository.permissions.unwrap().push,
+            is_archived: repository.archived,
+            is_fork: repository.fork,
+            parent: None,
+        };
+        assert_eq!(info.access_problem("aye-is"), None);
+
+        info.has_collaborator_access = false;
+        assert!(info
+            .access_problem("aye-is")
+            .unwrap()
+            .contains("no write access"));
+        info.is_fork = true;
+        info.parent = Some("upstream/feedbacker".to_string());
+        assert!(info
+            .access_problem("aye-is")
+            .unwrap()
+            .contains("send feedback for upstream/feedbacker"));
+        info.is_archived = true;
+        assert!(info.access_problem("aye-is").unwrap().contains("archived"));
+        println!("✅ Repository access test passed!");
+    }
+
+    #[test]
+    fn test_error_status() {
+        let not_found = anyhow::Error::new(GitHubStatusError {
+            operation: "GET /repos/aye-is/missing".to_string(),
+            status: StatusCode::NOT_FOUND,
+            message: "Not Found".to_string(),
+        })
+        .context("Failed to fetch repository information");
+        assert_eq!(error_status(&not_found), Some(StatusCode::NOT_FOUND));
+        assert_eq!(error_status(&anyhow::anyhow!("connection reset")), None);
+        println!("✅ GitHub error status test passed!");
     }
 
     #[test]
@@ -325,4 +1042,235 @@ mod tests {
         assert_eq!(deserialized.file_path, improvement.file_path);
         println!("✅ Code improvement serialization test passed!");
     }
+
+    /// 🐙 A client talking to a mock API
+    fn test_client(api_base_url: &str) -> GitHubClient {
+        GitHubClient::new(GitHubConfig {
+            username: "aye-is".to_string(),
+            token: "test-token".to_string(),
+            ssh_private_key_path: String::new(),
+            api_base_url: api_base_url.to_string(),
+            default_commit_message: String::new(),
+            default_branch_prefix: "feedbacker/".to_string(),
+            clone_cache_dir: String::new(),
+            clone_cache_size: 1,
+            rate_limit_reserve: 0,
+            rate_limit_max_wait_seconds: 0,
+            rate_limit_retries: 0,
+            endpoints: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collaborators_past_the_last_page_are_an_error() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let logins = |count: usize| {
+            let users: Vec<_> = (0..count)
+                .map(|i| serde_json::json!({ "login": format!("dev{}", i) }))
+                .collect();
+            ResponseTemplate::new(200).set_body_json(users)
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/small/collaborators"))
+            .and(query_param("page", "1"))
+            .respond_with(logins(100))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/small/collaborators"))
+            .and(query_param("page", "2"))
+            .respond_with(logins(3))
+            .mount(&server)
+            .await;
+        // 📏 Every page full: reading more would go past MAX_COLLABORATOR_PAGES
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/huge/collaborators"))
+            .respond_with(logins(100))
+            .expect(u64::from(MAX_COLLABORATOR_PAGES))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let small = client
+            .list_collaborators("aye-is", "small", "push")
+            .await
+            .unwrap();
+        assert_eq!(small.len(), 103);
+        let error = client
+            .list_collaborators("aye-is", "huge", "push")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("more than 1000 collaborators"));
+        server.verify().await;
+        println!("✅ Collaborator listing limit test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_feedback_branch_is_created_and_committed() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let json = |value: serde_json::Value| ResponseTemplate::new(200).set_body_json(value);
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/git/ref/heads/main"))
+            .respond_with(json(serde_json::json!({ "object": { "sha": "base" } })))
+            .mount(&server)
+            .await;
+        // 🔁 The branch is left over from an earlier attempt: it's reset to main
+        Mock::given(method("POST"))
+            .and(path("/repos/aye-is/demo/git/refs"))
+            .and(body_partial_json(serde_json::json!({
+                "ref": "refs/heads/feedbacker/feedback-1", "sha": "base"
+            })))
+            .respond_with(
+                ResponseTemplate::new(422)
+                    .set_body_json(serde_json::json!({ "message": "Reference already exists" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/aye-is/demo/git/refs/heads/feedbacker/feedback-1"))
+            .and(body_partial_json(serde_json::json!({ "sha": "base" })))
+            .respond_with(json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let sha = client
+            .create_feedback_branch("aye-is", "demo", "feedbacker/feedback-1", Some("main"))
+            .await
+            .unwrap();
+        assert_eq!(sha, "base");
+        server.verify().await;
+        server.reset().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/git/ref/heads/feedbacker/feedback-1"))
+            .respond_with(json(serde_json::json!({ "object": { "sha": "base" } })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/git/trees/base"))
+            .respond_with(json(serde_json::json!({
+                "tree": [{ "path": "run.sh", "mode": "100755", "type": "blob", "sha": "s1" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/git/commits/base"))
+            .respond_with(json(serde_json::json!({ "tree": { "sha": "base-tree" } })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/aye-is/demo/git/trees"))
+            .and(body_partial_json(serde_json::json!({
+                "base_tree": "base-tree",
+                "tree": [
+                    { "path": "run.sh", "mode": "100755", "content": "echo hi\n" },
+                    { "path": "old.txt", "sha": null }
+                ]
+            })))
+            .respond_with(json(serde_json::json!({ "sha": "new-tree" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/aye-is/demo/git/commits"))
+            .and(body_partial_json(serde_json::json!({
+                "tree": "new-tree", "parents": ["base"], "message": "📝 Say hi"
+            })))
+            .respond_with(json(serde_json::json!({ "sha": "new-commit" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/aye-is/demo/git/refs/heads/feedbacker/feedback-1"))
+            .and(body_partial_json(serde_json::json!({ "sha": "new-commit" })))
+            .respond_with(json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let improvement = |file_path: &str, change_type: ChangeType, new_content: &str| {
+            CodeImprovement {
+                file_path: file_path.to_string(),
+                description: String::new(),
+                change_type,
+                original_content: None,
+                new_content: new_content.to_string(),
+                line_number: None,
+            }
+        };
+        let request = FeedbackProcessingRequest {
+            feedback_id: Uuid::new_v4(),
+            repository: "aye-is/demo".to_string(),
+            feedback_content: "Say hi".to_string(),
+            improvements: vec![
+                improvement("run.sh", ChangeType::Modify, "echo hi\n"),
+                improvement("old.txt", ChangeType::Delete, ""),
+            ],
+            commit_message: "📝 Say hi".to_string(),
+            branch_name: "feedbacker/feedback-1".to_string(),
+            pull_request_settings: PullRequestSettings::default(),
+            test_plan: Vec::new(),
+        };
+        assert_eq!(client.apply_improvements(&request).await.unwrap(), "new-commit");
+        server.verify().await;
+        println!("✅ Feedback branch commit test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_validate_pull_request_settings_looks_up_each_name() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // 🏷️ Labels with slashes and spaces stay one path segment
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/labels/area%2Fui%20review"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "area/ui review"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/aye-is/demo/assignees/hue"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let settings = PullRequestSettings {
+            labels: vec!["area/ui review".to_string(), "missing".to_string()],
+            assignees: vec!["hue".to_string(), "stranger".to_string()],
+            ..Default::default()
+        };
+
+        let problems = client
+            .validate_pull_request_settings("aye-is", "demo", &settings)
+            .await
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                "Label 'missing' does not exist in aye-is/demo",
+                "User 'stranger' cannot be assigned in aye-is/demo",
+            ]
+        );
+        println!("✅ PR settings validation test passed!");
+    }
 }
@@ -0,0 +1,296 @@
+// 🧱 Special Git Objects - LFS Pointers and Submodules! 🧱
+// Some paths in a repository aren't ordinary files we can rewrite:
+// - 📦 Git LFS files are tiny pointer files; writing real content over them corrupts the repo
+// - 🔗 Submodules are commits in another repository, not files at all
+// This module finds them so the pipeline can skip them as context and refuse to edit them
+// Created with love by Aye & Hue - Some files are better left alone! ✨
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt;
+
+/// 📏 LFS pointer files are well under this size
+const MAX_LFS_POINTER_SIZE: u32 = 512;
+
+/// 🏷️ First line of every LFS pointer file
+const LFS_POINTER_PREFIX: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// 🔗 Index mode for submodule entries (gitlinks)
+pub const GITLINK_MODE: u32 = 0o160000;
+
+/// 🧱 Kinds of paths the pipeline cannot edit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnsupportedObject {
+    /// 📦 Tracked by Git LFS
+    LfsTracked,
+    /// 🔗 Inside a submodule
+    Submodule,
+}
+
+impl fmt::Display for UnsupportedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsupportedObject::LfsTracked => write!(f, "tracked by Git LFS"),
+            UnsupportedObject::Submodule => write!(f, "inside a submodule"),
+        }
+    }
+}
+
+/// 📦 A `filter=lfs` pattern from a .gitattributes file
+#[derive(Debug, Clone, PartialEq)]
+struct LfsPattern {
+    /// 📂 Directory containing the .gitattributes file ("" for the root)
+    base: String,
+    /// 🔍 Pattern as written
+    pattern: String,
+}
+
+/// 🧱 Snapshot of a repository's LFS and submodule paths
+/// Plain data, so it can travel through async pipeline stages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepositoryObjects {
+    /// 📦 `filter=lfs` patterns (cover files the plan wants to create)
+    lfs_patterns: Vec<LfsPattern>,
+    /// 📦 Existing files whose content is an LFS pointer
+    lfs_files: HashSet<String>,
+    /// 🔗 Submodule paths
+    submodules: Vec<String>,
+}
+
+impl RepositoryObjects {
+    /// 🔍 Scan the index of a repository (works with sparse checkouts)
+    pub fn scan(repository: &git2::Repository) -> Result<Self> {
+        let index = repository.index().context("Failed to read git index")?;
+        let mut objects = Self::default();
+
+        for entry in index.iter() {
+            let Ok(path) = String::from_utf8(entry.path.clone()) else {
+                continue;
+            };
+
+            if entry.mode == GITLINK_MODE {
+                objects.submodules.push(path);
+                continue;
+            }
+
+            if path == ".gitattributes" || path.ends_with("/.gitattributes") {
+                let blob = repository
+                    .find_blob(entry.id)
+                    .with_context(|| format!("Failed to read {}", path))?;
+                let base = path
+                    .strip_suffix(".gitattributes")
+                    .unwrap_or_default()
+                    .trim_end_matches('/');
+                objects.add_attributes(base, &String::from_utf8_lossy(blob.content()));
+                continue;
+            }
+
+            // 📦 Pointer files are tiny, so only peek at small blobs
+            if entry.file_size <= MAX_LFS_POINTER_SIZE {
+                if let Ok(blob) = repository.find_blob(entry.id) {
+                    if is_lfs_pointer(blob.content()) {
+                        objects.lfs_files.insert(path);
+                    }
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// 📝 Record the `filter=lfs` patterns from one .gitattributes file
+    fn add_attributes(&mut self, base: &str, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            if parts.any(|attribute| attribute == "filter=lfs") {
+                self.lfs_patterns.push(LfsPattern {
+                    base: base.to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+        }
+    }
+
+    /// 🧱 Why a path can't be edited (None = ordinary file)
+    pub fn classify(&self, path: &str) -> Option<UnsupportedObject> {
+        let in_submodule = self.submodules.iter().any(|submodule| {
+            path == submodule
+                || path
+                    .strip_prefix(submodule.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        });
+        if in_submodule {
+            return Some(UnsupportedObject::Submodule);
+        }
+
+        let lfs_tracked = self.lfs_files.contains(path)
+            || self
+                .lfs_patterns
+                .iter()
+                .any(|pattern| attribute_matches(pattern, path));
+        lfs_tracked.then_some(UnsupportedObject::LfsTracked)
+    }
+
+    /// 🧹 Drop paths that are useless as LLM context
+    pub fn filter_context<'a, I>(&self, files: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        files
+            .into_iter()
+            .filter(|file| self.classify(file).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// 🚫 Fail with a clear message if any path is an unsupported object
+    pub fn ensure_supported<'a, I>(&self, paths: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let problems: Vec<String> = paths
+            .into_iter()
+            .filter_map(|path| {
+                self.classify(path)
+                    .map(|kind| format!("'{}' is {}", path, kind))
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "Requested change touches paths Feedbacker cannot edit: {}",
+                problems.join("; ")
+            )
+        }
+    }
+}
+
+/// 📦 Whether blob content is a Git LFS pointer
+pub fn is_lfs_pointer(content: &[u8]) -> bool {
+    content.starts_with(LFS_POINTER_PREFIX.as_bytes())
+}
+
+/// 🔍 gitattributes-style matching: patterns without a slash match the file name
+/// anywhere below the attributes file; patterns with a slash match the relative path
+fn attribute_matches(pattern: &LfsPattern, path: &str) -> bool {
+    let relative = if pattern.base.is_empty() {
+        path
+    } else {
+        match path
+            .strip_prefix(pattern.base.as_str())
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            Some(rest) => rest,
+            None => return false,
+        }
+    };
+
+    let glob = pattern.pattern.trim_start_matches('/');
+    if pattern.pattern.trim_end_matches('/').contains('/') {
+        wildcard_match(glob.as_bytes(), relative.as_bytes())
+    } else {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        wildcard_match(glob.as_bytes(), name.as_bytes())
+    }
+}
+
+/// ✳️ Minimal glob: `*` and `?` stop at '/', `**` crosses directories
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let rest = pattern[2..].strip_prefix(b"/").unwrap_or(&pattern[2..]);
+            (0..=text.len()).any(|i| wildcard_match(rest, &text[i..]))
+        }
+        Some(b'*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if wildcard_match(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&b'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some(b'?') => {
+            text.first().is_some_and(|c| *c != b'/') && wildcard_match(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && wildcard_match(&pattern[1..], &text[1..]),
+    }
+}
+
+// 🧪 Tests - LFS pointers and submodules must never be rewritten!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objects() -> RepositoryObjects {
+        let mut objects = RepositoryObjects::default();
+        objects.add_attributes("", "*.psd filter=lfs diff=lfs merge=lfs -text\n# comment\n");
+        objects.add_attributes("assets", "videos/** filter=lfs\n*.md text\n");
+        objects.lfs_files.insert("legacy/blob.bin".to_string());
+        objects.submodules.push("vendor/lib".to_string());
+        objects
+    }
+
+    #[test]
+    fn test_classify_special_paths() {
+        let objects = objects();
+        assert_eq!(
+            objects.classify("design/logo.psd"),
+            Some(UnsupportedObject::LfsTracked)
+        );
+        assert_eq!(
+            objects.classify("assets/videos/intro/demo.mp4"),
+            Some(UnsupportedObject::LfsTracked)
+        );
+        assert_eq!(
+            objects.classify("legacy/blob.bin"),
+            Some(UnsupportedObject::LfsTracked)
+        );
+        assert_eq!(
+            objects.classify("vendor/lib/src/main.rs"),
+            Some(UnsupportedObject::Submodule)
+        );
+        assert_eq!(objects.classify("vendor/library.rs"), None);
+        assert_eq!(objects.classify("assets/README.md"), None);
+        assert_eq!(objects.classify("videos/demo.mp4"), None);
+        println!("✅ Special path classification test passed!");
+    }
+
+    #[test]
+    fn test_ensure_supported_reports_every_problem() {
+        let objects = objects();
+        assert!(objects.ensure_supported(["src/main.rs"]).is_ok());
+
+        let error = objects
+            .ensure_supported(["src/main.rs", "design/logo.psd", "vendor/lib"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'design/logo.psd' is tracked by Git LFS"));
+        assert!(error.contains("'vendor/lib' is inside a submodule"));
+        println!("✅ Unsupported change error test passed!");
+    }
+
+    #[test]
+    fn test_lfs_pointer_detection() {
+        let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 12\n";
+        assert!(is_lfs_pointer(pointer));
+        assert!(!is_lfs_pointer(b"fn main() {}"));
+        assert!(wildcard_match(b"*.rs", b"main.rs"));
+        assert!(!wildcard_match(b"*.rs", b"src/main.rs"));
+        println!("✅ LFS pointer detection test passed!");
+    }
+}
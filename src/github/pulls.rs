@@ -0,0 +1,607 @@
+// 🔀 Open Pull Requests - Reading Them and Rewriting Their Branches! 🔀
+// Reads what GitHub knows about open pull requests (whether they can be merged,
+// whether branch protection wants them up to date), and rewrites a PR branch
+// without a clone: trees are compared by blob SHA through the Git Data API, new
+// trees are built on top of a base commit, and the branch ref is moved to the
+// new commit. Only files whose content actually changes are ever downloaded, the
+// versions of one file in a single GraphQL request. Review threads are read
+// through GraphQL too, so a rewrite doesn't bury an open review
+// Created with love by Aye & Hue - Fresh branches, no checkout required! ✨
+
+use anyhow::{Context, Result};
+use axum::http::{Method, StatusCode};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::graphql::{
+    self, BlobNode, FileVersionsQuery, FileVersionsVariables, ReviewThreadsQuery,
+    ReviewThreadsVariables,
+};
+use super::rate_limit::Urgency;
+use super::GitHubClient;
+
+/// 📏 Open pull requests and PR commits read per request (GitHub's maximum)
+const PER_PAGE: u32 = 100;
+
+/// 🔀 A pull request as GitHub reports it
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestInfo {
+    pub number: u64,
+    pub html_url: String,
+    /// 🚦 "open" or "closed"
+    pub state: String,
+    #[serde(default)]
+    pub merged: bool,
+    /// ✅ Whether it merges cleanly (None while GitHub is still working it out,
+    /// and always in PR listings)
+    pub mergeable: Option<bool>,
+    /// 🛡️ "clean", "behind" (branch protection wants it up to date), "dirty"
+    /// (conflicts), "blocked", "unstable", "draft" or "unknown"
+    pub mergeable_state: Option<String>,
+    pub head: BranchRef,
+    pub base: BranchRef,
+}
+
+/// 🌿 One end of a pull request
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchRef {
+    /// 🏷️ Branch name
+    #[serde(rename = "ref")]
+    pub name: String,
+    pub sha: String,
+    /// 📦 Repository the branch is in (None when a fork was deleted)
+    pub repo: Option<BranchRepository>,
+}
+
+/// 📦 Repository of a PR branch
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchRepository {
+    pub full_name: String,
+}
+
+/// 📜 One commit of a pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequestCommit {
+    /// 👤 GitHub user who authored it (None when the email matches no account)
+    pub author: Option<String>,
+    pub message: String,
+}
+
+/// 📄 A file in a tree: its mode ("100644", "100755", "120000") and blob SHA
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TreeEntry {
+    pub mode: String,
+    pub sha: String,
+}
+
+/// 🌳 Every file of a commit, by path
+pub type BlobTree = BTreeMap<String, TreeEntry>;
+
+/// 📄 Content of a blob
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobContent {
+    /// 📝 None when it isn't text, or is too large for GitHub to return whole
+    pub text: Option<String>,
+    /// 📏 Size in bytes
+    pub size: u64,
+}
+
+/// 📄 The versions of a file a rebase merges: where the PR and its base branch
+/// started from (None when the file didn't exist yet) and each side now
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeVersions {
+    pub original: Option<BlobContent>,
+    pub ours: BlobContent,
+    pub theirs: BlobContent,
+}
+
+/// 💬 A review thread of a pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewThread {
+    pub path: String,
+    /// 🔢 Line it's on (None when that line is gone)
+    pub line: Option<u64>,
+    pub resolved: bool,
+    /// 🕰️ Whether later commits changed the lines it's about
+    pub outdated: bool,
+    /// 👤 Who started it ("ghost" for deleted accounts)
+    pub author: String,
+    /// 📝 Its first comment
+    pub body: String,
+}
+
+/// ✏️ One path changed in a new tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeChange {
+    /// 📄 An existing blob, as is
+    Blob { path: String, entry: TreeEntry },
+    /// 📝 New text content
+    Text {
+        path: String,
+        mode: String,
+        content: String,
+    },
+    /// 🗑️ The path is removed
+    Removed { path: String },
+}
+
+impl TreeChange {
+    /// 📁 Path the change is for
+    pub fn path(&self) -> &str {
+        match self {
+            TreeChange::Blob { path, .. }
+            | TreeChange::Text { path, .. }
+            | TreeChange::Removed { path } => path,
+        }
+    }
+
+    /// 📦 The change as an entry of a "create a tree" request
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            TreeChange::Blob { path, entry } => serde_json::json!({
+                "path": path, "mode": entry.mode, "type": "blob", "sha": entry.sha,
+            }),
+            TreeChange::Text {
+                path,
+                mode,
+                content,
+            } => serde_json::json!({
+                "path": path, "mode": mode, "type": "blob", "content": content,
+            }),
+            TreeChange::Removed { path } => serde_json::json!({
+                "path": path, "mode": "100644", "type": "blob", "sha": null,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitListItem {
+    author: Option<super::UserLogin>,
+    commit: CommitMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitMessage {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitRef {
+    object: ShaRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShaRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Comparison {
+    merge_base_commit: ShaRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommit {
+    tree: ShaRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitTree {
+    tree: Vec<GitTreeItem>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitTreeItem {
+    path: String,
+    mode: String,
+    #[serde(rename = "type")]
+    kind: String,
+    sha: String,
+}
+
+impl GitHubClient {
+    /// 📋 Open pull requests of a repository (the first hundred, oldest first)
+    /// Listings don't say whether a PR can be merged, see `get_pull_request`
+    pub async fn list_open_pull_requests(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestInfo>> {
+        self.send(
+            Method::GET,
+            &format!("/repos/{}/{}/pulls", owner, repo),
+            Some(&[
+                ("state", "open".to_string()),
+                ("sort", "created".to_string()),
+                ("direction", "asc".to_string()),
+                ("per_page", PER_PAGE.to_string()),
+            ]),
+            None,
+            Urgency::Deferrable,
+        )
+        .await
+        .with_context(|| format!("Failed to list open pull requests of {}/{}", owner, repo))
+    }
+
+    /// 🔍 One pull request, with its mergeability (asking also makes GitHub
+    /// start working it out when it is still unknown)
+    pub async fn get_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<PullRequestInfo> {
+        self.send(
+            Method::GET,
+            &format!("/repos/{}/{}/pulls/{}", owner, repo, number),
+            None::<&()>,
+            None,
+            Urgency::Deferrable,
+        )
+        .await
+        .with_context(|| format!("Failed to fetch PR #{} of {}/{}", number, owner, repo))
+    }
+
+    /// 🚫 Close a pull request without merging it
+    pub async fn close_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<()> {
+        let _: serde_json::Value = self
+            .send(
+                Method::PATCH,
+                &format!("/repos/{}/{}/pulls/{}", owner, repo, number),
+                None::<&()>,
+                Some(&serde_json::json!({ "state": "closed" })),
+                Urgency::Urgent,
+            )
+            .await
+            .with_context(|| format!("Failed to close PR #{} of {}/{}", number, owner, repo))?;
+
+        Ok(())
+    }
+
+    /// 📜 Commits of a pull request, oldest first
+    pub async fn pull_request_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<PullRequestCommit>> {
+        let commits: Vec<CommitListItem> = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/pulls/{}/commits", owner, repo, number),
+                Some(&[("per_page", PER_PAGE)]),
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to list commits of PR #{}", number))?;
+
+        Ok(commits
+            .into_iter()
+            .map(|item| PullRequestCommit {
+                author: item.author.map(|author| author.login),
+                message: item.commit.message,
+            })
+            .collect())
+    }
+
+    /// 📜 Messages of the latest commits on the default branch, newest first
+    pub async fn recent_commit_messages(
+        &self,
+        owner: &str,
+        repo: &str,
+        limit: u32,
+    ) -> Result<Vec<String>> {
+        let commits: Vec<CommitListItem> = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/commits", owner, repo),
+                Some(&[("per_page", limit.min(PER_PAGE))]),
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to list commits of {}/{}", owner, repo))?;
+
+        Ok(commits
+            .into_iter()
+            .map(|item| item.commit.message)
+            .collect())
+    }
+
+    /// 🌿 The commit a branch points at
+    pub async fn branch_head(&self, owner: &str, repo: &str, branch: &str) -> Result<String> {
+        let git_ref: GitRef = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/git/ref/heads/{}", owner, repo, branch),
+                None::<&()>,
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to read branch {} of {}/{}", branch, owner, repo))?;
+        Ok(git_ref.object.sha)
+    }
+
+    /// 🔗 The last commit two commits have in common
+    pub async fn merge_base(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> Result<String> {
+        let comparison: Comparison = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/compare/{}...{}", owner, repo, base, head),
+                Some(&[("per_page", 1)]),
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to compare {}...{}", base, head))?;
+        Ok(comparison.merge_base_commit.sha)
+    }
+
+    /// 🌳 Every file of a commit (fails for trees too large to list in one response)
+    pub async fn blob_tree(&self, owner: &str, repo: &str, commit: &str) -> Result<BlobTree> {
+        let tree: GitTree = self
+            .send(
+                Method::GET,
+                &format!("/repos/{}/{}/git/trees/{}", owner, repo, commit),
+                Some(&[("recursive", 1)]),
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to read the tree of {}", commit))?;
+        if tree.truncated {
+            anyhow::bail!(
+                "The tree of {} is too large to read through the API",
+                commit
+            );
+        }
+
+        Ok(tree
+            .tree
+            .into_iter()
+            .filter(|item| item.kind == "blob")
+            .map(|item| {
+                (
+                    item.path,
+                    TreeEntry {
+                        mode: item.mode,
+                        sha: item.sha,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 📄 The three versions of a file a rebase merges, by blob SHA, read in
+    /// one GraphQL request
+    pub async fn merge_versions(
+        &self,
+        owner: &str,
+        repo: &str,
+        original: Option<&str>,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<MergeVersions> {
+        let variables = FileVersionsVariables {
+            owner: owner.to_string(),
+            name: repo.to_string(),
+            original: original.map(str::to_string),
+            with_original: original.is_some(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+        };
+        let versions = self
+            .query::<FileVersionsQuery>(&variables, Urgency::Deferrable)
+            .await
+            .with_context(|| format!("Failed to read blobs {} and {}", ours, theirs))?
+            .repository
+            .with_context(|| format!("Repository {}/{} not found", owner, repo))?;
+
+        let found = |blob: Option<BlobNode>, sha: &str| {
+            blob.map(BlobContent::from)
+                .with_context(|| format!("Blob {} not found", sha))
+        };
+        Ok(MergeVersions {
+            original: match original {
+                Some(sha) => Some(found(versions.original, sha)?),
+                None => None,
+            },
+            ours: found(versions.ours, ours)?,
+            theirs: found(versions.theirs, theirs)?,
+        })
+    }
+
+    /// 💬 Unresolved review threads of a pull request, up to `limit`
+    /// Read through GraphQL a hundred threads at a time
+    pub async fn unresolved_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        limit: usize,
+    ) -> Result<Vec<ReviewThread>> {
+        let variables = ReviewThreadsVariables {
+            owner: owner.to_string(),
+            name: repo.to_string(),
+            number,
+            first: graphql::PAGE_SIZE,
+            after: None,
+        };
+        Ok(self
+            .query_all::<ReviewThreadsQuery, _>(variables, limit, Urgency::Deferrable, |thread| {
+                !thread.is_resolved()
+            })
+            .await
+            .with_context(|| format!("Failed to read the review threads of #{}", number))?
+            .into_iter()
+            .map(ReviewThread::from)
+            .collect())
+    }
+
+    /// 🧱 Commit `changes` on top of `parent` and return the new commit's SHA
+    pub async fn commit_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        parent: &str,
+        changes: &[TreeChange],
+        message: &str,
+    ) -> Result<String> {
+        let route = format!("/repos/{}/{}/git", owner, repo);
+        let parent_commit: GitCommit = self
+            .send(
+                Method::GET,
+                &format!("{}/commits/{}", route, parent),
+                None::<&()>,
+                None,
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to read commit {}", parent))?;
+
+        let tree: ShaRef = self
+            .send(
+                Method::POST,
+                &format!("{}/trees", route),
+                None::<&()>,
+                Some(&serde_json::json!({
+                    "base_tree": parent_commit.tree.sha,
+                    "tree": changes.iter().map(TreeChange::to_json).collect::<Vec<_>>(),
+                })),
+                Urgency::Deferrable,
+            )
+            .await
+            .context("Failed to create a tree")?;
+
+        let commit: ShaRef = self
+            .send(
+                Method::POST,
+                &format!("{}/commits", route),
+                None::<&()>,
+                Some(&serde_json::json!({
+                    "message": message,
+                    "tree": tree.sha,
+                    "parents": [parent],
+                })),
+                Urgency::Deferrable,
+            )
+            .await
+            .context("Failed to create a commit")?;
+        Ok(commit.sha)
+    }
+
+    /// 🌱 Create a branch at a commit, or reset it there when it already exists
+    /// (a retried run starts its branch over instead of failing on the old one)
+    pub async fn start_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        sha: &str,
+    ) -> Result<()> {
+        let created: Result<serde_json::Value> = self
+            .send(
+                Method::POST,
+                &format!("/repos/{}/{}/git/refs", owner, repo),
+                None::<&()>,
+                Some(&serde_json::json!({ "ref": format!("refs/heads/{}", branch), "sha": sha })),
+                Urgency::Urgent,
+            )
+            .await;
+        match created {
+            Ok(_) => Ok(()),
+            Err(e) if super::error_status(&e) == Some(StatusCode::UNPROCESSABLE_ENTITY) => {
+                self.force_update_branch(owner, repo, branch, sha).await
+            }
+            Err(e) => Err(e).with_context(|| {
+                format!("Failed to create branch {} in {}/{}", branch, owner, repo)
+            }),
+        }
+    }
+
+    /// 🔁 Point a branch at a commit, even when that rewrites its history
+    pub async fn force_update_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        sha: &str,
+    ) -> Result<()> {
+        let _: serde_json::Value = self
+            .send(
+                Method::PATCH,
+                &format!("/repos/{}/{}/git/refs/heads/{}", owner, repo, branch),
+                None::<&()>,
+                Some(&serde_json::json!({ "sha": sha, "force": true })),
+                Urgency::Deferrable,
+            )
+            .await
+            .with_context(|| format!("Failed to update branch {} of {}/{}", branch, owner, repo))?;
+        Ok(())
+    }
+}
+
+// 🧪 Tests - Branches rewritten, nothing lost!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_change_json() {
+        let entry = TreeEntry {
+            mode: "100755".to_string(),
+            sha: "abc123".to_string(),
+        };
+        let kept = TreeChange::Blob {
+            path: "bin/run".to_string(),
+            entry,
+        };
+        assert_eq!(kept.path(), "bin/run");
+        assert_eq!(kept.to_json()["mode"], "100755");
+        assert_eq!(kept.to_json()["sha"], "abc123");
+
+        let removed = TreeChange::Removed {
+            path: "old.rs".to_string(),
+        };
+        assert!(removed.to_json()["sha"].is_null());
+
+        let written = TreeChange::Text {
+            path: "src/lib.rs".to_string(),
+            mode: "100644".to_string(),
+            content: "pub fn lib() {}\n".to_string(),
+        };
+        assert_eq!(written.to_json()["content"], "pub fn lib() {}\n");
+        assert!(written.to_json().get("sha").is_none());
+        println!("✅ Tree change request test passed!");
+    }
+
+    #[test]
+    fn test_pull_request_info() {
+        let pr: PullRequestInfo = serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "html_url": "https://github.com/aye-is/feedbacker/pull/7",
+            "state": "open",
+            "mergeable": null,
+            "mergeable_state": "unknown",
+            "head": { "ref": "feedbacker/docs-1234", "sha": "bbb", "repo": { "full_name": "aye-is/feedbacker" } },
+            "base": { "ref": "main", "sha": "aaa", "repo": null }
+        }))
+        .unwrap();
+        assert!(!pr.merged);
+        assert_eq!(pr.mergeable, None);
+        assert_eq!(pr.head.name, "feedbacker/docs-1234");
+        assert!(pr.base.repo.is_none());
+        println!("✅ Pull request parsing test passed!");
+    }
+}
@@ -0,0 +1,292 @@
+// 🚦 GitHub Rate Limits - Knowing When to Slow Down! 🚦
+// Every REST response carries the quota left in its window (x-ratelimit-*
+// headers); the latest one per resource is kept here, exported at /metrics and
+// shown by the health check. Calls say how urgent they are: urgent ones (the
+// steps of an open PR run) only wait when the quota is gone, while deferrable
+// ones (scheduled scans, issue imports) stop at GITHUB_RATE_LIMIT_RESERVE and
+// fail with `RateLimited`, so their job is retried after the reset. Secondary
+// rate limits (403/429 with Retry-After) are waited out and the call retried
+// Created with love by Aye & Hue - Patience is a feature! ✨
+
+use axum::http::{HeaderMap, StatusCode};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::metrics;
+
+/// ⏳ Wait after a secondary rate limit that didn't say how long
+const SECONDARY_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// 🗂️ Latest quota per resource (core, search, graphql ...)
+static LIMITS: Mutex<BTreeMap<String, RateLimit>> = Mutex::new(BTreeMap::new());
+
+/// 🎯 How much a GitHub call may be put off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    /// 🔥 Part of a run in progress: waits only when the quota is gone
+    Urgent,
+    /// 🐢 Background work: held back near exhaustion and tried again later
+    Deferrable,
+}
+
+/// 📊 The quota of one resource, as reported by GitHub
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimit {
+    /// 🏷️ Resource the quota is for
+    pub resource: String,
+    /// 🎯 Requests allowed per window
+    pub limit: u64,
+    /// 🔋 Requests left in this window
+    pub remaining: u64,
+    /// ⏰ When the window resets
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimit {
+    /// 📥 The quota reported by a response (None without rate limit headers)
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| -> Option<u64> { header(headers, name)?.parse().ok() };
+        Some(Self {
+            resource: header(headers, "x-ratelimit-resource")
+                .unwrap_or("core")
+                .to_string(),
+            limit: number("x-ratelimit-limit")?,
+            remaining: number("x-ratelimit-remaining")?,
+            reset_at: Utc
+                .timestamp_opt(number("x-ratelimit-reset")? as i64, 0)
+                .single()?,
+        })
+    }
+}
+
+/// ⏸️ A GitHub call put off because the rate limit is (nearly) exhausted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimited {
+    /// 🔧 What was being done
+    pub operation: String,
+    /// ⏳ How long until the quota is back
+    pub wait: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GitHub rate limit reached, {} put off for {}s",
+            self.operation,
+            self.wait.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// 📝 Remember the quota a response reported
+pub fn record(headers: &HeaderMap) {
+    let Some(limit) = RateLimit::from_headers(headers) else {
+        return;
+    };
+    metrics::observe_github_rate_limit(&limit.resource, limit.limit, limit.remaining);
+    limits().insert(limit.resource.clone(), limit);
+}
+
+/// 🔍 The latest quota of a resource (None until GitHub has been called)
+pub fn current(resource: &str) -> Option<RateLimit> {
+    limits().get(resource).cloned()
+}
+
+/// ⏳ How long a call must wait before it is sent (None means go ahead)
+pub fn wait_before(
+    urgency: Urgency,
+    limit: Option<&RateLimit>,
+    reserve: u64,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let limit = limit?;
+    let floor = match urgency {
+        Urgency::Urgent => 0,
+        Urgency::Deferrable => reserve,
+    };
+    if limit.remaining > floor || limit.reset_at <= now {
+        return None;
+    }
+    Some(until(limit.reset_at, now))
+}
+
+/// 🔁 How long to wait before retrying a rate limited response (None when it wasn't one)
+pub fn retry_after(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    if let Some(seconds) = header(headers, "retry-after").and_then(|value| value.parse().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if let Some(limit) = RateLimit::from_headers(headers) {
+        if limit.remaining == 0 {
+            return Some(until(limit.reset_at, now));
+        }
+    }
+    // 🕵️ Secondary limits don't always send Retry-After; a 403 is only one when it says so
+    let secondary =
+        status == StatusCode::TOO_MANY_REQUESTS || body.to_ascii_lowercase().contains("rate limit");
+    secondary.then_some(SECONDARY_LIMIT_WAIT)
+}
+
+/// ⏰ Time until a reset, with a second to spare for clock skew
+fn until(reset_at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (reset_at - now)
+        .to_std()
+        .unwrap_or_default()
+        .saturating_add(Duration::from_secs(1))
+}
+
+/// 🔤 A header value as text
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// 🔒 The quota map (a poisoned lock only means a panic mid-insert)
+fn limits() -> std::sync::MutexGuard<'static, BTreeMap<String, RateLimit>> {
+    LIMITS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// 🧪 Tests - Slow and steady wins the race!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap()
+    }
+
+    fn limit(remaining: u64, reset_in: i64) -> RateLimit {
+        RateLimit {
+            resource: "core".to_string(),
+            limit: 5000,
+            remaining,
+            reset_at: now() + chrono::Duration::seconds(reset_in),
+        }
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let parsed = RateLimit::from_headers(&headers(&[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "42"),
+            ("x-ratelimit-reset", "1700000600"),
+            ("x-ratelimit-resource", "search"),
+        ]))
+        .unwrap();
+        assert_eq!(parsed.resource, "search");
+        assert_eq!(parsed.remaining, 42);
+        assert_eq!(parsed.reset_at, now() + chrono::Duration::seconds(600));
+
+        let core = RateLimit::from_headers(&headers(&[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "42"),
+            ("x-ratelimit-reset", "1700000600"),
+        ]))
+        .unwrap();
+        assert_eq!(core.resource, "core");
+        assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+        println!("✅ Rate limit header test passed!");
+    }
+
+    #[test]
+    fn test_wait_before() {
+        // 🔥 Urgent calls spend the reserve, deferrable ones keep it
+        assert_eq!(
+            wait_before(Urgency::Urgent, Some(&limit(150, 60)), 200, now()),
+            None
+        );
+        assert_eq!(
+            wait_before(Urgency::Deferrable, Some(&limit(150, 60)), 200, now()),
+            Some(Duration::from_secs(61))
+        );
+        assert_eq!(
+            wait_before(Urgency::Urgent, Some(&limit(0, 60)), 200, now()),
+            Some(Duration::from_secs(61))
+        );
+        assert_eq!(
+            wait_before(Urgency::Deferrable, Some(&limit(201, 60)), 200, now()),
+            None
+        );
+        // ⏰ A window that already reset doesn't hold anything back
+        assert_eq!(
+            wait_before(Urgency::Urgent, Some(&limit(0, -5)), 200, now()),
+            None
+        );
+        assert_eq!(wait_before(Urgency::Deferrable, None, 200, now()), None);
+        println!("✅ Rate limit wait test passed!");
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let forbidden = StatusCode::FORBIDDEN;
+        assert_eq!(
+            retry_after(forbidden, &headers(&[("retry-after", "30")]), "", now()),
+            Some(Duration::from_secs(30))
+        );
+        let exhausted = headers(&[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1700000120"),
+        ]);
+        assert_eq!(
+            retry_after(forbidden, &exhausted, "", now()),
+            Some(Duration::from_secs(121))
+        );
+        assert_eq!(
+            retry_after(
+                forbidden,
+                &HeaderMap::new(),
+                "You have exceeded a secondary rate limit",
+                now()
+            ),
+            Some(SECONDARY_LIMIT_WAIT)
+        );
+        assert_eq!(
+            retry_after(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), "", now()),
+            Some(SECONDARY_LIMIT_WAIT)
+        );
+        // 🚫 A plain permission error is not retried
+        assert_eq!(
+            retry_after(
+                forbidden,
+                &HeaderMap::new(),
+                "Resource not accessible",
+                now()
+            ),
+            None
+        );
+        assert_eq!(
+            retry_after(
+                StatusCode::NOT_FOUND,
+                &headers(&[("retry-after", "30")]),
+                "",
+                now()
+            ),
+            None
+        );
+        println!("✅ Rate limit retry test passed!");
+    }
+}
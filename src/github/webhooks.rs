@@ -1,2 +1,498 @@
-// 🪝 Webhook Handlers - GitHub Event Processing! 🪝
-// TODO: Implement webhook payload processing
+// 🪝 Webhook Events - GitHub Event Processing! 🪝
+// Typed shapes of the webhook deliveries Feedbacker reads. GitHub names the
+// event in the `X-GitHub-Event` header, not in the body, so `WebhookEvent::parse`
+// pairs the two and lets serde pick the variant. Only the fields we use are
+// declared; everything else in a delivery is ignored. Events we don't handle
+// parse as `Unsupported`, so handlers can match exhaustively and still answer 200
+// Created with love by Aye & Hue - Every delivery has a shape! ✨
+
+use anyhow::{Context, Result};
+use axum::http::HeaderMap;
+use serde::Deserialize;
+
+/// 🏷️ Header naming the event of a delivery
+pub const EVENT_HEADER: &str = "x-github-event";
+
+/// 🪝 A webhook delivery, by event
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "event", content = "payload", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// 🎫 An issue was opened, closed, labeled, assigned ...
+    Issues(IssuesEvent),
+    /// 💬 A comment on an issue or pull request
+    IssueComment(IssueCommentEvent),
+    /// 🔀 A pull request changed
+    PullRequest(PullRequestEvent),
+    /// ✅ A check suite finished (or was requested)
+    CheckSuite(CheckSuiteEvent),
+    /// 📤 Commits or a tag were pushed
+    Push(PushEvent),
+    /// 🔌 The GitHub App was installed, uninstalled, suspended ...
+    Installation(InstallationEvent),
+    /// 📋 Repositories were added to or removed from an installation
+    InstallationRepositories(InstallationRepositoriesEvent),
+    /// 🏓 Sent once when a webhook is created
+    Ping(PingEvent),
+    /// 🤷 Any other event
+    #[serde(other)]
+    Unsupported,
+}
+
+impl WebhookEvent {
+    /// 📥 Read a delivery body as the event named by its `X-GitHub-Event` header
+    pub fn parse(event: &str, payload: serde_json::Value) -> Result<Self> {
+        // 🤷 `Unsupported` takes no payload, and only it parses without one
+        if let Ok(WebhookEvent::Unsupported) =
+            serde_json::from_value(serde_json::json!({ "event": event }))
+        {
+            return Ok(WebhookEvent::Unsupported);
+        }
+        serde_json::from_value(serde_json::json!({ "event": event, "payload": payload }))
+            .with_context(|| format!("Malformed {} webhook payload", event))
+    }
+
+    /// 🏷️ The event name a request's headers carry (empty when missing)
+    pub fn name(headers: &HeaderMap) -> &str {
+        headers
+            .get(EVENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    }
+
+    /// 📦 Repository the event is about (None for installation events)
+    pub fn repository(&self) -> Option<&Repository> {
+        match self {
+            WebhookEvent::Issues(event) => Some(&event.repository),
+            WebhookEvent::IssueComment(event) => Some(&event.repository),
+            WebhookEvent::PullRequest(event) => Some(&event.repository),
+            WebhookEvent::CheckSuite(event) => Some(&event.repository),
+            WebhookEvent::Push(event) => Some(&event.repository),
+            WebhookEvent::Ping(event) => event.repository.as_ref(),
+            WebhookEvent::Installation(_)
+            | WebhookEvent::InstallationRepositories(_)
+            | WebhookEvent::Unsupported => None,
+        }
+    }
+
+    /// 🎯 The payload's `action` (None for pushes and pings, which have none)
+    pub fn action(&self) -> Option<&str> {
+        match self {
+            WebhookEvent::Issues(event) => Some(&event.action),
+            WebhookEvent::IssueComment(event) => Some(&event.action),
+            WebhookEvent::PullRequest(event) => Some(&event.action),
+            WebhookEvent::CheckSuite(event) => Some(&event.action),
+            WebhookEvent::Installation(event) => Some(&event.action),
+            WebhookEvent::InstallationRepositories(event) => Some(&event.action),
+            WebhookEvent::Push(_) | WebhookEvent::Ping(_) | WebhookEvent::Unsupported => None,
+        }
+    }
+}
+
+/// 👤 A user or organization account
+#[derive(Debug, Clone, Deserialize)]
+pub struct Account {
+    #[serde(default)]
+    pub id: u64,
+    pub login: String,
+}
+
+/// 📦 The repository an event is about
+#[derive(Debug, Clone, Deserialize)]
+pub struct Repository {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    /// 🏷️ `owner/repo`
+    pub full_name: String,
+    pub owner: Option<Account>,
+    pub default_branch: Option<String>,
+}
+
+impl Repository {
+    /// 👤 Login of the repository's owner (the part of `full_name` before the slash)
+    pub fn owner_login(&self) -> &str {
+        match &self.owner {
+            Some(owner) => &owner.login,
+            None => self.full_name.split('/').next().unwrap_or_default(),
+        }
+    }
+}
+
+/// 🏷️ A repository named in an installation event
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositoryName {
+    /// 🏷️ `owner/repo`
+    pub full_name: String,
+}
+
+/// 🏷️ A label on an issue
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    pub name: String,
+    #[serde(default)]
+    pub color: String,
+}
+
+/// 🎫 An issue as webhooks describe it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    pub id: u64,
+    pub number: u32,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    pub html_url: String,
+    pub user: Account,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub assignees: Vec<Account>,
+}
+
+/// 🎫 `issues`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssuesEvent {
+    pub action: String,
+    pub issue: Issue,
+    pub repository: Repository,
+    pub sender: Account,
+}
+
+/// 💬 A comment on an issue or pull request
+#[derive(Debug, Clone, Deserialize)]
+pub struct Comment {
+    pub id: u64,
+    pub body: Option<String>,
+    pub user: Account,
+    #[serde(default)]
+    pub html_url: String,
+}
+
+/// 💬 `issue_comment`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueCommentEvent {
+    pub action: String,
+    pub issue: Issue,
+    pub comment: Comment,
+    pub repository: Repository,
+    pub sender: Account,
+}
+
+/// 🔀 A pull request as webhooks describe it
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    #[serde(default)]
+    pub state: String,
+    /// 🔀 Whether a closed pull request was merged
+    #[serde(default)]
+    pub merged: bool,
+}
+
+/// 🔀 `pull_request`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestEvent {
+    pub action: String,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+}
+
+/// 🔗 A pull request a check suite ran for
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestRef {
+    pub number: u64,
+}
+
+/// ✅ A check suite as webhooks describe it
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckSuite {
+    /// 🏁 success, failure, neutral, cancelled, timed_out, action_required ... (None while running)
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub pull_requests: Vec<PullRequestRef>,
+}
+
+/// ✅ `check_suite`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckSuiteEvent {
+    pub action: String,
+    pub check_suite: CheckSuite,
+    pub repository: Repository,
+}
+
+/// 📜 One commit of a push, with the paths it changed
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PushCommit {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+/// 📤 `push`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushEvent {
+    /// 🌿 Pushed ref (`refs/heads/...` or `refs/tags/...`)
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// 🔖 SHAs before and after the push
+    pub before: String,
+    pub after: String,
+    /// 📜 Pushed commits (GitHub lists at most 2048)
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+    /// 💥 Force push (the commits don't say what changed since `before`)
+    #[serde(default)]
+    pub forced: bool,
+    /// 🗑️ The ref was deleted
+    #[serde(default)]
+    pub deleted: bool,
+    pub repository: Repository,
+}
+
+/// 🔌 A GitHub App installation as webhooks describe it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Installation {
+    pub id: i64,
+    pub account: Account,
+    /// 📋 `all` or `selected`
+    pub repository_selection: Option<String>,
+}
+
+/// 🔌 `installation`
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstallationEvent {
+    /// 🎬 created, deleted, suspend, unsuspend, new_permissions_accepted
+    pub action: String,
+    pub installation: Installation,
+    /// 📋 Repositories of a new installation
+    #[serde(default)]
+    pub repositories: Vec<RepositoryName>,
+}
+
+/// 📋 `installation_repositories`
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstallationRepositoriesEvent {
+    /// 🎬 added or removed
+    pub action: String,
+    pub installation: Installation,
+    /// 📋 `all` or `selected`, after the change
+    pub repository_selection: Option<String>,
+    #[serde(default)]
+    pub repositories_added: Vec<RepositoryName>,
+    #[serde(default)]
+    pub repositories_removed: Vec<RepositoryName>,
+}
+
+/// 🪝 The webhook a ping is about
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hook {
+    pub id: i64,
+    /// 📋 Events the webhook sends
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// 🏓 `ping`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PingEvent {
+    /// 🧘 A random bit of GitHub wisdom
+    #[serde(default)]
+    pub zen: String,
+    pub hook_id: Option<i64>,
+    pub hook: Option<Hook>,
+    /// 📦 Absent for organization and app webhooks
+    pub repository: Option<Repository>,
+}
+
+// 🧪 Tests - One shape per event!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn repository() -> serde_json::Value {
+        json!({
+            "id": 1,
+            "name": "feedbacker",
+            "full_name": "aye-is/feedbacker",
+            "owner": { "id": 2, "login": "aye-is" },
+            "default_branch": "main"
+        })
+    }
+
+    fn issue() -> serde_json::Value {
+        json!({
+            "id": 10,
+            "number": 7,
+            "title": "Crash on start",
+            "body": null,
+            "state": "open",
+            "html_url": "https://github.com/aye-is/feedbacker/issues/7",
+            "user": { "id": 3, "login": "hue" },
+            "labels": [{ "name": "bug", "color": "d73a4a" }]
+        })
+    }
+
+    #[test]
+    fn test_issues_events() {
+        let issues = json!({
+            "action": "opened",
+            "issue": issue(),
+            "repository": repository(),
+            "sender": { "id": 3, "login": "hue" }
+        });
+        match WebhookEvent::parse("issues", issues).unwrap() {
+            WebhookEvent::Issues(event) => {
+                assert_eq!(event.action, "opened");
+                assert_eq!(event.issue.number, 7);
+                assert_eq!(event.issue.labels[0].name, "bug");
+                assert!(event.issue.assignees.is_empty());
+                assert_eq!(event.repository.owner_login(), "aye-is");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let comment = json!({
+            "action": "created",
+            "issue": issue(),
+            "comment": { "id": 11, "body": "Same here", "user": { "id": 4, "login": "trish" } },
+            "repository": repository(),
+            "sender": { "id": 4, "login": "trish" }
+        });
+        match WebhookEvent::parse("issue_comment", comment).unwrap() {
+            WebhookEvent::IssueComment(event) => {
+                assert_eq!(event.comment.body.as_deref(), Some("Same here"));
+                assert_eq!(event.sender.login, "trish");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        println!("✅ Issue webhook events test passed!");
+    }
+
+    #[test]
+    fn test_pull_request_and_check_suite_events() {
+        let closed = json!({
+            "action": "closed",
+            "number": 12,
+            "pull_request": { "number": 12, "state": "closed", "merged": true },
+            "repository": repository()
+        });
+        match WebhookEvent::parse("pull_request", closed).unwrap() {
+            WebhookEvent::PullRequest(event) => {
+                assert_eq!(event.pull_request.number, 12);
+                assert!(event.pull_request.merged);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let suite = json!({
+            "action": "completed",
+            "check_suite": { "conclusion": "success", "pull_requests": [{ "number": 12 }] },
+            "repository": repository()
+        });
+        match WebhookEvent::parse("check_suite", suite).unwrap() {
+            WebhookEvent::CheckSuite(event) => {
+                assert_eq!(event.check_suite.conclusion.as_deref(), Some("success"));
+                assert_eq!(event.check_suite.pull_requests[0].number, 12);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        println!("✅ Pull request webhook events test passed!");
+    }
+
+    #[test]
+    fn test_push_event() {
+        let push = json!({
+            "ref": "refs/heads/main",
+            "before": "1111111",
+            "after": "2222222",
+            "commits": [{ "added": ["a.rs"], "modified": ["b.rs"] }],
+            "repository": repository()
+        });
+        match WebhookEvent::parse("push", push).unwrap() {
+            WebhookEvent::Push(event) => {
+                assert_eq!(event.git_ref, "refs/heads/main");
+                assert_eq!(event.commits[0].added, vec!["a.rs"]);
+                assert!(event.commits[0].removed.is_empty());
+                assert!(!event.forced);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        println!("✅ Push webhook event test passed!");
+    }
+
+    #[test]
+    fn test_installation_events() {
+        let created = json!({
+            "action": "created",
+            "installation": {
+                "id": 42,
+                "account": { "id": 2, "login": "aye-is" },
+                "repository_selection": "selected"
+            },
+            "repositories": [{ "full_name": "aye-is/feedbacker" }]
+        });
+        match WebhookEvent::parse("installation", created).unwrap() {
+            WebhookEvent::Installation(event) => {
+                assert_eq!(event.installation.id, 42);
+                assert_eq!(event.repositories[0].full_name, "aye-is/feedbacker");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let removed = json!({
+            "action": "removed",
+            "installation": { "id": 42, "account": { "login": "aye-is" } },
+            "repository_selection": "selected",
+            "repositories_added": [],
+            "repositories_removed": [{ "full_name": "aye-is/smart-tree" }]
+        });
+        let event = WebhookEvent::parse("installation_repositories", removed).unwrap();
+        assert!(event.repository().is_none());
+        assert_eq!(event.action(), Some("removed"));
+        match event {
+            WebhookEvent::InstallationRepositories(event) => {
+                assert_eq!(event.repositories_removed[0].full_name, "aye-is/smart-tree");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        println!("✅ Installation webhook events test passed!");
+    }
+
+    #[test]
+    fn test_ping_and_unsupported_events() {
+        let ping = json!({
+            "zen": "Keep it logically awesome.",
+            "hook_id": 99,
+            "hook": { "id": 99, "events": ["push", "pull_request"] },
+            "repository": repository()
+        });
+        let event = WebhookEvent::parse("ping", ping).unwrap();
+        assert_eq!(event.repository().unwrap().full_name, "aye-is/feedbacker");
+        assert_eq!(event.action(), None);
+        match event {
+            WebhookEvent::Ping(event) => {
+                assert_eq!(event.hook_id, Some(99));
+                assert_eq!(event.hook.unwrap().events.len(), 2);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // 🤷 Unknown events and missing headers are fine, malformed known ones aren't
+        let star = WebhookEvent::parse("star", json!({ "action": "created" })).unwrap();
+        assert!(matches!(star, WebhookEvent::Unsupported));
+        assert!(matches!(
+            WebhookEvent::parse("", json!({})).unwrap(),
+            WebhookEvent::Unsupported
+        ));
+        assert!(WebhookEvent::parse("push", json!({ "ref": 1 })).is_err());
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(WebhookEvent::name(&headers), "");
+        headers.insert(EVENT_HEADER, "ping".parse().unwrap());
+        assert_eq!(WebhookEvent::name(&headers), "ping");
+        println!("✅ Ping webhook event test passed!");
+    }
+}
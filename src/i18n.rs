@@ -0,0 +1,289 @@
+// 🌍 i18n - Feedbacker Speaks Your Language! 🌍
+// Messages for API responses, validation errors and the web UI live in Fluent
+// files under locales/ (one per language, compiled into the binary) instead of
+// string literals in handlers. Each request is handled in the locale picked from
+// its Accept-Language header, or from the signed-in user's preference (see
+// middleware::locale and middleware::auth); code running outside a request
+// (jobs, the CLI) speaks English. Missing translations fall back to English
+// Created with love by Aye & Hue - Hola, hello, and everything in between! ✨
+
+use std::{collections::HashMap, fmt, future::Future, str::FromStr};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentResource};
+use fluent_langneg::{negotiate_languages, parse_accepted_languages, NegotiationStrategy};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::{FluentArgs, FluentValue};
+
+/// 🗣️ A language Feedbacker has messages in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    /// 🇬🇧 English, the fallback for everything
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    /// 🇪🇸 Spanish
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Locale {
+    /// 📋 Every supported locale, fallback first
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    /// 🏷️ Language tag, as in Accept-Language and Content-Language
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+        }
+    }
+
+    /// 📄 This locale's Fluent messages
+    fn source(self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../locales/en/feedbacker.ftl"),
+            Locale::Spanish => include_str!("../locales/es/feedbacker.ftl"),
+        }
+    }
+
+    fn language_id(self) -> LanguageIdentifier {
+        self.code().parse().unwrap_or_default()
+    }
+
+    /// 🤝 Best supported locale for an Accept-Language header (English when
+    /// nothing matches)
+    pub fn negotiate(accept_language: &str) -> Locale {
+        let requested = parse_accepted_languages(accept_language);
+        let available = Locale::ALL.map(Locale::language_id);
+        let default = Locale::default().language_id();
+        negotiate_languages(
+            &requested,
+            &available,
+            Some(&default),
+            NegotiationStrategy::Lookup,
+        )
+        .first()
+        .and_then(|language| available.iter().position(|a| a == *language))
+        .map(|index| Locale::ALL[index])
+        .unwrap_or_default()
+    }
+
+    /// 🎯 Run a future (a request, usually) in this locale
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_LOCALE.scope(self, future).await
+    }
+
+    /// 🔍 Locale of the request being handled, English outside of one
+    pub fn current() -> Locale {
+        CURRENT_LOCALE
+            .try_with(|locale| *locale)
+            .unwrap_or_default()
+    }
+
+    /// 📝 A message in this locale; `id.attribute` picks an attribute
+    pub fn message(self, id: &str) -> String {
+        self.message_with(id, None)
+    }
+
+    /// 📝 A message in this locale, with its arguments filled in
+    pub fn message_with(self, id: &str, args: Option<&FluentArgs>) -> String {
+        [self, Locale::default()]
+            .into_iter()
+            .find_map(|locale| format_message(locale, id, args))
+            .unwrap_or_else(|| {
+                warn!("🌍 No message '{}' in any locale", id);
+                id.to_string()
+            })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    /// 🔍 A supported locale from a language tag; regions and scripts don't
+    /// matter ("es-MX" is Spanish)
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        let language = tag
+            .parse::<LanguageIdentifier>()
+            .map_err(|_| anyhow::anyhow!("Invalid language tag: {}", tag))?;
+        Locale::ALL
+            .into_iter()
+            .find(|locale| language.language.as_str() == locale.code())
+            .ok_or_else(|| anyhow::anyhow!("Unsupported locale: {}", tag))
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+lazy_static::lazy_static! {
+    /// 📚 Every locale's messages, parsed once
+    static ref BUNDLES: HashMap<Locale, FluentBundle<FluentResource>> = Locale::ALL
+        .into_iter()
+        .map(|locale| (locale, load_bundle(locale)))
+        .collect();
+}
+
+/// 📚 Parse a locale's messages (broken entries are logged and skipped)
+fn load_bundle(locale: Locale) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(locale.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            error!("❌ Broken messages in locale {}: {:?}", locale, errors);
+            resource
+        },
+    );
+    let mut bundle = FluentBundle::new_concurrent(vec![locale.language_id()]);
+    // 🚫 No Unicode isolation marks: messages end up in JSON and plain HTML
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("❌ Duplicate messages in locale {}: {:?}", locale, errors);
+    }
+    bundle
+}
+
+/// 📝 Format a message of one locale (None when it has no such message)
+fn format_message(locale: Locale, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = BUNDLES.get(&locale)?;
+    let (message_id, attribute) = match id.split_once('.') {
+        Some((message_id, attribute)) => (message_id, Some(attribute)),
+        None => (id, None),
+    };
+    let message = bundle.get_message(message_id)?;
+    let pattern = match attribute {
+        Some(attribute) => message.get_attribute(attribute)?.value(),
+        None => message.value()?,
+    };
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        warn!("🌍 Message '{}' ({}) had errors: {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// 📝 A message in the current locale
+pub fn t(id: &str) -> String {
+    Locale::current().message(id)
+}
+
+/// 📝 A message in the current locale, with its arguments filled in
+pub fn t_with<'a>(id: &str, args: impl IntoIterator<Item = (&'a str, FluentValue<'a>)>) -> String {
+    let args = FluentArgs::from_iter(args);
+    Locale::current().message_with(id, Some(&args))
+}
+
+// 🧪 Tests - Every message in every language!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 🏷️ Message ids defined in a locale's file
+    fn message_ids(locale: Locale) -> Vec<&'static str> {
+        locale
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_locales_have_the_same_messages() {
+        let english = message_ids(Locale::English);
+        assert!(english.len() > 50);
+        for locale in Locale::ALL {
+            let ids = message_ids(locale);
+            assert_eq!(ids, english, "{} has different messages", locale);
+            for id in ids {
+                assert!(
+                    format_message(locale, id, None).is_some(),
+                    "{} can't format {}",
+                    locale,
+                    id
+                );
+            }
+        }
+        println!("✅ Locale parity test passed!");
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(Locale::Spanish.message("error-not-found"), "No encontrado");
+        assert_eq!(Locale::English.message("page-login.title"), "Login");
+        assert_eq!(Locale::Spanish.message("page-login.title"), "Entrar");
+        assert_eq!(
+            Locale::Spanish.message("no-such-message"),
+            "no-such-message"
+        );
+
+        let mut args = FluentArgs::new();
+        args.set("limit_type", "user");
+        args.set("seconds", 1);
+        assert_eq!(
+            Locale::English.message_with("rate-limit-exceeded", Some(&args)),
+            "Rate limit exceeded for user. Try again in 1 second."
+        );
+        args.set("seconds", 30);
+        assert_eq!(
+            Locale::Spanish.message_with("rate-limit-exceeded", Some(&args)),
+            "Límite de solicitudes superado para user. Inténtalo de nuevo en 30 segundos."
+        );
+
+        let mut args = FluentArgs::new();
+        args.set("page", FluentValue::try_number("2"));
+        args.set("pages", FluentValue::try_number("3"));
+        args.set("total", FluentValue::try_number("1"));
+        assert_eq!(
+            Locale::English.message_with("admin-users-page", Some(&args)),
+            "Page 2 of 3 (1 account)"
+        );
+
+        let mut args = FluentArgs::new();
+        args.set("closed", 0);
+        assert_eq!(
+            Locale::English.message_with("feedback-rejected", Some(&args)),
+            "Rejection recorded; no pull request was open"
+        );
+        args.set("closed", 2);
+        assert_eq!(
+            Locale::English.message_with("feedback-rejected", Some(&args)),
+            "Rejection recorded and 2 pull requests closed"
+        );
+        println!("✅ Message formatting test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_negotiation_and_scope() {
+        assert_eq!(
+            Locale::negotiate("es-MX,es;q=0.9,en;q=0.8"),
+            Locale::Spanish
+        );
+        assert_eq!(Locale::negotiate("fr-FR, en-GB"), Locale::English);
+        assert_eq!(Locale::negotiate("de"), Locale::English);
+        assert_eq!(Locale::negotiate(""), Locale::English);
+        assert_eq!("es-419".parse::<Locale>().unwrap(), Locale::Spanish);
+        assert!("fr".parse::<Locale>().is_err());
+
+        assert_eq!(Locale::current(), Locale::English);
+        let title = Locale::Spanish
+            .scope(async { (Locale::current(), t("projects.title")) })
+            .await;
+        assert_eq!(title, (Locale::Spanish, "Proyectos".to_string()));
+        assert_eq!(
+            t_with("resource-not-found", [("resource", "Team".into())]),
+            "Team not found"
+        );
+        println!("✅ Locale negotiation test passed!");
+    }
+}
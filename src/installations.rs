@@ -0,0 +1,169 @@
+// 🔌 GitHub App Installations - Know Where We're Welcome! 🔌
+// `installation` and `installation_repositories` webhooks keep a table of the
+// repositories each installation of the GitHub App can reach. When a repository
+// drops out of every installation (removed, app uninstalled or suspended) its
+// projects are deactivated and their owners told, instead of pipelines failing
+// on a clone later; when access comes back, the projects are active again
+// Created with love by Aye & Hue - Only knocking on open doors! ✨
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::models::{GitHubInstallation, Notification, NotificationType, Project, User};
+use crate::i18n::{self, Locale};
+
+/// 🎬 What happened to an installation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallationAction {
+    /// ➕ The app was installed
+    Created,
+    /// 🗑️ The app was uninstalled
+    Deleted,
+    /// ⏸️ The installation was suspended
+    Suspended,
+    /// ▶️ The installation was unsuspended
+    Unsuspended,
+    /// 📋 Repositories were added to or removed from the installation
+    RepositoriesChanged,
+    /// 🤷 Anything else (new permissions accepted, ...): only account and selection are kept
+    Other,
+}
+
+/// 🔌 A change to what an installation can reach, read from a webhook
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstallationChange {
+    pub installation_id: i64,
+    /// 🏠 Account the app is installed on
+    pub account: String,
+    /// 📋 `all` or `selected`
+    pub repository_selection: String,
+    pub action: InstallationAction,
+    /// ➕ Repositories the event grants (`owner/repo`)
+    pub added: Vec<String>,
+    /// ➖ Repositories the event takes away
+    pub removed: Vec<String>,
+}
+
+/// 🔌 Projects an installation change switched on or off
+#[derive(Debug, Default, Serialize)]
+pub struct InstallationOutcome {
+    /// ⛔ Projects whose repository the app can no longer reach
+    pub deactivated: Vec<Uuid>,
+    /// ✅ Projects whose repository the app can reach again
+    pub reactivated: Vec<Uuid>,
+}
+
+/// 🔄 Apply an installation change to the stored installations, then
+/// deactivate projects no installation reaches any more and reactivate the
+/// ones that are reachable again
+pub async fn sync(pool: &PgPool, change: &InstallationChange) -> Result<InstallationOutcome> {
+    let mut installation = GitHubInstallation::upsert(
+        pool,
+        change.installation_id,
+        &change.account,
+        &change.repository_selection,
+    )
+    .await?;
+    installation.add_repositories(pool, &change.added).await?;
+    installation
+        .remove_repositories(pool, &change.removed)
+        .await?;
+
+    let mut granted: BTreeSet<String> = change.added.iter().cloned().collect();
+    let mut revoked: BTreeSet<String> = change.removed.iter().cloned().collect();
+    match change.action {
+        InstallationAction::Deleted | InstallationAction::Suspended => {
+            revoked.extend(reachable(pool, &installation).await?);
+            if change.action == InstallationAction::Deleted {
+                installation.delete(pool).await?;
+            } else {
+                installation.set_suspended(pool, true).await?;
+            }
+        }
+        InstallationAction::Created | InstallationAction::Unsuspended => {
+            installation.set_suspended(pool, false).await?;
+            granted.extend(reachable(pool, &installation).await?);
+        }
+        InstallationAction::RepositoriesChanged | InstallationAction::Other => {}
+    }
+
+    let mut outcome = InstallationOutcome::default();
+    for repository in &revoked {
+        if GitHubInstallation::grants_access(pool, repository).await? {
+            continue;
+        }
+        for project in Project::set_active_for_repository(pool, repository, false).await? {
+            info!(
+                "⛔ Deactivated project {}: installation {} no longer reaches {}",
+                project.id, installation.id, repository
+            );
+            notify_owner(pool, &project).await;
+            outcome.deactivated.push(project.id);
+        }
+    }
+    for repository in &granted {
+        if !GitHubInstallation::grants_access(pool, repository).await? {
+            continue;
+        }
+        for project in Project::set_active_for_repository(pool, repository, true).await? {
+            info!(
+                "✅ Reactivated project {}: installation {} reaches {} again",
+                project.id, installation.id, repository
+            );
+            outcome.reactivated.push(project.id);
+        }
+    }
+    Ok(outcome)
+}
+
+/// 📋 Repositories an installation reaches: its list, plus every project
+/// repository of its account when it was granted all of them
+async fn reachable(pool: &PgPool, installation: &GitHubInstallation) -> Result<Vec<String>> {
+    let mut repositories = installation.repositories(pool).await?;
+    if installation.repository_selection == GitHubInstallation::ALL {
+        repositories.extend(Project::repositories_of_account(pool, &installation.account).await?);
+    }
+    Ok(repositories)
+}
+
+/// 📣 Tell the project owner, in their language, why the project stopped (failures are logged)
+async fn notify_owner(pool: &PgPool, project: &Project) {
+    let owner = match User::find_by_id(pool, project.owner_id).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("⚠️ No deactivation notice for {}: {:#}", project.id, e);
+            return;
+        }
+    };
+    let locale: Locale = owner
+        .locale
+        .as_deref()
+        .and_then(|code| code.parse().ok())
+        .unwrap_or_default();
+    let created = locale
+        .scope(async {
+            let title = i18n::t("project-deactivated-title");
+            let content = i18n::t_with(
+                "project-deactivated-access-revoked",
+                [("repository", project.repository.clone().into())],
+            );
+            Notification::create(
+                pool,
+                owner.id,
+                NotificationType::Warning,
+                &title,
+                &content,
+                Some(project.id),
+            )
+            .await
+        })
+        .await;
+    if let Err(e) = created {
+        warn!("⚠️ Deactivation notice for {} failed: {:#}", owner.email, e);
+    }
+}
@@ -0,0 +1,299 @@
+// 🎫 Issue Import - Your Backlog, Already in Feedbacker! 🎫
+// POST /api/projects/:id/import-issues pages through a repository's open GitHub
+// issues (only those carrying every requested label, when labels are given) and
+// turns each one not imported before into a feedback item linked back to it
+// through `metadata.source_issue`. Issues are read through GraphQL with their
+// labels (kept as the feedback's labels) and latest comments (kept in the
+// content, as context for the pipeline). Imported items wait as `paused` until
+// they're retried, unless the import asks for them to be processed: then each
+// gets a project run queued (`processing`), or waits as `pending` while the
+// project is inactive
+// Created with love by Aye & Hue - Seeding Feedbacker from the backlog you already have! ✨
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::database::models::{Feedback, FeedbackStatus, Project};
+use crate::github::{parse_repository, GitHubClient, OpenIssue};
+use crate::jobs::{queue::JobQueue, runs};
+use crate::pipeline::PipelineMode;
+
+/// 📏 Most issues imported by one request
+pub const MAX_IMPORTED_ISSUES: usize = 500;
+
+/// 📏 Longest feedback content, in bytes (the same limit as a submission's)
+const MAX_CONTENT_BYTES: usize = 10_000;
+
+/// 🎫 Which issues to import, and what happens to them
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportIssuesRequest {
+    /// 🏷️ Only issues with all of these labels (every open issue when empty)
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// 🚀 Queue the imported feedback for processing instead of pausing it
+    #[serde(default)]
+    pub process: bool,
+    /// 📏 Import at most this many issues (default and maximum: MAX_IMPORTED_ISSUES)
+    pub limit: Option<usize>,
+}
+
+impl ImportIssuesRequest {
+    /// ✅ Validate the request before talking to GitHub
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.labels.iter().any(|label| label.trim().is_empty()) {
+            errors.push("Labels cannot be empty".to_string());
+        }
+        if self.labels.iter().any(|label| label.contains(',')) {
+            errors.push("Labels cannot contain commas".to_string());
+        }
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_IMPORTED_ISSUES {
+                errors.push(format!(
+                    "Limit must be between 1 and {}",
+                    MAX_IMPORTED_ISSUES
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 🚦 Status imported feedback starts in (processing when its run is
+    /// queued right away, which needs an active project)
+    pub fn initial_status(&self, project_active: bool) -> FeedbackStatus {
+        match (self.process, project_active) {
+            (true, true) => FeedbackStatus::Processing,
+            (true, false) => FeedbackStatus::Pending,
+            (false, _) => FeedbackStatus::Paused,
+        }
+    }
+}
+
+/// ✅ One issue that became feedback
+#[derive(Debug, Serialize)]
+pub struct ImportedIssue {
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub feedback_id: Uuid,
+}
+
+/// 📊 What an import did
+#[derive(Debug, Serialize)]
+pub struct ImportIssuesResponse {
+    /// ✅ Issues turned into feedback by this import
+    pub imported: Vec<ImportedIssue>,
+    /// 🔁 Matching issues skipped because an earlier import already has them
+    pub already_imported: usize,
+    /// 🚦 Status the imported feedback is in
+    pub status: FeedbackStatus,
+}
+
+/// 🎫 Import a project's open issues as feedback, queueing a run for each
+/// imported item when the request asks for processing
+pub async fn import_issues(
+    pool: &PgPool,
+    jobs: &dyn JobQueue,
+    github: &GitHubClient,
+    project: &Project,
+    user_id: Uuid,
+    request: &ImportIssuesRequest,
+) -> Result<ImportIssuesResponse> {
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let already = Feedback::imported_issue_numbers(pool, &project.repository).await?;
+    let limit = request.limit.unwrap_or(MAX_IMPORTED_ISSUES);
+    let status = request.initial_status(project.is_active);
+    let scope = project.settings()?.scope()?;
+
+    // 🔁 Ask for enough issues to fill the limit after skipping the ones we have
+    let issues = github
+        .list_open_issues(&owner, &repo, &request.labels, limit + already.len())
+        .await?;
+
+    let mut imported = Vec::new();
+    let mut already_imported = 0;
+    for issue in issues {
+        if already.contains(&(issue.number as i64)) {
+            already_imported += 1;
+            continue;
+        }
+        if imported.len() == limit {
+            break;
+        }
+
+        let mut feedback = Feedback::create(
+            pool,
+            Some(user_id),
+            project.repository.clone(),
+            scope.root.clone(),
+            issue_feedback_content(&issue),
+        )
+        .await?;
+        feedback
+            .merge_metadata(pool, source_issue_metadata(&issue))
+            .await?;
+        feedback.update_status(pool, status, None).await?;
+        if status == FeedbackStatus::Processing {
+            jobs.enqueue(&runs::run_job(project, &feedback, PipelineMode::Feedback))
+                .await?;
+        }
+
+        imported.push(ImportedIssue {
+            issue_number: issue.number,
+            issue_url: issue.html_url,
+            feedback_id: feedback.id,
+        });
+    }
+
+    info!(
+        "🎫 Imported {} issues of {} as {} feedback ({} imported before)",
+        imported.len(),
+        project.repository,
+        status.as_str(),
+        already_imported
+    );
+    Ok(ImportIssuesResponse {
+        imported,
+        already_imported,
+        status,
+    })
+}
+
+/// 📝 Feedback content for an issue: its title, description, latest comments
+/// and a link back, cut to fit a submission's limit
+pub fn issue_feedback_content(issue: &OpenIssue) -> String {
+    let source = format!("\n\nImported from {}", issue.html_url);
+    let mut content = issue.title.trim().to_string();
+    if let Some(body) = issue
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        content.push_str("\n\n");
+        content.push_str(body);
+    }
+    for comment in &issue.comments {
+        let body = comment.body.trim();
+        if !body.is_empty() {
+            content.push_str(&format!("\n\n@{} commented:\n{}", comment.author, body));
+        }
+    }
+
+    let room = MAX_CONTENT_BYTES.saturating_sub(source.len());
+    if content.len() > room {
+        let mut end = room;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+    content + &source
+}
+
+/// 🔗 Metadata patch linking feedback to the issue it came from (and its labels)
+fn source_issue_metadata(issue: &OpenIssue) -> serde_json::Value {
+    let mut metadata = serde_json::json!({
+        (Feedback::SOURCE_ISSUE_KEY): { "number": issue.number, "url": issue.html_url }
+    });
+    if !issue.labels.is_empty() {
+        metadata[Feedback::LABELS_KEY] = serde_json::json!(issue.labels);
+    }
+    metadata
+}
+
+// 🧪 Tests - Every issue accounted for!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::IssueComment;
+
+    fn issue(title: &str, body: Option<&str>) -> OpenIssue {
+        serde_json::from_value(serde_json::json!({
+            "number": 42,
+            "title": title,
+            "body": body,
+            "html_url": "https://github.com/aye-is/feedbacker/issues/42",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_issue_feedback_content() {
+        let content = issue_feedback_content(&issue("Add dark mode ", Some("Please!\n")));
+        assert_eq!(
+            content,
+            "Add dark mode\n\nPlease!\n\nImported from https://github.com/aye-is/feedbacker/issues/42"
+        );
+        let blank_body = issue_feedback_content(&issue("Crash on start", Some("  ")));
+        assert!(blank_body.starts_with("Crash on start\n\nImported from"));
+
+        let mut discussed = issue("Crash on start", None);
+        discussed.comments = vec![
+            IssueComment {
+                author: "hue".to_string(),
+                body: "Same here\n".to_string(),
+            },
+            IssueComment {
+                author: "ghost".to_string(),
+                body: " ".to_string(),
+            },
+        ];
+        assert_eq!(
+            issue_feedback_content(&discussed),
+            "Crash on start\n\n@hue commented:\nSame here\n\nImported from https://github.com/aye-is/feedbacker/issues/42"
+        );
+
+        let long = issue_feedback_content(&issue("Long", Some(&"ñ".repeat(MAX_CONTENT_BYTES))));
+        assert!(long.len() <= MAX_CONTENT_BYTES);
+        assert!(long.ends_with("/issues/42"));
+        println!("✅ Issue feedback content test passed!");
+    }
+
+    #[test]
+    fn test_source_issue_metadata() {
+        assert_eq!(
+            source_issue_metadata(&issue("Add dark mode", None)),
+            serde_json::json!({
+                "source_issue": {
+                    "number": 42,
+                    "url": "https://github.com/aye-is/feedbacker/issues/42"
+                }
+            })
+        );
+        let mut labelled = issue("Add dark mode", None);
+        labelled.labels = vec!["ui".to_string()];
+        assert_eq!(
+            source_issue_metadata(&labelled)["labels"],
+            serde_json::json!(["ui"])
+        );
+        println!("✅ Source issue metadata test passed!");
+    }
+
+    #[test]
+    fn test_validate_import_request() {
+        assert!(ImportIssuesRequest::default().validate().is_ok());
+        assert_eq!(
+            ImportIssuesRequest::default().initial_status(true),
+            FeedbackStatus::Paused
+        );
+
+        let request = ImportIssuesRequest {
+            labels: vec!["feedbacker".to_string(), " ".to_string(), "a,b".to_string()],
+            process: true,
+            limit: Some(MAX_IMPORTED_ISSUES + 1),
+        };
+        assert_eq!(request.validate().unwrap_err().len(), 3);
+        assert_eq!(request.initial_status(true), FeedbackStatus::Processing);
+        assert_eq!(request.initial_status(false), FeedbackStatus::Pending);
+        println!("✅ Import request validation test passed!");
+    }
+}
@@ -1,2 +1,94 @@
 // 🔄 Background Jobs Module - Async Task Processing! 🔄
-// TODO: Implement background job processing with tokio-cron-scheduler
+// Work queued in the background_jobs table (dispatched through the configured
+// queue backend) runs on a worker pool alongside the HTTP server; recurring
+// work is queued by the cron schedules in the scheduled_jobs table
+// Created with love by Aye & Hue - The work that happens while you sleep! ✨
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tokio_cron_scheduler::JobScheduler;
+
+use crate::api::AppState;
+use scheduler::ScanRunner;
+use schedules::Schedules;
+
+#[cfg(feature = "nats-queue")]
+pub mod nats_queue; // 📨 NATS JetStream queue backend
+pub mod queue; // 📮 Pluggable queue backends (Postgres, Redis Streams, NATS JetStream)
+#[cfg(feature = "redis-queue")]
+pub mod redis_queue; // 🟥 Redis Streams queue backend
+pub mod repo_health; // 🩺 Repository health analysis
+pub mod runs; // 🏭 Project runs, paced per project
+pub mod scheduler; // 🩺 Queueing and running repository health scans
+pub mod schedules; // ⏰ Recurring jobs on cron schedules
+pub mod worker; // 👷 Worker pool with per-type concurrency limits
+
+/// 🚀 Start the worker pool and the recurring job scheduler
+/// The returned scheduler must be kept alive for scheduled work to keep being queued
+pub async fn start(app_state: &AppState) -> Result<JobScheduler> {
+    let runner = ScanRunner::new(app_state);
+    let db_pool = app_state.db_pool.clone();
+    let retention = app_state.config.load().retention.clone();
+    let artifacts = app_state.config.load().artifacts.clone();
+    let handlers = HashMap::from([
+        (scheduler::SCAN_JOB.to_string(), runner.scan_handler()),
+        (runs::RUN_JOB.to_string(), runs::run_handler(app_state)),
+        (
+            scheduler::SCAN_DISPATCH_JOB.to_string(),
+            runner.dispatch_handler(),
+        ),
+        (
+            crate::export::EXPORT_JOB.to_string(),
+            crate::export::export_handler(app_state),
+        ),
+        (
+            crate::pipeline::rebase::REBASE_JOB.to_string(),
+            crate::pipeline::rebase::rebase_handler(app_state),
+        ),
+        (
+            crate::digest::DIGEST_JOB.to_string(),
+            crate::digest::digest_handler(app_state),
+        ),
+        (
+            crate::feedback_claims::CLAIM_EMAIL_JOB.to_string(),
+            crate::feedback_claims::claim_email_handler(app_state),
+        ),
+        (
+            crate::feedback_watchdog::WATCHDOG_JOB.to_string(),
+            crate::feedback_watchdog::watchdog_handler(app_state),
+        ),
+        (
+            crate::collaborators::COLLABORATOR_SYNC_JOB.to_string(),
+            crate::collaborators::collaborator_sync_handler(app_state),
+        ),
+        (
+            crate::style_guide::STYLE_ANALYSIS_JOB.to_string(),
+            crate::style_guide::style_analysis_handler(app_state),
+        ),
+        (
+            crate::security_alerts::SECURITY_ALERT_JOB.to_string(),
+            crate::security_alerts::security_alert_handler(app_state),
+        ),
+        (
+            schedules::CLEANUP_JOB.to_string(),
+            worker::handler(move |_job| {
+                let db_pool = db_pool.clone();
+                let retention = retention.clone();
+                let artifacts = artifacts.clone();
+                async move {
+                    crate::database::cleanup_old_records(&db_pool, &retention).await?;
+                    crate::export::purge_expired(&db_pool).await?;
+                    crate::artifacts::purge_expired(&db_pool, &artifacts).await?;
+                    crate::pipeline::checkpoint::purge_stale(&db_pool).await?;
+                    crate::middleware::request_signing::prune_seen_signatures(&db_pool).await?;
+                    Ok(())
+                }
+            }),
+        ),
+    ]);
+    app_state.workers.clone().start(handlers);
+
+    schedules::start(Schedules::new(app_state))
+        .await
+        .context("Failed to start recurring job scheduler")
+}
@@ -0,0 +1,297 @@
+// 📨 NATS Queue - Job Dispatch Through JetStream! 📨
+// Each job type has one subject per lane (`{prefix}.{job_type}.{lane}`) in a
+// work-queue stream named after the prefix. Workers of every instance pull from
+// one durable consumer per subject, so each announcement is delivered once,
+// interactive lanes first. The background_jobs row stays the source of truth:
+// a message is acknowledged, then the row is claimed only if it is still
+// pending. Anything JetStream misses (retries, requeues, messages lost while
+// NATS was down) is claimed from Postgres as a fallback, and core NATS messages
+// on `{prefix}.wakeup` replace LISTEN/NOTIFY for idle workers
+// Created with love by Aye & Hue - Fast lanes, safe books, different pipes! ✨
+
+use anyhow::{Context, Result};
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy, PullConsumer},
+    stream::{self, DiscardPolicy, RetentionPolicy},
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::queue::JobQueue;
+use crate::config::{JobsConfig, QueueBackend};
+use crate::database::models::{BackgroundJob, JobPriority, NewBackgroundJob};
+
+/// 📏 Messages the stream keeps at most (Postgres still has every job)
+const MAX_STREAM_LEN: i64 = 100_000;
+
+/// 🚦 Lanes in the order they are read
+const LANES: [JobPriority; 3] = [
+    JobPriority::Interactive,
+    JobPriority::Normal,
+    JobPriority::Bulk,
+];
+
+/// 🏷️ Subject announcing one job type's jobs in one lane
+pub fn job_subject(prefix: &str, job_type: &str, lane: JobPriority) -> String {
+    format!("{}.{}.{}", prefix, job_type, lane.as_str())
+}
+
+/// 🔔 Subject idle workers listen on (outside the stream: two tokens, not three)
+pub fn wakeup_subject(prefix: &str) -> String {
+    format!("{}.wakeup", prefix)
+}
+
+/// 🗄️ Stream holding every lane of every job type
+pub fn stream_name(prefix: &str) -> String {
+    prefix.replace('.', "_").to_uppercase()
+}
+
+/// 👥 Durable consumer shared by all instances for one subject
+pub fn consumer_name(job_type: &str, lane: JobPriority) -> String {
+    format!("{}-{}", job_type, lane.as_str())
+}
+
+/// 📨 Queue dispatching through NATS JetStream
+pub struct NatsQueue {
+    /// 🗄️ Where the jobs live
+    db_pool: PgPool,
+    /// 🏷️ Prefix of every subject
+    prefix: String,
+    /// 🔌 Core NATS connection, for wake-ups
+    client: async_nats::Client,
+    /// 📨 JetStream context, for announcing jobs
+    jetstream: jetstream::Context,
+    /// 🗄️ The work-queue stream
+    stream: stream::Stream,
+    /// 👥 Consumers looked up so far, by name
+    consumers: Mutex<HashMap<String, PullConsumer>>,
+    /// 🔔 Wake-up subscription
+    wakeups: Mutex<async_nats::Subscriber>,
+}
+
+// 🙈 Connections have nothing useful to print
+impl std::fmt::Debug for NatsQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NatsQueue")
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl NatsQueue {
+    /// 🔌 Connect to the configured NATS servers and create the stream unless it exists
+    pub async fn connect(config: &JobsConfig, db_pool: PgPool) -> Result<Self> {
+        let url = config
+            .nats_url
+            .as_deref()
+            .context("No NATS URL configured for the job queue")?;
+        let prefix = config.nats_prefix.clone();
+        let client = async_nats::connect(url)
+            .await
+            .context("Failed to connect to the job queue NATS")?;
+        let jetstream = jetstream::new(client.clone());
+        let stream = jetstream
+            .get_or_create_stream(stream::Config {
+                name: stream_name(&prefix),
+                subjects: vec![format!("{}.*.*", prefix)],
+                retention: RetentionPolicy::WorkQueue,
+                max_messages: MAX_STREAM_LEN,
+                discard: DiscardPolicy::Old,
+                ..Default::default()
+            })
+            .await
+            .context("Failed to create the job queue stream")?;
+        let wakeups = client
+            .subscribe(wakeup_subject(&prefix))
+            .await
+            .context("Failed to subscribe to job queue wake-ups")?;
+
+        Ok(Self {
+            db_pool,
+            prefix,
+            client,
+            jetstream,
+            stream,
+            consumers: Mutex::new(HashMap::new()),
+            wakeups: Mutex::new(wakeups),
+        })
+    }
+
+    /// 📤 Announce the job on its lane's subject and wake idle workers
+    async fn publish(&self, job: &BackgroundJob) -> Result<()> {
+        let subject = job_subject(&self.prefix, &job.job_type, job.lane());
+        self.jetstream
+            .publish(subject, job.id.to_string().into())
+            .await
+            .context("Failed to publish job to NATS")?
+            .await
+            .context("NATS did not store the job")?;
+        self.client
+            .publish(wakeup_subject(&self.prefix), job.job_type.clone().into())
+            .await
+            .context("Failed to wake NATS job workers")
+    }
+
+    /// 👥 The consumer of one subject, created unless it exists
+    async fn consumer(&self, job_type: &str, lane: JobPriority) -> Result<PullConsumer> {
+        let name = consumer_name(job_type, lane);
+        if let Some(consumer) = self.consumers.lock().await.get(&name) {
+            return Ok(consumer.clone());
+        }
+
+        let consumer = self
+            .stream
+            .get_or_create_consumer(
+                &name,
+                pull::Config {
+                    durable_name: Some(name.clone()),
+                    filter_subject: job_subject(&self.prefix, job_type, lane),
+                    ack_policy: AckPolicy::Explicit,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("Failed to create NATS consumer")?;
+        self.consumers.lock().await.insert(name, consumer.clone());
+        Ok(consumer)
+    }
+
+    /// 📥 Take the next job id announced on one subject
+    async fn read_entry(&self, job_type: &str, lane: JobPriority) -> Result<Option<Uuid>> {
+        let consumer = self.consumer(job_type, lane).await?;
+        let read = async {
+            loop {
+                let mut batch = consumer
+                    .fetch()
+                    .max_messages(1)
+                    .messages()
+                    .await
+                    .context("Failed to fetch from NATS consumer")?;
+                let Some(message) = batch.next().await else {
+                    return Ok(None);
+                };
+                let message = message
+                    .map_err(|e| anyhow::anyhow!(e))
+                    .context("Failed to read NATS message")?;
+
+                // 🧾 Acknowledge right away: if we crash now, the row is still pending in Postgres
+                message
+                    .ack()
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))
+                    .context("Failed to acknowledge NATS message")?;
+
+                match std::str::from_utf8(&message.payload)
+                    .ok()
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                {
+                    Some(job_id) => return Ok(Some(job_id)),
+                    None => warn!("⚠️ Dropping malformed message on {}", message.subject),
+                }
+            }
+        };
+        let entry = read.await;
+        if entry.is_err() {
+            // 🧹 The consumer may have been deleted; look it up again next time
+            self.consumers
+                .lock()
+                .await
+                .remove(&consumer_name(job_type, lane));
+        }
+        entry
+    }
+
+    /// 🎟️ Claim the first job announced on the subjects that is still pending
+    async fn claim_from_stream(
+        &self,
+        job_types: &[String],
+        worker_id: &str,
+    ) -> Result<Option<BackgroundJob>> {
+        for lane in LANES {
+            for job_type in job_types {
+                while let Some(job_id) = self.read_entry(job_type, lane).await? {
+                    if let Some(job) =
+                        BackgroundJob::claim_by_id(&self.db_pool, job_id, worker_id).await?
+                    {
+                        return Ok(Some(job));
+                    }
+                    // 🤷 Already claimed through the Postgres fallback, or no longer pending
+                    debug!("⏭️ Skipping stale announcement of job {}", job_id);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl JobQueue for NatsQueue {
+    fn backend(&self) -> QueueBackend {
+        QueueBackend::Nats
+    }
+
+    async fn enqueue(&self, job: &NewBackgroundJob) -> Result<BackgroundJob> {
+        let job = BackgroundJob::create(&self.db_pool, job).await?;
+        if let Err(e) = self.publish(&job).await {
+            // 🐘 The job is safe in Postgres; the fallback claim will find it
+            warn!("⚠️ Job {} not published to NATS: {:#}", job.id, e);
+        }
+        Ok(job)
+    }
+
+    async fn claim(&self, job_types: &[String], worker_id: &str) -> Result<Option<BackgroundJob>> {
+        match self.claim_from_stream(job_types, worker_id).await {
+            Ok(Some(job)) => return Ok(Some(job)),
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ NATS claim failed, claiming from Postgres: {:#}", e),
+        }
+        // 🐘 Retries, requeues, and anything NATS never saw
+        BackgroundJob::claim_next(&self.db_pool, job_types, worker_id).await
+    }
+
+    async fn wait_for_work(&self, timeout: Duration) {
+        let mut wakeups = self.wakeups.lock().await;
+        match tokio::time::timeout(timeout, wakeups.next()).await {
+            Ok(Some(message)) => {
+                debug!(
+                    "🔔 Woken by a new {} job",
+                    String::from_utf8_lossy(&message.payload)
+                );
+            }
+            Ok(None) => {
+                warn!("⚠️ NATS wake-up subscription closed");
+                tokio::time::sleep(timeout).await;
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+// 🧪 Tests - Every lane gets its own subject!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subjects() {
+        assert_eq!(
+            job_subject("feedbacker.jobs", "repository_scan", JobPriority::Bulk),
+            "feedbacker.jobs.repository_scan.bulk"
+        );
+        assert_eq!(wakeup_subject("feedbacker.jobs"), "feedbacker.jobs.wakeup");
+        assert_eq!(stream_name("feedbacker.jobs"), "FEEDBACKER_JOBS");
+        assert_eq!(
+            consumer_name("project_run", JobPriority::Interactive),
+            "project_run-interactive"
+        );
+        assert_eq!(LANES[0], JobPriority::Interactive);
+        println!("✅ NATS subject test passed!");
+    }
+}
@@ -0,0 +1,218 @@
+// 📮 Job Queue - Where Workers Get Their Next Job! 📮
+// Enqueueing, claiming, and waiting for work go through the `JobQueue` trait, so
+// the dispatch path can be swapped without touching the workers. Job state
+// (retries, heartbeats, dead letters) always lives in the background_jobs table;
+// a backend only decides how new work is announced and handed out. Postgres
+// claims with SKIP LOCKED and wakes workers with LISTEN/NOTIFY; Redis hands out
+// job ids through Streams consumer groups (see `redis_queue`), and NATS through
+// JetStream pull consumers (see `nats_queue`)
+// Created with love by Aye & Hue - One queue, pick your plumbing! ✨
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::config::{JobsConfig, QueueBackend};
+use crate::database::models::{BackgroundJob, NewBackgroundJob};
+
+/// 📮 How jobs reach the workers
+#[async_trait]
+pub trait JobQueue: Send + Sync + std::fmt::Debug {
+    /// 🏷️ Which backend this is
+    fn backend(&self) -> QueueBackend;
+
+    /// ➕ Store a job and announce it to idle workers
+    async fn enqueue(&self, job: &NewBackgroundJob) -> Result<BackgroundJob>;
+
+    /// 🎟️ Claim the next due job of one of `job_types` for `worker_id`
+    async fn claim(&self, job_types: &[String], worker_id: &str) -> Result<Option<BackgroundJob>>;
+
+    /// 💤 Return when new work is announced, or after `timeout` at the latest
+    async fn wait_for_work(&self, timeout: Duration);
+}
+
+/// 🔌 Queue for the configured backend
+pub async fn connect(config: &JobsConfig, db_pool: PgPool) -> Result<Arc<dyn JobQueue>> {
+    match config.backend {
+        QueueBackend::Postgres => Ok(Arc::new(PostgresQueue::new(db_pool))),
+        #[cfg(feature = "redis-queue")]
+        QueueBackend::Redis => Ok(Arc::new(
+            super::redis_queue::RedisQueue::connect(config, db_pool).await?,
+        )),
+        #[cfg(not(feature = "redis-queue"))]
+        QueueBackend::Redis => {
+            anyhow::bail!("The redis queue backend needs the redis-queue feature")
+        }
+        #[cfg(feature = "nats-queue")]
+        QueueBackend::Nats => Ok(Arc::new(
+            super::nats_queue::NatsQueue::connect(config, db_pool).await?,
+        )),
+        #[cfg(not(feature = "nats-queue"))]
+        QueueBackend::Nats => {
+            anyhow::bail!("The nats queue backend needs the nats-queue feature")
+        }
+    }
+}
+
+/// 🐘 Queue on the background_jobs table alone
+#[derive(Debug)]
+pub struct PostgresQueue {
+    /// 🗄️ Where the jobs live
+    db_pool: PgPool,
+    /// 🔔 LISTEN connection, opened on the first wait (Some(None) when it failed: poll only)
+    listener: Mutex<Option<Option<PgListener>>>,
+}
+
+impl PostgresQueue {
+    /// ➕ Queue on this database
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            listener: Mutex::new(None),
+        }
+    }
+
+    /// 🔔 Subscribe to new-work notifications (None falls back to polling)
+    async fn listen(&self) -> Option<PgListener> {
+        let subscribed = async {
+            let mut listener = PgListener::connect_with(&self.db_pool).await?;
+            listener.listen(BackgroundJob::CHANNEL).await?;
+            Ok::<_, sqlx::Error>(listener)
+        };
+        match subscribed.await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                warn!("⚠️ Job notifications unavailable, polling instead: {:#}", e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl JobQueue for PostgresQueue {
+    fn backend(&self) -> QueueBackend {
+        QueueBackend::Postgres
+    }
+
+    async fn enqueue(&self, job: &NewBackgroundJob) -> Result<BackgroundJob> {
+        BackgroundJob::enqueue(&self.db_pool, job).await
+    }
+
+    async fn claim(&self, job_types: &[String], worker_id: &str) -> Result<Option<BackgroundJob>> {
+        BackgroundJob::claim_next(&self.db_pool, job_types, worker_id).await
+    }
+
+    async fn wait_for_work(&self, timeout: Duration) {
+        let mut listener = self.listener.lock().await;
+        if listener.is_none() {
+            *listener = Some(self.listen().await);
+        }
+        let Some(Some(listener)) = listener.as_mut() else {
+            tokio::time::sleep(timeout).await;
+            return;
+        };
+
+        match tokio::time::timeout(timeout, listener.recv()).await {
+            Ok(Ok(notification)) => {
+                debug!("🔔 Woken by a new {} job", notification.payload());
+            }
+            Ok(Err(e)) => {
+                // 🔌 recv reconnects on the next call; don't spin meanwhile
+                warn!("⚠️ Job notification listener failed: {:#}", e);
+                tokio::time::sleep(timeout).await;
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// 📝 Queue that keeps what it's given, for tests of code that enqueues jobs
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct RecordingQueue {
+    /// 📥 Every job enqueued so far
+    pub jobs: std::sync::Mutex<Vec<NewBackgroundJob>>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl JobQueue for RecordingQueue {
+    fn backend(&self) -> QueueBackend {
+        QueueBackend::Postgres
+    }
+
+    async fn enqueue(&self, job: &NewBackgroundJob) -> Result<BackgroundJob> {
+        self.jobs.lock().unwrap().push(job.clone());
+        let now = chrono::Utc::now();
+        Ok(BackgroundJob {
+            id: uuid::Uuid::new_v4(),
+            job_type: job.job_type.clone(),
+            payload: job.payload.clone(),
+            status: BackgroundJob::PENDING.to_string(),
+            priority: job.priority.value(),
+            user_id: job.user_id,
+            retries: 0,
+            max_retries: job.max_retries,
+            error_message: None,
+            error_history: serde_json::json!([]),
+            scheduled_at: now,
+            started_at: None,
+            completed_at: None,
+            worker_id: None,
+            heartbeat_at: None,
+            dead_lettered_at: None,
+            created_at: now,
+        })
+    }
+
+    async fn claim(&self, _: &[String], _: &str) -> Result<Option<BackgroundJob>> {
+        Ok(None)
+    }
+
+    async fn wait_for_work(&self, timeout: Duration) {
+        tokio::time::sleep(timeout).await;
+    }
+}
+
+// 🧪 Tests - The configured backend is the one you get!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backend_selection() {
+        assert_eq!(
+            "Postgres".parse::<QueueBackend>().unwrap(),
+            QueueBackend::Postgres
+        );
+        assert_eq!(
+            "redis".parse::<QueueBackend>().unwrap(),
+            QueueBackend::Redis
+        );
+        assert_eq!("nats".parse::<QueueBackend>().unwrap(), QueueBackend::Nats);
+        assert!("kafka".parse::<QueueBackend>().is_err());
+
+        let db_pool = PgPool::connect_lazy("postgres://localhost/feedbacker").unwrap();
+        let config = JobsConfig {
+            workers: 1,
+            poll_interval_ms: 1000,
+            type_concurrency: Default::default(),
+            heartbeat_interval_seconds: 15,
+            stale_after_seconds: 120,
+            backend: QueueBackend::Postgres,
+            redis_url: None,
+            redis_prefix: "feedbacker:jobs".to_string(),
+            nats_url: None,
+            nats_prefix: "feedbacker.jobs".to_string(),
+        };
+        let queue = connect(&config, db_pool).await.unwrap();
+        assert_eq!(queue.backend(), QueueBackend::Postgres);
+        println!("✅ Queue backend selection test passed!");
+    }
+}
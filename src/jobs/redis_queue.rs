@@ -0,0 +1,308 @@
+// 🟥 Redis Queue - Job Dispatch Through Redis Streams! 🟥
+// Each job type has one stream per lane (`{prefix}:{job_type}:{lane}`) holding
+// job ids. Workers of every instance read them through one consumer group, so
+// each entry is delivered once, interactive lanes first. The background_jobs row
+// stays the source of truth: an entry is acknowledged, then the row is claimed
+// only if it is still pending. Anything Redis misses (retries, requeues, entries
+// lost while Redis was down) is claimed from Postgres as a fallback, and a
+// wake-up stream replaces LISTEN/NOTIFY for idle workers
+// Created with love by Aye & Hue - Fast lanes, safe books! ✨
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::streams::StreamReadReply;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::queue::JobQueue;
+use crate::config::{JobsConfig, QueueBackend};
+use crate::database::models::{BackgroundJob, JobPriority, NewBackgroundJob};
+
+/// 👥 Consumer group shared by all instances
+const GROUP: &str = "workers";
+
+/// 📏 Approximate length streams are trimmed to (Postgres still has every job)
+const MAX_STREAM_LEN: usize = 100_000;
+
+/// 🚦 Lanes in the order they are read
+const LANES: [JobPriority; 3] = [
+    JobPriority::Interactive,
+    JobPriority::Normal,
+    JobPriority::Bulk,
+];
+
+/// 🏷️ Stream holding the ids of one job type's jobs in one lane
+pub fn stream_key(prefix: &str, job_type: &str, lane: JobPriority) -> String {
+    format!("{}:{}:{}", prefix, job_type, lane.as_str())
+}
+
+/// 🔔 Stream idle workers block on
+pub fn wakeup_key(prefix: &str) -> String {
+    format!("{}:wakeup", prefix)
+}
+
+/// 🟥 Queue dispatching through Redis Streams
+pub struct RedisQueue {
+    /// 🗄️ Where the jobs live
+    db_pool: PgPool,
+    /// 🏷️ Prefix of every key
+    prefix: String,
+    /// 🔌 Connection for ordinary commands
+    redis: ConnectionManager,
+    /// 💤 Connection for blocking reads (they would hold up the shared one)
+    blocking: ConnectionManager,
+    /// 👥 Streams whose consumer group is known to exist
+    groups: Mutex<HashSet<String>>,
+    /// 🔔 Last wake-up entry seen ("$" until the first one)
+    last_wakeup: Mutex<String>,
+}
+
+// 🙈 Connections have nothing useful to print
+impl std::fmt::Debug for RedisQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisQueue")
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl RedisQueue {
+    /// 🔌 Connect to the configured Redis server
+    pub async fn connect(config: &JobsConfig, db_pool: PgPool) -> Result<Self> {
+        let url = config
+            .redis_url
+            .as_deref()
+            .context("No Redis URL configured for the job queue")?;
+        let client = redis::Client::open(url).context("Invalid job queue Redis URL")?;
+        let redis = client
+            .get_connection_manager()
+            .await
+            .context("Failed to connect to the job queue Redis")?;
+        let blocking = client
+            .get_connection_manager()
+            .await
+            .context("Failed to connect to the job queue Redis")?;
+
+        Ok(Self {
+            db_pool,
+            prefix: config.redis_prefix.clone(),
+            redis,
+            blocking,
+            groups: Mutex::new(HashSet::new()),
+            last_wakeup: Mutex::new("$".to_string()),
+        })
+    }
+
+    /// 📤 Add the job to its lane's stream and wake idle workers
+    async fn publish(&self, job: &BackgroundJob) -> Result<()> {
+        let mut redis = self.redis.clone();
+        redis::pipe()
+            .cmd("XADD")
+            .arg(stream_key(&self.prefix, &job.job_type, job.lane()))
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(MAX_STREAM_LEN)
+            .arg("*")
+            .arg("job_id")
+            .arg(job.id.to_string())
+            .ignore()
+            .cmd("XADD")
+            .arg(wakeup_key(&self.prefix))
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(1000)
+            .arg("*")
+            .arg("job_type")
+            .arg(&job.job_type)
+            .ignore()
+            .query_async::<()>(&mut redis)
+            .await
+            .context("Failed to publish job to Redis")
+    }
+
+    /// 👥 Create the consumer group of a stream (and the stream) unless it exists
+    async fn ensure_group(&self, key: &str) -> Result<()> {
+        if self.groups.lock().await.contains(key) {
+            return Ok(());
+        }
+
+        let mut redis = self.redis.clone();
+        let created = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(key)
+            .arg(GROUP)
+            .arg("0")
+            .arg("MKSTREAM")
+            .query_async::<()>(&mut redis)
+            .await;
+        match created {
+            Ok(()) => {}
+            Err(e) if e.code() == Some("BUSYGROUP") => {}
+            Err(e) => return Err(e).context("Failed to create Redis consumer group"),
+        }
+
+        self.groups.lock().await.insert(key.to_string());
+        Ok(())
+    }
+
+    /// 📥 Take the next entry of a stream: (entry id, job id)
+    async fn read_entry(&self, key: &str, worker_id: &str) -> Result<Option<(String, Uuid)>> {
+        self.ensure_group(key).await?;
+
+        let mut redis = self.redis.clone();
+        loop {
+            let reply = redis::cmd("XREADGROUP")
+                .arg("GROUP")
+                .arg(GROUP)
+                .arg(worker_id)
+                .arg("COUNT")
+                .arg(1)
+                .arg("STREAMS")
+                .arg(key)
+                .arg(">")
+                .query_async::<Option<StreamReadReply>>(&mut redis)
+                .await;
+            let reply = match reply {
+                Ok(reply) => reply,
+                Err(e) => {
+                    // 🧹 The stream was deleted (e.g. FLUSHALL); recreate the group next time
+                    if e.code() == Some("NOGROUP") {
+                        self.groups.lock().await.remove(key);
+                    }
+                    return Err(e).context("Failed to read from Redis stream");
+                }
+            };
+
+            let Some(entry) = reply
+                .into_iter()
+                .flat_map(|reply| reply.keys)
+                .flat_map(|stream| stream.ids)
+                .next()
+            else {
+                return Ok(None);
+            };
+
+            // 🧾 Acknowledge right away: if we crash now, the row is still pending in Postgres
+            redis::cmd("XACK")
+                .arg(key)
+                .arg(GROUP)
+                .arg(&entry.id)
+                .query_async::<()>(&mut redis)
+                .await
+                .context("Failed to acknowledge Redis stream entry")?;
+
+            match entry
+                .get::<String>("job_id")
+                .and_then(|id| Uuid::parse_str(&id).ok())
+            {
+                Some(job_id) => return Ok(Some((entry.id, job_id))),
+                None => warn!("⚠️ Dropping malformed entry {} in {}", entry.id, key),
+            }
+        }
+    }
+
+    /// 🎟️ Claim the first job announced on the streams that is still pending
+    async fn claim_from_streams(
+        &self,
+        job_types: &[String],
+        worker_id: &str,
+    ) -> Result<Option<BackgroundJob>> {
+        for lane in LANES {
+            for job_type in job_types {
+                let key = stream_key(&self.prefix, job_type, lane);
+                while let Some((entry_id, job_id)) = self.read_entry(&key, worker_id).await? {
+                    if let Some(job) =
+                        BackgroundJob::claim_by_id(&self.db_pool, job_id, worker_id).await?
+                    {
+                        return Ok(Some(job));
+                    }
+                    // 🤷 Already claimed through the Postgres fallback, or no longer pending
+                    debug!("⏭️ Skipping stale entry {} for job {}", entry_id, job_id);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl JobQueue for RedisQueue {
+    fn backend(&self) -> QueueBackend {
+        QueueBackend::Redis
+    }
+
+    async fn enqueue(&self, job: &NewBackgroundJob) -> Result<BackgroundJob> {
+        let job = BackgroundJob::create(&self.db_pool, job).await?;
+        if let Err(e) = self.publish(&job).await {
+            // 🐘 The job is safe in Postgres; the fallback claim will find it
+            warn!("⚠️ Job {} not published to Redis: {:#}", job.id, e);
+        }
+        Ok(job)
+    }
+
+    async fn claim(&self, job_types: &[String], worker_id: &str) -> Result<Option<BackgroundJob>> {
+        match self.claim_from_streams(job_types, worker_id).await {
+            Ok(Some(job)) => return Ok(Some(job)),
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ Redis claim failed, claiming from Postgres: {:#}", e),
+        }
+        // 🐘 Retries, requeues, and anything Redis never saw
+        BackgroundJob::claim_next(&self.db_pool, job_types, worker_id).await
+    }
+
+    async fn wait_for_work(&self, timeout: Duration) {
+        let mut last_wakeup = self.last_wakeup.lock().await;
+        let mut blocking = self.blocking.clone();
+        let reply = redis::cmd("XREAD")
+            .arg("BLOCK")
+            .arg(timeout.as_millis().max(1) as u64)
+            .arg("STREAMS")
+            .arg(wakeup_key(&self.prefix))
+            .arg(last_wakeup.as_str())
+            .query_async::<Option<StreamReadReply>>(&mut blocking)
+            .await;
+
+        match reply {
+            Ok(reply) => {
+                let entries = reply
+                    .into_iter()
+                    .flat_map(|reply| reply.keys)
+                    .flat_map(|stream| stream.ids);
+                if let Some(latest) = entries.last() {
+                    debug!("🔔 Woken by a new job (wake-up entry {})", latest.id);
+                    *last_wakeup = latest.id;
+                }
+            }
+            Err(e) => {
+                warn!("⚠️ Redis wake-up read failed: {:#}", e);
+                tokio::time::sleep(timeout).await;
+            }
+        }
+    }
+}
+
+// 🧪 Tests - Every lane gets its own stream!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_keys() {
+        assert_eq!(
+            stream_key("feedbacker:jobs", "repository_scan", JobPriority::Bulk),
+            "feedbacker:jobs:repository_scan:bulk"
+        );
+        assert_eq!(
+            stream_key("app", "project_run", JobPriority::Interactive),
+            "app:project_run:interactive"
+        );
+        assert_eq!(wakeup_key("feedbacker:jobs"), "feedbacker:jobs:wakeup");
+        assert_eq!(LANES[0], JobPriority::Interactive);
+        println!("✅ Redis stream key test passed!");
+    }
+}
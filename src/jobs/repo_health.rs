@@ -0,0 +1,489 @@
+// 🩺 Repository Health - Spotting Rot Before Users Do! 🩺
+// Pure analysis over the files of a checkout, no git or network involved:
+// - 📚 Docs that point at files which no longer exist
+// - 📝 Files crowded with TODO/FIXME markers
+// - 🧪 Sizeable source files with no tests anywhere in sight
+// The scheduler turns the findings into a suggested feedback item or an issue
+// Created with love by Aye & Hue - An apple a day keeps the tech debt away! ✨
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::models::{HealthCheck, PathScope};
+
+/// 📝 Markers counted by the TODO density check
+const TODO_MARKERS: &[&str] = &["TODO", "FIXME", "XXX", "HACK"];
+
+/// 📝 A file is flagged with at least this many markers...
+const MIN_TODO_MARKERS: usize = 5;
+
+/// 📝 ...and at least one marker per this many lines
+const TODO_LINES_PER_MARKER: usize = 50;
+
+/// 🧪 Source files shorter than this aren't worth a missing-tests finding
+const MIN_UNTESTED_LINES: usize = 80;
+
+/// ✂️ Findings kept per check so reports stay readable
+pub const MAX_FINDINGS_PER_CHECK: usize = 10;
+
+/// 🧪 Extensions we treat as source code
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "rb", "java", "kt", "swift", "c", "cc", "cpp",
+    "cs", "php",
+];
+
+/// 🏷️ Generic file names that say nothing about what they contain
+const GENERIC_STEMS: &[&str] = &["mod", "lib", "main", "index", "__init__"];
+
+/// 📄 A file from the checkout
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// 📂 Repository-relative path
+    pub path: String,
+    /// 📝 File contents
+    pub content: String,
+}
+
+/// 🔍 One thing a health check noticed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthFinding {
+    /// 🩺 Check that produced the finding
+    pub check: HealthCheck,
+    /// 📂 File the finding is about
+    pub path: String,
+    /// 💬 Human-readable description
+    pub message: String,
+}
+
+/// 📋 Result of analyzing a checkout
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    /// 🔍 Findings, grouped by check in the order the checks ran
+    pub findings: Vec<HealthFinding>,
+    /// 📊 Number of files looked at
+    pub files_scanned: usize,
+}
+
+/// 🩺 Run the requested checks over the files of a checkout
+pub fn analyze(files: &[SourceFile], checks: &[HealthCheck], scope: &PathScope) -> HealthReport {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+
+    for check in checks {
+        if !seen.insert(*check) {
+            continue;
+        }
+        let mut found = match check {
+            HealthCheck::OutdatedDocs => outdated_docs(files, scope),
+            HealthCheck::TodoDensity => todo_density(files),
+            HealthCheck::MissingTests => missing_tests(files),
+        };
+        found.truncate(MAX_FINDINGS_PER_CHECK);
+        findings.extend(found);
+    }
+
+    HealthReport {
+        findings,
+        files_scanned: files.len(),
+    }
+}
+
+impl HealthReport {
+    /// 🤷 Nothing worth filing
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// 🏷️ Title for the filed issue
+    pub fn title(&self) -> String {
+        format!(
+            "🩺 Repository health: {} finding{}",
+            self.findings.len(),
+            if self.findings.len() == 1 { "" } else { "s" }
+        )
+    }
+
+    /// 📝 Markdown summary, phrased as a request so it can be processed as feedback
+    pub fn to_markdown(&self, repository: &str, scope: &PathScope) -> String {
+        let mut body = format!(
+            "Feedbacker's scheduled health scan of `{}` (path `{}`, {} files) found some things worth tidying up. \
+             Please address the findings below.\n",
+            repository,
+            scope.display(),
+            self.files_scanned
+        );
+
+        let mut current = None;
+        for finding in &self.findings {
+            if current != Some(finding.check) {
+                current = Some(finding.check);
+                body.push_str(&format!("\n### {}\n\n", check_heading(finding.check)));
+            }
+            body.push_str(&format!("- `{}`: {}\n", finding.path, finding.message));
+        }
+
+        body
+    }
+}
+
+/// 🏷️ Section heading for a check
+fn check_heading(check: HealthCheck) -> &'static str {
+    match check {
+        HealthCheck::OutdatedDocs => "📚 Outdated documentation",
+        HealthCheck::TodoDensity => "📝 TODO density",
+        HealthCheck::MissingTests => "🧪 Missing tests",
+    }
+}
+
+/// 📚 Markdown docs referencing paths that aren't in the repository
+fn outdated_docs(files: &[SourceFile], scope: &PathScope) -> Vec<HealthFinding> {
+    let mut known: HashSet<&str> = HashSet::new();
+    for file in files {
+        let mut path = file.path.as_str();
+        known.insert(path);
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            known.insert(parent);
+            path = parent;
+        }
+    }
+
+    let mut findings = Vec::new();
+    for doc in files.iter().filter(|file| is_markdown(&file.path)) {
+        let doc_dir = doc.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let mut missing: Vec<String> = Vec::new();
+
+        for reference in doc_references(&doc.content) {
+            let bases = [doc_dir, scope.root.as_deref().unwrap_or(""), ""];
+            let candidates: Vec<String> = bases
+                .iter()
+                .filter_map(|base| join_relative(base, &reference))
+                .filter(|candidate| scope.contains(candidate))
+                .collect();
+
+            // 🚪 References that leave the scanned subtree can't be judged
+            if candidates.is_empty() {
+                continue;
+            }
+            let exists = candidates
+                .iter()
+                .any(|candidate| candidate.is_empty() || known.contains(candidate.as_str()));
+            if !exists && !missing.contains(&reference) {
+                missing.push(reference);
+            }
+        }
+
+        if !missing.is_empty() {
+            let listed: Vec<String> = missing.iter().map(|path| format!("`{}`", path)).collect();
+            findings.push(HealthFinding {
+                check: HealthCheck::OutdatedDocs,
+                path: doc.path.clone(),
+                message: format!(
+                    "references paths that no longer exist: {}",
+                    listed.join(", ")
+                ),
+            });
+        }
+    }
+    findings
+}
+
+/// 🔗 Relative paths mentioned in a markdown document (link targets and code spans)
+fn doc_references(content: &str) -> Vec<String> {
+    let mut references = Vec::new();
+
+    // 🔗 [text](target "title")
+    let mut rest = content;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let target = rest[..end].split_whitespace().next().unwrap_or_default();
+        let target = target.split('#').next().unwrap_or_default();
+        if is_relative_link(target) {
+            references.push(target.trim_start_matches("./").to_string());
+        }
+        rest = &rest[end..];
+    }
+
+    // 💻 `some/path.rs` - only spans that clearly look like file paths
+    let mut spans = content.split('`');
+    spans.next();
+    while let Some(span) = spans.next() {
+        if looks_like_path(span) {
+            references.push(span.trim_start_matches("./").to_string());
+        }
+        spans.next();
+    }
+
+    references
+}
+
+/// 🔗 Link targets we can check locally (no URLs, anchors, or absolute paths)
+fn is_relative_link(target: &str) -> bool {
+    !target.is_empty()
+        && !target.contains("://")
+        && !target.starts_with('#')
+        && !target.starts_with('/')
+        && !target.starts_with("mailto:")
+}
+
+/// 🔍 Heuristic for code spans: has a directory, ends in a file extension, no code syntax
+fn looks_like_path(span: &str) -> bool {
+    if span.is_empty()
+        || span.starts_with('/')
+        || span.starts_with('-')
+        || span.contains("://")
+        || span
+            .chars()
+            .any(|c| c.is_whitespace() || "(){}<>[]*$@=:,;|\"'".contains(c))
+    {
+        return false;
+    }
+
+    let Some((_, name)) = span.rsplit_once('/') else {
+        return false;
+    };
+    name.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && !extension.is_empty()
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// 🧭 Join a relative reference onto a base directory, resolving `.` and `..`
+/// Returns None when the reference climbs above the repository root
+fn join_relative(base: &str, reference: &str) -> Option<String> {
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in reference.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// 📝 Source files with a high density of TODO-style markers, worst first
+fn todo_density(files: &[SourceFile]) -> Vec<HealthFinding> {
+    let mut dense: Vec<(usize, usize, &SourceFile)> = files
+        .iter()
+        .filter(|file| is_source(&file.path))
+        .filter_map(|file| {
+            let lines = file.content.lines().count();
+            let markers = file
+                .content
+                .lines()
+                .filter(|line| has_todo_marker(line))
+                .count();
+            (markers >= MIN_TODO_MARKERS && markers * TODO_LINES_PER_MARKER >= lines)
+                .then_some((markers, lines, file))
+        })
+        .collect();
+
+    dense.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.path.cmp(&b.2.path)));
+    dense
+        .into_iter()
+        .map(|(markers, lines, file)| HealthFinding {
+            check: HealthCheck::TodoDensity,
+            path: file.path.clone(),
+            message: format!("{} TODO/FIXME markers in {} lines", markers, lines),
+        })
+        .collect()
+}
+
+/// 📝 Whether a line carries a marker as a whole word (so `TODOS_URL` doesn't count)
+fn has_todo_marker(line: &str) -> bool {
+    TODO_MARKERS.iter().any(|marker| {
+        line.match_indices(marker).any(|(at, _)| {
+            let before = line[..at].chars().next_back();
+            let after = line[at + marker.len()..].chars().next();
+            !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+        })
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 🧪 Sizeable source files with no inline tests and no matching test file, largest first
+fn missing_tests(files: &[SourceFile]) -> Vec<HealthFinding> {
+    let test_paths: Vec<String> = files
+        .iter()
+        .filter(|file| is_source(&file.path) && is_test_path(&file.path))
+        .map(|file| file.path.to_lowercase())
+        .collect();
+
+    let mut untested: Vec<(usize, &SourceFile)> = files
+        .iter()
+        .filter(|file| is_source(&file.path) && !is_test_path(&file.path))
+        .filter(|file| !has_inline_tests(&file.content))
+        .filter_map(|file| {
+            let lines = file.content.lines().count();
+            if lines < MIN_UNTESTED_LINES {
+                return None;
+            }
+            let subject = test_subject(&file.path);
+            let covered = test_paths.iter().any(|test| test.contains(&subject));
+            (!covered).then_some((lines, file))
+        })
+        .collect();
+
+    untested.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+    untested
+        .into_iter()
+        .map(|(lines, file)| HealthFinding {
+            check: HealthCheck::MissingTests,
+            path: file.path.clone(),
+            message: format!("{} lines with no tests", lines),
+        })
+        .collect()
+}
+
+/// 🧪 Name a test file would likely mention (the parent directory for mod.rs & friends)
+fn test_subject(path: &str) -> String {
+    let mut parts = path.rsplit('/');
+    let name = parts.next().unwrap_or(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    let subject = if GENERIC_STEMS.contains(&stem) {
+        parts.next().unwrap_or(stem)
+    } else {
+        stem
+    };
+    subject.to_lowercase()
+}
+
+/// 🧪 Test files by directory or naming convention
+pub fn is_test_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    let mut parts = lower.rsplit('/');
+    let name = parts.next().unwrap_or_default();
+    let in_test_dir = parts.any(|dir| matches!(dir, "test" | "tests" | "spec" | "__tests__"));
+
+    in_test_dir
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// 🧪 Rust-style tests living next to the code
+fn has_inline_tests(content: &str) -> bool {
+    content.contains("#[cfg(test)]") || content.contains("#[test]")
+}
+
+/// 🧪 Whether a path has a source code extension
+pub fn is_source(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, extension)| SOURCE_EXTENSIONS.contains(&extension))
+}
+
+fn is_markdown(path: &str) -> bool {
+    path.to_lowercase().ends_with(".md")
+}
+
+// 🧪 Tests - The doctor gets a checkup too!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> SourceFile {
+        SourceFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn lines(count: usize, line: &str) -> String {
+        vec![line; count].join("\n")
+    }
+
+    #[test]
+    fn test_outdated_docs() {
+        let files = vec![
+            file(
+                "README.md",
+                "See [the API](docs/api.md#routes) and `src/old/handler.rs`.\n\
+                 Config lives in `src/config.rs`, logs via `feedbacker=debug`.\n\
+                 [Home](https://example.com) and `aye-is/Feedbacker`.",
+            ),
+            file(
+                "docs/api.md",
+                "Back to [readme](../README.md), see [gone](../missing.txt)",
+            ),
+            file("src/config.rs", "pub struct Config;"),
+        ];
+
+        let findings = outdated_docs(&files, &PathScope::whole_repository());
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].path, "README.md");
+        assert!(findings[0].message.contains("`src/old/handler.rs`"));
+        assert!(!findings[0].message.contains("config.rs"));
+        assert!(findings[1].message.contains("`../missing.txt`"));
+
+        // 🚪 References outside the scanned subtree are skipped
+        let scoped = vec![file("crates/foo/README.md", "Uses [bar](../bar/README.md)")];
+        let scope = PathScope::new("crates/foo").unwrap();
+        assert!(outdated_docs(&scoped, &scope).is_empty());
+        println!("✅ Outdated docs check test passed!");
+    }
+
+    #[test]
+    fn test_todo_density() {
+        let noisy = format!("{}\n{}", lines(6, "// TODO: fix"), lines(40, "let x = 1;"));
+        let quiet = format!(
+            "{}\n{}",
+            lines(6, "// FIXME later"),
+            lines(400, "let x = 1;")
+        );
+        let files = vec![
+            file("src/noisy.rs", &noisy),
+            file("src/quiet.rs", &quiet),
+            file("src/names.rs", &lines(10, "const TODOS_URL: &str = \"\";")),
+            file("NOTES.md", &lines(20, "TODO")),
+        ];
+
+        let findings = todo_density(&files);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].path, "src/noisy.rs");
+        assert_eq!(findings[0].message, "6 TODO/FIXME markers in 46 lines");
+        println!("✅ TODO density check test passed!");
+    }
+
+    #[test]
+    fn test_missing_tests_and_report() {
+        let body = lines(100, "fn work() {}");
+        let files = vec![
+            file("src/parser.rs", &body),
+            file("tests/parser_roundtrip.rs", "#[test] fn roundtrip() {}"),
+            file(
+                "src/inline.rs",
+                &format!("{}\n#[cfg(test)]\nmod tests {{}}", body),
+            ),
+            file("src/billing/mod.rs", &body),
+            file("web/cart.ts", &body),
+            file("web/cart.spec.ts", "describe('cart', () => {})"),
+            file("src/tiny.rs", "fn tiny() {}"),
+        ];
+
+        let findings = missing_tests(&files);
+        let paths: Vec<&str> = findings.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/billing/mod.rs"]);
+
+        let report = analyze(
+            &files,
+            &[HealthCheck::MissingTests, HealthCheck::MissingTests],
+            &PathScope::whole_repository(),
+        );
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.title(), "🩺 Repository health: 1 finding");
+        let markdown = report.to_markdown("aye-is/Feedbacker", &PathScope::whole_repository());
+        assert!(markdown.contains("### 🧪 Missing tests"));
+        assert!(markdown.contains("- `src/billing/mod.rs`: 100 lines with no tests"));
+        println!("✅ Missing tests check test passed!");
+    }
+}
@@ -0,0 +1,244 @@
+// 🏭 Project Runs - Pipelines on the Worker Pool, at a Polite Pace! 🏭
+// Submitted feedback, dependency updates, docs passes and test generation runs
+// are queued as `project_run` jobs. Before one starts, its project's
+// `processing` settings are checked: while `max_concurrent_runs` runs of the project are going, it waits
+// for one of them to finish, and within `pr_cooldown_minutes` of the last PR
+// opened in the repository, it waits out the cooldown. Waiting runs are
+// deferred, not failed, so a repository with busy CI gets its PRs one at a time
+// instead of five within a minute. A run stopped by its token budget is paused,
+// not failed (see crate::budgets)
+// Created with love by Aye & Hue - Good bots wait their turn! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::worker::{self, Deferred, JobHandler};
+use crate::api::AppState;
+use crate::artifacts;
+use crate::budgets;
+use crate::config::Config;
+use crate::database::models::{
+    BackgroundJob, Feedback, FeedbackEvent, FeedbackStatus, JobPriority, NewBackgroundJob, Project,
+};
+use crate::feedback_trace::{self, Stage};
+use crate::llm::LlmManager;
+use crate::models::ProcessingSettings;
+use crate::pipeline::{run_project_mode, PipelineMode};
+
+/// 🏷️ Job type of a queued project run
+/// (payload: `{"feedback_id": ..., "project_id": ..., "mode": ...}`)
+pub const RUN_JOB: &str = "project_run";
+
+/// ⏳ How long a run waits for a busy project before looking again
+const BUSY_RECHECK_SECONDS: i64 = 60;
+
+/// 📥 Queue a project run tracked by `feedback`
+/// Runs aren't retried: a second attempt could open the same PRs again
+pub async fn queue_project_run(
+    app_state: &AppState,
+    project: &Project,
+    feedback: &Feedback,
+    mode: PipelineMode,
+) -> Result<()> {
+    app_state
+        .jobs
+        .enqueue(&run_job(project, feedback, mode))
+        .await?;
+    Ok(())
+}
+
+/// 📦 The job of a project run tracked by `feedback`
+pub fn run_job(project: &Project, feedback: &Feedback, mode: PipelineMode) -> NewBackgroundJob {
+    let payload = serde_json::json!({
+        "feedback_id": feedback.id,
+        "project_id": project.id,
+        "mode": mode,
+    });
+    NewBackgroundJob::new(RUN_JOB, payload)
+        .with_priority(JobPriority::Normal)
+        .with_user(feedback.user_id)
+        .with_max_retries(1)
+}
+
+/// 🔁 Queue a run again with the payload of its last job. Only for runs that
+/// stopped before opening a PR, like those paused at a token budget
+pub async fn requeue_project_run(
+    app_state: &AppState,
+    run: &BackgroundJob,
+    feedback: &Feedback,
+) -> Result<()> {
+    app_state.jobs.enqueue(&rerun_job(run, feedback)).await?;
+    Ok(())
+}
+
+/// 📦 The job running `run` again, with the same payload
+pub fn rerun_job(run: &BackgroundJob, feedback: &Feedback) -> NewBackgroundJob {
+    NewBackgroundJob::new(RUN_JOB, run.payload.clone())
+        .with_priority(JobPriority::Normal)
+        .with_user(feedback.user_id)
+        .with_max_retries(1)
+}
+
+/// 🚦 Whether a run has to wait, given how many runs of its project are ahead
+/// of it and when the repository last got a PR
+pub fn pacing(
+    settings: &ProcessingSettings,
+    running_ahead: i64,
+    last_pull_request: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<Deferred> {
+    if let Some(max) = settings.max_concurrent_runs {
+        if running_ahead >= max as i64 {
+            return Some(Deferred {
+                reason: format!(
+                    "{} runs of the project are going (at most {})",
+                    running_ahead, max
+                ),
+                until: now + Duration::seconds(BUSY_RECHECK_SECONDS),
+            });
+        }
+    }
+
+    let cooldown = Duration::minutes(settings.pr_cooldown_minutes as i64);
+    let until = last_pull_request? + cooldown;
+    (until > now).then(|| Deferred {
+        reason: format!(
+            "Waiting out the {} minute cooldown after the last pull request",
+            settings.pr_cooldown_minutes
+        ),
+        until,
+    })
+}
+
+/// 🔧 Worker pool handler that runs queued project runs
+pub fn run_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    let llm = app_state.llm_manager.clone();
+    worker::handler(move |job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        let llm = llm.clone();
+        async move { run(&db_pool, &config, &llm, &job).await }
+    })
+}
+
+/// 🏃 Run one queued project run, once its project's pacing allows
+async fn run(pool: &PgPool, config: &Config, llm: &LlmManager, job: &BackgroundJob) -> Result<()> {
+    let id = |key: &str| -> Result<Uuid> {
+        serde_json::from_value(job.payload[key].clone())
+            .with_context(|| format!("Project run job has no {}", key))
+    };
+    let mode: PipelineMode = serde_json::from_value(job.payload["mode"].clone())
+        .context("Project run job has no mode")?;
+    let mut feedback = Feedback::find_by_id(pool, id("feedback_id")?)
+        .await?
+        .context("The feedback tracking this run no longer exists")?;
+    let project = Project::find_by_id(pool, id("project_id")?)
+        .await?
+        .context("The project of this run no longer exists")?;
+    if !project.is_active {
+        // ⛔ Deactivated when the GitHub App lost the repository: cloning would only fail
+        let message = format!("Project {} is inactive", project.repository);
+        warn!("⛔ {:?} run {} skipped: {}", mode, feedback.id, message);
+        feedback
+            .update_status(pool, FeedbackStatus::Failed, Some(message))
+            .await?;
+        return Ok(());
+    }
+
+    let processing = project.settings()?.processing;
+    let running_ahead = BackgroundJob::running_ahead_of(
+        pool,
+        job,
+        &serde_json::json!({ "project_id": project.id }),
+    )
+    .await?;
+    let last_pull_request = match processing.pr_cooldown_minutes {
+        0 => None,
+        _ => FeedbackEvent::last_pull_request_at(pool, &project.repository).await?,
+    };
+    if let Some(deferred) = pacing(&processing, running_ahead, last_pull_request, Utc::now()) {
+        return Err(deferred.into());
+    }
+
+    let outcome = feedback_trace::traced(
+        Stage::Pipeline.span(Some(feedback.id)),
+        run_project_mode(mode, pool, config, llm, &project, &feedback),
+    )
+    .await;
+
+    if let Some(exceeded) = outcome.as_ref().err().and_then(budgets::exceeded_by) {
+        // ⏸️ Not a failure: the run resumes from its checkpoint once more is approved
+        info!("💸 {:?} run {} paused: {}", mode, feedback.id, exceeded);
+        if let Err(e) = budgets::pause(pool, &mut feedback, &project, exceeded).await {
+            warn!("⚠️ Could not pause run {}: {:#}", feedback.id, e);
+        }
+        return Ok(());
+    }
+
+    let (status, error_message) = match &outcome {
+        Ok(results) => {
+            info!(
+                "✅ {:?} run {} opened {} PRs",
+                mode,
+                feedback.id,
+                results.len()
+            );
+            (FeedbackStatus::Completed, None)
+        }
+        Err(e) => {
+            error!("❌ {:?} run {} failed: {:#}", mode, feedback.id, e);
+            (FeedbackStatus::Failed, Some(format!("{:#}", e)))
+        }
+    };
+    if let Err(e) = feedback.update_status(pool, status, error_message).await {
+        warn!("⚠️ Could not update run {} status: {:#}", feedback.id, e);
+    }
+    // 🗄️ Failed runs too: their logs are what debugging needs most
+    if let Err(e) = artifacts::capture(pool, &config.artifacts, feedback.id).await {
+        warn!("⚠️ Could not store the artifacts of run {}: {:#}", feedback.id, e);
+    }
+    outcome.map(|_| ())
+}
+
+// 🧪 Tests - Patience, one PR at a time!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_pacing() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 12, 0, 0).unwrap();
+        let unlimited = ProcessingSettings::default();
+        assert_eq!(pacing(&unlimited, 5, Some(now), now), None);
+
+        let one_at_a_time = ProcessingSettings {
+            max_concurrent_runs: Some(1),
+            pr_cooldown_minutes: 0,
+        };
+        assert_eq!(pacing(&one_at_a_time, 0, Some(now), now), None);
+        let busy = pacing(&one_at_a_time, 1, None, now).unwrap();
+        assert_eq!(busy.until, now + Duration::seconds(BUSY_RECHECK_SECONDS));
+
+        let cooldown = ProcessingSettings {
+            max_concurrent_runs: None,
+            pr_cooldown_minutes: 30,
+        };
+        assert_eq!(pacing(&cooldown, 0, None, now), None);
+        let recent = now - Duration::minutes(10);
+        assert_eq!(
+            pacing(&cooldown, 0, Some(recent), now).unwrap().until,
+            now + Duration::minutes(20)
+        );
+        assert_eq!(
+            pacing(&cooldown, 0, Some(now - Duration::minutes(30)), now),
+            None
+        );
+        println!("✅ Project run pacing test passed!");
+    }
+}
@@ -0,0 +1,265 @@
+// ⏰ Scan Scheduler - Proactive Repository Health Checks! ⏰
+// The `repository_scans` schedule (see schedules.rs) runs a dispatch job every
+// minute that asks which opted-in projects are due according to their own cron
+// schedule, and queues a bulk `repository_scan` job for each. The worker pool
+// runs the scans on a cached sparse clone and files their findings as a
+// suggested feedback item or a GitHub issue, depending on the project's settings.
+// The `proactive_scans` feature flag switches scans off globally or per project
+// Created with love by Aye & Hue - Finding problems before users do! ✨
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use super::queue::JobQueue;
+use super::repo_health::{self, SourceFile};
+use super::worker::{self, JobHandler};
+use crate::api::AppState;
+use crate::config::Config;
+use crate::database::models::{
+    BackgroundJob, Feedback, JobPriority, NewBackgroundJob, Project, RepositoryScan,
+};
+use crate::feature_flags::{names as flag_names, FeatureFlags};
+use crate::github::git::{github_clone_url, CloneCache, CloneOptions};
+use crate::github::{parse_repository, GitHubClient};
+use crate::models::ScanOutput;
+use crate::scm;
+
+/// 🏷️ Job type of a queued scan (payload: `{"project_id": ...}`)
+pub const SCAN_JOB: &str = "repository_scan";
+
+/// 🏷️ Job type that queues the due scans (run by the `repository_scans` schedule)
+pub const SCAN_DISPATCH_JOB: &str = "repository_scan_dispatch";
+
+/// 📏 Files larger than this are skipped by the health checks
+const MAX_SCANNED_FILE_BYTES: usize = 512 * 1024;
+
+/// 🏃 Everything a scan needs, shared between scan jobs
+#[derive(Clone)]
+pub struct ScanRunner {
+    /// ⚙️ Application configuration (shared, so rotated tokens reach running scans)
+    config: Arc<ArcSwap<Config>>,
+    /// 🗄️ Database connection pool
+    db_pool: PgPool,
+    /// 📮 Queue the scans go into
+    jobs: Arc<dyn JobQueue>,
+    /// 🗃️ Clone cache shared with the rest of the service
+    clone_cache: CloneCache,
+    /// 🚩 Runtime feature flags (`proactive_scans` can switch scans off)
+    flags: Arc<FeatureFlags>,
+}
+
+impl ScanRunner {
+    /// ➕ Build a runner from the application state
+    pub fn new(app_state: &AppState) -> Self {
+        let config = app_state.config.load();
+        let github = &config.github;
+        Self {
+            config: app_state.config.clone(),
+            db_pool: app_state.db_pool.clone(),
+            jobs: app_state.jobs.clone(),
+            clone_cache: CloneCache::new(&github.clone_cache_dir, github.clone_cache_size),
+            flags: app_state.flags.clone(),
+        }
+    }
+
+    /// 🔧 Worker pool handler that queues the scans that are due
+    pub fn dispatch_handler(&self) -> JobHandler {
+        let runner = self.clone();
+        worker::handler(move |_job| {
+            let runner = runner.clone();
+            async move { runner.run_due_scans().await }
+        })
+    }
+
+    /// 🔧 Worker pool handler that runs queued scans
+    pub fn scan_handler(&self) -> JobHandler {
+        let runner = self.clone();
+        worker::handler(move |job| {
+            let runner = runner.clone();
+            async move {
+                let project_id: Uuid = serde_json::from_value(job.payload["project_id"].clone())
+                    .context("Scan job has no project_id")?;
+                let project = Project::find_by_id(&runner.db_pool, project_id)
+                    .await?
+                    .with_context(|| format!("Project {} no longer exists", project_id))?;
+                runner.scan_project(&project).await
+            }
+        })
+    }
+
+    /// 🔍 Queue a scan for every opted-in project whose schedule has come around
+    pub async fn run_due_scans(&self) -> Result<()> {
+        let now = Utc::now();
+        let projects = Project::list_scan_enabled(&self.db_pool).await?;
+        debug!("⏰ Checking {} scan-enabled projects", projects.len());
+
+        for project in projects {
+            if !self.scans_allowed(&project).await {
+                continue;
+            }
+
+            let settings = match project.settings() {
+                Ok(settings) => settings,
+                Err(e) => {
+                    warn!("⚠️ Skipping scan for {}: {:#}", project.repository, e);
+                    continue;
+                }
+            };
+
+            let last_scan = RepositoryScan::latest_for_project(&self.db_pool, project.id)
+                .await?
+                .map(|scan| scan.started_at);
+            match settings.scans.is_due(last_scan, now) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!("⚠️ Skipping scan for {}: {:#}", project.repository, e);
+                    continue;
+                }
+            }
+
+            self.queue_scan(&project).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 🚩 Scans run unless the `proactive_scans` flag exists and is off for this project
+    pub async fn scans_allowed(&self, project: &Project) -> bool {
+        let allowed = self
+            .flags
+            .evaluate(flag_names::PROACTIVE_SCANS, Some(project.id))
+            .await
+            .unwrap_or(true);
+        if !allowed {
+            debug!("🚩 Scans are switched off for {}", project.repository);
+        }
+        allowed
+    }
+
+    /// 📥 Queue a scan of a project, unless one is already waiting
+    /// Returns whether it was queued
+    pub async fn queue_scan(&self, project: &Project) -> Result<bool> {
+        // 🔒 A slow or backed-up scan must not be queued twice
+        let payload = serde_json::json!({ "project_id": project.id });
+        if BackgroundJob::is_queued(&self.db_pool, SCAN_JOB, &payload).await? {
+            debug!("⏳ Scan for {} is already queued", project.repository);
+            return Ok(false);
+        }
+
+        let job = NewBackgroundJob::new(SCAN_JOB, payload).with_priority(JobPriority::Bulk);
+        self.jobs.enqueue(&job).await?;
+        debug!("📥 Queued scan for {}", project.repository);
+        Ok(true)
+    }
+
+    /// 🩺 Run one scan, recording the outcome whether it succeeds or not
+    pub async fn scan_project(&self, project: &Project) -> Result<()> {
+        let mut scan = RepositoryScan::start(&self.db_pool, project.id).await?;
+        info!("🩺 Scanning {} (scan {})", project.repository, scan.id);
+
+        match self.analyze_and_file(project, scan.id).await {
+            Ok((findings, feedback_id, issue_url)) => {
+                scan.complete(&self.db_pool, findings, feedback_id, issue_url)
+                    .await
+            }
+            Err(e) => {
+                scan.fail(&self.db_pool, &format!("{:#}", e)).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// 🔬 Check out the project, run its health checks, and file any findings
+    async fn analyze_and_file(
+        &self,
+        project: &Project,
+        scan_id: Uuid,
+    ) -> Result<(serde_json::Value, Option<Uuid>, Option<String>)> {
+        let settings = project.settings()?;
+        let scope = settings.scope()?;
+
+        let github_config =
+            scm::github_config(&self.db_pool, &self.config.load().github, project).await?;
+        let options = CloneOptions {
+            scope: scope.clone(),
+            token: Some(github_config.token.clone()),
+            ..Default::default()
+        };
+        let cache = self.clone_cache.clone();
+        let repository = project.repository.clone();
+        let clone_url = github_clone_url(&github_config.api_base_url, &repository);
+        let checks = settings.scans.checks.clone();
+        let analysis_scope = scope.clone();
+
+        // 🧵 git2 and file reads are blocking
+        let report = tokio::task::spawn_blocking(move || -> Result<_> {
+            let workspace = cache.checkout(&repository, &clone_url, &options)?;
+            let objects = workspace.objects()?;
+            let files: Vec<SourceFile> = objects
+                .filter_context(&workspace.list_files()?)
+                .into_iter()
+                .filter_map(|path| {
+                    let content = workspace.read_file(&path)?;
+                    (content.len() <= MAX_SCANNED_FILE_BYTES)
+                        .then_some(SourceFile { path, content })
+                })
+                .collect();
+            Ok(repo_health::analyze(&files, &checks, &analysis_scope))
+        })
+        .await
+        .context("Scan task panicked")??;
+
+        let findings = serde_json::to_value(&report.findings)?;
+        if report.is_empty() {
+            info!("💚 {} looks healthy, nothing to file", project.repository);
+            return Ok((findings, None, None));
+        }
+
+        let body = report.to_markdown(&project.repository, &scope);
+        match settings.scans.output {
+            ScanOutput::Feedback => {
+                let mut feedback = Feedback::create(
+                    &self.db_pool,
+                    None,
+                    project.repository.clone(),
+                    scope.root.clone(),
+                    body,
+                )
+                .await?;
+                feedback
+                    .merge_metadata(
+                        &self.db_pool,
+                        serde_json::json!({ "source": "repository_scan", "scan_id": scan_id }),
+                    )
+                    .await?;
+                info!(
+                    "📝 Filed {} findings for {} as feedback {}",
+                    report.findings.len(),
+                    project.repository,
+                    feedback.id
+                );
+                Ok((findings, Some(feedback.id), None))
+            }
+            ScanOutput::Issue => {
+                let (owner, repo) = parse_repository(&project.repository)?;
+                let github = GitHubClient::new(github_config)?;
+                let url = github
+                    .create_issue(&owner, &repo, &report.title(), &body)
+                    .await?;
+                info!(
+                    "🎫 Filed {} findings for {} as {}",
+                    report.findings.len(),
+                    project.repository,
+                    url
+                );
+                Ok((findings, None, Some(url)))
+            }
+        }
+    }
+}
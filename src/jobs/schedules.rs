@@ -0,0 +1,367 @@
+// ⏰ Recurring Schedules - Cron Jobs, Queued Like Everything Else! ⏰
+// Rows in scheduled_jobs pair a cron expression with a job type. A single
+// tokio-cron-scheduler tick runs every minute on one instance at a time (an
+// advisory lock keeps the others out), enqueues a job for every schedule that
+// has come due, and moves it on to its next occurrence. Runs missed while the
+// service was down follow the schedule's catch-up policy, and a run is skipped
+// while the previous one is still queued or running (unless overlap is allowed).
+// Operators can pause, resume, and trigger schedules through the admin API
+// Created with love by Aye & Hue - Clockwork you can steer! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use croner::Cron;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{debug, error, info};
+
+use super::queue::JobQueue;
+use super::scheduler::SCAN_DISPATCH_JOB;
+use crate::api::AppState;
+use crate::database::models::{
+    BackgroundJob, CatchUpPolicy, JobPriority, NewBackgroundJob, ScheduledJob,
+};
+use crate::database::with_advisory_lock;
+
+/// 🕐 How often due schedules are checked (every minute, on the minute)
+pub const SCHEDULE_TICK: &str = "0 * * * * *";
+
+/// 🔒 Advisory lock held by the one instance running a tick
+const SCHEDULE_TICK_LOCK: i64 = 0x0046_4253_4348_4544; // "FBSCHED"
+
+/// 🏷️ Job type of the database cleanup
+pub const CLEANUP_JOB: &str = "database_cleanup";
+
+/// ⏳ Runs later than this count as missed for the `skip` policy
+const SKIP_GRACE_SECONDS: i64 = 120;
+
+/// 🔁 Most runs the `all` policy enqueues in one catch-up
+const MAX_CATCH_UP_RUNS: usize = 24;
+
+/// 📋 A schedule every installation has (created on startup, then left to operators)
+pub struct BuiltinSchedule {
+    pub name: &'static str,
+    pub job_type: &'static str,
+    pub cron_expression: &'static str,
+    pub catch_up: CatchUpPolicy,
+}
+
+/// 📋 Built-in schedules
+pub const BUILTIN_SCHEDULES: &[BuiltinSchedule] = &[
+    // 🩺 Queue the repository scans whose per-project schedule has come around
+    BuiltinSchedule {
+        name: "repository_scans",
+        job_type: SCAN_DISPATCH_JOB,
+        cron_expression: "0 * * * * *",
+        catch_up: CatchUpPolicy::Skip,
+    },
+    // 🧹 Nightly cleanup of old records
+    BuiltinSchedule {
+        name: "database_cleanup",
+        job_type: CLEANUP_JOB,
+        cron_expression: "0 0 3 * * *",
+        catch_up: CatchUpPolicy::Once,
+    },
+    // ⏱️ Fail or requeue feedback a crashed worker left mid-pipeline
+    BuiltinSchedule {
+        name: "feedback_watchdog",
+        job_type: crate::feedback_watchdog::WATCHDOG_JOB,
+        cron_expression: "0 */5 * * * *",
+        catch_up: CatchUpPolicy::Skip,
+    },
+    // 📬 Morning activity digests for the users whose digest has come due
+    BuiltinSchedule {
+        name: "email_digests",
+        job_type: crate::digest::DIGEST_JOB,
+        cron_expression: "0 0 8 * * *",
+        catch_up: CatchUpPolicy::Once,
+    },
+    // 👥 Mirror repository collaborators into the projects that opted in
+    BuiltinSchedule {
+        name: "collaborator_sync",
+        job_type: crate::collaborators::COLLABORATOR_SYNC_JOB,
+        cron_expression: "0 20 * * * *",
+        catch_up: CatchUpPolicy::Once,
+    },
+    // 🎨 Learn the style of newly onboarded projects, and refresh stale profiles
+    BuiltinSchedule {
+        name: "style_analysis",
+        job_type: crate::style_guide::STYLE_ANALYSIS_JOB,
+        cron_expression: "0 40 * * * *",
+        catch_up: CatchUpPolicy::Skip,
+    },
+];
+
+/// 🗓️ Parse a schedule's cron expression (UTC, 5 or 6 fields)
+pub fn parse_cron(expression: &str) -> Result<Cron> {
+    Cron::new(expression)
+        .with_seconds_optional()
+        .parse()
+        .with_context(|| format!("Invalid cron expression '{}'", expression))
+}
+
+/// ⏰ First occurrence strictly after `after`
+pub fn next_occurrence(cron: &Cron, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    cron.find_next_occurrence(&after, false)
+        .context("Cron expression never fires")
+}
+
+/// 📅 What a due schedule should do now
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuePlan {
+    /// 🔁 Jobs to enqueue
+    pub runs: usize,
+    /// ⏰ When to look again
+    pub next_run_at: DateTime<Utc>,
+}
+
+/// 📅 Plan the runs of a schedule due at `next_run_at`, checked at `now`
+pub fn plan(
+    cron: &Cron,
+    policy: CatchUpPolicy,
+    next_run_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<DuePlan> {
+    if next_run_at > now {
+        return Ok(DuePlan {
+            runs: 0,
+            next_run_at,
+        });
+    }
+
+    let runs = match policy {
+        CatchUpPolicy::Skip => usize::from((now - next_run_at).num_seconds() <= SKIP_GRACE_SECONDS),
+        CatchUpPolicy::Once => 1,
+        CatchUpPolicy::All => {
+            let mut missed = 1;
+            let mut occurrence = next_run_at;
+            while missed < MAX_CATCH_UP_RUNS {
+                occurrence = next_occurrence(cron, occurrence)?;
+                if occurrence > now {
+                    break;
+                }
+                missed += 1;
+            }
+            missed
+        }
+    };
+
+    Ok(DuePlan {
+        runs,
+        next_run_at: next_occurrence(cron, now)?,
+    })
+}
+
+/// ⏰ Everything a schedule tick needs
+#[derive(Clone)]
+pub struct Schedules {
+    /// 🗄️ Where the schedules live
+    db_pool: PgPool,
+    /// 📮 Where their jobs go
+    jobs: Arc<dyn JobQueue>,
+}
+
+impl Schedules {
+    /// ➕ Schedules of the application
+    pub fn new(app_state: &AppState) -> Self {
+        Self {
+            db_pool: app_state.db_pool.clone(),
+            jobs: app_state.jobs.clone(),
+        }
+    }
+
+    /// 📋 Create the built-in schedules that don't exist yet
+    pub async fn ensure_builtin(&self) -> Result<()> {
+        let now = Utc::now();
+        for builtin in BUILTIN_SCHEDULES {
+            let cron = parse_cron(builtin.cron_expression)?;
+            ScheduledJob::ensure(
+                &self.db_pool,
+                builtin.name,
+                builtin.job_type,
+                builtin.cron_expression,
+                builtin.catch_up,
+                next_occurrence(&cron, now)?,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// ⏰ Enqueue the jobs of every due schedule
+    pub async fn run_due(&self) -> Result<()> {
+        let now = Utc::now();
+        for mut schedule in ScheduledJob::list_due(&self.db_pool, now).await? {
+            if let Err(e) = self.run_schedule(&mut schedule, now).await {
+                error!("❌ Schedule {} failed: {:#}", schedule.name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// ⏰ Enqueue one due schedule's runs and move it on
+    async fn run_schedule(&self, schedule: &mut ScheduledJob, now: DateTime<Utc>) -> Result<()> {
+        let cron = parse_cron(&schedule.cron_expression)?;
+        let plan = plan(&cron, schedule.catch_up_policy(), schedule.next_run_at, now)?;
+
+        let mut runs = plan.runs;
+        if runs == 0 {
+            debug!("🙈 Skipping missed runs of {}", schedule.name);
+        } else if !schedule.allow_overlap && self.is_running(schedule).await? {
+            info!(
+                "⏭️ Skipping {}: its previous run is still going",
+                schedule.name
+            );
+            runs = 0;
+        }
+
+        let mut last_job = None;
+        for _ in 0..runs {
+            last_job = Some(self.enqueue(schedule).await?.id);
+        }
+        schedule
+            .advance(&self.db_pool, plan.next_run_at, last_job)
+            .await
+    }
+
+    /// ▶️ Run a schedule now, outside its cron times (its next run stays as planned)
+    /// None when its previous run is still going and overlap isn't allowed
+    pub async fn trigger(&self, schedule: &mut ScheduledJob) -> Result<Option<BackgroundJob>> {
+        if !schedule.allow_overlap && self.is_running(schedule).await? {
+            return Ok(None);
+        }
+
+        let job = self.enqueue(schedule).await?;
+        schedule
+            .advance(&self.db_pool, schedule.next_run_at, Some(job.id))
+            .await?;
+        Ok(Some(job))
+    }
+
+    /// 🏃 Whether the job of the schedule's last run is still queued or running
+    async fn is_running(&self, schedule: &ScheduledJob) -> Result<bool> {
+        let Some(job_id) = schedule.last_job_id else {
+            return Ok(false);
+        };
+        Ok(BackgroundJob::find_by_id(&self.db_pool, job_id)
+            .await?
+            .is_some_and(|job| {
+                job.status == BackgroundJob::PENDING || job.status == BackgroundJob::RUNNING
+            }))
+    }
+
+    /// 📥 Enqueue one run of a schedule
+    async fn enqueue(&self, schedule: &ScheduledJob) -> Result<BackgroundJob> {
+        let job = NewBackgroundJob::new(&schedule.job_type, schedule.payload.clone())
+            .with_priority(JobPriority::Bulk);
+        let job = self.jobs.enqueue(&job).await?;
+        debug!(
+            "📥 {} queued {} job {}",
+            schedule.name, job.job_type, job.id
+        );
+        Ok(job)
+    }
+}
+
+/// 🚀 Create the built-in schedules and start ticking in the background
+/// The returned scheduler must be kept alive for schedules to keep running
+pub async fn start(schedules: Schedules) -> Result<JobScheduler> {
+    schedules
+        .ensure_builtin()
+        .await
+        .context("Failed to create built-in schedules")?;
+
+    let scheduler = JobScheduler::new()
+        .await
+        .context("Failed to create job scheduler")?;
+
+    let job = Job::new_async(SCHEDULE_TICK, move |_id, _scheduler| {
+        let schedules = schedules.clone();
+        Box::pin(async move {
+            // 🔒 Every instance ticks, but only one enqueues
+            let tick = with_advisory_lock(&schedules.db_pool, SCHEDULE_TICK_LOCK, || {
+                schedules.run_due()
+            });
+            match tick.await {
+                Ok(Some(())) => {}
+                Ok(None) => debug!("⏰ Another instance is running this schedule tick"),
+                Err(e) => error!("❌ Schedule tick failed: {:#}", e),
+            }
+        })
+    })
+    .context("Failed to create schedule tick")?;
+
+    scheduler
+        .add(job)
+        .await
+        .context("Failed to add schedule tick")?;
+    scheduler
+        .start()
+        .await
+        .context("Failed to start job scheduler")?;
+
+    info!("⏰ Recurring job scheduler started");
+    Ok(scheduler)
+}
+
+// 🧪 Tests - Missed runs are caught up exactly as the policy says!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_catch_up_policies() {
+        let hourly = parse_cron("0 * * * *").unwrap();
+        let at = |hour, minute| Utc.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap();
+        let due = at(10, 0);
+
+        // ⏰ Not due yet
+        let early = plan(&hourly, CatchUpPolicy::Once, due, at(9, 59)).unwrap();
+        assert_eq!(early.runs, 0);
+        assert_eq!(early.next_run_at, due);
+
+        // 🕐 On time, every policy runs once and moves on to the next hour
+        for policy in [CatchUpPolicy::Skip, CatchUpPolicy::Once, CatchUpPolicy::All] {
+            let on_time = plan(&hourly, policy, due, at(10, 1)).unwrap();
+            assert_eq!(on_time.runs, 1, "{:?}", policy);
+            assert_eq!(on_time.next_run_at, at(11, 0));
+        }
+
+        // 💤 Down from 10:00 until 13:30: four occurrences were missed
+        let back = at(13, 30);
+        assert_eq!(
+            plan(&hourly, CatchUpPolicy::Skip, due, back).unwrap().runs,
+            0
+        );
+        assert_eq!(
+            plan(&hourly, CatchUpPolicy::Once, due, back).unwrap().runs,
+            1
+        );
+        let all = plan(&hourly, CatchUpPolicy::All, due, back).unwrap();
+        assert_eq!(all.runs, 4);
+        assert_eq!(all.next_run_at, at(14, 0));
+
+        // 🔁 Catch-up is capped
+        let week_later = due + chrono::Duration::days(7);
+        let capped = plan(&hourly, CatchUpPolicy::All, due, week_later).unwrap();
+        assert_eq!(capped.runs, MAX_CATCH_UP_RUNS);
+        println!("✅ Catch-up policy test passed!");
+    }
+
+    #[test]
+    fn test_builtin_schedules_parse() {
+        for builtin in BUILTIN_SCHEDULES {
+            assert!(
+                parse_cron(builtin.cron_expression).is_ok(),
+                "{}",
+                builtin.name
+            );
+        }
+        assert!(parse_cron("every tuesday").is_err());
+        assert_eq!("ALL".parse::<CatchUpPolicy>().unwrap(), CatchUpPolicy::All);
+        assert!("sometimes".parse::<CatchUpPolicy>().is_err());
+        println!("✅ Built-in schedule test passed!");
+    }
+}
@@ -0,0 +1,389 @@
+// 👷 Worker Pool - Queued Jobs, Run With Limits! 👷
+// A single dispatcher claims jobs from the job queue and runs each
+// on its own task. A worker semaphore caps how many run at once, and per-type
+// semaphores cap expensive kinds of work (e.g. only 2 concurrent git clones).
+// Types at their limit are left in the queue instead of taking a worker slot.
+// An idle dispatcher sleeps until the queue announces new work or a job finishes,
+// with a slow fallback poll for scheduled retries and missed announcements.
+// Several instances can share one queue: claims skip rows locked by others,
+// running jobs heartbeat, and a reaper retries jobs whose instance died.
+// In maintenance mode the dispatcher claims nothing; running jobs finish.
+// A handler that can't start yet returns `Deferred`, and its job goes back to
+// the queue until then without using up a retry
+// Created with love by Aye & Hue - Busy, but never overwhelmed! ✨
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn, Instrument};
+use uuid::Uuid;
+
+use super::queue::JobQueue;
+use crate::config::JobsConfig;
+use crate::database::models::BackgroundJob;
+use crate::feedback_trace::{self, Stage};
+use crate::maintenance::Maintenance;
+
+/// 🚧 How often a paused pool checks whether maintenance is over
+const MAINTENANCE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 🔧 Runs one job; an error counts as a failed attempt (retried, then dead-lettered)
+pub type JobHandler =
+    Arc<dyn Fn(BackgroundJob) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// ⏸️ A job that can't run yet: it goes back to the queue until `until`,
+/// without counting as a failed attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deferred {
+    /// 💬 Why it has to wait
+    pub reason: String,
+    /// ⏰ When to try again
+    pub until: DateTime<Utc>,
+}
+
+impl fmt::Display for Deferred {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (deferred until {})", self.reason, self.until)
+    }
+}
+
+impl std::error::Error for Deferred {}
+
+/// 🔧 Wrap an async function as a job handler
+pub fn handler<F, Fut>(f: F) -> JobHandler
+where
+    F: Fn(BackgroundJob) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    Arc::new(move |job| Box::pin(f(job)))
+}
+
+/// 📊 How busy the pool is right now
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerUtilization {
+    /// 👷 This instance's worker id
+    pub instance: String,
+    /// 📮 Queue backend the jobs come from
+    pub backend: &'static str,
+    /// 👷 Worker slots
+    pub workers: usize,
+    /// 🏃 Slots running a job
+    pub busy: usize,
+    /// 🚦 Per-type limits and how much of each is in use
+    pub job_types: BTreeMap<String, TypeUtilization>,
+}
+
+/// 🚦 One job type's limit and current use
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeUtilization {
+    pub limit: usize,
+    pub running: usize,
+}
+
+/// 👷 Shared worker limits; `start` runs the dispatcher
+#[derive(Debug)]
+pub struct WorkerPool {
+    /// 🗄️ Where job state lives (heartbeats, outcomes, reaping)
+    db_pool: PgPool,
+    /// 📮 Where jobs are claimed from
+    queue: Arc<dyn JobQueue>,
+    /// ⚙️ Worker settings
+    config: JobsConfig,
+    /// 👷 Unique id of this instance, stored on the jobs it claims
+    worker_id: String,
+    /// 👷 One permit per worker slot
+    workers: Arc<Semaphore>,
+    /// 🚦 One semaphore per limited job type
+    type_limits: HashMap<String, Arc<Semaphore>>,
+    /// 🏁 Signalled when a job finishes (a type limit may have freed up)
+    finished: Arc<Notify>,
+    /// 🚧 No jobs are claimed while maintenance mode is on
+    maintenance: Option<Arc<Maintenance>>,
+}
+
+impl WorkerPool {
+    /// ➕ Create an idle pool from configuration
+    pub fn new(db_pool: PgPool, queue: Arc<dyn JobQueue>, config: &JobsConfig) -> Self {
+        Self {
+            db_pool,
+            queue,
+            config: config.clone(),
+            worker_id: instance_id(),
+            workers: Arc::new(Semaphore::new(config.workers)),
+            type_limits: config
+                .type_concurrency
+                .iter()
+                .map(|(job_type, limit)| (job_type.clone(), Arc::new(Semaphore::new(*limit))))
+                .collect(),
+            finished: Arc::new(Notify::new()),
+            maintenance: None,
+        }
+    }
+
+    /// 🚧 Pause claiming while maintenance mode is on
+    pub fn with_maintenance(mut self, maintenance: Arc<Maintenance>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// 📊 Current use of the worker slots and type limits
+    pub fn utilization(&self) -> WorkerUtilization {
+        WorkerUtilization {
+            instance: self.worker_id.clone(),
+            backend: self.queue.backend().as_str(),
+            workers: self.config.workers,
+            busy: self.config.workers - self.workers.available_permits(),
+            job_types: self
+                .type_limits
+                .iter()
+                .map(|(job_type, semaphore)| {
+                    let limit = self.config.type_concurrency[job_type];
+                    let running = limit - semaphore.available_permits();
+                    (job_type.clone(), TypeUtilization { limit, running })
+                })
+                .collect(),
+        }
+    }
+
+    /// 🚀 Start dispatching jobs of the handled types, and reaping stale ones, in the background
+    pub fn start(self: Arc<Self>, handlers: HashMap<String, JobHandler>) -> JoinHandle<()> {
+        info!(
+            "👷 Worker pool {} started: {} workers for {:?}",
+            self.worker_id,
+            self.config.workers,
+            handlers.keys().collect::<Vec<_>>()
+        );
+        let reaper = self.clone();
+        tokio::spawn(async move { reaper.reap().await });
+        tokio::spawn(async move { self.dispatch(handlers).await })
+    }
+
+    /// 🪦 Periodically retry jobs whose worker stopped heartbeating (on any instance)
+    async fn reap(&self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.heartbeat_interval_seconds));
+        let stale_after = chrono::Duration::seconds(self.config.stale_after_seconds as i64);
+        loop {
+            interval.tick().await;
+            match BackgroundJob::reap_stale(&self.db_pool, chrono::Utc::now() - stale_after).await {
+                Ok(reaped) => {
+                    for job in reaped {
+                        warn!(
+                            "🪦 Reaped stale {} job {} ({})",
+                            job.job_type,
+                            job.id,
+                            job.error_message.as_deref().unwrap_or_default()
+                        );
+                    }
+                }
+                Err(e) => error!("❌ Failed to reap stale jobs: {:#}", e),
+            }
+        }
+    }
+
+    /// 🔁 Claim and launch jobs whenever a worker slot is free
+    async fn dispatch(&self, handlers: HashMap<String, JobHandler>) {
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        let mut paused = false;
+        loop {
+            if let Some(maintenance) = &self.maintenance {
+                let active = maintenance.is_active().await;
+                if active != paused {
+                    paused = active;
+                    if paused {
+                        info!("🚧 Maintenance mode: worker pool paused");
+                    } else {
+                        info!("▶️ Maintenance mode over: worker pool resumed");
+                    }
+                }
+                if paused {
+                    tokio::time::sleep(poll_interval.min(MAINTENANCE_POLL_INTERVAL)).await;
+                    continue;
+                }
+            }
+
+            let Ok(worker) = self.workers.clone().acquire_owned().await else {
+                return;
+            };
+
+            let job_types = self.claimable_types(&handlers);
+            let job = if job_types.is_empty() {
+                None
+            } else {
+                match self.queue.claim(&job_types, &self.worker_id).await {
+                    Ok(job) => job,
+                    Err(e) => {
+                        error!("❌ Failed to claim a job: {:#}", e);
+                        None
+                    }
+                }
+            };
+
+            let Some(job) = job else {
+                drop(worker);
+                tokio::select! {
+                    _ = self.queue.wait_for_work(poll_interval) => {}
+                    _ = self.finished.notified() => {}
+                }
+                continue;
+            };
+
+            // 🚦 Only this loop takes type permits, and it just saw one free
+            let type_permit = match self.type_limits.get(&job.job_type) {
+                Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => {
+                        warn!("⚠️ {} limit reached after claiming", job.job_type);
+                        None
+                    }
+                },
+                None => None,
+            };
+            let handler = handlers[&job.job_type].clone();
+            let db_pool = self.db_pool.clone();
+            let heartbeat = Duration::from_secs(self.config.heartbeat_interval_seconds);
+            let finished = self.finished.clone();
+            tokio::spawn(async move {
+                run_job(db_pool, handler, job, heartbeat, worker, type_permit).await;
+                finished.notify_one();
+            });
+        }
+    }
+
+    /// 🏷️ Handled job types that are below their concurrency limit
+    fn claimable_types(&self, handlers: &HashMap<String, JobHandler>) -> Vec<String> {
+        handlers
+            .keys()
+            .filter(|job_type| {
+                self.type_limits
+                    .get(*job_type)
+                    .is_none_or(|semaphore| semaphore.available_permits() > 0)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// 👷 Worker id of this process: host name plus a random suffix (restarts get a new one)
+fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "feedbacker".to_string());
+    let suffix = Uuid::new_v4().simple().to_string();
+    format!("{}-{}", host, &suffix[..8])
+}
+
+/// 🏃 Run one job, heartbeating while it runs, and record how it went
+/// The permits are released when it's done
+async fn run_job(
+    db_pool: PgPool,
+    handler: JobHandler,
+    mut job: BackgroundJob,
+    heartbeat: Duration,
+    _worker: OwnedSemaphorePermit,
+    _type_permit: Option<OwnedSemaphorePermit>,
+) {
+    debug!("🏃 Running {} job {}", job.job_type, job.id);
+    // 🧵 Jobs about a feedback item name it in their payload
+    let feedback_id = job
+        .payload
+        .get("feedback_id")
+        .and_then(|id| id.as_str())
+        .and_then(|id| id.parse::<Uuid>().ok());
+    let span = Stage::Job.span(feedback_id);
+    let mut run = handler(job.clone()).instrument(span.clone());
+    let mut ticks = tokio::time::interval(heartbeat);
+    ticks.tick().await; // ⏱️ The first tick fires immediately; the claim is fresh
+    let outcome = loop {
+        tokio::select! {
+            outcome = &mut run => break outcome,
+            _ = ticks.tick() => match job.heartbeat(&db_pool).await {
+                Ok(true) => {}
+                Ok(false) => warn!("⚠️ Lost the claim on job {} while it was running", job.id),
+                Err(e) => warn!("⚠️ Heartbeat for job {} failed: {:#}", job.id, e),
+            },
+        }
+    };
+
+    let recorded = match outcome {
+        Ok(()) => job.complete(&db_pool).await,
+        Err(e) => match e.downcast_ref::<Deferred>() {
+            Some(deferred) => {
+                info!("⏸️ {} job {}: {}", job.job_type, job.id, deferred);
+                job.defer(&db_pool, deferred.until).await
+            }
+            None => {
+                feedback_trace::mark_failed(&span);
+                warn!("❌ {} job {} failed: {:#}", job.job_type, job.id, e);
+                job.fail(&db_pool, &format!("{:#}", e)).await
+            }
+        },
+    };
+    match recorded {
+        Ok(true) if job.status == BackgroundJob::DEAD => {
+            error!("☠️ {} job {} dead-lettered", job.job_type, job.id);
+        }
+        Ok(true) => {}
+        // 🪦 Reaped while we ran; the retry (or dead letter) belongs to whoever has it now
+        Ok(false) => warn!("⚠️ Outcome of job {} dropped: claim was lost", job.id),
+        Err(e) => error!("❌ Could not record the outcome of job {}: {:#}", job.id, e),
+    }
+}
+
+// 🧪 Tests - Limits are counted the way the health endpoint reports them!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QueueBackend;
+    use crate::jobs::queue::PostgresQueue;
+
+    #[tokio::test]
+    async fn test_utilization_and_type_limits() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/feedbacker").unwrap();
+        let config = JobsConfig {
+            workers: 4,
+            poll_interval_ms: 1000,
+            type_concurrency: HashMap::from([("repository_scan".to_string(), 1)]),
+            heartbeat_interval_seconds: 15,
+            stale_after_seconds: 120,
+            backend: QueueBackend::Postgres,
+            redis_url: None,
+            redis_prefix: "feedbacker:jobs".to_string(),
+            nats_url: None,
+            nats_prefix: "feedbacker.jobs".to_string(),
+        };
+        let queue = Arc::new(PostgresQueue::new(db_pool.clone()));
+        let pool = WorkerPool::new(db_pool, queue, &config);
+        let handlers = HashMap::from([
+            ("repository_scan".to_string(), handler(|_| async { Ok(()) })),
+            ("project_run".to_string(), handler(|_| async { Ok(()) })),
+        ]);
+
+        let mut types = pool.claimable_types(&handlers);
+        types.sort();
+        assert_eq!(types, vec!["project_run", "repository_scan"]);
+
+        let _worker = pool.workers.clone().try_acquire_owned().unwrap();
+        let _scan = pool.type_limits["repository_scan"]
+            .clone()
+            .try_acquire_owned()
+            .unwrap();
+        assert_eq!(pool.claimable_types(&handlers), vec!["project_run"]);
+
+        let utilization = pool.utilization();
+        assert_ne!(utilization.instance, instance_id());
+        assert_eq!(utilization.backend, "postgres");
+        assert_eq!(utilization.workers, 4);
+        assert_eq!(utilization.busy, 1);
+        assert_eq!(utilization.job_types["repository_scan"].running, 1);
+        assert_eq!(utilization.job_types["repository_scan"].limit, 1);
+        println!("✅ Worker utilization test passed!");
+    }
+}
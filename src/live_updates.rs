@@ -0,0 +1,137 @@
+// 📡 Live Updates - Feedback News the Moment It Happens! 📡
+// Every feedback event (status changes included) is announced with NOTIFY on
+// FeedbackEvent::CHANNEL by whichever instance recorded it. Each instance keeps
+// one LISTEN connection, loads the announced events and fans them out to its
+// WebSocket subscribers (see api::live), so tracking pages update without polling.
+// Events announced while the listener is reconnecting are missed; subscribers
+// get a fresh snapshot whenever they fall behind
+// Created with love by Aye & Hue - Hot off the pipeline! ✨
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::{api::AppState, cache::CacheNamespace, database::models::FeedbackEvent};
+
+/// 📦 How far a slow subscriber may fall behind before it has to resync
+const CHANNEL_CAPACITY: usize = 256;
+
+/// ⏳ Pause before listening again after the connection dropped
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// 📡 Fan-out of this instance's view of new feedback events
+#[derive(Debug)]
+pub struct LiveUpdates {
+    sender: broadcast::Sender<Arc<FeedbackEvent>>,
+}
+
+impl Default for LiveUpdates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveUpdates {
+    /// ➕ No subscribers yet
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// 👂 Receive every event from now on (filtering is up to the subscriber)
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<FeedbackEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// 📣 Hand an event to the current subscribers, if there are any
+    fn publish(&self, event: FeedbackEvent) {
+        // 🤷 No receivers just means nobody is watching right now
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    /// 👀 Whether anyone is subscribed
+    fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
+
+/// 🚀 Relay announced events to this instance's subscribers until shutdown
+pub fn start(app_state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = relay(&app_state).await {
+                warn!("⚠️ Feedback event listener failed: {:#}", e);
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+/// 🔔 Listen for announcements and publish the events they name
+async fn relay(app_state: &AppState) -> Result<()> {
+    let mut listener = PgListener::connect_with(&app_state.db_pool).await?;
+    listener.listen(FeedbackEvent::CHANNEL).await?;
+    info!("📡 Listening for feedback events");
+
+    loop {
+        let notification = listener.recv().await?;
+        // 🗃️ Statuses change with their feedback, whoever is subscribed
+        app_state
+            .cache
+            .invalidate_all(CacheNamespace::ProjectStatus)
+            .await;
+        if !app_state.live_updates.has_subscribers() {
+            continue;
+        }
+        let Ok(event_id) = notification.payload().parse::<Uuid>() else {
+            warn!(
+                "⚠️ Ignoring malformed feedback event notification: {}",
+                notification.payload()
+            );
+            continue;
+        };
+        match FeedbackEvent::find_by_id(&app_state.db_pool, event_id).await? {
+            Some(event) => app_state.live_updates.publish(event),
+            None => debug!("🔍 Announced feedback event {} is already gone", event_id),
+        }
+    }
+}
+
+// 🧪 Tests - Everyone hears the news!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let live = LiveUpdates::new();
+        assert!(!live.has_subscribers());
+        // 🤷 Nobody listening is fine
+        live.publish(sample_event());
+
+        let mut first = live.subscribe();
+        let mut second = live.subscribe();
+        assert!(live.has_subscribers());
+        let event = sample_event();
+        live.publish(event.clone());
+        assert_eq!(first.try_recv().unwrap().id, event.id);
+        assert_eq!(second.try_recv().unwrap().id, event.id);
+        assert!(first.try_recv().is_err());
+        println!("✅ Live update fan-out test passed!");
+    }
+
+    fn sample_event() -> FeedbackEvent {
+        FeedbackEvent {
+            id: Uuid::new_v4(),
+            feedback_id: Uuid::new_v4(),
+            event_type: FeedbackEvent::STATUS_CHANGED.to_string(),
+            payload: serde_json::json!({ "status": "processing" }),
+            created_at: chrono::Utc::now(),
+            version: 1,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// 🎭 Anthropic Provider - Messages API over HTTP! 🎭
+// Created with love by Aye & Hue! ✨
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{structured::arguments_to_content, CompletionRequest, CompletionResponse, TokenUsage};
+use crate::config::{AnthropicConfig, LlmProvider};
+
+/// 🌐 Messages endpoint
+const MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+/// 🌐 Models endpoint (a cheap authenticated GET for health pings)
+const MODELS_URL: &str = "https://api.anthropic.com/v1/models";
+/// 📅 API version header value
+const API_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    model: String,
+    content: Vec<ContentBlock>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+    /// 🔧 Arguments of a `tool_use` block
+    input: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+/// 🚀 Send a messages request
+pub async fn complete(
+    http: &reqwest::Client,
+    config: &AnthropicConfig,
+    request: &CompletionRequest,
+) -> Result<CompletionResponse> {
+    let response = http
+        .post(MESSAGES_URL)
+        .header("x-api-key", &config.api_key)
+        .header("anthropic-version", API_VERSION)
+        .json(&request_body(config, request))
+        .send()
+        .await
+        .context("Failed to reach Anthropic")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        anyhow::bail!("Anthropic returned {}: {}", status, text);
+    }
+
+    let message: MessagesResponse = response
+        .json()
+        .await
+        .context("Failed to parse Anthropic response")?;
+    into_response(message)
+}
+
+/// 🏓 Check that the API is reachable and the key is accepted
+pub async fn ping(
+    http: &reqwest::Client,
+    config: &AnthropicConfig,
+    timeout: std::time::Duration,
+) -> Result<()> {
+    let response = http
+        .get(MODELS_URL)
+        .header("x-api-key", &config.api_key)
+        .header("anthropic-version", API_VERSION)
+        .timeout(timeout)
+        .send()
+        .await
+        .context("Failed to reach Anthropic")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Anthropic returned {}", status);
+    }
+    Ok(())
+}
+
+/// 📦 Request body, forcing the schema's tool when structured output is requested
+fn request_body(config: &AnthropicConfig, request: &CompletionRequest) -> serde_json::Value {
+    let messages: Vec<_> = request
+        .messages
+        .iter()
+        .map(|message| json!({ "role": message.role, "content": message.content }))
+        .collect();
+
+    let mut body = json!({
+        "model": request.model.as_deref().unwrap_or(&config.default_model),
+        "messages": messages,
+        "max_tokens": request.max_tokens.unwrap_or(config.max_tokens),
+    });
+    if let Some(system) = &request.system {
+        body["system"] = json!(system);
+    }
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if let Some(schema) = &request.output_schema {
+        body["tools"] = json!([{
+            "name": schema.name,
+            "description": schema.description,
+            "input_schema": schema.schema,
+        }]);
+        body["tool_choice"] = json!({ "type": "tool", "name": schema.name });
+    }
+    body
+}
+
+/// 📥 Provider-neutral response (a `tool_use` block wins over text when present)
+fn into_response(message: MessagesResponse) -> Result<CompletionResponse> {
+    let tool_input = message
+        .content
+        .iter()
+        .find(|block| block.kind == "tool_use")
+        .and_then(|block| block.input.as_ref());
+    let content = match tool_input {
+        Some(input) => arguments_to_content(input)?,
+        None => message
+            .content
+            .iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text.as_deref())
+            .collect::<Vec<_>>()
+            .join(""),
+    };
+
+    if content.is_empty() {
+        anyhow::bail!("Anthropic response contained no text content");
+    }
+
+    Ok(CompletionResponse {
+        content,
+        provider: LlmProvider::Anthropic,
+        model: message.model,
+        usage: message
+            .usage
+            .map(|u| TokenUsage {
+                prompt_tokens: u.input_tokens,
+                completion_tokens: u.output_tokens,
+            })
+            .unwrap_or_default(),
+    })
+}
+
+// 🧪 Tests - Tools in, arguments out!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    #[test]
+    fn test_structured_request_and_response() {
+        let config = AnthropicConfig {
+            api_key: "sk-ant-test".to_string(),
+            default_model: "claude".to_string(),
+            max_tokens: 100,
+            context_window: None,
+            small_model: None,
+        };
+        let request = CompletionRequest {
+            output_schema: Some(OutputSchema {
+                name: "submit_plan".to_string(),
+                description: "Submit the plan".to_string(),
+                schema: json!({ "type": "object" }),
+            }),
+            ..CompletionRequest::new(None, "Plan it")
+        };
+
+        let body = request_body(&config, &request);
+        assert_eq!(
+            body["tools"][0]["input_schema"],
+            json!({ "type": "object" })
+        );
+        assert_eq!(
+            body["tool_choice"],
+            json!({ "type": "tool", "name": "submit_plan" })
+        );
+
+        let message: MessagesResponse = serde_json::from_value(json!({
+            "model": "claude",
+            "content": [
+                { "type": "text", "text": "Here you go" },
+                { "type": "tool_use", "id": "t1", "name": "submit_plan", "input": { "steps": [] } }
+            ],
+            "usage": { "input_tokens": 7, "output_tokens": 3 }
+        }))
+        .unwrap();
+        let response = into_response(message).unwrap();
+        assert_eq!(response.content, "{\"steps\":[]}");
+        println!("✅ Anthropic structured output test passed!");
+    }
+}
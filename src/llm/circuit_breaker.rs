@@ -0,0 +1,144 @@
+// 🔌 Circuit Breaker - Stop Knocking on Doors That Won't Open! 🔌
+// One breaker per provider. After `threshold` consecutive failed calls the
+// breaker opens and the provider is skipped; once the cooldown passes, a single
+// half-open probe is let through. A successful probe closes the breaker, a
+// failed one opens it for another cooldown
+// Created with love by Aye & Hue - Failing fast is a kindness! ✨
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 🚦 Breaker state as reported to health checks
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// ✅ Calls flow normally
+    Closed,
+    /// 🛑 Calls are skipped until the cooldown passes
+    Open,
+    /// 🔍 One probe call is allowed to test recovery
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// 🔢 Consecutive failures while closed
+    failures: u32,
+    /// 🛑 When the breaker last opened (None = closed)
+    opened_at: Option<Instant>,
+    /// 🔍 A half-open probe is in flight
+    probing: bool,
+}
+
+/// 🔌 Per-provider circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// 🔢 Consecutive failures that open the breaker
+    threshold: u32,
+    /// ⏱️ How long the breaker stays open before probing
+    cooldown: Duration,
+    /// 🔒 Mutable state
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    /// ➕ Create a closed breaker
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner {
+                failures: 0,
+                opened_at: None,
+                probing: false,
+            }),
+        }
+    }
+
+    /// 🚦 Current state
+    pub fn state(&self, now: Instant) -> CircuitState {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) < self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 🎟️ Ask to make a call; false means skip this provider
+    /// In the half-open state only one caller gets through until it reports back
+    pub fn try_acquire(&self, now: Instant) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => true,
+            Some(opened_at) if now.duration_since(opened_at) < self.cooldown => false,
+            Some(_) if inner.probing => false,
+            Some(_) => {
+                inner.probing = true;
+                true
+            }
+        }
+    }
+
+    /// ✅ A call succeeded: close the breaker
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = 0;
+        inner.opened_at = None;
+        inner.probing = false;
+    }
+
+    /// ❌ A call failed: count it, and open the breaker at the threshold (or on a failed probe)
+    pub fn record_failure(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.opened_at.is_some() {
+            inner.opened_at = Some(now);
+            inner.probing = false;
+            return;
+        }
+
+        inner.failures += 1;
+        if inner.failures >= self.threshold {
+            inner.opened_at = Some(now);
+        }
+    }
+}
+
+// 🧪 Tests - The breaker must trip, rest, and recover!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+        let start = Instant::now();
+
+        assert!(breaker.try_acquire(start));
+        breaker.record_failure(start);
+        assert_eq!(breaker.state(start), CircuitState::Closed);
+        breaker.record_failure(start);
+        assert_eq!(breaker.state(start), CircuitState::Open);
+        assert!(!breaker.try_acquire(start + Duration::from_secs(10)));
+
+        // 🔍 After the cooldown exactly one probe gets through
+        let later = start + Duration::from_secs(31);
+        assert_eq!(breaker.state(later), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire(later));
+        assert!(!breaker.try_acquire(later));
+
+        // ❌ A failed probe re-opens for a fresh cooldown
+        breaker.record_failure(later);
+        assert!(!breaker.try_acquire(later + Duration::from_secs(29)));
+        let retry = later + Duration::from_secs(31);
+        assert!(breaker.try_acquire(retry));
+
+        // ✅ A successful probe closes it and resets the count
+        breaker.record_success();
+        assert_eq!(breaker.state(retry), CircuitState::Closed);
+        breaker.record_failure(retry);
+        assert_eq!(breaker.state(retry), CircuitState::Closed);
+        println!("✅ Circuit breaker test passed!");
+    }
+}
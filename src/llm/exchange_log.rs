@@ -0,0 +1,290 @@
+// 📜 Exchange Log - Prompts and Responses You Can Actually Look At! 📜
+// When a prompt change makes the pipeline worse, the only real evidence is the
+// exact text that went back and forth. Exchanges are stored (redacted) when
+// ENABLE_DEV_FEATURES is on, or when the project opted in via `prompt_logging`.
+// Token usage is added to the feedback's and the project's totals for every call,
+// logged or not, and a call over the trace's spend caps is refused before it's made.
+// Logging never fails a call - a storage error is only a warning
+// Created with love by Aye & Hue - Debugging prompts with evidence! ✨
+
+use arc_swap::ArcSwap;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::{CompletionRequest, CompletionResponse};
+use crate::budgets::{self, SpendLimits};
+use crate::config::{LlmProvider, ModelTier};
+use crate::database::models::{Feedback, LlmExchange, NewLlmExchange, Project};
+use crate::utils::redaction::Redactor;
+
+/// 🏷️ Who a call was made for, attached to the request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExchangeTrace {
+    /// 🏷️ Pipeline stage (prompt template name)
+    pub stage: String,
+    /// 📝 Feedback being processed
+    pub feedback_id: Option<Uuid>,
+    /// 🏠 Project being processed
+    pub project_id: Option<Uuid>,
+    /// ✅ The project opted in to prompt logging
+    pub opted_in: bool,
+    /// 🧭 The project's model tier overrides per stage (see `LlmManager::route`)
+    pub routing: HashMap<String, ModelTier>,
+    /// 💸 Token caps the call has to stay under (see crate::budgets)
+    pub budget: SpendLimits,
+    /// 🤖 Provider tried before the configured chain (the submitter's default)
+    pub provider: Option<LlmProvider>,
+}
+
+impl ExchangeTrace {
+    /// ➕ Trace for a call made on behalf of a feedback item
+    pub fn new(stage: &str, feedback_id: Uuid, project_id: Uuid, opted_in: bool) -> Self {
+        Self {
+            stage: stage.to_string(),
+            feedback_id: Some(feedback_id),
+            project_id: Some(project_id),
+            opted_in,
+            routing: HashMap::new(),
+            budget: SpendLimits::default(),
+            provider: None,
+        }
+    }
+
+    /// 🧭 Same trace with the project's model tier overrides
+    pub fn with_routing(self, routing: HashMap<String, ModelTier>) -> Self {
+        Self { routing, ..self }
+    }
+
+    /// 💸 Same trace with the project's spend caps
+    pub fn with_budget(self, budget: SpendLimits) -> Self {
+        Self { budget, ..self }
+    }
+
+    /// 🤖 Same trace, trying `provider` first when it's configured
+    pub fn with_provider(self, provider: Option<LlmProvider>) -> Self {
+        Self { provider, ..self }
+    }
+
+    /// 🏷️ Same trace for a different stage
+    pub fn with_stage(&self, stage: &str) -> Self {
+        Self {
+            stage: stage.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+/// 📜 Where exchanges are stored, and how they are redacted first
+#[derive(Debug, Clone)]
+pub struct ExchangeLog {
+    /// 🗄️ Database the exchanges go to
+    pool: PgPool,
+    /// 🙈 Scrubs secrets before anything is stored (replaced when secrets rotate)
+    redactor: Arc<ArcSwap<Redactor>>,
+    /// 🧪 Log every call, opted in or not (ENABLE_DEV_FEATURES, shared by every clone)
+    log_all: Arc<AtomicBool>,
+}
+
+impl ExchangeLog {
+    /// ➕ Create an exchange log
+    pub fn new(pool: PgPool, redactor: Redactor, log_all: bool) -> Self {
+        Self {
+            pool,
+            redactor: Arc::new(ArcSwap::from_pointee(redactor)),
+            log_all: Arc::new(AtomicBool::new(log_all)),
+        }
+    }
+
+    /// 🙈 Scrub a new set of secrets from now on
+    pub fn set_redactor(&self, redactor: Redactor) {
+        self.redactor.store(Arc::new(redactor));
+    }
+
+    /// 🧪 Start or stop logging every call (on a config reload)
+    pub fn set_log_all(&self, log_all: bool) {
+        self.log_all.store(log_all, Ordering::Relaxed);
+    }
+
+    /// ✅ Whether a call with this trace is logged
+    pub fn should_log(&self, trace: Option<&ExchangeTrace>) -> bool {
+        self.log_all.load(Ordering::Relaxed) || trace.is_some_and(|trace| trace.opted_in)
+    }
+
+    /// 💸 Refuse a call whose feedback or project is over its spend caps
+    pub async fn check_budget(&self, request: &CompletionRequest) -> anyhow::Result<()> {
+        match &request.trace {
+            Some(trace) => budgets::check(&self.pool, trace).await,
+            None => Ok(()),
+        }
+    }
+
+    /// 💾 Store one call (the whole fallback chain) and its outcome
+    pub async fn record(
+        &self,
+        request: &CompletionRequest,
+        result: &anyhow::Result<CompletionResponse>,
+        duration: Duration,
+    ) {
+        // 📊 Token usage is counted on the feedback whether or not the exchange is stored
+        if let (Some(feedback_id), Ok(response)) = (
+            request.trace.as_ref().and_then(|trace| trace.feedback_id),
+            result,
+        ) {
+            if let Err(e) = Feedback::add_token_usage(
+                &self.pool,
+                feedback_id,
+                response.usage.prompt_tokens.into(),
+                response.usage.completion_tokens.into(),
+            )
+            .await
+            {
+                warn!("⚠️ Could not count token usage: {:#}", e);
+            }
+        }
+        // 🗓️ And on the project's month, for its monthly cap
+        if let (Some(project_id), Ok(response)) = (
+            request.trace.as_ref().and_then(|trace| trace.project_id),
+            result,
+        ) {
+            if let Err(e) = Project::add_token_usage(
+                &self.pool,
+                project_id,
+                response.usage.prompt_tokens.into(),
+                response.usage.completion_tokens.into(),
+            )
+            .await
+            {
+                warn!("⚠️ Could not count project token usage: {:#}", e);
+            }
+        }
+
+        if !self.should_log(request.trace.as_ref()) {
+            return;
+        }
+
+        let exchange = build_exchange(&self.redactor.load(), request, result, duration);
+        match LlmExchange::record(&self.pool, &exchange).await {
+            Ok(stored) => debug!("📜 Stored LLM exchange {}", stored.id),
+            Err(e) => warn!("⚠️ Could not store LLM exchange: {:#}", e),
+        }
+    }
+}
+
+/// 🙈 Redacted row for a call
+fn build_exchange(
+    redactor: &Redactor,
+    request: &CompletionRequest,
+    result: &anyhow::Result<CompletionResponse>,
+    duration: Duration,
+) -> NewLlmExchange {
+    let trace = request.trace.as_ref();
+    let mut exchange = NewLlmExchange {
+        feedback_id: trace.and_then(|trace| trace.feedback_id),
+        project_id: trace.and_then(|trace| trace.project_id),
+        stage: trace.map(|trace| trace.stage.clone()),
+        request: request_json(redactor, request),
+        duration_ms: duration.as_millis() as i64,
+        ..Default::default()
+    };
+
+    match result {
+        Ok(response) => {
+            exchange.provider = Some(response.provider.as_str().to_string());
+            exchange.model = Some(response.model.clone());
+            exchange.response = Some(redactor.redact(&response.content));
+            exchange.prompt_tokens = response.usage.prompt_tokens as i32;
+            exchange.completion_tokens = response.usage.completion_tokens as i32;
+        }
+        Err(e) => exchange.error_message = Some(redactor.redact(&format!("{:#}", e))),
+    }
+    exchange
+}
+
+/// 📤 The parts of a request worth reading later, redacted
+fn request_json(redactor: &Redactor, request: &CompletionRequest) -> Value {
+    json!({
+        "system": request.system.as_deref().map(|system| redactor.redact(system)),
+        "messages": request
+            .messages
+            .iter()
+            .map(|message| json!({ "role": message.role, "content": redactor.redact(&message.content) }))
+            .collect::<Vec<_>>(),
+        "model": request.model,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+        "output_schema": request.output_schema.as_ref().map(|schema| &schema.name),
+    })
+}
+
+// 🧪 Tests - What gets stored must already be clean!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LlmProvider;
+    use crate::llm::TokenUsage;
+
+    #[test]
+    fn test_exchange_is_redacted() {
+        let redactor = Redactor::new(vec!["sk-test-1234567890".to_string()]);
+        let feedback_id = Uuid::new_v4();
+        let mut request = CompletionRequest::new(
+            Some("Key: sk-test-1234567890".to_string()),
+            "Fix login for jane@example.com",
+        );
+        request.trace = Some(ExchangeTrace::new(
+            "change_plan",
+            feedback_id,
+            Uuid::new_v4(),
+            true,
+        ));
+
+        let response = CompletionResponse {
+            content: "Use password=s3cr3t-value please".to_string(),
+            provider: LlmProvider::Anthropic,
+            model: "claude".to_string(),
+            usage: TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 5,
+            },
+        };
+        let exchange = build_exchange(
+            &redactor,
+            &request,
+            &Ok(response),
+            Duration::from_millis(1500),
+        );
+
+        assert_eq!(exchange.feedback_id, Some(feedback_id));
+        assert_eq!(exchange.stage.as_deref(), Some("change_plan"));
+        assert_eq!(exchange.provider.as_deref(), Some("anthropic"));
+        assert_eq!(exchange.duration_ms, 1500);
+        assert_eq!(exchange.request["system"], "Key: [REDACTED]");
+        assert_eq!(
+            exchange.request["messages"][0]["content"],
+            "Fix login for [REDACTED_EMAIL]"
+        );
+        assert_eq!(
+            exchange.response.as_deref(),
+            Some("Use password=[REDACTED] please")
+        );
+
+        let failed = build_exchange(
+            &redactor,
+            &request,
+            &Err(anyhow::anyhow!("401 for key sk-test-1234567890")),
+            Duration::ZERO,
+        );
+        assert_eq!(failed.provider, None);
+        assert_eq!(
+            failed.error_message.as_deref(),
+            Some("401 for key [REDACTED]")
+        );
+        println!("✅ Exchange redaction test passed!");
+    }
+}
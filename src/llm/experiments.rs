@@ -0,0 +1,244 @@
+// 🧪 Prompt Experiments - Evaluate Prompt Changes Instead of Guessing! 🧪
+// Each pipeline stage can have stored prompt versions. The active version
+// replaces the built-in template; candidate versions get a percentage of
+// feedback items. Every feedback item lands in a stable bucket per stage, the
+// version it was served is recorded, and outcomes (PR merged, CI passed, user
+// approval) are aggregated per version
+// Created with love by Aye & Hue - Let the numbers pick the wording! ✨
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::prompts::{self, names, PromptTemplate};
+use crate::database::models::{PromptAssignment, PromptVersion};
+
+/// 🏷️ Pipeline stages whose prompts can be versioned
+pub const EXPERIMENT_STAGES: &[&str] = &[
+    names::CHANGE_PLAN,
+    names::FILE_EDIT,
+    names::DOCS_EDIT,
+    names::TEST_FILE,
+    names::REBASE_FILE,
+    names::PR_RISK,
+];
+
+/// 🎲 Stable 0..100 bucket of a feedback item for one stage (FNV-1a)
+/// Stages hash independently, so one experiment doesn't skew another
+pub fn traffic_bucket(feedback_id: Uuid, name: &str) -> u32 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in feedback_id.as_bytes().iter().chain(name.as_bytes()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    (hash % 100) as u32
+}
+
+/// 🎯 Version serving a bucket: candidates take their traffic share first (oldest
+/// first), the rest goes to the active version, or None for the built-in template
+pub fn choose_version(versions: &[PromptVersion], bucket: u32) -> Option<&PromptVersion> {
+    let mut threshold = 0;
+    for candidate in versions
+        .iter()
+        .filter(|version| !version.is_active && version.traffic_percent > 0)
+    {
+        threshold += candidate.traffic_percent as u32;
+        if bucket < threshold {
+            return Some(candidate);
+        }
+    }
+    versions.iter().find(|version| version.is_active)
+}
+
+/// ✅ Check a candidate body before it is stored
+/// It may only use placeholders the pipeline fills in for the built-in template
+pub fn validate_candidate(name: &str, body: &str) -> Result<(), Vec<String>> {
+    if !EXPERIMENT_STAGES.contains(&name) {
+        return Err(vec![format!(
+            "'{}' is not a versioned prompt (expected one of: {})",
+            name,
+            EXPERIMENT_STAGES.join(", ")
+        )]);
+    }
+    if body.trim().is_empty() {
+        return Err(vec!["Prompt body is empty".to_string()]);
+    }
+
+    let known = prompts::builtin(name)
+        .map(|template| template.placeholders())
+        .unwrap_or_default();
+    let errors: Vec<String> = PromptTemplate::new(name, body)
+        .placeholders()
+        .into_iter()
+        .filter(|placeholder| !known.contains(placeholder))
+        .map(|placeholder| {
+            format!(
+                "Unknown placeholder '{{{{{}}}}}' (available: {})",
+                placeholder,
+                known.join(", ")
+            )
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 🎲 Check that a stage's candidates don't claim more than all traffic
+/// `changed` is the version getting `percent`; the active version doesn't count
+pub fn validate_traffic(
+    versions: &[PromptVersion],
+    changed: i32,
+    percent: i32,
+) -> Result<(), String> {
+    if !(0..=100).contains(&percent) {
+        return Err("Traffic must be between 0 and 100 percent".to_string());
+    }
+    let total: i32 = versions
+        .iter()
+        .filter(|version| !version.is_active && version.version != changed)
+        .map(|version| version.traffic_percent)
+        .sum::<i32>()
+        + percent;
+    if total > 100 {
+        return Err(format!(
+            "Candidates would get {}% of traffic; the total can't exceed 100%",
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// 📚 The prompt versions chosen for one feedback item
+#[derive(Debug, Clone, Default)]
+pub struct PromptBook {
+    /// 📜 Chosen template per stage (stages not listed use the built-in)
+    templates: HashMap<String, PromptTemplate>,
+}
+
+impl PromptBook {
+    /// 🎲 Choose a version for each stage and record the assignment
+    pub async fn load(pool: &PgPool, feedback_id: Uuid, stages: &[&str]) -> Result<Self> {
+        let mut templates = HashMap::new();
+        for stage in stages {
+            let versions = PromptVersion::list(pool, Some(stage)).await?;
+            let template = match choose_version(&versions, traffic_bucket(feedback_id, stage)) {
+                Some(chosen) => PromptTemplate::versioned(stage, &chosen.body, chosen.version),
+                None => builtin(stage)?,
+            };
+
+            debug!(
+                "🎲 Feedback {} uses {} v{}",
+                feedback_id, stage, template.version
+            );
+            PromptAssignment::record(pool, feedback_id, stage, template.version).await?;
+            templates.insert(stage.to_string(), template);
+        }
+        Ok(Self { templates })
+    }
+
+    /// 📜 Template for a stage: the chosen version, or the built-in one
+    pub fn template(&self, name: &str) -> Result<PromptTemplate> {
+        match self.templates.get(name) {
+            Some(template) => Ok(template.clone()),
+            None => builtin(name),
+        }
+    }
+}
+
+/// 📚 Built-in template, failing clearly when the name is unknown
+fn builtin(name: &str) -> Result<PromptTemplate> {
+    prompts::builtin(name).with_context(|| format!("Prompt template '{}' is missing", name))
+}
+
+// 🧪 Tests - Same feedback, same prompt; traffic splits as configured!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn version(number: i32, traffic_percent: i32, is_active: bool) -> PromptVersion {
+        PromptVersion {
+            id: Uuid::new_v4(),
+            name: names::CHANGE_PLAN.to_string(),
+            version: number,
+            body: format!("v{} {{{{feedback}}}}", number),
+            description: None,
+            traffic_percent,
+            is_active,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_version_selection() {
+        let feedback_id = Uuid::new_v4();
+        let bucket = traffic_bucket(feedback_id, names::CHANGE_PLAN);
+        assert!(bucket < 100);
+        assert_eq!(bucket, traffic_bucket(feedback_id, names::CHANGE_PLAN));
+
+        // 🎲 Buckets spread roughly evenly
+        let low = (0..1000)
+            .filter(|_| traffic_bucket(Uuid::new_v4(), names::FILE_EDIT) < 20)
+            .count();
+        assert!(
+            (100..300).contains(&low),
+            "{} of 1000 in the 20% bucket",
+            low
+        );
+
+        assert!(choose_version(&[], 5).is_none());
+        let versions = vec![
+            version(1, 0, true),
+            version(2, 10, false),
+            version(3, 15, false),
+        ];
+        assert_eq!(choose_version(&versions, 0).unwrap().version, 2);
+        assert_eq!(choose_version(&versions, 9).unwrap().version, 2);
+        assert_eq!(choose_version(&versions, 10).unwrap().version, 3);
+        assert_eq!(choose_version(&versions, 24).unwrap().version, 3);
+        assert_eq!(choose_version(&versions, 25).unwrap().version, 1);
+
+        // 📚 Without an active version the rest stays on the built-in template
+        let candidates_only = vec![version(1, 50, false)];
+        assert!(choose_version(&candidates_only, 50).is_none());
+        println!("✅ Prompt version selection test passed!");
+    }
+
+    #[test]
+    fn test_candidate_validation() {
+        assert!(
+            validate_candidate(names::CHANGE_PLAN, "Plan {{feedback}} for {{ repository }}")
+                .is_ok()
+        );
+        let errors = validate_candidate(
+            names::CHANGE_PLAN,
+            "Plan {{feedback}} using {{secret_sauce}}",
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("'{{secret_sauce}}'"));
+        assert!(validate_candidate(names::PR_DESCRIPTION, "{{summary}}").is_err());
+        assert!(validate_candidate(names::DOCS_EDIT, "  ").is_err());
+
+        let versions = vec![
+            version(1, 0, true),
+            version(2, 60, false),
+            version(3, 30, false),
+        ];
+        assert!(validate_traffic(&versions, 3, 40).is_ok());
+        assert!(validate_traffic(&versions, 3, 41).is_err());
+        assert!(validate_traffic(&versions, 1, 10).is_ok());
+        assert!(validate_traffic(&versions, 2, -1).is_err());
+
+        let book = PromptBook::default();
+        assert_eq!(book.template(names::TEST_FILE).unwrap().version, 0);
+        assert!(book.template("does_not_exist").is_err());
+        println!("✅ Prompt candidate validation test passed!");
+    }
+}
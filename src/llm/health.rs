@@ -0,0 +1,171 @@
+// 🏓 Provider Health - Knock Before You Need to Walk In! 🏓
+// Health checks ping each configured provider with a cheap authenticated call
+// (its models list) under a short timeout. Results are cached so a busy health
+// endpoint doesn't turn into API traffic, and they feed the circuit breaker: a
+// failed ping counts as a failure, and a successful ping while half-open is the
+// recovery probe that closes it. An open breaker isn't pinged at all
+// Created with love by Aye & Hue - Know it's down before the pipeline does! ✨
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use super::{anthropic, circuit_breaker::CircuitState, openai, LlmManager};
+use crate::config::LlmProvider;
+
+/// 🏓 Result of a provider health ping
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderPing {
+    /// ✅ The provider answered and accepted our credentials
+    pub reachable: bool,
+    /// ⏱️ Round trip of the ping (None when it wasn't sent)
+    pub latency_ms: Option<u64>,
+    /// 💬 Why the provider is unreachable
+    pub error: Option<String>,
+    /// 🚦 Circuit breaker state after the ping
+    pub circuit: CircuitState,
+    /// 🕒 When the ping was made
+    pub checked_at: DateTime<Utc>,
+}
+
+impl LlmManager {
+    /// 🏓 Health of a provider, pinging it unless a recent result is cached
+    /// None when the provider isn't configured
+    pub async fn check_health(&self, provider: &LlmProvider) -> Option<ProviderPing> {
+        if !self.is_configured(provider) {
+            return None;
+        }
+
+        let max_age = Duration::from_secs(self.config().health_check_cache_seconds);
+        if let Some((pinged_at, ping)) = self.health.lock().unwrap().get(provider) {
+            if pinged_at.elapsed() < max_age {
+                return Some(ProviderPing {
+                    circuit: self.circuit_state(provider),
+                    ..ping.clone()
+                });
+            }
+        }
+
+        let ping = self.ping(provider).await;
+        self.health
+            .lock()
+            .unwrap()
+            .insert(*provider, (Instant::now(), ping.clone()));
+        Some(ping)
+    }
+
+    /// 📡 Ping a provider and report the result to its circuit breaker
+    async fn ping(&self, provider: &LlmProvider) -> ProviderPing {
+        let breaker = self.breakers.get(provider);
+        let was_closed = self.circuit_state(provider) == CircuitState::Closed;
+
+        // 🛑 Open breakers are left alone; half-open ones get this ping as their one probe
+        if !breaker.is_none_or(|breaker| breaker.try_acquire(Instant::now())) {
+            return ProviderPing {
+                reachable: false,
+                latency_ms: None,
+                error: Some("Circuit open, waiting for cooldown".to_string()),
+                circuit: self.circuit_state(provider),
+                checked_at: Utc::now(),
+            };
+        }
+
+        let config = self.config();
+        let timeout = Duration::from_secs(config.health_check_timeout_seconds);
+        let started = Instant::now();
+        let result = match provider {
+            LlmProvider::OpenAi => match &config.openai {
+                Some(config) => openai::ping(&self.http, config, timeout).await,
+                None => Err(anyhow::anyhow!("OpenAI is not configured")),
+            },
+            LlmProvider::Anthropic => match &config.anthropic {
+                Some(config) => anthropic::ping(&self.http, config, timeout).await,
+                None => Err(anyhow::anyhow!("Anthropic is not configured")),
+            },
+            LlmProvider::Custom => match &config.custom {
+                Some(config) => openai::ping_custom(&self.http, config, timeout).await,
+                None => Err(anyhow::anyhow!("Custom LLM endpoint is not configured")),
+            },
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let error = match result {
+            Ok(()) => {
+                debug!("🏓 {} answered in {}ms", provider.as_str(), latency_ms);
+                // ✅ Only a probe changes the breaker; a healthy ping while closed
+                // shouldn't wipe out failures real calls are counting
+                if !was_closed {
+                    if let Some(breaker) = breaker {
+                        breaker.record_success();
+                    }
+                }
+                None
+            }
+            Err(e) => {
+                warn!("🏓 {} health ping failed: {:#}", provider.as_str(), e);
+                if let Some(breaker) = breaker {
+                    breaker.record_failure(Instant::now());
+                }
+                Some(format!("{:#}", e))
+            }
+        };
+
+        ProviderPing {
+            reachable: error.is_none(),
+            latency_ms: Some(latency_ms),
+            error,
+            circuit: self.circuit_state(provider),
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+// 🧪 Tests - An unreachable provider shows up as one, once per cache window!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CustomLlmConfig, LlmConfig, RoutingConfig};
+
+    #[tokio::test]
+    async fn test_unreachable_provider_trips_breaker() {
+        let manager = LlmManager::new(&LlmConfig {
+            openai: None,
+            anthropic: None,
+            custom: Some(CustomLlmConfig {
+                base_url: "http://127.0.0.1:9/v1".to_string(),
+                api_key: None,
+                default_model: "local-model".to_string(),
+                temperature: 0.2,
+                max_tokens: 1000,
+                context_window: None,
+                small_model: None,
+                supports_tools: true,
+            }),
+            default_provider: LlmProvider::Custom,
+            timeout_seconds: 1,
+            max_retries: 0,
+            fallback_providers: Vec::new(),
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: RoutingConfig {
+                tiers: Default::default(),
+                small_prompt_tokens: 100,
+            },
+        });
+
+        assert!(manager.check_health(&LlmProvider::OpenAi).await.is_none());
+
+        let ping = manager.check_health(&LlmProvider::Custom).await.unwrap();
+        assert!(!ping.reachable);
+        assert!(ping.error.is_some());
+        assert_eq!(ping.circuit, CircuitState::Open);
+
+        // 🗄️ The second check is served from the cache
+        let cached = manager.check_health(&LlmProvider::Custom).await.unwrap();
+        assert_eq!(cached.checked_at, ping.checked_at);
+        println!("✅ Provider health ping test passed!");
+    }
+}
@@ -1,2 +1,656 @@
 // 🤖 LLM Integration Module - AI Magic! 🤖
-// TODO: Implement OpenAI, Anthropic, and other LLM integrations
+// This module talks to our AI friends (OpenAI, Anthropic) over plain HTTP
+// Every provider speaks the same CompletionRequest/CompletionResponse language! 🗣️
+// Calls walk a fallback chain (default provider first) and skip providers whose
+// circuit breaker is open, so one provider's outage doesn't stall the pipeline
+// Requests are token-counted first: a provider whose context window can't hold
+// the request is skipped, and if none can, the call fails with a clear error
+// Opted-in calls are stored, redacted, in the exchange log for debugging, and
+// calls over their feedback's or project's token budget are refused up front
+// Model names can be switched on a config reload; everything else stays as built
+// Created with love by Aye & Hue - Making AI calls boring (in the best way)! ✨
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::config::{Config, LlmConfig, LlmProvider};
+use crate::feedback_trace::{self, Stage};
+use crate::metrics;
+use crate::utils::redaction::Redactor;
+use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use exchange_log::{ExchangeLog, ExchangeTrace};
+pub use experiments::PromptBook;
+pub use health::ProviderPing;
+pub use structured::{OutputSchema, StructuredOutput};
+pub use tokens::{ContextBudget, ContextItem, ContextSelection};
+
+pub mod anthropic; // 🎭 Anthropic Messages API
+pub mod circuit_breaker; // 🔌 Per-provider circuit breakers
+pub mod exchange_log; // 📜 Opt-in, redacted prompt/response logging
+pub mod experiments; // 🧪 Prompt versions and A/B traffic splits
+pub mod health; // 🏓 Cached provider health pings
+pub mod openai; // 🧠 OpenAI (and compatible) Chat Completions API
+pub mod prompts; // 📜 Prompt and markdown templates
+pub mod routing; // 🧭 Small models for small tasks
+pub mod structured; // 🧱 Schema-constrained output via tool calling
+pub mod tokens; // 🔢 Token counting and context-window budgets
+
+/// 🗣️ Who is speaking in a conversation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 👤 The user (us, on behalf of the feedback)
+    User,
+    /// 🤖 The model
+    Assistant,
+}
+
+/// 💬 A single message in a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// 🗣️ Speaker
+    pub role: Role,
+    /// 📝 Message text
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// 👤 Create a user message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    /// 🤖 Create an assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// 📤 Provider-neutral completion request
+#[derive(Debug, Clone, Default)]
+pub struct CompletionRequest {
+    /// 💬 System message (project-specific instructions)
+    pub system: Option<String>,
+    /// 🗣️ Conversation so far
+    pub messages: Vec<ChatMessage>,
+    /// 🤖 Model override (provider default when None)
+    pub model: Option<String>,
+    /// 📏 Maximum tokens to generate (provider default when None)
+    pub max_tokens: Option<u32>,
+    /// 🌡️ Sampling temperature (provider default when None)
+    pub temperature: Option<f32>,
+    /// 🧱 Force a reply matching this schema via tool calling (free text when None)
+    pub output_schema: Option<OutputSchema>,
+    /// 🏷️ Who the call is for, used by the exchange log and model routing (untraced when None)
+    pub trace: Option<ExchangeTrace>,
+}
+
+impl CompletionRequest {
+    /// ➕ Single-turn request with an optional system message
+    pub fn new(system: Option<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            system,
+            messages: vec![ChatMessage::user(prompt)],
+            ..Default::default()
+        }
+    }
+
+    /// 🏷️ Attach a trace for the exchange log
+    pub fn traced(self, trace: Option<ExchangeTrace>) -> Self {
+        Self { trace, ..self }
+    }
+}
+
+/// 📊 Token usage reported by the provider
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenUsage {
+    /// 📥 Tokens in the prompt
+    pub prompt_tokens: u32,
+    /// 📤 Tokens in the completion
+    pub completion_tokens: u32,
+}
+
+impl TokenUsage {
+    /// ➕ Total tokens billed
+    pub fn total(&self) -> u32 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// 📥 Provider-neutral completion response
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionResponse {
+    /// 📝 Generated text (the tool arguments as JSON for structured requests)
+    pub content: String,
+    /// 🤖 Provider that answered
+    pub provider: LlmProvider,
+    /// 🤖 Model that answered
+    pub model: String,
+    /// 📊 Token usage
+    pub usage: TokenUsage,
+}
+
+/// 🤖 Entry point for all LLM calls
+#[derive(Debug, Clone)]
+pub struct LlmManager {
+    /// ⚙️ LLM configuration (model names are swapped in on reload)
+    config: Arc<ArcSwap<LlmConfig>>,
+    /// 🌐 Shared HTTP client (connection pooling for free!)
+    http: reqwest::Client,
+    /// 🔌 One circuit breaker per provider, shared by every clone of the manager
+    breakers: Arc<HashMap<LlmProvider, CircuitBreaker>>,
+    /// 🏓 Latest health ping per provider, and when it was made
+    health: Arc<Mutex<HashMap<LlmProvider, (Instant, ProviderPing)>>>,
+    /// 📜 Where opted-in exchanges are stored (nothing is stored when None)
+    exchange_log: Option<ExchangeLog>,
+}
+
+impl LlmManager {
+    /// ➕ Create a manager from configuration
+    pub fn new(config: &LlmConfig) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        let cooldown = Duration::from_secs(config.circuit_breaker_cooldown_seconds);
+        let breakers = [
+            LlmProvider::OpenAi,
+            LlmProvider::Anthropic,
+            LlmProvider::Custom,
+        ]
+            .into_iter()
+            .map(|provider| {
+                (
+                    provider,
+                    CircuitBreaker::new(config.circuit_breaker_threshold, cooldown),
+                )
+            })
+            .collect();
+
+        Self {
+            config: Arc::new(ArcSwap::from_pointee(config.clone())),
+            http,
+            breakers: Arc::new(breakers),
+            health: Arc::new(Mutex::new(HashMap::new())),
+            exchange_log: None,
+        }
+    }
+
+    /// 📜 Store opted-in exchanges in this log
+    pub fn with_exchange_log(self, exchange_log: ExchangeLog) -> Self {
+        Self {
+            exchange_log: Some(exchange_log),
+            ..self
+        }
+    }
+
+    /// ⚙️ The configuration in use (as built, with the latest model names)
+    pub fn config(&self) -> Arc<LlmConfig> {
+        self.config.load_full()
+    }
+
+    /// 🔄 Switch to the default and small model names of `config`
+    /// Credentials, endpoints and limits stay as the manager was built
+    pub fn reload_models(&self, config: &LlmConfig) {
+        let mut current = LlmConfig::clone(&self.config.load());
+        if let (Some(current), Some(new)) = (current.openai.as_mut(), &config.openai) {
+            current.default_model = new.default_model.clone();
+            current.small_model = new.small_model.clone();
+        }
+        if let (Some(current), Some(new)) = (current.anthropic.as_mut(), &config.anthropic) {
+            current.default_model = new.default_model.clone();
+            current.small_model = new.small_model.clone();
+        }
+        if let (Some(current), Some(new)) = (current.custom.as_mut(), &config.custom) {
+            current.default_model = new.default_model.clone();
+            current.small_model = new.small_model.clone();
+        }
+        self.config.store(Arc::new(current));
+    }
+
+    /// 🔑 Switch to the rotated API keys of `config`, and redact them from logged exchanges
+    pub fn reload_credentials(&self, config: &Config) {
+        let mut current = LlmConfig::clone(&self.config.load());
+        if let (Some(current), Some(new)) = (current.openai.as_mut(), &config.llm.openai) {
+            current.api_key = new.api_key.clone();
+        }
+        if let (Some(current), Some(new)) = (current.anthropic.as_mut(), &config.llm.anthropic) {
+            current.api_key = new.api_key.clone();
+        }
+        if let (Some(current), Some(new)) = (current.custom.as_mut(), &config.llm.custom) {
+            current.api_key = new.api_key.clone();
+        }
+        self.config.store(Arc::new(current));
+        if let Some(exchange_log) = &self.exchange_log {
+            exchange_log.set_redactor(Redactor::from_config(config));
+        }
+    }
+
+    /// 🧪 Log every exchange, opted in or not (no-op without an exchange log)
+    pub fn set_log_all_exchanges(&self, log_all: bool) {
+        if let Some(exchange_log) = &self.exchange_log {
+            exchange_log.set_log_all(log_all);
+        }
+    }
+
+    /// 🔍 Whether a provider has credentials configured
+    pub fn is_configured(&self, provider: &LlmProvider) -> bool {
+        let config = self.config.load();
+        match provider {
+            LlmProvider::OpenAi => config.openai.is_some(),
+            LlmProvider::Anthropic => config.anthropic.is_some(),
+            LlmProvider::Custom => config.custom.is_some(),
+        }
+    }
+
+    /// 🪂 Providers in the order they are tried: default first, then configured fallbacks
+    pub fn provider_chain(&self) -> Vec<LlmProvider> {
+        let config = self.config.load();
+        let mut chain = vec![config.default_provider];
+        for provider in &config.fallback_providers {
+            if !chain.contains(provider) {
+                chain.push(*provider);
+            }
+        }
+        chain.retain(|provider| self.is_configured(provider));
+        chain
+    }
+
+    /// 🪂 Provider chain for one request: the trace's preferred provider (a
+    /// user's default) goes first when it's configured, the rest keep their order
+    pub fn provider_chain_for(&self, request: &CompletionRequest) -> Vec<LlmProvider> {
+        let mut chain = self.provider_chain();
+        let preferred = request.trace.as_ref().and_then(|trace| trace.provider);
+        if let Some(provider) = preferred.filter(|provider| self.is_configured(provider)) {
+            chain.retain(|other| *other != provider);
+            chain.insert(0, provider);
+        }
+        chain
+    }
+
+    /// 🚦 Circuit breaker state of a provider
+    pub fn circuit_state(&self, provider: &LlmProvider) -> CircuitState {
+        self.breakers
+            .get(provider)
+            .map(|breaker| breaker.state(Instant::now()))
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// 📏 Token budget of a provider for a request (its model override and reply size)
+    pub fn budget_for(
+        &self,
+        provider: &LlmProvider,
+        request: &CompletionRequest,
+    ) -> Option<ContextBudget> {
+        let config = self.config.load();
+        let (model, max_tokens, window) = match provider {
+            LlmProvider::OpenAi => config.openai.as_ref().map(|config| {
+                (
+                    &config.default_model,
+                    config.max_tokens,
+                    config.context_window,
+                )
+            })?,
+            LlmProvider::Anthropic => config.anthropic.as_ref().map(|config| {
+                (
+                    &config.default_model,
+                    config.max_tokens,
+                    config.context_window,
+                )
+            })?,
+            LlmProvider::Custom => config.custom.as_ref().map(|config| {
+                (
+                    &config.default_model,
+                    config.max_tokens,
+                    config.context_window,
+                )
+            })?,
+        };
+        Some(ContextBudget::new(
+            *provider,
+            request.model.as_deref().unwrap_or(model),
+            window,
+            request.max_tokens.unwrap_or(max_tokens) as usize,
+        ))
+    }
+
+    /// 📏 Tightest budget across the fallback chain
+    /// Prompts planned against it fit whichever provider ends up answering
+    pub fn context_budget(&self, request: &CompletionRequest) -> Result<ContextBudget> {
+        self.provider_chain_for(request)
+            .iter()
+            .filter_map(|provider| self.budget_for(provider, &self.route(provider, request)))
+            .min_by_key(ContextBudget::input_limit)
+            .context("No LLM provider is configured (set OPENAI_API_KEY or ANTHROPIC_API_KEY)")
+    }
+
+    /// 🚀 Complete using the fallback chain
+    /// The response records which provider actually answered
+    pub async fn complete(&self, request: &CompletionRequest) -> Result<CompletionResponse> {
+        // 💸 Nothing is spent past a budget until someone approves it
+        if let Some(exchange_log) = &self.exchange_log {
+            exchange_log.check_budget(request).await?;
+        }
+        let started = Instant::now();
+        let feedback_id = request.trace.as_ref().and_then(|trace| trace.feedback_id);
+        let result = feedback_trace::traced(
+            Stage::Llm.span(feedback_id),
+            self.complete_with_fallback(request),
+        )
+        .await;
+        if let Some(exchange_log) = &self.exchange_log {
+            exchange_log
+                .record(request, &result, started.elapsed())
+                .await;
+        }
+        result
+    }
+
+    /// 🪂 Walk the provider chain until one answers, routing the request per provider
+    async fn complete_with_fallback(
+        &self,
+        request: &CompletionRequest,
+    ) -> Result<CompletionResponse> {
+        let chain = self.provider_chain_for(request);
+        if chain.is_empty() {
+            anyhow::bail!(
+                "No LLM provider is configured (set OPENAI_API_KEY or ANTHROPIC_API_KEY)"
+            );
+        }
+
+        let mut failures = Vec::new();
+        let mut overflows = Vec::new();
+        let chain_len = chain.len();
+        for provider in chain {
+            let request = self.route(&provider, request);
+            let request = request.as_ref();
+
+            // 🔢 A request that can't fit is never sent (and isn't the provider's fault)
+            if let Some(overflow) = self
+                .budget_for(&provider, request)
+                .and_then(|budget| budget.overflow(budget.count_request(request)))
+            {
+                debug!("🔢 Skipping {:?}: {}", provider, overflow);
+                metrics::record_llm_request(provider.as_str(), "too_large");
+                overflows.push(overflow);
+                continue;
+            }
+
+            let breaker = &self.breakers[&provider];
+            if !breaker.try_acquire(Instant::now()) {
+                debug!("🔌 Skipping {:?}: circuit is open", provider);
+                metrics::record_llm_request(provider.as_str(), "skipped");
+                failures.push(format!("{}: circuit open", provider.as_str()));
+                continue;
+            }
+
+            match self.complete_with(&provider, request).await {
+                Ok(response) => {
+                    breaker.record_success();
+                    metrics::record_llm_request(provider.as_str(), "success");
+                    if !failures.is_empty() {
+                        info!(
+                            "🪂 Fell back to {:?} after: {}",
+                            provider,
+                            failures.join("; ")
+                        );
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    breaker.record_failure(Instant::now());
+                    metrics::record_llm_request(provider.as_str(), "failure");
+                    warn!(
+                        "⚠️ {:?} failed, trying the next provider: {:#}",
+                        provider, e
+                    );
+                    failures.push(format!("{}: {:#}", provider.as_str(), e));
+                }
+            }
+        }
+
+        if overflows.len() == chain_len {
+            anyhow::bail!(
+                "Request is too large for every configured model: {}",
+                overflows.join("; ")
+            );
+        }
+        failures.extend(overflows);
+        anyhow::bail!("All LLM providers failed: {}", failures.join("; "))
+    }
+
+    /// 🎯 Complete using a specific provider, retrying transient failures
+    pub async fn complete_with(
+        &self,
+        provider: &LlmProvider,
+        request: &CompletionRequest,
+    ) -> Result<CompletionResponse> {
+        let config = self.config.load_full();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            debug!("🤖 LLM call to {:?} (attempt {})", provider, attempt);
+
+            let result = match provider {
+                LlmProvider::OpenAi => {
+                    let provider_config = config
+                        .openai
+                        .as_ref()
+                        .context("OpenAI is not configured (set OPENAI_API_KEY)")?;
+                    openai::complete(&self.http, provider_config, request).await
+                }
+                LlmProvider::Anthropic => {
+                    let provider_config = config
+                        .anthropic
+                        .as_ref()
+                        .context("Anthropic is not configured (set ANTHROPIC_API_KEY)")?;
+                    anthropic::complete(&self.http, provider_config, request).await
+                }
+                LlmProvider::Custom => {
+                    let provider_config = config
+                        .custom
+                        .as_ref()
+                        .context("Custom LLM endpoint is not configured (set CUSTOM_LLM_BASE_URL and CUSTOM_LLM_MODEL)")?;
+                    openai::complete_custom(&self.http, provider_config, request).await
+                }
+            };
+
+            match result {
+                Ok(response) => {
+                    info!(
+                        "✅ LLM response from {:?}/{} ({} tokens)",
+                        response.provider,
+                        response.model,
+                        response.usage.total()
+                    );
+                    return Ok(response);
+                }
+                Err(e) if attempt <= config.max_retries => {
+                    let backoff = Duration::from_millis(500 * 2u64.pow(attempt - 1));
+                    warn!(
+                        "⚠️ LLM call to {:?} failed (attempt {}), retrying in {:?}: {:#}",
+                        provider, attempt, backoff, e
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// 🧹 Pull a JSON payload out of a model reply
+/// Models love wrapping JSON in ``` fences or chatty preambles
+pub fn extract_json(text: &str) -> Option<&str> {
+    let trimmed = text.trim();
+    if let Some(fenced) = trimmed.strip_prefix("```") {
+        let body = fenced
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or(fenced);
+        if let Some(end) = body.rfind("```") {
+            return Some(body[..end].trim());
+        }
+    }
+
+    let start = trimmed.find(['{', '['])?;
+    let end = trimmed.rfind(['}', ']'])?;
+    (end > start).then(|| &trimmed[start..=end])
+}
+
+// 🧪 Tests - Making sure our AI plumbing is leak-free!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_from_fenced_block() {
+        let reply = "```json\n{\"steps\": []}\n```";
+        assert_eq!(extract_json(reply), Some("{\"steps\": []}"));
+        println!("✅ Fenced JSON extraction test passed!");
+    }
+
+    #[test]
+    fn test_extract_json_from_chatty_reply() {
+        let reply = "Sure! Here's the plan: {\"summary\": \"x\"} Hope that helps.";
+        assert_eq!(extract_json(reply), Some("{\"summary\": \"x\"}"));
+        assert_eq!(extract_json("no json here"), None);
+        println!("✅ Chatty JSON extraction test passed!");
+    }
+
+    #[test]
+    fn test_provider_chain() {
+        let key = |api_key: &str| crate::config::AnthropicConfig {
+            api_key: api_key.to_string(),
+            default_model: "claude".to_string(),
+            max_tokens: 100,
+            context_window: None,
+            small_model: None,
+        };
+        let mut config = LlmConfig {
+            openai: None,
+            anthropic: Some(key("sk-ant")),
+            custom: None,
+            default_provider: LlmProvider::OpenAi,
+            timeout_seconds: 1,
+            max_retries: 0,
+            fallback_providers: vec![LlmProvider::Anthropic, LlmProvider::OpenAi],
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: Default::default(),
+        };
+
+        // 🪂 Unconfigured providers drop out of the chain
+        let manager = LlmManager::new(&config);
+        assert_eq!(manager.provider_chain(), vec![LlmProvider::Anthropic]);
+        assert_eq!(
+            manager.circuit_state(&LlmProvider::Anthropic),
+            CircuitState::Closed
+        );
+
+        // 🤖 A preferred provider goes first, but only when it's configured
+        let preferring = |provider| {
+            CompletionRequest::new(None, "hi")
+                .traced(Some(ExchangeTrace::default().with_provider(Some(provider))))
+        };
+        assert_eq!(
+            manager.provider_chain_for(&preferring(LlmProvider::OpenAi)),
+            vec![LlmProvider::Anthropic]
+        );
+        config.openai = Some(crate::config::OpenAiConfig {
+            api_key: "sk".to_string(),
+            default_model: "gpt".to_string(),
+            temperature: 0.0,
+            max_tokens: 100,
+            context_window: None,
+            small_model: None,
+        });
+        config.default_provider = LlmProvider::Anthropic;
+        config.fallback_providers = vec![LlmProvider::OpenAi];
+        let manager = LlmManager::new(&config);
+        assert_eq!(
+            manager.provider_chain_for(&preferring(LlmProvider::OpenAi)),
+            vec![LlmProvider::OpenAi, LlmProvider::Anthropic]
+        );
+        assert_eq!(
+            manager.provider_chain_for(&CompletionRequest::default()),
+            vec![LlmProvider::Anthropic, LlmProvider::OpenAi]
+        );
+
+        config.openai = None;
+        config.default_provider = LlmProvider::OpenAi;
+        config.fallback_providers.clear();
+        let manager = LlmManager::new(&config);
+        assert!(manager.provider_chain().is_empty());
+        println!("✅ Provider chain test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_fails_before_sending() {
+        let config = LlmConfig {
+            openai: Some(crate::config::OpenAiConfig {
+                api_key: "sk-test".to_string(),
+                default_model: "gpt-4".to_string(),
+                temperature: 0.2,
+                max_tokens: 2000,
+                context_window: None,
+                small_model: None,
+            }),
+            anthropic: None,
+            custom: None,
+            default_provider: LlmProvider::OpenAi,
+            timeout_seconds: 1,
+            max_retries: 0,
+            fallback_providers: Vec::new(),
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: Default::default(),
+        };
+        let manager = LlmManager::new(&config);
+        let request = CompletionRequest::new(None, "word ".repeat(7000));
+
+        let budget = manager.context_budget(&request).unwrap();
+        assert_eq!(budget.input_limit(), 8192 - 2000);
+
+        // 🚫 No HTTP call is made, and the breaker stays closed
+        let error = manager.complete(&request).await.unwrap_err().to_string();
+        assert!(
+            error.contains("too large for every configured model"),
+            "{}",
+            error
+        );
+        assert!(error.contains("openai/gpt-4 needs"), "{}", error);
+        assert_eq!(
+            manager.circuit_state(&LlmProvider::OpenAi),
+            CircuitState::Closed
+        );
+        println!("✅ Oversized request test passed!");
+    }
+
+    #[test]
+    fn test_token_usage_total() {
+        let usage = TokenUsage {
+            prompt_tokens: 120,
+            completion_tokens: 30,
+        };
+        assert_eq!(usage.total(), 150);
+        println!("✅ Token usage test passed!");
+    }
+}
@@ -0,0 +1,374 @@
+// 🧠 OpenAI Provider - Chat Completions over HTTP! 🧠
+// Also speaks for the `custom` provider: any backend with an OpenAI-compatible
+// /chat/completions endpoint (vLLM, LM Studio, Together, ...)
+// Created with love by Aye & Hue! ✨
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{structured::arguments_to_content, CompletionRequest, CompletionResponse, TokenUsage};
+use crate::config::{CustomLlmConfig, LlmProvider, OpenAiConfig};
+
+/// 🌐 API base URL
+const API_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// 🔌 Where a chat completions request goes, and its defaults
+struct Endpoint<'a> {
+    /// 🤖 Provider reported on responses
+    provider: LlmProvider,
+    /// 🏷️ Name used in errors
+    label: &'static str,
+    /// 🌐 API base URL, up to and including the version
+    base_url: String,
+    /// 🔑 Bearer token (None for backends without auth)
+    api_key: Option<&'a str>,
+    /// 🤖 Model when the request doesn't pick one
+    default_model: &'a str,
+    /// 🌡️ Temperature when the request doesn't pick one
+    temperature: f32,
+    /// 📏 Maximum reply tokens when the request doesn't pick them
+    max_tokens: u32,
+    /// 🧱 Structured output via tools (instructions in the prompt otherwise)
+    supports_tools: bool,
+}
+
+impl<'a> Endpoint<'a> {
+    /// 🧠 api.openai.com
+    fn openai(config: &'a OpenAiConfig) -> Self {
+        Self {
+            provider: LlmProvider::OpenAi,
+            label: "OpenAI",
+            base_url: API_BASE_URL.to_string(),
+            api_key: Some(&config.api_key),
+            default_model: &config.default_model,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            supports_tools: true,
+        }
+    }
+
+    /// 🔌 An OpenAI-compatible backend
+    fn custom(config: &'a CustomLlmConfig) -> Self {
+        Self {
+            provider: LlmProvider::Custom,
+            label: "Custom LLM endpoint",
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.as_deref().filter(|key| !key.is_empty()),
+            default_model: &config.default_model,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            supports_tools: config.supports_tools,
+        }
+    }
+
+    /// 🌐 URL of an API path (e.g. "chat/completions")
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// 🔑 Request with the bearer token attached, when there is one
+    fn authorized(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletion {
+    model: String,
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    function: FunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    arguments: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+/// 🚀 Send a chat completion request to OpenAI
+pub async fn complete(
+    http: &reqwest::Client,
+    config: &OpenAiConfig,
+    request: &CompletionRequest,
+) -> Result<CompletionResponse> {
+    send(http, &Endpoint::openai(config), request).await
+}
+
+/// 🔌 Send a chat completion request to an OpenAI-compatible backend
+pub async fn complete_custom(
+    http: &reqwest::Client,
+    config: &CustomLlmConfig,
+    request: &CompletionRequest,
+) -> Result<CompletionResponse> {
+    send(http, &Endpoint::custom(config), request).await
+}
+
+/// 🏓 Check that OpenAI is reachable and the key is accepted
+pub async fn ping(
+    http: &reqwest::Client,
+    config: &OpenAiConfig,
+    timeout: std::time::Duration,
+) -> Result<()> {
+    ping_endpoint(http, &Endpoint::openai(config), timeout).await
+}
+
+/// 🏓 Check that an OpenAI-compatible backend is reachable
+pub async fn ping_custom(
+    http: &reqwest::Client,
+    config: &CustomLlmConfig,
+    timeout: std::time::Duration,
+) -> Result<()> {
+    ping_endpoint(http, &Endpoint::custom(config), timeout).await
+}
+
+/// 🏓 GET the models list: cheap, authenticated, and supported by compatible servers
+async fn ping_endpoint(
+    http: &reqwest::Client,
+    endpoint: &Endpoint<'_>,
+    timeout: std::time::Duration,
+) -> Result<()> {
+    let response = endpoint
+        .authorized(http.get(endpoint.url("models")))
+        .timeout(timeout)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", endpoint.label))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("{} returned {}", endpoint.label, status);
+    }
+    Ok(())
+}
+
+/// 📡 POST the request and read the completion
+async fn send(
+    http: &reqwest::Client,
+    endpoint: &Endpoint<'_>,
+    request: &CompletionRequest,
+) -> Result<CompletionResponse> {
+    let response = endpoint
+        .authorized(http.post(endpoint.url("chat/completions")))
+        .json(&request_body(endpoint, request))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", endpoint.label))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        anyhow::bail!("{} returned {}: {}", endpoint.label, status, text);
+    }
+
+    let completion: ChatCompletion = response
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {} response", endpoint.label))?;
+    into_response(endpoint, completion)
+}
+
+/// 📦 Request body, forcing the schema's function when structured output is requested
+/// Backends without tool support get the schema as system instructions instead
+fn request_body(endpoint: &Endpoint<'_>, request: &CompletionRequest) -> serde_json::Value {
+    let schema_instructions = request
+        .output_schema
+        .as_ref()
+        .filter(|_| !endpoint.supports_tools)
+        .map(|schema| {
+            format!(
+                "Reply with only a JSON object (the arguments of `{}`) matching this JSON Schema:\n{}",
+                schema.name, schema.schema
+            )
+        });
+    let system = match (&request.system, schema_instructions) {
+        (Some(system), Some(instructions)) => Some(format!("{}\n\n{}", system, instructions)),
+        (system, instructions) => system.clone().or(instructions),
+    };
+
+    let mut messages = Vec::new();
+    if let Some(system) = system {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    for message in &request.messages {
+        messages.push(json!({ "role": message.role, "content": message.content }));
+    }
+
+    let mut body = json!({
+        "model": request.model.as_deref().unwrap_or(endpoint.default_model),
+        "messages": messages,
+        "max_tokens": request.max_tokens.unwrap_or(endpoint.max_tokens),
+        "temperature": request.temperature.unwrap_or(endpoint.temperature),
+    });
+    if let (Some(schema), true) = (&request.output_schema, endpoint.supports_tools) {
+        body["tools"] = json!([{
+            "type": "function",
+            "function": {
+                "name": schema.name,
+                "description": schema.description,
+                "parameters": schema.schema,
+            },
+        }]);
+        body["tool_choice"] = json!({ "type": "function", "function": { "name": schema.name } });
+    }
+    body
+}
+
+/// 📥 Provider-neutral response (tool arguments win over text when present)
+fn into_response(
+    endpoint: &Endpoint<'_>,
+    completion: ChatCompletion,
+) -> Result<CompletionResponse> {
+    let message = completion
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message)
+        .with_context(|| format!("{} response contained no choices", endpoint.label))?;
+
+    let content = match message.tool_calls.into_iter().next() {
+        Some(call) => arguments_to_content(&call.function.arguments)?,
+        None => message
+            .content
+            .with_context(|| format!("{} response contained no content", endpoint.label))?,
+    };
+
+    Ok(CompletionResponse {
+        content,
+        provider: endpoint.provider,
+        model: completion.model,
+        usage: completion
+            .usage
+            .map(|u| TokenUsage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+            })
+            .unwrap_or_default(),
+    })
+}
+
+// 🧪 Tests - Tools in, arguments out!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    #[test]
+    fn test_structured_request_and_response() {
+        let config = OpenAiConfig {
+            api_key: "sk-test".to_string(),
+            default_model: "gpt-4".to_string(),
+            temperature: 0.2,
+            max_tokens: 100,
+            context_window: None,
+            small_model: None,
+        };
+        let request = CompletionRequest {
+            output_schema: Some(OutputSchema {
+                name: "submit_plan".to_string(),
+                description: "Submit the plan".to_string(),
+                schema: json!({ "type": "object" }),
+            }),
+            ..CompletionRequest::new(Some("Be brief".to_string()), "Plan it")
+        };
+
+        let endpoint = Endpoint::openai(&config);
+        let body = request_body(&endpoint, &request);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["tools"][0]["function"]["name"], "submit_plan");
+        assert_eq!(body["tool_choice"]["function"]["name"], "submit_plan");
+
+        let completion: ChatCompletion = serde_json::from_value(json!({
+            "model": "gpt-4",
+            "choices": [{ "message": { "content": null, "tool_calls": [{
+                "id": "call_1", "type": "function",
+                "function": { "name": "submit_plan", "arguments": "{\"steps\": []}" }
+            }] } }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5 }
+        }))
+        .unwrap();
+        let response = into_response(&endpoint, completion).unwrap();
+        assert_eq!(response.content, "{\"steps\": []}");
+        assert_eq!(response.usage.total(), 15);
+        println!("✅ OpenAI structured output test passed!");
+    }
+
+    #[test]
+    fn test_custom_endpoint() {
+        let mut config = CustomLlmConfig {
+            base_url: "http://localhost:8000/v1/".to_string(),
+            api_key: Some(String::new()),
+            default_model: "llama-3-8b".to_string(),
+            temperature: 0.1,
+            max_tokens: 100,
+            context_window: Some(8192),
+            small_model: None,
+            supports_tools: false,
+        };
+        let request = CompletionRequest {
+            output_schema: Some(OutputSchema {
+                name: "submit_plan".to_string(),
+                description: "Submit the plan".to_string(),
+                schema: json!({ "type": "object" }),
+            }),
+            ..CompletionRequest::new(None, "Plan it")
+        };
+
+        let endpoint = Endpoint::custom(&config);
+        assert_eq!(
+            endpoint.url("chat/completions"),
+            "http://localhost:8000/v1/chat/completions"
+        );
+        assert_eq!(endpoint.api_key, None);
+
+        // 🧱 Without tool support the schema travels as system instructions
+        let body = request_body(&endpoint, &request);
+        assert_eq!(body["model"], "llama-3-8b");
+        assert!(body.get("tools").is_none());
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert!(body["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("`submit_plan`"));
+
+        config.supports_tools = true;
+        let body = request_body(&Endpoint::custom(&config), &request);
+        assert_eq!(body["tools"][0]["function"]["name"], "submit_plan");
+
+        let completion: ChatCompletion = serde_json::from_value(json!({
+            "model": "llama-3-8b",
+            "choices": [{ "message": { "content": "{\"steps\": []}" } }]
+        }))
+        .unwrap();
+        let response = into_response(&Endpoint::custom(&config), completion).unwrap();
+        assert_eq!(response.provider, LlmProvider::Custom);
+        assert_eq!(response.usage.total(), 0);
+        println!("✅ Custom endpoint test passed!");
+    }
+}
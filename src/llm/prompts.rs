@@ -0,0 +1,314 @@
+// 📜 Prompt Templates - Words That Make the Magic Happen! 📜
+// Tiny `{{variable}}` templates used for prompts and generated markdown
+// Created with love by Aye & Hue - Keeping our wording in one place! ✨
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// 📜 A named template with `{{variable}}` placeholders
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    /// 🏷️ Template name (used in errors and logs)
+    pub name: String,
+    /// 📄 Template body
+    pub body: String,
+    /// 🔢 Stored version this came from (0 = built-in)
+    pub version: i32,
+}
+
+impl PromptTemplate {
+    /// ➕ Create a template from a name and body
+    pub fn new(name: &str, body: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            body: body.to_string(),
+            version: 0,
+        }
+    }
+
+    /// 🔢 Create a template from a stored prompt version
+    pub fn versioned(name: &str, body: &str, version: i32) -> Self {
+        Self {
+            version,
+            ..Self::new(name, body)
+        }
+    }
+
+    /// 🔍 Placeholder names used by the template, in order of first use
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let key = after[..end].trim().to_string();
+            if !found.contains(&key) {
+                found.push(key);
+            }
+            rest = &after[end + 2..];
+        }
+        found
+    }
+
+    /// 🎨 Render the template, failing if any placeholder has no value
+    pub fn render(&self, vars: &HashMap<&str, String>) -> Result<String> {
+        let mut output = String::with_capacity(self.body.len());
+        let mut rest = self.body.as_str();
+        let mut missing = Vec::new();
+
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+
+            match after.find("}}") {
+                Some(end) => {
+                    let key = after[..end].trim();
+                    match vars.get(key) {
+                        Some(value) => output.push_str(value),
+                        None => missing.push(key.to_string()),
+                    }
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    // 🔓 Unclosed braces are kept as literal text
+                    output.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        output.push_str(rest);
+
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Template '{}' is missing values for: {}",
+                self.name,
+                missing.join(", ")
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+/// 📚 Built-in template names
+pub mod names {
+    /// 🐙 Pull request description
+    pub const PR_DESCRIPTION: &str = "pr_description";
+    /// 🗺️ Multi-file change planning
+    pub const CHANGE_PLAN: &str = "change_plan";
+    /// 📄 Single planned file generation
+    pub const FILE_EDIT: &str = "file_edit";
+    /// 📚 Documentation-only edit of one file
+    pub const DOCS_EDIT: &str = "docs_edit";
+    /// 🧪 Test file covering uncovered functions
+    pub const TEST_FILE: &str = "test_file";
+    /// 🔀 Re-applying a pull request's change to a file the base branch also changed
+    pub const REBASE_FILE: &str = "rebase_file";
+    /// ⚖️ Risk score of a pull request's changes
+    pub const PR_RISK: &str = "pr_risk";
+}
+
+/// 🐙 Structured pull request body
+const PR_DESCRIPTION_TEMPLATE: &str = r#"## 🤖 AI-Generated Improvements
+
+{{summary}}
+
+### 💡 Why
+This pull request was generated by Feedbacker from the following user feedback:
+
+{{feedback_quote}}
+
+🔗 Track this feedback: {{tracking_url}}
+
+### 📁 Modified Files
+{{modified_files}}
+
+{{risk}}
+
+### 🧪 Test Plan
+{{test_plan}}
+
+---
+🚢 Generated with love by [Feedbacker](https://github.com/aye-is/feedbacker) - Aye & Hue
+🤖 Feedback ID: `{{feedback_id}}`
+"#;
+
+/// 🗺️ Planning call: ask for an ordered list of file edits as JSON
+const CHANGE_PLAN_TEMPLATE: &str = r#"You are planning code changes for the repository {{repository}}.
+
+User feedback:
+{{feedback}}
+
+Repository files:
+{{file_listing}}
+
+Only plan edits to files under `{{scope}}`.
+Produce a plan of file edits that implements the feedback. Order the steps so that
+each file only depends on files earlier in the list. Submit the plan with the
+`submit_change_plan` tool: a one-sentence `summary` of the overall change and `steps`,
+each with a relative `file_path`, a `change_type` (create, modify, delete, or append),
+and the `intent` of that file edit.
+"#;
+
+/// 📄 Generation call: produce the full new content of one planned file
+const FILE_EDIT_TEMPLATE: &str = r#"You are implementing step {{step_number}} of {{step_count}} of a change plan for {{repository}}.
+
+User feedback:
+{{feedback}}
+
+Overall plan:
+{{plan_outline}}
+
+Current step: {{change_type}} `{{file_path}}`
+Intent: {{intent}}
+
+Current file content:
+{{original_content}}
+
+Submit the complete new content of `{{file_path}}` in the `content` field of the `write_file` tool.
+"#;
+
+/// 📚 Docs pass: document one file without touching its code
+const DOCS_EDIT_TEMPLATE: &str = r#"You are improving the documentation of `{{file_path}}` in {{repository}}.
+
+{{instructions}}
+
+Rules:
+- Only add or improve comments, doc comments, docstrings, and documentation text.
+- Do not change any code: no renames, no new imports, no reformatting of code lines.
+- Match the documentation style the file already uses.
+
+Current file content:
+{{original_content}}
+
+Submit the complete new content of `{{file_path}}` in the `content` field of the `write_file` tool.
+"#;
+
+/// 🧪 Test generation: write one test file for uncovered functions
+const TEST_FILE_TEMPLATE: &str = r#"You are writing tests for `{{source_path}}` in {{repository}}.
+
+Request: {{request}}
+
+These functions have no test coverage:
+{{functions}}
+
+{{conventions}}
+
+Rules:
+- Only write tests. Do not change, stub, or reimplement the code under test.
+- Tests must compile and pass against the current code.
+- Prefer small, deterministic tests without network or filesystem access.
+
+Source of `{{source_path}}`:
+{{source_content}}
+
+Current content of `{{test_path}}` (empty if it does not exist yet):
+{{test_content}}
+
+Submit the complete new content of `{{test_path}}` in the `content` field of the `write_file` tool.
+"#;
+
+/// 🔀 Rebase: carry a PR's change to one file over to the base branch's new version
+const REBASE_FILE_TEMPLATE: &str = r#"You are updating a pull request in {{repository}} that no longer applies cleanly to its base branch.
+
+The pull request was opened for this request: {{request}}
+
+Both the pull request and the base branch changed `{{file_path}}` since the pull request was created.
+
+Version the pull request started from:
+{{original_content}}
+
+Version in the pull request:
+{{pull_request_content}}
+
+Version on the base branch now:
+{{base_content}}
+
+Rules:
+- Start from the base branch version and keep every change made there.
+- Re-apply the pull request's change on top of it, adapted to the new code.
+- Do not leave conflict markers or make unrelated changes.
+
+Submit the complete new content of `{{file_path}}` in the `content` field of the `write_file` tool.
+"#;
+
+/// ⚖️ Risk scoring: how careful should the reviewer of this diff be?
+const PR_RISK_TEMPLATE: &str = r#"You are reviewing a pull request for {{repository}} before a maintainer does, to judge how risky it is to merge.
+
+The pull request was generated for this request:
+{{request}}
+
+Size: {{summary}}
+
+Checks on the paths it touches flagged:
+{{factors}}
+
+Diff (may be cut short):
+{{diff}}
+
+Levels:
+- low: small, covered by tests, nothing sensitive.
+- medium: worth a careful look, e.g. behavior changes without tests or wide edits.
+- high: could break builds, data, deployments or security, or removes safeguards such as tests or checks.
+
+Judge the code itself, not only its size. Submit the `level` and a one- or two-sentence `rationale` for the reviewer with the `submit_risk` tool.
+"#;
+
+/// 📚 Look up a built-in template by name
+pub fn builtin(name: &str) -> Option<PromptTemplate> {
+    match name {
+        names::PR_DESCRIPTION => Some(PromptTemplate::new(name, PR_DESCRIPTION_TEMPLATE)),
+        names::CHANGE_PLAN => Some(PromptTemplate::new(name, CHANGE_PLAN_TEMPLATE)),
+        names::FILE_EDIT => Some(PromptTemplate::new(name, FILE_EDIT_TEMPLATE)),
+        names::DOCS_EDIT => Some(PromptTemplate::new(name, DOCS_EDIT_TEMPLATE)),
+        names::TEST_FILE => Some(PromptTemplate::new(name, TEST_FILE_TEMPLATE)),
+        names::REBASE_FILE => Some(PromptTemplate::new(name, REBASE_FILE_TEMPLATE)),
+        names::PR_RISK => Some(PromptTemplate::new(name, PR_RISK_TEMPLATE)),
+        _ => None,
+    }
+}
+
+// 🧪 Tests - Templates must render exactly what we expect!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_rendering() {
+        let template = PromptTemplate::new("greeting", "Hello {{ name }}, welcome to {{place}}!");
+        let vars = HashMap::from([
+            ("name", "Trisha".to_string()),
+            ("place", "Feedbacker".to_string()),
+        ]);
+
+        let rendered = template.render(&vars).unwrap();
+        assert_eq!(rendered, "Hello Trisha, welcome to Feedbacker!");
+        println!("✅ Template rendering test passed!");
+    }
+
+    #[test]
+    fn test_template_missing_variables() {
+        let template = PromptTemplate::new("greeting", "Hello {{name}} from {{team}}");
+        let vars = HashMap::from([("name", "Aye".to_string())]);
+
+        let error = template.render(&vars).unwrap_err().to_string();
+        assert!(error.contains("team"));
+        assert_eq!(template.placeholders(), vec!["name", "team"]);
+        println!("✅ Template missing variable test passed!");
+    }
+
+    #[test]
+    fn test_builtin_templates() {
+        assert!(builtin(names::PR_DESCRIPTION).is_some());
+        assert!(builtin(names::CHANGE_PLAN).is_some());
+        assert!(builtin(names::DOCS_EDIT).is_some());
+        assert!(builtin(names::TEST_FILE).is_some());
+        assert!(builtin(names::FILE_EDIT).is_some());
+        assert!(builtin(names::REBASE_FILE).is_some());
+        assert!(builtin(names::PR_RISK).is_some());
+        assert!(builtin("does_not_exist").is_none());
+        println!("✅ Built-in template lookup test passed!");
+    }
+}
@@ -0,0 +1,224 @@
+// 🧭 Model Routing - Small Models for Small Jobs! 🧭
+// Not every call needs the biggest model. Each task type (pipeline stage) maps
+// to a tier: `small` tasks go to the provider's small model, `large` ones to
+// its default model, and `auto` ones to the small model only while the prompt
+// is short. Projects can override the tier per stage, on top of the configured
+// rules (LLM_ROUTING_RULES), on top of the built-in ones below
+// Created with love by Aye & Hue - Big brains only where they pay off! ✨
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tracing::debug;
+
+use super::{prompts::names, CompletionRequest, LlmManager};
+use crate::config::{LlmProvider, ModelTier, RoutingConfig};
+
+/// 🏷️ Built-in tiers; tasks not listed (or untraced calls) use the large model
+const DEFAULT_TIERS: &[(&str, ModelTier)] = &[
+    ("labeling", ModelTier::Small),
+    ("summarization", ModelTier::Small),
+    (names::DOCS_EDIT, ModelTier::Auto),
+    (names::CHANGE_PLAN, ModelTier::Large),
+    (names::FILE_EDIT, ModelTier::Large),
+    (names::TEST_FILE, ModelTier::Large),
+    (names::REBASE_FILE, ModelTier::Large),
+    (names::PR_RISK, ModelTier::Auto),
+];
+
+/// 🧭 Tier for a task: project override, then configured rule, then built-in rule
+pub fn tier_for(
+    config: &RoutingConfig,
+    overrides: Option<&HashMap<String, ModelTier>>,
+    task: Option<&str>,
+) -> ModelTier {
+    let Some(task) = task else {
+        return ModelTier::Large;
+    };
+    overrides
+        .and_then(|overrides| overrides.get(task))
+        .or_else(|| config.tiers.get(task))
+        .copied()
+        .or_else(|| {
+            DEFAULT_TIERS
+                .iter()
+                .find(|(name, _)| *name == task)
+                .map(|(_, tier)| *tier)
+        })
+        .unwrap_or(ModelTier::Large)
+}
+
+impl LlmManager {
+    /// 🐣 Small model configured for a provider
+    fn small_model(&self, provider: &LlmProvider) -> Option<String> {
+        let config = self.config.load();
+        match provider {
+            LlmProvider::OpenAi => config.openai.as_ref()?.small_model.clone(),
+            LlmProvider::Anthropic => config.anthropic.as_ref()?.small_model.clone(),
+            LlmProvider::Custom => config.custom.as_ref()?.small_model.clone(),
+        }
+    }
+
+    /// 🧭 The request as it is sent to `provider`: on the small model when the task's tier
+    /// calls for it. Explicit model overrides are kept, and prompts that don't fit the
+    /// small model's window stay on the default model
+    pub fn route<'a>(
+        &self,
+        provider: &LlmProvider,
+        request: &'a CompletionRequest,
+    ) -> Cow<'a, CompletionRequest> {
+        if request.model.is_some() {
+            return Cow::Borrowed(request);
+        }
+        let Some(small_model) = self.small_model(provider) else {
+            return Cow::Borrowed(request);
+        };
+
+        let config = self.config.load();
+        let trace = request.trace.as_ref();
+        let tier = tier_for(
+            &config.routing,
+            trace.map(|trace| &trace.routing),
+            trace.map(|trace| trace.stage.as_str()),
+        );
+        if tier == ModelTier::Large {
+            return Cow::Borrowed(request);
+        }
+
+        let routed = CompletionRequest {
+            model: Some(small_model.clone()),
+            ..request.clone()
+        };
+        let Some(budget) = self.budget_for(provider, &routed) else {
+            return Cow::Borrowed(request);
+        };
+        let needed = budget.count_request(&routed);
+        let small = budget.overflow(needed).is_none()
+            && (tier == ModelTier::Small || needed <= config.routing.small_prompt_tokens);
+
+        if small {
+            debug!(
+                "🧭 Routing {:?} task ({} tokens) to {}/{}",
+                trace.map(|trace| trace.stage.as_str()),
+                needed,
+                provider.as_str(),
+                small_model
+            );
+            Cow::Owned(routed)
+        } else {
+            Cow::Borrowed(request)
+        }
+    }
+}
+
+// 🧪 Tests - The right job for the right model!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{LlmConfig, OpenAiConfig};
+    use crate::llm::ExchangeTrace;
+    use uuid::Uuid;
+
+    fn llm_manager(small_model: Option<&str>) -> LlmManager {
+        LlmManager::new(&LlmConfig {
+            openai: Some(OpenAiConfig {
+                api_key: "sk-test".to_string(),
+                default_model: "gpt-4o".to_string(),
+                temperature: 0.2,
+                max_tokens: 1000,
+                context_window: None,
+                small_model: small_model.map(str::to_string),
+            }),
+            anthropic: None,
+            custom: None,
+            default_provider: LlmProvider::OpenAi,
+            timeout_seconds: 1,
+            max_retries: 0,
+            fallback_providers: Vec::new(),
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: RoutingConfig {
+                tiers: HashMap::from([("summarization".to_string(), ModelTier::Large)]),
+                small_prompt_tokens: 100,
+            },
+        })
+    }
+
+    fn request(stage: &str, prompt: String) -> CompletionRequest {
+        CompletionRequest::new(None, prompt).traced(Some(ExchangeTrace::new(
+            stage,
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            false,
+        )))
+    }
+
+    #[test]
+    fn test_tier_rules() {
+        let config = RoutingConfig {
+            tiers: HashMap::from([(names::CHANGE_PLAN.to_string(), ModelTier::Auto)]),
+            small_prompt_tokens: 100,
+        };
+        let overrides = HashMap::from([(names::CHANGE_PLAN.to_string(), ModelTier::Small)]);
+
+        assert_eq!(tier_for(&config, None, None), ModelTier::Large);
+        assert_eq!(tier_for(&config, None, Some("labeling")), ModelTier::Small);
+        assert_eq!(tier_for(&config, None, Some("unknown")), ModelTier::Large);
+        assert_eq!(
+            tier_for(&config, None, Some(names::CHANGE_PLAN)),
+            ModelTier::Auto
+        );
+        assert_eq!(
+            tier_for(&config, Some(&overrides), Some(names::CHANGE_PLAN)),
+            ModelTier::Small
+        );
+        println!("✅ Routing tier rules test passed!");
+    }
+
+    #[test]
+    fn test_requests_are_routed_by_task_and_size() {
+        let manager = llm_manager(Some("gpt-4o-mini"));
+        let provider = LlmProvider::OpenAi;
+        let model = |request: &CompletionRequest| {
+            manager
+                .route(&provider, request)
+                .model
+                .clone()
+                .unwrap_or_else(|| "default".to_string())
+        };
+
+        // 📏 `auto` follows the prompt size
+        let short = request(names::DOCS_EDIT, "Document this".to_string());
+        assert_eq!(model(&short), "gpt-4o-mini");
+        let long = request(names::DOCS_EDIT, "word ".repeat(500));
+        assert_eq!(model(&long), "default");
+
+        // 🐣 `small` ignores the size, 🦣 `large` and config rules win over built-ins
+        assert_eq!(
+            model(&request("labeling", "word ".repeat(500))),
+            "gpt-4o-mini"
+        );
+        assert_eq!(
+            model(&request(names::FILE_EDIT, "x".to_string())),
+            "default"
+        );
+        assert_eq!(model(&request("summarization", "x".to_string())), "default");
+
+        // 🏠 Project overrides beat everything
+        let mut pinned = short.clone();
+        if let Some(trace) = pinned.trace.as_mut() {
+            trace.routing = HashMap::from([(names::DOCS_EDIT.to_string(), ModelTier::Large)]);
+        }
+        assert_eq!(model(&pinned), "default");
+
+        // 🎯 Explicit models and providers without a small model are left alone
+        let explicit = CompletionRequest {
+            model: Some("gpt-4-turbo".to_string()),
+            ..short.clone()
+        };
+        assert_eq!(model(&explicit), "gpt-4-turbo");
+        assert!(llm_manager(None).route(&provider, &short).model.is_none());
+        println!("✅ Request routing test passed!");
+    }
+}
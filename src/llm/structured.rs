@@ -0,0 +1,311 @@
+// 🧱 Structured Output - JSON In, JSON Out, No Guesswork! 🧱
+// Schema-constrained generation: the request carries a JSON Schema that the
+// providers enforce through tool calling (OpenAI `tools`, Anthropic `tool_use`).
+// Replies are checked against the schema and deserialized into typed structs;
+// a reply that doesn't fit is sent back with the violations for another try
+// Created with love by Aye & Hue - Free text is for humans! ✨
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{extract_json, ChatMessage, CompletionRequest, CompletionResponse, LlmManager};
+
+/// 🔁 Total attempts (first try + re-prompts) before giving up on a schema
+pub const MAX_SCHEMA_ATTEMPTS: usize = 3;
+
+/// 📐 A named JSON Schema the model must answer with
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OutputSchema {
+    /// 🏷️ Tool/function name the providers expose
+    pub name: String,
+    /// 📝 What the output is for
+    pub description: String,
+    /// 📐 JSON Schema of the output object
+    pub schema: Value,
+}
+
+/// 🧱 Types that can be requested as structured output
+pub trait StructuredOutput: DeserializeOwned {
+    /// 📐 Schema the model's reply must match
+    fn output_schema() -> OutputSchema;
+}
+
+impl LlmManager {
+    /// 🧱 Complete with a schema-constrained reply, re-prompting on violations
+    /// `check` adds domain validation on top of the schema (e.g. paths inside the scope)
+    pub async fn complete_structured<T, F>(
+        &self,
+        request: &CompletionRequest,
+        check: F,
+    ) -> Result<(T, CompletionResponse)>
+    where
+        T: StructuredOutput,
+        F: Fn(&T) -> Result<(), Vec<String>>,
+    {
+        let schema = T::output_schema();
+        let mut request = CompletionRequest {
+            output_schema: Some(schema.clone()),
+            ..request.clone()
+        };
+
+        let mut last_errors = Vec::new();
+        for attempt in 1..=MAX_SCHEMA_ATTEMPTS {
+            let response = self.complete(&request).await?;
+            match parse_structured::<T>(&response.content, &schema).and_then(|value| {
+                check(&value)?;
+                Ok(value)
+            }) {
+                Ok(value) => return Ok((value, response)),
+                Err(errors) => {
+                    tracing::warn!(
+                        "⚠️ {} reply violated its schema (attempt {}): {}",
+                        schema.name,
+                        attempt,
+                        errors.join("; ")
+                    );
+                    // 🔁 Show the model its own reply and what was wrong with it
+                    request
+                        .messages
+                        .push(ChatMessage::assistant(response.content));
+                    request.messages.push(ChatMessage::user(format!(
+                        "That output is invalid:\n- {}\nCall `{}` again with corrected arguments.",
+                        errors.join("\n- "),
+                        schema.name
+                    )));
+                    last_errors = errors;
+
+                    // ✂️ Drop the oldest failed attempts once the history outgrows the window,
+                    // keeping the original prompt and the latest attempt
+                    while request.messages.len() > 3 && !self.fits(&request) {
+                        request.messages.drain(1..3);
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "{} output still invalid after {} attempts: {}",
+            schema.name,
+            MAX_SCHEMA_ATTEMPTS,
+            last_errors.join("; ")
+        )
+    }
+
+    /// 🔢 Whether a request fits the tightest context window in the chain
+    fn fits(&self, request: &CompletionRequest) -> bool {
+        self.context_budget(request)
+            .map(|budget| budget.overflow(budget.count_request(request)).is_none())
+            .unwrap_or(true)
+    }
+}
+
+/// 🔍 Parse a reply, check it against the schema, and deserialize it
+/// Errors are phrased for the model, since they are sent back in the re-prompt
+pub fn parse_structured<T: DeserializeOwned>(
+    content: &str,
+    schema: &OutputSchema,
+) -> Result<T, Vec<String>> {
+    let payload = extract_json(content).ok_or_else(|| vec!["No JSON object found".to_string()])?;
+    let value: Value =
+        serde_json::from_str(payload).map_err(|e| vec![format!("Invalid JSON: {}", e)])?;
+
+    let errors = validate_schema(&value, &schema.schema, "$");
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    serde_json::from_value(value).map_err(|e| vec![format!("Unexpected shape: {}", e)])
+}
+
+/// 📐 Check a value against the JSON Schema subset our schemas use:
+/// type, properties, required, additionalProperties (false), items, enum, minItems, maxItems, minLength
+pub fn validate_schema(value: &Value, schema: &Value, path: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            errors.push(format!("{} must be of type {}", path, expected));
+            return errors;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{} must be one of {}",
+                path,
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if (text.chars().count() as u64) < min {
+                errors.push(format!("{} must be at least {} characters", path, min));
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(required) {
+                errors.push(format!("{} is missing required field '{}'", path, required));
+            }
+        }
+        for (key, child) in object {
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(child_schema) => errors.extend(validate_schema(
+                    child,
+                    child_schema,
+                    &format!("{}.{}", path, key),
+                )),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{} has unexpected field '{}'", path, key))
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                errors.push(format!("{} must have at least {} items", path, min));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                errors.push(format!("{} must have at most {} items", path, max));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                errors.extend(validate_schema(
+                    item,
+                    item_schema,
+                    &format!("{}[{}]", path, index),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// 🔧 Tool arguments arrive as a JSON object (Anthropic) or a JSON string (OpenAI)
+pub(crate) fn arguments_to_content(arguments: &Value) -> Result<String> {
+    match arguments {
+        Value::String(text) => Ok(text.clone()),
+        other => serde_json::to_string(other).context("Failed to serialize tool arguments"),
+    }
+}
+
+// 🧪 Tests - Schemas are only useful if they actually say no!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Answer {
+        verdict: String,
+        reasons: Vec<String>,
+    }
+
+    fn answer_schema() -> OutputSchema {
+        OutputSchema {
+            name: "submit_answer".to_string(),
+            description: "Submit the answer".to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "verdict": { "type": "string", "enum": ["yes", "no"] },
+                    "reasons": { "type": "array", "items": { "type": "string", "minLength": 1 }, "maxItems": 2 }
+                },
+                "required": ["verdict", "reasons"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let schema = answer_schema();
+        assert!(validate_schema(
+            &json!({ "verdict": "yes", "reasons": ["a"] }),
+            &schema.schema,
+            "$"
+        )
+        .is_empty());
+
+        let errors = validate_schema(
+            &json!({ "verdict": "maybe", "reasons": ["a", "", "c"], "extra": 1 }),
+            &schema.schema,
+            "$",
+        );
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.verdict must be one of")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("$.reasons[1] must be at least 1")));
+        assert!(errors.iter().any(|e| e.contains("at most 2 items")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("unexpected field 'extra'")));
+
+        let missing = validate_schema(&json!({ "reasons": 3 }), &schema.schema, "$");
+        assert_eq!(missing.len(), 2, "{:?}", missing);
+        println!("✅ Schema validation test passed!");
+    }
+
+    #[test]
+    fn test_parse_structured_reply() {
+        let schema = answer_schema();
+        let answer: Answer = parse_structured(
+            "```json\n{\"verdict\": \"no\", \"reasons\": [\"x\"]}\n```",
+            &schema,
+        )
+        .unwrap();
+        assert_eq!(answer.verdict, "no");
+
+        assert!(parse_structured::<Answer>("no json", &schema).is_err());
+        assert!(
+            parse_structured::<Answer>("{\"verdict\": \"yes\"}", &schema)
+                .unwrap_err()
+                .iter()
+                .any(|e| e.contains("'reasons'"))
+        );
+
+        assert_eq!(
+            arguments_to_content(&json!("{\"a\":1}")).unwrap(),
+            "{\"a\":1}"
+        );
+        assert_eq!(
+            arguments_to_content(&json!({ "a": 1 })).unwrap(),
+            "{\"a\":1}"
+        );
+        println!("✅ Structured reply parsing test passed!");
+    }
+}
@@ -0,0 +1,284 @@
+// 🔢 Token Counting & Context Budgets - Know Before You Send! 🔢
+// Providers answer an oversized request with an opaque 400, usually after a
+// retry or two. Instead we count tokens locally, compare them with the model's
+// context window (minus the tokens reserved for the reply), and fail with a
+// clear error - or, for prompts we assemble ourselves, decide up front which
+// context makes the cut
+// Created with love by Aye & Hue - Every token accounted for! ✨
+
+use tiktoken_rs::tokenizer::Tokenizer;
+
+use super::CompletionRequest;
+use crate::config::LlmProvider;
+
+/// 📨 Framing tokens each chat message costs on top of its text
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// 📨 Tokens the provider adds to prime the reply
+const REPLY_PRIMING_TOKENS: usize = 3;
+
+/// 🛟 Anthropic (and custom backends) don't use tiktoken, so cl100k counts get this much headroom (percent)
+const ESTIMATE_MARGIN_PERCENT: usize = 10;
+
+/// ✂️ Don't bother truncating an item into fewer tokens than this
+const MIN_TRUNCATED_TOKENS: usize = 64;
+
+/// 🔤 Raw BPE token count with the encoding used by a provider/model
+fn encoded_len(provider: LlmProvider, model: &str, text: &str) -> usize {
+    let o200k = provider == LlmProvider::OpenAi
+        && tiktoken_rs::tokenizer::get_tokenizer(model) == Some(Tokenizer::O200kBase);
+    let bpe = if o200k {
+        tiktoken_rs::o200k_base_singleton()
+    } else {
+        tiktoken_rs::cl100k_base_singleton()
+    };
+    let tokens = bpe.lock().encode_with_special_tokens(text).len();
+    tokens
+}
+
+/// 🪟 Context window (input + output tokens) of a model
+/// Unknown OpenAI and custom models get tiktoken's conservative default
+pub fn context_window(provider: LlmProvider, model: &str) -> usize {
+    match provider {
+        LlmProvider::OpenAi | LlmProvider::Custom => tiktoken_rs::model::get_context_size(model),
+        LlmProvider::Anthropic if model.starts_with("claude-2.0") => 100_000,
+        LlmProvider::Anthropic if model.starts_with("claude-instant") => 100_000,
+        LlmProvider::Anthropic => 200_000,
+    }
+}
+
+/// 📏 Token budget for one provider/model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextBudget {
+    /// 🤖 Provider the budget applies to
+    pub provider: LlmProvider,
+    /// 🤖 Model the budget applies to
+    pub model: String,
+    /// 🪟 Total context window
+    pub window: usize,
+    /// 📤 Tokens kept free for the reply
+    pub reserved_output: usize,
+}
+
+/// 📦 One piece of optional context competing for the budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextItem {
+    /// 🏷️ What this is (a file path, a message, ...)
+    pub label: String,
+    /// 📝 The text itself
+    pub text: String,
+    /// 🥇 Lower goes in first
+    pub priority: u32,
+    /// ✂️ May be cut short instead of dropped
+    pub truncatable: bool,
+}
+
+/// 📋 What fit into the budget
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextSelection {
+    /// ✅ Items that fit, in their original order (possibly truncated)
+    pub included: Vec<ContextItem>,
+    /// ✂️ Labels of items that were cut short
+    pub truncated: Vec<String>,
+    /// 🗑️ Labels of items left out
+    pub dropped: Vec<String>,
+    /// 🔢 Tokens used by the included items
+    pub tokens: usize,
+}
+
+impl ContextBudget {
+    /// ➕ Budget for a model, with its known (or overridden) context window
+    pub fn new(
+        provider: LlmProvider,
+        model: &str,
+        window_override: Option<usize>,
+        reserved_output: usize,
+    ) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+            window: window_override.unwrap_or_else(|| context_window(provider, model)),
+            reserved_output,
+        }
+    }
+
+    /// 📥 Tokens available for the prompt
+    pub fn input_limit(&self) -> usize {
+        self.window.saturating_sub(self.reserved_output)
+    }
+
+    /// 🔢 Tokens in a piece of text
+    pub fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        let tokens = encoded_len(self.provider, &self.model, text);
+        match self.provider {
+            LlmProvider::OpenAi => tokens,
+            LlmProvider::Anthropic | LlmProvider::Custom => {
+                tokens + tokens * ESTIMATE_MARGIN_PERCENT / 100
+            }
+        }
+    }
+
+    /// 🔢 Prompt tokens of a whole request: system, messages, and the tool schema
+    pub fn count_request(&self, request: &CompletionRequest) -> usize {
+        let system = request
+            .system
+            .as_deref()
+            .map(|system| self.count(system) + MESSAGE_OVERHEAD_TOKENS)
+            .unwrap_or(0);
+        let messages: usize = request
+            .messages
+            .iter()
+            .map(|message| self.count(&message.content) + MESSAGE_OVERHEAD_TOKENS)
+            .sum();
+        let schema = request
+            .output_schema
+            .as_ref()
+            .map(|schema| {
+                self.count(&schema.name)
+                    + self.count(&schema.description)
+                    + self.count(&schema.schema.to_string())
+            })
+            .unwrap_or(0);
+        system + messages + schema + REPLY_PRIMING_TOKENS
+    }
+
+    /// 🚫 Clear error for a prompt of `needed` tokens, or None when it fits
+    pub fn overflow(&self, needed: usize) -> Option<String> {
+        (needed > self.input_limit()).then(|| {
+            format!(
+                "{}/{} needs {} prompt tokens but only {} fit ({} token window, {} reserved for the reply)",
+                self.provider.as_str(),
+                self.model,
+                needed,
+                self.input_limit(),
+                self.window,
+                self.reserved_output
+            )
+        })
+    }
+
+    /// ✂️ Cut text down to at most `max_tokens`, keeping whole lines
+    pub fn truncate(&self, text: &str, max_tokens: usize) -> String {
+        if self.count(text) <= max_tokens {
+            return text.to_string();
+        }
+
+        let mut kept = String::new();
+        let mut used = 0;
+        for line in text.split_inclusive('\n') {
+            let cost = self.count(line);
+            if used + cost > max_tokens {
+                break;
+            }
+            kept.push_str(line);
+            used += cost;
+        }
+        kept
+    }
+
+    /// 🧮 Pick the items that fit into `available` tokens, highest priority first
+    /// Items that don't fit are truncated when allowed, otherwise dropped
+    pub fn select(&self, items: Vec<ContextItem>, available: usize) -> ContextSelection {
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&index| items[index].priority);
+
+        let mut selection = ContextSelection::default();
+        let mut kept: Vec<Option<ContextItem>> = vec![None; items.len()];
+        for index in order {
+            let item = &items[index];
+            let remaining = available.saturating_sub(selection.tokens);
+            let cost = self.count(&item.text);
+
+            if cost <= remaining {
+                selection.tokens += cost;
+                kept[index] = Some(item.clone());
+            } else if item.truncatable && remaining >= MIN_TRUNCATED_TOKENS {
+                let text = self.truncate(&item.text, remaining);
+                selection.tokens += self.count(&text);
+                selection.truncated.push(item.label.clone());
+                kept[index] = Some(ContextItem {
+                    text,
+                    ..item.clone()
+                });
+            } else {
+                selection.dropped.push(item.label.clone());
+            }
+        }
+
+        selection.included = kept.into_iter().flatten().collect();
+        selection
+    }
+}
+
+// 🧪 Tests - Counting has to be right before budgeting can be!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ChatMessage;
+
+    #[test]
+    fn test_counting_and_windows() {
+        let openai = ContextBudget::new(LlmProvider::OpenAi, "gpt-4", None, 2000);
+        assert_eq!(openai.window, 8192);
+        assert_eq!(openai.input_limit(), 6192);
+        assert_eq!(openai.count("hello world"), 2);
+        assert_eq!(openai.count(""), 0);
+
+        // 🛟 Anthropic estimates carry headroom over the cl100k count
+        let text = "fn main() { println!(\"hello\"); }\n".repeat(20);
+        let anthropic = ContextBudget::new(LlmProvider::Anthropic, "claude-3-sonnet", None, 4096);
+        assert_eq!(anthropic.window, 200_000);
+        assert!(anthropic.count(&text) > openai.count(&text));
+        assert_eq!(
+            ContextBudget::new(LlmProvider::OpenAi, "custom", Some(1000), 200).input_limit(),
+            800
+        );
+
+        let mut request = CompletionRequest::new(Some("Be brief".to_string()), "hello world");
+        request.messages.push(ChatMessage::assistant("hi"));
+        let needed = openai.count_request(&request);
+        assert_eq!(
+            needed,
+            2 + 2 + 1 + 3 * MESSAGE_OVERHEAD_TOKENS + REPLY_PRIMING_TOKENS
+        );
+        assert!(openai.overflow(needed).is_none());
+        let error = openai.overflow(7000).unwrap();
+        assert!(error.contains("openai/gpt-4 needs 7000 prompt tokens but only 6192 fit"));
+        println!("✅ Token counting test passed!");
+    }
+
+    #[test]
+    fn test_context_selection() {
+        let budget = ContextBudget::new(LlmProvider::OpenAi, "gpt-4o", None, 0);
+        let item = |label: &str, text: String, priority: u32, truncatable: bool| ContextItem {
+            label: label.to_string(),
+            text,
+            priority,
+            truncatable,
+        };
+        let long = "line of context\n".repeat(100);
+        let items = vec![
+            item("extra", "x ".repeat(50), 2, false),
+            item("history", long.clone(), 1, true),
+            item("feedback", "make it fast".to_string(), 0, false),
+        ];
+
+        let selection = budget.select(items, 150);
+        assert!(selection.tokens <= 150);
+        assert_eq!(selection.dropped, vec!["extra".to_string()]);
+        assert_eq!(selection.truncated, vec!["history".to_string()]);
+        // 📋 Original order is preserved for whatever made it in
+        let labels: Vec<&str> = selection
+            .included
+            .iter()
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["history", "feedback"]);
+        assert!(selection.included[0].text.ends_with('\n'));
+        assert!(long.starts_with(&selection.included[0].text));
+        println!("✅ Context selection test passed!");
+    }
+}
@@ -8,48 +8,112 @@
 
 use anyhow::{Context, Result};
 use axum::{
+    extract::State,
     http::StatusCode,
     middleware as axum_middleware,
     response::{Html, IntoResponse},
-    routing::{get, post},
+    routing::{any, delete, get, post, put},
     Router,
 };
+use clap::Parser;
 use std::net::SocketAddr;
 use tokio::signal;
 use tower::ServiceBuilder;
-use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
+use tower_http::{
+    catch_panic::CatchPanicLayer, compression::CompressionLayer, cors::CorsLayer,
+    trace::TraceLayer,
+};
 use tracing::{error, info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
 
 // 🎯 Import all our amazing modules that we're about to create!
 mod api; // 📡 API routes for feedback submission and management
+mod artifacts; // 🗄️ Diffs, plans and sandbox logs of runs, kept locally or in S3 behind signed links
 mod auth; // 🔐 Authentication and authorization magic
+mod budgets; // 💸 Token caps per feedback item and per project month, and approving more
+mod cache; // 🗃️ Response cache (in process, optionally Redis) for hot read endpoints
+mod cli; // 🖥️  Maintenance subcommands (feedbacker migrate, doctor ...)
+mod collaborators; // 👥 Repository collaborators mirrored into project member roles
 mod config; // ⚙️  Configuration management (because settings matter!)
 mod database; // 🗄️  Database operations and connections
+mod digest; // 📬 Daily and weekly activity digests by email, with unsubscribe links
+mod doctor; // 🩺 check-config and doctor: validate settings, probe dependencies
+mod email; // 📧 Outgoing email over SMTP
+mod errors; // 🧯 Error taxonomy: codes, statuses and problem types
+mod event_stream; // 📤 Feedback and pull request events published to Kafka or NATS
+mod export; // 📦 Project feedback exports (CSV / JSON Lines) and signed download links
+mod feature_flags; // 🚩 Database-backed runtime feature flags
+mod feedback_bulk; // 🧹 Retry, cancel or label many feedback items in one transaction
+mod feedback_claims; // 🙋 Anonymous submitters claiming their feedback through a signed email link
+mod feedback_lifecycle; // 🧾 Feedback status as a projection of its event timeline, and replaying it
+mod feedback_trace; // 🧵 Feedback ids on pipeline spans, and the stage timings they record
+mod feedback_triage; // 🗂️ Assigning feedback to maintainers, and their internal notes
+mod feedback_watchdog; // ⏱️ Feedback stuck mid-pipeline: failed or requeued, and admins told
 mod github; // 🐙 GitHub integration for the legendary aye-is user
+mod highlighting; // 🖍️ Syntax highlighting for the diff preview
+mod i18n; // 🌍 Translated messages (Fluent) and the request's locale
+mod installations; // 🔌 Repositories the GitHub App reaches, and projects it lost access to
+mod issue_import; // 🎫 Open GitHub issues imported as feedback
 mod jobs; // 🔄 Background job processing for async operations
+mod live_updates; // 📡 Feedback events fanned out to WebSocket subscribers
 mod llm; // 🤖 LLM integration (OpenAI, Anthropic, and friends!)
+mod maintenance; // 🚧 Maintenance mode: writes refused, workers paused
+mod mcp; // 🤖 MCP server: feedback tools for AI agents (stdio and /api/mcp)
+mod metrics; // 📈 Prometheus metrics
 mod middleware; // 🛡️  Custom middleware for rate limiting and security
 mod models; // 📊 Data models and structures
+mod organizations; // 🏢 Organization roles, project access and quotas
+mod pipeline; // 🏭 Feedback-to-PR processing pipeline
+mod reload; // 🔄 Hot reload of rate limits, flags, log level, and model names
+mod roles; // 🎭 Editable roles and their permissions
+mod scm; // 🏢 GitHub Enterprise endpoints per project and organization
+mod secrets; // 🔑 Credentials from Vault, AWS and GCP secrets managers
+mod security_alerts; // 🔐 Notifications and emails on new sign-ins, password changes and API keys
+mod sso; // 🔐 OIDC single sign-on with JIT provisioning
+mod style_guide; // 🎨 Repository style profiles learned at onboarding and injected into prompts
+mod tls; // 🔒 HTTPS from certificate files or ACME, and the HTTP→HTTPS redirect
 mod utils; // 🔧 Utility functions and helpers
 
 use config::Config;
-use middleware::{auth::auth_middleware, rate_limiting::rate_limit_middleware};
+use middleware::{
+    auth::auth_middleware, conditional::etag_middleware, error_handling::error_handling_middleware,
+    ip_allowlist::ip_allowlist_middleware, locale::locale_middleware, logging::logging_middleware,
+    maintenance::maintenance_middleware, problem_json::problem_json_middleware,
+    rate_limiting::rate_limit_middleware, request_signing::request_signing_middleware,
+    webhook_deliveries::webhook_delivery_middleware,
+};
 
 // 🎊 The main function - Where the magic begins! 🎊
 #[tokio::main]
 async fn main() -> Result<()> {
+    // 🖥️ Parse the command line first, so --help works without any setup
+    let mut cli = cli::Cli::parse();
+
     // 🌈 Initialize our beautiful logging system
     // Because knowing what's happening is half the battle!
-    init_logging()?;
+    // (on stderr for `feedbacker mcp`, whose stdout carries the protocol)
+    init_logging(matches!(cli.command, Some(cli::Command::Mcp)))?;
+
+    // 🛠️ Maintenance subcommands run and exit without starting the service
+    if let Some(command) = cli.maintenance_command()? {
+        return cli::run(command, cli.config.as_deref()).await;
+    }
 
     // 🎨 Display our fabulous startup banner
     display_startup_banner();
 
     // ⚙️ Load configuration from environment and files
-    let config = Config::load()
+    let mut config = Config::load_from(cli.config.as_deref())
         .context("Failed to load configuration - check your environment variables!")?;
 
+    // 🔑 Fetch the credentials that are referenced from a secrets manager
+    let secret_resolver = secrets::SecretResolver::new(&config.secrets);
+    let secret_bindings = secrets::resolve_config(&secret_resolver, &mut config)
+        .await
+        .context("Failed to read secrets from the secrets manager")?;
+
     info!("🚀 Configuration loaded successfully!");
     info!("🎯 Server will listen on: {}", config.server.address);
     info!(
@@ -58,19 +122,70 @@ async fn main() -> Result<()> {
     );
 
     // 🔗 Initialize database connection pool
-    let db_pool = database::create_pool(&config.database.url)
+    let db_pool = database::create_pool(&config.database)
         .await
         .context("Failed to create database connection pool")?;
+    database::monitor_pool(db_pool.clone(), std::time::Duration::from_secs(15));
 
-    // 🏃‍♂️ Run database migrations (keeping things up to date!)
-    database::run_migrations(&db_pool)
+    // 🔏 Make sure the migrations already applied are the ones this build has
+    database::verify_migrations(&db_pool, config.database.migration_drift)
         .await
-        .context("Failed to run database migrations")?;
+        .context("Migration checksum verification failed")?;
+
+    // 🏃‍♂️ Run database migrations (keeping things up to date!)
+    if config.database.auto_migrate {
+        database::run_migrations(&db_pool)
+            .await
+            .context("Failed to run database migrations")?;
+
+        info!("✅ Database connection established and migrations complete!");
+    } else {
+        info!("⏸️ Automatic migrations disabled - apply them with `feedbacker migrate up`");
+    }
 
-    info!("✅ Database connection established and migrations complete!");
+    // 📮 Connect the job queue backend
+    let queue = jobs::queue::connect(&config.jobs, db_pool.clone())
+        .await
+        .context("Failed to connect the job queue")?;
+    info!("📮 Job queue backend: {}", queue.backend().as_str());
 
     // 🎯 Create our amazing application state
-    let app_state = api::AppState::new(config.clone(), db_pool);
+    let app_state = api::AppState::new(config.clone(), cli.config.as_deref(), db_pool, queue);
+
+    // 🔏 Make sure there is a key to sign tokens with (the secret signs until there is)
+    if let Err(e) = app_state.token_keys.ensure_current(&config.auth).await {
+        warn!("⚠️ No token signing key: {:#}", e);
+    }
+
+    // 📡 SIGHUP reloads the non-structural settings (so does POST /api/admin/config/reload)
+    reload::watch_sighup(app_state.clone());
+
+    // 🔑 Keep referenced secrets fresh, so rotations don't need a restart
+    secrets::start_refresh(app_state.clone(), secret_resolver, secret_bindings);
+
+    // 🚦 Share rate limit counts with the other replicas through the database
+    middleware::rate_limiting::start_sync(app_state.clone());
+
+    // 📡 Push feedback events from every instance to this one's WebSocket subscribers
+    live_updates::start(app_state.clone());
+
+    // 📤 Publish feedback and pull request events to Kafka or NATS (EVENT_STREAM)
+    event_stream::start(&app_state);
+
+    // 🧵 Store how long each stage of each feedback item takes
+    feedback_trace::start_recorder(app_state.db_pool.clone());
+
+    // ⏰ Start the job workers and recurring schedules (kept alive until shutdown)
+    let _scheduler = if config.features.enable_background_jobs {
+        Some(
+            jobs::start(&app_state)
+                .await
+                .context("Failed to start background jobs")?,
+        )
+    } else {
+        info!("⏸️ Background jobs disabled - scheduled jobs will not run");
+        None
+    };
 
     // 🏗️ Build our beautiful Axum router
     let app = create_router(app_state, &config).context("Failed to create router")?;
@@ -85,18 +200,8 @@ async fn main() -> Result<()> {
     info!("🎉 Starting Feedbacker service on {}", addr);
     info!("🌟 Ready to process feedback and create amazing PRs!");
 
-    // 🚀 Launch the server with graceful shutdown
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .context("Failed to bind to address")?;
-
-    info!("🎊 Feedbacker is now LIVE and ready for action! 🎊");
-
-    // 🛡️ Run the server with graceful shutdown handling
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .context("Server error occurred")?;
+    // 🚀 Launch the server (over HTTPS when TLS is configured) with graceful shutdown
+    tls::serve(app, addr, &config.server, shutdown_signal()).await?;
 
     info!("👋 Feedbacker service shutting down gracefully. Thanks for using our service!");
 
@@ -105,14 +210,29 @@ async fn main() -> Result<()> {
 
 // 🌈 Initialize our beautiful logging system
 // This makes debugging a joy instead of a chore!
-fn init_logging() -> Result<()> {
+// The filter sits behind a reload handle so a config reload can change the level
+fn init_logging(to_stderr: bool) -> Result<()> {
+    let (filter, handle) = tracing_subscriber::reload::Layer::new(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "feedbacker=debug,tower_http=debug".into()),
+    );
+    let writer = if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "feedbacker=debug,tower_http=debug".into()),
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_filter(filter),
         )
-        .with(tracing_subscriber::fmt::layer())
+        // 🐢 Query timings are collected whatever the log filter lets through
+        .with(database::query_metrics::QueryMetricsLayer::filtered())
+        // 🧵 Stage timings per feedback item, likewise
+        .with(feedback_trace::StageTimingLayer::filtered())
         .init();
+    reload::install_log_filter(handle);
 
     Ok(())
 }
@@ -143,20 +263,273 @@ fn create_router(app_state: api::AppState, config: &Config) -> Result<Router> {
     // 🎯 Create the main API router
     let api_router = Router::new()
         // 📝 Feedback submission endpoint - the heart of our service!
-        .route("/api/feedback", post(api::feedback::submit_feedback))
+        .route(
+            "/api/feedback",
+            get(api::feedback::list_feedback).post(api::feedback::submit_feedback),
+        )
+        .route("/api/feedback/bulk", post(api::feedback::bulk_feedback))
+        .route(
+            "/api/feedback/:id",
+            get(api::feedback::get_feedback).layer(axum_middleware::from_fn(etag_middleware)),
+        )
+        .route(
+            "/api/feedback/:id/diff",
+            get(api::feedback::get_feedback_diff),
+        )
+        .route(
+            "/api/feedback/:id/events",
+            get(api::feedback::get_feedback_events),
+        )
+        // 🧵 Where a feedback item's time went, per stage (admins)
+        .route(
+            "/api/feedback/:id/trace",
+            get(api::feedback::get_feedback_trace),
+        )
+        // 📡 Live status and pipeline events over WebSockets
+        // 🗂️ Triage: assignees and internal notes (project maintainers)
+        .route(
+            "/api/feedback/:id/assignee",
+            put(api::feedback::assign_feedback),
+        )
+        .route(
+            "/api/feedback/:id/comments",
+            get(api::feedback::list_feedback_comments).post(api::feedback::add_feedback_comment),
+        )
+        .route(
+            "/api/feedback/:id/comments/:comment_id",
+            delete(api::feedback::delete_feedback_comment),
+        )
+        // 🗄️ What a run produced, behind signed links
+        .route(
+            "/api/feedback/:id/artifacts",
+            get(api::artifacts::list_artifacts),
+        )
+        .route(
+            "/api/artifacts/:feedback_id/:kind",
+            get(api::artifacts::download_artifact),
+        )
+        .route("/api/feedback/:id/ws", get(api::live::feedback_socket))
+        .route("/api/ws", get(api::live::socket))
+        // 🤖 MCP for AI agents (feedbacker mcp serves the same tools on stdio)
+        .route("/api/mcp", post(api::mcp::handle_message))
+        .route(
+            "/api/feedback/:id/approval",
+            post(api::feedback::approve_feedback),
+        )
+        // 💸 Resume feedback paused at its token budget (project admins)
+        .route(
+            "/api/feedback/:id/budget-approval",
+            post(api::feedback::approve_budget),
+        )
         // 📊 Status and health check endpoints
         .route("/api/health", get(api::health::health_check))
+        .route(
+            "/api/health/detailed",
+            get(api::health::detailed_health_check),
+        )
+        .route("/metrics", get(metrics::metrics_handler))
         .route(
             "/api/status/:project_id",
             get(api::status::get_project_status),
         )
+        .route("/api/rate-limit", get(api::rate_limit::get_rate_limit))
         // 🔍 Project management endpoints
         .route("/api/projects", get(api::projects::list_projects))
-        .route("/api/projects/:id", get(api::projects::get_project))
-        // 🐙 GitHub webhook endpoint for status updates
-        .route("/api/webhook/github", post(api::webhooks::github_webhook))
+        .route(
+            "/api/projects/:id",
+            get(api::projects::get_project).layer(axum_middleware::from_fn(etag_middleware)),
+        )
+        .route(
+            "/api/projects/:id/config",
+            put(api::projects::update_project_config),
+        )
+        .route(
+            "/api/projects/:id/collaborators",
+            get(api::projects::list_collaborators),
+        )
+        .route(
+            "/api/projects/:id/webhook-deliveries",
+            get(api::projects::list_webhook_deliveries),
+        )
+        .route(
+            "/api/projects/:id/style-profile",
+            get(api::projects::get_style_profile).post(api::projects::analyze_style),
+        )
+        .route(
+            "/api/projects/:id/dependency-updates",
+            post(api::projects::start_dependency_updates),
+        )
+        .route(
+            "/api/projects/:id/docs-pass",
+            post(api::projects::start_docs_pass),
+        )
+        .route(
+            "/api/projects/:id/test-generation",
+            post(api::projects::start_test_generation),
+        )
+        .route(
+            "/api/projects/:id/import-issues",
+            post(api::projects::import_issues),
+        )
+        .route(
+            "/api/projects/:id/export",
+            get(api::exports::export_project),
+        )
+        .route(
+            "/api/projects/:id/exports/:export_id",
+            get(api::exports::get_export),
+        )
+        .route(
+            "/api/exports/:export_id/download",
+            get(api::exports::download_export),
+        )
+        // 🏢 Organizations and teams
+        .route(
+            "/api/orgs",
+            get(api::organizations::list_organizations)
+                .post(api::organizations::create_organization),
+        )
+        .route("/api/orgs/:id", get(api::organizations::get_organization))
+        .route(
+            "/api/orgs/:id/settings",
+            put(api::organizations::update_organization_settings),
+        )
+        .route("/api/orgs/:id/members", put(api::organizations::set_member))
+        .route(
+            "/api/orgs/:id/members/:user_id",
+            delete(api::organizations::remove_member),
+        )
+        .route("/api/orgs/:id/teams", post(api::organizations::create_team))
+        .route(
+            "/api/orgs/:id/teams/:team_id",
+            delete(api::organizations::delete_team),
+        )
+        .route(
+            "/api/orgs/:id/teams/:team_id/members/:user_id",
+            put(api::organizations::add_team_member)
+                .delete(api::organizations::remove_team_member),
+        )
+        .route(
+            "/api/orgs/:id/projects/:project_id",
+            put(api::organizations::assign_project).delete(api::organizations::remove_project),
+        )
+        .route(
+            "/api/orgs/:id/api-keys",
+            post(api::organizations::issue_api_key),
+        )
+        .route(
+            "/api/orgs/:id/sso",
+            get(api::organizations::get_sso_provider)
+                .put(api::organizations::set_sso_provider)
+                .delete(api::organizations::delete_sso_provider),
+        )
+        // 👑 Admin debugging endpoints
+        .route(
+            "/api/admin/llm-exchanges",
+            get(api::admin::list_llm_exchanges),
+        )
+        .route(
+            "/api/admin/llm-exchanges/:id",
+            get(api::admin::get_llm_exchange),
+        )
+        .route("/api/admin/prompts", get(api::admin::list_prompt_versions))
+        .route(
+            "/api/admin/prompts/:name/versions",
+            post(api::admin::create_prompt_version),
+        )
+        .route(
+            "/api/admin/prompts/:name/versions/:version/traffic",
+            put(api::admin::set_prompt_traffic),
+        )
+        .route(
+            "/api/admin/prompts/:name/versions/:version/activate",
+            post(api::admin::activate_prompt_version),
+        )
+        .route(
+            "/api/admin/prompts/:name/stats",
+            get(api::admin::get_prompt_stats),
+        )
+        .route(
+            "/api/admin/jobs/dead",
+            get(api::admin::list_dead_jobs).delete(api::admin::purge_dead_jobs),
+        )
+        .route(
+            "/api/admin/jobs/dead/requeue",
+            post(api::admin::requeue_dead_jobs),
+        )
+        .route("/api/admin/jobs/:id", get(api::admin::get_job))
+        .route("/api/admin/jobs/:id/requeue", post(api::admin::requeue_job))
+        .route("/api/admin/schedules", get(api::admin::list_schedules))
+        .route(
+            "/api/admin/schedules/:name/pause",
+            post(api::admin::pause_schedule),
+        )
+        .route(
+            "/api/admin/schedules/:name/resume",
+            post(api::admin::resume_schedule),
+        )
+        .route(
+            "/api/admin/schedules/:name/trigger",
+            post(api::admin::trigger_schedule),
+        )
+        .route("/api/admin/config/reload", post(api::admin::reload_config))
+        .route("/api/admin/overview", get(api::admin::get_overview))
+        .route("/api/admin/users", get(api::admin::list_users))
+        .route("/api/admin/users/:user_id", put(api::admin::update_user))
+        .route(
+            "/api/admin/roles",
+            get(api::admin::list_roles).post(api::admin::create_role),
+        )
+        .route(
+            "/api/admin/roles/:name",
+            put(api::admin::update_role).delete(api::admin::delete_role),
+        )
+        .route(
+            "/api/admin/users/:user_id/roles/:name",
+            put(api::admin::assign_role).delete(api::admin::unassign_role),
+        )
+        .route(
+            "/api/admin/orgs/:id/quotas",
+            put(api::admin::set_organization_quotas),
+        )
+        .route(
+            "/api/admin/maintenance",
+            get(api::admin::get_maintenance).put(api::admin::set_maintenance),
+        )
+        .route(
+            "/api/admin/feature-flags",
+            get(api::admin::list_feature_flags).post(api::admin::create_feature_flag),
+        )
+        .route(
+            "/api/admin/feature-flags/:name",
+            get(api::admin::get_feature_flag)
+                .put(api::admin::update_feature_flag)
+                .delete(api::admin::delete_feature_flag),
+        )
+        .route(
+            "/api/admin/feature-flags/:name/evaluate",
+            get(api::admin::evaluate_feature_flag),
+        )
+        .route(
+            "/api/admin/feature-flags/:name/projects/:project_id",
+            put(api::admin::set_feature_flag_override)
+                .delete(api::admin::clear_feature_flag_override),
+        )
+        // 🐙 GitHub webhook endpoint for status updates (deliveries are logged)
+        .route(
+            "/api/webhook/github",
+            post(api::webhooks::github_webhook).layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                webhook_delivery_middleware,
+            )),
+        )
         // 🎯 GitHub issue automation webhooks
-        .route("/api/webhook/issues", post(api::issue_hooks::github_issue_webhook))
+        .route(
+            "/api/webhook/issues",
+            post(api::issue_hooks::github_issue_webhook).layer(
+                axum_middleware::from_fn_with_state(app_state.clone(), webhook_delivery_middleware),
+            ),
+        )
         // 🔧 Manual issue management endpoints
         .route("/api/issues/:owner/:repo/:issue_number/comment", post(api::issue_hooks::add_issue_comment))
         .route("/api/issues/:owner/:repo/:issue_number/labels", post(api::issue_hooks::add_issue_labels))
@@ -164,12 +537,22 @@ fn create_router(app_state: api::AppState, config: &Config) -> Result<Router> {
         // 🤖 Smart Tree integration endpoint
         .route(
             "/api/smart-tree/latest",
-            get(api::smart_tree::get_latest_version),
+            get(api::smart_tree::get_latest_version)
+                .layer(axum_middleware::from_fn(etag_middleware)),
         )
         // 🔐 Authentication endpoints
         .route("/api/auth/login", post(api::auth::login))
         .route("/api/auth/logout", post(api::auth::logout))
-        .route("/api/auth/register", post(api::auth::register));
+        .route("/api/users/me/preferences", put(api::auth::update_preferences))
+        // 👤 The signed-in user's own profile, password and notifications
+        .route("/api/users/me", get(api::users::get_profile).patch(api::users::update_profile))
+        .route("/api/users/me/password", put(api::users::change_password))
+        .route("/api/users/me/notifications", get(api::users::list_notifications))
+        .route("/api/users/me/notifications/:id/read", post(api::users::mark_notification_read))
+        .route("/api/auth/register", post(api::auth::register))
+        .route("/api/auth/sso/:slug/login", get(api::sso::login))
+        .route("/api/auth/sso/:slug/callback", get(api::sso::callback))
+        .route("/.well-known/jwks.json", get(api::auth::jwks));
 
     // 🎨 Create the web UI router for our beautiful interface
     let web_router = Router::new()
@@ -178,6 +561,19 @@ fn create_router(app_state: api::AppState, config: &Config) -> Result<Router> {
         // 📊 Project dashboard
         .route("/projects", get(api::web::projects_page))
         .route("/projects/:id", get(api::web::project_detail_page))
+        // 🌍 Opt-in public status pages (public, see middleware/auth.rs)
+        .route("/p/:slug/status", get(api::web::public_status_page))
+        // 📭 Digest opt-out from the link in every digest (public, signed)
+        .route("/digest/unsubscribe", get(api::web::digest_unsubscribe_page))
+        // 🙋 Claiming anonymous feedback (signed link)
+        .route("/feedback/claim", get(api::web::feedback_claim_page))
+        // 👑 Admin console (SystemAdmin only, see middleware/auth.rs)
+        .route("/admin", get(api::web::admin_page))
+        .route("/admin/users", get(api::web::admin_users_page))
+        // 🔍 Proposed changes, for review before merging
+        .route("/feedback/:id/diff", get(api::web::feedback_diff_page))
+        // 📝 Feedback form with live progress
+        .route("/submit", get(api::web::submit_page))
         // 🔐 Authentication pages
         .route("/login", get(api::web::login_page))
         .route("/register", get(api::web::register_page))
@@ -185,30 +581,73 @@ fn create_router(app_state: api::AppState, config: &Config) -> Result<Router> {
         .route("/docs", get(api::web::docs_page))
         .route("/about", get(api::web::about_page));
 
-    // 🛡️ Apply middleware layers (like adding layers to a delicious cake!)
-    let app = Router::new()
+    let routes = Router::new()
         .merge(api_router)
         .merge(web_router)
-        .layer(
+        // 🧭 Unknown API paths get a JSON 404 instead of an empty one
+        .route("/api", any(api::fallback::not_found))
+        .route("/api/*path", any(api::fallback::not_found))
+        // 🔍 Anything else is a page that isn't there
+        .fallback(api::web::not_found_page)
+        .with_state(app_state.clone());
+
+    // 🛡️ Apply middleware layers (like adding layers to a delicious cake!)
+    // They wrap the router as a whole, so they see finished responses: axum only
+    // adds the Allow header to a 405 after any per-route layers have run
+    let app = Router::new().fallback_service(routes).layer(
             ServiceBuilder::new()
                 // 📊 Tracing layer for request logging
                 .layer(TraceLayer::new_for_http())
+                // 🧯 Request ids, and error details only where it's safe to show them
+                .layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    error_handling_middleware,
+                ))
+                // 📊 Access log of every request (inside the request id, outside auth)
+                .layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    logging_middleware,
+                ))
+                // 🌍 Messages in the client's language (Accept-Language)
+                .layer(axum_middleware::from_fn(locale_middleware))
+                // 🧱 Admin and webhook routes only from their allowed networks
+                .layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    ip_allowlist_middleware,
+                ))
+                // 💥 Panicking handlers become a logged 500 (inside the error scope, so with the request id)
+                .layer(CatchPanicLayer::custom(errors::panic_response))
                 // 🗜️ Compression for faster responses
                 .layer(CompressionLayer::new())
                 // 🌍 CORS support for web clients
                 .layer(CorsLayer::permissive()) // TODO: Make this more restrictive in production
-                // 🚦 Rate limiting to prevent abuse
+                // 📄 Errors as RFC 7807 problem documents, for clients that ask
+                .layer(axum_middleware::from_fn(problem_json_middleware))
+                // 🚷 Wrong-method requests get a JSON 405 listing what's allowed
+                .layer(axum_middleware::map_response(
+                    api::fallback::describe_method_not_allowed,
+                ))
+                // 🔐 Authentication middleware for protected routes
+                .layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                ))
+                // 🚦 Rate limiting to prevent abuse (after auth, so API keys get their own tier)
                 .layer(axum_middleware::from_fn_with_state(
                     app_state.clone(),
                     rate_limit_middleware,
                 ))
-                // 🔐 Authentication middleware for protected routes
+                // 🔏 Accounts with a signing key must sign every request (after auth, to know the key)
                 .layer(axum_middleware::from_fn_with_state(
                     app_state.clone(),
-                    auth_middleware,
+                    request_signing_middleware,
+                ))
+                // 🚧 Maintenance mode (after auth, so admins can still make changes)
+                .layer(axum_middleware::from_fn_with_state(
+                    app_state.clone(),
+                    maintenance_middleware,
                 )),
-        )
-        .with_state(app_state);
+    );
 
     info!("🎉 Router created successfully with all middleware layers!");
 
@@ -216,7 +655,8 @@ fn create_router(app_state: api::AppState, config: &Config) -> Result<Router> {
 }
 
 // 🏠 Home page handler - Our beautiful welcome page!
-async fn web_home() -> impl IntoResponse {
+async fn web_home(State(app_state): State<api::AppState>) -> impl IntoResponse {
+    let banner = api::web::maintenance_banner(&app_state).await;
     Html(
         r#"
 <!DOCTYPE html>
@@ -302,6 +742,7 @@ async fn web_home() -> impl IntoResponse {
 
         <div style="margin-top: 40px;">
             <a href="/projects" class="button">📊 View Projects</a>
+            <a href="/submit" class="button">📝 Submit Feedback</a>
             <a href="/docs" class="button">📚 Documentation</a>
             <a href="/about" class="button">ℹ️ About</a>
         </div>
@@ -312,7 +753,8 @@ async fn web_home() -> impl IntoResponse {
     </div>
 </body>
 </html>
-    "#,
+    "#
+        .replace("<body>", &format!("<body>\n{}", banner)),
     )
 }
 
@@ -381,7 +823,7 @@ mod tests {
     #[tokio::test]
     async fn test_logging_initialization() {
         // This test ensures our logging setup doesn't panic
-        let result = init_logging();
+        let result = init_logging(false);
         assert!(result.is_ok());
         println!("✅ Logging initialization test passed!");
     }
@@ -0,0 +1,105 @@
+// 🚧 Maintenance Mode - Hold the Writes While We Work! 🚧
+// For migrations and incidents: while maintenance mode is on, the API refuses
+// mutating requests from everyone but admins with a 503 (reads keep working),
+// job workers stop claiming new jobs (running ones finish), and the web UI shows
+// a banner. It is switched on either way:
+//   - ⚙️ MAINTENANCE_MODE=true, at startup or on a config reload
+//   - 👑 PUT /api/admin/maintenance, stored as the `maintenance_mode` feature
+//     flag so every instance follows (within the flag cache TTL)
+// Created with love by Aye & Hue - Pardon our dust! ✨
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::database::models::FeatureFlag;
+use crate::feature_flags::{names, FeatureFlags};
+use crate::reload::LiveConfig;
+
+/// 🚧 Whether maintenance mode is on, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    /// 💬 What users are told
+    pub message: String,
+    /// 🏷️ Who switched it on: "configuration" or "admin"
+    pub source: Option<&'static str>,
+    /// ⏰ When an admin switched it on
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// 🚧 Maintenance mode, as seen from the configuration and the feature flags
+#[derive(Debug)]
+pub struct Maintenance {
+    live: Arc<LiveConfig>,
+    flags: Arc<FeatureFlags>,
+}
+
+impl Maintenance {
+    pub fn new(live: Arc<LiveConfig>, flags: Arc<FeatureFlags>) -> Self {
+        Self { live, flags }
+    }
+
+    /// 🔍 Current status; the configuration wins over the admin switch
+    pub async fn status(&self) -> MaintenanceStatus {
+        let config = self.live.current().maintenance.clone();
+        if config.enabled {
+            return MaintenanceStatus {
+                enabled: true,
+                message: config.message,
+                source: Some("configuration"),
+                since: None,
+            };
+        }
+
+        let flags = self.flags.snapshot().await;
+        if flags.evaluate(names::MAINTENANCE_MODE, None) == Some(true) {
+            let (description, since) = flags.details(names::MAINTENANCE_MODE).unwrap_or_default();
+            return MaintenanceStatus {
+                enabled: true,
+                message: description
+                    .filter(|message| !message.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or(config.message),
+                source: Some("admin"),
+                since,
+            };
+        }
+
+        MaintenanceStatus {
+            enabled: false,
+            message: config.message,
+            source: None,
+            since: None,
+        }
+    }
+
+    /// ✅ Whether maintenance mode is on
+    pub async fn is_active(&self) -> bool {
+        self.status().await.enabled
+    }
+
+    /// 👑 Switch the admin side on or off (a new message replaces the stored one)
+    pub async fn set(
+        &self,
+        db_pool: &PgPool,
+        enabled: bool,
+        message: Option<&str>,
+    ) -> Result<MaintenanceStatus> {
+        match FeatureFlag::find_by_name(db_pool, names::MAINTENANCE_MODE).await? {
+            Some(mut flag) => {
+                let description = message.map(str::to_string).or(flag.description.clone());
+                flag.update(db_pool, description.as_deref(), enabled, 100)
+                    .await?;
+            }
+            None => {
+                FeatureFlag::create(db_pool, names::MAINTENANCE_MODE, message, enabled, 100)
+                    .await?;
+            }
+        }
+        self.flags.invalidate();
+        Ok(self.status().await)
+    }
+}
@@ -0,0 +1,516 @@
+// 🤖 MCP Server - AI Agents Talk to Feedbacker Directly! 🤖
+// Speaks the Model Context Protocol (JSON-RPC 2.0) so agents can submit feedback,
+// follow it and list projects as tools, with no HTTP glue of their own. Two
+// transports: `feedbacker mcp` on stdin/stdout (one message per line, signed in
+// with the API key in $FEEDBACKER_API_KEY) and POST /api/mcp (signed in like any
+// other API call). Tools call the same functions as the HTTP handlers, and the
+// account's permissions and API key scopes apply to them just the same
+// Created with love by Aye & Hue - Robots filing feedback for robots! ✨
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        feedback::{self, SubmitFeedbackRequest, SubmitRejection},
+        projects, AppState,
+    },
+    errors::ErrorKind,
+    i18n,
+    middleware::auth::{ApiScope, AuthenticatedUser, Permission},
+};
+
+/// 📜 Protocol revisions we speak, newest first
+pub const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// 🔧 A tool agents can call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// 📝 Submit feedback for a repository
+    SubmitFeedback,
+    /// 🔍 Status of submitted feedback
+    GetFeedbackStatus,
+    /// 📋 Projects the account can see
+    ListProjects,
+}
+
+impl Tool {
+    /// 📋 Every tool, in the order tools/list shows them
+    pub const ALL: [Tool; 3] = [
+        Tool::SubmitFeedback,
+        Tool::GetFeedbackStatus,
+        Tool::ListProjects,
+    ];
+
+    /// 🏷️ Name agents call it by
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::SubmitFeedback => "submit_feedback",
+            Tool::GetFeedbackStatus => "get_feedback_status",
+            Tool::ListProjects => "list_projects",
+        }
+    }
+
+    /// 🔍 A tool by name
+    pub fn from_name(name: &str) -> Option<Tool> {
+        Tool::ALL.into_iter().find(|tool| tool.name() == name)
+    }
+
+    /// 🔑 API key scope the tool needs (the HTTP API's for the same thing)
+    fn scope(self) -> ApiScope {
+        match self {
+            Tool::SubmitFeedback => ApiScope::FeedbackWrite,
+            Tool::GetFeedbackStatus => ApiScope::FeedbackRead,
+            Tool::ListProjects => ApiScope::ProjectsRead,
+        }
+    }
+
+    /// 🎯 Permission the tool needs, if any
+    fn permission(self) -> Option<Permission> {
+        match self {
+            Tool::GetFeedbackStatus => Some(Permission::ReadFeedback),
+            Tool::SubmitFeedback | Tool::ListProjects => None,
+        }
+    }
+
+    /// 📄 How tools/list describes it
+    fn definition(self) -> Value {
+        let (description, input_schema) = match self {
+            Tool::SubmitFeedback => (
+                "Submit feedback about a GitHub repository. Feedbacker turns it into a \
+                 pull request; follow its progress with get_feedback_status.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "repository": {
+                            "type": "string",
+                            "description": "Target repository, as owner/repo"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "What to improve (10 to 10000 characters)"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Monorepo subdirectory to scope the change to"
+                        },
+                        "llm_provider": {
+                            "type": "string",
+                            "description": "LLM provider to use instead of the project's default"
+                        }
+                    },
+                    "required": ["repository", "content"]
+                }),
+            ),
+            Tool::GetFeedbackStatus => (
+                "Current status of submitted feedback, with its branch and pull request once \
+                 they exist.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "feedback_id": {
+                            "type": "string",
+                            "format": "uuid",
+                            "description": "Id returned by submit_feedback"
+                        }
+                    },
+                    "required": ["feedback_id"]
+                }),
+            ),
+            Tool::ListProjects => (
+                "Repositories registered with Feedbacker that this account can see.",
+                json!({ "type": "object", "properties": {} }),
+            ),
+        };
+        json!({
+            "name": self.name(),
+            "description": description,
+            "inputSchema": input_schema,
+        })
+    }
+}
+
+/// ❌ A JSON-RPC error
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    /// 🔢 JSON-RPC error code
+    pub code: i64,
+    /// 📝 What went wrong
+    pub message: String,
+}
+
+impl RpcError {
+    fn parse_error(e: impl std::fmt::Display) -> Self {
+        Self {
+            code: -32700,
+            message: format!("Parse error: {}", e),
+        }
+    }
+
+    fn invalid_request(message: &str) -> Self {
+        Self {
+            code: -32600,
+            message: format!("Invalid request: {}", message),
+        }
+    }
+
+    fn method_not_found(method: &str) -> Self {
+        Self {
+            code: -32601,
+            message: format!("Method not found: {}", method),
+        }
+    }
+
+    fn invalid_params(e: impl std::fmt::Display) -> Self {
+        Self {
+            code: -32602,
+            message: format!("Invalid params: {}", e),
+        }
+    }
+}
+
+/// 📨 A JSON-RPC request or notification (no id)
+#[derive(Debug, PartialEq)]
+pub struct RpcRequest {
+    /// 🆔 Request id, echoed in the response (None for notifications)
+    pub id: Option<Value>,
+    /// 🎯 Method called
+    pub method: String,
+    /// 📦 Its parameters ({} when omitted)
+    pub params: Value,
+}
+
+/// 📨 Read a JSON-RPC message
+pub fn parse_request(message: Value) -> Result<RpcRequest, RpcError> {
+    let Value::Object(mut message) = message else {
+        return Err(RpcError::invalid_request("expected an object"));
+    };
+    if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err(RpcError::invalid_request("jsonrpc must be \"2.0\""));
+    }
+    let Some(Value::String(method)) = message.remove("method") else {
+        return Err(RpcError::invalid_request("method must be a string"));
+    };
+    let id = match message.remove("id") {
+        None => None,
+        Some(id @ (Value::String(_) | Value::Number(_))) => Some(id),
+        Some(_) => return Err(RpcError::invalid_request("id must be a string or number")),
+    };
+    Ok(RpcRequest {
+        id,
+        method,
+        params: message.remove("params").unwrap_or_else(|| json!({})),
+    })
+}
+
+/// 📬 The response to a request
+pub fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+/// 🤝 Answer to initialize: the client's protocol revision when we speak it, else our newest
+pub fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| Some(*version) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "feedbacker", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Submit feedback about a repository with submit_feedback, then follow \
+                         it with get_feedback_status until its pull request is open.",
+    })
+}
+
+/// ✅ A tool's successful result, as text and as structured content
+fn tool_result(value: Value) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": value.to_string() }],
+        "structuredContent": value,
+        "isError": false,
+    })
+}
+
+/// ❌ A tool call that failed, explained to the agent
+fn tool_error(message: String) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": message }],
+        "isError": true,
+    })
+}
+
+/// 🔧 tools/call parameters
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Option<Value>,
+}
+
+/// 🔍 get_feedback_status arguments
+#[derive(Debug, Deserialize)]
+struct FeedbackStatusArguments {
+    feedback_id: Uuid,
+}
+
+/// 🤖 Answers MCP messages on behalf of one signed-in account
+pub struct McpServer {
+    app_state: AppState,
+    user: AuthenticatedUser,
+}
+
+impl McpServer {
+    /// ➕ A server acting as this user
+    pub fn new(app_state: AppState, user: AuthenticatedUser) -> Self {
+        Self { app_state, user }
+    }
+
+    /// 📨 Answer one message (None for notifications, which get no answer)
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let request = match parse_request(message) {
+            Ok(request) => request,
+            Err(error) => return Some(response(Value::Null, Err(error))),
+        };
+        let Some(id) = request.id else {
+            debug!("🤖 MCP notification: {}", request.method);
+            return None;
+        };
+
+        let result = match request.method.as_str() {
+            "initialize" => Ok(initialize_result(&request.params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({
+                "tools": Tool::ALL.map(Tool::definition),
+            })),
+            "tools/call" => self.call_tool(request.params).await,
+            method => Err(RpcError::method_not_found(method)),
+        };
+        Some(response(id, result))
+    }
+
+    /// 🔧 Run a tool; failures the agent can act on come back as error results
+    async fn call_tool(&self, params: Value) -> Result<Value, RpcError> {
+        let call: ToolCall = serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+        let tool = Tool::from_name(&call.name)
+            .ok_or_else(|| RpcError::invalid_params(format!("Unknown tool: {}", call.name)))?;
+        let arguments = call.arguments.unwrap_or_else(|| json!({}));
+
+        if !self.user.has_scope(tool.scope()) {
+            warn!(
+                "🚫 API key of {} lacks the {} scope for MCP tool {}",
+                self.user.email,
+                tool.scope().as_str(),
+                tool.name()
+            );
+            return Ok(tool_error(i18n::t_with(
+                "auth-missing-scope",
+                [("scope", tool.scope().as_str().into())],
+            )));
+        }
+        if let Some(permission) = tool.permission() {
+            if !self.user.has_permission(permission) {
+                warn!(
+                    "🚫 {} lacks {:?} for MCP tool {}",
+                    self.user.email,
+                    permission,
+                    tool.name()
+                );
+                return Ok(tool_error(i18n::t("auth-insufficient-permissions")));
+            }
+        }
+
+        info!("🤖 MCP tool {} called by {}", tool.name(), self.user.email);
+        match tool {
+            Tool::SubmitFeedback => self.submit_feedback(arguments).await,
+            Tool::GetFeedbackStatus => self.get_feedback_status(arguments).await,
+            Tool::ListProjects => self.list_projects().await,
+        }
+    }
+
+    /// 📝 submit_feedback
+    async fn submit_feedback(&self, arguments: Value) -> Result<Value, RpcError> {
+        let request: SubmitFeedbackRequest =
+            serde_json::from_value(arguments).map_err(RpcError::invalid_params)?;
+        Ok(match feedback::submit(&self.app_state, Some(self.user.id), request).await {
+            Ok(submitted) => tool_result(json!(submitted)),
+            Err(SubmitRejection::Invalid(errors)) => tool_error(format!(
+                "{}: {}",
+                ErrorKind::Validation.title(),
+                errors.join("; ")
+            )),
+            Err(SubmitRejection::QuotaExceeded(message))
+            | Err(SubmitRejection::RepositoryNotAllowed(message))
+            | Err(SubmitRejection::RepositoryNotAccessible(message)) => tool_error(message),
+            Err(SubmitRejection::Failed(e)) => internal_error("submit_feedback", e),
+        })
+    }
+
+    /// 🔍 get_feedback_status
+    async fn get_feedback_status(&self, arguments: Value) -> Result<Value, RpcError> {
+        let FeedbackStatusArguments { feedback_id } =
+            serde_json::from_value(arguments).map_err(RpcError::invalid_params)?;
+        Ok(
+            match feedback::fetch_feedback_details(&self.app_state, &self.user, feedback_id).await {
+                Ok(Some(details)) => tool_result(json!(details)),
+                Ok(None) => tool_error(i18n::t_with(
+                    "resource-not-found",
+                    [("resource", "Feedback".into())],
+                )),
+                Err(e) => internal_error("get_feedback_status", e),
+            },
+        )
+    }
+
+    /// 📋 list_projects
+    async fn list_projects(&self) -> Result<Value, RpcError> {
+        Ok(
+            match projects::visible_projects(&self.app_state.db_pool, &self.user).await {
+                Ok(projects) => tool_result(json!({ "projects": projects })),
+                Err(e) => internal_error("list_projects", e),
+            },
+        )
+    }
+}
+
+/// 💥 Log an unexpected failure; the agent only hears that something broke
+fn internal_error(tool: &str, e: anyhow::Error) -> Value {
+    error!("❌ MCP tool {} failed: {:#}", tool, e);
+    tool_error(ErrorKind::Internal.title())
+}
+
+/// 🖥️ Serve MCP on stdin/stdout until stdin closes
+/// Messages are one JSON object per line; logs go to stderr
+pub async fn serve_stdio(server: McpServer) -> Result<()> {
+    let locale = server.user.locale.unwrap_or_default();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    info!("🤖 MCP server ready on stdio as {}", server.user.email);
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => locale.scope(server.handle(message)).await,
+            Err(e) => Some(response(Value::Null, Err(RpcError::parse_error(e)))),
+        };
+        if let Some(reply) = reply {
+            stdout.write_all(format!("{}\n", reply).as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+
+    info!("👋 MCP client disconnected");
+    Ok(())
+}
+
+// 🧪 Tests - Speaking JSON-RPC fluently!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse_request(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": "list_projects" }
+        }))
+        .unwrap();
+        assert_eq!(request.id, Some(json!(7)));
+        assert_eq!(request.method, "tools/call");
+        assert_eq!(request.params["name"], "list_projects");
+
+        let notification =
+            parse_request(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+                .unwrap();
+        assert_eq!(notification.id, None);
+        assert_eq!(notification.params, json!({}));
+
+        for invalid in [
+            json!([1, 2]),
+            json!({ "id": 1, "method": "ping" }),
+            json!({ "jsonrpc": "2.0", "id": 1 }),
+            json!({ "jsonrpc": "2.0", "id": {}, "method": "ping" }),
+        ] {
+            assert_eq!(parse_request(invalid).unwrap_err().code, -32600);
+        }
+        println!("✅ MCP request parsing test passed!");
+    }
+
+    #[test]
+    fn test_responses() {
+        assert_eq!(
+            response(json!("a"), Ok(json!({}))),
+            json!({ "jsonrpc": "2.0", "id": "a", "result": {} })
+        );
+        let error = response(
+            Value::Null,
+            Err(RpcError::method_not_found("resources/list")),
+        );
+        assert_eq!(error["error"]["code"], -32601);
+        assert_eq!(
+            error["error"]["message"],
+            "Method not found: resources/list"
+        );
+
+        let failed = tool_error("nope".to_string());
+        assert_eq!(failed["isError"], true);
+        assert_eq!(failed["content"][0]["text"], "nope");
+        let ok = tool_result(json!({ "projects": [] }));
+        assert_eq!(ok["structuredContent"], json!({ "projects": [] }));
+        assert_eq!(ok["content"][0]["text"], r#"{"projects":[]}"#);
+        println!("✅ MCP response test passed!");
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version() {
+        let old = initialize_result(&json!({ "protocolVersion": "2024-11-05" }));
+        assert_eq!(old["protocolVersion"], "2024-11-05");
+        assert_eq!(old["serverInfo"]["name"], "feedbacker");
+        assert!(old["capabilities"]["tools"].is_object());
+
+        let unknown = initialize_result(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(unknown["protocolVersion"], PROTOCOL_VERSIONS[0]);
+        println!("✅ MCP version negotiation test passed!");
+    }
+
+    #[test]
+    fn test_tools() {
+        for tool in Tool::ALL {
+            assert_eq!(Tool::from_name(tool.name()), Some(tool));
+            let definition = tool.definition();
+            assert_eq!(definition["name"], tool.name());
+            assert_eq!(definition["inputSchema"]["type"], "object");
+        }
+        assert_eq!(Tool::from_name("delete_everything"), None);
+        assert_eq!(
+            Tool::SubmitFeedback.definition()["inputSchema"]["required"],
+            json!(["repository", "content"])
+        );
+
+        // 📝 The submit_feedback schema matches what the HTTP API accepts
+        let request: SubmitFeedbackRequest = serde_json::from_value(json!({
+            "repository": "aye-is/feedbacker",
+            "content": "Please add a dark mode to the dashboard",
+            "path": "web",
+        }))
+        .unwrap();
+        assert_eq!(request.path.as_deref(), Some("web"));
+        println!("✅ MCP tool definitions test passed!");
+    }
+}
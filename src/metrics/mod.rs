@@ -0,0 +1,350 @@
+// 📈 Metrics Module - Numbers That Tell Us How We're Doing! 📈
+// Process-wide Prometheus metrics, rendered in text format at /metrics
+// Created with love by Aye & Hue - If we can't measure it, we can't improve it! ✨
+
+use axum::{http::header, response::IntoResponse};
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::time::Duration;
+
+lazy_static::lazy_static! {
+    /// 📚 Registry holding every Feedbacker metric
+    pub static ref REGISTRY: Registry = Registry::new();
+
+    /// ⏱️ Time spent preparing repository checkouts, by source (clone | cache)
+    pub static ref GIT_CHECKOUT_SECONDS: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new(
+            "feedbacker_git_checkout_seconds",
+            "Time spent preparing a repository checkout",
+        )
+        .buckets(vec![0.05, 0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0]),
+        &["source"],
+    ));
+
+    /// 🗃️ Clone cache lookups, by result (hit | miss)
+    pub static ref GIT_CLONE_CACHE_LOOKUPS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_git_clone_cache_lookups_total", "Clone cache lookups"),
+        &["result"],
+    ));
+
+    /// 🗃️ Response cache lookups, by namespace, layer (local | redis) and result (hit | miss)
+    pub static ref RESPONSE_CACHE_LOOKUPS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_response_cache_lookups_total", "Response cache lookups"),
+        &["namespace", "layer", "result"],
+    ));
+
+    /// 🧹 Response cache invalidations, by namespace
+    pub static ref RESPONSE_CACHE_INVALIDATIONS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new(
+            "feedbacker_response_cache_invalidations_total",
+            "Response cache invalidations",
+        ),
+        &["namespace"],
+    ));
+
+    /// 🤖 LLM calls per provider, by outcome (success | failure | skipped | too_large)
+    pub static ref LLM_REQUESTS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_llm_requests_total", "LLM calls per provider"),
+        &["provider", "outcome"],
+    ));
+
+    /// 🏊‍♂️ Database pool connections, by state (idle | active)
+    pub static ref DB_POOL_CONNECTIONS: IntGaugeVec = register(IntGaugeVec::new(
+        Opts::new("feedbacker_db_pool_connections", "Open database pool connections"),
+        &["state"],
+    ));
+
+    /// 🎯 Most connections the database pool will open
+    pub static ref DB_POOL_MAX_CONNECTIONS: IntGauge = register(IntGauge::new(
+        "feedbacker_db_pool_max_connections",
+        "Database pool size limit",
+    ));
+
+    /// ⏱️ Time to get a connection from the pool (sampled)
+    pub static ref DB_POOL_ACQUIRE_SECONDS: Histogram = register(Histogram::with_opts(
+        HistogramOpts::new(
+            "feedbacker_db_pool_acquire_seconds",
+            "Time to get a database connection from the pool",
+        )
+        .buckets(vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0]),
+    ));
+
+    /// ⌛ Sampled acquires that gave up waiting for a connection
+    pub static ref DB_POOL_ACQUIRE_TIMEOUTS: IntCounter = register(IntCounter::new(
+        "feedbacker_db_pool_acquire_timeouts_total",
+        "Database connection acquires that timed out",
+    ));
+
+    /// 🚦 GitHub API requests left in the current window, by resource (core | search | graphql ...)
+    pub static ref GITHUB_RATE_LIMIT_REMAINING: IntGaugeVec = register(IntGaugeVec::new(
+        Opts::new(
+            "feedbacker_github_rate_limit_remaining",
+            "GitHub API requests left before the rate limit resets",
+        ),
+        &["resource"],
+    ));
+
+    /// 🎯 GitHub API requests allowed per window, by resource
+    pub static ref GITHUB_RATE_LIMIT_LIMIT: IntGaugeVec = register(IntGaugeVec::new(
+        Opts::new("feedbacker_github_rate_limit_limit", "GitHub API requests allowed per window"),
+        &["resource"],
+    ));
+
+    /// ⏸️ GitHub calls held back by the rate limit, by outcome (retried | deferred)
+    pub static ref GITHUB_RATE_LIMITED_CALLS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new(
+            "feedbacker_github_rate_limited_calls_total",
+            "GitHub calls held back by the rate limit",
+        ),
+        &["outcome"],
+    ));
+
+    /// 🗄️ Time spent in database statements, by operation and table
+    pub static ref DB_QUERY_SECONDS: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new(
+            "feedbacker_db_query_duration_seconds",
+            "Time spent executing database statements",
+        )
+        .buckets(vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+        &["operation", "table"],
+    ));
+
+    /// 🐢 Statements over the slow query threshold, by operation and table
+    pub static ref DB_SLOW_QUERIES: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_db_slow_queries_total", "Slow database statements"),
+        &["operation", "table"],
+    ));
+
+    /// 🧹 Rows removed (or cleared) by retention cleanup, by table
+    pub static ref RETENTION_CLEANUP_ROWS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_retention_cleanup_rows_total", "Rows removed by retention cleanup"),
+        &["table"],
+    ));
+
+    /// ⏱️ Stuck feedback found by the watchdog, by status and action (failed | requeued)
+    pub static ref STUCK_FEEDBACK: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_stuck_feedback_total", "Feedback found stuck mid-pipeline"),
+        &["status", "action"],
+    ));
+
+    /// ⏳ Seconds the longest waiting in-flight item has gone without an update, by status
+    pub static ref FEEDBACK_OLDEST_IN_STATUS: IntGaugeVec = register(IntGaugeVec::new(
+        Opts::new(
+            "feedbacker_feedback_oldest_in_status_seconds",
+            "Time since the longest waiting item in a status was updated",
+        ),
+        &["status"],
+    ));
+
+    /// 💸 Runs stopped at a token budget, by scope (feedback | project)
+    pub static ref BUDGET_EXCEEDED: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("feedbacker_budget_exceeded_total", "Runs paused at a token budget"),
+        &["scope"],
+    ));
+}
+
+/// 📝 Register a collector with the global registry
+fn register<T>(result: prometheus::Result<T>) -> T
+where
+    T: prometheus::core::Collector + Clone + 'static,
+{
+    let collector = result.expect("metric definition is valid");
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("metric is registered once");
+    collector
+}
+
+/// ⏱️ Record how long a checkout took and where it came from
+pub fn observe_git_checkout(source: &str, elapsed: Duration) {
+    GIT_CHECKOUT_SECONDS
+        .with_label_values(&[source])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 🗃️ Record a clone cache hit or miss
+pub fn record_clone_cache_lookup(hit: bool) {
+    GIT_CLONE_CACHE_LOOKUPS
+        .with_label_values(&[if hit { "hit" } else { "miss" }])
+        .inc();
+}
+
+/// 🗃️ Record a response cache hit or miss in one layer
+pub fn record_response_cache_lookup(namespace: &str, layer: &str, hit: bool) {
+    RESPONSE_CACHE_LOOKUPS
+        .with_label_values(&[namespace, layer, if hit { "hit" } else { "miss" }])
+        .inc();
+}
+
+/// 🧹 Record a response cache invalidation
+pub fn record_response_cache_invalidation(namespace: &str) {
+    RESPONSE_CACHE_INVALIDATIONS
+        .with_label_values(&[namespace])
+        .inc();
+}
+
+/// 🤖 Record the outcome of an LLM call (skipped = circuit breaker was open, too_large = over the context window)
+pub fn record_llm_request(provider: &str, outcome: &str) {
+    LLM_REQUESTS.with_label_values(&[provider, outcome]).inc();
+}
+
+/// 🚦 Record the rate limit a GitHub response reported
+pub fn observe_github_rate_limit(resource: &str, limit: u64, remaining: u64) {
+    GITHUB_RATE_LIMIT_LIMIT
+        .with_label_values(&[resource])
+        .set(limit as i64);
+    GITHUB_RATE_LIMIT_REMAINING
+        .with_label_values(&[resource])
+        .set(remaining as i64);
+}
+
+/// ⏸️ Record a GitHub call held back by the rate limit (retried = sent again after a wait, deferred = put off)
+pub fn record_github_rate_limited_call(outcome: &str) {
+    GITHUB_RATE_LIMITED_CALLS
+        .with_label_values(&[outcome])
+        .inc();
+}
+
+/// 🏊‍♂️ Record the current size of the database pool
+pub fn observe_db_pool(size: u32, idle: u32, max: u32) {
+    DB_POOL_CONNECTIONS
+        .with_label_values(&["idle"])
+        .set(idle as i64);
+    DB_POOL_CONNECTIONS
+        .with_label_values(&["active"])
+        .set(size.saturating_sub(idle) as i64);
+    DB_POOL_MAX_CONNECTIONS.set(max as i64);
+}
+
+/// ⏱️ Record how long getting a database connection took
+pub fn observe_db_acquire(elapsed: Duration) {
+    DB_POOL_ACQUIRE_SECONDS.observe(elapsed.as_secs_f64());
+}
+
+/// ⌛ Record a database connection acquire that timed out
+pub fn record_db_acquire_timeout() {
+    DB_POOL_ACQUIRE_TIMEOUTS.inc();
+}
+
+/// 🗄️ Record how long a database statement took
+pub fn observe_db_query(operation: &str, table: &str, elapsed: Duration, slow: bool) {
+    DB_QUERY_SECONDS
+        .with_label_values(&[operation, table])
+        .observe(elapsed.as_secs_f64());
+    if slow {
+        DB_SLOW_QUERIES.with_label_values(&[operation, table]).inc();
+    }
+}
+
+/// 🔢 Statements run and how many of them were slow, since startup
+pub fn db_query_totals() -> (u64, u64) {
+    let total = DB_QUERY_SECONDS
+        .collect()
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .map(|metric| metric.get_histogram().get_sample_count())
+        .sum();
+    let slow = DB_SLOW_QUERIES
+        .collect()
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .map(|metric| metric.get_counter().get_value() as u64)
+        .sum();
+    (total, slow)
+}
+
+/// 🧹 Record how many rows a cleanup run removed from a table
+pub fn record_retention_cleanup(table: &str, rows: u64) {
+    RETENTION_CLEANUP_ROWS
+        .with_label_values(&[table])
+        .inc_by(rows);
+}
+
+/// ⏱️ Record a stuck feedback item and what was done with it
+pub fn record_stuck_feedback(status: &str, action: &str) {
+    STUCK_FEEDBACK.with_label_values(&[status, action]).inc();
+}
+
+/// ⏳ Record how long the longest waiting item in a status has gone without an update
+pub fn observe_oldest_in_status(status: &str, age: Duration) {
+    FEEDBACK_OLDEST_IN_STATUS
+        .with_label_values(&[status])
+        .set(age.as_secs() as i64);
+}
+
+/// 💸 Record a run paused at a token budget
+pub fn record_budget_exceeded(scope: &str) {
+    BUDGET_EXCEEDED.with_label_values(&[scope]).inc();
+}
+
+/// 📄 Render all metrics in Prometheus text format
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+        tracing::error!("❌ Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// 📈 GET /metrics
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            TextEncoder::new().format_type().to_string(),
+        )],
+        render(),
+    )
+}
+
+// 🧪 Tests - Making sure our numbers add up!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_render() {
+        observe_git_checkout("clone", Duration::from_millis(1500));
+        record_clone_cache_lookup(true);
+        record_llm_request("anthropic", "skipped");
+        record_retention_cleanup("user_sessions", 3);
+        record_stuck_feedback("processing", "failed");
+        observe_oldest_in_status("processing", Duration::from_secs(90));
+        record_budget_exceeded("project");
+        record_response_cache_lookup("projects", "local", false);
+        observe_db_pool(5, 2, 20);
+        record_db_acquire_timeout();
+        observe_db_query("select", "feedback", Duration::from_millis(700), true);
+
+        let output = render();
+        assert!(output.contains("feedbacker_git_checkout_seconds_bucket"));
+        assert!(output.contains("feedbacker_git_clone_cache_lookups_total{result=\"hit\"}"));
+        assert!(output.contains(
+            "feedbacker_llm_requests_total{outcome=\"skipped\",provider=\"anthropic\"}"
+        ));
+        assert!(output.contains(
+            "feedbacker_retention_cleanup_rows_total{table=\"user_sessions\"}"
+        ));
+        assert!(output.contains(
+            "feedbacker_response_cache_lookups_total{layer=\"local\",namespace=\"projects\",result=\"miss\"}"
+        ));
+        assert!(output.contains("feedbacker_db_pool_connections{state=\"active\"} 3"));
+        assert!(output.contains("feedbacker_db_pool_acquire_timeouts_total"));
+        assert!(output.contains(
+            "feedbacker_db_slow_queries_total{operation=\"select\",table=\"feedback\"}"
+        ));
+        assert!(output.contains(
+            "feedbacker_stuck_feedback_total{action=\"failed\",status=\"processing\"}"
+        ));
+        assert!(output.contains(
+            "feedbacker_feedback_oldest_in_status_seconds{status=\"processing\"} 90"
+        ));
+        assert!(output.contains("feedbacker_budget_exceeded_total{scope=\"project\"}"));
+        let (total, slow) = db_query_totals();
+        assert!(total >= 1 && slow >= 1);
+        println!("✅ Metrics rendering test passed!");
+    }
+}
@@ -6,19 +6,24 @@
 
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+
+use crate::auth::keys::{self, KeyRing};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::{
-    api::{ApiResponse, AppState},
+    api::{ApiResponse, AppState, ErrorResponse},
     database::models::{User, UserRole},
+    i18n::{self, Locale},
+    middleware::{logging::RequestUser, rate_limiting::RateLimitTier},
+    organizations,
 };
 
 /// 🎫 JWT Claims structure
@@ -53,6 +58,18 @@ pub struct AuthenticatedUser {
     pub name: String,
     /// 👑 User role
     pub role: UserRole,
+    /// 🏢 Organization owning this service account (None = not limited to one)
+    pub organization_id: Option<Uuid>,
+    /// 🎯 Permissions granted by the user's roles (see crate::roles)
+    pub permissions: HashSet<Permission>,
+    /// 🔑 Scopes the account's API key is limited to (None = not limited)
+    pub scopes: Option<HashSet<ApiScope>>,
+    /// 🎚️ Rate limit tier of the account's API key
+    pub rate_limit_tier: RateLimitTier,
+    /// 🌍 Language the user picked (None = whatever their client asks for)
+    pub locale: Option<Locale>,
+    /// 🔏 Key the account must sign its requests with (None = unsigned requests)
+    pub request_signing_key: Option<String>,
     /// 🎫 Original JWT claims (for additional validation if needed)
     pub claims: Claims,
 }
@@ -69,25 +86,34 @@ impl AuthenticatedUser {
     }
 
     /// 🎯 Check if user has specific permission
+    /// Admins hold every permission; organization service accounts never get system-wide ones
     pub fn has_permission(&self, permission: Permission) -> bool {
-        match permission {
-            Permission::ReadFeedback => true, // All authenticated users can read their own feedback
-            Permission::SubmitFeedback => true, // All authenticated users can submit feedback
-            Permission::ManageProjects => matches!(self.role, UserRole::Admin | UserRole::Service),
-            Permission::ViewAllFeedback => matches!(self.role, UserRole::Admin),
-            Permission::ManageUsers => matches!(self.role, UserRole::Admin),
-            Permission::SystemAdmin => matches!(self.role, UserRole::Admin),
+        if permission.is_system_wide() && self.organization_id.is_some() {
+            return false;
+        }
+        self.is_admin() || self.permissions.contains(&permission)
+    }
+
+    /// 🔑 Check if the account's API key may be used for this scope
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        match &self.scopes {
+            Some(scopes) => scopes.contains(&scope),
+            None => true,
         }
     }
 }
 
 /// 🎯 Permission enumeration for fine-grained access control
-#[derive(Debug, Clone, PartialEq)]
+/// Granted through roles, which admins edit at /api/admin/roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Permission {
     /// 👀 Read feedback (own feedback for users, all for admins)
     ReadFeedback,
     /// 📝 Submit new feedback
     SubmitFeedback,
+    /// 👍 Approve or reject the pull requests made for feedback
+    ApprovePullRequests,
     /// 🏠 Manage projects (create, update, delete)
     ManageProjects,
     /// 📊 View all feedback (admin only)
@@ -98,6 +124,173 @@ pub enum Permission {
     SystemAdmin,
 }
 
+impl Permission {
+    /// 📋 Every permission, for the role editor
+    pub const ALL: [Permission; 7] = [
+        Permission::ReadFeedback,
+        Permission::SubmitFeedback,
+        Permission::ApprovePullRequests,
+        Permission::ManageProjects,
+        Permission::ViewAllFeedback,
+        Permission::ManageUsers,
+        Permission::SystemAdmin,
+    ];
+
+    /// 🏷️ Name stored in role_permissions
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::ReadFeedback => "read_feedback",
+            Permission::SubmitFeedback => "submit_feedback",
+            Permission::ApprovePullRequests => "approve_pull_requests",
+            Permission::ManageProjects => "manage_projects",
+            Permission::ViewAllFeedback => "view_all_feedback",
+            Permission::ManageUsers => "manage_users",
+            Permission::SystemAdmin => "system_admin",
+        }
+    }
+
+    /// 🌍 Permissions over the whole system rather than one's own work
+    pub fn is_system_wide(self) -> bool {
+        matches!(
+            self,
+            Permission::ViewAllFeedback | Permission::ManageUsers | Permission::SystemAdmin
+        )
+    }
+
+    /// 🌱 What an account role grants before any role is edited
+    /// (also used when the roles can't be read from the database)
+    pub fn defaults_for(role: &UserRole) -> HashSet<Permission> {
+        let granted: &[Permission] = match role {
+            UserRole::Admin => &Permission::ALL,
+            UserRole::Service => &[
+                Permission::ReadFeedback,
+                Permission::SubmitFeedback,
+                Permission::ApprovePullRequests,
+                Permission::ManageProjects,
+            ],
+            UserRole::User => &[
+                Permission::ReadFeedback,
+                Permission::SubmitFeedback,
+                Permission::ApprovePullRequests,
+            ],
+        };
+        granted.iter().copied().collect()
+    }
+}
+
+impl std::str::FromStr for Permission {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Permission::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown permission: {}", s))
+    }
+}
+
+/// 🔑 Area of the API a service account's key can be limited to
+/// Checked on top of permissions: a scoped key never reaches more than its
+/// account's roles allow, only less. Routes outside every area stay open to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiScope {
+    /// 👀 Read feedback and its events
+    #[serde(rename = "feedback:read")]
+    FeedbackRead,
+    /// 📝 Submit feedback and approve its pull requests
+    #[serde(rename = "feedback:write")]
+    FeedbackWrite,
+    /// 👀 Read projects and their status
+    #[serde(rename = "projects:read")]
+    ProjectsRead,
+    /// 🏠 Configure projects and start their pipelines
+    #[serde(rename = "projects:write")]
+    ProjectsWrite,
+    /// 🏷️ Comment on, label and close GitHub issues
+    #[serde(rename = "issues:write")]
+    IssuesWrite,
+    /// 👀 Read organizations and their teams
+    #[serde(rename = "orgs:read")]
+    OrgsRead,
+    /// 🏢 Change organizations, their members, teams and keys
+    #[serde(rename = "orgs:write")]
+    OrgsWrite,
+    /// ⚙️ Administration and user management
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiScope {
+    /// 📋 Every scope, for validation and listings
+    pub const ALL: [ApiScope; 8] = [
+        ApiScope::FeedbackRead,
+        ApiScope::FeedbackWrite,
+        ApiScope::ProjectsRead,
+        ApiScope::ProjectsWrite,
+        ApiScope::IssuesWrite,
+        ApiScope::OrgsRead,
+        ApiScope::OrgsWrite,
+        ApiScope::Admin,
+    ];
+
+    /// 🏷️ Name stored on the service account
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiScope::FeedbackRead => "feedback:read",
+            ApiScope::FeedbackWrite => "feedback:write",
+            ApiScope::ProjectsRead => "projects:read",
+            ApiScope::ProjectsWrite => "projects:write",
+            ApiScope::IssuesWrite => "issues:write",
+            ApiScope::OrgsRead => "orgs:read",
+            ApiScope::OrgsWrite => "orgs:write",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    /// 🗺️ Scope a request needs, if its route belongs to a scoped area
+    pub fn required_for(method: &Method, path: &str) -> Option<ApiScope> {
+        let reading = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        let under = |prefix: &str| path == prefix || path.starts_with(&format!("{}/", prefix));
+
+        if under("/api/admin") || under("/api/users") {
+            Some(ApiScope::Admin)
+        } else if under("/api/feedback") || under("/api/ws") {
+            Some(if reading {
+                ApiScope::FeedbackRead
+            } else {
+                ApiScope::FeedbackWrite
+            })
+        } else if under("/api/projects") || under("/api/status") {
+            Some(if reading {
+                ApiScope::ProjectsRead
+            } else {
+                ApiScope::ProjectsWrite
+            })
+        } else if under("/api/issues") {
+            Some(ApiScope::IssuesWrite)
+        } else if under("/api/orgs") {
+            Some(if reading {
+                ApiScope::OrgsRead
+            } else {
+                ApiScope::OrgsWrite
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl std::str::FromStr for ApiScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        ApiScope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown API key scope: {}", s))
+    }
+}
+
 /// 🔐 Main authentication middleware
 /// Validates JWT tokens and populates request with user information
 pub async fn auth_middleware(
@@ -114,20 +307,22 @@ pub async fn auth_middleware(
         return Ok(next.run(request).await);
     }
 
-    // 🔍 Extract token from headers
-    let token = match extract_token_from_headers(&headers) {
+    // 🔍 Extract token from headers (or the query, for WebSocket handshakes)
+    let token = match extract_token_from_headers(&headers).or_else(|| websocket_token(&request)) {
         Some(token) => token,
         None => {
             warn!(
                 "🚫 Missing authentication token for protected path: {}",
                 path
             );
-            return Err(unauthorized_response("Authentication token required"));
+            return Err(unauthorized_response(&i18n::t("auth-token-required")));
         }
     };
 
     // ✅ Validate the JWT token
-    match validate_jwt_token(&token, &app_state.config.auth.jwt_secret).await {
+    let jwt_secret = keys::accepted_secret(&app_state.config.load().auth).map(str::to_string);
+    let signing_keys = app_state.token_keys.snapshot().await;
+    match validate_jwt_token(&token, &signing_keys, jwt_secret.as_deref()).await {
         Ok(claims) => {
             // 🔍 Optionally verify user still exists and is active
             match verify_user_active(&claims, &app_state).await {
@@ -144,24 +339,89 @@ pub async fn auth_middleware(
                                 "🚫 Insufficient permissions for user {} on path: {}",
                                 user.email, path
                             );
-                            return Err(forbidden_response("Insufficient permissions"));
+                            return Err(forbidden_response(&i18n::t(
+                                "auth-insufficient-permissions",
+                            )));
+                        }
+                    }
+
+                    // 🔑 Scoped API keys only reach their areas of the API
+                    if let Some(scope) = ApiScope::required_for(request.method(), path) {
+                        if !user.has_scope(scope) {
+                            warn!(
+                                "🚫 API key of {} lacks the {} scope for path: {}",
+                                user.email,
+                                scope.as_str(),
+                                path
+                            );
+                            return Err(forbidden_response(&i18n::t_with(
+                                "auth-missing-scope",
+                                [("scope", scope.as_str().into())],
+                            )));
+                        }
+                    }
+
+                    // 🏢 Project routes: the user needs a role on that project
+                    if let Some((project_id, access)) =
+                        organizations::project_access_for(request.method(), path)
+                    {
+                        match organizations::authorize_project(
+                            &app_state.db_pool,
+                            &user,
+                            project_id,
+                            access,
+                        )
+                        .await
+                        {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                warn!(
+                                    "🚫 User {} has no {:?} access to project {}",
+                                    user.email, access, project_id
+                                );
+                                return Err(forbidden_response(&i18n::t("auth-no-project-access")));
+                            }
+                            Err(e) => {
+                                error!("❌ Project access check failed: {:#}", e);
+                                return Err(forbidden_response(&i18n::t(
+                                    "auth-project-access-unverified",
+                                )));
+                            }
                         }
                     }
 
                     // 📦 Add user to request extensions so handlers can access it
+                    let locale = user.locale;
+                    let request_user = RequestUser(user.id);
                     request.extensions_mut().insert(user);
 
-                    Ok(next.run(request).await)
+                    // 🌍 A saved language beats the client's Accept-Language
+                    let mut response = match locale {
+                        Some(locale) => {
+                            request.extensions_mut().insert(locale);
+                            let mut response = locale.scope(next.run(request)).await;
+                            response.headers_mut().insert(
+                                header::CONTENT_LANGUAGE,
+                                HeaderValue::from_static(locale.code()),
+                            );
+                            response
+                        }
+                        None => next.run(request).await,
+                    };
+
+                    // 📊 Tell the access log who this was
+                    response.extensions_mut().insert(request_user);
+                    Ok(response)
                 }
                 Err(e) => {
                     error!("❌ User verification failed: {:#}", e);
-                    Err(unauthorized_response("Invalid user or account disabled"))
+                    Err(unauthorized_response(&i18n::t("auth-invalid-user")))
                 }
             }
         }
         Err(e) => {
             warn!("🚫 JWT validation failed for path {}: {:#}", path, e);
-            Err(unauthorized_response("Invalid or expired token"))
+            Err(unauthorized_response(&i18n::t("auth-invalid-token")))
         }
     }
 }
@@ -175,12 +435,16 @@ fn is_public_path(path: &str) -> bool {
         "/api/liveness",          // Liveness probe
         "/api/auth/login",        // Login endpoint
         "/api/auth/register",     // Registration endpoint
+        "/.well-known/jwks.json", // Public token signing keys
         "/api/webhook/github",    // GitHub webhooks (authenticated differently)
         "/api/smart-tree/latest", // Smart Tree version check
         "/about",                 // About page
         "/docs",                  // Documentation
         "/login",                 // Login page
         "/register",              // Registration page
+        "/submit",                // Feedback form (it signs in through the API)
+        "/digest/unsubscribe",    // Digest opt-out, authorized by its signed link
+        "/feedback/claim",        // Anonymous feedback claims, authorized by their signed link
     ];
 
     // 🎯 Check exact matches
@@ -190,9 +454,13 @@ fn is_public_path(path: &str) -> bool {
 
     // 🎯 Check prefixes for public endpoints
     let public_prefixes = [
-        "/static/", // Static assets
-        "/assets/", // Assets
-        "/favicon", // Favicon
+        "/static/",        // Static assets
+        "/assets/",        // Assets
+        "/favicon",        // Favicon
+        "/api/auth/sso/",  // Single sign-on redirects and callbacks
+        "/p/",             // Opt-in public project status pages
+        "/api/exports/",   // Export downloads, authorized by their signed link
+        "/api/artifacts/", // Artifact downloads, authorized by their signed link
     ];
 
     public_prefixes
@@ -221,17 +489,57 @@ fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
     None
 }
 
+/// 🔌 Browsers can't set headers on a WebSocket handshake, so those may carry
+/// the token as `?access_token=` instead
+fn websocket_token(request: &Request) -> Option<String> {
+    let upgrading = request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if !upgrading {
+        return None;
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("access_token="))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
 /// ✅ Validate JWT token and extract claims
-async fn validate_jwt_token(token: &str, secret: &str) -> anyhow::Result<Claims> {
-    let decoding_key = DecodingKey::from_secret(secret.as_ref());
-    let mut validation = Validation::new(Algorithm::HS256);
+/// Key-pair tokens are checked against the signing key named in their `kid`
+/// header; HS256 ones against the secret, when `secret` is given
+async fn validate_jwt_token(
+    token: &str,
+    signing_keys: &KeyRing,
+    secret: Option<&str>,
+) -> anyhow::Result<Claims> {
+    let header =
+        jsonwebtoken::decode_header(token).map_err(|e| anyhow::anyhow!("Malformed JWT: {}", e))?;
+    let secret_key;
+    let decoding_key = match (header.alg, header.kid.as_deref()) {
+        (Algorithm::HS256, _) => {
+            let secret =
+                secret.ok_or_else(|| anyhow::anyhow!("HS256 tokens are no longer accepted"))?;
+            secret_key = DecodingKey::from_secret(secret.as_ref());
+            &secret_key
+        }
+        (algorithm, Some(kid)) => signing_keys
+            .decoding_key(kid, algorithm)
+            .ok_or_else(|| anyhow::anyhow!("Unknown or expired signing key: {}", kid))?,
+        (_, None) => anyhow::bail!("Token names no signing key"),
+    };
+    let mut validation = Validation::new(header.alg);
 
     // 🎯 Set validation parameters
     validation.set_issuer(&["feedbacker"]);
     validation.validate_exp = true;
     validation.validate_nbf = false; // We don't use "not before"
 
-    let token_data = decode::<Claims>(token, &decoding_key, &validation)
+    let token_data = decode::<Claims>(token, decoding_key, &validation)
         .map_err(|e| anyhow::anyhow!("JWT validation failed: {}", e))?;
 
     Ok(token_data.claims)
@@ -245,17 +553,39 @@ async fn verify_user_active(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|e| anyhow::anyhow!("Invalid user ID in token: {}", e))?;
 
-    // TODO: Implement proper user verification when database is ready
-    let user: Option<User> = None;
+    let user = User::find_by_id(&app_state.db_pool, user_id)
+        .await?
+        .filter(|user| user.is_active);
 
     match user {
+        // 🚫 Revoked by a key or secret rotation
+        Some(user) if !user.accepts_token_issued_at(claims.iat as i64) => {
+            anyhow::bail!("Token was revoked");
+        }
         Some(user) => {
             // ✅ User exists and is active
+            let permissions = app_state.roles.permissions_for(&user).await;
+            // 🔑 Scope and tier names this build doesn't know grant nothing
+            let scopes = user
+                .api_scopes
+                .as_ref()
+                .map(|names| names.iter().filter_map(|name| name.parse().ok()).collect());
+            let rate_limit_tier = user
+                .rate_limit_tier
+                .as_deref()
+                .and_then(|name| name.parse().ok())
+                .unwrap_or_default();
             Ok(AuthenticatedUser {
                 id: user.id,
                 email: user.email,
                 name: user.name,
                 role: user.role,
+                organization_id: user.organization_id,
+                permissions,
+                scopes,
+                rate_limit_tier,
+                locale: user.locale.as_deref().and_then(|code| code.parse().ok()),
+                request_signing_key: user.request_signing_key,
                 claims: claims.clone(),
             })
         }
@@ -265,25 +595,63 @@ async fn verify_user_active(
     }
 }
 
+/// 🔑 The active user a token (a login token or API key) belongs to
+/// For callers that don't come through HTTP, like the MCP server on stdio
+pub async fn authenticate_token(
+    app_state: &AppState,
+    token: &str,
+) -> anyhow::Result<AuthenticatedUser> {
+    let jwt_secret = keys::accepted_secret(&app_state.config.load().auth).map(str::to_string);
+    let signing_keys = app_state.token_keys.snapshot().await;
+    let claims = validate_jwt_token(token, &signing_keys, jwt_secret.as_deref()).await?;
+    verify_user_active(&claims, app_state).await
+}
+
 /// 🎯 Get required permission for a specific path
 fn get_required_permission(path: &str) -> Option<Permission> {
     // 🗺️ Map paths to required permissions
-    if path.starts_with("/api/admin/") {
+    // 👑 The admin API and the admin console built on it
+    if path.starts_with("/api/admin/") || path == "/admin" || path.starts_with("/admin/") {
         return Some(Permission::SystemAdmin);
     }
 
-    if path.starts_with("/api/users/") && path != "/api/users/me" {
+    // 🏥 Component health shows pool stats, slow queries and GitHub quota
+    if path == "/api/health/detailed" {
+        return Some(Permission::SystemAdmin);
+    }
+
+    // 👤 Everyone manages their own account under /api/users/me
+    if path.starts_with("/api/users/")
+        && path != "/api/users/me"
+        && !path.starts_with("/api/users/me/")
+    {
         return Some(Permission::ManageUsers);
     }
 
+    // 🏢 Routes of one project are checked against the user's role on it instead
     if path.starts_with("/api/projects/") && !path.contains("/feedback") {
-        return Some(Permission::ManageProjects);
+        let id = path["/api/projects/".len()..]
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        if Uuid::parse_str(id).is_err() {
+            return Some(Permission::ManageProjects);
+        }
     }
 
     if path == "/api/feedback/all" {
         return Some(Permission::ViewAllFeedback);
     }
 
+    if path.starts_with("/api/feedback/") && path.ends_with("/approval") {
+        return Some(Permission::ApprovePullRequests);
+    }
+
+    // 🧵 Stage timings show the pipeline's insides
+    if path.starts_with("/api/feedback/") && path.ends_with("/trace") {
+        return Some(Permission::SystemAdmin);
+    }
+
     // 📝 Most feedback endpoints just require basic authentication
     if path.starts_with("/api/feedback/") {
         return Some(Permission::ReadFeedback);
@@ -312,12 +680,12 @@ fn forbidden_response(message: &str) -> Response {
 /// 🎫 JWT token creation utilities (for the auth module to use)
 pub mod jwt_utils {
     use super::*;
-    use jsonwebtoken::{encode, EncodingKey, Header};
+    use crate::auth::keys::TokenSigner;
 
     /// ➕ Create a new JWT token for a user
     pub fn create_jwt_token(
         user: &User,
-        secret: &str,
+        signer: &TokenSigner,
         expiration_hours: u64,
     ) -> anyhow::Result<String> {
         let now = chrono::Utc::now();
@@ -334,17 +702,15 @@ pub mod jwt_utils {
             iss: "feedbacker".to_string(),
         };
 
-        let header = Header::new(Algorithm::HS256);
-        let encoding_key = EncodingKey::from_secret(secret.as_ref());
-
-        encode(&header, &claims, &encoding_key)
-            .map_err(|e| anyhow::anyhow!("Failed to create JWT token: {}", e))
+        signer
+            .sign(&claims)
+            .map_err(|e| anyhow::anyhow!("Failed to create JWT token: {:#}", e))
     }
 
     /// 🔄 Refresh a JWT token (create a new one with extended expiration)
     pub fn refresh_jwt_token(
         claims: &Claims,
-        secret: &str,
+        signer: &TokenSigner,
         expiration_hours: u64,
     ) -> anyhow::Result<String> {
         let now = chrono::Utc::now();
@@ -360,11 +726,9 @@ pub mod jwt_utils {
             iss: "feedbacker".to_string(),
         };
 
-        let header = Header::new(Algorithm::HS256);
-        let encoding_key = EncodingKey::from_secret(secret.as_ref());
-
-        encode(&header, &new_claims, &encoding_key)
-            .map_err(|e| anyhow::anyhow!("Failed to refresh JWT token: {}", e))
+        signer
+            .sign(&new_claims)
+            .map_err(|e| anyhow::anyhow!("Failed to refresh JWT token: {:#}", e))
     }
 }
 
@@ -380,6 +744,10 @@ mod tests {
         assert!(is_public_path("/api/auth/login"));
         assert!(is_public_path("/static/css/style.css"));
         assert!(is_public_path("/favicon.ico"));
+        assert!(is_public_path("/api/auth/sso/aye-is/callback"));
+        assert!(is_public_path("/.well-known/jwks.json"));
+        assert!(is_public_path("/submit"));
+        assert!(is_public_path("/p/feedbacker/status"));
 
         assert!(!is_public_path("/api/feedback"));
         assert!(!is_public_path("/api/projects"));
@@ -402,6 +770,20 @@ mod tests {
         let token2 = extract_token_from_headers(&headers2);
         assert_eq!(token2, Some("api_key_123".to_string()));
 
+        // 🔌 WebSocket handshakes may pass it in the query, nothing else may
+        let handshake = |upgrade: Option<&str>| {
+            let mut request = Request::builder().uri("/api/ws?since=now&access_token=ws_token_1");
+            if let Some(upgrade) = upgrade {
+                request = request.header(header::UPGRADE, upgrade);
+            }
+            request.body(axum::body::Body::empty()).unwrap()
+        };
+        assert_eq!(
+            websocket_token(&handshake(Some("websocket"))),
+            Some("ws_token_1".to_string())
+        );
+        assert_eq!(websocket_token(&handshake(None)), None);
+
         println!("✅ Token extraction test passed!");
     }
 
@@ -412,6 +794,12 @@ mod tests {
             email: "admin@example.com".to_string(),
             name: "Admin User".to_string(),
             role: UserRole::Admin,
+            organization_id: None,
+            permissions: Permission::defaults_for(&UserRole::Admin),
+            scopes: None,
+            rate_limit_tier: RateLimitTier::Standard,
+            locale: None,
+            request_signing_key: None,
             claims: Claims {
                 sub: "123".to_string(),
                 email: "admin@example.com".to_string(),
@@ -432,6 +820,12 @@ mod tests {
             email: "user@example.com".to_string(),
             name: "Regular User".to_string(),
             role: UserRole::User,
+            organization_id: None,
+            permissions: Permission::defaults_for(&UserRole::User),
+            scopes: None,
+            rate_limit_tier: RateLimitTier::Standard,
+            locale: None,
+            request_signing_key: None,
             claims: Claims {
                 sub: "456".to_string(),
                 email: "user@example.com".to_string(),
@@ -447,6 +841,27 @@ mod tests {
         assert!(!regular_user.has_permission(Permission::ViewAllFeedback));
         assert!(regular_user.has_permission(Permission::SubmitFeedback));
 
+        // 🏢 An organization's service account never gets system-wide permissions
+        let org_admin_key = AuthenticatedUser {
+            organization_id: Some(Uuid::new_v4()),
+            ..admin_user
+        };
+        assert!(!org_admin_key.has_permission(Permission::SystemAdmin));
+        assert!(org_admin_key.has_permission(Permission::ManageProjects));
+
+        // 🎭 Non-admins hold exactly what their roles grant
+        let reviewer = AuthenticatedUser {
+            permissions: [Permission::ApprovePullRequests].into_iter().collect(),
+            ..regular_user
+        };
+        assert!(reviewer.has_permission(Permission::ApprovePullRequests));
+        assert!(!reviewer.has_permission(Permission::ManageProjects));
+        assert!(!reviewer.has_permission(Permission::SubmitFeedback));
+        assert_eq!(
+            "approve_pull_requests".parse::<Permission>().unwrap(),
+            Permission::ApprovePullRequests
+        );
+
         println!("✅ Permission checking test passed!");
     }
 
@@ -456,15 +871,25 @@ mod tests {
             get_required_permission("/api/admin/settings"),
             Some(Permission::SystemAdmin)
         );
+        assert_eq!(
+            get_required_permission("/admin/users"),
+            Some(Permission::SystemAdmin)
+        );
+        assert_eq!(get_required_permission("/administrators"), None);
         assert_eq!(
             get_required_permission("/api/users/123"),
             Some(Permission::ManageUsers)
         );
         assert_eq!(get_required_permission("/api/users/me"), None);
+        assert_eq!(get_required_permission("/api/users/me/preferences"), None);
         assert_eq!(
             get_required_permission("/api/projects/create"),
             Some(Permission::ManageProjects)
         );
+        assert_eq!(
+            get_required_permission(&format!("/api/projects/{}/config", Uuid::new_v4())),
+            None
+        );
         assert_eq!(
             get_required_permission("/api/feedback/all"),
             Some(Permission::ViewAllFeedback)
@@ -473,7 +898,93 @@ mod tests {
             get_required_permission("/api/feedback/123"),
             Some(Permission::ReadFeedback)
         );
+        assert_eq!(
+            get_required_permission("/api/feedback/123/approval"),
+            Some(Permission::ApprovePullRequests)
+        );
+        assert_eq!(
+            get_required_permission("/api/feedback/123/trace"),
+            Some(Permission::SystemAdmin)
+        );
+        assert_eq!(
+            get_required_permission("/api/health/detailed"),
+            Some(Permission::SystemAdmin)
+        );
 
         println!("✅ Required permission mapping test passed!");
     }
+
+    #[test]
+    fn test_api_key_scopes() {
+        let project = format!("/api/projects/{}", Uuid::new_v4());
+        assert_eq!(
+            ApiScope::required_for(&Method::POST, "/api/feedback"),
+            Some(ApiScope::FeedbackWrite)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::GET, "/api/feedback/123/events"),
+            Some(ApiScope::FeedbackRead)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::GET, "/api/ws"),
+            Some(ApiScope::FeedbackRead)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::GET, &project),
+            Some(ApiScope::ProjectsRead)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::POST, &format!("{}/docs-pass", project)),
+            Some(ApiScope::ProjectsWrite)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::GET, "/api/admin/jobs"),
+            Some(ApiScope::Admin)
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::GET, "/api/feedbackers"),
+            None
+        );
+        assert_eq!(
+            ApiScope::required_for(&Method::POST, "/api/auth/logout"),
+            None
+        );
+
+        // 🔑 Unscoped keys reach everything, scoped ones only their areas
+        let user = AuthenticatedUser {
+            id: Uuid::new_v4(),
+            email: "smart-tree@example.com".to_string(),
+            name: "Smart Tree".to_string(),
+            role: UserRole::Service,
+            organization_id: None,
+            permissions: Permission::defaults_for(&UserRole::Service),
+            scopes: None,
+            rate_limit_tier: RateLimitTier::Trusted,
+            locale: None,
+            request_signing_key: None,
+            claims: Claims {
+                sub: "789".to_string(),
+                email: "smart-tree@example.com".to_string(),
+                name: "Smart Tree".to_string(),
+                role: UserRole::Service,
+                exp: 0,
+                iat: 0,
+                iss: "feedbacker".to_string(),
+            },
+        };
+        assert!(user.has_scope(ApiScope::ProjectsWrite));
+        let scoped = AuthenticatedUser {
+            scopes: Some([ApiScope::FeedbackWrite].into_iter().collect()),
+            ..user
+        };
+        assert!(scoped.has_scope(ApiScope::FeedbackWrite));
+        assert!(!scoped.has_scope(ApiScope::ProjectsWrite));
+        assert_eq!(
+            "feedback:write".parse::<ApiScope>().unwrap(),
+            ApiScope::FeedbackWrite
+        );
+        assert!("feedback:delete".parse::<ApiScope>().is_err());
+
+        println!("✅ API key scope test passed!");
+    }
 }
@@ -0,0 +1,129 @@
+// 🏷️ Conditional Requests - Nothing New? Nothing Sent! 🏷️
+// Read endpoints that clients poll (feedback detail, project detail, Smart Tree
+// latest) get an ETag hashed from their response, and a request whose
+// If-None-Match already names it gets an empty 304 instead of the same JSON again.
+// The envelope's `timestamp` changes on every response, so it's left out of the
+// hash. Tags are weak: compression outside this layer changes the bytes, not the data
+// Created with love by Aye & Hue - Polling without the bandwidth bill! ✨
+
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+/// 📏 Responses larger than this are passed through without an ETag
+const MAX_TAGGED_BODY_BYTES: u64 = 1024 * 1024;
+
+/// 🏷️ Tag successful GET responses and answer matching revalidations with 304
+pub async fn etag_middleware(request: Request, next: Next) -> Response {
+    let is_read = matches!(*request.method(), Method::GET | Method::HEAD);
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+
+    let small = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= MAX_TAGGED_BODY_BYTES);
+    if !is_read || response.status() != StatusCode::OK || !small {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_TAGGED_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("⚠️ Response body for {} couldn't be read: {}", path, e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let etag = entity_tag(&bytes);
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.insert(header::ETAG, etag_value);
+
+    if if_none_match
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| if_none_match_matches(value, &etag))
+    {
+        parts.status = StatusCode::NOT_MODIFIED;
+        strip_content_headers(&mut parts.headers);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// 🔏 Weak ETag of a response body (JSON without the envelope's timestamp)
+pub fn entity_tag(body: &[u8]) -> String {
+    let digest = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Object(mut envelope)) => {
+            envelope.remove("timestamp");
+            Sha256::digest(serde_json::to_vec(&envelope).unwrap_or_default())
+        }
+        _ => Sha256::digest(body),
+    };
+    format!("W/\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// 🤝 Whether an If-None-Match header names this ETag (weak comparison, RFC 9110)
+fn if_none_match_matches(header: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header.trim() == "*"
+        || header
+            .split(',')
+            .any(|candidate| opaque(candidate) == opaque(etag))
+}
+
+/// 🧹 Headers describing a body a 304 doesn't have
+fn strip_content_headers(headers: &mut HeaderMap) {
+    headers.remove(header::CONTENT_TYPE);
+    headers.remove(header::CONTENT_LENGTH);
+}
+
+// 🧪 Tests - Same data, same tag!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_tag_ignores_timestamp() {
+        let first = br#"{"success":true,"message":"Feedback found","data":{"status":"pending"},"timestamp":"2024-01-01T00:00:00Z"}"#;
+        let later = br#"{"success":true,"message":"Feedback found","data":{"status":"pending"},"timestamp":"2024-01-01T00:00:05Z"}"#;
+        let changed = br#"{"success":true,"message":"Feedback found","data":{"status":"completed"},"timestamp":"2024-01-01T00:00:05Z"}"#;
+
+        assert_eq!(entity_tag(first), entity_tag(later));
+        assert_ne!(entity_tag(first), entity_tag(changed));
+        assert_ne!(entity_tag(b"plain"), entity_tag(b"text"));
+
+        let tag = entity_tag(first);
+        assert!(tag.starts_with("W/\"") && tag.ends_with('"'));
+        assert_eq!(tag.len(), 2 + 1 + 32 + 1);
+        println!("✅ Entity tag test passed!");
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = entity_tag(b"{}");
+        let opaque = etag.trim_start_matches("W/");
+
+        assert!(if_none_match_matches(&etag, &etag));
+        assert!(if_none_match_matches(opaque, &etag));
+        assert!(if_none_match_matches(
+            &format!("\"other\", {}", etag),
+            &etag
+        ));
+        assert!(if_none_match_matches(" * ", &etag));
+        assert!(!if_none_match_matches("\"other\"", &etag));
+        assert!(!if_none_match_matches("", &etag));
+        println!("✅ If-None-Match test passed!");
+    }
+}
@@ -0,0 +1,97 @@
+// 🧯 Error Handling Middleware - Every Request Gets a Name Tag! 🧯
+// Gives each request an id (the caller's X-Request-Id when it's sensible, a
+// fresh one otherwise), echoes it back, and handles the request inside an
+// ErrorScope: failures are logged with their full chain under that id, and
+// the client gets the id plus, on development servers only, the chain itself.
+// Production, and staging with it, never sees past the code and message
+// Created with love by Aye & Hue - Quote this number and we'll find it! ✨
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use crate::{api::AppState, errors::ErrorScope};
+
+/// 🏷️ Where the request id comes in and goes out
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 📏 Longest caller-supplied request id we'll keep
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// 🏷️ The id of the request being handled, for handlers that want it
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// 🧯 Tag the request with an id and handle it in an error scope
+pub async fn error_handling_middleware(
+    State(app_state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request_id_from(request.headers());
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+
+    let scope = ErrorScope {
+        request_id: request_id.clone(),
+        expose_details: app_state.config.load().is_development(),
+    };
+    let mut response = scope.run(next.run(request)).await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+/// 🔍 The caller's request id if it's safe to log and echo, a fresh one otherwise
+fn request_id_from(headers: &HeaderMap) -> String {
+    headers
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LENGTH
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+// 🧪 Tests - Name tags for everyone!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_request_id(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER.clone(), value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_request_id_from() {
+        assert_eq!(
+            request_id_from(&with_request_id("trace-7f3a_01.b")),
+            "trace-7f3a_01.b"
+        );
+
+        // 🆕 Missing or unsuitable ids are replaced
+        let fresh = request_id_from(&HeaderMap::new());
+        assert!(Uuid::parse_str(&fresh).is_ok());
+        for unsuitable in ["", "has spaces", "quote\"d", &"x".repeat(129)] {
+            let id = request_id_from(&with_request_id(unsuitable));
+            assert!(Uuid::parse_str(&id).is_ok(), "{:?} was kept", unsuitable);
+        }
+        println!("✅ Request id test passed!");
+    }
+}
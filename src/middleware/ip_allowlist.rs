@@ -0,0 +1,268 @@
+// 🧱 IP Allowlist Middleware - Only From Where We Expect! 🧱
+// Route groups can be limited to networks: the admin API and console to
+// ADMIN_IP_ALLOWLIST (the office VPN, say), and the webhook endpoints to
+// WEBHOOK_IP_ALLOWLIST plus, with WEBHOOK_ALLOW_GITHUB_HOOKS, the ranges GitHub
+// publishes for hook deliveries (GET /meta, cached for an hour; when a refresh
+// fails the last good list is kept, or nothing is let in without one, and the
+// fetch isn't tried again for a minute). A group without any allowlist is open.
+// The client address is the one rate limiting uses, so TRUSTED_PROXIES applies
+// Created with love by Aye & Hue - Friends at the door, everyone else outside! ✨
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use ipnet::IpNet;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::{
+    api::{ApiResponse, AppState, ErrorResponse},
+    config::{GitHubConfig, IpAllowlistConfig},
+    github::GitHubClient,
+    i18n,
+    middleware::rate_limiting::extract_client_ip,
+};
+
+/// ⏳ How long GitHub's hook ranges are trusted before they're fetched again
+const HOOK_RANGES_TTL: Duration = Duration::from_secs(3600);
+
+/// 🔁 How long after a failed fetch of the hook ranges before the next try
+const HOOK_RANGES_RETRY: Duration = Duration::from_secs(60);
+
+/// 🗂️ Routes that can be limited to an allowlist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    /// 👑 /api/admin/* and the /admin console
+    Admin,
+    /// 🪝 /api/webhook/*
+    Webhooks,
+}
+
+impl RouteGroup {
+    /// 🔍 The group a path belongs to, if any
+    pub fn for_path(path: &str) -> Option<Self> {
+        let within = |prefix: &str| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        };
+        if within("/api/admin") || within("/admin") {
+            Some(RouteGroup::Admin)
+        } else if within("/api/webhook") {
+            Some(RouteGroup::Webhooks)
+        } else {
+            None
+        }
+    }
+
+    /// 🌐 The configured networks of this group, and whether it is limited at all
+    fn allowlist(self, config: &IpAllowlistConfig) -> (&[IpNet], bool) {
+        match self {
+            RouteGroup::Admin => (&config.admin, !config.admin.is_empty()),
+            RouteGroup::Webhooks => (
+                &config.webhooks,
+                !config.webhooks.is_empty() || config.github_hooks,
+            ),
+        }
+    }
+}
+
+/// 🌐 Whether an address is in any of the networks
+fn contains(networks: &[IpNet], ip: IpAddr) -> bool {
+    networks.iter().any(|network| network.contains(&ip))
+}
+
+/// 🐙 GitHub's webhook delivery ranges, fetched from its meta API and cached
+#[derive(Debug, Default)]
+pub struct GitHubHookRanges {
+    cached: RwLock<HookRangesCache>,
+}
+
+/// 🗂️ The last good hook ranges, and when fetching them last failed
+#[derive(Debug, Default)]
+struct HookRangesCache {
+    ranges: Option<(Instant, Vec<IpNet>)>,
+    failed_at: Option<Instant>,
+}
+
+impl HookRangesCache {
+    /// 🔍 The answer for an address, unless it's time to fetch again: the
+    /// ranges are stale (or missing) and no fetch failed in the last minute
+    fn lookup(&self, ip: IpAddr, now: Instant) -> Option<bool> {
+        let fresh = self
+            .ranges
+            .as_ref()
+            .is_some_and(|(fetched_at, _)| now.duration_since(*fetched_at) < HOOK_RANGES_TTL);
+        let backing_off = self
+            .failed_at
+            .is_some_and(|failed_at| now.duration_since(failed_at) < HOOK_RANGES_RETRY);
+        (fresh || backing_off).then(|| self.allows(ip))
+    }
+
+    /// ✅ Whether the last good ranges hold an address (false without any)
+    fn allows(&self, ip: IpAddr) -> bool {
+        self.ranges
+            .as_ref()
+            .is_some_and(|(_, ranges)| contains(ranges, ip))
+    }
+}
+
+impl GitHubHookRanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ✅ Whether GitHub delivers webhooks from this address
+    /// (false while the ranges have never been fetched)
+    pub async fn contains(&self, github: &GitHubConfig, ip: IpAddr) -> bool {
+        if let Some(allowed) = self.cached.read().await.lookup(ip, Instant::now()) {
+            return allowed;
+        }
+
+        let mut cached = self.cached.write().await;
+        // 🏁 Someone else may have fetched (or failed to) while we waited for the lock
+        if let Some(allowed) = cached.lookup(ip, Instant::now()) {
+            return allowed;
+        }
+        let fetched = match GitHubClient::new(github.clone()) {
+            Ok(client) => client.hook_ranges().await,
+            Err(e) => Err(e),
+        };
+        match fetched {
+            Ok(ranges) => {
+                info!("🐙 Fetched {} GitHub webhook IP ranges", ranges.len());
+                cached.ranges = Some((Instant::now(), ranges));
+                cached.failed_at = None;
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️ {:#}; keeping the last known ranges, retrying in {}s",
+                    e,
+                    HOOK_RANGES_RETRY.as_secs()
+                );
+                cached.failed_at = Some(Instant::now());
+            }
+        }
+        cached.allows(ip)
+    }
+}
+
+/// 🧱 Refuse requests to a limited route group from outside its allowlist
+pub async fn ip_allowlist_middleware(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(group) = RouteGroup::for_path(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let config = app_state.config.load();
+    let (networks, limited) = group.allowlist(&config.server.ip_allowlists);
+    if !limited {
+        return next.run(request).await;
+    }
+
+    let client = extract_client_ip(&request, &config.server.trusted_proxies).to_canonical();
+    let allowed = contains(networks, client)
+        || (group == RouteGroup::Webhooks
+            && config.server.ip_allowlists.github_hooks
+            && app_state
+                .github_hook_ranges
+                .contains(&config.github, client)
+                .await);
+    if allowed {
+        return next.run(request).await;
+    }
+
+    warn!(
+        "🧱 Refused {} {} from {} (not in the {:?} allowlist)",
+        request.method(),
+        request.uri().path(),
+        client,
+        group
+    );
+    let api_response = ApiResponse::<()>::error(
+        "ip_not_allowed".to_string(),
+        i18n::t("ip-not-allowed"),
+        None,
+    );
+    (StatusCode::FORBIDDEN, Json(api_response)).into_response()
+}
+
+// 🧪 Tests - Only the right doors are guarded!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_groups() {
+        assert_eq!(RouteGroup::for_path("/api/admin"), Some(RouteGroup::Admin));
+        assert_eq!(
+            RouteGroup::for_path("/api/admin/users"),
+            Some(RouteGroup::Admin)
+        );
+        assert_eq!(
+            RouteGroup::for_path("/admin/users"),
+            Some(RouteGroup::Admin)
+        );
+        assert_eq!(RouteGroup::for_path("/administrator"), None);
+        assert_eq!(
+            RouteGroup::for_path("/api/webhook/github"),
+            Some(RouteGroup::Webhooks)
+        );
+        assert_eq!(RouteGroup::for_path("/api/feedback"), None);
+
+        let config = IpAllowlistConfig {
+            admin: vec!["10.8.0.0/16".parse().unwrap()],
+            webhooks: Vec::new(),
+            github_hooks: false,
+        };
+        let (admin, limited) = RouteGroup::Admin.allowlist(&config);
+        assert!(limited);
+        assert!(contains(admin, "10.8.3.4".parse().unwrap()));
+        assert!(!contains(admin, "203.0.113.9".parse().unwrap()));
+        assert!(!RouteGroup::Webhooks.allowlist(&config).1);
+
+        let github_only = IpAllowlistConfig {
+            github_hooks: true,
+            ..Default::default()
+        };
+        assert!(RouteGroup::Webhooks.allowlist(&github_only).1);
+        println!("✅ IP allowlist route group test passed!");
+    }
+
+    #[test]
+    fn test_hook_ranges_backoff() {
+        let ip: IpAddr = "192.30.252.1".parse().unwrap();
+        let start = Instant::now();
+        let later = |seconds: u64| start + Duration::from_secs(seconds);
+        let cache = |failed_at: Option<Instant>| HookRangesCache {
+            ranges: Some((start, vec!["192.30.252.0/22".parse().unwrap()])),
+            failed_at,
+        };
+
+        // 🐙 Fresh ranges answer, stale ones are fetched again
+        assert_eq!(cache(None).lookup(ip, later(10)), Some(true));
+        assert_eq!(cache(None).lookup(ip, later(7200)), None);
+
+        // 🔁 After a failed fetch, the last good ranges answer for a minute
+        let failed = cache(Some(later(7200)));
+        assert_eq!(failed.lookup(ip, later(7205)), Some(true));
+        assert_eq!(failed.lookup(ip, later(7290)), None);
+
+        // 🚪 Without any good ranges, nothing is let in while backing off
+        let never = HookRangesCache {
+            ranges: None,
+            failed_at: Some(start),
+        };
+        assert_eq!(never.lookup(ip, later(5)), Some(false));
+        assert_eq!(never.lookup(ip, later(90)), None);
+        assert_eq!(HookRangesCache::default().lookup(ip, start), None);
+        println!("✅ GitHub hook ranges backoff test passed!");
+    }
+}
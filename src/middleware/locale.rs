@@ -0,0 +1,57 @@
+// 🌍 Locale Middleware - Answer in the Language You Were Asked In! 🌍
+// Picks the request's locale from its Accept-Language header and handles the
+// request in it (see crate::i18n), so messages built anywhere below come out
+// translated. Signed-in users with a saved language get that one instead: the
+// auth middleware narrows the scope once it knows who's asking. Responses say
+// which language they're in with Content-Language
+// Created with love by Aye & Hue - ¡Bienvenidos! ✨
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::i18n::Locale;
+
+/// 🌍 Handle the request in the locale its client asked for
+pub async fn locale_middleware(mut request: Request, next: Next) -> Response {
+    let locale = requested_locale(request.headers());
+    request.extensions_mut().insert(locale);
+
+    let mut response = locale.scope(next.run(request)).await;
+    // 🏷️ Unless something further in (a user's preference) already said otherwise
+    response
+        .headers_mut()
+        .entry(header::CONTENT_LANGUAGE)
+        .or_insert(HeaderValue::from_static(locale.code()));
+    response
+}
+
+/// 🤝 The locale named by the Accept-Language header (English without one)
+fn requested_locale(headers: &HeaderMap) -> Locale {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Locale::negotiate)
+        .unwrap_or_default()
+}
+
+// 🧪 Tests - Spanish in, Spanish out!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requested_locale() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(requested_locale(&headers), Locale::English);
+
+        headers.insert(header::ACCEPT_LANGUAGE, "es-ES,es;q=0.9".parse().unwrap());
+        assert_eq!(requested_locale(&headers), Locale::Spanish);
+        headers.insert(header::ACCEPT_LANGUAGE, "ja".parse().unwrap());
+        assert_eq!(requested_locale(&headers), Locale::English);
+        println!("✅ Requested locale test passed!");
+    }
+}
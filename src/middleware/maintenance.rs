@@ -0,0 +1,89 @@
+// 🚧 Maintenance Middleware - Reads Welcome, Writes Wait! 🚧
+// While maintenance mode is on (see crate::maintenance), mutating API requests
+// get a 503 with a Retry-After header and the maintenance message. Admins are
+// let through so they can work (and switch it off), and so is logging in.
+// Runs inside the auth middleware, which has already identified the caller
+// Created with love by Aye & Hue - We'll be right back! ✨
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use tracing::debug;
+
+use crate::{
+    api::{ApiResponse, AppState, ErrorResponse},
+    middleware::auth::AuthenticatedUser,
+};
+
+/// ⏳ Seconds clients are asked to wait before retrying
+const RETRY_AFTER_SECONDS: u64 = 120;
+
+/// 🚧 Refuse non-admin writes while maintenance mode is on
+pub async fn maintenance_middleware(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_admin = request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .is_some_and(AuthenticatedUser::is_admin);
+    if !is_write(request.method()) || is_exempt_path(request.uri().path()) || is_admin {
+        return next.run(request).await;
+    }
+
+    let status = app_state.maintenance.status().await;
+    if !status.enabled {
+        return next.run(request).await;
+    }
+
+    debug!(
+        "🚧 Refused {} {} during maintenance",
+        request.method(),
+        request.uri().path()
+    );
+    let api_response = ApiResponse::<()>::error(
+        "maintenance".to_string(),
+        status.message,
+        Some(serde_json::json!({
+            "since": status.since,
+            "retry_after_seconds": RETRY_AFTER_SECONDS,
+        })),
+    );
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, RETRY_AFTER_SECONDS.to_string())],
+        Json(api_response),
+    )
+        .into_response()
+}
+
+/// ✏️ Methods that change something
+fn is_write(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// 🔓 Writes that stay open: admins need to be able to log in
+fn is_exempt_path(path: &str) -> bool {
+    path == "/api/auth/login"
+}
+
+// 🧪 Tests - Only writes are held back!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_applies_to_writes() {
+        assert!(!is_write(&Method::GET));
+        assert!(!is_write(&Method::OPTIONS));
+        assert!(is_write(&Method::POST));
+        assert!(is_write(&Method::DELETE));
+        assert!(is_exempt_path("/api/auth/login"));
+        assert!(!is_exempt_path("/api/feedback"));
+        println!("✅ Maintenance write detection test passed!");
+    }
+}
@@ -5,14 +5,30 @@
 // Trisha from Accounting loves when security is both strong and organized! 🔐
 
 pub mod auth; // 🔐 Authentication middleware
+pub mod conditional; // 🏷️ ETags and 304s for polled read endpoints
 pub mod cors; // 🌍 CORS handling middleware
+pub mod error_handling; // 🧯 Request ids and sanitized error details
+pub mod ip_allowlist; // 🧱 Admin and webhook routes limited to allowed networks
+pub mod locale; // 🌍 Request locale from Accept-Language
 pub mod logging; // 📊 Request logging middleware
+pub mod maintenance; // 🚧 Maintenance mode (503 for writes)
+pub mod problem_json; // 📄 RFC 7807 error responses, when asked for
 pub mod rate_limiting; // 🚦 Rate limiting middleware
+pub mod request_signing; // 🔏 Signed, replay-proof requests from machine clients
 pub mod security; // 🛡️ Security headers middleware
+pub mod webhook_deliveries; // 📬 Log of webhook deliveries and what became of them
 
 // Re-export commonly used middleware functions
 pub use auth::auth_middleware;
+pub use conditional::etag_middleware;
 pub use cors::cors_middleware;
+pub use error_handling::error_handling_middleware;
+pub use ip_allowlist::ip_allowlist_middleware;
+pub use locale::locale_middleware;
 pub use logging::logging_middleware;
+pub use maintenance::maintenance_middleware;
+pub use problem_json::problem_json_middleware;
 pub use rate_limiting::rate_limit_middleware;
+pub use request_signing::request_signing_middleware;
 pub use security::security_headers_middleware;
+pub use webhook_deliveries::webhook_delivery_middleware;
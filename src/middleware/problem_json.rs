@@ -0,0 +1,121 @@
+// 📄 Problem+JSON Middleware - Errors in the Format Clients Ask For! 📄
+// Clients sending `Accept: application/problem+json` get error responses as
+// RFC 7807 problem documents instead of the usual envelope: same status and
+// headers (Retry-After and friends stay), with `type` and `title` from the
+// error taxonomy in crate::errors. Everyone else gets the envelope as before.
+// Runs outside auth, rate limiting and maintenance, so their refusals convert too
+// Created with love by Aye & Hue - Same problem, better manners! ✨
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::warn;
+
+use crate::errors::Problem;
+
+/// 📄 The problem document media type
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// 📏 Error bodies larger than this are passed through untouched
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// 📄 Turn error responses into problem documents for clients that accept them
+pub async fn problem_json_middleware(request: Request, next: Next) -> Response {
+    let wants_problem = accepts_problem_json(request.headers());
+    let instance = request.uri().path().to_string();
+    let response = next.run(request).await;
+
+    let status = response.status();
+    if !wants_problem
+        || !(status.is_client_error() || status.is_server_error())
+        || !is_json(response.headers())
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("⚠️ Error body for {} couldn't be read: {}", instance, e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let problem = serde_json::from_slice(&bytes)
+        .ok()
+        .and_then(|body| Problem::from_response(status, &body, &instance));
+    let Some(problem) = problem else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let document = serde_json::to_vec(&problem).unwrap_or_default();
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(document))
+}
+
+/// 🤝 Whether the Accept header names application/problem+json
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let media_type = params.next()?.trim();
+            // 🚫 q=0 means "anything but this"
+            let refused = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+            (!refused).then_some(media_type)
+        })
+        .any(|media_type| media_type.eq_ignore_ascii_case(PROBLEM_JSON))
+}
+
+/// 📦 Whether a response carries plain JSON
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+// 🧪 Tests - Only the clients who ask get problems!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_accepts_problem_json() {
+        assert!(accepts_problem_json(&accept("application/problem+json")));
+        assert!(accepts_problem_json(&accept(
+            "application/json, application/problem+json;q=0.9"
+        )));
+        assert!(accepts_problem_json(&accept("Application/Problem+JSON")));
+
+        assert!(!accepts_problem_json(&HeaderMap::new()));
+        assert!(!accepts_problem_json(&accept("application/json")));
+        assert!(!accepts_problem_json(&accept("*/*")));
+        assert!(!accepts_problem_json(&accept(
+            "application/problem+json;q=0"
+        )));
+        println!("✅ Problem+JSON negotiation test passed!");
+    }
+}
@@ -1,131 +1,379 @@
 // 🚦 Rate Limiting Middleware - Traffic Control for Feedbacker! 🚦
 // This module provides intelligent rate limiting to prevent abuse
-// Built with governor crate for high-performance rate limiting! ⚡
+// Per-client sliding windows, with higher tiers for trusted API keys! ⚡
+// Requests are counted in memory, so checks never wait on the database; every
+// RATE_LIMIT_SYNC_SECONDS the counts are added to the rate_limits table and
+// the totals of every replica read back, and a client seen for the first time
+// starts from its stored window, so limits survive restarts and apply across
+// replicas (give or take one sync interval) without Redis. The latest refused
+// requests are kept in memory for the admin console
 // Created with love by Aye & Hue - Making fair usage beautiful! ✨
 // Trisha from Accounting appreciates when resources are used fairly! 📊
 
+use anyhow::Result;
 use axum::{
-    extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
-use governor::{
-    clock::{DefaultClock, QuantaClock},
-    state::{InMemoryState, NotKeyed},
-    Quota, RateLimiter,
-};
-use nonzero_ext::*;
+use chrono::{DateTime, Utc};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::{
     collections::HashMap,
-    net::IpAddr,
+    net::{IpAddr, SocketAddr},
     str::FromStr,
-    sync::{Arc, Mutex},
-    time::Duration,
+    sync::Mutex,
+    time::{Duration, Instant},
 };
 use tracing::{debug, info, warn};
 
 use crate::{
-    api::{ApiResponse, AppState},
+    api::{ApiResponse, AppState, ErrorResponse},
+    config::RateLimitConfig,
     database::models::RateLimit,
+    i18n,
+    middleware::auth::AuthenticatedUser,
+    utils::recent::RecentLog,
 };
 
-/// 🚦 Rate limiter for different types of requests
-/// Uses in-memory storage for high performance with optional database persistence
+/// 🧹 Expired windows are swept out once this many clients are tracked
+const MAX_TRACKED_WINDOWS: usize = 10_000;
+
+/// 🧹 How often rows nobody has used for STALE_AFTER are deleted
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// ⏳ Rows untouched for this long can't affect any window anymore
+const STALE_AFTER: Duration = Duration::from_secs(2 * 3600);
+
+/// 🚦 Request counts of every client, shared through the app state
+/// Each client gets a sliding window per limit type; the limit itself is passed
+/// in on every check, so reloaded limits and key tiers apply right away
+#[derive(Debug, Default)]
 pub struct RateLimitManager {
-    /// 📊 General API rate limiter (requests per minute)
-    pub api_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
-    /// 📝 Feedback submission rate limiter (submissions per hour)
-    pub feedback_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
-    /// 🗄️ Database connection for persistent rate limiting
-    pub db_limiters: Arc<Mutex<HashMap<String, RateLimitEntry>>>,
+    /// 🗂️ Current window by limit type and client
+    windows: Mutex<HashMap<String, RateLimitEntry>>,
+    /// 🗄️ Where counts are persisted (None = this instance only)
+    db_pool: Option<PgPool>,
+    /// 🚫 The latest refused requests on this instance
+    hits: RecentLog<RateLimitHit>,
 }
 
-/// 📊 Rate limit entry for database persistence
+/// 🚫 A request refused for being over its limit
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitHit {
+    pub at: DateTime<Utc>,
+    /// 🔑 `user:<id>` or `ip:<address>`
+    pub client_id: String,
+    pub path: String,
+    pub limit_type: String,
+    pub tier: &'static str,
+}
+
+/// 📊 One client's window, estimated as the current window's count plus the
+/// previous one's weighted by how much of it still overlaps the sliding window
 #[derive(Debug, Clone)]
 pub struct RateLimitEntry {
-    /// 📈 Current request count
+    /// 📋 What is being counted
+    pub limit_type: RateLimitType,
+    /// 📈 Requests in the current window (every instance's, as of the last sync)
     pub count: u32,
-    /// ⏰ Window start time
-    pub window_start: chrono::DateTime<chrono::Utc>,
-    /// 🕒 Last request time
-    pub last_request: chrono::DateTime<chrono::Utc>,
+    /// 📈 Requests in the window before it
+    pub previous_count: u32,
+    /// ⏰ When the current window started
+    pub window_start: DateTime<Utc>,
+    /// 📤 Requests counted here that the database doesn't have yet
+    pub pending: u32,
+}
+
+impl RateLimitEntry {
+    /// ➕ An empty window starting now
+    fn new(limit_type: RateLimitType, now: DateTime<Utc>) -> Self {
+        Self {
+            limit_type,
+            count: 0,
+            previous_count: 0,
+            window_start: now,
+            pending: 0,
+        }
+    }
+
+    /// 🗄️ The window as stored in the database
+    fn from_row(limit_type: RateLimitType, row: &RateLimit) -> Self {
+        Self {
+            limit_type,
+            count: row.request_count.max(0) as u32,
+            previous_count: row.previous_count.max(0) as u32,
+            window_start: row.window_start,
+            pending: 0,
+        }
+    }
+
+    /// ⏱️ Window length in milliseconds
+    fn window_ms(&self) -> i64 {
+        self.limit_type.window().as_millis() as i64
+    }
+
+    /// ⏩ Move forward to the window holding `now`
+    fn advance(&mut self, now: DateTime<Utc>) {
+        let elapsed = (now - self.window_start).num_milliseconds();
+        let windows = elapsed / self.window_ms();
+        if windows < 1 {
+            return;
+        }
+        self.previous_count = if windows == 1 { self.count } else { 0 };
+        self.count = 0;
+        self.window_start += chrono::Duration::milliseconds(windows * self.window_ms());
+    }
+
+    /// 📈 Requests in the sliding window ending at `now`
+    fn estimate(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed =
+            (now - self.window_start).num_milliseconds().max(0) as f64 / self.window_ms() as f64;
+        self.previous_count as f64 * (1.0 - elapsed).max(0.0) + self.count as f64
+    }
+
+    /// ⏰ When the current window ends
+    fn window_end(&self) -> DateTime<Utc> {
+        self.window_start + chrono::Duration::milliseconds(self.window_ms())
+    }
+
+    /// ⏳ How long until one more request fits under `limit`
+    fn retry_after(&self, now: DateTime<Utc>, limit: u32) -> Duration {
+        let room = limit as f64 - 1.0;
+        let window_ms = self.window_ms() as f64;
+        let elapsed_ms = (now - self.window_start).num_milliseconds().max(0) as f64;
+        let wait_ms = if self.count as f64 > room {
+            // 🔜 Not before this window has slid far enough out of the next one
+            let fraction = 1.0 - room / self.count as f64;
+            window_ms - elapsed_ms + fraction.clamp(0.0, 1.0) * window_ms
+        } else {
+            // 🔜 Once enough of the previous window has slid out
+            let fraction = 1.0 - (room - self.count as f64) / self.previous_count.max(1) as f64;
+            fraction.clamp(0.0, 1.0) * window_ms - elapsed_ms
+        };
+        // ⏰ Round up to whole seconds, so clients don't retry a moment too early
+        let wait_ms = wait_ms.max(0.0).round() as u64;
+        Duration::from_secs(wait_ms.div_ceil(1000).max(1))
+    }
 }
 
 impl RateLimitManager {
     /// ➕ Create a new rate limit manager
-    pub fn new(requests_per_minute: u32, feedback_per_hour: u32) -> Self {
-        // 📊 Create API rate limiter (requests per minute)
-        let api_quota = Quota::per_minute(nonzero_ext::nonzero!(60u32));
-        let api_limiter = Arc::new(RateLimiter::direct(api_quota));
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        // 📝 Create feedback rate limiter (submissions per hour)
-        let feedback_quota = Quota::per_hour(nonzero_ext::nonzero!(10u32));
-        let feedback_limiter = Arc::new(RateLimiter::direct(feedback_quota));
+    /// 🚫 Remember a refused request for the admin console
+    pub fn record_hit(&self, hit: RateLimitHit) {
+        self.hits.push(hit);
+    }
 
-        Self {
-            api_limiter,
-            feedback_limiter,
-            db_limiters: Arc::new(Mutex::new(HashMap::new())),
-        }
+    /// 🚫 The latest refused requests on this instance, newest first
+    pub fn recent_hits(&self) -> Vec<RateLimitHit> {
+        self.hits.snapshot()
     }
 
-    /// 🔍 Check if a request is within rate limits
+    /// 🗄️ Persist the counts in the rate_limits table (see `sync`)
+    pub fn with_database(mut self, db_pool: PgPool) -> Self {
+        self.db_pool = Some(db_pool);
+        self
+    }
+
+    /// 🔍 Count a request against a client's window, unless it is already full
     pub async fn check_rate_limit(
         &self,
         client_id: &str,
         limit_type: RateLimitType,
-        app_state: &AppState,
+        limit: u32,
     ) -> RateLimitResult {
-        match limit_type {
-            RateLimitType::Api => {
-                if self.api_limiter.check().is_ok() {
-                    debug!("✅ API rate limit check passed for client: {}", client_id);
-                    RateLimitResult::Allowed
-                } else {
-                    warn!("🚫 API rate limit exceeded for client: {}", client_id);
-                    RateLimitResult::Limited {
-                        retry_after: Duration::from_secs(60),
-                        limit_type: "api".to_string(),
+        let key = format!("{}:{}", limit_type.as_str(), client_id);
+        self.load(&key, limit_type).await;
+
+        let now = Utc::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= MAX_TRACKED_WINDOWS {
+            windows.retain(|_, entry| {
+                entry.pending > 0 || now < entry.window_end() + entry.limit_type.chrono_window()
+            });
+        }
+
+        let entry = windows
+            .entry(key)
+            .or_insert_with(|| RateLimitEntry::new(limit_type, now));
+        entry.advance(now);
+
+        if entry.estimate(now) + 1.0 <= limit as f64 {
+            entry.count += 1;
+            entry.pending += 1;
+            debug!(
+                "✅ {} rate limit check passed for client: {}",
+                limit_type.as_str(),
+                client_id
+            );
+            RateLimitResult::Allowed
+        } else {
+            warn!(
+                "🚫 {} rate limit exceeded for client: {}",
+                limit_type.as_str(),
+                client_id
+            );
+            RateLimitResult::Limited {
+                retry_after: entry.retry_after(now, limit),
+                limit,
+                limit_type: limit_type.as_str().to_string(),
+            }
+        }
+    }
+
+    /// 👀 A client's usage of its current window, without counting a request
+    pub fn usage(&self, client_id: &str, limit_type: RateLimitType, limit: u32) -> RateLimitUsage {
+        let now = Utc::now();
+        let key = format!("{}:{}", limit_type.as_str(), client_id);
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut entry) = windows.get(&key).cloned() else {
+            return RateLimitUsage {
+                used: 0,
+                remaining: limit,
+                resets_in: None,
+            };
+        };
+        entry.advance(now);
+        let used = entry.estimate(now).ceil() as u32;
+        RateLimitUsage {
+            used,
+            remaining: limit.saturating_sub(used),
+            resets_in: (entry.window_end() - now).to_std().ok(),
+        }
+    }
+
+    /// 📥 Start tracking a client from its stored window, the first time it's seen
+    /// If the database can't be read, the client starts from an empty window
+    async fn load(&self, key: &str, limit_type: RateLimitType) {
+        let Some(pool) = &self.db_pool else {
+            return;
+        };
+        if self
+            .windows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(key)
+        {
+            return;
+        }
+
+        let entry = match RateLimit::find(pool, key).await {
+            Ok(Some(row)) => RateLimitEntry::from_row(limit_type, &row),
+            Ok(None) => RateLimitEntry::new(limit_type, Utc::now()),
+            Err(e) => {
+                warn!("⚠️ Rate limit window of {} could not be read: {:#}", key, e);
+                RateLimitEntry::new(limit_type, Utc::now())
+            }
+        };
+        self.windows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key.to_string())
+            .or_insert(entry);
+    }
+
+    /// 📤 Add the requests counted here to the database and read back the totals
+    /// of every instance, for the clients seen since the last sync
+    pub async fn sync(&self) -> Result<()> {
+        let Some(pool) = &self.db_pool else {
+            return Ok(());
+        };
+        let mut batch: Vec<(String, RateLimitType, u32)> = {
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            windows
+                .iter_mut()
+                .filter(|(_, entry)| entry.pending > 0)
+                .map(|(key, entry)| {
+                    (
+                        key.clone(),
+                        entry.limit_type,
+                        std::mem::take(&mut entry.pending),
+                    )
+                })
+                .collect()
+        };
+
+        while let Some((key, limit_type, added)) = batch.pop() {
+            let recorded =
+                RateLimit::record(pool, &key, limit_type.as_str(), added, limit_type.window())
+                    .await;
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            match recorded {
+                Ok(row) => {
+                    if let Some(entry) = windows.get_mut(&key) {
+                        // 📈 Keep what was counted here while the write was under way
+                        let pending = entry.pending;
+                        *entry = RateLimitEntry::from_row(limit_type, &row);
+                        entry.count += pending;
+                        entry.pending = pending;
                     }
                 }
-            }
-            RateLimitType::Feedback => {
-                // 📝 For feedback, use both in-memory and database checking
-                if self.feedback_limiter.check().is_ok() {
-                    // TODO: Add database rate limiting when database is ready
-                    debug!(
-                        "✅ Feedback rate limit check passed for client: {}",
-                        client_id
-                    );
-                    RateLimitResult::Allowed
-                } else {
-                    warn!(
-                        "🚫 In-memory feedback rate limit exceeded for client: {}",
-                        client_id
-                    );
-                    RateLimitResult::Limited {
-                        retry_after: Duration::from_secs(3600),
-                        limit_type: "feedback".to_string(),
+                Err(e) => {
+                    // 🔁 Put the unwritten counts back for the next sync
+                    for (key, _, added) in batch.into_iter().chain([(key, limit_type, added)]) {
+                        if let Some(entry) = windows.get_mut(&key) {
+                            entry.pending += added;
+                        }
                     }
+                    return Err(e);
                 }
             }
-            RateLimitType::Webhook => {
-                // 🪝 Webhooks have a more lenient rate limit
-                debug!(
-                    "✅ Webhook rate limit check passed for client: {}",
-                    client_id
-                );
-                RateLimitResult::Allowed
-            }
         }
+        Ok(())
     }
+}
+
+/// 🔄 Sync the counts with the database every RATE_LIMIT_SYNC_SECONDS, in the background
+/// (and delete the rows of clients gone quiet every PURGE_INTERVAL)
+pub fn start_sync(app_state: AppState) {
+    let sync_seconds = app_state.config.load().rate_limiting.sync_seconds;
+    if sync_seconds == 0 {
+        info!("🚦 Rate limits are kept in memory only (RATE_LIMIT_SYNC_SECONDS=0)");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(Duration::from_secs(sync_seconds));
+        let mut purged_at: Option<Instant> = None;
+        loop {
+            ticks.tick().await;
+            if let Err(e) = app_state.rate_limits.sync().await {
+                warn!("⚠️ Rate limits could not be synced: {:#}", e);
+            }
+
+            if purged_at.is_some_and(|at| at.elapsed() < PURGE_INTERVAL) {
+                continue;
+            }
+            purged_at = Some(Instant::now());
+            match RateLimit::delete_stale(&app_state.db_pool, STALE_AFTER).await {
+                Ok(0) => {}
+                Ok(deleted) => debug!("🧹 Deleted {} stale rate limit windows", deleted),
+                Err(e) => warn!("⚠️ Stale rate limit windows could not be deleted: {:#}", e),
+            }
+        }
+    });
+}
 
-    // TODO: Implement database rate limiting when database is ready
+/// 👀 How much of one window a client has used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitUsage {
+    /// 📈 Requests counted in the current window
+    pub used: u32,
+    /// 📉 Requests left before being limited
+    pub remaining: u32,
+    /// ⏰ Time until the window ends (None = no window open; the next request starts one)
+    pub resets_in: Option<Duration>,
 }
 
 /// 🚦 Rate limit types for different endpoints
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RateLimitType {
     /// 📊 General API requests
     Api,
@@ -135,6 +383,87 @@ pub enum RateLimitType {
     Webhook,
 }
 
+impl RateLimitType {
+    /// 🏷️ Name used in responses and logs
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RateLimitType::Api => "api",
+            RateLimitType::Feedback => "feedback",
+            RateLimitType::Webhook => "webhook",
+        }
+    }
+
+    /// ⏱️ How long one counting window lasts
+    pub fn window(self) -> Duration {
+        match self {
+            RateLimitType::Api | RateLimitType::Webhook => Duration::from_secs(60),
+            RateLimitType::Feedback => Duration::from_secs(3600),
+        }
+    }
+
+    /// ⏱️ The window as a chrono duration
+    fn chrono_window(self) -> chrono::Duration {
+        chrono::Duration::from_std(self.window()).unwrap_or_else(|_| chrono::Duration::zero())
+    }
+}
+
+/// 🎚️ How much traffic an API key may send, chosen when the key is issued
+/// Anonymous traffic and ordinary accounts are on the standard tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitTier {
+    /// 👤 RATE_LIMIT_REQUESTS_PER_MINUTE and RATE_LIMIT_FEEDBACK_PER_HOUR
+    #[default]
+    Standard,
+    /// 🤝 The RATE_LIMIT_TRUSTED_* limits, for integrations like Smart Tree's server
+    Trusted,
+    /// ♾️ Not limited at all
+    Unlimited,
+}
+
+impl RateLimitTier {
+    /// 🏷️ Name stored on the service account
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RateLimitTier::Standard => "standard",
+            RateLimitTier::Trusted => "trusted",
+            RateLimitTier::Unlimited => "unlimited",
+        }
+    }
+
+    /// 📏 Requests allowed per window of this type (None = not limited)
+    /// Webhooks are authenticated by their signature and never limited here
+    pub fn limit(self, config: &RateLimitConfig, limit_type: RateLimitType) -> Option<u32> {
+        match (self, limit_type) {
+            (RateLimitTier::Unlimited, _) | (_, RateLimitType::Webhook) => None,
+            (RateLimitTier::Standard, RateLimitType::Api) => Some(config.requests_per_minute),
+            (RateLimitTier::Standard, RateLimitType::Feedback) => Some(config.feedback_per_hour),
+            (RateLimitTier::Trusted, RateLimitType::Api) => {
+                Some(config.trusted_requests_per_minute)
+            }
+            (RateLimitTier::Trusted, RateLimitType::Feedback) => {
+                Some(config.trusted_feedback_per_hour)
+            }
+        }
+    }
+}
+
+impl FromStr for RateLimitTier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "standard" => Ok(RateLimitTier::Standard),
+            "trusted" => Ok(RateLimitTier::Trusted),
+            "unlimited" => Ok(RateLimitTier::Unlimited),
+            _ => anyhow::bail!(
+                "Unknown rate limit tier: {} (use standard, trusted or unlimited)",
+                s
+            ),
+        }
+    }
+}
+
 /// 📊 Rate limit check result
 #[derive(Debug)]
 pub enum RateLimitResult {
@@ -144,61 +473,90 @@ pub enum RateLimitResult {
     Limited {
         /// ⏰ How long to wait before retrying
         retry_after: Duration,
+        /// 📏 Requests allowed per window
+        limit: u32,
         /// 📋 Type of rate limit that was exceeded
         limit_type: String,
     },
 }
 
 /// 🚦 Main rate limiting middleware
-/// This is applied to all routes and provides intelligent rate limiting
+/// Runs after authentication: signed-in callers are counted per account at
+/// their API key's tier, everyone else per client IP at the standard tier
 pub async fn rate_limit_middleware(
     State(app_state): State<AppState>,
-    headers: HeaderMap,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
     let path = request.uri().path();
-    let client_ip = extract_client_ip(&headers, &request);
 
-    // 🎯 Determine the type of rate limiting based on the path
-    let limit_type = determine_limit_type(path);
+    // 🎯 Determine the type of rate limiting based on the request
+    let limit_type = determine_limit_type(request.method(), path);
+
+    // 🔄 Limits as of the latest config reload
+    let limits = app_state.live.current().rate_limiting.clone();
 
-    // 🏗️ Create rate limiter if not exists (in a real implementation, this would be stored in app state)
-    let rate_limiter = RateLimitManager::new(
-        app_state.config.rate_limiting.requests_per_minute,
-        app_state.config.rate_limiting.feedback_per_hour,
-    );
+    // 🔑 Who is asking, and on which tier
+    let (client_id, tier) = match request.extensions().get::<AuthenticatedUser>() {
+        Some(user) => (client_id_for(user), user.rate_limit_tier),
+        None => (
+            format!(
+                "ip:{}",
+                extract_client_ip(&request, &app_state.config.load().server.trusted_proxies)
+            ),
+            RateLimitTier::Standard,
+        ),
+    };
 
     // 🔍 Check rate limits
-    let client_id = client_ip.to_string();
-    let result = rate_limiter
-        .check_rate_limit(&client_id, limit_type, &app_state)
-        .await;
+    let result = match tier.limit(&limits, limit_type) {
+        Some(limit) => {
+            app_state
+                .rate_limits
+                .check_rate_limit(&client_id, limit_type, limit)
+                .await
+        }
+        None => RateLimitResult::Allowed,
+    };
 
     match result {
         RateLimitResult::Allowed => {
-            debug!("✅ Rate limit check passed for {}: {}", client_ip, path);
+            debug!("✅ Rate limit check passed for {}: {}", client_id, path);
             Ok(next.run(request).await)
         }
         RateLimitResult::Limited {
             retry_after,
+            limit,
             limit_type,
         } => {
             warn!(
-                "🚫 Rate limit exceeded for {}: {} (type: {})",
-                client_ip, path, limit_type
+                "🚫 Rate limit exceeded for {}: {} (type: {}, tier: {})",
+                client_id,
+                path,
+                limit_type,
+                tier.as_str()
             );
+            app_state.rate_limits.record_hit(RateLimitHit {
+                at: Utc::now(),
+                client_id: client_id.clone(),
+                path: path.to_string(),
+                limit_type: limit_type.clone(),
+                tier: tier.as_str(),
+            });
 
             let error_response = ApiResponse::<()>::error(
                 "rate_limit_exceeded".to_string(),
-                format!(
-                    "Rate limit exceeded for {}. Try again in {} seconds.",
-                    limit_type,
-                    retry_after.as_secs()
+                i18n::t_with(
+                    "rate-limit-exceeded",
+                    [
+                        ("limit_type", limit_type.as_str().into()),
+                        ("seconds", retry_after.as_secs().into()),
+                    ],
                 ),
                 Some(serde_json::json!({
                     "retry_after_seconds": retry_after.as_secs(),
-                    "limit_type": limit_type
+                    "limit_type": limit_type,
+                    "tier": tier.as_str()
                 })),
             );
 
@@ -206,12 +564,9 @@ pub async fn rate_limit_middleware(
                 (StatusCode::TOO_MANY_REQUESTS, Json(error_response)).into_response();
 
             // 📋 Add rate limit headers
-            response.headers_mut().insert(
-                "X-RateLimit-Limit",
-                format!("{}", app_state.config.rate_limiting.requests_per_minute)
-                    .parse()
-                    .unwrap(),
-            );
+            response
+                .headers_mut()
+                .insert("X-RateLimit-Limit", format!("{}", limit).parse().unwrap());
             response
                 .headers_mut()
                 .insert("X-RateLimit-Remaining", "0".parse().unwrap());
@@ -235,44 +590,66 @@ pub async fn rate_limit_middleware(
     }
 }
 
-/// 🌐 Extract client IP address from request
-/// Handles various proxy headers for accurate IP detection
-fn extract_client_ip(headers: &HeaderMap, _request: &Request) -> IpAddr {
-    // 🔍 Check common proxy headers
-    if let Some(forwarded_for) = headers.get("X-Forwarded-For") {
-        if let Ok(header_str) = forwarded_for.to_str() {
-            if let Some(ip_str) = header_str.split(',').next() {
-                if let Ok(ip) = IpAddr::from_str(ip_str.trim()) {
-                    return ip;
-                }
-            }
-        }
-    }
+/// 🔑 Client a signed-in caller's requests are counted under
+pub fn client_id_for(user: &AuthenticatedUser) -> String {
+    format!("user:{}", user.id)
+}
 
-    if let Some(real_ip) = headers.get("X-Real-IP") {
-        if let Ok(header_str) = real_ip.to_str() {
-            if let Ok(ip) = IpAddr::from_str(header_str.trim()) {
-                return ip;
-            }
-        }
+/// 🌐 The client's IP address: the connection's peer, or, when that peer is a
+/// trusted proxy, the address its forwarding headers name. X-Forwarded-For is
+/// read right to left past trusted hops, since every hop appends to it and only
+/// the entries our own proxies added can be believed
+pub fn extract_client_ip(request: &Request, trusted_proxies: &[IpNet]) -> IpAddr {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    client_ip(request.headers(), peer, trusted_proxies)
+}
+
+/// 🌐 The client's IP address, from the peer address and the forwarding headers
+pub fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+
+    // 🎯 Without a peer address (served without connect info) we know nothing
+    let Some(peer) = peer.map(|ip| ip.to_canonical()) else {
+        return IpAddr::from([127, 0, 0, 1]);
+    };
+    if !is_trusted(&peer) {
+        return peer;
     }
 
-    if let Some(cf_connecting_ip) = headers.get("CF-Connecting-IP") {
-        if let Ok(header_str) = cf_connecting_ip.to_str() {
-            if let Ok(ip) = IpAddr::from_str(header_str.trim()) {
-                return ip;
+    if let Some(forwarded_for) = header_str(headers, "X-Forwarded-For") {
+        let mut client = peer;
+        for hop in forwarded_for.rsplit(',') {
+            // 🧱 A garbled hop ends what can be believed
+            let Ok(ip) = IpAddr::from_str(hop.trim()) else {
+                break;
+            };
+            client = ip.to_canonical();
+            if !is_trusted(&client) {
+                break;
             }
         }
+        return client;
     }
 
-    // 🎯 Fall back to connection peer (may not be accurate behind proxies)
-    // For now, return a default IP - in a real implementation, you'd extract from the connection
-    IpAddr::from_str("127.0.0.1").unwrap()
+    // 🔍 Single-address headers set by the proxy in front of us
+    ["X-Real-IP", "CF-Connecting-IP"]
+        .into_iter()
+        .find_map(|name| IpAddr::from_str(header_str(headers, name)?.trim()).ok())
+        .unwrap_or(peer)
+}
+
+/// 🔍 A header's value, when it is text
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
 }
 
-/// 🎯 Determine rate limit type based on request path
-fn determine_limit_type(path: &str) -> RateLimitType {
-    if path.starts_with("/api/feedback") && !path.ends_with("/stats") {
+/// 🎯 Determine rate limit type based on the request
+/// Reading feedback counts as a plain API request; only changes use the feedback quota
+fn determine_limit_type(method: &Method, path: &str) -> RateLimitType {
+    if path.starts_with("/api/feedback") && !path.ends_with("/stats") && method != Method::GET {
         RateLimitType::Feedback
     } else if path.starts_with("/api/webhook") {
         RateLimitType::Webhook
@@ -290,23 +667,27 @@ mod tests {
     #[test]
     fn test_determine_limit_type() {
         assert!(matches!(
-            determine_limit_type("/api/feedback"),
+            determine_limit_type(&Method::POST, "/api/feedback"),
             RateLimitType::Feedback
         ));
         assert!(matches!(
-            determine_limit_type("/api/feedback/123"),
+            determine_limit_type(&Method::POST, "/api/feedback/123"),
             RateLimitType::Feedback
         ));
         assert!(matches!(
-            determine_limit_type("/api/feedback/stats"),
+            determine_limit_type(&Method::GET, "/api/feedback/123/events"),
             RateLimitType::Api
         ));
         assert!(matches!(
-            determine_limit_type("/api/webhook/github"),
+            determine_limit_type(&Method::GET, "/api/feedback/stats"),
+            RateLimitType::Api
+        ));
+        assert!(matches!(
+            determine_limit_type(&Method::POST, "/api/webhook/github"),
             RateLimitType::Webhook
         ));
         assert!(matches!(
-            determine_limit_type("/api/health"),
+            determine_limit_type(&Method::GET, "/api/health"),
             RateLimitType::Api
         ));
         println!("✅ Rate limit type determination test passed!");
@@ -314,43 +695,165 @@ mod tests {
 
     #[test]
     fn test_extract_client_ip() {
+        let proxies: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+        let client = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+        let proxy = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
         let mut headers = HeaderMap::new();
         headers.insert(
             "X-Forwarded-For",
-            "192.168.1.100, 10.0.0.1".parse().unwrap(),
+            "192.168.1.100, 203.0.113.7, 10.0.0.2".parse().unwrap(),
         );
 
-        // Create a mock request (in real implementation, you'd need to create a proper request)
-        // For this test, we'll focus on the header parsing logic
+        // 🚫 Forwarding headers from anyone else are ignored
+        assert_eq!(client_ip(&headers, Some(client), &proxies), client);
+        assert_eq!(client_ip(&headers, Some(proxy), &[]), proxy);
 
-        // Test that we can parse the first IP from X-Forwarded-For
-        if let Some(forwarded_for) = headers.get("X-Forwarded-For") {
-            if let Ok(header_str) = forwarded_for.to_str() {
-                if let Some(ip_str) = header_str.split(',').next() {
-                    if let Ok(ip) = IpAddr::from_str(ip_str.trim()) {
-                        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)));
-                    }
-                }
-            }
-        }
+        // 🔁 Right to left past our own proxies: the spoofed first entry is skipped
+        assert_eq!(client_ip(&headers, Some(proxy), &proxies), client);
+
+        // 🧱 Everything trusted: the leftmost hop; garbage: the last hop we believed
+        headers.insert("X-Forwarded-For", "10.1.1.1, 10.0.0.2".parse().unwrap());
+        assert_eq!(
+            client_ip(&headers, Some(proxy), &proxies),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 1, 1))
+        );
+        headers.insert("X-Forwarded-For", "nonsense, 10.0.0.2".parse().unwrap());
+        assert_eq!(
+            client_ip(&headers, Some(proxy), &proxies),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))
+        );
+
+        // 🔍 X-Real-IP, only from a trusted proxy
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Real-IP", "203.0.113.7".parse().unwrap());
+        assert_eq!(client_ip(&headers, Some(proxy), &proxies), client);
+        assert_eq!(client_ip(&headers, Some(client), &[]), client);
+
+        // 🔌 IPv4 peers on a dual-stack socket
+        let mapped = IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped());
+        assert_eq!(client_ip(&headers, Some(mapped), &proxies), client);
+        assert_eq!(
+            client_ip(&headers, None, &proxies),
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        );
 
         println!("✅ Client IP extraction test passed!");
     }
 
     #[tokio::test]
     async fn test_rate_limit_manager() {
-        let manager = RateLimitManager::new(60, 10); // 60 requests per minute, 10 feedback per hour
+        let manager = RateLimitManager::new();
 
-        // Test that initial requests are allowed
-        for _ in 0..5 {
-            assert!(manager.api_limiter.check().is_ok());
-        }
-
-        // Test that feedback limiter works
+        // 📊 Each client gets its own window, up to the limit
         for _ in 0..3 {
-            assert!(manager.feedback_limiter.check().is_ok());
+            assert!(matches!(
+                manager
+                    .check_rate_limit("ip:10.0.0.1", RateLimitType::Api, 3)
+                    .await,
+                RateLimitResult::Allowed
+            ));
+        }
+        match manager
+            .check_rate_limit("ip:10.0.0.1", RateLimitType::Api, 3)
+            .await
+        {
+            RateLimitResult::Limited {
+                retry_after, limit, ..
+            } => {
+                assert_eq!(limit, 3);
+                // ⏳ Until the full window has slid far enough out of the next one
+                assert!(retry_after > Duration::from_secs(60));
+                assert!(retry_after <= Duration::from_secs(120));
+            }
+            RateLimitResult::Allowed => panic!("the fourth request should be limited"),
         }
+        assert!(matches!(
+            manager
+                .check_rate_limit("ip:10.0.0.2", RateLimitType::Api, 3)
+                .await,
+            RateLimitResult::Allowed
+        ));
+
+        // 📝 Feedback is counted separately from other requests
+        assert!(matches!(
+            manager
+                .check_rate_limit("ip:10.0.0.1", RateLimitType::Feedback, 1)
+                .await,
+            RateLimitResult::Allowed
+        ));
+
+        // 👀 Looking at usage doesn't count as a request
+        let usage = manager.usage("ip:10.0.0.2", RateLimitType::Api, 3);
+        assert_eq!((usage.used, usage.remaining), (1, 2));
+        assert!(usage.resets_in.is_some());
+        assert_eq!(
+            manager.usage("ip:10.0.0.2", RateLimitType::Api, 3).used,
+            usage.used
+        );
+        let fresh = manager.usage("ip:10.0.0.3", RateLimitType::Feedback, 10);
+        assert_eq!((fresh.remaining, fresh.resets_in), (10, None));
 
         println!("✅ Rate limit manager test passed!");
     }
+
+    #[test]
+    fn test_sliding_window() {
+        let start = Utc::now();
+        let at = |seconds: i64| start + chrono::Duration::seconds(seconds);
+        let mut entry = RateLimitEntry::new(RateLimitType::Api, start);
+        entry.count = 60;
+
+        // ⏩ A full window rolls into the previous one, which then slides out
+        entry.advance(at(75));
+        assert_eq!((entry.previous_count, entry.count), (60, 0));
+        assert_eq!(entry.window_start, at(60));
+        assert!((entry.estimate(at(75)) - 45.0).abs() < 0.01);
+        assert!((entry.estimate(at(90)) - 30.0).abs() < 0.01);
+
+        // ⏳ At a limit of 45, one more request fits once another second has slid out
+        let retry = entry.retry_after(at(75), 45);
+        assert_eq!(retry, Duration::from_secs(1));
+
+        // 🧹 Two windows later nothing is left
+        entry.advance(at(185));
+        assert_eq!((entry.previous_count, entry.count), (0, 0));
+        assert_eq!(entry.window_start, at(180));
+        println!("✅ Sliding window test passed!");
+    }
+
+    #[test]
+    fn test_rate_limit_tiers() {
+        let config = RateLimitConfig {
+            requests_per_minute: 60,
+            feedback_per_hour: 10,
+            burst_size: 10,
+            window_seconds: 60,
+            trusted_requests_per_minute: 600,
+            trusted_feedback_per_hour: 200,
+            sync_seconds: 5,
+        };
+
+        assert_eq!(
+            RateLimitTier::Standard.limit(&config, RateLimitType::Api),
+            Some(60)
+        );
+        assert_eq!(
+            RateLimitTier::Trusted.limit(&config, RateLimitType::Feedback),
+            Some(200)
+        );
+        assert_eq!(
+            RateLimitTier::Unlimited.limit(&config, RateLimitType::Api),
+            None
+        );
+        assert_eq!(
+            RateLimitTier::Standard.limit(&config, RateLimitType::Webhook),
+            None
+        );
+        assert_eq!(
+            "trusted".parse::<RateLimitTier>().unwrap(),
+            RateLimitTier::Trusted
+        );
+        assert!("vip".parse::<RateLimitTier>().is_err());
+        println!("✅ Rate limit tier test passed!");
+    }
 }
@@ -0,0 +1,249 @@
+// 🔏 Request Signing Middleware - Signed, Fresh and Only Once! 🔏
+// Service accounts issued a signing key with their API key (the Smart Tree
+// server-side integration, say) must sign every request: X-Feedbacker-Timestamp
+// holds the Unix time and X-Feedbacker-Signature the HMAC-SHA256 described in
+// feedbacker_types::signing. Requests signed more than
+// REQUEST_SIGNATURE_MAX_AGE_SECONDS ago, or dated more than a few minutes ahead,
+// are refused, and so is any signature already accepted (kept
+// SEEN_SIGNATURE_HOURS in the database, so every instance knows it). A signature
+// stops being fresh long before it is forgotten, so even with TLS ending at a
+// proxy, a captured request can't be replayed
+// Created with love by Aye & Hue - Once is enough! ✨
+
+use anyhow::{Context, Result};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::Utc;
+use feedbacker_types::signing::{self, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    api::{ApiResponse, AppState, ErrorResponse},
+    i18n,
+    middleware::auth::AuthenticatedUser,
+};
+
+/// ⏳ How long accepted signatures are remembered, counted from when they were seen
+pub const SEEN_SIGNATURE_HOURS: u64 = 24;
+
+/// ⏳ Longest REQUEST_SIGNATURE_MAX_AGE_SECONDS allowed: half of how long
+/// signatures are remembered, so none is forgotten while it is still fresh
+pub const MAX_SIGNATURE_AGE_SECONDS: u64 = SEEN_SIGNATURE_HOURS * 3600 / 2;
+
+/// ⏰ How far ahead of the server's clock a signing time may be
+const MAX_CLOCK_SKEW_SECONDS: u64 = 300;
+
+/// 🙅 Why a signed request was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Refusal {
+    Missing,
+    Expired,
+    Invalid,
+    Replayed,
+    TooLarge,
+}
+
+impl Refusal {
+    fn into_response(self) -> Response {
+        let (status, code, message) = match self {
+            Refusal::Missing => (
+                StatusCode::UNAUTHORIZED,
+                "request_signature_required",
+                "request-signature-required",
+            ),
+            Refusal::Expired => (
+                StatusCode::UNAUTHORIZED,
+                "request_signature_expired",
+                "request-signature-expired",
+            ),
+            Refusal::Invalid => (
+                StatusCode::UNAUTHORIZED,
+                "request_signature_invalid",
+                "request-signature-invalid",
+            ),
+            Refusal::Replayed => (
+                StatusCode::UNAUTHORIZED,
+                "request_signature_replayed",
+                "request-signature-replayed",
+            ),
+            Refusal::TooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                "request-signature-body-too-large",
+            ),
+        };
+        let api_response = ApiResponse::<()>::error(code.to_string(), i18n::t(message), None);
+        (status, Json(api_response)).into_response()
+    }
+}
+
+/// ⏰ The timestamp and signature headers, when both are present
+fn signature_headers(headers: &HeaderMap) -> Option<(i64, &str)> {
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let signature = headers.get(SIGNATURE_HEADER)?.to_str().ok()?.trim();
+    Some((timestamp, signature))
+}
+
+/// ⏳ Whether a signing time is at most `max_age` seconds ago, or ahead of now
+/// by no more than the clock skew allowance
+fn is_fresh(timestamp: i64, now: i64, max_age: u64) -> bool {
+    if timestamp > now {
+        timestamp.abs_diff(now) <= max_age.min(MAX_CLOCK_SKEW_SECONDS)
+    } else {
+        now.abs_diff(timestamp) <= max_age
+    }
+}
+
+/// 🔁 Remember an accepted signature; false when it was seen before
+async fn record_signature(pool: &PgPool, signature: &str, user_id: Uuid) -> Result<bool> {
+    let inserted = sqlx::query(
+        "INSERT INTO request_signatures (signature, user_id) VALUES ($1, $2) ON CONFLICT (signature) DO NOTHING",
+    )
+    .bind(signature.to_lowercase())
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .context("Failed to record request signature")?
+    .rows_affected();
+    Ok(inserted == 1)
+}
+
+/// 🧹 Forget signatures old enough that their timestamps are refused anyway
+pub async fn prune_seen_signatures(pool: &PgPool) -> Result<u64> {
+    let pruned = sqlx::query(
+        "DELETE FROM request_signatures WHERE seen_at < NOW() - make_interval(hours => $1)",
+    )
+    .bind(SEEN_SIGNATURE_HOURS as i32)
+    .execute(pool)
+    .await
+    .context("Failed to prune request signatures")?
+    .rows_affected();
+    Ok(pruned)
+}
+
+/// 🔏 Refuse unsigned, stale, forged or replayed requests from accounts that sign
+pub async fn request_signing_middleware(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some((user_id, key)) = request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .and_then(|user| Some((user.id, user.request_signing_key.clone()?)))
+    else {
+        return next.run(request).await;
+    };
+    let config = app_state.config.load();
+
+    let Some((timestamp, signature)) = signature_headers(request.headers()) else {
+        return Refusal::Missing.into_response();
+    };
+    let signature = signature.to_string();
+    if !is_fresh(
+        timestamp,
+        Utc::now().timestamp(),
+        config.auth.request_signature_max_age_seconds,
+    ) {
+        return Refusal::Expired.into_response();
+    }
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, config.server.max_body_size).await else {
+        return Refusal::TooLarge.into_response();
+    };
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    if !signing::verify(
+        &key,
+        timestamp,
+        parts.method.as_str(),
+        path_and_query,
+        &body,
+        &signature,
+    ) {
+        warn!(
+            "🔏 Refused a badly signed {} {} from {}",
+            parts.method, path_and_query, user_id
+        );
+        return Refusal::Invalid.into_response();
+    }
+
+    match record_signature(&app_state.db_pool, &signature, user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            warn!(
+                "🔁 Refused a replayed {} {} from {}",
+                parts.method, path_and_query, user_id
+            );
+            return Refusal::Replayed.into_response();
+        }
+        Err(e) => return crate::errors::error_response("Request signature check failed", e),
+    }
+
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+// 🧪 Tests - No second helpings!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_signature_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(signature_headers(&headers), None);
+
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static("1700000000"));
+        assert_eq!(signature_headers(&headers), None);
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_static(" ab12 "));
+        assert_eq!(signature_headers(&headers), Some((1700000000, "ab12")));
+
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static("yesterday"));
+        assert_eq!(signature_headers(&headers), None);
+        println!("✅ Signature header test passed!");
+    }
+
+    #[test]
+    fn test_freshness() {
+        let now = 1700000000;
+        assert!(is_fresh(now, now, 300));
+        assert!(is_fresh(now - 300, now, 300));
+        // ⏰ A client clock running a little ahead is fine too
+        assert!(is_fresh(now + 120, now, 300));
+        assert!(!is_fresh(now - 301, now, 300));
+        assert!(!is_fresh(now + 301, now, 300));
+        assert!(!is_fresh(i64::MIN, now, 300));
+
+        // 🔮 A long max age doesn't stretch how far ahead a timestamp may be: a
+        // request dated tomorrow would still be fresh after its signature is forgotten
+        let max_age = MAX_SIGNATURE_AGE_SECONDS;
+        assert!(is_fresh(now - max_age as i64, now, max_age));
+        assert!(is_fresh(now + 300, now, max_age));
+        assert!(!is_fresh(now + 301, now, max_age));
+        assert!(!is_fresh(now + 6 * 3600, now, max_age));
+        assert!(!is_fresh(i64::MAX, now, max_age));
+        assert!(is_fresh(now + 30, now, 60));
+        assert!(!is_fresh(now + 61, now, 60));
+        // ⏳ Fresh for at most max age + skew after signing, well within what's remembered
+        assert!(max_age + MAX_CLOCK_SKEW_SECONDS < SEEN_SIGNATURE_HOURS * 3600);
+        println!("✅ Signature freshness test passed!");
+    }
+}
@@ -0,0 +1,144 @@
+// 📬 Webhook Delivery Middleware - Every Delivery Leaves a Receipt! 📬
+// Wraps the webhook endpoints: each delivery is logged with its GitHub
+// delivery id, event, action and repository, the status and message we
+// answered with, and how long processing took. Project owners read the log at
+// GET /api/projects/:id/webhook-deliveries to see whether their hook reaches
+// us and what became of it. A failure to log never changes the answer GitHub gets
+// Created with love by Aye & Hue - Signed, sealed, delivered! ✨
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::Value;
+use std::time::Instant;
+use tracing::warn;
+
+use crate::{
+    api::{ApiResponse, AppState, ErrorResponse},
+    database::models::{NewWebhookDelivery, WebhookDelivery},
+    github::webhooks::WebhookEvent,
+};
+
+/// 🏷️ Header carrying GitHub's id of a delivery
+pub const DELIVERY_HEADER: &str = "x-github-delivery";
+
+/// 📬 Log every delivery to the wrapped webhook endpoint
+pub async fn webhook_delivery_middleware(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let (parts, body) = request.into_parts();
+    let max_body_size = app_state.config.load().server.max_body_size;
+    let Ok(body) = to_bytes(body, max_body_size).await else {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ApiResponse::<()>::error(
+                "payload_too_large".to_string(),
+                "Webhook payload is too large".to_string(),
+                None,
+            )),
+        )
+            .into_response();
+    };
+
+    let payload = serde_json::from_slice::<Value>(&body).unwrap_or_default();
+    let (action, repository) = delivery_subject(&payload);
+    let endpoint = parts.uri.path().rsplit('/').next().unwrap_or_default();
+    let mut delivery = NewWebhookDelivery {
+        delivery_id: delivery_id(&parts.headers),
+        endpoint: endpoint.to_string(),
+        event: WebhookEvent::name(&parts.headers).to_string(),
+        action,
+        repository,
+        status_code: 0,
+        result: String::new(),
+        duration_ms: 0,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // 📝 Webhook handlers answer with a small envelope, so it's read whole
+    let (parts, body) = response.into_parts();
+    let bytes = to_bytes(body, usize::MAX).await.unwrap_or_default();
+    delivery.status_code = i32::from(parts.status.as_u16());
+    delivery.result = response_message(&bytes).unwrap_or_else(|| {
+        parts
+            .status
+            .canonical_reason()
+            .unwrap_or_default()
+            .to_string()
+    });
+    delivery.duration_ms = started.elapsed().as_millis() as i64;
+
+    if let Err(e) = WebhookDelivery::record(&app_state.db_pool, &delivery).await {
+        warn!(
+            "⚠️ Could not log {} delivery {:?}: {:#}",
+            delivery.event, delivery.delivery_id, e
+        );
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// 🏷️ GitHub's id of a delivery, when the header is there
+fn delivery_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(DELIVERY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// 🎯 The `action` and `owner/repo` a delivery is about, read from the raw
+/// payload so events we don't handle are logged against their repository too
+fn delivery_subject(payload: &Value) -> (Option<String>, Option<String>) {
+    let action = payload["action"].as_str().map(str::to_string);
+    let repository = payload["repository"]["full_name"]
+        .as_str()
+        .map(str::to_string);
+    (action, repository)
+}
+
+/// 📝 The `message` of an API response body
+fn response_message(body: &[u8]) -> Option<String> {
+    let body = serde_json::from_slice::<Value>(body).ok()?;
+    body["message"].as_str().map(str::to_string)
+}
+
+// 🧪 Tests - Every receipt says what it's for!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_delivery_details() {
+        let payload = json!({
+            "action": "opened",
+            "repository": { "full_name": "aye-is/feedbacker" }
+        });
+        assert_eq!(
+            delivery_subject(&payload),
+            (
+                Some("opened".to_string()),
+                Some("aye-is/feedbacker".to_string())
+            )
+        );
+        assert_eq!(delivery_subject(&json!({ "zen": "Hi" })), (None, None));
+        assert_eq!(delivery_subject(&Value::Null), (None, None));
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(delivery_id(&headers), None);
+        headers.insert(DELIVERY_HEADER, "72d3162e-cc78".parse().unwrap());
+        assert_eq!(delivery_id(&headers).as_deref(), Some("72d3162e-cc78"));
+
+        let body = br#"{"success":true,"message":"pong","timestamp":"2024-01-01T00:00:00Z"}"#;
+        assert_eq!(response_message(body).as_deref(), Some("pong"));
+        assert_eq!(response_message(b"not json"), None);
+        println!("✅ Webhook delivery details test passed!");
+    }
+}
@@ -1,2 +1,13 @@
 // 📊 Models Module - Additional Data Models! 📊
-// TODO: Implement additional models beyond database models
+// Typed structures that live alongside (or inside) our database models
+// Created with love by Aye & Hue! ✨
+
+pub mod path_scope; // 📁 Monorepo subdirectory scoping
+pub mod project_config; // ⚙️ Typed per-project settings
+
+pub use path_scope::PathScope;
+pub use project_config::{
+    BudgetSettings, CollaboratorPermission, CollaboratorSettings, FormatTool, FormattingSettings,
+    HealthCheck, LicensingSettings, ProcessingSettings, ProjectConfig, PublicStatusSettings,
+    PullRequestSettings, RiskLevel, ScanOutput, ScanSettings, ScmSettings,
+};
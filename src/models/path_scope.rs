@@ -0,0 +1,162 @@
+// 📁 Path Scope - Keeping Monorepo Feedback in Its Lane! 📁
+// Feedback about `crates/foo` should only read and change files under `crates/foo`.
+// A scope is an optional repository subdirectory; no scope means the whole repo
+// Created with love by Aye & Hue - Big repos, small blast radius! ✨
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 📁 Repository subtree that a feedback item is allowed to touch
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PathScope {
+    /// 📂 Normalized subdirectory (None = repository root)
+    pub root: Option<String>,
+}
+
+impl PathScope {
+    /// 🌍 Scope covering the whole repository
+    pub fn whole_repository() -> Self {
+        Self::default()
+    }
+
+    /// 📂 Scope for a subdirectory, normalizing the path first
+    pub fn new(path: &str) -> Result<Self> {
+        Ok(Self {
+            root: normalize_scope_path(path)?,
+        })
+    }
+
+    /// 🧭 Combine the project scope with a feedback scope
+    /// Feedback may narrow the project scope, but never escape it
+    pub fn resolve(project_path: Option<&str>, feedback_path: Option<&str>) -> Result<Self> {
+        let project = match project_path {
+            Some(path) => Self::new(path)?,
+            None => Self::whole_repository(),
+        };
+
+        match feedback_path {
+            Some(path) => {
+                let feedback = Self::new(path)?;
+                if let Some(root) = &feedback.root {
+                    if !project.contains(root) {
+                        anyhow::bail!(
+                            "Path '{}' is outside the project scope '{}'",
+                            root,
+                            project.display()
+                        );
+                    }
+                }
+                Ok(if feedback.root.is_some() {
+                    feedback
+                } else {
+                    project
+                })
+            }
+            None => Ok(project),
+        }
+    }
+
+    /// 🔍 Whether a repository-relative file path falls inside this scope
+    pub fn contains(&self, file_path: &str) -> bool {
+        match &self.root {
+            None => true,
+            Some(root) => {
+                let file_path = file_path.trim_start_matches("./");
+                file_path == root
+                    || file_path
+                        .strip_prefix(root.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+        }
+    }
+
+    /// 🧹 Keep only the files inside this scope
+    pub fn filter<'a, I>(&self, files: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        files
+            .into_iter()
+            .filter(|file| self.contains(file))
+            .cloned()
+            .collect()
+    }
+
+    /// 🌿 Paths to materialize in a sparse checkout (empty = everything)
+    pub fn sparse_paths(&self) -> Vec<String> {
+        self.root.iter().cloned().collect()
+    }
+
+    /// 🏷️ Human-readable form for prompts and errors
+    pub fn display(&self) -> &str {
+        self.root.as_deref().unwrap_or(".")
+    }
+}
+
+/// 🧹 Normalize a user-supplied subdirectory
+/// Returns None for the repository root ("", ".", "/")
+pub fn normalize_scope_path(path: &str) -> Result<Option<String>> {
+    let trimmed = path.trim();
+    if trimmed.contains('\\') {
+        anyhow::bail!("Path '{}' must use forward slashes", trimmed);
+    }
+
+    let mut segments = Vec::new();
+    for segment in trimmed.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => anyhow::bail!("Path '{}' cannot contain '..'", trimmed),
+            segment => segments.push(segment),
+        }
+    }
+
+    if trimmed.starts_with('/') && !segments.is_empty() {
+        anyhow::bail!("Path '{}' must be relative to the repository root", trimmed);
+    }
+
+    Ok((!segments.is_empty()).then(|| segments.join("/")))
+}
+
+// 🧪 Tests - Scopes must never leak outside their subtree!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_scope_path() {
+        assert_eq!(
+            normalize_scope_path("./crates/foo/").unwrap().as_deref(),
+            Some("crates/foo")
+        );
+        assert_eq!(normalize_scope_path(" . ").unwrap(), None);
+        assert!(normalize_scope_path("crates/../secrets").is_err());
+        assert!(normalize_scope_path("/etc").is_err());
+        assert!(normalize_scope_path("crates\\foo").is_err());
+        println!("✅ Scope path normalization test passed!");
+    }
+
+    #[test]
+    fn test_scope_contains() {
+        let scope = PathScope::new("crates/foo").unwrap();
+        assert!(scope.contains("crates/foo/src/lib.rs"));
+        assert!(scope.contains("./crates/foo/Cargo.toml"));
+        assert!(!scope.contains("crates/foobar/src/lib.rs"));
+        assert!(!scope.contains("Cargo.toml"));
+        assert!(PathScope::whole_repository().contains("anything/at/all.rs"));
+        assert_eq!(scope.sparse_paths(), vec!["crates/foo"]);
+        println!("✅ Scope containment test passed!");
+    }
+
+    #[test]
+    fn test_scope_resolution() {
+        let scope = PathScope::resolve(Some("crates"), Some("crates/foo")).unwrap();
+        assert_eq!(scope.display(), "crates/foo");
+
+        let scope = PathScope::resolve(Some("crates"), None).unwrap();
+        assert_eq!(scope.display(), "crates");
+
+        assert!(PathScope::resolve(Some("crates"), Some("docs")).is_err());
+        assert_eq!(PathScope::resolve(None, None).unwrap().display(), ".");
+        println!("✅ Scope resolution test passed!");
+    }
+}
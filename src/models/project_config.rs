@@ -0,0 +1,731 @@
+// ⚙️ Project Configuration - Typed View of `Project.config`! ⚙️
+// Projects store their settings as JSONB, this module gives them structure
+// Created with love by Aye & Hue - Making per-project settings a breeze! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::path_scope::{normalize_scope_path, PathScope};
+use crate::config::{is_endpoint_name, ModelTier};
+
+/// 🐙 GitHub allows at most 10 assignees per issue/PR
+pub const MAX_PR_ASSIGNEES: usize = 10;
+
+/// 🗓️ Default scan schedule: Mondays at 03:00 UTC
+pub const DEFAULT_SCAN_SCHEDULE: &str = "0 3 * * 1";
+
+/// 📏 Longest public status page slug
+pub const MAX_SLUG_LEN: usize = 64;
+
+/// ⏳ Longest cooldown between pull requests to one repository (a week)
+pub const MAX_PR_COOLDOWN_MINUTES: u32 = 7 * 24 * 60;
+
+/// 📜 Longest license header a project can require
+pub const MAX_LICENSE_HEADER_LINES: usize = 20;
+
+/// 🚫 Boilerplate generated code may not add unless a project says otherwise:
+/// copyleft license notices and proprietary "all rights reserved" claims
+pub const DEFAULT_FORBIDDEN_LICENSE_MARKERS: &[&str] = &[
+    "GNU General Public License",
+    "GNU Affero General Public License",
+    "GNU Lesser General Public License",
+    "SPDX-License-Identifier: GPL",
+    "SPDX-License-Identifier: AGPL",
+    "SPDX-License-Identifier: LGPL",
+    "All rights reserved",
+];
+
+/// ⚙️ Typed project configuration stored in `projects.config`
+/// Unknown keys are ignored so older rows keep loading happily
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// 📁 Monorepo subdirectory this project lives in (None = whole repository)
+    pub path: Option<String>,
+    /// 🐙 Settings applied to every pull request Feedbacker opens
+    pub pull_requests: PullRequestSettings,
+    /// 🩺 Scheduled repository health scans (opt-in)
+    pub scans: ScanSettings,
+    /// 📜 Store redacted LLM prompts and responses for this project (browsable by admins)
+    pub prompt_logging: bool,
+    /// 🧭 Model tier per pipeline stage, overriding the service-wide routing rules
+    pub model_routing: HashMap<String, ModelTier>,
+    /// 🌍 Public status page at /p/:slug/status (opt-in)
+    pub public_status: PublicStatusSettings,
+    /// 🏢 Which GitHub the repository is on (the organization's, else the default)
+    pub scm: ScmSettings,
+    /// 🚦 How many runs may go at once, and how long to wait between PRs
+    pub processing: ProcessingSettings,
+    /// 💸 Token caps overriding MAX_TOKENS_PER_FEEDBACK / MAX_TOKENS_PER_PROJECT_MONTH
+    pub budget: BudgetSettings,
+    /// 👥 Repository collaborators mirrored into project roles (opt-in)
+    pub collaborators: CollaboratorSettings,
+    /// 🎨 Formatters and linters run over generated files before a PR opens
+    pub formatting: FormattingSettings,
+    /// ⚖️ License header for new files, and license text generated code may not add
+    pub licensing: LicensingSettings,
+}
+
+/// ⚖️ Licensing policy generated changes are checked against before a PR opens
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LicensingSettings {
+    /// 📜 Header every new source file starts with (`{year}` stands for any year)
+    pub header: Option<String>,
+    /// 🚫 Text generated code may not add (matched case-insensitively)
+    pub forbidden_markers: Vec<String>,
+}
+
+impl Default for LicensingSettings {
+    fn default() -> Self {
+        Self {
+            header: None,
+            forbidden_markers: DEFAULT_FORBIDDEN_LICENSE_MARKERS
+                .iter()
+                .map(|marker| marker.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 🎨 The formatter pass over generated files (on by default)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FormattingSettings {
+    /// ✅ Whether generated files are formatted at all
+    pub enabled: bool,
+    /// 🧰 Tools to run (None = the ones the repository is set up for)
+    pub tools: Option<Vec<FormatTool>>,
+}
+
+impl Default for FormattingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tools: None,
+        }
+    }
+}
+
+/// 🧰 Formatters and linters the formatter pass knows how to run, in the
+/// order they run (lint fixes first, so formatters tidy up after them)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatTool {
+    /// 📎 `cargo clippy --fix` (machine-applicable lint fixes)
+    Clippy,
+    /// 🦀 `cargo fmt`
+    Rustfmt,
+    /// ⚡ `ruff check --fix` and `ruff format`
+    Ruff,
+    /// 🐍 `black`
+    Black,
+    /// 💅 `prettier --write`
+    Prettier,
+}
+
+impl FormatTool {
+    /// 🏷️ Name used in settings, events and commit messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FormatTool::Clippy => "clippy",
+            FormatTool::Rustfmt => "rustfmt",
+            FormatTool::Ruff => "ruff",
+            FormatTool::Black => "black",
+            FormatTool::Prettier => "prettier",
+        }
+    }
+}
+
+/// 👥 Who on GitHub gets a member role on the project, synced every hour
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CollaboratorSettings {
+    /// ✅ Whether the repository's collaborators are mirrored at all
+    pub sync: bool,
+    /// 🔑 Least access to the repository that counts
+    pub permission: CollaboratorPermission,
+}
+
+/// 🔑 GitHub repository permission levels a collaborator sync can ask for
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollaboratorPermission {
+    /// 📤 Can push (write access)
+    #[default]
+    Push,
+    /// 🛠️ Can manage the repository without admin access
+    Maintain,
+    /// 👑 Repository admins
+    Admin,
+}
+
+impl CollaboratorPermission {
+    /// 🏷️ Name GitHub's collaborator filter uses
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CollaboratorPermission::Push => "push",
+            CollaboratorPermission::Maintain => "maintain",
+            CollaboratorPermission::Admin => "admin",
+        }
+    }
+}
+
+/// 💸 A project's own spend caps (None = the service-wide cap, 0 = no cap)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BudgetSettings {
+    /// 📝 Tokens one feedback item may use
+    pub feedback_tokens: Option<u64>,
+    /// 🗓️ Tokens the project may use per calendar month
+    pub monthly_tokens: Option<u64>,
+}
+
+/// 🚦 Pacing of project runs, so busy CI isn't flooded with PRs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProcessingSettings {
+    /// 🏭 Runs of this project that may go at once (None = no limit)
+    pub max_concurrent_runs: Option<usize>,
+    /// ⏳ Minutes to wait after a PR is opened in the repository before the
+    /// next run starts (0 = no cooldown)
+    pub pr_cooldown_minutes: u32,
+}
+
+/// 🏢 Where a repository is hosted, for projects and organizations (`settings.scm`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScmSettings {
+    /// 🏷️ Name of a GITHUB_ENDPOINTS entry (None = inherited, down to GITHUB_API_BASE_URL)
+    pub endpoint: Option<String>,
+}
+
+/// 🌍 Public status page settings for a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PublicStatusSettings {
+    /// ✅ Whether anyone may see the page, signed in or not
+    pub enabled: bool,
+    /// 🔗 URL name of the page: lowercase letters, digits and `-`
+    pub slug: Option<String>,
+}
+
+/// 🐙 Pull request settings for a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PullRequestSettings {
+    /// 🏷️ Labels added to every PR (must already exist in the repo)
+    pub labels: Vec<String>,
+    /// 🎯 Milestone title the PR is attached to (must be open)
+    pub milestone: Option<String>,
+    /// 📝 Open PRs as drafts instead of ready for review
+    pub draft: bool,
+    /// 👥 GitHub users assigned to every PR
+    pub assignees: Vec<String>,
+    /// ✂️ Maximum changed lines per PR; larger changes are split into a series
+    pub max_pr_size: Option<usize>,
+    /// 🔀 Bring open Feedbacker PRs up to date when the base branch moves on and
+    /// they fall behind or conflict (needs the GitHub webhook to send push events)
+    pub auto_rebase: bool,
+    /// ⚖️ Assessed risk at which a PR needs a maintainer's approval: it's opened
+    /// as a draft until someone marks it ready for review (None = never)
+    pub approval_risk: Option<RiskLevel>,
+}
+
+impl Default for PullRequestSettings {
+    fn default() -> Self {
+        Self {
+            labels: Vec::new(),
+            milestone: None,
+            draft: false,
+            assignees: Vec::new(),
+            max_pr_size: None,
+            auto_rebase: false,
+            approval_risk: Some(RiskLevel::High),
+        }
+    }
+}
+
+/// ⚖️ How risky a generated change looks (see pipeline::risk)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    /// 🟢 Small, well-tested, nothing sensitive
+    Low,
+    /// 🟡 Worth a careful look
+    Medium,
+    /// 🔴 Migrations, CI or a large change with little test coverage
+    High,
+}
+
+impl RiskLevel {
+    /// 🏷️ Name used in labels and PR bodies
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
+/// 🩺 Scheduled repository health scan settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScanSettings {
+    /// ✅ Whether this project is scanned at all
+    pub enabled: bool,
+    /// 🗓️ Cron expression (UTC, 5 or 6 fields) for when scans run
+    pub schedule: String,
+    /// 🔍 Which health checks to run
+    pub checks: Vec<HealthCheck>,
+    /// 📬 Where findings are filed
+    pub output: ScanOutput,
+    /// 📤 Also scan when a push to the default branch touches the project's files
+    /// (needs the GitHub webhook to send push events)
+    pub on_push: bool,
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: DEFAULT_SCAN_SCHEDULE.to_string(),
+            checks: vec![
+                HealthCheck::OutdatedDocs,
+                HealthCheck::TodoDensity,
+                HealthCheck::MissingTests,
+            ],
+            output: ScanOutput::Feedback,
+            on_push: false,
+        }
+    }
+}
+
+/// 🔍 Repository health checks a scan can run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheck {
+    /// 📚 Docs that reference files which no longer exist
+    OutdatedDocs,
+    /// 📝 Files crowded with TODO/FIXME markers
+    TodoDensity,
+    /// 🧪 Sizeable source files without any tests
+    MissingTests,
+}
+
+/// 📬 Where scan findings end up
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanOutput {
+    /// 📝 A suggested feedback item, processed like user feedback
+    #[default]
+    Feedback,
+    /// 🐙 A GitHub issue on the repository
+    Issue,
+}
+
+impl ProjectConfig {
+    /// 📥 Parse the config column, treating NULL as defaults
+    pub fn from_json(value: Option<&serde_json::Value>) -> Result<Self> {
+        match value {
+            Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+                .context("Project config does not match the expected format"),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// 📤 Serialize back into the JSONB column format
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}))
+    }
+
+    /// 📁 Scope for this project (the whole repository unless `path` is set)
+    pub fn scope(&self) -> Result<PathScope> {
+        PathScope::resolve(self.path.as_deref(), None)
+    }
+
+    /// ✅ Local validation that doesn't need the GitHub API
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if let Some(path) = &self.path {
+            if let Err(e) = normalize_scope_path(path) {
+                errors.push(e.to_string());
+            }
+        }
+        self.pull_requests.validate_into(&mut errors);
+        self.scans.validate_into(&mut errors);
+        self.public_status.validate_into(&mut errors);
+        self.scm.validate_into(&mut errors);
+        self.processing.validate_into(&mut errors);
+        self.formatting.validate_into(&mut errors);
+        self.licensing.validate_into(&mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl PullRequestSettings {
+    /// ✅ Check for empty names, duplicates, and GitHub limits
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        if self.labels.iter().any(|label| label.trim().is_empty()) {
+            errors.push("Pull request labels cannot be empty".to_string());
+        }
+        if has_duplicates(&self.labels) {
+            errors.push("Pull request labels must be unique".to_string());
+        }
+
+        if let Some(milestone) = &self.milestone {
+            if milestone.trim().is_empty() {
+                errors.push("Pull request milestone cannot be empty".to_string());
+            }
+        }
+
+        if self.assignees.iter().any(|user| user.trim().is_empty()) {
+            errors.push("Pull request assignees cannot be empty".to_string());
+        }
+        if has_duplicates(&self.assignees) {
+            errors.push("Pull request assignees must be unique".to_string());
+        }
+        if self.assignees.len() > MAX_PR_ASSIGNEES {
+            errors.push(format!(
+                "Pull requests can have at most {} assignees",
+                MAX_PR_ASSIGNEES
+            ));
+        }
+
+        if self.max_pr_size == Some(0) {
+            errors.push("Maximum pull request size must be greater than zero".to_string());
+        }
+    }
+
+    /// 🔍 Whether anything needs to be applied after the PR is created
+    pub fn has_issue_fields(&self) -> bool {
+        !self.labels.is_empty() || self.milestone.is_some() || !self.assignees.is_empty()
+    }
+}
+
+impl ProcessingSettings {
+    /// ✅ At least one run at a time, and a cooldown of at most a week
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        if self.max_concurrent_runs == Some(0) {
+            errors.push("Maximum concurrent runs must be greater than zero".to_string());
+        }
+        if self.pr_cooldown_minutes > MAX_PR_COOLDOWN_MINUTES {
+            errors.push(format!(
+                "Pull request cooldown can be at most {} minutes",
+                MAX_PR_COOLDOWN_MINUTES
+            ));
+        }
+    }
+}
+
+impl FormattingSettings {
+    /// ✅ Each tool listed at most once
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        let tools = self.tools.as_deref().unwrap_or_default();
+        let unique: std::collections::HashSet<_> = tools.iter().collect();
+        if unique.len() != tools.len() {
+            errors.push("Formatting tools must be listed at most once".to_string());
+        }
+    }
+}
+
+impl LicensingSettings {
+    /// ✅ A header that says something (and not too much), no blank markers
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        if let Some(header) = &self.header {
+            if header.trim().is_empty() {
+                errors.push("License header cannot be empty".to_string());
+            } else if header.lines().count() > MAX_LICENSE_HEADER_LINES {
+                errors.push(format!(
+                    "License header can be at most {} lines",
+                    MAX_LICENSE_HEADER_LINES
+                ));
+            }
+        }
+        if self
+            .forbidden_markers
+            .iter()
+            .any(|marker| marker.trim().is_empty())
+        {
+            errors.push("Forbidden license markers cannot be empty".to_string());
+        }
+    }
+}
+
+impl ScanSettings {
+    /// 🗓️ Parse the cron schedule
+    pub fn cron(&self) -> Result<croner::Cron> {
+        croner::Cron::new(&self.schedule)
+            .with_seconds_optional()
+            .parse()
+            .with_context(|| format!("Invalid scan schedule '{}'", self.schedule))
+    }
+
+    /// ⏰ Whether a scan is due, given when the last one started
+    /// Projects that were never scanned get their first scan right away
+    pub fn is_due(&self, last_scan: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<bool> {
+        let Some(since) = last_scan else {
+            return Ok(true);
+        };
+        let next = self
+            .cron()?
+            .find_next_occurrence(&since, false)
+            .context("Scan schedule never fires")?;
+        Ok(next <= now)
+    }
+
+    /// ✅ Check the schedule parses and there is something to run
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        if let Err(e) = self.cron() {
+            errors.push(format!("{:#}", e));
+        }
+        if self.enabled && self.checks.is_empty() {
+            errors.push("Enabled scans must run at least one check".to_string());
+        }
+    }
+}
+
+impl PublicStatusSettings {
+    /// 🔗 Slug of the page, while it is public
+    pub fn public_slug(&self) -> Option<&str> {
+        self.slug.as_deref().filter(|_| self.enabled)
+    }
+
+    /// ✅ A public page needs a well-formed slug
+    fn validate_into(&self, errors: &mut Vec<String>) {
+        match &self.slug {
+            Some(slug) => {
+                let valid = !slug.is_empty()
+                    && slug.len() <= MAX_SLUG_LEN
+                    && !slug.starts_with('-')
+                    && !slug.ends_with('-')
+                    && slug
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+                if !valid {
+                    errors.push(format!(
+                        "Public status slug must be 1-{} lowercase letters, digits or inner hyphens",
+                        MAX_SLUG_LEN
+                    ));
+                }
+            }
+            None if self.enabled => {
+                errors.push("Public status pages need a slug".to_string());
+            }
+            None => {}
+        }
+    }
+}
+
+impl ScmSettings {
+    /// 📥 The `scm` key of an organization's settings object (defaults when absent)
+    pub fn from_organization_settings(settings: &serde_json::Value) -> Result<Self> {
+        match settings.get("scm") {
+            Some(scm) if !scm.is_null() => serde_json::from_value(scm.clone())
+                .context("Organization scm settings do not match the expected format"),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// ✅ Endpoint names are checked against GITHUB_ENDPOINTS when saved
+    pub fn validate_into(&self, errors: &mut Vec<String>) {
+        if let Some(endpoint) = &self.endpoint {
+            if !is_endpoint_name(endpoint) {
+                errors.push(format!(
+                    "SCM endpoint '{}' may only use lowercase letters, digits and dashes",
+                    endpoint
+                ));
+            }
+        }
+    }
+}
+
+/// 🔍 Case-insensitive duplicate check (GitHub treats labels/users that way)
+fn has_duplicates(values: &[String]) -> bool {
+    let mut seen = std::collections::HashSet::new();
+    values
+        .iter()
+        .any(|value| !seen.insert(value.to_lowercase()))
+}
+
+// 🧪 Tests - Making sure project settings parse and validate correctly!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_project_config_defaults_from_null() {
+        let config = ProjectConfig::from_json(None).unwrap();
+        assert_eq!(config, ProjectConfig::default());
+
+        let config = ProjectConfig::from_json(Some(&serde_json::Value::Null)).unwrap();
+        assert!(!config.pull_requests.draft);
+        assert!(config.formatting.enabled);
+        assert_eq!(config.formatting.tools, None);
+        assert_eq!(config.licensing.header, None);
+        assert!(config
+            .licensing
+            .forbidden_markers
+            .iter()
+            .any(|marker| marker == "All rights reserved"));
+        assert_eq!(config.pull_requests.approval_risk, Some(RiskLevel::High));
+
+        // 🚫 An explicit null turns the approval gate off
+        let value = serde_json::json!({ "pull_requests": { "approval_risk": null } });
+        let config = ProjectConfig::from_json(Some(&value)).unwrap();
+        assert_eq!(config.pull_requests.approval_risk, None);
+        println!("✅ Project config defaults test passed!");
+    }
+
+    #[test]
+    fn test_project_config_parsing() {
+        let value = serde_json::json!({
+            "path": "crates/foo/",
+            "pull_requests": {
+                "labels": ["feedbacker", "ai"],
+                "milestone": "v1.0",
+                "draft": true,
+                "assignees": ["aye-is"],
+                "approval_risk": "medium"
+            },
+            "model_routing": { "docs_edit": "small" },
+            "scm": { "endpoint": "ghe" },
+            "processing": { "max_concurrent_runs": 1, "pr_cooldown_minutes": 30 },
+            "budget": { "monthly_tokens": 2000000 },
+            "collaborators": { "sync": true, "permission": "maintain" },
+            "formatting": { "tools": ["prettier", "clippy"] },
+            "licensing": { "header": "Copyright {year} Aye\nSPDX-License-Identifier: MIT" },
+            "unknown_key": 42
+        });
+
+        let config = ProjectConfig::from_json(Some(&value)).unwrap();
+        assert_eq!(config.pull_requests.labels, vec!["feedbacker", "ai"]);
+        assert_eq!(config.pull_requests.milestone.as_deref(), Some("v1.0"));
+        assert!(config.pull_requests.draft);
+        assert!(config.pull_requests.has_issue_fields());
+        assert_eq!(config.pull_requests.approval_risk, Some(RiskLevel::Medium));
+        assert_eq!(config.model_routing["docs_edit"], ModelTier::Small);
+        assert_eq!(config.scm.endpoint.as_deref(), Some("ghe"));
+        assert_eq!(config.processing.max_concurrent_runs, Some(1));
+        assert_eq!(config.processing.pr_cooldown_minutes, 30);
+        assert_eq!(config.budget.monthly_tokens, Some(2_000_000));
+        assert_eq!(config.budget.feedback_tokens, None);
+        assert!(config.collaborators.sync);
+        assert_eq!(config.collaborators.permission.as_str(), "maintain");
+        assert!(config.formatting.enabled);
+        assert_eq!(
+            config.formatting.tools,
+            Some(vec![FormatTool::Prettier, FormatTool::Clippy])
+        );
+        assert_eq!(
+            config.licensing.header.as_deref(),
+            Some("Copyright {year} Aye\nSPDX-License-Identifier: MIT")
+        );
+        assert_eq!(
+            config.licensing.forbidden_markers.len(),
+            DEFAULT_FORBIDDEN_LICENSE_MARKERS.len()
+        );
+        assert!(config.validate().is_ok());
+        assert_eq!(config.scope().unwrap().display(), "crates/foo");
+        println!("✅ Project config parsing test passed!");
+    }
+
+    #[test]
+    fn test_project_config_validation() {
+        let mut config = ProjectConfig::default();
+        config.pull_requests.labels = vec!["bug".to_string(), "BUG".to_string()];
+        config.pull_requests.milestone = Some("  ".to_string());
+        config.pull_requests.assignees = (0..11).map(|i| format!("user{}", i)).collect();
+        config.pull_requests.max_pr_size = Some(0);
+        config.path = Some("../outside".to_string());
+        config.scans.schedule = "every monday".to_string();
+        config.scm.endpoint = Some("GHE".to_string());
+        config.processing.max_concurrent_runs = Some(0);
+        config.processing.pr_cooldown_minutes = MAX_PR_COOLDOWN_MINUTES + 1;
+        config.formatting.tools = Some(vec![FormatTool::Black, FormatTool::Black]);
+        config.licensing.header = Some(" \n ".to_string());
+        config.licensing.forbidden_markers.push(String::new());
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 12);
+        println!("✅ Project config validation test passed!");
+    }
+
+    #[test]
+    fn test_public_status_settings() {
+        let mut status = PublicStatusSettings {
+            enabled: true,
+            slug: None,
+        };
+        let check = |status: &PublicStatusSettings| {
+            let mut errors = Vec::new();
+            status.validate_into(&mut errors);
+            errors.len()
+        };
+        assert_eq!(check(&status), 1);
+
+        for bad in [
+            "",
+            "-feedbacker",
+            "Feedbacker",
+            "feed_backer",
+            &"x".repeat(65),
+        ] {
+            status.slug = Some(bad.to_string());
+            assert_eq!(check(&status), 1, "{:?} should be rejected", bad);
+        }
+
+        status.slug = Some("smart-tree-2".to_string());
+        assert_eq!(check(&status), 0);
+        assert_eq!(status.public_slug(), Some("smart-tree-2"));
+        status.enabled = false;
+        assert_eq!(status.public_slug(), None);
+        println!("✅ Public status settings test passed!");
+    }
+
+    #[test]
+    fn test_scan_schedule_due() {
+        let value = serde_json::json!({
+            "scans": { "enabled": true, "schedule": "0 3 * * *", "output": "issue" }
+        });
+        let config = ProjectConfig::from_json(Some(&value)).unwrap();
+        assert_eq!(config.scans.output, ScanOutput::Issue);
+        assert_eq!(config.scans.checks.len(), 3);
+        assert!(config.validate().is_ok());
+
+        let at = |h: u32| Utc.with_ymd_and_hms(2024, 5, 6, h, 0, 0).unwrap();
+        let scans = &config.scans;
+        assert!(!scans.is_due(Some(at(3)), at(23)).unwrap());
+        assert!(!scans.is_due(Some(at(1)), at(2)).unwrap());
+        assert!(scans.is_due(Some(at(2)), at(4)).unwrap());
+        assert!(scans.is_due(None, at(0)).unwrap());
+        println!("✅ Scan schedule test passed!");
+    }
+
+    #[test]
+    fn test_organization_scm_settings() {
+        let settings = serde_json::json!({ "scm": { "endpoint": "ghe" }, "theme": "dark" });
+        assert_eq!(
+            ScmSettings::from_organization_settings(&settings).unwrap(),
+            ScmSettings {
+                endpoint: Some("ghe".to_string())
+            }
+        );
+        assert_eq!(
+            ScmSettings::from_organization_settings(&serde_json::json!({})).unwrap(),
+            ScmSettings::default()
+        );
+        let invalid = serde_json::json!({ "scm": { "endpoint": 7 } });
+        assert!(ScmSettings::from_organization_settings(&invalid).is_err());
+        println!("✅ Organization SCM settings test passed!");
+    }
+}
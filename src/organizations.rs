@@ -0,0 +1,574 @@
+// 🏢 Organizations - Share Projects, Not Passwords! 🏢
+// Who may do what with a project once teams share them:
+//   - 👑 system admins, service accounts not owned by an organization, and the
+//     project's owner: everything
+//   - 👥 members of the project's organization: what their role allows
+//     (viewer reads, member runs pipelines, admin configures), and when the
+//     project is limited to a team, only that team plus the organization's admins
+//   - 🐙 GitHub users who can push to the repository, when the project mirrors
+//     its collaborators (see crate::collaborators): member
+//   - 🔑 service accounts owned by an organization: only that organization's projects
+// The auth middleware applies this to every /api/projects/:id and
+// /api/status/:project_id request; the quota checks guard project and feedback counts,
+// and the repository policy keeps members' feedback on the organization's own repositories
+// Created with love by Aye & Hue - Many hands, one repo! ✨
+
+use anyhow::{Context, Result};
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::models::{
+    Feedback, OrgRole, Organization, OrganizationMember, Project, ProjectCollaborator, Team, User,
+    UserRole,
+};
+use crate::middleware::auth::AuthenticatedUser;
+
+/// 🎯 What a request does with a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectAccess {
+    /// 👀 Read it
+    View,
+    /// 🏃 Start pipeline runs
+    Run,
+    /// ⚙️ Change its configuration
+    Manage,
+}
+
+impl ProjectAccess {
+    /// 👑 Least organization role allowed to do this
+    pub fn required_role(self) -> OrgRole {
+        match self {
+            ProjectAccess::View => OrgRole::Viewer,
+            ProjectAccess::Run => OrgRole::Member,
+            ProjectAccess::Manage => OrgRole::Admin,
+        }
+    }
+}
+
+/// 🗺️ The project a request targets and what it does with it, for project routes
+pub fn project_access_for(method: &Method, path: &str) -> Option<(Uuid, ProjectAccess)> {
+    let rest = path
+        .strip_prefix("/api/projects/")
+        .or_else(|| path.strip_prefix("/api/status/"))?;
+    let (id, action) = rest.split_once('/').unwrap_or((rest, ""));
+    let project_id = Uuid::parse_str(id).ok()?;
+
+    let access = if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        ProjectAccess::View
+    } else if action.is_empty() || action == "config" {
+        ProjectAccess::Manage
+    } else {
+        ProjectAccess::Run
+    };
+    Some((project_id, access))
+}
+
+/// 🪪 What project roles depend on about an account: the signed-in user, or
+/// any stored user (e.g. someone feedback is being assigned to)
+#[derive(Debug, Clone, Copy)]
+pub struct Account {
+    pub id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub admin: bool,
+    pub service: bool,
+}
+
+impl From<&AuthenticatedUser> for Account {
+    fn from(user: &AuthenticatedUser) -> Self {
+        Account {
+            id: user.id,
+            organization_id: user.organization_id,
+            admin: user.is_admin(),
+            service: user.is_service(),
+        }
+    }
+}
+
+impl From<&User> for Account {
+    fn from(user: &User) -> Self {
+        Account {
+            id: user.id,
+            organization_id: user.organization_id,
+            admin: matches!(user.role, UserRole::Admin),
+            service: matches!(user.role, UserRole::Service),
+        }
+    }
+}
+
+/// 👑 A user's effective role on a project, given their organization membership
+/// (`on_team`: whether they are on the project's team, when it has one)
+pub fn project_role(
+    user: impl Into<Account>,
+    project: &Project,
+    membership: Option<OrgRole>,
+    on_team: bool,
+) -> Option<OrgRole> {
+    let user = user.into();
+    // 🔑 Organization service accounts stay inside their organization
+    if user.organization_id.is_some() && user.organization_id != project.organization_id {
+        return None;
+    }
+    let unscoped_service = user.service && user.organization_id.is_none();
+    if user.admin || unscoped_service || project.owner_id == user.id {
+        return Some(OrgRole::Owner);
+    }
+
+    let role = membership?;
+    if project.team_id.is_some() && !on_team && role < OrgRole::Admin {
+        return None;
+    }
+    Some(role)
+}
+
+/// 👑 Look up a user's effective role on a project
+pub async fn project_role_for(
+    pool: &PgPool,
+    user: impl Into<Account>,
+    project: &Project,
+) -> Result<Option<OrgRole>> {
+    let user = user.into();
+    let membership = match project.organization_id {
+        Some(organization_id) => {
+            OrganizationMember::role_of(pool, organization_id, user.id).await?
+        }
+        None => None,
+    };
+    let on_team = match project.team_id {
+        Some(team_id) if membership.is_some() => Team::has_member(pool, team_id, user.id).await?,
+        _ => false,
+    };
+    let role = project_role(user, project, membership, on_team);
+    // 👥 People who can push to the repository, when the project mirrors them
+    let collaborator_eligible = role < Some(OrgRole::Member)
+        && user.organization_id.is_none()
+        && !user.service
+        && project
+            .settings()
+            .is_ok_and(|settings| settings.collaborators.sync);
+    if collaborator_eligible
+        && ProjectCollaborator::includes_user(pool, project.id, user.id).await?
+    {
+        return Ok(Some(OrgRole::Member));
+    }
+    Ok(role)
+}
+
+/// 👀 Filters for Project::list_visible: (member, organization) for this user
+/// Organization service accounts see their organization's projects, system
+/// admins and unscoped service accounts everything, others what they can reach
+pub fn visible_projects_filter(user: &AuthenticatedUser) -> (Option<Uuid>, Option<Uuid>) {
+    match user.organization_id {
+        Some(organization_id) => (None, Some(organization_id)),
+        None if user.is_admin() || user.is_service() => (None, None),
+        None => (Some(user.id), None),
+    }
+}
+
+/// 👀 Whether a user may see a feedback item: its submitter, an admin, or
+/// anyone with a role on a project for its repository
+pub async fn can_view_feedback(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    feedback: &Feedback,
+) -> Result<bool> {
+    if feedback.user_id == Some(user.id) || user.is_admin() {
+        return Ok(true);
+    }
+    for project in Project::list_by_repository(pool, &feedback.repository).await? {
+        if project_role_for(pool, user, &project).await?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 👑 A user's best role on the projects for a repository (Owner for admins,
+/// None when they have no role on any of them)
+pub async fn repository_role(
+    pool: &PgPool,
+    user: impl Into<Account>,
+    repository: &str,
+) -> Result<Option<OrgRole>> {
+    let user = user.into();
+    if user.admin {
+        return Ok(Some(OrgRole::Owner));
+    }
+    let mut best = None;
+    for project in Project::list_by_repository(pool, repository).await? {
+        best = best.max(project_role_for(pool, user, &project).await?);
+    }
+    Ok(best)
+}
+
+/// ✅ Whether a user may do this with a project
+/// Unknown projects are allowed through, so the handler answers 404
+pub async fn authorize_project(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    project_id: Uuid,
+    access: ProjectAccess,
+) -> Result<bool> {
+    let Some(project) = Project::find_by_id(pool, project_id).await? else {
+        return Ok(true);
+    };
+    let role = project_role_for(pool, user, &project).await?;
+    Ok(role.is_some_and(|role| role >= access.required_role()))
+}
+
+/// 👑 A user's role in an organization (system admins act as owners)
+pub async fn organization_role(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    organization_id: Uuid,
+) -> Result<Option<OrgRole>> {
+    if user
+        .organization_id
+        .is_some_and(|owner| owner != organization_id)
+    {
+        return Ok(None);
+    }
+    if user.is_admin() {
+        return Ok(Some(OrgRole::Owner));
+    }
+    OrganizationMember::role_of(pool, organization_id, user.id).await
+}
+
+/// 📏 Why the organization can't take another project, if it can't
+pub async fn project_quota_exceeded(
+    pool: &PgPool,
+    organization: &Organization,
+) -> Result<Option<String>> {
+    let Some(limit) = organization.max_projects else {
+        return Ok(None);
+    };
+    let count = organization.project_count(pool).await?;
+    Ok((count >= limit as i64).then(|| {
+        format!(
+            "Organization {} has reached its limit of {} projects",
+            organization.slug, limit
+        )
+    }))
+}
+
+/// 📏 Why feedback for this repository can't be accepted this month, if it can't
+pub async fn feedback_quota_exceeded(pool: &PgPool, repository: &str) -> Result<Option<String>> {
+    let Some(organization) = Organization::find_by_repository(pool, repository).await? else {
+        return Ok(None);
+    };
+    let Some(limit) = organization.max_monthly_feedback else {
+        return Ok(None);
+    };
+    let count = organization.monthly_feedback_count(pool).await?;
+    Ok((count >= limit as i64).then(|| {
+        format!(
+            "Organization {} has used its {} feedback submissions for this month",
+            organization.slug, limit
+        )
+    }))
+}
+
+/// 🚧 Which repositories an organization's members may send feedback to
+/// (the `repositories` key of its settings; empty lists mean no restriction)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryPolicy {
+    /// 📋 Repositories allowed by name, as `owner/repo`
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// 🏠 GitHub accounts whose repositories are all allowed
+    #[serde(default)]
+    pub owners: Vec<String>,
+}
+
+impl RepositoryPolicy {
+    /// 📥 The `repositories` key of an organization's settings object (defaults when absent)
+    pub fn from_organization_settings(settings: &serde_json::Value) -> Result<Self> {
+        match settings.get("repositories") {
+            Some(policy) if !policy.is_null() => serde_json::from_value(policy.clone())
+                .context("Organization repository settings do not match the expected format"),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// 🔒 Whether the policy limits anything at all
+    pub fn is_restricted(&self) -> bool {
+        !self.allowlist.is_empty() || !self.owners.is_empty()
+    }
+
+    /// ✅ Whether a repository is on the allowlist or owned by an allowed account
+    pub fn allows(&self, repository: &str) -> bool {
+        if !self.is_restricted() {
+            return true;
+        }
+        let owner = repository.split_once('/').map_or("", |(owner, _)| owner);
+        self.allowlist
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(repository))
+            || self
+                .owners
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(owner))
+    }
+
+    /// ✅ Entries must look like `owner/repo` and `owner`
+    pub fn validate_into(&self, errors: &mut Vec<String>) {
+        for repository in &self.allowlist {
+            if crate::github::parse_repository(repository).is_err() {
+                errors.push(format!(
+                    "Allowed repository '{}' must look like owner/repo",
+                    repository
+                ));
+            }
+        }
+        for owner in &self.owners {
+            if owner.is_empty() || owner.contains('/') {
+                errors.push(format!(
+                    "Allowed repository owner '{}' must be a GitHub account name",
+                    owner
+                ));
+            }
+        }
+    }
+}
+
+/// 🚧 Why an organization won't take a project for this repository, if it won't
+pub fn repository_not_allowed(
+    organization: &Organization,
+    repository: &str,
+) -> Result<Option<String>> {
+    let policy = RepositoryPolicy::from_organization_settings(&organization.settings)?;
+    Ok((!policy.allows(repository)).then(|| {
+        format!(
+            "Organization {} does not allow repository {}",
+            organization.slug, repository
+        )
+    }))
+}
+
+/// 🚧 Why this user may not send feedback to a repository, if they may not:
+/// when any of their organizations restricts repositories, one of them has to allow it
+pub async fn feedback_repository_not_allowed(
+    pool: &PgPool,
+    user_id: Uuid,
+    repository: &str,
+) -> Result<Option<String>> {
+    let mut restricting = Vec::new();
+    for organization in Organization::list(pool, Some(user_id)).await? {
+        let policy = RepositoryPolicy::from_organization_settings(&organization.settings)?;
+        if !policy.is_restricted() {
+            continue;
+        }
+        if policy.allows(repository) {
+            return Ok(None);
+        }
+        restricting.push(organization.slug);
+    }
+    Ok((!restricting.is_empty()).then(|| {
+        format!(
+            "Repository {} is not allowed by organization {}",
+            repository,
+            restricting.join(", ")
+        )
+    }))
+}
+
+/// ✅ Check an organization slug: lowercase letters, digits and dashes, 2 to 100 characters
+pub fn validate_slug(slug: &str) -> Result<(), String> {
+    let valid = (2..=100).contains(&slug.len())
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid slug '{}': use 2-100 lowercase letters, digits and dashes",
+            slug
+        ))
+    }
+}
+
+/// 🔑 Whether a user may hold an organization's API keys
+pub fn is_organization_service_account(user: &User, organization_id: Uuid) -> bool {
+    matches!(user.role, UserRole::Service) && user.organization_id == Some(organization_id)
+}
+
+// 🧪 Tests - The right people, the right projects!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::{Claims, Permission};
+    use chrono::Utc;
+
+    fn user(role: UserRole, organization_id: Option<Uuid>) -> AuthenticatedUser {
+        AuthenticatedUser {
+            id: Uuid::new_v4(),
+            email: "someone@example.com".to_string(),
+            name: "Someone".to_string(),
+            role: role.clone(),
+            organization_id,
+            permissions: Permission::defaults_for(&role),
+            scopes: None,
+            rate_limit_tier: Default::default(),
+            locale: None,
+            request_signing_key: None,
+            claims: Claims {
+                sub: String::new(),
+                email: String::new(),
+                name: String::new(),
+                role,
+                exp: 0,
+                iat: 0,
+                iss: "feedbacker".to_string(),
+            },
+        }
+    }
+
+    fn project(organization_id: Option<Uuid>, team_id: Option<Uuid>) -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::new_v4(),
+            repository: "aye-is/feedbacker".to_string(),
+            description: None,
+            default_llm_provider: None,
+            system_message: None,
+            config: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            last_activity_at: None,
+            organization_id,
+            team_id,
+        }
+    }
+
+    #[test]
+    fn test_project_role() {
+        let org = Uuid::new_v4();
+        let shared = project(Some(org), None);
+        let team_only = project(Some(org), Some(Uuid::new_v4()));
+        let member = user(UserRole::User, None);
+
+        // 👤 Owners and admins always, strangers never
+        let mut owner = user(UserRole::User, None);
+        owner.id = shared.owner_id;
+        assert_eq!(
+            project_role(&owner, &shared, None, false),
+            Some(OrgRole::Owner)
+        );
+        assert_eq!(
+            project_role(&user(UserRole::Admin, None), &shared, None, false),
+            Some(OrgRole::Owner)
+        );
+        assert_eq!(
+            project_role(&user(UserRole::Service, None), &shared, None, false),
+            Some(OrgRole::Owner)
+        );
+        assert_eq!(project_role(&member, &shared, None, false), None);
+
+        // 👥 Members get their role; team projects need the team or an org admin
+        assert_eq!(
+            project_role(&member, &shared, Some(OrgRole::Member), false),
+            Some(OrgRole::Member)
+        );
+        assert_eq!(
+            project_role(&member, &team_only, Some(OrgRole::Member), false),
+            None
+        );
+        assert_eq!(
+            project_role(&member, &team_only, Some(OrgRole::Member), true),
+            Some(OrgRole::Member)
+        );
+        assert_eq!(
+            project_role(&member, &team_only, Some(OrgRole::Admin), false),
+            Some(OrgRole::Admin)
+        );
+
+        // 🔑 Organization service accounts stay inside their organization
+        let service = user(UserRole::Service, Some(org));
+        assert_eq!(
+            project_role(&service, &shared, Some(OrgRole::Member), false),
+            Some(OrgRole::Member)
+        );
+        let elsewhere = project(Some(Uuid::new_v4()), None);
+        assert_eq!(
+            project_role(&service, &elsewhere, Some(OrgRole::Member), false),
+            None
+        );
+        println!("✅ Project role test passed!");
+    }
+
+    #[test]
+    fn test_project_access_for() {
+        let id = Uuid::new_v4();
+        let path = |suffix: &str| format!("/api/projects/{}{}", id, suffix);
+
+        assert_eq!(
+            project_access_for(&Method::GET, &path("")),
+            Some((id, ProjectAccess::View))
+        );
+        assert_eq!(
+            project_access_for(&Method::PUT, &path("/config")),
+            Some((id, ProjectAccess::Manage))
+        );
+        assert_eq!(
+            project_access_for(&Method::POST, &path("/docs-pass")),
+            Some((id, ProjectAccess::Run))
+        );
+        assert_eq!(
+            project_access_for(&Method::GET, &format!("/api/status/{}", id)),
+            Some((id, ProjectAccess::View))
+        );
+        assert_eq!(
+            project_access_for(&Method::GET, "/api/projects/create"),
+            None
+        );
+        assert_eq!(project_access_for(&Method::GET, "/api/feedback"), None);
+        assert!(ProjectAccess::Manage.required_role() > ProjectAccess::Run.required_role());
+        println!("✅ Project access mapping test passed!");
+    }
+
+    #[test]
+    fn test_validate_slug() {
+        assert!(validate_slug("aye-is").is_ok());
+        assert!(validate_slug("team42").is_ok());
+        assert!(validate_slug("a").is_err());
+        assert!(validate_slug("-edge").is_err());
+        assert!(validate_slug("Aye Is").is_err());
+        assert_eq!("admin".parse::<OrgRole>().unwrap(), OrgRole::Admin);
+        assert!("boss".parse::<OrgRole>().is_err());
+        println!("✅ Organization slug test passed!");
+    }
+
+    #[test]
+    fn test_repository_policy() {
+        let settings = serde_json::json!({
+            "repositories": { "allowlist": ["Partner/Widget"], "owners": ["aye-is"] }
+        });
+        let policy = RepositoryPolicy::from_organization_settings(&settings).unwrap();
+        assert!(policy.is_restricted());
+        assert!(policy.allows("aye-is/feedbacker"));
+        assert!(policy.allows("AYE-IS/anything"));
+        assert!(policy.allows("partner/widget"));
+        assert!(!policy.allows("partner/other"));
+        assert!(!policy.allows("someone/aye-is"));
+
+        let open = RepositoryPolicy::from_organization_settings(&serde_json::json!({})).unwrap();
+        assert!(!open.is_restricted());
+        assert!(open.allows("anyone/anything"));
+
+        let mut errors = Vec::new();
+        RepositoryPolicy {
+            allowlist: vec!["not-a-repo".to_string()],
+            owners: vec!["a/b".to_string()],
+        }
+        .validate_into(&mut errors);
+        assert_eq!(errors.len(), 2);
+        let invalid = serde_json::json!({ "repositories": { "owners": "aye-is" } });
+        assert!(RepositoryPolicy::from_organization_settings(&invalid).is_err());
+        println!("✅ Repository policy test passed!");
+    }
+}
@@ -0,0 +1,206 @@
+// 💾 Pipeline Checkpoints - Don't Pay for the Same Work Twice! 💾
+// A run that dies after its plan and half its files were generated (a worker
+// crash, a GitHub outage while opening the PR) leaves a checkpoint behind: the
+// commit its context was read at, the change plan, and every file generated so
+// far. When the run is retried (its checkout comes from the clone cache) and
+// the repository is still at that commit, the plan and those files are reused
+// and only what's missing goes to the model. A checkpoint from an older commit
+// is thrown away (its files were written against code that has moved on), and
+// it's cleared once the pull request is open, or when the generated changes
+// themselves were rejected
+// Created with love by Aye & Hue - Pick up right where we left off! ✨
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::database::models::PipelineCheckpoint;
+use crate::github::CodeImprovement;
+
+/// ⏳ Days a checkpoint nobody retried is kept
+const MAX_AGE_DAYS: i64 = 14;
+
+/// 💾 What a run has done so far
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    feedback_id: Uuid,
+    base_commit: String,
+    plan: Option<serde_json::Value>,
+    generated: Vec<CodeImprovement>,
+}
+
+impl Checkpoint {
+    /// 🔁 Pick up the checkpoint a previous attempt left at `base_commit`
+    /// (an empty one when there is none)
+    pub async fn resume(pool: &PgPool, feedback_id: Uuid, base_commit: &str) -> Result<Self> {
+        let stored = PipelineCheckpoint::find(pool, feedback_id).await?;
+        let checkpoint = Self::from_stored(feedback_id, base_commit, stored);
+        if checkpoint.plan.is_some() || !checkpoint.generated.is_empty() {
+            info!(
+                "💾 Resuming feedback {} at {} with {} files already generated",
+                feedback_id,
+                base_commit,
+                checkpoint.generated.len()
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    /// 🧭 A checkpoint from what was stored, dropping it when it was made at
+    /// another commit or can't be read
+    fn from_stored(
+        feedback_id: Uuid,
+        base_commit: &str,
+        stored: Option<PipelineCheckpoint>,
+    ) -> Self {
+        let mut checkpoint = Self {
+            feedback_id,
+            base_commit: base_commit.to_string(),
+            plan: None,
+            generated: Vec::new(),
+        };
+        let Some(stored) = stored.filter(|stored| stored.base_commit == base_commit) else {
+            return checkpoint;
+        };
+        match serde_json::from_value(stored.generated) {
+            Ok(generated) => {
+                checkpoint.plan = stored.plan;
+                checkpoint.generated = generated;
+            }
+            Err(e) => warn!(
+                "⚠️ Ignoring the unreadable checkpoint of feedback {}: {}",
+                feedback_id, e
+            ),
+        }
+        checkpoint
+    }
+
+    /// 🗺️ The plan a previous attempt made (None if it didn't get that far)
+    pub fn plan<T: DeserializeOwned>(&self) -> Option<T> {
+        self.plan
+            .as_ref()
+            .and_then(|plan| serde_json::from_value(plan.clone()).ok())
+    }
+
+    /// 📄 A file a previous attempt already generated
+    pub fn generated(&self, file_path: &str) -> Option<&CodeImprovement> {
+        self.generated
+            .iter()
+            .find(|improvement| improvement.file_path == file_path)
+    }
+
+    /// 💾 Remember the plan
+    pub async fn save_plan<T: Serialize>(&mut self, pool: &PgPool, plan: &T) -> Result<()> {
+        self.plan = Some(serde_json::to_value(plan)?);
+        // 🗺️ Files generated for another plan don't belong to this one
+        self.generated.clear();
+        self.save(pool).await
+    }
+
+    /// 💾 Remember a generated file (replacing an earlier version of it)
+    pub async fn save_file(&mut self, pool: &PgPool, improvement: &CodeImprovement) -> Result<()> {
+        self.add(improvement.clone());
+        self.save(pool).await
+    }
+
+    fn add(&mut self, improvement: CodeImprovement) {
+        self.generated
+            .retain(|generated| generated.file_path != improvement.file_path);
+        self.generated.push(improvement);
+    }
+
+    async fn save(&self, pool: &PgPool) -> Result<()> {
+        PipelineCheckpoint::save(
+            pool,
+            self.feedback_id,
+            &self.base_commit,
+            self.plan.as_ref(),
+            &serde_json::to_value(&self.generated)?,
+        )
+        .await
+    }
+
+    /// 🗑️ Forget the checkpoint: the run finished, or its changes were rejected
+    pub async fn clear(pool: &PgPool, feedback_id: Uuid) -> Result<()> {
+        PipelineCheckpoint::delete(pool, feedback_id).await
+    }
+}
+
+/// 🧹 Delete checkpoints of runs nobody retried (called by the cleanup job)
+pub async fn purge_stale(pool: &PgPool) -> Result<()> {
+    let deleted =
+        PipelineCheckpoint::delete_stale(pool, Utc::now() - Duration::days(MAX_AGE_DAYS)).await?;
+    if deleted > 0 {
+        info!("🧹 Deleted {} stale pipeline checkpoints", deleted);
+    }
+    Ok(())
+}
+
+// 🧪 Tests - Resume only what still fits!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::ChangeType;
+
+    fn improvement(path: &str, content: &str) -> CodeImprovement {
+        CodeImprovement {
+            file_path: path.to_string(),
+            description: "Document things".to_string(),
+            change_type: ChangeType::Modify,
+            original_content: Some("fn a() {}".to_string()),
+            new_content: content.to_string(),
+            line_number: None,
+        }
+    }
+
+    fn stored(base_commit: &str, generated: serde_json::Value) -> PipelineCheckpoint {
+        PipelineCheckpoint {
+            feedback_id: Uuid::nil(),
+            base_commit: base_commit.to_string(),
+            plan: Some(serde_json::json!({ "summary": "Docs", "steps": [] })),
+            generated,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_resume_from_stored() {
+        let files =
+            serde_json::to_value(vec![improvement("src/lib.rs", "/// A\nfn a() {}")]).unwrap();
+        let checkpoint =
+            Checkpoint::from_stored(Uuid::nil(), "abc123", Some(stored("abc123", files.clone())));
+        assert!(checkpoint.generated("src/lib.rs").is_some());
+        assert!(checkpoint.generated("src/main.rs").is_none());
+        let plan: serde_json::Value = checkpoint.plan().unwrap();
+        assert_eq!(plan["summary"], "Docs");
+
+        // 🧭 The repository moved on: start over
+        let moved = Checkpoint::from_stored(Uuid::nil(), "def456", Some(stored("abc123", files)));
+        assert!(moved.plan.is_none() && moved.generated.is_empty());
+
+        let unreadable = Checkpoint::from_stored(
+            Uuid::nil(),
+            "abc123",
+            Some(stored("abc123", serde_json::json!({ "not": "a list" }))),
+        );
+        assert!(unreadable.plan.is_none() && unreadable.generated.is_empty());
+        println!("✅ Checkpoint resume test passed!");
+    }
+
+    #[test]
+    fn test_add_replaces_same_file() {
+        let mut checkpoint = Checkpoint::from_stored(Uuid::nil(), "abc123", None);
+        checkpoint.add(improvement("src/lib.rs", "first"));
+        checkpoint.add(improvement("src/util.rs", "util"));
+        checkpoint.add(improvement("src/lib.rs", "second"));
+        assert_eq!(checkpoint.generated.len(), 2);
+        assert_eq!(
+            checkpoint.generated("src/lib.rs").unwrap().new_content,
+            "second"
+        );
+        println!("✅ Checkpoint file replacement test passed!");
+    }
+}
@@ -0,0 +1,1228 @@
+// ⬆️ Dependency Update Mode - Dependabot-Lite on Feedbacker Rails! ⬆️
+// Reads Cargo.toml, package.json, and pyproject.toml manifests from a checkout,
+// asks crates.io / npm / PyPI for the latest releases, and opens grouped PRs:
+// - 🧺 Compatible bumps for one manifest share a single PR
+// - 💥 Breaking bumps get a PR each, so they can be reviewed (or ignored) on their own
+// Manifests are edited in place as text so formatting and comments survive,
+// then go through the repository's formatters like any generated file.
+// Lockfiles are left for CI or the reviewer to regenerate
+// Created with love by Aye & Hue - Fresh dependencies, zero drama! ✨
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+use super::{
+    formatting::{self, FormattingReport},
+    pr_description::build_pull_request,
+    risk,
+    sandbox::Sandbox,
+};
+use crate::config::Config;
+use crate::database::models::{Feedback, FeedbackEvent, Project};
+use crate::feedback_trace::{self, Stage};
+use crate::github::git::{github_clone_url, CloneCache, CloneOptions};
+use crate::github::{
+    parse_repository, ChangeType, CodeImprovement, FeedbackProcessingRequest, GitHubClient,
+    PullRequestResult,
+};
+use crate::jobs::repo_health::SourceFile;
+use crate::models::{FormatTool, PullRequestSettings};
+use crate::scm;
+use crate::utils::encoding::path_segment;
+
+/// 📦 Registry lookups per run (keeps huge monorepos from hammering registries)
+pub const MAX_DEPENDENCIES_CHECKED: usize = 200;
+
+/// 🐙 Update PRs opened per run
+pub const MAX_UPDATE_PULL_REQUESTS: usize = 10;
+
+/// 📜 Changelog excerpts are cut to this many characters
+const MAX_CHANGELOG_CHARS: usize = 1000;
+
+/// 🤖 Registries (crates.io in particular) require a descriptive User-Agent
+const REGISTRY_USER_AGENT: &str = "feedbacker (https://github.com/aye-is/feedbacker)";
+
+/// 📦 Package ecosystems we know how to update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// 🦀 Cargo.toml / crates.io
+    Cargo,
+    /// 📦 package.json / npm
+    Npm,
+    /// 🐍 pyproject.toml / PyPI
+    PyPi,
+}
+
+impl Ecosystem {
+    /// 🏷️ Short name used in titles and branch names
+    pub fn label(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPi => "pip",
+        }
+    }
+
+    /// 🔒 How the reviewer refreshes the lockfile after merging the manifest bump
+    fn lockfile_step(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "Run `cargo update` and commit the refreshed Cargo.lock",
+            Ecosystem::Npm => "Run `npm install` and commit the refreshed lockfile",
+            Ecosystem::PyPi => "Re-lock the Python environment (e.g. `poetry lock` or `uv lock`)",
+        }
+    }
+}
+
+/// 📄 Ecosystem for a manifest path (None = not a manifest we handle)
+pub fn manifest_ecosystem(path: &str) -> Option<Ecosystem> {
+    match path.rsplit('/').next()? {
+        "Cargo.toml" => Some(Ecosystem::Cargo),
+        "package.json" => Some(Ecosystem::Npm),
+        "pyproject.toml" => Some(Ecosystem::PyPi),
+        _ => None,
+    }
+}
+
+/// 📦 One dependency declaration in a manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestDependency {
+    /// 📦 Ecosystem (decides which registry to ask)
+    pub ecosystem: Ecosystem,
+    /// 📄 Manifest the declaration lives in
+    pub manifest_path: String,
+    /// 🏷️ Package name on the registry
+    pub name: String,
+    /// 🔑 Key the manifest uses (differs from `name` for renamed Cargo deps)
+    pub key: String,
+    /// 📏 Version requirement, e.g. `^1.2` or `>=2.28`
+    pub requirement: String,
+    /// ✍️ String literal exactly as written (the requirement, or a whole PEP 508 spec)
+    pub raw: String,
+}
+
+/// 🔍 Parse every dependency declaration out of a manifest
+pub fn parse_manifest(path: &str, content: &str) -> Result<Vec<ManifestDependency>> {
+    let ecosystem = manifest_ecosystem(path)
+        .with_context(|| format!("'{}' is not a supported manifest", path))?;
+
+    let mut dependencies = Vec::new();
+    let mut push = |name: &str, key: &str, requirement: &str, raw: &str| {
+        dependencies.push(ManifestDependency {
+            ecosystem,
+            manifest_path: path.to_string(),
+            name: name.to_string(),
+            key: key.to_string(),
+            requirement: requirement.trim().to_string(),
+            raw: raw.to_string(),
+        });
+    };
+
+    match ecosystem {
+        Ecosystem::Cargo => {
+            let manifest: toml::Value =
+                toml::from_str(content).with_context(|| format!("Failed to parse {}", path))?;
+            let mut tables: Vec<&toml::Value> = Vec::new();
+            for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                tables.extend(manifest.get(section));
+                tables.extend(manifest.get("workspace").and_then(|w| w.get(section)));
+            }
+            if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+                for target in targets.values() {
+                    for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                        tables.extend(target.get(section));
+                    }
+                }
+            }
+
+            for (key, spec) in tables.iter().filter_map(|t| t.as_table()).flatten() {
+                match spec {
+                    toml::Value::String(requirement) => push(key, key, requirement, requirement),
+                    toml::Value::Table(table) => {
+                        // 🔗 Path/git/workspace deps without a registry version are left alone
+                        let Some(requirement) = table.get("version").and_then(|v| v.as_str())
+                        else {
+                            continue;
+                        };
+                        let name = table.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+                        push(name, key, requirement, requirement);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ecosystem::Npm => {
+            let manifest: serde_json::Value = serde_json::from_str(content)
+                .with_context(|| format!("Failed to parse {}", path))?;
+            for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+                let Some(table) = manifest.get(section).and_then(|t| t.as_object()) else {
+                    continue;
+                };
+                for (name, requirement) in table {
+                    if let Some(requirement) = requirement.as_str() {
+                        push(name, name, requirement, requirement);
+                    }
+                }
+            }
+        }
+        Ecosystem::PyPi => {
+            let manifest: toml::Value =
+                toml::from_str(content).with_context(|| format!("Failed to parse {}", path))?;
+
+            // 🐍 PEP 621: arrays of PEP 508 strings
+            let project = manifest.get("project");
+            let mut specs: Vec<&str> = project
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|d| d.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|spec| spec.as_str())
+                .collect();
+            if let Some(extras) = project
+                .and_then(|p| p.get("optional-dependencies"))
+                .and_then(|o| o.as_table())
+            {
+                for group in extras.values().filter_map(|g| g.as_array()) {
+                    specs.extend(group.iter().filter_map(|spec| spec.as_str()));
+                }
+            }
+            for spec in specs {
+                if let Some((name, requirement)) = split_pep508(spec) {
+                    push(name, name, requirement, spec);
+                }
+            }
+
+            // 📜 Poetry: name = "^1.2" or name = { version = "^1.2" }
+            let poetry = manifest.get("tool").and_then(|t| t.get("poetry"));
+            let mut tables: Vec<&toml::Value> = Vec::new();
+            tables.extend(poetry.and_then(|p| p.get("dependencies")));
+            tables.extend(poetry.and_then(|p| p.get("dev-dependencies")));
+            if let Some(groups) = poetry
+                .and_then(|p| p.get("group"))
+                .and_then(|g| g.as_table())
+            {
+                tables.extend(groups.values().filter_map(|g| g.get("dependencies")));
+            }
+            for (name, spec) in tables.iter().filter_map(|t| t.as_table()).flatten() {
+                if name == "python" {
+                    continue;
+                }
+                let requirement = match spec {
+                    toml::Value::String(requirement) => Some(requirement.as_str()),
+                    toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+                    _ => None,
+                };
+                if let Some(requirement) = requirement {
+                    push(name, name, requirement, requirement);
+                }
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
+
+/// 🐍 Split a PEP 508 spec like `requests[socks]>=2.28; python_version > "3.8"`
+fn split_pep508(spec: &str) -> Option<(&str, &str)> {
+    let spec = spec.split(';').next()?.trim();
+    let at = spec.find(|c: char| "<>=!~( ".contains(c))?;
+    let name = spec[..at].split('[').next()?.trim();
+    let requirement = spec[at..]
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    (!name.is_empty() && !requirement.is_empty()).then_some((name, requirement))
+}
+
+/// 📏 Split a single-clause requirement into its operator and numeric version
+/// Only pinned, caret and tilde requirements (`1.2`, `=1.2`, `^1.2`, `~1.2`,
+/// `~=1.2`) qualify. Open-ended ones like `>=1.2` already allow the latest
+/// release, and ranges, upper bounds, wildcards, and pre-releases are not touched
+fn parse_requirement(requirement: &str) -> Option<(&str, Vec<u64>)> {
+    let requirement = requirement.trim();
+    let at = requirement.find(|c: char| c.is_ascii_digit())?;
+    let operator = &requirement[..at];
+    if !operator.chars().all(|c| "^~= ".contains(c)) {
+        return None;
+    }
+    Some((operator, parse_version(&requirement[at..])?))
+}
+
+/// 🔢 Parse `1`, `1.2`, or `1.2.3` (anything else, like pre-releases, is rejected)
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    (1..=3).contains(&parts.len()).then_some(parts)
+}
+
+/// ⬆️ A dependency with a newer release than its manifest allows
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyUpdate {
+    /// 📦 Declaration being bumped
+    pub dependency: ManifestDependency,
+    /// 🆕 Latest version on the registry
+    pub latest: String,
+    /// 📏 Requirement written into the manifest
+    pub new_requirement: String,
+    /// 💥 Whether the bump crosses a semver-breaking boundary
+    pub breaking: bool,
+    /// 📜 Release notes excerpt, when we could find them
+    pub changelog: Option<String>,
+}
+
+impl DependencyUpdate {
+    /// ✍️ New string literal for the manifest
+    fn new_raw(&self) -> String {
+        self.dependency
+            .raw
+            .replacen(&self.dependency.requirement, &self.new_requirement, 1)
+    }
+
+    /// 📏 Current requirement without its operator, for titles
+    fn current_version(&self) -> &str {
+        let requirement = &self.dependency.requirement;
+        let at = requirement
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or_default();
+        &requirement[at..]
+    }
+}
+
+/// 🔍 Decide whether `latest` warrants an update of `dependency`
+/// The new requirement keeps the operator and precision of the old one,
+/// so `^1.2` becomes `^1.5`, never `^1.5.3`
+pub fn plan_update(dependency: &ManifestDependency, latest: &str) -> Option<DependencyUpdate> {
+    let (operator, current) = parse_requirement(&dependency.requirement)?;
+    let latest_parts = parse_version(latest)?;
+    if latest_parts.len() < current.len() {
+        return None;
+    }
+
+    let bumped = &latest_parts[..current.len()];
+    if bumped <= current.as_slice() {
+        return None;
+    }
+
+    let new_requirement = format!(
+        "{}{}",
+        operator,
+        bumped
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(".")
+    );
+    Some(DependencyUpdate {
+        dependency: dependency.clone(),
+        latest: latest.to_string(),
+        new_requirement,
+        breaking: is_breaking(&current, &latest_parts),
+        changelog: None,
+    })
+}
+
+/// 💥 Semver-breaking: the first non-zero component changed (0.x minors break too)
+fn is_breaking(current: &[u64], latest: &[u64]) -> bool {
+    let component = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+    let significant = (0..3).find(|&i| component(current, i) != 0).unwrap_or(2);
+    (0..=significant).any(|i| component(current, i) != component(latest, i))
+}
+
+/// ✍️ Apply updates to a manifest's text, touching only the requirement literals
+pub fn rewrite_manifest(content: &str, updates: &[&DependencyUpdate]) -> Result<String> {
+    let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+
+    for update in updates {
+        let dependency = &update.dependency;
+        let old = format!("\"{}\"", dependency.raw);
+        let new = format!("\"{}\"", update.new_raw());
+        // 🐍 PEP 508 literals contain the package name, so they identify themselves
+        let self_describing = dependency.raw != dependency.requirement;
+
+        let mut header = "";
+        let mut rewritten = false;
+        for line in lines.iter_mut() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                header = trimmed;
+            }
+            let declares = self_describing
+                || declares_key(line, &dependency.key)
+                || header_declares_key(header, &dependency.key);
+            if declares && line.contains(&old) {
+                *line = line.replacen(&old, &new, 1);
+                rewritten = true;
+                break;
+            }
+        }
+
+        if !rewritten {
+            anyhow::bail!(
+                "Could not find {} {} in {}",
+                dependency.key,
+                dependency.raw,
+                dependency.manifest_path
+            );
+        }
+    }
+
+    Ok(lines.concat())
+}
+
+/// 🔑 `key = ...`, `"key" = ...`, or `"key": ...`
+fn declares_key(line: &str, key: &str) -> bool {
+    line.trim_start()
+        .trim_start_matches('"')
+        .strip_prefix(key)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| matches!(c, '"' | ' ' | '\t' | '=' | ':'))
+}
+
+/// 🔑 `[dependencies.key]` style tables
+fn header_declares_key(header: &str, key: &str) -> bool {
+    let header = header.trim_end_matches(']').trim_end_matches('"');
+    header
+        .strip_suffix(key)
+        .is_some_and(|rest| rest.ends_with('.') || rest.ends_with(".\""))
+}
+
+/// 🧺 Updates that go out together in one PR
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateGroup {
+    /// 📦 Ecosystem of every update in the group
+    pub ecosystem: Ecosystem,
+    /// 📄 Manifest the group edits
+    pub manifest_path: String,
+    /// 💥 Whether this is a (single) breaking bump
+    pub breaking: bool,
+    /// ⬆️ The updates themselves
+    pub updates: Vec<DependencyUpdate>,
+}
+
+/// 🧺 Group per manifest: compatible bumps together, each breaking bump alone
+pub fn group_updates(updates: Vec<DependencyUpdate>) -> Vec<UpdateGroup> {
+    let mut groups: Vec<UpdateGroup> = Vec::new();
+
+    for update in updates {
+        let manifest_path = update.dependency.manifest_path.clone();
+        let ecosystem = update.dependency.ecosystem;
+        if update.breaking {
+            groups.push(UpdateGroup {
+                ecosystem,
+                manifest_path,
+                breaking: true,
+                updates: vec![update],
+            });
+            continue;
+        }
+
+        match groups
+            .iter_mut()
+            .find(|group| !group.breaking && group.manifest_path == manifest_path)
+        {
+            Some(group) => group.updates.push(update),
+            None => groups.push(UpdateGroup {
+                ecosystem,
+                manifest_path,
+                breaking: false,
+                updates: vec![update],
+            }),
+        }
+    }
+
+    // 🧺 Compatible groups first: they are the easy merges
+    groups.sort_by_key(|group| group.breaking);
+    groups
+}
+
+impl UpdateGroup {
+    /// 🏷️ PR title
+    pub fn title(&self) -> String {
+        match self.updates.as_slice() {
+            [update] => format!(
+                "⬆️ Bump {} from {} to {} in {}",
+                update.dependency.name,
+                update.current_version(),
+                update.latest,
+                self.manifest_path
+            ),
+            updates => format!(
+                "⬆️ Bump {} {} dependencies in {}",
+                updates.len(),
+                self.ecosystem.label(),
+                self.manifest_path
+            ),
+        }
+    }
+
+    /// 🌿 Branch name (below the configured prefix)
+    pub fn branch_name(&self, prefix: &str) -> String {
+        let directory = self
+            .manifest_path
+            .rsplit_once('/')
+            .map(|(dir, _)| dir.replace('/', "-"))
+            .unwrap_or_else(|| "root".to_string());
+        let suffix = match (self.breaking, self.updates.first()) {
+            (true, Some(update)) => format!(
+                "{}-{}",
+                sanitize_branch(&update.dependency.name),
+                update.latest
+            ),
+            _ => "compatible".to_string(),
+        };
+        format!(
+            "{}deps/{}-{}-{}",
+            prefix,
+            self.ecosystem.label(),
+            sanitize_branch(&directory),
+            suffix
+        )
+    }
+
+    /// 📝 Markdown table of bumps plus changelog excerpts
+    pub fn summary(&self) -> String {
+        let mut summary = String::from("| Package | From | To |\n|---|---|---|\n");
+        for update in &self.updates {
+            summary.push_str(&format!(
+                "| `{}` | `{}` | `{}` |\n",
+                update.dependency.name, update.dependency.requirement, update.new_requirement
+            ));
+        }
+        if self.breaking {
+            summary.push_str(
+                "\n💥 This is a semver-breaking update; check the release notes before merging.\n",
+            );
+        }
+
+        for update in &self.updates {
+            if let Some(changelog) = &update.changelog {
+                summary.push_str(&format!(
+                    "\n<details>\n<summary>📜 {} {} release notes</summary>\n\n{}\n\n</details>\n",
+                    update.dependency.name, update.latest, changelog
+                ));
+            }
+        }
+        summary
+    }
+
+    /// 🔧 The manifest edit for this group
+    pub fn improvement(&self, manifest_content: &str) -> Result<CodeImprovement> {
+        let updates: Vec<&DependencyUpdate> = self.updates.iter().collect();
+        let names: Vec<&str> = self
+            .updates
+            .iter()
+            .map(|update| update.dependency.name.as_str())
+            .collect();
+
+        Ok(CodeImprovement {
+            file_path: self.manifest_path.clone(),
+            description: format!("Bump {}", names.join(", ")),
+            change_type: ChangeType::Modify,
+            original_content: Some(manifest_content.to_string()),
+            new_content: rewrite_manifest(manifest_content, &updates)?,
+            line_number: None,
+        })
+    }
+}
+
+/// 🌿 Keep branch names to characters git and GitHub are happy with
+fn sanitize_branch(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// 🆕 What a registry told us about a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseInfo {
+    /// 🆕 Latest stable version
+    pub version: String,
+    /// 🔗 Source repository URL, if the registry knows it
+    pub repository_url: Option<String>,
+}
+
+/// 📦 Minimal crates.io response
+#[derive(Debug, Deserialize)]
+struct CratesResponse {
+    #[serde(rename = "crate")]
+    krate: CratesCrate,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesCrate {
+    max_stable_version: Option<String>,
+    repository: Option<String>,
+}
+
+/// 📦 Minimal npm `latest` response
+#[derive(Debug, Deserialize)]
+struct NpmLatest {
+    version: String,
+    repository: Option<serde_json::Value>,
+}
+
+/// 🐍 Minimal PyPI response
+#[derive(Debug, Deserialize)]
+struct PypiResponse {
+    info: PypiInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct PypiInfo {
+    version: String,
+    home_page: Option<String>,
+    project_urls: Option<HashMap<String, String>>,
+}
+
+/// 🔗 Registry URL of a package's latest release
+/// The name is one path segment, so npm's `@scope/name` goes out as `@scope%2Fname`
+fn release_url(ecosystem: Ecosystem, name: &str) -> String {
+    let name = path_segment(name);
+    match ecosystem {
+        Ecosystem::Cargo => format!("https://crates.io/api/v1/crates/{}", name),
+        Ecosystem::Npm => format!("https://registry.npmjs.org/{}/latest", name),
+        Ecosystem::PyPi => format!("https://pypi.org/pypi/{}/json", name),
+    }
+}
+
+/// 📦 Client for the public package registries
+#[derive(Debug, Clone)]
+pub struct RegistryClient {
+    http: reqwest::Client,
+}
+
+impl RegistryClient {
+    /// ➕ Create a registry client
+    pub fn new() -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .user_agent(REGISTRY_USER_AGENT)
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .context("Failed to create registry HTTP client")?;
+        Ok(Self { http })
+    }
+
+    /// 🆕 Latest stable release of a package (None = unknown package)
+    pub async fn latest_release(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+    ) -> Result<Option<ReleaseInfo>> {
+        let url = release_url(ecosystem, name);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach registry for {}", name))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response
+            .error_for_status()
+            .with_context(|| format!("Registry lookup for {} failed", name))?;
+
+        let release = match ecosystem {
+            Ecosystem::Cargo => {
+                let body: CratesResponse = response.json().await?;
+                body.krate.max_stable_version.map(|version| ReleaseInfo {
+                    version,
+                    repository_url: body.krate.repository,
+                })
+            }
+            Ecosystem::Npm => {
+                let body: NpmLatest = response.json().await?;
+                let repository_url = match body.repository {
+                    Some(serde_json::Value::String(url)) => Some(url),
+                    Some(value) => value
+                        .get("url")
+                        .and_then(|u| u.as_str())
+                        .map(str::to_string),
+                    None => None,
+                };
+                Some(ReleaseInfo {
+                    version: body.version,
+                    repository_url,
+                })
+            }
+            Ecosystem::PyPi => {
+                let body: PypiResponse = response.json().await?;
+                let repository_url = body
+                    .info
+                    .project_urls
+                    .unwrap_or_default()
+                    .into_values()
+                    .chain(body.info.home_page)
+                    .find(|url| github_repository(url).is_some());
+                Some(ReleaseInfo {
+                    version: body.info.version,
+                    repository_url,
+                })
+            }
+        };
+        Ok(release)
+    }
+}
+
+/// 🐙 `(owner, repo)` from the many ways registries spell a GitHub URL
+pub fn github_repository(url: &str) -> Option<(String, String)> {
+    let rest = url.strip_prefix("github:").or_else(|| {
+        url.find("github.com")
+            .map(|at| &url[at + "github.com".len()..])
+            .and_then(|rest| rest.strip_prefix('/').or_else(|| rest.strip_prefix(':')))
+    })?;
+
+    let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?.trim_end_matches(".git");
+    let repo = repo.split(['#', '?']).next()?;
+    (!owner.is_empty() && !repo.is_empty()).then(|| (owner.to_string(), repo.to_string()))
+}
+
+/// ✂️ Trim release notes to a reviewable excerpt, preferring a line boundary
+fn changelog_excerpt(notes: &str) -> String {
+    let notes = notes.trim();
+    if notes.chars().count() <= MAX_CHANGELOG_CHARS {
+        return notes.to_string();
+    }
+
+    let cut: String = notes.chars().take(MAX_CHANGELOG_CHARS).collect();
+    let cut = cut.rsplit_once('\n').map_or(cut.as_str(), |(head, _)| head);
+    format!("{}\n\n…", cut.trim_end())
+}
+
+/// 🔍 Check every dependency against its registry; lookup failures are logged and skipped
+pub async fn find_updates(
+    registry: &RegistryClient,
+    github: &GitHubClient,
+    dependencies: &[ManifestDependency],
+) -> Vec<DependencyUpdate> {
+    let mut releases: HashMap<(Ecosystem, String), Option<ReleaseInfo>> = HashMap::new();
+    let mut notes: HashMap<(Ecosystem, String), Option<String>> = HashMap::new();
+    let mut updates = Vec::new();
+
+    for dependency in dependencies {
+        let package = (dependency.ecosystem, dependency.name.clone());
+        if !releases.contains_key(&package) {
+            if releases.len() >= MAX_DEPENDENCIES_CHECKED {
+                warn!(
+                    "⚠️ Stopping after {} registry lookups",
+                    MAX_DEPENDENCIES_CHECKED
+                );
+                break;
+            }
+            let release = registry
+                .latest_release(dependency.ecosystem, &dependency.name)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("⚠️ Skipping {}: {:#}", dependency.name, e);
+                    None
+                });
+            releases.insert(package.clone(), release);
+        }
+
+        let Some(release) = releases[&package].clone() else {
+            continue;
+        };
+        let Some(mut update) = plan_update(dependency, &release.version) else {
+            continue;
+        };
+
+        if !notes.contains_key(&package) {
+            let excerpt = match release
+                .repository_url
+                .as_deref()
+                .and_then(github_repository)
+            {
+                Some((owner, repo)) => github
+                    .find_release_notes(&owner, &repo, &release.version)
+                    .await
+                    .unwrap_or_else(|e| {
+                        debug!("📜 No release notes for {}: {:#}", dependency.name, e);
+                        None
+                    })
+                    .map(|body| changelog_excerpt(&body)),
+                None => None,
+            };
+            notes.insert(package.clone(), excerpt);
+        }
+        update.changelog = notes[&package].clone();
+        updates.push(update);
+    }
+
+    updates
+}
+
+/// 🐙 Where and how update PRs are opened
+pub struct UpdateRun<'a> {
+    /// 🗄️ Where formatter runs are recorded
+    pub pool: &'a PgPool,
+    /// 🐙 GitHub client
+    pub github: &'a GitHubClient,
+    /// 👤 Repository owner
+    pub owner: &'a str,
+    /// 📦 Repository name
+    pub repo: &'a str,
+    /// 🎯 Branch the PRs target
+    pub base_branch: &'a str,
+    /// 📝 Feedback item tracking this run
+    pub feedback: &'a Feedback,
+    /// 🏷️ Project PR settings
+    pub settings: &'a PullRequestSettings,
+    /// 🌿 Prefix for branch names
+    pub branch_prefix: &'a str,
+    /// 🔗 Public URL for tracking links
+    pub public_url: &'a str,
+    /// 🧪 Copy of the checkout the formatters run in (None: no formatter pass)
+    pub sandbox: Option<&'a Sandbox>,
+    /// 🎨 Formatters the repository is set up for
+    pub format_tools: &'a [FormatTool],
+    /// 📄 Manifests and formatter configs of the checkout
+    pub setup_files: &'a [SourceFile],
+}
+
+/// 🐙 Open one PR per group using the regular branch/commit/PR machinery
+/// `manifests` maps manifest paths to their current content
+pub async fn open_update_pull_requests(
+    run: &UpdateRun<'_>,
+    groups: &[UpdateGroup],
+    manifests: &HashMap<String, String>,
+) -> Result<Vec<PullRequestResult>> {
+    let mut results = Vec::new();
+
+    for group in groups.iter().take(MAX_UPDATE_PULL_REQUESTS) {
+        let content = manifests
+            .get(&group.manifest_path)
+            .with_context(|| format!("Manifest {} was not loaded", group.manifest_path))?;
+        let improvement = match group.improvement(content) {
+            Ok(improvement) => improvement,
+            Err(e) => {
+                warn!("⚠️ Skipping '{}': {:#}", group.title(), e);
+                continue;
+            }
+        };
+
+        // 🎨 The bumped manifest goes out the way the repository's formatters leave it
+        let mut improvements = vec![improvement];
+        let formatting = match run.sandbox {
+            Some(sandbox) => {
+                sandbox.apply(&improvements)?;
+                formatting::format_generated(
+                    run.pool,
+                    run.feedback.id,
+                    sandbox,
+                    run.format_tools,
+                    run.setup_files,
+                    &mut improvements,
+                    |_| Ok(()),
+                )
+                .await?
+            }
+            None => FormattingReport::default(),
+        };
+
+        let request = FeedbackProcessingRequest {
+            feedback_id: run.feedback.id,
+            repository: format!("{}/{}", run.owner, run.repo),
+            feedback_content: run.feedback.content.clone(),
+            improvements,
+            commit_message: formatting.amend_commit_message(format!(
+                "{}\n\n{}",
+                group.title(),
+                group.summary()
+            )),
+            branch_name: group.branch_name(run.branch_prefix),
+            pull_request_settings: run.settings.clone(),
+            test_plan: std::iter::once(group.ecosystem.lockfile_step().to_string())
+                .chain(formatting.test_plan_line())
+                .chain(std::iter::once(
+                    "Run the test suite against the updated dependencies".to_string(),
+                ))
+                .collect(),
+        };
+        // ⚖️ No LLM in this mode, so the rules score it on their own
+        let risk = risk::assess(&request.improvements);
+        let pull_request = build_pull_request(&request, &risk, run.base_branch, run.public_url)?;
+        let settings = risk.apply_to(run.settings);
+
+        run.github
+            .create_feedback_branch(
+                run.owner,
+                run.repo,
+                &request.branch_name,
+                Some(run.base_branch),
+            )
+            .await?;
+        run.github.apply_improvements(&request).await?;
+        let result = feedback_trace::traced(
+            Stage::PullRequest.span(None),
+            run.github
+                .create_pull_request(run.owner, run.repo, &pull_request, &settings),
+        )
+        .await?;
+
+        info!(
+            "⬆️ Opened dependency PR #{}: {}",
+            result.number,
+            group.title()
+        );
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// 🏭 Dependency update mode for one project, tracked by `feedback`
+pub async fn run_dependency_update_mode(
+    pool: &PgPool,
+    config: &Config,
+    project: &Project,
+    feedback: &Feedback,
+) -> Result<Vec<PullRequestResult>> {
+    let settings = project.settings()?;
+    let scope = settings.scope()?;
+    let (owner, repo) = parse_repository(&project.repository)?;
+
+    // 📥 Pull the manifests out of a (cached, sparse) checkout
+    let cache = CloneCache::new(
+        &config.github.clone_cache_dir,
+        config.github.clone_cache_size,
+    );
+    let github_config = scm::github_config(pool, &config.github, project).await?;
+    let options = CloneOptions {
+        scope,
+        token: Some(github_config.token.clone()),
+        ..Default::default()
+    };
+    let repository = project.repository.clone();
+    let clone_url = github_clone_url(&github_config.api_base_url, &repository);
+    let format_settings = settings.formatting.clone();
+    let sandbox_config = config.sandbox.clone();
+    let git = Stage::Git.span(None);
+    let (manifests, setup_files, format_tools, sandbox) =
+        tokio::task::spawn_blocking(move || -> Result<_> {
+            let workspace = feedback_trace::traced_blocking(git, || {
+                cache.checkout(&repository, &clone_url, &options)
+            })?;
+            let paths = workspace.list_files()?;
+            let manifests: HashMap<String, String> = paths
+                .iter()
+                .filter(|path| manifest_ecosystem(path).is_some())
+                .filter_map(|path| Some((path.clone(), workspace.read_file(path)?)))
+                .collect();
+            let setup_files: Vec<SourceFile> = paths
+                .into_iter()
+                .filter(|path| formatting::is_setup_file(path))
+                .filter_map(|path| {
+                    let content = workspace.read_file(&path)?;
+                    Some(SourceFile { path, content })
+                })
+                .collect();
+            // 🎨 A sandbox is only needed when there is a formatter to run
+            let format_tools = formatting::tools_for(&format_settings, &setup_files);
+            let sandbox = if format_tools.is_empty() {
+                None
+            } else {
+                Some(Sandbox::create(&sandbox_config, workspace.root())?)
+            };
+            Ok((manifests, setup_files, format_tools, sandbox))
+        })
+        .await
+        .context("Checkout task panicked")??;
+
+    let mut dependencies = Vec::new();
+    for (path, content) in &manifests {
+        match parse_manifest(path, content) {
+            Ok(found) => dependencies.extend(found),
+            Err(e) => warn!("⚠️ Skipping manifest {}: {:#}", path, e),
+        }
+    }
+    dependencies.sort_by(|a, b| a.manifest_path.cmp(&b.manifest_path));
+
+    let github = GitHubClient::new(github_config)?;
+    let registry = RegistryClient::new()?;
+    // 📜 Release notes come from the default GitHub, wherever the project lives
+    let default_github = GitHubClient::new(config.github.clone())?;
+    let updates = find_updates(&registry, &default_github, &dependencies).await;
+    let groups = group_updates(updates);
+
+    FeedbackEvent::record(
+        pool,
+        feedback.id,
+        FeedbackEvent::DEPENDENCY_UPDATES_FOUND,
+        serde_json::json!({
+            "manifests": manifests.len(),
+            "dependencies": dependencies.len(),
+            "groups": groups.iter().map(UpdateGroup::title).collect::<Vec<_>>(),
+        }),
+    )
+    .await?;
+
+    if groups.is_empty() {
+        info!(
+            "💚 All dependencies of {} are up to date",
+            project.repository
+        );
+        return Ok(Vec::new());
+    }
+
+    let base_branch = github
+        .get_repository_info(&owner, &repo)
+        .await?
+        .default_branch;
+    let run = UpdateRun {
+        pool,
+        github: &github,
+        owner: &owner,
+        repo: &repo,
+        base_branch: &base_branch,
+        feedback,
+        settings: &settings.pull_requests,
+        branch_prefix: &config.github.default_branch_prefix,
+        public_url: &config.server.public_url,
+        sandbox: sandbox.as_ref(),
+        format_tools: &format_tools,
+        setup_files: &setup_files,
+    };
+    let results = open_update_pull_requests(&run, &groups, &manifests).await?;
+
+    for result in &results {
+        FeedbackEvent::record(
+            pool,
+            feedback.id,
+            FeedbackEvent::PULL_REQUEST_OPENED,
+            serde_json::json!({ "number": result.number, "url": result.url, "title": result.title }),
+        )
+        .await?;
+    }
+
+    Ok(results)
+}
+
+// 🧪 Tests - Bumps must be precise, grouped, and formatting-preserving!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO: &str = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+serde_json = "1.0" # keep in sync
+tokio = "1"
+rand = "0.7.3"
+local = { path = "../local" }
+renamed = { package = "octocrab", version = "0.38" }
+
+[dependencies.axum]
+version = "0.6"
+"#;
+
+    fn find<'a>(dependencies: &'a [ManifestDependency], name: &str) -> &'a ManifestDependency {
+        dependencies.iter().find(|d| d.name == name).unwrap()
+    }
+
+    fn update(dependencies: &[ManifestDependency], name: &str, latest: &str) -> DependencyUpdate {
+        plan_update(find(dependencies, name), latest).unwrap()
+    }
+
+    #[test]
+    fn test_parse_manifests() {
+        let cargo = parse_manifest("Cargo.toml", CARGO).unwrap();
+        let mut names: Vec<&str> = cargo.iter().map(|d| d.name.as_str()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["axum", "octocrab", "rand", "serde", "serde_json", "tokio"]
+        );
+        assert_eq!(find(&cargo, "octocrab").key, "renamed");
+        assert_eq!(find(&cargo, "axum").requirement, "0.6");
+
+        let npm = parse_manifest(
+            "web/package.json",
+            r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"vite": "~5.0.0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(npm.len(), 2);
+        assert_eq!(npm[0].manifest_path, "web/package.json");
+
+        let pyproject = parse_manifest(
+            "pyproject.toml",
+            r#"[project]
+dependencies = ["requests[socks]>=2.28 ; python_version > '3.8'", "rich"]
+[tool.poetry.dependencies]
+python = "^3.10"
+httpx = { version = "^0.24" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(pyproject.len(), 2);
+        assert_eq!(pyproject[0].name, "requests");
+        assert_eq!(pyproject[0].requirement, ">=2.28");
+        assert_eq!(pyproject[1].name, "httpx");
+        println!("✅ Manifest parsing test passed!");
+    }
+
+    #[test]
+    fn test_plan_update() {
+        let cargo = parse_manifest("Cargo.toml", CARGO).unwrap();
+        assert!(plan_update(find(&cargo, "serde_json"), "1.0.120").is_none());
+        assert!(plan_update(find(&cargo, "tokio"), "1.41.0").is_none());
+
+        let serde = update(&cargo, "serde", "2.1.0");
+        assert_eq!(serde.new_requirement, "2.1");
+        assert!(serde.breaking);
+
+        let rand = update(&cargo, "rand", "0.7.9");
+        assert_eq!(rand.new_requirement, "0.7.9");
+        assert!(!rand.breaking);
+        assert!(update(&cargo, "rand", "0.8.5").breaking);
+
+        let caret = ManifestDependency {
+            requirement: "^18.2.0".to_string(),
+            raw: "^18.2.0".to_string(),
+            ..find(&cargo, "serde").clone()
+        };
+        assert_eq!(
+            plan_update(&caret, "18.3.1").unwrap().new_requirement,
+            "^18.3.1"
+        );
+        assert!(plan_update(&caret, "19.0.0-rc.1").is_none());
+
+        let range = ManifestDependency {
+            requirement: ">=1.0, <2".to_string(),
+            ..caret
+        };
+        assert!(plan_update(&range, "3.0.0").is_none());
+
+        // 🔓 Open-ended requirements already allow the latest release
+        for requirement in [">=1.0", ">1.0", ">= 1.0"] {
+            let open = ManifestDependency {
+                requirement: requirement.to_string(),
+                ..range.clone()
+            };
+            assert!(plan_update(&open, "3.0.0").is_none(), "{}", requirement);
+        }
+        // 🔒 Pinned and tilde requirements are still bumped
+        for (requirement, bumped) in [("=1.0", "=3.0"), ("==1.0.2", "==3.0.0"), ("~1.2", "~3.0")] {
+            let pinned = ManifestDependency {
+                requirement: requirement.to_string(),
+                ..range.clone()
+            };
+            assert_eq!(
+                plan_update(&pinned, "3.0.0").unwrap().new_requirement,
+                bumped
+            );
+        }
+        println!("✅ Update planning test passed!");
+    }
+
+    #[test]
+    fn test_release_url() {
+        assert_eq!(
+            release_url(Ecosystem::Npm, "@types/node"),
+            "https://registry.npmjs.org/@types%2Fnode/latest"
+        );
+        assert_eq!(
+            release_url(Ecosystem::Npm, "react"),
+            "https://registry.npmjs.org/react/latest"
+        );
+        assert_eq!(
+            release_url(Ecosystem::Cargo, "serde_json"),
+            "https://crates.io/api/v1/crates/serde_json"
+        );
+        assert_eq!(
+            release_url(Ecosystem::PyPi, "zope.interface"),
+            "https://pypi.org/pypi/zope.interface/json"
+        );
+        println!("✅ Registry URL test passed!");
+    }
+
+    #[test]
+    fn test_rewrite_and_group() {
+        let cargo = parse_manifest("Cargo.toml", CARGO).unwrap();
+        let updates = vec![
+            update(&cargo, "serde", "1.2.0"),
+            update(&cargo, "axum", "0.7.5"),
+            update(&cargo, "octocrab", "0.39.0"),
+            update(&cargo, "rand", "0.7.9"),
+        ];
+
+        let groups = group_updates(updates);
+        assert_eq!(groups.len(), 3);
+        assert!(!groups[0].breaking);
+        assert_eq!(groups[0].updates.len(), 2);
+        assert_eq!(
+            groups[0].title(),
+            "⬆️ Bump 2 cargo dependencies in Cargo.toml"
+        );
+        assert_eq!(
+            groups[0].branch_name("feedbacker/"),
+            "feedbacker/deps/cargo-root-compatible"
+        );
+        assert_eq!(
+            groups[1].title(),
+            "⬆️ Bump axum from 0.6 to 0.7.5 in Cargo.toml"
+        );
+        assert_eq!(
+            groups[1].branch_name("feedbacker/"),
+            "feedbacker/deps/cargo-root-axum-0.7.5"
+        );
+
+        let improvement = groups[0].improvement(CARGO).unwrap();
+        let content = improvement.new_content;
+        assert!(content.contains(r#"serde = { version = "1.2", features = ["derive"] }"#));
+        assert!(content.contains(r#"serde_json = "1.0" # keep in sync"#));
+        assert!(content.contains(r#"rand = "0.7.9""#));
+
+        let axum = groups[1].improvement(CARGO).unwrap().new_content;
+        assert!(axum.contains("[dependencies.axum]\nversion = \"0.7\"\n"));
+        let renamed = groups[2].improvement(CARGO).unwrap().new_content;
+        assert!(renamed.contains(r#"renamed = { package = "octocrab", version = "0.39" }"#));
+        assert!(groups[2]
+            .summary()
+            .contains("| `octocrab` | `0.38` | `0.39` |"));
+        println!("✅ Manifest rewrite and grouping test passed!");
+    }
+
+    #[test]
+    fn test_github_repository_urls() {
+        let expected = Some(("serde-rs".to_string(), "serde".to_string()));
+        assert_eq!(
+            github_repository("https://github.com/serde-rs/serde"),
+            expected
+        );
+        assert_eq!(
+            github_repository("git+https://github.com/serde-rs/serde.git"),
+            expected
+        );
+        assert_eq!(
+            github_repository("git@github.com:serde-rs/serde.git"),
+            expected
+        );
+        assert_eq!(github_repository("github:serde-rs/serde"), expected);
+        assert_eq!(
+            github_repository("https://github.com/serde-rs/serde/tree/master/serde"),
+            expected
+        );
+        assert_eq!(github_repository("https://gitlab.com/serde-rs/serde"), None);
+        assert!(changelog_excerpt(&"line\n".repeat(500)).ends_with('…'));
+        println!("✅ GitHub repository URL test passed!");
+    }
+}
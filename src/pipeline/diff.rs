@@ -0,0 +1,263 @@
+// 🔍 Proposed Diff Stage - See It Before It Ships! 🔍
+// Generated changes are turned into one unified diff and stored with the
+// feedback, so maintainers can review them in the web UI. The parser splits a
+// stored diff back into files and numbered lines for the preview page
+// Created with love by Aye & Hue - No surprises in review! ✨
+
+use anyhow::Result;
+use similar::TextDiff;
+use sqlx::PgPool;
+
+use crate::{
+    database::models::Feedback,
+    github::{ChangeType, CodeImprovement},
+};
+
+/// 📏 Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// 🏷️ What a diff line does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// 📍 `@@ -a,b +c,d @@` hunk header
+    Hunk,
+    /// ⚪ Unchanged context
+    Context,
+    /// ➕ Added line
+    Added,
+    /// ➖ Removed line
+    Removed,
+}
+
+/// 📄 One line of a file diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// 🔢 Line number in the original file (None for added lines and headers)
+    pub old_line: Option<usize>,
+    /// 🔢 Line number in the new file (None for removed lines and headers)
+    pub new_line: Option<usize>,
+    /// 📝 Line text without the +/-/space prefix
+    pub text: String,
+}
+
+/// 📁 The diff of one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// 📁 Path of the file (the new path, or the old one for deletions)
+    pub path: String,
+    pub lines: Vec<DiffLine>,
+}
+
+impl FileDiff {
+    /// ➕ Number of added lines
+    pub fn additions(&self) -> usize {
+        self.count(DiffLineKind::Added)
+    }
+
+    /// ➖ Number of removed lines
+    pub fn deletions(&self) -> usize {
+        self.count(DiffLineKind::Removed)
+    }
+
+    fn count(&self, kind: DiffLineKind) -> usize {
+        self.lines.iter().filter(|line| line.kind == kind).count()
+    }
+}
+
+/// 📝 Unified diff of one improvement, with git-style file headers
+pub fn improvement_diff(improvement: &CodeImprovement) -> String {
+    let path = &improvement.file_path;
+    let original = improvement.original_content.as_deref().unwrap_or_default();
+    let (old, new, old_header, new_header) = match improvement.change_type {
+        ChangeType::Create => (
+            "",
+            improvement.new_content.as_str(),
+            "/dev/null".to_string(),
+            format!("b/{}", path),
+        ),
+        ChangeType::Delete => (original, "", format!("a/{}", path), "/dev/null".to_string()),
+        ChangeType::Modify | ChangeType::Append => (
+            original,
+            improvement.new_content.as_str(),
+            format!("a/{}", path),
+            format!("b/{}", path),
+        ),
+    };
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut unified = diff.unified_diff();
+    unified
+        .context_radius(CONTEXT_LINES)
+        .header(&old_header, &new_header);
+    format!("diff --git a/{path} b/{path}\n{}", unified)
+}
+
+/// 📦 One unified diff covering every improvement
+pub fn unified_diff(improvements: &[CodeImprovement]) -> String {
+    improvements.iter().map(improvement_diff).collect()
+}
+
+/// 💾 Store the diff of the proposed changes for the preview page
+pub async fn record_proposed_diff(
+    pool: &PgPool,
+    feedback: &Feedback,
+    improvements: &[CodeImprovement],
+) -> Result<()> {
+    feedback
+        .record_proposed_diff(pool, &unified_diff(improvements))
+        .await
+}
+
+/// ✂️ Split a unified diff into files and numbered lines
+pub fn parse_unified_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    // 📋 Between `diff --git` and the first hunk: ---/+++ headers, modes...
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            let path = paths
+                .split_once(" b/")
+                .map(|(_, new_path)| new_path)
+                .unwrap_or(paths);
+            files.push(FileDiff {
+                path: path.to_string(),
+                lines: Vec::new(),
+            });
+            in_header = true;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            in_header = false;
+            (old_line, new_line) = hunk_starts(line);
+            file.lines.push(DiffLine {
+                kind: DiffLineKind::Hunk,
+                old_line: None,
+                new_line: None,
+                text: line.to_string(),
+            });
+            continue;
+        }
+        if in_header {
+            continue;
+        }
+
+        let kind = match line.chars().next() {
+            Some('+') => DiffLineKind::Added,
+            Some('-') => DiffLineKind::Removed,
+            Some(' ') | None => DiffLineKind::Context,
+            // 🤷 "\ No newline at end of file" and friends
+            Some(_) => continue,
+        };
+        let text = line.get(1..).unwrap_or_default();
+        let (old, new) = match kind {
+            DiffLineKind::Added => (None, Some(new_line)),
+            DiffLineKind::Removed => (Some(old_line), None),
+            _ => (Some(old_line), Some(new_line)),
+        };
+        old_line += usize::from(old.is_some());
+        new_line += usize::from(new.is_some());
+        file.lines.push(DiffLine {
+            kind,
+            old_line: old,
+            new_line: new,
+            text: text.to_string(),
+        });
+    }
+
+    files
+}
+
+/// 📍 First old and new line numbers of a `@@ -a,b +c,d @@` header
+fn hunk_starts(header: &str) -> (usize, usize) {
+    let start = |marker: char| {
+        header
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix(marker))
+            .and_then(|range| range.split(',').next())
+            .and_then(|line| line.parse().ok())
+            .unwrap_or(1)
+    };
+    (start('-'), start('+'))
+}
+
+// 🧪 Tests - Diffs must round-trip with the right line numbers!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn improvement(change_type: ChangeType, original: Option<&str>, new: &str) -> CodeImprovement {
+        CodeImprovement {
+            file_path: "src/lib.rs".to_string(),
+            description: "Tweak things".to_string(),
+            change_type,
+            original_content: original.map(str::to_string),
+            new_content: new.to_string(),
+            line_number: None,
+        }
+    }
+
+    #[test]
+    fn test_improvement_diff() {
+        let modify = improvement(
+            ChangeType::Modify,
+            Some("fn a() {}\nfn b() {}\nfn c() {}\n"),
+            "fn a() {}\nfn b2() {}\nfn c() {}\n",
+        );
+        let diff = improvement_diff(&modify);
+        assert!(diff.starts_with(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n"
+        ));
+        assert!(diff.contains("-fn b() {}\n+fn b2() {}\n"));
+
+        let create = improvement_diff(&improvement(ChangeType::Create, None, "hello\n"));
+        assert!(create.contains("--- /dev/null\n+++ b/src/lib.rs\n"));
+        let delete = improvement_diff(&improvement(ChangeType::Delete, Some("bye\n"), ""));
+        assert!(delete.contains("--- a/src/lib.rs\n+++ /dev/null\n"));
+        println!("✅ Improvement diff test passed!");
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let original: String = (1..=10).map(|n| format!("line {}\n", n)).collect();
+        let changed = original.replace("line 6\n", "-- line six\n");
+        let diff = unified_diff(&[
+            improvement(ChangeType::Modify, Some(&original), &changed),
+            improvement(ChangeType::Create, None, "new\n"),
+        ]);
+
+        let files = parse_unified_diff(&diff);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!((files[0].additions(), files[0].deletions()), (1, 1));
+        assert_eq!(files[0].lines[0].kind, DiffLineKind::Hunk);
+
+        let removed = files[0]
+            .lines
+            .iter()
+            .find(|line| line.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!((removed.old_line, removed.new_line), (Some(6), None));
+        let added = files[0]
+            .lines
+            .iter()
+            .find(|line| line.kind == DiffLineKind::Added)
+            .unwrap();
+        assert_eq!(
+            (added.old_line, added.new_line, added.text.as_str()),
+            (None, Some(6), "-- line six")
+        );
+        let after = files[0].lines.last().unwrap();
+        assert_eq!((after.old_line, after.new_line), (Some(9), Some(9)));
+
+        assert_eq!(files[1].additions(), 1);
+        assert_eq!(files[1].lines[1].new_line, Some(1));
+        println!("✅ Unified diff parsing test passed!");
+    }
+}
@@ -0,0 +1,472 @@
+// 📝 Feedback Mode - User Feedback, Planned and Built One File at a Time! 📝
+// Feedback submitted for a repository with a project is queued as a project
+// run and lands here: the feedback's scope is read from the checkout, a
+// planning call lays out the file edits, each file is generated on its own
+// (see planning), the repository's formatters run over the result in a sandbox,
+// it is checked against the licensing policy, and the PR (or PR series) is
+// opened. Every run goes through the plan, whatever the
+// size of the change, so the plan is always in the feedback metadata and each
+// step in the events timeline
+// Created with love by Aye & Hue - From "please fix" to pull request! ✨
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+use super::{
+    checkpoint::Checkpoint,
+    formatting::{self, FormattingReport},
+    licensing,
+    planning::{run_planned_generation, ChangePlan, PlanningContext, PLAN_METADATA_KEY},
+    risk::RiskScorer,
+    sandbox::Sandbox,
+    splitting::publish_request,
+};
+use crate::{
+    budgets::SpendLimits,
+    config::{BudgetConfig, Config},
+    database::models::{Feedback, FeedbackEvent, Project},
+    feedback_trace::{self, Stage},
+    github::{
+        git::{github_clone_url, CloneCache, CloneOptions},
+        objects::RepositoryObjects,
+        parse_repository, CodeImprovement, FeedbackProcessingRequest, GitHubClient,
+        PullRequestResult,
+    },
+    jobs::repo_health::SourceFile,
+    llm::{prompts::names, ExchangeTrace, LlmManager, PromptBook},
+    models::{FormatTool, PathScope},
+    scm, style_guide,
+    utils::text,
+};
+
+/// 📏 Files larger than this are not sent to the LLM
+const MAX_FEEDBACK_FILE_BYTES: usize = 256 * 1024;
+
+/// ✂️ Longest commit subject taken from the feedback itself
+const SUBJECT_LENGTH: usize = 72;
+
+/// 📂 What a feedback run reads from its scoped checkout
+#[derive(Debug, Clone, Default)]
+pub struct FeedbackCheckout {
+    /// 🧭 Commit the listing and contents were read at
+    pub base_commit: String,
+    /// 📋 Every file in scope (the planner is shown those in `contents`)
+    pub file_listing: Vec<String>,
+    /// 📄 Contents of the files the model may read and edit
+    pub contents: HashMap<String, String>,
+    /// 🧱 LFS and submodule paths that must not be edited
+    pub objects: RepositoryObjects,
+    /// 🎨 Formatters the repository is set up for (none: no formatter pass)
+    pub format_tools: Vec<FormatTool>,
+}
+
+impl FeedbackCheckout {
+    /// 📄 Manifests and formatter configs among the contents
+    fn setup_files(&self) -> Vec<SourceFile> {
+        self.contents
+            .iter()
+            .filter(|(path, _)| formatting::is_setup_file(path))
+            .map(|(path, content)| SourceFile {
+                path: path.clone(),
+                content: content.clone(),
+            })
+            .collect()
+    }
+}
+
+/// 🔎 Trace the LLM exchanges of a feedback run are logged under
+async fn run_trace(
+    pool: &PgPool,
+    budgets: &BudgetConfig,
+    project: &Project,
+    feedback: &Feedback,
+) -> Result<ExchangeTrace> {
+    let settings = project.settings()?;
+    Ok(ExchangeTrace::new(
+        names::CHANGE_PLAN,
+        feedback.id,
+        project.id,
+        settings.prompt_logging,
+    )
+    .with_routing(settings.model_routing.clone())
+    .with_budget(SpendLimits::resolve(budgets, &settings.budget))
+    .with_provider(feedback.submitter_llm_provider(pool).await?))
+}
+
+/// 🗺️ Plan and generate the changes for a feedback item, format them in
+/// `sandbox` (a copy of the checkout, when there are formatters to run), then
+/// check them against the project's licensing policy. The plan ends up in the
+/// feedback's metadata; a failed licensing check clears the checkpoint so a
+/// retry starts over
+pub async fn implement(
+    pool: &PgPool,
+    budgets: &BudgetConfig,
+    llm: &LlmManager,
+    project: &Project,
+    feedback: &mut Feedback,
+    checkout: &FeedbackCheckout,
+    sandbox: Option<&Sandbox>,
+) -> Result<(Vec<CodeImprovement>, FormattingReport)> {
+    let settings = project.settings()?;
+    let scope = PathScope::resolve(settings.path.as_deref(), feedback.path.as_deref())?;
+    let trace = run_trace(pool, budgets, project, feedback).await?;
+    let prompts =
+        PromptBook::load(pool, feedback.id, &[names::CHANGE_PLAN, names::FILE_EDIT]).await?;
+    // 🎨 Generated in the repository's learned style
+    let styled = style_guide::styled(pool, project).await?;
+    let content = feedback.content.clone();
+    // 📏 Files whose content wasn't read are hidden from the planner, but still exist
+    let editable: Vec<String> = checkout
+        .file_listing
+        .iter()
+        .filter(|path| checkout.contents.contains_key(*path))
+        .cloned()
+        .collect();
+    let existing: HashSet<String> = checkout.file_listing.iter().cloned().collect();
+    let context = PlanningContext {
+        repository: &project.repository,
+        base_commit: &checkout.base_commit,
+        feedback: &content,
+        file_listing: &editable,
+        existing_files: &existing,
+        scope: &scope,
+        objects: &checkout.objects,
+        system_message: styled.system_message.as_deref(),
+        trace: Some(trace),
+        prompts: &prompts,
+    };
+
+    let mut improvements = run_planned_generation(pool, llm, feedback, &context, |path| {
+        checkout.contents.get(path).cloned()
+    })
+    .await?;
+
+    // 🎨 The PR carries what the repository's formatters make of the changes
+    let formatting = match sandbox {
+        Some(sandbox) => {
+            sandbox.apply(&improvements)?;
+            formatting::format_generated(
+                pool,
+                feedback.id,
+                sandbox,
+                &checkout.format_tools,
+                &checkout.setup_files(),
+                &mut improvements,
+                |_| Ok(()),
+            )
+            .await?
+        }
+        None => FormattingReport::default(),
+    };
+    if let Err(e) = licensing::enforce(pool, feedback.id, &settings.licensing, &improvements).await
+    {
+        // 🗑️ A retry should plan the change again, not check these files again
+        Checkpoint::clear(pool, feedback.id).await?;
+        return Err(e);
+    }
+    Ok((improvements, formatting))
+}
+
+/// 🏭 Feedback run for one project, implementing `feedback`
+pub async fn run_feedback(
+    pool: &PgPool,
+    config: &Config,
+    llm: &LlmManager,
+    project: &Project,
+    feedback: &Feedback,
+) -> Result<Vec<PullRequestResult>> {
+    let settings = project.settings()?;
+    let scope = PathScope::resolve(settings.path.as_deref(), feedback.path.as_deref())?;
+    let (owner, repo) = parse_repository(&project.repository)?;
+
+    // 📥 Read the scoped checkout (LFS pointers and submodules are skipped)
+    let cache = CloneCache::new(
+        &config.github.clone_cache_dir,
+        config.github.clone_cache_size,
+    );
+    let github_config = scm::github_config(pool, &config.github, project).await?;
+    let options = CloneOptions {
+        scope,
+        token: Some(github_config.token.clone()),
+        ..Default::default()
+    };
+    let repository = project.repository.clone();
+    let clone_url = github_clone_url(&github_config.api_base_url, &repository);
+    let format_settings = settings.formatting.clone();
+    let sandbox_config = config.sandbox.clone();
+    let git = Stage::Git.span(None);
+    let (checkout, sandbox) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let workspace = feedback_trace::traced_blocking(git, || {
+            cache.checkout(&repository, &clone_url, &options)
+        })?;
+        let objects = workspace.objects()?;
+        let file_listing = workspace.list_files()?;
+        let contents = objects
+            .filter_context(&file_listing)
+            .into_iter()
+            .filter_map(|path| {
+                let content = workspace.read_file(&path)?;
+                (content.len() <= MAX_FEEDBACK_FILE_BYTES).then_some((path, content))
+            })
+            .collect();
+        let mut checkout = FeedbackCheckout {
+            base_commit: workspace.head_sha()?,
+            file_listing,
+            contents,
+            objects,
+            format_tools: Vec::new(),
+        };
+        // 🎨 A sandbox is only needed when there is a formatter to run
+        checkout.format_tools = formatting::tools_for(&format_settings, &checkout.setup_files());
+        let sandbox = if checkout.format_tools.is_empty() {
+            None
+        } else {
+            Some(Sandbox::create(&sandbox_config, workspace.root())?)
+        };
+        Ok((checkout, sandbox))
+    })
+    .await
+    .context("Checkout task panicked")??;
+
+    let mut feedback = feedback.clone();
+    let (improvements, formatting) = implement(
+        pool,
+        &config.budgets,
+        llm,
+        project,
+        &mut feedback,
+        &checkout,
+        sandbox.as_ref(),
+    )
+    .await?;
+    drop(sandbox);
+
+    let plan: Option<ChangePlan> = feedback
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(PLAN_METADATA_KEY))
+        .and_then(|plan| serde_json::from_value(plan.clone()).ok());
+    let subject = text::preview(&feedback.content, SUBJECT_LENGTH);
+    let request = FeedbackProcessingRequest {
+        feedback_id: feedback.id,
+        repository: project.repository.clone(),
+        feedback_content: feedback.content.clone(),
+        commit_message: formatting.amend_commit_message(match &plan {
+            Some(plan) => format!("📝 {}\n\n{}", subject, plan.summary),
+            None => format!("📝 {}", subject),
+        }),
+        branch_name: format!(
+            "{}feedback-{}",
+            config.github.default_branch_prefix,
+            &feedback.id.to_string()[..8]
+        ),
+        pull_request_settings: settings.pull_requests.clone(),
+        test_plan: std::iter::once(
+            "Check each planned file edit does what its intent says".to_string(),
+        )
+        .chain(formatting.test_plan_line())
+        .chain(std::iter::once(
+            "Run the test suite against the changes".to_string(),
+        ))
+        .collect(),
+        improvements,
+    };
+
+    let trace = run_trace(pool, &config.budgets, project, &feedback).await?;
+    let scorer = RiskScorer::model(
+        pool,
+        llm,
+        feedback.id,
+        project.system_message.clone(),
+        &trace,
+    )
+    .await?;
+    let github = GitHubClient::new(github_config)?;
+    let base_branch = github
+        .get_repository_info(&owner, &repo)
+        .await?
+        .default_branch;
+    let results = publish_request(
+        &github,
+        &owner,
+        &repo,
+        &request,
+        &scorer,
+        &base_branch,
+        &config.server.public_url,
+    )
+    .await?;
+
+    for result in &results {
+        FeedbackEvent::record(
+            pool,
+            feedback.id,
+            FeedbackEvent::PULL_REQUEST_OPENED,
+            json!({ "number": result.number, "url": result.url, "title": result.title }),
+        )
+        .await?;
+    }
+    info!(
+        "✅ Feedback {} implemented in {} PRs",
+        feedback.id,
+        results.len()
+    );
+    Checkpoint::clear(pool, feedback.id).await?;
+    Ok(results)
+}
+
+// 🧪 Tests - Processed feedback has to leave its plan behind!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CustomLlmConfig, LlmConfig, LlmProvider, RoutingConfig};
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// 🤖 An OpenAI-compatible reply calling the requested tool
+    fn tool_reply(arguments: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "model": "local-model",
+            "choices": [{
+                "message": {
+                    "content": null,
+                    "tool_calls": [{ "function": { "arguments": arguments.to_string() } }]
+                }
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 10 }
+        }))
+    }
+
+    fn llm_for(server: &MockServer) -> LlmManager {
+        LlmManager::new(&LlmConfig {
+            openai: None,
+            anthropic: None,
+            custom: Some(CustomLlmConfig {
+                base_url: format!("{}/v1", server.uri()),
+                api_key: None,
+                default_model: "local-model".to_string(),
+                temperature: 0.2,
+                max_tokens: 1000,
+                context_window: Some(32_000),
+                small_model: None,
+                supports_tools: true,
+            }),
+            default_provider: LlmProvider::Custom,
+            timeout_seconds: 5,
+            max_retries: 0,
+            fallback_providers: Vec::new(),
+            circuit_breaker_threshold: 5,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: RoutingConfig {
+                tiers: Default::default(),
+                small_prompt_tokens: 0,
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_processed_feedback_records_its_plan() {
+        // This test only runs if we have a (migrated) test database available
+        let Ok(database_url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPool::connect(&database_url).await.unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_string_contains("submit_change_plan"))
+            .respond_with(tool_reply(json!({
+                "summary": "Greet visitors politely",
+                "steps": [{
+                    "file_path": "src/lib.rs",
+                    "change_type": "modify",
+                    "intent": "Say please in the greeting"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_string_contains("write_file"))
+            .respond_with(tool_reply(json!({
+                "content": "pub fn greet() -> &'static str {\n    \"Hello, please\"\n}\n"
+            })))
+            .mount(&server)
+            .await;
+
+        let project = Project::create(&pool, uuid::Uuid::new_v4(), "aye-is/demo".to_string(), None)
+            .await
+            .unwrap();
+        let mut feedback = Feedback::create(
+            &pool,
+            None,
+            project.repository.clone(),
+            None,
+            "The greeting should be more polite".to_string(),
+        )
+        .await
+        .unwrap();
+        let checkout = FeedbackCheckout {
+            base_commit: "0123456789abcdef".to_string(),
+            file_listing: vec!["README.md".to_string(), "src/lib.rs".to_string()],
+            contents: HashMap::from([(
+                "src/lib.rs".to_string(),
+                "pub fn greet() -> &'static str {\n    \"Hi\"\n}\n".to_string(),
+            )]),
+            objects: RepositoryObjects::default(),
+            format_tools: Vec::new(),
+        };
+
+        let (improvements, formatting) = implement(
+            &pool,
+            &BudgetConfig {
+                feedback_tokens: 0,
+                project_monthly_tokens: 0,
+            },
+            &llm_for(&server),
+            &project,
+            &mut feedback,
+            &checkout,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(improvements.len(), 1);
+        assert!(formatting.tools.is_empty());
+        assert!(improvements[0].new_content.contains("Hello, please"));
+
+        // 🗺️ The plan is in the stored metadata and on the timeline
+        let stored = Feedback::find_by_id(&pool, feedback.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let plan: ChangePlan =
+            serde_json::from_value(stored.metadata.unwrap()[PLAN_METADATA_KEY].clone()).unwrap();
+        assert_eq!(plan.summary, "Greet visitors politely");
+        assert_eq!(plan.steps[0].file_path, "src/lib.rs");
+        let events: Vec<String> = FeedbackEvent::list_for_feedback(&pool, feedback.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|event| event.event_type)
+            .collect();
+        for expected in [
+            FeedbackEvent::PLAN_CREATED,
+            FeedbackEvent::FILE_GENERATED,
+            FeedbackEvent::LICENSE_CHECKED,
+        ] {
+            assert!(
+                events.iter().any(|event| event == expected),
+                "{} missing",
+                expected
+            );
+        }
+        println!("✅ Feedback plan metadata test passed!");
+    }
+}
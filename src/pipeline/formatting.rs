@@ -0,0 +1,491 @@
+// 🎨 Formatter Pass - PRs That Pass the Style Checks Before CI Runs Them! 🎨
+// Once generated files are written into the sandbox, the formatters and linters
+// the repository is set up for run over them: `cargo clippy --fix` and
+// `cargo fmt` for Rust crates, ruff and black for Python, prettier for
+// JavaScript and friends. Whatever they change is folded back into the
+// generated files, so the fixes go out in the PR's commit instead of failing
+// its style checks. A tool that isn't installed or fails is recorded and
+// skipped: formatting tidies a run, it never stops one. Projects pick the
+// tools, or turn the pass off, with their `formatting` settings
+// Created with love by Aye & Hue - Tidy diffs, happy reviewers! ✨
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::sandbox::{Sandbox, SandboxOutcome, TestCommand};
+use crate::database::models::FeedbackEvent;
+use crate::feedback_trace::{self, Stage};
+use crate::github::{ChangeType, CodeImprovement};
+use crate::jobs::repo_health::SourceFile;
+use crate::models::{FormatTool, FormattingSettings};
+
+/// 💅 Config files that mean a repository formats with prettier
+const PRETTIER_CONFIGS: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+    ".prettierrc.toml",
+    ".prettierrc.js",
+    "prettier.config.js",
+];
+
+/// 📋 What the formatter pass did to a run's generated files
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FormattingReport {
+    /// 🧰 Tools that ran cleanly
+    pub tools: Vec<FormatTool>,
+    /// 📝 Generated files the tools changed
+    pub reformatted: Vec<String>,
+    /// ↩️ Files whose formatted version broke the run's rules, kept as generated
+    pub kept: Vec<String>,
+    /// ⚠️ Tools that couldn't start or failed, and why
+    pub problems: Vec<String>,
+}
+
+impl FormattingReport {
+    /// 📝 `message` with a note on the formatting fixes it now carries
+    pub fn amend_commit_message(&self, message: String) -> String {
+        if self.reformatted.is_empty() {
+            return message;
+        }
+        format!(
+            "{}\n\nFormatted with {} ({} file(s) tidied).",
+            message,
+            self.tool_names(),
+            self.reformatted.len()
+        )
+    }
+
+    /// 📋 PR test plan line (None when no tool ran cleanly)
+    pub fn test_plan_line(&self) -> Option<String> {
+        (!self.tools.is_empty()).then(|| {
+            format!(
+                "{} ran over the generated files in the sandbox",
+                self.tool_names()
+            )
+        })
+    }
+
+    /// 🏷️ "rustfmt, prettier"
+    fn tool_names(&self) -> String {
+        self.tools
+            .iter()
+            .map(|tool| tool.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// 📄 File name of a repository path
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// 📁 Directory of a repository path ("" for the root)
+fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// 🔍 Tools the repository is set up for: rustfmt in Cargo crates, clippy when
+/// it's configured or CI runs it, ruff and black when pyproject.toml or their
+/// own config sets them up, prettier when it's configured or a dependency
+pub fn detect(files: &[SourceFile]) -> Vec<FormatTool> {
+    let mut tools = BTreeSet::new();
+    for file in files {
+        let name = file_name(&file.path);
+        match name {
+            "Cargo.toml" => {
+                tools.insert(FormatTool::Rustfmt);
+            }
+            "clippy.toml" | ".clippy.toml" => {
+                tools.insert(FormatTool::Clippy);
+            }
+            "ruff.toml" | ".ruff.toml" => {
+                tools.insert(FormatTool::Ruff);
+            }
+            "pyproject.toml" => {
+                if file.content.contains("[tool.ruff") {
+                    tools.insert(FormatTool::Ruff);
+                }
+                if file.content.contains("[tool.black]") {
+                    tools.insert(FormatTool::Black);
+                }
+            }
+            "package.json" if file.content.contains("\"prettier\"") => {
+                tools.insert(FormatTool::Prettier);
+            }
+            _ if PRETTIER_CONFIGS.contains(&name) => {
+                tools.insert(FormatTool::Prettier);
+            }
+            _ => {}
+        }
+        if file.path.starts_with(".github/workflows/") && file.content.contains("cargo clippy") {
+            tools.insert(FormatTool::Clippy);
+        }
+    }
+    tools.into_iter().collect()
+}
+
+/// 📄 Whether `detect` or `plan_runs` reads a file: manifests, formatter
+/// configs and CI workflows. Modes that don't read the whole checkout keep these
+pub fn is_setup_file(path: &str) -> bool {
+    let name = file_name(path);
+    matches!(
+        name,
+        "Cargo.toml"
+            | "clippy.toml"
+            | ".clippy.toml"
+            | "ruff.toml"
+            | ".ruff.toml"
+            | "pyproject.toml"
+            | "package.json"
+    ) || PRETTIER_CONFIGS.contains(&name)
+        || path.starts_with(".github/workflows/")
+}
+
+/// 🧰 Tools a run should use, in running order (none when the pass is off)
+pub fn tools_for(settings: &FormattingSettings, files: &[SourceFile]) -> Vec<FormatTool> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+    let mut tools = settings.tools.clone().unwrap_or_else(|| detect(files));
+    tools.sort();
+    tools.dedup();
+    tools
+}
+
+/// 📄 Extensions a tool works on
+fn extensions(tool: FormatTool) -> &'static [&'static str] {
+    match tool {
+        FormatTool::Clippy | FormatTool::Rustfmt => &["rs"],
+        FormatTool::Ruff | FormatTool::Black => &["py", "pyi"],
+        FormatTool::Prettier => &[
+            "js", "jsx", "mjs", "cjs", "ts", "tsx", "json", "css", "scss",
+        ],
+    }
+}
+
+/// 📦 Manifest of the directory a tool runs from
+fn manifest(tool: FormatTool) -> &'static str {
+    match tool {
+        FormatTool::Clippy | FormatTool::Rustfmt => "Cargo.toml",
+        FormatTool::Ruff | FormatTool::Black => "pyproject.toml",
+        FormatTool::Prettier => "package.json",
+    }
+}
+
+/// ▶️ Commands that run a tool over `files` (relative to where it runs)
+/// Cargo's tools work on the whole crate; only generated files are read back
+pub fn commands(tool: FormatTool, files: &[String]) -> Vec<TestCommand> {
+    let with_files = |program: &str, args: &[&str]| {
+        let mut command = TestCommand::new(program, args);
+        command.args.extend(files.iter().cloned());
+        command
+    };
+    match tool {
+        FormatTool::Clippy => vec![TestCommand::new(
+            "cargo",
+            &["clippy", "--fix", "--allow-no-vcs", "--all-targets"],
+        )],
+        FormatTool::Rustfmt => vec![TestCommand::new("cargo", &["fmt", "--all"])],
+        FormatTool::Ruff => vec![
+            with_files("ruff", &["check", "--fix", "--exit-zero"]),
+            with_files("ruff", &["format"]),
+        ],
+        FormatTool::Black => vec![with_files("black", &["--quiet"])],
+        FormatTool::Prettier => vec![with_files("npx", &["--no-install", "prettier", "--write"])],
+    }
+}
+
+/// 📋 Where each tool runs and on which files: the generated files it works on,
+/// grouped under the closest directory with its manifest (the root when none has one)
+pub fn plan_runs(
+    tools: &[FormatTool],
+    generated: &[&str],
+    files: &[SourceFile],
+) -> Vec<(FormatTool, String, Vec<String>)> {
+    let mut runs = Vec::new();
+    for &tool in tools {
+        let manifest_dirs: BTreeSet<&str> = files
+            .iter()
+            .filter(|file| file_name(&file.path) == manifest(tool))
+            .map(|file| parent(&file.path))
+            .collect();
+        let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for path in generated.iter().filter(|path| {
+            path.rsplit_once('.')
+                .is_some_and(|(_, extension)| extensions(tool).contains(&extension))
+        }) {
+            let mut dir = parent(path);
+            while !dir.is_empty() && !manifest_dirs.contains(dir) {
+                dir = parent(dir);
+            }
+            let relative = if dir.is_empty() {
+                path
+            } else {
+                &path[dir.len() + 1..]
+            };
+            groups.entry(dir).or_default().push(relative.to_string());
+        }
+        runs.extend(
+            groups
+                .into_iter()
+                .map(|(dir, paths)| (tool, dir.to_string(), paths)),
+        );
+    }
+    runs
+}
+
+/// 📝 Why a command didn't pass
+fn failure(outcome: &SandboxOutcome) -> String {
+    if outcome.timed_out {
+        format!("`{}` timed out", outcome.command)
+    } else {
+        format!("`{}` exited with {:?}", outcome.command, outcome.exit_code)
+    }
+}
+
+/// 🎨 Run the formatter pass over generated files already written to the sandbox
+/// Formatted content replaces what was generated unless `check` rejects it (a
+/// docs pass must stay documentation-only, say); rejected files are put back.
+/// Every command is recorded as a sandbox run, and the outcome as a
+/// `files_formatted` event
+pub async fn format_generated(
+    pool: &PgPool,
+    feedback_id: Uuid,
+    sandbox: &Sandbox,
+    tools: &[FormatTool],
+    files: &[SourceFile],
+    improvements: &mut [CodeImprovement],
+    check: impl Fn(&CodeImprovement) -> Result<()>,
+) -> Result<FormattingReport> {
+    let mut report = FormattingReport::default();
+    let generated: Vec<&str> = improvements
+        .iter()
+        .filter(|improvement| improvement.change_type != ChangeType::Delete)
+        .map(|improvement| improvement.file_path.as_str())
+        .collect();
+    let runs = plan_runs(tools, &generated, files);
+    if runs.is_empty() {
+        debug!("🎨 No formatter applies to the generated files");
+        return Ok(report);
+    }
+
+    let mut failed = BTreeSet::new();
+    for (tool, dir, paths) in &runs {
+        for command in commands(*tool, paths) {
+            let span = Stage::Sandbox.span(None);
+            match feedback_trace::traced(span, sandbox.run(dir, &command)).await {
+                Ok(outcome) => {
+                    FeedbackEvent::record(
+                        pool,
+                        feedback_id,
+                        FeedbackEvent::SANDBOX_RUN,
+                        serde_json::to_value(&outcome)?,
+                    )
+                    .await?;
+                    if !outcome.passed {
+                        report.problems.push(failure(&outcome));
+                        failed.insert(*tool);
+                    }
+                }
+                // 🤷 Most likely the tool isn't installed on this worker
+                Err(e) => {
+                    warn!("⚠️ Could not run {}: {:#}", tool.as_str(), e);
+                    report.problems.push(format!("{}: {:#}", tool.as_str(), e));
+                    failed.insert(*tool);
+                    break;
+                }
+            }
+        }
+    }
+    report.tools = runs
+        .iter()
+        .map(|(tool, _, _)| *tool)
+        .filter(|tool| !failed.contains(tool))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    for improvement in improvements
+        .iter_mut()
+        .filter(|improvement| improvement.change_type != ChangeType::Delete)
+    {
+        let Some(formatted) = sandbox.read_file(&improvement.file_path) else {
+            continue;
+        };
+        if formatted == improvement.new_content {
+            continue;
+        }
+        let candidate = CodeImprovement {
+            new_content: formatted,
+            ..improvement.clone()
+        };
+        match check(&candidate) {
+            Ok(()) => {
+                report.reformatted.push(improvement.file_path.clone());
+                *improvement = candidate;
+            }
+            Err(e) => {
+                debug!("↩️ Keeping {} as generated: {:#}", improvement.file_path, e);
+                sandbox.apply(std::slice::from_ref(improvement))?;
+                report.kept.push(improvement.file_path.clone());
+            }
+        }
+    }
+
+    info!(
+        "🎨 Formatter pass: {} files tidied, {} kept as generated, {} problems",
+        report.reformatted.len(),
+        report.kept.len(),
+        report.problems.len()
+    );
+    FeedbackEvent::record(
+        pool,
+        feedback_id,
+        FeedbackEvent::FILES_FORMATTED,
+        json!(&report),
+    )
+    .await?;
+    Ok(report)
+}
+
+// 🧪 Tests - Every tool in its place, every file in its crate!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> SourceFile {
+        SourceFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_detect_tools() {
+        let files = vec![
+            file("Cargo.toml", "[workspace]\n"),
+            file(
+                ".github/workflows/ci.yml",
+                "steps:\n  - run: cargo clippy -- -D warnings\n",
+            ),
+            file("tools/pyproject.toml", "[tool.black]\nline-length = 100\n"),
+            file(
+                "web/package.json",
+                "{ \"devDependencies\": { \"prettier\": \"^3\" } }",
+            ),
+        ];
+        assert_eq!(
+            detect(&files),
+            vec![
+                FormatTool::Clippy,
+                FormatTool::Rustfmt,
+                FormatTool::Black,
+                FormatTool::Prettier
+            ]
+        );
+        assert!(detect(&[file("web/package.json", "{}")]).is_empty());
+        assert_eq!(
+            detect(&[file("ruff.toml", ""), file(".prettierrc", "{}")]),
+            vec![FormatTool::Ruff, FormatTool::Prettier]
+        );
+
+        let settings = FormattingSettings {
+            enabled: true,
+            tools: Some(vec![FormatTool::Prettier, FormatTool::Clippy]),
+        };
+        assert_eq!(
+            tools_for(&settings, &files),
+            vec![FormatTool::Clippy, FormatTool::Prettier]
+        );
+        let off = FormattingSettings {
+            enabled: false,
+            tools: None,
+        };
+        assert!(tools_for(&off, &files).is_empty());
+
+        assert!(files.iter().all(|file| is_setup_file(&file.path)));
+        assert!(is_setup_file("web/.prettierrc.json"));
+        assert!(!is_setup_file("src/main.rs"));
+        assert!(!is_setup_file("docs/package.md"));
+        println!("✅ Formatter detection test passed!");
+    }
+
+    #[test]
+    fn test_plan_runs() {
+        let files = vec![
+            file("Cargo.toml", ""),
+            file("crates/core/Cargo.toml", ""),
+            file("web/package.json", ""),
+        ];
+        let generated = [
+            "crates/core/src/parser.rs",
+            "src/main.rs",
+            "web/src/app.ts",
+            "docs/guide.md",
+        ];
+        let runs = plan_runs(
+            &[FormatTool::Rustfmt, FormatTool::Prettier],
+            &generated,
+            &files,
+        );
+        assert_eq!(
+            runs,
+            vec![
+                (
+                    FormatTool::Rustfmt,
+                    String::new(),
+                    vec!["src/main.rs".to_string()]
+                ),
+                (
+                    FormatTool::Rustfmt,
+                    "crates/core".to_string(),
+                    vec!["src/parser.rs".to_string()]
+                ),
+                (
+                    FormatTool::Prettier,
+                    "web".to_string(),
+                    vec!["src/app.ts".to_string()]
+                ),
+            ]
+        );
+        assert!(plan_runs(&[FormatTool::Black], &generated, &files).is_empty());
+
+        let prettier = commands(FormatTool::Prettier, &["src/app.ts".to_string()]);
+        assert_eq!(
+            prettier[0].display(),
+            "npx --no-install prettier --write src/app.ts"
+        );
+        assert_eq!(commands(FormatTool::Ruff, &[]).len(), 2);
+        println!("✅ Formatter run planning test passed!");
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = FormattingReport::default();
+        assert_eq!(
+            report.amend_commit_message("Add tests".to_string()),
+            "Add tests"
+        );
+        assert_eq!(report.test_plan_line(), None);
+
+        report.tools = vec![FormatTool::Rustfmt, FormatTool::Prettier];
+        report.reformatted = vec!["src/lib.rs".to_string()];
+        assert_eq!(
+            report.amend_commit_message("Add tests".to_string()),
+            "Add tests\n\nFormatted with rustfmt, prettier (1 file(s) tidied)."
+        );
+        assert_eq!(
+            report.test_plan_line().as_deref(),
+            Some("rustfmt, prettier ran over the generated files in the sandbox")
+        );
+        println!("✅ Formatting report test passed!");
+    }
+}
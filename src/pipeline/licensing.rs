@@ -0,0 +1,354 @@
+// ⚖️ License Policy - Every New File Carries the Project's Header! ⚖️
+// Before a run opens its PR, the generated files are checked against the
+// project's `licensing` settings. New source files must start with the
+// project's license header (a template where `{year}` stands for any year, in
+// whatever comment syntax the file uses), and no file may pick up license
+// boilerplate the project can't ship: copyleft notices, "all rights reserved"
+// claims, or whatever markers the project lists. A violation fails the run
+// with every offending file, line and rule spelled out
+// Created with love by Aye & Hue - Clean code, clean licenses! ✨
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::fmt;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::database::models::FeedbackEvent;
+use crate::github::{ChangeType, CodeImprovement};
+use crate::jobs::repo_health::is_source;
+use crate::models::LicensingSettings;
+
+/// 🔍 Lines from the top of a new file searched for the header (room for a
+/// shebang, an encoding line or a module doc comment above it)
+const HEADER_SEARCH_LINES: usize = 40;
+
+/// 💬 Comment markers stripped before header lines are compared (longest first)
+const COMMENT_MARKERS: &[&str] = &[
+    "<!--", "\"\"\"", "'''", "//!", "///", "-->", "//", "/*", "*/", "--", "#", "*", ";",
+];
+
+/// 🚨 One way a generated file breaks the licensing policy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LicenseViolation {
+    /// 📂 Repository-relative path
+    pub file_path: String,
+    /// 📍 1-based line the problem is on (None = the file as a whole)
+    pub line: Option<usize>,
+    /// 📝 What's wrong
+    pub problem: String,
+}
+
+impl fmt::Display for LicenseViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file_path, line, self.problem),
+            None => write!(f, "{}: {}", self.file_path, self.problem),
+        }
+    }
+}
+
+/// 💬 Prompt rule asking for the header on new files (None without a header)
+pub fn prompt_section(settings: &LicensingSettings, year: i32) -> Option<String> {
+    let header = settings.header.as_deref()?.trim();
+    Some(format!(
+        "## License header\nEvery new source file must start with this license header, written in the file's comment syntax:\n\n{}",
+        header.replace("{year}", &year.to_string())
+    ))
+}
+
+/// ✂️ A line with its comment markers and surrounding whitespace removed
+fn strip_comment(line: &str) -> &str {
+    let mut line = line.trim();
+    while let Some(rest) = COMMENT_MARKERS
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        line = rest.trim_start();
+    }
+    for marker in ["*/", "-->", "\"\"\"", "'''"] {
+        line = line.strip_suffix(marker).unwrap_or(line);
+    }
+    line.trim()
+}
+
+/// 🔍 Whether a line reads like a template line (`{year}` matches four digits)
+fn line_matches(template: &str, line: &str) -> bool {
+    let mut parts = template.split("{year}");
+    let Some(mut rest) = parts.next().and_then(|first| line.strip_prefix(first)) else {
+        return false;
+    };
+    for part in parts {
+        let has_year = rest.len() >= 4 && rest.as_bytes()[..4].iter().all(u8::is_ascii_digit);
+        let Some(after) = has_year.then(|| rest[4..].strip_prefix(part)).flatten() else {
+            return false;
+        };
+        rest = after;
+    }
+    rest.is_empty()
+}
+
+/// 📜 Whether a file starts with the header template, in any comment syntax
+pub fn has_header(template: &str, content: &str) -> bool {
+    let expected: Vec<&str> = template
+        .lines()
+        .map(strip_comment)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if expected.is_empty() {
+        return true;
+    }
+    let head: Vec<&str> = content
+        .lines()
+        .take(HEADER_SEARCH_LINES)
+        .map(strip_comment)
+        .filter(|line| !line.is_empty())
+        .collect();
+    head.windows(expected.len()).any(|window| {
+        window
+            .iter()
+            .zip(&expected)
+            .all(|(line, template)| line_matches(template, line))
+    })
+}
+
+/// 🚫 First line of a marker the change adds (None when it was already there
+/// as often before the change)
+fn added_marker(marker: &str, content: &str, original: Option<&str>) -> Option<usize> {
+    let needle = marker.to_lowercase();
+    let count = |text: &str| text.to_lowercase().matches(&needle).count();
+    if count(content) <= original.map_or(0, count) {
+        return None;
+    }
+    content
+        .lines()
+        .position(|line| line.to_lowercase().contains(&needle))
+        .map(|index| index + 1)
+}
+
+/// 🔍 Every way the changes break the policy
+/// Markers the project's own header uses are its license, so they never count
+pub fn check(
+    settings: &LicensingSettings,
+    improvements: &[CodeImprovement],
+) -> Vec<LicenseViolation> {
+    let header = settings
+        .header
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    let markers: Vec<&str> = settings
+        .forbidden_markers
+        .iter()
+        .map(String::as_str)
+        .filter(|marker| !header.contains(&marker.to_lowercase()))
+        .collect();
+
+    let mut violations = Vec::new();
+    for improvement in improvements
+        .iter()
+        .filter(|improvement| improvement.change_type != ChangeType::Delete)
+    {
+        if let Some(template) = &settings.header {
+            if improvement.change_type == ChangeType::Create
+                && is_source(&improvement.file_path)
+                && !has_header(template, &improvement.new_content)
+            {
+                violations.push(LicenseViolation {
+                    file_path: improvement.file_path.clone(),
+                    line: None,
+                    problem: "new source file does not start with the project's license header"
+                        .to_string(),
+                });
+            }
+        }
+        for marker in &markers {
+            if let Some(line) = added_marker(
+                marker,
+                &improvement.new_content,
+                improvement.original_content.as_deref(),
+            ) {
+                violations.push(LicenseViolation {
+                    file_path: improvement.file_path.clone(),
+                    line: Some(line),
+                    problem: format!("adds forbidden license text \"{}\"", marker),
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// ⚖️ Check a run's generated files, failing it with every violation listed
+/// The outcome is recorded as a `license_checked` event either way
+pub async fn enforce(
+    pool: &PgPool,
+    feedback_id: Uuid,
+    settings: &LicensingSettings,
+    improvements: &[CodeImprovement],
+) -> Result<()> {
+    let violations = check(settings, improvements);
+    FeedbackEvent::record(
+        pool,
+        feedback_id,
+        FeedbackEvent::LICENSE_CHECKED,
+        json!({ "files": improvements.len(), "violations": violations }),
+    )
+    .await?;
+    if violations.is_empty() {
+        debug!(
+            "⚖️ {} generated files pass the licensing policy",
+            improvements.len()
+        );
+        return Ok(());
+    }
+
+    warn!(
+        "🚨 Feedback {} broke the licensing policy {} times",
+        feedback_id,
+        violations.len()
+    );
+    let details: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    anyhow::bail!(
+        "Generated changes break the licensing policy:\n- {}",
+        details.join("\n- ")
+    )
+}
+
+// 🧪 Tests - Making sure headers are found and boilerplate is caught!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn improvement(
+        path: &str,
+        change_type: ChangeType,
+        original: Option<&str>,
+        new: &str,
+    ) -> CodeImprovement {
+        CodeImprovement {
+            file_path: path.to_string(),
+            description: String::new(),
+            change_type,
+            original_content: original.map(str::to_string),
+            new_content: new.to_string(),
+            line_number: None,
+        }
+    }
+
+    #[test]
+    fn test_has_header() {
+        let template = "Copyright {year} Aye & Hue\nSPDX-License-Identifier: MIT";
+        assert!(has_header(
+            template,
+            "// Copyright 2026 Aye & Hue\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n"
+        ));
+        assert!(has_header(
+            template,
+            "#!/usr/bin/env python3\n# Copyright 2024 Aye & Hue\n#\n# SPDX-License-Identifier: MIT\nimport os\n"
+        ));
+        assert!(has_header(
+            template,
+            "/*\n * Copyright 2025 Aye & Hue\n * SPDX-License-Identifier: MIT\n */\n"
+        ));
+        // 🚫 Wrong year shape, wrong holder, or buried too deep
+        assert!(!has_header(
+            template,
+            "// Copyright 26 Aye & Hue\n// SPDX-License-Identifier: MIT\n"
+        ));
+        assert!(!has_header(
+            template,
+            "// Copyright 2026 Someone Else\n// SPDX-License-Identifier: MIT\n"
+        ));
+        let buried = format!(
+            "{}// Copyright 2026 Aye & Hue\n// SPDX-License-Identifier: MIT\n",
+            "let x = 1;\n".repeat(HEADER_SEARCH_LINES)
+        );
+        assert!(!has_header(template, &buried));
+        // 💬 A template written with comment markers works the same
+        assert!(has_header(
+            "// SPDX-License-Identifier: MIT",
+            "# SPDX-License-Identifier: MIT\n"
+        ));
+        println!("✅ License header detection test passed!");
+    }
+
+    #[test]
+    fn test_check_violations() {
+        let settings = LicensingSettings {
+            header: Some("SPDX-License-Identifier: MIT".to_string()),
+            ..Default::default()
+        };
+        let improvements = vec![
+            improvement("src/new.rs", ChangeType::Create, None, "pub fn new() {}\n"),
+            improvement(
+                "tests/ok.rs",
+                ChangeType::Create,
+                None,
+                "// SPDX-License-Identifier: MIT\n#[test]\nfn ok() {}\n",
+            ),
+            // 📚 Only source files need the header, existing files never do
+            improvement("docs/guide.md", ChangeType::Create, None, "# Guide\n"),
+            improvement(
+                "src/lib.rs",
+                ChangeType::Modify,
+                Some("pub mod a;\n"),
+                "pub mod a;\n// Copyright Acme. All Rights Reserved.\npub mod b;\n",
+            ),
+            // ♻️ Boilerplate that was already in the file isn't added by us
+            improvement(
+                "vendor/gpl.c",
+                ChangeType::Modify,
+                Some("/* GNU General Public License */\n"),
+                "/* GNU General Public License */\nint x;\n",
+            ),
+        ];
+
+        let violations = check(&settings, &improvements);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            violations[0].to_string(),
+            "src/new.rs: new source file does not start with the project's license header"
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "src/lib.rs:2: adds forbidden license text \"All rights reserved\""
+        );
+
+        // ⚖️ A GPL project's own header isn't forbidden boilerplate
+        let settings = LicensingSettings {
+            header: Some("SPDX-License-Identifier: GPL-3.0-or-later".to_string()),
+            ..Default::default()
+        };
+        let gpl = vec![improvement(
+            "src/new.rs",
+            ChangeType::Create,
+            None,
+            "// SPDX-License-Identifier: GPL-3.0-or-later\n",
+        )];
+        assert!(check(&settings, &gpl).is_empty());
+
+        // 🤷 No header configured and no markers: nothing to enforce
+        let settings = LicensingSettings {
+            header: None,
+            forbidden_markers: Vec::new(),
+        };
+        assert!(check(&settings, &improvements).is_empty());
+        println!("✅ License policy check test passed!");
+    }
+
+    #[test]
+    fn test_prompt_section() {
+        assert_eq!(prompt_section(&LicensingSettings::default(), 2026), None);
+        let settings = LicensingSettings {
+            header: Some("Copyright {year} Aye & Hue\n".to_string()),
+            ..Default::default()
+        };
+        let section = prompt_section(&settings, 2026).unwrap();
+        assert!(section.starts_with("## License header\n"));
+        assert!(section.ends_with("\n\nCopyright 2026 Aye & Hue"));
+        println!("✅ License prompt section test passed!");
+    }
+}
@@ -0,0 +1,36 @@
+// 🏭 Pipeline Module - Where Feedback Becomes Pull Requests! 🏭
+// This module holds the stages that turn a feedback item into a PR:
+// context gathering, generation, validation, and PR assembly
+// Created with love by Aye & Hue - Making every stage traceable! ✨
+// Trisha from Accounting likes her processes with clearly labelled steps! 📋
+
+pub mod checkpoint; // 💾 What an unfinished run already generated, for its retry
+pub mod dependencies; // ⬆️ Dependency update mode
+pub mod diff; // 🔍 Proposed diffs for review in the web UI
+pub mod docs; // 📚 Documentation-only pass
+pub mod feedback; // 📝 User feedback, planned and generated file by file
+pub mod formatting; // 🎨 Formatters and linters over generated files, in the sandbox
+pub mod licensing; // ⚖️ License headers and forbidden boilerplate in generated files
+pub mod mode; // 🎛️ What kind of run a feedback item is
+pub mod planning; // 🗺️ Multi-file change planning and per-file generation
+pub mod pr_description; // 📝 Structured pull request bodies
+pub mod rebase; // 🔀 Keeping open Feedbacker PRs up to date with their base
+pub mod risk; // ⚖️ Model-scored risk of changes, and which PRs need approval
+pub mod sandbox; // 🧪 Throwaway checkouts for running generated tests
+pub mod splitting; // ✂️ Splitting oversized changes into PR series
+pub mod testgen; // 🧪 Test generation mode
+
+pub use dependencies::run_dependency_update_mode;
+pub use diff::{
+    parse_unified_diff, record_proposed_diff, unified_diff, DiffLine, DiffLineKind, FileDiff,
+};
+pub use docs::run_docs_pass;
+pub use feedback::run_feedback;
+pub use mode::{run_project_mode, PipelineMode};
+pub use planning::{run_planned_generation, ChangePlan, PlannedFileEdit, PlanningContext};
+pub use pr_description::{build_pull_request, render_pr_description, PrDescriptionInput};
+pub use sandbox::{Sandbox, SandboxOutcome, TestCommand};
+pub use splitting::{
+    open_pull_request_series, plan_pull_requests, publish_request, PullRequestPart,
+};
+pub use testgen::run_test_generation;
@@ -0,0 +1,112 @@
+// 🎛️ Pipeline Modes - Not Every Run Starts From User Feedback! 🎛️
+// The mode is stored in the feedback metadata so every stage (and the
+// events timeline) knows what kind of run it is part of
+// Created with love by Aye & Hue - One pipeline, many jobs! ✨
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::{
+    dependencies::run_dependency_update_mode, docs::run_docs_pass, feedback::run_feedback,
+    testgen::run_test_generation,
+};
+use crate::config::Config;
+use crate::database::models::{Feedback, Project};
+use crate::github::PullRequestResult;
+use crate::llm::LlmManager;
+
+/// 🏷️ Metadata key holding the mode of a feedback item
+pub const MODE_METADATA_KEY: &str = "mode";
+
+/// 🎛️ What a pipeline run is trying to do
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineMode {
+    /// 📝 Implement user feedback (the classic flow)
+    #[default]
+    Feedback,
+    /// ⬆️ Bump outdated dependencies in the repository's manifests
+    DependencyUpdate,
+    /// 📚 Documentation-only pass over undocumented APIs and stale docs
+    Documentation,
+    /// 🧪 Test-only changes covering uncovered functions, verified in the sandbox
+    TestGeneration,
+}
+
+impl PipelineMode {
+    /// 🔍 Read the mode from feedback metadata (missing or unknown = Feedback)
+    pub fn from_metadata(metadata: Option<&serde_json::Value>) -> Self {
+        metadata
+            .and_then(|metadata| metadata.get(MODE_METADATA_KEY))
+            .and_then(|mode| serde_json::from_value(mode.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 📦 Metadata patch recording this mode
+    pub fn to_metadata(self) -> serde_json::Value {
+        serde_json::json!({ MODE_METADATA_KEY: self })
+    }
+
+    /// 📝 Feedback content recorded for a project-level run of this mode
+    pub fn run_description(self, repository: &str) -> String {
+        match self {
+            PipelineMode::Feedback => format!("Feedback for {}", repository),
+            PipelineMode::DependencyUpdate => {
+                format!("Keep the dependencies of {} up to date", repository)
+            }
+            PipelineMode::Documentation => format!(
+                "Document the undocumented public APIs of {} and fix stale documentation",
+                repository
+            ),
+            PipelineMode::TestGeneration => {
+                format!("Add tests for the untested functions of {}", repository)
+            }
+        }
+    }
+}
+
+/// 🏭 Run a mode to completion on a project, tracked by `feedback`
+/// (for `Feedback`, the feedback is also what gets implemented)
+pub async fn run_project_mode(
+    mode: PipelineMode,
+    pool: &PgPool,
+    config: &Config,
+    llm: &LlmManager,
+    project: &Project,
+    feedback: &Feedback,
+) -> Result<Vec<PullRequestResult>> {
+    match mode {
+        PipelineMode::DependencyUpdate => {
+            run_dependency_update_mode(pool, config, project, feedback).await
+        }
+        PipelineMode::Documentation => run_docs_pass(pool, config, llm, project, feedback).await,
+        PipelineMode::TestGeneration => {
+            run_test_generation(pool, config, llm, project, feedback).await
+        }
+        PipelineMode::Feedback => run_feedback(pool, config, llm, project, feedback).await,
+    }
+}
+
+// 🧪 Tests - Modes must survive the trip through JSONB!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_metadata_round_trip() {
+        let metadata = PipelineMode::DependencyUpdate.to_metadata();
+        assert_eq!(metadata, serde_json::json!({ "mode": "dependency_update" }));
+        assert_eq!(
+            PipelineMode::from_metadata(Some(&metadata)),
+            PipelineMode::DependencyUpdate
+        );
+        assert_eq!(PipelineMode::from_metadata(None), PipelineMode::Feedback);
+        let unknown = serde_json::json!({ "mode": "time_travel" });
+        assert_eq!(
+            PipelineMode::from_metadata(Some(&unknown)),
+            PipelineMode::Feedback
+        );
+        println!("✅ Pipeline mode metadata test passed!");
+    }
+}
@@ -0,0 +1,733 @@
+// 🗺️ Change Planning Stage - Big Feedback, Small Steps! 🗺️
+// Single-shot generation falls over when a change spans many files, so we split it:
+// 1. 🧭 A planning call produces an ordered list of file edits
+// 2. 📄 Each file is generated on its own and validated against the plan
+// The plan lives in feedback metadata and every step lands in the events timeline
+// Created with love by Aye & Hue - One file at a time! ✨
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+
+use crate::{
+    config::LlmProvider,
+    database::models::{Feedback, FeedbackEvent},
+    github::{objects::RepositoryObjects, ChangeType, CodeImprovement},
+    pipeline::{checkpoint::Checkpoint, diff::record_proposed_diff},
+    llm::{
+        extract_json,
+        prompts::names,
+        CompletionRequest, ContextBudget, ContextItem, ExchangeTrace, LlmManager, OutputSchema,
+        PromptBook, StructuredOutput,
+    },
+    models::PathScope,
+};
+
+/// 📏 Upper bound on steps in a single plan
+pub const MAX_PLAN_STEPS: usize = 25;
+
+/// 🔢 Tokens kept back for the "more files not shown" note
+const LISTING_NOTE_TOKENS: usize = 16;
+
+/// 🔑 Metadata key the plan is stored under
+pub const PLAN_METADATA_KEY: &str = "change_plan";
+
+/// 📄 One planned file edit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlannedFileEdit {
+    /// 📁 Repository-relative file path
+    pub file_path: String,
+    /// 🔄 What kind of edit this is
+    pub change_type: ChangeType,
+    /// 🎯 What this edit is supposed to achieve
+    pub intent: String,
+}
+
+/// 🗺️ Ordered list of file edits produced by the planning call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChangePlan {
+    /// 📝 One-sentence summary of the overall change
+    pub summary: String,
+    /// 📋 Edits in the order they should be generated
+    pub steps: Vec<PlannedFileEdit>,
+}
+
+impl ChangePlan {
+    /// 🧹 Parse a plan out of a model reply
+    pub fn parse(reply: &str) -> Result<Self> {
+        let payload = extract_json(reply).context("Planning reply contained no JSON")?;
+        serde_json::from_str(payload).context("Planning reply was not a valid change plan")
+    }
+
+    /// ✅ Check the plan is something we can safely execute
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.steps.is_empty() {
+            errors.push("Plan contains no file edits".to_string());
+        }
+        if self.steps.len() > MAX_PLAN_STEPS {
+            errors.push(format!(
+                "Plan has {} steps (maximum {})",
+                self.steps.len(),
+                MAX_PLAN_STEPS
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for step in &self.steps {
+            if !is_safe_relative_path(&step.file_path) {
+                errors.push(format!("Unsafe file path in plan: '{}'", step.file_path));
+            }
+            if !seen.insert(step.file_path.as_str()) {
+                errors.push(format!("File planned more than once: '{}'", step.file_path));
+            }
+            if step.intent.trim().is_empty() {
+                errors.push(format!("Missing intent for '{}'", step.file_path));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 📁 Validate, and also require every step to stay inside the scope
+    pub fn validate_within(&self, scope: &PathScope) -> Result<(), Vec<String>> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        for step in &self.steps {
+            if !scope.contains(&step.file_path) {
+                errors.push(format!(
+                    "'{}' is outside the feedback scope '{}'",
+                    step.file_path,
+                    scope.display()
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 📋 Numbered outline used in per-file prompts
+    pub fn outline(&self) -> String {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                format!(
+                    "{}. {} `{}` - {}",
+                    i + 1,
+                    change_type_label(&step.change_type),
+                    step.file_path,
+                    step.intent
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl StructuredOutput for ChangePlan {
+    fn output_schema() -> OutputSchema {
+        OutputSchema {
+            name: "submit_change_plan".to_string(),
+            description: "Submit the ordered list of file edits that implements the feedback"
+                .to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "summary": { "type": "string", "minLength": 1 },
+                    "steps": {
+                        "type": "array",
+                        "minItems": 1,
+                        "maxItems": MAX_PLAN_STEPS,
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "file_path": { "type": "string", "minLength": 1 },
+                                "change_type": {
+                                    "type": "string",
+                                    "enum": ["create", "modify", "delete", "append"]
+                                },
+                                "intent": { "type": "string", "minLength": 1 }
+                            },
+                            "required": ["file_path", "change_type", "intent"],
+                            "additionalProperties": false
+                        }
+                    }
+                },
+                "required": ["summary", "steps"],
+                "additionalProperties": false
+            }),
+        }
+    }
+}
+
+/// 📄 Full content of one generated file, as returned by the model
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct GeneratedFile {
+    /// 📝 Complete new file content
+    pub content: String,
+}
+
+impl StructuredOutput for GeneratedFile {
+    fn output_schema() -> OutputSchema {
+        OutputSchema {
+            name: "write_file".to_string(),
+            description: "Submit the complete new content of the file".to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "content": { "type": "string", "minLength": 1 }
+                },
+                "required": ["content"],
+                "additionalProperties": false
+            }),
+        }
+    }
+}
+
+/// 🧭 Inputs shared by the planning and generation calls
+#[derive(Debug, Clone)]
+pub struct PlanningContext<'a> {
+    /// 🎯 Target repository ("owner/repo")
+    pub repository: &'a str,
+    /// 🧭 Commit the file listing and contents were read at
+    pub base_commit: &'a str,
+    /// 📝 Feedback being implemented
+    pub feedback: &'a str,
+    /// 📁 Repository file listing shown to the planner: the files it can edit
+    pub file_listing: &'a [String],
+    /// 📂 Every file of the repository, including those too large or not text
+    /// enough to edit (a plan can't create over one)
+    pub existing_files: &'a HashSet<String>,
+    /// 📂 Subtree the plan is allowed to touch
+    pub scope: &'a PathScope,
+    /// 🧱 LFS and submodule paths that must not be edited
+    pub objects: &'a RepositoryObjects,
+    /// 💬 Project-specific system message
+    pub system_message: Option<&'a str>,
+    /// 📜 Exchange log trace for the calls (stage is set per call)
+    pub trace: Option<ExchangeTrace>,
+    /// 🧪 Prompt versions chosen for this feedback (see `PromptBook::load`)
+    pub prompts: &'a PromptBook,
+}
+
+/// 🧭 Phase 1: ask the LLM for an ordered, validated change plan
+/// Also returns the provider that produced it (the fallback chain may pick any)
+pub async fn plan_changes(
+    llm: &LlmManager,
+    context: &PlanningContext<'_>,
+) -> Result<(ChangePlan, LlmProvider)> {
+    let template = context.prompts.template(names::CHANGE_PLAN)?;
+    let render = |file_listing: String| {
+        template.render(&HashMap::from([
+            ("repository", context.repository.to_string()),
+            ("feedback", context.feedback.trim().to_string()),
+            ("file_listing", file_listing),
+            ("scope", context.scope.display().to_string()),
+        ]))
+    };
+
+    // 🔢 Everything but the file listing is required; the listing gets whatever is left
+    let skeleton = CompletionRequest {
+        output_schema: Some(ChangePlan::output_schema()),
+        ..CompletionRequest::new(
+            context.system_message.map(str::to_string),
+            render(String::new())?,
+        )
+    };
+    let budget = llm.context_budget(&skeleton)?;
+    let required = budget.count_request(&skeleton);
+    if let Some(overflow) = budget.overflow(required + LISTING_NOTE_TOKENS) {
+        anyhow::bail!(
+            "Feedback is too large to plan, even without the file listing: {}",
+            overflow
+        );
+    }
+    let files = context
+        .objects
+        .filter_context(&context.scope.filter(context.file_listing));
+    let file_listing = fit_file_listing(
+        &budget,
+        &files,
+        context.feedback,
+        budget.input_limit() - required - LISTING_NOTE_TOKENS,
+    );
+    let prompt = render(file_listing)?;
+
+    // 🔁 Unsafe, out-of-scope, or LFS/submodule steps are sent back for another try
+    let request = CompletionRequest::new(context.system_message.map(str::to_string), prompt)
+        .traced(
+            context
+                .trace
+                .as_ref()
+                .map(|trace| trace.with_stage(names::CHANGE_PLAN)),
+        );
+    let (plan, response) = llm
+        .complete_structured(&request, |plan: &ChangePlan| {
+            plan.validate_within(context.scope)?;
+            context
+                .objects
+                .ensure_supported(plan.steps.iter().map(|step| step.file_path.as_str()))
+                .map_err(|e| vec![format!("{:#}", e)])
+        })
+        .await
+        .context("Change plan rejected")?;
+
+    info!(
+        "🗺️ Planned {} file edits for {}",
+        plan.steps.len(),
+        context.repository
+    );
+    Ok((plan, response.provider))
+}
+
+/// 📄 Phase 2: generate and validate a single planned file
+/// Also returns the provider that wrote it (None for deletions, which skip the model)
+pub async fn generate_file_edit(
+    llm: &LlmManager,
+    context: &PlanningContext<'_>,
+    plan: &ChangePlan,
+    step_index: usize,
+    original_content: Option<String>,
+) -> Result<(CodeImprovement, Option<LlmProvider>)> {
+    let step = plan
+        .steps
+        .get(step_index)
+        .context("Step index is outside the plan")?;
+
+    context
+        .objects
+        .ensure_supported([step.file_path.as_str()])?;
+    check_original(
+        step,
+        original_content.as_deref(),
+        context.existing_files.contains(&step.file_path),
+    )?;
+
+    // 🗑️ Deletions don't need the model at all
+    if step.change_type == ChangeType::Delete {
+        return Ok((improvement_for(step, original_content, String::new()), None));
+    }
+
+    let template = context.prompts.template(names::FILE_EDIT)?;
+    let prompt = template.render(&HashMap::from([
+        ("repository", context.repository.to_string()),
+        ("feedback", context.feedback.trim().to_string()),
+        ("plan_outline", plan.outline()),
+        ("step_number", (step_index + 1).to_string()),
+        ("step_count", plan.steps.len().to_string()),
+        (
+            "change_type",
+            change_type_label(&step.change_type).to_string(),
+        ),
+        ("file_path", step.file_path.clone()),
+        ("intent", step.intent.clone()),
+        (
+            "original_content",
+            original_content
+                .clone()
+                .unwrap_or_else(|| "(new file)".to_string()),
+        ),
+    ]))?;
+
+    let request = CompletionRequest::new(context.system_message.map(str::to_string), prompt)
+        .traced(
+            context
+                .trace
+                .as_ref()
+                .map(|trace| trace.with_stage(names::FILE_EDIT)),
+        );
+
+    // 🔢 The whole file comes back in one reply, so it has to fit the reply budget
+    let budget = llm.context_budget(&request)?;
+    if let Some(original) = &original_content {
+        let tokens = budget.count(original);
+        if tokens > budget.reserved_output {
+            anyhow::bail!(
+                "'{}' is about {} tokens, more than the {} tokens a reply from {}/{} may hold",
+                step.file_path,
+                tokens,
+                budget.reserved_output,
+                budget.provider.as_str(),
+                budget.model
+            );
+        }
+    }
+
+    let (file, response) = llm
+        .complete_structured(&request, |file: &GeneratedFile| {
+            validate_generated_content(step, original_content.as_deref(), &file.content)
+                .map(|_| ())
+                .map_err(|e| vec![e.to_string()])
+        })
+        .await?;
+
+    let new_content = validate_generated_content(step, original_content.as_deref(), &file.content)?;
+    Ok((
+        improvement_for(step, original_content, new_content),
+        Some(response.provider),
+    ))
+}
+
+/// 🏭 Run both phases for a feedback item, recording progress as we go
+/// `read_file` returns the current content of a repository file (None if missing).
+/// The plan and each generated file are checkpointed, so a retry at the same
+/// commit only generates what's missing; the checkpoint is left for the caller
+/// to clear once the pull request is open
+pub async fn run_planned_generation<F>(
+    pool: &PgPool,
+    llm: &LlmManager,
+    feedback: &mut Feedback,
+    context: &PlanningContext<'_>,
+    read_file: F,
+) -> Result<Vec<CodeImprovement>>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut checkpoint = Checkpoint::resume(pool, feedback.id, context.base_commit).await?;
+    let plan = match checkpoint.plan::<ChangePlan>() {
+        Some(plan) => plan,
+        None => {
+            let (plan, provider) = plan_changes(llm, context).await?;
+            feedback.record_llm_provider(pool, provider).await?;
+            checkpoint.save_plan(pool, &plan).await?;
+
+            feedback
+                .merge_metadata(pool, json!({ PLAN_METADATA_KEY: &plan }))
+                .await?;
+            FeedbackEvent::record(
+                pool,
+                feedback.id,
+                FeedbackEvent::PLAN_CREATED,
+                serde_json::to_value(&plan)?,
+            )
+            .await?;
+            plan
+        }
+    };
+
+    let mut improvements = Vec::with_capacity(plan.steps.len());
+    for (index, step) in plan.steps.iter().enumerate() {
+        if let Some(improvement) = checkpoint.generated(&step.file_path) {
+            FeedbackEvent::record(
+                pool,
+                feedback.id,
+                FeedbackEvent::FILE_GENERATED,
+                json!({ "step": index + 1, "file_path": step.file_path, "resumed": true }),
+            )
+            .await?;
+            improvements.push(improvement.clone());
+            continue;
+        }
+        let original = read_file(&step.file_path);
+
+        match generate_file_edit(llm, context, &plan, index, original).await {
+            Ok((improvement, provider)) => {
+                if let Some(provider) = provider {
+                    feedback.record_llm_provider(pool, provider).await?;
+                }
+                checkpoint.save_file(pool, &improvement).await?;
+                FeedbackEvent::record(
+                    pool,
+                    feedback.id,
+                    FeedbackEvent::FILE_GENERATED,
+                    json!({ "step": index + 1, "file_path": step.file_path }),
+                )
+                .await?;
+                improvements.push(improvement);
+            }
+            Err(e) => {
+                warn!("❌ Planned edit for '{}' failed: {:#}", step.file_path, e);
+                FeedbackEvent::record(
+                    pool,
+                    feedback.id,
+                    FeedbackEvent::FILE_FAILED,
+                    json!({
+                        "step": index + 1,
+                        "file_path": step.file_path,
+                        "error": format!("{:#}", e),
+                    }),
+                )
+                .await?;
+                return Err(e.context(format!("Planned edit for '{}' failed", step.file_path)));
+            }
+        }
+    }
+    record_proposed_diff(pool, feedback, &improvements).await?;
+
+    Ok(improvements)
+}
+
+/// 🔍 The repository state has to match what the plan expects
+/// `exists` says whether the path is in the repository at all, even when its
+/// content wasn't read (too large, or not text)
+fn check_original(step: &PlannedFileEdit, original: Option<&str>, exists: bool) -> Result<()> {
+    match (&step.change_type, original) {
+        (ChangeType::Create, _) if exists || original.is_some() => {
+            anyhow::bail!("Plan creates '{}' but it already exists", step.file_path)
+        }
+        (ChangeType::Modify | ChangeType::Append | ChangeType::Delete, None) if exists => {
+            anyhow::bail!(
+                "Plan edits '{}' but it is too large or not text enough to edit",
+                step.file_path
+            )
+        }
+        (ChangeType::Modify | ChangeType::Append | ChangeType::Delete, None) => {
+            anyhow::bail!("Plan edits '{}' but it does not exist", step.file_path)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// ✅ Clean up and sanity-check generated file content
+fn validate_generated_content(
+    step: &PlannedFileEdit,
+    original: Option<&str>,
+    reply: &str,
+) -> Result<String> {
+    let content = strip_code_fence(reply);
+
+    if content.trim().is_empty() {
+        anyhow::bail!("Generated content for '{}' is empty", step.file_path);
+    }
+    if original.is_some_and(|original| original.trim() == content.trim()) {
+        anyhow::bail!("Generated content for '{}' is unchanged", step.file_path);
+    }
+
+    Ok(content)
+}
+
+/// 🧹 Models often wrap whole files in a ``` fence
+pub fn strip_code_fence(reply: &str) -> String {
+    let trimmed = reply.trim();
+    if let Some(fenced) = trimmed.strip_prefix("```") {
+        if let Some((_, body)) = fenced.split_once('\n') {
+            if let Some(inner) = body.trim_end().strip_suffix("```") {
+                return inner.to_string();
+            }
+        }
+    }
+
+    let mut content = trimmed.to_string();
+    content.push('\n');
+    content
+}
+
+/// 🔢 Fit the repository file listing into `available` tokens
+/// Files named in the feedback go first, then shallower paths; the rest are counted in a note
+fn fit_file_listing(
+    budget: &ContextBudget,
+    files: &[String],
+    feedback: &str,
+    available: usize,
+) -> String {
+    let feedback = feedback.to_lowercase();
+    let items = files
+        .iter()
+        .map(|path| {
+            let stem = path
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.split('.').next())
+                .unwrap_or_default()
+                .to_lowercase();
+            let mentioned = stem.len() >= 3 && feedback.contains(&stem);
+            ContextItem {
+                label: path.clone(),
+                text: format!("{}\n", path),
+                priority: if mentioned {
+                    0
+                } else {
+                    1 + path.matches('/').count() as u32
+                },
+                truncatable: false,
+            }
+        })
+        .collect();
+
+    let selection = budget.select(items, available);
+    let mut listing: String = selection
+        .included
+        .iter()
+        .map(|item| item.text.as_str())
+        .collect();
+    if !selection.dropped.is_empty() {
+        info!(
+            "🔢 File listing trimmed to fit the context window: {} of {} files omitted",
+            selection.dropped.len(),
+            files.len()
+        );
+        listing.push_str(&format!(
+            "… and {} more files not shown\n",
+            selection.dropped.len()
+        ));
+    }
+    listing.trim_end().to_string()
+}
+
+/// 🛡️ Relative, non-escaping paths only
+fn is_safe_relative_path(path: &str) -> bool {
+    !path.trim().is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && !path
+            .split('/')
+            .any(|segment| segment == ".." || segment.is_empty())
+}
+
+/// 🏷️ Human-readable change type
+fn change_type_label(change_type: &ChangeType) -> &'static str {
+    match change_type {
+        ChangeType::Create => "create",
+        ChangeType::Modify => "modify",
+        ChangeType::Delete => "delete",
+        ChangeType::Append => "append",
+    }
+}
+
+/// 🔧 Turn a planned step into a code improvement
+fn improvement_for(
+    step: &PlannedFileEdit,
+    original_content: Option<String>,
+    new_content: String,
+) -> CodeImprovement {
+    CodeImprovement {
+        file_path: step.file_path.clone(),
+        description: step.intent.clone(),
+        change_type: step.change_type.clone(),
+        original_content,
+        new_content,
+        line_number: None,
+    }
+}
+
+// 🧪 Tests - A plan is only as good as its validation!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::structured::parse_structured;
+
+    fn step(path: &str, change_type: ChangeType) -> PlannedFileEdit {
+        PlannedFileEdit {
+            file_path: path.to_string(),
+            change_type,
+            intent: "Do the thing".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_plan_from_reply() {
+        let reply = r#"```json
+{"summary": "Add dark mode", "steps": [
+  {"file_path": "src/theme.rs", "change_type": "create", "intent": "Theme definitions"},
+  {"file_path": "src/main.rs", "change_type": "modify", "intent": "Register theme"}
+]}
+```"#;
+
+        let plan = ChangePlan::parse(reply).unwrap();
+        assert_eq!(plan.summary, "Add dark mode");
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].change_type, ChangeType::Create);
+        assert!(plan.validate().is_ok());
+        assert!(plan.outline().starts_with("1. create `src/theme.rs`"));
+
+        // 🧱 The same reply passes the structured output schema
+        let structured: ChangePlan = parse_structured(reply, &ChangePlan::output_schema()).unwrap();
+        assert_eq!(structured, plan);
+        let unknown_type = r#"{"summary": "x", "steps": [{"file_path": "a.rs", "change_type": "rename", "intent": "y"}]}"#;
+        assert!(
+            parse_structured::<ChangePlan>(unknown_type, &ChangePlan::output_schema()).is_err()
+        );
+        println!("✅ Plan parsing test passed!");
+    }
+
+    #[test]
+    fn test_plan_validation_rejects_unsafe_plans() {
+        let plan = ChangePlan {
+            summary: "Sneaky".to_string(),
+            steps: vec![
+                step("../etc/passwd", ChangeType::Modify),
+                step("/abs/path.rs", ChangeType::Create),
+                step("src/lib.rs", ChangeType::Modify),
+                step("src/lib.rs", ChangeType::Modify),
+            ],
+        };
+
+        let errors = plan.validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+
+        let scoped = ChangePlan {
+            summary: "Scoped".to_string(),
+            steps: vec![
+                step("crates/foo/src/lib.rs", ChangeType::Modify),
+                step("crates/bar/src/lib.rs", ChangeType::Modify),
+            ],
+        };
+        let scope = PathScope::new("crates/foo").unwrap();
+        assert_eq!(scoped.validate_within(&scope).unwrap_err().len(), 1);
+
+        let empty = ChangePlan {
+            summary: String::new(),
+            steps: vec![],
+        };
+        assert!(empty.validate().is_err());
+        println!("✅ Plan validation test passed!");
+    }
+
+    #[test]
+    fn test_generated_content_validation() {
+        let modify = step("src/lib.rs", ChangeType::Modify);
+
+        let content =
+            validate_generated_content(&modify, Some("old"), "```rust\nfn new() {}\n```").unwrap();
+        assert_eq!(content, "fn new() {}\n");
+
+        assert!(validate_generated_content(&modify, Some("same\n"), "same").is_err());
+        assert!(validate_generated_content(&modify, Some("old"), "   ").is_err());
+        assert!(check_original(&modify, None, false).is_err());
+        assert!(check_original(&modify, Some("x"), true).is_ok());
+        let create = step("src/new.rs", ChangeType::Create);
+        assert!(check_original(&create, Some("x"), true).is_err());
+        assert!(check_original(&create, None, false).is_ok());
+        // 📏 Existing files whose content wasn't read can't be created over or edited
+        let created_over = check_original(&create, None, true).unwrap_err();
+        assert!(created_over.to_string().contains("already exists"));
+        let too_large = check_original(&modify, None, true).unwrap_err();
+        assert!(too_large.to_string().contains("too large"));
+        println!("✅ Generated content validation test passed!");
+    }
+
+    #[test]
+    fn test_file_listing_fits_budget() {
+        let budget = ContextBudget::new(LlmProvider::OpenAi, "gpt-4o", None, 0);
+        let files: Vec<String> = ["README.md", "src/lib.rs", "src/deep/nested/theme.rs"]
+            .iter()
+            .map(|path| path.to_string())
+            .chain((0..50).map(|i| format!("src/generated/module_{}.rs", i)))
+            .collect();
+
+        let everything = fit_file_listing(&budget, &files, "Fix the theme", 10_000);
+        assert_eq!(everything.lines().count(), files.len());
+
+        // 🥇 The file named in the feedback survives, then the shallowest paths
+        let trimmed = fit_file_listing(&budget, &files, "Fix the theme", 20);
+        let lines: Vec<&str> = trimmed.lines().collect();
+        assert_eq!(
+            lines[..3],
+            ["README.md", "src/lib.rs", "src/deep/nested/theme.rs"]
+        );
+        assert!(lines.last().unwrap().ends_with("more files not shown"));
+        println!("✅ File listing budget test passed!");
+    }
+}
@@ -0,0 +1,236 @@
+// 📝 PR Description Stage - Telling Reviewers What Happened and Why! 📝
+// Builds a structured pull request body from the feedback and generated changes,
+// rendered through the prompt template system instead of raw LLM output
+// Created with love by Aye & Hue - Reviewers deserve good descriptions! ✨
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    github::{CodeImprovement, FeedbackProcessingRequest, NewPullRequest},
+    llm::prompts::{self, names},
+    pipeline::risk::RiskAssessment,
+};
+
+/// 📝 Everything needed to render a PR description
+#[derive(Debug, Clone)]
+pub struct PrDescriptionInput<'a> {
+    /// 🆔 Feedback this PR was generated from
+    pub feedback_id: Uuid,
+    /// 📝 Original feedback text (quoted as the rationale)
+    pub feedback_content: &'a str,
+    /// 🎯 Short summary of the change
+    pub summary: &'a str,
+    /// 🔧 Changes included in the PR
+    pub improvements: &'a [CodeImprovement],
+    /// 🧪 Verification steps suggested by the generation stage
+    pub test_plan: &'a [String],
+    /// 🔗 Link back to the feedback in Feedbacker
+    pub tracking_url: String,
+    /// ⚖️ Risk section (see pipeline::risk)
+    pub risk: String,
+}
+
+/// 🎨 Render the structured PR body
+pub fn render_pr_description(input: &PrDescriptionInput) -> Result<String> {
+    let template = prompts::builtin(names::PR_DESCRIPTION)
+        .context("PR description template is missing")?;
+
+    let vars = HashMap::from([
+        ("summary", input.summary.trim().to_string()),
+        ("feedback_quote", quote_feedback(input.feedback_content)),
+        ("tracking_url", input.tracking_url.clone()),
+        ("modified_files", format_modified_files(input.improvements)),
+        ("risk", input.risk.clone()),
+        ("test_plan", format_test_plan(input.test_plan, input.improvements)),
+        ("feedback_id", input.feedback_id.to_string()),
+    ]);
+
+    template.render(&vars)
+}
+
+/// 🐙 Assemble the title, body, and branches for a processing request
+/// The body carries `risk`, the assessment of the request's changes
+pub fn build_pull_request(
+    request: &FeedbackProcessingRequest,
+    risk: &RiskAssessment,
+    base_branch: &str,
+    public_url: &str,
+) -> Result<NewPullRequest> {
+    let mut lines = request.commit_message.lines();
+    let title = lines
+        .next()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .unwrap_or("🤖 Feedbacker improvements")
+        .to_string();
+    let rest = lines.collect::<Vec<_>>().join("\n");
+    let summary = if rest.trim().is_empty() {
+        title.clone()
+    } else {
+        rest
+    };
+
+    let body = render_pr_description(&PrDescriptionInput {
+        feedback_id: request.feedback_id,
+        feedback_content: &request.feedback_content,
+        summary: &summary,
+        improvements: &request.improvements,
+        test_plan: &request.test_plan,
+        tracking_url: tracking_url(public_url, request.feedback_id),
+        risk: risk.section(&request.pull_request_settings),
+    })?;
+
+    Ok(NewPullRequest {
+        title,
+        body,
+        head: request.branch_name.clone(),
+        base: base_branch.to_string(),
+    })
+}
+
+/// 🔗 Tracking link for a feedback item: its page in the web UI, with the
+/// status, the proposed changes and the pull request
+pub fn tracking_url(public_url: &str, feedback_id: Uuid) -> String {
+    format!(
+        "{}/feedback/{}/diff",
+        public_url.trim_end_matches('/'),
+        feedback_id
+    )
+}
+
+/// 💬 Quote feedback as a markdown blockquote, line by line
+fn quote_feedback(content: &str) -> String {
+    content
+        .trim()
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 📁 Bullet list of touched files
+fn format_modified_files(improvements: &[CodeImprovement]) -> String {
+    if improvements.is_empty() {
+        return "_No file changes._".to_string();
+    }
+
+    improvements
+        .iter()
+        .map(|improvement| {
+            format!(
+                "- `{}` ({}): {}",
+                improvement.file_path,
+                format!("{:?}", improvement.change_type).to_lowercase(),
+                improvement.description
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 🧪 Checklist of verification steps, with sensible defaults
+fn format_test_plan(test_plan: &[String], improvements: &[CodeImprovement]) -> String {
+    let mut steps: Vec<String> = test_plan
+        .iter()
+        .map(|step| step.trim().to_string())
+        .filter(|step| !step.is_empty())
+        .collect();
+
+    if steps.is_empty() {
+        steps.push("CI passes on this branch".to_string());
+        steps.push(format!(
+            "Review the {} changed file(s) against the original feedback",
+            improvements.len()
+        ));
+    }
+
+    steps
+        .iter()
+        .map(|step| format!("- [ ] {}", step))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 🧪 Tests - PR descriptions are the first thing reviewers read!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::ChangeType;
+    use crate::models::PullRequestSettings;
+
+    fn sample_improvement() -> CodeImprovement {
+        CodeImprovement {
+            file_path: "src/main.rs".to_string(),
+            description: "Add error handling".to_string(),
+            change_type: ChangeType::Modify,
+            original_content: None,
+            new_content: "// Updated with error handling".to_string(),
+            line_number: Some(10),
+        }
+    }
+
+    #[test]
+    fn test_generate_pr_description() {
+        let improvements = vec![sample_improvement()];
+        let feedback_id = Uuid::new_v4();
+
+        let description = render_pr_description(&PrDescriptionInput {
+            feedback_id,
+            feedback_content: "Please add error handling to the main function",
+            summary: "Adds error handling to main",
+            improvements: &improvements,
+            test_plan: &[],
+            tracking_url: tracking_url("https://f.8b.is/", feedback_id),
+            risk: "### ⚖️ Risk: low".to_string(),
+        })
+        .unwrap();
+
+        assert!(description.contains("AI-Generated Improvements"));
+        assert!(description.contains("> Please add error handling"));
+        assert!(description.contains("`src/main.rs` (modify)"));
+        assert!(description.contains(&format!("https://f.8b.is/feedback/{}/diff", feedback_id)));
+        assert!(description.contains("- [ ] CI passes"));
+        assert!(description.contains("### ⚖️ Risk: low"));
+        assert!(description.contains("Feedbacker"));
+        println!("✅ PR description generation test passed!");
+    }
+
+    #[test]
+    fn test_multiline_feedback_quote() {
+        let quoted = quote_feedback("First line\n\nSecond line");
+        assert_eq!(quoted, "> First line\n>\n> Second line");
+        println!("✅ Feedback quoting test passed!");
+    }
+
+    #[test]
+    fn test_build_pull_request() {
+        let request = FeedbackProcessingRequest {
+            feedback_id: Uuid::new_v4(),
+            repository: "owner/repo".to_string(),
+            feedback_content: "Make errors friendlier".to_string(),
+            improvements: vec![sample_improvement()],
+            commit_message: "Improve error messages\n\nWraps errors with context.".to_string(),
+            branch_name: "feedbacker/friendly-errors".to_string(),
+            pull_request_settings: PullRequestSettings::default(),
+            test_plan: vec!["Run cargo test".to_string()],
+        };
+
+        let risk = crate::pipeline::risk::assess(&request.improvements);
+        let pr = build_pull_request(&request, &risk, "main", "https://f.8b.is").unwrap();
+        assert_eq!(pr.title, "Improve error messages");
+        assert_eq!(pr.head, "feedbacker/friendly-errors");
+        assert_eq!(pr.base, "main");
+        assert!(pr.body.contains("Wraps errors with context."));
+        assert!(pr.body.contains("- [ ] Run cargo test"));
+        assert!(pr.body.contains("### ⚖️ Risk: low"));
+        println!("✅ Pull request assembly test passed!");
+    }
+}
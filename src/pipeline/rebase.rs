@@ -0,0 +1,828 @@
+// 🔀 PR Rebasing - Keeping Feedbacker PRs Current! 🔀
+// When the base branch moves on, an open Feedbacker PR can fall behind (branch
+// protection that requires up-to-date branches reports it as "behind") or
+// conflict with it ("dirty"). Projects with `pull_requests.auto_rebase` get a
+// `pull_request_rebase` job on every push to the default branch, which puts
+// such PRs back on top of the base branch: files only the PR changed are carried
+// over as they are, and files both sides changed are regenerated by the LLM from
+// the three versions and run through the repository's formatters in a checkout
+// of the base branch. The branch is rewritten as one commit through the Git Data
+// API. PRs with commits by anyone else, PRs waiting on reviews or checks
+// ("blocked"), PRs with unresolved review threads (a rewrite would mark them
+// outdated) and conflicts the LLM can't resolve are left for a maintainer, with
+// a `pull_request_stale` event saying why
+// Created with love by Aye & Hue - No more stale red PRs! ✨
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap};
+use tracing::{debug, info, warn};
+
+use super::{
+    formatting,
+    planning::{strip_code_fence, GeneratedFile},
+    sandbox::Sandbox,
+};
+use crate::{
+    api::AppState,
+    config::{Config, GitHubConfig},
+    database::models::{
+        BackgroundJob, Feedback, FeedbackEvent, JobPriority, NewBackgroundJob, Project,
+    },
+    feedback_trace::{self, Stage},
+    github::{
+        git::{github_clone_url, CloneCache, CloneOptions},
+        parse_repository,
+        pulls::{BlobContent, BlobTree, PullRequestInfo, TreeChange, TreeEntry},
+        rate_limit::RateLimited,
+        ChangeType, CodeImprovement, GitHubClient,
+    },
+    jobs::{
+        repo_health::SourceFile,
+        worker::{self, JobHandler},
+    },
+    llm::{
+        prompts::{names, PromptTemplate},
+        CompletionRequest, ExchangeTrace, LlmManager, PromptBook,
+    },
+    models::ProjectConfig,
+    scm, style_guide,
+};
+
+/// 🏷️ Job type that brings a repository's open PRs up to date
+/// (payload: `{"repository": "owner/repo"}`)
+pub const REBASE_JOB: &str = "pull_request_rebase";
+
+/// 🔁 Retries of a rebase job while GitHub is still checking mergeability
+const MAX_REBASE_RETRIES: i32 = 3;
+
+/// ⚔️ Conflicting files regenerated per PR; more than this is left for a maintainer
+const MAX_REGENERATED_FILES: usize = 10;
+
+/// 📏 Conflicting files larger than this are not sent to the LLM
+const MAX_REGENERATED_FILE_BYTES: usize = 64 * 1024;
+
+/// 🚦 Where an open PR stands against its base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// ✅ Merges as it is
+    Current,
+    /// 🐢 Branch protection wants it up to date with the base branch
+    Behind,
+    /// ⚔️ Conflicts with the base branch
+    Conflicting,
+    /// 🛡️ Waiting on reviews or required checks (rebasing won't help)
+    Blocked,
+    /// ⏳ GitHub hasn't worked out its mergeability yet
+    Pending,
+    /// 🔒 Merged or closed
+    Closed,
+}
+
+impl Freshness {
+    /// 🔍 What GitHub's mergeability says about a PR
+    pub fn of(pr: &PullRequestInfo) -> Self {
+        if pr.state != "open" || pr.merged {
+            return Freshness::Closed;
+        }
+        match pr.mergeable_state.as_deref() {
+            Some("behind") => Freshness::Behind,
+            Some("dirty") => Freshness::Conflicting,
+            Some("blocked") => Freshness::Blocked,
+            Some("clean" | "unstable" | "has_hooks") => Freshness::Current,
+            Some("unknown") | None => Freshness::Pending,
+            // 📝 Drafts (and states GitHub adds later) only say whether they merge
+            Some(_) => match pr.mergeable {
+                Some(true) => Freshness::Current,
+                Some(false) => Freshness::Conflicting,
+                None => Freshness::Pending,
+            },
+        }
+    }
+
+    /// 🔀 Whether rebasing brings the PR back
+    pub fn needs_rebase(self) -> bool {
+        matches!(self, Freshness::Behind | Freshness::Conflicting)
+    }
+
+    /// 🏷️ Name shown in events and logs
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Freshness::Current => "current",
+            Freshness::Behind => "behind",
+            Freshness::Conflicting => "conflicting",
+            Freshness::Blocked => "blocked",
+            Freshness::Pending => "pending",
+            Freshness::Closed => "closed",
+        }
+    }
+}
+
+/// 🧩 What happens to one file the PR changed when it moves to the new base
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileMerge {
+    /// ✅ The base branch left it alone: the PR's version goes on top
+    Carry(TreeChange),
+    /// ⚔️ Both sides changed it
+    Conflict(FileConflict),
+}
+
+/// ⚔️ A file both the PR and the base branch changed
+/// (None where a side doesn't have the file)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileConflict {
+    pub path: String,
+    pub original: Option<TreeEntry>,
+    pub ours: Option<TreeEntry>,
+    pub theirs: Option<TreeEntry>,
+}
+
+/// 🧩 Files the PR changed since `original` (the merge base), merged with the
+/// base branch's new tree `theirs`. Files the PR didn't change, and changes the
+/// base branch already has, need nothing
+pub fn merge_trees(original: &BlobTree, ours: &BlobTree, theirs: &BlobTree) -> Vec<FileMerge> {
+    let paths: BTreeSet<&String> = original.keys().chain(ours.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (original, ours, theirs) = (original.get(path), ours.get(path), theirs.get(path));
+            if ours == original || ours == theirs {
+                return None;
+            }
+            if theirs == original {
+                return Some(FileMerge::Carry(match ours {
+                    Some(entry) => TreeChange::Blob {
+                        path: path.clone(),
+                        entry: entry.clone(),
+                    },
+                    None => TreeChange::Removed { path: path.clone() },
+                }));
+            }
+            Some(FileMerge::Conflict(FileConflict {
+                path: path.clone(),
+                original: original.cloned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+            }))
+        })
+        .collect()
+}
+
+/// ✅ Problem with a regenerated file, if any (conflict markers left behind)
+pub fn check_resolution(content: &str) -> Option<String> {
+    if content.trim().is_empty() {
+        return Some("The file is empty".to_string());
+    }
+    content
+        .lines()
+        .any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>"))
+        .then(|| "The file still contains conflict markers".to_string())
+}
+
+/// 📊 What a rebase job did with a repository's open PRs
+#[derive(Debug, Default)]
+pub struct RefreshSummary {
+    /// 🔀 PRs moved onto the new base
+    pub rebased: Vec<u64>,
+    /// 🟠 PRs left for a maintainer
+    pub stale: Vec<u64>,
+    /// ⏳ PRs GitHub is still checking
+    pub pending: Vec<u64>,
+}
+
+/// 🔀 A PR put back on top of its base branch
+#[derive(Debug)]
+struct Rebased {
+    base: String,
+    head: String,
+    regenerated: Vec<String>,
+}
+
+/// 🧰 What rebasing the PRs of one repository needs
+struct RebaseRun<'a> {
+    pool: &'a PgPool,
+    config: &'a Config,
+    llm: &'a LlmManager,
+    /// 🔑 Where and how to clone the repository, for the formatter pass
+    github_config: GitHubConfig,
+    github: GitHubClient,
+    owner: String,
+    repo: String,
+    project: &'a Project,
+    settings: &'a ProjectConfig,
+    /// 🤖 The account Feedbacker commits as (PRs with other authors are left alone)
+    username: &'a str,
+}
+
+/// 📥 Queue a rebase of a repository's open PRs, unless one is already waiting
+/// Returns whether it was queued
+pub async fn queue_rebase(app_state: &AppState, repository: &str) -> Result<bool> {
+    let payload = json!({ "repository": repository });
+    if BackgroundJob::is_queued(&app_state.db_pool, REBASE_JOB, &payload).await? {
+        debug!("⏳ Rebase of {} is already queued", repository);
+        return Ok(false);
+    }
+
+    let job = NewBackgroundJob::new(REBASE_JOB, payload)
+        .with_priority(JobPriority::Bulk)
+        .with_max_retries(MAX_REBASE_RETRIES);
+    app_state.jobs.enqueue(&job).await?;
+    debug!("📥 Queued rebase of {}", repository);
+    Ok(true)
+}
+
+/// 🔧 Worker pool handler that rebases the open PRs of a repository
+/// PRs GitHub is still checking fail the job, so it is retried a bit later
+pub fn rebase_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    let llm = app_state.llm_manager.clone();
+    worker::handler(move |job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        let llm = llm.clone();
+        async move {
+            let repository = job.payload["repository"]
+                .as_str()
+                .context("Rebase job has no repository")?;
+            let summary = refresh_pull_requests(&db_pool, &config, &llm, repository).await?;
+            if !summary.pending.is_empty() {
+                anyhow::bail!(
+                    "GitHub is still checking whether {} PRs of {} can be merged",
+                    summary.pending.len(),
+                    repository
+                );
+            }
+            Ok(())
+        }
+    })
+}
+
+/// 🔀 Rebase the open Feedbacker PRs of a repository that fell behind or conflict
+/// The first project of the repository with `auto_rebase` decides the endpoint,
+/// system message and model routing
+pub async fn refresh_pull_requests(
+    pool: &PgPool,
+    config: &Config,
+    llm: &LlmManager,
+    repository: &str,
+) -> Result<RefreshSummary> {
+    let mut summary = RefreshSummary::default();
+    let Some((project, settings)) = Project::list_by_repository(pool, repository)
+        .await?
+        .into_iter()
+        .find_map(|project| {
+            let settings = project.settings().ok()?;
+            settings
+                .pull_requests
+                .auto_rebase
+                .then_some((project, settings))
+        })
+    else {
+        debug!("🤷 No project of {} rebases its PRs", repository);
+        return Ok(summary);
+    };
+    // 🎨 Regenerated files follow the repository's learned style
+    let project = style_guide::styled(pool, &project).await?;
+
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let github_config = scm::github_config(pool, &config.github, &project).await?;
+    let run = RebaseRun {
+        pool,
+        config,
+        llm,
+        github: GitHubClient::new(github_config.clone())?,
+        github_config,
+        owner,
+        repo,
+        project: &project,
+        settings: &settings,
+        username: &config.github.username,
+    };
+
+    for listed in run
+        .github
+        .list_open_pull_requests(&run.owner, &run.repo)
+        .await?
+    {
+        let ours = listed
+            .head
+            .name
+            .starts_with(&config.github.default_branch_prefix)
+            && listed
+                .head
+                .repo
+                .as_ref()
+                .is_some_and(|head| head.full_name.eq_ignore_ascii_case(&project.repository));
+        if !ours {
+            continue;
+        }
+        let Some(feedback) =
+            Feedback::find_by_pull_request(pool, &project.repository, listed.number).await?
+        else {
+            continue;
+        };
+
+        let pr = run
+            .github
+            .get_pull_request(&run.owner, &run.repo, listed.number)
+            .await?;
+        let freshness = Freshness::of(&pr);
+        match freshness {
+            Freshness::Pending => summary.pending.push(pr.number),
+            Freshness::Blocked => debug!(
+                "🛡️ PR #{} of {} waits on reviews or checks",
+                pr.number, project.repository
+            ),
+            _ if freshness.needs_rebase() => {
+                let span = Stage::PullRequest.span(Some(feedback.id));
+                match feedback_trace::traced(span, run.rebase(&feedback, &pr)).await {
+                    Ok(Some(rebased)) => {
+                        info!(
+                            "🔀 Rebased PR #{} of {} onto {} ({} files regenerated)",
+                            pr.number,
+                            project.repository,
+                            rebased.base,
+                            rebased.regenerated.len()
+                        );
+                        FeedbackEvent::record(
+                            pool,
+                            feedback.id,
+                            FeedbackEvent::PULL_REQUEST_REBASED,
+                            json!({
+                                "number": pr.number,
+                                "url": pr.html_url,
+                                "base": rebased.base,
+                                "head": rebased.head,
+                                "regenerated_files": rebased.regenerated,
+                            }),
+                        )
+                        .await?;
+                        summary.rebased.push(pr.number);
+                    }
+                    // 🕰️ GitHub's view was older than the branch
+                    Ok(None) => summary.pending.push(pr.number),
+                    // ⏸️ Held back for the rate limit: the job is retried later
+                    Err(e) if e.chain().any(|cause| cause.is::<RateLimited>()) => return Err(e),
+                    Err(e) => {
+                        warn!(
+                            "🟠 Leaving PR #{} of {} {}: {:#}",
+                            pr.number,
+                            project.repository,
+                            freshness.as_str(),
+                            e
+                        );
+                        FeedbackEvent::record(
+                            pool,
+                            feedback.id,
+                            FeedbackEvent::PULL_REQUEST_STALE,
+                            json!({
+                                "number": pr.number,
+                                "url": pr.html_url,
+                                "state": freshness.as_str(),
+                                "reason": format!("{:#}", e),
+                            }),
+                        )
+                        .await?;
+                        summary.stale.push(pr.number);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    info!(
+        "🔀 Open PRs of {}: {} rebased, {} left for a maintainer, {} still being checked",
+        repository,
+        summary.rebased.len(),
+        summary.stale.len(),
+        summary.pending.len()
+    );
+    Ok(summary)
+}
+
+impl RebaseRun<'_> {
+    /// 🔀 Put a PR back on top of its base branch (None when it already is)
+    async fn rebase(&self, feedback: &Feedback, pr: &PullRequestInfo) -> Result<Option<Rebased>> {
+        let (owner, repo) = (self.owner.as_str(), self.repo.as_str());
+        let commits = self
+            .github
+            .pull_request_commits(owner, repo, pr.number)
+            .await?;
+        if let Some(other) = commits.iter().find(|commit| {
+            !commit
+                .author
+                .as_deref()
+                .is_some_and(|author| author.eq_ignore_ascii_case(self.username))
+        }) {
+            anyhow::bail!(
+                "It has commits by {}, which a rebase would overwrite",
+                other.author.as_deref().unwrap_or("an unknown author")
+            );
+        }
+        if let Some(thread) = self
+            .github
+            .unresolved_review_threads(owner, repo, pr.number, 1)
+            .await?
+            .first()
+        {
+            anyhow::bail!(
+                "{} has an unresolved review thread on {}, which a rebase would mark outdated",
+                thread.author,
+                thread.path
+            );
+        }
+
+        let base = self.github.branch_head(owner, repo, &pr.base.name).await?;
+        let merge_base = self
+            .github
+            .merge_base(owner, repo, &base, &pr.head.sha)
+            .await?;
+        if merge_base == base {
+            return Ok(None);
+        }
+
+        let original = self.github.blob_tree(owner, repo, &merge_base).await?;
+        let ours = self.github.blob_tree(owner, repo, &pr.head.sha).await?;
+        let theirs = self.github.blob_tree(owner, repo, &base).await?;
+        let merges = merge_trees(&original, &ours, &theirs);
+
+        let conflicts = merges
+            .iter()
+            .filter(|merge| matches!(merge, FileMerge::Conflict(_)))
+            .count();
+        if conflicts > MAX_REGENERATED_FILES {
+            anyhow::bail!(
+                "{} files conflict with {}, more than the {} regenerated automatically",
+                conflicts,
+                pr.base.name,
+                MAX_REGENERATED_FILES
+            );
+        }
+
+        let mut changes = Vec::new();
+        let mut regenerated = Vec::new();
+        if conflicts > 0 {
+            let template = PromptBook::load(self.pool, feedback.id, &[names::REBASE_FILE])
+                .await?
+                .template(names::REBASE_FILE)?;
+            let trace = ExchangeTrace::new(
+                names::REBASE_FILE,
+                feedback.id,
+                self.project.id,
+                self.settings.prompt_logging,
+            )
+            .with_routing(self.settings.model_routing.clone())
+            .with_provider(feedback.submitter_llm_provider(self.pool).await?);
+            for merge in merges {
+                match merge {
+                    FileMerge::Carry(change) => changes.push(change),
+                    FileMerge::Conflict(conflict) => {
+                        let change = self
+                            .regenerate(feedback, &template, &trace, &conflict)
+                            .await
+                            .with_context(|| {
+                                format!("Could not resolve the conflict in {}", conflict.path)
+                            })?;
+                        changes.push(change);
+                        regenerated.push(conflict.path);
+                    }
+                }
+            }
+            // 🎨 Carried files were formatted when the PR was opened; these weren't
+            self.format_regenerated(feedback, &pr.base.name, &mut changes, &regenerated)
+                .await?;
+        } else {
+            changes.extend(merges.into_iter().filter_map(|merge| match merge {
+                FileMerge::Carry(change) => Some(change),
+                FileMerge::Conflict(_) => None,
+            }));
+        }
+
+        let message = commits
+            .first()
+            .map(|commit| commit.message.clone())
+            .unwrap_or_else(|| format!("Feedbacker changes for #{}", pr.number));
+        let head = self
+            .github
+            .commit_tree(owner, repo, &base, &changes, &message)
+            .await?;
+        self.github
+            .force_update_branch(owner, repo, &pr.head.name, &head)
+            .await?;
+
+        Ok(Some(Rebased {
+            base,
+            head,
+            regenerated,
+        }))
+    }
+
+    /// ✍️ Re-apply the PR's change to a file on top of the base branch's new version
+    async fn regenerate(
+        &self,
+        feedback: &Feedback,
+        template: &PromptTemplate,
+        trace: &ExchangeTrace,
+        conflict: &FileConflict,
+    ) -> Result<TreeChange> {
+        let (Some(ours_entry), Some(theirs_entry)) = (&conflict.ours, &conflict.theirs) else {
+            anyhow::bail!("One side removed the file and the other changed it");
+        };
+        let versions = self
+            .github
+            .merge_versions(
+                &self.owner,
+                &self.repo,
+                conflict.original.as_ref().map(|entry| entry.sha.as_str()),
+                &ours_entry.sha,
+                &theirs_entry.sha,
+            )
+            .await?;
+        let original = versions.original.map(prompt_text).transpose()?;
+        let ours = prompt_text(versions.ours)?;
+        let theirs = prompt_text(versions.theirs)?;
+
+        let prompt = template.render(&HashMap::from([
+            ("repository", self.project.repository.clone()),
+            ("request", feedback.content.clone()),
+            ("file_path", conflict.path.clone()),
+            ("original_content", original.unwrap_or_default()),
+            ("pull_request_content", ours),
+            ("base_content", theirs),
+        ]))?;
+        let request = CompletionRequest::new(self.project.system_message.clone(), prompt)
+            .traced(Some(trace.clone()));
+        let (file, response) = self
+            .llm
+            .complete_structured(&request, |file: &GeneratedFile| {
+                match check_resolution(&strip_code_fence(&file.content)) {
+                    Some(problem) => Err(vec![problem]),
+                    None => Ok(()),
+                }
+            })
+            .await?;
+        feedback
+            .record_llm_provider(self.pool, response.provider)
+            .await?;
+        Ok(TreeChange::Text {
+            path: conflict.path.clone(),
+            mode: ours_entry.mode.clone(),
+            content: strip_code_fence(&file.content),
+        })
+    }
+
+    /// 🎨 Run the formatter pass over the regenerated files, in a sandbox copy of
+    /// the base branch, and put what the formatters made of them in `changes`
+    async fn format_regenerated(
+        &self,
+        feedback: &Feedback,
+        base_branch: &str,
+        changes: &mut [TreeChange],
+        regenerated: &[String],
+    ) -> Result<()> {
+        if !self.settings.formatting.enabled {
+            return Ok(());
+        }
+        let cache = CloneCache::new(
+            &self.config.github.clone_cache_dir,
+            self.config.github.clone_cache_size,
+        );
+        let options = CloneOptions {
+            branch: Some(base_branch.to_string()),
+            scope: self.settings.scope()?,
+            token: Some(self.github_config.token.clone()),
+            ..Default::default()
+        };
+        let repository = self.project.repository.clone();
+        let clone_url = github_clone_url(&self.github_config.api_base_url, &repository);
+        let format_settings = self.settings.formatting.clone();
+        let sandbox_config = self.config.sandbox.clone();
+        let git = Stage::Git.span(Some(feedback.id));
+        let (setup_files, tools, sandbox) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let workspace = feedback_trace::traced_blocking(git, || {
+                cache.checkout(&repository, &clone_url, &options)
+            })?;
+            let setup_files: Vec<SourceFile> = workspace
+                .list_files()?
+                .into_iter()
+                .filter(|path| formatting::is_setup_file(path))
+                .filter_map(|path| {
+                    let content = workspace.read_file(&path)?;
+                    Some(SourceFile { path, content })
+                })
+                .collect();
+            let tools = formatting::tools_for(&format_settings, &setup_files);
+            let sandbox = if tools.is_empty() {
+                None
+            } else {
+                Some(Sandbox::create(&sandbox_config, workspace.root())?)
+            };
+            Ok((setup_files, tools, sandbox))
+        })
+        .await
+        .context("Checkout task panicked")??;
+        let Some(sandbox) = sandbox else {
+            return Ok(());
+        };
+
+        let mut improvements: Vec<CodeImprovement> = changes
+            .iter()
+            .filter_map(|change| match change {
+                TreeChange::Text { path, content, .. } if regenerated.contains(path) => {
+                    Some(CodeImprovement {
+                        file_path: path.clone(),
+                        description: "Regenerated on top of the base branch".to_string(),
+                        change_type: ChangeType::Modify,
+                        original_content: None,
+                        new_content: content.clone(),
+                        line_number: None,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        sandbox.apply(&improvements)?;
+        formatting::format_generated(
+            self.pool,
+            feedback.id,
+            &sandbox,
+            &tools,
+            &setup_files,
+            &mut improvements,
+            |_| Ok(()),
+        )
+        .await?;
+
+        for change in changes.iter_mut() {
+            if let TreeChange::Text { path, content, .. } = change {
+                if let Some(improvement) = improvements
+                    .iter()
+                    .find(|improvement| &improvement.file_path == path)
+                {
+                    *content = improvement.new_content.clone();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 📄 Text of a blob small enough for a prompt
+fn prompt_text(blob: BlobContent) -> Result<String> {
+    if blob.size > MAX_REGENERATED_FILE_BYTES as u64 {
+        anyhow::bail!(
+            "It is larger than {} KiB",
+            MAX_REGENERATED_FILE_BYTES / 1024
+        );
+    }
+    blob.text.context("It is not a text file")
+}
+
+// 🧪 Tests - Behind today, current tomorrow!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(state: &str, mergeable: Option<bool>, mergeable_state: Option<&str>) -> PullRequestInfo {
+        serde_json::from_value(json!({
+            "number": 7,
+            "html_url": "https://github.com/aye-is/feedbacker/pull/7",
+            "state": state,
+            "mergeable": mergeable,
+            "mergeable_state": mergeable_state,
+            "head": { "ref": "feedbacker/docs-1234", "sha": "bbb", "repo": null },
+            "base": { "ref": "main", "sha": "aaa", "repo": null }
+        }))
+        .unwrap()
+    }
+
+    fn tree(files: &[(&str, &str)]) -> BlobTree {
+        files
+            .iter()
+            .map(|(path, sha)| {
+                (
+                    path.to_string(),
+                    TreeEntry {
+                        mode: "100644".to_string(),
+                        sha: sha.to_string(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_freshness() {
+        let of = |state, mergeable, mergeable_state| {
+            Freshness::of(&pr(state, mergeable, mergeable_state))
+        };
+        assert_eq!(of("open", Some(true), Some("clean")), Freshness::Current);
+        assert_eq!(of("open", Some(true), Some("unstable")), Freshness::Current);
+        assert_eq!(of("open", Some(true), Some("behind")), Freshness::Behind);
+        assert_eq!(
+            of("open", Some(false), Some("dirty")),
+            Freshness::Conflicting
+        );
+        assert_eq!(of("open", Some(true), Some("blocked")), Freshness::Blocked);
+        assert_eq!(of("open", None, Some("unknown")), Freshness::Pending);
+        assert_eq!(of("open", None, None), Freshness::Pending);
+        assert_eq!(
+            of("open", Some(false), Some("draft")),
+            Freshness::Conflicting
+        );
+        assert_eq!(of("open", Some(true), Some("draft")), Freshness::Current);
+        assert_eq!(of("closed", Some(false), Some("dirty")), Freshness::Closed);
+
+        assert!(Freshness::Behind.needs_rebase());
+        assert!(Freshness::Conflicting.needs_rebase());
+        assert!(!Freshness::Blocked.needs_rebase());
+        assert!(!Freshness::Pending.needs_rebase());
+        println!("✅ PR freshness test passed!");
+    }
+
+    #[test]
+    fn test_merge_trees() {
+        let original = tree(&[
+            ("README.md", "r1"),
+            ("src/lib.rs", "l1"),
+            ("src/old.rs", "o1"),
+            ("src/main.rs", "m1"),
+        ]);
+        // 🔀 The PR edits lib.rs and main.rs, removes old.rs and adds new.rs
+        let ours = tree(&[
+            ("README.md", "r1"),
+            ("src/lib.rs", "l2"),
+            ("src/main.rs", "m2"),
+            ("src/new.rs", "n1"),
+        ]);
+        // 🌳 The base branch edits README.md and main.rs, and already has lib.rs's change
+        let theirs = tree(&[
+            ("README.md", "r2"),
+            ("src/lib.rs", "l2"),
+            ("src/old.rs", "o1"),
+            ("src/main.rs", "m3"),
+        ]);
+
+        let merges = merge_trees(&original, &ours, &theirs);
+        assert_eq!(merges.len(), 3);
+        assert_eq!(
+            merges[0],
+            FileMerge::Conflict(FileConflict {
+                path: "src/main.rs".to_string(),
+                original: original.get("src/main.rs").cloned(),
+                ours: ours.get("src/main.rs").cloned(),
+                theirs: theirs.get("src/main.rs").cloned(),
+            })
+        );
+        assert!(matches!(
+            &merges[1],
+            FileMerge::Carry(TreeChange::Blob { path, entry }) if path == "src/new.rs" && entry.sha == "n1"
+        ));
+        assert_eq!(
+            merges[2],
+            FileMerge::Carry(TreeChange::Removed {
+                path: "src/old.rs".to_string()
+            })
+        );
+
+        // 🗑️ Removed by the PR but changed on the base branch: a conflict
+        let theirs = tree(&[("src/old.rs", "o2")]);
+        let merges = merge_trees(&tree(&[("src/old.rs", "o1")]), &tree(&[]), &theirs);
+        assert!(matches!(
+            &merges[0],
+            FileMerge::Conflict(FileConflict { ours: None, .. })
+        ));
+        println!("✅ Tree merge test passed!");
+    }
+
+    #[test]
+    fn test_check_resolution() {
+        assert_eq!(check_resolution("fn main() {}\n"), None);
+        assert!(check_resolution("  \n").is_some());
+        assert!(
+            check_resolution("<<<<<<< HEAD\nfn a() {}\n=======\nfn b() {}\n>>>>>>> main\n")
+                .is_some()
+        );
+        // 📝 A setext heading underline is not a conflict marker
+        assert_eq!(check_resolution("Title\n=======\n"), None);
+        println!("✅ Conflict resolution check test passed!");
+    }
+
+    #[test]
+    fn test_prompt_text() {
+        let text = |text: Option<&str>, size: u64| BlobContent {
+            text: text.map(str::to_string),
+            size,
+        };
+        assert_eq!(
+            prompt_text(text(Some("fn a() {}\n"), 10)).unwrap(),
+            "fn a() {}\n"
+        );
+        assert!(prompt_text(text(None, 10)).is_err());
+        assert!(prompt_text(text(Some("big"), 100 * 1024)).is_err());
+        println!("✅ Prompt text test passed!");
+    }
+}
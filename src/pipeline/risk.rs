@@ -0,0 +1,765 @@
+// ⚖️ PR Risk Stage - How Careful Should the Reviewer Be? ⚖️
+// Before a PR is opened, the model reads its diff and scores the risk (low,
+// medium or high) with a short rationale for the reviewer. Fixed rules score the
+// same change on what it touches: how many files and lines, whether it edits
+// database migrations, CI or configuration, and whether its tests grew or shrank
+// along with the code. Their level is a floor the model can raise but not lower
+// (the change it's judging was generated by a model too), and it's the whole
+// score when the model can't be reached. The level is added to the PR as a
+// `risk: <level>` label and a section of its body. At or above the project's
+// `pull_requests.approval_risk` (high unless configured) the PR is opened as a
+// draft, so it can't be merged before a maintainer approves it by marking it
+// ready for review
+// Created with love by Aye & Hue - Measure twice, merge once! ✨
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    github::{ChangeType, CodeImprovement, FeedbackProcessingRequest},
+    jobs::repo_health::is_test_path,
+    llm::{
+        prompts::{names, PromptTemplate},
+        CompletionRequest, ExchangeTrace, LlmManager, OutputSchema, PromptBook, StructuredOutput,
+    },
+    models::{PullRequestSettings, RiskLevel},
+    pipeline::{diff::unified_diff, splitting::change_size},
+};
+
+/// 🏷️ Prefix of the label carrying a PR's risk level
+pub const RISK_LABEL_PREFIX: &str = "risk: ";
+
+/// 📏 Changed lines from which a change counts as medium-sized, then large
+const MEDIUM_CHANGE_LINES: usize = 100;
+const LARGE_CHANGE_LINES: usize = 400;
+
+/// 📁 Files touched from which a change counts as wide, then sprawling
+const WIDE_CHANGE_FILES: usize = 4;
+const SPRAWLING_CHANGE_FILES: usize = 10;
+
+/// 🎚️ Scores from which a change is medium, then high risk
+const MEDIUM_RISK_SCORE: u32 = 2;
+const HIGH_RISK_SCORE: u32 = 4;
+
+/// 📋 Paths listed per factor before the rest are counted
+const LISTED_PATHS: usize = 3;
+
+/// 📝 Longest rationale the model may give, in characters
+const MAX_RATIONALE_CHARS: usize = 500;
+
+/// 📁 What a changed file is, as far as risk goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// 🗄️ Database migrations
+    Migration,
+    /// 🔁 CI pipelines
+    Ci,
+    /// ⚙️ Configuration, manifests and build files
+    Config,
+    /// 🧪 Tests
+    Test,
+    /// 📚 Documentation
+    Docs,
+    /// 🦀 Everything else
+    Source,
+}
+
+/// 🔍 What kind of file a path is
+pub fn classify(path: &str) -> FileKind {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or_default();
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .unwrap_or_default();
+    let directories: Vec<&str> = lower.split('/').rev().skip(1).collect();
+
+    if lower.starts_with(".github/workflows/")
+        || lower.starts_with(".circleci/")
+        || lower.starts_with(".buildkite/")
+        || matches!(
+            name,
+            ".gitlab-ci.yml" | ".travis.yml" | "jenkinsfile" | "azure-pipelines.yml"
+        )
+    {
+        return FileKind::Ci;
+    }
+    if extension == "sql"
+        || name.starts_with("migration")
+        || directories
+            .iter()
+            .any(|dir| matches!(*dir, "migrations" | "migration" | "migrate"))
+    {
+        return FileKind::Migration;
+    }
+    if is_test_path(path) {
+        return FileKind::Test;
+    }
+    if matches!(extension, "md" | "rst" | "adoc" | "txt") || directories.contains(&"docs") {
+        return FileKind::Docs;
+    }
+    if name.starts_with(".env")
+        || matches!(
+            name,
+            "dockerfile" | "makefile" | "build.rs" | "package.json" | "go.mod" | "go.sum"
+        )
+        || name.ends_with(".lock")
+        || name.ends_with("-lock.json")
+        || matches!(
+            extension,
+            "toml" | "yaml" | "yml" | "ini" | "cfg" | "conf" | "properties"
+        )
+    {
+        return FileKind::Config;
+    }
+    FileKind::Source
+}
+
+/// ⚖️ How risky a change looks, and why
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RiskAssessment {
+    pub level: RiskLevel,
+    pub score: u32,
+    pub files_touched: usize,
+    pub lines_changed: usize,
+    /// 🧪 Net lines of test files added (negative when tests shrink)
+    pub test_lines_delta: i64,
+    /// 📋 What raised the rule-based score, one reason each
+    pub factors: Vec<String>,
+    /// 🤖 The model's reasoning (None: scored by the rules alone)
+    pub rationale: Option<String>,
+}
+
+impl RiskAssessment {
+    /// 🏷️ The label added to the PR
+    pub fn label(&self) -> String {
+        format!("{}{}", RISK_LABEL_PREFIX, self.level.as_str())
+    }
+
+    /// 🚦 Whether a PR this risky needs a maintainer's approval
+    pub fn requires_approval(&self, settings: &PullRequestSettings) -> bool {
+        settings
+            .approval_risk
+            .is_some_and(|threshold| self.level >= threshold)
+    }
+
+    /// 🐙 The project's PR settings plus the risk label, and a draft when
+    /// approval is required
+    pub fn apply_to(&self, settings: &PullRequestSettings) -> PullRequestSettings {
+        let mut settings = settings.clone();
+        // 🏷️ A risk label from the project settings would contradict ours
+        settings.labels.retain(|label| {
+            !label
+                .to_lowercase()
+                .starts_with(RISK_LABEL_PREFIX.trim_end())
+        });
+        settings.labels.push(self.label());
+        settings.draft |= self.requires_approval(&settings);
+        settings
+    }
+
+    /// 📝 The risk section of the PR body
+    pub fn section(&self, settings: &PullRequestSettings) -> String {
+        let tests = match self.test_lines_delta {
+            0 => "no test changes".to_string(),
+            delta => format!("tests {:+} lines", delta),
+        };
+        let scored_by = match &self.rationale {
+            Some(rationale) => format!("🤖 {}", rationale),
+            None => "_Scored by rules on the files and lines the change touches: the model could not score it._"
+                .to_string(),
+        };
+        let mut lines = vec![
+            format!("### ⚖️ Risk: {}", self.level.as_str()),
+            scored_by,
+            String::new(),
+            format!(
+                "- {} files, {} changed lines, {}",
+                self.files_touched, self.lines_changed, tests
+            ),
+        ];
+        lines.extend(self.factors.iter().map(|factor| format!("- {}", factor)));
+        if self.requires_approval(settings) {
+            lines.push(String::new());
+            lines.push(
+                "> ⚠️ Opened as a draft: changes this risky need a maintainer's approval. Mark it ready for review to approve it."
+                    .to_string(),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+/// 🤖 The model's verdict on a change
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ModelRisk {
+    pub level: RiskLevel,
+    /// 📝 Why, in a sentence or two for the reviewer
+    pub rationale: String,
+}
+
+impl StructuredOutput for ModelRisk {
+    fn output_schema() -> OutputSchema {
+        OutputSchema {
+            name: "submit_risk".to_string(),
+            description: "Submit how risky the pull request is to merge, and why".to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "level": { "type": "string", "enum": ["low", "medium", "high"] },
+                    "rationale": {
+                        "type": "string",
+                        "minLength": 1,
+                        "maxLength": MAX_RATIONALE_CHARS
+                    }
+                },
+                "required": ["level", "rationale"],
+                "additionalProperties": false
+            }),
+        }
+    }
+}
+
+/// ⚖️ How a change's risk gets scored: by the model when there is one, with the
+/// rules as its floor and fallback, else by the rules alone
+pub struct RiskScorer<'a> {
+    model: Option<ModelScorer<'a>>,
+}
+
+/// 🤖 What the model needs to score a change
+struct ModelScorer<'a> {
+    llm: &'a LlmManager,
+    template: PromptTemplate,
+    system_message: Option<String>,
+    trace: ExchangeTrace,
+}
+
+impl<'a> RiskScorer<'a> {
+    /// 📏 The rules alone (no LLM)
+    pub fn rules() -> Self {
+        Self { model: None }
+    }
+
+    /// 🤖 Model scoring for a feedback item, with its prompt version chosen
+    /// `trace` is the run's trace; its exchanges are logged under `pr_risk`
+    pub async fn model(
+        pool: &PgPool,
+        llm: &'a LlmManager,
+        feedback_id: Uuid,
+        system_message: Option<String>,
+        trace: &ExchangeTrace,
+    ) -> Result<Self> {
+        let template = PromptBook::load(pool, feedback_id, &[names::PR_RISK])
+            .await?
+            .template(names::PR_RISK)?;
+        Ok(Self {
+            model: Some(ModelScorer {
+                llm,
+                template,
+                system_message,
+                trace: trace.with_stage(names::PR_RISK),
+            }),
+        })
+    }
+
+    /// ⚖️ Score the changes of a processing request
+    /// A model that fails falls back to the rules rather than failing the PR
+    pub async fn assess(&self, request: &FeedbackProcessingRequest) -> RiskAssessment {
+        let rules = assess(&request.improvements);
+        let Some(model) = &self.model else {
+            return rules;
+        };
+
+        match ask_model(model, request, &rules).await {
+            Ok(verdict) => {
+                info!(
+                    "⚖️ {} scored {} risk by the model ({} by the rules)",
+                    request.branch_name,
+                    verdict.level.as_str(),
+                    rules.level.as_str()
+                );
+                RiskAssessment {
+                    level: verdict.level.max(rules.level),
+                    rationale: Some(verdict.rationale.trim().to_string()),
+                    ..rules
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️ Could not score the risk of {} with the model, using the rules: {:#}",
+                    request.branch_name, e
+                );
+                rules
+            }
+        }
+    }
+}
+
+/// 🤖 Ask the model to score a change from its diff, cut to fit the context window
+async fn ask_model(
+    model: &ModelScorer<'_>,
+    request: &FeedbackProcessingRequest,
+    rules: &RiskAssessment,
+) -> Result<ModelRisk> {
+    let factors = match rules.factors.is_empty() {
+        true => "- Nothing flagged".to_string(),
+        false => rules
+            .factors
+            .iter()
+            .map(|factor| format!("- {}", factor))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let render = |diff: String| {
+        model.template.render(&HashMap::from([
+            ("repository", request.repository.clone()),
+            ("request", request.feedback_content.trim().to_string()),
+            (
+                "summary",
+                format!(
+                    "{} files, {} changed lines, tests {:+} lines",
+                    rules.files_touched, rules.lines_changed, rules.test_lines_delta
+                ),
+            ),
+            ("factors", factors.clone()),
+            ("diff", diff),
+        ]))
+    };
+
+    // 🔢 Everything but the diff is required; the diff gets whatever is left
+    let skeleton = CompletionRequest {
+        output_schema: Some(ModelRisk::output_schema()),
+        ..CompletionRequest::new(model.system_message.clone(), render(String::new())?)
+    };
+    let budget = model.llm.context_budget(&skeleton)?;
+    let required = budget.count_request(&skeleton);
+    if let Some(overflow) = budget.overflow(required) {
+        anyhow::bail!("Request is too large to score: {}", overflow);
+    }
+    let diff = budget.truncate(
+        &unified_diff(&request.improvements),
+        budget.input_limit() - required,
+    );
+
+    let request = CompletionRequest::new(model.system_message.clone(), render(diff)?)
+        .traced(Some(model.trace.clone()));
+    let (verdict, _) = model
+        .llm
+        .complete_structured(&request, |_: &ModelRisk| Ok(()))
+        .await
+        .context("Risk score rejected")?;
+    Ok(verdict)
+}
+
+/// ⚖️ Score a change by what it touches (fixed rules, no LLM)
+pub fn assess(improvements: &[CodeImprovement]) -> RiskAssessment {
+    let mut score = 0;
+    let mut factors = Vec::new();
+    let mut add = |points: u32, factor: String| {
+        score += points;
+        factors.push(factor);
+    };
+
+    let files_touched = improvements.len();
+    let lines_changed: usize = improvements.iter().map(change_size).sum();
+    let paths_of = |kind: FileKind| -> Vec<&str> {
+        improvements
+            .iter()
+            .filter(|improvement| classify(&improvement.file_path) == kind)
+            .map(|improvement| improvement.file_path.as_str())
+            .collect()
+    };
+
+    // 📏 Size
+    if lines_changed >= LARGE_CHANGE_LINES {
+        add(2, format!("Large change ({} lines)", lines_changed));
+    } else if lines_changed >= MEDIUM_CHANGE_LINES {
+        add(1, format!("Medium-sized change ({} lines)", lines_changed));
+    }
+    if files_touched >= SPRAWLING_CHANGE_FILES {
+        add(2, format!("Touches {} files", files_touched));
+    } else if files_touched >= WIDE_CHANGE_FILES {
+        add(1, format!("Touches {} files", files_touched));
+    }
+
+    // 🗄️ Sensitive files
+    let migrations = paths_of(FileKind::Migration);
+    if !migrations.is_empty() {
+        add(
+            HIGH_RISK_SCORE,
+            format!("Changes database migrations: {}", list_paths(&migrations)),
+        );
+    }
+    let ci = paths_of(FileKind::Ci);
+    if !ci.is_empty() {
+        add(
+            HIGH_RISK_SCORE,
+            format!("Changes CI configuration: {}", list_paths(&ci)),
+        );
+    }
+    let config = paths_of(FileKind::Config);
+    if !config.is_empty() {
+        add(
+            1,
+            format!(
+                "Changes configuration or build files: {}",
+                list_paths(&config)
+            ),
+        );
+    }
+    let deleted: Vec<&str> = improvements
+        .iter()
+        .filter(|improvement| matches!(improvement.change_type, ChangeType::Delete))
+        .map(|improvement| improvement.file_path.as_str())
+        .collect();
+    if !deleted.is_empty() {
+        add(1, format!("Deletes files: {}", list_paths(&deleted)));
+    }
+
+    // 🧪 Test coverage
+    let test_lines_delta: i64 = improvements
+        .iter()
+        .filter(|improvement| classify(&improvement.file_path) == FileKind::Test)
+        .map(line_delta)
+        .sum();
+    let tests_touched = improvements.iter().any(|improvement| {
+        classify(&improvement.file_path) == FileKind::Test || inline_tests_changed(improvement)
+    });
+    let source_lines: usize = improvements
+        .iter()
+        .filter(|improvement| classify(&improvement.file_path) == FileKind::Source)
+        .map(change_size)
+        .sum();
+    if test_lines_delta < 0 {
+        add(2, format!("Removes {} lines of tests", -test_lines_delta));
+    } else if source_lines > 0 && !tests_touched {
+        add(
+            1,
+            format!("Code changes ({} lines) without test changes", source_lines),
+        );
+    }
+
+    let level = if score >= HIGH_RISK_SCORE {
+        RiskLevel::High
+    } else if score >= MEDIUM_RISK_SCORE {
+        RiskLevel::Medium
+    } else {
+        RiskLevel::Low
+    };
+    RiskAssessment {
+        level,
+        score,
+        files_touched,
+        lines_changed,
+        test_lines_delta,
+        factors,
+        rationale: None,
+    }
+}
+
+/// 📋 A few paths in backticks, then how many more
+fn list_paths(paths: &[&str]) -> String {
+    let mut listed: Vec<String> = paths
+        .iter()
+        .take(LISTED_PATHS)
+        .map(|path| format!("`{}`", path))
+        .collect();
+    if paths.len() > LISTED_PATHS {
+        listed.push(format!("{} more", paths.len() - LISTED_PATHS));
+    }
+    listed.join(", ")
+}
+
+/// ➕ Net lines a change adds to its file
+fn line_delta(improvement: &CodeImprovement) -> i64 {
+    let lines = |content: &str| content.lines().count() as i64;
+    let old = improvement.original_content.as_deref().map_or(0, lines);
+    match improvement.change_type {
+        ChangeType::Delete => -old,
+        ChangeType::Append => lines(&improvement.new_content),
+        ChangeType::Create | ChangeType::Modify => lines(&improvement.new_content) - old,
+    }
+}
+
+/// 🧪 Whether a source file's own tests (`#[test]`, `def test_`) changed in number
+fn inline_tests_changed(improvement: &CodeImprovement) -> bool {
+    let count = |content: &str| {
+        content
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| {
+                line.starts_with("#[test]")
+                    || line.starts_with("#[tokio::test")
+                    || line.starts_with("def test_")
+            })
+            .count()
+    };
+    let old = improvement.original_content.as_deref().map_or(0, count);
+    let new = match improvement.change_type {
+        ChangeType::Delete => 0,
+        ChangeType::Append => old + count(&improvement.new_content),
+        ChangeType::Create | ChangeType::Modify => count(&improvement.new_content),
+    };
+    new != old
+}
+
+// 🧪 Tests - Risky changes get a second look!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CustomLlmConfig, LlmConfig, LlmProvider, RoutingConfig};
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn change(
+        path: &str,
+        change_type: ChangeType,
+        original: Option<&str>,
+        new: &str,
+    ) -> CodeImprovement {
+        CodeImprovement {
+            file_path: path.to_string(),
+            description: "Change things".to_string(),
+            change_type,
+            original_content: original.map(str::to_string),
+            new_content: new.to_string(),
+            line_number: None,
+        }
+    }
+
+    fn lines(count: usize) -> String {
+        (0..count).map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(".github/workflows/ci.yml"), FileKind::Ci);
+        assert_eq!(classify(".gitlab-ci.yml"), FileKind::Ci);
+        assert_eq!(classify("db/migrations/0001_init.rs"), FileKind::Migration);
+        assert_eq!(classify("src/database/migrations.rs"), FileKind::Migration);
+        assert_eq!(classify("schema/users.sql"), FileKind::Migration);
+        assert_eq!(classify("tests/api_tests.rs"), FileKind::Test);
+        assert_eq!(classify("src/app.test.ts"), FileKind::Test);
+        assert_eq!(classify("README.md"), FileKind::Docs);
+        assert_eq!(classify("Cargo.toml"), FileKind::Config);
+        assert_eq!(classify("web/package-lock.json"), FileKind::Config);
+        assert_eq!(classify(".env.example"), FileKind::Config);
+        assert_eq!(classify("src/main.rs"), FileKind::Source);
+        println!("✅ Risk file classification test passed!");
+    }
+
+    #[test]
+    fn test_assess() {
+        // 🟢 A small fix with its test
+        let small = assess(&[
+            change("src/lib.rs", ChangeType::Modify, Some("a\nb\n"), "a\nc\n"),
+            change("tests/lib_tests.rs", ChangeType::Create, None, &lines(12)),
+        ]);
+        assert_eq!(small.level, RiskLevel::Low);
+        assert_eq!(small.test_lines_delta, 12);
+        assert!(small.factors.is_empty());
+        assert_eq!(small.label(), "risk: low");
+
+        // 🟡 Code without tests plus a manifest bump
+        let untested = assess(&[
+            change("src/lib.rs", ChangeType::Modify, Some("a\n"), "b\n"),
+            change("Cargo.toml", ChangeType::Modify, Some("x = 1\n"), "x = 2\n"),
+        ]);
+        assert_eq!(untested.level, RiskLevel::Medium);
+        assert_eq!(untested.factors.len(), 2);
+
+        // 🦀 Inline Rust tests count as touching tests
+        let inline = assess(&[change(
+            "src/lib.rs",
+            ChangeType::Modify,
+            Some("fn a() {}\n"),
+            "fn a() {}\n#[test]\nfn it_works() {}\n",
+        )]);
+        assert_eq!(inline.level, RiskLevel::Low);
+
+        // 🔴 Migrations, and tests going away
+        let migration = assess(&[change(
+            "migrations/0002.sql",
+            ChangeType::Create,
+            None,
+            "ALTER TABLE x;\n",
+        )]);
+        assert_eq!(migration.level, RiskLevel::High);
+        let removed_tests = assess(&[
+            change(
+                "src/lib.rs",
+                ChangeType::Modify,
+                Some(&lines(150)),
+                &lines(30),
+            ),
+            change(
+                "tests/lib_tests.rs",
+                ChangeType::Delete,
+                Some(&lines(40)),
+                "",
+            ),
+        ]);
+        assert_eq!(removed_tests.test_lines_delta, -40);
+        assert_eq!(removed_tests.level, RiskLevel::High);
+        println!("✅ Risk assessment test passed!");
+    }
+
+    #[test]
+    fn test_approval() {
+        let ci = assess(&[change(
+            ".github/workflows/ci.yml",
+            ChangeType::Modify,
+            Some("a\n"),
+            "b\n",
+        )]);
+        let settings = PullRequestSettings {
+            labels: vec!["feedbacker".to_string(), "Risk: low".to_string()],
+            ..Default::default()
+        };
+        assert!(ci.requires_approval(&settings));
+        let applied = ci.apply_to(&settings);
+        assert!(applied.draft);
+        assert_eq!(applied.labels, vec!["feedbacker", "risk: high"]);
+        assert!(ci.section(&settings).contains("### ⚖️ Risk: high"));
+        assert!(ci.section(&settings).contains("Scored by rules"));
+        let reviewed = RiskAssessment {
+            rationale: Some("Edits the release workflow".to_string()),
+            ..ci.clone()
+        };
+        assert!(reviewed
+            .section(&settings)
+            .contains("🤖 Edits the release workflow"));
+        assert!(!reviewed.section(&settings).contains("Scored by rules"));
+        assert!(ci.section(&settings).contains("ready for review"));
+
+        let never = PullRequestSettings {
+            approval_risk: None,
+            ..Default::default()
+        };
+        assert!(!ci.requires_approval(&never));
+        assert!(!ci.apply_to(&never).draft);
+        assert!(!ci.section(&never).contains("ready for review"));
+
+        let strict = PullRequestSettings {
+            approval_risk: Some(RiskLevel::Low),
+            ..Default::default()
+        };
+        assert!(assess(&[]).requires_approval(&strict));
+        println!("✅ Risk approval test passed!");
+    }
+
+    fn llm_for(server: &MockServer) -> LlmManager {
+        LlmManager::new(&LlmConfig {
+            openai: None,
+            anthropic: None,
+            custom: Some(CustomLlmConfig {
+                base_url: format!("{}/v1", server.uri()),
+                api_key: None,
+                default_model: "local-model".to_string(),
+                temperature: 0.2,
+                max_tokens: 1000,
+                context_window: Some(32_000),
+                small_model: None,
+                supports_tools: true,
+            }),
+            default_provider: LlmProvider::Custom,
+            timeout_seconds: 5,
+            max_retries: 0,
+            fallback_providers: Vec::new(),
+            circuit_breaker_threshold: 5,
+            circuit_breaker_cooldown_seconds: 60,
+            health_check_timeout_seconds: 1,
+            health_check_cache_seconds: 60,
+            routing: RoutingConfig {
+                tiers: Default::default(),
+                small_prompt_tokens: 0,
+            },
+        })
+    }
+
+    fn scorer_for(llm: &LlmManager) -> RiskScorer<'_> {
+        RiskScorer {
+            model: Some(ModelScorer {
+                llm,
+                template: crate::llm::prompts::builtin(names::PR_RISK).unwrap(),
+                system_message: None,
+                trace: ExchangeTrace::new(names::PR_RISK, Uuid::new_v4(), Uuid::new_v4(), false),
+            }),
+        }
+    }
+
+    fn request_for(improvements: Vec<CodeImprovement>) -> FeedbackProcessingRequest {
+        FeedbackProcessingRequest {
+            feedback_id: Uuid::new_v4(),
+            repository: "aye-is/demo".to_string(),
+            feedback_content: "Tidy things up".to_string(),
+            improvements,
+            commit_message: "Tidy".to_string(),
+            branch_name: "feedbacker/tidy".to_string(),
+            pull_request_settings: PullRequestSettings::default(),
+            test_plan: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_scoring() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_string_contains("submit_risk"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "model": "local-model",
+                "choices": [{
+                    "message": {
+                        "content": null,
+                        "tool_calls": [{ "function": { "arguments": json!({
+                            "level": "medium",
+                            "rationale": "Changes how errors are reported to callers."
+                        }).to_string() } }]
+                    }
+                }],
+                "usage": { "prompt_tokens": 10, "completion_tokens": 10 }
+            })))
+            .mount(&server)
+            .await;
+        let llm = llm_for(&server);
+        let scorer = scorer_for(&llm);
+
+        // 🤖 The model can raise the rules' level...
+        let small = request_for(vec![
+            change("src/lib.rs", ChangeType::Modify, Some("a\nb\n"), "a\nc\n"),
+            change("tests/lib_tests.rs", ChangeType::Create, None, &lines(12)),
+        ]);
+        let raised = scorer.assess(&small).await;
+        assert_eq!(raised.level, RiskLevel::Medium);
+        assert_eq!(
+            raised.rationale.as_deref(),
+            Some("Changes how errors are reported to callers.")
+        );
+
+        // 🔴 ...but not lower it
+        let ci = request_for(vec![change(
+            ".github/workflows/ci.yml",
+            ChangeType::Modify,
+            Some("a\n"),
+            "b\n",
+        )]);
+        assert_eq!(scorer.assess(&ci).await.level, RiskLevel::High);
+
+        // 📏 The rules score on their own when the model can't be reached
+        let unreachable = llm_for(&MockServer::start().await);
+        let fallback = scorer_for(&unreachable).assess(&small).await;
+        assert_eq!(fallback.level, RiskLevel::Low);
+        assert_eq!(fallback.rationale, None);
+        assert_eq!(
+            RiskScorer::rules().assess(&ci).await,
+            assess(&ci.improvements)
+        );
+        println!("✅ Model risk scoring test passed!");
+    }
+}
@@ -0,0 +1,332 @@
+// 🧪 Sandbox - Generated Code Proves Itself Before Anyone Reviews It! 🧪
+// A sandbox is a throwaway copy of a checkout (without `.git`) where generated
+// files are written and test commands are run with a scrubbed environment
+// (no tokens, no database URL) and a hard wall-clock limit.
+// The copy is deleted when the sandbox is dropped
+// Created with love by Aye & Hue - Trust, but run the tests! ✨
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::SandboxConfig;
+use crate::github::{ChangeType, CodeImprovement};
+
+/// 🌍 Environment variables passed through to sandboxed commands (everything else is dropped)
+const PASSTHROUGH_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+    "VIRTUAL_ENV",
+    "NODE_PATH",
+];
+
+/// ▶️ A command to run inside the sandbox
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestCommand {
+    /// 🔧 Executable
+    pub program: String,
+    /// 📋 Arguments
+    pub args: Vec<String>,
+}
+
+impl TestCommand {
+    /// ➕ Build a command from a program and its arguments
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// 🏷️ Shell-like rendering for logs, events, and PR test plans
+    pub fn display(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 📊 Result of one sandboxed command
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxOutcome {
+    /// ▶️ The command that ran
+    pub command: String,
+    /// ✅ Exited successfully within the time limit
+    pub passed: bool,
+    /// 🔢 Exit code (None when killed)
+    pub exit_code: Option<i32>,
+    /// ⏱️ Killed for running past the time limit
+    pub timed_out: bool,
+    /// ⏱️ Wall-clock duration
+    pub duration_ms: u64,
+    /// 📜 Tail of stdout + stderr
+    pub output: String,
+}
+
+/// 📦 A throwaway copy of a checkout
+#[derive(Debug)]
+pub struct Sandbox {
+    /// 📂 Root of the copy
+    root: PathBuf,
+    /// ⏱️ Per-command time limit
+    timeout: Duration,
+    /// 📏 Output bytes kept per command
+    max_output_bytes: usize,
+}
+
+impl Sandbox {
+    /// 📥 Copy `source` (minus `.git`) into a fresh sandbox directory
+    /// Blocking - call from `spawn_blocking`
+    pub fn create(config: &SandboxConfig, source: &Path) -> Result<Self> {
+        let root = PathBuf::from(&config.work_dir).join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&root)
+            .with_context(|| format!("Failed to create sandbox {}", root.display()))?;
+        let sandbox = Self {
+            root,
+            timeout: Duration::from_secs(config.timeout_seconds),
+            max_output_bytes: config.max_output_bytes,
+        };
+
+        copy_tree(source, &sandbox.root)?;
+        debug!("📦 Sandbox ready at {}", sandbox.root.display());
+        Ok(sandbox)
+    }
+
+    /// 📂 Root directory of the copy
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// ✍️ Write generated changes into the copy
+    pub fn apply(&self, improvements: &[CodeImprovement]) -> Result<()> {
+        for improvement in improvements {
+            let path = self.resolve(&improvement.file_path)?;
+            match improvement.change_type {
+                ChangeType::Delete => {
+                    if path.exists() {
+                        std::fs::remove_file(&path).with_context(|| {
+                            format!("Failed to delete {}", improvement.file_path)
+                        })?;
+                    }
+                }
+                ChangeType::Create | ChangeType::Modify | ChangeType::Append => {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&path, &improvement.new_content)
+                        .with_context(|| format!("Failed to write {}", improvement.file_path))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 📖 A file of the copy, as commands left it (None when missing or not text)
+    pub fn read_file(&self, relative: &str) -> Option<String> {
+        std::fs::read_to_string(self.resolve(relative).ok()?).ok()
+    }
+
+    /// ▶️ Run a command from `working_dir` (relative to the sandbox root)
+    pub async fn run(&self, working_dir: &str, command: &TestCommand) -> Result<SandboxOutcome> {
+        let cwd = if working_dir.is_empty() {
+            self.root.clone()
+        } else {
+            self.resolve(working_dir)?
+        };
+
+        let mut process = tokio::process::Command::new(&command.program);
+        process
+            .args(&command.args)
+            .current_dir(&cwd)
+            .env_clear()
+            .env("CI", "true")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for key in PASSTHROUGH_ENV {
+            if let Ok(value) = std::env::var(key) {
+                process.env(key, value);
+            }
+        }
+
+        info!("🧪 Running `{}` in sandbox", command.display());
+        let started = Instant::now();
+        let child = process
+            .spawn()
+            .with_context(|| format!("Failed to start `{}`", command.display()))?;
+
+        // ⏱️ Dropping the timed-out future kills the child (kill_on_drop)
+        let outcome = match tokio::time::timeout(self.timeout, child.wait_with_output()).await {
+            Ok(output) => {
+                let output = output.context("Failed to collect command output")?;
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                SandboxOutcome {
+                    command: command.display(),
+                    passed: output.status.success(),
+                    exit_code: output.status.code(),
+                    timed_out: false,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    output: tail(&text, self.max_output_bytes),
+                }
+            }
+            Err(_) => {
+                warn!(
+                    "⏱️ `{}` timed out after {}s",
+                    command.display(),
+                    self.timeout.as_secs()
+                );
+                SandboxOutcome {
+                    command: command.display(),
+                    passed: false,
+                    exit_code: None,
+                    timed_out: true,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    output: format!("Timed out after {}s", self.timeout.as_secs()),
+                }
+            }
+        };
+        Ok(outcome)
+    }
+
+    /// 🛡️ Map a repository-relative path into the sandbox, refusing escapes
+    fn resolve(&self, relative: &str) -> Result<PathBuf> {
+        let path = Path::new(relative);
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            anyhow::bail!("Path '{}' escapes the sandbox", relative);
+        }
+        Ok(self.root.join(path))
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.root) {
+            warn!("⚠️ Could not remove sandbox {}: {}", self.root.display(), e);
+        }
+    }
+}
+
+/// 📋 Recursively copy a directory, skipping `.git` and symlinks
+fn copy_tree(source: &Path, destination: &Path) -> Result<()> {
+    for entry in
+        std::fs::read_dir(source).with_context(|| format!("Failed to read {}", source.display()))?
+    {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = destination.join(entry.file_name());
+        if entry.file_name() == ".git" || file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// ✂️ Last `max_bytes` of the output, cut on a character boundary
+fn tail(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("…{}", &text[start..])
+}
+
+// 🧪 Tests - The sandbox must isolate, run, and give up on time!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(timeout_seconds: u64) -> SandboxConfig {
+        SandboxConfig {
+            work_dir: std::env::temp_dir()
+                .join("feedbacker-sandbox-tests")
+                .display()
+                .to_string(),
+            timeout_seconds,
+            max_output_bytes: 1024,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_applies_and_runs() {
+        let source = std::env::temp_dir().join(format!("feedbacker-src-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(source.join(".git")).unwrap();
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::write(source.join("src/lib.py"), "def parse(): pass\n").unwrap();
+
+        let sandbox = Sandbox::create(&config(30), &source).unwrap();
+        sandbox
+            .apply(&[CodeImprovement {
+                file_path: "tests/test_lib.py".to_string(),
+                description: "Add tests".to_string(),
+                change_type: ChangeType::Create,
+                original_content: None,
+                new_content: "def test_parse(): pass\n".to_string(),
+                line_number: None,
+            }])
+            .unwrap();
+
+        let check = TestCommand::new(
+            "sh",
+            &["-c", "test -f src/lib.py && test -f tests/test_lib.py && test ! -e .git && echo isolated"],
+        );
+        let outcome = sandbox.run("", &check).await.unwrap();
+        assert!(outcome.passed, "{}", outcome.output);
+        assert!(outcome.output.contains("isolated"));
+
+        let failing = sandbox
+            .run("src", &TestCommand::new("sh", &["-c", "exit 3"]))
+            .await
+            .unwrap();
+        assert!(!failing.passed);
+        assert_eq!(failing.exit_code, Some(3));
+
+        assert!(sandbox.run("../elsewhere", &check).await.is_err());
+        let root = sandbox.root().to_path_buf();
+        drop(sandbox);
+        assert!(!root.exists());
+        std::fs::remove_dir_all(source).unwrap();
+        println!("✅ Sandbox apply and run test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_timeout() {
+        let source = std::env::temp_dir().join(format!("feedbacker-src-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&source).unwrap();
+
+        let sandbox = Sandbox::create(&config(1), &source).unwrap();
+        let outcome = sandbox
+            .run("", &TestCommand::new("sleep", &["10"]))
+            .await
+            .unwrap();
+        assert!(outcome.timed_out);
+        assert!(!outcome.passed);
+        assert!(outcome.duration_ms < 5_000);
+
+        assert_eq!(tail("héllo", 4), "…llo");
+        std::fs::remove_dir_all(source).unwrap();
+        println!("✅ Sandbox timeout test passed!");
+    }
+}
@@ -0,0 +1,449 @@
+// ✂️ PR Splitting Stage - Big Changes, Bite-Sized Reviews! ✂️
+// When a generated change blows past the project's `max_pr_size`, we split it into
+// a series of smaller PRs grouped by directory. The parts are stacked: the first
+// targets the base branch and each later one starts from, and targets, the branch
+// of the part before it. Every PR then shows only its own files for review, while
+// its branch holds everything it builds on, so each part can be merged as soon as
+// the ones before it are. Every part is labelled with the risk of its own changes
+// (see pipeline::risk)
+// Created with love by Aye & Hue - Reviewers have feelings too! ✨
+
+use anyhow::Result;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::{
+    feedback_trace::{self, Stage},
+    github::{
+        ChangeType, CodeImprovement, FeedbackProcessingRequest, GitHubClient, NewPullRequest,
+        PullRequestResult,
+    },
+    models::PullRequestSettings,
+    pipeline::{build_pull_request, risk::RiskScorer},
+};
+
+/// 🏷️ Heading of the series section at the top of every split PR body
+const SERIES_HEADING: &str = "### 🧩 Pull Request Series";
+
+/// 📦 A group of improvements that will become one PR
+#[derive(Debug, Clone)]
+pub struct ChangeGroup {
+    /// 📁 Directories covered by this group
+    pub directories: Vec<String>,
+    /// 🔧 Improvements in this group
+    pub improvements: Vec<CodeImprovement>,
+    /// 📏 Estimated changed lines
+    pub size: usize,
+}
+
+/// 🧩 One PR in a (possibly single-element) series
+#[derive(Debug, Clone)]
+pub struct PullRequestPart {
+    /// 🌿 Branch holding this part's changes
+    pub branch_name: String,
+    /// 🎯 Branch this part starts from and targets: the base branch for the
+    /// first part, the previous part's branch after that
+    pub base_branch: String,
+    /// 🔧 Improvements committed to that branch
+    pub improvements: Vec<CodeImprovement>,
+    /// 🐙 PR to open for this part
+    pub pull_request: NewPullRequest,
+    /// 🏷️ Settings to open it with: the project's, plus its risk label
+    pub settings: PullRequestSettings,
+}
+
+/// 📏 Rough changed-line count for an improvement
+pub fn change_size(improvement: &CodeImprovement) -> usize {
+    let new_lines = improvement.new_content.lines().count();
+    let old_lines = improvement
+        .original_content
+        .as_deref()
+        .map(|content| content.lines().count())
+        .unwrap_or(0);
+
+    let size = match improvement.change_type {
+        ChangeType::Create | ChangeType::Append => new_lines,
+        ChangeType::Delete => old_lines,
+        ChangeType::Modify => match improvement.original_content.as_deref() {
+            Some(original) => line_difference(original, &improvement.new_content),
+            None => new_lines,
+        },
+    };
+
+    size.max(1)
+}
+
+/// ✂️ Split improvements into groups no larger than `max_size` changed lines
+/// Files in the same directory stay together unless the directory alone is too big
+pub fn split_changes(improvements: &[CodeImprovement], max_size: usize) -> Vec<ChangeGroup> {
+    let max_size = max_size.max(1);
+
+    // 📁 Group by directory, keeping first-seen order (which follows the plan order)
+    let mut order: Vec<String> = Vec::new();
+    let mut by_directory: HashMap<String, Vec<CodeImprovement>> = HashMap::new();
+    for improvement in improvements {
+        let directory = directory_of(&improvement.file_path);
+        if !by_directory.contains_key(&directory) {
+            order.push(directory.clone());
+        }
+        by_directory
+            .entry(directory)
+            .or_default()
+            .push(improvement.clone());
+    }
+
+    let mut groups: Vec<ChangeGroup> = Vec::new();
+    let mut current = empty_group();
+
+    for directory in order {
+        let files = by_directory.remove(&directory).unwrap_or_default();
+        let directory_size: usize = files.iter().map(change_size).sum();
+
+        if directory_size <= max_size {
+            if current.size + directory_size > max_size && !current.improvements.is_empty() {
+                groups.push(std::mem::replace(&mut current, empty_group()));
+            }
+            current.directories.push(directory);
+            current.size += directory_size;
+            current.improvements.extend(files);
+            continue;
+        }
+
+        // 🔪 Directory is too big on its own: split it file by file
+        if !current.improvements.is_empty() {
+            groups.push(std::mem::replace(&mut current, empty_group()));
+        }
+        for file in files {
+            let size = change_size(&file);
+            if current.size + size > max_size && !current.improvements.is_empty() {
+                groups.push(std::mem::replace(&mut current, empty_group()));
+            }
+            if current.directories.last() != Some(&directory) {
+                current.directories.push(directory.clone());
+            }
+            current.size += size;
+            current.improvements.push(file);
+        }
+        groups.push(std::mem::replace(&mut current, empty_group()));
+    }
+
+    if !current.improvements.is_empty() {
+        groups.push(current);
+    }
+
+    groups
+}
+
+/// 🐙 Turn a processing request into one PR, or a series when it's too big
+/// Each PR is scored by `scorer` on its own changes
+pub async fn plan_pull_requests(
+    request: &FeedbackProcessingRequest,
+    scorer: &RiskScorer<'_>,
+    base_branch: &str,
+    public_url: &str,
+) -> Result<Vec<PullRequestPart>> {
+    let total_size: usize = request.improvements.iter().map(change_size).sum();
+    let groups = match request.pull_request_settings.max_pr_size {
+        Some(max_size) if total_size > max_size => split_changes(&request.improvements, max_size),
+        _ => Vec::new(),
+    };
+
+    if groups.len() <= 1 {
+        let risk = scorer.assess(request).await;
+        return Ok(vec![PullRequestPart {
+            branch_name: request.branch_name.clone(),
+            base_branch: base_branch.to_string(),
+            improvements: request.improvements.clone(),
+            pull_request: build_pull_request(request, &risk, base_branch, public_url)?,
+            settings: risk.apply_to(&request.pull_request_settings),
+        }]);
+    }
+
+    let total = groups.len();
+    let branch_names: Vec<String> = (1..=total)
+        .map(|index| part_branch_name(&request.branch_name, index, total))
+        .collect();
+    let branch_refs: Vec<String> = branch_names
+        .iter()
+        .map(|branch| format!("`{}`", branch))
+        .collect();
+
+    let mut parts = Vec::with_capacity(total);
+    for (i, group) in groups.into_iter().enumerate() {
+        let part_request = FeedbackProcessingRequest {
+            improvements: group.improvements.clone(),
+            branch_name: branch_names[i].clone(),
+            ..request.clone()
+        };
+        // 🥞 Stacked on the previous part, so this one has everything it builds on
+        let part_base = match i {
+            0 => base_branch.to_string(),
+            _ => branch_names[i - 1].clone(),
+        };
+
+        let risk = scorer.assess(&part_request).await;
+        let mut pull_request = build_pull_request(&part_request, &risk, &part_base, public_url)?;
+        pull_request.title = format!(
+            "[{}/{}] {} ({})",
+            i + 1,
+            total,
+            pull_request.title,
+            group.directories.join(", ")
+        );
+        pull_request.body = format!("{}\n{}", series_section(i, &branch_refs), pull_request.body);
+
+        parts.push(PullRequestPart {
+            branch_name: branch_names[i].clone(),
+            base_branch: part_base,
+            settings: risk.apply_to(&request.pull_request_settings),
+            improvements: group.improvements,
+            pull_request,
+        });
+    }
+    Ok(parts)
+}
+
+/// 🐙 Open every part in order, then cross-link the series by PR number
+/// Each part's branch must already be pushed with its improvements
+pub async fn open_pull_request_series(
+    github: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    parts: &[PullRequestPart],
+) -> Result<Vec<PullRequestResult>> {
+    let mut results = Vec::with_capacity(parts.len());
+    for part in parts {
+        let result = feedback_trace::traced(
+            Stage::PullRequest.span(None),
+            github.create_pull_request(owner, repo, &part.pull_request, &part.settings),
+        )
+        .await?;
+        info!(
+            "🧩 Opened part {}/{}: #{}",
+            results.len() + 1,
+            parts.len(),
+            result.number
+        );
+        results.push(result);
+    }
+
+    if results.len() > 1 {
+        let numbers: Vec<u64> = results.iter().map(|result| result.number).collect();
+        for (i, (part, result)) in parts.iter().zip(&results).enumerate() {
+            let body = link_series(&part.pull_request.body, i, &numbers);
+            if let Err(e) = github
+                .update_pull_request_body(owner, repo, result.number, &body)
+                .await
+            {
+                // 🟡 The PRs exist; a stale branch list isn't worth failing over
+                warn!("⚠️ Could not cross-link PR #{}: {:#}", result.number, e);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// 🚀 Push every part's branch and open the PR (or series) for a processing request
+/// Parts are pushed in order, so each one starts from its predecessor's commit
+pub async fn publish_request(
+    github: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    request: &FeedbackProcessingRequest,
+    scorer: &RiskScorer<'_>,
+    base_branch: &str,
+    public_url: &str,
+) -> Result<Vec<PullRequestResult>> {
+    let parts = plan_pull_requests(request, scorer, base_branch, public_url).await?;
+    for part in &parts {
+        let part_request = FeedbackProcessingRequest {
+            improvements: part.improvements.clone(),
+            branch_name: part.branch_name.clone(),
+            ..request.clone()
+        };
+        github
+            .create_feedback_branch(owner, repo, &part.branch_name, Some(&part.base_branch))
+            .await?;
+        github.apply_improvements(&part_request).await?;
+    }
+
+    open_pull_request_series(github, owner, repo, &parts).await
+}
+
+/// 🔗 Swap the branch-based series section for one with PR numbers
+pub fn link_series(body: &str, part_index: usize, numbers: &[u64]) -> String {
+    let rest = body
+        .strip_prefix(SERIES_HEADING)
+        .and_then(|section| section.split_once("\n\n"))
+        .map(|(_, rest)| rest)
+        .unwrap_or(body);
+
+    let refs: Vec<String> = numbers
+        .iter()
+        .map(|number| format!("#{}", number))
+        .collect();
+    format!("{}\n{}", series_section(part_index, &refs), rest)
+}
+
+/// 🌿 Branch name for one part of a series
+fn part_branch_name(branch_name: &str, index: usize, total: usize) -> String {
+    format!("{}-part-{}-of-{}", branch_name, index, total)
+}
+
+/// 🧩 Series section listing every part (branches before opening, PR numbers after)
+fn series_section(part_index: usize, refs: &[String]) -> String {
+    let mut section = format!(
+        "{}\nThis is part {} of {}. Each part is stacked on the one before it, so it builds with everything it needs: merge them in order, starting with part 1 (GitHub retargets the next part once a merged part's branch is deleted).\n",
+        SERIES_HEADING,
+        part_index + 1,
+        refs.len()
+    );
+    for (i, reference) in refs.iter().enumerate() {
+        let marker = if i == part_index { " 👈 this PR" } else { "" };
+        section.push_str(&format!("- Part {}: {}{}\n", i + 1, reference, marker));
+    }
+    section
+}
+
+/// 📁 Directory a file lives in ("." for the repository root)
+fn directory_of(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((directory, _)) if !directory.is_empty() => directory.to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+/// 📏 Lines added plus lines removed, ignoring order
+fn line_difference(original: &str, updated: &str) -> usize {
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    for line in original.lines() {
+        *counts.entry(line).or_default() += 1;
+    }
+    for line in updated.lines() {
+        *counts.entry(line).or_default() -= 1;
+    }
+    counts.values().map(|count| count.unsigned_abs()).sum()
+}
+
+/// 📦 Fresh, empty group
+fn empty_group() -> ChangeGroup {
+    ChangeGroup {
+        directories: Vec::new(),
+        improvements: Vec::new(),
+        size: 0,
+    }
+}
+
+// 🧪 Tests - Splitting should be predictable and never lose a file!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn created(path: &str, lines: usize) -> CodeImprovement {
+        CodeImprovement {
+            file_path: path.to_string(),
+            description: format!("Create {}", path),
+            change_type: ChangeType::Create,
+            original_content: None,
+            new_content: "line\n".repeat(lines),
+            line_number: None,
+        }
+    }
+
+    #[test]
+    fn test_change_size() {
+        let modified = CodeImprovement {
+            change_type: ChangeType::Modify,
+            original_content: Some("a\nb\nc\n".to_string()),
+            new_content: "a\nB\nc\nd\n".to_string(),
+            ..created("src/lib.rs", 0)
+        };
+        assert_eq!(change_size(&modified), 3);
+        assert_eq!(change_size(&created("src/new.rs", 40)), 40);
+        println!("✅ Change size test passed!");
+    }
+
+    #[test]
+    fn test_split_changes_groups_by_directory() {
+        let improvements = vec![
+            created("src/api/a.rs", 30),
+            created("src/db/b.rs", 30),
+            created("src/api/c.rs", 30),
+            created("README.md", 10),
+            created("src/huge/d.rs", 80),
+            created("src/huge/e.rs", 80),
+        ];
+
+        let groups = split_changes(&improvements, 100);
+        let total: usize = groups.iter().map(|g| g.improvements.len()).sum();
+        assert_eq!(total, improvements.len());
+        assert_eq!(groups.len(), 3);
+
+        // 📁 src/api files stay together even though they weren't adjacent
+        assert_eq!(groups[0].directories, vec!["src/api", "src/db", "."]);
+        assert_eq!(groups[0].improvements[1].file_path, "src/api/c.rs");
+        // 🔪 An oversized directory is split file by file
+        assert_eq!(groups[1].directories, vec!["src/huge"]);
+        assert_eq!(groups[2].improvements.len(), 1);
+        assert!(groups.iter().all(|g| g.size <= 100));
+        println!("✅ PR splitting test passed!");
+    }
+
+    #[tokio::test]
+    async fn test_plan_pull_requests_series() {
+        let request = FeedbackProcessingRequest {
+            feedback_id: Uuid::new_v4(),
+            repository: "aye-is/feedbacker".to_string(),
+            feedback_content: "Split me".to_string(),
+            improvements: vec![created("src/a/x.rs", 60), created("src/b/y.rs", 60)],
+            commit_message: "Big change".to_string(),
+            branch_name: "feedbacker/big".to_string(),
+            pull_request_settings: PullRequestSettings {
+                max_pr_size: Some(100),
+                ..Default::default()
+            },
+            test_plan: vec![],
+        };
+
+        let parts = plan_pull_requests(&request, &RiskScorer::rules(), "main", "https://f.8b.is")
+            .await
+            .unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].branch_name, "feedbacker/big-part-2-of-2");
+        assert_eq!(parts[0].pull_request.title, "[1/2] Big change (src/a)");
+        assert!(parts[0].pull_request.body.contains("part 1 of 2"));
+        assert!(parts[0].pull_request.body.contains("merge them in order"));
+        // 🥞 Each later part targets the branch of the part before it
+        assert_eq!(parts[0].base_branch, "main");
+        assert_eq!(parts[0].pull_request.base, "main");
+        assert_eq!(parts[1].base_branch, "feedbacker/big-part-1-of-2");
+        assert_eq!(parts[1].pull_request.base, "feedbacker/big-part-1-of-2");
+        assert_eq!(parts[1].pull_request.head, "feedbacker/big-part-2-of-2");
+        assert_eq!(parts[0].settings.labels, vec!["risk: low"]);
+        assert!(parts[0]
+            .pull_request
+            .body
+            .contains("`feedbacker/big-part-2-of-2`"));
+
+        let linked = link_series(&parts[1].pull_request.body, 1, &[41, 42]);
+        assert!(linked.contains("- Part 1: #41\n"));
+        assert!(linked.contains("- Part 2: #42 👈 this PR"));
+        assert!(!linked.contains("`feedbacker/big-part-1-of-2`"));
+        assert!(linked.contains("AI-Generated Improvements"));
+
+        let unsplit = FeedbackProcessingRequest {
+            pull_request_settings: PullRequestSettings::default(),
+            ..request
+        };
+        assert_eq!(
+            plan_pull_requests(&unsplit, &RiskScorer::rules(), "main", "https://f.8b.is")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        println!("✅ PR series planning test passed!");
+    }
+}
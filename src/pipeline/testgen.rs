@@ -0,0 +1,1095 @@
+// 🧪 Test Generation Mode - Feedback Like "Add Tests for the Parser"! 🧪
+// Finds functions without coverage (from an uploaded LCOV report when there is
+// one, otherwise public functions no test mentions), narrows them to what the
+// request asks about, and has the LLM write test files - and only test files.
+// The generated tests are run in the sandbox, and the PR is opened only if
+// they compile and pass
+// Created with love by Aye & Hue - Untested code is just a rumour! ✨
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::{info, warn};
+
+use super::{
+    checkpoint::Checkpoint,
+    diff::record_proposed_diff,
+    docs::Language,
+    formatting, licensing,
+    planning::{strip_code_fence, GeneratedFile},
+    risk::RiskScorer,
+    sandbox::{Sandbox, SandboxOutcome, TestCommand},
+    splitting::publish_request,
+};
+use crate::{
+    budgets::{self, SpendLimits},
+    config::{Config, LlmProvider},
+    database::models::{Feedback, FeedbackEvent, Project},
+    feedback_trace::{self, Stage},
+    github::{
+        git::{github_clone_url, CloneCache, CloneOptions},
+        parse_repository, ChangeType, CodeImprovement, FeedbackProcessingRequest, GitHubClient,
+        PullRequestResult,
+    },
+    jobs::repo_health::{is_test_path, SourceFile},
+    llm::{
+        prompts::{names, PromptTemplate},
+        CompletionRequest, ExchangeTrace, LlmManager, PromptBook,
+    },
+    models::PathScope,
+    scm, style_guide,
+};
+
+/// 🏷️ Metadata key holding an uploaded LCOV coverage report
+pub const COVERAGE_METADATA_KEY: &str = "coverage_report";
+
+/// 📏 Largest coverage report accepted
+pub const MAX_COVERAGE_REPORT_BYTES: usize = 5 * 1024 * 1024;
+
+/// 📄 Source files covered per run (one test file each)
+pub const MAX_TEST_TARGETS: usize = 5;
+
+/// 📏 Files larger than this are not sent to the LLM
+const MAX_TESTGEN_FILE_BYTES: usize = 64 * 1024;
+
+/// 📏 Size of the example test shown to the LLM
+const MAX_EXAMPLE_TEST_BYTES: usize = 2 * 1024;
+
+/// 🙈 Request words that say nothing about *what* to test
+const FOCUS_STOP_WORDS: &[&str] = &[
+    "add",
+    "and",
+    "code",
+    "cover",
+    "coverage",
+    "for",
+    "function",
+    "functions",
+    "missing",
+    "module",
+    "more",
+    "please",
+    "some",
+    "test",
+    "tests",
+    "the",
+    "unit",
+    "with",
+    "write",
+];
+
+/// 🔍 A function without test coverage
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UncoveredFunction {
+    /// 📂 Repository-relative source path
+    pub path: String,
+    /// 🏷️ Function name
+    pub name: String,
+    /// 📍 1-based line of the definition
+    pub line: usize,
+}
+
+/// 📊 Function coverage parsed from an LCOV report
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    /// 📂 Per-file function hits, keyed by the path as written in the report
+    files: BTreeMap<String, Vec<FunctionHits>>,
+}
+
+/// 🔢 Hit count of one function in a coverage report
+#[derive(Debug, Clone)]
+struct FunctionHits {
+    line: usize,
+    name: String,
+    hits: u64,
+}
+
+impl CoverageReport {
+    /// 📥 Parse LCOV (`SF:`, `FN:`, `FNDA:` records); other records are ignored
+    pub fn parse_lcov(text: &str) -> Result<Self> {
+        let mut report = Self::default();
+        let mut current: Option<(String, Vec<FunctionHits>)> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if let Some(path) = line.strip_prefix("SF:") {
+                current = Some((path.to_string(), Vec::new()));
+            } else if line == "end_of_record" {
+                if let Some((path, functions)) = current.take() {
+                    report.files.entry(path).or_default().extend(functions);
+                }
+            } else if let Some(record) = line.strip_prefix("FN:") {
+                let (_, functions) = current
+                    .as_mut()
+                    .with_context(|| format!("FN record outside a file on line {}", index + 1))?;
+                let (start, name) = record
+                    .split_once(',')
+                    .with_context(|| format!("Malformed FN record on line {}", index + 1))?;
+                // 📍 Newer LCOV writes `FN:start,end,name`
+                let name = name.rsplit(',').next().unwrap_or(name);
+                functions.push(FunctionHits {
+                    line: start
+                        .parse()
+                        .with_context(|| format!("Bad line number on line {}", index + 1))?,
+                    name: name.to_string(),
+                    hits: 0,
+                });
+            } else if let Some(record) = line.strip_prefix("FNDA:") {
+                let (_, functions) = current
+                    .as_mut()
+                    .with_context(|| format!("FNDA record outside a file on line {}", index + 1))?;
+                let (hits, name) = record
+                    .split_once(',')
+                    .with_context(|| format!("Malformed FNDA record on line {}", index + 1))?;
+                let hits: u64 = hits
+                    .parse()
+                    .with_context(|| format!("Bad hit count on line {}", index + 1))?;
+                for function in functions.iter_mut().filter(|f| f.name == name) {
+                    function.hits += hits;
+                }
+            }
+        }
+
+        if report.files.is_empty() {
+            anyhow::bail!("Coverage report has no LCOV file records (SF:...end_of_record)");
+        }
+        Ok(report)
+    }
+
+    /// 🔍 Read the report uploaded with a run, if any
+    pub fn from_metadata(metadata: Option<&serde_json::Value>) -> Result<Option<Self>> {
+        match metadata
+            .and_then(|metadata| metadata.get(COVERAGE_METADATA_KEY))
+            .and_then(|report| report.as_str())
+        {
+            Some(text) => Self::parse_lcov(text).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 🔍 Functions with zero hits, mapped onto repository files
+    pub fn uncovered(&self, files: &[SourceFile]) -> Vec<UncoveredFunction> {
+        let mut uncovered = Vec::new();
+        let mut seen = HashSet::new();
+
+        for (report_path, functions) in &self.files {
+            let Some(file) = resolve_report_path(report_path, files) else {
+                continue;
+            };
+            if is_test_path(&file.path) {
+                continue;
+            }
+            for function in functions.iter().filter(|function| function.hits == 0) {
+                // 🦀 Rust names are mangled in LCOV; the source line has the real name
+                let name = function_name_at(&file.content, function.line)
+                    .unwrap_or_else(|| function.name.clone());
+                if seen.insert((file.path.clone(), name.clone())) {
+                    uncovered.push(UncoveredFunction {
+                        path: file.path.clone(),
+                        name,
+                        line: function.line,
+                    });
+                }
+            }
+        }
+        uncovered
+    }
+}
+
+/// 🧭 Report paths are often absolute CI paths; match the longest repository path suffix
+fn resolve_report_path<'a>(report_path: &str, files: &'a [SourceFile]) -> Option<&'a SourceFile> {
+    let report_path = report_path.replace('\\', "/");
+    let report_path = report_path.trim_start_matches("./");
+    files
+        .iter()
+        .filter(|file| {
+            report_path == file.path || report_path.ends_with(&format!("/{}", file.path))
+        })
+        .max_by_key(|file| file.path.len())
+}
+
+/// 🏷️ Name of the function defined on a 1-based line, if it looks like a definition
+fn function_name_at(content: &str, line: usize) -> Option<String> {
+    let text = content.lines().nth(line.checked_sub(1)?)?;
+    ["fn ", "def ", "function "].iter().find_map(|keyword| {
+        let start = text.find(keyword)? + keyword.len();
+        let name = identifier(&text[start..]);
+        (!name.is_empty()).then_some(name)
+    })
+}
+
+/// 🔍 Public functions in one source file
+pub fn public_functions(path: &str, content: &str) -> Vec<UncoveredFunction> {
+    let Some(language) = Language::from_path(path) else {
+        return Vec::new();
+    };
+
+    let mut functions = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if language == Language::Rust && trimmed.starts_with("#[cfg(test)]") {
+            break;
+        }
+
+        let definition = match language {
+            Language::Rust => [
+                "pub fn ",
+                "pub async fn ",
+                "pub const fn ",
+                "pub unsafe fn ",
+            ]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix)),
+            Language::Python if trimmed.len() == line.len() => ["def ", "async def "]
+                .iter()
+                .find_map(|prefix| trimmed.strip_prefix(prefix)),
+            Language::Python => None,
+            Language::JavaScript => [
+                "export function ",
+                "export async function ",
+                "export default function ",
+                "export default async function ",
+            ]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+            .or_else(|| {
+                let rest = trimmed.strip_prefix("export const ")?;
+                (rest.contains("=>") || rest.contains("function")).then_some(rest)
+            }),
+        };
+
+        let Some(rest) = definition else {
+            continue;
+        };
+        let name = identifier(rest);
+        if name.is_empty() || name.starts_with('_') || name == "main" {
+            continue;
+        }
+        functions.push(UncoveredFunction {
+            path: path.to_string(),
+            name,
+            line: index + 1,
+        });
+    }
+    functions
+}
+
+/// 🔍 Without a coverage report: public functions that no test mentions by name
+pub fn find_untested_functions(files: &[SourceFile]) -> Vec<UncoveredFunction> {
+    let mut corpus = String::new();
+    for file in files {
+        if is_test_path(&file.path) {
+            corpus.push_str(&file.content);
+        } else if let Some(start) = file.content.find("#[cfg(test)]") {
+            // 🦀 Inline Rust test modules count too
+            corpus.push_str(&file.content[start..]);
+        }
+        corpus.push('\n');
+    }
+
+    files
+        .iter()
+        .filter(|file| !is_test_path(&file.path))
+        .flat_map(|file| public_functions(&file.path, &file.content))
+        .filter(|function| !mentions(&corpus, &function.name))
+        .collect()
+}
+
+/// 🔤 Whether `name` appears in `text` as a whole identifier
+fn mentions(text: &str, name: &str) -> bool {
+    text.match_indices(name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// 🏷️ Leading identifier characters of a string
+fn identifier(text: &str) -> String {
+    text.chars()
+        .take_while(|c| is_identifier_char(*c))
+        .collect()
+}
+
+/// 🎯 Words in the request that name what to test ("add tests for the parser" → ["parser"])
+pub fn focus_terms(request: &str) -> Vec<String> {
+    request
+        .to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| word.len() >= 3 && !FOCUS_STOP_WORDS.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 🎯 Keep the functions the request is about (all of them if it names nothing we recognise)
+pub fn apply_focus(functions: Vec<UncoveredFunction>, request: &str) -> Vec<UncoveredFunction> {
+    let terms = focus_terms(request);
+    if terms.is_empty() {
+        return functions;
+    }
+
+    let focused: Vec<UncoveredFunction> = functions
+        .iter()
+        .filter(|function| {
+            let path = function.path.to_lowercase();
+            let name = function.name.to_lowercase();
+            terms
+                .iter()
+                .any(|term| path.contains(term.as_str()) || name.contains(term.as_str()))
+        })
+        .cloned()
+        .collect();
+    if focused.is_empty() {
+        warn!(
+            "⚠️ Nothing uncovered matches {:?}; covering the largest gaps instead",
+            terms
+        );
+        return functions;
+    }
+    focused
+}
+
+/// 🎯 One test file to write
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestTarget {
+    /// 📂 Source file under test
+    pub source_path: String,
+    /// 🧪 Test file to create or extend
+    pub test_path: String,
+    /// 🏠 Directory the test command runs from ("" = repository root)
+    pub project_root: String,
+    /// 🔍 Functions the tests must cover
+    pub functions: Vec<UncoveredFunction>,
+}
+
+impl TestTarget {
+    fn language(&self) -> Option<Language> {
+        Language::from_path(&self.source_path)
+    }
+}
+
+/// 🎯 Group uncovered functions into test files, most uncovered first
+pub fn plan_test_targets(
+    functions: Vec<UncoveredFunction>,
+    files: &[SourceFile],
+    scope: &PathScope,
+) -> Vec<TestTarget> {
+    let paths: HashSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+
+    let mut by_file: BTreeMap<String, Vec<UncoveredFunction>> = BTreeMap::new();
+    for function in functions {
+        if scope.contains(&function.path) {
+            by_file
+                .entry(function.path.clone())
+                .or_default()
+                .push(function);
+        }
+    }
+
+    let mut targets: Vec<TestTarget> = by_file
+        .into_iter()
+        .filter_map(|(source_path, functions)| {
+            let language = Language::from_path(&source_path)?;
+            let project_root = project_root(&source_path, language, &paths, scope);
+            // 🦀 Integration tests can only reach library crates
+            if language == Language::Rust
+                && !paths.contains(join(&project_root, "src/lib.rs").as_str())
+            {
+                return None;
+            }
+            Some(TestTarget {
+                test_path: test_path(&source_path, &project_root, language),
+                source_path,
+                project_root,
+                functions,
+            })
+        })
+        .collect();
+
+    targets.sort_by(|a, b| {
+        b.functions
+            .len()
+            .cmp(&a.functions.len())
+            .then_with(|| a.source_path.cmp(&b.source_path))
+    });
+    targets.truncate(MAX_TEST_TARGETS);
+    targets
+}
+
+/// 🏠 Nearest ancestor directory holding the language's manifest (scope root as fallback)
+fn project_root(
+    source_path: &str,
+    language: Language,
+    paths: &HashSet<&str>,
+    scope: &PathScope,
+) -> String {
+    let manifests: &[&str] = match language {
+        Language::Rust => &["Cargo.toml"],
+        Language::Python => &["pyproject.toml", "setup.py", "setup.cfg"],
+        Language::JavaScript => &["package.json"],
+    };
+
+    let mut dir = parent_dir(source_path);
+    loop {
+        if manifests
+            .iter()
+            .any(|manifest| paths.contains(join(dir, manifest).as_str()))
+        {
+            return dir.to_string();
+        }
+        if dir.is_empty() {
+            return scope.root.clone().unwrap_or_default();
+        }
+        dir = parent_dir(dir);
+    }
+}
+
+/// 🧪 Where tests for a source file go, following each ecosystem's convention
+fn test_path(source_path: &str, project_root: &str, language: Language) -> String {
+    let file_name = source_path.rsplit('/').next().unwrap_or(source_path);
+    let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+    // 📦 mod.rs / __init__.py / index.ts are named after their directory
+    let stem = if matches!(stem, "mod" | "__init__" | "index") {
+        parent_dir(source_path)
+            .rsplit('/')
+            .next()
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or(stem)
+    } else {
+        stem
+    };
+
+    match language {
+        Language::Rust => join(project_root, &format!("tests/{}_tests.rs", stem)),
+        Language::Python => join(project_root, &format!("tests/test_{}.py", stem)),
+        Language::JavaScript => join(
+            parent_dir(source_path),
+            &format!("{}.test.{}", stem, extension),
+        ),
+    }
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+/// ▶️ Commands that run exactly the generated tests, one per language and project root
+pub fn test_commands(targets: &[TestTarget]) -> Vec<(String, TestCommand)> {
+    let mut grouped: BTreeMap<(String, u8), Vec<String>> = BTreeMap::new();
+    for target in targets {
+        let Some(language) = target.language() else {
+            continue;
+        };
+        let relative = target
+            .test_path
+            .strip_prefix(&format!("{}/", target.project_root))
+            .unwrap_or(&target.test_path)
+            .to_string();
+        let key = match language {
+            Language::Rust => 0,
+            Language::Python => 1,
+            Language::JavaScript => 2,
+        };
+        let tests = grouped
+            .entry((target.project_root.clone(), key))
+            .or_default();
+        if !tests.contains(&relative) {
+            tests.push(relative);
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|((root, key), tests)| {
+            let tests: Vec<&str> = tests.iter().map(String::as_str).collect();
+            let command = match key {
+                0 => {
+                    let mut args = vec!["test"];
+                    for test in &tests {
+                        args.push("--test");
+                        args.push(test.trim_start_matches("tests/").trim_end_matches(".rs"));
+                    }
+                    TestCommand::new("cargo", &args)
+                }
+                1 => TestCommand::new(
+                    "python3",
+                    &[&["-m", "pytest", "-q"][..], &tests[..]].concat(),
+                ),
+                _ => TestCommand::new("npm", &[&["test", "--"][..], &tests[..]].concat()),
+            };
+            (root, command)
+        })
+        .collect()
+}
+
+/// 🛡️ Reject anything in test generation mode that isn't a test file
+pub fn ensure_tests_only(improvement: &CodeImprovement) -> Result<()> {
+    let path = &improvement.file_path;
+    if !is_test_path(path) {
+        anyhow::bail!("Test generation may only write test files, not '{}'", path);
+    }
+    if !matches!(
+        improvement.change_type,
+        ChangeType::Create | ChangeType::Modify
+    ) {
+        anyhow::bail!("Test generation may not delete or append to '{}'", path);
+    }
+
+    let has_tests = match Language::from_path(path) {
+        Some(Language::Rust) => {
+            improvement.new_content.contains("#[test]")
+                || improvement.new_content.contains("#[tokio::test]")
+        }
+        Some(Language::Python) => improvement.new_content.contains("def test_"),
+        Some(Language::JavaScript) => ["test(", "it(", "describe("]
+            .iter()
+            .any(|call| improvement.new_content.contains(call)),
+        None => false,
+    };
+    if !has_tests {
+        anyhow::bail!("Generated '{}' contains no test cases", path);
+    }
+    Ok(())
+}
+
+/// 📝 Language-specific instructions for the test file
+fn conventions(target: &TestTarget, contents: &HashMap<&str, &str>) -> String {
+    let mut text = match target.language() {
+        Some(Language::Rust) => {
+            let crate_name = contents
+                .get(join(&target.project_root, "Cargo.toml").as_str())
+                .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+                .and_then(|manifest| {
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(|name| name.replace('-', "_"))
+                })
+                .unwrap_or_else(|| "the_crate".to_string());
+            format!(
+                "This is a Rust integration test file, compiled as its own crate. \
+                 Use the public API through `use {}::...;` and mark tests with #[test].",
+                crate_name
+            )
+        }
+        Some(Language::Python) => {
+            "Write pytest tests. Import the module under test the same way the existing tests do."
+                .to_string()
+        }
+        _ => "Write tests for the project's existing test runner (see package.json), \
+              importing the module under test with a relative path."
+            .to_string(),
+    };
+
+    // 🧭 An existing test from the same project shows the house style
+    let example = contents.iter().find(|(path, _)| {
+        is_test_path(path)
+            && **path != target.test_path
+            && Language::from_path(path) == target.language()
+            && path.starts_with(target.project_root.as_str())
+    });
+    if let Some((path, content)) = example {
+        let mut end = content.len().min(MAX_EXAMPLE_TEST_BYTES);
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.push_str(&format!(
+            "\n\nExisting test for reference (`{}`):\n{}",
+            path,
+            &content[..end]
+        ));
+    }
+    text
+}
+
+/// 📄 Generate one test file
+async fn generate_test_file(
+    llm: &LlmManager,
+    project: &Project,
+    template: &PromptTemplate,
+    request: &str,
+    target: &TestTarget,
+    contents: &HashMap<&str, &str>,
+    trace: &ExchangeTrace,
+) -> Result<(CodeImprovement, LlmProvider)> {
+    let existing = contents.get(target.test_path.as_str()).copied();
+    let functions: Vec<String> = target
+        .functions
+        .iter()
+        .map(|function| format!("- `{}` (line {})", function.name, function.line))
+        .collect();
+
+    let prompt = template.render(&HashMap::from([
+        ("repository", project.repository.clone()),
+        ("request", request.trim().to_string()),
+        ("source_path", target.source_path.clone()),
+        ("test_path", target.test_path.clone()),
+        ("functions", functions.join("\n")),
+        ("conventions", conventions(target, contents)),
+        (
+            "source_content",
+            contents
+                .get(target.source_path.as_str())
+                .copied()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        ("test_content", existing.unwrap_or_default().to_string()),
+    ]))?;
+
+    // ✍️ Writes outside test files go back to the model as a schema violation
+    let build = |reply: &str| -> Result<CodeImprovement> {
+        let improvement = CodeImprovement {
+            file_path: target.test_path.clone(),
+            description: format!(
+                "Test {} uncovered functions in {}",
+                target.functions.len(),
+                target.source_path
+            ),
+            change_type: if existing.is_some() {
+                ChangeType::Modify
+            } else {
+                ChangeType::Create
+            },
+            original_content: existing.map(str::to_string),
+            new_content: strip_code_fence(reply),
+            line_number: None,
+        };
+        ensure_tests_only(&improvement)?;
+        Ok(improvement)
+    };
+
+    let completion =
+        CompletionRequest::new(project.system_message.clone(), prompt).traced(Some(trace.clone()));
+    let (file, response) = llm
+        .complete_structured(&completion, |file: &GeneratedFile| {
+            build(&file.content)
+                .map(|_| ())
+                .map_err(|e| vec![format!("{:#}", e)])
+        })
+        .await?;
+    let improvement = build(&file.content)?;
+    Ok((improvement, response.provider))
+}
+
+/// 🏭 Test generation for one project, driven by the request in `feedback`
+pub async fn run_test_generation(
+    pool: &PgPool,
+    config: &Config,
+    llm: &LlmManager,
+    project: &Project,
+    feedback: &Feedback,
+) -> Result<Vec<PullRequestResult>> {
+    let settings = project.settings()?;
+    let trace = ExchangeTrace::new(
+        names::TEST_FILE,
+        feedback.id,
+        project.id,
+        settings.prompt_logging,
+    )
+    .with_routing(settings.model_routing.clone())
+    .with_budget(SpendLimits::resolve(&config.budgets, &settings.budget))
+    .with_provider(feedback.submitter_llm_provider(pool).await?);
+    let scope = settings.scope()?;
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let coverage = CoverageReport::from_metadata(feedback.metadata.as_ref())?;
+
+    // 📥 Read the scoped checkout and copy it into a sandbox while it's in hand
+    let cache = CloneCache::new(
+        &config.github.clone_cache_dir,
+        config.github.clone_cache_size,
+    );
+    let github_config = scm::github_config(pool, &config.github, project).await?;
+    let options = CloneOptions {
+        scope: scope.clone(),
+        token: Some(github_config.token.clone()),
+        ..Default::default()
+    };
+    let repository = project.repository.clone();
+    let clone_url = github_clone_url(&github_config.api_base_url, &repository);
+    let sandbox_config = config.sandbox.clone();
+    let git = Stage::Git.span(None);
+    let (files, sandbox, base_commit) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let workspace = feedback_trace::traced_blocking(git, || {
+            cache.checkout(&repository, &clone_url, &options)
+        })?;
+        let objects = workspace.objects()?;
+        let files: Vec<SourceFile> = objects
+            .filter_context(&workspace.list_files()?)
+            .into_iter()
+            .filter_map(|path| {
+                let content = workspace.read_file(&path)?;
+                (content.len() <= MAX_TESTGEN_FILE_BYTES).then_some(SourceFile { path, content })
+            })
+            .collect();
+        let sandbox = Sandbox::create(&sandbox_config, workspace.root())?;
+        Ok((files, sandbox, workspace.head_sha()?))
+    })
+    .await
+    .context("Checkout task panicked")??;
+
+    let uncovered = match &coverage {
+        Some(report) => report.uncovered(&files),
+        None => find_untested_functions(&files),
+    };
+    let targets = plan_test_targets(apply_focus(uncovered, &feedback.content), &files, &scope);
+    FeedbackEvent::record(
+        pool,
+        feedback.id,
+        FeedbackEvent::TEST_TARGETS_FOUND,
+        json!({
+            "source": if coverage.is_some() { "coverage_report" } else { "test_references" },
+            "targets": &targets,
+        }),
+    )
+    .await?;
+    if targets.is_empty() {
+        info!(
+            "💚 Found nothing untested to cover in {}",
+            project.repository
+        );
+        return Ok(Vec::new());
+    }
+
+    let contents: HashMap<&str, &str> = files
+        .iter()
+        .map(|file| (file.path.as_str(), file.content.as_str()))
+        .collect();
+
+    let template = PromptBook::load(pool, feedback.id, &[names::TEST_FILE])
+        .await?
+        .template(names::TEST_FILE)?;
+    // 🎨 Tests are written in the repository's learned style
+    let styled = style_guide::styled(pool, project).await?;
+    let mut checkpoint = Checkpoint::resume(pool, feedback.id, &base_commit).await?;
+    let mut improvements = Vec::new();
+    let mut generated = Vec::new();
+    for target in &targets {
+        if let Some(improvement) = checkpoint.generated(&target.test_path) {
+            FeedbackEvent::record(
+                pool,
+                feedback.id,
+                FeedbackEvent::FILE_GENERATED,
+                json!({ "file_path": target.test_path, "resumed": true }),
+            )
+            .await?;
+            improvements.push(improvement.clone());
+            generated.push(target.clone());
+            continue;
+        }
+        match generate_test_file(
+            llm,
+            &styled,
+            &template,
+            &feedback.content,
+            target,
+            &contents,
+            &trace,
+        )
+        .await
+        {
+            Ok((improvement, provider)) => {
+                feedback.record_llm_provider(pool, provider).await?;
+                checkpoint.save_file(pool, &improvement).await?;
+                FeedbackEvent::record(
+                    pool,
+                    feedback.id,
+                    FeedbackEvent::FILE_GENERATED,
+                    json!({ "file_path": target.test_path }),
+                )
+                .await?;
+                improvements.push(improvement);
+                generated.push(target.clone());
+            }
+            // 💸 A budget stop pauses the whole run
+            Err(e) if budgets::exceeded_by(&e).is_some() => return Err(e),
+            Err(e) => {
+                // 🟡 One bad file shouldn't sink the whole run
+                warn!("⚠️ Skipping tests for {}: {:#}", target.source_path, e);
+                FeedbackEvent::record(
+                    pool,
+                    feedback.id,
+                    FeedbackEvent::FILE_FAILED,
+                    json!({ "file_path": target.test_path, "error": format!("{:#}", e) }),
+                )
+                .await?;
+            }
+        }
+    }
+    if improvements.is_empty() {
+        anyhow::bail!("No tests could be generated");
+    }
+
+    // 🎨 Generated tests are formatted the way the repository's CI expects
+    // before they run, so what passes is what gets committed
+    sandbox.apply(&improvements)?;
+    let formatting = formatting::format_generated(
+        pool,
+        feedback.id,
+        &sandbox,
+        &formatting::tools_for(&settings.formatting, &files),
+        &files,
+        &mut improvements,
+        ensure_tests_only,
+    )
+    .await?;
+    if let Err(e) = licensing::enforce(pool, feedback.id, &settings.licensing, &improvements).await
+    {
+        // 🗑️ A retry should write new tests, not check these again
+        Checkpoint::clear(pool, feedback.id).await?;
+        return Err(e);
+    }
+
+    // 🧪 The PR only happens if every generated test compiles and passes
+    let mut outcomes: Vec<SandboxOutcome> = Vec::new();
+    for (root, command) in test_commands(&generated) {
+        let outcome =
+            feedback_trace::traced(Stage::Sandbox.span(None), sandbox.run(&root, &command))
+                .await?;
+        FeedbackEvent::record(
+            pool,
+            feedback.id,
+            FeedbackEvent::SANDBOX_RUN,
+            serde_json::to_value(&outcome)?,
+        )
+        .await?;
+        if !outcome.passed {
+            // 🗑️ A retry should write new tests, not run these again
+            Checkpoint::clear(pool, feedback.id).await?;
+            anyhow::bail!(
+                "Generated tests did not pass in the sandbox: `{}` {}",
+                outcome.command,
+                if outcome.timed_out {
+                    "timed out".to_string()
+                } else {
+                    format!("exited with {:?}", outcome.exit_code)
+                }
+            );
+        }
+        outcomes.push(outcome);
+    }
+    drop(sandbox);
+    record_proposed_diff(pool, feedback, &improvements).await?;
+
+    let function_count: usize = generated.iter().map(|target| target.functions.len()).sum();
+    let request = FeedbackProcessingRequest {
+        feedback_id: feedback.id,
+        repository: project.repository.clone(),
+        feedback_content: feedback.content.clone(),
+        commit_message: formatting.amend_commit_message(format!(
+            "🧪 Add tests for {} uncovered functions\n\nTest-only changes covering functions that had no tests. \
+             Every generated test passed in the sandbox before this PR was opened.",
+            function_count
+        )),
+        improvements,
+        branch_name: format!(
+            "{}tests-{}",
+            config.github.default_branch_prefix,
+            &feedback.id.to_string()[..8]
+        ),
+        pull_request_settings: settings.pull_requests.clone(),
+        test_plan: outcomes
+            .iter()
+            .map(|outcome| {
+                format!(
+                    "`{}` passed in the sandbox ({:.1}s)",
+                    outcome.command,
+                    outcome.duration_ms as f64 / 1000.0
+                )
+            })
+            .chain(formatting.test_plan_line())
+            .chain(std::iter::once(
+                "Check the assertions describe the intended behavior".to_string(),
+            ))
+            .collect(),
+    };
+
+    let scorer = RiskScorer::model(
+        pool,
+        llm,
+        feedback.id,
+        project.system_message.clone(),
+        &trace,
+    )
+    .await?;
+    let github = GitHubClient::new(github_config)?;
+    let base_branch = github
+        .get_repository_info(&owner, &repo)
+        .await?
+        .default_branch;
+    let results = publish_request(
+        &github,
+        &owner,
+        &repo,
+        &request,
+        &scorer,
+        &base_branch,
+        &config.server.public_url,
+    )
+    .await?;
+
+    for result in &results {
+        FeedbackEvent::record(
+            pool,
+            feedback.id,
+            FeedbackEvent::PULL_REQUEST_OPENED,
+            json!({ "number": result.number, "url": result.url, "title": result.title }),
+        )
+        .await?;
+    }
+    Checkpoint::clear(pool, feedback.id).await?;
+    Ok(results)
+}
+
+// 🧪 Tests - Tests for the test generator (it's tests all the way down)!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> SourceFile {
+        SourceFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lcov_uncovered_functions() {
+        let lcov = "TN:\nSF:/home/ci/work/repo/src/parser.rs\nFN:1,_RNvCs1_6parser5parse\n\
+                    FN:5,_RNvCs1_6parser4lex\nFNDA:3,_RNvCs1_6parser5parse\nFNDA:0,_RNvCs1_6parser4lex\n\
+                    DA:1,3\nend_of_record\nSF:src/other.rs\nFN:1,other\nend_of_record\n";
+        let files = vec![
+            file(
+                "src/parser.rs",
+                "pub fn parse() {}\n\n\n\npub fn lex() {}\n",
+            ),
+            file("src/other.rs", "fn other() {}\n"),
+        ];
+
+        let report = CoverageReport::parse_lcov(lcov).unwrap();
+        let names: Vec<String> = report
+            .uncovered(&files)
+            .into_iter()
+            .map(|function| format!("{}:{}", function.path, function.name))
+            .collect();
+        assert_eq!(names, vec!["src/parser.rs:lex", "src/other.rs:other"]);
+
+        assert!(CoverageReport::parse_lcov("not a coverage report").is_err());
+        let metadata = json!({ COVERAGE_METADATA_KEY: lcov });
+        assert!(CoverageReport::from_metadata(Some(&metadata))
+            .unwrap()
+            .is_some());
+        assert!(CoverageReport::from_metadata(None).unwrap().is_none());
+        println!("✅ LCOV coverage parsing test passed!");
+    }
+
+    #[test]
+    fn test_untested_functions_and_focus() {
+        let files = vec![
+            file("Cargo.toml", "[package]\nname = \"my-lib\"\n"),
+            file("src/lib.rs", "pub mod parser;\npub fn version() {}\n"),
+            file(
+                "src/parser.rs",
+                "pub fn parse() {}\npub fn tokenize() {}\nfn helper() {}\n\
+                 #[cfg(test)]\nmod tests {\n    #[test]\n    fn t() { super::tokenize(); }\n}\n",
+            ),
+            file(
+                "tests/version.rs",
+                "#[test]\nfn v() { my_lib::version(); }\n",
+            ),
+            file(
+                "app/main.py",
+                "def main():\n    pass\n\ndef render_page():\n    pass\n",
+            ),
+        ];
+
+        let untested = find_untested_functions(&files);
+        let names: Vec<&str> = untested.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["parse", "render_page"]);
+
+        let focused = apply_focus(untested.clone(), "Please add tests for the parser");
+        assert_eq!(focused.len(), 1);
+        assert_eq!(focused[0].name, "parse");
+        assert_eq!(focus_terms("add unit tests"), Vec::<String>::new());
+        assert_eq!(
+            apply_focus(untested.clone(), "tests for the renderer").len(),
+            2
+        );
+
+        let targets = plan_test_targets(untested, &files, &PathScope::whole_repository());
+        let paths: Vec<&str> = targets.iter().map(|t| t.test_path.as_str()).collect();
+        assert_eq!(paths, vec!["tests/test_main.py", "tests/parser_tests.rs"]);
+        let contents: HashMap<&str, &str> = files
+            .iter()
+            .map(|file| (file.path.as_str(), file.content.as_str()))
+            .collect();
+        let rust = conventions(&targets[1], &contents);
+        assert!(rust.contains("use my_lib::...;"));
+        assert!(rust.contains("tests/version.rs"));
+        println!("✅ Untested function discovery test passed!");
+    }
+
+    #[test]
+    fn test_commands_and_guardrail() {
+        let target = |source: &str, test: &str, root: &str| TestTarget {
+            source_path: source.to_string(),
+            test_path: test.to_string(),
+            project_root: root.to_string(),
+            functions: Vec::new(),
+        };
+        let commands: Vec<(String, String)> = test_commands(&[
+            target("src/a.rs", "tests/a_tests.rs", ""),
+            target("src/b.rs", "tests/b_tests.rs", ""),
+            target("py/pkg/c.py", "py/tests/test_c.py", "py"),
+            target("web/src/d.ts", "web/src/d.test.ts", "web"),
+        ])
+        .into_iter()
+        .map(|(root, command)| (root, command.display()))
+        .collect();
+        assert_eq!(
+            commands,
+            vec![
+                (
+                    "".to_string(),
+                    "cargo test --test a_tests --test b_tests".to_string()
+                ),
+                (
+                    "py".to_string(),
+                    "python3 -m pytest -q tests/test_c.py".to_string()
+                ),
+                ("web".to_string(), "npm test -- src/d.test.ts".to_string()),
+            ]
+        );
+
+        let improvement = |path: &str, content: &str| CodeImprovement {
+            file_path: path.to_string(),
+            description: "Add tests".to_string(),
+            change_type: ChangeType::Create,
+            original_content: None,
+            new_content: content.to_string(),
+            line_number: None,
+        };
+        assert!(ensure_tests_only(&improvement("tests/a_tests.rs", "#[test]\nfn a() {}")).is_ok());
+        assert!(ensure_tests_only(&improvement("src/a.rs", "#[test]\nfn a() {}")).is_err());
+        assert!(ensure_tests_only(&improvement("tests/test_c.py", "import c\n")).is_err());
+        let mut deletion = improvement("tests/test_c.py", "def test_c(): pass");
+        deletion.change_type = ChangeType::Delete;
+        assert!(ensure_tests_only(&deletion).is_err());
+        println!("✅ Test commands and guardrail test passed!");
+    }
+}
@@ -0,0 +1,357 @@
+// 🔄 Hot Reload - New Settings Without a Restart! 🔄
+// Some settings are only read while serving: rate limits, feature flags, the log
+// level, LLM model names, and maintenance mode. They live in a `LiveSettings`
+// snapshot behind an `ArcSwap` in the app state, so handlers always see a whole,
+// consistent set. SIGHUP (or POST /api/admin/config/reload) loads the
+// configuration again the way startup does (environment over the config file),
+// swaps the snapshot, and logs what changed. Everything else (database, server
+// address, credentials, workers) is structural and still needs a restart. Prompt
+// templates need no reload at all: versions are read from the database every
+// time they are used
+// Created with love by Aye & Hue - Turn the knobs while the engine runs! ✨
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{error, info, warn};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::api::AppState;
+use crate::config::{Config, FeaturesConfig, MaintenanceConfig, RateLimitConfig};
+
+/// 📈 Handle swapping the filter of the log output
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// 📈 The log filter handle, installed once logging is set up
+static LOG_FILTER: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// 🚀 Flags that are only read at startup; a reload records them but they need a restart
+const RESTART_ONLY: &[&str] = &[
+    "features.enable_background_jobs",
+    "features.enable_web_ui",
+    "features.enable_github_webhooks",
+    "features.enable_metrics",
+    "rate_limiting.sync_seconds",
+];
+
+/// 🔄 The settings that can change while the service runs
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveSettings {
+    /// 🚦 Request limits
+    pub rate_limiting: RateLimitConfig,
+    /// 🔧 Feature flags
+    pub features: FeaturesConfig,
+    /// 📈 Log level (LOG_LEVEL, as an env filter directive)
+    pub log_level: String,
+    /// 🤖 Model names per configured provider
+    pub models: BTreeMap<&'static str, ModelNames>,
+    /// 🚧 Maintenance mode forced from configuration
+    pub maintenance: MaintenanceConfig,
+}
+
+/// 🤖 Model names of one provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelNames {
+    pub default_model: String,
+    pub small_model: Option<String>,
+}
+
+impl LiveSettings {
+    /// 📸 The live settings of a configuration
+    pub fn from_config(config: &Config) -> Self {
+        let mut models = BTreeMap::new();
+        if let Some(openai) = &config.llm.openai {
+            models.insert(
+                "openai",
+                ModelNames::new(&openai.default_model, &openai.small_model),
+            );
+        }
+        if let Some(anthropic) = &config.llm.anthropic {
+            models.insert(
+                "anthropic",
+                ModelNames::new(&anthropic.default_model, &anthropic.small_model),
+            );
+        }
+        if let Some(custom) = &config.llm.custom {
+            models.insert(
+                "custom",
+                ModelNames::new(&custom.default_model, &custom.small_model),
+            );
+        }
+
+        Self {
+            rate_limiting: config.rate_limiting.clone(),
+            features: config.features.clone(),
+            log_level: config.logging.level.clone(),
+            models,
+            maintenance: config.maintenance.clone(),
+        }
+    }
+}
+
+impl ModelNames {
+    fn new(default_model: &str, small_model: &Option<String>) -> Self {
+        Self {
+            default_model: default_model.to_string(),
+            small_model: small_model.clone(),
+        }
+    }
+}
+
+/// 📝 One setting that changed on a reload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingChange {
+    /// 🏷️ Dotted path of the setting (e.g. rate_limiting.burst_size)
+    pub setting: String,
+    pub old: Value,
+    pub new: Value,
+    /// 🚀 Only takes effect after a restart
+    pub needs_restart: bool,
+}
+
+/// 🔄 The live settings of the running service, and where they are reloaded from
+#[derive(Debug)]
+pub struct LiveConfig {
+    /// 📸 Current snapshot
+    settings: ArcSwap<LiveSettings>,
+    /// 📄 Config file given with --config (the default lookup applies otherwise)
+    config_path: Option<PathBuf>,
+    /// 🔒 One reload at a time
+    reloading: Mutex<()>,
+}
+
+impl LiveConfig {
+    /// ➕ Live settings starting from the startup configuration
+    pub fn new(config: &Config, config_path: Option<PathBuf>) -> Self {
+        Self {
+            settings: ArcSwap::from_pointee(LiveSettings::from_config(config)),
+            config_path,
+            reloading: Mutex::new(()),
+        }
+    }
+
+    /// 📸 The current settings
+    pub fn current(&self) -> Arc<LiveSettings> {
+        self.settings.load_full()
+    }
+}
+
+/// 📈 Remember the log filter handle so reloads can change the level
+pub fn install_log_filter(handle: LogFilterHandle) {
+    let _ = LOG_FILTER.set(handle);
+}
+
+/// 🔄 Load the configuration again and apply what changed in the live settings
+/// An invalid configuration is rejected as a whole; the running settings stay
+pub fn reload(app_state: &AppState) -> Result<Vec<SettingChange>> {
+    let live = &app_state.live;
+    let _reloading = live.reloading.lock().unwrap_or_else(|e| e.into_inner());
+
+    let config = Config::load_from(live.config_path.as_deref())
+        .context("Configuration reload failed; keeping the current settings")?;
+    let old = live.current();
+    let mut new = LiveSettings::from_config(&config);
+    // 🔑 Providers come and go with their credentials, which need a restart
+    new.models
+        .retain(|provider, _| old.models.contains_key(provider));
+    let changes = diff(&old, &new);
+    if changes.is_empty() {
+        info!("🔄 Configuration reloaded: nothing changed");
+        return Ok(changes);
+    }
+
+    if new.log_level != old.log_level {
+        set_log_level(&new.log_level)?;
+    }
+    app_state.llm_manager.reload_models(&config.llm);
+    app_state
+        .llm_manager
+        .set_log_all_exchanges(new.features.enable_dev_features);
+    live.settings.store(Arc::new(new));
+
+    for change in &changes {
+        info!(
+            "🔄 {}: {} → {}{}",
+            change.setting,
+            change.old,
+            change.new,
+            if change.needs_restart {
+                " (takes effect after a restart)"
+            } else {
+                ""
+            }
+        );
+    }
+    info!(
+        "🔄 Configuration reloaded: {} settings changed",
+        changes.len()
+    );
+    Ok(changes)
+}
+
+/// 📈 Switch the log output to a new filter
+fn set_log_level(level: &str) -> Result<()> {
+    let filter =
+        EnvFilter::try_new(level).with_context(|| format!("Invalid LOG_LEVEL '{}'", level))?;
+    match LOG_FILTER.get() {
+        Some(handle) => handle
+            .reload(filter)
+            .context("Failed to change the log level"),
+        None => {
+            warn!("⚠️ Log level can't be changed: logging has no reload handle");
+            Ok(())
+        }
+    }
+}
+
+/// 📝 Every setting whose value differs, in path order
+pub fn diff(old: &LiveSettings, new: &LiveSettings) -> Vec<SettingChange> {
+    let mut old_values = BTreeMap::new();
+    let mut new_values = BTreeMap::new();
+    flatten(
+        "",
+        &serde_json::to_value(old).unwrap_or_default(),
+        &mut old_values,
+    );
+    flatten(
+        "",
+        &serde_json::to_value(new).unwrap_or_default(),
+        &mut new_values,
+    );
+
+    let mut settings: Vec<&String> = old_values.keys().chain(new_values.keys()).collect();
+    settings.sort();
+    settings.dedup();
+    settings
+        .into_iter()
+        .filter_map(|setting| {
+            let old = old_values.get(setting).cloned().unwrap_or(Value::Null);
+            let new = new_values.get(setting).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| SettingChange {
+                setting: setting.clone(),
+                needs_restart: RESTART_ONLY.contains(&setting.as_str()),
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+/// 🗂️ Leaf values of a JSON document by dotted path
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, out);
+            }
+        }
+        leaf => {
+            out.insert(prefix.to_string(), leaf.clone());
+        }
+    }
+}
+
+/// 📡 Reload the configuration on every SIGHUP, in the background
+#[cfg(unix)]
+pub fn watch_sighup(app_state: AppState) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            warn!("⚠️ SIGHUP reload unavailable: {:#}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            info!("📡 SIGHUP received, reloading configuration");
+            if let Err(e) = reload(&app_state) {
+                error!("❌ {:#}", e);
+            }
+        }
+    });
+}
+
+/// 📡 SIGHUP doesn't exist here; use the admin endpoint to reload
+#[cfg(not(unix))]
+pub fn watch_sighup(_app_state: AppState) {}
+
+// 🧪 Tests - Only what really changed gets reported!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> LiveSettings {
+        LiveSettings {
+            rate_limiting: RateLimitConfig {
+                requests_per_minute: 60,
+                feedback_per_hour: 10,
+                burst_size: 10,
+                window_seconds: 60,
+                trusted_requests_per_minute: 600,
+                trusted_feedback_per_hour: 200,
+                sync_seconds: 5,
+            },
+            features: FeaturesConfig {
+                enable_background_jobs: true,
+                enable_email_notifications: false,
+                enable_web_ui: true,
+                enable_github_webhooks: true,
+                enable_metrics: true,
+                enable_dev_features: false,
+            },
+            log_level: "info".to_string(),
+            models: BTreeMap::from([(
+                "openai",
+                ModelNames {
+                    default_model: "gpt-4o".to_string(),
+                    small_model: None,
+                },
+            )]),
+            maintenance: MaintenanceConfig {
+                enabled: false,
+                message: "Back soon".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_settings_diff() {
+        let old = settings();
+        assert!(diff(&old, &old.clone()).is_empty());
+
+        let mut new = settings();
+        new.rate_limiting.requests_per_minute = 120;
+        new.features.enable_background_jobs = false;
+        new.log_level = "debug".to_string();
+        new.models.get_mut("openai").unwrap().small_model = Some("gpt-4o-mini".to_string());
+
+        let changes = diff(&old, &new);
+        let settings: Vec<&str> = changes.iter().map(|c| c.setting.as_str()).collect();
+        assert_eq!(
+            settings,
+            vec![
+                "features.enable_background_jobs",
+                "log_level",
+                "models.openai.small_model",
+                "rate_limiting.requests_per_minute",
+            ]
+        );
+        assert!(changes[0].needs_restart);
+        assert!(!changes[1].needs_restart);
+        assert_eq!(changes[2].old, Value::Null);
+        assert_eq!(changes[3].old, serde_json::json!(60));
+        assert_eq!(changes[3].new, serde_json::json!(120));
+        println!("✅ Settings diff test passed!");
+    }
+}
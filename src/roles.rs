@@ -0,0 +1,249 @@
+// 🎭 Roles - Who Can Do What, Editable at Runtime! 🎭
+// A user's permissions are the grants of the built-in role named after their
+// account role (user, service, admin) plus those of every role assigned to
+// them, so "can approve PRs but not manage projects" is just a role with that
+// one grant. Admins always hold every permission, whatever the tables say.
+// Grants are read from a snapshot refreshed every CACHE_TTL; edits through
+// /api/admin/roles refresh this instance right away, other instances within the TTL
+// Created with love by Aye & Hue - The right keys for the right doors! ✨
+
+use anyhow::Result;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::database::models::{Role, RoleAssignment, User, UserRole};
+use crate::middleware::auth::Permission;
+
+/// ⏱️ How long a snapshot is used before the roles are read again
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// 📸 Every role's grants and every assignment at one point in time
+#[derive(Debug, Clone, Default)]
+pub struct RoleSet {
+    grants: HashMap<String, HashSet<Permission>>,
+    assignments: HashMap<Uuid, Vec<String>>,
+}
+
+impl RoleSet {
+    /// 🏗️ Build a snapshot from stored roles and assignments
+    /// (grants of permissions this build doesn't know are ignored)
+    pub fn new(roles: Vec<Role>, assignments: Vec<RoleAssignment>) -> Self {
+        let grants = roles
+            .into_iter()
+            .map(|role| {
+                let permissions = role
+                    .permissions
+                    .iter()
+                    .filter_map(|name| name.parse().ok())
+                    .collect();
+                (role.name, permissions)
+            })
+            .collect();
+        let mut by_user: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for assignment in assignments {
+            by_user
+                .entry(assignment.user_id)
+                .or_default()
+                .push(assignment.role_name);
+        }
+        Self {
+            grants,
+            assignments: by_user,
+        }
+    }
+
+    /// 🎯 Everything a user may do
+    /// A built-in role missing from the snapshot grants its defaults
+    pub fn permissions_for(&self, user_id: Uuid, role: &UserRole) -> HashSet<Permission> {
+        if matches!(role, UserRole::Admin) {
+            return Permission::ALL.into_iter().collect();
+        }
+
+        let mut permissions = self
+            .grants
+            .get(builtin_role(role))
+            .cloned()
+            .unwrap_or_else(|| Permission::defaults_for(role));
+        for name in self.assignments.get(&user_id).into_iter().flatten() {
+            if let Some(granted) = self.grants.get(name) {
+                permissions.extend(granted);
+            }
+        }
+        permissions
+    }
+}
+
+/// 🎭 Cached role evaluator, shared through the app state
+#[derive(Debug)]
+pub struct Roles {
+    db_pool: PgPool,
+    /// 📸 Latest snapshot and when it was read
+    cache: RwLock<Option<(Instant, Arc<RoleSet>)>>,
+}
+
+impl Roles {
+    /// ➕ An evaluator that reads roles on first use
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// 🎯 Everything a user may do
+    pub async fn permissions_for(&self, user: &User) -> HashSet<Permission> {
+        self.snapshot().await.permissions_for(user.id, &user.role)
+    }
+
+    /// 📸 Current roles, read again when the snapshot is older than CACHE_TTL
+    /// If the database can't be read, the previous snapshot keeps being used
+    pub async fn snapshot(&self) -> Arc<RoleSet> {
+        let cached = self.cache.read().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some((read_at, roles)) = &cached {
+            if read_at.elapsed() < CACHE_TTL {
+                return roles.clone();
+            }
+        }
+
+        match self.load().await {
+            Ok(roles) => {
+                let roles = Arc::new(roles);
+                *self.cache.write().unwrap_or_else(|e| e.into_inner()) =
+                    Some((Instant::now(), roles.clone()));
+                roles
+            }
+            Err(e) => {
+                warn!("⚠️ Roles could not be read: {:#}", e);
+                cached.map(|(_, roles)| roles).unwrap_or_default()
+            }
+        }
+    }
+
+    /// 🔄 Forget the snapshot so the next check sees the latest changes
+    pub fn invalidate(&self) {
+        *self.cache.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    async fn load(&self) -> Result<RoleSet> {
+        let roles = Role::list(&self.db_pool).await?;
+        let assignments = RoleAssignment::list(&self.db_pool).await?;
+        Ok(RoleSet::new(roles, assignments))
+    }
+}
+
+/// 🏷️ Name of the built-in role for an account role
+pub fn builtin_role(role: &UserRole) -> &'static str {
+    match role {
+        UserRole::User => "user",
+        UserRole::Admin => "admin",
+        UserRole::Service => "service",
+    }
+}
+
+/// ✅ Check a role name: lowercase letters, digits, `_` and `-`, up to 100 characters
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 100
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid role name '{}': use lowercase letters, digits, '_' and '-' (at most 100)",
+            name
+        ))
+    }
+}
+
+/// ✅ Check permission names, returning them deduplicated and sorted
+pub fn validate_permissions(names: &[String]) -> Result<Vec<String>, Vec<String>> {
+    let unknown: Vec<String> = names
+        .iter()
+        .filter(|name| name.parse::<Permission>().is_err())
+        .map(|name| format!("Unknown permission: {}", name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(unknown);
+    }
+    let mut names = names.to_vec();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+// 🧪 Tests - Grants add up, admins get everything!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn role(name: &str, permissions: &[Permission]) -> Role {
+        Role {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            is_builtin: false,
+            permissions: permissions.iter().map(|p| p.as_str().to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        let reviewer_id = Uuid::new_v4();
+        let roles = RoleSet::new(
+            vec![
+                role("user", &[Permission::ReadFeedback]),
+                role("reviewer", &[Permission::ApprovePullRequests]),
+            ],
+            vec![RoleAssignment {
+                user_id: reviewer_id,
+                role_name: "reviewer".to_string(),
+            }],
+        );
+
+        // 🎭 Built-in grants, plus assigned roles
+        let plain = roles.permissions_for(Uuid::new_v4(), &UserRole::User);
+        assert_eq!(plain, [Permission::ReadFeedback].into_iter().collect());
+        let reviewer = roles.permissions_for(reviewer_id, &UserRole::User);
+        assert!(reviewer.contains(&Permission::ApprovePullRequests));
+        assert!(!reviewer.contains(&Permission::ManageProjects));
+
+        // 🌱 Missing built-in roles fall back to the defaults; admins get everything
+        assert_eq!(
+            roles.permissions_for(Uuid::new_v4(), &UserRole::Service),
+            Permission::defaults_for(&UserRole::Service)
+        );
+        assert_eq!(
+            RoleSet::default()
+                .permissions_for(Uuid::new_v4(), &UserRole::Admin)
+                .len(),
+            Permission::ALL.len()
+        );
+        println!("✅ Role permissions test passed!");
+    }
+
+    #[test]
+    fn test_role_validation() {
+        assert!(validate_name("reviewer").is_ok());
+        assert!(validate_name("Release Manager").is_err());
+        let names = vec![
+            "read_feedback".to_string(),
+            "approve_pull_requests".to_string(),
+            "read_feedback".to_string(),
+        ];
+        assert_eq!(
+            validate_permissions(&names).unwrap(),
+            vec!["approve_pull_requests", "read_feedback"]
+        );
+        assert!(validate_permissions(&["fly".to_string()]).is_err());
+        println!("✅ Role validation test passed!");
+    }
+}
@@ -0,0 +1,131 @@
+// 🏢 SCM Endpoints - github.com and GitHub Enterprise, Side by Side! 🏢
+// GITHUB_ENDPOINTS names the GitHub Enterprise Server instances this Feedbacker
+// talks to besides GITHUB_API_BASE_URL, each with its own token
+// (GITHUB_TOKEN_<NAME>). A project picks one with `scm.endpoint` in its config,
+// or inherits its organization's `scm.endpoint` setting; everything else stays
+// on the default. URLs and tokens only live in the instance configuration, so
+// a project admin can't send a token to a server of their own choosing. A
+// project moving to another endpoint must be reachable through it to be saved
+// Created with love by Aye & Hue - One Feedbacker, many GitHubs! ✨
+
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::config::{GitHubConfig, GitHubEndpoint};
+use crate::database::models::{Organization, Project};
+use crate::github::{parse_repository, GitHubClient};
+use crate::models::ScmSettings;
+
+/// 🏷️ The endpoint in effect: the project's own, else its organization's
+pub fn endpoint_name<'a>(
+    project: &'a ScmSettings,
+    organization: Option<&'a ScmSettings>,
+) -> Option<&'a str> {
+    project
+        .endpoint
+        .as_deref()
+        .or_else(|| organization?.endpoint.as_deref())
+}
+
+/// 🐙 GitHub configuration for a project's repository
+pub async fn github_config(
+    pool: &PgPool,
+    github: &GitHubConfig,
+    project: &Project,
+) -> Result<GitHubConfig> {
+    resolve(pool, github, project, &project.settings()?.scm).await
+}
+
+/// 🐙 GitHub configuration for a project with the given `scm` settings
+/// (for settings about to be saved)
+pub async fn resolve(
+    pool: &PgPool,
+    github: &GitHubConfig,
+    project: &Project,
+    scm: &ScmSettings,
+) -> Result<GitHubConfig> {
+    let organization = match (&scm.endpoint, project.organization_id) {
+        (None, Some(organization_id)) => Organization::find_by_id(pool, organization_id)
+            .await?
+            .map(|organization| ScmSettings::from_organization_settings(&organization.settings))
+            .transpose()?,
+        _ => None,
+    };
+    github.for_endpoint(endpoint_name(scm, organization.as_ref()))
+}
+
+/// ✅ Problems with `scm` settings about to be saved: a malformed or unknown
+/// endpoint name
+pub fn validate(endpoints: &[GitHubEndpoint], scm: &ScmSettings) -> Vec<String> {
+    let mut problems = Vec::new();
+    scm.validate_into(&mut problems);
+    if let Some(name) = &scm.endpoint {
+        if problems.is_empty() && !endpoints.iter().any(|endpoint| &endpoint.name == name) {
+            problems.push(format!(
+                "SCM endpoint '{}' is not configured on this instance",
+                name
+            ));
+        }
+    }
+    problems
+}
+
+/// 🔌 Whether `repository` can be reached with a GitHub configuration
+/// (a problem for the caller to show when it can't)
+pub async fn check_connectivity(github: GitHubConfig, repository: &str) -> Option<String> {
+    let api_base_url = github.api_base_url.clone();
+    let reached = async {
+        let (owner, repo) = parse_repository(repository)?;
+        GitHubClient::new(github)?
+            .get_repository_info(&owner, &repo)
+            .await
+    }
+    .await;
+    reached.err().map(|e| {
+        format!(
+            "{} is not reachable through {}: {:#}",
+            repository, api_base_url, e
+        )
+    })
+}
+
+// 🧪 Tests - Every repository finds its way home!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scm(endpoint: Option<&str>) -> ScmSettings {
+        ScmSettings {
+            endpoint: endpoint.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_endpoint_name() {
+        assert_eq!(endpoint_name(&scm(None), None), None);
+        assert_eq!(
+            endpoint_name(&scm(None), Some(&scm(Some("ghe")))),
+            Some("ghe")
+        );
+        assert_eq!(
+            endpoint_name(&scm(Some("ghe-eu")), Some(&scm(Some("ghe")))),
+            Some("ghe-eu")
+        );
+        assert_eq!(endpoint_name(&scm(Some("ghe-eu")), None), Some("ghe-eu"));
+        println!("✅ SCM endpoint inheritance test passed!");
+    }
+
+    #[test]
+    fn test_validate() {
+        let github = [GitHubEndpoint {
+            name: "ghe".to_string(),
+            api_base_url: "https://ghe.example.com/api/v3".to_string(),
+            token: "ghp_ghe".to_string(),
+        }];
+        assert!(validate(&github, &scm(None)).is_empty());
+        assert!(validate(&github, &scm(Some("ghe"))).is_empty());
+        assert_eq!(validate(&github, &scm(Some("ghe-eu"))).len(), 1);
+        assert_eq!(validate(&github, &scm(Some("GHE"))).len(), 1);
+        println!("✅ SCM settings validation test passed!");
+    }
+}
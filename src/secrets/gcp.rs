@@ -0,0 +1,72 @@
+// 🌐 GCP Secret Manager - Secret Versions Over the REST API 🌐
+// `gcp-sm://projects/<project>/secrets/<secret>[/versions/<version>]` reads the
+// version (latest when none is named) with GCP_ACCESS_TOKEN, or with a token
+// from the metadata server when running on Google Cloud
+// Created with love by Aye & Hue - Secrets straight from the cloud! ✨
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde_json::Value;
+
+use crate::config::SecretsConfig;
+
+/// 🎫 Where workloads on Google Cloud get an access token for their service account
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// 📥 The payload of a secret version
+pub async fn fetch(http: &reqwest::Client, config: &SecretsConfig, name: &str) -> Result<String> {
+    let name = if name.contains("/versions/") {
+        name.to_string()
+    } else {
+        format!("{}/versions/latest", name)
+    };
+    let token = match &config.gcp_access_token {
+        Some(token) => token.clone(),
+        None => metadata_token(http).await?,
+    };
+
+    let url = format!("https://secretmanager.googleapis.com/v1/{}:access", name);
+    let response = http
+        .get(&url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .context("GCP Secret Manager request failed")?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("GCP Secret Manager answered {} for {}", status, name);
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .context("GCP Secret Manager response is not JSON")?;
+    let data = body["payload"]["data"]
+        .as_str()
+        .with_context(|| format!("Secret version {} has no payload", name))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .context("Secret payload is not base64")?;
+    String::from_utf8(bytes).context("Secret payload is not UTF-8 text")
+}
+
+/// 🎫 Access token of the instance's service account
+async fn metadata_token(http: &reqwest::Client) -> Result<String> {
+    let response = http
+        .get(METADATA_TOKEN_URL)
+        .header("Metadata-Flavor", "Google")
+        .send()
+        .await
+        .context("GCP_ACCESS_TOKEN is not set and the metadata server is unreachable")?;
+    let body: Value = response
+        .error_for_status()
+        .context("Metadata server refused a token")?
+        .json()
+        .await
+        .context("Metadata server token is not JSON")?;
+    body["access_token"]
+        .as_str()
+        .map(str::to_string)
+        .context("Metadata server response has no access_token")
+}
@@ -0,0 +1,50 @@
+// 🏛️ HashiCorp Vault - KV Secrets Over the HTTP API 🏛️
+// `vault://<path>#<key>` reads GET {VAULT_ADDR}/v1/<path> with VAULT_TOKEN (and
+// VAULT_NAMESPACE on Vault Enterprise). KV v2 paths include the `data/` segment
+// (secret/data/feedbacker); KV v2 and KV v1 responses are both understood
+// Created with love by Aye & Hue - The vault opens for the right token! ✨
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::config::SecretsConfig;
+
+/// 📥 The secret at `path`, as a JSON object of its keys
+pub async fn fetch(http: &reqwest::Client, config: &SecretsConfig, path: &str) -> Result<String> {
+    let addr = config
+        .vault_addr
+        .as_deref()
+        .context("VAULT_ADDR is not set")?;
+    let token = config
+        .vault_token
+        .as_deref()
+        .context("VAULT_TOKEN is not set")?;
+
+    let url = format!("{}/v1/{}", addr.trim_end_matches('/'), path);
+    let mut request = http.get(&url).header("X-Vault-Token", token);
+    if let Some(namespace) = &config.vault_namespace {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response = request.send().await.context("Vault request failed")?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Vault answered {} for {}", status, path);
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .context("Vault response is not JSON")?;
+    secret_data(&body)
+        .map(Value::to_string)
+        .with_context(|| format!("Vault response for {} has no secret data", path))
+}
+
+/// 🗂️ The keys of a KV secret: KV v2 nests them under data.data, next to metadata
+fn secret_data(body: &Value) -> Option<&Value> {
+    let data = body.get("data")?;
+    match (data.get("data"), data.get("metadata")) {
+        (Some(inner), Some(_)) => Some(inner),
+        _ => Some(data),
+    }
+}
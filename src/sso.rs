@@ -0,0 +1,531 @@
+// 🔐 Single Sign-On - Sign In Where You Already Work! 🔐
+// Organizations plug in their OpenID Connect provider (see SsoProvider):
+//   1. 🚪 GET /api/auth/sso/:slug/login sends the browser to the provider with
+//      a one-time state, a nonce and a PKCE challenge
+//   2. 🔙 the provider sends it back to /api/auth/sso/:slug/callback, where the
+//      code is exchanged and the ID token checked (signature from the provider's
+//      JWKS, issuer, audience, expiry, nonce)
+//   3. 🪪 the provider account is linked to a user: a linked account, else a
+//      verified email already in the organization, else a new account (JIT
+//      provisioning); the user's organization role follows their groups
+// Password login can be turned off entirely (PASSWORD_LOGIN_ENABLED=false) or
+// per organization (`enforce_sso`). SAML providers aren't supported yet
+// Created with love by Aye & Hue - One login to rule them all! ✨
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::info;
+
+use crate::auth;
+use crate::database::models::{
+    OrgRole, OrganizationMember, SsoLoginState, SsoProvider, SsoProviderSettings, User, UserRole,
+};
+
+/// ⏳ How long a sign-in may take between leaving for the provider and coming back
+pub const LOGIN_STATE_MAX_AGE_SECONDS: i64 = 600;
+
+/// 📏 Length of generated state and nonce values
+const STATE_LENGTH: usize = 32;
+
+/// 📏 Length of the PKCE verifier (43 to 128 allowed)
+const CODE_VERIFIER_LENGTH: usize = 64;
+
+/// 🗺️ The endpoints a provider publishes in its discovery document
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// 🪪 Who the provider says signed in
+#[derive(Debug, Clone, PartialEq)]
+pub struct SsoIdentity {
+    /// 🆔 The provider's stable ID for the account (`sub`)
+    pub subject: String,
+    pub email: Option<String>,
+    /// ✅ Whether the provider verified the email
+    pub email_verified: bool,
+    pub name: Option<String>,
+    /// 👥 Groups from the provider's groups claim
+    pub groups: Vec<String>,
+}
+
+/// 🎫 What the token endpoint answers
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+/// 🌐 Where the provider sends users back to
+pub fn redirect_uri(public_url: &str, slug: &str) -> String {
+    format!("{}/api/auth/sso/{}/callback", public_url, slug)
+}
+
+/// 🚪 Start a sign-in: remember its state and return the provider URL to send the user to
+pub async fn begin_login(
+    pool: &PgPool,
+    provider: &SsoProvider,
+    redirect_uri: &str,
+) -> Result<String> {
+    let discovery = discover(&provider.issuer_url).await?;
+    let state = auth::generate_secret(STATE_LENGTH);
+    let nonce = auth::generate_secret(STATE_LENGTH);
+    let code_verifier = auth::generate_secret(CODE_VERIFIER_LENGTH);
+    SsoLoginState::create(pool, &state, provider.id, &nonce, &code_verifier).await?;
+
+    let url = reqwest::Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", redirect_uri),
+            ("scope", provider.scopes.as_str()),
+            ("state", state.as_str()),
+            ("nonce", nonce.as_str()),
+            ("code_challenge", pkce_challenge(&code_verifier).as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .context("Provider published an invalid authorization endpoint")?;
+    Ok(url.into())
+}
+
+/// 🔙 Finish a sign-in: exchange the code and check the ID token
+pub async fn finish_login(
+    provider: &SsoProvider,
+    login_state: &SsoLoginState,
+    code: &str,
+    redirect_uri: &str,
+) -> Result<SsoIdentity> {
+    let discovery = discover(&provider.issuer_url).await?;
+    let response = http()
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+            ("code_verifier", login_state.code_verifier.as_str()),
+        ])
+        .send()
+        .await
+        .context("Failed to reach the provider's token endpoint")?
+        .error_for_status()
+        .context("Provider refused the authorization code")?;
+    let tokens: TokenResponse = response
+        .json()
+        .await
+        .context("Provider sent an unreadable token response")?;
+    let id_token = tokens
+        .id_token
+        .context("Provider sent no ID token (is the openid scope requested?)")?;
+
+    let claims = verify_id_token(&discovery, provider, &id_token, &login_state.nonce).await?;
+    identity_from_claims(&claims, &provider.groups_claim)
+}
+
+/// 🪪 The user a provider account signs in as, creating or linking it as needed,
+/// with their organization role brought in line with their groups
+/// The inner error explains why the sign-in was refused
+pub async fn sign_in(
+    pool: &PgPool,
+    provider: &SsoProvider,
+    identity: &SsoIdentity,
+) -> Result<Result<User, String>> {
+    let mut user = match provider.linked_user(pool, &identity.subject).await? {
+        Some(user) => user,
+        None => match find_or_provision(pool, provider, identity).await? {
+            Ok(user) => user,
+            Err(refusal) => return Ok(Err(refusal)),
+        },
+    };
+    if !user.is_active {
+        return Ok(Err("This account is disabled".to_string()));
+    }
+    if matches!(user.role, UserRole::Service) {
+        return Ok(Err("Service accounts can't sign in with SSO".to_string()));
+    }
+
+    provider.link_user(pool, &identity.subject, user.id).await?;
+    user.record_sso_login(pool).await?;
+
+    // 👥 Owners are managed by hand; everyone else gets what their groups say
+    let role = map_role(provider, &identity.groups);
+    let current = OrganizationMember::role_of(pool, provider.organization_id, user.id).await?;
+    if current != Some(OrgRole::Owner) && current != Some(role) {
+        OrganizationMember::set(pool, provider.organization_id, user.id, role).await?;
+    }
+    Ok(Ok(user))
+}
+
+/// 🔍 An existing account for a provider account seen for the first time, or a new one
+async fn find_or_provision(
+    pool: &PgPool,
+    provider: &SsoProvider,
+    identity: &SsoIdentity,
+) -> Result<Result<User, String>> {
+    let Some(email) = identity.email.as_deref() else {
+        return Ok(Err(
+            "The identity provider didn't share an email address".to_string()
+        ));
+    };
+    if !provider.email_domains.is_empty() && !in_email_domains(provider, email) {
+        return Ok(Err(format!(
+            "{} isn't in one of the organization's email domains",
+            email
+        )));
+    }
+
+    if let Some(existing) = User::find_by_email(pool, email).await? {
+        // 🔗 Only accounts already in the organization, so a provider can't claim strangers
+        let member = OrganizationMember::role_of(pool, provider.organization_id, existing.id)
+            .await?
+            .is_some();
+        if !identity.email_verified || !member || matches!(existing.role, UserRole::Admin) {
+            return Ok(Err(format!(
+                "An account for {} already exists; an organization admin has to add it before it can use SSO",
+                email
+            )));
+        }
+        return Ok(Ok(existing));
+    }
+
+    if !provider.jit_provisioning {
+        return Ok(Err(format!(
+            "There is no account for {}; ask an organization admin to invite you",
+            email
+        )));
+    }
+    // 🌱 Password login stays impossible: nobody knows this password
+    let password_hash =
+        auth::hash_password(&auth::generate_secret(auth::GENERATED_PASSWORD_LENGTH))?;
+    let name = identity.name.clone().unwrap_or_else(|| email.to_string());
+    let user = User::create(pool, email.to_string(), name, password_hash, UserRole::User).await?;
+    info!("🌱 Provisioned {} through single sign-on", user.email);
+    Ok(Ok(user))
+}
+
+/// ✅ Check provider settings (`has_secret`: a client secret is already stored)
+pub fn validate_settings(
+    settings: &SsoProviderSettings,
+    has_secret: bool,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let issuer = settings.issuer_url.as_str();
+    let local = issuer.starts_with("http://localhost") || issuer.starts_with("http://127.0.0.1");
+    if !issuer.starts_with("https://") && !local {
+        errors.push("issuer_url must start with https://".to_string());
+    }
+    if settings.client_id.trim().is_empty() {
+        errors.push("client_id is required".to_string());
+    }
+    let secret_given = settings
+        .client_secret
+        .as_deref()
+        .is_some_and(|secret| !secret.is_empty());
+    if !secret_given && !has_secret {
+        errors.push("client_secret is required".to_string());
+    }
+    if !settings
+        .scopes
+        .split_whitespace()
+        .any(|scope| scope == "openid")
+    {
+        errors.push("scopes must include openid".to_string());
+    }
+    if settings.groups_claim.trim().is_empty() {
+        errors.push("groups_claim must not be empty".to_string());
+    }
+    for domain in &settings.email_domains {
+        let domain = domain.trim();
+        if domain.is_empty() || domain.contains('@') || !domain.contains('.') {
+            errors.push(format!("Invalid email domain: {}", domain));
+        }
+    }
+    if settings.enforce_sso && settings.email_domains.is_empty() {
+        errors.push("enforce_sso needs at least one email domain".to_string());
+    }
+    // 👑 Ownership is only ever granted by hand
+    if settings.default_role == OrgRole::Owner
+        || settings
+            .role_mappings
+            .values()
+            .any(|role| *role == OrgRole::Owner)
+    {
+        errors.push("Groups can't be mapped to the owner role".to_string());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 👑 Highest organization role any of the user's groups maps to, else the default
+pub fn map_role(provider: &SsoProvider, groups: &[String]) -> OrgRole {
+    let mappings: HashMap<String, OrgRole> =
+        serde_json::from_value(provider.role_mappings.clone()).unwrap_or_default();
+    groups
+        .iter()
+        .filter_map(|group| mappings.get(group))
+        .copied()
+        .max()
+        .unwrap_or_else(|| provider.default_role.parse().unwrap_or(OrgRole::Viewer))
+}
+
+/// 📧 Whether an email belongs to one of the provider's domains
+pub fn in_email_domains(provider: &SsoProvider, email: &str) -> bool {
+    email.rsplit_once('@').is_some_and(|(_, domain)| {
+        provider
+            .email_domains
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+    })
+}
+
+/// 🔑 PKCE S256 challenge for a verifier (RFC 7636)
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// 🪪 Read the identity out of verified ID token claims
+/// `groups_claim` may hold a list of names or a single one
+pub fn identity_from_claims(
+    claims: &Map<String, Value>,
+    groups_claim: &str,
+) -> Result<SsoIdentity> {
+    let text = |name: &str| claims.get(name).and_then(Value::as_str).map(str::to_string);
+    let subject = text("sub").context("ID token has no subject")?;
+    let groups = match claims.get(groups_claim) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(group)) => vec![group.clone()],
+        _ => Vec::new(),
+    };
+    // ✅ Some providers send "true" as a string
+    let email_verified = match claims.get("email_verified") {
+        Some(Value::Bool(verified)) => *verified,
+        Some(Value::String(verified)) => verified == "true",
+        _ => false,
+    };
+
+    Ok(SsoIdentity {
+        subject,
+        email: text("email"),
+        email_verified,
+        name: text("name"),
+        groups,
+    })
+}
+
+/// 🔏 Check an ID token against the provider's keys, issuer, client ID and nonce
+async fn verify_id_token(
+    discovery: &Discovery,
+    provider: &SsoProvider,
+    id_token: &str,
+    nonce: &str,
+) -> Result<Map<String, Value>> {
+    let header = jsonwebtoken::decode_header(id_token).context("Malformed ID token")?;
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        anyhow::bail!("ID tokens signed with a shared secret aren't accepted");
+    }
+
+    let keys: JwkSet = http()
+        .get(&discovery.jwks_uri)
+        .send()
+        .await
+        .context("Failed to fetch the provider's signing keys")?
+        .error_for_status()
+        .context("Provider refused to share its signing keys")?
+        .json()
+        .await
+        .context("Provider sent unreadable signing keys")?;
+    let key = match &header.kid {
+        Some(kid) => keys.find(kid),
+        None => keys.keys.first(),
+    }
+    .context("No provider signing key matches the ID token")?;
+    let key = DecodingKey::from_jwk(key).context("Unusable provider signing key")?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[discovery.issuer.as_str()]);
+    validation.set_audience(&[provider.client_id.as_str()]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+    let token = jsonwebtoken::decode::<Map<String, Value>>(id_token, &key, &validation)
+        .context("ID token failed verification")?;
+
+    if token.claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+        anyhow::bail!("ID token nonce doesn't match this sign-in");
+    }
+    Ok(token.claims)
+}
+
+/// 🗺️ Fetch a provider's discovery document
+async fn discover(issuer_url: &str) -> Result<Discovery> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer_url.trim_end_matches('/')
+    );
+    let discovery: Discovery = http()
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Provider refused {}", url))?
+        .json()
+        .await
+        .context("Provider sent an unreadable discovery document")?;
+
+    if discovery.issuer.trim_end_matches('/') != issuer_url.trim_end_matches('/') {
+        anyhow::bail!(
+            "Provider claims issuer {} instead of {}",
+            discovery.issuer,
+            issuer_url
+        );
+    }
+    Ok(discovery)
+}
+
+/// 🌐 HTTP client for provider calls
+fn http() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+// 🧪 Tests - The right people, with the right roles!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn provider(role_mappings: Value, email_domains: &[&str]) -> SsoProvider {
+        SsoProvider {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            protocol: "oidc".to_string(),
+            issuer_url: "https://login.example.com".to_string(),
+            client_id: "feedbacker".to_string(),
+            client_secret: "secret".to_string(),
+            scopes: "openid email profile".to_string(),
+            email_domains: email_domains.iter().map(|d| d.to_string()).collect(),
+            groups_claim: "groups".to_string(),
+            role_mappings,
+            default_role: "member".to_string(),
+            jit_provisioning: true,
+            enforce_sso: false,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_map_role() {
+        let provider = provider(
+            serde_json::json!({ "eng-leads": "admin", "contractors": "viewer" }),
+            &[],
+        );
+        let groups = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            map_role(&provider, &groups(&["contractors"])),
+            OrgRole::Viewer
+        );
+        assert_eq!(
+            map_role(&provider, &groups(&["contractors", "eng-leads"])),
+            OrgRole::Admin
+        );
+        // 🌱 No mapped group: the default role
+        assert_eq!(map_role(&provider, &groups(&["sales"])), OrgRole::Member);
+        assert_eq!(map_role(&provider, &[]), OrgRole::Member);
+        println!("✅ SSO role mapping test passed!");
+    }
+
+    #[test]
+    fn test_identity_from_claims() {
+        let claims = serde_json::json!({
+            "sub": "00u1abcd",
+            "email": "hue@example.com",
+            "email_verified": "true",
+            "name": "Hue",
+            "roles": "eng-leads",
+        });
+        let identity = identity_from_claims(claims.as_object().unwrap(), "roles").unwrap();
+        assert_eq!(identity.subject, "00u1abcd");
+        assert_eq!(identity.email.as_deref(), Some("hue@example.com"));
+        assert!(identity.email_verified);
+        assert_eq!(identity.groups, vec!["eng-leads"]);
+
+        let anonymous = serde_json::json!({ "email": "someone@example.com" });
+        assert!(identity_from_claims(anonymous.as_object().unwrap(), "groups").is_err());
+        println!("✅ SSO claims test passed!");
+    }
+
+    #[test]
+    fn test_pkce_and_domains() {
+        // 🔑 Example from RFC 7636, appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let provider = provider(Value::Object(Map::new()), &["example.com"]);
+        assert!(in_email_domains(&provider, "aye@Example.com"));
+        assert!(!in_email_domains(&provider, "aye@example.com.evil.io"));
+        assert!(!in_email_domains(&provider, "not-an-email"));
+        assert_eq!(
+            redirect_uri("https://feedbacker.example.com", "aye-is"),
+            "https://feedbacker.example.com/api/auth/sso/aye-is/callback"
+        );
+        println!("✅ PKCE and email domain test passed!");
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let settings: SsoProviderSettings = serde_json::from_value(serde_json::json!({
+            "issuer_url": "https://login.example.com",
+            "client_id": "feedbacker",
+            "client_secret": "secret",
+            "email_domains": ["example.com"],
+            "role_mappings": { "eng-leads": "admin" },
+            "enforce_sso": true,
+        }))
+        .unwrap();
+        assert!(validate_settings(&settings, false).is_ok());
+        assert_eq!(settings.default_role, OrgRole::Member);
+        assert!(settings.jit_provisioning);
+
+        // 🔒 A stored secret may be kept; owners and plain HTTP may not be handed out
+        let mut changed = settings.clone();
+        changed.client_secret = None;
+        assert!(validate_settings(&changed, true).is_ok());
+        assert!(validate_settings(&changed, false).is_err());
+        changed.issuer_url = "http://login.example.com".to_string();
+        changed
+            .role_mappings
+            .insert("founders".to_string(), OrgRole::Owner);
+        changed.email_domains.clear();
+        assert_eq!(validate_settings(&changed, true).unwrap_err().len(), 3);
+        println!("✅ SSO settings validation test passed!");
+    }
+}
@@ -0,0 +1,896 @@
+// 🎨 Style Guide Learning - Generated Code That Looks Like It Belongs! 🎨
+// Every project gets its repository analyzed once it's onboarded (the hourly
+// `style_analysis` schedule picks up projects that were never analyzed, and
+// refreshes profiles older than a month): indentation and line width from
+// .editorconfig, rustfmt, prettier, black and ruff settings (measured from the
+// sources when nothing is configured), the formatters and linters that guard
+// the code, how source files are named, and how commit subjects read. The
+// profile is stored per project and appended to the project's system message
+// for generation prompts, so the model follows the house style instead of its
+// own habits. Maintainers read it, or ask for a fresh analysis, at
+// /api/projects/:id/style-profile
+// Created with love by Aye & Hue - When in Rome, indent like the Romans! ✨
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::config::GitHubConfig;
+use crate::database::models::{
+    BackgroundJob, JobPriority, NewBackgroundJob, Project, ProjectStyleProfile,
+};
+use crate::github::git::{github_clone_url, CloneCache, CloneOptions};
+use crate::github::{parse_repository, GitHubClient};
+use crate::jobs::repo_health::SourceFile;
+use crate::jobs::worker::{self, JobHandler};
+use crate::pipeline::{docs::Language, licensing};
+use crate::scm;
+
+/// 🔧 Job type of style analyses (one project, or every project that is due)
+pub const STYLE_ANALYSIS_JOB: &str = "style_analysis";
+
+/// 📅 Profiles older than this are analyzed again
+const MAX_PROFILE_AGE_DAYS: i32 = 30;
+
+/// 🔢 Projects analyzed per scheduled run
+const PROJECTS_PER_RUN: i64 = 10;
+
+/// 📜 Recent commits read to learn the commit style
+const COMMITS_SAMPLED: u32 = 50;
+
+/// 📜 Commit subjects kept as examples
+const MAX_COMMIT_EXAMPLES: usize = 3;
+
+/// 📏 Files larger than this aren't read
+const MAX_ANALYZED_FILE_BYTES: usize = 64 * 1024;
+
+/// 📄 Source files read for indentation and naming
+const MAX_SOURCE_FILES: usize = 200;
+
+/// ↔️ Indented lines needed before measured indentation is trusted
+const MIN_INDENTED_LINES: usize = 20;
+
+/// 🏷️ Distinctly named source files needed before a naming convention is trusted
+const MIN_NAMED_FILES: usize = 3;
+
+/// 🧰 Config files that give away a formatter or linter, by file name
+const TOOL_CONFIGS: &[(&str, &str)] = &[
+    ("rustfmt.toml", "rustfmt"),
+    (".rustfmt.toml", "rustfmt"),
+    ("clippy.toml", "clippy"),
+    (".clippy.toml", "clippy"),
+    (".prettierrc", "prettier"),
+    (".prettierrc.json", "prettier"),
+    (".prettierrc.yaml", "prettier"),
+    (".prettierrc.yml", "prettier"),
+    ("prettier.config.js", "prettier"),
+    (".eslintrc", "eslint"),
+    (".eslintrc.json", "eslint"),
+    (".eslintrc.js", "eslint"),
+    ("eslint.config.js", "eslint"),
+    ("biome.json", "biome"),
+    ("ruff.toml", "ruff"),
+    (".flake8", "flake8"),
+    (".clang-format", "clang-format"),
+    (".editorconfig", "editorconfig"),
+];
+
+/// 🐍 pyproject.toml `[tool.*]` sections that configure a formatter or linter
+const PYPROJECT_TOOLS: &[&str] = &["black", "ruff", "isort", "flake8"];
+
+/// ↔️ How code is indented
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Indentation {
+    /// ⇥ Hard tabs
+    Tabs,
+    /// ␣ This many spaces per level
+    Spaces(u8),
+}
+
+impl Indentation {
+    /// 📝 Human-readable description for prompts
+    pub fn describe(self) -> String {
+        match self {
+            Indentation::Tabs => "tabs".to_string(),
+            Indentation::Spaces(width) => format!("{} spaces", width),
+        }
+    }
+}
+
+/// 🏷️ How source file names are written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingConvention {
+    /// 🐍 `user_profile.rs`
+    Snake,
+    /// 🍢 `user-profile.ts`
+    Kebab,
+    /// 🐪 `userProfile.js`
+    Camel,
+    /// 🏛️ `UserProfile.tsx`
+    Pascal,
+}
+
+impl NamingConvention {
+    /// 🔍 Convention of a file stem (None when a single lowercase word can't tell)
+    pub fn of(stem: &str) -> Option<Self> {
+        if stem.is_empty() || stem.starts_with(['_', '-']) {
+            return None;
+        }
+        let upper = stem.chars().any(|c| c.is_ascii_uppercase());
+        let lower = stem.chars().any(|c| c.is_ascii_lowercase());
+        match (stem.contains('_'), stem.contains('-')) {
+            (true, false) if !upper => Some(NamingConvention::Snake),
+            (false, true) if !upper => Some(NamingConvention::Kebab),
+            (false, false) if upper && lower => {
+                if stem.starts_with(|c: char| c.is_ascii_uppercase()) {
+                    Some(NamingConvention::Pascal)
+                } else {
+                    Some(NamingConvention::Camel)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// 📝 Name of the convention, written in itself
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NamingConvention::Snake => "snake_case",
+            NamingConvention::Kebab => "kebab-case",
+            NamingConvention::Camel => "camelCase",
+            NamingConvention::Pascal => "PascalCase",
+        }
+    }
+}
+
+/// 📜 How commit subjects are written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitStyle {
+    /// 🏷️ `feat(api): add pagination`
+    Conventional,
+    /// 🎫 `[PROJ-12] Add pagination`
+    BracketedId,
+    /// ✨ `✨ Add pagination` or `:sparkles: Add pagination`
+    Emoji,
+    /// 📝 `Add pagination`
+    Plain,
+}
+
+impl CommitStyle {
+    /// 🔍 Style of one commit subject
+    pub fn of(subject: &str) -> Self {
+        if is_conventional(subject) {
+            CommitStyle::Conventional
+        } else if subject.starts_with('[') && subject.contains(']') {
+            CommitStyle::BracketedId
+        } else if starts_with_emoji(subject) {
+            CommitStyle::Emoji
+        } else {
+            CommitStyle::Plain
+        }
+    }
+
+    /// 📝 Human-readable description for prompts
+    pub fn describe(self) -> &'static str {
+        match self {
+            CommitStyle::Conventional => "Conventional Commits (`type(scope): summary`)",
+            CommitStyle::BracketedId => "a bracketed ticket id first (`[ID] Summary`)",
+            CommitStyle::Emoji => "an emoji first",
+            CommitStyle::Plain => "a plain summary",
+        }
+    }
+}
+
+/// 🏷️ `type(scope)!: summary` with a lowercase type
+fn is_conventional(subject: &str) -> bool {
+    let Some((prefix, summary)) = subject.split_once(": ") else {
+        return false;
+    };
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let kind = match prefix.split_once('(') {
+        Some((kind, scope)) if scope.ends_with(')') => kind,
+        Some(_) => return false,
+        None => prefix,
+    };
+    !kind.is_empty() && kind.chars().all(|c| c.is_ascii_lowercase()) && !summary.trim().is_empty()
+}
+
+/// ✨ A leading emoji, or a leading gitmoji code like `:sparkles:`
+fn starts_with_emoji(subject: &str) -> bool {
+    let Some(first) = subject.chars().next() else {
+        return false;
+    };
+    if let Some(code) = subject.strip_prefix(':') {
+        return code.split_once(':').is_some_and(|(name, _)| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    }
+    !first.is_ascii() && !first.is_alphanumeric()
+}
+
+/// 🎨 What a repository's code and history say about its conventions
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StyleProfile {
+    /// 🧰 Formatters and linters configured in the repository
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// ↔️ Indentation, from configs or measured in the sources
+    pub indentation: Option<Indentation>,
+    /// 📏 Longest line the configs allow
+    pub max_line_width: Option<u32>,
+    /// 🏷️ How source files are named
+    pub file_naming: Option<NamingConvention>,
+    /// 📜 How commit subjects are written
+    pub commit_style: Option<CommitStyle>,
+    /// 📜 Recent subjects written in that style
+    #[serde(default)]
+    pub commit_examples: Vec<String>,
+}
+
+impl StyleProfile {
+    /// 📝 The profile as a prompt section (None when nothing was learned)
+    pub fn prompt_section(&self) -> Option<String> {
+        let mut rules = Vec::new();
+        if let Some(indentation) = self.indentation {
+            rules.push(format!("- Indent with {}", indentation.describe()));
+        }
+        if let Some(width) = self.max_line_width {
+            rules.push(format!("- Keep lines within {} characters", width));
+        }
+        if !self.tools.is_empty() {
+            rules.push(format!(
+                "- Code must pass {} with the repository's configuration",
+                self.tools.join(", ")
+            ));
+        }
+        if let Some(naming) = self.file_naming {
+            rules.push(format!("- Name new source files in {}", naming.as_str()));
+        }
+        if let Some(style) = self.commit_style {
+            let mut rule = format!("- Commit subjects use {}", style.describe());
+            if !self.commit_examples.is_empty() {
+                let examples: Vec<String> = self
+                    .commit_examples
+                    .iter()
+                    .map(|subject| format!("`{}`", subject))
+                    .collect();
+                rule.push_str(&format!(", e.g. {}", examples.join(", ")));
+            }
+            rules.push(rule);
+        }
+        if rules.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "## House style\nThis repository has its own conventions. Follow them instead of your usual style, and match the code around your change:\n{}",
+            rules.join("\n")
+        ))
+    }
+
+    /// 💬 A system message with the profile appended to `base`
+    pub fn system_message(&self, base: Option<&str>) -> Option<String> {
+        let base = base.map(str::trim).filter(|base| !base.is_empty());
+        match (base, self.prompt_section()) {
+            (Some(base), Some(section)) => Some(format!("{}\n\n{}", base, section)),
+            (None, Some(section)) => Some(section),
+            (base, None) => base.map(str::to_string),
+        }
+    }
+}
+
+/// ⚙️ Indentation and line width a config file asks for
+#[derive(Debug, Default, PartialEq)]
+struct ConfiguredStyle {
+    indentation: Option<Indentation>,
+    max_line_width: Option<u32>,
+}
+
+/// 📄 File name of a repository path
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// 🧰 Formatter or linter a config file belongs to
+fn config_tool(path: &str) -> Option<&'static str> {
+    let name = file_name(path);
+    TOOL_CONFIGS
+        .iter()
+        .find(|(config, _)| *config == name)
+        .map(|(_, tool)| *tool)
+}
+
+/// 📋 The files worth reading: every tool config and pyproject.toml, plus the
+/// first MAX_SOURCE_FILES source files
+pub fn style_inputs(paths: Vec<String>) -> Vec<String> {
+    let mut sources = 0;
+    paths
+        .into_iter()
+        .filter(|path| {
+            if config_tool(path).is_some() || file_name(path) == "pyproject.toml" {
+                return true;
+            }
+            if Language::from_path(path).is_none() || sources >= MAX_SOURCE_FILES {
+                return false;
+            }
+            sources += 1;
+            true
+        })
+        .collect()
+}
+
+/// ⚙️ Settings of an `.editorconfig` `[*]` section
+fn editorconfig(content: &str) -> ConfiguredStyle {
+    let mut style = ConfiguredStyle::default();
+    let mut settings = HashMap::new();
+    let mut in_wildcard = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_wildcard = line == "[*]";
+        } else if let Some((key, value)) = line.split_once('=') {
+            if in_wildcard {
+                settings.insert(key.trim().to_lowercase(), value.trim().to_lowercase());
+            }
+        }
+    }
+
+    let size = settings
+        .get("indent_size")
+        .or_else(|| settings.get("tab_width"))
+        .and_then(|size| size.parse().ok());
+    style.indentation = match settings.get("indent_style").map(String::as_str) {
+        Some("tab") => Some(Indentation::Tabs),
+        Some("space") => size.map(Indentation::Spaces),
+        _ => None,
+    };
+    style.max_line_width = settings
+        .get("max_line_length")
+        .and_then(|width| width.parse().ok());
+    style
+}
+
+/// 🦀 rustfmt settings (rustfmt's defaults for whatever isn't set)
+fn rustfmt(content: &str) -> ConfiguredStyle {
+    let config: toml::Table = toml::from_str(content).unwrap_or_default();
+    let hard_tabs = config.get("hard_tabs").and_then(toml::Value::as_bool);
+    let tab_spaces = config.get("tab_spaces").and_then(toml::Value::as_integer);
+    let max_width = config.get("max_width").and_then(toml::Value::as_integer);
+    ConfiguredStyle {
+        indentation: Some(if hard_tabs == Some(true) {
+            Indentation::Tabs
+        } else {
+            Indentation::Spaces(tab_spaces.unwrap_or(4) as u8)
+        }),
+        max_line_width: Some(max_width.unwrap_or(100) as u32),
+    }
+}
+
+/// 💅 prettier settings (only JSON configs can be read; prettier's defaults otherwise)
+fn prettier(content: &str) -> ConfiguredStyle {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(content) else {
+        return ConfiguredStyle::default();
+    };
+    ConfiguredStyle {
+        indentation: Some(if config["useTabs"].as_bool() == Some(true) {
+            Indentation::Tabs
+        } else {
+            Indentation::Spaces(config["tabWidth"].as_u64().unwrap_or(2) as u8)
+        }),
+        max_line_width: Some(config["printWidth"].as_u64().unwrap_or(80) as u32),
+    }
+}
+
+/// 🐍 black and ruff settings of a pyproject.toml or ruff.toml (`table` is the
+/// ruff/black settings table itself)
+fn python_tool(table: &toml::Table) -> ConfiguredStyle {
+    let width = table
+        .get("line-length")
+        .and_then(toml::Value::as_integer)
+        .unwrap_or(88);
+    let indent = table
+        .get("indent-width")
+        .and_then(toml::Value::as_integer)
+        .unwrap_or(4);
+    ConfiguredStyle {
+        indentation: Some(Indentation::Spaces(indent as u8)),
+        max_line_width: Some(width as u32),
+    }
+}
+
+/// ⚙️ What one config file asks for, and the tools it configures
+fn read_config(path: &str, content: &str) -> (ConfiguredStyle, Vec<&'static str>) {
+    if file_name(path) == "pyproject.toml" {
+        let config: toml::Table = toml::from_str(content).unwrap_or_default();
+        let Some(tool) = config.get("tool").and_then(toml::Value::as_table) else {
+            return (ConfiguredStyle::default(), Vec::new());
+        };
+        let tools: Vec<&'static str> = PYPROJECT_TOOLS
+            .iter()
+            .copied()
+            .filter(|name| tool.contains_key(*name))
+            .collect();
+        let style = ["black", "ruff"]
+            .iter()
+            .find_map(|name| tool.get(*name).and_then(toml::Value::as_table))
+            .map(python_tool)
+            .unwrap_or_default();
+        return (style, tools);
+    }
+
+    let Some(tool) = config_tool(path) else {
+        return (ConfiguredStyle::default(), Vec::new());
+    };
+    let style = match tool {
+        "editorconfig" => editorconfig(content),
+        "rustfmt" => rustfmt(content),
+        "prettier" => prettier(content),
+        "ruff" => python_tool(&toml::from_str(content).unwrap_or_default()),
+        _ => ConfiguredStyle::default(),
+    };
+    (style, vec![tool])
+}
+
+/// ↔️ Indentation most source lines use
+fn measure_indentation(files: &[SourceFile]) -> Option<Indentation> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps: HashMap<usize, usize> = HashMap::new();
+    for file in files
+        .iter()
+        .filter(|file| Language::from_path(&file.path).is_some())
+    {
+        let mut previous = 0;
+        for line in file.content.lines().filter(|line| !line.trim().is_empty()) {
+            if line.starts_with('\t') {
+                tab_lines += 1;
+                continue;
+            }
+            let width = line.len() - line.trim_start_matches(' ').len();
+            if width > 0 {
+                space_lines += 1;
+            }
+            if width > previous {
+                *steps.entry(width - previous).or_default() += 1;
+            }
+            previous = width;
+        }
+    }
+
+    if tab_lines + space_lines < MIN_INDENTED_LINES {
+        return None;
+    }
+    if tab_lines > space_lines {
+        return Some(Indentation::Tabs);
+    }
+    // 📏 Continuation lines and doc comment stars make odd steps, so only the
+    // usual widths count
+    steps
+        .into_iter()
+        .filter(|(step, _)| matches!(*step, 2 | 4 | 8))
+        .max_by_key(|(step, count)| (*count, *step))
+        .map(|(step, _)| Indentation::Spaces(step as u8))
+}
+
+/// 🏷️ The naming convention of at least two thirds of the distinctly named source files
+fn measure_file_naming(files: &[SourceFile]) -> Option<NamingConvention> {
+    let mut counts: HashMap<NamingConvention, usize> = HashMap::new();
+    for file in files
+        .iter()
+        .filter(|file| Language::from_path(&file.path).is_some())
+    {
+        let stem = file_name(&file.path).split('.').next().unwrap_or_default();
+        if let Some(convention) = NamingConvention::of(stem) {
+            *counts.entry(convention).or_default() += 1;
+        }
+    }
+
+    let total: usize = counts.values().sum();
+    let (convention, count) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    (total >= MIN_NAMED_FILES && count * 3 >= total * 2).then_some(convention)
+}
+
+/// 📜 The style of at least half the recent commit subjects, with examples
+/// (merge commits say nothing about how people write)
+fn measure_commit_style(messages: &[String]) -> (Option<CommitStyle>, Vec<String>) {
+    let subjects: Vec<&str> = messages
+        .iter()
+        .filter_map(|message| message.lines().next())
+        .map(str::trim)
+        .filter(|subject| !subject.is_empty() && !subject.starts_with("Merge "))
+        .collect();
+    let mut counts: HashMap<CommitStyle, usize> = HashMap::new();
+    for subject in &subjects {
+        *counts.entry(CommitStyle::of(subject)).or_default() += 1;
+    }
+
+    let Some((style, count)) = counts.into_iter().max_by_key(|(_, count)| *count) else {
+        return (None, Vec::new());
+    };
+    if count * 2 < subjects.len() {
+        return (None, Vec::new());
+    }
+    let examples = subjects
+        .iter()
+        .filter(|subject| CommitStyle::of(subject) == style)
+        .take(MAX_COMMIT_EXAMPLES)
+        .map(|subject| subject.to_string())
+        .collect();
+    (Some(style), examples)
+}
+
+/// 🎨 Learn a repository's style from its files and recent commit messages
+/// Configs closest to the root win; indentation is measured from the sources
+/// when no config sets it
+pub fn analyze(files: &[SourceFile], commit_messages: &[String]) -> StyleProfile {
+    let mut configs: Vec<&SourceFile> = files
+        .iter()
+        .filter(|file| {
+            config_tool(&file.path).is_some() || file_name(&file.path) == "pyproject.toml"
+        })
+        .collect();
+    configs.sort_by_key(|file| (file.path.matches('/').count(), file.path.as_str()));
+
+    let mut profile = StyleProfile::default();
+    for file in configs {
+        let (style, tools) = read_config(&file.path, &file.content);
+        profile.indentation = profile.indentation.or(style.indentation);
+        profile.max_line_width = profile.max_line_width.or(style.max_line_width);
+        for tool in tools {
+            // 📐 .editorconfig is a convention file, not something that checks the code
+            if tool != "editorconfig" && !profile.tools.iter().any(|known| known == tool) {
+                profile.tools.push(tool.to_string());
+            }
+        }
+    }
+
+    profile.indentation = profile.indentation.or_else(|| measure_indentation(files));
+    profile.file_naming = measure_file_naming(files);
+    (profile.commit_style, profile.commit_examples) = measure_commit_style(commit_messages);
+    profile
+}
+
+/// 👀 A stored profile as the API shows it, with the section prompts get
+#[derive(Debug, Serialize)]
+pub struct StyleProfileView {
+    pub profile: StyleProfile,
+    /// 🔖 Commit the checkout was analyzed at
+    pub commit_sha: Option<String>,
+    /// 📅 When the analysis ran
+    pub analyzed_at: DateTime<Utc>,
+    /// 📝 What is appended to the system message (None when nothing was learned)
+    pub prompt_section: Option<String>,
+}
+
+impl StyleProfileView {
+    /// 👀 View of a stored profile
+    pub fn new(stored: ProjectStyleProfile) -> Result<Self> {
+        let profile: StyleProfile =
+            serde_json::from_value(stored.profile).context("Stored style profile is invalid")?;
+        Ok(Self {
+            prompt_section: profile.prompt_section(),
+            profile,
+            commit_sha: stored.commit_sha,
+            analyzed_at: stored.analyzed_at,
+        })
+    }
+}
+
+/// 🎨 The project with its learned style, and the license header new files
+/// need, appended to its system message (unchanged while it was never
+/// analyzed and has no header)
+pub async fn styled(pool: &PgPool, project: &Project) -> Result<Project> {
+    let mut project = project.clone();
+    if let Some(stored) = ProjectStyleProfile::find(pool, project.id).await? {
+        let profile: StyleProfile =
+            serde_json::from_value(stored.profile).context("Stored style profile is invalid")?;
+        project.system_message = profile.system_message(project.system_message.as_deref());
+    }
+    // ⚖️ Asked for up front, so the licensing check doesn't fail the run later
+    if let Some(section) =
+        licensing::prompt_section(&project.settings()?.licensing, Utc::now().year())
+    {
+        project.system_message = Some(match project.system_message.take() {
+            Some(message) => format!("{}\n\n{}", message.trim_end(), section),
+            None => section,
+        });
+    }
+    Ok(project)
+}
+
+/// 📥 Queue an analysis of one project, unless one is already waiting
+/// Returns whether it was queued
+pub async fn queue_analysis(app_state: &AppState, project_id: Uuid) -> Result<bool> {
+    let payload = json!({ "project_id": project_id });
+    if BackgroundJob::is_queued(&app_state.db_pool, STYLE_ANALYSIS_JOB, &payload).await? {
+        debug!(
+            "⏳ Style analysis of project {} is already queued",
+            project_id
+        );
+        return Ok(false);
+    }
+
+    let job = NewBackgroundJob::new(STYLE_ANALYSIS_JOB, payload).with_priority(JobPriority::Bulk);
+    app_state.jobs.enqueue(&job).await?;
+    debug!("📥 Queued style analysis of project {}", project_id);
+    Ok(true)
+}
+
+/// 🔧 Worker pool handler that analyzes the job's project, or every project
+/// that is due when the job names none (the hourly schedule)
+pub fn style_analysis_handler(app_state: &AppState) -> JobHandler {
+    let db_pool = app_state.db_pool.clone();
+    let config = app_state.config.clone();
+    worker::handler(move |job| {
+        let db_pool = db_pool.clone();
+        let config = config.load_full();
+        async move {
+            let Some(project_id) = job.payload.get("project_id") else {
+                return analyze_due(&db_pool, &config.github).await;
+            };
+            let project_id: Uuid = serde_json::from_value(project_id.clone())
+                .context("Style analysis job has an invalid project id")?;
+            let project = Project::find_by_id(&db_pool, project_id)
+                .await?
+                .with_context(|| format!("Project {} not found", project_id))?;
+            analyze_project(&db_pool, &config.github, &project).await?;
+            Ok(())
+        }
+    })
+}
+
+/// 🔄 Analyze the projects that were never analyzed (newly onboarded) or whose
+/// profile is stale. One project failing doesn't hold up the others
+pub async fn analyze_due(pool: &PgPool, github: &GitHubConfig) -> Result<()> {
+    let projects =
+        Project::list_style_analysis_due(pool, MAX_PROFILE_AGE_DAYS, PROJECTS_PER_RUN).await?;
+    for project in &projects {
+        if let Err(e) = analyze_project(pool, github, project).await {
+            warn!(
+                "⚠️ Could not analyze the style of {}: {:#}",
+                project.repository, e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 🎨 Learn a project's style from its checkout and recent commits, and store it
+pub async fn analyze_project(
+    pool: &PgPool,
+    github: &GitHubConfig,
+    project: &Project,
+) -> Result<ProjectStyleProfile> {
+    let settings = project.settings()?;
+    let github_config = scm::github_config(pool, github, project).await?;
+    let options = CloneOptions {
+        scope: settings.scope()?,
+        token: Some(github_config.token.clone()),
+        ..Default::default()
+    };
+    let cache = CloneCache::new(&github.clone_cache_dir, github.clone_cache_size);
+    let repository = project.repository.clone();
+    let clone_url = github_clone_url(&github_config.api_base_url, &repository);
+
+    // 🧵 git2 and file reads are blocking
+    let (files, head_sha) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let workspace = cache.checkout(&repository, &clone_url, &options)?;
+        let objects = workspace.objects()?;
+        let files: Vec<SourceFile> = style_inputs(objects.filter_context(&workspace.list_files()?))
+            .into_iter()
+            .filter_map(|path| {
+                let content = workspace.read_file(&path)?;
+                (content.len() <= MAX_ANALYZED_FILE_BYTES).then_some(SourceFile { path, content })
+            })
+            .collect();
+        Ok((files, workspace.head_sha()?))
+    })
+    .await
+    .context("Style analysis task panicked")??;
+
+    let (owner, repo) = parse_repository(&project.repository)?;
+    let messages = GitHubClient::new(github_config)?
+        .recent_commit_messages(&owner, &repo, COMMITS_SAMPLED)
+        .await?;
+
+    let profile = analyze(&files, &messages);
+    info!(
+        "🎨 Learned the style of {} from {} files and {} commits",
+        project.repository,
+        files.len(),
+        messages.len()
+    );
+    ProjectStyleProfile::upsert(
+        pool,
+        project.id,
+        &serde_json::to_value(&profile)?,
+        Some(&head_sha),
+    )
+    .await
+}
+
+// 🧪 Tests - Learning the house rules!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> SourceFile {
+        SourceFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_conventions() {
+        assert_eq!(
+            NamingConvention::of("user_profile"),
+            Some(NamingConvention::Snake)
+        );
+        assert_eq!(
+            NamingConvention::of("user-profile"),
+            Some(NamingConvention::Kebab)
+        );
+        assert_eq!(
+            NamingConvention::of("userProfile"),
+            Some(NamingConvention::Camel)
+        );
+        assert_eq!(
+            NamingConvention::of("UserProfile"),
+            Some(NamingConvention::Pascal)
+        );
+        assert_eq!(NamingConvention::of("users"), None);
+        assert_eq!(NamingConvention::of("__init__"), None);
+
+        assert_eq!(
+            CommitStyle::of("feat(api): add pagination"),
+            CommitStyle::Conventional
+        );
+        assert_eq!(CommitStyle::of("fix!: drop v1"), CommitStyle::Conventional);
+        assert_eq!(
+            CommitStyle::of("[PROJ-12] Add pagination"),
+            CommitStyle::BracketedId
+        );
+        assert_eq!(CommitStyle::of("✨ Add pagination"), CommitStyle::Emoji);
+        assert_eq!(
+            CommitStyle::of(":sparkles: Add pagination"),
+            CommitStyle::Emoji
+        );
+        assert_eq!(CommitStyle::of("Add pagination"), CommitStyle::Plain);
+        assert_eq!(CommitStyle::of("Note: this is plain"), CommitStyle::Plain);
+        println!("✅ Naming and commit conventions test passed!");
+    }
+
+    #[test]
+    fn test_analyze() {
+        let indented = "fn main() {\n    let x = 1;\n    if x > 0 {\n        run();\n    }\n}\n";
+        let mut files = vec![
+            file("rustfmt.toml", "max_width = 120\n"),
+            file("clippy.toml", "msrv = \"1.75\"\n"),
+            file(
+                ".editorconfig",
+                "root = true\n\n[*]\nindent_style = tab\nmax_line_length = 80\n",
+            ),
+            file("web/.prettierrc", "{ \"tabWidth\": 2 }"),
+            file("pyproject.toml", "[tool.black]\nline-length = 99\n"),
+        ];
+        for name in ["user_profile", "feedback_store", "api_keys", "lib"] {
+            files.push(file(&format!("src/{}.rs", name), &indented.repeat(3)));
+        }
+        let messages = vec![
+            "feat(api): add pagination\n\nLonger body".to_string(),
+            "Merge pull request #4 from aye-is/next".to_string(),
+            "fix: handle empty pages".to_string(),
+            "Update README".to_string(),
+        ];
+
+        let profile = analyze(&files, &messages);
+        // 📐 Root configs win over nested ones; the first root config in path order sets values
+        assert_eq!(profile.indentation, Some(Indentation::Tabs));
+        assert_eq!(profile.max_line_width, Some(80));
+        assert_eq!(
+            profile.tools,
+            vec!["clippy", "black", "rustfmt", "prettier"]
+        );
+        assert_eq!(profile.file_naming, Some(NamingConvention::Snake));
+        assert_eq!(profile.commit_style, Some(CommitStyle::Conventional));
+        assert_eq!(
+            profile.commit_examples,
+            vec!["feat(api): add pagination", "fix: handle empty pages"]
+        );
+
+        // ↔️ Without configs, indentation is measured
+        let sources: Vec<SourceFile> = files.into_iter().skip(5).collect();
+        let measured = analyze(&sources, &[]);
+        assert_eq!(measured.indentation, Some(Indentation::Spaces(4)));
+        assert_eq!(measured.max_line_width, None);
+        assert!(measured.tools.is_empty());
+        assert_eq!(measured.commit_style, None);
+        assert_eq!(analyze(&[], &[]), StyleProfile::default());
+        println!("✅ Style analysis test passed!");
+    }
+
+    #[test]
+    fn test_config_readers() {
+        assert_eq!(
+            rustfmt("hard_tabs = true"),
+            ConfiguredStyle {
+                indentation: Some(Indentation::Tabs),
+                max_line_width: Some(100),
+            }
+        );
+        assert_eq!(
+            prettier("{ \"useTabs\": false, \"printWidth\": 100 }"),
+            ConfiguredStyle {
+                indentation: Some(Indentation::Spaces(2)),
+                max_line_width: Some(100),
+            }
+        );
+        assert_eq!(prettier("semi: false"), ConfiguredStyle::default());
+        assert_eq!(
+            editorconfig("[*.md]\nindent_size = 8\n[*]\nindent_style = space\nindent_size = 2\n"),
+            ConfiguredStyle {
+                indentation: Some(Indentation::Spaces(2)),
+                max_line_width: None,
+            }
+        );
+        let (style, tools) = read_config("pyproject.toml", "[tool.ruff]\nline-length = 100\n");
+        assert_eq!(style.max_line_width, Some(100));
+        assert_eq!(tools, vec!["ruff"]);
+        assert_eq!(
+            style_inputs(vec![
+                "README.md".to_string(),
+                "src/main.rs".to_string(),
+                ".prettierrc".to_string(),
+            ]),
+            vec!["src/main.rs", ".prettierrc"]
+        );
+        println!("✅ Style config readers test passed!");
+    }
+
+    #[test]
+    fn test_system_message() {
+        assert_eq!(StyleProfile::default().prompt_section(), None);
+        assert_eq!(
+            StyleProfile::default().system_message(Some("Be careful")),
+            Some("Be careful".to_string())
+        );
+        assert_eq!(StyleProfile::default().system_message(Some("  ")), None);
+
+        let profile = StyleProfile {
+            tools: vec!["rustfmt".to_string()],
+            indentation: Some(Indentation::Spaces(4)),
+            max_line_width: Some(100),
+            file_naming: Some(NamingConvention::Snake),
+            commit_style: Some(CommitStyle::BracketedId),
+            commit_examples: vec!["[FB-1] Add login".to_string()],
+        };
+        let section = profile.prompt_section().unwrap();
+        assert!(section.starts_with("## House style"));
+        assert!(section.contains("- Indent with 4 spaces"));
+        assert!(section.contains("- Keep lines within 100 characters"));
+        assert!(section.contains("- Code must pass rustfmt"));
+        assert!(section.contains("- Name new source files in snake_case"));
+        assert!(section.contains("e.g. `[FB-1] Add login`"));
+
+        let message = profile.system_message(Some("Be careful")).unwrap();
+        assert!(message.starts_with("Be careful\n\n## House style"));
+        assert_eq!(profile.system_message(None), Some(section));
+
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["indentation"], serde_json::json!({ "spaces": 4 }));
+        assert_eq!(
+            serde_json::from_value::<StyleProfile>(json).unwrap(),
+            profile
+        );
+        println!("✅ Style system message test passed!");
+    }
+}
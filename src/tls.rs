@@ -0,0 +1,281 @@
+// 🔒 TLS Termination - HTTPS Without a Reverse Proxy! 🔒
+// Small self-hosted deployments can serve HTTPS themselves: with TLS_CERT_PATH
+// and TLS_KEY_PATH from PEM files (picked up again when they change on disk, so
+// certbot renewals need no restart), or with TLS_ACME_DOMAINS from Let's Encrypt
+// through rustls-acme (builds with the `acme` feature). ACME answers the
+// TLS-ALPN-01 challenge, so the HTTPS listener must be reachable on port 443.
+// TLS_REDIRECT_PORT adds a plain HTTP listener that sends everything to HTTPS.
+// With none of these set, the server speaks plain HTTP as before
+// Created with love by Aye & Hue - Padlocks for everyone! ✨
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::config::{ServerConfig, TlsMode};
+
+/// 🔁 How often certificate files are checked for renewals
+const CERT_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// 🚀 Serve the app over HTTPS or plain HTTP, as configured, until `shutdown` resolves
+pub async fn serve(
+    app: Router,
+    addr: SocketAddr,
+    config: &ServerConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    // 🔌 With the peer address of each connection, for client IPs behind proxies
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    if config.tls.mode == TlsMode::Off {
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .context("Failed to bind to address")?;
+        info!("🎊 Feedbacker is now LIVE and ready for action! 🎊");
+        return axum::serve(listener, service)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .context("Server error occurred");
+    }
+
+    // 🔐 One crypto provider for every TLS listener (already installed is fine)
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let handle = Handle::new();
+    let redirect_handle = Handle::new();
+    tokio::spawn({
+        let handle = handle.clone();
+        let redirect_handle = redirect_handle.clone();
+        async move {
+            shutdown.await;
+            handle.graceful_shutdown(None);
+            redirect_handle.graceful_shutdown(None);
+        }
+    });
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            if let Some(addr) = handle.listening().await {
+                info!("🎊 Feedbacker is now LIVE on https://{}! 🎊", addr);
+            }
+        }
+    });
+
+    if let Some(port) = config.tls.redirect_port {
+        let redirect_addr = SocketAddr::new(addr.ip(), port);
+        let target = Arc::new(RedirectTarget {
+            public_url: config.public_url.clone(),
+            https_port: addr.port(),
+        });
+        let redirects = Router::new().fallback(redirect_to_https).with_state(target);
+        tokio::spawn(async move {
+            info!("↪️ Redirecting http://{} to HTTPS", redirect_addr);
+            if let Err(e) = axum_server::bind(redirect_addr)
+                .handle(redirect_handle)
+                .serve(redirects.into_make_service())
+                .await
+            {
+                warn!(
+                    "⚠️ HTTP redirect listener on {} stopped: {}",
+                    redirect_addr, e
+                );
+            }
+        });
+    }
+
+    match &config.tls.mode {
+        TlsMode::Off => unreachable!("plain HTTP is served above"),
+        TlsMode::Files {
+            cert_path,
+            key_path,
+        } => {
+            let rustls = RustlsConfig::from_pem_file(cert_path, key_path)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to load the TLS certificate {} and key {}",
+                        cert_path.display(),
+                        key_path.display()
+                    )
+                })?;
+            watch_certificate_files(rustls.clone(), cert_path.clone(), key_path.clone());
+            info!("🔒 Serving HTTPS with {}", cert_path.display());
+            axum_server::bind_rustls(addr, rustls)
+                .handle(handle)
+                .serve(service)
+                .await
+                .context("Server error occurred")
+        }
+        #[cfg(feature = "acme")]
+        TlsMode::Acme {
+            domains,
+            contact,
+            cache_dir,
+            staging,
+        } => {
+            use futures::StreamExt;
+            use rustls_acme::{caches::DirCache, AcmeConfig};
+
+            let mut state = AcmeConfig::new(domains)
+                .contact(contact.iter().map(|email| format!("mailto:{}", email)))
+                .cache(DirCache::new(cache_dir.clone()))
+                .directory_lets_encrypt(!staging)
+                .state();
+            let acceptor = state.axum_acceptor(state.default_rustls_config());
+            tokio::spawn(async move {
+                while let Some(event) = state.next().await {
+                    match event {
+                        Ok(event) => info!("🔏 ACME: {:?}", event),
+                        Err(e) => warn!("⚠️ ACME certificate problem: {:?}", e),
+                    }
+                }
+            });
+            info!(
+                "🔒 Serving HTTPS for {} with ACME certificates{}",
+                domains.join(", "),
+                if *staging { " (staging)" } else { "" }
+            );
+            axum_server::bind(addr)
+                .acceptor(acceptor)
+                .handle(handle)
+                .serve(service)
+                .await
+                .context("Server error occurred")
+        }
+        #[cfg(not(feature = "acme"))]
+        TlsMode::Acme { .. } => {
+            anyhow::bail!("TLS_ACME_DOMAINS needs a build with the acme feature")
+        }
+    }
+}
+
+/// 🔁 Load the certificate again whenever its files change (certbot renewals)
+fn watch_certificate_files(rustls: RustlsConfig, cert_path: PathBuf, key_path: PathBuf) {
+    tokio::spawn(async move {
+        let mut seen = (modified(&cert_path), modified(&key_path));
+        let mut ticks = tokio::time::interval(CERT_CHECK_INTERVAL);
+        ticks.tick().await; // ⏱️ The first tick fires immediately; the files were just read
+        loop {
+            ticks.tick().await;
+            let current = (modified(&cert_path), modified(&key_path));
+            if current == seen {
+                continue;
+            }
+            match rustls.reload_from_pem_file(&cert_path, &key_path).await {
+                Ok(()) => {
+                    info!("🔁 Reloaded the TLS certificate {}", cert_path.display());
+                    seen = current;
+                }
+                // 🔁 Half-written renewals are tried again on the next check
+                Err(e) => warn!(
+                    "⚠️ TLS certificate reload failed, keeping the old one: {}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+/// ⏰ When a file last changed (None when it can't be read)
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// ↪️ Where plain HTTP requests are sent
+#[derive(Debug)]
+struct RedirectTarget {
+    /// 🔗 SERVER_PUBLIC_URL (used when it is an https:// URL)
+    public_url: String,
+    /// 🔒 Port the HTTPS listener is on
+    https_port: u16,
+}
+
+/// ↪️ Send a plain HTTP request to the same path over HTTPS
+async fn redirect_to_https(
+    State(target): State<Arc<RedirectTarget>>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok());
+    match https_location(&target.public_url, host, target.https_port, &uri) {
+        Some(location) => Redirect::permanent(&location).into_response(),
+        None => (StatusCode::BAD_REQUEST, "Use HTTPS").into_response(),
+    }
+}
+
+/// 🧭 The HTTPS URL for a request: on the public URL when it is https://,
+/// else on the requested host (its port swapped for the HTTPS one)
+pub fn https_location(
+    public_url: &str,
+    host: Option<&str>,
+    https_port: u16,
+    uri: &Uri,
+) -> Option<String> {
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    if public_url.starts_with("https://") {
+        return Some(format!("{}{}", public_url.trim_end_matches('/'), path));
+    }
+
+    let host = host?.trim();
+    let name = match host.strip_prefix('[') {
+        // 🌐 [::1]:80 keeps its brackets
+        Some(rest) => &host[..rest.find(']')? + 2],
+        None => host.split(':').next()?,
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some(match https_port {
+        443 => format!("https://{}{}", name, path),
+        port => format!("https://{}:{}{}", name, port, path),
+    })
+}
+
+// 🧪 Tests - Every road leads to HTTPS!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_location() {
+        let uri: Uri = "/api/feedback?page=2".parse().unwrap();
+        assert_eq!(
+            https_location("https://f.8b.is", Some("203.0.113.7"), 443, &uri).as_deref(),
+            Some("https://f.8b.is/api/feedback?page=2")
+        );
+        assert_eq!(
+            https_location("http://localhost:8080", Some("f.8b.is:80"), 443, &uri).as_deref(),
+            Some("https://f.8b.is/api/feedback?page=2")
+        );
+        assert_eq!(
+            https_location("http://localhost:8080", Some("[::1]:8080"), 8443, &uri).as_deref(),
+            Some("https://[::1]:8443/api/feedback?page=2")
+        );
+        assert_eq!(
+            https_location("", Some("f.8b.is"), 443, &"/".parse().unwrap()).as_deref(),
+            Some("https://f.8b.is/")
+        );
+        assert_eq!(https_location("", None, 443, &uri), None);
+        assert_eq!(https_location("", Some(":80"), 443, &uri), None);
+        assert_eq!(https_location("", Some("[::1"), 443, &uri), None);
+        println!("✅ HTTPS redirect location test passed!");
+    }
+}
@@ -0,0 +1,37 @@
+// 🔗 URL Encoding - Names That Stay One Path Segment! 🔗
+// Label names, user logins and package names go into REST paths as-is from
+// config and manifests. A slash, space or '#' in one would change the route
+// (npm's `@scope/name` is the classic), so each is percent-encoded as a single
+// segment before it's formatted into a URL
+// Created with love by Aye & Hue - One name, one segment! ✨
+
+/// 🔗 `value` percent-encoded as one URL path segment
+/// Unreserved characters (RFC 3986) and '@' are kept, everything else is
+/// encoded byte by byte in UTF-8
+pub fn path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'@') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// 🧪 Tests - Making sure a name never spills into the next segment!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_segment() {
+        assert_eq!(path_segment("serde_json"), "serde_json");
+        assert_eq!(path_segment("@types/node"), "@types%2Fnode");
+        assert_eq!(path_segment("good first issue"), "good%20first%20issue");
+        assert_eq!(path_segment("area: ui/ux #1"), "area%3A%20ui%2Fux%20%231");
+        assert_eq!(path_segment("🐛 bug"), "%F0%9F%90%9B%20bug");
+        println!("✅ Path segment encoding test passed!");
+    }
+}
@@ -1,2 +1,8 @@
 // 🔧 Utils Module - Helpful Utilities! 🔧
-// TODO: Implement utility functions
+// Small, dependency-free helpers shared across modules
+
+pub mod encoding; // 🔗 Names percent-encoded as single URL path segments
+pub mod redaction; // 🙈 Scrubbing secrets out of stored text
+pub mod text; // ✂️ Previews and truncation that never split a character
+pub mod recent; // 🕒 Bounded in-memory logs of recent happenings
+pub mod signed_links; // 🔏 HMAC-signed links, one domain per purpose
@@ -0,0 +1,72 @@
+// 🕒 Recent Logs - What Just Happened, Without a Database! 🕒
+// A small bounded buffer for things operators want to glance at (errors,
+// rate-limit hits) in the admin console. Entries live in this instance's memory
+// only: the oldest fall out once it is full, and a restart starts afresh
+// Created with love by Aye & Hue - Short memories, clear heads! ✨
+
+use std::{collections::VecDeque, sync::Mutex};
+
+/// 📏 Entries kept unless asked for another size
+pub const DEFAULT_CAPACITY: usize = 50;
+
+/// 🕒 The latest `capacity` entries, newest first when read
+#[derive(Debug)]
+pub struct RecentLog<T> {
+    entries: Mutex<VecDeque<T>>,
+    capacity: usize,
+}
+
+impl<T: Clone> RecentLog<T> {
+    /// ➕ An empty log keeping at most `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// 📝 Add an entry, dropping the oldest when full
+    pub fn push(&self, entry: T) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_back();
+        }
+        if self.capacity > 0 {
+            entries.push_front(entry);
+        }
+    }
+
+    /// 📋 Every kept entry, newest first
+    pub fn snapshot(&self) -> Vec<T> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().cloned().collect()
+    }
+}
+
+impl<T: Clone> Default for RecentLog<T> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+// 🧪 Tests - Newest first, never more than asked!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_log() {
+        let log = RecentLog::with_capacity(3);
+        assert!(log.snapshot().is_empty());
+
+        for n in 1..=5 {
+            log.push(n);
+        }
+        assert_eq!(log.snapshot(), vec![5, 4, 3]);
+
+        let empty = RecentLog::with_capacity(0);
+        empty.push(1);
+        assert!(empty.snapshot().is_empty());
+        println!("✅ Recent log test passed!");
+    }
+}
@@ -0,0 +1,196 @@
+// ✂️ Text Helpers - Shorter, Never Broken! ✂️
+// Previews of feedback are cut by grapheme (what a reader sees as one
+// character), so emoji, flags and CJK text never get split or panic a byte
+// slice. Previews are plain text: markdown markers are dropped and everything
+// is put on one line before cutting, preferably at a word boundary
+// Created with love by Aye & Hue - Even 👩‍👩‍👧 survives the cut! ✨
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// … Appended to text that was cut
+pub const ELLIPSIS: &str = "…";
+
+/// 📐 Where truncation may cut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cut {
+    /// ✂️ Right at the limit (still between graphemes)
+    Anywhere,
+    /// 🔤 At the last space before the limit, unless that throws away more than half
+    WordBoundary,
+}
+
+/// ✂️ `text` cut to at most `max_graphemes` graphemes, the ellipsis included
+pub fn truncate(text: &str, max_graphemes: usize, cut: Cut) -> String {
+    let mut starts = text.grapheme_indices(true).map(|(start, _)| start);
+    let Some(end) = starts.nth(max_graphemes.saturating_sub(1)) else {
+        return text.to_string();
+    };
+    if starts.next().is_none() {
+        // 🎯 Exactly at the limit: nothing to cut
+        return text.to_string();
+    }
+
+    let mut kept = &text[..end];
+    // 🔤 A cut right before a space already ends on a whole word
+    let at_boundary = text[end..].starts_with(char::is_whitespace);
+    if cut == Cut::WordBoundary && !at_boundary {
+        if let Some(space) = kept.rfind(char::is_whitespace) {
+            if space >= kept.len() / 2 {
+                kept = &kept[..space];
+            }
+        }
+    }
+    format!("{}{}", kept.trim_end(), ELLIPSIS)
+}
+
+/// 👀 A one-line plain-text preview of markdown, at most `max_graphemes` long
+pub fn preview(markdown: &str, max_graphemes: usize) -> String {
+    truncate(&strip_markdown(markdown), max_graphemes, Cut::WordBoundary)
+}
+
+/// 🧹 Markdown as plain text on one line: headings, quotes, list markers,
+/// fences and emphasis dropped, links and images reduced to their text
+pub fn strip_markdown(markdown: &str) -> String {
+    let mut words = Vec::new();
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            continue;
+        }
+        let line = strip_block_markers(line);
+        words.extend(strip_inline(line).split_whitespace().map(str::to_string));
+    }
+    words.join(" ")
+}
+
+/// 🧱 A line without its heading, quote, list or task marker
+fn strip_block_markers(mut line: &str) -> &str {
+    loop {
+        let before = line;
+        if let Some(rest) = line.strip_prefix('>') {
+            line = rest.trim_start();
+        }
+        let hashes = line.len() - line.trim_start_matches('#').len();
+        if (1..=6).contains(&hashes) {
+            let rest = &line[hashes..];
+            // 🎫 "#42" is an issue reference, not a heading
+            if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                line = rest.trim_start();
+            }
+        }
+        for bullet in ["- ", "* ", "+ "] {
+            if let Some(rest) = line.strip_prefix(bullet) {
+                line = rest.trim_start();
+            }
+        }
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            let rest = &line[digits..];
+            if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+                line = rest.trim_start();
+            }
+        }
+        for task in ["[ ] ", "[x] ", "[X] "] {
+            if let Some(rest) = line.strip_prefix(task) {
+                line = rest.trim_start();
+            }
+        }
+        if line == before {
+            return line;
+        }
+    }
+}
+
+/// 🔗 A line without emphasis and code markers, links and images as their text
+fn strip_inline(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        // 🔗 [text](url) and ![alt](url) keep only the text
+        let link = rest.strip_prefix("![").or_else(|| rest.strip_prefix('['));
+        if let Some((text, after)) = link.and_then(split_link) {
+            plain.push_str(text);
+            rest = after;
+            continue;
+        }
+        if let Some(after) = ["**", "__", "~~"]
+            .iter()
+            .find_map(|marker| rest.strip_prefix(marker))
+        {
+            rest = after;
+            continue;
+        }
+        if c != '`' && c != '*' {
+            plain.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    plain
+}
+
+/// 🔗 `text](url)rest` split into the link text and what follows the link
+fn split_link(after_bracket: &str) -> Option<(&str, &str)> {
+    let (text, rest) = after_bracket.split_once("](")?;
+    if text.contains('[') {
+        return None;
+    }
+    let (_, after) = rest.split_once(')')?;
+    Some((text, after))
+}
+
+// 🧪 Tests - Every character in one piece!
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_multi_byte() {
+        assert_eq!(truncate("short", 10, Cut::Anywhere), "short");
+        assert_eq!(truncate("exact", 5, Cut::Anywhere), "exact");
+        assert_eq!(truncate("abcdef", 4, Cut::Anywhere), "abc…");
+
+        // 🌏 Byte slicing would have panicked on every one of these
+        assert_eq!(truncate("日本語のテキスト", 4, Cut::Anywhere), "日本語…");
+        assert_eq!(truncate("🚀🚀🚀🚀🚀", 3, Cut::Anywhere), "🚀🚀…");
+        let family = "👩‍👩‍👧👩‍👩‍👧👩‍👩‍👧";
+        assert_eq!(truncate(family, 2, Cut::Anywhere), "👩‍👩‍👧…");
+        assert_eq!(
+            truncate("e\u{301}e\u{301}e\u{301}", 2, Cut::Anywhere),
+            "e\u{301}…"
+        );
+        println!("✅ Multi-byte truncation test passed!");
+    }
+
+    #[test]
+    fn test_truncate_word_boundary() {
+        let text = "This is a very long content that should be truncated";
+        assert_eq!(
+            truncate(text, 20, Cut::WordBoundary),
+            "This is a very long…"
+        );
+        assert_eq!(truncate(text, 20, Cut::Anywhere), "This is a very long…");
+        assert_eq!(truncate(text, 17, Cut::WordBoundary), "This is a very…");
+        assert_eq!(truncate(text, 17, Cut::Anywhere), "This is a very l…");
+        // 🔤 No space in the second half: cut inside the word
+        assert_eq!(
+            truncate("a supercalifragilistic", 12, Cut::WordBoundary),
+            "a supercali…"
+        );
+        println!("✅ Word boundary truncation test passed!");
+    }
+
+    #[test]
+    fn test_markdown_preview() {
+        let markdown = "## Dark mode 🌙\n\n> Please add **dark mode** to the [dashboard](https://f.8b.is).\n\n- [ ] toggle in `settings`\n1. remember it\n\n```rust\nlet theme = Theme::Dark;\n```\n\nSee #42 and ![screenshot](shot.png)";
+        assert_eq!(
+            strip_markdown(markdown),
+            "Dark mode 🌙 Please add dark mode to the dashboard. toggle in settings remember it let theme = Theme::Dark; See #42 and screenshot"
+        );
+        assert_eq!(preview(markdown, 24), "Dark mode 🌙 Please add…");
+        assert_eq!(
+            strip_markdown("snake_case [not a link] (x)"),
+            "snake_case [not a link] (x)"
+        );
+        println!("✅ Markdown preview test passed!");
+    }
+}